@@ -0,0 +1,209 @@
+//! Audit trail of the SPARQL updates executed by the server, stored as RDF in a graph of the store.
+//!
+//! Each executed update is recorded as:
+//! ```turtle
+//! @prefix audit: <http://oxigraph.org/audit#> .
+//! @prefix prov: <http://www.w3.org/ns/prov#> .
+//! @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+//!
+//! [] a prov:Activity, audit:Update ;
+//!     prov:endedAtTime "2025-01-01T00:00:00Z"^^xsd:dateTime ;
+//!     audit:operation "INSERT DATA { GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 1 } }" ;
+//!     audit:principal "alice" ;
+//!     audit:graph <http://example.com/g> .
+//! ```
+//! `audit:graph` is `audit:DefaultGraph` for the default graph and `audit:AllGraphs` if the affected graphs are only known during evaluation
+//! (`CLEAR ALL`, `DROP NAMED` or `DELETE`/`INSERT` templates with a variable graph name).
+use oxigraph::model::vocab::{rdf, xsd};
+use oxigraph::model::{BlankNode, GraphName, Literal, NamedNode, NamedNodeRef, Quad, Term};
+use oxigraph::store::{StorageError, Store};
+use oxsdatatypes::DateTime;
+use spargebra::algebra::GraphTarget;
+use spargebra::term::GraphNamePattern;
+use spargebra::{GraphUpdateOperation, Update};
+
+const PROV_ACTIVITY: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/prov#Activity");
+const PROV_ENDED_AT_TIME: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/prov#endedAtTime");
+const AUDIT_UPDATE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/audit#Update");
+const AUDIT_OPERATION: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/audit#operation");
+const AUDIT_PRINCIPAL: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/audit#principal");
+const AUDIT_GRAPH: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/audit#graph");
+const AUDIT_DEFAULT_GRAPH: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/audit#DefaultGraph");
+const AUDIT_ALL_GRAPHS: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/audit#AllGraphs");
+
+/// The authenticated user of a request, recorded in the audit graph.
+///
+/// A [`Middleware`](crate::Middleware) authenticating the requests should insert it into the request extensions
+/// in [`on_request`](crate::Middleware::on_request):
+/// ```
+/// use oxhttp::model::{Body, Request, Response};
+/// use oxigraph::model::NamedNode;
+/// use oxigraph::store::Store;
+/// use oxigraph_server::{Middleware, Principal, SparqlServer};
+///
+/// struct Authentication;
+///
+/// impl Middleware for Authentication {
+///     fn on_request(&self, request: &mut Request<Body>) -> Option<Response<Body>> {
+///         // Authentication logic
+///         request.extensions_mut().insert(Principal::new("alice"));
+///         None
+///     }
+/// }
+///
+/// let _server = SparqlServer::new(Store::new()?)
+///     .with_middleware(Authentication)
+///     .with_audit_graph(NamedNode::new("http://example.com/audit")?);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Principal(String);
+
+impl Principal {
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Adds the record of an executed update to the audit graph
+pub fn record_update(
+    store: &Store,
+    audit_graph: &NamedNode,
+    operation: &str,
+    update: &Update,
+    principal: Option<&Principal>,
+) -> Result<(), StorageError> {
+    let record = BlankNode::default();
+    let graph_name = GraphName::from(audit_graph.clone());
+    let mut quads = vec![
+        Quad::new(
+            record.clone(),
+            rdf::TYPE,
+            PROV_ACTIVITY.into_owned(),
+            graph_name.clone(),
+        ),
+        Quad::new(
+            record.clone(),
+            rdf::TYPE,
+            AUDIT_UPDATE.into_owned(),
+            graph_name.clone(),
+        ),
+        Quad::new(
+            record.clone(),
+            PROV_ENDED_AT_TIME,
+            Literal::new_typed_literal(DateTime::now().to_string(), xsd::DATE_TIME),
+            graph_name.clone(),
+        ),
+        Quad::new(
+            record.clone(),
+            AUDIT_OPERATION,
+            Literal::from(operation),
+            graph_name.clone(),
+        ),
+    ];
+    if let Some(principal) = principal {
+        quads.push(Quad::new(
+            record.clone(),
+            AUDIT_PRINCIPAL,
+            Literal::from(principal.as_str()),
+            graph_name.clone(),
+        ));
+    }
+    for graph in affected_graphs(update) {
+        quads.push(Quad::new(
+            record.clone(),
+            AUDIT_GRAPH,
+            graph,
+            graph_name.clone(),
+        ));
+    }
+    store.extend(quads)
+}
+
+/// Checks if the update explicitly modifies the audit graph
+pub fn modifies_audit_graph(update: &Update, audit_graph: &NamedNode) -> bool {
+    affected_graphs(update).contains(&audit_graph.clone().into())
+}
+
+/// Checks if the update only modifies the given graph
+pub fn only_modifies_graph(update: &Update, graph: &GraphName) -> bool {
+    let graph: Term = match graph {
+        GraphName::NamedNode(graph) => graph.clone().into(),
+        GraphName::DefaultGraph => AUDIT_DEFAULT_GRAPH.into_owned().into(),
+        GraphName::BlankNode(_) => return false,
+    };
+    affected_graphs(update).iter().all(|g| *g == graph)
+}
+
+/// The graphs that might be modified by the update
+fn affected_graphs(update: &Update) -> Vec<Term> {
+    let mut graphs = Vec::new();
+    let mut add_graph = |graph: Term| {
+        if !graphs.contains(&graph) {
+            graphs.push(graph);
+        }
+    };
+    for operation in &update.operations {
+        match operation {
+            GraphUpdateOperation::InsertData { data } => {
+                for quad in data {
+                    add_graph(graph_name_term(&quad.graph_name));
+                }
+            }
+            GraphUpdateOperation::DeleteData { data } => {
+                for quad in data {
+                    add_graph(graph_name_term(&quad.graph_name));
+                }
+            }
+            GraphUpdateOperation::DeleteInsert { delete, insert, .. } => {
+                for graph_name in delete
+                    .iter()
+                    .map(|quad| &quad.graph_name)
+                    .chain(insert.iter().map(|quad| &quad.graph_name))
+                {
+                    add_graph(match graph_name {
+                        GraphNamePattern::NamedNode(graph_name) => graph_name.clone().into(),
+                        GraphNamePattern::DefaultGraph => AUDIT_DEFAULT_GRAPH.into_owned().into(),
+                        GraphNamePattern::Variable(_) => AUDIT_ALL_GRAPHS.into_owned().into(),
+                    });
+                }
+            }
+            GraphUpdateOperation::Load { destination, .. } => {
+                add_graph(graph_name_term(destination));
+            }
+            GraphUpdateOperation::Create { graph, .. } => add_graph(graph.clone().into()),
+            GraphUpdateOperation::Clear { graph, .. }
+            | GraphUpdateOperation::Drop { graph, .. } => {
+                add_graph(match graph {
+                    GraphTarget::NamedNode(graph) => graph.clone().into(),
+                    GraphTarget::DefaultGraph => AUDIT_DEFAULT_GRAPH.into_owned().into(),
+                    GraphTarget::NamedGraphs | GraphTarget::AllGraphs => {
+                        AUDIT_ALL_GRAPHS.into_owned().into()
+                    }
+                });
+            }
+        }
+    }
+    graphs
+}
+
+fn graph_name_term(graph_name: &spargebra::term::GraphName) -> Term {
+    match graph_name {
+        spargebra::term::GraphName::NamedNode(graph_name) => graph_name.clone().into(),
+        spargebra::term::GraphName::DefaultGraph => AUDIT_DEFAULT_GRAPH.into_owned().into(),
+    }
+}
@@ -0,0 +1,492 @@
+//! Authentication of the server requests with HTTP Basic credentials or bearer tokens and a read/write role model.
+use crate::audit::Principal;
+use crate::middleware::Middleware;
+use json_event_parser::{JsonEvent, WriterJsonSerializer};
+use oxhttp::model::header::{AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER, WWW_AUTHENTICATE};
+use oxhttp::model::{Body, Method, Request, Response, StatusCode};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// What an authenticated user is allowed to do.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Role {
+    /// Evaluate queries and read the store, the saved queries and the other endpoints.
+    Read,
+    /// Also evaluate updates, write with the Graph Store Protocol, change the saved queries and use the `/admin` endpoints.
+    Write,
+}
+
+/// Limits of the operations of a principal, set with [`AuthMiddleware::with_role_limits`] and [`AuthMiddleware::with_principal_limits`].
+///
+/// They apply to all the read operations: SPARQL, SQL and GraphQL queries, reads of the `/store`, `/fragments` and `/changes` endpoints.
+/// SPARQL updates are also counted in the daily budget.
+///
+/// Exceeding a limit returns a quota-exceeded error with a JSON body giving the exceeded quota, its limit and the principal:
+/// ```json
+/// { "error": "quota-exceeded", "quota": "maxResultRows", "limit": 1000, "principal": "alice", "message": "..." }
+/// ```
+/// The status is `429 Too Many Requests` with a `Retry-After` header if the daily budget is exhausted
+/// and `403 Forbidden` if a query exceeds the other limits.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct QueryLimits {
+    max_result_rows: Option<u64>,
+    max_query_time: Option<Duration>,
+    daily_budget: Option<u64>,
+}
+
+impl QueryLimits {
+    /// Limits that do not limit anything.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximal number of solutions of a `SELECT` query or of triples of a `CONSTRUCT` or `DESCRIBE` query.
+    ///
+    /// It also limits the number of quads read from `/store` and `/fragments`, of solutions of each SPARQL query generated for `/graphql`
+    /// and of changes sent by `/changes`, whose stream is then closed.
+    /// The results are buffered before being sent to be able to reject the queries returning too many of them.
+    #[inline]
+    #[must_use]
+    pub fn with_max_result_rows(mut self, max_result_rows: u64) -> Self {
+        self.max_result_rows = Some(max_result_rows);
+        self
+    }
+
+    /// Sets the maximal evaluation time of a query.
+    ///
+    /// If the limit is reached after the first results are sent, the response is truncated instead of being a quota-exceeded error.
+    /// The `/changes` stream is closed after this time.
+    /// It is given in milliseconds in the quota-exceeded errors.
+    #[inline]
+    #[must_use]
+    pub fn with_max_query_time(mut self, max_query_time: Duration) -> Self {
+        self.max_query_time = Some(max_query_time);
+        self
+    }
+
+    /// Sets the maximal number of operations per UTC day, the SPARQL updates included.
+    ///
+    /// The requests without credentials share the same budget.
+    #[inline]
+    #[must_use]
+    pub fn with_daily_budget(mut self, daily_budget: u64) -> Self {
+        self.daily_budget = Some(daily_budget);
+        self
+    }
+}
+
+/// A [`Middleware`] authenticating the requests with [HTTP Basic](https://www.rfc-editor.org/rfc/rfc7617) credentials
+/// or [bearer tokens](https://www.rfc-editor.org/rfc/rfc6750) and checking the [`Role`] they are given.
+///
+/// The requests writing to the server are the `POST` requests to other endpoints than `/query`, `/sql` and `/graphql`,
+/// the `PUT`, `DELETE` and `PATCH` requests and all the requests to the `/admin` endpoints.
+/// The other requests only require the [`Role::Read`] role.
+///
+/// Requests without credentials are rejected with a `401 Unauthorized` status unless an [anonymous role](Self::with_anonymous_role) is set
+/// and requests with a too low role with a `403 Forbidden` status.
+/// The name of the user or of the token is inserted as a [`Principal`] into the request extensions and is recorded in the [audit graph](crate::SparqlServer::with_audit_graph).
+/// The operations of the principals can be limited with [`QueryLimits`].
+///
+/// ```
+/// use oxhttp::model::{Body, Request, StatusCode};
+/// use oxigraph::store::Store;
+/// use oxigraph_server::{AuthMiddleware, Role, SparqlServer};
+///
+/// let server = SparqlServer::new(Store::new()?).with_middleware(
+///     AuthMiddleware::new()
+///         .with_user("alice", "secret", Role::Write)
+///         .with_token("ci", "0123456789abcdef", Role::Read),
+/// );
+///
+/// let mut request = Request::builder()
+///     .uri("http://localhost/query?query=ASK%7B%7D")
+///     .body(Body::empty())?;
+/// assert_eq!(server.handle(&mut request).status(), StatusCode::UNAUTHORIZED);
+///
+/// let mut request = Request::builder()
+///     .uri("http://localhost/query?query=ASK%7B%7D")
+///     .header("authorization", "Bearer 0123456789abcdef")
+///     .body(Body::empty())?;
+/// assert_eq!(server.handle(&mut request).status(), StatusCode::OK);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default)]
+pub struct AuthMiddleware {
+    /// The users by SHA-256 hash of their Base64 encoded `name:password` credentials
+    users: HashMap<[u8; 32], (Principal, Role)>,
+    /// The tokens by SHA-256 hash
+    tokens: HashMap<[u8; 32], (Principal, Role)>,
+    anonymous_role: Option<Role>,
+    role_limits: HashMap<Role, QueryLimits>,
+    principal_limits: HashMap<String, QueryLimits>,
+    daily_usage: Arc<Mutex<DailyUsage>>,
+}
+
+impl AuthMiddleware {
+    /// Builds a middleware without any user or token, rejecting all the requests.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a user authenticated with HTTP Basic credentials.
+    ///
+    /// The name must not contain a `:`.
+    #[inline]
+    #[must_use]
+    pub fn with_user(mut self, name: impl Into<String>, password: &str, role: Role) -> Self {
+        let name = name.into();
+        let credentials = base64_encode(format!("{name}:{password}").as_bytes());
+        self.users.insert(
+            Sha256::digest(credentials.as_bytes()).into(),
+            (Principal::new(name), role),
+        );
+        self
+    }
+
+    /// Adds a bearer token, named `name` in the audit graph.
+    #[inline]
+    #[must_use]
+    pub fn with_token(mut self, name: impl Into<String>, token: &str, role: Role) -> Self {
+        self.tokens.insert(
+            Sha256::digest(token.as_bytes()).into(),
+            (Principal::new(name), role),
+        );
+        self
+    }
+
+    /// Gives the requests without credentials the given role instead of rejecting them.
+    ///
+    /// For example, [`Role::Read`] allows anyone to query the server but only authenticated users to write to it.
+    #[inline]
+    #[must_use]
+    pub fn with_anonymous_role(mut self, role: Role) -> Self {
+        self.anonymous_role = Some(role);
+        self
+    }
+
+    /// Limits the operations of the principals with the given role and of the requests without credentials given this role.
+    ///
+    /// ```
+    /// use oxhttp::model::{Body, Request, StatusCode};
+    /// use oxigraph::store::Store;
+    /// use oxigraph_server::{AuthMiddleware, QueryLimits, Role, SparqlServer};
+    ///
+    /// let server = SparqlServer::new(Store::new()?).with_middleware(
+    ///     AuthMiddleware::new()
+    ///         .with_anonymous_role(Role::Read)
+    ///         .with_role_limits(Role::Read, QueryLimits::new().with_daily_budget(1)),
+    /// );
+    ///
+    /// let mut request = Request::builder()
+    ///     .uri("http://localhost/query?query=ASK%7B%7D")
+    ///     .body(Body::empty())?;
+    /// assert_eq!(server.handle(&mut request).status(), StatusCode::OK);
+    /// assert_eq!(server.handle(&mut request).status(), StatusCode::TOO_MANY_REQUESTS);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_role_limits(mut self, role: Role, limits: QueryLimits) -> Self {
+        self.role_limits.insert(role, limits);
+        self
+    }
+
+    /// Limits the operations of the user or token named `name`, replacing the limits of its role.
+    #[inline]
+    #[must_use]
+    pub fn with_principal_limits(mut self, name: impl Into<String>, limits: QueryLimits) -> Self {
+        self.principal_limits.insert(name.into(), limits);
+        self
+    }
+
+    /// Inserts the limits of the principal, `None` for the requests without credentials, into the request extensions
+    /// and counts the operations in the daily budget, returning the quota-exceeded response if it is exhausted
+    fn apply_limits(
+        &self,
+        request: &mut Request<Body>,
+        principal: Option<&Principal>,
+        role: Role,
+    ) -> Option<Response<Body>> {
+        let limits = PrincipalLimits {
+            limits: *principal
+                .and_then(|principal| self.principal_limits.get(principal.as_str()))
+                .or_else(|| self.role_limits.get(&role))?,
+            principal: principal.map(|principal| principal.as_str().to_owned()),
+            daily_usage: Arc::clone(&self.daily_usage),
+        };
+        if is_operation(request) {
+            if let Err(exceeded) = limits.charge_operation() {
+                return Some(exceeded.into_response());
+            }
+        }
+        request.extensions_mut().insert(limits);
+        None
+    }
+
+    fn unauthorized(&self, message: &str) -> Response<Body> {
+        let mut response = Response::builder().status(StatusCode::UNAUTHORIZED);
+        if !self.users.is_empty() {
+            response = response.header(WWW_AUTHENTICATE, "Basic realm=\"Oxigraph\"");
+        }
+        if !self.tokens.is_empty() {
+            response = response.header(WWW_AUTHENTICATE, "Bearer realm=\"Oxigraph\"");
+        }
+        response.body(message.to_owned().into()).unwrap()
+    }
+}
+
+impl Middleware for AuthMiddleware {
+    fn on_request(&self, request: &mut Request<Body>) -> Option<Response<Body>> {
+        let (required_role, action) = required_permission(request);
+        let Some(authorization) = request.headers().get(AUTHORIZATION) else {
+            return match self.anonymous_role {
+                Some(role) if role >= required_role => self.apply_limits(request, None, role),
+                _ => Some(self.unauthorized("Authentication is required")),
+            };
+        };
+        let Some((scheme, credentials)) = authorization
+            .to_str()
+            .ok()
+            .and_then(|authorization| authorization.split_once(' '))
+        else {
+            return Some(self.unauthorized("Invalid Authorization header"));
+        };
+        let credentials: [u8; 32] = Sha256::digest(credentials.trim().as_bytes()).into();
+        let found = if scheme.eq_ignore_ascii_case("Basic") {
+            self.users.get(&credentials)
+        } else if scheme.eq_ignore_ascii_case("Bearer") {
+            self.tokens.get(&credentials)
+        } else {
+            None
+        };
+        let Some((principal, role)) = found else {
+            return Some(self.unauthorized("Invalid credentials"));
+        };
+        if *role < required_role {
+            return Some(
+                Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body(format!("{} is not allowed to {action}", principal.as_str()).into())
+                    .unwrap(),
+            );
+        }
+        let (principal, role) = (principal.clone(), *role);
+        if let Some(response) = self.apply_limits(request, Some(&principal), role) {
+            return Some(response);
+        }
+        request.extensions_mut().insert(principal);
+        None
+    }
+}
+
+/// The number of operations of each principal during the current day, `None` being the requests without credentials
+#[derive(Default)]
+struct DailyUsage {
+    day: u64,
+    operations: HashMap<Option<String>, u64>,
+}
+
+/// The [`QueryLimits`] of the principal of a request, inserted into the request extensions by [`AuthMiddleware`]
+#[derive(Clone)]
+pub(crate) struct PrincipalLimits {
+    limits: QueryLimits,
+    principal: Option<String>,
+    daily_usage: Arc<Mutex<DailyUsage>>,
+}
+
+impl PrincipalLimits {
+    pub fn max_result_rows(&self) -> Option<u64> {
+        self.limits.max_result_rows
+    }
+
+    pub fn max_query_time(&self) -> Option<Duration> {
+        self.limits.max_query_time
+    }
+
+    /// Counts an operation in the daily budget, failing if it is exhausted
+    fn charge_operation(&self) -> Result<(), QuotaExceeded> {
+        let Some(daily_budget) = self.limits.daily_budget else {
+            return Ok(());
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut usage = self
+            .daily_usage
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if usage.day != now / SECONDS_PER_DAY {
+            usage.day = now / SECONDS_PER_DAY;
+            usage.operations.clear();
+        }
+        let operations = usage.operations.entry(self.principal.clone()).or_default();
+        if *operations >= daily_budget {
+            return Err(self.exceeded(
+                "dailyBudget",
+                daily_budget,
+                &format!("the daily budget of {daily_budget} operations is exhausted"),
+                Some(SECONDS_PER_DAY - now % SECONDS_PER_DAY),
+            ));
+        }
+        *operations += 1;
+        Ok(())
+    }
+
+    pub fn result_rows_exceeded(&self, max_result_rows: u64) -> QuotaExceeded {
+        self.exceeded(
+            "maxResultRows",
+            max_result_rows,
+            &format!("the query returns more than {max_result_rows} results"),
+            None,
+        )
+    }
+
+    pub fn query_time_exceeded(&self, max_query_time: Duration) -> QuotaExceeded {
+        self.exceeded(
+            "maxQueryTime",
+            max_query_time.as_millis().try_into().unwrap_or(u64::MAX),
+            &format!(
+                "the query evaluation takes more than {}s",
+                max_query_time.as_secs_f32()
+            ),
+            None,
+        )
+    }
+
+    fn exceeded(
+        &self,
+        quota: &'static str,
+        limit: u64,
+        reason: &str,
+        retry_after: Option<u64>,
+    ) -> QuotaExceeded {
+        QuotaExceeded {
+            quota,
+            limit,
+            principal: self.principal.clone(),
+            message: if let Some(principal) = &self.principal {
+                format!("Quota exceeded for {principal}: {reason}")
+            } else {
+                format!("Quota exceeded for the requests without credentials: {reason}")
+            },
+            retry_after,
+        }
+    }
+}
+
+/// A [`QueryLimits`] limit has been exceeded
+pub(crate) struct QuotaExceeded {
+    quota: &'static str,
+    limit: u64,
+    principal: Option<String>,
+    message: String,
+    /// Number of seconds until the daily budget is reset
+    retry_after: Option<u64>,
+}
+
+impl QuotaExceeded {
+    pub fn into_response(self) -> Response<Body> {
+        let mut body = WriterJsonSerializer::new(Vec::new());
+        for event in [
+            JsonEvent::StartObject,
+            JsonEvent::ObjectKey("error".into()),
+            JsonEvent::String("quota-exceeded".into()),
+            JsonEvent::ObjectKey("quota".into()),
+            JsonEvent::String(self.quota.into()),
+            JsonEvent::ObjectKey("limit".into()),
+            JsonEvent::Number(self.limit.to_string().into()),
+            JsonEvent::ObjectKey("principal".into()),
+            self.principal
+                .as_deref()
+                .map_or(JsonEvent::Null, |principal| {
+                    JsonEvent::String(principal.into())
+                }),
+            JsonEvent::ObjectKey("message".into()),
+            JsonEvent::String(self.message.as_str().into()),
+            JsonEvent::EndObject,
+        ] {
+            body.serialize_event(event).unwrap();
+        }
+        let mut response = Response::builder()
+            .status(if self.retry_after.is_some() {
+                StatusCode::TOO_MANY_REQUESTS
+            } else {
+                StatusCode::FORBIDDEN
+            })
+            .header(CONTENT_TYPE, "application/json");
+        if let Some(retry_after) = self.retry_after {
+            response = response.header(RETRY_AFTER, retry_after);
+        }
+        response.body(body.finish().unwrap().into()).unwrap()
+    }
+}
+
+/// Returns the role required by the request and the action it is doing, used in the `403 Forbidden` messages
+fn required_permission(request: &Request<Body>) -> (Role, &'static str) {
+    let path = request.uri().path();
+    if path.starts_with("/admin/") {
+        return (Role::Write, "use the /admin endpoints");
+    }
+    let is_write = match *request.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => false,
+        Method::POST => !matches!(path, "/query" | "/sql" | "/graphql"),
+        _ => true,
+    };
+    if is_write {
+        (Role::Write, "write")
+    } else {
+        (Role::Read, "read")
+    }
+}
+
+/// If the request is an operation limited by [`QueryLimits`] and counted in the daily budget
+fn is_operation(request: &Request<Body>) -> bool {
+    let path = request.uri().path();
+    match *request.method() {
+        Method::GET => {
+            matches!(path, "/sql" | "/graphql" | "/fragments" | "/changes")
+                || path.starts_with("/store")
+                || (path == "/query"
+                    && request.uri().query().is_some_and(|query| !query.is_empty()))
+        }
+        Method::HEAD => {
+            path == "/query" && request.uri().query().is_some_and(|query| !query.is_empty())
+        }
+        Method::POST => matches!(path, "/query" | "/update" | "/sql" | "/graphql"),
+        _ => false,
+    }
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let indexes = [
+            bytes[0] >> 2,
+            ((bytes[0] & 0b11) << 4) | (bytes[1] >> 4),
+            ((bytes[1] & 0b1111) << 2) | (bytes[2] >> 6),
+            bytes[2] & 0b11_1111,
+        ];
+        for (i, index) in indexes.into_iter().enumerate() {
+            output.push(if i <= chunk.len() {
+                char::from(BASE64_ALPHABET[usize::from(index)])
+            } else {
+                '='
+            });
+        }
+    }
+    output
+}
@@ -0,0 +1,185 @@
+//! Vocabulary metadata used by query editors for autocompletion.
+//!
+//! The metadata is a JSON object with the classes and predicates used in the dataset with their usage counts
+//! and prefixes for their namespaces:
+//! ```json
+//! {
+//!   "prefixes": {"schema": "http://schema.org/"},
+//!   "classes": [{"iri": "http://schema.org/Person", "count": 2}],
+//!   "predicates": [{"iri": "http://schema.org/name", "count": 3}]
+//! }
+//! ```
+//! Classes and predicates are sorted by decreasing usage count.
+use json_event_parser::{JsonEvent, WriterJsonSerializer};
+use oxigraph::model::vocab::rdf;
+use oxigraph::model::{NamedNode, Term};
+use oxigraph::store::{QuadShard, StorageError, Store};
+use std::collections::HashMap;
+use std::io;
+#[cfg(not(target_family = "wasm"))]
+use std::num::NonZeroUsize;
+#[cfg(not(target_family = "wasm"))]
+use std::thread;
+
+/// Well known prefixes used when a namespace of the dataset matches them
+const WELL_KNOWN_PREFIXES: [(&str, &str); 16] = [
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("owl", "http://www.w3.org/2002/07/owl#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("dcat", "http://www.w3.org/ns/dcat#"),
+    ("prov", "http://www.w3.org/ns/prov#"),
+    ("sh", "http://www.w3.org/ns/shacl#"),
+    ("void", "http://rdfs.org/ns/void#"),
+    ("geo", "http://www.opengis.net/ont/geosparql#"),
+    ("schema", "http://schema.org/"),
+    ("schema", "https://schema.org/"),
+    ("wd", "http://www.wikidata.org/entity/"),
+];
+
+#[derive(Default)]
+struct VocabularyUsage {
+    classes: HashMap<NamedNode, u64>,
+    predicates: HashMap<NamedNode, u64>,
+}
+
+impl VocabularyUsage {
+    fn merge(&mut self, other: Self) {
+        for (class, count) in other.classes {
+            *self.classes.entry(class).or_default() += count;
+        }
+        for (predicate, count) in other.predicates {
+            *self.predicates.entry(predicate).or_default() += count;
+        }
+    }
+}
+
+/// Serializes the autocompletion metadata of the store as JSON.
+///
+/// At most `limit` classes and `limit` predicates are returned.
+/// The full store is scanned to compute the usage counts.
+pub fn generate_autocomplete_metadata(store: &Store, limit: usize) -> io::Result<Vec<u8>> {
+    let usage = compute_usage(store)?;
+    let classes = most_used(usage.classes, limit);
+    let predicates = most_used(usage.predicates, limit);
+    let prefixes = prefixes(classes.iter().chain(&predicates));
+
+    let mut output = WriterJsonSerializer::new(Vec::new());
+    output.serialize_event(JsonEvent::StartObject)?;
+    output.serialize_event(JsonEvent::ObjectKey("prefixes".into()))?;
+    output.serialize_event(JsonEvent::StartObject)?;
+    for (prefix, namespace) in &prefixes {
+        output.serialize_event(JsonEvent::ObjectKey(prefix.as_str().into()))?;
+        output.serialize_event(JsonEvent::String(namespace.as_str().into()))?;
+    }
+    output.serialize_event(JsonEvent::EndObject)?;
+    for (key, entries) in [("classes", &classes), ("predicates", &predicates)] {
+        output.serialize_event(JsonEvent::ObjectKey(key.into()))?;
+        output.serialize_event(JsonEvent::StartArray)?;
+        for (iri, count) in entries {
+            output.serialize_event(JsonEvent::StartObject)?;
+            output.serialize_event(JsonEvent::ObjectKey("iri".into()))?;
+            output.serialize_event(JsonEvent::String(iri.as_str().into()))?;
+            output.serialize_event(JsonEvent::ObjectKey("count".into()))?;
+            output.serialize_event(JsonEvent::Number(count.to_string().into()))?;
+            output.serialize_event(JsonEvent::EndObject)?;
+        }
+        output.serialize_event(JsonEvent::EndArray)?;
+    }
+    output.serialize_event(JsonEvent::EndObject)?;
+    output.finish()
+}
+
+fn compute_usage(store: &Store) -> Result<VocabularyUsage, StorageError> {
+    #[cfg(not(target_family = "wasm"))]
+    let thread_count = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    #[cfg(target_family = "wasm")]
+    let thread_count = 1;
+    let shards = store.parallel_scan(None, None, None, None, thread_count)?;
+    let count_shard = |shard: &QuadShard| -> Result<VocabularyUsage, StorageError> {
+        let mut usage = VocabularyUsage::default();
+        for quad in shard {
+            let quad = quad?;
+            if quad.predicate == rdf::TYPE {
+                if let Term::NamedNode(class) = quad.object {
+                    *usage.classes.entry(class).or_default() += 1;
+                }
+            }
+            *usage.predicates.entry(quad.predicate).or_default() += 1;
+        }
+        Ok(usage)
+    };
+    let mut usage = VocabularyUsage::default();
+    #[cfg(not(target_family = "wasm"))]
+    thread::scope(|scope| {
+        let handles = shards
+            .iter()
+            .map(|shard| scope.spawn(|| count_shard(shard)))
+            .collect::<Vec<_>>();
+        for handle in handles {
+            usage.merge(handle.join().map_err(|_| {
+                StorageError::Other("The vocabulary usage computation panicked".into())
+            })??);
+        }
+        Ok::<_, StorageError>(())
+    })?;
+    #[cfg(target_family = "wasm")]
+    for shard in &shards {
+        usage.merge(count_shard(shard)?);
+    }
+    Ok(usage)
+}
+
+fn most_used(counts: HashMap<NamedNode, u64>, limit: usize) -> Vec<(NamedNode, u64)> {
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_unstable_by(|(iri1, count1), (iri2, count2)| {
+        count2.cmp(count1).then_with(|| iri1.cmp(iri2))
+    });
+    counts.truncate(limit);
+    counts
+}
+
+/// Builds prefixes for the namespaces of the given IRIs, using well known prefixes if possible
+fn prefixes<'a>(iris: impl IntoIterator<Item = &'a (NamedNode, u64)>) -> Vec<(String, String)> {
+    let mut namespace_counts = HashMap::<&str, u64>::new();
+    for (iri, count) in iris {
+        if let Some(namespace) = namespace(iri.as_str()) {
+            *namespace_counts.entry(namespace).or_default() += count;
+        }
+    }
+    let mut namespaces = namespace_counts.into_iter().collect::<Vec<_>>();
+    namespaces.sort_unstable_by(|(namespace1, count1), (namespace2, count2)| {
+        count2.cmp(count1).then_with(|| namespace1.cmp(namespace2))
+    });
+    let mut prefixes = Vec::<(String, String)>::new();
+    let mut generated_prefix_count = 0;
+    for (namespace, _) in namespaces {
+        let well_known_prefix = WELL_KNOWN_PREFIXES
+            .iter()
+            .find(|(prefix, well_known_namespace)| {
+                *well_known_namespace == namespace && !prefixes.iter().any(|(p, _)| p == prefix)
+            })
+            .map(|(prefix, _)| (*prefix).to_owned());
+        let prefix = well_known_prefix.unwrap_or_else(|| {
+            loop {
+                generated_prefix_count += 1;
+                let prefix = format!("ns{generated_prefix_count}");
+                if !prefixes.iter().any(|(p, _)| *p == prefix) {
+                    break prefix;
+                }
+            }
+        });
+        prefixes.push((prefix, namespace.to_owned()));
+    }
+    prefixes
+}
+
+/// The IRI up to its last `#` or `/` if there is a local name after it
+fn namespace(iri: &str) -> Option<&str> {
+    let end = iri.rfind('#').or_else(|| iri.rfind('/'))? + 1;
+    (end < iri.len()).then(|| &iri[..end])
+}
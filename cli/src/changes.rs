@@ -0,0 +1,52 @@
+//! Changes committed to the store, streamed as [server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+//!
+//! A client listens to `/changes`, optionally with `graph={graph-iri}` and `default` parameters to only follow some graphs.
+//! Each committed transaction is sent as a `change` event with a [RDF Patch](https://afs.github.io/rdf-delta/rdf-patch.html) payload,
+//! one `A` (addition) or `D` (deletion) row per data line:
+//! ```text
+//! event: change
+//! data: D <http://example.com/s> <http://example.com/p> "old" <http://example.com/g> .
+//! data: A <http://example.com/s> <http://example.com/p> "new" <http://example.com/g> .
+//! ```
+//! A comment is sent when nothing has been committed for a while so that closed connections are detected.
+use oxigraph::model::GraphName;
+use oxigraph::store::StoreEvent;
+use oxttl::RdfPatchSerializer;
+use std::io::{self, Write};
+
+/// Writes the changes of a committed transaction to the `graphs` (all graphs if empty) as a server-sent event
+///
+/// Nothing is written if none of the changes is about the `graphs`.
+/// Returns the number of written changes.
+pub fn write_change_event(
+    mut writer: impl Write,
+    events: &[StoreEvent],
+    graphs: &[GraphName],
+) -> io::Result<usize> {
+    let is_followed = |event: &&StoreEvent| {
+        let (StoreEvent::Inserted(quad) | StoreEvent::Removed(quad)) = event;
+        graphs.is_empty() || graphs.contains(&quad.graph_name)
+    };
+    if !events.iter().any(|event| is_followed(&event)) {
+        return Ok(0);
+    }
+    let mut serializer = RdfPatchSerializer::new().low_level();
+    writeln!(writer, "event: change")?;
+    let mut written = 0;
+    for event in events.iter().filter(is_followed) {
+        written += 1;
+        write!(writer, "data: ")?;
+        match event {
+            StoreEvent::Inserted(quad) => serializer.serialize_addition(quad, &mut writer)?,
+            StoreEvent::Removed(quad) => serializer.serialize_deletion(quad, &mut writer)?,
+        }
+    }
+    writeln!(writer)?;
+    Ok(written)
+}
+
+/// Writes a server-sent event comment, ignored by the clients
+pub fn write_keep_alive(mut writer: impl Write) -> io::Result<()> {
+    writeln!(writer, ": keep-alive")?;
+    writeln!(writer)
+}
@@ -24,11 +24,143 @@ pub enum Command {
         /// Allows cross-origin requests
         #[arg(long)]
         cors: bool,
+        /// Origin allowed to send cross-origin requests, like "https://example.com"
+        ///
+        /// Can be repeated to allow several origins.
+        /// Unlike --cors that allows any origin, the browsers are then allowed to send credentials like the Authorization header.
+        #[arg(long, value_name = "ORIGIN", conflicts_with = "cors")]
+        cors_allow_origin: Vec<String>,
+        /// User allowed to access the server with HTTP Basic authentication, given as ROLE=USER:PASSWORD
+        ///
+        /// ROLE is "read" to query the server or "write" to also update it.
+        /// Can be repeated to add several users.
+        /// If some users or tokens are set, the requests without credentials are rejected unless --anonymous-role is set.
+        #[arg(long, value_name = "ROLE=USER:PASSWORD")]
+        auth_user: Vec<String>,
+        /// Bearer token allowed to access the server, given as ROLE=NAME:TOKEN
+        ///
+        /// ROLE is "read" to query the server or "write" to also update it.
+        /// NAME identifies the token in the audit graph.
+        /// Can be repeated to add several tokens.
+        #[arg(long, value_name = "ROLE=NAME:TOKEN")]
+        auth_token: Vec<String>,
+        /// File listing the users and tokens allowed to access the server, one per line
+        ///
+        /// The lines are "user ROLE=USER:PASSWORD" or "token ROLE=NAME:TOKEN" like the --auth-user and --auth-token values.
+        /// Empty lines and lines starting with "#" are ignored.
+        /// Unlike the command line options, it does not expose the secrets in the process list.
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        auth_file: Option<PathBuf>,
+        /// Role given to the requests without credentials: "read" or "write"
+        ///
+        /// By default, they are rejected if some users or tokens are set.
+        #[arg(long)]
+        anonymous_role: Option<String>,
+        /// Limits of the operations of the users and tokens with a role, given as ROLE:LIMITS
+        ///
+        /// LIMITS is a comma-separated list of "max-result-rows=COUNT", "max-query-time=SECONDS" and "daily-budget=COUNT",
+        /// like "read:max-result-rows=10000,daily-budget=1000".
+        /// They apply to the SPARQL, SQL and GraphQL queries and to the reads of `/store`, `/fragments` and `/changes`.
+        /// They also apply to the requests without credentials if --anonymous-role is set to ROLE.
+        /// Can be repeated to limit several roles.
+        #[arg(long, value_name = "ROLE:LIMITS")]
+        role_limits: Vec<String>,
+        /// Limits of the operations of a user or a token, given as NAME:LIMITS like the --role-limits values
+        ///
+        /// They replace the limits of its role.
+        /// Can be repeated to limit several users or tokens.
+        #[arg(long, value_name = "NAME:LIMITS")]
+        principal_limits: Vec<String>,
         /// If the SPARQL queries should look for triples in all the dataset graphs by default (i.e., without `GRAPH` operations)
         ///
         /// This is equivalent as setting the union-default-graph option in all SPARQL queries
         #[arg(long)]
         union_default_graph: bool,
+        /// File containing the SHACL shapes from which the schema of the GraphQL endpoint at `/graphql` is built
+        ///
+        /// The endpoint is disabled if not set.
+        #[cfg(feature = "graphql")]
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        graphql_schema: Option<PathBuf>,
+        /// Name of the graph in which the executed SPARQL updates are recorded
+        ///
+        /// Each update is recorded with its text, its execution time and the graphs it affects.
+        /// Updates are not recorded if not set.
+        #[arg(long, value_hint = ValueHint::Url)]
+        audit_graph: Option<String>,
+        /// Name of the graph in which the size of the dataset is recorded at each statistics rebuild
+        ///
+        /// The number of quads, of distinct terms and of quads per graph are recorded with the rebuild time
+        /// and their history and growth trend are exposed at `/statistics`.
+        /// The statistics are not recorded if not set.
+        #[arg(long, value_hint = ValueHint::Url)]
+        statistics_graph: Option<String>,
+        /// Number of seconds between two statistics rebuilds
+        ///
+        /// Each rebuild scans the full dataset.
+        #[arg(long, default_value_t = 86400, requires = "statistics_graph")]
+        statistics_interval: u64,
+        /// Exposes at `/admin/maintenance` an endpoint to put the dataset in read-only maintenance mode
+        ///
+        /// `PUT` enables the mode and waits for the running writes to finish, `DELETE` disables it and `GET` returns its status.
+        /// While enabled, SPARQL updates and Graph Store Protocol writes are rejected with a 503 status.
+        /// Beware, this endpoint is only authenticated if --auth-user, --auth-token or --auth-file is set.
+        #[arg(long)]
+        maintenance_endpoint: bool,
+        /// Predicate linking the aliases of a resource, like `http://www.w3.org/2002/07/owl#sameAs`
+        ///
+        /// If set, the SPARQL queries treat the resources linked by this predicate as a single node:
+        /// the patterns match the triples of all the aliases of their subject and object.
+        /// It makes the queries slower.
+        #[arg(long, value_hint = ValueHint::Url)]
+        identity_predicate: Option<String>,
+        /// Predicate whose string literal values are indexed for full-text search
+        ///
+        /// Can be repeated to index several predicates.
+        /// The index is built in memory at startup and the SPARQL queries can then use the
+        /// `?literal <http://oxigraph.org/text#search> "words"` pattern to find the indexed literals containing all the words.
+        #[arg(long, value_hint = ValueHint::Url)]
+        full_text_predicate: Vec<String>,
+        /// How the writes are persisted on disk: "sync", "async" or "interval(MS)"
+        ///
+        /// "sync" waits for each commit to be synced to the disk, grouping the concurrent commits into the same sync.
+        /// "async" does not wait for the disk: the last commits might be lost on a system crash.
+        /// "interval(MS)" is like "async" but also syncs the writes to the disk every MS milliseconds.
+        ///
+        /// By default, "async" is used.
+        #[arg(long, requires = "location")]
+        durability: Option<String>,
+        /// How much memory and how many threads the storage uses: "default" or "small"
+        ///
+        /// "small" uses small block caches and write buffers and a single background compaction thread
+        /// to run on devices with little memory like Raspberry Pis or CI runners.
+        #[arg(long, requires = "location")]
+        storage_profile: Option<String>,
+        /// Rejects the writes with a 503 Service Unavailable error instead of slowing them down
+        /// when the storage stalls the writes to let its compaction catch up
+        ///
+        /// The rejected writes are logged with the number of bytes pending compaction.
+        #[arg(long, requires = "location")]
+        write_stall_errors: bool,
+        /// Maximal number of quads of a named graph, given as GRAPH=COUNT
+        ///
+        /// Can be repeated to set quotas on several graphs.
+        /// SPARQL updates and Graph Store Protocol writes that would exceed the quota are rejected with a 507 status.
+        /// The writes with the `no_transaction` parameter are not checked.
+        #[arg(long, value_name = "GRAPH=COUNT")]
+        graph_max_quads: Vec<String>,
+        /// Maximal size in bytes of the N-Triples serialization of a named graph, given as GRAPH=BYTES
+        ///
+        /// Can be repeated to set quotas on several graphs.
+        /// SPARQL updates and Graph Store Protocol writes that would exceed the quota are rejected with a 507 status.
+        /// The writes with the `no_transaction` parameter are not checked.
+        #[arg(long, value_name = "GRAPH=BYTES")]
+        graph_max_bytes: Vec<String>,
+        /// Exposes at `/admin/quotas` the quotas of the graphs and their current size as JSON
+        ///
+        /// Beware, this endpoint is only authenticated if --auth-user, --auth-token or --auth-file is set.
+        #[arg(long)]
+        graph_quota_endpoint: bool,
     },
     /// Start Oxigraph HTTP server in read-only mode
     ///
@@ -44,11 +176,145 @@ pub enum Command {
         /// Allow cross-origin requests
         #[arg(long)]
         cors: bool,
+        /// Origin allowed to send cross-origin requests, like "https://example.com"
+        ///
+        /// Can be repeated to allow several origins.
+        /// Unlike --cors that allows any origin, the browsers are then allowed to send credentials like the Authorization header.
+        #[arg(long, value_name = "ORIGIN", conflicts_with = "cors")]
+        cors_allow_origin: Vec<String>,
+        /// User allowed to access the server with HTTP Basic authentication, given as ROLE=USER:PASSWORD
+        ///
+        /// ROLE is "read" to query the server or "write" to also update it.
+        /// Can be repeated to add several users.
+        /// If some users or tokens are set, the requests without credentials are rejected unless --anonymous-role is set.
+        #[arg(long, value_name = "ROLE=USER:PASSWORD")]
+        auth_user: Vec<String>,
+        /// Bearer token allowed to access the server, given as ROLE=NAME:TOKEN
+        ///
+        /// ROLE is "read" to query the server or "write" to also update it.
+        /// NAME identifies the token in the audit graph.
+        /// Can be repeated to add several tokens.
+        #[arg(long, value_name = "ROLE=NAME:TOKEN")]
+        auth_token: Vec<String>,
+        /// File listing the users and tokens allowed to access the server, one per line
+        ///
+        /// The lines are "user ROLE=USER:PASSWORD" or "token ROLE=NAME:TOKEN" like the --auth-user and --auth-token values.
+        /// Empty lines and lines starting with "#" are ignored.
+        /// Unlike the command line options, it does not expose the secrets in the process list.
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        auth_file: Option<PathBuf>,
+        /// Role given to the requests without credentials: "read" or "write"
+        ///
+        /// By default, they are rejected if some users or tokens are set.
+        #[arg(long)]
+        anonymous_role: Option<String>,
+        /// Limits of the operations of the users and tokens with a role, given as ROLE:LIMITS
+        ///
+        /// LIMITS is a comma-separated list of "max-result-rows=COUNT", "max-query-time=SECONDS" and "daily-budget=COUNT",
+        /// like "read:max-result-rows=10000,daily-budget=1000".
+        /// They apply to the SPARQL, SQL and GraphQL queries and to the reads of `/store`, `/fragments` and `/changes`.
+        /// They also apply to the requests without credentials if --anonymous-role is set to ROLE.
+        /// Can be repeated to limit several roles.
+        #[arg(long, value_name = "ROLE:LIMITS")]
+        role_limits: Vec<String>,
+        /// Limits of the operations of a user or a token, given as NAME:LIMITS like the --role-limits values
+        ///
+        /// They replace the limits of its role.
+        /// Can be repeated to limit several users or tokens.
+        #[arg(long, value_name = "NAME:LIMITS")]
+        principal_limits: Vec<String>,
         /// If the SPARQL queries should look for triples in all the dataset graphs by default (i.e. without `GRAPH` operations)
         ///
         /// This is equivalent as setting the union-default-graph option in all SPARQL queries
         #[arg(long)]
         union_default_graph: bool,
+        /// File containing the SHACL shapes from which the schema of the GraphQL endpoint at `/graphql` is built
+        ///
+        /// The endpoint is disabled if not set.
+        #[cfg(feature = "graphql")]
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        graphql_schema: Option<PathBuf>,
+    },
+    /// Start Oxigraph HTTP server in read-only mode following a read-write primary
+    ///
+    /// It opens the database as a secondary instance that regularly catches up with the changes written by the primary
+    /// `serve` process, allowing to scale read queries on the same machine or on a shared storage.
+    ServeSecondary {
+        /// Directory where the primary Oxigraph instance is writing to
+        #[arg(long, value_hint = ValueHint::DirPath)]
+        primary_location: PathBuf,
+        /// Directory to which the secondary Oxigraph instance writes its own log files
+        ///
+        /// It must not be shared with an other secondary instance.
+        #[arg(long, value_hint = ValueHint::DirPath)]
+        secondary_location: PathBuf,
+        /// Number of seconds between two catch-ups with the primary
+        #[arg(long, default_value_t = 1)]
+        catch_up_interval: u64,
+        /// Host and port to listen to
+        #[arg(short, long, default_value = "localhost:7878")]
+        bind: String,
+        /// Allow cross-origin requests
+        #[arg(long)]
+        cors: bool,
+        /// Origin allowed to send cross-origin requests, like "https://example.com"
+        ///
+        /// Can be repeated to allow several origins.
+        /// Unlike --cors that allows any origin, the browsers are then allowed to send credentials like the Authorization header.
+        #[arg(long, value_name = "ORIGIN", conflicts_with = "cors")]
+        cors_allow_origin: Vec<String>,
+        /// User allowed to access the server with HTTP Basic authentication, given as ROLE=USER:PASSWORD
+        ///
+        /// ROLE is "read" to query the server or "write" to also update it.
+        /// Can be repeated to add several users.
+        /// If some users or tokens are set, the requests without credentials are rejected unless --anonymous-role is set.
+        #[arg(long, value_name = "ROLE=USER:PASSWORD")]
+        auth_user: Vec<String>,
+        /// Bearer token allowed to access the server, given as ROLE=NAME:TOKEN
+        ///
+        /// ROLE is "read" to query the server or "write" to also update it.
+        /// NAME identifies the token in the audit graph.
+        /// Can be repeated to add several tokens.
+        #[arg(long, value_name = "ROLE=NAME:TOKEN")]
+        auth_token: Vec<String>,
+        /// File listing the users and tokens allowed to access the server, one per line
+        ///
+        /// The lines are "user ROLE=USER:PASSWORD" or "token ROLE=NAME:TOKEN" like the --auth-user and --auth-token values.
+        /// Empty lines and lines starting with "#" are ignored.
+        /// Unlike the command line options, it does not expose the secrets in the process list.
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        auth_file: Option<PathBuf>,
+        /// Role given to the requests without credentials: "read" or "write"
+        ///
+        /// By default, they are rejected if some users or tokens are set.
+        #[arg(long)]
+        anonymous_role: Option<String>,
+        /// Limits of the operations of the users and tokens with a role, given as ROLE:LIMITS
+        ///
+        /// LIMITS is a comma-separated list of "max-result-rows=COUNT", "max-query-time=SECONDS" and "daily-budget=COUNT",
+        /// like "read:max-result-rows=10000,daily-budget=1000".
+        /// They apply to the SPARQL, SQL and GraphQL queries and to the reads of `/store`, `/fragments` and `/changes`.
+        /// They also apply to the requests without credentials if --anonymous-role is set to ROLE.
+        /// Can be repeated to limit several roles.
+        #[arg(long, value_name = "ROLE:LIMITS")]
+        role_limits: Vec<String>,
+        /// Limits of the operations of a user or a token, given as NAME:LIMITS like the --role-limits values
+        ///
+        /// They replace the limits of its role.
+        /// Can be repeated to limit several users or tokens.
+        #[arg(long, value_name = "NAME:LIMITS")]
+        principal_limits: Vec<String>,
+        /// If the SPARQL queries should look for triples in all the dataset graphs by default (i.e. without `GRAPH` operations)
+        ///
+        /// This is equivalent as setting the union-default-graph option in all SPARQL queries
+        #[arg(long)]
+        union_default_graph: bool,
+        /// File containing the SHACL shapes from which the schema of the GraphQL endpoint at `/graphql` is built
+        ///
+        /// The endpoint is disabled if not set.
+        #[cfg(feature = "graphql")]
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        graphql_schema: Option<PathBuf>,
     },
     /// Create a database backup into a target directory
     ///
@@ -60,6 +326,9 @@ pub enum Command {
     /// but hard links will be used to point to the original database immutable snapshots.
     /// This allows cheap regular backups.
     ///
+    /// With --incremental, the destination is a backup repository keeping all the backup generations
+    /// and only the files not already in it are copied.
+    ///
     /// If you want to move your data to another RDF storage system, you should use the dump operation instead.
     Backup {
         /// Directory in which Oxigraph data are persisted
@@ -68,6 +337,12 @@ pub enum Command {
         /// Directory in which the backup will be written
         #[arg(short, long, value_hint = ValueHint::DirPath)]
         destination: PathBuf,
+        /// Adds an incremental backup to the backup repository in the destination directory
+        #[arg(long)]
+        incremental: bool,
+        /// Number of most recent backups to keep in the repository with --incremental
+        #[arg(long, requires = "incremental")]
+        keep: Option<u32>,
     },
     /// Load file(s) into the store
     ///
@@ -81,15 +356,17 @@ pub enum Command {
         /// If multiple files are provided, they are loaded in parallel.
         ///
         /// If no file is given, stdin is used as if it were the input file content.
-        /// In this case, the content format must be specified using the --format option.
+        /// In this case, the content format is guessed from its first bytes if the --format option is not set.
         #[arg(short, long, num_args = 0.., value_hint = ValueHint::FilePath)]
         file: Vec<PathBuf>,
         /// The format of the file(s) to load
         ///
         /// It can be an extension like "nt" or a MIME type like "application/n-triples".
+        /// When the "html" feature is enabled, "html" extracts the RDFa, microdata and JSON-LD data embedded in HTML pages.
         ///
-        /// By default, the format is guessed from the loaded file extension.
-        #[arg(long, required_unless_present = "file")]
+        /// By default, the format is guessed from the loaded file extension
+        /// or, if the file has no known extension, from its first bytes.
+        #[arg(long)]
         format: Option<String>,
         /// Base IRI of the file(s) to load
         #[arg(long, value_hint = ValueHint::Url)]
@@ -106,6 +383,59 @@ pub enum Command {
         /// Only available when loading a graph file (N-Triples, Turtle...) and not a dataset file (N-Quads, TriG...).
         #[arg(long, value_hint = ValueHint::Url)]
         graph: Option<String>,
+        /// Write the same database files each time the same files are loaded into a new database
+        ///
+        /// The files are loaded one after the other and their blank nodes get identifiers derived from their position in the file list.
+        /// It is useful to build reproducible container images with a pre-loaded dataset.
+        #[arg(long)]
+        reproducible: bool,
+        /// Public key used to check the signatures of the loaded files before loading them
+        ///
+        /// The key must be an Ed25519 public key in PEM format.
+        /// The signature of each file is read from the file path with the ".sig" extension added, as written by the "dump --sign-key" command.
+        /// Loading is refused if a signature is missing or does not match the file content.
+        #[arg(long, requires = "file", value_hint = ValueHint::FilePath)]
+        verify_key: Option<PathBuf>,
+        /// Only print a warning when a signature checked with --verify-key is missing or invalid instead of refusing to load
+        #[arg(long, requires = "verify_key")]
+        warn_on_invalid_signature: bool,
+        /// Directory in which the loaded data is sorted before being ingested into the database
+        ///
+        /// The sorted data is spilled to temporary files in this directory instead of being kept in memory.
+        /// It makes the loading of datasets larger than the RAM faster.
+        /// The directory should have enough free space to store about twice the size of the loaded data.
+        #[arg(long, value_hint = ValueHint::DirPath)]
+        external_sort_dir: Option<PathBuf>,
+    },
+    /// Generate RDF from tabular files (CSV, TSV or JSON arrays of objects) and load it into the database
+    ///
+    /// The mapping follows the CSV on the Web (CSVW) minimal mode: each row is mapped to a blank node
+    /// and each cell to a triple with the column as predicate, unless a CSVW metadata file customizes it.
+    Map {
+        /// Directory in which Oxigraph data are persisted
+        #[arg(short, long, value_hint = ValueHint::DirPath)]
+        location: PathBuf,
+        /// File(s) to map
+        #[arg(short, long, num_args = 1.., required = true, value_hint = ValueHint::FilePath)]
+        file: Vec<PathBuf>,
+        /// CSVW metadata file describing the mapping
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        metadata: Option<PathBuf>,
+        /// The format of the file(s) to map: "csv", "tsv" or "json"
+        ///
+        /// By default, the format is guessed from the mapped file extension.
+        #[arg(long)]
+        format: Option<String>,
+        /// Base IRI used to build the default property IRIs and to resolve relative IRIs
+        ///
+        /// By default, the file URL is used.
+        #[arg(long, value_hint = ValueHint::Url)]
+        base: Option<String>,
+        /// Name of the graph to load the data to
+        ///
+        /// By default, the default graph is used.
+        #[arg(long, value_hint = ValueHint::Url)]
+        graph: Option<String>,
     },
     /// Dump the store content into a file
     Dump {
@@ -133,6 +463,35 @@ pub enum Command {
         /// If the format does not support named graphs, then this parameter must be set.
         #[arg(long, value_hint = ValueHint::Url)]
         graph: Option<String>,
+        /// Private key used to sign the dump
+        ///
+        /// The key must be an Ed25519 private key in PEM format, for example generated with "openssl genpkey -algorithm ed25519".
+        /// The signature of the canonical form of the dumped data is written next to the dump file with the ".sig" extension added.
+        /// The dumped data is loaded in memory to canonicalize the blank nodes.
+        #[arg(long, requires = "file", value_hint = ValueHint::FilePath)]
+        sign_key: Option<PathBuf>,
+        /// Write the quads sorted by graph name, subject, predicate and object
+        ///
+        /// It makes the dumps of the same content byte-identical and easy to diff.
+        /// The quads that do not fit in memory are sorted in temporary files.
+        #[arg(long)]
+        sort: bool,
+    },
+    /// Write a reproducible release bundle of the store content
+    ///
+    /// The bundle contains the graphs as canonical N-Quads files (default.nq and graphs/{SHA-256 of the graph name}.nq)
+    /// with sorted lines, a VoID description (void.ttl) and the SHA-256 checksums of these files (SHA256SUMS).
+    ///
+    /// The full store content is loaded in memory to canonicalize the blank nodes.
+    Publish {
+        /// Directory in which Oxigraph data are persisted
+        #[arg(short, long, value_hint = ValueHint::DirPath)]
+        location: PathBuf,
+        /// Directory in which the bundle will be written
+        ///
+        /// It must be empty or not exist yet.
+        #[arg(short, long, value_hint = ValueHint::DirPath)]
+        out: PathBuf,
     },
     /// Execute a SPARQL query against the store
     Query {
@@ -161,6 +520,7 @@ pub enum Command {
         /// The results format
         ///
         /// It can be an extension like "nt" or a MIME type like "application/n-triples".
+        /// SELECT results can also be written in the Apache Arrow IPC streaming format with "arrows".
         ///
         /// By default, the format is guessed from the results file extension.
         #[arg(long, required_unless_present = "results_file")]
@@ -185,6 +545,18 @@ pub enum Command {
         /// If the SPARQL queries should look for triples in all the dataset graphs by default (ie. without `GRAPH` operations)
         #[arg(long)]
         union_default_graph: bool,
+        /// CSV or TSV file joined with the query like a `VALUES` clause at the end of the query
+        ///
+        /// The first row contains the variable names.
+        /// Cells in N-Triples syntax like `<http://example.com>` or `12` are parsed as RDF terms, the other non-empty cells as simple literals.
+        /// Empty cells are unbound.
+        ///
+        /// The format is guessed from the file extension and is CSV by default.
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        values_file: Option<PathBuf>,
+        /// Print to stderr every second the number of results returned and of quads read so far
+        #[arg(long)]
+        progress: bool,
     },
     /// Execute a SPARQL update against the store
     Update {
@@ -205,6 +577,39 @@ pub enum Command {
         #[arg(long, value_hint = ValueHint::Url)]
         update_base: Option<String>,
     },
+    /// Replay a log of SPARQL queries against the store
+    ///
+    /// The log is a JSON Lines file where each line is either a JSON string with a query
+    /// or a JSON object with the query in its "query" key.
+    ///
+    /// A TSV report with the duration and a hash of the results of each query is written.
+    /// The hash does not depend on the results order or on the blank node identifiers.
+    /// Use the --baseline option to compare it with the report of a previous replay,
+    /// e.g. done with an older Oxigraph version.
+    Replay {
+        /// Directory in which Oxigraph data are persisted
+        #[arg(short, long, value_hint = ValueHint::DirPath)]
+        location: PathBuf,
+        /// The query log file
+        #[arg(value_hint = ValueHint::FilePath)]
+        log: PathBuf,
+        /// Number of queries executed concurrently
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+        concurrency: u16,
+        /// File in which the replay report will be stored
+        ///
+        /// If no file is given, stdout is used.
+        #[arg(short, long, value_hint = ValueHint::FilePath)]
+        report_file: Option<PathBuf>,
+        /// Report of a previous replay to compare the durations and the results with
+        ///
+        /// The command fails if some results are different.
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        baseline: Option<PathBuf>,
+        /// If the SPARQL queries should look for triples in all the dataset graphs by default (ie. without `GRAPH` operations)
+        #[arg(long)]
+        union_default_graph: bool,
+    },
     /// Optimize the database storage
     ///
     /// Done by default in the background when serving requests.
@@ -220,13 +625,15 @@ pub enum Command {
         ///
         /// If no file is given, stdin is used as if it were the input file content.
         /// In this case, the content format must be specified using the --format option.
+        ///
+        /// Gzip and zstd compressed content is decompressed on the fly.
         #[arg(short, long, value_hint = ValueHint::FilePath)]
         from_file: Option<PathBuf>,
         /// The format of the file(s) to convert from
         ///
         /// It can be an extension like "nt" or a MIME type like "application/n-triples".
         ///
-        /// By default, the format is guessed from the input file extension.
+        /// By default, the format is guessed from the input file extension, ignoring the ".gz" and ".zst" compression extensions.
         #[arg(long, required_unless_present = "from_file")]
         from_format: Option<String>,
         /// Base IRI of the file to read
@@ -236,18 +643,34 @@ pub enum Command {
         ///
         /// If no file is given, stdout is used.
         /// In this case, the output format must be specified using the --to-format option.
+        ///
+        /// The file is compressed with gzip if its extension is ".gz" and with zstd if it is ".zst".
         #[arg(short, long, value_hint = ValueHint::FilePath)]
         to_file: Option<PathBuf>,
         /// The format of the file(s) to convert to
         ///
         /// It can be an extension like "nt" or a MIME type like "application/n-triples".
         ///
-        /// By default, the format is guessed from the target file extension.
+        /// By default, the format is guessed from the target file extension, ignoring the ".gz" and ".zst" compression extensions.
         #[arg(long, required_unless_present = "to_file")]
         to_format: Option<String>,
         /// Base IRI of the file to write
         #[arg(long, value_hint = ValueHint::Url)]
         to_base: Option<String>,
+        /// Prefix to use in the file to write, like "schema=http://schema.org/"
+        ///
+        /// It can be repeated.
+        /// The prefixes are added to the ones declared in the input file and take precedence over them.
+        #[arg(long, value_name = "NAME=IRI")]
+        prefix: Vec<String>,
+        /// Write the file in a more human-readable way
+        ///
+        /// Only Turtle and TriG support it.
+        /// The triples are grouped by subject and predicate, the blank nodes used once are written inline
+        /// and all the prefixes declared in the input file are reused.
+        /// The whole file is loaded in memory before being written.
+        #[arg(long)]
+        pretty: bool,
         /// Attempt to keep converting even if the data file is invalid
         ///
         /// This disables most of the validation on RDF content.
@@ -255,6 +678,7 @@ pub enum Command {
         lenient: bool,
         /// Only load the given named graph from the input file
         ///
+        /// Its triples are written in the default graph, or in the --to-graph graph if set.
         /// By default, all graphs are loaded.
         #[arg(long, conflicts_with = "from_default_graph", value_hint = ValueHint::Url)]
         from_graph: Option<String>,
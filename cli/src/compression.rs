@@ -0,0 +1,95 @@
+//! Transparent gzip and zstd (de)compression of the files read and written by the CLI.
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::ffi::OsStr;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+const GZIP_MAGIC_NUMBER: &[u8] = b"\x1F\x8B";
+const ZSTD_MAGIC_NUMBER: &[u8] = b"\x28\xB5\x2F\xFD";
+
+/// A compression format of a file
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Guesses the compression from the file extension (`gz` or `zst`)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(OsStr::to_str)? {
+            "gz" => Some(Self::Gzip),
+            "zst" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Removes the compression extension of the path if any, allowing to guess the format of the compressed content
+pub fn without_compression_extension(path: &Path) -> PathBuf {
+    if Compression::from_path(path).is_some() {
+        path.with_extension("")
+    } else {
+        path.into()
+    }
+}
+
+/// Decompresses the read bytes if they start with a gzip or zstd header
+pub fn decompressed_reader<'a>(reader: impl Read + 'a) -> io::Result<Box<dyn Read + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let start = reader.fill_buf()?;
+    Ok(if start.starts_with(GZIP_MAGIC_NUMBER) {
+        Box::new(MultiGzDecoder::new(reader))
+    } else if start.starts_with(ZSTD_MAGIC_NUMBER) {
+        Box::new(zstd::Decoder::with_buffer(reader)?)
+    } else {
+        Box::new(reader)
+    })
+}
+
+/// A writer compressing the written bytes if a [`Compression`] is given
+pub enum CompressedWriter<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    pub fn new(writer: W, compression: Option<Compression>) -> io::Result<Self> {
+        Ok(match compression {
+            None => Self::Plain(writer),
+            Some(Compression::Gzip) => {
+                Self::Gzip(GzEncoder::new(writer, flate2::Compression::default()))
+            }
+            Some(Compression::Zstd) => Self::Zstd(zstd::Encoder::new(writer, 0)?),
+        })
+    }
+
+    /// Writes the end of the compressed stream and returns the underlying writer
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Plain(writer) => Ok(writer),
+            Self::Gzip(writer) => writer.finish(),
+            Self::Zstd(writer) => writer.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            Self::Gzip(writer) => writer.write(buf),
+            Self::Zstd(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            Self::Gzip(writer) => writer.flush(),
+            Self::Zstd(writer) => writer.flush(),
+        }
+    }
+}
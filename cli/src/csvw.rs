@@ -0,0 +1,759 @@
+//! Generation of RDF from tabular data (CSV, TSV and JSON arrays of objects).
+//!
+//! The mapping follows the [CSV on the Web](https://www.w3.org/TR/csv2rdf/) minimal mode.
+//! Each row is mapped to a subject, by default a blank node, and each non-empty cell to a triple
+//! whose predicate is by default `<{base}#{column name}>` and object a literal with the cell value.
+//!
+//! A [CSVW metadata file](https://www.w3.org/TR/tabular-metadata/) might be used to customize the mapping.
+//! The supported properties are `dialect` (`delimiter` and `header`) and `tableSchema` with `columns`
+//! and the inherited `aboutUrl`, `propertyUrl`, `valueUrl`, `datatype`, `lang`, `null` and `separator` properties.
+//! Columns also support `name`, `titles`, `suppressOutput` and `virtual`.
+use anyhow::{Context, bail};
+use json_event_parser::{JsonEvent, ReaderJsonParser};
+use oxigraph::model::vocab::{rdf, xsd};
+use oxigraph::model::{BlankNode, GraphName, Literal, NamedNode, NamedOrBlankNode, Quad, Term};
+use oxigraph::sparql::Variable;
+use oxiri::Iri;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::str::FromStr;
+
+/// Well known prefixes from the [CSVW initial context](https://www.w3.org/ns/csvw) usable in templates.
+const PREFIXES: [(&str, &str); 10] = [
+    ("csvw", "http://www.w3.org/ns/csvw#"),
+    ("dc", "http://purl.org/dc/terms/"),
+    ("dcat", "http://www.w3.org/ns/dcat#"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("owl", "http://www.w3.org/2002/07/owl#"),
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("schema", "http://schema.org/"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+];
+
+/// A tabular data format.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TabularFormat {
+    Csv,
+    Tsv,
+    /// A JSON array of objects, each object being a row
+    Json,
+}
+
+impl TabularFormat {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" | "text/csv" => Some(Self::Csv),
+            "tsv" | "tab" | "text/tab-separated-values" => Some(Self::Tsv),
+            "json" | "application/json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Mapping from tabular data to RDF.
+#[derive(Default)]
+pub struct TabularMapping {
+    properties: Properties,
+    columns: Vec<Column>,
+    delimiter: Option<u8>,
+    header: bool,
+}
+
+/// The CSVW inherited properties
+#[derive(Default, Clone)]
+struct Properties {
+    about_url: Option<String>,
+    property_url: Option<String>,
+    value_url: Option<String>,
+    datatype: Option<NamedNode>,
+    lang: Option<String>,
+    null: Option<String>,
+    separator: Option<String>,
+}
+
+impl Properties {
+    fn inherit(mut self, parent: &Self) -> Self {
+        self.about_url = self.about_url.or_else(|| parent.about_url.clone());
+        self.property_url = self.property_url.or_else(|| parent.property_url.clone());
+        self.value_url = self.value_url.or_else(|| parent.value_url.clone());
+        self.datatype = self.datatype.or_else(|| parent.datatype.clone());
+        self.lang = self.lang.or_else(|| parent.lang.clone());
+        self.null = self.null.or_else(|| parent.null.clone());
+        self.separator = self.separator.or_else(|| parent.separator.clone());
+        self
+    }
+
+    fn set(&mut self, key: &str, value: JsonValue) -> anyhow::Result<()> {
+        let target = match key {
+            "aboutUrl" => &mut self.about_url,
+            "propertyUrl" => &mut self.property_url,
+            "valueUrl" => &mut self.value_url,
+            "lang" => &mut self.lang,
+            "null" => &mut self.null,
+            "separator" => &mut self.separator,
+            "datatype" => {
+                let datatype = match value {
+                    JsonValue::String(name) => name,
+                    JsonValue::Object(datatype) => {
+                        match datatype.into_iter().find(|(k, _)| k == "base") {
+                            Some((_, JsonValue::String(name))) => name,
+                            _ => bail!("Only datatypes with a base are supported"),
+                        }
+                    }
+                    _ => bail!("Invalid datatype"),
+                };
+                self.datatype = Some(datatype_iri(&datatype)?);
+                return Ok(());
+            }
+            _ => return Ok(()), // Not supported properties and annotations
+        };
+        let JsonValue::String(value) = value else {
+            bail!("The CSVW property {key} must be a string");
+        };
+        *target = Some(value);
+        Ok(())
+    }
+}
+
+struct Column {
+    name: String,
+    properties: Properties,
+    suppress_output: bool,
+    is_virtual: bool,
+}
+
+/// A table cell with the datatype implied by the source format if any.
+struct Cell {
+    value: String,
+    datatype: Option<NamedNode>,
+}
+
+type Row = Vec<(String, Vec<Cell>)>;
+
+impl TabularMapping {
+    /// The CSVW minimal mode mapping, without metadata.
+    pub fn minimal() -> Self {
+        Self {
+            header: true,
+            ..Self::default()
+        }
+    }
+
+    /// Reads a CSVW metadata file.
+    pub fn from_metadata(reader: impl Read) -> anyhow::Result<Self> {
+        let JsonValue::Object(metadata) = JsonValue::parse(reader)? else {
+            bail!("CSVW metadata must be a JSON object");
+        };
+        let mut mapping = Self::minimal();
+        let mut table_properties = Properties::default();
+        let mut schema_properties = Properties::default();
+        let mut columns = Vec::new();
+        for (key, value) in metadata {
+            match key.as_str() {
+                "dialect" => {
+                    let JsonValue::Object(dialect) = value else {
+                        bail!("The CSVW dialect must be an object");
+                    };
+                    for (key, value) in dialect {
+                        match (key.as_str(), value) {
+                            ("delimiter", JsonValue::String(delimiter)) => {
+                                let &[delimiter] = delimiter.as_bytes() else {
+                                    bail!("Only single byte CSV delimiters are supported");
+                                };
+                                mapping.delimiter = Some(delimiter);
+                            }
+                            ("header", JsonValue::Boolean(header)) => mapping.header = header,
+                            _ => (),
+                        }
+                    }
+                }
+                "tableSchema" => {
+                    let JsonValue::Object(schema) = value else {
+                        bail!("The CSVW tableSchema must be an object");
+                    };
+                    for (key, value) in schema {
+                        if key == "columns" {
+                            let JsonValue::Array(values) = value else {
+                                bail!("The CSVW columns must be an array");
+                            };
+                            columns = values;
+                        } else {
+                            schema_properties.set(&key, value)?;
+                        }
+                    }
+                }
+                _ => table_properties.set(&key, value)?,
+            }
+        }
+        let schema_properties = schema_properties.inherit(&table_properties);
+        mapping.properties = schema_properties.clone();
+        for (i, column) in columns.into_iter().enumerate() {
+            let JsonValue::Object(column) = column else {
+                bail!("The CSVW columns must be objects");
+            };
+            let mut name = None;
+            let mut titles = Vec::new();
+            let mut properties = Properties::default();
+            let mut suppress_output = false;
+            let mut is_virtual = false;
+            for (key, value) in column {
+                match (key.as_str(), value) {
+                    ("name", JsonValue::String(value)) => name = Some(value),
+                    ("titles", JsonValue::String(value)) => titles.push(value),
+                    ("titles", JsonValue::Array(values)) => {
+                        titles.extend(values.into_iter().filter_map(|v| match v {
+                            JsonValue::String(v) => Some(v),
+                            _ => None,
+                        }))
+                    }
+                    ("suppressOutput", JsonValue::Boolean(value)) => suppress_output = value,
+                    ("virtual", JsonValue::Boolean(value)) => is_virtual = value,
+                    (key, value) => properties.set(key, value)?,
+                }
+            }
+            let name = name
+                .or_else(|| titles.into_iter().next())
+                .unwrap_or_else(|| format!("_col.{}", i + 1));
+            if mapping.columns.iter().any(|c| c.name == name) {
+                bail!("The column name {name} is used multiple times");
+            }
+            mapping.columns.push(Column {
+                name,
+                properties: properties.inherit(&schema_properties),
+                suppress_output,
+                is_virtual,
+            });
+        }
+        Ok(mapping)
+    }
+
+    /// Maps the given tabular file to quads.
+    ///
+    /// `base` is used to build the default property IRIs and to resolve relative IRIs.
+    pub fn map<'a>(
+        &'a self,
+        format: TabularFormat,
+        reader: impl Read + 'a,
+        base: Iri<String>,
+        graph_name: GraphName,
+    ) -> impl Iterator<Item = anyhow::Result<Quad>> + 'a {
+        let rows: Box<dyn Iterator<Item = anyhow::Result<Row>>> = match format {
+            TabularFormat::Csv | TabularFormat::Tsv => Box::new(CsvRows {
+                reader: BufReader::new(reader),
+                delimiter: self.delimiter.unwrap_or(if format == TabularFormat::Tsv {
+                    b'\t'
+                } else {
+                    b','
+                }),
+                header: None,
+                mapping: self,
+            }),
+            TabularFormat::Json => Box::new(JsonRows {
+                parser: ReaderJsonParser::new(reader),
+                started: false,
+            }),
+        };
+        let mut buffer = VecDeque::new();
+        let mut rows = rows.enumerate();
+        std::iter::from_fn(move || {
+            loop {
+                if let Some(quad) = buffer.pop_front() {
+                    return Some(Ok(quad));
+                }
+                let (i, row) = rows.next()?;
+                match row.and_then(|row| self.map_row(&row, i + 1, &base, &graph_name)) {
+                    Ok(quads) => buffer.extend(quads),
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+        })
+    }
+
+    fn map_row(
+        &self,
+        row: &Row,
+        row_number: usize,
+        base: &Iri<String>,
+        graph_name: &GraphName,
+    ) -> anyhow::Result<Vec<Quad>> {
+        let default_subject = NamedOrBlankNode::from(BlankNode::default());
+        let mut quads = Vec::new();
+        // Virtual columns have no cells
+        let mut add_cells = |name: &str, properties: &Properties, cells: Option<&[Cell]>| {
+            let context = TemplateContext {
+                row,
+                row_number,
+                column_name: name,
+                column_value: None,
+            };
+            let subject = if let Some(about_url) = &properties.about_url {
+                expand_template_iri(about_url, &context, base)?.into()
+            } else {
+                default_subject.clone()
+            };
+            let predicate = if let Some(property_url) = &properties.property_url {
+                expand_template_iri(property_url, &context, base)?
+            } else {
+                NamedNode::new(format!(
+                    "{}#{}",
+                    base.as_str().split('#').next().unwrap_or_default(),
+                    percent_encode(name, false)
+                ))?
+            };
+            let Some(cells) = cells else {
+                if let Some(value_url) = &properties.value_url {
+                    quads.push(Quad::new(
+                        subject,
+                        predicate,
+                        expand_template_iri(value_url, &context, base)?,
+                        graph_name.clone(),
+                    ));
+                }
+                return Ok::<_, anyhow::Error>(());
+            };
+            for cell in cells {
+                let values = if let Some(separator) = &properties.separator {
+                    cell.value.split(separator.as_str()).collect()
+                } else {
+                    vec![cell.value.as_str()]
+                };
+                for value in values {
+                    if value == properties.null.as_deref().unwrap_or_default() {
+                        continue;
+                    }
+                    let object = if let Some(value_url) = &properties.value_url {
+                        let context = TemplateContext {
+                            column_value: Some(value),
+                            ..context
+                        };
+                        expand_template_iri(value_url, &context, base)?.into()
+                    } else if let Some(datatype) = &properties.datatype {
+                        Literal::new_typed_literal(value, datatype.clone()).into()
+                    } else if let Some(lang) = &properties.lang {
+                        Literal::new_language_tagged_literal(value, lang)
+                            .with_context(|| format!("Invalid language tag {lang}"))?
+                            .into()
+                    } else if let Some(datatype) = &cell.datatype {
+                        Literal::new_typed_literal(value, datatype.clone()).into()
+                    } else {
+                        Term::from(Literal::new_simple_literal(value))
+                    };
+                    quads.push(Quad::new(
+                        subject.clone(),
+                        predicate.clone(),
+                        object,
+                        graph_name.clone(),
+                    ));
+                }
+            }
+            Ok(())
+        };
+        if self.columns.is_empty() {
+            for (name, cells) in row {
+                add_cells(name, &self.properties, Some(cells))?;
+            }
+        } else {
+            for column in &self.columns {
+                if column.suppress_output {
+                    continue;
+                }
+                if column.is_virtual {
+                    if column.properties.value_url.is_none() {
+                        bail!("The virtual column {} must have a valueUrl", column.name);
+                    }
+                    add_cells(&column.name, &column.properties, None)?;
+                } else if let Some((_, cells)) = row.iter().find(|(name, _)| *name == column.name) {
+                    add_cells(&column.name, &column.properties, Some(cells))?;
+                }
+            }
+        }
+        Ok(quads)
+    }
+}
+
+fn datatype_iri(name: &str) -> anyhow::Result<NamedNode> {
+    Ok(match name {
+        "any" => NamedNode::new_unchecked("http://www.w3.org/2001/XMLSchema#anyAtomicType"),
+        "binary" => NamedNode::from(xsd::BASE_64_BINARY),
+        "datetime" => xsd::DATE_TIME.into(),
+        "html" => rdf::HTML.into(),
+        "json" => NamedNode::new_unchecked("http://www.w3.org/ns/csvw#JSON"),
+        "number" => xsd::DOUBLE.into(),
+        "xml" => rdf::XML_LITERAL.into(),
+        _ if name.bytes().all(|c| c.is_ascii_alphabetic()) => {
+            NamedNode::new_unchecked(format!("http://www.w3.org/2001/XMLSchema#{name}"))
+        }
+        _ => NamedNode::new(expand_prefix(name).unwrap_or_else(|| name.into()))
+            .with_context(|| format!("Invalid datatype {name}"))?,
+    })
+}
+
+fn expand_prefix(name: &str) -> Option<String> {
+    let (prefix, local) = name.split_once(':')?;
+    if local.starts_with("//") {
+        return None;
+    }
+    let (_, namespace) = PREFIXES.iter().find(|(p, _)| *p == prefix)?;
+    Some(format!("{namespace}{local}"))
+}
+
+#[derive(Clone, Copy)]
+struct TemplateContext<'a> {
+    row: &'a Row,
+    row_number: usize,
+    column_name: &'a str,
+    /// The value of the current column if it should override the row cell (lists)
+    column_value: Option<&'a str>,
+}
+
+impl TemplateContext<'_> {
+    fn value(&self, variable: &str) -> Option<String> {
+        match variable {
+            "_row" => Some(self.row_number.to_string()),
+            "_name" => Some(self.column_name.into()),
+            _ if variable == self.column_name && self.column_value.is_some() => {
+                self.column_value.map(Into::into)
+            }
+            _ => self
+                .row
+                .iter()
+                .find(|(name, _)| name == variable)
+                .and_then(|(_, cells)| Some(cells.first()?.value.clone())),
+        }
+    }
+}
+
+/// Expands a [RFC 6570](https://www.rfc-editor.org/rfc/rfc6570) level 2 URI template and resolves it against the base IRI.
+fn expand_template_iri(
+    template: &str,
+    context: &TemplateContext<'_>,
+    base: &Iri<String>,
+) -> anyhow::Result<NamedNode> {
+    let mut expanded = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .with_context(|| format!("Unclosed expression in URI template {template}"))?
+            + start;
+        let expression = &rest[start + 1..end];
+        let (prefix, variable, reserved) = if let Some(variable) = expression.strip_prefix('+') {
+            ("", variable, true)
+        } else if let Some(variable) = expression.strip_prefix('#') {
+            ("#", variable, true)
+        } else {
+            ("", expression, false)
+        };
+        if let Some(value) = context.value(variable) {
+            expanded.push_str(prefix);
+            expanded.push_str(&percent_encode(&value, reserved));
+        }
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    if let Some(iri) = expand_prefix(&expanded) {
+        return Ok(NamedNode::new(iri)?);
+    }
+    Ok(NamedNode::new(
+        base.resolve(&expanded)
+            .with_context(|| format!("Invalid IRI {expanded}"))?
+            .into_inner(),
+    )?)
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+pub fn percent_encode(value: &str, allow_reserved: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric()
+            || matches!(c, '-' | '.' | '_' | '~')
+            || (allow_reserved && ":/?#[]@!$&'()*+,;=%".contains(c))
+        {
+            encoded.push(c);
+        } else {
+            let mut buffer = [0; 4];
+            for b in c.encode_utf8(&mut buffer).bytes() {
+                encoded.push('%');
+                encoded.push(char::from(HEX_DIGITS[usize::from(b >> 4)]));
+                encoded.push(char::from(HEX_DIGITS[usize::from(b & 15)]));
+            }
+        }
+    }
+    encoded
+}
+
+struct CsvRows<'a, R: BufRead> {
+    reader: R,
+    delimiter: u8,
+    header: Option<Vec<String>>,
+    mapping: &'a TabularMapping,
+}
+
+/// Reads a [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180) record.
+pub fn read_csv_record(
+    reader: &mut impl BufRead,
+    delimiter: u8,
+) -> anyhow::Result<Option<Vec<String>>> {
+    let mut line = Vec::new();
+    if reader.read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+    let mut fields = Vec::new();
+    let mut field = Vec::new();
+    let mut in_quotes = false;
+    let mut i = 0;
+    loop {
+        let Some(&c) = line.get(i) else {
+            if in_quotes {
+                // The quoted field contains a new line
+                if reader.read_until(b'\n', &mut line)? == 0 {
+                    bail!("Unterminated quoted CSV field");
+                }
+                continue;
+            }
+            break;
+        };
+        i += 1;
+        if in_quotes {
+            if c == b'"' {
+                if line.get(i) == Some(&b'"') {
+                    field.push(b'"');
+                    i += 1;
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == b'"' && field.is_empty() {
+            in_quotes = true;
+        } else if c == delimiter {
+            fields.push(String::from_utf8(std::mem::take(&mut field))?);
+        } else if c != b'\n' && !(c == b'\r' && line.get(i) == Some(&b'\n')) {
+            field.push(c);
+        }
+    }
+    fields.push(String::from_utf8(field)?);
+    Ok(Some(fields))
+}
+
+/// Reads CSV or TSV data with a header row of variable names as `VALUES` rows.
+///
+/// Cells in N-Triples syntax like `<http://example.com>`, `"foo"@en` or `12` are parsed as RDF terms,
+/// the other non-empty cells as simple literals. Empty cells are unbound.
+pub fn read_values(
+    mut reader: impl BufRead,
+    delimiter: u8,
+) -> anyhow::Result<Vec<Vec<(Variable, Term)>>> {
+    let Some(header) = read_csv_record(&mut reader, delimiter)? else {
+        return Ok(Vec::new());
+    };
+    let variables = header
+        .iter()
+        .map(|name| {
+            let name = name.trim();
+            Variable::new(name.strip_prefix(['?', '$']).unwrap_or(name))
+                .with_context(|| format!("Invalid variable name {name}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let mut rows = Vec::new();
+    while let Some(record) = read_csv_record(&mut reader, delimiter)? {
+        rows.push(
+            variables
+                .iter()
+                .zip(record)
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(variable, value)| {
+                    let term = match Term::from_str(&value) {
+                        Ok(term @ (Term::NamedNode(_) | Term::Literal(_))) => term,
+                        _ => Literal::new_simple_literal(value).into(),
+                    };
+                    (variable.clone(), term)
+                })
+                .collect(),
+        );
+    }
+    Ok(rows)
+}
+
+impl<R: BufRead> Iterator for CsvRows<'_, R> {
+    type Item = anyhow::Result<Row>;
+
+    fn next(&mut self) -> Option<anyhow::Result<Row>> {
+        if self.header.is_none() {
+            let header = if self.mapping.header {
+                match read_csv_record(&mut self.reader, self.delimiter) {
+                    Ok(Some(header)) => header,
+                    Ok(None) => return None,
+                    Err(e) => return Some(Err(e)),
+                }
+            } else {
+                Vec::new()
+            };
+            // With a schema, the cells are mapped to the non virtual columns by position
+            let header = if self.mapping.columns.is_empty() {
+                header
+            } else {
+                self.mapping
+                    .columns
+                    .iter()
+                    .filter(|c| !c.is_virtual)
+                    .map(|c| c.name.clone())
+                    .collect()
+            };
+            self.header = Some(header);
+        }
+        let record = match read_csv_record(&mut self.reader, self.delimiter) {
+            Ok(Some(record)) => record,
+            Ok(None) => return None,
+            Err(e) => return Some(Err(e)),
+        };
+        let header = self.header.as_ref()?;
+        Some(Ok(record
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| {
+                (
+                    header
+                        .get(i)
+                        .cloned()
+                        .unwrap_or_else(|| format!("_col.{}", i + 1)),
+                    vec![Cell {
+                        value,
+                        datatype: None,
+                    }],
+                )
+            })
+            .collect()))
+    }
+}
+
+struct JsonRows<R: Read> {
+    parser: ReaderJsonParser<R>,
+    started: bool,
+}
+
+impl<R: Read> JsonRows<R> {
+    fn read_row(&mut self) -> anyhow::Result<Option<Row>> {
+        if !self.started {
+            self.started = true;
+            if self.parser.parse_next()? != JsonEvent::StartArray {
+                bail!("The JSON input must be an array of objects");
+            }
+        }
+        match self.parser.parse_next()? {
+            JsonEvent::StartObject => (),
+            JsonEvent::EndArray => return Ok(None),
+            _ => bail!("The JSON input must be an array of objects"),
+        }
+        let mut row = Row::new();
+        loop {
+            let key = match self.parser.parse_next()? {
+                JsonEvent::ObjectKey(key) => key.into_owned(),
+                JsonEvent::EndObject => return Ok(Some(row)),
+                _ => bail!("Invalid JSON object"),
+            };
+            let mut cells = Vec::new();
+            match JsonValue::parse_next(&mut self.parser)? {
+                JsonValue::Array(values) => {
+                    for value in values {
+                        cells.extend(json_cell(value, &key)?);
+                    }
+                }
+                value => cells.extend(json_cell(value, &key)?),
+            }
+            row.push((key, cells));
+        }
+    }
+}
+
+fn json_cell(value: JsonValue, key: &str) -> anyhow::Result<Option<Cell>> {
+    Ok(Some(match value {
+        JsonValue::String(value) => Cell {
+            value,
+            datatype: None,
+        },
+        JsonValue::Number(value) => Cell {
+            datatype: Some(if value.contains(['.', 'e', 'E']) {
+                xsd::DOUBLE.into()
+            } else {
+                xsd::INTEGER.into()
+            }),
+            value,
+        },
+        JsonValue::Boolean(value) => Cell {
+            value: value.to_string(),
+            datatype: Some(xsd::BOOLEAN.into()),
+        },
+        JsonValue::Null => return Ok(None),
+        JsonValue::Array(_) | JsonValue::Object(_) => {
+            bail!("Nested JSON values are not supported, found one for the key {key}")
+        }
+    }))
+}
+
+impl<R: Read> Iterator for JsonRows<R> {
+    type Item = anyhow::Result<Row>;
+
+    fn next(&mut self) -> Option<anyhow::Result<Row>> {
+        self.read_row().transpose()
+    }
+}
+
+pub enum JsonValue {
+    String(String),
+    Number(String),
+    Boolean(bool),
+    Null,
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    pub fn parse(reader: impl Read) -> anyhow::Result<Self> {
+        Self::parse_next(&mut ReaderJsonParser::new(reader))
+    }
+
+    fn parse_next<R: Read>(parser: &mut ReaderJsonParser<R>) -> anyhow::Result<Self> {
+        Self::parse_next_or_array_end(parser)?.context("Unexpected end of JSON array")
+    }
+
+    /// Parses the next value or returns `None` if the end of the current array is reached
+    fn parse_next_or_array_end<R: Read>(
+        parser: &mut ReaderJsonParser<R>,
+    ) -> anyhow::Result<Option<Self>> {
+        Ok(Some(match parser.parse_next()? {
+            JsonEvent::EndArray => return Ok(None),
+            JsonEvent::String(value) => Self::String(value.into_owned()),
+            JsonEvent::Number(value) => Self::Number(value.into_owned()),
+            JsonEvent::Boolean(value) => Self::Boolean(value),
+            JsonEvent::Null => Self::Null,
+            JsonEvent::StartArray => {
+                let mut values = Vec::new();
+                while let Some(value) = Self::parse_next_or_array_end(parser)? {
+                    values.push(value);
+                }
+                Self::Array(values)
+            }
+            JsonEvent::StartObject => {
+                let mut values = Vec::new();
+                loop {
+                    let key = match parser.parse_next()? {
+                        JsonEvent::ObjectKey(key) => key.into_owned(),
+                        JsonEvent::EndObject => break,
+                        _ => bail!("Invalid JSON object"),
+                    };
+                    values.push((key, Self::parse_next(parser)?));
+                }
+                Self::Object(values)
+            }
+            JsonEvent::EndObject | JsonEvent::ObjectKey(_) | JsonEvent::Eof => {
+                bail!("Unexpected end of JSON value")
+            }
+        }))
+    }
+}
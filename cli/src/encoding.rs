@@ -0,0 +1,166 @@
+//! Support of the `gzip` and `zstd` HTTP [content codings](https://www.rfc-editor.org/rfc/rfc9110#name-content-codings):
+//! streaming decompression of the request bodies and streaming compression of the large responses.
+use crate::server::HttpError;
+use flate2::read::{GzEncoder, MultiGzDecoder};
+use oxhttp::model::header::{
+    ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, ETAG, VARY,
+};
+use oxhttp::model::{Body, HeaderValue, Method, Request, Response, StatusCode};
+use std::mem::take;
+use std::str::FromStr;
+
+/// Size under which the responses are not worth compressing
+const MIN_COMPRESSED_RESPONSE_SIZE: u64 = 1024;
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+enum ContentCoding {
+    Gzip,
+    Zstd,
+}
+
+impl ContentCoding {
+    fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("gzip") || name.eq_ignore_ascii_case("x-gzip") {
+            Some(Self::Gzip)
+        } else if name.eq_ignore_ascii_case("zstd") {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+/// Decompresses on the fly the request body according to its `Content-Encoding` header
+pub fn decode_request_body(request: &mut Request<Body>) -> Result<(), HttpError> {
+    let Some(content_encoding) = request.headers().get(CONTENT_ENCODING) else {
+        return Ok(());
+    };
+    let content_encoding = content_encoding.to_str().unwrap_or_default().trim();
+    if content_encoding.eq_ignore_ascii_case("identity") {
+        return Ok(());
+    }
+    let coding = ContentCoding::from_name(content_encoding).ok_or_else(|| {
+        (
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!(
+                "The content encoding '{content_encoding}' is not supported, only gzip and zstd are"
+            ),
+        )
+    })?;
+    let body = take(request.body_mut());
+    *request.body_mut() = match coding {
+        ContentCoding::Gzip => Body::from_read(MultiGzDecoder::new(body)),
+        ContentCoding::Zstd => Body::from_read(zstd::Decoder::new(body).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to start the zstd decompression: {e}"),
+            )
+        })?),
+    };
+    let headers = request.headers_mut();
+    headers.remove(CONTENT_ENCODING);
+    headers.remove(CONTENT_LENGTH);
+    Ok(())
+}
+
+/// Compresses on the fly the response body with the preferred coding of the `Accept-Encoding` header
+///
+/// Small responses and server-sent events streams are not compressed.
+/// The `ETag` of a compressed response is made weak because its bytes differ from the ones of the uncompressed representation.
+pub fn encode_response_body(request: &Request<Body>, response: &mut Response<Body>) {
+    if response.headers().contains_key(CONTENT_ENCODING)
+        || response
+            .headers()
+            .get(CONTENT_TYPE)
+            .is_some_and(|content_type| content_type.as_bytes().starts_with(b"text/event-stream"))
+    {
+        return;
+    }
+    // Set even if this response is not compressed, so that caches do not reuse HEAD or 304 responses across codings
+    if !response
+        .headers()
+        .get_all(VARY)
+        .iter()
+        .filter_map(|vary| vary.to_str().ok())
+        .flat_map(|vary| vary.split(','))
+        .any(|name| name.trim().eq_ignore_ascii_case("Accept-Encoding"))
+    {
+        response
+            .headers_mut()
+            .append(VARY, HeaderValue::from_static("Accept-Encoding"));
+    }
+    if request.method() == Method::HEAD
+        || response
+            .body()
+            .len()
+            .is_some_and(|len| len < MIN_COMPRESSED_RESPONSE_SIZE)
+    {
+        return;
+    }
+    let Some(coding) = request
+        .headers()
+        .get(ACCEPT_ENCODING)
+        .and_then(|accept_encoding| accept_encoding.to_str().ok())
+        .and_then(negotiate_coding)
+    else {
+        return;
+    };
+    let body = take(response.body_mut());
+    *response.body_mut() = match coding {
+        ContentCoding::Gzip => Body::from_read(GzEncoder::new(body, flate2::Compression::fast())),
+        ContentCoding::Zstd => match zstd::stream::read::Encoder::new(body, 0) {
+            Ok(encoder) => Body::from_read(encoder),
+            Err(e) => {
+                *response = Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(format!("Failed to start the zstd compression: {e}").into())
+                    .unwrap();
+                return;
+            }
+        },
+    };
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_ENCODING, HeaderValue::from_static(coding.name()));
+    if let Some(etag) = headers.get(ETAG) {
+        if !etag.as_bytes().starts_with(b"W/") {
+            let mut weak = b"W/".to_vec();
+            weak.extend_from_slice(etag.as_bytes());
+            if let Ok(weak) = HeaderValue::from_bytes(&weak) {
+                headers.insert(ETAG, weak);
+            }
+        }
+    }
+}
+
+/// Returns the supported coding with the highest quality value, the first one in case of a tie
+fn negotiate_coding(accept_encoding: &str) -> Option<ContentCoding> {
+    let mut result = None;
+    let mut result_score = 0_f32;
+    for possible in accept_encoding.split(',') {
+        let (name, parameters) = possible.split_once(';').unwrap_or((possible, ""));
+        let mut score = 1.;
+        if let Some((name, value)) = parameters.split_once('=') {
+            if name.trim().eq_ignore_ascii_case("q") {
+                let Ok(value) = f32::from_str(value.trim()) else {
+                    continue; // Invalid value, we ignore the coding
+                };
+                score = value;
+            }
+        }
+        if score <= result_score {
+            continue;
+        }
+        if let Some(coding) = ContentCoding::from_name(name.trim()) {
+            result = Some(coding);
+            result_score = score;
+        }
+    }
+    result
+}
@@ -0,0 +1,393 @@
+//! [SPARQL Anything](https://sparql-anything.cc/)-style facade exposing non-RDF files to SPARQL queries.
+//!
+//! A `SERVICE <x-sparql-anything:data.csv> { ... }` clause evaluates its pattern against the
+//! [Facade-X](https://sparql-anything.readthedocs.io/en/latest/Facade-X/) view of the file:
+//! * the document is a blank node of type `fx:root`,
+//! * CSV and TSV rows, JSON arrays and XML element children are containers whose members are linked with `rdf:_1`, `rdf:_2`...
+//! * CSV cells with the `csv.headers=true` option, JSON object keys and XML attributes are properties in the `xyz:` namespace,
+//! * XML elements are typed with their qualified name.
+//!
+//! The service IRI is either `x-sparql-anything:PATH` or a list of options like
+//! `x-sparql-anything:location=PATH,csv.headers=true`.
+//! The supported options are `location`, `media-type`, `csv.headers` and `csv.delimiter`.
+use crate::csvw::{JsonValue, TabularFormat, percent_encode, read_csv_record};
+use anyhow::{Context, bail};
+use oxigraph::model::vocab::{rdf, xsd};
+use oxigraph::model::{BlankNode, GraphName, Literal, NamedNode, Quad, Term};
+use oxigraph::sparql::{Query, QueryOptions, QueryResults, ServiceHandler};
+use oxigraph::store::Store;
+use quick_xml::NsReader;
+use quick_xml::events::Event;
+use quick_xml::name::{LocalName, ResolveResult};
+use spargebra::algebra::GraphPattern;
+use spargebra::term::NamedNodePattern;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// The prefix of the facade service IRIs
+pub const SERVICE_PREFIX: &str = "x-sparql-anything:";
+const FX_ROOT: &str = "http://sparql.xyz/facade-x/ns/root";
+const XYZ: &str = "http://sparql.xyz/facade-x/data/";
+const RDF_MEMBER_PREFIX: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#_";
+
+/// Registers a [`FacadeService`] for each facade service called by the query
+pub fn register_facade_services(
+    mut options: QueryOptions,
+    query: &spargebra::Query,
+) -> anyhow::Result<QueryOptions> {
+    let (spargebra::Query::Select { pattern, .. }
+    | spargebra::Query::Construct { pattern, .. }
+    | spargebra::Query::Describe { pattern, .. }
+    | spargebra::Query::Ask { pattern, .. }) = query;
+    let mut names = Vec::new();
+    collect_facade_services(pattern, &mut names);
+    for name in names {
+        let service = FacadeService::new(&name)?;
+        options = options.with_service_handler(name, service);
+    }
+    Ok(options)
+}
+
+fn collect_facade_services(pattern: &GraphPattern, names: &mut Vec<NamedNode>) {
+    match pattern {
+        GraphPattern::Service { name, inner, .. } => {
+            if let NamedNodePattern::NamedNode(name) = name {
+                if name.as_str().starts_with(SERVICE_PREFIX) && !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+            collect_facade_services(inner, names);
+        }
+        GraphPattern::Join { left, right }
+        | GraphPattern::LeftJoin { left, right, .. }
+        | GraphPattern::Lateral { left, right }
+        | GraphPattern::Union { left, right }
+        | GraphPattern::Minus { left, right } => {
+            collect_facade_services(left, names);
+            collect_facade_services(right, names);
+        }
+        GraphPattern::Filter { inner, .. }
+        | GraphPattern::Graph { inner, .. }
+        | GraphPattern::Extend { inner, .. }
+        | GraphPattern::OrderBy { inner, .. }
+        | GraphPattern::Project { inner, .. }
+        | GraphPattern::Distinct { inner }
+        | GraphPattern::Reduced { inner }
+        | GraphPattern::Slice { inner, .. }
+        | GraphPattern::Group { inner, .. } => collect_facade_services(inner, names),
+        GraphPattern::Bgp { .. } | GraphPattern::Path { .. } | GraphPattern::Values { .. } => (),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum FacadeFormat {
+    Csv,
+    Tsv,
+    Json,
+    Xml,
+}
+
+impl FacadeFormat {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match TabularFormat::from_extension(name) {
+            Some(TabularFormat::Csv) => Self::Csv,
+            Some(TabularFormat::Tsv) => Self::Tsv,
+            Some(TabularFormat::Json) => Self::Json,
+            None => match name.to_ascii_lowercase().as_str() {
+                "xml" | "application/xml" | "text/xml" => Self::Xml,
+                _ => return None,
+            },
+        })
+    }
+}
+
+/// Evaluates the service patterns against the Facade-X view of a file, loaded on the first call
+pub struct FacadeService {
+    location: PathBuf,
+    format: FacadeFormat,
+    csv_headers: bool,
+    csv_delimiter: Option<u8>,
+    store: OnceLock<Result<Store, String>>,
+}
+
+impl FacadeService {
+    fn new(name: &NamedNode) -> anyhow::Result<Self> {
+        let options = &name.as_str()[SERVICE_PREFIX.len()..];
+        let mut location = None;
+        let mut media_type = None;
+        let mut csv_headers = false;
+        let mut csv_delimiter = None;
+        if options.contains('=') {
+            for option in options.split(',') {
+                let (key, value) = option
+                    .split_once('=')
+                    .with_context(|| format!("Invalid facade option '{option}' in {name}"))?;
+                match key {
+                    "location" => location = Some(value),
+                    "media-type" => media_type = Some(value),
+                    "csv.headers" => {
+                        csv_headers = value
+                            .parse()
+                            .with_context(|| format!("Invalid csv.headers value in {name}"))?
+                    }
+                    "csv.delimiter" => {
+                        let &[delimiter] = value.as_bytes() else {
+                            bail!(
+                                "The csv.delimiter value must be a single ASCII character in {name}"
+                            )
+                        };
+                        csv_delimiter = Some(delimiter);
+                    }
+                    _ => bail!("Unsupported facade option '{key}' in {name}"),
+                }
+            }
+        } else {
+            location = Some(options);
+        }
+        let location = location.with_context(|| format!("No location given in {name}"))?;
+        let location = PathBuf::from(location.strip_prefix("file://").unwrap_or(location));
+        let format = if let Some(media_type) = media_type {
+            FacadeFormat::from_name(media_type)
+                .with_context(|| format!("Unsupported facade media type {media_type}"))?
+        } else {
+            location
+                .extension()
+                .and_then(|e| e.to_str())
+                .and_then(FacadeFormat::from_name)
+                .with_context(|| {
+                    format!(
+                        "Not able to guess the format of {}, use the media-type option",
+                        location.display()
+                    )
+                })?
+        };
+        Ok(Self {
+            location,
+            format,
+            csv_headers,
+            csv_delimiter,
+            store: OnceLock::new(),
+        })
+    }
+
+    fn load(&self) -> anyhow::Result<Store> {
+        let file = BufReader::new(File::open(&self.location).with_context(|| {
+            format!("Failed to open the facade file {}", self.location.display())
+        })?);
+        let mut builder = FacadeBuilder::default();
+        let root = BlankNode::default();
+        builder.add(
+            root.clone(),
+            rdf::TYPE.into(),
+            NamedNode::new_unchecked(FX_ROOT),
+        );
+        match self.format {
+            FacadeFormat::Csv => builder.add_csv(
+                &root,
+                file,
+                self.csv_delimiter.unwrap_or(b','),
+                self.csv_headers,
+            )?,
+            FacadeFormat::Tsv => builder.add_csv(
+                &root,
+                file,
+                self.csv_delimiter.unwrap_or(b'\t'),
+                self.csv_headers,
+            )?,
+            FacadeFormat::Json => builder.add_json(root, JsonValue::parse(file)?),
+            FacadeFormat::Xml => builder.add_xml(root, file)?,
+        }
+        let store = Store::new()?;
+        store.bulk_loader().load_quads(builder.quads)?;
+        Ok(store)
+    }
+}
+
+impl ServiceHandler for FacadeService {
+    type Error = io::Error;
+
+    fn handle(&self, query: Query) -> Result<QueryResults, io::Error> {
+        let store = self
+            .store
+            .get_or_init(|| self.load().map_err(|e| format!("{e:#}")))
+            .as_ref()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.clone()))?;
+        store.query(query).map_err(io::Error::other)
+    }
+}
+
+#[derive(Default)]
+struct FacadeBuilder {
+    quads: Vec<Quad>,
+}
+
+impl FacadeBuilder {
+    fn add(&mut self, subject: BlankNode, predicate: NamedNode, object: impl Into<Term>) {
+        self.quads.push(Quad::new(
+            subject,
+            predicate,
+            object,
+            GraphName::DefaultGraph,
+        ));
+    }
+
+    fn add_csv(
+        &mut self,
+        root: &BlankNode,
+        mut reader: BufReader<File>,
+        delimiter: u8,
+        headers: bool,
+    ) -> anyhow::Result<()> {
+        let header = if headers {
+            read_csv_record(&mut reader, delimiter)?.unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let mut row_index = 0;
+        while let Some(record) = read_csv_record(&mut reader, delimiter)? {
+            row_index += 1;
+            let row = BlankNode::default();
+            self.add(root.clone(), member(row_index), row.clone());
+            for (i, value) in record.into_iter().enumerate() {
+                let predicate = if let Some(name) = header.get(i) {
+                    data_property(name)
+                } else {
+                    member(i + 1)
+                };
+                self.add(row.clone(), predicate, Literal::new_simple_literal(value));
+            }
+        }
+        Ok(())
+    }
+
+    fn add_json(&mut self, root: BlankNode, value: JsonValue) {
+        match value {
+            JsonValue::Object(entries) => {
+                for (key, value) in entries {
+                    if let Some(object) = self.json_term(value) {
+                        self.add(root.clone(), data_property(&key), object);
+                    }
+                }
+            }
+            JsonValue::Array(values) => {
+                for (i, value) in values.into_iter().enumerate() {
+                    if let Some(object) = self.json_term(value) {
+                        self.add(root.clone(), member(i + 1), object);
+                    }
+                }
+            }
+            primitive => {
+                if let Some(object) = self.json_term(primitive) {
+                    self.add(root, member(1), object);
+                }
+            }
+        }
+    }
+
+    fn json_term(&mut self, value: JsonValue) -> Option<Term> {
+        Some(match value {
+            JsonValue::String(value) => Literal::new_simple_literal(value).into(),
+            JsonValue::Number(value) => Literal::new_typed_literal(
+                &value,
+                if value.contains(['.', 'e', 'E']) {
+                    xsd::DOUBLE
+                } else {
+                    xsd::INTEGER
+                },
+            )
+            .into(),
+            JsonValue::Boolean(value) => Literal::from(value).into(),
+            JsonValue::Null => return None,
+            nested @ (JsonValue::Array(_) | JsonValue::Object(_)) => {
+                let node = BlankNode::default();
+                self.add_json(node.clone(), nested);
+                node.into()
+            }
+        })
+    }
+
+    fn add_xml(&mut self, root: BlankNode, reader: BufReader<File>) -> anyhow::Result<()> {
+        let mut reader = NsReader::from_reader(reader);
+        let mut buffer = Vec::new();
+        // The open elements with their number of members
+        let mut stack = vec![(root, 0)];
+        loop {
+            let (namespace, event) = reader.read_resolved_event_into(&mut buffer)?;
+            let is_start = matches!(event, Event::Start(_));
+            match event {
+                Event::Start(element) | Event::Empty(element) => {
+                    let node = BlankNode::default();
+                    let name = xml_name(namespace, element.local_name())?;
+                    self.add_member(&mut stack, node.clone().into());
+                    self.add(node.clone(), rdf::TYPE.into(), name);
+                    for attribute in element.attributes() {
+                        let attribute = attribute?;
+                        if attribute.key.as_namespace_binding().is_some() {
+                            continue;
+                        }
+                        let (namespace, local_name) = reader.resolve_attribute(attribute.key);
+                        let value = attribute.decode_and_unescape_value(reader.decoder())?;
+                        self.add(
+                            node.clone(),
+                            xml_name(namespace, local_name)?,
+                            Literal::new_simple_literal(value),
+                        );
+                    }
+                    if is_start {
+                        stack.push((node, 0));
+                    }
+                }
+                Event::End(_) => {
+                    stack.pop();
+                }
+                Event::Text(text) => {
+                    let text = text.unescape()?;
+                    if !text.trim().is_empty() {
+                        self.add_member(&mut stack, Literal::new_simple_literal(text).into());
+                    }
+                }
+                Event::CData(text) => {
+                    self.add_member(
+                        &mut stack,
+                        Literal::new_simple_literal(text.decode()?).into(),
+                    );
+                }
+                Event::Eof => return Ok(()),
+                Event::Comment(_) | Event::Decl(_) | Event::PI(_) | Event::DocType(_) => (),
+            }
+            buffer.clear();
+        }
+    }
+
+    fn add_member(&mut self, stack: &mut [(BlankNode, usize)], member_value: Term) {
+        if let Some((parent, count)) = stack.last_mut() {
+            *count += 1;
+            let (parent, index) = (parent.clone(), *count);
+            self.add(parent, member(index), member_value);
+        }
+    }
+}
+
+fn member(index: usize) -> NamedNode {
+    NamedNode::new_unchecked(format!("{RDF_MEMBER_PREFIX}{index}"))
+}
+
+fn data_property(name: &str) -> NamedNode {
+    NamedNode::new_unchecked(format!("{XYZ}{}", percent_encode(name, false)))
+}
+
+fn xml_name(namespace: ResolveResult<'_>, local_name: LocalName<'_>) -> anyhow::Result<NamedNode> {
+    let local_name = std::str::from_utf8(local_name.into_inner())?;
+    Ok(match namespace {
+        ResolveResult::Bound(namespace) => NamedNode::new(format!(
+            "{}{local_name}",
+            std::str::from_utf8(namespace.into_inner())?
+        ))?,
+        ResolveResult::Unbound => data_property(local_name),
+        ResolveResult::Unknown(prefix) => {
+            bail!("Unknown XML prefix {}", String::from_utf8_lossy(&prefix))
+        }
+    })
+}
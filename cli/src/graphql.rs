@@ -0,0 +1,1172 @@
+//! A GraphQL endpoint whose schema is derived from SHACL shapes.
+//!
+//! Each `sh:NodeShape` becomes a GraphQL object type named after the shape local name.
+//! Its `sh:property` constraints become fields: the field name is the `sh:name` of the property (or the local name of its `sh:path`),
+//! `sh:node` and `sh:class` make the field an object field and `sh:maxCount 1` a single value field.
+//! All object types also get an `id` field returning the IRI (or blank node identifier) of the resource.
+//!
+//! Shapes with a `sh:targetClass` are exposed as root query fields with the same name as the type.
+//! These fields take optional `id`, `limit` and `offset` arguments and may also be filtered by scalar fields:
+//! `{ Person(name: "Alice") { id name knows { name } } }`.
+//!
+//! Each GraphQL query is translated into a single SPARQL query evaluated against the store.
+use json_event_parser::{JsonEvent, JsonSyntaxError, SliceJsonParser, WriterJsonSerializer};
+use oxigraph::io::{RdfFormat, RdfParser};
+use oxigraph::model::vocab::{rdf, xsd};
+use oxigraph::model::{Graph, NamedNode, NamedNodeRef, NamedOrBlankNodeRef, Term, TermRef, Triple};
+use oxigraph::sparql::{QueryResults, QuerySolution};
+use oxigraph::store::Store;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::{error, fmt, io};
+
+mod sh {
+    use oxigraph::model::NamedNodeRef;
+
+    pub const NODE_SHAPE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/shacl#NodeShape");
+    pub const TARGET_CLASS: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/shacl#targetClass");
+    pub const PROPERTY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/shacl#property");
+    pub const PATH: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/shacl#path");
+    pub const INVERSE_PATH: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/shacl#inversePath");
+    pub const NAME: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/shacl#name");
+    pub const NODE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/shacl#node");
+    pub const CLASS: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/shacl#class");
+    pub const MAX_COUNT: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/shacl#maxCount");
+}
+
+/// An error raised while building the schema or evaluating a GraphQL request.
+#[derive(Debug)]
+pub struct GraphQlError(String);
+
+impl GraphQlError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for GraphQlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl error::Error for GraphQlError {}
+
+impl From<io::Error> for GraphQlError {
+    fn from(error: io::Error) -> Self {
+        Self(error.to_string())
+    }
+}
+
+impl From<JsonSyntaxError> for GraphQlError {
+    fn from(error: JsonSyntaxError) -> Self {
+        Self(format!("Invalid JSON: {error}"))
+    }
+}
+
+/// The GraphQL schema mapped to the RDF data.
+pub struct GraphQlSchema {
+    types: BTreeMap<String, ObjectType>,
+}
+
+struct ObjectType {
+    target_class: Option<NamedNode>,
+    fields: BTreeMap<String, FieldDefinition>,
+}
+
+struct FieldDefinition {
+    predicate: NamedNode,
+    inverse: bool,
+    /// Name of the object type if the field is an object field
+    object_type: Option<String>,
+    single: bool,
+}
+
+impl GraphQlSchema {
+    /// Reads the SHACL shapes from an RDF file.
+    pub fn from_shacl_reader(format: RdfFormat, reader: impl Read) -> anyhow::Result<Self> {
+        let graph = RdfParser::from_format(format)
+            .for_reader(reader)
+            .map(|q| Ok(Triple::from(q?)))
+            .collect::<anyhow::Result<Graph>>()?;
+        Ok(Self::from_shacl(&graph)?)
+    }
+
+    /// Builds the schema from the SHACL shapes contained in the graph.
+    pub fn from_shacl(shapes: &Graph) -> Result<Self, GraphQlError> {
+        let mut type_names = HashMap::new();
+        for shape in shapes.subjects_for_predicate_object(rdf::TYPE, sh::NODE_SHAPE) {
+            let NamedOrBlankNodeRef::NamedNode(shape) = shape else {
+                return Err(GraphQlError::new(
+                    "SHACL node shapes must be identified by an IRI to be mapped to GraphQL types",
+                ));
+            };
+            let name = graphql_name(local_name(shape.as_str()));
+            if type_names.values().any(|n| *n == name) {
+                return Err(GraphQlError::new(format!(
+                    "Multiple SHACL shapes are mapped to the GraphQL type {name}"
+                )));
+            }
+            type_names.insert(shape, name);
+        }
+        let mut class_types = HashMap::new();
+        for (shape, name) in &type_names {
+            if let Some(TermRef::NamedNode(class)) =
+                shapes.object_for_subject_predicate(*shape, sh::TARGET_CLASS)
+            {
+                class_types.insert(class, name.clone());
+            }
+        }
+
+        let mut types = BTreeMap::new();
+        for (shape, name) in &type_names {
+            let mut fields = BTreeMap::new();
+            for property in shapes.objects_for_subject_predicate(*shape, sh::PROPERTY) {
+                let property = match property {
+                    TermRef::NamedNode(p) => NamedOrBlankNodeRef::from(p),
+                    TermRef::BlankNode(p) => p.into(),
+                    _ => continue,
+                };
+                let (predicate, inverse) = match shapes
+                    .object_for_subject_predicate(property, sh::PATH)
+                {
+                    Some(TermRef::NamedNode(path)) => (path, false),
+                    Some(TermRef::BlankNode(path)) => {
+                        let Some(TermRef::NamedNode(path)) =
+                            shapes.object_for_subject_predicate(path, sh::INVERSE_PATH)
+                        else {
+                            return Err(GraphQlError::new(format!(
+                                "Only IRIs and sh:inversePath are supported as sh:path in the shape {shape}"
+                            )));
+                        };
+                        (path, true)
+                    }
+                    _ => {
+                        return Err(GraphQlError::new(format!(
+                            "A property of the shape {shape} has no valid sh:path"
+                        )));
+                    }
+                };
+                let field_name = match shapes.object_for_subject_predicate(property, sh::NAME) {
+                    Some(TermRef::Literal(name)) => graphql_name(name.value()),
+                    _ => graphql_name(local_name(predicate.as_str())),
+                };
+                if field_name == "id" || field_name == "__typename" {
+                    return Err(GraphQlError::new(format!(
+                        "The field name {field_name} is reserved, found in shape {shape}"
+                    )));
+                }
+                let object_type = if let Some(TermRef::NamedNode(node)) =
+                    shapes.object_for_subject_predicate(property, sh::NODE)
+                {
+                    Some(type_names.get(&node).cloned().ok_or_else(|| {
+                        GraphQlError::new(format!(
+                            "The shape {node} used in sh:node is not a sh:NodeShape"
+                        ))
+                    })?)
+                } else if let Some(TermRef::NamedNode(class)) =
+                    shapes.object_for_subject_predicate(property, sh::CLASS)
+                {
+                    class_types.get(&class).cloned()
+                } else {
+                    None
+                };
+                let single = matches!(
+                    shapes.object_for_subject_predicate(property, sh::MAX_COUNT),
+                    Some(TermRef::Literal(count)) if count.value() == "1"
+                );
+                if fields
+                    .insert(
+                        field_name.clone(),
+                        FieldDefinition {
+                            predicate: predicate.into_owned(),
+                            inverse,
+                            object_type,
+                            single,
+                        },
+                    )
+                    .is_some()
+                {
+                    return Err(GraphQlError::new(format!(
+                        "The field {field_name} is defined multiple times in shape {shape}"
+                    )));
+                }
+            }
+            let target_class = match shapes.object_for_subject_predicate(*shape, sh::TARGET_CLASS) {
+                Some(TermRef::NamedNode(class)) => Some(class.into_owned()),
+                _ => None,
+            };
+            types.insert(
+                name.clone(),
+                ObjectType {
+                    target_class,
+                    fields,
+                },
+            );
+        }
+        Ok(Self { types })
+    }
+
+    /// Evaluates a GraphQL query document and returns the JSON serialization of the response.
+    pub fn execute(
+        &self,
+        store: &Store,
+        document: &str,
+        variables: &HashMap<String, Value>,
+    ) -> Result<Vec<u8>, GraphQlError> {
+        self.execute_with(document, variables, |query| {
+            let QueryResults::Solutions(solutions) = store
+                .query(query)
+                .map_err(|e| GraphQlError::new(e.to_string()))?
+            else {
+                return Err(GraphQlError::new(
+                    "The generated SPARQL query is not a SELECT",
+                ));
+            };
+            solutions
+                .collect::<Result<_, _>>()
+                .map_err(|e| GraphQlError::new(e.to_string()))
+        })
+    }
+
+    /// Evaluates a GraphQL query document like [`execute`](Self::execute), `evaluate` returning the solutions of the generated SPARQL queries.
+    pub(crate) fn execute_with(
+        &self,
+        document: &str,
+        variables: &HashMap<String, Value>,
+        mut evaluate: impl FnMut(&str) -> Result<Vec<QuerySolution>, GraphQlError>,
+    ) -> Result<Vec<u8>, GraphQlError> {
+        let (selection, mut defaults) = Parser::new(document).parse_document()?;
+        defaults.extend(variables.iter().map(|(k, v)| (k.clone(), v.clone())));
+        let variables = &defaults;
+        let mut output = WriterJsonSerializer::new(Vec::new());
+        output.serialize_event(JsonEvent::StartObject)?;
+        output.serialize_event(JsonEvent::ObjectKey("data".into()))?;
+        output.serialize_event(JsonEvent::StartObject)?;
+        for field in &selection {
+            output.serialize_event(JsonEvent::ObjectKey(field.response_key().into()))?;
+            if field.name == "__typename" {
+                output.serialize_event(JsonEvent::String("Query".into()))?;
+                continue;
+            }
+            let object_type = self.types.get(&field.name).ok_or_else(|| {
+                GraphQlError::new(format!("Cannot query field {} on type Query", field.name))
+            })?;
+            let class = object_type.target_class.as_ref().ok_or_else(|| {
+                GraphQlError::new(format!(
+                    "The type {} has no sh:targetClass and can't be queried directly",
+                    field.name
+                ))
+            })?;
+            let plan = self.plan_root_field(field, &field.name, object_type, class, variables)?;
+            let mut roots = ResultNode::default();
+            for solution in evaluate(&plan.sparql)? {
+                roots.add(&plan.root, &solution);
+            }
+            output.serialize_event(JsonEvent::StartArray)?;
+            if let Some(values) = roots.values.first() {
+                for (term, node) in &values.entries {
+                    write_object(&mut output, &plan.root.fields, term, node)?;
+                }
+            }
+            output.serialize_event(JsonEvent::EndArray)?;
+        }
+        output.serialize_event(JsonEvent::EndObject)?;
+        output.serialize_event(JsonEvent::EndObject)?;
+        output.finish().map_err(Into::into)
+    }
+
+    fn plan_root_field(
+        &self,
+        field: &Field,
+        type_name: &str,
+        object_type: &ObjectType,
+        class: &NamedNode,
+        variables: &HashMap<String, Value>,
+    ) -> Result<QueryPlan, GraphQlError> {
+        let mut root_pattern = format!("?v0 <{}> {class} .", rdf::TYPE.as_str());
+        let mut modifiers = String::new();
+        let mut filter_count = 0;
+        for (name, value) in &field.arguments {
+            let value = value.resolve(variables)?;
+            match name.as_str() {
+                "id" => {
+                    let Value::String(id) = value else {
+                        return Err(GraphQlError::new("The id argument must be a string"));
+                    };
+                    let id = NamedNode::new(id.as_str())
+                        .map_err(|e| GraphQlError::new(format!("Invalid id {id}: {e}")))?;
+                    root_pattern.push_str(" VALUES ?v0 { ");
+                    root_pattern.push_str(&id.to_string());
+                    root_pattern.push_str(" }");
+                }
+                "limit" | "offset" => {
+                    let Value::Int(count) = value else {
+                        return Err(GraphQlError::new(format!(
+                            "The {name} argument must be an integer"
+                        )));
+                    };
+                    if *count < 0 {
+                        return Err(GraphQlError::new(format!(
+                            "The {name} argument must be positive"
+                        )));
+                    }
+                    modifiers.push(' ');
+                    modifiers.push_str(&name.to_ascii_uppercase());
+                    modifiers.push(' ');
+                    modifiers.push_str(&count.to_string());
+                }
+                _ => {
+                    let definition = object_type.fields.get(name).ok_or_else(|| {
+                        GraphQlError::new(format!(
+                            "Unknown argument {name} on field Query.{type_name}"
+                        ))
+                    })?;
+                    let filter = match value {
+                        Value::String(v) => {
+                            format!("STR(?f{filter_count}) = {}", string_literal(v))
+                        }
+                        Value::Int(v) => format!("?f{filter_count} = {v}"),
+                        Value::Float(v) => format!("?f{filter_count} = {v:e}"),
+                        Value::Boolean(v) => format!("?f{filter_count} = {v}"),
+                        _ => {
+                            return Err(GraphQlError::new(format!(
+                                "Unsupported value for the argument {name}"
+                            )));
+                        }
+                    };
+                    root_pattern.push(' ');
+                    root_pattern.push_str(&triple_pattern(
+                        "?v0",
+                        definition,
+                        &format!("?f{filter_count}"),
+                    ));
+                    root_pattern.push_str(" FILTER(");
+                    root_pattern.push_str(&filter);
+                    root_pattern.push(')');
+                    filter_count += 1;
+                }
+            }
+        }
+        let mut sparql = String::new();
+        let mut variable_count = 1;
+        let root = self.plan_selection(
+            type_name,
+            object_type,
+            &field.selection_set,
+            0,
+            &mut variable_count,
+            &mut sparql,
+        )?;
+        let projection = (0..variable_count)
+            .map(|i| format!("?v{i}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(QueryPlan {
+            sparql: format!(
+                "SELECT {projection} WHERE {{ {{ SELECT DISTINCT ?v0 WHERE {{ {root_pattern} }} ORDER BY ?v0{modifiers} }} {sparql} }}"
+            ),
+            root: PlannedField {
+                variable: "v0".into(),
+                fields: root,
+            },
+        })
+    }
+
+    fn plan_selection(
+        &self,
+        type_name: &str,
+        object_type: &ObjectType,
+        selection_set: &[Field],
+        parent_variable: usize,
+        variable_count: &mut usize,
+        sparql: &mut String,
+    ) -> Result<Vec<PlannedSelection>, GraphQlError> {
+        if selection_set.is_empty() {
+            return Err(GraphQlError::new(format!(
+                "A selection set is required on the fields of type {type_name}"
+            )));
+        }
+        let mut planned = Vec::with_capacity(selection_set.len());
+        for field in selection_set {
+            if !field.arguments.is_empty() {
+                return Err(GraphQlError::new(format!(
+                    "Arguments are only supported on root fields, found on {type_name}.{}",
+                    field.name
+                )));
+            }
+            let kind = match field.name.as_str() {
+                "id" => PlannedKind::Id,
+                "__typename" => PlannedKind::TypeName(type_name.into()),
+                name => {
+                    let definition = object_type.fields.get(name).ok_or_else(|| {
+                        GraphQlError::new(format!("Cannot query field {name} on type {type_name}"))
+                    })?;
+                    let variable = *variable_count;
+                    *variable_count += 1;
+                    sparql.push_str("OPTIONAL { ");
+                    sparql.push_str(&triple_pattern(
+                        &format!("?v{parent_variable}"),
+                        definition,
+                        &format!("?v{variable}"),
+                    ));
+                    sparql.push(' ');
+                    let planned = if let Some(child_type_name) = &definition.object_type {
+                        let child_type = &self.types[child_type_name];
+                        PlannedKind::Object(
+                            PlannedField {
+                                variable: format!("v{variable}"),
+                                fields: self.plan_selection(
+                                    child_type_name,
+                                    child_type,
+                                    &field.selection_set,
+                                    variable,
+                                    variable_count,
+                                    sparql,
+                                )?,
+                            },
+                            definition.single,
+                        )
+                    } else {
+                        if !field.selection_set.is_empty() {
+                            return Err(GraphQlError::new(format!(
+                                "The field {type_name}.{name} is a scalar and can't have a selection set"
+                            )));
+                        }
+                        PlannedKind::Scalar(format!("v{variable}"), definition.single)
+                    };
+                    sparql.push_str("} ");
+                    planned
+                }
+            };
+            planned.push(PlannedSelection {
+                key: field.response_key().into(),
+                kind,
+            });
+        }
+        Ok(planned)
+    }
+}
+
+struct QueryPlan {
+    sparql: String,
+    root: PlannedField,
+}
+
+/// An object field: the variable binding the objects and the selected fields.
+struct PlannedField {
+    variable: String,
+    fields: Vec<PlannedSelection>,
+}
+
+struct PlannedSelection {
+    key: String,
+    kind: PlannedKind,
+}
+
+enum PlannedKind {
+    Id,
+    TypeName(String),
+    Scalar(String, bool),
+    Object(PlannedField, bool),
+}
+
+/// The distinct values of each field of an object, in the order of the query solutions.
+#[derive(Default)]
+struct ResultNode {
+    values: Vec<ResultValues>,
+}
+
+#[derive(Default)]
+struct ResultValues {
+    entries: Vec<(Term, ResultNode)>,
+    positions: HashMap<Term, usize>,
+}
+
+impl ResultValues {
+    fn entry(&mut self, term: &Term) -> &mut ResultNode {
+        let position = *self.positions.entry(term.clone()).or_insert_with(|| {
+            self.entries.push((term.clone(), ResultNode::default()));
+            self.entries.len() - 1
+        });
+        &mut self.entries[position].1
+    }
+}
+
+impl ResultNode {
+    /// Adds the solution values of `field` to this node
+    fn add(&mut self, field: &PlannedField, solution: &QuerySolution) {
+        if self.values.is_empty() {
+            self.values.push(ResultValues::default());
+        }
+        if let Some(term) = solution.get(field.variable.as_str()) {
+            self.values[0]
+                .entry(term)
+                .add_fields(&field.fields, solution);
+        }
+    }
+
+    fn add_fields(&mut self, fields: &[PlannedSelection], solution: &QuerySolution) {
+        if self.values.is_empty() {
+            self.values.resize_with(fields.len(), ResultValues::default);
+        }
+        for (field, values) in fields.iter().zip(&mut self.values) {
+            match &field.kind {
+                PlannedKind::Id | PlannedKind::TypeName(_) => (),
+                PlannedKind::Scalar(variable, _) => {
+                    if let Some(term) = solution.get(variable.as_str()) {
+                        values.entry(term);
+                    }
+                }
+                PlannedKind::Object(child, _) => {
+                    if let Some(term) = solution.get(child.variable.as_str()) {
+                        values.entry(term).add_fields(&child.fields, solution);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn write_object(
+    output: &mut WriterJsonSerializer<Vec<u8>>,
+    fields: &[PlannedSelection],
+    term: &Term,
+    node: &ResultNode,
+) -> Result<(), GraphQlError> {
+    output.serialize_event(JsonEvent::StartObject)?;
+    for (i, field) in fields.iter().enumerate() {
+        output.serialize_event(JsonEvent::ObjectKey(field.key.as_str().into()))?;
+        let values = node.values.get(i).map_or(&[][..], |v| v.entries.as_slice());
+        match &field.kind {
+            PlannedKind::Id => write_scalar(output, term)?,
+            PlannedKind::TypeName(name) => {
+                output.serialize_event(JsonEvent::String(name.as_str().into()))?
+            }
+            PlannedKind::Scalar(_, single) => {
+                write_values(output, values, *single, |output, (term, _)| {
+                    write_scalar(output, term)
+                })?
+            }
+            PlannedKind::Object(child, single) => {
+                write_values(output, values, *single, |output, (term, node)| {
+                    write_object(output, &child.fields, term, node)
+                })?
+            }
+        }
+    }
+    output
+        .serialize_event(JsonEvent::EndObject)
+        .map_err(Into::into)
+}
+
+fn write_values(
+    output: &mut WriterJsonSerializer<Vec<u8>>,
+    values: &[(Term, ResultNode)],
+    single: bool,
+    write_value: impl Fn(
+        &mut WriterJsonSerializer<Vec<u8>>,
+        &(Term, ResultNode),
+    ) -> Result<(), GraphQlError>,
+) -> Result<(), GraphQlError> {
+    if single {
+        if let Some(value) = values.first() {
+            write_value(output, value)
+        } else {
+            output.serialize_event(JsonEvent::Null).map_err(Into::into)
+        }
+    } else {
+        output.serialize_event(JsonEvent::StartArray)?;
+        for value in values {
+            write_value(output, value)?;
+        }
+        output
+            .serialize_event(JsonEvent::EndArray)
+            .map_err(Into::into)
+    }
+}
+
+fn write_scalar(
+    output: &mut WriterJsonSerializer<Vec<u8>>,
+    term: &Term,
+) -> Result<(), GraphQlError> {
+    let event = match term {
+        Term::NamedNode(node) => JsonEvent::String(node.as_str().into()),
+        Term::BlankNode(node) => JsonEvent::String(format!("_:{}", node.as_str()).into()),
+        Term::Literal(literal) => {
+            let datatype = literal.datatype();
+            if datatype == xsd::BOOLEAN && matches!(literal.value(), "true" | "false" | "1" | "0") {
+                JsonEvent::Boolean(matches!(literal.value(), "true" | "1"))
+            } else if is_numeric_datatype(datatype)
+                && literal.value().parse::<f64>().is_ok_and(f64::is_finite)
+            {
+                JsonEvent::Number(literal.value().trim_start_matches('+').into())
+            } else {
+                JsonEvent::String(literal.value().into())
+            }
+        }
+        #[cfg(feature = "rdf-12")]
+        Term::Triple(triple) => JsonEvent::String(triple.to_string().into()),
+    };
+    output.serialize_event(event).map_err(Into::into)
+}
+
+fn is_numeric_datatype(datatype: NamedNodeRef<'_>) -> bool {
+    [
+        xsd::INTEGER,
+        xsd::INT,
+        xsd::LONG,
+        xsd::SHORT,
+        xsd::NON_NEGATIVE_INTEGER,
+        xsd::POSITIVE_INTEGER,
+        xsd::DECIMAL,
+        xsd::DOUBLE,
+        xsd::FLOAT,
+    ]
+    .contains(&datatype)
+}
+
+fn triple_pattern(subject: &str, field: &FieldDefinition, object: &str) -> String {
+    if field.inverse {
+        format!("{object} {} {subject} .", field.predicate)
+    } else {
+        format!("{subject} {} {object} .", field.predicate)
+    }
+}
+
+fn string_literal(value: &str) -> String {
+    oxigraph::model::LiteralRef::new_simple_literal(value).to_string()
+}
+
+fn local_name(iri: &str) -> &str {
+    iri.rsplit(['#', '/', ':']).next().unwrap_or(iri)
+}
+
+/// Converts a string into a valid GraphQL name
+fn graphql_name(name: &str) -> String {
+    let mut result = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+    result
+}
+
+/// Serializes an error as a GraphQL response
+pub fn serialize_error(error: &GraphQlError) -> io::Result<Vec<u8>> {
+    let mut output = WriterJsonSerializer::new(Vec::new());
+    for event in [
+        JsonEvent::StartObject,
+        JsonEvent::ObjectKey("errors".into()),
+        JsonEvent::StartArray,
+        JsonEvent::StartObject,
+        JsonEvent::ObjectKey("message".into()),
+        JsonEvent::String(error.0.as_str().into()),
+        JsonEvent::EndObject,
+        JsonEvent::EndArray,
+        JsonEvent::EndObject,
+    ] {
+        output.serialize_event(event)?;
+    }
+    output.finish()
+}
+
+/// A GraphQL input value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Variable(String),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+    Null,
+    Enum(String),
+    List(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn resolve<'a>(
+        &'a self,
+        variables: &'a HashMap<String, Value>,
+    ) -> Result<&'a Self, GraphQlError> {
+        if let Self::Variable(name) = self {
+            variables
+                .get(name)
+                .ok_or_else(|| GraphQlError::new(format!("The variable ${name} is not defined")))
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// Parses a GraphQL over HTTP JSON request body
+///
+/// Returns the query document and the variables.
+pub fn parse_json_request(body: &[u8]) -> Result<(String, HashMap<String, Value>), GraphQlError> {
+    let mut parser = SliceJsonParser::new(body);
+    let mut query = None;
+    let mut variables = HashMap::new();
+    if parser.parse_next()? != JsonEvent::StartObject {
+        return Err(GraphQlError::new(
+            "The GraphQL request must be a JSON object",
+        ));
+    }
+    loop {
+        match parser.parse_next()? {
+            JsonEvent::ObjectKey(key) => match key.as_ref() {
+                "query" => match parser.parse_next()? {
+                    JsonEvent::String(value) => query = Some(value.into_owned()),
+                    _ => return Err(GraphQlError::new("The query must be a string")),
+                },
+                "variables" => match parse_json_value(&mut parser, None)? {
+                    Value::Object(values) => variables.extend(values),
+                    Value::Null => (),
+                    _ => return Err(GraphQlError::new("The variables must be an object")),
+                },
+                _ => {
+                    parse_json_value(&mut parser, None)?;
+                }
+            },
+            JsonEvent::EndObject => break,
+            _ => return Err(GraphQlError::new("Invalid GraphQL request")),
+        }
+    }
+    Ok((
+        query.ok_or_else(|| GraphQlError::new("The GraphQL request has no query"))?,
+        variables,
+    ))
+}
+
+/// Parses a JSON value as variables
+pub fn parse_json_variables(json: &[u8]) -> Result<HashMap<String, Value>, GraphQlError> {
+    match parse_json_value(&mut SliceJsonParser::new(json), None)? {
+        Value::Object(values) => Ok(values.into_iter().collect()),
+        Value::Null => Ok(HashMap::new()),
+        _ => Err(GraphQlError::new("The variables must be a JSON object")),
+    }
+}
+
+fn parse_json_value(
+    parser: &mut SliceJsonParser<'_>,
+    first: Option<JsonEvent<'_>>,
+) -> Result<Value, GraphQlError> {
+    let event = match first {
+        Some(event) => event,
+        None => parser.parse_next()?,
+    };
+    Ok(match event {
+        JsonEvent::String(value) => Value::String(value.into_owned()),
+        JsonEvent::Number(value) => {
+            if let Ok(value) = value.parse() {
+                Value::Int(value)
+            } else {
+                Value::Float(
+                    value
+                        .parse()
+                        .map_err(|_| GraphQlError::new(format!("Invalid number {value}")))?,
+                )
+            }
+        }
+        JsonEvent::Boolean(value) => Value::Boolean(value),
+        JsonEvent::Null => Value::Null,
+        JsonEvent::StartArray => {
+            let mut values = Vec::new();
+            loop {
+                match parser.parse_next()? {
+                    JsonEvent::EndArray => break,
+                    event => values.push(parse_json_value(parser, Some(event))?),
+                }
+            }
+            Value::List(values)
+        }
+        JsonEvent::StartObject => {
+            let mut values = Vec::new();
+            loop {
+                match parser.parse_next()? {
+                    JsonEvent::EndObject => break,
+                    JsonEvent::ObjectKey(key) => {
+                        let key = key.into_owned();
+                        values.push((key, parse_json_value(parser, None)?));
+                    }
+                    _ => return Err(GraphQlError::new("Invalid JSON object")),
+                }
+            }
+            Value::Object(values)
+        }
+        JsonEvent::EndArray | JsonEvent::EndObject | JsonEvent::ObjectKey(_) | JsonEvent::Eof => {
+            return Err(GraphQlError::new("Unexpected end of JSON value"));
+        }
+    })
+}
+
+/// A field of a GraphQL selection set
+#[derive(Debug, PartialEq)]
+struct Field {
+    alias: Option<String>,
+    name: String,
+    arguments: Vec<(String, Value)>,
+    selection_set: Vec<Field>,
+}
+
+impl Field {
+    fn response_key(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// A parser for the subset of the GraphQL query language supported by the endpoint:
+/// a single query operation without fragments nor directives.
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Punctuator(char),
+    Name(&'a str),
+    Int(i64),
+    Float(f64),
+    String(Cow<'a, str>),
+    Spread,
+    Eof,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// Returns the selection set of the operation and the default values of its variables
+    fn parse_document(&mut self) -> Result<(Vec<Field>, HashMap<String, Value>), GraphQlError> {
+        let mut defaults = HashMap::new();
+        let selection_set = match self.next_token()? {
+            Token::Punctuator('{') => self.parse_selection_set()?,
+            Token::Name("query") => {
+                let mut token = self.next_token()?;
+                if let Token::Name(_) = token {
+                    token = self.next_token()?;
+                }
+                if token == Token::Punctuator('(') {
+                    defaults = self.parse_variable_definitions()?;
+                    token = self.next_token()?;
+                }
+                if token != Token::Punctuator('{') {
+                    return Err(self.unexpected(&token));
+                }
+                self.parse_selection_set()?
+            }
+            Token::Name(kind @ ("mutation" | "subscription")) => {
+                return Err(GraphQlError::new(format!(
+                    "GraphQL {kind} operations are not supported"
+                )));
+            }
+            Token::Name("fragment") => {
+                return Err(GraphQlError::new("GraphQL fragments are not supported"));
+            }
+            token => return Err(self.unexpected(&token)),
+        };
+        match self.next_token()? {
+            Token::Eof => Ok((selection_set, defaults)),
+            Token::Name("fragment") => {
+                Err(GraphQlError::new("GraphQL fragments are not supported"))
+            }
+            _ => Err(GraphQlError::new(
+                "Only documents with a single operation are supported",
+            )),
+        }
+    }
+
+    /// Parses a selection set after its opening brace
+    fn parse_selection_set(&mut self) -> Result<Vec<Field>, GraphQlError> {
+        let mut fields = Vec::new();
+        loop {
+            let name = match self.next_token()? {
+                Token::Punctuator('}') => {
+                    if fields.is_empty() {
+                        return Err(GraphQlError::new("Selection sets can't be empty"));
+                    }
+                    return Ok(fields);
+                }
+                Token::Spread => {
+                    return Err(GraphQlError::new("GraphQL fragments are not supported"));
+                }
+                Token::Name(name) => name,
+                token => return Err(self.unexpected(&token)),
+            };
+            let mut field = Field {
+                alias: None,
+                name: name.into(),
+                arguments: Vec::new(),
+                selection_set: Vec::new(),
+            };
+            let mut token = self.peek_token()?;
+            if token == Token::Punctuator(':') {
+                self.next_token()?;
+                let Token::Name(name) = self.next_token()? else {
+                    return Err(GraphQlError::new("A field name is expected after an alias"));
+                };
+                field.alias = Some(field.name);
+                field.name = name.into();
+                token = self.peek_token()?;
+            }
+            if token == Token::Punctuator('(') {
+                self.next_token()?;
+                field.arguments = self.parse_arguments()?;
+                token = self.peek_token()?;
+            }
+            if token == Token::Punctuator('@') {
+                return Err(GraphQlError::new("GraphQL directives are not supported"));
+            }
+            if token == Token::Punctuator('{') {
+                self.next_token()?;
+                field.selection_set = self.parse_selection_set()?;
+            }
+            fields.push(field);
+        }
+    }
+
+    /// Parses arguments after their opening parenthesis
+    fn parse_arguments(&mut self) -> Result<Vec<(String, Value)>, GraphQlError> {
+        let mut arguments = Vec::new();
+        loop {
+            match self.next_token()? {
+                Token::Punctuator(')') => return Ok(arguments),
+                Token::Name(name) => {
+                    self.expect(':')?;
+                    let value = self.parse_value()?;
+                    arguments.push((name.into(), value));
+                }
+                token => return Err(self.unexpected(&token)),
+            }
+        }
+    }
+
+    /// Parses variable definitions after their opening parenthesis.
+    ///
+    /// Only default values are kept, the types are not validated.
+    fn parse_variable_definitions(&mut self) -> Result<HashMap<String, Value>, GraphQlError> {
+        let mut defaults = HashMap::new();
+        loop {
+            match self.next_token()? {
+                Token::Punctuator(')') => return Ok(defaults),
+                Token::Punctuator('$') => {
+                    let Token::Name(name) = self.next_token()? else {
+                        return Err(GraphQlError::new("A variable name is expected after $"));
+                    };
+                    self.expect(':')?;
+                    self.parse_type()?;
+                    if self.peek_token()? == Token::Punctuator('=') {
+                        self.next_token()?;
+                        defaults.insert(name.into(), self.parse_value()?);
+                    }
+                }
+                token => return Err(self.unexpected(&token)),
+            }
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<(), GraphQlError> {
+        match self.next_token()? {
+            Token::Name(_) => (),
+            Token::Punctuator('[') => {
+                self.parse_type()?;
+                self.expect(']')?;
+            }
+            token => return Err(self.unexpected(&token)),
+        }
+        if self.peek_token()? == Token::Punctuator('!') {
+            self.next_token()?;
+        }
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Value, GraphQlError> {
+        Ok(match self.next_token()? {
+            Token::Punctuator('$') => {
+                let Token::Name(name) = self.next_token()? else {
+                    return Err(GraphQlError::new("A variable name is expected after $"));
+                };
+                Value::Variable(name.into())
+            }
+            Token::Int(value) => Value::Int(value),
+            Token::Float(value) => Value::Float(value),
+            Token::String(value) => Value::String(value.into()),
+            Token::Name("true") => Value::Boolean(true),
+            Token::Name("false") => Value::Boolean(false),
+            Token::Name("null") => Value::Null,
+            Token::Name(name) => Value::Enum(name.into()),
+            Token::Punctuator('[') => {
+                let mut values = Vec::new();
+                while self.peek_token()? != Token::Punctuator(']') {
+                    values.push(self.parse_value()?);
+                }
+                self.next_token()?;
+                Value::List(values)
+            }
+            Token::Punctuator('{') => {
+                let mut values = Vec::new();
+                loop {
+                    match self.next_token()? {
+                        Token::Punctuator('}') => break,
+                        Token::Name(name) => {
+                            self.expect(':')?;
+                            values.push((name.into(), self.parse_value()?));
+                        }
+                        token => return Err(self.unexpected(&token)),
+                    }
+                }
+                Value::Object(values)
+            }
+            token => return Err(self.unexpected(&token)),
+        })
+    }
+
+    fn expect(&mut self, punctuator: char) -> Result<(), GraphQlError> {
+        let token = self.next_token()?;
+        if token == Token::Punctuator(punctuator) {
+            Ok(())
+        } else {
+            Err(GraphQlError::new(format!(
+                "Expected '{punctuator}', found {token:?}"
+            )))
+        }
+    }
+
+    fn unexpected(&self, token: &Token<'_>) -> GraphQlError {
+        GraphQlError::new(format!(
+            "Unexpected {token:?} at position {}",
+            self.position
+        ))
+    }
+
+    fn peek_token(&mut self) -> Result<Token<'a>, GraphQlError> {
+        let position = self.position;
+        let token = self.next_token();
+        self.position = position;
+        token
+    }
+
+    fn next_token(&mut self) -> Result<Token<'a>, GraphQlError> {
+        // We skip ignored tokens
+        loop {
+            let rest = &self.input[self.position..];
+            let trimmed = rest.trim_start_matches(|c: char| {
+                c.is_ascii_whitespace() || c == ',' || c == '\u{feff}'
+            });
+            self.position += rest.len() - trimmed.len();
+            if trimmed.starts_with('#') {
+                self.position += trimmed.find(['\n', '\r']).unwrap_or(trimmed.len());
+            } else {
+                break;
+            }
+        }
+        let rest = &self.input[self.position..];
+        let Some(c) = rest.chars().next() else {
+            return Ok(Token::Eof);
+        };
+        if rest.starts_with("...") {
+            self.position += 3;
+            return Ok(Token::Spread);
+        }
+        if "!$&():=@[]{}|".contains(c) {
+            self.position += c.len_utf8();
+            return Ok(Token::Punctuator(c));
+        }
+        if c == '_' || c.is_ascii_alphabetic() {
+            let end = rest
+                .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len());
+            self.position += end;
+            return Ok(Token::Name(&rest[..end]));
+        }
+        if c == '-' || c.is_ascii_digit() {
+            let end = rest[1..]
+                .find(|c: char| !c.is_ascii_digit() && !matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+                .map_or(rest.len(), |e| e + 1);
+            let number = &rest[..end];
+            self.position += end;
+            return if number.contains(['.', 'e', 'E']) {
+                number
+                    .parse()
+                    .map(Token::Float)
+                    .map_err(|_| GraphQlError::new(format!("Invalid float {number}")))
+            } else {
+                number
+                    .parse()
+                    .map(Token::Int)
+                    .map_err(|_| GraphQlError::new(format!("Invalid integer {number}")))
+            };
+        }
+        if c == '"' {
+            return self.parse_string();
+        }
+        Err(GraphQlError::new(format!(
+            "Unexpected character '{c}' at position {}",
+            self.position
+        )))
+    }
+
+    fn parse_string(&mut self) -> Result<Token<'a>, GraphQlError> {
+        let rest = &self.input[self.position..];
+        if let Some(block) = rest.strip_prefix("\"\"\"") {
+            let end = block
+                .find("\"\"\"")
+                .ok_or_else(|| GraphQlError::new("Unterminated block string"))?;
+            self.position += end + 6;
+            return Ok(Token::String(Cow::Owned(
+                block[..end].replace("\\\"\"\"", "\"\"\""),
+            )));
+        }
+        let mut value = String::new();
+        let mut chars = rest[1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += i + 2;
+                    return Ok(Token::String(Cow::Owned(value)));
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('/') => value.push('/'),
+                    Some('b') => value.push('\u{8}'),
+                    Some('f') => value.push('\u{c}'),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('u') => {
+                        let code = (0..4)
+                            .filter_map(|_| chars.next().map(|(_, c)| c))
+                            .collect::<String>();
+                        value.push(
+                            u32::from_str_radix(&code, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| {
+                                    GraphQlError::new(format!("Invalid unicode escape \\u{code}"))
+                                })?,
+                        );
+                    }
+                    _ => return Err(GraphQlError::new("Invalid string escape sequence")),
+                },
+                '\n' | '\r' => return Err(GraphQlError::new("Unterminated string")),
+                other => value.push(other),
+            }
+        }
+        Err(GraphQlError::new("Unterminated string"))
+    }
+}
@@ -0,0 +1,59 @@
+//! The Oxigraph SPARQL HTTP server as a library.
+//!
+//! [`SparqlServer`] implements the handlers of the `oxigraph serve` command.
+//! It can be spawned as a standalone HTTP server or its [`handle`](SparqlServer::handle) method can be called from another HTTP application
+//! to mount the SPARQL endpoint inside it.
+//! Authentication, logging or request transformations can be plugged in with a [`Middleware`].
+//! [`AuthMiddleware`] provides HTTP Basic and bearer token authentication with a read and a write role and per principal [`QueryLimits`].
+//! With the `wasi-http` feature, it can handle the requests of the [WASI HTTP](https://github.com/WebAssembly/wasi-http) interface with [`handle_wasi_request`].
+//! With the `tower` feature, [`SparqlServer`] is also a [`tower_service::Service`] that can be mounted in [axum](https://docs.rs/axum) or any other tower based application.
+//!
+//! ```no_run
+//! use oxigraph::store::Store;
+//! use oxigraph_server::SparqlServer;
+//!
+//! SparqlServer::new(Store::new()?)
+//!     .with_cors()
+//!     .spawn("localhost:7878")?
+//!     .join()?;
+//! # Result::<_, Box<dyn std::error::Error>>::Ok(())
+//! ```
+#![allow(clippy::print_stderr, clippy::cast_precision_loss)]
+#![doc(test(attr(deny(warnings))))]
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![doc(html_favicon_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+#![doc(html_logo_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+
+mod audit;
+mod auth;
+mod autocomplete;
+mod changes;
+mod encoding;
+#[cfg(feature = "graphql")]
+mod graphql;
+mod maintenance;
+mod middleware;
+mod progress;
+mod quotas;
+mod saved_queries;
+mod server;
+#[cfg(feature = "tower")]
+mod service;
+mod service_description;
+mod sql;
+mod statistics;
+mod tpf;
+#[cfg(feature = "wasi-http")]
+mod wasi_http;
+
+pub use crate::audit::Principal;
+pub use crate::auth::{AuthMiddleware, QueryLimits, Role};
+#[cfg(feature = "graphql")]
+pub use crate::graphql::GraphQlSchema;
+pub use crate::maintenance::MaintenanceMode;
+pub use crate::middleware::{CorsMiddleware, Middleware, Operation};
+pub use crate::saved_queries::SavedQueries;
+pub use crate::server::{HttpError, SparqlServer, default_query_options};
+pub use crate::statistics::record_statistics;
+#[cfg(feature = "wasi-http")]
+pub use crate::wasi_http::handle_wasi_request;
@@ -1,58 +1,59 @@
 #![allow(clippy::print_stderr, clippy::cast_precision_loss, clippy::use_debug)]
 use crate::cli::{Args, Command};
-use crate::service_description::{EndpointKind, generate_service_description};
-use anyhow::{Context, bail, ensure};
+use crate::compression::{
+    CompressedWriter, Compression, decompressed_reader, without_compression_extension,
+};
+use crate::csvw::{TabularFormat, TabularMapping, read_values};
+use crate::facade::register_facade_services;
+use crate::publish::publish;
+use crate::replay::{print_summary, read_query_log, read_report, replay_queries, write_report};
+use crate::signature::{verify_signature, write_signature};
+use anyhow::{Context, anyhow, bail, ensure};
 use clap::Parser;
 use flate2::read::MultiGzDecoder;
-use oxhttp::Server;
-use oxhttp::model::header::{
-    ACCEPT, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
-    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD,
-    CONTENT_TYPE, LOCATION, ORIGIN,
-};
-use oxhttp::model::uri::PathAndQuery;
-use oxhttp::model::{Body, HeaderValue, Method, Request, Response, StatusCode, Uri};
+#[cfg(feature = "html")]
+use oxhtml::HtmlParser;
 use oxigraph::io::{JsonLdProfileSet, LoadedDocument, RdfFormat, RdfParser, RdfSerializer};
-use oxigraph::model::{
-    GraphName, GraphNameRef, IriParseError, NamedNode, NamedNodeRef, NamedOrBlankNode,
+use oxigraph::model::{Dataset, GraphName, GraphNameRef, NamedNode, NamedNodeRef, Quad};
+use oxigraph::sparql::results::{
+    ArrowSolutionsSerializer, QueryResultsFormat, QueryResultsSerializer,
+};
+use oxigraph::sparql::{IdentityResolution, Query, QueryProgress, QueryResults, Update};
+use oxigraph::store::{
+    BackupRepository, BulkLoader, Durability, GraphQuota, StorageProfile, Store,
+};
+#[cfg(feature = "graphql")]
+use oxigraph_server::GraphQlSchema;
+use oxigraph_server::{
+    AuthMiddleware, QueryLimits, Role, SavedQueries, SparqlServer, default_query_options,
+    record_statistics,
 };
-use oxigraph::sparql::results::{QueryResultsFormat, QueryResultsSerializer};
-use oxigraph::sparql::{Query, QueryOptions, QueryResults, Update};
-use oxigraph::store::{BulkLoader, LoaderError, Store};
 use oxiri::Iri;
-use rand::random;
 use rayon_core::ThreadPoolBuilder;
-#[cfg(feature = "geosparql")]
-use spargeo::register_geosparql_functions;
-use std::borrow::Cow;
-use std::cell::RefCell;
-use std::cmp::{max, min};
+use spargebra::SparqlParser;
+use std::cmp::max;
+use std::collections::HashMap;
 #[cfg(target_os = "linux")]
 use std::env;
 use std::ffi::OsStr;
 use std::fs::File;
-use std::io::{self, BufWriter, Read, Write, stdin, stdout};
-use std::net::ToSocketAddrs;
+use std::io::{self, BufReader, BufWriter, Read, Write, stdin, stdout};
 #[cfg(target_os = "linux")]
 use std::os::unix::net::UnixDatagram;
 use std::path::Path;
-use std::rc::Rc;
-use std::str::FromStr;
-use std::thread::available_parallelism;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, available_parallelism};
 use std::time::{Duration, Instant};
-use std::{fmt, fs, str};
-use url::{Url, form_urlencoded};
+use std::{fs, str};
+use url::Url;
 
 mod cli;
-mod service_description;
-
-const MAX_SPARQL_BODY_SIZE: u64 = 1024 * 1024 * 128; // 128MB
-const HTTP_TIMEOUT: Duration = Duration::from_secs(60);
-const HTML_ROOT_PAGE: &str = include_str!("../templates/query.html");
-#[expect(clippy::large_include_file)]
-const YASGUI_JS: &str = include_str!("../templates/yasgui/yasgui.min.js");
-const YASGUI_CSS: &str = include_str!("../templates/yasgui/yasgui.min.css");
-const LOGO: &str = include_str!("../logo.svg");
+mod compression;
+mod csvw;
+mod facade;
+mod publish;
+mod replay;
+mod signature;
 
 pub fn main() -> anyhow::Result<()> {
     let matches = Args::parse();
@@ -61,36 +62,235 @@ pub fn main() -> anyhow::Result<()> {
             location,
             bind,
             cors,
+            cors_allow_origin,
+            auth_user,
+            auth_token,
+            auth_file,
+            anonymous_role,
+            role_limits,
+            principal_limits,
             union_default_graph,
-        } => serve(
-            if let Some(location) = location {
-                Store::open(location)
+            #[cfg(feature = "graphql")]
+            graphql_schema,
+            audit_graph,
+            statistics_graph,
+            statistics_interval,
+            maintenance_endpoint,
+            identity_predicate,
+            full_text_predicate,
+            durability,
+            storage_profile,
+            write_stall_errors,
+            graph_max_quads,
+            graph_max_bytes,
+            graph_quota_endpoint,
+        } => {
+            let durability = durability
+                .as_deref()
+                .map(durability_from_name)
+                .transpose()?;
+            let storage_profile = storage_profile
+                .as_deref()
+                .map(storage_profile_from_name)
+                .transpose()?
+                .unwrap_or_default();
+            let (mut store, saved_queries) = if let Some(location) = location {
+                let mut store = Store::open_with_profile(&location, storage_profile)?;
+                if let Some(durability) = durability {
+                    store = store.with_durability(durability)?;
+                }
+                if write_stall_errors {
+                    store = store.with_write_stall_errors()?;
+                }
+                (store, SavedQueries::open(location.join("saved-queries"))?)
             } else {
-                Store::new()
-            }?,
-            &bind,
-            false,
-            cors,
-            union_default_graph,
-        ),
+                (Store::new()?, SavedQueries::in_memory())
+            };
+            let mut graph_quotas = HashMap::<_, GraphQuota>::new();
+            for limit in &graph_max_quads {
+                let (graph_name, max_quads) = graph_limit_from_arg(limit)?;
+                let quota = graph_quotas.entry(graph_name).or_default();
+                *quota = quota.with_max_quads(max_quads);
+            }
+            for limit in &graph_max_bytes {
+                let (graph_name, max_bytes) = graph_limit_from_arg(limit)?;
+                let quota = graph_quotas.entry(graph_name).or_default();
+                *quota = quota.with_max_bytes(max_bytes);
+            }
+            for (graph_name, quota) in graph_quotas {
+                store = store.with_graph_quota(&graph_name, quota);
+            }
+            if let Some(identity_predicate) = identity_predicate {
+                store =
+                    store.with_query_rewriter(IdentityResolution::new().with_identity_predicate(
+                        NamedNode::new(&identity_predicate).with_context(|| {
+                            format!("The identity predicate {identity_predicate} is invalid")
+                        })?,
+                    ));
+            }
+            if !full_text_predicate.is_empty() {
+                let predicates = full_text_predicate
+                    .iter()
+                    .map(|predicate| {
+                        NamedNode::new(predicate).with_context(|| {
+                            format!("The full-text predicate {predicate} is invalid")
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                store = store.with_full_text_index(&predicates)?;
+            }
+            let mut server = SparqlServer::new(store.clone()).with_saved_queries(saved_queries);
+            if let Some(audit_graph) = audit_graph {
+                server =
+                    server.with_audit_graph(NamedNode::new(&audit_graph).with_context(|| {
+                        format!("The audit graph name {audit_graph} is invalid")
+                    })?);
+            }
+            if let Some(statistics_graph) = statistics_graph {
+                let statistics_graph = NamedNode::new(&statistics_graph).with_context(|| {
+                    format!("The statistics graph name {statistics_graph} is invalid")
+                })?;
+                let statistics_store = store.clone();
+                let recorded_statistics_graph = statistics_graph.clone();
+                thread::spawn(move || -> ! {
+                    loop {
+                        if let Err(e) =
+                            record_statistics(&statistics_store, &recorded_statistics_graph)
+                        {
+                            eprintln!("Failed to record the dataset statistics: {e}");
+                        }
+                        thread::sleep(Duration::from_secs(statistics_interval));
+                    }
+                });
+                server = server.with_statistics_graph(statistics_graph);
+            }
+            if maintenance_endpoint {
+                server = server.with_maintenance_endpoint();
+            }
+            if graph_quota_endpoint {
+                server = server.with_graph_quota_endpoint();
+            }
+            serve(
+                server,
+                &bind,
+                cors,
+                cors_allow_origin,
+                auth_middleware(
+                    &auth_user,
+                    &auth_token,
+                    auth_file.as_deref(),
+                    anonymous_role.as_deref(),
+                    &role_limits,
+                    &principal_limits,
+                )?,
+                union_default_graph,
+                #[cfg(feature = "graphql")]
+                graphql_schema.as_deref(),
+            )
+        }
         Command::ServeReadOnly {
             location,
             bind,
             cors,
+            cors_allow_origin,
+            auth_user,
+            auth_token,
+            auth_file,
+            anonymous_role,
+            role_limits,
+            principal_limits,
             union_default_graph,
+            #[cfg(feature = "graphql")]
+            graphql_schema,
         } => serve(
-            Store::open_read_only(location)?,
+            SparqlServer::new(Store::open_read_only(&location)?)
+                .with_saved_queries(SavedQueries::open(location.join("saved-queries"))?)
+                .with_read_only(),
             &bind,
-            true,
             cors,
+            cors_allow_origin,
+            auth_middleware(
+                &auth_user,
+                &auth_token,
+                auth_file.as_deref(),
+                anonymous_role.as_deref(),
+                &role_limits,
+                &principal_limits,
+            )?,
             union_default_graph,
+            #[cfg(feature = "graphql")]
+            graphql_schema.as_deref(),
         ),
+        Command::ServeSecondary {
+            primary_location,
+            secondary_location,
+            catch_up_interval,
+            bind,
+            cors,
+            cors_allow_origin,
+            auth_user,
+            auth_token,
+            auth_file,
+            anonymous_role,
+            role_limits,
+            principal_limits,
+            union_default_graph,
+            #[cfg(feature = "graphql")]
+            graphql_schema,
+        } => {
+            let store = Store::open_secondary(&primary_location, &secondary_location)?;
+            let catching_up_store = store.clone();
+            thread::spawn(move || -> ! {
+                loop {
+                    thread::sleep(Duration::from_secs(catch_up_interval));
+                    if let Err(e) = catching_up_store.try_catch_up_with_primary() {
+                        eprintln!("Failed to catch up with the primary: {e}");
+                    }
+                }
+            });
+            serve(
+                SparqlServer::new(store)
+                    .with_saved_queries(SavedQueries::open(primary_location.join("saved-queries"))?)
+                    .with_read_only(),
+                &bind,
+                cors,
+                cors_allow_origin,
+                auth_middleware(
+                    &auth_user,
+                    &auth_token,
+                    auth_file.as_deref(),
+                    anonymous_role.as_deref(),
+                    &role_limits,
+                    &principal_limits,
+                )?,
+                union_default_graph,
+                #[cfg(feature = "graphql")]
+                graphql_schema.as_deref(),
+            )
+        }
         Command::Backup {
             location,
             destination,
+            incremental,
+            keep,
         } => {
             let store = Store::open_read_only(location)?;
-            store.backup(destination)?;
+            if incremental {
+                let repository = BackupRepository::open(destination)?;
+                let backup = store.incremental_backup(&repository)?;
+                repository.verify(backup.id())?;
+                if let Some(keep) = keep {
+                    repository.purge_old_backups(keep)?;
+                }
+                eprintln!(
+                    "Backup {} created ({} files, {} MB)",
+                    backup.id(),
+                    backup.file_count(),
+                    backup.size() / 1_000_000
+                );
+            } else {
+                store.backup(destination)?;
+            }
             Ok(())
         }
         Command::Load {
@@ -100,10 +300,14 @@ pub fn main() -> anyhow::Result<()> {
             format,
             base,
             graph,
+            reproducible,
+            verify_key,
+            warn_on_invalid_signature,
+            external_sort_dir,
         } => {
             let store = Store::open(location)?;
             let format = if let Some(format) = format {
-                Some(rdf_format_from_name(&format)?)
+                Some(load_format_from_name(&format)?)
             } else {
                 None
             };
@@ -115,55 +319,83 @@ pub fn main() -> anyhow::Result<()> {
             } else {
                 None
             };
-            #[expect(clippy::cast_precision_loss)]
+            if let Some(verify_key) = &verify_key {
+                for file in &file {
+                    let data = read_to_dataset(file, format, base.as_deref(), lenient)?;
+                    if let Err(error) = verify_signature(file, data, verify_key) {
+                        if warn_on_invalid_signature {
+                            eprintln!("Warning: {error}");
+                        } else {
+                            return Err(error.context(format!(
+                                "Refusing to load {}, use --warn-on-invalid-signature to load it anyway",
+                                file.display()
+                            )));
+                        }
+                    }
+                }
+            }
             if file.is_empty() {
                 // We read from stdin
-                let start = Instant::now();
-                let mut loader = store.bulk_loader().on_progress(move |size| {
-                    let elapsed = start.elapsed();
+                let mut loader = store.bulk_loader().on_detailed_progress(move |progress| {
                     eprintln!(
-                        "{size} triples loaded in {}s ({} t/s)",
-                        elapsed.as_secs(),
-                        ((size as f64) / elapsed.as_secs_f64()).round()
+                        "{} triples loaded in {}s ({} t/s, {} MB written)",
+                        progress.loaded_quads(),
+                        progress.elapsed().as_secs(),
+                        progress.quads_per_second().round(),
+                        progress.written_bytes() / 1_000_000
                     )
                 });
+                if let Some(external_sort_dir) = &external_sort_dir {
+                    loader = loader.with_external_sort(external_sort_dir);
+                }
                 if lenient {
                     loader = loader.on_parse_error(move |e| {
                         eprintln!("Parsing error: {e}");
                         Ok(())
                     })
                 }
+                if reproducible {
+                    loader = loader.with_reproducible_output(0);
+                }
                 bulk_load(
                     &loader,
                     stdin().lock(),
-                    format.context("The --format option must be set when loading from stdin")?,
+                    format.unwrap_or(LoadFormat::Detect),
                     base.as_deref(),
                     graph,
                     lenient,
                 )
             } else {
                 ThreadPoolBuilder::new()
-                    .num_threads(max(1, available_parallelism()?.get() / 2))
+                    .num_threads(if reproducible {
+                        1 // The files are loaded one after the other in a fixed order
+                    } else {
+                        max(1, available_parallelism()?.get() / 2)
+                    })
                     .thread_name(|i| format!("Oxigraph bulk loader thread {i}"))
                     .build()?
                     .scope(|s| {
-                        for file in file {
+                        for (i, file) in file.into_iter().enumerate() {
                             let store = store.clone();
                             let graph = graph.clone();
                             let base = base.clone();
+                            let external_sort_dir = external_sort_dir.clone();
                             s.spawn(move |_| {
                                 let f = file.clone();
-                                let start = Instant::now();
-                                let mut loader = store.bulk_loader().on_progress(move |size| {
-                                    let elapsed = start.elapsed();
-                                    eprintln!(
-                                        "{} triples loaded in {}s ({} t/s) from {}",
-                                        size,
-                                        elapsed.as_secs(),
-                                        ((size as f64) / elapsed.as_secs_f64()).round(),
-                                        f.display()
-                                    )
-                                });
+                                let mut loader =
+                                    store.bulk_loader().on_detailed_progress(move |progress| {
+                                        eprintln!(
+                                            "{} triples loaded in {}s ({} t/s, {} MB written) from {}",
+                                            progress.loaded_quads(),
+                                            progress.elapsed().as_secs(),
+                                            progress.quads_per_second().round(),
+                                            progress.written_bytes() / 1_000_000,
+                                            f.display()
+                                        )
+                                    });
+                                if let Some(external_sort_dir) = external_sort_dir {
+                                    loader = loader.with_external_sort(external_sort_dir);
+                                }
                                 if lenient {
                                     let f = file.clone();
                                     loader = loader.on_parse_error(move |e| {
@@ -171,6 +403,9 @@ pub fn main() -> anyhow::Result<()> {
                                         Ok(())
                                     })
                                 }
+                                if reproducible {
+                                    loader = loader.with_reproducible_output(i as u64);
+                                }
                                 let fp = match File::open(&file) {
                                     Ok(fp) => fp,
                                     Err(error) => {
@@ -188,8 +423,7 @@ pub fn main() -> anyhow::Result<()> {
                                             &loader,
                                             MultiGzDecoder::new(fp),
                                             format.unwrap_or_else(|| {
-                                                rdf_format_from_path(&file.with_extension(""))
-                                                    .unwrap()
+                                                load_format_from_path(&file.with_extension(""))
                                             }),
                                             base.as_deref(),
                                             graph,
@@ -199,9 +433,8 @@ pub fn main() -> anyhow::Result<()> {
                                         bulk_load(
                                             &loader,
                                             fp,
-                                            format.unwrap_or_else(|| {
-                                                rdf_format_from_path(&file).unwrap()
-                                            }),
+                                            format
+                                                .unwrap_or_else(|| load_format_from_path(&file)),
                                             base.as_deref(),
                                             graph,
                                             lenient,
@@ -222,11 +455,81 @@ pub fn main() -> anyhow::Result<()> {
                 Ok(())
             }
         }
+        Command::Map {
+            location,
+            file,
+            metadata,
+            format,
+            base,
+            graph,
+        } => {
+            let store = Store::open(location)?;
+            let mapping = if let Some(metadata) = metadata {
+                TabularMapping::from_metadata(File::open(&metadata)?).with_context(|| {
+                    format!("Failed to read the CSVW metadata {}", metadata.display())
+                })?
+            } else {
+                TabularMapping::minimal()
+            };
+            let format = if let Some(format) = format {
+                Some(
+                    TabularFormat::from_extension(&format)
+                        .with_context(|| format!("The tabular format '{format}' is unknown"))?,
+                )
+            } else {
+                None
+            };
+            let graph_name = if let Some(iri) = &graph {
+                NamedNode::new(iri)
+                    .with_context(|| format!("The target graph name {iri} is invalid"))?
+                    .into()
+            } else {
+                GraphName::DefaultGraph
+            };
+            for file in file {
+                let format = if let Some(format) = format {
+                    format
+                } else {
+                    file.extension()
+                        .and_then(OsStr::to_str)
+                        .and_then(TabularFormat::from_extension)
+                        .with_context(|| {
+                            format!(
+                                "The format of {} can't be guessed from its extension, please use the --format option",
+                                file.display()
+                            )
+                        })?
+                };
+                let base = if let Some(base) = &base {
+                    Iri::parse(base.clone()).with_context(|| format!("Invalid base IRI {base}"))?
+                } else {
+                    let url = Url::from_file_path(fs::canonicalize(&file)?).map_err(|()| {
+                        anyhow!(
+                            "The file path {} can't be converted to an URL",
+                            file.display()
+                        )
+                    })?;
+                    Iri::parse(url.into()).context("Invalid file URL")?
+                };
+                store
+                    .bulk_loader()
+                    .load_ok_quads::<_, anyhow::Error>(mapping.map(
+                        format,
+                        File::open(&file)?,
+                        base,
+                        graph_name.clone(),
+                    ))
+                    .with_context(|| format!("Failed to map {}", file.display()))?;
+            }
+            Ok(())
+        }
         Command::Dump {
             location,
             file,
             format,
             graph,
+            sign_key,
+            sort,
         } => {
             let store = Store::open_read_only(location)?;
             let format = if let Some(format) = format {
@@ -250,15 +553,40 @@ pub fn main() -> anyhow::Result<()> {
             if let Some(file) = file {
                 close_file_writer(dump(
                     &store,
-                    BufWriter::new(File::create(file)?),
+                    BufWriter::new(File::create(&file)?),
                     format,
                     graph,
+                    sort,
                 )?)?;
+                if let Some(sign_key) = sign_key {
+                    let data = if let Some(graph) = graph {
+                        store
+                            .quads_for_pattern(None, None, None, Some(graph))
+                            .map(|quad| {
+                                quad.map(|quad| {
+                                    Quad::new(
+                                        quad.subject,
+                                        quad.predicate,
+                                        quad.object,
+                                        GraphName::DefaultGraph,
+                                    )
+                                })
+                            })
+                            .collect::<Result<_, _>>()?
+                    } else {
+                        store.iter().collect::<Result<_, _>>()?
+                    };
+                    write_signature(&file, data, &sign_key)?;
+                }
             } else {
-                dump(&store, stdout().lock(), format, graph)?.flush()?;
+                dump(&store, stdout().lock(), format, graph, sort)?.flush()?;
             }
             Ok(())
         }
+        Command::Publish { location, out } => {
+            let store = Store::open_read_only(location)?;
+            publish(&store, &out)
+        }
         Command::Query {
             location,
             query,
@@ -270,6 +598,8 @@ pub fn main() -> anyhow::Result<()> {
             explain_file,
             stats,
             union_default_graph,
+            values_file,
+            progress,
         } => {
             let query = if let Some(query) = query {
                 query
@@ -280,116 +610,187 @@ pub fn main() -> anyhow::Result<()> {
             } else {
                 io::read_to_string(stdin().lock())?
             };
-            let mut query = Query::parse(&query, query_base.as_deref())?;
+            let mut parser = SparqlParser::new();
+            if let Some(query_base) = &query_base {
+                parser = parser.with_base_iri(query_base)?;
+            }
+            let query = parser.parse_query(&query)?;
+            let mut options = register_facade_services(default_query_options(), &query)?;
+            let mut query = Query::from(query);
             if union_default_graph {
                 query.dataset_mut().set_default_graph_as_union();
             }
+            if let Some(values_file) = values_file {
+                let delimiter = if values_file
+                    .extension()
+                    .and_then(OsStr::to_str)
+                    .and_then(TabularFormat::from_extension)
+                    == Some(TabularFormat::Tsv)
+                {
+                    b'\t'
+                } else {
+                    b','
+                };
+                options = options.with_values(read_values(
+                    BufReader::new(File::open(&values_file).with_context(|| {
+                        format!("Not able to open values file {}", values_file.display())
+                    })?),
+                    delimiter,
+                )?);
+            }
+            let progress = progress.then(QueryProgress::new);
+            if let Some(progress) = &progress {
+                options = options.with_progress(progress.clone());
+            }
             let store = Store::open_read_only(location)?;
-            let (results, explanation) =
-                store.explain_query_opt(query, default_query_options(), stats)?;
-            let print_result = (|| {
-                match results? {
-                    QueryResults::Solutions(solutions) => {
-                        let format = if let Some(name) = results_format {
-                            if let Some(format) = QueryResultsFormat::from_extension(&name) {
-                                format
-                            } else if let Some(format) = QueryResultsFormat::from_media_type(&name)
-                            {
-                                format
+            let (results, explanation) = store.explain_query_opt(query, options, stats)?;
+            let done = AtomicBool::new(false);
+            let print_result = thread::scope(|scope| {
+                if let Some(progress) = &progress {
+                    scope.spawn(|| print_progress(progress, &done));
+                }
+                let print_result = (|| {
+                    match results? {
+                        QueryResults::Solutions(solutions)
+                            if is_arrow_stream_format(
+                                results_format.as_deref(),
+                                results_file.as_deref(),
+                            ) =>
+                        {
+                            if let Some(results_file) = results_file {
+                                let mut serializer = ArrowSolutionsSerializer::new()
+                                    .serialize_solutions_to_writer(
+                                        BufWriter::new(File::create(results_file)?),
+                                        solutions.variables().to_vec(),
+                                    );
+                                for solution in solutions {
+                                    serializer.serialize(&solution?)?;
+                                }
+                                close_file_writer(serializer.finish()?)?;
                             } else {
-                                bail!("The file format '{name}' is unknown")
-                            }
-                        } else if let Some(results_file) = &results_file {
-                            format_from_path(results_file, |ext| {
-                                QueryResultsFormat::from_extension(ext).with_context(|| {
-                                    format!("The file extension '{ext}' is unknown")
-                                })
-                            })?
-                        } else {
-                            bail!("The --results-format option must be set when writing to stdout")
-                        };
-                        if let Some(results_file) = results_file {
-                            let mut serializer = QueryResultsSerializer::from_format(format)
-                                .serialize_solutions_to_writer(
-                                    BufWriter::new(File::create(results_file)?),
-                                    solutions.variables().to_vec(),
-                                )?;
-                            for solution in solutions {
-                                serializer.serialize(&solution?)?;
-                            }
-                            close_file_writer(serializer.finish()?)?;
-                        } else {
-                            let mut serializer = QueryResultsSerializer::from_format(format)
-                                .serialize_solutions_to_writer(
-                                    stdout().lock(),
-                                    solutions.variables().to_vec(),
-                                )?;
-                            for solution in solutions {
-                                serializer.serialize(&solution?)?;
+                                let mut serializer = ArrowSolutionsSerializer::new()
+                                    .serialize_solutions_to_writer(
+                                        stdout().lock(),
+                                        solutions.variables().to_vec(),
+                                    );
+                                for solution in solutions {
+                                    serializer.serialize(&solution?)?;
+                                }
+                                serializer.finish()?.flush()?;
                             }
-                            serializer.finish()?.flush()?;
                         }
-                    }
-                    QueryResults::Boolean(result) => {
-                        let format = if let Some(name) = results_format {
-                            if let Some(format) = QueryResultsFormat::from_extension(&name) {
-                                format
-                            } else if let Some(format) = QueryResultsFormat::from_media_type(&name)
-                            {
-                                format
+                        QueryResults::Solutions(solutions) => {
+                            let format = if let Some(name) = results_format {
+                                if let Some(format) = QueryResultsFormat::from_extension(&name) {
+                                    format
+                                } else if let Some(format) =
+                                    QueryResultsFormat::from_media_type(&name)
+                                {
+                                    format
+                                } else {
+                                    bail!("The file format '{name}' is unknown")
+                                }
+                            } else if let Some(results_file) = &results_file {
+                                format_from_path(results_file, |ext| {
+                                    QueryResultsFormat::from_extension(ext).with_context(|| {
+                                        format!("The file extension '{ext}' is unknown")
+                                    })
+                                })?
                             } else {
-                                bail!("The file format '{name}' is unknown")
-                            }
-                        } else if let Some(results_file) = &results_file {
-                            format_from_path(results_file, |ext| {
-                                QueryResultsFormat::from_extension(ext).with_context(|| {
-                                    format!("The file extension '{ext}' is unknown")
-                                })
-                            })?
-                        } else {
-                            bail!("The --results-format option must be set when writing to stdout")
-                        };
-                        if let Some(results_file) = results_file {
-                            close_file_writer(
-                                QueryResultsSerializer::from_format(format)
-                                    .serialize_boolean_to_writer(
+                                bail!(
+                                    "The --results-format option must be set when writing to stdout"
+                                )
+                            };
+                            if let Some(results_file) = results_file {
+                                let mut serializer = QueryResultsSerializer::from_format(format)
+                                    .serialize_solutions_to_writer(
                                         BufWriter::new(File::create(results_file)?),
-                                        result,
-                                    )?,
-                            )?;
-                        } else {
-                            QueryResultsSerializer::from_format(format)
-                                .serialize_boolean_to_writer(stdout().lock(), result)?
-                                .flush()?;
+                                        solutions.variables().to_vec(),
+                                    )?;
+                                for solution in solutions {
+                                    serializer.serialize(&solution?)?;
+                                }
+                                close_file_writer(serializer.finish()?)?;
+                            } else {
+                                let mut serializer = QueryResultsSerializer::from_format(format)
+                                    .serialize_solutions_to_writer(
+                                        stdout().lock(),
+                                        solutions.variables().to_vec(),
+                                    )?;
+                                for solution in solutions {
+                                    serializer.serialize(&solution?)?;
+                                }
+                                serializer.finish()?.flush()?;
+                            }
                         }
-                    }
-                    QueryResults::Graph(triples) => {
-                        let format = if let Some(name) = &results_format {
-                            rdf_format_from_name(name)
-                        } else if let Some(results_file) = &results_file {
-                            rdf_format_from_path(results_file)
-                        } else {
-                            bail!("The --results-format option must be set when writing to stdout")
-                        }?;
-                        let serializer = RdfSerializer::from_format(format);
-                        if let Some(results_file) = results_file {
-                            let mut serializer =
-                                serializer.for_writer(BufWriter::new(File::create(results_file)?));
-                            for triple in triples {
-                                serializer.serialize_triple(triple?.as_ref())?;
+                        QueryResults::Boolean(result) => {
+                            let format = if let Some(name) = results_format {
+                                if let Some(format) = QueryResultsFormat::from_extension(&name) {
+                                    format
+                                } else if let Some(format) =
+                                    QueryResultsFormat::from_media_type(&name)
+                                {
+                                    format
+                                } else {
+                                    bail!("The file format '{name}' is unknown")
+                                }
+                            } else if let Some(results_file) = &results_file {
+                                format_from_path(results_file, |ext| {
+                                    QueryResultsFormat::from_extension(ext).with_context(|| {
+                                        format!("The file extension '{ext}' is unknown")
+                                    })
+                                })?
+                            } else {
+                                bail!(
+                                    "The --results-format option must be set when writing to stdout"
+                                )
+                            };
+                            if let Some(results_file) = results_file {
+                                close_file_writer(
+                                    QueryResultsSerializer::from_format(format)
+                                        .serialize_boolean_to_writer(
+                                            BufWriter::new(File::create(results_file)?),
+                                            result,
+                                        )?,
+                                )?;
+                            } else {
+                                QueryResultsSerializer::from_format(format)
+                                    .serialize_boolean_to_writer(stdout().lock(), result)?
+                                    .flush()?;
                             }
-                            close_file_writer(serializer.finish()?)?;
-                        } else {
-                            let mut serializer = serializer.for_writer(stdout().lock());
-                            for triple in triples {
-                                serializer.serialize_triple(triple?.as_ref())?;
+                        }
+                        QueryResults::Graph(triples) => {
+                            let format = if let Some(name) = &results_format {
+                                rdf_format_from_name(name)
+                            } else if let Some(results_file) = &results_file {
+                                rdf_format_from_path(results_file)
+                            } else {
+                                bail!(
+                                    "The --results-format option must be set when writing to stdout"
+                                )
+                            }?;
+                            let serializer = RdfSerializer::from_format(format);
+                            if let Some(results_file) = results_file {
+                                let mut serializer = serializer
+                                    .for_writer(BufWriter::new(File::create(results_file)?));
+                                for triple in triples {
+                                    serializer.serialize_triple(triple?.as_ref())?;
+                                }
+                                close_file_writer(serializer.finish()?)?;
+                            } else {
+                                let mut serializer = serializer.for_writer(stdout().lock());
+                                for triple in triples {
+                                    serializer.serialize_triple(triple?.as_ref())?;
+                                }
+                                serializer.finish()?.flush()?;
                             }
-                            serializer.finish()?.flush()?;
                         }
                     }
-                }
-                Ok(())
-            })();
+                    Ok(())
+                })();
+                done.store(true, Ordering::Relaxed);
+                print_result
+            });
             if let Some(explain_file) = explain_file {
                 let mut file = BufWriter::new(File::create(&explain_file)?);
                 match explain_file.extension().and_then(OsStr::to_str) {
@@ -431,6 +832,49 @@ pub fn main() -> anyhow::Result<()> {
             store.flush()?;
             Ok(())
         }
+        Command::Replay {
+            location,
+            log,
+            concurrency,
+            report_file,
+            baseline,
+            union_default_graph,
+        } => {
+            let queries =
+                read_query_log(BufReader::new(File::open(&log).with_context(|| {
+                    format!("Not able to read query log {}", log.display())
+                })?))?;
+            let baseline = if let Some(baseline) = baseline {
+                Some(read_report(BufReader::new(
+                    File::open(&baseline).with_context(|| {
+                        format!("Not able to read baseline report {}", baseline.display())
+                    })?,
+                ))?)
+            } else {
+                None
+            };
+            let store = Store::open_read_only(location)?;
+            let replayed = replay_queries(
+                &store,
+                &queries,
+                &default_query_options(),
+                union_default_graph,
+                concurrency.into(),
+            );
+            if let Some(report_file) = report_file {
+                let mut file = BufWriter::new(File::create(report_file)?);
+                write_report(&mut file, &replayed)?;
+                file.flush()?;
+            } else {
+                write_report(&mut stdout().lock(), &replayed)?;
+            }
+            let different = print_summary(&replayed, baseline.as_deref());
+            ensure!(
+                different == 0,
+                "{different} queries have results different from the baseline"
+            );
+            Ok(())
+        }
         Command::Optimize { location } => {
             let store = Store::open(location)?;
             store.optimize()?;
@@ -443,6 +887,8 @@ pub fn main() -> anyhow::Result<()> {
             to_file,
             to_format,
             to_base,
+            prefix,
+            pretty,
             lenient,
             from_graph,
             from_default_graph,
@@ -451,7 +897,7 @@ pub fn main() -> anyhow::Result<()> {
             let from_format = if let Some(format) = from_format {
                 rdf_format_from_name(&format)?
             } else if let Some(file) = &from_file {
-                rdf_format_from_path(file)?
+                rdf_format_from_path(&without_compression_extension(file))?
             } else {
                 bail!("The --from-format option must be set when reading from stdin")
             };
@@ -465,11 +911,23 @@ pub fn main() -> anyhow::Result<()> {
             let to_format = if let Some(format) = to_format {
                 rdf_format_from_name(&format)?
             } else if let Some(file) = &to_file {
-                rdf_format_from_path(file)?
+                rdf_format_from_path(&without_compression_extension(file))?
             } else {
                 bail!("The --to-format option must be set when writing to stdout")
             };
-            let serializer = RdfSerializer::from_format(to_format);
+            let mut serializer = RdfSerializer::from_format(to_format);
+            if pretty {
+                serializer = serializer.pretty();
+            }
+            let prefixes = prefix
+                .iter()
+                .map(|prefix| {
+                    let (name, iri) = prefix.split_once('=').with_context(|| {
+                        format!("The prefix '{prefix}' must be written like NAME=IRI")
+                    })?;
+                    Ok((name.into(), iri.into()))
+                })
+                .collect::<anyhow::Result<Vec<(String, String)>>>()?;
 
             let from_graph = if let Some(from_graph) = from_graph {
                 Some(
@@ -490,64 +948,90 @@ pub fn main() -> anyhow::Result<()> {
                 GraphName::DefaultGraph
             };
 
-            match (from_file, to_file) {
-                (Some(from_file), Some(to_file)) => close_file_writer(do_convert(
-                    parser,
-                    File::open(from_file)?,
-                    serializer,
-                    BufWriter::new(File::create(to_file)?),
-                    lenient,
-                    &from_graph,
-                    &to_graph,
-                    to_base.as_deref(),
-                )?),
-                (Some(from_file), None) => do_convert(
-                    parser,
-                    File::open(from_file)?,
-                    serializer,
-                    stdout().lock(),
-                    lenient,
-                    &from_graph,
-                    &to_graph,
-                    to_base.as_deref(),
-                )?
-                .flush(),
-                (None, Some(to_file)) => close_file_writer(do_convert(
-                    parser,
-                    stdin().lock(),
-                    serializer,
-                    BufWriter::new(File::create(to_file)?),
-                    lenient,
-                    &from_graph,
-                    &to_graph,
-                    to_base.as_deref(),
-                )?),
-                (None, None) => do_convert(
+            let reader = if let Some(from_file) = from_file {
+                decompressed_reader(File::open(from_file)?)?
+            } else {
+                decompressed_reader(stdin().lock())?
+            };
+            if let Some(to_file) = to_file {
+                let writer = CompressedWriter::new(
+                    BufWriter::new(File::create(&to_file)?),
+                    Compression::from_path(&to_file),
+                )?;
+                close_file_writer(
+                    do_convert(
+                        parser,
+                        reader,
+                        serializer,
+                        writer,
+                        pretty,
+                        lenient,
+                        &from_graph,
+                        &to_graph,
+                        to_base.as_deref(),
+                        &prefixes,
+                    )?
+                    .finish()?,
+                )
+            } else {
+                do_convert(
                     parser,
-                    stdin().lock(),
+                    reader,
                     serializer,
                     stdout().lock(),
+                    pretty,
                     lenient,
                     &from_graph,
                     &to_graph,
                     to_base.as_deref(),
+                    &prefixes,
                 )?
-                .flush(),
+                .flush()
             }?;
             Ok(())
         }
     }
 }
 
+/// Prints the query evaluation progress to stderr every second until the evaluation is done
+fn print_progress(progress: &QueryProgress, done: &AtomicBool) {
+    let mut last_print = Instant::now();
+    loop {
+        let is_done = done.load(Ordering::Relaxed) || progress.is_finished();
+        if is_done || last_print.elapsed() >= Duration::from_secs(1) {
+            last_print = Instant::now();
+            eprintln!(
+                "{} results, {} lookups and {} quads read in {:.1}s",
+                progress.result_count(),
+                progress.scan_count(),
+                progress.read_quad_count(),
+                progress.elapsed().unwrap_or_default().as_secs_f64()
+            );
+        }
+        if is_done {
+            return;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
 fn bulk_load(
     loader: &BulkLoader,
     reader: impl Read,
-    format: RdfFormat,
+    format: LoadFormat,
     base_iri: Option<&str>,
     to_graph_name: Option<NamedNode>,
     lenient: bool,
 ) -> anyhow::Result<()> {
-    let mut parser = RdfParser::from_format(format);
+    let mut parser = match format {
+        LoadFormat::Rdf(format) => RdfParser::from_format(format),
+        // Turtle is used if the format cannot be guessed
+        LoadFormat::Detect => RdfParser::from_format(RdfFormat::Turtle).detect_format(),
+        #[cfg(feature = "html")]
+        LoadFormat::Html => {
+            return bulk_load_html(loader, reader, base_iri, to_graph_name, lenient);
+        }
+    };
     if let Some(to_graph_name) = to_graph_name {
         parser = parser.with_default_graph(to_graph_name);
     }
@@ -563,16 +1047,96 @@ fn bulk_load(
     Ok(())
 }
 
+/// Loads the RDFa, microdata and JSON-LD data embedded in an HTML file
+#[cfg(feature = "html")]
+fn bulk_load_html(
+    loader: &BulkLoader,
+    reader: impl Read,
+    base_iri: Option<&str>,
+    to_graph_name: Option<NamedNode>,
+    lenient: bool,
+) -> anyhow::Result<()> {
+    let mut parser = HtmlParser::new();
+    if let Some(base_iri) = base_iri {
+        parser = parser
+            .with_base_iri(base_iri)
+            .with_context(|| format!("Invalid base IRI {base_iri}"))?;
+    }
+    let graph_name = to_graph_name.map_or(GraphName::DefaultGraph, GraphName::from);
+    loader.load_ok_quads::<_, anyhow::Error>(parser.for_reader(reader).filter_map(
+        |quad| match quad {
+            Ok(mut quad) => {
+                if quad.graph_name.is_default_graph() {
+                    quad.graph_name = graph_name.clone();
+                }
+                Some(Ok(quad))
+            }
+            Err(e) if lenient => {
+                eprintln!("Parsing error: {e}");
+                None
+            }
+            Err(e) => Some(Err(e)),
+        },
+    ))
+}
+
+/// Parses a file to load in memory, for example to check its signature
+fn read_to_dataset(
+    file: &Path,
+    format: Option<LoadFormat>,
+    base_iri: Option<&str>,
+    lenient: bool,
+) -> anyhow::Result<Dataset> {
+    let store = Store::new()?;
+    let fp =
+        File::open(file).with_context(|| format!("Error while opening file {}", file.display()))?;
+    if file.extension().is_some_and(|e| e == OsStr::new("gz")) {
+        bulk_load(
+            &store.bulk_loader(),
+            MultiGzDecoder::new(fp),
+            format.unwrap_or_else(|| load_format_from_path(&file.with_extension(""))),
+            base_iri,
+            None,
+            lenient,
+        )
+    } else {
+        bulk_load(
+            &store.bulk_loader(),
+            fp,
+            format.unwrap_or_else(|| load_format_from_path(file)),
+            base_iri,
+            None,
+            lenient,
+        )
+    }
+    .with_context(|| format!("Error while loading file {}", file.display()))?;
+    Ok(store.iter().collect::<Result<_, _>>()?)
+}
+
 fn dump<W: Write>(
     store: &Store,
     writer: W,
     format: RdfFormat,
     from_graph_name: Option<GraphNameRef<'_>>,
+    sort: bool,
 ) -> anyhow::Result<W> {
     ensure!(
         format.supports_datasets() || from_graph_name.is_some(),
         "The --graph option is required when writing a format not supporting datasets like NTriples, Turtle or RDF/XML. Use --graph \"default\" to dump only the default graph."
     );
+    if sort {
+        let mut serializer = RdfSerializer::from_format(format).for_sorted_writer(writer);
+        if let Some(from_graph_name) = from_graph_name {
+            for quad in store.quads_for_pattern(None, None, None, Some(from_graph_name)) {
+                serializer.serialize_triple(quad?.as_ref())?;
+            }
+        } else {
+            for quad in store {
+                serializer.serialize_quad(&quad?)?;
+            }
+        }
+        return Ok(serializer.finish()?);
+    }
     Ok(if let Some(from_graph_name) = from_graph_name {
         store.dump_graph_to_writer(from_graph_name, format, writer)
     } else {
@@ -585,10 +1149,12 @@ fn do_convert<R: Read, W: Write>(
     reader: R,
     mut serializer: RdfSerializer,
     writer: W,
+    pretty: bool,
     lenient: bool,
     from_graph: &Option<GraphName>,
     default_graph: &GraphName,
     to_base: Option<&str>,
+    prefixes: &[(String, String)],
 ) -> anyhow::Result<W> {
     if lenient {
         parser = parser.lenient();
@@ -610,13 +1176,23 @@ fn do_convert<R: Read, W: Write>(
                 }), // TODO: is it a good fallback?
         })
     });
-    let first = parser.next(); // We read the first element to get prefixes and the base IRI
+    // We read the first element to get prefixes and the base IRI
+    // In pretty mode nothing is written before the end so we read everything to get all prefixes
+    let first = if pretty {
+        parser.by_ref().collect::<Vec<_>>()
+    } else {
+        parser.next().into_iter().collect()
+    };
     if let Some(base_iri) = to_base.or_else(|| parser.base_iri()) {
         serializer = serializer
             .with_base_iri(base_iri)
             .with_context(|| format!("Invalid base IRI: {base_iri}"))?;
     }
-    for (prefix_name, prefix_iri) in parser.prefixes() {
+    for (prefix_name, prefix_iri) in parser
+        .prefixes()
+        .filter(|(name, _)| !prefixes.iter().any(|(n, _)| n == name))
+        .chain(prefixes.iter().map(|(n, i)| (n.as_str(), i.as_str())))
+    {
         serializer = serializer
             .with_prefix(prefix_name, prefix_iri)
             .with_context(|| format!("Invalid IRI for prefix {prefix_name}: {prefix_iri}"))?;
@@ -667,6 +1243,16 @@ fn format_from_path<T>(
     }
 }
 
+/// The Apache Arrow IPC streaming format is only supported for SELECT results
+fn is_arrow_stream_format(name: Option<&str>, path: Option<&Path>) -> bool {
+    if let Some(name) = name {
+        name == "arrows" || name == "application/vnd.apache.arrow.stream"
+    } else {
+        path.and_then(Path::extension)
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("arrows"))
+    }
+}
+
 fn rdf_format_from_path(path: &Path) -> anyhow::Result<RdfFormat> {
     format_from_path(path, |ext| {
         RdfFormat::from_extension(ext)
@@ -674,6 +1260,43 @@ fn rdf_format_from_path(path: &Path) -> anyhow::Result<RdfFormat> {
     })
 }
 
+/// A format accepted by the load command
+#[derive(Clone, Copy)]
+enum LoadFormat {
+    Rdf(RdfFormat),
+    /// Guessed from the first bytes of the file
+    Detect,
+    /// RDFa, microdata and JSON-LD embedded in HTML
+    #[cfg(feature = "html")]
+    Html,
+}
+
+fn load_format_from_path(path: &Path) -> LoadFormat {
+    let Some(extension) = path.extension().and_then(OsStr::to_str) else {
+        return LoadFormat::Detect;
+    };
+    #[cfg(feature = "html")]
+    if is_html_format_name(extension) {
+        return LoadFormat::Html;
+    }
+    RdfFormat::from_extension(extension).map_or(LoadFormat::Detect, LoadFormat::Rdf)
+}
+
+fn load_format_from_name(name: &str) -> anyhow::Result<LoadFormat> {
+    #[cfg(feature = "html")]
+    if is_html_format_name(name) {
+        return Ok(LoadFormat::Html);
+    }
+    Ok(LoadFormat::Rdf(rdf_format_from_name(name)?))
+}
+
+#[cfg(feature = "html")]
+fn is_html_format_name(name: &str) -> bool {
+    ["html", "htm", "xhtml", "text/html", "application/xhtml+xml"]
+        .iter()
+        .any(|n| n.eq_ignore_ascii_case(name))
+}
+
 fn rdf_format_from_name(name: &str) -> anyhow::Result<RdfFormat> {
     if let Some(t) = RdfFormat::from_extension(name) {
         return Ok(t);
@@ -684,1036 +1307,207 @@ fn rdf_format_from_name(name: &str) -> anyhow::Result<RdfFormat> {
     bail!("The file format '{name}' is unknown")
 }
 
-fn serve(
-    store: Store,
-    bind: &str,
-    read_only: bool,
-    cors: bool,
-    union_default_graph: bool,
-) -> anyhow::Result<()> {
-    let mut server = if cors {
-        Server::new(cors_middleware(move |request| {
-            handle_request(request, store.clone(), read_only, union_default_graph)
-                .unwrap_or_else(|(status, message)| error(status, message))
-        }))
-    } else {
-        Server::new(move |request| {
-            handle_request(request, store.clone(), read_only, union_default_graph)
-                .unwrap_or_else(|(status, message)| error(status, message))
-        })
-    }
-    .with_global_timeout(HTTP_TIMEOUT)
-    .with_server_name(concat!("Oxigraph/", env!("CARGO_PKG_VERSION")))?
-    .with_max_concurrent_connections(available_parallelism()?.get() * 128);
-    for socket in bind.to_socket_addrs()? {
-        server = server.bind(socket);
-    }
-    let server = server.spawn()?;
-    #[cfg(target_os = "linux")]
-    systemd_notify_ready()?;
-    eprintln!("Listening for requests at http://{bind}");
-    server.join()?;
-    Ok(())
-}
-
-fn cors_middleware(
-    on_request: impl Fn(&mut Request<Body>) -> Response<Body> + Send + Sync + 'static,
-) -> impl Fn(&mut Request<Body>) -> Response<Body> + Send + Sync + 'static {
-    move |request| {
-        if *request.method() == Method::OPTIONS {
-            let mut response = Response::builder().status(StatusCode::NO_CONTENT);
-            let request_headers = request.headers();
-            if request_headers.get(ORIGIN).is_some() {
-                response = response.header(
-                    ACCESS_CONTROL_ALLOW_ORIGIN.clone(),
-                    HeaderValue::from_static("*"),
-                );
-            }
-            if let Some(method) = request_headers.get(ACCESS_CONTROL_REQUEST_METHOD) {
-                response = response.header(ACCESS_CONTROL_ALLOW_METHODS, method.clone());
-            }
-            if let Some(headers) = request_headers.get(ACCESS_CONTROL_REQUEST_HEADERS) {
-                response = response.header(ACCESS_CONTROL_ALLOW_HEADERS, headers.clone());
-            }
-            response.body(Body::empty()).unwrap()
-        } else {
-            let mut response = on_request(request);
-            if request.headers().get(ORIGIN).is_some() {
-                response
-                    .headers_mut()
-                    .append(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
-            }
-            response
-        }
-    }
-}
-
-type HttpError = (StatusCode, String);
-
-fn handle_request(
-    request: &mut Request<Body>,
-    store: Store,
-    read_only: bool,
-    union_default_graph: bool,
-) -> Result<Response<Body>, HttpError> {
-    match (request.uri().path(), request.method().as_ref()) {
-        ("/", "HEAD") => Ok(Response::builder()
-            .header(CONTENT_TYPE, "text/html")
-            .body(Body::empty())
-            .unwrap()),
-        ("/", "GET") => Ok(Response::builder()
-            .header(CONTENT_TYPE, "text/html")
-            .body(HTML_ROOT_PAGE.into())
-            .unwrap()),
-        ("/yasgui.min.css", "HEAD") => Ok(Response::builder()
-            .header(CONTENT_TYPE, "text/css")
-            .body(Body::empty())
-            .unwrap()),
-        ("/yasgui.min.css", "GET") => Ok(Response::builder()
-            .header(CONTENT_TYPE, "text/css")
-            .body(YASGUI_CSS.into())
-            .unwrap()),
-        ("/yasgui.min.js", "HEAD") => Ok(Response::builder()
-            .header(CONTENT_TYPE, "application/javascript")
-            .body(Body::empty())
-            .unwrap()),
-        ("/yasgui.min.js", "GET") => Ok(Response::builder()
-            .header(CONTENT_TYPE, "application/javascript")
-            .body(YASGUI_JS.into())
-            .unwrap()),
-        ("/logo.svg", "HEAD") => Ok(Response::builder()
-            .header(CONTENT_TYPE, "image/svg+xml")
-            .body(Body::empty())
-            .unwrap()),
-        ("/logo.svg", "GET") => Ok(Response::builder()
-            .header(CONTENT_TYPE, "image/svg+xml")
-            .body(LOGO.into())
-            .unwrap()),
-        ("/query", "GET") => {
-            let query = url_query(request);
-            if query.is_empty() {
-                let format = rdf_content_negotiation(request)?;
-                let description =
-                    generate_service_description(format, EndpointKind::Query, union_default_graph);
-                Ok(Response::builder()
-                    .header(CONTENT_TYPE, format.media_type())
-                    .body(description.into())
-                    .unwrap())
-            } else {
-                configure_and_evaluate_sparql_query(
-                    &store,
-                    &[url_query(request)],
-                    None,
-                    request,
-                    union_default_graph,
-                )
-            }
-        }
-        ("/query", "POST") => {
-            let content_type =
-                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
-            if content_type == "application/sparql-query" {
-                let query = limited_string_body(request)?;
-                configure_and_evaluate_sparql_query(
-                    &store,
-                    &[url_query(request)],
-                    Some(query),
-                    request,
-                    union_default_graph,
-                )
-            } else if content_type == "application/x-www-form-urlencoded" {
-                let buffer = limited_body(request)?;
-                configure_and_evaluate_sparql_query(
-                    &store,
-                    &[url_query(request), &buffer],
-                    None,
-                    request,
-                    union_default_graph,
-                )
-            } else {
-                Err(unsupported_media_type(&content_type))
-            }
-        }
-        ("/update", "GET") => {
-            if read_only {
-                return Err(the_server_is_read_only());
-            }
-            let format = rdf_content_negotiation(request)?;
-            let description =
-                generate_service_description(format, EndpointKind::Update, union_default_graph);
-            Ok(Response::builder()
-                .header(CONTENT_TYPE, format.media_type())
-                .body(description.into())
-                .unwrap())
-        }
-        ("/update", "POST") => {
-            if read_only {
-                return Err(the_server_is_read_only());
-            }
-            let content_type =
-                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
-            if content_type == "application/sparql-update" {
-                let update = limited_string_body(request)?;
-                configure_and_evaluate_sparql_update(
-                    &store,
-                    &[url_query(request)],
-                    Some(update),
-                    request,
-                    union_default_graph,
-                )
-            } else if content_type == "application/x-www-form-urlencoded" {
-                let buffer = limited_body(request)?;
-                configure_and_evaluate_sparql_update(
-                    &store,
-                    &[url_query(request), &buffer],
-                    None,
-                    request,
-                    union_default_graph,
-                )
-            } else {
-                Err(unsupported_media_type(&content_type))
-            }
-        }
-        (path, "GET") if path.starts_with("/store") => {
-            if let Some(target) = store_target(request)? {
-                assert_that_graph_exists(&store, &target)?;
-                let format = rdf_content_negotiation(request)?;
-
-                let quads = store.quads_for_pattern(
-                    None,
-                    None,
-                    None,
-                    Some(GraphName::from(target).as_ref()),
-                );
-                ReadForWrite::build_response(
-                    move |w| Ok((RdfSerializer::from_format(format).for_writer(w), quads)),
-                    |(mut serializer, mut quads)| {
-                        Ok(if let Some(q) = quads.next() {
-                            serializer.serialize_triple(&q?.into())?;
-                            Some((serializer, quads))
-                        } else {
-                            serializer.finish()?;
-                            None
-                        })
-                    },
-                    format.media_type(),
-                )
-            } else {
-                let format = rdf_content_negotiation(request)?;
-                if !format.supports_datasets() {
-                    return Err(bad_request(format!(
-                        "It is not possible to serialize the full RDF dataset using {format} that does not support named graphs"
-                    )));
-                }
-                ReadForWrite::build_response(
-                    move |w| {
-                        Ok((
-                            RdfSerializer::from_format(format).for_writer(w),
-                            store.iter(),
-                        ))
-                    },
-                    |(mut serializer, mut quads)| {
-                        Ok(if let Some(q) = quads.next() {
-                            serializer.serialize_quad(&q?)?;
-                            Some((serializer, quads))
-                        } else {
-                            serializer.finish()?;
-                            None
-                        })
-                    },
-                    format.media_type(),
+fn durability_from_name(name: &str) -> anyhow::Result<Durability> {
+    Ok(match name {
+        "sync" => Durability::Sync,
+        "async" => Durability::Async,
+        _ => {
+            let Some(interval) = name
+                .strip_prefix("interval(")
+                .and_then(|n| n.strip_suffix(')'))
+            else {
+                bail!(
+                    "The durability '{name}' is unknown, it must be 'sync', 'async' or 'interval(MS)'"
                 )
+            };
+            let interval = interval
+                .parse()
+                .with_context(|| format!("The durability interval '{interval}' is invalid"))?;
+            if interval == 0 {
+                bail!("The durability interval must be at least 1ms");
             }
+            Durability::Interval(Duration::from_millis(interval))
         }
-        (path, "PUT") if path.starts_with("/store") => {
-            if read_only {
-                return Err(the_server_is_read_only());
-            }
-            let content_type =
-                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
-            if let Some(target) = store_target(request)? {
-                let format = RdfFormat::from_media_type(&content_type)
-                    .ok_or_else(|| unsupported_media_type(&content_type))?;
-                let new = !match &target {
-                    NamedGraphName::NamedNode(target) => {
-                        if store
-                            .contains_named_graph(target)
-                            .map_err(internal_server_error)?
-                        {
-                            store.clear_graph(target).map_err(internal_server_error)?;
-                            true
-                        } else {
-                            store
-                                .insert_named_graph(target)
-                                .map_err(internal_server_error)?;
-                            false
-                        }
-                    }
-                    NamedGraphName::DefaultGraph => {
-                        store
-                            .clear_graph(GraphNameRef::DefaultGraph)
-                            .map_err(internal_server_error)?;
-                        true
-                    }
-                };
-                web_load_graph(&store, request, format, &GraphName::from(target))?;
-                Ok(Response::builder()
-                    .status(if new {
-                        StatusCode::CREATED
-                    } else {
-                        StatusCode::NO_CONTENT
-                    })
-                    .body(Body::empty())
-                    .unwrap())
-            } else {
-                let format = RdfFormat::from_media_type(&content_type)
-                    .ok_or_else(|| unsupported_media_type(&content_type))?;
-                store.clear().map_err(internal_server_error)?;
-                web_load_dataset(&store, request, format)?;
-                Ok(Response::builder()
-                    .status(StatusCode::NO_CONTENT)
-                    .body(Body::empty())
-                    .unwrap())
-            }
-        }
-        (path, "DELETE") if path.starts_with("/store") => {
-            if read_only {
-                return Err(the_server_is_read_only());
-            }
-            if let Some(target) = store_target(request)? {
-                match target {
-                    NamedGraphName::DefaultGraph => store
-                        .clear_graph(GraphNameRef::DefaultGraph)
-                        .map_err(internal_server_error)?,
-                    NamedGraphName::NamedNode(target) => {
-                        if store
-                            .contains_named_graph(&target)
-                            .map_err(internal_server_error)?
-                        {
-                            store
-                                .remove_named_graph(&target)
-                                .map_err(internal_server_error)?;
-                        } else {
-                            return Err((
-                                StatusCode::NOT_FOUND,
-                                format!("The graph {target} does not exists"),
-                            ));
-                        }
-                    }
-                }
-            } else {
-                store.clear().map_err(internal_server_error)?;
-            }
-            Ok(Response::builder()
-                .status(StatusCode::NO_CONTENT)
-                .body(Body::empty())
-                .unwrap())
-        }
-        (path, "POST") if path.starts_with("/store") => {
-            if read_only {
-                return Err(the_server_is_read_only());
-            }
-            let content_type =
-                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
-            if let Some(target) = store_target(request)? {
-                let format = RdfFormat::from_media_type(&content_type)
-                    .ok_or_else(|| unsupported_media_type(&content_type))?;
-                let new = assert_that_graph_exists(&store, &target).is_ok();
-                web_load_graph(&store, request, format, &GraphName::from(target))?;
-                Ok(Response::builder()
-                    .status(if new {
-                        StatusCode::CREATED
-                    } else {
-                        StatusCode::NO_CONTENT
-                    })
-                    .body(Body::empty())
-                    .unwrap())
-            } else {
-                let format = RdfFormat::from_media_type(&content_type)
-                    .ok_or_else(|| unsupported_media_type(&content_type))?;
-                if format.supports_datasets() {
-                    web_load_dataset(&store, request, format)?;
-                    Ok(Response::builder()
-                        .status(StatusCode::NO_CONTENT)
-                        .body(Body::empty())
-                        .unwrap())
-                } else {
-                    let graph =
-                        resolve_with_base(request, &format!("/store/{:x}", random::<u128>()))?;
-                    web_load_graph(&store, request, format, &graph.clone().into())?;
-                    Ok(Response::builder()
-                        .status(StatusCode::CREATED)
-                        .header(LOCATION, graph.into_string())
-                        .body(Body::empty())
-                        .unwrap())
-                }
-            }
-        }
-        (path, "HEAD") if path.starts_with("/store") => {
-            if let Some(target) = store_target(request)? {
-                assert_that_graph_exists(&store, &target)?;
-            }
-            Ok(Response::builder().body(Body::empty()).unwrap())
-        }
-        _ => Err((
-            StatusCode::NOT_FOUND,
-            format!(
-                "{} {} is not supported by this server",
-                request.method(),
-                request.uri().path()
-            ),
-        )),
-    }
-}
-
-fn base_url(request: &Request<Body>) -> String {
-    let uri = request.uri();
-    if uri.query().is_some() {
-        // We remove the query
-        let mut parts = uri.clone().into_parts();
-        if let Some(path_and_query) = &mut parts.path_and_query {
-            if path_and_query.query().is_some() {
-                *path_and_query = PathAndQuery::try_from(path_and_query.path()).unwrap();
-            }
-        };
-        Uri::from_parts(parts).unwrap().to_string()
-    } else {
-        uri.to_string()
-    }
-}
-
-fn resolve_with_base(request: &Request<Body>, url: &str) -> Result<NamedNode, HttpError> {
-    Ok(Iri::parse(base_url(request))
-        .map_err(bad_request)?
-        .resolve(url)
-        .map_err(bad_request)?
-        .into())
-}
-
-fn url_query(request: &Request<Body>) -> &[u8] {
-    request.uri().query().unwrap_or_default().as_bytes()
-}
-
-fn url_query_parameter<'a>(request: &'a Request<Body>, param: &str) -> Option<Cow<'a, str>> {
-    form_urlencoded::parse(url_query(request))
-        .find(|(k, _)| k == param)
-        .map(|(_, v)| v)
-}
-
-fn limited_string_body(request: &mut Request<Body>) -> Result<String, HttpError> {
-    String::from_utf8(limited_body(request)?)
-        .map_err(|e| bad_request(format!("Invalid UTF-8 body: {e}")))
+    })
 }
 
-fn limited_body(request: &mut Request<Body>) -> Result<Vec<u8>, HttpError> {
-    let body = request.body_mut();
-    if let Some(body_len) = body.len() {
-        if body_len > MAX_SPARQL_BODY_SIZE {
-            // it's too big
-            return Err(bad_request(format!(
-                "SPARQL body payloads are limited to {MAX_SPARQL_BODY_SIZE} bytes, found {body_len} bytes"
-            )));
-        }
-        let mut payload = Vec::with_capacity(
-            body_len
-                .try_into()
-                .map_err(|_| bad_request("Huge body size"))?,
-        );
-        body.read_to_end(&mut payload)
-            .map_err(internal_server_error)?;
-        Ok(payload)
-    } else {
-        let mut payload = Vec::new();
-        body.take(MAX_SPARQL_BODY_SIZE + 1)
-            .read_to_end(&mut payload)
-            .map_err(internal_server_error)?;
-        if payload.len() > MAX_SPARQL_BODY_SIZE.try_into().unwrap() {
-            return Err(bad_request(format!(
-                "SPARQL body payloads are limited to {MAX_SPARQL_BODY_SIZE} bytes"
-            )));
-        }
-        Ok(payload)
-    }
+/// Parses a GRAPH=LIMIT argument
+fn graph_limit_from_arg(arg: &str) -> anyhow::Result<(NamedNode, usize)> {
+    let Some((graph_name, limit)) = arg.rsplit_once('=') else {
+        bail!("The graph quota '{arg}' must be written GRAPH=LIMIT")
+    };
+    Ok((
+        NamedNode::new(graph_name)
+            .with_context(|| format!("The quota graph name {graph_name} is invalid"))?,
+        limit
+            .parse()
+            .with_context(|| format!("The graph quota limit '{limit}' is invalid"))?,
+    ))
 }
 
-fn configure_and_evaluate_sparql_query(
-    store: &Store,
-    encoded: &[&[u8]],
-    mut query: Option<String>,
-    request: &Request<Body>,
-    default_use_default_graph_as_union: bool,
-) -> Result<Response<Body>, HttpError> {
-    let mut default_graph_uris = Vec::new();
-    let mut named_graph_uris = Vec::new();
-    let mut use_default_graph_as_union = false;
-    for encoded in encoded {
-        for (k, v) in form_urlencoded::parse(encoded) {
-            match k.as_ref() {
-                "query" => {
-                    if query.is_some() {
-                        return Err(bad_request("Multiple query parameters provided"));
-                    }
-                    query = Some(v.into_owned())
-                }
-                "default-graph-uri" => default_graph_uris.push(v.into_owned()),
-                "union-default-graph" => use_default_graph_as_union = true,
-                "named-graph-uri" => named_graph_uris.push(v.into_owned()),
-                _ => (),
+/// Builds the authentication middleware from the --auth-*, --anonymous-role and --*-limits options, if any user, token or anonymous role is set
+fn auth_middleware(
+    users: &[String],
+    tokens: &[String],
+    file: Option<&Path>,
+    anonymous_role: Option<&str>,
+    role_limits: &[String],
+    principal_limits: &[String],
+) -> anyhow::Result<Option<AuthMiddleware>> {
+    let mut entries = users
+        .iter()
+        .map(|user| ("user", user.clone()))
+        .chain(tokens.iter().map(|token| ("token", token.clone())))
+        .collect::<Vec<_>>();
+    if let Some(file) = file {
+        let content = fs::read_to_string(file).with_context(|| {
+            format!("Failed to read the authentication file {}", file.display())
+        })?;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
             }
-        }
-    }
-    if default_graph_uris.is_empty() && named_graph_uris.is_empty() {
-        use_default_graph_as_union |= default_use_default_graph_as_union;
-    }
-    let query = query.ok_or_else(|| bad_request("You should set the 'query' parameter"))?;
-    evaluate_sparql_query(
-        store,
-        &query,
-        use_default_graph_as_union,
-        default_graph_uris,
-        named_graph_uris,
-        request,
-    )
-}
-
-fn evaluate_sparql_query(
-    store: &Store,
-    query: &str,
-    use_default_graph_as_union: bool,
-    default_graph_uris: Vec<String>,
-    named_graph_uris: Vec<String>,
-    request: &Request<Body>,
-) -> Result<Response<Body>, HttpError> {
-    let mut query = Query::parse(query, Some(&base_url(request))).map_err(bad_request)?;
-
-    if use_default_graph_as_union {
-        if !default_graph_uris.is_empty() || !named_graph_uris.is_empty() {
-            return Err(bad_request(
-                "default-graph-uri or named-graph-uri and union-default-graph should not be set at the same time",
-            ));
-        }
-        query.dataset_mut().set_default_graph_as_union()
-    } else if !default_graph_uris.is_empty() || !named_graph_uris.is_empty() {
-        query.dataset_mut().set_default_graph(
-            default_graph_uris
-                .into_iter()
-                .map(|e| Ok(NamedNode::new(e)?.into()))
-                .collect::<Result<Vec<GraphName>, IriParseError>>()
-                .map_err(bad_request)?,
-        );
-        query.dataset_mut().set_available_named_graphs(
-            named_graph_uris
-                .into_iter()
-                .map(|e| Ok(NamedNode::new(e)?.into()))
-                .collect::<Result<Vec<NamedOrBlankNode>, IriParseError>>()
-                .map_err(bad_request)?,
-        );
-    }
-
-    let results = store
-        .query_opt(query, default_query_options())
-        .map_err(internal_server_error)?;
-    match results {
-        QueryResults::Solutions(solutions) => {
-            let format = query_results_content_negotiation(request)?;
-            ReadForWrite::build_response(
-                move |w| {
-                    Ok((
-                        QueryResultsSerializer::from_format(format)
-                            .serialize_solutions_to_writer(w, solutions.variables().to_vec())?,
-                        solutions,
-                    ))
-                },
-                |(mut serializer, mut solutions)| {
-                    Ok(if let Some(solution) = solutions.next() {
-                        serializer.serialize(&solution?)?;
-                        Some((serializer, solutions))
-                    } else {
-                        serializer.finish()?;
-                        None
-                    })
-                },
-                format.media_type(),
-            )
-        }
-        QueryResults::Boolean(result) => {
-            let format = query_results_content_negotiation(request)?;
-            let mut body = Vec::new();
-            QueryResultsSerializer::from_format(format)
-                .serialize_boolean_to_writer(&mut body, result)
-                .map_err(internal_server_error)?;
-            Ok(Response::builder()
-                .header(CONTENT_TYPE, format.media_type())
-                .body(body.into())
-                .unwrap())
-        }
-        QueryResults::Graph(triples) => {
-            let format = rdf_content_negotiation(request)?;
-            ReadForWrite::build_response(
-                move |w| Ok((RdfSerializer::from_format(format).for_writer(w), triples)),
-                |(mut serializer, mut triples)| {
-                    Ok(if let Some(t) = triples.next() {
-                        serializer.serialize_triple(&t?)?;
-                        Some((serializer, triples))
-                    } else {
-                        serializer.finish()?;
-                        None
-                    })
-                },
-                format.media_type(),
-            )
-        }
-    }
-}
-
-fn default_query_options() -> QueryOptions {
-    let mut options = QueryOptions::default();
-    #[cfg(feature = "geosparql")]
-    {
-        options = register_geosparql_functions(options);
-    }
-    options
-}
-
-fn configure_and_evaluate_sparql_update(
-    store: &Store,
-    encoded: &[&[u8]],
-    mut update: Option<String>,
-    request: &Request<Body>,
-    default_use_default_graph_as_union: bool,
-) -> Result<Response<Body>, HttpError> {
-    let mut use_default_graph_as_union = false;
-    let mut default_graph_uris = Vec::new();
-    let mut named_graph_uris = Vec::new();
-    for encoded in encoded {
-        for (k, v) in form_urlencoded::parse(encoded) {
-            match k.as_ref() {
-                "update" => {
-                    if update.is_some() {
-                        return Err(bad_request("Multiple update parameters provided"));
-                    }
-                    update = Some(v.into_owned())
-                }
-                "using-graph-uri" => default_graph_uris.push(v.into_owned()),
-                "using-union-graph" => use_default_graph_as_union = true,
-                "using-named-graph-uri" => named_graph_uris.push(v.into_owned()),
-                _ => (),
+            match line.split_once(char::is_whitespace) {
+                Some(("user", user)) => entries.push(("user", user.trim().to_owned())),
+                Some(("token", token)) => entries.push(("token", token.trim().to_owned())),
+                _ => bail!(
+                    "The authentication file line '{line}' must be 'user ROLE=USER:PASSWORD' or 'token ROLE=NAME:TOKEN'"
+                ),
             }
         }
     }
-    if default_graph_uris.is_empty() && named_graph_uris.is_empty() {
-        use_default_graph_as_union |= default_use_default_graph_as_union;
-    }
-    let update = update.ok_or_else(|| bad_request("You should set the 'update' parameter"))?;
-    evaluate_sparql_update(
-        store,
-        &update,
-        use_default_graph_as_union,
-        default_graph_uris,
-        named_graph_uris,
-        request,
-    )
-}
-
-fn evaluate_sparql_update(
-    store: &Store,
-    update: &str,
-    use_default_graph_as_union: bool,
-    default_graph_uris: Vec<String>,
-    named_graph_uris: Vec<String>,
-    request: &Request<Body>,
-) -> Result<Response<Body>, HttpError> {
-    let mut update =
-        Update::parse(update, Some(base_url(request).as_str())).map_err(bad_request)?;
-
-    if use_default_graph_as_union {
-        if !default_graph_uris.is_empty() || !named_graph_uris.is_empty() {
-            return Err(bad_request(
-                "using-graph-uri or using-named-graph-uri and using-union-graph should not be set at the same time",
-            ));
-        }
-        for using in update.using_datasets_mut() {
-            if !using.is_default_dataset() {
-                return Err(bad_request(
-                    "using-union-graph must not be used with a SPARQL UPDATE containing USING",
-                ));
-            }
-            using.set_default_graph_as_union();
-        }
-    } else if !default_graph_uris.is_empty() || !named_graph_uris.is_empty() {
-        let default_graph_uris = default_graph_uris
-            .into_iter()
-            .map(|e| Ok(NamedNode::new(e)?.into()))
-            .collect::<Result<Vec<GraphName>, IriParseError>>()
-            .map_err(bad_request)?;
-        let named_graph_uris = named_graph_uris
-            .into_iter()
-            .map(|e| Ok(NamedNode::new(e)?.into()))
-            .collect::<Result<Vec<NamedOrBlankNode>, IriParseError>>()
-            .map_err(bad_request)?;
-        for using in update.using_datasets_mut() {
-            if !using.is_default_dataset() {
-                return Err(bad_request(
-                    "using-graph-uri and using-named-graph-uri must not be used with a SPARQL UPDATE containing USING",
-                ));
-            }
-            using.set_default_graph(default_graph_uris.clone());
-            using.set_available_named_graphs(named_graph_uris.clone());
-        }
+    if entries.is_empty() && anonymous_role.is_none() {
+        return Ok(None);
     }
-    store
-        .update_opt(update, default_query_options())
-        .map_err(internal_server_error)?;
-    Ok(Response::builder()
-        .status(StatusCode::NO_CONTENT)
-        .body(Body::empty())
-        .unwrap())
-}
-
-fn store_target(request: &Request<Body>) -> Result<Option<NamedGraphName>, HttpError> {
-    if request.uri().path() == "/store" {
-        if let Some(graph) = url_query_parameter(request, "graph") {
-            if url_query_parameter(request, "default").is_some() {
-                Err(bad_request(
-                    "Both graph and default parameters should not be set at the same time",
-                ))
-            } else {
-                Ok(Some(NamedGraphName::NamedNode(resolve_with_base(
-                    request, &graph,
-                )?)))
-            }
-        } else if url_query_parameter(request, "default").is_some() {
-            Ok(Some(NamedGraphName::DefaultGraph))
+    let mut auth = AuthMiddleware::new();
+    for (kind, entry) in entries {
+        let Some((role, name, secret)) = entry.split_once('=').and_then(|(role, credentials)| {
+            let (name, secret) = credentials.split_once(':')?;
+            Some((role, name, secret))
+        }) else {
+            bail!("The {kind} '{entry}' must be written ROLE=NAME:SECRET")
+        };
+        let role = role_from_name(role)?;
+        auth = if kind == "user" {
+            auth.with_user(name, secret, role)
         } else {
-            Ok(None)
-        }
-    } else {
-        Ok(Some(NamedGraphName::NamedNode(resolve_with_base(
-            request, "",
-        )?)))
+            auth.with_token(name, secret, role)
+        };
     }
-}
-
-fn assert_that_graph_exists(store: &Store, target: &NamedGraphName) -> Result<(), HttpError> {
-    if match target {
-        NamedGraphName::DefaultGraph => true,
-        NamedGraphName::NamedNode(target) => store
-            .contains_named_graph(target)
-            .map_err(internal_server_error)?,
-    } {
-        Ok(())
-    } else {
-        Err((
-            StatusCode::NOT_FOUND,
-            format!(
-                "The graph {} does not exists",
-                GraphName::from(target.clone())
-            ),
-        ))
+    if let Some(anonymous_role) = anonymous_role {
+        auth = auth.with_anonymous_role(role_from_name(anonymous_role)?);
     }
-}
-
-#[derive(Eq, PartialEq, Debug, Clone, Hash)]
-enum NamedGraphName {
-    NamedNode(NamedNode),
-    DefaultGraph,
-}
-
-impl From<NamedGraphName> for GraphName {
-    fn from(graph_name: NamedGraphName) -> Self {
-        match graph_name {
-            NamedGraphName::NamedNode(node) => node.into(),
-            NamedGraphName::DefaultGraph => Self::DefaultGraph,
-        }
+    for limits in role_limits {
+        let (role, limits) = query_limits_from_arg(limits)?;
+        auth = auth.with_role_limits(role_from_name(role)?, limits);
     }
-}
-
-fn rdf_content_negotiation(request: &Request<Body>) -> Result<RdfFormat, HttpError> {
-    content_negotiation(
-        request,
-        RdfFormat::from_media_type,
-        RdfFormat::NQuads,
-        &[
-            ("application", RdfFormat::NQuads),
-            ("text", RdfFormat::NQuads),
-        ],
-        "application/n-quads or text/turtle",
-    )
-}
-
-fn query_results_content_negotiation(
-    request: &Request<Body>,
-) -> Result<QueryResultsFormat, HttpError> {
-    content_negotiation(
-        request,
-        QueryResultsFormat::from_media_type,
-        QueryResultsFormat::Json,
-        &[
-            ("application", QueryResultsFormat::Json),
-            ("text", QueryResultsFormat::Json),
-        ],
-        "application/sparql-results+json or text/tsv",
-    )
-}
-
-fn content_negotiation<F: Copy>(
-    request: &Request<Body>,
-    parse: impl Fn(&str) -> Option<F>,
-    default: F,
-    default_by_base: &[(&str, F)],
-    example: &str,
-) -> Result<F, HttpError> {
-    let header = request
-        .headers()
-        .get(ACCEPT)
-        .map(|h| h.to_str())
-        .transpose()
-        .map_err(|_| bad_request("The Accept header should be a valid ASCII string"))?
-        .unwrap_or_default();
-
-    if header.is_empty() {
-        return Ok(default);
-    }
-    let mut result = None;
-    let mut result_score = 0_f32;
-    for mut possible in header.split(',') {
-        let mut score = 1.;
-        if let Some((possible_type, last_parameter)) = possible.rsplit_once(';') {
-            if let Some((name, value)) = last_parameter.split_once('=') {
-                if name.trim().eq_ignore_ascii_case("q") {
-                    score = f32::from_str(value.trim()).map_err(|_| {
-                        bad_request(format!("Invalid Accept media type score: {value}"))
-                    })?;
-                    possible = possible_type;
-                }
-            }
-        }
-        if score <= result_score {
-            continue;
-        }
-        let (possible_base, possible_sub) = possible
-            .split_once(';')
-            .unwrap_or((possible, ""))
-            .0
-            .split_once('/')
-            .ok_or_else(|| bad_request(format!("Invalid media type: '{possible}'")))?;
-        let possible_base = possible_base.trim();
-        let possible_sub = possible_sub.trim();
-
-        let mut format = None;
-        if possible_base == "*" && possible_sub == "*" {
-            format = Some(default);
-        } else if possible_sub == "*" {
-            for (base, sub_format) in default_by_base {
-                if *base == possible_base {
-                    format = Some(*sub_format);
-                }
-            }
-        } else {
-            format = parse(possible);
-        }
-        if let Some(format) = format {
-            result = Some(format);
-            result_score = score;
-        }
+    for limits in principal_limits {
+        let (name, limits) = query_limits_from_arg(limits)?;
+        auth = auth.with_principal_limits(name, limits);
     }
-
-    result.ok_or_else(|| {
-        (
-            StatusCode::NOT_ACCEPTABLE,
-            format!("The accept header does not provide any accepted format like {example}"),
-        )
-    })
+    Ok(Some(auth))
 }
 
-fn content_type(request: &Request<Body>) -> Option<String> {
-    let value = request.headers().get(CONTENT_TYPE)?.to_str().ok()?;
-    Some(
-        value
-            .split_once(';')
-            .map_or(value, |(b, _)| b)
-            .trim()
-            .to_ascii_lowercase(),
-    )
-}
-
-fn web_load_graph(
-    store: &Store,
-    request: &mut Request<Body>,
-    format: RdfFormat,
-    to_graph_name: &GraphName,
-) -> Result<(), HttpError> {
-    let base_iri = if let GraphName::NamedNode(graph_name) = to_graph_name {
-        Some(graph_name.as_str())
-    } else {
-        None
+/// Parses a NAME:LIMITS argument, LIMITS being a comma-separated list of KEY=VALUE pairs
+fn query_limits_from_arg(arg: &str) -> anyhow::Result<(&str, QueryLimits)> {
+    let Some((name, limits)) = arg.split_once(':') else {
+        bail!("The limits '{arg}' must be written NAME:LIMITS")
     };
-    let mut parser = RdfParser::from_format(format)
-        .without_named_graphs()
-        .with_default_graph(to_graph_name.clone());
-    if url_query_parameter(request, "lenient").is_some() {
-        parser = parser.lenient();
-    }
-    if let Some(base_iri) = base_iri {
-        parser = parser.with_base_iri(base_iri).map_err(bad_request)?;
-    }
-    if url_query_parameter(request, "no_transaction").is_some() {
-        web_bulk_loader(store, request).load_from_reader(parser, request.body_mut())
-    } else {
-        store.load_from_reader(parser, request.body_mut())
-    }
-    .map_err(loader_to_http_error)
-}
-
-fn web_load_dataset(
-    store: &Store,
-    request: &mut Request<Body>,
-    format: RdfFormat,
-) -> Result<(), HttpError> {
-    let mut parser = RdfParser::from_format(format);
-    if url_query_parameter(request, "lenient").is_some() {
-        parser = parser.lenient();
-    }
-    if url_query_parameter(request, "no_transaction").is_some() {
-        web_bulk_loader(store, request).load_from_reader(parser, request.body_mut())
-    } else {
-        store.load_from_reader(parser, request.body_mut())
-    }
-    .map_err(loader_to_http_error)
-}
-
-fn web_bulk_loader(store: &Store, request: &Request<Body>) -> BulkLoader {
-    let start = Instant::now();
-    let mut loader = store.bulk_loader().on_progress(move |size| {
-        let elapsed = start.elapsed();
-        eprintln!(
-            "{} triples loaded in {}s ({} t/s)",
-            size,
-            elapsed.as_secs(),
-            ((size as f64) / elapsed.as_secs_f64()).round()
-        )
-    });
-    if url_query_parameter(request, "lenient").is_some() {
-        loader = loader.on_parse_error(move |e| {
-            eprintln!("Parsing error: {e}");
-            Ok(())
-        })
+    let mut query_limits = QueryLimits::new();
+    for limit in limits.split(',') {
+        let Some((key, value)) = limit.split_once('=') else {
+            bail!("The limit '{limit}' must be written KEY=VALUE")
+        };
+        query_limits = match key.trim() {
+            "max-result-rows" => query_limits.with_max_result_rows(
+                value
+                    .parse()
+                    .with_context(|| format!("The max-result-rows limit '{value}' is invalid"))?,
+            ),
+            "max-query-time" => {
+                query_limits.with_max_query_time(
+                    Duration::try_from_secs_f64(value.parse().with_context(|| {
+                        format!("The max-query-time limit '{value}' is invalid")
+                    })?)
+                    .with_context(|| format!("The max-query-time limit '{value}' is invalid"))?,
+                )
+            }
+            "daily-budget" => query_limits.with_daily_budget(
+                value
+                    .parse()
+                    .with_context(|| format!("The daily-budget limit '{value}' is invalid"))?,
+            ),
+            _ => bail!(
+                "The limit '{key}' is unknown, it must be 'max-result-rows', 'max-query-time' or 'daily-budget'"
+            ),
+        };
     }
-    loader
-}
-
-fn error(status: StatusCode, message: impl fmt::Display) -> Response<Body> {
-    Response::builder()
-        .status(status)
-        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
-        .body(message.to_string().into())
-        .unwrap()
+    Ok((name, query_limits))
 }
 
-fn bad_request(message: impl fmt::Display) -> HttpError {
-    (StatusCode::BAD_REQUEST, message.to_string())
-}
-
-fn the_server_is_read_only() -> HttpError {
-    (StatusCode::FORBIDDEN, "The server is read-only".into())
-}
-
-fn unsupported_media_type(content_type: &str) -> HttpError {
-    (
-        StatusCode::UNSUPPORTED_MEDIA_TYPE,
-        format!("No supported content Content-Type given: {content_type}"),
-    )
-}
-
-fn internal_server_error(message: impl fmt::Display) -> HttpError {
-    eprintln!("Internal server error: {message}");
-    (StatusCode::INTERNAL_SERVER_ERROR, message.to_string())
-}
-
-fn loader_to_http_error(e: LoaderError) -> HttpError {
-    match e {
-        LoaderError::Parsing(e) => bad_request(e),
-        LoaderError::Storage(e) => internal_server_error(e),
-        LoaderError::InvalidBaseIri { .. } => bad_request(e),
-    }
+fn role_from_name(name: &str) -> anyhow::Result<Role> {
+    Ok(match name {
+        "read" => Role::Read,
+        "write" => Role::Write,
+        _ => bail!("The role '{name}' is unknown, it must be 'read' or 'write'"),
+    })
 }
 
-/// Hacky tool to allow implementing read on top of a write loop
-struct ReadForWrite<O, U: (Fn(O) -> io::Result<Option<O>>)> {
-    buffer: Rc<RefCell<Vec<u8>>>,
-    position: usize,
-    add_more_data: U,
-    state: Option<O>,
+fn storage_profile_from_name(name: &str) -> anyhow::Result<StorageProfile> {
+    Ok(match name {
+        "default" => StorageProfile::Default,
+        "small" => StorageProfile::Small,
+        _ => bail!("The storage profile '{name}' is unknown, it must be 'default' or 'small'"),
+    })
 }
 
-impl<O: 'static, U: (Fn(O) -> io::Result<Option<O>>) + 'static> ReadForWrite<O, U> {
-    fn build_response(
-        initial_state_builder: impl FnOnce(ReadForWriteWriter) -> io::Result<O>,
-        add_more_data: U,
-        content_type: &'static str,
-    ) -> Result<Response<Body>, HttpError> {
-        let buffer = Rc::new(RefCell::new(Vec::new()));
-        let state = initial_state_builder(ReadForWriteWriter {
-            buffer: Rc::clone(&buffer),
-        })
-        .map_err(internal_server_error)?;
-        Response::builder()
-            .header(CONTENT_TYPE, content_type)
-            .body(Body::from_read(Self {
-                buffer,
-                position: 0,
-                add_more_data,
-                state: Some(state),
-            }))
-            .map_err(internal_server_error)
-    }
+#[cfg(feature = "graphql")]
+fn load_graphql_schema(file: &Path) -> anyhow::Result<GraphQlSchema> {
+    let format = file
+        .extension()
+        .and_then(OsStr::to_str)
+        .and_then(RdfFormat::from_extension)
+        .with_context(|| {
+            format!(
+                "The file {} has no extension or an unknown one, the SHACL shapes format can't be guessed",
+                file.display()
+            )
+        })?;
+    GraphQlSchema::from_shacl_reader(format, File::open(file)?)
+        .with_context(|| format!("Failed to build the GraphQL schema from {}", file.display()))
 }
 
-impl<O, U: (Fn(O) -> io::Result<Option<O>>)> Read for ReadForWrite<O, U> {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        while self.position == self.buffer.borrow().len() {
-            // We read more data
-            if let Some(state) = self.state.take() {
-                self.buffer.borrow_mut().clear();
-                self.position = 0;
-                self.state = match (self.add_more_data)(state) {
-                    Ok(state) => state,
-                    Err(e) => {
-                        eprintln!("Internal server error while streaming results: {e}");
-                        self.buffer
-                            .borrow_mut()
-                            .write_all(e.to_string().as_bytes())?;
-                        None
-                    }
-                }
-            } else {
-                return Ok(0); // End
-            }
-        }
-        let buffer = self.buffer.borrow();
-        let len = min(buffer.len() - self.position, buf.len());
-        buf[..len].copy_from_slice(&buffer[self.position..self.position + len]);
-        self.position += len;
-        Ok(len)
+fn serve(
+    mut server: SparqlServer,
+    bind: &str,
+    cors: bool,
+    cors_allow_origin: Vec<String>,
+    auth: Option<AuthMiddleware>,
+    union_default_graph: bool,
+    #[cfg(feature = "graphql")] graphql_schema: Option<&Path>,
+) -> anyhow::Result<()> {
+    if cors {
+        server = server.with_cors();
+    } else if !cors_allow_origin.is_empty() {
+        server = server.with_cors_allowed_origins(cors_allow_origin);
     }
-}
-
-struct ReadForWriteWriter {
-    buffer: Rc<RefCell<Vec<u8>>>,
-}
-
-impl Write for ReadForWriteWriter {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.buffer.borrow_mut().write(buf)
+    if let Some(auth) = auth {
+        server = server.with_middleware(auth);
     }
-
-    fn flush(&mut self) -> io::Result<()> {
-        Ok(())
+    if union_default_graph {
+        server = server.with_union_default_graph();
     }
-
-    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
-        self.buffer.borrow_mut().write_all(buf)
+    #[cfg(feature = "graphql")]
+    if let Some(graphql_schema) = graphql_schema {
+        server = server.with_graphql_schema(load_graphql_schema(graphql_schema)?);
     }
+    let server = server.spawn(bind)?;
+    #[cfg(target_os = "linux")]
+    systemd_notify_ready()?;
+    eprintln!("Listening for requests at http://{bind}");
+    server.join()?;
+    Ok(())
 }
 
 fn close_file_writer(writer: BufWriter<File>) -> io::Result<()> {
@@ -1733,19 +1527,21 @@ fn systemd_notify_ready() -> io::Result<()> {
 }
 
 #[cfg(test)]
-#[expect(clippy::panic_in_result_fn)]
 mod tests {
     use super::*;
     use anyhow::Result;
     use assert_cmd::Command;
+    use assert_cmd::assert::Assert;
     use assert_fs::prelude::*;
     use assert_fs::{NamedTempFile, TempDir};
+    use ed25519_dalek::SigningKey;
+    use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+    use ed25519_dalek::pkcs8::{EncodePrivateKey, EncodePublicKey};
     use flate2::Compression;
     use flate2::write::GzEncoder;
-    use oxhttp::model::header::ACCEPT;
     use predicates::prelude::*;
-    use std::fs::remove_dir_all;
-    use std::io::read_to_string;
+    use sha2::Digest;
+    use std::fs::{self, remove_dir_all};
 
     fn cli_command() -> Command {
         let mut command = Command::new(env!("CARGO"));
@@ -1838,6 +1634,82 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cli_map_csv_with_metadata() -> Result<()> {
+        let store_dir = TempDir::new()?;
+        let input_file = NamedTempFile::new("people.csv")?;
+        input_file.write_str("id,name,friends\n1,Alice,2 3\n2,\"Bob \"\"B\"\"\",\n")?;
+        let metadata_file = NamedTempFile::new("metadata.json")?;
+        metadata_file.write_str(
+            r#"{
+                "@context": "http://www.w3.org/ns/csvw",
+                "tableSchema": {
+                    "aboutUrl": "http://example.com/person/{id}",
+                    "columns": [
+                        { "name": "id", "suppressOutput": true },
+                        { "name": "name", "propertyUrl": "schema:name" },
+                        { "name": "friends", "separator": " ", "propertyUrl": "schema:knows", "valueUrl": "http://example.com/person/{friends}" },
+                        { "virtual": true, "propertyUrl": "rdf:type", "valueUrl": "schema:Person" }
+                    ]
+                }
+            }"#,
+        )?;
+        cli_command()
+            .arg("map")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--file")
+            .arg(input_file.path())
+            .arg("--metadata")
+            .arg(metadata_file.path())
+            .assert()
+            .success();
+        cli_command()
+            .arg("query")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--query")
+            .arg("SELECT ?s ?p ?o WHERE { ?s ?p ?o } ORDER BY ?s ?p ?o")
+            .arg("--results-format")
+            .arg("tsv")
+            .assert()
+            .stdout("?s\t?p\t?o\n<http://example.com/person/1>\t<http://schema.org/knows>\t<http://example.com/person/2>\n<http://example.com/person/1>\t<http://schema.org/knows>\t<http://example.com/person/3>\n<http://example.com/person/1>\t<http://schema.org/name>\t\"Alice\"\n<http://example.com/person/1>\t<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>\t<http://schema.org/Person>\n<http://example.com/person/2>\t<http://schema.org/name>\t\"Bob \\\"B\\\"\"\n<http://example.com/person/2>\t<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>\t<http://schema.org/Person>\n")
+            .success();
+        Ok(())
+    }
+
+    #[test]
+    fn cli_map_json() -> Result<()> {
+        let store_dir = TempDir::new()?;
+        let input_file = NamedTempFile::new("input.json")?;
+        input_file
+            .write_str(r#"[{"name": "Alice", "age": 42, "tags": ["a", "b"]}, {"name": null}]"#)?;
+        cli_command()
+            .arg("map")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--file")
+            .arg(input_file.path())
+            .arg("--base")
+            .arg("http://example.com/data")
+            .arg("--graph")
+            .arg("http://example.com/g")
+            .assert()
+            .success();
+        cli_command()
+            .arg("query")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--query")
+            .arg("SELECT ?p ?o WHERE { GRAPH <http://example.com/g> { [] ?p ?o } } ORDER BY ?p ?o")
+            .arg("--results-format")
+            .arg("tsv")
+            .assert()
+            .stdout("?p\t?o\n<http://example.com/data#age>\t42\n<http://example.com/data#name>\t\"Alice\"\n<http://example.com/data#tags>\t\"a\"\n<http://example.com/data#tags>\t\"b\"\n")
+            .success();
+        Ok(())
+    }
+
     #[test]
     fn cli_load_and_dump_dataset() -> Result<()> {
         let store_dir = TempDir::new()?;
@@ -1867,6 +1739,126 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cli_load_with_external_sort() -> Result<()> {
+        let store_dir = TempDir::new()?;
+        let sort_dir = TempDir::new()?;
+        let input_file = NamedTempFile::new("input.nq")?;
+        input_file
+            .write_str("<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .")?;
+        cli_command()
+            .arg("load")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--file")
+            .arg(input_file.path())
+            .arg("--external-sort-dir")
+            .arg(sort_dir.path())
+            .assert()
+            .success();
+        cli_command()
+            .arg("dump")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--format")
+            .arg("nq")
+            .assert()
+            .success()
+            .stdout("<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .\n");
+        Ok(())
+    }
+
+    #[test]
+    fn cli_load_reproducible() -> Result<()> {
+        let input_file = NamedTempFile::new("input.ttl")?;
+        input_file.write_str(
+            "<http://example.com/s> <http://example.com/p> [ <http://example.com/p> _:b ] .",
+        )?;
+        let load_and_dump = || -> Result<Assert> {
+            let store_dir = TempDir::new()?;
+            cli_command()
+                .arg("load")
+                .arg("--location")
+                .arg(store_dir.path())
+                .arg("--file")
+                .arg(input_file.path())
+                .arg("--reproducible")
+                .assert()
+                .success();
+            Ok(cli_command()
+                .arg("dump")
+                .arg("--location")
+                .arg(store_dir.path())
+                .arg("--format")
+                .arg("nq")
+                .assert()
+                .success())
+        };
+        let first_dump = load_and_dump()?.get_output().stdout.clone();
+        load_and_dump()?.stdout(first_dump);
+        Ok(())
+    }
+
+    #[test]
+    fn cli_dump_signed_and_load_verified() -> Result<()> {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let private_key = NamedTempFile::new("key.pem")?;
+        private_key.write_str(&signing_key.to_pkcs8_pem(LineEnding::LF)?)?;
+        let public_key = NamedTempFile::new("key.pub.pem")?;
+        public_key.write_str(
+            &signing_key
+                .verifying_key()
+                .to_public_key_pem(LineEnding::LF)?,
+        )?;
+        let store_dir = initialized_cli_store(
+            "<http://example.com/s> <http://example.com/p> [ <http://example.com/p> \"o\" ] .",
+        )?;
+        let dump_dir = TempDir::new()?;
+        let dump_file = dump_dir.child("dump.ttl");
+        cli_command()
+            .arg("dump")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--file")
+            .arg(dump_file.path())
+            .arg("--graph")
+            .arg("default")
+            .arg("--sign-key")
+            .arg(private_key.path())
+            .assert()
+            .success();
+        dump_dir
+            .child("dump.ttl.sig")
+            .assert(predicate::path::exists());
+
+        let load_dir = TempDir::new()?;
+        let load = || {
+            let mut command = cli_command();
+            command
+                .arg("load")
+                .arg("--location")
+                .arg(load_dir.path())
+                .arg("--file")
+                .arg(dump_file.path())
+                .arg("--verify-key")
+                .arg(public_key.path());
+            command
+        };
+        load().assert().success();
+
+        dump_file.write_str("<http://example.com/s> <http://example.com/p> \"o\" .")?;
+        load()
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("does not match"));
+        load()
+            .arg("--warn-on-invalid-signature")
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("does not match"));
+        Ok(())
+    }
+
     #[test]
     fn cli_load_gzip_dataset() -> Result<()> {
         let store_dir = TempDir::new()?;
@@ -1930,6 +1922,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cli_load_and_dump_sorted() -> Result<()> {
+        let store_dir = TempDir::new()?;
+        cli_command()
+            .arg("load")
+            .arg("-l")
+            .arg(store_dir.path())
+            .arg("--format")
+            .arg("nq")
+            .write_stdin("<http://example.com/s2> <http://example.com/p> <http://example.com/o> <http://example.com/g> .\n<http://example.com/s2> <http://example.com/p> <http://example.com/o> .\n<http://example.com/s1> <http://example.com/p> <http://example.com/o> .\n")
+            .assert()
+            .success();
+
+        cli_command()
+            .arg("dump")
+            .arg("-l")
+            .arg(store_dir.path())
+            .arg("--format")
+            .arg("nq")
+            .arg("--sort")
+            .assert()
+            .success()
+            .stdout("<http://example.com/s1> <http://example.com/p> <http://example.com/o> .\n<http://example.com/s2> <http://example.com/p> <http://example.com/o> .\n<http://example.com/s2> <http://example.com/p> <http://example.com/o> <http://example.com/g> .\n");
+        Ok(())
+    }
+
     #[test]
     fn cli_load_and_dump_with_format() -> Result<()> {
         let store_dir = TempDir::new()?;
@@ -1990,6 +2008,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cli_load_from_stdin_with_detected_format() -> Result<()> {
+        let store_dir = TempDir::new()?;
+        cli_command()
+            .arg("load")
+            .arg("--location")
+            .arg(store_dir.path())
+            .write_stdin("<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .")
+            .assert()
+            .success();
+
+        cli_command()
+            .arg("dump")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--format")
+            .arg("nq")
+            .assert()
+            .success()
+            .stdout("<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .\n");
+        Ok(())
+    }
+
     #[test]
     fn cli_backup() -> Result<()> {
         let store_dir = initialized_cli_store(
@@ -2006,32 +2047,220 @@ mod tests {
             .arg(backup_dir.path())
             .assert()
             .success();
-
-        assert_cli_state(
-            &store_dir,
-            "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n",
-        );
+
+        assert_cli_state(
+            &store_dir,
+            "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cli_incremental_backup() -> Result<()> {
+        let store_dir = initialized_cli_store(
+            "<http://example.com/s> <http://example.com/p> <http://example.com/o> .",
+        )?;
+
+        let repository_dir = TempDir::new()?;
+        for _ in 0..2 {
+            cli_command()
+                .arg("backup")
+                .arg("--location")
+                .arg(store_dir.path())
+                .arg("--destination")
+                .arg(repository_dir.path())
+                .arg("--incremental")
+                .arg("--keep")
+                .arg("1")
+                .assert()
+                .success();
+        }
+        ensure!(
+            BackupRepository::open(repository_dir.path())?
+                .backups()?
+                .len()
+                == 1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cli_publish() -> Result<()> {
+        let store_dir = initialized_cli_store(
+            "<http://example.com/s> <http://example.com/p> \"o\" , <http://example.com/o> .\n\
+            <http://example.com/g> { <http://example.com/s> a <http://example.com/C> }",
+        )?;
+        let out_dir = TempDir::new()?;
+        cli_command()
+            .arg("publish")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--out")
+            .arg(out_dir.path())
+            .assert()
+            .success();
+
+        let default_graph = "<http://example.com/s> <http://example.com/p> \"o\" .\n\
+            <http://example.com/s> <http://example.com/p> <http://example.com/o> .\n";
+        let named_graph = "<http://example.com/s> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.com/C> <http://example.com/g> .\n";
+        let named_graph_hash = hex::encode(sha2::Sha256::digest("<http://example.com/g>"));
+        out_dir.child("default.nq").assert(default_graph);
+        out_dir
+            .child(format!("graphs/{named_graph_hash}.nq"))
+            .assert(named_graph);
+        out_dir.child("void.ttl").assert(
+            predicate::str::contains("<> a void:Dataset ;\n    void:triples 3 ;")
+                .and(predicate::str::contains(format!(
+                    "sd:name <http://example.com/g> ;\n    void:dataDump <graphs/{named_graph_hash}.nq> ;\n    void:triples 1 ;"
+                ))),
+        );
+        out_dir
+            .child("SHA256SUMS")
+            .assert(predicate::str::starts_with(format!(
+                "{}  default.nq\n{}  graphs/{named_graph_hash}.nq\n",
+                hex::encode(sha2::Sha256::digest(default_graph)),
+                hex::encode(sha2::Sha256::digest(named_graph))
+            )));
+
+        // The output directory must be empty
+        cli_command()
+            .arg("publish")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--out")
+            .arg(out_dir.path())
+            .assert()
+            .failure();
+        Ok(())
+    }
+
+    #[test]
+    fn cli_ask_query_inline() -> Result<()> {
+        let store_dir = initialized_cli_store(
+            "<http://example.com/s> <http://example.com/p> <http://example.com/o> .",
+        )?;
+        cli_command()
+            .arg("query")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--query")
+            .arg("ASK { <s> <p> <o> }")
+            .arg("--query-base")
+            .arg("http://example.com/")
+            .arg("--results-format")
+            .arg("csv")
+            .assert()
+            .stdout("true")
+            .success();
+        Ok(())
+    }
+
+    #[test]
+    fn cli_query_facade() -> Result<()> {
+        let store_dir = initialized_cli_store(
+            "<http://example.com/alice> <http://xmlns.com/foaf/0.1/name> \"Alice\" .",
+        )?;
+        let csv_file = NamedTempFile::new("people.csv")?;
+        csv_file.write_str("name,age\nAlice,42\nBob,21\n")?;
+        let xml_file = NamedTempFile::new("people.xml")?;
+        xml_file.write_str("<people><person name=\"Bob\">Builder</person></people>")?;
+        cli_command()
+            .arg("query")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--query")
+            .arg(format!(
+                "SELECT ?person ?age WHERE {{
+                    ?person <http://xmlns.com/foaf/0.1/name> ?name
+                    SERVICE <x-sparql-anything:location={},csv.headers=true> {{
+                        ?row <http://sparql.xyz/facade-x/data/name> ?name ; <http://sparql.xyz/facade-x/data/age> ?age
+                    }}
+                }}",
+                csv_file.path().display()
+            ))
+            .arg("--results-format")
+            .arg("tsv")
+            .assert()
+            .stdout("?person\t?age\n<http://example.com/alice>\t\"42\"\n")
+            .success();
+        cli_command()
+            .arg("query")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--query")
+            .arg(format!(
+                "SELECT ?name ?text WHERE {{
+                    SERVICE <x-sparql-anything:{}> {{
+                        [] a <http://sparql.xyz/facade-x/data/person> ;
+                            <http://sparql.xyz/facade-x/data/name> ?name ;
+                            <http://www.w3.org/1999/02/22-rdf-syntax-ns#_1> ?text
+                    }}
+                }}",
+                xml_file.path().display()
+            ))
+            .arg("--results-format")
+            .arg("tsv")
+            .assert()
+            .stdout("?name\t?text\n\"Bob\"\t\"Builder\"\n")
+            .success();
         Ok(())
     }
 
     #[test]
-    fn cli_ask_query_inline() -> Result<()> {
+    fn cli_replay() -> Result<()> {
         let store_dir = initialized_cli_store(
             "<http://example.com/s> <http://example.com/p> <http://example.com/o> .",
         )?;
+        let log_file = NamedTempFile::new("log.jsonl")?;
+        log_file.write_str(
+            "\"ASK { ?s ?p ?o }\"\n\n{\"query\": \"SELECT ?s WHERE { ?s ?p ?o }\", \"time\": 12}\n",
+        )?;
+        let report_file = NamedTempFile::new("report.tsv")?;
         cli_command()
-            .arg("query")
+            .arg("replay")
             .arg("--location")
             .arg(store_dir.path())
-            .arg("--query")
-            .arg("ASK { <s> <p> <o> }")
-            .arg("--query-base")
-            .arg("http://example.com/")
-            .arg("--results-format")
-            .arg("csv")
+            .arg(log_file.path())
+            .arg("--concurrency")
+            .arg("2")
+            .arg("--report-file")
+            .arg(report_file.path())
             .assert()
-            .stdout("true")
+            .stderr(predicate::str::contains("2 queries replayed"))
+            .success();
+        cli_command()
+            .arg("replay")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg(log_file.path())
+            .arg("--baseline")
+            .arg(report_file.path())
+            .assert()
+            .stdout(predicate::str::starts_with("line\tduration_ms\thash\n1\t"))
+            .stderr(predicate::str::contains(
+                "All the results are the same as in the baseline",
+            ))
             .success();
+        cli_command()
+            .arg("update")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--update")
+            .arg("INSERT DATA { <http://example.com/s2> <http://example.com/p> <http://example.com/o> }")
+            .assert()
+            .success();
+        cli_command()
+            .arg("replay")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg(log_file.path())
+            .arg("--baseline")
+            .arg(report_file.path())
+            .assert()
+            .stderr(predicate::str::contains(
+                "1 queries have results different from the baseline, on lines: 3",
+            ))
+            .failure();
         Ok(())
     }
 
@@ -2077,6 +2306,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cli_select_query_arrow() -> Result<()> {
+        let store_dir = initialized_cli_store(
+            "<http://example.com/s> <http://example.com/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .",
+        )?;
+        let output_file = NamedTempFile::new("output.arrows")?;
+        cli_command()
+            .arg("query")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--query")
+            .arg("SELECT ?o WHERE { ?s ?p ?o }")
+            .arg("--results-file")
+            .arg(output_file.path())
+            .assert()
+            .success();
+        // Starts with the schema message and ends with the end of stream marker
+        let content = fs::read(output_file.path())?;
+        ensure!(content.starts_with(&[0xFF; 4]));
+        ensure!(content.ends_with(&[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]));
+        Ok(())
+    }
+
+    #[test]
+    fn cli_query_values_file() -> Result<()> {
+        let store_dir = initialized_cli_store(
+            "<http://example.com/alice> <http://xmlns.com/foaf/0.1/name> \"Alice\" . <http://example.com/bob> <http://xmlns.com/foaf/0.1/name> \"Bob\" .",
+        )?;
+        let csv_file = NamedTempFile::new("people.csv")?;
+        csv_file.write_str("name,age\nAlice,42\nCarol,21\n")?;
+        cli_command()
+            .arg("query")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--query")
+            .arg("SELECT ?person ?age WHERE { ?person <http://xmlns.com/foaf/0.1/name> ?name }")
+            .arg("--values-file")
+            .arg(csv_file.path())
+            .arg("--results-format")
+            .arg("tsv")
+            .assert()
+            .stdout("?person\t?age\n<http://example.com/alice>\t42\n")
+            .success();
+        Ok(())
+    }
+
     #[test]
     fn cli_ask_union_default_graph() -> Result<()> {
         let store_dir = initialized_cli_store(
@@ -2210,6 +2485,51 @@ mod tests {
             .stdout("<http://example.com/sg> <http://example.com/pg> <http://example.com/og> .\n");
     }
 
+    #[test]
+    fn cli_convert_compressed_with_prefix() -> Result<()> {
+        let input_file = NamedTempFile::new("input.nq.gz")?;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(
+            b"<http://example.com/s> <http://schema.org/name> \"Foo\" <http://example.com/g> .\n",
+        )?;
+        input_file.write_binary(&encoder.finish()?)?;
+        let output_file = NamedTempFile::new("output.ttl.zst")?;
+        cli_command()
+            .arg("convert")
+            .arg("--from-file")
+            .arg(input_file.path())
+            .arg("--from-graph")
+            .arg("http://example.com/g")
+            .arg("--to-file")
+            .arg(output_file.path())
+            .arg("--prefix")
+            .arg("schema=http://schema.org/")
+            .assert()
+            .success();
+        let output = zstd::decode_all(File::open(output_file.path())?)?;
+        ensure!(
+            output == b"@prefix schema: <http://schema.org/> .\n<http://example.com/s> schema:name \"Foo\" .\n",
+            "Unexpected output: {}",
+            String::from_utf8_lossy(&output)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cli_convert_pretty() {
+        cli_command()
+            .arg("convert")
+            .arg("--from-format")
+            .arg("nt")
+            .arg("--to-format")
+            .arg("ttl")
+            .arg("--pretty")
+            .write_stdin("<http://example.com/s> <http://example.com/p> _:o .\n<http://example.com/s2> <http://example.com/p> <http://example.com/o> .\n_:o <http://example.com/p> <http://example.com/o> .\n<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n")
+            .assert()
+            .stdout("<http://example.com/s> <http://example.com/p> [ <http://example.com/p> <http://example.com/o> ] , <http://example.com/o> .\n<http://example.com/s2> <http://example.com/p> <http://example.com/o> .\n")
+            .success();
+    }
+
     #[test]
     fn cli_convert_to_base() {
         cli_command()
@@ -2245,804 +2565,33 @@ mod tests {
     }
 
     #[test]
-    fn get_ui() -> Result<()> {
-        ServerTest::new()?.test_status(
-            Request::builder().uri("http://localhost/").body(())?,
-            StatusCode::OK,
-        )
-    }
-
-    #[test]
-    fn post_dataset_file() -> Result<()> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store")
-            .header(CONTENT_TYPE, "application/trig")
-            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
-        ServerTest::new()?.test_status(request, StatusCode::NO_CONTENT)
-    }
-
-    #[test]
-    fn post_wrong_file() -> Result<()> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store")
-            .header(CONTENT_TYPE, "application/trig")
-            .body("<http://example.com>")?;
-        ServerTest::new()?.test_status(request, StatusCode::BAD_REQUEST)
-    }
-
-    #[test]
-    fn post_unsupported_file() -> Result<()> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store")
-            .header(CONTENT_TYPE, "text/foo")
-            .body(())?;
-        ServerTest::new()?.test_status(request, StatusCode::UNSUPPORTED_MEDIA_TYPE)
-    }
-
-    #[test]
-    fn get_query() -> Result<()> {
-        let server = ServerTest::new()?;
-
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store")
-            .header(CONTENT_TYPE, "application/trig")
-            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        let request = Request::builder()
-            .uri(
-                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
-            )
-            .header(ACCEPT, "text/csv")
-            .body(())?;
-        server.test_body(
-            request,
-            "s,p,o\r\nhttp://example.com,http://example.com,http://example.com\r\n",
-        )
-    }
-
-    #[test]
-    fn get_query_accept_star() -> Result<()> {
-        let request = Request::builder()
-            .uri(
-                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
-            )
-            .header(ACCEPT, "*/*")
-            .body(())?;
-        ServerTest::new()?.test_body(
-            request,
-            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
-        )
-    }
-
-    #[test]
-    fn get_query_accept_substar() -> Result<()> {
-        let request = Request::builder()
-            .uri(
-                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
-            )
-            .header(ACCEPT, "text/*")
-            .body(())?;
-        ServerTest::new()?.test_body(
-            request,
-            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
-        )
-    }
-
-    #[test]
-    fn get_query_accept_good() -> Result<()> {
-        let request = Request::builder()
-            .uri(
-                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
-            )
-            .header(ACCEPT, "application/sparql-results+json;charset=utf-8")
-            .body(())?;
-        ServerTest::new()?.test_body(
-            request,
-            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
-        )
-    }
-
-    #[test]
-    fn get_query_accept_bad() -> Result<()> {
-        let request = Request::builder()
-            .uri(
-                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
-            )
-            .header(ACCEPT, "application/foo")
-            .body(())?;
-        ServerTest::new()?.test_status(request, StatusCode::NOT_ACCEPTABLE)
-    }
-
-    #[test]
-    fn get_query_accept_explicit_priority() -> Result<()> {
-        let request = Request::builder()
-            .uri(
-                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
-            )
-            .header(ACCEPT, "text/foo;q=0.5 , text/json ; q = 0.7")
-            .body(())?;
-        ServerTest::new()?.test_body(
-            request,
-            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
-        )
-    }
-
-    #[test]
-    fn get_query_accept_implicit_priority() -> Result<()> {
-        let request = Request::builder()
-            .uri(
-                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
-            )
-            .header(ACCEPT, "text/json,text/foo")
-            .body(())?;
-        ServerTest::new()?.test_body(
-            request,
-            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
-        )
-    }
-    #[test]
-    fn get_query_accept_implicit_and_explicit_priority() -> Result<()> {
-        let request = Request::builder()
-            .uri(
-                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
-            )
-            .header(ACCEPT, "text/foo;q=0.9,text/csv")
-            .body(())?;
-        ServerTest::new()?.test_body(request, "s,p,o\r\n")
-    }
-
-    #[test]
-    fn get_bad_query() -> Result<()> {
-        ServerTest::new()?.test_status(
-            Request::builder()
-                .uri("http://localhost/query?query=SELECT")
-                .body(())?,
-            StatusCode::BAD_REQUEST,
-        )
-    }
-
-    #[test]
-    fn get_query_union_graph() -> Result<()> {
-        let server = ServerTest::new()?;
-
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store/1")
-            .header(CONTENT_TYPE, "text/turtle")
-            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
-        server.test_status(request, StatusCode::CREATED)?;
-
-        let request =Request::builder().uri(
-            "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}&union-default-graph"
-        ).header(ACCEPT, "text/csv")
-            .body(())?;
-        server.test_body(
-            request,
-            "s,p,o\r\nhttp://example.com,http://example.com,http://example.com\r\n",
-        )
-    }
-
-    #[test]
-    fn get_query_union_graph_in_url_and_urlencoded() -> Result<()> {
-        let server = ServerTest::new()?;
-
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store/1")
-            .header(CONTENT_TYPE, "text/turtle")
-            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
-        server.test_status(request, StatusCode::CREATED)?;
-
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/query?union-default-graph")
-            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
-            .header(ACCEPT, "text/csv")
-            .body("query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}")?;
-        server.test_body(
-            request,
-            "s,p,o\r\nhttp://example.com,http://example.com,http://example.com\r\n",
-        )
-    }
-
-    #[test]
-    fn get_query_union_graph_and_default_graph() -> Result<()> {
-        ServerTest::new()?.test_status(Request::builder().uri(
-            "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&union-default-graph&default-graph-uri=http://example.com",
-        ).body(())?, StatusCode::BAD_REQUEST)
-    }
-
-    #[test]
-    fn get_query_description() -> Result<()> {
-        ServerTest::new()?.test_status(
-            Request::builder().uri("http://localhost/query").body(())?,
-            StatusCode::OK,
-        )
-    }
-
-    #[test]
-    fn post_query() -> Result<()> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/query")
-            .header(CONTENT_TYPE, "application/sparql-query")
-            .body("SELECT * WHERE { ?s ?p ?o }")?;
-        ServerTest::new()?.test_status(request, StatusCode::OK)
-    }
-
-    #[test]
-    fn post_bad_query() -> Result<()> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/query")
-            .header(CONTENT_TYPE, "application/sparql-query")
-            .body("SELECT")?;
-        ServerTest::new()?.test_status(request, StatusCode::BAD_REQUEST)
-    }
-
-    #[test]
-    fn post_unknown_query() -> Result<()> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/query")
-            .header(CONTENT_TYPE, "application/sparql-todo")
-            .body("SELECT")?;
-        ServerTest::new()?.test_status(request, StatusCode::UNSUPPORTED_MEDIA_TYPE)
-    }
-
-    #[test]
-    fn post_federated_query_wikidata() -> Result<()> {
-        let request = Request::builder().method(Method::POST).uri("http://localhost/query")
-            .header(CONTENT_TYPE, "application/sparql-query")
-            .body("SELECT * WHERE { SERVICE <https://query.wikidata.org/sparql> { <https://en.wikipedia.org/wiki/Paris> ?p ?o } }")?;
-        ServerTest::new()?.test_status(request, StatusCode::OK)
-    }
-
-    #[test]
-    fn post_federated_query_dbpedia() -> Result<()> {
-        let request = Request::builder().method(Method::POST).uri("http://localhost/query")
-            .header(CONTENT_TYPE, "application/sparql-query")
-            .body("SELECT * WHERE { SERVICE <https://dbpedia.org/sparql> { <http://dbpedia.org/resource/Paris> ?p ?o } }")?;
-        ServerTest::new()?.test_status(request, StatusCode::OK)
-    }
-
-    #[test]
-    fn get_update_description() -> Result<()> {
-        ServerTest::new()?.test_status(
-            Request::builder().uri("http://localhost/update").body(())?,
-            StatusCode::OK,
-        )
-    }
-
-    #[test]
-    fn post_update() -> Result<()> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/update")
-            .header(CONTENT_TYPE, "application/sparql-update")
-            .body(
-                "INSERT DATA { <http://example.com> <http://example.com> <http://example.com> }",
-            )?;
-        ServerTest::new()?.test_status(request, StatusCode::NO_CONTENT)
-    }
-
-    #[test]
-    fn post_bad_update() -> Result<()> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/update")
-            .header(CONTENT_TYPE, "application/sparql-update")
-            .body("INSERT")?;
-        ServerTest::new()?.test_status(request, StatusCode::BAD_REQUEST)
-    }
-
-    #[test]
-    fn post_update_read_only() -> Result<()> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/update")
-            .header(CONTENT_TYPE, "application/sparql-update")
-            .body(
-                "INSERT DATA { <http://example.com> <http://example.com> <http://example.com> }",
-            )?;
-        ServerTest::check_status(
-            ServerTest::new()?.exec_read_only(request),
-            StatusCode::FORBIDDEN,
-        )
-    }
-
-    #[test]
-    fn graph_store_url_normalization() -> Result<()> {
-        let server = ServerTest::new()?;
-
-        // PUT
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store?graph=http://example.com")
-            .header(CONTENT_TYPE, "text/turtle")
-            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
-        server.test_status(request, StatusCode::CREATED)?;
-
-        // GET good URI
-        server.test_status(
-            Request::builder()
-                .uri("http://localhost/store?graph=http://example.com")
-                .body(())?,
-            StatusCode::OK,
-        )?;
-
-        // GET bad URI
-        server.test_status(
-            Request::builder()
-                .uri("http://localhost/store?graph=http://example.com/")
-                .body(())?,
-            StatusCode::NOT_FOUND,
-        )
-    }
-
-    #[test]
-    fn graph_store_base_url() -> Result<()> {
-        let server = ServerTest::new()?;
-
-        // POST
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store?graph=http://example.com")
-            .header(CONTENT_TYPE, "text/turtle")
-            .body("<> <http://example.com/p> <http://example.com/o1> .")?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET
-        let request = Request::builder()
-            .uri("http://localhost/store?graph=http://example.com")
-            .header(ACCEPT, "application/n-triples")
-            .body(())?;
-        server.test_body(
-            request,
-            "<http://example.com> <http://example.com/p> <http://example.com/o1> .\n",
-        )?;
-
-        // PUT
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store?graph=http://example.com")
-            .header(CONTENT_TYPE, "text/turtle")
-            .body("<> <http://example.com/p> <http://example.com/o2> .")?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET
-        let request = Request::builder()
-            .uri("http://localhost/store?graph=http://example.com")
-            .header(ACCEPT, "application/n-triples")
-            .body(())?;
-        server.test_body(
-            request,
-            "<http://example.com> <http://example.com/p> <http://example.com/o2> .\n",
-        )
-    }
-
-    #[test]
-    fn graph_store_protocol() -> Result<()> {
-        // Tests from https://www.w3.org/2009/sparql/docs/tests/data-sparql11/http-rdf-update/
-
-        let server = ServerTest::new()?;
-
-        // PUT - Initial state
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store/person/1.ttl")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(
-                r#"
-@prefix foaf: <http://xmlns.com/foaf/0.1/> .
-@prefix v: <http://www.w3.org/2006/vcard/ns#> .
-
-<http://$HOST$/$GRAPHSTORE$/person/1> a foaf:Person;
-    foaf:businessCard [
-        a v:VCard;
-        v:fn "John Doe"
-    ].
-"#,
-            )?;
-        server.test_status(request, StatusCode::CREATED)?;
-
-        // GET of PUT - Initial state
-        let request = Request::builder()
-            .uri("http://localhost/store?graph=/store/person/1.ttl")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // HEAD on an existing graph
-        server.test_status(
-            Request::builder()
-                .method(Method::HEAD)
-                .uri("http://localhost/store/person/1.ttl")
-                .body(())?,
-            StatusCode::OK,
-        )?;
-
-        // HEAD on a non-existing graph
-        server.test_status(
-            Request::builder()
-                .method(Method::HEAD)
-                .uri("http://localhost/store/person/4.ttl")
-                .body(())?,
-            StatusCode::NOT_FOUND,
-        )?;
-
-        // PUT - graph already in store
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store/person/1.ttl")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(
-                r#"
-@prefix foaf: <http://xmlns.com/foaf/0.1/> .
-@prefix v: <http://www.w3.org/2006/vcard/ns#> .
-
-<http://$HOST$/$GRAPHSTORE$/person/1> a foaf:Person;
-    foaf:businessCard [
-        a v:VCard;
-        v:fn "Jane Doe"
-    ].
-"#,
-            )?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET of PUT - graph already in store
-        let request = Request::builder()
-            .uri("http://localhost/store/person/1.ttl")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // PUT - default graph
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store?default")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(
-                r#"
-@prefix foaf: <http://xmlns.com/foaf/0.1/> .
-@prefix v: <http://www.w3.org/2006/vcard/ns#> .
-
-[]  a foaf:Person;
-    foaf:businessCard [
-        a v:VCard;
-        v:given-name "Alice"
-    ] .
-"#,
-            )?;
-        server.test_status(request, StatusCode::NO_CONTENT)?; // The default graph always exists in Oxigraph
-
-        // GET of PUT - default graph
-        let request = Request::builder()
-            .uri("http://localhost/store?default")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // PUT - mismatched payload
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store/person/1.ttl")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body("@prefix foo")?;
-        server.test_status(request, StatusCode::BAD_REQUEST)?;
-
-        // PUT - empty graph
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store/person/2.ttl")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(())?;
-        server.test_status(request, StatusCode::CREATED)?;
-
-        // GET of PUT - empty graph
-        let request = Request::builder()
-            .uri("http://localhost/store/person/2.ttl")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // PUT - replace empty graph
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store/person/2.ttl")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(
-                r#"
-@prefix foaf: <http://xmlns.com/foaf/0.1/> .
-@prefix v: <http://www.w3.org/2006/vcard/ns#> .
-
-[]  a foaf:Person;
-    foaf:businessCard [
-        a v:VCard;
-        v:given-name "Alice"
-    ] .
-"#,
-            )?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET of replacement for empty graph
-        let request = Request::builder()
-            .uri("http://localhost/store/person/2.ttl")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // DELETE - existing graph
-        server.test_status(
-            Request::builder()
-                .method(Method::DELETE)
-                .uri("http://localhost/store/person/2.ttl")
-                .body(())?,
-            StatusCode::NO_CONTENT,
-        )?;
-
-        // GET of DELETE - existing graph
-        server.test_status(
-            Request::builder()
-                .uri("http://localhost/store/person/2.ttl")
-                .body(())?,
-            StatusCode::NOT_FOUND,
-        )?;
-
-        // DELETE - non-existent graph
-        server.test_status(
-            Request::builder()
-                .method(Method::DELETE)
-                .uri("http://localhost/store/person/2.ttl")
-                .body(())?,
-            StatusCode::NOT_FOUND,
-        )?;
-
-        // POST - existing graph
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store/person/1.ttl")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(())?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // TODO: POST - multipart/form-data
-        // TODO: GET of POST - multipart/form-data
-
-        // POST - create new graph
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(
-                r#"
-@prefix foaf: <http://xmlns.com/foaf/0.1/> .
-@prefix v: <http://www.w3.org/2006/vcard/ns#> .
-
-[]  a foaf:Person;
-    foaf:businessCard [
-        a v:VCard;
-        v:given-name "Alice"
-    ] .
-"#,
-            )?;
-        let response = server.exec(request);
-        assert_eq!(response.status(), StatusCode::CREATED);
-        let location = response.headers().get(LOCATION).unwrap().to_str()?;
-
-        // GET of POST - create new graph
-        let request = Request::builder()
-            .uri(location)
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // POST - empty graph to existing graph
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri(location)
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(())?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET of POST - after noop
-        let request = Request::builder()
-            .uri(location)
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)
-    }
-
-    #[test]
-    fn graph_store_lenient_bulk() -> Result<()> {
-        let server = ServerTest::new()?;
-        let invalid_data = "
-@prefix foaf: <http://xmlns.com/foaf/0.1/> .
-@prefix v: <http://www.w3.org/2006/vcard/ns#> .
-
-<http://$HOST$/$GRAPHSTORE$/person/1> a foaf:Person . foo";
-
-        // POST
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store/person/1.ttl?no_transaction&lenient")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(invalid_data)?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET of POST
-        let request = Request::builder()
-            .uri("http://localhost/store?graph=/store/person/1.ttl")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // POST dataset
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store?lenient&no_transaction")
-            .header(CONTENT_TYPE, "application/trig; charset=utf-8")
-            .body(invalid_data)?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET of POST dataset
-        let request = Request::builder()
-            .uri("http://localhost/store?default")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // PUT
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store/person/1.ttl?lenient&no_transaction")
-            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
-            .body(invalid_data)?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET of PUT - Initial state
-        let request = Request::builder()
-            .uri("http://localhost/store?graph=/store/person/1.ttl")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // PUT dataset
-        let request = Request::builder()
-            .method(Method::PUT)
-            .uri("http://localhost/store?lenient&no_transaction")
-            .header(CONTENT_TYPE, "application/trig; charset=utf-8")
-            .body(invalid_data)?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET of PUT dataset
-        let request = Request::builder()
-            .uri("http://localhost/store?default")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::OK)?;
-
-        // GET of PUT dataset - replacement
-        let request = Request::builder()
-            .uri("http://localhost/store?graph=/store/person/1.ttl")
-            .header(ACCEPT, "text/turtle")
-            .body(())?;
-        server.test_status(request, StatusCode::NOT_FOUND)
+    fn cli_serve_invalid_durability() -> Result<()> {
+        let store_dir = TempDir::new()?;
+        cli_command()
+            .arg("serve")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--durability")
+            .arg("interval(0)")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("at least 1ms"));
+        Ok(())
     }
 
     #[test]
-    fn lenient_load() -> Result<()> {
-        let server = ServerTest::new()?;
-
-        // POST
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store?lenient&graph=http://example.com")
-            .header(CONTENT_TYPE, "text/turtle")
-            .body("< s> < p> \"\\uD83D\\uDC68\" .")?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET
-        let request = Request::builder()
-            .uri("http://localhost/store?graph=http://example.com")
-            .header(ACCEPT, "application/n-triples")
-            .body(())?;
-        server.test_body(
-            request,
-            "<http://example.com/ s> <http://example.com/ p> \"\u{1f468}\" .\n",
-        )?;
-
-        // PUT
-        let request = Request::builder().method(Method::PUT).uri(
-            "http://localhost/store?lenient&graph=http://example.com",
-        )
-        .header(CONTENT_TYPE, "text/turtle")
-        .body("< s> < p> \"\\uD83D\\uDC68\\u200D\\uD83D\\uDC69\\u200D\\uD83D\\uDC67\\u200D\\uD83D\\uDC67\" .")?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET
-        let request = Request::builder()
-            .uri("http://localhost/store?graph=http://example.com")
-            .header(ACCEPT, "application/n-triples")
-            .body(())?;
-        server.test_body(
-            request,
-            "<http://example.com/ s> <http://example.com/ p> \"\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f467}\" .\n",
-        )?;
-
-        // POST dataset
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri("http://localhost/store?lenient")
-            .header(CONTENT_TYPE, "application/trig")
-            .body("<s> <p> \"\"@abcdefghijklmn .")?;
-        server.test_status(request, StatusCode::NO_CONTENT)?;
-
-        // GET
-        let request = Request::builder()
-            .uri("http://localhost/store")
-            .header(ACCEPT, "application/n-quads")
-            .body(())?;
-        server.test_body(request, "<s> <p> \"\"@abcdefghijklmn .\n<http://example.com/ s> <http://example.com/ p> \"\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f467}\" <http://example.com> .\n")
-    }
-
-    struct ServerTest {
-        store: Store,
-    }
-
-    impl ServerTest {
-        fn new() -> Result<Self> {
-            Ok(Self {
-                store: Store::new()?,
-            })
-        }
-
-        fn exec(&self, request: Request<impl Into<Body>>) -> Response<Body> {
-            handle_request(
-                &mut request.map(Into::into),
-                self.store.clone(),
-                false,
-                false,
-            )
-            .unwrap_or_else(|(status, message)| error(status, message))
-        }
-
-        fn exec_read_only(&self, request: Request<impl Into<Body>>) -> Response<Body> {
-            handle_request(
-                &mut request.map(Into::into),
-                self.store.clone(),
-                true,
-                false,
-            )
-            .unwrap_or_else(|(status, message)| error(status, message))
-        }
-
-        fn test_status(
-            &self,
-            request: Request<impl Into<Body>>,
-            expected_status: StatusCode,
-        ) -> Result<()> {
-            Self::check_status(self.exec(request), expected_status)
-        }
-
-        fn check_status(mut response: Response<Body>, expected_status: StatusCode) -> Result<()> {
-            let body = read_to_string(response.body_mut())?;
-            assert_eq!(response.status(), expected_status, "Error message: {body}");
-            Ok(())
-        }
-
-        fn test_body(&self, request: Request<impl Into<Body>>, expected_body: &str) -> Result<()> {
-            let mut response = self.exec(request);
-            let body = read_to_string(response.body_mut())?;
-            assert_eq!(response.status(), StatusCode::OK, "Error message: {body}");
-            assert_eq!(&body, expected_body);
-            Ok(())
-        }
+    fn cli_serve_invalid_storage_profile() -> Result<()> {
+        let store_dir = TempDir::new()?;
+        cli_command()
+            .arg("serve")
+            .arg("--location")
+            .arg(store_dir.path())
+            .arg("--storage-profile")
+            .arg("tiny")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("'default' or 'small'"));
+        Ok(())
     }
 
     #[test]
@@ -0,0 +1,110 @@
+//! Maintenance mode of the server, draining the writes before backups, migrations or reindexing.
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Read-only maintenance mode of a [`SparqlServer`](crate::SparqlServer).
+///
+/// When enabled, the requests writing to the dataset (SPARQL updates and Graph Store Protocol writes)
+/// are rejected with a `503 Service Unavailable` status and [`enable`](Self::enable) waits for the running writes to finish,
+/// so backups, migrations or reindexing can then run safely on the store.
+/// The queries are still evaluated.
+///
+/// ```
+/// use oxigraph::store::Store;
+/// use oxigraph_server::SparqlServer;
+///
+/// let server = SparqlServer::new(Store::new()?);
+/// server.maintenance_mode().enable();
+/// // Backup, migration...
+/// server.maintenance_mode().disable();
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Clone, Default)]
+pub struct MaintenanceMode {
+    state: Arc<(Mutex<WriteState>, Condvar)>,
+}
+
+#[derive(Default)]
+struct WriteState {
+    enabled: bool,
+    running_writes: usize,
+}
+
+impl MaintenanceMode {
+    /// Enables the maintenance mode and waits for the running writes to finish.
+    pub fn enable(&self) {
+        let (state, writes_finished) = &*self.state;
+        let mut state = lock(state);
+        state.enabled = true;
+        while state.running_writes > 0 {
+            state = writes_finished
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Enables the maintenance mode and waits at most `timeout` for the running writes to finish.
+    ///
+    /// Returns `false` if some writes are still running after the timeout. The maintenance mode stays enabled.
+    pub fn enable_with_timeout(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let (state, writes_finished) = &*self.state;
+        let mut state = lock(state);
+        state.enabled = true;
+        while state.running_writes > 0 {
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                return false;
+            };
+            state = writes_finished
+                .wait_timeout(state, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        true
+    }
+
+    /// Disables the maintenance mode, writes are accepted again.
+    pub fn disable(&self) {
+        lock(&self.state.0).enabled = false;
+    }
+
+    /// If the maintenance mode is enabled.
+    pub fn is_enabled(&self) -> bool {
+        lock(&self.state.0).enabled
+    }
+
+    /// The number of running writes.
+    ///
+    /// It might not be zero just after the maintenance mode is enabled with [`enable_with_timeout`](Self::enable_with_timeout).
+    pub fn running_writes(&self) -> usize {
+        lock(&self.state.0).running_writes
+    }
+
+    /// Registers a write until the returned guard is dropped, returns `None` if the maintenance mode is enabled
+    pub(crate) fn start_write(&self) -> Option<RunningWrite> {
+        let mut state = lock(&self.state.0);
+        if state.enabled {
+            return None;
+        }
+        state.running_writes += 1;
+        Some(RunningWrite { mode: self.clone() })
+    }
+}
+
+/// A write registered with [`MaintenanceMode::start_write`]
+pub(crate) struct RunningWrite {
+    mode: MaintenanceMode,
+}
+
+impl Drop for RunningWrite {
+    fn drop(&mut self) {
+        let (state, writes_finished) = &*self.mode.state;
+        lock(state).running_writes -= 1;
+        writes_finished.notify_all();
+    }
+}
+
+fn lock(state: &Mutex<WriteState>) -> MutexGuard<'_, WriteState> {
+    // The state is always consistent, we can ignore poisoning
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
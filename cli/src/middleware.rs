@@ -0,0 +1,128 @@
+//! Hooks to customize how the HTTP server handles requests without changing the handlers.
+//!
+//! The middlewares are called in order before the request is handled and in reverse order on the response.
+use crate::server::HttpError;
+use oxhttp::model::header::{
+    ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_REQUEST_HEADERS,
+    ACCESS_CONTROL_REQUEST_METHOD, ORIGIN, VARY,
+};
+use oxhttp::model::{Body, HeaderValue, Method, Request, Response, StatusCode};
+use oxigraph::sparql::{Query, Update};
+
+/// A SPARQL operation parsed by the server, given to [`Middleware::on_operation`]
+pub enum Operation<'a> {
+    /// A query sent to `/query` or built from a SQL query sent to `/sql`
+    Query(&'a Query),
+    /// An update sent to `/update`
+    Update(&'a Update),
+}
+
+/// Pre and post request hooks of the HTTP server
+///
+/// All the methods do nothing by default.
+pub trait Middleware: Send + Sync {
+    /// Called before the request is handled.
+    ///
+    /// If a response is returned, the request is not handled and the response is sent instead.
+    fn on_request(&self, _request: &mut Request<Body>) -> Option<Response<Body>> {
+        None
+    }
+
+    /// Called when a SPARQL operation has been parsed, before its evaluation.
+    ///
+    /// If an error is returned, the operation is not evaluated and the error is sent instead.
+    fn on_operation(
+        &self,
+        _request: &Request<Body>,
+        _operation: Operation<'_>,
+    ) -> Result<(), HttpError> {
+        Ok(())
+    }
+
+    /// Called on every response before it is sent, including error responses.
+    fn on_response(&self, _request: &Request<Body>, _response: &mut Response<Body>) {}
+}
+
+/// Allows cross-origin requests, from any origin by default
+///
+/// It answers the `OPTIONS` preflight requests and sets the `Access-Control-*` headers on the responses.
+#[derive(Default)]
+pub struct CorsMiddleware {
+    /// The allowed origins, all of them if `None`
+    allowed_origins: Option<Vec<String>>,
+}
+
+impl CorsMiddleware {
+    /// Allows cross-origin requests from any origin.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allows cross-origin requests from the given origin like `https://example.com`.
+    ///
+    /// Can be called multiple times to allow several origins.
+    /// Unlike with any origin allowed, the requests can then include credentials like the `Authorization` header.
+    #[inline]
+    #[must_use]
+    pub fn with_allowed_origin(mut self, origin: impl Into<String>) -> Self {
+        self.allowed_origins
+            .get_or_insert_with(Vec::new)
+            .push(origin.into());
+        self
+    }
+
+    /// The `Access-Control-Allow-Origin` value for the request, if it is a cross-origin request from an allowed origin
+    fn allowed_origin(&self, request: &Request<Body>) -> Option<HeaderValue> {
+        let origin = request.headers().get(ORIGIN)?;
+        let Some(allowed_origins) = &self.allowed_origins else {
+            return Some(HeaderValue::from_static("*"));
+        };
+        allowed_origins
+            .iter()
+            .any(|allowed| allowed.as_bytes() == origin.as_bytes())
+            .then(|| origin.clone())
+    }
+}
+
+impl Middleware for CorsMiddleware {
+    fn on_request(&self, request: &mut Request<Body>) -> Option<Response<Body>> {
+        if *request.method() != Method::OPTIONS {
+            return None;
+        }
+        let mut response = Response::builder().status(StatusCode::NO_CONTENT);
+        if self.allowed_origins.is_none() || self.allowed_origin(request).is_some() {
+            let request_headers = request.headers();
+            if let Some(method) = request_headers.get(ACCESS_CONTROL_REQUEST_METHOD) {
+                response = response.header(ACCESS_CONTROL_ALLOW_METHODS, method.clone());
+            }
+            if let Some(headers) = request_headers.get(ACCESS_CONTROL_REQUEST_HEADERS) {
+                response = response.header(ACCESS_CONTROL_ALLOW_HEADERS, headers.clone());
+            }
+        }
+        Some(response.body(Body::empty()).unwrap())
+    }
+
+    fn on_response(&self, request: &Request<Body>, response: &mut Response<Body>) {
+        let headers = response.headers_mut();
+        if self.allowed_origins.is_some() {
+            // The response depends on the origin
+            headers.append(VARY, HeaderValue::from_static("Origin"));
+        }
+        let Some(allowed_origin) = self.allowed_origin(request) else {
+            return;
+        };
+        headers.append(ACCESS_CONTROL_ALLOW_ORIGIN, allowed_origin);
+        headers.append(
+            ACCESS_CONTROL_EXPOSE_HEADERS,
+            HeaderValue::from_static("ETag, Location"),
+        );
+        if self.allowed_origins.is_some() {
+            headers.append(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+    }
+}
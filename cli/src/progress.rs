@@ -0,0 +1,75 @@
+//! Progress of the running queries, streamed to the web UI as [server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+//!
+//! A client sets a `progress-id` parameter on its `/query` request and listens to `/query/progress?id={progress-id}`.
+//! The `progress` events have a JSON payload like `{"results":12,"lookups":3,"readQuads":1024,"elapsed":0.53}`
+//! and the stream is closed with an `end` event when all the results have been returned.
+use oxigraph::sparql::QueryProgress;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// The progress of the running queries indexed by the id given by the client
+#[derive(Clone, Default)]
+pub struct QueryProgressRegistry {
+    progresses: Arc<Mutex<HashMap<String, QueryProgress>>>,
+}
+
+impl QueryProgressRegistry {
+    /// Returns the progress for the given id, creating it if the query or the listener is not there yet
+    pub fn register(&self, id: &str) -> ProgressRegistration {
+        let progress = self
+            .progresses
+            .lock()
+            .unwrap()
+            .entry(id.into())
+            .or_default()
+            .clone();
+        ProgressRegistration {
+            registry: self.clone(),
+            id: id.into(),
+            progress,
+        }
+    }
+}
+
+/// A query or a listener using a [`QueryProgress`] of the registry. It is removed from the registry on drop.
+pub struct ProgressRegistration {
+    registry: QueryProgressRegistry,
+    id: String,
+    progress: QueryProgress,
+}
+
+impl ProgressRegistration {
+    pub fn progress(&self) -> &QueryProgress {
+        &self.progress
+    }
+
+    /// Writes the current progress as a server-sent event, followed by an `end` event if the query is finished
+    ///
+    /// Returns if the query is finished.
+    pub fn write_event(&self, mut writer: impl Write) -> io::Result<bool> {
+        let is_finished = self.progress.is_finished();
+        writeln!(writer, "event: progress")?;
+        writeln!(
+            writer,
+            "data: {{\"results\":{},\"lookups\":{},\"readQuads\":{},\"elapsed\":{}}}",
+            self.progress.result_count(),
+            self.progress.scan_count(),
+            self.progress.read_quad_count(),
+            self.progress.elapsed().unwrap_or_default().as_secs_f64()
+        )?;
+        writeln!(writer)?;
+        if is_finished {
+            writeln!(writer, "event: end")?;
+            writeln!(writer, "data:")?;
+            writeln!(writer)?;
+        }
+        Ok(is_finished)
+    }
+}
+
+impl Drop for ProgressRegistration {
+    fn drop(&mut self) {
+        self.registry.progresses.lock().unwrap().remove(&self.id);
+    }
+}
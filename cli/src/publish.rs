@@ -0,0 +1,150 @@
+//! Reproducible release bundles of a store content.
+//!
+//! The bundle is a directory with:
+//! * `default.nq` with the default graph and `graphs/{hash}.nq` for each named graph, `{hash}` being the SHA-256 of the graph name in N-Triples syntax.
+//!   The files are in N-Quads with blank nodes canonicalized and lines sorted, so publishing the same data twice gives the same files.
+//! * `void.ttl` with a [VoID](https://www.w3.org/TR/void/) description of the dataset and of each graph.
+//! * `SHA256SUMS` with the SHA-256 checksums of all the other files, in the format of `sha256sum --check`.
+use anyhow::{Context, ensure};
+use oxigraph::model::dataset::CanonicalizationAlgorithm;
+use oxigraph::model::vocab::rdf;
+use oxigraph::model::{Dataset, GraphNameRef, NamedNodeRef, NamedOrBlankNodeRef, TermRef};
+use oxigraph::store::Store;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::Path;
+
+const DEFAULT_GRAPH_FILE: &str = "default.nq";
+const NAMED_GRAPHS_DIRECTORY: &str = "graphs";
+const VOID_FILE: &str = "void.ttl";
+const CHECKSUMS_FILE: &str = "SHA256SUMS";
+
+#[derive(Default)]
+struct GraphDump<'a> {
+    lines: Vec<String>,
+    subjects: HashSet<NamedOrBlankNodeRef<'a>>,
+    predicates: HashSet<NamedNodeRef<'a>>,
+    objects: HashSet<TermRef<'a>>,
+    classes: HashSet<TermRef<'a>>,
+}
+
+/// Writes the release bundle of the store into the `out` directory that must be empty or not exist yet
+///
+/// The full store is loaded in memory to canonicalize the blank nodes.
+pub fn publish(store: &Store, out: &Path) -> anyhow::Result<()> {
+    if out.exists() {
+        ensure!(
+            fs::read_dir(out)
+                .with_context(|| format!("Not able to read directory {}", out.display()))?
+                .next()
+                .is_none(),
+            "The output directory {} must be empty",
+            out.display()
+        );
+    }
+    fs::create_dir_all(out.join(NAMED_GRAPHS_DIRECTORY))
+        .with_context(|| format!("Not able to create directory {}", out.display()))?;
+
+    let mut dataset = store.iter().collect::<Result<Dataset, _>>()?;
+    dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
+    let mut graphs = HashMap::<GraphNameRef<'_>, GraphDump<'_>>::new();
+    graphs.entry(GraphNameRef::DefaultGraph).or_default();
+    for quad in &dataset {
+        let graph = graphs.entry(quad.graph_name).or_default();
+        graph.lines.push(format!("{quad} .\n"));
+        graph.subjects.insert(quad.subject);
+        graph.predicates.insert(quad.predicate);
+        graph.objects.insert(quad.object);
+        if quad.predicate == rdf::TYPE {
+            graph.classes.insert(quad.object);
+        }
+    }
+    let mut graphs = graphs
+        .into_iter()
+        .map(|(graph_name, graph)| {
+            let file = match graph_name {
+                GraphNameRef::DefaultGraph => DEFAULT_GRAPH_FILE.to_owned(),
+                _ => format!(
+                    "{NAMED_GRAPHS_DIRECTORY}/{}.nq",
+                    hex::encode(Sha256::digest(graph_name.to_string()))
+                ),
+            };
+            (file, graph_name, graph)
+        })
+        .collect::<Vec<_>>();
+    graphs.sort_unstable_by(|(file1, _, _), (file2, _, _)| file1.cmp(file2));
+
+    let mut checksums = Vec::new();
+    for (file, _, graph) in &mut graphs {
+        graph.lines.sort_unstable();
+        let content = graph.lines.concat();
+        fs::write(out.join(&*file), &content)
+            .with_context(|| format!("Not able to write {file}"))?;
+        checksums.push((file.clone(), Sha256::digest(&content)));
+    }
+    let void = void_description(&graphs)?;
+    fs::write(out.join(VOID_FILE), &void)
+        .with_context(|| format!("Not able to write {VOID_FILE}"))?;
+    checksums.push((VOID_FILE.to_owned(), Sha256::digest(&void)));
+    checksums.sort_unstable_by(|(file1, _), (file2, _)| file1.cmp(file2));
+    let mut manifest = String::new();
+    for (file, checksum) in checksums {
+        writeln!(manifest, "{}  {file}", hex::encode(checksum))?;
+    }
+    fs::write(out.join(CHECKSUMS_FILE), manifest)
+        .with_context(|| format!("Not able to write {CHECKSUMS_FILE}"))?;
+    Ok(())
+}
+
+/// Builds the VoID description in Turtle with IRIs relative to the bundle directory
+fn void_description(
+    graphs: &[(String, GraphNameRef<'_>, GraphDump<'_>)],
+) -> Result<String, fmt::Error> {
+    let mut void = String::new();
+    writeln!(
+        void,
+        "@prefix sd: <http://www.w3.org/ns/sparql-service-description#> ."
+    )?;
+    writeln!(void, "@prefix void: <http://rdfs.org/ns/void#> .")?;
+    writeln!(void)?;
+    writeln!(void, "<> a void:Dataset ;")?;
+    writeln!(
+        void,
+        "    void:triples {} ;",
+        graphs
+            .iter()
+            .map(|(_, _, graph)| graph.lines.len())
+            .sum::<usize>()
+    )?;
+    writeln!(
+        void,
+        "    void:subset {} .",
+        graphs
+            .iter()
+            .map(|(file, _, _)| format!("<#{}>", subset_id(file)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )?;
+    for (file, graph_name, graph) in graphs {
+        writeln!(void)?;
+        writeln!(void, "<#{}> a void:Dataset ;", subset_id(file))?;
+        if !graph_name.is_default_graph() {
+            writeln!(void, "    sd:name {graph_name} ;")?;
+        }
+        writeln!(void, "    void:dataDump <{file}> ;")?;
+        writeln!(void, "    void:triples {} ;", graph.lines.len())?;
+        writeln!(void, "    void:distinctSubjects {} ;", graph.subjects.len())?;
+        writeln!(void, "    void:properties {} ;", graph.predicates.len())?;
+        writeln!(void, "    void:distinctObjects {} ;", graph.objects.len())?;
+        writeln!(void, "    void:classes {} .", graph.classes.len())?;
+    }
+    Ok(void)
+}
+
+fn subset_id(file: &str) -> &str {
+    file.trim_start_matches(NAMED_GRAPHS_DIRECTORY)
+        .trim_start_matches('/')
+        .trim_end_matches(".nq")
+}
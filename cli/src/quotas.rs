@@ -0,0 +1,57 @@
+//! Usage of the [graph quotas](oxigraph::store::Store::with_graph_quota) of the store.
+//!
+//! It is a JSON object with, for each graph with a quota sorted by name, its limits (`null` if not limited) and its current size:
+//! ```json
+//! {
+//!   "graphs": [
+//!     { "graph": "http://example.com/g", "maxQuads": 1000, "maxBytes": null, "quads": 12, "bytes": 1024 }
+//!   ]
+//! }
+//! ```
+use json_event_parser::{JsonEvent, WriterJsonSerializer};
+use oxigraph::model::NamedOrBlankNode;
+use oxigraph::store::Store;
+use std::io;
+
+pub fn generate_graph_quotas(store: &Store) -> io::Result<Vec<u8>> {
+    let mut quotas = store
+        .graph_quotas()
+        .map(|(graph_name, quota)| {
+            let usage = store.graph_usage(graph_name);
+            let graph_name = match graph_name {
+                NamedOrBlankNode::NamedNode(graph_name) => graph_name.as_str().to_owned(),
+                NamedOrBlankNode::BlankNode(graph_name) => graph_name.to_string(),
+            };
+            (graph_name, quota, usage)
+        })
+        .collect::<Vec<_>>();
+    quotas.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+
+    let mut output = WriterJsonSerializer::new(Vec::new());
+    output.serialize_event(JsonEvent::StartObject)?;
+    output.serialize_event(JsonEvent::ObjectKey("graphs".into()))?;
+    output.serialize_event(JsonEvent::StartArray)?;
+    for (graph_name, quota, usage) in quotas {
+        let usage = usage?;
+        output.serialize_event(JsonEvent::StartObject)?;
+        output.serialize_event(JsonEvent::ObjectKey("graph".into()))?;
+        output.serialize_event(JsonEvent::String(graph_name.into()))?;
+        for (key, value) in [
+            ("maxQuads", quota.max_quads()),
+            ("maxBytes", quota.max_bytes()),
+            ("quads", Some(usage.quads())),
+            ("bytes", Some(usage.bytes())),
+        ] {
+            output.serialize_event(JsonEvent::ObjectKey(key.into()))?;
+            output.serialize_event(if let Some(value) = value {
+                JsonEvent::Number(value.to_string().into())
+            } else {
+                JsonEvent::Null
+            })?;
+        }
+        output.serialize_event(JsonEvent::EndObject)?;
+    }
+    output.serialize_event(JsonEvent::EndArray)?;
+    output.serialize_event(JsonEvent::EndObject)?;
+    output.finish()
+}
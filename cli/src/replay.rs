@@ -0,0 +1,257 @@
+//! Replay of SPARQL query logs.
+//!
+//! The query log is a [JSON Lines](https://jsonlines.org/) file: each line is either a JSON string with the query
+//! or a JSON object with the query in its `query` key, like `{"query": "SELECT * WHERE { ?s ?p ?o }"}`.
+//!
+//! The replay report is a TSV file with, for each query, its line number in the log, its evaluation duration
+//! and a hash of its results computed with [`QueryResults::digest`](oxigraph::sparql::QueryResults::digest).
+//! The hash does not depend on the results order or on the blank node identifiers
+//! so the reports generated by two versions of Oxigraph can be compared.
+use crate::csvw::JsonValue;
+use anyhow::{Context, bail};
+use oxigraph::sparql::{Query, QueryOptions};
+use oxigraph::store::Store;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Value of the hash column of the queries that failed
+const ERROR_HASH: &str = "error";
+
+/// A query of the log
+pub struct LoggedQuery {
+    line: usize,
+    query: String,
+}
+
+/// The replay of a query
+pub struct ReplayedQuery {
+    line: usize,
+    duration: Duration,
+    hash: String,
+}
+
+/// Reads a JSON Lines query log
+pub fn read_query_log(reader: impl BufRead) -> anyhow::Result<Vec<LoggedQuery>> {
+    let mut queries = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let query = match JsonValue::parse(line.as_bytes())
+            .with_context(|| format!("Invalid JSON on line {line_number} of the query log"))?
+        {
+            JsonValue::String(query) => query,
+            JsonValue::Object(entries) => entries
+                .into_iter()
+                .find_map(|(key, value)| match (key.as_str(), value) {
+                    ("query", JsonValue::String(query)) => Some(query),
+                    _ => None,
+                })
+                .with_context(|| {
+                    format!("No \"query\" string on line {line_number} of the query log")
+                })?,
+            _ => {
+                bail!("Line {line_number} of the query log must be a JSON string or a JSON object")
+            }
+        };
+        queries.push(LoggedQuery {
+            line: line_number,
+            query,
+        });
+    }
+    Ok(queries)
+}
+
+/// Executes the queries using `concurrency` threads and returns their replay ordered by line
+pub fn replay_queries(
+    store: &Store,
+    queries: &[LoggedQuery],
+    options: &QueryOptions,
+    union_default_graph: bool,
+    concurrency: usize,
+) -> Vec<ReplayedQuery> {
+    let next = AtomicUsize::new(0);
+    let mut replayed = thread::scope(|scope| {
+        let workers = (0..concurrency.max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut replayed = Vec::new();
+                    while let Some(query) = queries.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        replayed.push(replay_query(store, query, options, union_default_graph));
+                    }
+                    replayed
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect::<Vec<_>>()
+    });
+    replayed.sort_unstable_by_key(|query| query.line);
+    replayed
+}
+
+fn replay_query(
+    store: &Store,
+    query: &LoggedQuery,
+    options: &QueryOptions,
+    union_default_graph: bool,
+) -> ReplayedQuery {
+    let start = Instant::now();
+    let hash = (|| {
+        let mut parsed = Query::parse(&query.query, None)?;
+        if union_default_graph {
+            parsed.dataset_mut().set_default_graph_as_union();
+        }
+        anyhow::Ok(
+            store
+                .query_opt(parsed, options.clone())?
+                .digest()?
+                .to_string(),
+        )
+    })();
+    let duration = start.elapsed();
+    ReplayedQuery {
+        line: query.line,
+        duration,
+        hash: hash.unwrap_or_else(|e| {
+            eprintln!("The query on line {} failed: {e}", query.line);
+            ERROR_HASH.into()
+        }),
+    }
+}
+
+/// Writes the replay report as TSV
+pub fn write_report(output: &mut impl Write, replayed: &[ReplayedQuery]) -> anyhow::Result<()> {
+    writeln!(output, "line\tduration_ms\thash")?;
+    for query in replayed {
+        writeln!(
+            output,
+            "{}\t{:.3}\t{}",
+            query.line,
+            query.duration.as_secs_f64() * 1000.,
+            query.hash
+        )?;
+    }
+    Ok(())
+}
+
+/// Reads a report written by [`write_report`]
+pub fn read_report(reader: impl BufRead) -> anyhow::Result<Vec<ReplayedQuery>> {
+    let mut replayed = Vec::new();
+    for (i, line) in reader.lines().enumerate().skip(1) {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut columns = line.split('\t');
+        let (Some(query_line), Some(duration), Some(hash)) =
+            (columns.next(), columns.next(), columns.next())
+        else {
+            bail!("Line {} of the report must have 3 columns", i + 1);
+        };
+        replayed.push(ReplayedQuery {
+            line: query_line
+                .parse()
+                .with_context(|| format!("Invalid line number on line {} of the report", i + 1))?,
+            duration: Duration::try_from_secs_f64(
+                duration
+                    .parse::<f64>()
+                    .with_context(|| format!("Invalid duration on line {} of the report", i + 1))?
+                    / 1000.,
+            )
+            .with_context(|| format!("Invalid duration on line {} of the report", i + 1))?,
+            hash: hash.into(),
+        });
+    }
+    Ok(replayed)
+}
+
+/// Prints a summary of the replay to stderr and returns the number of queries with results different from the baseline
+pub fn print_summary(replayed: &[ReplayedQuery], baseline: Option<&[ReplayedQuery]>) -> usize {
+    let errors = replayed.iter().filter(|q| q.hash == ERROR_HASH).count();
+    let total = replayed.iter().map(|q| q.duration).sum::<Duration>();
+    eprintln!(
+        "{} queries replayed in {:.3}s with {errors} errors",
+        replayed.len(),
+        total.as_secs_f64()
+    );
+    let Some(baseline) = baseline else {
+        eprintln!(
+            "Latency: median {}, 95th percentile {}",
+            format_duration(percentile(replayed.iter().map(|q| q.duration), 50)),
+            format_duration(percentile(replayed.iter().map(|q| q.duration), 95))
+        );
+        return 0;
+    };
+    let baseline = baseline
+        .iter()
+        .map(|q| (q.line, q))
+        .collect::<HashMap<_, _>>();
+    let compared = replayed
+        .iter()
+        .filter_map(|q| Some((q, *baseline.get(&q.line)?)))
+        .collect::<Vec<_>>();
+    if compared.len() < replayed.len() {
+        eprintln!(
+            "{} queries are not in the baseline",
+            replayed.len() - compared.len()
+        );
+    }
+    eprintln!(
+        "Latency: median {} (baseline {}), 95th percentile {} (baseline {})",
+        format_duration(percentile(compared.iter().map(|(q, _)| q.duration), 50)),
+        format_duration(percentile(compared.iter().map(|(_, b)| b.duration), 50)),
+        format_duration(percentile(compared.iter().map(|(q, _)| q.duration), 95)),
+        format_duration(percentile(compared.iter().map(|(_, b)| b.duration), 95)),
+    );
+    let slower = compared
+        .iter()
+        .filter(|(q, b)| q.duration > 2 * b.duration)
+        .map(|(q, _)| q.line.to_string())
+        .collect::<Vec<_>>();
+    if !slower.is_empty() {
+        eprintln!(
+            "{} queries are more than twice slower than in the baseline, on lines: {}",
+            slower.len(),
+            slower.join(", ")
+        );
+    }
+    let different = compared
+        .iter()
+        .filter(|(q, b)| q.hash != b.hash)
+        .map(|(q, _)| q.line.to_string())
+        .collect::<Vec<_>>();
+    if different.is_empty() {
+        eprintln!("All the results are the same as in the baseline");
+    } else {
+        eprintln!(
+            "{} queries have results different from the baseline, on lines: {}",
+            different.len(),
+            different.join(", ")
+        );
+    }
+    different.len()
+}
+
+/// Nearest-rank percentile
+fn percentile(durations: impl Iterator<Item = Duration>, percent: usize) -> Duration {
+    let mut durations = durations.collect::<Vec<_>>();
+    if durations.is_empty() {
+        return Duration::ZERO;
+    }
+    durations.sort_unstable();
+    let rank = (durations.len() * percent).div_ceil(100).max(1);
+    durations[rank.min(durations.len()) - 1]
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.)
+}
@@ -0,0 +1,213 @@
+use crate::audit::Principal;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+const QUERY_FILE_EXTENSION: &str = "rq";
+/// Name of the directory of the queries saved without a [`Principal`], the directories of the principals start with `~`
+const ANONYMOUS_OWNER_DIRECTORY: &str = "@anonymous";
+
+/// The principal that saved the queries, `None` for anonymous requests, and the dataset they are saved for
+type Scope = (Option<String>, String);
+
+/// Named SPARQL queries saved by the users of the server.
+///
+/// The queries are scoped by the [`Principal`] that saved them and the dataset they were saved for:
+/// users only see the queries they saved for the dataset they are querying.
+/// The queries saved by the requests without a principal are shared by all these requests.
+///
+/// If a directory is given, each query is persisted in it as a `<dataset>/~<principal>/<name>.rq` file,
+/// or a `<dataset>/@anonymous/<name>.rq` file if saved without a principal.
+pub struct SavedQueries {
+    directory: Option<PathBuf>,
+    queries: RwLock<BTreeMap<Scope, BTreeMap<String, String>>>,
+}
+
+impl SavedQueries {
+    /// Saved queries only kept in memory.
+    pub fn in_memory() -> Self {
+        Self {
+            directory: None,
+            queries: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Saved queries persisted in the given directory.
+    ///
+    /// The directory is only created when the first query is saved.
+    pub fn open(directory: PathBuf) -> io::Result<Self> {
+        let mut queries = BTreeMap::<Scope, BTreeMap<String, String>>::new();
+        if directory.is_dir() {
+            for dataset_entry in fs::read_dir(&directory)? {
+                let dataset_path = dataset_entry?.path();
+                let Some(dataset) = valid_directory_name(&dataset_path) else {
+                    continue;
+                };
+                for owner_entry in fs::read_dir(&dataset_path)? {
+                    let owner_path = owner_entry?.path();
+                    let Some(owner) = owner_path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .and_then(decode_owner)
+                    else {
+                        continue;
+                    };
+                    if !owner_path.is_dir() {
+                        continue;
+                    }
+                    for entry in fs::read_dir(&owner_path)? {
+                        let path = entry?.path();
+                        if path.extension().is_some_and(|e| e == QUERY_FILE_EXTENSION) {
+                            if let Some(name) = path.file_stem().and_then(|n| n.to_str()) {
+                                if is_valid_name(name) {
+                                    queries
+                                        .entry((owner.clone(), dataset.to_owned()))
+                                        .or_default()
+                                        .insert(name.to_owned(), fs::read_to_string(&path)?);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            directory: Some(directory),
+            queries: RwLock::new(queries),
+        })
+    }
+
+    /// The queries saved by `owner` for `dataset` as (name, query) pairs ordered by name.
+    pub fn list(&self, owner: Option<&Principal>, dataset: &str) -> Vec<(String, String)> {
+        self.queries
+            .read()
+            .unwrap()
+            .get(&scope(owner, dataset))
+            .into_iter()
+            .flatten()
+            .map(|(name, query)| (name.clone(), query.clone()))
+            .collect()
+    }
+
+    pub fn get(&self, owner: Option<&Principal>, dataset: &str, name: &str) -> Option<String> {
+        self.queries
+            .read()
+            .unwrap()
+            .get(&scope(owner, dataset))?
+            .get(name)
+            .cloned()
+    }
+
+    /// Saves a query and returns `true` if `owner` had no query with the same name for `dataset` before.
+    pub fn insert(
+        &self,
+        owner: Option<&Principal>,
+        dataset: &str,
+        name: &str,
+        query: String,
+    ) -> io::Result<bool> {
+        let mut queries = self.queries.write().unwrap();
+        if let Some(path) = self.file_path(owner, dataset, name) {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, &query)?;
+        }
+        Ok(queries
+            .entry(scope(owner, dataset))
+            .or_default()
+            .insert(name.to_owned(), query)
+            .is_none())
+    }
+
+    /// Removes a query and returns `true` if it existed.
+    ///
+    /// The query is kept if its file can't be removed.
+    pub fn remove(&self, owner: Option<&Principal>, dataset: &str, name: &str) -> io::Result<bool> {
+        let mut queries = self.queries.write().unwrap();
+        let scope = scope(owner, dataset);
+        let Some(scoped_queries) = queries.get_mut(&scope) else {
+            return Ok(false);
+        };
+        if !scoped_queries.contains_key(name) {
+            return Ok(false);
+        }
+        if let Some(path) = self.file_path(owner, dataset, name) {
+            fs::remove_file(path)?;
+        }
+        scoped_queries.remove(name);
+        if scoped_queries.is_empty() {
+            queries.remove(&scope);
+        }
+        Ok(true)
+    }
+
+    fn file_path(&self, owner: Option<&Principal>, dataset: &str, name: &str) -> Option<PathBuf> {
+        Some(
+            self.directory
+                .as_ref()?
+                .join(dataset)
+                .join(encode_owner(owner))
+                .join(format!("{name}.{QUERY_FILE_EXTENSION}")),
+        )
+    }
+}
+
+/// Query and dataset names are restricted to characters that are safe both in URLs and file names.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 128
+        && name
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_')
+}
+
+fn scope(owner: Option<&Principal>, dataset: &str) -> Scope {
+    (owner.map(|o| o.as_str().to_owned()), dataset.to_owned())
+}
+
+fn valid_directory_name(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    (path.is_dir() && is_valid_name(name)).then_some(name)
+}
+
+/// Percent-encodes the principal name prefixed with `~` to make it a safe and non-empty file name
+fn encode_owner(owner: Option<&Principal>) -> String {
+    let Some(owner) = owner else {
+        return ANONYMOUS_OWNER_DIRECTORY.into();
+    };
+    let mut encoded = String::with_capacity(owner.as_str().len() + 1);
+    encoded.push('~');
+    for c in owner.as_str().bytes() {
+        if c.is_ascii_alphanumeric() || c == b'-' || c == b'_' {
+            encoded.push(char::from(c));
+        } else {
+            write!(encoded, "%{c:02X}").unwrap();
+        }
+    }
+    encoded
+}
+
+/// Inverse of [`encode_owner`], returns `None` if the name is not a valid encoding
+#[expect(clippy::option_option)]
+fn decode_owner(encoded: &str) -> Option<Option<String>> {
+    if encoded == ANONYMOUS_OWNER_DIRECTORY {
+        return Some(None);
+    }
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut bytes = encoded.strip_prefix('~')?.bytes();
+    while let Some(c) = bytes.next() {
+        if c == b'%' {
+            let hex = [bytes.next()?, bytes.next()?];
+            decoded.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else if c.is_ascii_alphanumeric() || c == b'-' || c == b'_' {
+            decoded.push(c);
+        } else {
+            return None;
+        }
+    }
+    Some(Some(String::from_utf8(decoded).ok()?))
+}
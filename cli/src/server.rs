@@ -0,0 +1,4603 @@
+use crate::audit::{Principal, modifies_audit_graph, only_modifies_graph, record_update};
+use crate::auth::{PrincipalLimits, QuotaExceeded};
+use crate::autocomplete::generate_autocomplete_metadata;
+use crate::changes::{write_change_event, write_keep_alive};
+use crate::encoding::{decode_request_body, encode_response_body};
+#[cfg(feature = "graphql")]
+use crate::graphql::{self, GraphQlSchema};
+use crate::maintenance::{MaintenanceMode, RunningWrite};
+use crate::middleware::{CorsMiddleware, Middleware, Operation};
+use crate::progress::{ProgressRegistration, QueryProgressRegistry};
+use crate::quotas::generate_graph_quotas;
+use crate::saved_queries::{SavedQueries, is_valid_name};
+use crate::service_description::{EndpointKind, generate_service_description};
+use crate::sql::SqlQuery;
+use crate::statistics::generate_statistics_trend;
+use crate::tpf::{TriplePattern, triple_pattern_fragment};
+use oxhttp::model::header::{
+    ACCEPT, CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_NONE_MATCH, LOCATION,
+};
+use oxhttp::model::uri::PathAndQuery;
+use oxhttp::model::{Body, HeaderValue, Method, Request, Response, StatusCode, Uri};
+use oxhttp::{ListeningServer, Server};
+use oxigraph::io::{RdfFormat, RdfParser, RdfSerializer};
+use oxigraph::model::{
+    GraphName, GraphNameRef, IriParseError, LiteralRef, NamedNode, NamedOrBlankNode, Quad, Term,
+    Triple, Variable, VariableRef,
+};
+use oxigraph::sparql::results::{QueryResultsFormat, QueryResultsSerializer};
+use oxigraph::sparql::{
+    EvaluationError, Query, QueryOptions, QueryResults, QuerySolutionIter, QueryTripleIter, Update,
+};
+use oxigraph::store::{BulkLoader, LoaderError, StorageError, Store};
+use oxiri::Iri;
+use oxttl::RdfPatchParser;
+use oxttl::rdfpatch::RdfPatchChange;
+use rand::random;
+use spargebra::{KeysetPagination, SparqlParser};
+#[cfg(feature = "geosparql")]
+use spargeo::register_geosparql_functions;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::min;
+#[cfg(feature = "graphql")]
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::ToSocketAddrs;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread::{self, available_parallelism};
+use std::time::{Duration, Instant};
+use std::{fmt, str};
+use url::form_urlencoded;
+
+const DEFAULT_MAX_SPARQL_BODY_SIZE: u64 = 1024 * 1024 * 128; // 128MB
+const DEFAULT_DATASET_NAME: &str = "default";
+const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_AUTOCOMPLETE_LIMIT: usize = 1000;
+const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_millis(500);
+const CHANGES_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+const HTML_ROOT_PAGE: &str = include_str!("../templates/query.html");
+const HTML_BROWSE_PAGE: &str = include_str!("../templates/browse.html");
+const HTML_UPLOAD_PAGE: &str = include_str!("../templates/upload.html");
+#[expect(clippy::large_include_file)]
+const YASGUI_JS: &str = include_str!("../templates/yasgui/yasgui.min.js");
+const YASGUI_CSS: &str = include_str!("../templates/yasgui/yasgui.min.css");
+const LOGO: &str = include_str!("../logo.svg");
+
+/// Errors returned by the request handlers and the [`Middleware`]s: an HTTP status and a message
+pub type HttpError = (StatusCode, String);
+
+/// A [SPARQL 1.1 Protocol](https://www.w3.org/TR/sparql11-protocol/) and [SPARQL 1.1 Graph Store HTTP Protocol](https://www.w3.org/TR/sparql11-http-rdf-update/) server on top of a [`Store`]
+///
+/// It also serves the web UI, the saved queries, the SQL endpoint and, with the `graphql` feature, the GraphQL endpoint.
+///
+/// The server can be run on its own with [`spawn`](Self::spawn)
+/// or mounted inside another HTTP application by forwarding it the requests with [`handle`](Self::handle).
+/// The routes are relative to the server root: `/query`, `/update`, `/store`...
+///
+/// ```
+/// use oxhttp::model::{Body, Request, StatusCode};
+/// use oxigraph::store::Store;
+/// use oxigraph_server::SparqlServer;
+///
+/// let server = SparqlServer::new(Store::new()?).with_read_only();
+/// let mut request = Request::builder()
+///     .uri("http://localhost/query?query=ASK%7B%7D")
+///     .body(Body::empty())?;
+/// assert_eq!(server.handle(&mut request).status(), StatusCode::OK);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Clone)]
+#[expect(clippy::struct_excessive_bools)]
+pub struct SparqlServer {
+    store: Store,
+    saved_queries: Arc<SavedQueries>,
+    dataset_name: String,
+    read_only: bool,
+    union_default_graph: bool,
+    #[cfg(feature = "graphql")]
+    graphql_schema: Option<Arc<GraphQlSchema>>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    max_body_size: u64,
+    timeout: Duration,
+    max_concurrent_connections: Option<usize>,
+    audit_graph: Option<NamedNode>,
+    statistics_graph: Option<NamedNode>,
+    maintenance_mode: MaintenanceMode,
+    maintenance_endpoint: bool,
+    graph_quota_endpoint: bool,
+    query_progresses: QueryProgressRegistry,
+}
+
+impl SparqlServer {
+    /// Builds a server on top of the given store, with the saved queries kept in memory.
+    pub fn new(store: Store) -> Self {
+        Self {
+            store,
+            saved_queries: Arc::new(SavedQueries::in_memory()),
+            dataset_name: DEFAULT_DATASET_NAME.into(),
+            read_only: false,
+            union_default_graph: false,
+            #[cfg(feature = "graphql")]
+            graphql_schema: None,
+            middlewares: Vec::new(),
+            max_body_size: DEFAULT_MAX_SPARQL_BODY_SIZE,
+            timeout: DEFAULT_HTTP_TIMEOUT,
+            max_concurrent_connections: None,
+            audit_graph: None,
+            statistics_graph: None,
+            maintenance_mode: MaintenanceMode::default(),
+            maintenance_endpoint: false,
+            graph_quota_endpoint: false,
+            query_progresses: QueryProgressRegistry::default(),
+        }
+    }
+
+    /// Sets where the saved queries exposed at `/queries` are stored.
+    ///
+    /// The saved queries can be shared between several servers given different [dataset names](Self::with_dataset_name).
+    #[inline]
+    #[must_use]
+    pub fn with_saved_queries(mut self, saved_queries: impl Into<Arc<SavedQueries>>) -> Self {
+        self.saved_queries = saved_queries.into();
+        self
+    }
+
+    /// Sets the name of the served dataset, `default` by default.
+    ///
+    /// The [saved queries](Self::with_saved_queries) are scoped by dataset and by [`Principal`].
+    /// The name must only contain ASCII letters, digits, `-` and `_`.
+    #[inline]
+    #[must_use]
+    pub fn with_dataset_name(mut self, dataset_name: impl Into<String>) -> Self {
+        let dataset_name = dataset_name.into();
+        assert!(
+            is_valid_name(&dataset_name),
+            "Invalid dataset name '{dataset_name}', only ASCII letters, digits, '-' and '_' are allowed"
+        );
+        self.dataset_name = dataset_name;
+        self
+    }
+
+    /// Rejects all the requests modifying the store or the saved queries.
+    #[inline]
+    #[must_use]
+    pub fn with_read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Uses the union of all graphs as the default graph of the queries that do not set a dataset.
+    #[inline]
+    #[must_use]
+    pub fn with_union_default_graph(mut self) -> Self {
+        self.union_default_graph = true;
+        self
+    }
+
+    /// Serves the given GraphQL schema at `/graphql`.
+    #[cfg(feature = "graphql")]
+    #[inline]
+    #[must_use]
+    pub fn with_graphql_schema(mut self, schema: GraphQlSchema) -> Self {
+        self.graphql_schema = Some(Arc::new(schema));
+        self
+    }
+
+    /// Allows cross-origin requests from any origin.
+    ///
+    /// The [`CorsMiddleware`] is called before all the other middlewares.
+    #[inline]
+    #[must_use]
+    pub fn with_cors(mut self) -> Self {
+        self.middlewares.insert(0, Arc::new(CorsMiddleware::new()));
+        self
+    }
+
+    /// Only allows cross-origin requests from the given origins like `https://example.com`.
+    ///
+    /// The [`CorsMiddleware`] is called before all the other middlewares.
+    #[inline]
+    #[must_use]
+    pub fn with_cors_allowed_origins(
+        mut self,
+        origins: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let cors = origins
+            .into_iter()
+            .fold(CorsMiddleware::new(), CorsMiddleware::with_allowed_origin);
+        self.middlewares.insert(0, Arc::new(cors));
+        self
+    }
+
+    /// Adds a [`Middleware`], for example to authenticate the requests.
+    ///
+    /// The middlewares are called in the order they are added before the request is handled
+    /// and in the reverse order on the response.
+    #[inline]
+    #[must_use]
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Sets the maximal size in bytes of the SPARQL, SQL and GraphQL request bodies (128MB by default).
+    #[inline]
+    #[must_use]
+    pub fn with_max_body_size(mut self, max_body_size: u64) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Sets the timeout of the HTTP connections opened by [`spawn`](Self::spawn) (60s by default).
+    #[inline]
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the maximal number of concurrent HTTP connections accepted by [`spawn`](Self::spawn).
+    ///
+    /// By default, it is 128 times the available parallelism.
+    #[inline]
+    #[must_use]
+    pub fn with_max_concurrent_connections(mut self, max_concurrent_connections: usize) -> Self {
+        self.max_concurrent_connections = Some(max_concurrent_connections);
+        self
+    }
+
+    /// Records the executed SPARQL updates in the given graph of the store.
+    ///
+    /// Each update is recorded with its text, its execution time, the affected graphs
+    /// and the [`Principal`] set in the request extensions by a middleware, if any.
+    /// The record is written just after the update, in a separate transaction.
+    /// Updates explicitly modifying the audit graph are rejected.
+    /// Changes made with the Graph Store Protocol at `/store` are not recorded.
+    #[inline]
+    #[must_use]
+    pub fn with_audit_graph(mut self, audit_graph: NamedNode) -> Self {
+        self.audit_graph = Some(audit_graph);
+        self
+    }
+
+    /// Exposes at `/statistics` the history of the dataset size recorded in the given graph by [`record_statistics`](crate::record_statistics).
+    ///
+    /// The endpoint returns the recorded snapshots and the growth per day of the number of quads and terms as JSON.
+    #[inline]
+    #[must_use]
+    pub fn with_statistics_graph(mut self, statistics_graph: NamedNode) -> Self {
+        self.statistics_graph = Some(statistics_graph);
+        self
+    }
+
+    /// Exposes the [maintenance mode](Self::maintenance_mode) at `/admin/maintenance`.
+    ///
+    /// `GET` returns its status as JSON, `PUT` enables it and waits for the running writes to finish
+    /// (`202 Accepted` is returned if they are still running after the server timeout) and `DELETE` disables it.
+    /// The endpoint is not authenticated: a [`Middleware`] should restrict its access.
+    #[inline]
+    #[must_use]
+    pub fn with_maintenance_endpoint(mut self) -> Self {
+        self.maintenance_endpoint = true;
+        self
+    }
+
+    /// Exposes at `/admin/quotas` the [graph quotas](Store::with_graph_quota) of the store and the current size of their graphs as JSON.
+    ///
+    /// Each request scans the graphs with a quota.
+    /// The endpoint is not authenticated: a [`Middleware`] should restrict its access.
+    #[inline]
+    #[must_use]
+    pub fn with_graph_quota_endpoint(mut self) -> Self {
+        self.graph_quota_endpoint = true;
+        self
+    }
+
+    /// The read-only maintenance mode of the server, shared by all its clones.
+    #[inline]
+    pub fn maintenance_mode(&self) -> &MaintenanceMode {
+        &self.maintenance_mode
+    }
+
+    /// Handles a request, calling the middlewares before and after the handler.
+    ///
+    /// Errors are returned as responses with a plain text body.
+    pub fn handle(&self, request: &mut Request<Body>) -> Response<Body> {
+        let mut response = self
+            .middlewares
+            .iter()
+            .find_map(|middleware| middleware.on_request(request))
+            .unwrap_or_else(|| {
+                decode_request_body(request)
+                    .and_then(|()| handle_request(request, self))
+                    .unwrap_or_else(|(status, message)| error(status, message))
+            });
+        for middleware in self.middlewares.iter().rev() {
+            middleware.on_response(request, &mut response);
+        }
+        encode_response_body(request, &mut response);
+        response
+    }
+
+    /// Starts an HTTP server listening on the given addresses in background threads.
+    pub fn spawn(self, bind: impl ToSocketAddrs) -> io::Result<ListeningServer> {
+        let timeout = self.timeout;
+        let max_concurrent_connections = match self.max_concurrent_connections {
+            Some(max_concurrent_connections) => max_concurrent_connections,
+            None => available_parallelism()?.get() * 128,
+        };
+        let mut server = Server::new(move |request| {
+            // oxhttp already decodes the gzip and deflate request bodies
+            if request
+                .headers()
+                .get(CONTENT_ENCODING)
+                .is_some_and(|coding| matches!(coding.as_bytes(), b"gzip" | b"deflate"))
+            {
+                request.headers_mut().remove(CONTENT_ENCODING);
+            }
+            self.handle(request)
+        })
+        .with_global_timeout(timeout)
+        .with_server_name(concat!("Oxigraph/", env!("CARGO_PKG_VERSION")))
+        .map_err(io::Error::other)?
+        .with_max_concurrent_connections(max_concurrent_connections);
+        for socket in bind.to_socket_addrs()? {
+            server = server.bind(socket);
+        }
+        server.spawn()
+    }
+}
+
+fn handle_request(
+    request: &mut Request<Body>,
+    state: &SparqlServer,
+) -> Result<Response<Body>, HttpError> {
+    let store = state.store.clone();
+    let saved_queries = &state.saved_queries;
+    let dataset_name = state.dataset_name.as_str();
+    let principal = request.extensions().get::<Principal>().cloned();
+    let principal = principal.as_ref();
+    let limits = request.extensions().get::<PrincipalLimits>().cloned();
+    let limits = limits.as_ref();
+    let read_only = state.read_only;
+    let union_default_graph = state.union_default_graph;
+    match (request.uri().path(), request.method().as_ref()) {
+        ("/" | "/browse" | "/upload", "HEAD") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "text/html")
+            .body(Body::empty())
+            .unwrap()),
+        ("/", "GET") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "text/html")
+            .body(HTML_ROOT_PAGE.into())
+            .unwrap()),
+        ("/browse", "GET") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "text/html")
+            .body(HTML_BROWSE_PAGE.into())
+            .unwrap()),
+        ("/upload", "GET") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "text/html")
+            .body(HTML_UPLOAD_PAGE.into())
+            .unwrap()),
+        ("/yasgui.min.css", "HEAD") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "text/css")
+            .body(Body::empty())
+            .unwrap()),
+        ("/yasgui.min.css", "GET") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "text/css")
+            .body(YASGUI_CSS.into())
+            .unwrap()),
+        ("/yasgui.min.js", "HEAD") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "application/javascript")
+            .body(Body::empty())
+            .unwrap()),
+        ("/yasgui.min.js", "GET") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "application/javascript")
+            .body(YASGUI_JS.into())
+            .unwrap()),
+        ("/logo.svg", "HEAD") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "image/svg+xml")
+            .body(Body::empty())
+            .unwrap()),
+        ("/logo.svg", "GET") => Ok(Response::builder()
+            .header(CONTENT_TYPE, "image/svg+xml")
+            .body(LOGO.into())
+            .unwrap()),
+        ("/query/progress", "GET") => {
+            let id = form_urlencoded::parse(url_query(request))
+                .find_map(|(k, v)| (k == "id").then_some(v))
+                .ok_or_else(|| bad_request("You should set the 'id' parameter"))?;
+            let registration = state.query_progresses.register(&id);
+            ReadForWrite::build_response(
+                move |mut w| {
+                    let is_finished = registration.write_event(&mut w)?;
+                    Ok((w, registration, is_finished))
+                },
+                |(mut w, registration, is_finished)| {
+                    if is_finished {
+                        return Ok(None);
+                    }
+                    thread::sleep(PROGRESS_EVENT_INTERVAL);
+                    let is_finished = registration.write_event(&mut w)?;
+                    Ok(Some((w, registration, is_finished)))
+                },
+                "text/event-stream",
+            )
+        }
+        ("/changes", "GET") => {
+            let mut graphs = Vec::new();
+            for (k, v) in form_urlencoded::parse(url_query(request)) {
+                match k.as_ref() {
+                    "graph" => graphs.push(resolve_with_base(request, &v)?.into()),
+                    "default" => graphs.push(GraphName::DefaultGraph),
+                    _ => (),
+                }
+            }
+            let changes = store.watch().map_err(internal_server_error)?;
+            // The stream is closed once the principal query time or result rows limit is reached
+            let deadline = limits
+                .and_then(PrincipalLimits::max_query_time)
+                .map(|max_query_time| Instant::now() + max_query_time);
+            let remaining_changes = limits
+                .and_then(PrincipalLimits::max_result_rows)
+                .unwrap_or(u64::MAX);
+            ReadForWrite::build_response(
+                move |w| Ok((w, changes, graphs, remaining_changes)),
+                move |(mut w, changes, graphs, mut remaining_changes)| {
+                    let mut timeout = CHANGES_KEEP_ALIVE_INTERVAL;
+                    if let Some(deadline) = deadline {
+                        let Some(remaining) = deadline.checked_duration_since(Instant::now())
+                        else {
+                            return Ok(None);
+                        };
+                        timeout = timeout.min(remaining);
+                    }
+                    match changes.recv_timeout(timeout) {
+                        Ok(events) => {
+                            let written = write_change_event(&mut w, &events, &graphs)?;
+                            remaining_changes = remaining_changes
+                                .saturating_sub(written.try_into().unwrap_or(u64::MAX));
+                            if remaining_changes == 0 {
+                                return Ok(None);
+                            }
+                        }
+                        Err(RecvTimeoutError::Timeout) => write_keep_alive(&mut w)?,
+                        Err(RecvTimeoutError::Disconnected) => return Ok(None),
+                    }
+                    Ok(Some((w, changes, graphs, remaining_changes)))
+                },
+                "text/event-stream",
+            )
+        }
+        ("/query", "GET") => {
+            let query = url_query(request);
+            if query.is_empty() {
+                let format = rdf_content_negotiation(request)?;
+                let description =
+                    generate_service_description(format, EndpointKind::Query, union_default_graph);
+                Ok(Response::builder()
+                    .header(CONTENT_TYPE, format.media_type())
+                    .body(description.into())
+                    .unwrap())
+            } else {
+                configure_and_evaluate_sparql_query(
+                    &store,
+                    &[url_query(request)],
+                    None,
+                    request,
+                    union_default_graph,
+                    state,
+                )
+            }
+        }
+        ("/query", "HEAD") => {
+            let query = url_query(request);
+            if query.is_empty() {
+                let format = rdf_content_negotiation(request)?;
+                Ok(Response::builder()
+                    .header(CONTENT_TYPE, format.media_type())
+                    .body(Body::empty())
+                    .unwrap())
+            } else {
+                configure_and_evaluate_sparql_query(
+                    &store,
+                    &[url_query(request)],
+                    None,
+                    request,
+                    union_default_graph,
+                    state,
+                )
+            }
+        }
+        ("/query", "POST") => {
+            let content_type =
+                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
+            if content_type == "application/sparql-query" {
+                let query = limited_string_body(request, state.max_body_size)?;
+                configure_and_evaluate_sparql_query(
+                    &store,
+                    &[url_query(request)],
+                    Some(query),
+                    request,
+                    union_default_graph,
+                    state,
+                )
+            } else if content_type == "application/x-www-form-urlencoded" {
+                let buffer = limited_body(request, state.max_body_size)?;
+                configure_and_evaluate_sparql_query(
+                    &store,
+                    &[url_query(request), &buffer],
+                    None,
+                    request,
+                    union_default_graph,
+                    state,
+                )
+            } else {
+                Err(unsupported_media_type(&content_type))
+            }
+        }
+        ("/update", "GET") => {
+            if read_only {
+                return Err(the_server_is_read_only());
+            }
+            let format = rdf_content_negotiation(request)?;
+            let description =
+                generate_service_description(format, EndpointKind::Update, union_default_graph);
+            Ok(Response::builder()
+                .header(CONTENT_TYPE, format.media_type())
+                .body(description.into())
+                .unwrap())
+        }
+        ("/update", "POST") => {
+            if read_only {
+                return Err(the_server_is_read_only());
+            }
+            let _write = start_write(state)?;
+            let content_type =
+                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
+            if content_type == "application/sparql-update" {
+                let update = limited_string_body(request, state.max_body_size)?;
+                configure_and_evaluate_sparql_update(
+                    &store,
+                    &[url_query(request)],
+                    Some(update),
+                    request,
+                    union_default_graph,
+                    state,
+                )
+            } else if content_type == "application/x-www-form-urlencoded" {
+                let buffer = limited_body(request, state.max_body_size)?;
+                configure_and_evaluate_sparql_update(
+                    &store,
+                    &[url_query(request), &buffer],
+                    None,
+                    request,
+                    union_default_graph,
+                    state,
+                )
+            } else {
+                Err(unsupported_media_type(&content_type))
+            }
+        }
+        #[cfg(feature = "graphql")]
+        ("/graphql", "GET") => {
+            let schema = graphql_schema(state)?;
+            let query = url_query_parameter(request, "query")
+                .ok_or_else(|| bad_request("You should set the 'query' parameter"))?;
+            let variables = if let Some(variables) = url_query_parameter(request, "variables") {
+                graphql::parse_json_variables(variables.as_bytes()).map_err(bad_request)?
+            } else {
+                HashMap::new()
+            };
+            evaluate_graphql(schema, &store, &query, &variables, limits)
+        }
+        #[cfg(feature = "graphql")]
+        ("/graphql", "POST") => {
+            let schema = graphql_schema(state)?;
+            let content_type =
+                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
+            let (query, variables) = if content_type == "application/json" {
+                graphql::parse_json_request(&limited_body(request, state.max_body_size)?)
+                    .map_err(bad_request)?
+            } else if content_type == "application/graphql" {
+                (
+                    limited_string_body(request, state.max_body_size)?,
+                    HashMap::new(),
+                )
+            } else {
+                return Err(unsupported_media_type(&content_type));
+            };
+            evaluate_graphql(schema, &store, &query, &variables, limits)
+        }
+        ("/sql", "GET") => {
+            let query = url_query_parameter(request, "query")
+                .ok_or_else(|| bad_request("You should set the 'query' parameter"))?
+                .into_owned();
+            evaluate_sql_query(
+                &store,
+                &|name| saved_queries.get(principal, dataset_name, name),
+                &query,
+                union_default_graph,
+                request,
+                &state.middlewares,
+            )
+        }
+        ("/sql", "POST") => {
+            let content_type =
+                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
+            if content_type != "application/sql" {
+                return Err(unsupported_media_type(&content_type));
+            }
+            let query = limited_string_body(request, state.max_body_size)?;
+            evaluate_sql_query(
+                &store,
+                &|name| saved_queries.get(principal, dataset_name, name),
+                &query,
+                union_default_graph,
+                request,
+                &state.middlewares,
+            )
+        }
+        ("/autocomplete", "GET") => {
+            let limit = if let Some(limit) = url_query_parameter(request, "limit") {
+                limit
+                    .parse()
+                    .map_err(|e| bad_request(format!("Invalid limit '{limit}': {e}")))?
+            } else {
+                DEFAULT_AUTOCOMPLETE_LIMIT
+            };
+            let body =
+                generate_autocomplete_metadata(&store, limit).map_err(internal_server_error)?;
+            Ok(Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.into())
+                .unwrap())
+        }
+        ("/statistics", "GET") => {
+            let Some(statistics_graph) = &state.statistics_graph else {
+                return Err((
+                    StatusCode::NOT_FOUND,
+                    "The dataset statistics history is not enabled on this server".into(),
+                ));
+            };
+            let body = generate_statistics_trend(&store, statistics_graph)
+                .map_err(internal_server_error)?;
+            Ok(Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.into())
+                .unwrap())
+        }
+        ("/fragments", "GET") => {
+            let format = rdf_content_negotiation(request)?;
+            let parameters = form_urlencoded::parse(url_query(request)).collect::<Vec<_>>();
+            let pattern = TriplePattern::from_parameters(
+                parameters.iter().map(|(k, v)| (k.as_ref(), v.as_ref())),
+            )
+            .map_err(bad_request)?;
+            let page = if let Some(page) = url_query_parameter(request, "page") {
+                page.parse()
+                    .ok()
+                    .filter(|page| *page > 0)
+                    .ok_or_else(|| bad_request(format!("Invalid page '{page}'")))?
+            } else {
+                1
+            };
+            let quads = triple_pattern_fragment(
+                &store,
+                &base_url(request),
+                &pattern,
+                page,
+                union_default_graph,
+            )
+            .map_err(internal_server_error)?;
+            let quads = match collect_results(quads.into_iter().map(Ok), limits)
+                .map_err(internal_server_error)?
+            {
+                Ok(quads) => quads,
+                Err(exceeded) => return Ok(exceeded.into_response()),
+            };
+            let mut serializer = RdfSerializer::from_format(format)
+                .with_prefix("hydra", "http://www.w3.org/ns/hydra/core#")
+                .map_err(internal_server_error)?
+                .with_prefix("void", "http://rdfs.org/ns/void#")
+                .map_err(internal_server_error)?
+                .for_writer(Vec::new());
+            for quad in &quads {
+                if format.supports_datasets() {
+                    serializer.serialize_quad(quad)
+                } else {
+                    serializer.serialize_triple(quad.as_ref())
+                }
+                .map_err(internal_server_error)?;
+            }
+            Ok(Response::builder()
+                .header(CONTENT_TYPE, format.media_type())
+                .body(serializer.finish().map_err(internal_server_error)?.into())
+                .unwrap())
+        }
+        ("/admin/maintenance", method) if state.maintenance_endpoint => {
+            let maintenance_mode = state.maintenance_mode();
+            match method {
+                "GET" => Ok(Response::builder()
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(
+                        format!(
+                            "{{\"enabled\":{},\"runningWrites\":{}}}",
+                            maintenance_mode.is_enabled(),
+                            maintenance_mode.running_writes()
+                        )
+                        .into(),
+                    )
+                    .unwrap()),
+                "PUT" => {
+                    let status = if maintenance_mode.enable_with_timeout(state.timeout) {
+                        StatusCode::NO_CONTENT
+                    } else {
+                        StatusCode::ACCEPTED
+                    };
+                    Ok(Response::builder()
+                        .status(status)
+                        .body(Body::empty())
+                        .unwrap())
+                }
+                "DELETE" => {
+                    maintenance_mode.disable();
+                    Ok(Response::builder()
+                        .status(StatusCode::NO_CONTENT)
+                        .body(Body::empty())
+                        .unwrap())
+                }
+                _ => Err((
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    format!("{method} is not supported by /admin/maintenance"),
+                )),
+            }
+        }
+        ("/admin/quotas", "GET") if state.graph_quota_endpoint => Ok(Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(
+                generate_graph_quotas(&store)
+                    .map_err(internal_server_error)?
+                    .into(),
+            )
+            .unwrap()),
+        ("/queries", "GET") => {
+            let format = query_results_content_negotiation(request)?;
+            let mut body = Vec::new();
+            let mut serializer = QueryResultsSerializer::from_format(format)
+                .serialize_solutions_to_writer(
+                    &mut body,
+                    vec![
+                        Variable::new_unchecked("name"),
+                        Variable::new_unchecked("query"),
+                    ],
+                )
+                .map_err(internal_server_error)?;
+            for (name, query) in saved_queries.list(principal, dataset_name) {
+                serializer
+                    .serialize([
+                        (
+                            VariableRef::new_unchecked("name"),
+                            LiteralRef::from(name.as_str()),
+                        ),
+                        (
+                            VariableRef::new_unchecked("query"),
+                            LiteralRef::from(query.as_str()),
+                        ),
+                    ])
+                    .map_err(internal_server_error)?;
+            }
+            serializer.finish().map_err(internal_server_error)?;
+            Ok(Response::builder()
+                .header(CONTENT_TYPE, format.media_type())
+                .body(body.into())
+                .unwrap())
+        }
+        (path, "GET") if path.starts_with("/queries/") => {
+            let name = saved_query_name(path)?;
+            let query = saved_queries
+                .get(principal, dataset_name, name)
+                .ok_or_else(|| saved_query_not_found(name))?;
+            Ok(Response::builder()
+                .header(CONTENT_TYPE, "application/sparql-query")
+                .body(query.into())
+                .unwrap())
+        }
+        (path, "PUT") if path.starts_with("/queries/") => {
+            if read_only {
+                return Err(the_server_is_read_only());
+            }
+            let name = saved_query_name(path)?.to_owned();
+            let content_type =
+                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
+            if content_type != "application/sparql-query" {
+                return Err(unsupported_media_type(&content_type));
+            }
+            let query = limited_string_body(request, state.max_body_size)?;
+            Query::parse(&query, Some(&base_url(request))).map_err(bad_request)?;
+            let new = saved_queries
+                .insert(principal, dataset_name, &name, query)
+                .map_err(internal_server_error)?;
+            Ok(Response::builder()
+                .status(if new {
+                    StatusCode::CREATED
+                } else {
+                    StatusCode::NO_CONTENT
+                })
+                .body(Body::empty())
+                .unwrap())
+        }
+        (path, "DELETE") if path.starts_with("/queries/") => {
+            if read_only {
+                return Err(the_server_is_read_only());
+            }
+            let name = saved_query_name(path)?;
+            if !saved_queries
+                .remove(principal, dataset_name, name)
+                .map_err(internal_server_error)?
+            {
+                return Err(saved_query_not_found(name));
+            }
+            Ok(Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .body(Body::empty())
+                .unwrap())
+        }
+        (path, "GET") if path.starts_with("/store") => {
+            if let Some(target) = store_target(request)? {
+                assert_that_graph_exists(&store, &target)?;
+                let format = rdf_content_negotiation(request)?;
+
+                let quads = match limit_results(
+                    store.quads_for_pattern(
+                        None,
+                        None,
+                        None,
+                        Some(GraphName::from(target).as_ref()),
+                    ),
+                    limits,
+                )? {
+                    Ok(quads) => quads,
+                    Err(exceeded) => return Ok(exceeded.into_response()),
+                };
+                ReadForWrite::build_response(
+                    move |w| Ok((RdfSerializer::from_format(format).for_writer(w), quads)),
+                    |(mut serializer, mut quads)| {
+                        Ok(if let Some(q) = quads.next() {
+                            serializer.serialize_triple(&q?.into())?;
+                            Some((serializer, quads))
+                        } else {
+                            serializer.finish()?;
+                            None
+                        })
+                    },
+                    format.media_type(),
+                )
+            } else {
+                let format = rdf_content_negotiation(request)?;
+                if !format.supports_datasets() {
+                    return Err(bad_request(format!(
+                        "It is not possible to serialize the full RDF dataset using {format} that does not support named graphs"
+                    )));
+                }
+                let quads = match limit_results(store.iter(), limits)? {
+                    Ok(quads) => quads,
+                    Err(exceeded) => return Ok(exceeded.into_response()),
+                };
+                ReadForWrite::build_response(
+                    move |w| Ok((RdfSerializer::from_format(format).for_writer(w), quads)),
+                    |(mut serializer, mut quads)| {
+                        Ok(if let Some(q) = quads.next() {
+                            serializer.serialize_quad(&q?)?;
+                            Some((serializer, quads))
+                        } else {
+                            serializer.finish()?;
+                            None
+                        })
+                    },
+                    format.media_type(),
+                )
+            }
+        }
+        (path, "PUT") if path.starts_with("/store") => {
+            if read_only {
+                return Err(the_server_is_read_only());
+            }
+            let _write = start_write(state)?;
+            let content_type =
+                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
+            if let Some(target) = store_target(request)? {
+                let format = RdfFormat::from_media_type(&content_type)
+                    .ok_or_else(|| unsupported_media_type(&content_type))?;
+                let new = !match &target {
+                    NamedGraphName::NamedNode(target) => {
+                        if store
+                            .contains_named_graph(target)
+                            .map_err(internal_server_error)?
+                        {
+                            store.clear_graph(target).map_err(storage_write_error)?;
+                            true
+                        } else {
+                            store
+                                .insert_named_graph(target)
+                                .map_err(storage_write_error)?;
+                            false
+                        }
+                    }
+                    NamedGraphName::DefaultGraph => {
+                        store
+                            .clear_graph(GraphNameRef::DefaultGraph)
+                            .map_err(storage_write_error)?;
+                        true
+                    }
+                };
+                web_load_graph(&store, request, format, &GraphName::from(target))?;
+                Ok(Response::builder()
+                    .status(if new {
+                        StatusCode::CREATED
+                    } else {
+                        StatusCode::NO_CONTENT
+                    })
+                    .body(Body::empty())
+                    .unwrap())
+            } else {
+                let format = RdfFormat::from_media_type(&content_type)
+                    .ok_or_else(|| unsupported_media_type(&content_type))?;
+                store.clear().map_err(storage_write_error)?;
+                web_load_dataset(&store, request, format)?;
+                Ok(Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(Body::empty())
+                    .unwrap())
+            }
+        }
+        (path, "DELETE") if path.starts_with("/store") => {
+            if read_only {
+                return Err(the_server_is_read_only());
+            }
+            let _write = start_write(state)?;
+            if let Some(target) = store_target(request)? {
+                match target {
+                    NamedGraphName::DefaultGraph => store
+                        .clear_graph(GraphNameRef::DefaultGraph)
+                        .map_err(storage_write_error)?,
+                    NamedGraphName::NamedNode(target) => {
+                        if store
+                            .contains_named_graph(&target)
+                            .map_err(internal_server_error)?
+                        {
+                            store
+                                .remove_named_graph(&target)
+                                .map_err(storage_write_error)?;
+                        } else {
+                            return Err((
+                                StatusCode::NOT_FOUND,
+                                format!("The graph {target} does not exists"),
+                            ));
+                        }
+                    }
+                }
+            } else {
+                store.clear().map_err(storage_write_error)?;
+            }
+            Ok(Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .body(Body::empty())
+                .unwrap())
+        }
+        (path, "POST") if path.starts_with("/store") => {
+            if read_only {
+                return Err(the_server_is_read_only());
+            }
+            let _write = start_write(state)?;
+            let content_type =
+                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
+            if let Some(target) = store_target(request)? {
+                let format = RdfFormat::from_media_type(&content_type)
+                    .ok_or_else(|| unsupported_media_type(&content_type))?;
+                let new = assert_that_graph_exists(&store, &target).is_ok();
+                web_load_graph(&store, request, format, &GraphName::from(target))?;
+                Ok(Response::builder()
+                    .status(if new {
+                        StatusCode::CREATED
+                    } else {
+                        StatusCode::NO_CONTENT
+                    })
+                    .body(Body::empty())
+                    .unwrap())
+            } else {
+                let format = RdfFormat::from_media_type(&content_type)
+                    .ok_or_else(|| unsupported_media_type(&content_type))?;
+                if format.supports_datasets() {
+                    web_load_dataset(&store, request, format)?;
+                    Ok(Response::builder()
+                        .status(StatusCode::NO_CONTENT)
+                        .body(Body::empty())
+                        .unwrap())
+                } else {
+                    let graph =
+                        resolve_with_base(request, &format!("/store/{:x}", random::<u128>()))?;
+                    web_load_graph(&store, request, format, &graph.clone().into())?;
+                    Ok(Response::builder()
+                        .status(StatusCode::CREATED)
+                        .header(LOCATION, graph.into_string())
+                        .body(Body::empty())
+                        .unwrap())
+                }
+            }
+        }
+        (path, "PATCH") if path.starts_with("/store") => {
+            if read_only {
+                return Err(the_server_is_read_only());
+            }
+            let _write = start_write(state)?;
+            let content_type =
+                content_type(request).ok_or_else(|| bad_request("No Content-Type given"))?;
+            let target = store_target(request)?.map(GraphName::from);
+            if content_type == "application/rdf-patch" {
+                let changes = read_rdf_patch(request, target.as_ref())?;
+                if let Some(audit_graph) = &state.audit_graph {
+                    if changes
+                        .iter()
+                        .any(|(quad, _)| quad.graph_name == audit_graph.clone().into())
+                    {
+                        return Err((
+                            StatusCode::FORBIDDEN,
+                            format!("The audit graph {audit_graph} can't be modified"),
+                        ));
+                    }
+                }
+                store
+                    .transaction(|mut transaction| {
+                        for (quad, is_addition) in &changes {
+                            if *is_addition {
+                                transaction.insert(quad)?;
+                            } else {
+                                transaction.remove(quad)?;
+                            }
+                        }
+                        Result::<_, StorageError>::Ok(())
+                    })
+                    .map_err(storage_write_error)?;
+                Ok(Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(Body::empty())
+                    .unwrap())
+            } else if content_type == "application/sparql-update" {
+                let update = limited_string_body(request, state.max_body_size)?;
+                if let Some(target) = &target {
+                    let parsed_update = SparqlParser::new()
+                        .with_base_iri(base_url(request))
+                        .map_err(bad_request)?
+                        .parse_update(&update)
+                        .map_err(bad_request)?;
+                    if !only_modifies_graph(&parsed_update, target) {
+                        return Err(bad_request(format!(
+                            "The update must only modify the target graph {target}"
+                        )));
+                    }
+                }
+                evaluate_sparql_update(
+                    &store,
+                    &update,
+                    false,
+                    Vec::new(),
+                    Vec::new(),
+                    request,
+                    state,
+                )
+            } else {
+                Err(unsupported_media_type(&content_type))
+            }
+        }
+        (path, "HEAD") if path.starts_with("/store") => {
+            if let Some(target) = store_target(request)? {
+                assert_that_graph_exists(&store, &target)?;
+            }
+            Ok(Response::builder().body(Body::empty()).unwrap())
+        }
+        _ => Err((
+            StatusCode::NOT_FOUND,
+            format!(
+                "{} {} is not supported by this server",
+                request.method(),
+                request.uri().path()
+            ),
+        )),
+    }
+}
+
+fn base_url(request: &Request<Body>) -> String {
+    let uri = request.uri();
+    if uri.query().is_some() {
+        // We remove the query
+        let mut parts = uri.clone().into_parts();
+        if let Some(path_and_query) = &mut parts.path_and_query {
+            if path_and_query.query().is_some() {
+                *path_and_query = PathAndQuery::try_from(path_and_query.path()).unwrap();
+            }
+        };
+        Uri::from_parts(parts).unwrap().to_string()
+    } else {
+        uri.to_string()
+    }
+}
+
+fn resolve_with_base(request: &Request<Body>, url: &str) -> Result<NamedNode, HttpError> {
+    Ok(Iri::parse(base_url(request))
+        .map_err(bad_request)?
+        .resolve(url)
+        .map_err(bad_request)?
+        .into())
+}
+
+fn url_query(request: &Request<Body>) -> &[u8] {
+    request.uri().query().unwrap_or_default().as_bytes()
+}
+
+#[cfg(feature = "graphql")]
+fn graphql_schema(state: &SparqlServer) -> Result<&GraphQlSchema, HttpError> {
+    state.graphql_schema.as_deref().ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            "The GraphQL endpoint is not enabled, use the --graphql-schema option to enable it"
+                .into(),
+        )
+    })
+}
+
+#[cfg(feature = "graphql")]
+fn evaluate_graphql(
+    schema: &GraphQlSchema,
+    store: &Store,
+    query: &str,
+    variables: &HashMap<String, graphql::Value>,
+    limits: Option<&PrincipalLimits>,
+) -> Result<Response<Body>, HttpError> {
+    let options = limited_query_options(limits);
+    let mut exceeded_quota = None;
+    let result = schema.execute_with(query, variables, |sparql| {
+        let error = match store.query_opt(sparql, options.clone()) {
+            Ok(QueryResults::Solutions(solutions)) => match collect_results(solutions, limits) {
+                Ok(Ok(solutions)) => return Ok(solutions),
+                Ok(Err(exceeded)) => {
+                    exceeded_quota = Some(exceeded);
+                    return Err(graphql::GraphQlError::new("Quota exceeded"));
+                }
+                Err(error) => error,
+            },
+            Ok(_) => {
+                return Err(graphql::GraphQlError::new(
+                    "The generated SPARQL query is not a SELECT",
+                ));
+            }
+            Err(error) => error,
+        };
+        exceeded_quota = query_time_exceeded(&error, limits);
+        Err(graphql::GraphQlError::new(error.to_string()))
+    });
+    if let Some(exceeded) = exceeded_quota {
+        return Ok(exceeded.into_response());
+    }
+    let (status, body) = match result {
+        Ok(body) => (StatusCode::OK, body),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            graphql::serialize_error(&e).map_err(internal_server_error)?,
+        ),
+    };
+    Ok(Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.into())
+        .unwrap())
+}
+
+fn url_query_parameter<'a>(request: &'a Request<Body>, param: &str) -> Option<Cow<'a, str>> {
+    form_urlencoded::parse(url_query(request))
+        .find(|(k, _)| k == param)
+        .map(|(_, v)| v)
+}
+
+fn limited_string_body(
+    request: &mut Request<Body>,
+    max_body_size: u64,
+) -> Result<String, HttpError> {
+    String::from_utf8(limited_body(request, max_body_size)?)
+        .map_err(|e| bad_request(format!("Invalid UTF-8 body: {e}")))
+}
+
+fn limited_body(request: &mut Request<Body>, max_body_size: u64) -> Result<Vec<u8>, HttpError> {
+    let body = request.body_mut();
+    if let Some(body_len) = body.len() {
+        if body_len > max_body_size {
+            // it's too big
+            return Err(bad_request(format!(
+                "SPARQL body payloads are limited to {max_body_size} bytes, found {body_len} bytes"
+            )));
+        }
+        let mut payload = Vec::with_capacity(
+            body_len
+                .try_into()
+                .map_err(|_| bad_request("Huge body size"))?,
+        );
+        body.read_to_end(&mut payload)
+            .map_err(internal_server_error)?;
+        Ok(payload)
+    } else {
+        let mut payload = Vec::new();
+        body.take(max_body_size + 1)
+            .read_to_end(&mut payload)
+            .map_err(internal_server_error)?;
+        if payload.len() > max_body_size.try_into().unwrap() {
+            return Err(bad_request(format!(
+                "SPARQL body payloads are limited to {max_body_size} bytes"
+            )));
+        }
+        Ok(payload)
+    }
+}
+
+fn configure_and_evaluate_sparql_query(
+    store: &Store,
+    encoded: &[&[u8]],
+    mut query: Option<String>,
+    request: &Request<Body>,
+    default_use_default_graph_as_union: bool,
+    state: &SparqlServer,
+) -> Result<Response<Body>, HttpError> {
+    let mut default_graph_uris = Vec::new();
+    let mut named_graph_uris = Vec::new();
+    let mut use_default_graph_as_union = None;
+    let mut progress_id = None;
+    let mut page_keys = Vec::new();
+    let mut page_size = None;
+    let mut page_after = Vec::new();
+    for encoded in encoded {
+        for (k, v) in form_urlencoded::parse(encoded) {
+            match k.as_ref() {
+                "query" => {
+                    if query.is_some() {
+                        return Err(bad_request("Multiple query parameters provided"));
+                    }
+                    query = Some(v.into_owned())
+                }
+                "default-graph-uri" => default_graph_uris.push(v.into_owned()),
+                "union-default-graph" => {
+                    use_default_graph_as_union = Some(parse_boolean_parameter(&k, &v)?)
+                }
+                "named-graph-uri" => named_graph_uris.push(v.into_owned()),
+                "progress-id" => progress_id = Some(v.into_owned()),
+                "page-key" => page_keys.push(v.into_owned()),
+                "page-size" => {
+                    page_size = Some(v.parse::<usize>().map_err(|e| {
+                        bad_request(format!("Invalid page-size parameter '{v}': {e}"))
+                    })?)
+                }
+                "page-after" => page_after.push(v.into_owned()),
+                _ => (),
+            }
+        }
+    }
+    let use_default_graph_as_union = use_default_graph_as_union.unwrap_or(
+        default_use_default_graph_as_union
+            && default_graph_uris.is_empty()
+            && named_graph_uris.is_empty(),
+    );
+    let mut query = query.ok_or_else(|| bad_request("You should set the 'query' parameter"))?;
+    if !page_keys.is_empty() {
+        query = paginate_sparql_query(&query, &page_keys, page_size, &page_after, request)?;
+    } else if page_size.is_some() || !page_after.is_empty() {
+        return Err(bad_request(
+            "The page-size and page-after parameters require the page-key parameter",
+        ));
+    }
+    evaluate_sparql_query(
+        store,
+        &query,
+        use_default_graph_as_union,
+        default_graph_uris,
+        named_graph_uris,
+        request,
+        &state.middlewares,
+        progress_id.map(|id| state.query_progresses.register(&id)),
+    )
+}
+
+/// Rewrites a SELECT query into one of its pages sorted by the `keys` variables
+///
+/// `after` contains the key values of the last solution of the previous page, an empty value meaning unbound.
+fn paginate_sparql_query(
+    query: &str,
+    keys: &[String],
+    page_size: Option<usize>,
+    after: &[String],
+    request: &Request<Body>,
+) -> Result<String, HttpError> {
+    let query = Query::parse(query, Some(&base_url(request))).map_err(bad_request)?;
+    let keys = keys
+        .iter()
+        .map(|key| Variable::new(key.strip_prefix('?').unwrap_or(key)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(bad_request)?;
+    let page_size =
+        page_size.ok_or_else(|| bad_request("The page-key parameter requires page-size"))?;
+    let pagination = KeysetPagination::new(query.into(), keys, page_size).map_err(bad_request)?;
+    let page = if after.is_empty() {
+        pagination.first_page()
+    } else {
+        let last_key = after
+            .iter()
+            .map(|value| {
+                if value.is_empty() {
+                    Ok(None)
+                } else {
+                    Term::from_str(value).map(Some)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(bad_request)?;
+        pagination.page_after(&last_key).map_err(bad_request)?
+    };
+    Ok(page.to_string())
+}
+
+/// Parses a boolean parameter value, an empty value meaning `true`
+fn parse_boolean_parameter(name: &str, value: &str) -> Result<bool, HttpError> {
+    match value {
+        "" | "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(bad_request(format!(
+            "The {name} parameter must be 'true' or 'false', found '{value}'"
+        ))),
+    }
+}
+
+fn evaluate_sql_query(
+    store: &Store,
+    saved_query: &dyn Fn(&str) -> Option<String>,
+    query: &str,
+    use_default_graph_as_union: bool,
+    request: &Request<Body>,
+    middlewares: &[Arc<dyn Middleware>],
+) -> Result<Response<Body>, HttpError> {
+    let query = SqlQuery::parse(query).map_err(bad_request)?;
+    let table_query = saved_query(query.table()).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("The table {} does not exist", query.table()),
+        )
+    })?;
+    let query = query
+        .to_sparql(&table_query, &base_url(request))
+        .map_err(bad_request)?;
+    evaluate_sparql_query(
+        store,
+        &query.to_string(),
+        use_default_graph_as_union,
+        Vec::new(),
+        Vec::new(),
+        request,
+        middlewares,
+        None,
+    )
+}
+
+fn evaluate_sparql_query(
+    store: &Store,
+    query: &str,
+    use_default_graph_as_union: bool,
+    default_graph_uris: Vec<String>,
+    named_graph_uris: Vec<String>,
+    request: &Request<Body>,
+    middlewares: &[Arc<dyn Middleware>],
+    progress: Option<ProgressRegistration>,
+) -> Result<Response<Body>, HttpError> {
+    let mut query = Query::parse(query, Some(&base_url(request))).map_err(bad_request)?;
+
+    if use_default_graph_as_union {
+        if !default_graph_uris.is_empty() || !named_graph_uris.is_empty() {
+            return Err(bad_request(
+                "default-graph-uri or named-graph-uri and union-default-graph should not be set at the same time",
+            ));
+        }
+        query.dataset_mut().set_default_graph_as_union()
+    } else if !default_graph_uris.is_empty() || !named_graph_uris.is_empty() {
+        query.dataset_mut().set_default_graph(
+            default_graph_uris
+                .into_iter()
+                .map(|e| Ok(NamedNode::new(e)?.into()))
+                .collect::<Result<Vec<GraphName>, IriParseError>>()
+                .map_err(bad_request)?,
+        );
+        query.dataset_mut().set_available_named_graphs(
+            named_graph_uris
+                .into_iter()
+                .map(|e| Ok(NamedNode::new(e)?.into()))
+                .collect::<Result<Vec<NamedOrBlankNode>, IriParseError>>()
+                .map_err(bad_request)?,
+        );
+    }
+
+    for middleware in middlewares {
+        middleware.on_operation(request, Operation::Query(&query))?;
+    }
+    let limits = request.extensions().get::<PrincipalLimits>();
+    let mut options = limited_query_options(limits);
+
+    if let Some(progress) = &progress {
+        options = options.with_progress(progress.progress().clone());
+    }
+    let mut results = match store.query_opt(query, options) {
+        Ok(results) => results,
+        Err(error) => return query_evaluation_error(error, limits),
+    };
+
+    // The results digest is used as an ETag for HEAD requests and conditional requests.
+    // The results are buffered to compute it before sending them and to evaluate the query only once.
+    let is_head = *request.method() == Method::HEAD;
+    let if_none_match = request
+        .headers()
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok());
+    let mut etag = None;
+    if is_head || if_none_match.is_some() {
+        let buffered = match BufferedResults::new(results, limits)? {
+            Ok(buffered) => buffered,
+            Err(exceeded) => return Ok(exceeded.into_response()),
+        };
+        let media_type = if matches!(buffered, BufferedResults::Graph(_)) {
+            rdf_content_negotiation(request)?.media_type()
+        } else {
+            query_results_content_negotiation(request)?.media_type()
+        };
+        let digest = format!(
+            "W/\"{}\"",
+            buffered
+                .clone()
+                .into_results()
+                .digest()
+                .map_err(internal_server_error)?
+        );
+        if if_none_match.is_some_and(|value| etag_matches(value, &digest)) {
+            return Ok(Response::builder()
+                .status(StatusCode::NOT_MODIFIED)
+                .header(ETAG, digest)
+                .body(Body::empty())
+                .unwrap());
+        }
+        if is_head {
+            return Ok(Response::builder()
+                .header(CONTENT_TYPE, media_type)
+                .header(ETAG, digest)
+                .body(Body::empty())
+                .unwrap());
+        }
+        results = buffered.into_results();
+        etag = Some(digest);
+    }
+
+    let mut response = match results {
+        QueryResults::Solutions(solutions) => {
+            let format = query_results_content_negotiation(request)?;
+            let variables = solutions.variables().to_vec();
+            let solutions = match limit_results(solutions, limits)? {
+                Ok(solutions) => solutions,
+                Err(exceeded) => return Ok(exceeded.into_response()),
+            };
+            ReadForWrite::build_response(
+                move |w| {
+                    Ok((
+                        QueryResultsSerializer::from_format(format)
+                            .serialize_solutions_to_writer(w, variables)?,
+                        solutions,
+                        progress,
+                    ))
+                },
+                |(mut serializer, mut solutions, progress)| {
+                    Ok(if let Some(solution) = solutions.next() {
+                        serializer.serialize(&solution?)?;
+                        Some((serializer, solutions, progress))
+                    } else {
+                        serializer.finish()?;
+                        None
+                    })
+                },
+                format.media_type(),
+            )
+        }
+        QueryResults::Boolean(result) => {
+            let format = query_results_content_negotiation(request)?;
+            let mut body = Vec::new();
+            QueryResultsSerializer::from_format(format)
+                .serialize_boolean_to_writer(&mut body, result)
+                .map_err(internal_server_error)?;
+            Ok(Response::builder()
+                .header(CONTENT_TYPE, format.media_type())
+                .body(body.into())
+                .unwrap())
+        }
+        QueryResults::Graph(triples) => {
+            let format = rdf_content_negotiation(request)?;
+            let triples = match limit_results(triples, limits)? {
+                Ok(triples) => triples,
+                Err(exceeded) => return Ok(exceeded.into_response()),
+            };
+            ReadForWrite::build_response(
+                move |w| {
+                    Ok((
+                        RdfSerializer::from_format(format).for_writer(w),
+                        triples,
+                        progress,
+                    ))
+                },
+                |(mut serializer, mut triples, progress)| {
+                    Ok(if let Some(t) = triples.next() {
+                        serializer.serialize_triple(&t?)?;
+                        Some((serializer, triples, progress))
+                    } else {
+                        serializer.finish()?;
+                        None
+                    })
+                },
+                format.media_type(),
+            )
+        }
+    }?;
+    if let Some(etag) = etag {
+        response.headers_mut().insert(
+            ETAG,
+            HeaderValue::from_str(&etag).map_err(internal_server_error)?,
+        );
+    }
+    Ok(response)
+}
+
+/// Query results kept in memory, to be able to go through them multiple times
+#[derive(Clone)]
+enum BufferedResults {
+    Solutions(Arc<[Variable]>, Vec<Vec<Option<Term>>>),
+    Boolean(bool),
+    Graph(Vec<Triple>),
+}
+
+impl BufferedResults {
+    fn new(
+        results: QueryResults,
+        limits: Option<&PrincipalLimits>,
+    ) -> Result<Result<Self, QuotaExceeded>, HttpError> {
+        Ok(Ok(match results {
+            QueryResults::Solutions(solutions) => {
+                let variables = solutions.variables().into();
+                match collect_results(solutions, limits).map_err(internal_server_error)? {
+                    Ok(solutions) => Self::Solutions(
+                        variables,
+                        solutions
+                            .into_iter()
+                            .map(|solution| solution.values().to_vec())
+                            .collect(),
+                    ),
+                    Err(exceeded) => return Ok(Err(exceeded)),
+                }
+            }
+            QueryResults::Boolean(result) => Self::Boolean(result),
+            QueryResults::Graph(triples) => {
+                match collect_results(triples, limits).map_err(internal_server_error)? {
+                    Ok(triples) => Self::Graph(triples),
+                    Err(exceeded) => return Ok(Err(exceeded)),
+                }
+            }
+        }))
+    }
+
+    fn into_results(self) -> QueryResults {
+        match self {
+            Self::Solutions(variables, solutions) => {
+                QuerySolutionIter::new(variables, solutions.into_iter().map(Ok)).into()
+            }
+            Self::Boolean(result) => QueryResults::Boolean(result),
+            Self::Graph(triples) => {
+                QueryResults::Graph(QueryTripleIter::new(triples.into_iter().map(Ok)))
+            }
+        }
+    }
+}
+
+/// Converts a query evaluation error, the timeouts caused by the principal query time limit becoming quota-exceeded responses
+fn query_evaluation_error(
+    error: EvaluationError,
+    limits: Option<&PrincipalLimits>,
+) -> Result<Response<Body>, HttpError> {
+    query_time_exceeded(&error, limits)
+        .map(QuotaExceeded::into_response)
+        .ok_or_else(|| internal_server_error(error))
+}
+
+/// The quota-exceeded error if the error is a timeout caused by the principal query time limit
+fn query_time_exceeded(
+    error: &EvaluationError,
+    limits: Option<&PrincipalLimits>,
+) -> Option<QuotaExceeded> {
+    let limits = limits?;
+    let max_query_time = limits.max_query_time()?;
+    matches!(error, EvaluationError::Timeout).then(|| limits.query_time_exceeded(max_query_time))
+}
+
+/// The query options used by the server, with the principal query time limit if any
+fn limited_query_options(limits: Option<&PrincipalLimits>) -> QueryOptions {
+    let options = default_query_options();
+    if let Some(max_query_time) = limits.and_then(PrincipalLimits::max_query_time) {
+        options.with_timeout(max_query_time)
+    } else {
+        options
+    }
+}
+
+/// Results of a read operation on which the principal limits are applied
+type LimitedResults<T> = Box<dyn Iterator<Item = Result<T, EvaluationError>>>;
+
+/// Applies the principal limits to the results of a read operation
+///
+/// The results fail with a timeout once the principal query time is exceeded.
+/// They are buffered if their number is limited, to reject them before sending anything.
+fn limit_results<T: 'static, E: Into<EvaluationError> + 'static>(
+    results: impl Iterator<Item = Result<T, E>> + 'static,
+    limits: Option<&PrincipalLimits>,
+) -> Result<Result<LimitedResults<T>, QuotaExceeded>, HttpError> {
+    let deadline = limits
+        .and_then(PrincipalLimits::max_query_time)
+        .map(|max_query_time| Instant::now() + max_query_time);
+    let results = results.map(move |result| {
+        if deadline.is_some_and(|deadline| Instant::now() > deadline) {
+            return Err(EvaluationError::Timeout);
+        }
+        result.map_err(Into::into)
+    });
+    if limits.and_then(PrincipalLimits::max_result_rows).is_none() {
+        return Ok(Ok(Box::new(results)));
+    }
+    Ok(
+        match collect_results(results, limits).map_err(internal_server_error)? {
+            Ok(results) => Ok(Box::new(results.into_iter().map(Ok))),
+            Err(exceeded) => Err(exceeded),
+        },
+    )
+}
+
+/// Collects the query results, failing with a quota-exceeded error if there are more than the principal `max_result_rows` of them
+fn collect_results<T>(
+    results: impl Iterator<Item = Result<T, EvaluationError>>,
+    limits: Option<&PrincipalLimits>,
+) -> Result<Result<Vec<T>, QuotaExceeded>, EvaluationError> {
+    let max_result_rows = limits.and_then(|limits| Some((limits, limits.max_result_rows()?)));
+    let mut collected = Vec::new();
+    for result in results {
+        if let Some((limits, max_result_rows)) = max_result_rows {
+            if u64::try_from(collected.len()).unwrap_or(u64::MAX) >= max_result_rows {
+                return Ok(Err(limits.result_rows_exceeded(max_result_rows)));
+            }
+        }
+        match result {
+            Ok(result) => collected.push(result),
+            Err(error) => return query_time_exceeded(&error, limits).map(Err).ok_or(error),
+        }
+    }
+    Ok(Ok(collected))
+}
+
+/// Checks if an `If-None-Match` header value matches an entity tag using the weak comparison
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// The query options used by the server, with the GeoSPARQL functions if the `geosparql` feature is enabled
+pub fn default_query_options() -> QueryOptions {
+    let mut options = QueryOptions::default();
+    #[cfg(feature = "geosparql")]
+    {
+        options = register_geosparql_functions(options);
+    }
+    options
+}
+
+fn configure_and_evaluate_sparql_update(
+    store: &Store,
+    encoded: &[&[u8]],
+    mut update: Option<String>,
+    request: &Request<Body>,
+    default_use_default_graph_as_union: bool,
+    state: &SparqlServer,
+) -> Result<Response<Body>, HttpError> {
+    let mut use_default_graph_as_union = None;
+    let mut default_graph_uris = Vec::new();
+    let mut named_graph_uris = Vec::new();
+    for encoded in encoded {
+        for (k, v) in form_urlencoded::parse(encoded) {
+            match k.as_ref() {
+                "update" => {
+                    if update.is_some() {
+                        return Err(bad_request("Multiple update parameters provided"));
+                    }
+                    update = Some(v.into_owned())
+                }
+                "using-graph-uri" => default_graph_uris.push(v.into_owned()),
+                "using-union-graph" => {
+                    use_default_graph_as_union = Some(parse_boolean_parameter(&k, &v)?)
+                }
+                "using-named-graph-uri" => named_graph_uris.push(v.into_owned()),
+                _ => (),
+            }
+        }
+    }
+    let use_default_graph_as_union = use_default_graph_as_union.unwrap_or(
+        default_use_default_graph_as_union
+            && default_graph_uris.is_empty()
+            && named_graph_uris.is_empty(),
+    );
+    let update = update.ok_or_else(|| bad_request("You should set the 'update' parameter"))?;
+    evaluate_sparql_update(
+        store,
+        &update,
+        use_default_graph_as_union,
+        default_graph_uris,
+        named_graph_uris,
+        request,
+        state,
+    )
+}
+
+fn evaluate_sparql_update(
+    store: &Store,
+    update: &str,
+    use_default_graph_as_union: bool,
+    default_graph_uris: Vec<String>,
+    named_graph_uris: Vec<String>,
+    request: &Request<Body>,
+    state: &SparqlServer,
+) -> Result<Response<Body>, HttpError> {
+    let parsed_update = SparqlParser::new()
+        .with_base_iri(base_url(request))
+        .map_err(bad_request)?
+        .parse_update(update)
+        .map_err(bad_request)?;
+    let audited_update = if let Some(audit_graph) = &state.audit_graph {
+        if modifies_audit_graph(&parsed_update, audit_graph) {
+            return Err((
+                StatusCode::FORBIDDEN,
+                format!("The audit graph {audit_graph} can't be modified"),
+            ));
+        }
+        Some(parsed_update.clone())
+    } else {
+        None
+    };
+    let update_text = update;
+    let mut update = Update::from(parsed_update);
+
+    if use_default_graph_as_union {
+        if !default_graph_uris.is_empty() || !named_graph_uris.is_empty() {
+            return Err(bad_request(
+                "using-graph-uri or using-named-graph-uri and using-union-graph should not be set at the same time",
+            ));
+        }
+        for using in update.using_datasets_mut() {
+            if !using.is_default_dataset() {
+                return Err(bad_request(
+                    "using-union-graph must not be used with a SPARQL UPDATE containing USING",
+                ));
+            }
+            using.set_default_graph_as_union();
+        }
+    } else if !default_graph_uris.is_empty() || !named_graph_uris.is_empty() {
+        let default_graph_uris = default_graph_uris
+            .into_iter()
+            .map(|e| Ok(NamedNode::new(e)?.into()))
+            .collect::<Result<Vec<GraphName>, IriParseError>>()
+            .map_err(bad_request)?;
+        let named_graph_uris = named_graph_uris
+            .into_iter()
+            .map(|e| Ok(NamedNode::new(e)?.into()))
+            .collect::<Result<Vec<NamedOrBlankNode>, IriParseError>>()
+            .map_err(bad_request)?;
+        for using in update.using_datasets_mut() {
+            if !using.is_default_dataset() {
+                return Err(bad_request(
+                    "using-graph-uri and using-named-graph-uri must not be used with a SPARQL UPDATE containing USING",
+                ));
+            }
+            using.set_default_graph(default_graph_uris.clone());
+            using.set_available_named_graphs(named_graph_uris.clone());
+        }
+    }
+    for middleware in &state.middlewares {
+        middleware.on_operation(request, Operation::Update(&update))?;
+    }
+    store
+        .update_opt(update, default_query_options())
+        .map_err(|e| match e {
+            EvaluationError::Storage(e) => storage_write_error(e),
+            other => internal_server_error(other),
+        })?;
+    if let (Some(audit_graph), Some(audited_update)) = (&state.audit_graph, &audited_update) {
+        record_update(
+            store,
+            audit_graph,
+            update_text,
+            audited_update,
+            request.extensions().get::<Principal>(),
+        )
+        .map_err(internal_server_error)?;
+    }
+    Ok(Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Body::empty())
+        .unwrap())
+}
+
+fn store_target(request: &Request<Body>) -> Result<Option<NamedGraphName>, HttpError> {
+    if request.uri().path() == "/store" {
+        if let Some(graph) = url_query_parameter(request, "graph") {
+            if url_query_parameter(request, "default").is_some() {
+                Err(bad_request(
+                    "Both graph and default parameters should not be set at the same time",
+                ))
+            } else {
+                Ok(Some(NamedGraphName::NamedNode(resolve_with_base(
+                    request, &graph,
+                )?)))
+            }
+        } else if url_query_parameter(request, "default").is_some() {
+            Ok(Some(NamedGraphName::DefaultGraph))
+        } else {
+            Ok(None)
+        }
+    } else {
+        Ok(Some(NamedGraphName::NamedNode(resolve_with_base(
+            request, "",
+        )?)))
+    }
+}
+
+/// Reads the changes of a RDF Patch body as (quad, is addition) pairs
+///
+/// The changes to the default graph are moved to the target graph if any,
+/// changes to other graphs than the target graph are rejected.
+fn read_rdf_patch(
+    request: &mut Request<Body>,
+    target: Option<&GraphName>,
+) -> Result<Vec<(Quad, bool)>, HttpError> {
+    let mut changes = Vec::new();
+    let mut transaction_start = None;
+    for change in RdfPatchParser::new().for_reader(request.body_mut()) {
+        let (mut quad, is_addition) = match change.map_err(bad_request)? {
+            RdfPatchChange::Addition(quad) => (quad, true),
+            RdfPatchChange::Deletion(quad) => (quad, false),
+            RdfPatchChange::TransactionStart => {
+                if transaction_start.is_some() {
+                    return Err(bad_request(
+                        "Nested RDF Patch transactions are not supported",
+                    ));
+                }
+                transaction_start = Some(changes.len());
+                continue;
+            }
+            RdfPatchChange::TransactionCommit => {
+                if transaction_start.take().is_none() {
+                    return Err(bad_request("TC is not allowed outside of a transaction"));
+                }
+                continue;
+            }
+            RdfPatchChange::TransactionAbort => {
+                let Some(start) = transaction_start.take() else {
+                    return Err(bad_request("TA is not allowed outside of a transaction"));
+                };
+                changes.truncate(start);
+                continue;
+            }
+        };
+        if let Some(target) = target {
+            if quad.graph_name.is_default_graph() {
+                quad.graph_name = target.clone();
+            } else if quad.graph_name != *target {
+                return Err(bad_request(format!(
+                    "The patch changes the graph {} and not only the target graph {target}",
+                    quad.graph_name
+                )));
+            }
+        }
+        changes.push((quad, is_addition));
+    }
+    if transaction_start.is_some() {
+        return Err(bad_request("The RDF Patch transaction is not committed"));
+    }
+    Ok(changes)
+}
+
+fn saved_query_name(path: &str) -> Result<&str, HttpError> {
+    let name = path.strip_prefix("/queries/").unwrap_or_default();
+    if is_valid_name(name) {
+        Ok(name)
+    } else {
+        Err(bad_request(format!(
+            "Invalid saved query name '{name}', only ASCII letters, digits, '-' and '_' are allowed"
+        )))
+    }
+}
+
+fn saved_query_not_found(name: &str) -> HttpError {
+    (
+        StatusCode::NOT_FOUND,
+        format!("The saved query {name} does not exist"),
+    )
+}
+
+fn assert_that_graph_exists(store: &Store, target: &NamedGraphName) -> Result<(), HttpError> {
+    if match target {
+        NamedGraphName::DefaultGraph => true,
+        NamedGraphName::NamedNode(target) => store
+            .contains_named_graph(target)
+            .map_err(internal_server_error)?,
+    } {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            format!(
+                "The graph {} does not exists",
+                GraphName::from(target.clone())
+            ),
+        ))
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+enum NamedGraphName {
+    NamedNode(NamedNode),
+    DefaultGraph,
+}
+
+impl From<NamedGraphName> for GraphName {
+    fn from(graph_name: NamedGraphName) -> Self {
+        match graph_name {
+            NamedGraphName::NamedNode(node) => node.into(),
+            NamedGraphName::DefaultGraph => Self::DefaultGraph,
+        }
+    }
+}
+
+fn rdf_content_negotiation(request: &Request<Body>) -> Result<RdfFormat, HttpError> {
+    content_negotiation(
+        request,
+        RdfFormat::from_media_type,
+        RdfFormat::NQuads,
+        &[
+            ("application", RdfFormat::NQuads),
+            ("text", RdfFormat::NQuads),
+        ],
+        "application/n-quads or text/turtle",
+    )
+}
+
+fn query_results_content_negotiation(
+    request: &Request<Body>,
+) -> Result<QueryResultsFormat, HttpError> {
+    content_negotiation(
+        request,
+        QueryResultsFormat::from_media_type,
+        QueryResultsFormat::Json,
+        &[
+            ("application", QueryResultsFormat::Json),
+            ("text", QueryResultsFormat::Json),
+        ],
+        "application/sparql-results+json or text/tsv",
+    )
+}
+
+fn content_negotiation<F: Copy>(
+    request: &Request<Body>,
+    parse: impl Fn(&str) -> Option<F>,
+    default: F,
+    default_by_base: &[(&str, F)],
+    example: &str,
+) -> Result<F, HttpError> {
+    let header = request
+        .headers()
+        .get(ACCEPT)
+        .map(|h| h.to_str())
+        .transpose()
+        .map_err(|_| bad_request("The Accept header should be a valid ASCII string"))?
+        .unwrap_or_default();
+
+    if header.is_empty() {
+        return Ok(default);
+    }
+    let mut result = None;
+    let mut result_score = 0_f32;
+    for mut possible in header.split(',') {
+        let mut score = 1.;
+        if let Some((possible_type, last_parameter)) = possible.rsplit_once(';') {
+            if let Some((name, value)) = last_parameter.split_once('=') {
+                if name.trim().eq_ignore_ascii_case("q") {
+                    score = f32::from_str(value.trim()).map_err(|_| {
+                        bad_request(format!("Invalid Accept media type score: {value}"))
+                    })?;
+                    possible = possible_type;
+                }
+            }
+        }
+        if score <= result_score {
+            continue;
+        }
+        let (possible_base, possible_sub) = possible
+            .split_once(';')
+            .unwrap_or((possible, ""))
+            .0
+            .split_once('/')
+            .ok_or_else(|| bad_request(format!("Invalid media type: '{possible}'")))?;
+        let possible_base = possible_base.trim();
+        let possible_sub = possible_sub.trim();
+
+        let mut format = None;
+        if possible_base == "*" && possible_sub == "*" {
+            format = Some(default);
+        } else if possible_sub == "*" {
+            for (base, sub_format) in default_by_base {
+                if *base == possible_base {
+                    format = Some(*sub_format);
+                }
+            }
+        } else {
+            format = parse(possible);
+        }
+        if let Some(format) = format {
+            result = Some(format);
+            result_score = score;
+        }
+    }
+
+    result.ok_or_else(|| {
+        (
+            StatusCode::NOT_ACCEPTABLE,
+            format!("The accept header does not provide any accepted format like {example}"),
+        )
+    })
+}
+
+fn content_type(request: &Request<Body>) -> Option<String> {
+    let value = request.headers().get(CONTENT_TYPE)?.to_str().ok()?;
+    Some(
+        value
+            .split_once(';')
+            .map_or(value, |(b, _)| b)
+            .trim()
+            .to_ascii_lowercase(),
+    )
+}
+
+fn web_load_graph(
+    store: &Store,
+    request: &mut Request<Body>,
+    format: RdfFormat,
+    to_graph_name: &GraphName,
+) -> Result<(), HttpError> {
+    let base_iri = if let GraphName::NamedNode(graph_name) = to_graph_name {
+        Some(graph_name.as_str())
+    } else {
+        None
+    };
+    let mut parser = RdfParser::from_format(format)
+        .without_named_graphs()
+        .with_default_graph(to_graph_name.clone());
+    if url_query_parameter(request, "lenient").is_some() {
+        parser = parser.lenient();
+    }
+    if let Some(base_iri) = base_iri {
+        parser = parser.with_base_iri(base_iri).map_err(bad_request)?;
+    }
+    if url_query_parameter(request, "no_transaction").is_some() {
+        web_bulk_loader(store, request).load_from_reader(parser, request.body_mut())
+    } else {
+        store.load_from_reader(parser, request.body_mut())
+    }
+    .map_err(loader_to_http_error)
+}
+
+fn web_load_dataset(
+    store: &Store,
+    request: &mut Request<Body>,
+    format: RdfFormat,
+) -> Result<(), HttpError> {
+    let mut parser = RdfParser::from_format(format);
+    if url_query_parameter(request, "lenient").is_some() {
+        parser = parser.lenient();
+    }
+    if url_query_parameter(request, "no_transaction").is_some() {
+        web_bulk_loader(store, request).load_from_reader(parser, request.body_mut())
+    } else {
+        store.load_from_reader(parser, request.body_mut())
+    }
+    .map_err(loader_to_http_error)
+}
+
+fn web_bulk_loader(store: &Store, request: &Request<Body>) -> BulkLoader {
+    let start = Instant::now();
+    let mut loader = store.bulk_loader().on_progress(move |size| {
+        let elapsed = start.elapsed();
+        eprintln!(
+            "{} triples loaded in {}s ({} t/s)",
+            size,
+            elapsed.as_secs(),
+            ((size as f64) / elapsed.as_secs_f64()).round()
+        )
+    });
+    if url_query_parameter(request, "lenient").is_some() {
+        loader = loader.on_parse_error(move |e| {
+            eprintln!("Parsing error: {e}");
+            Ok(())
+        })
+    }
+    loader
+}
+
+fn error(status: StatusCode, message: impl fmt::Display) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(message.to_string().into())
+        .unwrap()
+}
+
+fn bad_request(message: impl fmt::Display) -> HttpError {
+    (StatusCode::BAD_REQUEST, message.to_string())
+}
+
+fn the_server_is_read_only() -> HttpError {
+    (StatusCode::FORBIDDEN, "The server is read-only".into())
+}
+
+/// Registers a write to the dataset, failing if the server is in maintenance mode
+fn start_write(state: &SparqlServer) -> Result<RunningWrite, HttpError> {
+    state.maintenance_mode.start_write().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "The dataset is in maintenance mode and does not accept writes".into(),
+        )
+    })
+}
+
+fn unsupported_media_type(content_type: &str) -> HttpError {
+    (
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        format!("No supported content Content-Type given: {content_type}"),
+    )
+}
+
+/// Converts the error of a write, the write stalls becoming `503 Service Unavailable` errors the client can retry later
+/// and the exceeded graph quotas `507 Insufficient Storage` errors
+fn storage_write_error(error: StorageError) -> HttpError {
+    match error {
+        StorageError::WriteStall(error) => {
+            eprintln!("Write rejected: {error}");
+            (StatusCode::SERVICE_UNAVAILABLE, error.to_string())
+        }
+        StorageError::GraphQuotaExceeded(error) => {
+            (StatusCode::INSUFFICIENT_STORAGE, error.to_string())
+        }
+        other => internal_server_error(other),
+    }
+}
+
+fn internal_server_error(message: impl fmt::Display) -> HttpError {
+    eprintln!("Internal server error: {message}");
+    (StatusCode::INTERNAL_SERVER_ERROR, message.to_string())
+}
+
+fn loader_to_http_error(e: LoaderError) -> HttpError {
+    match e {
+        LoaderError::Parsing(e) => bad_request(e),
+        LoaderError::Storage(e) => storage_write_error(e),
+        LoaderError::InvalidBaseIri { .. } => bad_request(e),
+    }
+}
+
+/// Hacky tool to allow implementing read on top of a write loop
+struct ReadForWrite<O, U: (Fn(O) -> io::Result<Option<O>>)> {
+    buffer: Rc<RefCell<Vec<u8>>>,
+    position: usize,
+    add_more_data: U,
+    state: Option<O>,
+}
+
+impl<O: 'static, U: (Fn(O) -> io::Result<Option<O>>) + 'static> ReadForWrite<O, U> {
+    fn build_response(
+        initial_state_builder: impl FnOnce(ReadForWriteWriter) -> io::Result<O>,
+        add_more_data: U,
+        content_type: &'static str,
+    ) -> Result<Response<Body>, HttpError> {
+        let buffer = Rc::new(RefCell::new(Vec::new()));
+        let state = initial_state_builder(ReadForWriteWriter {
+            buffer: Rc::clone(&buffer),
+        })
+        .map_err(internal_server_error)?;
+        Response::builder()
+            .header(CONTENT_TYPE, content_type)
+            .body(Body::from_read(Self {
+                buffer,
+                position: 0,
+                add_more_data,
+                state: Some(state),
+            }))
+            .map_err(internal_server_error)
+    }
+}
+
+impl<O, U: (Fn(O) -> io::Result<Option<O>>)> Read for ReadForWrite<O, U> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buffer.borrow().len() {
+            // We read more data
+            if let Some(state) = self.state.take() {
+                self.buffer.borrow_mut().clear();
+                self.position = 0;
+                self.state = match (self.add_more_data)(state) {
+                    Ok(state) => state,
+                    Err(e) => {
+                        eprintln!("Internal server error while streaming results: {e}");
+                        self.buffer
+                            .borrow_mut()
+                            .write_all(e.to_string().as_bytes())?;
+                        None
+                    }
+                }
+            } else {
+                return Ok(0); // End
+            }
+        }
+        let buffer = self.buffer.borrow();
+        let len = min(buffer.len() - self.position, buf.len());
+        buf[..len].copy_from_slice(&buffer[self.position..self.position + len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+struct ReadForWriteWriter {
+    buffer: Rc<RefCell<Vec<u8>>>,
+}
+
+impl Write for ReadForWriteWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.buffer.borrow_mut().write_all(buf)
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::auth::{AuthMiddleware, QueryLimits, Role};
+    use crate::statistics::record_statistics;
+    use anyhow::Result;
+    use oxhttp::model::header::RETRY_AFTER;
+    use oxigraph::model::{Literal, NamedNodeRef, Quad};
+    use oxigraph::store::GraphQuota;
+    use std::fs;
+    use std::io::{Cursor, read_to_string};
+
+    #[test]
+    fn get_ui() -> Result<()> {
+        ServerTest::new()?.test_status(
+            Request::builder().uri("http://localhost/").body(())?,
+            StatusCode::OK,
+        )
+    }
+
+    #[test]
+    fn post_dataset_file() -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store")
+            .header(CONTENT_TYPE, "application/trig")
+            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
+        ServerTest::new()?.test_status(request, StatusCode::NO_CONTENT)
+    }
+
+    #[test]
+    fn post_wrong_file() -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store")
+            .header(CONTENT_TYPE, "application/trig")
+            .body("<http://example.com>")?;
+        ServerTest::new()?.test_status(request, StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn post_unsupported_file() -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store")
+            .header(CONTENT_TYPE, "text/foo")
+            .body(())?;
+        ServerTest::new()?.test_status(request, StatusCode::UNSUPPORTED_MEDIA_TYPE)
+    }
+
+    #[test]
+    fn get_browse_page() -> Result<()> {
+        ServerTest::new()?.test_status(
+            Request::builder().uri("http://localhost/browse").body(())?,
+            StatusCode::OK,
+        )
+    }
+
+    #[test]
+    fn get_upload_page() -> Result<()> {
+        ServerTest::new()?.test_status(
+            Request::builder().uri("http://localhost/upload").body(())?,
+            StatusCode::OK,
+        )
+    }
+
+    #[test]
+    fn get_query() -> Result<()> {
+        let server = ServerTest::new()?;
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store")
+            .header(CONTENT_TYPE, "application/trig")
+            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        let request = Request::builder()
+            .uri(
+                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
+            )
+            .header(ACCEPT, "text/csv")
+            .body(())?;
+        server.test_body(
+            request,
+            "s,p,o\r\nhttp://example.com,http://example.com,http://example.com\r\n",
+        )
+    }
+
+    #[test]
+    fn get_query_accept_star() -> Result<()> {
+        let request = Request::builder()
+            .uri(
+                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
+            )
+            .header(ACCEPT, "*/*")
+            .body(())?;
+        ServerTest::new()?.test_body(
+            request,
+            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
+        )
+    }
+
+    #[test]
+    fn get_query_accept_substar() -> Result<()> {
+        let request = Request::builder()
+            .uri(
+                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
+            )
+            .header(ACCEPT, "text/*")
+            .body(())?;
+        ServerTest::new()?.test_body(
+            request,
+            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
+        )
+    }
+
+    #[test]
+    fn query_etag() -> Result<()> {
+        let server = ServerTest::new()?;
+        let query_url =
+            "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}";
+
+        let request = Request::builder()
+            .method(Method::HEAD)
+            .uri(query_url)
+            .header(ACCEPT, "text/csv")
+            .body(())?;
+        let response = server.exec(request);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "text/csv; charset=utf-8"
+        );
+        let etag = response.headers().get(ETAG).unwrap().clone();
+
+        let request = Request::builder()
+            .uri(query_url)
+            .header(IF_NONE_MATCH, etag.clone())
+            .body(())?;
+        server.test_status(request, StatusCode::NOT_MODIFIED)?;
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store")
+            .header(CONTENT_TYPE, "application/trig")
+            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        let request = Request::builder()
+            .uri(query_url)
+            .header(ACCEPT, "text/csv")
+            .header(IF_NONE_MATCH, etag.clone())
+            .body(())?;
+        let response = server.exec(request);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers().get(ETAG), Some(&etag));
+        Ok(())
+    }
+
+    #[test]
+    fn query_etag_single_evaluation() -> Result<()> {
+        let server = ServerTest::new()?;
+        server.test_status(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/update")
+                .header(CONTENT_TYPE, "application/sparql-update")
+                .body("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 , 2 }")?,
+            StatusCode::NO_CONTENT,
+        )?;
+
+        // The query results are different at each evaluation
+        let registration = server.state.query_progresses.register("q1");
+        let mut response = server.exec(
+            Request::builder()
+                .uri("http://localhost/query?query=SELECT%20%3Fo%20(STRUUID()%20AS%20%3Fid)%20WHERE%20%7B%20%3Fs%20%3Fp%20%3Fo%20%7D&progress-id=q1")
+                .header(ACCEPT, "application/sparql-results+json")
+                .header(IF_NONE_MATCH, "W/\"foo\"")
+                .body(())?,
+        );
+        let body = read_to_string(response.body_mut())?;
+        assert_eq!(response.status(), StatusCode::OK, "Error message: {body}");
+        assert_eq!(registration.progress().result_count(), 2);
+        let digest = QueryResults::read(Cursor::new(body), QueryResultsFormat::Json)?.digest()?;
+        assert_eq!(
+            response.headers().get(ETAG).unwrap(),
+            format!("W/\"{digest}\"").as_str()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_query_accept_good() -> Result<()> {
+        let request = Request::builder()
+            .uri(
+                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
+            )
+            .header(ACCEPT, "application/sparql-results+json;charset=utf-8")
+            .body(())?;
+        ServerTest::new()?.test_body(
+            request,
+            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
+        )
+    }
+
+    #[test]
+    fn get_query_accept_bad() -> Result<()> {
+        let request = Request::builder()
+            .uri(
+                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
+            )
+            .header(ACCEPT, "application/foo")
+            .body(())?;
+        ServerTest::new()?.test_status(request, StatusCode::NOT_ACCEPTABLE)
+    }
+
+    #[test]
+    fn get_query_accept_explicit_priority() -> Result<()> {
+        let request = Request::builder()
+            .uri(
+                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
+            )
+            .header(ACCEPT, "text/foo;q=0.5 , text/json ; q = 0.7")
+            .body(())?;
+        ServerTest::new()?.test_body(
+            request,
+            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
+        )
+    }
+
+    #[test]
+    fn get_query_accept_implicit_priority() -> Result<()> {
+        let request = Request::builder()
+            .uri(
+                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
+            )
+            .header(ACCEPT, "text/json,text/foo")
+            .body(())?;
+        ServerTest::new()?.test_body(
+            request,
+            r#"{"head":{"vars":["s","p","o"]},"results":{"bindings":[]}}"#,
+        )
+    }
+    #[test]
+    fn get_query_accept_implicit_and_explicit_priority() -> Result<()> {
+        let request = Request::builder()
+            .uri(
+                "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}",
+            )
+            .header(ACCEPT, "text/foo;q=0.9,text/csv")
+            .body(())?;
+        ServerTest::new()?.test_body(request, "s,p,o\r\n")
+    }
+
+    #[test]
+    fn get_bad_query() -> Result<()> {
+        ServerTest::new()?.test_status(
+            Request::builder()
+                .uri("http://localhost/query?query=SELECT")
+                .body(())?,
+            StatusCode::BAD_REQUEST,
+        )
+    }
+
+    #[test]
+    fn get_query_union_graph() -> Result<()> {
+        let server = ServerTest::new()?;
+
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/1")
+            .header(CONTENT_TYPE, "text/turtle")
+            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
+        server.test_status(request, StatusCode::CREATED)?;
+
+        let request =Request::builder().uri(
+            "http://localhost/query?query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}&union-default-graph"
+        ).header(ACCEPT, "text/csv")
+            .body(())?;
+        server.test_body(
+            request,
+            "s,p,o\r\nhttp://example.com,http://example.com,http://example.com\r\n",
+        )
+    }
+
+    #[test]
+    fn get_query_union_graph_in_url_and_urlencoded() -> Result<()> {
+        let server = ServerTest::new()?;
+
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/1")
+            .header(CONTENT_TYPE, "text/turtle")
+            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
+        server.test_status(request, StatusCode::CREATED)?;
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/query?union-default-graph")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(ACCEPT, "text/csv")
+            .body("query=SELECT%20?s%20?p%20?o%20WHERE%20{%20?s%20?p%20?o%20}")?;
+        server.test_body(
+            request,
+            "s,p,o\r\nhttp://example.com,http://example.com,http://example.com\r\n",
+        )
+    }
+
+    #[test]
+    fn get_query_union_graph_and_default_graph() -> Result<()> {
+        ServerTest::new()?.test_status(Request::builder().uri(
+            "http://localhost/query?query=SELECT%20*%20WHERE%20{%20?s%20?p%20?o%20}&union-default-graph&default-graph-uri=http://example.com",
+        ).body(())?, StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn get_query_union_graph_boolean_value() -> Result<()> {
+        let server = ServerTest {
+            state: SparqlServer::new(Store::new()?).with_union_default_graph(),
+        };
+
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/1")
+            .header(CONTENT_TYPE, "text/turtle")
+            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
+        server.test_status(request, StatusCode::CREATED)?;
+
+        let request = Request::builder()
+            .uri("http://localhost/query?query=ASK%20{%20?s%20?p%20?o%20}&union-default-graph=true")
+            .header(ACCEPT, "text/csv")
+            .body(())?;
+        server.test_body(request, "true")?;
+        let request = Request::builder()
+            .uri(
+                "http://localhost/query?query=ASK%20{%20?s%20?p%20?o%20}&union-default-graph=false",
+            )
+            .header(ACCEPT, "text/csv")
+            .body(())?;
+        server.test_body(request, "false")?;
+        let request = Request::builder()
+            .uri("http://localhost/query?query=ASK%20{%20?s%20?p%20?o%20}&union-default-graph=yes")
+            .body(())?;
+        server.test_status(request, StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn get_query_keyset_pages() -> Result<()> {
+        let server = ServerTest::new()?;
+
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store?default")
+            .header(CONTENT_TYPE, "text/turtle")
+            .body("<http://example.com/1> <http://example.com/p> 1 . <http://example.com/2> <http://example.com/p> 2 . <http://example.com/3> <http://example.com/p> 3 .")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        let request = Request::builder()
+            .uri("http://localhost/query?query=SELECT%20?s%20WHERE%20{%20?s%20?p%20?o%20}&page-key=s&page-size=2")
+            .header(ACCEPT, "text/csv")
+            .body(())?;
+        server.test_body(
+            request,
+            "s\r\nhttp://example.com/1\r\nhttp://example.com/2\r\n",
+        )?;
+        let request = Request::builder()
+            .uri("http://localhost/query?query=SELECT%20?s%20WHERE%20{%20?s%20?p%20?o%20}&page-key=s&page-size=2&page-after=%3Chttp://example.com/2%3E")
+            .header(ACCEPT, "text/csv")
+            .body(())?;
+        server.test_body(request, "s\r\nhttp://example.com/3\r\n")?;
+        let request = Request::builder()
+            .uri("http://localhost/query?query=SELECT%20?s%20WHERE%20{%20?s%20?p%20?o%20}%20LIMIT%202&page-key=s&page-size=2")
+            .body(())?;
+        server.test_status(request, StatusCode::BAD_REQUEST)?;
+        let request = Request::builder()
+            .uri("http://localhost/query?query=SELECT%20?s%20WHERE%20{%20?s%20?p%20?o%20}&page-key=o&page-size=2")
+            .body(())?;
+        server.test_status(request, StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn get_query_description() -> Result<()> {
+        ServerTest::new()?.test_status(
+            Request::builder().uri("http://localhost/query").body(())?,
+            StatusCode::OK,
+        )
+    }
+
+    #[test]
+    fn post_query() -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/query")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("SELECT * WHERE { ?s ?p ?o }")?;
+        ServerTest::new()?.test_status(request, StatusCode::OK)
+    }
+
+    #[test]
+    fn post_bad_query() -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/query")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("SELECT")?;
+        ServerTest::new()?.test_status(request, StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn post_unknown_query() -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/query")
+            .header(CONTENT_TYPE, "application/sparql-todo")
+            .body("SELECT")?;
+        ServerTest::new()?.test_status(request, StatusCode::UNSUPPORTED_MEDIA_TYPE)
+    }
+
+    #[test]
+    fn post_federated_query_wikidata() -> Result<()> {
+        let request = Request::builder().method(Method::POST).uri("http://localhost/query")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("SELECT * WHERE { SERVICE <https://query.wikidata.org/sparql> { <https://en.wikipedia.org/wiki/Paris> ?p ?o } }")?;
+        ServerTest::new()?.test_status(request, StatusCode::OK)
+    }
+
+    #[test]
+    fn post_federated_query_dbpedia() -> Result<()> {
+        let request = Request::builder().method(Method::POST).uri("http://localhost/query")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("SELECT * WHERE { SERVICE <https://dbpedia.org/sparql> { <http://dbpedia.org/resource/Paris> ?p ?o } }")?;
+        ServerTest::new()?.test_status(request, StatusCode::OK)
+    }
+
+    #[test]
+    fn get_update_description() -> Result<()> {
+        ServerTest::new()?.test_status(
+            Request::builder().uri("http://localhost/update").body(())?,
+            StatusCode::OK,
+        )
+    }
+
+    #[test]
+    fn post_update() -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body(
+                "INSERT DATA { <http://example.com> <http://example.com> <http://example.com> }",
+            )?;
+        ServerTest::new()?.test_status(request, StatusCode::NO_CONTENT)
+    }
+
+    #[test]
+    fn post_bad_update() -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body("INSERT")?;
+        ServerTest::new()?.test_status(request, StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn post_update_read_only() -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body(
+                "INSERT DATA { <http://example.com> <http://example.com> <http://example.com> }",
+            )?;
+        ServerTest::check_status(
+            ServerTest::new()?.exec_read_only(request),
+            StatusCode::FORBIDDEN,
+        )
+    }
+
+    #[test]
+    fn middlewares() -> Result<()> {
+        struct NoUpdateMiddleware;
+
+        impl Middleware for NoUpdateMiddleware {
+            fn on_request(&self, request: &mut Request<Body>) -> Option<Response<Body>> {
+                (request.uri().path() == "/forbidden").then(|| {
+                    Response::builder()
+                        .status(StatusCode::FORBIDDEN)
+                        .body(Body::empty())
+                        .unwrap()
+                })
+            }
+
+            fn on_operation(
+                &self,
+                _request: &Request<Body>,
+                operation: Operation<'_>,
+            ) -> Result<(), HttpError> {
+                match operation {
+                    Operation::Query(_) => Ok(()),
+                    Operation::Update(_) => {
+                        Err((StatusCode::FORBIDDEN, "Updates are not allowed".to_owned()))
+                    }
+                }
+            }
+
+            fn on_response(&self, _request: &Request<Body>, response: &mut Response<Body>) {
+                response
+                    .headers_mut()
+                    .insert("x-middleware", HeaderValue::from_static("true"));
+            }
+        }
+
+        let mut server = ServerTest::new()?;
+        server.state.middlewares.push(Arc::new(NoUpdateMiddleware));
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body(
+                "INSERT DATA { <http://example.com> <http://example.com> <http://example.com> }",
+            )?;
+        let response = server.exec(request);
+        assert_eq!(response.headers().get("x-middleware").unwrap(), "true");
+        ServerTest::check_status(response, StatusCode::FORBIDDEN)?;
+
+        let request = Request::builder()
+            .uri("http://localhost/forbidden")
+            .body(())?;
+        server.test_status(request, StatusCode::FORBIDDEN)?;
+
+        let request = Request::builder()
+            .uri("http://localhost/query?query=ASK%7B%7D")
+            .header(ACCEPT, "application/sparql-results+json")
+            .body(())?;
+        server.test_body(request, "{\"head\":{},\"boolean\":true}")
+    }
+
+    #[test]
+    fn cors() -> Result<()> {
+        let mut server = ServerTest::new()?;
+        server
+            .state
+            .middlewares
+            .push(Arc::new(CorsMiddleware::new()));
+
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("http://localhost/query")
+            .header("origin", "http://example.com")
+            .header("access-control-request-method", "POST")
+            .body(())?;
+        let response = server.exec(request);
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response
+                .headers()
+                .get("access-control-allow-origin")
+                .unwrap(),
+            "*"
+        );
+        assert_eq!(
+            response
+                .headers()
+                .get("access-control-allow-methods")
+                .unwrap(),
+            "POST"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cors_allowed_origins() -> Result<()> {
+        let mut server = ServerTest::new()?;
+        server.state = server
+            .state
+            .with_cors_allowed_origins(["https://example.com", "https://example.org"]);
+
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("http://localhost/update")
+            .header("origin", "https://example.org")
+            .header("access-control-request-method", "POST")
+            .header(
+                "access-control-request-headers",
+                "authorization, content-type",
+            )
+            .body(())?;
+        let response = server.exec(request);
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let headers = response.headers();
+        assert_eq!(
+            headers.get("access-control-allow-origin").unwrap(),
+            "https://example.org"
+        );
+        assert_eq!(headers.get("access-control-allow-methods").unwrap(), "POST");
+        assert_eq!(
+            headers.get("access-control-allow-headers").unwrap(),
+            "authorization, content-type"
+        );
+        assert_eq!(
+            headers.get("access-control-allow-credentials").unwrap(),
+            "true"
+        );
+        assert_eq!(headers.get("vary").unwrap(), "Origin");
+
+        let request = Request::builder()
+            .uri("http://localhost/query?query=ASK%7B%7D")
+            .header("origin", "https://example.com")
+            .body(())?;
+        let response = server.exec(request);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get("access-control-allow-origin")
+                .unwrap(),
+            "https://example.com"
+        );
+
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("http://localhost/update")
+            .header("origin", "https://example.net")
+            .header("access-control-request-method", "POST")
+            .body(())?;
+        let response = server.exec(request);
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(
+            response
+                .headers()
+                .get("access-control-allow-origin")
+                .is_none()
+        );
+        assert!(
+            response
+                .headers()
+                .get("access-control-allow-methods")
+                .is_none()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn changes() -> Result<()> {
+        let server = ServerTest::new()?;
+        let mut all_changes = server.exec(
+            Request::builder()
+                .uri("http://localhost/changes")
+                .body(())?,
+        );
+        assert_eq!(all_changes.status(), StatusCode::OK);
+        assert_eq!(
+            all_changes.headers().get(CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+        let mut graph_changes = server.exec(
+            Request::builder()
+                .uri("http://localhost/changes?graph=http://example.com/g")
+                .body(())?,
+        );
+        server.test_status(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/update")
+                .header(CONTENT_TYPE, "application/sparql-update")
+                .body("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 }")?,
+            StatusCode::NO_CONTENT,
+        )?;
+        server.test_status(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/update")
+                .header(CONTENT_TYPE, "application/sparql-update")
+                .body("DELETE DATA { <http://example.com/s> <http://example.com/p> 1 } ; INSERT DATA { GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 2 } }")?,
+            StatusCode::NO_CONTENT,
+        )?;
+
+        let mut buffer = [0; 1024];
+        let mut events = String::new();
+        while events.matches("\n\n").count() < 2 {
+            let len = all_changes.body_mut().read(&mut buffer)?;
+            events.push_str(str::from_utf8(&buffer[..len])?);
+        }
+        assert_eq!(
+            events,
+            "event: change\n\
+            data: A <http://example.com/s> <http://example.com/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n\n\
+            event: change\n\
+            data: D <http://example.com/s> <http://example.com/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n\
+            data: A <http://example.com/s> <http://example.com/p> \"2\"^^<http://www.w3.org/2001/XMLSchema#integer> <http://example.com/g> .\n\n"
+        );
+        let len = graph_changes.body_mut().read(&mut buffer)?;
+        assert_eq!(
+            str::from_utf8(&buffer[..len])?,
+            "event: change\n\
+            data: A <http://example.com/s> <http://example.com/p> \"2\"^^<http://www.w3.org/2001/XMLSchema#integer> <http://example.com/g> .\n\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn query_progress() -> Result<()> {
+        let server = ServerTest::new()?;
+        server.test_status(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/update")
+                .header(CONTENT_TYPE, "application/sparql-update")
+                .body("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 , 2 }")?,
+            StatusCode::NO_CONTENT,
+        )?;
+
+        // The listener is registered before the query
+        let mut events = server.exec(
+            Request::builder()
+                .uri("http://localhost/query/progress?id=q1")
+                .body(())?,
+        );
+        assert_eq!(events.status(), StatusCode::OK);
+        assert_eq!(
+            events.headers().get(CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+        server.test_body(
+            Request::builder()
+                .uri("http://localhost/query?query=SELECT%20%3Fo%20WHERE%20%7B%20%3Fs%20%3Fp%20%3Fo%20%7D%20ORDER%20BY%20%3Fo&progress-id=q1")
+                .header(ACCEPT, "text/csv")
+                .body(())?,
+            "o\r\n1\r\n2\r\n",
+        )?;
+        let events = read_to_string(events.body_mut())?;
+        assert!(
+            events.starts_with(
+                "event: progress\ndata: {\"results\":0,\"lookups\":0,\"readQuads\":0,\"elapsed\":0}\n\n"
+            ),
+            "{events}"
+        );
+        assert!(
+            events.contains("data: {\"results\":2,\"lookups\":1,\"readQuads\":2,"),
+            "{events}"
+        );
+        assert!(events.ends_with("event: end\ndata:\n\n"), "{events}");
+
+        server.test_status(
+            Request::builder()
+                .uri("http://localhost/query/progress")
+                .body(())?,
+            StatusCode::BAD_REQUEST,
+        )
+    }
+
+    #[test]
+    fn autocomplete() -> Result<()> {
+        let server = ServerTest::new()?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body(
+                "PREFIX schema: <http://schema.org/> INSERT DATA { \
+                <http://example.com/a> a schema:Person ; schema:name \"a\" . \
+                <http://example.com/b> a schema:Person ; schema:name \"b\" . \
+                GRAPH <http://example.com/g> { <http://example.com/c> a schema:Person ; <http://example.com/p> 1 } \
+                }",
+            )?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+        let request = Request::builder()
+            .uri("http://localhost/autocomplete")
+            .body(())?;
+        server.test_body(
+            request,
+            "{\"prefixes\":{\"schema\":\"http://schema.org/\",\"rdf\":\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\",\"ns1\":\"http://example.com/\"},\
+            \"classes\":[{\"iri\":\"http://schema.org/Person\",\"count\":3}],\
+            \"predicates\":[{\"iri\":\"http://www.w3.org/1999/02/22-rdf-syntax-ns#type\",\"count\":3},{\"iri\":\"http://schema.org/name\",\"count\":2},{\"iri\":\"http://example.com/p\",\"count\":1}]}",
+        )?;
+        let request = Request::builder()
+            .uri("http://localhost/autocomplete?limit=1")
+            .body(())?;
+        server.test_body(
+            request,
+            "{\"prefixes\":{\"schema\":\"http://schema.org/\",\"rdf\":\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"},\
+            \"classes\":[{\"iri\":\"http://schema.org/Person\",\"count\":3}],\
+            \"predicates\":[{\"iri\":\"http://www.w3.org/1999/02/22-rdf-syntax-ns#type\",\"count\":3}]}",
+        )?;
+        let request = Request::builder()
+            .uri("http://localhost/autocomplete?limit=foo")
+            .body(())?;
+        server.test_status(request, StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn audit_graph() -> Result<()> {
+        struct AuthenticationMiddleware;
+
+        impl Middleware for AuthenticationMiddleware {
+            fn on_request(&self, request: &mut Request<Body>) -> Option<Response<Body>> {
+                request.extensions_mut().insert(Principal::new("alice"));
+                None
+            }
+        }
+
+        let mut server = ServerTest::new()?;
+        server.state = server
+            .state
+            .with_middleware(AuthenticationMiddleware)
+            .with_audit_graph(NamedNode::new("http://example.com/audit")?);
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body("INSERT DATA { GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 1 } <http://example.com/s> <http://example.com/p> 2 }")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body("CLEAR GRAPH <http://example.com/audit>")?;
+        server.test_status(request, StatusCode::FORBIDDEN)?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body(
+                "DELETE { GRAPH ?g { ?s ?p ?o } } WHERE { GRAPH ?g { ?s ?p ?o FILTER(?o = 1) } }",
+            )?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/query")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .header(ACCEPT, "text/csv")
+            .body(
+                "PREFIX audit: <http://oxigraph.org/audit#> PREFIX prov: <http://www.w3.org/ns/prov#> \
+                SELECT ?operation ?principal ?graph WHERE { GRAPH <http://example.com/audit> { \
+                ?record a prov:Activity, audit:Update ; prov:endedAtTime ?time ; audit:operation ?operation ; audit:principal ?principal ; audit:graph ?graph \
+                } } ORDER BY ?graph",
+            )?;
+        server.test_body(
+            request,
+            "operation,principal,graph\r\n\
+            INSERT DATA { GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 1 } <http://example.com/s> <http://example.com/p> 2 },alice,http://example.com/g\r\n\
+            DELETE { GRAPH ?g { ?s ?p ?o } } WHERE { GRAPH ?g { ?s ?p ?o FILTER(?o = 1) } },alice,http://oxigraph.org/audit#AllGraphs\r\n\
+            INSERT DATA { GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 1 } <http://example.com/s> <http://example.com/p> 2 },alice,http://oxigraph.org/audit#DefaultGraph\r\n",
+        )
+    }
+
+    #[test]
+    fn authentication() -> Result<()> {
+        let mut server = ServerTest::new()?;
+        server.state = server.state.with_middleware(
+            AuthMiddleware::new()
+                .with_user("alice", "secret", Role::Write)
+                .with_user("bob", "pwd", Role::Read)
+                .with_token("ci", "token", Role::Read),
+        );
+        let update = |authorization: Option<&str>| {
+            let mut request = Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/update")
+                .header(CONTENT_TYPE, "application/sparql-update");
+            if let Some(authorization) = authorization {
+                request = request.header("authorization", authorization);
+            }
+            request.body("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 }")
+        };
+        let query = |authorization: &str| {
+            Request::builder()
+                .uri("http://localhost/query?query=ASK%7B%7D")
+                .header("authorization", authorization)
+                .body(())
+        };
+
+        let response = server.exec(update(None)?);
+        assert_eq!(
+            response.headers().get("www-authenticate").unwrap(),
+            "Basic realm=\"Oxigraph\""
+        );
+        ServerTest::check_status(response, StatusCode::UNAUTHORIZED)?;
+        server.test_status(
+            update(Some("Basic YWxpY2U6d3Jvbmc="))?,
+            StatusCode::UNAUTHORIZED,
+        )?;
+        server.test_status(update(Some("Basic Ym9iOnB3ZA=="))?, StatusCode::FORBIDDEN)?;
+        server.test_status(update(Some("Bearer token"))?, StatusCode::FORBIDDEN)?;
+        server.test_status(
+            update(Some("basic YWxpY2U6c2VjcmV0"))?,
+            StatusCode::NO_CONTENT,
+        )?;
+        server.test_status(query("Basic Ym9iOnB3ZA==")?, StatusCode::OK)?;
+        server.test_status(query("Bearer token")?, StatusCode::OK)?;
+        server.test_status(query("Bearer other")?, StatusCode::UNAUTHORIZED)?;
+        let mut response = server.exec(
+            Request::builder()
+                .uri("http://localhost/admin/quotas")
+                .header("authorization", "Basic Ym9iOnB3ZA==")
+                .body(())?,
+        );
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            read_to_string(response.body_mut())?,
+            "bob is not allowed to use the /admin endpoints"
+        );
+
+        // Anonymous reads
+        let mut server = ServerTest::new()?;
+        server.state = server.state.with_middleware(
+            AuthMiddleware::new()
+                .with_user("alice", "secret", Role::Write)
+                .with_anonymous_role(Role::Read),
+        );
+        server.test_status(
+            Request::builder()
+                .uri("http://localhost/query?query=ASK%7B%7D")
+                .body(())?,
+            StatusCode::OK,
+        )?;
+        server.test_status(update(None)?, StatusCode::UNAUTHORIZED)?;
+        server.test_status(
+            update(Some("Basic YWxpY2U6c2VjcmV0"))?,
+            StatusCode::NO_CONTENT,
+        )
+    }
+
+    #[test]
+    fn query_limits() -> Result<()> {
+        let mut server = ServerTest::new()?;
+        server.state = server.state.with_middleware(
+            AuthMiddleware::new()
+                .with_user("alice", "secret", Role::Write)
+                .with_user("bob", "pwd", Role::Read)
+                .with_anonymous_role(Role::Read)
+                .with_role_limits(Role::Read, QueryLimits::new().with_daily_budget(2))
+                .with_principal_limits("alice", QueryLimits::new().with_max_result_rows(2)),
+        );
+        let query = |query: &str, authorization: Option<&str>| {
+            let mut request = Request::builder().uri(format!(
+                "http://localhost/query?query={}",
+                form_urlencoded::byte_serialize(query.as_bytes()).collect::<String>()
+            ));
+            if let Some(authorization) = authorization {
+                request = request.header("authorization", authorization);
+            }
+            request.body(())
+        };
+
+        // Max result rows
+        server.test_status(
+            query(
+                "SELECT ?v WHERE { VALUES ?v { 1 2 } }",
+                Some("Basic YWxpY2U6c2VjcmV0"),
+            )?,
+            StatusCode::OK,
+        )?;
+        let mut response = server.exec(query(
+            "SELECT ?v WHERE { VALUES ?v { 1 2 3 } }",
+            Some("Basic YWxpY2U6c2VjcmV0"),
+        )?);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            read_to_string(response.body_mut())?,
+            "{\"error\":\"quota-exceeded\",\"quota\":\"maxResultRows\",\"limit\":2,\"principal\":\"alice\",\"message\":\"Quota exceeded for alice: the query returns more than 2 results\"}"
+        );
+        server.test_status(
+            query(
+                "CONSTRUCT { ?v ?v ?v } WHERE { VALUES ?v { <http://example.com/1> <http://example.com/2> <http://example.com/3> } }",
+                Some("Basic YWxpY2U6c2VjcmV0"),
+            )?,
+            StatusCode::FORBIDDEN,
+        )?;
+
+        // Daily budget, the principal limits replace the role ones
+        server.test_status(query("ASK {}", Some("Basic Ym9iOnB3ZA=="))?, StatusCode::OK)?;
+        server.test_status(query("ASK {}", Some("Basic Ym9iOnB3ZA=="))?, StatusCode::OK)?;
+        let mut response = server.exec(query("ASK {}", Some("Basic Ym9iOnB3ZA=="))?);
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .unwrap()
+            .to_str()?
+            .parse::<u64>()?;
+        assert!(retry_after <= 24 * 60 * 60);
+        assert_eq!(
+            read_to_string(response.body_mut())?,
+            "{\"error\":\"quota-exceeded\",\"quota\":\"dailyBudget\",\"limit\":2,\"principal\":\"bob\",\"message\":\"Quota exceeded for bob: the daily budget of 2 operations is exhausted\"}"
+        );
+        server.test_status(
+            query("ASK {}", Some("Basic YWxpY2U6c2VjcmV0"))?,
+            StatusCode::OK,
+        )?;
+
+        // The requests without credentials share their own budget
+        server.test_status(query("ASK {}", None)?, StatusCode::OK)?;
+        server.test_status(query("ASK {}", None)?, StatusCode::OK)?;
+        let mut response = server.exec(query("ASK {}", None)?);
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            read_to_string(response.body_mut())?,
+            "{\"error\":\"quota-exceeded\",\"quota\":\"dailyBudget\",\"limit\":2,\"principal\":null,\"message\":\"Quota exceeded for the requests without credentials: the daily budget of 2 operations is exhausted\"}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn read_limits() -> Result<()> {
+        let mut server = ServerTest::new()?;
+        server.state.store.load_from_reader(
+            RdfFormat::NQuads,
+            "<http://example.com/s> <http://example.com/p> \"1\" .\n<http://example.com/s> <http://example.com/p> \"2\" .\n<http://example.com/s> <http://example.com/p> \"3\" <http://example.com/g> .".as_bytes(),
+        )?;
+        server.state = server.state.with_middleware(
+            AuthMiddleware::new()
+                .with_user("bob", "pwd", Role::Read)
+                .with_anonymous_role(Role::Read)
+                .with_role_limits(
+                    Role::Read,
+                    QueryLimits::new()
+                        .with_max_result_rows(2)
+                        .with_max_query_time(Duration::from_millis(100)),
+                )
+                .with_principal_limits("bob", QueryLimits::new().with_daily_budget(1)),
+        );
+        let read = |path: &str, authorization: Option<&str>| {
+            let mut request = Request::builder()
+                .uri(format!("http://localhost{path}"))
+                .header(ACCEPT, "application/n-quads");
+            if let Some(authorization) = authorization {
+                request = request.header("authorization", authorization);
+            }
+            request.body(())
+        };
+
+        // Max result rows
+        server.test_status(read("/store?default", None)?, StatusCode::OK)?;
+        let mut response = server.exec(read("/store", None)?);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            read_to_string(response.body_mut())?,
+            "{\"error\":\"quota-exceeded\",\"quota\":\"maxResultRows\",\"limit\":2,\"principal\":null,\"message\":\"Quota exceeded for the requests without credentials: the query returns more than 2 results\"}"
+        );
+        server.test_status(read("/fragments", None)?, StatusCode::FORBIDDEN)?;
+
+        // Max query time, the changes stream is closed
+        let mut response = server.exec(read("/changes", None)?);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(read_to_string(response.body_mut())?, ": keep-alive\n\n");
+
+        // Daily budget
+        server.test_status(
+            read(
+                "/store?graph=http://example.com/g",
+                Some("Basic Ym9iOnB3ZA=="),
+            )?,
+            StatusCode::OK,
+        )?;
+        server.test_status(
+            read(
+                "/store?graph=http://example.com/g",
+                Some("Basic Ym9iOnB3ZA=="),
+            )?,
+            StatusCode::TOO_MANY_REQUESTS,
+        )?;
+        server.test_status(
+            read("/changes", Some("Basic Ym9iOnB3ZA=="))?,
+            StatusCode::TOO_MANY_REQUESTS,
+        )
+    }
+
+    #[cfg(feature = "graphql")]
+    #[test]
+    fn graphql_limits() -> Result<()> {
+        let mut server = graphql_server()?;
+        server.state = server.state.with_middleware(
+            AuthMiddleware::new()
+                .with_anonymous_role(Role::Read)
+                .with_role_limits(Role::Read, QueryLimits::new().with_max_result_rows(1)),
+        );
+        let mut response = server.exec(
+            Request::builder()
+                .uri(format!(
+                    "http://localhost/graphql?query={}",
+                    form_urlencoded::byte_serialize(b"{ Person { id name } }").collect::<String>()
+                ))
+                .body(())?,
+        );
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            read_to_string(response.body_mut())?,
+            "{\"error\":\"quota-exceeded\",\"quota\":\"maxResultRows\",\"limit\":1,\"principal\":null,\"message\":\"Quota exceeded for the requests without credentials: the query returns more than 1 results\"}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn statistics_history() -> Result<()> {
+        let mut server = ServerTest::new()?;
+        let request = Request::builder()
+            .uri("http://localhost/statistics")
+            .body(())?;
+        server.test_status(request, StatusCode::NOT_FOUND)?;
+
+        let statistics_graph = NamedNode::new("http://example.com/statistics")?;
+        server.state = server.state.with_statistics_graph(statistics_graph.clone());
+        let request = Request::builder()
+            .uri("http://localhost/statistics")
+            .body(())?;
+        server.test_body(
+            request,
+            "{\"snapshots\":[],\"quadsPerDay\":null,\"termsPerDay\":null}",
+        )?;
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body("INSERT DATA { GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 1 } <http://example.com/s> <http://example.com/p> 2 , 3 }")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+        record_statistics(&server.state.store, &statistics_graph)?;
+        record_statistics(&server.state.store, &statistics_graph)?;
+
+        let mut response = server.exec(
+            Request::builder()
+                .uri("http://localhost/statistics")
+                .body(())?,
+        );
+        let body = read_to_string(response.body_mut())?;
+        assert_eq!(response.status(), StatusCode::OK, "Error message: {body}");
+        let snapshot = "\"quads\":3,\"terms\":6,\"graphs\":[{\"graph\":null,\"quads\":2},{\"graph\":\"http://example.com/g\",\"quads\":1}]}";
+        assert_eq!(body.matches(snapshot).count(), 2, "{body}");
+        assert!(body.contains("\"quadsPerDay\":"), "{body}");
+        Ok(())
+    }
+
+    #[test]
+    fn triple_pattern_fragments() -> Result<()> {
+        let server = ServerTest::new()?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body(
+                "INSERT { <http://example.com/s> <http://example.com/p> ?o } WHERE { VALUES ?i { 0 1 2 3 4 5 6 7 8 9 } VALUES ?j { 0 1 2 3 4 5 6 7 8 9 } BIND(10 * ?i + ?j AS ?o) } ; \
+                INSERT DATA { <http://example.com/s> <http://example.com/p> \"foo\"@en . GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 1 } }",
+            )?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        let fragment = |uri: &str| -> Result<Vec<Quad>> {
+            let mut response = server.exec(
+                Request::builder()
+                    .uri(uri)
+                    .header(ACCEPT, "application/n-quads")
+                    .body(())?,
+            );
+            let body = read_to_string(response.body_mut())?;
+            assert_eq!(response.status(), StatusCode::OK, "Error message: {body}");
+            Ok(RdfParser::from_format(RdfFormat::NQuads)
+                .for_slice(body.as_bytes())
+                .collect::<Result<_, _>>()?)
+        };
+        let fragment_url = "http://localhost/fragments?subject=http%3A%2F%2Fexample.com%2Fs";
+        let metadata = GraphName::from(NamedNode::new(format!("{fragment_url}#metadata"))?);
+        let quads = fragment(fragment_url)?;
+        assert_eq!(
+            quads
+                .iter()
+                .filter(|q| q.graph_name.is_default_graph())
+                .count(),
+            100
+        );
+        assert!(quads.contains(&Quad::new(
+            NamedNode::new(fragment_url)?,
+            NamedNode::new("http://www.w3.org/ns/hydra/core#totalItems")?,
+            Literal::from(101),
+            metadata.clone(),
+        )));
+        assert!(quads.contains(&Quad::new(
+            NamedNode::new(fragment_url)?,
+            NamedNode::new("http://www.w3.org/ns/hydra/core#next")?,
+            NamedNode::new(format!("{fragment_url}&page=2"))?,
+            metadata,
+        )));
+
+        let quads = fragment(&format!("{fragment_url}&page=2"))?;
+        assert_eq!(
+            quads
+                .iter()
+                .filter(|q| q.graph_name.is_default_graph())
+                .count(),
+            1
+        );
+        assert!(
+            quads
+                .iter()
+                .any(|q| q.predicate.as_str() == "http://www.w3.org/ns/hydra/core#previous")
+        );
+        assert!(
+            !quads
+                .iter()
+                .any(|q| q.predicate.as_str() == "http://www.w3.org/ns/hydra/core#next")
+        );
+
+        let quads = fragment("http://localhost/fragments?object=%22foo%22%40en&predicate=")?;
+        assert!(quads.contains(&Quad::new(
+            NamedNode::new("http://example.com/s")?,
+            NamedNode::new("http://example.com/p")?,
+            Literal::new_language_tagged_literal("foo", "en")?,
+            GraphName::DefaultGraph,
+        )));
+
+        let request = Request::builder()
+            .uri("http://localhost/fragments?predicate=%22foo%22")
+            .body(())?;
+        server.test_status(request, StatusCode::BAD_REQUEST)?;
+        let request = Request::builder()
+            .uri("http://localhost/fragments?page=0")
+            .body(())?;
+        server.test_status(request, StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn maintenance_mode() -> Result<()> {
+        let mut server = ServerTest::new()?;
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/admin/maintenance")
+            .body(())?;
+        server.test_status(request, StatusCode::NOT_FOUND)?;
+        server.state = server.state.with_maintenance_endpoint();
+
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/admin/maintenance")
+            .body(())?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+        let request = Request::builder()
+            .uri("http://localhost/admin/maintenance")
+            .body(())?;
+        server.test_body(request, "{\"enabled\":true,\"runningWrites\":0}")?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 }")?;
+        server.test_status(request, StatusCode::SERVICE_UNAVAILABLE)?;
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store?default")
+            .header(CONTENT_TYPE, "application/n-triples")
+            .body("<http://example.com/s> <http://example.com/p> 1 .")?;
+        server.test_status(request, StatusCode::SERVICE_UNAVAILABLE)?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/query")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .header(ACCEPT, "text/csv")
+            .body("ASK { ?s ?p ?o }")?;
+        server.test_body(request, "false")?;
+
+        let request = Request::builder()
+            .method(Method::DELETE)
+            .uri("http://localhost/admin/maintenance")
+            .body(())?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+        assert!(!server.state.maintenance_mode().is_enabled());
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 }")?;
+        server.test_status(request, StatusCode::NO_CONTENT)
+    }
+
+    #[test]
+    fn graph_quotas() -> Result<()> {
+        let mut server = ServerTest::new()?;
+        let request = Request::builder()
+            .uri("http://localhost/admin/quotas")
+            .body(())?;
+        server.test_status(request, StatusCode::NOT_FOUND)?;
+        server.state = SparqlServer::new(Store::new()?.with_graph_quota(
+            NamedNodeRef::new("http://example.com/g")?,
+            GraphQuota::default().with_max_quads(1),
+        ))
+        .with_graph_quota_endpoint();
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/update")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body("INSERT DATA { GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 1 } }")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store?graph=http://example.com/g")
+            .header(CONTENT_TYPE, "application/n-triples")
+            .body("<http://example.com/s> <http://example.com/p> \"2\" .")?;
+        server.test_status(request, StatusCode::INSUFFICIENT_STORAGE)?;
+        let request = Request::builder()
+            .uri("http://localhost/admin/quotas")
+            .body(())?;
+        server.test_body(
+            request,
+            "{\"graphs\":[{\"graph\":\"http://example.com/g\",\"maxQuads\":1,\"maxBytes\":null,\"quads\":1,\"bytes\":96}]}",
+        )
+    }
+
+    #[test]
+    fn maintenance_mode_drains_writes() {
+        let maintenance_mode = MaintenanceMode::default();
+        let write = maintenance_mode.start_write();
+        assert!(write.is_some());
+        assert!(!maintenance_mode.enable_with_timeout(Duration::from_millis(10)));
+        assert!(maintenance_mode.is_enabled());
+        assert_eq!(maintenance_mode.running_writes(), 1);
+        assert!(maintenance_mode.start_write().is_none());
+        drop(write);
+        assert!(maintenance_mode.enable_with_timeout(Duration::from_millis(10)));
+        maintenance_mode.disable();
+        assert!(maintenance_mode.start_write().is_some());
+    }
+
+    #[test]
+    fn saved_queries() -> Result<()> {
+        let server = ServerTest::new()?;
+
+        // PUT
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/queries/all-triples")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("SELECT * WHERE { ?s ?p ?o }")?;
+        server.test_status(request, StatusCode::CREATED)?;
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/queries/all-triples")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("ASK { ?s ?p ?o }")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET
+        let request = Request::builder()
+            .uri("http://localhost/queries/all-triples")
+            .body(())?;
+        server.test_body(request, "ASK { ?s ?p ?o }")?;
+        let request = Request::builder()
+            .uri("http://localhost/queries")
+            .header(ACCEPT, "text/csv")
+            .body(())?;
+        server.test_body(request, "name,query\r\nall-triples,ASK { ?s ?p ?o }\r\n")?;
+
+        // DELETE
+        let request = Request::builder()
+            .method(Method::DELETE)
+            .uri("http://localhost/queries/all-triples")
+            .body(())?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+        let request = Request::builder()
+            .uri("http://localhost/queries/all-triples")
+            .body(())?;
+        server.test_status(request, StatusCode::NOT_FOUND)
+    }
+
+    #[test]
+    fn saved_queries_scopes() -> Result<()> {
+        let saved_queries = Arc::new(SavedQueries::in_memory());
+        let mut server = ServerTest::new()?;
+        server.state = server
+            .state
+            .with_saved_queries(Arc::clone(&saved_queries))
+            .with_middleware(
+                AuthMiddleware::new()
+                    .with_user("alice", "secret", Role::Write)
+                    .with_user("bob", "pwd", Role::Write)
+                    .with_anonymous_role(Role::Read),
+            );
+        let get = |authorization: Option<&str>| {
+            let mut request = Request::builder().uri("http://localhost/queries/q");
+            if let Some(authorization) = authorization {
+                request = request.header("authorization", authorization);
+            }
+            request.body(())
+        };
+
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/queries/q")
+            .header("authorization", "Basic YWxpY2U6c2VjcmV0")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("ASK {}")?;
+        server.test_status(request, StatusCode::CREATED)?;
+        server.test_body(get(Some("Basic YWxpY2U6c2VjcmV0"))?, "ASK {}")?;
+        server.test_status(get(Some("Basic Ym9iOnB3ZA=="))?, StatusCode::NOT_FOUND)?;
+        server.test_status(get(None)?, StatusCode::NOT_FOUND)?;
+        let request = Request::builder()
+            .method(Method::DELETE)
+            .uri("http://localhost/queries/q")
+            .header("authorization", "Basic Ym9iOnB3ZA==")
+            .body(())?;
+        server.test_status(request, StatusCode::NOT_FOUND)?;
+
+        // Another dataset sharing the same saved queries
+        server.state = server.state.with_dataset_name("other");
+        server.test_status(get(Some("Basic YWxpY2U6c2VjcmV0"))?, StatusCode::NOT_FOUND)?;
+        assert_eq!(
+            saved_queries.get(Some(&Principal::new("alice")), "default", "q"),
+            Some("ASK {}".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn saved_queries_persistence() -> Result<()> {
+        let directory = assert_fs::TempDir::new()?;
+        let alice = Principal::new("alice/a");
+        let saved_queries = SavedQueries::open(directory.path().join("saved-queries"))?;
+        assert!(saved_queries.insert(Some(&alice), "default", "q", "ASK {}".into())?);
+        assert!(saved_queries.insert(None, "default", "q", "SELECT * {}".into())?);
+
+        let saved_queries = SavedQueries::open(directory.path().join("saved-queries"))?;
+        assert_eq!(
+            saved_queries.list(Some(&alice), "default"),
+            [("q".into(), "ASK {}".into())]
+        );
+        assert_eq!(
+            saved_queries.get(None, "default", "q"),
+            Some("SELECT * {}".into())
+        );
+        assert!(saved_queries.remove(Some(&alice), "default", "q")?);
+        assert!(!saved_queries.remove(Some(&alice), "default", "q")?);
+
+        // The query is kept if its file can't be removed
+        fs::remove_dir_all(
+            directory
+                .path()
+                .join("saved-queries")
+                .join("default")
+                .join("@anonymous"),
+        )?;
+        saved_queries.remove(None, "default", "q").unwrap_err();
+        assert_eq!(
+            saved_queries.get(None, "default", "q"),
+            Some("SELECT * {}".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn saved_queries_bad_requests() -> Result<()> {
+        let server = ServerTest::new()?;
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/queries/foo")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("SELECT")?;
+        server.test_status(request, StatusCode::BAD_REQUEST)?;
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/queries/foo%20bar")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("ASK {}")?;
+        server.test_status(request, StatusCode::BAD_REQUEST)?;
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/queries/foo")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("ASK {}")?;
+        ServerTest::check_status(server.exec_read_only(request), StatusCode::FORBIDDEN)
+    }
+
+    #[test]
+    fn sql() -> Result<()> {
+        let server = ServerTest::new()?;
+        server.state.store.load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            ex:alice ex:name "Alice" ; ex:age 42 .
+            ex:bob ex:name "Bob" ; ex:age 7 .
+            ex:carol ex:name "Carol" ."#
+                .as_bytes(),
+        )?;
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/queries/people")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("PREFIX ex: <http://example.com/> SELECT ?name ?age WHERE { ?s ex:name ?name OPTIONAL { ?s ex:age ?age } }")?;
+        server.test_status(request, StatusCode::CREATED)?;
+        let request = Request::builder()
+            .uri(format!(
+                "http://localhost/sql?query={}",
+                form_urlencoded::byte_serialize(
+                    b"SELECT name FROM people WHERE age > 10 OR age IS NULL"
+                )
+                .collect::<String>()
+            ))
+            .body(())?;
+        server.test_status(request, StatusCode::BAD_REQUEST)?;
+        let request = Request::builder()
+            .uri(format!(
+                "http://localhost/sql?query={}",
+                form_urlencoded::byte_serialize(b"SELECT name, age FROM people WHERE age >= 10")
+                    .collect::<String>()
+            ))
+            .header(ACCEPT, "text/csv")
+            .body(())?;
+        server.test_body(request, "name,age\r\nAlice,42\r\n")?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/sql")
+            .header(CONTENT_TYPE, "application/sql")
+            .header(ACCEPT, "text/csv")
+            .body(
+                "select * from \"people\" where name <> 'Bob' order by name desc limit 1 offset 1;",
+            )?;
+        server.test_body(request, "name,age\r\nAlice,42\r\n")?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/sql")
+            .header(CONTENT_TYPE, "application/sql")
+            .header(ACCEPT, "text/csv")
+            .body("SELECT name FROM people WHERE age IS NULL")?;
+        server.test_body(request, "name\r\nCarol\r\n")
+    }
+
+    #[test]
+    fn sql_bad_requests() -> Result<()> {
+        let server = ServerTest::new()?;
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/queries/ask")
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .body("ASK {}")?;
+        server.test_status(request, StatusCode::CREATED)?;
+        for (query, status) in [
+            ("SELECT * FROM unknown", StatusCode::NOT_FOUND),
+            ("SELECT * FROM ask", StatusCode::BAD_REQUEST),
+            ("DELETE FROM ask", StatusCode::BAD_REQUEST),
+            ("SELECT * FROM ask WHERE", StatusCode::BAD_REQUEST),
+        ] {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/sql")
+                .header(CONTENT_TYPE, "application/sql")
+                .body(query)?;
+            server.test_status(request, status)?;
+        }
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/sql")
+            .header(CONTENT_TYPE, "text/plain")
+            .body("SELECT * FROM ask")?;
+        server.test_status(request, StatusCode::UNSUPPORTED_MEDIA_TYPE)
+    }
+
+    #[cfg(feature = "graphql")]
+    fn graphql_server() -> Result<ServerTest> {
+        let shapes = r#"
+            @prefix sh: <http://www.w3.org/ns/shacl#> .
+            @prefix ex: <http://example.com/> .
+            ex:Person a sh:NodeShape ;
+                sh:targetClass ex:Person ;
+                sh:property [ sh:path ex:name ; sh:maxCount 1 ] ,
+                    [ sh:path ex:age ; sh:maxCount 1 ] ,
+                    [ sh:path ex:knows ; sh:class ex:Person ] ,
+                    [ sh:path [ sh:inversePath ex:knows ] ; sh:name "knownBy" ; sh:node ex:Person ] ."#;
+        let mut server = ServerTest::new()?;
+        server.state.graphql_schema = Some(Arc::new(GraphQlSchema::from_shacl_reader(
+            RdfFormat::Turtle,
+            shapes.as_bytes(),
+        )?));
+        server.state.store.load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            ex:alice a ex:Person ; ex:name "Alice" ; ex:age 42 ; ex:knows ex:bob .
+            ex:bob a ex:Person ; ex:name "Bob" ."#
+                .as_bytes(),
+        )?;
+        Ok(server)
+    }
+
+    #[cfg(feature = "graphql")]
+    #[test]
+    fn graphql_bad_requests() -> Result<()> {
+        let server = graphql_server()?;
+        for query in [
+            "{ Person { unknown } }",
+            "{ Person { knows } }",
+            "{ Person { name { id } } }",
+            "{ Person { ...Fields } }",
+            "mutation { Person { id } }",
+            "{ Person { id }",
+        ] {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/graphql")
+                .header(CONTENT_TYPE, "application/graphql")
+                .body(query)?;
+            server.test_status(request, StatusCode::BAD_REQUEST)?;
+        }
+        let request = Request::builder()
+            .uri("http://localhost/graphql?query=%7Bid%7D")
+            .body(())?;
+        ServerTest::new()?.test_status(request, StatusCode::NOT_FOUND)
+    }
+
+    #[test]
+    fn graph_store_url_normalization() -> Result<()> {
+        let server = ServerTest::new()?;
+
+        // PUT
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store?graph=http://example.com")
+            .header(CONTENT_TYPE, "text/turtle")
+            .body("<http://example.com> <http://example.com> <http://example.com> .")?;
+        server.test_status(request, StatusCode::CREATED)?;
+
+        // GET good URI
+        server.test_status(
+            Request::builder()
+                .uri("http://localhost/store?graph=http://example.com")
+                .body(())?,
+            StatusCode::OK,
+        )?;
+
+        // GET bad URI
+        server.test_status(
+            Request::builder()
+                .uri("http://localhost/store?graph=http://example.com/")
+                .body(())?,
+            StatusCode::NOT_FOUND,
+        )
+    }
+
+    #[test]
+    fn graph_store_base_url() -> Result<()> {
+        let server = ServerTest::new()?;
+
+        // POST
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store?graph=http://example.com")
+            .header(CONTENT_TYPE, "text/turtle")
+            .body("<> <http://example.com/p> <http://example.com/o1> .")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=http://example.com")
+            .header(ACCEPT, "application/n-triples")
+            .body(())?;
+        server.test_body(
+            request,
+            "<http://example.com> <http://example.com/p> <http://example.com/o1> .\n",
+        )?;
+
+        // PUT
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store?graph=http://example.com")
+            .header(CONTENT_TYPE, "text/turtle")
+            .body("<> <http://example.com/p> <http://example.com/o2> .")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=http://example.com")
+            .header(ACCEPT, "application/n-triples")
+            .body(())?;
+        server.test_body(
+            request,
+            "<http://example.com> <http://example.com/p> <http://example.com/o2> .\n",
+        )
+    }
+
+    #[test]
+    fn graph_store_protocol() -> Result<()> {
+        // Tests from https://www.w3.org/2009/sparql/docs/tests/data-sparql11/http-rdf-update/
+
+        let server = ServerTest::new()?;
+
+        // PUT - Initial state
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/person/1.ttl")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(
+                r#"
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix v: <http://www.w3.org/2006/vcard/ns#> .
+
+<http://$HOST$/$GRAPHSTORE$/person/1> a foaf:Person;
+    foaf:businessCard [
+        a v:VCard;
+        v:fn "John Doe"
+    ].
+"#,
+            )?;
+        server.test_status(request, StatusCode::CREATED)?;
+
+        // GET of PUT - Initial state
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=/store/person/1.ttl")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // HEAD on an existing graph
+        server.test_status(
+            Request::builder()
+                .method(Method::HEAD)
+                .uri("http://localhost/store/person/1.ttl")
+                .body(())?,
+            StatusCode::OK,
+        )?;
+
+        // HEAD on a non-existing graph
+        server.test_status(
+            Request::builder()
+                .method(Method::HEAD)
+                .uri("http://localhost/store/person/4.ttl")
+                .body(())?,
+            StatusCode::NOT_FOUND,
+        )?;
+
+        // PUT - graph already in store
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/person/1.ttl")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(
+                r#"
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix v: <http://www.w3.org/2006/vcard/ns#> .
+
+<http://$HOST$/$GRAPHSTORE$/person/1> a foaf:Person;
+    foaf:businessCard [
+        a v:VCard;
+        v:fn "Jane Doe"
+    ].
+"#,
+            )?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET of PUT - graph already in store
+        let request = Request::builder()
+            .uri("http://localhost/store/person/1.ttl")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // PUT - default graph
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store?default")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(
+                r#"
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix v: <http://www.w3.org/2006/vcard/ns#> .
+
+[]  a foaf:Person;
+    foaf:businessCard [
+        a v:VCard;
+        v:given-name "Alice"
+    ] .
+"#,
+            )?;
+        server.test_status(request, StatusCode::NO_CONTENT)?; // The default graph always exists in Oxigraph
+
+        // GET of PUT - default graph
+        let request = Request::builder()
+            .uri("http://localhost/store?default")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // PUT - mismatched payload
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/person/1.ttl")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body("@prefix foo")?;
+        server.test_status(request, StatusCode::BAD_REQUEST)?;
+
+        // PUT - empty graph
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/person/2.ttl")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(())?;
+        server.test_status(request, StatusCode::CREATED)?;
+
+        // GET of PUT - empty graph
+        let request = Request::builder()
+            .uri("http://localhost/store/person/2.ttl")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // PUT - replace empty graph
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/person/2.ttl")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(
+                r#"
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix v: <http://www.w3.org/2006/vcard/ns#> .
+
+[]  a foaf:Person;
+    foaf:businessCard [
+        a v:VCard;
+        v:given-name "Alice"
+    ] .
+"#,
+            )?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET of replacement for empty graph
+        let request = Request::builder()
+            .uri("http://localhost/store/person/2.ttl")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // DELETE - existing graph
+        server.test_status(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri("http://localhost/store/person/2.ttl")
+                .body(())?,
+            StatusCode::NO_CONTENT,
+        )?;
+
+        // GET of DELETE - existing graph
+        server.test_status(
+            Request::builder()
+                .uri("http://localhost/store/person/2.ttl")
+                .body(())?,
+            StatusCode::NOT_FOUND,
+        )?;
+
+        // DELETE - non-existent graph
+        server.test_status(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri("http://localhost/store/person/2.ttl")
+                .body(())?,
+            StatusCode::NOT_FOUND,
+        )?;
+
+        // POST - existing graph
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/person/1.ttl")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(())?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // TODO: POST - multipart/form-data
+        // TODO: GET of POST - multipart/form-data
+
+        // POST - create new graph
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(
+                r#"
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix v: <http://www.w3.org/2006/vcard/ns#> .
+
+[]  a foaf:Person;
+    foaf:businessCard [
+        a v:VCard;
+        v:given-name "Alice"
+    ] .
+"#,
+            )?;
+        let response = server.exec(request);
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let location = response.headers().get(LOCATION).unwrap().to_str()?;
+
+        // GET of POST - create new graph
+        let request = Request::builder()
+            .uri(location)
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // POST - empty graph to existing graph
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri(location)
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(())?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET of POST - after noop
+        let request = Request::builder()
+            .uri(location)
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)
+    }
+
+    #[test]
+    fn compressed_bodies() -> Result<()> {
+        use flate2::read::MultiGzDecoder;
+        use flate2::write::GzEncoder;
+        use oxhttp::model::header::{ACCEPT_ENCODING, VARY};
+
+        let server = ServerTest::new()?;
+        let update = zstd::encode_all(
+            "INSERT DATA { <http://example.com/s> <http://example.com/p> 1 }".as_bytes(),
+            0,
+        )?;
+        server.test_status(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/update")
+                .header(CONTENT_TYPE, "application/sparql-update")
+                .header(CONTENT_ENCODING, "zstd")
+                .body(update)?,
+            StatusCode::NO_CONTENT,
+        )?;
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"<http://example.com/s> <http://example.com/p> 2 .")?;
+        server.test_status(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/store?default")
+                .header(CONTENT_TYPE, "text/turtle")
+                .header(CONTENT_ENCODING, "gzip")
+                .body(encoder.finish()?)?,
+            StatusCode::CREATED,
+        )?;
+        server.test_status(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/update")
+                .header(CONTENT_TYPE, "application/sparql-update")
+                .header(CONTENT_ENCODING, "br")
+                .body("INSERT DATA {}")?,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        )?;
+
+        let query = "http://localhost/query?query=SELECT%20%3Fo%20WHERE%20%7B%20%3Fs%20%3Fp%20%3Fo%20%7D%20ORDER%20BY%20%3Fo";
+        let mut response = server.exec(
+            Request::builder()
+                .uri(query)
+                .header(ACCEPT, "text/csv")
+                .header(ACCEPT_ENCODING, "br, gzip;q=0.5, zstd;q=0.8")
+                .body(())?,
+        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(CONTENT_ENCODING).unwrap(), "zstd");
+        assert_eq!(zstd::decode_all(response.body_mut())?, b"o\r\n1\r\n2\r\n");
+        let mut response = server.exec(
+            Request::builder()
+                .uri("http://localhost/store?default")
+                .header(ACCEPT, "application/n-triples")
+                .header(ACCEPT_ENCODING, "gzip")
+                .body(())?,
+        );
+        assert_eq!(response.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        let dump = read_to_string(MultiGzDecoder::new(response.body_mut()))?;
+        let mut lines = dump.lines().collect::<Vec<_>>();
+        lines.sort_unstable();
+        assert_eq!(
+            lines,
+            [
+                "<http://example.com/s> <http://example.com/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .",
+                "<http://example.com/s> <http://example.com/p> \"2\"^^<http://www.w3.org/2001/XMLSchema#integer> ."
+            ]
+        );
+        let response = server.exec(
+            Request::builder()
+                .uri(query)
+                .header(ACCEPT, "text/csv")
+                .body(())?,
+        );
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(response.headers().get(VARY).unwrap(), "Accept-Encoding");
+
+        // The entity tags of the compressed representations are weak
+        let mut response = Response::builder()
+            .header(ETAG, "\"abc\"")
+            .body(Body::from(vec![b'a'; 2048]))?;
+        encode_response_body(
+            &Request::builder()
+                .header(ACCEPT_ENCODING, "gzip")
+                .body(Body::empty())?,
+            &mut response,
+        );
+        assert_eq!(response.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(response.headers().get(ETAG).unwrap(), "W/\"abc\"");
+        assert_eq!(response.headers().get(VARY).unwrap(), "Accept-Encoding");
+        let mut response = Response::builder()
+            .header(ETAG, "\"abc\"")
+            .body(Body::from(vec![b'a'; 2048]))?;
+        encode_response_body(&Request::builder().body(Body::empty())?, &mut response);
+        assert_eq!(response.headers().get(ETAG).unwrap(), "\"abc\"");
+        assert_eq!(response.headers().get(VARY).unwrap(), "Accept-Encoding");
+        Ok(())
+    }
+
+    #[test]
+    fn graph_store_patch() -> Result<()> {
+        let server = ServerTest::new()?;
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store?graph=http://example.com/g")
+            .header(CONTENT_TYPE, "application/n-triples")
+            .body("<http://example.com/s> <http://example.com/p> \"old\" .\n")?;
+        server.test_status(request, StatusCode::CREATED)?;
+
+        // RDF Patch
+        let request = Request::builder()
+            .method(Method::PATCH)
+            .uri("http://localhost/store?graph=http://example.com/g")
+            .header(CONTENT_TYPE, "application/rdf-patch")
+            .body(
+                "TX .\n\
+                D <http://example.com/s> <http://example.com/p> \"old\" .\n\
+                A <http://example.com/s> <http://example.com/p> \"new\" <http://example.com/g> .\n\
+                TC .\n\
+                TX .\n\
+                A <http://example.com/s> <http://example.com/p> \"aborted\" .\n\
+                TA .\n",
+            )?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=http://example.com/g")
+            .header(ACCEPT, "application/n-triples")
+            .body(())?;
+        server.test_body(
+            request,
+            "<http://example.com/s> <http://example.com/p> \"new\" .\n",
+        )?;
+
+        // A change outside of the target graph is rejected and nothing is applied
+        let request = Request::builder()
+            .method(Method::PATCH)
+            .uri("http://localhost/store?graph=http://example.com/g")
+            .header(CONTENT_TYPE, "application/rdf-patch")
+            .body(
+                "D <http://example.com/s> <http://example.com/p> \"new\" .\n\
+                A <http://example.com/s> <http://example.com/p> \"other\" <http://example.com/g2> .\n",
+            )?;
+        server.test_status(request, StatusCode::BAD_REQUEST)?;
+
+        // SPARQL update
+        let request = Request::builder()
+            .method(Method::PATCH)
+            .uri("http://localhost/store?graph=http://example.com/g")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body(
+                "WITH <http://example.com/g> DELETE { ?s ?p ?o } INSERT { ?s ?p \"newer\" } WHERE { ?s ?p ?o }",
+            )?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+        let request = Request::builder()
+            .method(Method::PATCH)
+            .uri("http://localhost/store?graph=http://example.com/g")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body("INSERT DATA { <http://example.com/s> <http://example.com/p> \"default\" }")?;
+        server.test_status(request, StatusCode::BAD_REQUEST)?;
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=http://example.com/g")
+            .header(ACCEPT, "application/n-triples")
+            .body(())?;
+        server.test_body(
+            request,
+            "<http://example.com/s> <http://example.com/p> \"newer\" .\n",
+        )?;
+
+        let request = Request::builder()
+            .method(Method::PATCH)
+            .uri("http://localhost/store?graph=http://example.com/g")
+            .header(CONTENT_TYPE, "text/turtle")
+            .body("")?;
+        server.test_status(request, StatusCode::UNSUPPORTED_MEDIA_TYPE)
+    }
+
+    #[test]
+    fn graph_store_lenient_bulk() -> Result<()> {
+        let server = ServerTest::new()?;
+        let invalid_data = "
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix v: <http://www.w3.org/2006/vcard/ns#> .
+
+<http://$HOST$/$GRAPHSTORE$/person/1> a foaf:Person . foo";
+
+        // POST
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store/person/1.ttl?no_transaction&lenient")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(invalid_data)?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET of POST
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=/store/person/1.ttl")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // POST dataset
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store?lenient&no_transaction")
+            .header(CONTENT_TYPE, "application/trig; charset=utf-8")
+            .body(invalid_data)?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET of POST dataset
+        let request = Request::builder()
+            .uri("http://localhost/store?default")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // PUT
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store/person/1.ttl?lenient&no_transaction")
+            .header(CONTENT_TYPE, "text/turtle; charset=utf-8")
+            .body(invalid_data)?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET of PUT - Initial state
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=/store/person/1.ttl")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // PUT dataset
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("http://localhost/store?lenient&no_transaction")
+            .header(CONTENT_TYPE, "application/trig; charset=utf-8")
+            .body(invalid_data)?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET of PUT dataset
+        let request = Request::builder()
+            .uri("http://localhost/store?default")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::OK)?;
+
+        // GET of PUT dataset - replacement
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=/store/person/1.ttl")
+            .header(ACCEPT, "text/turtle")
+            .body(())?;
+        server.test_status(request, StatusCode::NOT_FOUND)
+    }
+
+    #[test]
+    fn lenient_load() -> Result<()> {
+        let server = ServerTest::new()?;
+
+        // POST
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store?lenient&graph=http://example.com")
+            .header(CONTENT_TYPE, "text/turtle")
+            .body("< s> < p> \"\\uD83D\\uDC68\" .")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=http://example.com")
+            .header(ACCEPT, "application/n-triples")
+            .body(())?;
+        server.test_body(
+            request,
+            "<http://example.com/ s> <http://example.com/ p> \"\u{1f468}\" .\n",
+        )?;
+
+        // PUT
+        let request = Request::builder().method(Method::PUT).uri(
+            "http://localhost/store?lenient&graph=http://example.com",
+        )
+        .header(CONTENT_TYPE, "text/turtle")
+        .body("< s> < p> \"\\uD83D\\uDC68\\u200D\\uD83D\\uDC69\\u200D\\uD83D\\uDC67\\u200D\\uD83D\\uDC67\" .")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET
+        let request = Request::builder()
+            .uri("http://localhost/store?graph=http://example.com")
+            .header(ACCEPT, "application/n-triples")
+            .body(())?;
+        server.test_body(
+            request,
+            "<http://example.com/ s> <http://example.com/ p> \"\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f467}\" .\n",
+        )?;
+
+        // POST dataset
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/store?lenient")
+            .header(CONTENT_TYPE, "application/trig")
+            .body("<s> <p> \"\"@abcdefghijklmn .")?;
+        server.test_status(request, StatusCode::NO_CONTENT)?;
+
+        // GET
+        let request = Request::builder()
+            .uri("http://localhost/store")
+            .header(ACCEPT, "application/n-quads")
+            .body(())?;
+        server.test_body(request, "<s> <p> \"\"@abcdefghijklmn .\n<http://example.com/ s> <http://example.com/ p> \"\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f467}\" <http://example.com> .\n")
+    }
+
+    struct ServerTest {
+        state: SparqlServer,
+    }
+
+    impl ServerTest {
+        fn new() -> Result<Self> {
+            Ok(Self {
+                state: SparqlServer::new(Store::new()?),
+            })
+        }
+
+        fn exec(&self, request: Request<impl Into<Body>>) -> Response<Body> {
+            self.state.handle(&mut request.map(Into::into))
+        }
+
+        fn exec_read_only(&self, request: Request<impl Into<Body>>) -> Response<Body> {
+            self.state
+                .clone()
+                .with_read_only()
+                .handle(&mut request.map(Into::into))
+        }
+
+        fn test_status(
+            &self,
+            request: Request<impl Into<Body>>,
+            expected_status: StatusCode,
+        ) -> Result<()> {
+            Self::check_status(self.exec(request), expected_status)
+        }
+
+        fn check_status(mut response: Response<Body>, expected_status: StatusCode) -> Result<()> {
+            let body = read_to_string(response.body_mut())?;
+            assert_eq!(response.status(), expected_status, "Error message: {body}");
+            Ok(())
+        }
+
+        fn test_body(&self, request: Request<impl Into<Body>>, expected_body: &str) -> Result<()> {
+            let mut response = self.exec(request);
+            let body = read_to_string(response.body_mut())?;
+            assert_eq!(response.status(), StatusCode::OK, "Error message: {body}");
+            assert_eq!(&body, expected_body);
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "graphql")]
+    #[test]
+    fn graphql_get() -> Result<()> {
+        let request = Request::builder()
+            .uri(format!(
+                "http://localhost/graphql?query={}",
+                form_urlencoded::byte_serialize(
+                    b"{ Person { id name age knows { name } knownBy { name } } }"
+                )
+                .collect::<String>()
+            ))
+            .body(())?;
+        graphql_server()?.test_body(
+            request,
+            r#"{"data":{"Person":[{"id":"http://example.com/alice","name":"Alice","age":42,"knows":[{"name":"Bob"}],"knownBy":[]},{"id":"http://example.com/bob","name":"Bob","age":null,"knows":[],"knownBy":[{"name":"Alice"}]}]}}"#,
+        )
+    }
+
+    #[cfg(feature = "graphql")]
+    #[test]
+    fn graphql_post() -> Result<()> {
+        let server = graphql_server()?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/graphql")
+            .header(CONTENT_TYPE, "application/json")
+            .body(r#"{"query":"query Q($name: String) { people: Person(name: $name) { name __typename } }","variables":{"name":"Bob"}}"#)?;
+        server.test_body(
+            request,
+            r#"{"data":{"people":[{"name":"Bob","__typename":"Person"}]}}"#,
+        )?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/graphql")
+            .header(CONTENT_TYPE, "application/graphql")
+            .body("{ Person(limit: 1, offset: 1) { id } }")?;
+        server.test_body(
+            request,
+            r#"{"data":{"Person":[{"id":"http://example.com/bob"}]}}"#,
+        )
+    }
+}
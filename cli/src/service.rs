@@ -0,0 +1,159 @@
+//! [`tower_service::Service`] implementation of [`SparqlServer`] for async web applications.
+use crate::server::SparqlServer;
+use bytes::Bytes;
+use http_body::Body as HttpBody;
+use http_body_util::{BodyExt, Full};
+use oxhttp::model::header::{CONTENT_TYPE, HOST};
+use oxhttp::model::uri::{Authority, Scheme};
+use oxhttp::model::{Body, HeaderValue, Request, Response, StatusCode, Uri};
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+/// The requests are handled by [`SparqlServer::handle`] in a [`tokio::task::spawn_blocking`] task.
+///
+/// The request body is fully read before the request is handled and the response body is fully written before being returned.
+/// URIs without authority, like the ones given by HTTP/2 servers, are resolved against the `Host` header.
+///
+/// ```
+/// use http_body_util::{BodyExt, Empty};
+/// use oxhttp::model::{Request, StatusCode};
+/// use oxigraph::store::Store;
+/// use oxigraph_server::SparqlServer;
+/// use tower_service::Service;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut server = SparqlServer::new(Store::new()?);
+/// let request = Request::builder()
+///     .uri("/query?query=ASK%7B%7D")
+///     .header("host", "localhost")
+///     .header("accept", "application/sparql-results+json")
+///     .body(Empty::<bytes::Bytes>::new())?;
+/// let response = server.call(request).await?;
+/// assert_eq!(response.status(), StatusCode::OK);
+/// assert_eq!(
+///     response.into_body().collect().await?.to_bytes(),
+///     "{\"head\":{},\"boolean\":true}"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+impl<B> Service<Request<B>> for SparqlServer
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    type Response = Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let server = self.clone();
+        Box::pin(async move {
+            let (mut parts, body) = req.into_parts();
+            let body = match body.collect().await {
+                Ok(body) => body.to_bytes(),
+                Err(e) => {
+                    return Ok(error(
+                        StatusCode::BAD_REQUEST,
+                        format!("Failed to read the request body: {}", e.into()),
+                    ));
+                }
+            };
+            if parts.uri.authority().is_none() {
+                match absolute_uri(parts.uri, parts.headers.get(HOST)) {
+                    Ok(uri) => parts.uri = uri,
+                    Err(message) => return Ok(error(StatusCode::BAD_REQUEST, message)),
+                }
+            }
+            let response = tokio::task::spawn_blocking(move || {
+                let mut request = Request::from_parts(parts, Body::from(body.to_vec()));
+                let (parts, body) = server.handle(&mut request).into_parts();
+                body.to_vec()
+                    .map(|body| Response::from_parts(parts, Full::new(Bytes::from(body))))
+            })
+            .await;
+            Ok(match response {
+                Ok(Ok(response)) => response,
+                Ok(Err(e)) => error(StatusCode::INTERNAL_SERVER_ERROR, e),
+                Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e),
+            })
+        })
+    }
+}
+
+fn absolute_uri(uri: Uri, host: Option<&HeaderValue>) -> Result<Uri, String> {
+    let host = host.ok_or("The request URI has no authority and no Host header is set")?;
+    let mut parts = uri.into_parts();
+    parts.authority = Some(
+        Authority::try_from(host.as_bytes()).map_err(|e| format!("Invalid Host header: {e}"))?,
+    );
+    if parts.scheme.is_none() {
+        parts.scheme = Some(Scheme::HTTP);
+    }
+    Uri::from_parts(parts).map_err(|e| format!("Invalid request URI: {e}"))
+}
+
+fn error(status: StatusCode, message: impl fmt::Display) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(Full::new(Bytes::from(message.to_string())))
+        .unwrap()
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use http_body_util::Empty;
+    use oxhttp::model::Method;
+    use oxigraph::store::Store;
+
+    #[tokio::test]
+    async fn update_and_query() -> Result<(), Box<dyn Error>> {
+        let mut server = SparqlServer::new(Store::new()?);
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/update")
+            .header(HOST, "localhost")
+            .header(CONTENT_TYPE, "application/sparql-update")
+            .body(Full::new(Bytes::from(
+                "INSERT DATA { <s> <http://example.com/p> <http://example.com/o> }",
+            )))?;
+        assert_eq!(server.call(request).await?.status(), StatusCode::NO_CONTENT);
+
+        let request = Request::builder()
+            .uri("/query?query=SELECT%20?s%20WHERE%20%7B?s%20?p%20?o%7D")
+            .header(HOST, "example.com:8080")
+            .header("accept", "text/csv")
+            .body(Empty::<Bytes>::new())?;
+        let response = server.call(request).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.into_body().collect().await?.to_bytes(),
+            "s\r\nhttp://localhost/s\r\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn no_host() -> Result<(), Box<dyn Error>> {
+        let request = Request::builder()
+            .uri("/query?query=ASK%7B%7D")
+            .body(Empty::<Bytes>::new())?;
+        let response = SparqlServer::new(Store::new()?).call(request).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        Ok(())
+    }
+}
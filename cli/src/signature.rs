@@ -0,0 +1,82 @@
+//! Detached signatures of the dumped data.
+//!
+//! The signature is the [Ed25519](https://www.rfc-editor.org/rfc/rfc8032) signature of the canonical N-Quads serialization of the data:
+//! blank nodes canonicalized and lines sorted like in the [`publish`](crate::publish) bundles.
+//! It does not depend on the file syntax, so a dump converted to another format still has a valid signature.
+//! It is written raw (64 bytes) next to the signed file with the `.sig` extension.
+//!
+//! The keys are PEM files as written by `openssl genpkey -algorithm ed25519` (PKCS#8 private key)
+//! and `openssl pkey -pubout` (SubjectPublicKeyInfo public key).
+use anyhow::{Context, ensure};
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use oxigraph::model::Dataset;
+use oxigraph::model::dataset::CanonicalizationAlgorithm;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Returns the canonical N-Quads serialization of the dataset
+///
+/// <div class="warning">The blank node canonicalization is not stable between Oxigraph versions:
+/// data with blank nodes should be verified with the Oxigraph version used to sign it.</div>
+pub fn canonical_n_quads(mut dataset: Dataset) -> Vec<u8> {
+    dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
+    let mut lines = dataset
+        .iter()
+        .map(|quad| format!("{quad} .\n"))
+        .collect::<Vec<_>>();
+    lines.sort_unstable();
+    lines.concat().into_bytes()
+}
+
+/// The file in which the signature of `file` is stored
+pub fn signature_path(file: &Path) -> PathBuf {
+    let mut path = OsString::from(file);
+    path.push(".sig");
+    path.into()
+}
+
+/// Signs the canonical form of the data with the private key stored in `key_file` and writes the signature next to `file`
+pub fn write_signature(file: &Path, data: Dataset, key_file: &Path) -> anyhow::Result<()> {
+    let key = SigningKey::from_pkcs8_pem(
+        &fs::read_to_string(key_file)
+            .with_context(|| format!("Not able to read the private key {}", key_file.display()))?,
+    )
+    .with_context(|| {
+        format!(
+            "The private key {} is not a PEM Ed25519 key",
+            key_file.display()
+        )
+    })?;
+    let signature = key.sign(&canonical_n_quads(data));
+    let signature_file = signature_path(file);
+    fs::write(&signature_file, signature.to_bytes())
+        .with_context(|| format!("Not able to write {}", signature_file.display()))
+}
+
+/// Checks that the signature next to `file` is a valid signature of the data with the public key stored in `key_file`
+pub fn verify_signature(file: &Path, data: Dataset, key_file: &Path) -> anyhow::Result<()> {
+    let key = VerifyingKey::from_public_key_pem(
+        &fs::read_to_string(key_file)
+            .with_context(|| format!("Not able to read the public key {}", key_file.display()))?,
+    )
+    .with_context(|| {
+        format!(
+            "The public key {} is not a PEM Ed25519 key",
+            key_file.display()
+        )
+    })?;
+    let signature_file = signature_path(file);
+    let signature = fs::read(&signature_file)
+        .with_context(|| format!("Not able to read {}", signature_file.display()))?;
+    let signature = Signature::from_slice(&signature)
+        .with_context(|| format!("{} is not an Ed25519 signature", signature_file.display()))?;
+    ensure!(
+        key.verify(&canonical_n_quads(data), &signature).is_ok(),
+        "The signature {} does not match the content of {}",
+        signature_file.display(),
+        file.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,482 @@
+//! A read-only SQL layer over the saved SPARQL `SELECT` queries.
+//!
+//! Each saved `SELECT` query is exposed as a table whose columns are its projected variables.
+//! The supported SQL subset is:
+//! ```sql
+//! SELECT [DISTINCT] (* | column, ...) FROM table
+//! [WHERE condition AND ...]
+//! [ORDER BY column [ASC | DESC], ...]
+//! [LIMIT count] [OFFSET count]
+//! ```
+//! where conditions are either comparisons (`=`, `<>`, `!=`, `<`, `<=`, `>`, `>=`) between a column and a literal
+//! or `column IS [NOT] NULL`.
+//!
+//! SQL queries are translated into SPARQL queries wrapping the algebra of the saved query.
+//! String literals are compared to the lexical form of the RDF terms and numbers and booleans to their values.
+use oxigraph::model::Literal;
+use oxigraph::model::vocab::xsd;
+use spargebra::algebra::{Expression, Function, GraphPattern, OrderExpression};
+use spargebra::{Query, SparqlParser};
+use std::{error, fmt};
+
+/// An error raised while parsing or translating a SQL query.
+#[derive(Debug)]
+pub struct SqlError(String);
+
+impl SqlError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for SqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl error::Error for SqlError {}
+
+/// A parsed SQL `SELECT` query.
+#[derive(Debug, PartialEq)]
+pub struct SqlQuery {
+    /// `None` for `*`
+    columns: Option<Vec<String>>,
+    distinct: bool,
+    table: String,
+    conditions: Vec<Condition>,
+    order_by: Vec<(String, bool)>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum Condition {
+    Compare(String, Comparison, SqlValue),
+    IsNull(String, bool),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, PartialEq)]
+enum SqlValue {
+    String(String),
+    Number(String),
+    Boolean(bool),
+}
+
+impl SqlQuery {
+    pub fn parse(query: &str) -> Result<Self, SqlError> {
+        let mut parser = Parser {
+            tokens: tokenize(query)?,
+            position: 0,
+        };
+        let query = parser.parse_select()?;
+        if parser.peek() == Some(&Token::Symbol(";")) {
+            parser.position += 1;
+        }
+        if let Some(token) = parser.peek() {
+            return Err(SqlError::new(format!(
+                "Unexpected {token} at the end of the query"
+            )));
+        }
+        Ok(query)
+    }
+
+    /// The name of the table, i.e. of the saved query, the query reads from.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Builds the SPARQL query evaluating this SQL query against the given table query.
+    pub fn to_sparql(&self, table_query: &str, base: &str) -> Result<Query, SqlError> {
+        let Query::Select {
+            dataset,
+            mut pattern,
+            base_iri,
+        } = SparqlParser::new()
+            .with_base_iri(base)
+            .map_err(|e| SqlError::new(e.to_string()))?
+            .parse_query(table_query)
+            .map_err(|e| SqlError::new(format!("Invalid query for table {}: {e}", self.table)))?
+        else {
+            return Err(SqlError::new(format!(
+                "The saved query {} is not a SELECT query and can't be used as a table",
+                self.table
+            )));
+        };
+        let mut table_columns = Vec::new();
+        pattern.on_in_scope_variable(|v| {
+            if !table_columns.contains(v) {
+                table_columns.push(v.clone());
+            }
+        });
+        let column = |name: &str| {
+            table_columns
+                .iter()
+                .find(|v| v.as_str() == name)
+                .cloned()
+                .ok_or_else(|| {
+                    SqlError::new(format!("The table {} has no column {name}", self.table))
+                })
+        };
+
+        let mut filter = None;
+        for condition in &self.conditions {
+            let expression = match condition {
+                Condition::Compare(name, comparison, value) => {
+                    let variable = Expression::from(column(name)?);
+                    let (left, right) = match value {
+                        SqlValue::String(value) => (
+                            Expression::FunctionCall(Function::Str, vec![variable]),
+                            Literal::new_simple_literal(value),
+                        ),
+                        SqlValue::Number(value) => (
+                            variable,
+                            if value.contains(['e', 'E']) {
+                                Literal::new_typed_literal(value, xsd::DOUBLE)
+                            } else if value.contains('.') {
+                                Literal::new_typed_literal(value, xsd::DECIMAL)
+                            } else {
+                                Literal::new_typed_literal(value, xsd::INTEGER)
+                            },
+                        ),
+                        SqlValue::Boolean(value) => (variable, Literal::from(*value)),
+                    };
+                    let (left, right) = (Box::new(left), Box::new(right.into()));
+                    match comparison {
+                        Comparison::Equal => Expression::Equal(left, right),
+                        Comparison::NotEqual => {
+                            Expression::Not(Box::new(Expression::Equal(left, right)))
+                        }
+                        Comparison::Less => Expression::Less(left, right),
+                        Comparison::LessOrEqual => Expression::LessOrEqual(left, right),
+                        Comparison::Greater => Expression::Greater(left, right),
+                        Comparison::GreaterOrEqual => Expression::GreaterOrEqual(left, right),
+                    }
+                }
+                Condition::IsNull(name, is_null) => {
+                    let bound = Expression::Bound(column(name)?);
+                    if *is_null {
+                        Expression::Not(Box::new(bound))
+                    } else {
+                        bound
+                    }
+                }
+            };
+            filter = Some(if let Some(filter) = filter {
+                Expression::And(Box::new(filter), Box::new(expression))
+            } else {
+                expression
+            });
+        }
+        if let Some(expr) = filter {
+            pattern = GraphPattern::Filter {
+                expr,
+                inner: Box::new(pattern),
+            };
+        }
+        if !self.order_by.is_empty() {
+            pattern = GraphPattern::OrderBy {
+                inner: Box::new(pattern),
+                expression: self
+                    .order_by
+                    .iter()
+                    .map(|(name, ascending)| {
+                        let variable = Expression::from(column(name)?);
+                        Ok(if *ascending {
+                            OrderExpression::Asc(variable)
+                        } else {
+                            OrderExpression::Desc(variable)
+                        })
+                    })
+                    .collect::<Result<_, SqlError>>()?,
+            };
+        }
+        pattern = GraphPattern::Project {
+            inner: Box::new(pattern),
+            variables: if let Some(columns) = &self.columns {
+                columns
+                    .iter()
+                    .map(|name| column(name))
+                    .collect::<Result<_, _>>()?
+            } else {
+                table_columns.clone()
+            },
+        };
+        if self.distinct {
+            pattern = GraphPattern::Distinct {
+                inner: Box::new(pattern),
+            };
+        }
+        if self.limit.is_some() || self.offset > 0 {
+            pattern = GraphPattern::Slice {
+                inner: Box::new(pattern),
+                start: self.offset,
+                length: self.limit,
+            };
+        }
+        Ok(Query::Select {
+            dataset,
+            pattern,
+            base_iri,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// Unquoted identifiers and keywords
+    Word(String),
+    QuotedIdentifier(String),
+    String(String),
+    Number(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word(w) | Self::Number(w) => f.write_str(w),
+            Self::QuotedIdentifier(w) => write!(f, "\"{w}\""),
+            Self::String(w) => write!(f, "'{w}'"),
+            Self::Symbol(s) => f.write_str(s),
+        }
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, SqlError> {
+    const SYMBOLS: [&str; 10] = ["<>", "!=", "<=", ">=", "=", "<", ">", ",", "*", ";"];
+    let mut tokens = Vec::new();
+    let mut rest = query;
+    loop {
+        rest = rest.trim_start();
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.find('\n').map_or("", |end| &comment[end..]);
+            continue;
+        }
+        let Some(c) = rest.chars().next() else {
+            return Ok(tokens);
+        };
+        if let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        } else if c == '_' || c.is_ascii_alphabetic() {
+            let end = rest
+                .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len());
+            tokens.push(Token::Word(rest[..end].into()));
+            rest = &rest[end..];
+        } else if c.is_ascii_digit() || c == '-' || c == '.' {
+            let end = rest[1..]
+                .find(|c: char| !c.is_ascii_digit() && !matches!(c, '.' | 'e' | 'E'))
+                .map_or(rest.len(), |e| e + 1);
+            let number = &rest[..end];
+            if number.parse::<f64>().is_err() {
+                return Err(SqlError::new(format!("Invalid number {number}")));
+            }
+            tokens.push(Token::Number(number.into()));
+            rest = &rest[end..];
+        } else if c == '\'' || c == '"' {
+            // Quotes are escaped by doubling them
+            let mut value = String::new();
+            let mut chars = rest.char_indices().skip(1).peekable();
+            let end = loop {
+                match chars.next() {
+                    Some((i, d)) if d == c => {
+                        if chars.peek().is_some_and(|(_, d)| *d == c) {
+                            chars.next();
+                            value.push(c);
+                        } else {
+                            break i + 1;
+                        }
+                    }
+                    Some((_, d)) => value.push(d),
+                    None => return Err(SqlError::new("Unterminated quoted string")),
+                }
+            };
+            tokens.push(if c == '\'' {
+                Token::String(value)
+            } else {
+                Token::QuotedIdentifier(value)
+            });
+            rest = &rest[end..];
+        } else {
+            return Err(SqlError::new(format!("Unexpected character '{c}'")));
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn parse_select(&mut self) -> Result<SqlQuery, SqlError> {
+        self.expect_keyword("SELECT")?;
+        let distinct = self.next_is_keyword("DISTINCT");
+        let columns = if self.next_is_symbol("*") {
+            None
+        } else {
+            let mut columns = vec![self.parse_identifier()?];
+            while self.next_is_symbol(",") {
+                columns.push(self.parse_identifier()?);
+            }
+            Some(columns)
+        };
+        self.expect_keyword("FROM")?;
+        let table = self.parse_identifier()?;
+        let mut conditions = Vec::new();
+        if self.next_is_keyword("WHERE") {
+            loop {
+                conditions.push(self.parse_condition()?);
+                if !self.next_is_keyword("AND") {
+                    break;
+                }
+            }
+        }
+        let mut order_by = Vec::new();
+        if self.next_is_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            loop {
+                let column = self.parse_identifier()?;
+                let ascending = !self.next_is_keyword("DESC");
+                if ascending {
+                    self.next_is_keyword("ASC");
+                }
+                order_by.push((column, ascending));
+                if !self.next_is_symbol(",") {
+                    break;
+                }
+            }
+        }
+        let limit = if self.next_is_keyword("LIMIT") {
+            Some(self.parse_count()?)
+        } else {
+            None
+        };
+        let offset = if self.next_is_keyword("OFFSET") {
+            self.parse_count()?
+        } else {
+            0
+        };
+        Ok(SqlQuery {
+            columns,
+            distinct,
+            table,
+            conditions,
+            order_by,
+            limit,
+            offset,
+        })
+    }
+
+    fn parse_condition(&mut self) -> Result<Condition, SqlError> {
+        let column = self.parse_identifier()?;
+        if self.next_is_keyword("IS") {
+            let not = self.next_is_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Condition::IsNull(column, !not));
+        }
+        let comparison = match self.next() {
+            Some(Token::Symbol("=")) => Comparison::Equal,
+            Some(Token::Symbol("<>" | "!=")) => Comparison::NotEqual,
+            Some(Token::Symbol("<")) => Comparison::Less,
+            Some(Token::Symbol("<=")) => Comparison::LessOrEqual,
+            Some(Token::Symbol(">")) => Comparison::Greater,
+            Some(Token::Symbol(">=")) => Comparison::GreaterOrEqual,
+            token => return Err(unexpected(token, "a comparison operator")),
+        };
+        let value = match self.next() {
+            Some(Token::String(value)) => SqlValue::String(value.clone()),
+            Some(Token::Number(value)) => SqlValue::Number(value.clone()),
+            Some(Token::Word(value)) if value.eq_ignore_ascii_case("TRUE") => {
+                SqlValue::Boolean(true)
+            }
+            Some(Token::Word(value)) if value.eq_ignore_ascii_case("FALSE") => {
+                SqlValue::Boolean(false)
+            }
+            token => return Err(unexpected(token, "a literal")),
+        };
+        Ok(Condition::Compare(column, comparison, value))
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, SqlError> {
+        match self.next() {
+            Some(Token::Word(name)) if !is_keyword(name) => Ok(name.clone()),
+            Some(Token::QuotedIdentifier(name)) => Ok(name.clone()),
+            token => Err(unexpected(token, "an identifier")),
+        }
+    }
+
+    fn parse_count(&mut self) -> Result<usize, SqlError> {
+        match self.next() {
+            Some(Token::Number(value)) => value
+                .parse()
+                .map_err(|_| SqlError::new(format!("Invalid count {value}"))),
+            token => Err(unexpected(token, "a positive integer")),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), SqlError> {
+        if self.next_is_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(unexpected(self.peek(), keyword))
+        }
+    }
+
+    fn next_is_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next_is_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    [
+        "SELECT", "DISTINCT", "FROM", "WHERE", "AND", "ORDER", "BY", "ASC", "DESC", "LIMIT",
+        "OFFSET", "IS", "NOT", "NULL",
+    ]
+    .iter()
+    .any(|k| k.eq_ignore_ascii_case(word))
+}
+
+fn unexpected(token: Option<&Token>, expected: &str) -> SqlError {
+    if let Some(token) = token {
+        SqlError::new(format!("Expected {expected}, found {token}"))
+    } else {
+        SqlError::new(format!("Expected {expected}, found the end of the query"))
+    }
+}
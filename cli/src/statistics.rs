@@ -0,0 +1,363 @@
+//! History of the dataset size, stored as RDF in a graph of the store for capacity planning.
+//!
+//! Each statistics rebuild is recorded as:
+//! ```turtle
+//! @prefix prov: <http://www.w3.org/ns/prov#> .
+//! @prefix stats: <http://oxigraph.org/statistics#> .
+//! @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+//!
+//! [] a stats:Snapshot ;
+//!     prov:generatedAtTime "2025-01-01T00:00:00Z"^^xsd:dateTime ;
+//!     stats:quads 3 ;
+//!     stats:terms 7 ;
+//!     stats:graph [ stats:graphName stats:DefaultGraph ; stats:quads 2 ] ,
+//!         [ stats:graphName <http://example.com/g> ; stats:quads 1 ] .
+//! ```
+//! The quads of the statistics graph itself are not counted.
+//!
+//! The trend is a JSON object with the snapshots sorted by time and the growth per day of the number of quads and terms,
+//! computed with a linear regression over all the snapshots (`null` if there are less than two snapshots):
+//! ```json
+//! {
+//!   "snapshots": [{"time": "2025-01-01T00:00:00Z", "quads": 3, "terms": 7, "graphs": [{"graph": null, "quads": 2}, {"graph": "http://example.com/g", "quads": 1}]}],
+//!   "quadsPerDay": null,
+//!   "termsPerDay": null
+//! }
+//! ```
+use json_event_parser::{JsonEvent, WriterJsonSerializer};
+use oxigraph::model::vocab::{rdf, xsd};
+use oxigraph::model::{
+    BlankNode, GraphName, Literal, NamedNode, NamedNodeRef, NamedOrBlankNode, Quad, Term, TermRef,
+};
+use oxigraph::store::{QuadShard, StorageError, Store};
+use oxsdatatypes::DateTime;
+use std::collections::{HashMap, HashSet};
+use std::io;
+#[cfg(not(target_family = "wasm"))]
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+#[cfg(not(target_family = "wasm"))]
+use std::thread;
+use std::time::Duration;
+
+const PROV_GENERATED_AT_TIME: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/prov#generatedAtTime");
+const STATS_SNAPSHOT: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/statistics#Snapshot");
+const STATS_QUADS: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/statistics#quads");
+const STATS_TERMS: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/statistics#terms");
+const STATS_GRAPH: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/statistics#graph");
+const STATS_GRAPH_NAME: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/statistics#graphName");
+const STATS_DEFAULT_GRAPH: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://oxigraph.org/statistics#DefaultGraph");
+const SECONDS_PER_DAY: f64 = 86400.;
+
+#[derive(Default)]
+struct DatasetSize {
+    terms: HashSet<Term>,
+    graphs: HashMap<GraphName, u64>,
+}
+
+impl DatasetSize {
+    fn merge(&mut self, other: Self) {
+        self.terms.extend(other.terms);
+        for (graph, count) in other.graphs {
+            *self.graphs.entry(graph).or_default() += count;
+        }
+    }
+}
+
+struct Snapshot {
+    time: DateTime,
+    quads: u64,
+    terms: u64,
+    graphs: Vec<(GraphName, u64)>,
+}
+
+/// Computes the size of the dataset and adds it with the current time to the statistics graph
+///
+/// The full store is scanned to count the distinct terms.
+pub fn record_statistics(store: &Store, statistics_graph: &NamedNode) -> Result<(), StorageError> {
+    let size = compute_size(store, statistics_graph)?;
+    let record = BlankNode::default();
+    let graph_name = GraphName::from(statistics_graph.clone());
+    let mut quads = vec![
+        Quad::new(
+            record.clone(),
+            rdf::TYPE,
+            STATS_SNAPSHOT.into_owned(),
+            graph_name.clone(),
+        ),
+        Quad::new(
+            record.clone(),
+            PROV_GENERATED_AT_TIME,
+            Literal::new_typed_literal(DateTime::now().to_string(), xsd::DATE_TIME),
+            graph_name.clone(),
+        ),
+        Quad::new(
+            record.clone(),
+            STATS_QUADS,
+            Literal::from(size.graphs.values().sum::<u64>()),
+            graph_name.clone(),
+        ),
+        Quad::new(
+            record.clone(),
+            STATS_TERMS,
+            Literal::from(u64::try_from(size.terms.len()).unwrap_or(u64::MAX)),
+            graph_name.clone(),
+        ),
+    ];
+    for (graph, count) in size.graphs {
+        let graph_record = BlankNode::default();
+        quads.push(Quad::new(
+            record.clone(),
+            STATS_GRAPH,
+            graph_record.clone(),
+            graph_name.clone(),
+        ));
+        quads.push(Quad::new(
+            graph_record.clone(),
+            STATS_GRAPH_NAME,
+            match graph {
+                GraphName::NamedNode(graph) => Term::from(graph),
+                GraphName::BlankNode(graph) => graph.into(),
+                GraphName::DefaultGraph => STATS_DEFAULT_GRAPH.into_owned().into(),
+            },
+            graph_name.clone(),
+        ));
+        quads.push(Quad::new(
+            graph_record,
+            STATS_QUADS,
+            Literal::from(count),
+            graph_name.clone(),
+        ));
+    }
+    store.extend(quads)
+}
+
+/// Serializes the recorded statistics and their growth per day as JSON
+pub fn generate_statistics_trend(
+    store: &Store,
+    statistics_graph: &NamedNode,
+) -> io::Result<Vec<u8>> {
+    let snapshots = read_snapshots(store, statistics_graph)?;
+    let quads_per_day = growth_per_day(&snapshots, |snapshot| snapshot.quads);
+    let terms_per_day = growth_per_day(&snapshots, |snapshot| snapshot.terms);
+
+    let mut output = WriterJsonSerializer::new(Vec::new());
+    output.serialize_event(JsonEvent::StartObject)?;
+    output.serialize_event(JsonEvent::ObjectKey("snapshots".into()))?;
+    output.serialize_event(JsonEvent::StartArray)?;
+    for snapshot in &snapshots {
+        output.serialize_event(JsonEvent::StartObject)?;
+        output.serialize_event(JsonEvent::ObjectKey("time".into()))?;
+        output.serialize_event(JsonEvent::String(snapshot.time.to_string().into()))?;
+        output.serialize_event(JsonEvent::ObjectKey("quads".into()))?;
+        output.serialize_event(JsonEvent::Number(snapshot.quads.to_string().into()))?;
+        output.serialize_event(JsonEvent::ObjectKey("terms".into()))?;
+        output.serialize_event(JsonEvent::Number(snapshot.terms.to_string().into()))?;
+        output.serialize_event(JsonEvent::ObjectKey("graphs".into()))?;
+        output.serialize_event(JsonEvent::StartArray)?;
+        for (graph, count) in &snapshot.graphs {
+            output.serialize_event(JsonEvent::StartObject)?;
+            output.serialize_event(JsonEvent::ObjectKey("graph".into()))?;
+            output.serialize_event(match graph {
+                GraphName::NamedNode(graph) => JsonEvent::String(graph.as_str().into()),
+                GraphName::BlankNode(graph) => JsonEvent::String(graph.to_string().into()),
+                GraphName::DefaultGraph => JsonEvent::Null,
+            })?;
+            output.serialize_event(JsonEvent::ObjectKey("quads".into()))?;
+            output.serialize_event(JsonEvent::Number(count.to_string().into()))?;
+            output.serialize_event(JsonEvent::EndObject)?;
+        }
+        output.serialize_event(JsonEvent::EndArray)?;
+        output.serialize_event(JsonEvent::EndObject)?;
+    }
+    output.serialize_event(JsonEvent::EndArray)?;
+    for (key, growth) in [
+        ("quadsPerDay", quads_per_day),
+        ("termsPerDay", terms_per_day),
+    ] {
+        output.serialize_event(JsonEvent::ObjectKey(key.into()))?;
+        output.serialize_event(if let Some(growth) = growth {
+            JsonEvent::Number(growth.to_string().into())
+        } else {
+            JsonEvent::Null
+        })?;
+    }
+    output.serialize_event(JsonEvent::EndObject)?;
+    output.finish()
+}
+
+fn compute_size(store: &Store, statistics_graph: &NamedNode) -> Result<DatasetSize, StorageError> {
+    #[cfg(not(target_family = "wasm"))]
+    let thread_count = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    #[cfg(target_family = "wasm")]
+    let thread_count = 1;
+    let statistics_graph = GraphName::from(statistics_graph.clone());
+    let shards = store.parallel_scan(None, None, None, None, thread_count)?;
+    let count_shard = |shard: &QuadShard| -> Result<DatasetSize, StorageError> {
+        let mut size = DatasetSize::default();
+        for quad in shard {
+            let quad = quad?;
+            if quad.graph_name == statistics_graph {
+                continue;
+            }
+            size.terms.insert(quad.subject.into());
+            size.terms.insert(quad.predicate.into());
+            size.terms.insert(quad.object);
+            if let Some(graph) = match &quad.graph_name {
+                GraphName::NamedNode(graph) => Some(Term::from(graph.clone())),
+                GraphName::BlankNode(graph) => Some(Term::from(graph.clone())),
+                GraphName::DefaultGraph => None,
+            } {
+                size.terms.insert(graph);
+            }
+            *size.graphs.entry(quad.graph_name).or_default() += 1;
+        }
+        Ok(size)
+    };
+    let mut size = DatasetSize::default();
+    #[cfg(not(target_family = "wasm"))]
+    thread::scope(|scope| {
+        let handles = shards
+            .iter()
+            .map(|shard| scope.spawn(|| count_shard(shard)))
+            .collect::<Vec<_>>();
+        for handle in handles {
+            size.merge(handle.join().map_err(|_| {
+                StorageError::Other("The dataset statistics computation panicked".into())
+            })??);
+        }
+        Ok::<_, StorageError>(())
+    })?;
+    #[cfg(target_family = "wasm")]
+    for shard in &shards {
+        size.merge(count_shard(shard)?);
+    }
+    Ok(size)
+}
+
+fn read_snapshots(
+    store: &Store,
+    statistics_graph: &NamedNode,
+) -> Result<Vec<Snapshot>, StorageError> {
+    let graph_name = GraphName::from(statistics_graph.clone());
+    let mut snapshots = Vec::new();
+    for quad in store.quads_for_pattern(
+        None,
+        Some(rdf::TYPE),
+        Some(STATS_SNAPSHOT.into()),
+        Some(graph_name.as_ref()),
+    ) {
+        let record = quad?.subject;
+        let Some(time) = object(store, &record, PROV_GENERATED_AT_TIME, &graph_name)?
+            .and_then(|time| parse_literal::<DateTime>(&time))
+        else {
+            continue; // Not a valid snapshot
+        };
+        let mut graphs = Vec::new();
+        for quad in store.quads_for_pattern(
+            Some(record.as_ref()),
+            Some(STATS_GRAPH),
+            None,
+            Some(graph_name.as_ref()),
+        ) {
+            let graph_record = match quad?.object {
+                Term::NamedNode(graph_record) => NamedOrBlankNode::from(graph_record),
+                Term::BlankNode(graph_record) => graph_record.into(),
+                _ => continue,
+            };
+            let graph = match object(store, &graph_record, STATS_GRAPH_NAME, &graph_name)? {
+                Some(Term::NamedNode(graph)) if graph == STATS_DEFAULT_GRAPH => {
+                    GraphName::DefaultGraph
+                }
+                Some(Term::NamedNode(graph)) => graph.into(),
+                Some(Term::BlankNode(graph)) => graph.into(),
+                _ => continue,
+            };
+            if let Some(count) = object(store, &graph_record, STATS_QUADS, &graph_name)?
+                .and_then(|count| parse_literal(&count))
+            {
+                graphs.push((graph, count));
+            }
+        }
+        graphs.sort_unstable_by_key(|(graph, _)| (!graph.is_default_graph(), graph.to_string()));
+        snapshots.push(Snapshot {
+            time,
+            quads: object(store, &record, STATS_QUADS, &graph_name)?
+                .and_then(|count| parse_literal(&count))
+                .unwrap_or_default(),
+            terms: object(store, &record, STATS_TERMS, &graph_name)?
+                .and_then(|count| parse_literal(&count))
+                .unwrap_or_default(),
+            graphs,
+        });
+    }
+    snapshots.sort_unstable_by(|snapshot1, snapshot2| {
+        snapshot1
+            .time
+            .partial_cmp(&snapshot2.time)
+            .unwrap_or_else(|| snapshot1.time.to_string().cmp(&snapshot2.time.to_string()))
+    });
+    Ok(snapshots)
+}
+
+fn object(
+    store: &Store,
+    subject: &NamedOrBlankNode,
+    predicate: NamedNodeRef<'_>,
+    graph_name: &GraphName,
+) -> Result<Option<Term>, StorageError> {
+    store
+        .quads_for_pattern(
+            Some(subject.as_ref()),
+            Some(predicate),
+            None,
+            Some(graph_name.as_ref()),
+        )
+        .next()
+        .transpose()
+        .map(|quad| quad.map(|quad| quad.object))
+}
+
+fn parse_literal<T: FromStr>(term: &Term) -> Option<T> {
+    let TermRef::Literal(literal) = term.as_ref() else {
+        return None;
+    };
+    literal.value().parse().ok()
+}
+
+/// The slope of the least squares linear regression of the value over time, in units per day
+fn growth_per_day(snapshots: &[Snapshot], value: impl Fn(&Snapshot) -> u64) -> Option<f64> {
+    let first = snapshots.first()?;
+    let points = snapshots
+        .iter()
+        .map(|snapshot| {
+            let elapsed = Duration::try_from(snapshot.time.checked_sub(first.time)?).ok()?;
+            Some((
+                elapsed.as_secs_f64() / SECONDS_PER_DAY,
+                value(snapshot) as f64,
+            ))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if points.len() < 2 {
+        return None;
+    }
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let variance = points
+        .iter()
+        .map(|(x, _)| (x - mean_x).powi(2))
+        .sum::<f64>();
+    (variance > 0.).then(|| covariance / variance)
+}
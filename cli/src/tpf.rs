@@ -0,0 +1,270 @@
+//! [Triple Pattern Fragments](https://linkeddatafragments.org/specification/triple-pattern-fragments/) of the store.
+//!
+//! A fragment is a page of the triples matching a triple pattern,
+//! with the total number of matching triples and [Hydra](https://www.hydra-cg.com/spec/latest/core/) hypermedia controls
+//! to fetch the other pages and to build the URLs of the other fragments.
+//! The pattern terms are given with the `subject`, `predicate` and `object` URL query parameters
+//! in the Hydra explicit representation (IRIs are given as is, literals like `"foo"@en` and variables like `?o` or an empty value).
+//! The metadata and the controls are in the `<{fragment}#metadata>` graph for RDF formats supporting datasets
+//! and mixed with the data triples otherwise.
+use oxigraph::model::vocab::{rdf, xsd};
+use oxigraph::model::{
+    BlankNode, GraphName, GraphNameRef, Literal, NamedNode, NamedNodeRef, NamedOrBlankNode, Quad,
+    Term,
+};
+use oxigraph::store::{StorageError, Store};
+use std::str::FromStr;
+use url::form_urlencoded;
+
+/// Number of triples in a page
+pub const TPF_PAGE_SIZE: usize = 100;
+
+const VOID_DATASET: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://rdfs.org/ns/void#Dataset");
+const VOID_SUBSET: NamedNodeRef<'_> = NamedNodeRef::new_unchecked("http://rdfs.org/ns/void#subset");
+const VOID_TRIPLES: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://rdfs.org/ns/void#triples");
+const HYDRA_COLLECTION: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#Collection");
+const HYDRA_PARTIAL_COLLECTION_VIEW: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#PartialCollectionView");
+const HYDRA_TOTAL_ITEMS: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#totalItems");
+const HYDRA_ITEMS_PER_PAGE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#itemsPerPage");
+const HYDRA_FIRST: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#first");
+const HYDRA_NEXT: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#next");
+const HYDRA_PREVIOUS: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#previous");
+const HYDRA_SEARCH: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#search");
+const HYDRA_TEMPLATE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#template");
+const HYDRA_VARIABLE_REPRESENTATION: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#variableRepresentation");
+const HYDRA_EXPLICIT_REPRESENTATION: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#ExplicitRepresentation");
+const HYDRA_MAPPING: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#mapping");
+const HYDRA_VARIABLE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#variable");
+const HYDRA_PROPERTY: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/hydra/core#property");
+
+/// A triple pattern, `None` being a variable
+#[derive(Default)]
+pub struct TriplePattern {
+    pub subject: Option<NamedOrBlankNode>,
+    pub predicate: Option<NamedNode>,
+    pub object: Option<Term>,
+}
+
+impl TriplePattern {
+    /// Builds the pattern from the `subject`, `predicate` and `object` parameters in the Hydra explicit representation
+    pub fn from_parameters<'a>(
+        parameters: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, String> {
+        let mut pattern = Self::default();
+        for (key, value) in parameters {
+            if !matches!(key, "subject" | "predicate" | "object") {
+                continue;
+            }
+            let Some(term) = parse_explicit_term(value)? else {
+                continue;
+            };
+            match key {
+                "subject" => {
+                    pattern.subject = Some(match term {
+                        Term::NamedNode(subject) => subject.into(),
+                        Term::BlankNode(subject) => subject.into(),
+                        _ => return Err(format!("The subject {term} must be an IRI")),
+                    })
+                }
+                "predicate" => {
+                    let Term::NamedNode(predicate) = term else {
+                        return Err(format!("The predicate {term} must be an IRI"));
+                    };
+                    pattern.predicate = Some(predicate)
+                }
+                "object" => pattern.object = Some(term),
+                _ => (),
+            }
+        }
+        Ok(pattern)
+    }
+}
+
+/// Returns the triples of the given page (starting at 1) of the fragment followed by its metadata and controls
+///
+/// `endpoint` is the URL of the fragments endpoint.
+/// The triples are read from the default graph or, if `union_default_graph` is set, from all the graphs.
+pub fn triple_pattern_fragment(
+    store: &Store,
+    endpoint: &str,
+    pattern: &TriplePattern,
+    page: usize,
+    union_default_graph: bool,
+) -> Result<Vec<Quad>, StorageError> {
+    let offset = page.saturating_sub(1).saturating_mul(TPF_PAGE_SIZE);
+    let mut quads = Vec::new();
+    let mut total = 0;
+    for quad in store.quads_for_pattern(
+        pattern.subject.as_ref().map(Into::into),
+        pattern.predicate.as_ref().map(Into::into),
+        pattern.object.as_ref().map(Into::into),
+        (!union_default_graph).then_some(GraphNameRef::DefaultGraph),
+    ) {
+        let quad = quad?;
+        if total >= offset && total < offset + TPF_PAGE_SIZE {
+            quads.push(Quad::new(
+                quad.subject,
+                quad.predicate,
+                quad.object,
+                GraphName::DefaultGraph,
+            ));
+        }
+        total += 1;
+    }
+
+    let fragment = NamedNode::new_unchecked(fragment_url(endpoint, pattern, page));
+    let dataset = NamedNode::new_unchecked(format!("{endpoint}#dataset"));
+    let metadata = GraphName::from(NamedNode::new_unchecked(format!(
+        "{}#metadata",
+        fragment.as_str()
+    )));
+    let metadata = metadata.as_ref();
+    let total_items = Literal::new_typed_literal(total.to_string(), xsd::INTEGER);
+    quads.extend([
+        Quad::new(dataset.clone(), rdf::TYPE, VOID_DATASET, metadata),
+        Quad::new(dataset.clone(), rdf::TYPE, HYDRA_COLLECTION, metadata),
+        Quad::new(dataset.clone(), VOID_SUBSET, fragment.clone(), metadata),
+        Quad::new(
+            fragment.clone(),
+            rdf::TYPE,
+            HYDRA_PARTIAL_COLLECTION_VIEW,
+            metadata,
+        ),
+        Quad::new(
+            fragment.clone(),
+            VOID_TRIPLES,
+            total_items.clone(),
+            metadata,
+        ),
+        Quad::new(fragment.clone(), HYDRA_TOTAL_ITEMS, total_items, metadata),
+        Quad::new(
+            fragment.clone(),
+            HYDRA_ITEMS_PER_PAGE,
+            Literal::new_typed_literal(TPF_PAGE_SIZE.to_string(), xsd::INTEGER),
+            metadata,
+        ),
+        Quad::new(
+            fragment.clone(),
+            HYDRA_FIRST,
+            NamedNode::new_unchecked(fragment_url(endpoint, pattern, 1)),
+            metadata,
+        ),
+    ]);
+    if page > 1 {
+        quads.push(Quad::new(
+            fragment.clone(),
+            HYDRA_PREVIOUS,
+            NamedNode::new_unchecked(fragment_url(endpoint, pattern, page - 1)),
+            metadata,
+        ));
+    }
+    if offset + TPF_PAGE_SIZE < total {
+        quads.push(Quad::new(
+            fragment.clone(),
+            HYDRA_NEXT,
+            NamedNode::new_unchecked(fragment_url(endpoint, pattern, page + 1)),
+            metadata,
+        ));
+    }
+
+    let search = BlankNode::default();
+    quads.extend([
+        Quad::new(dataset, HYDRA_SEARCH, search.clone(), metadata),
+        Quad::new(
+            search.clone(),
+            HYDRA_TEMPLATE,
+            Literal::from(format!("{endpoint}{{?subject,predicate,object}}")),
+            metadata,
+        ),
+        Quad::new(
+            search.clone(),
+            HYDRA_VARIABLE_REPRESENTATION,
+            HYDRA_EXPLICIT_REPRESENTATION,
+            metadata,
+        ),
+    ]);
+    for (variable, property) in [
+        ("subject", rdf::SUBJECT),
+        ("predicate", rdf::PREDICATE),
+        ("object", rdf::OBJECT),
+    ] {
+        let mapping = BlankNode::default();
+        quads.extend([
+            Quad::new(search.clone(), HYDRA_MAPPING, mapping.clone(), metadata),
+            Quad::new(
+                mapping.clone(),
+                HYDRA_VARIABLE,
+                Literal::from(variable),
+                metadata,
+            ),
+            Quad::new(mapping, HYDRA_PROPERTY, property, metadata),
+        ]);
+    }
+    Ok(quads)
+}
+
+/// Parses a term in the Hydra explicit representation, returning `None` for variables
+fn parse_explicit_term(value: &str) -> Result<Option<Term>, String> {
+    if value.is_empty() || value.starts_with('?') {
+        return Ok(None);
+    }
+    Ok(Some(if value.starts_with('"') {
+        Literal::from_str(value)
+            .map_err(|e| format!("Invalid literal {value}: {e}"))?
+            .into()
+    } else if value.starts_with("_:") {
+        BlankNode::from_str(value)
+            .map_err(|e| format!("Invalid blank node {value}: {e}"))?
+            .into()
+    } else {
+        NamedNode::new(value)
+            .map_err(|e| format!("Invalid IRI {value}: {e}"))?
+            .into()
+    }))
+}
+
+/// Serializes a term in the Hydra explicit representation
+fn explicit_term(term: impl Into<Term>) -> String {
+    match term.into() {
+        Term::NamedNode(term) => term.into_string(),
+        term => term.to_string(),
+    }
+}
+
+fn fragment_url(endpoint: &str, pattern: &TriplePattern, page: usize) -> String {
+    let mut query = form_urlencoded::Serializer::new(String::new());
+    if let Some(subject) = &pattern.subject {
+        query.append_pair("subject", &explicit_term(subject.clone()));
+    }
+    if let Some(predicate) = &pattern.predicate {
+        query.append_pair("predicate", &explicit_term(predicate.clone()));
+    }
+    if let Some(object) = &pattern.object {
+        query.append_pair("object", &explicit_term(object.clone()));
+    }
+    if page > 1 {
+        query.append_pair("page", &page.to_string());
+    }
+    let query = query.finish();
+    if query.is_empty() {
+        endpoint.into()
+    } else {
+        format!("{endpoint}?{query}")
+    }
+}
@@ -0,0 +1,139 @@
+//! [WASI HTTP](https://github.com/WebAssembly/wasi-http) binding of [`SparqlServer`] to run it in WebAssembly components.
+use crate::server::SparqlServer;
+use oxhttp::model::header::CONTENT_TYPE;
+use oxhttp::model::{Body, HeaderName, HeaderValue, Method, Request, Response, StatusCode, Uri};
+use std::io::{self, Write};
+use wasi::http::types::{
+    Fields, IncomingBody, IncomingRequest, Method as WasiMethod, OutgoingBody, OutgoingResponse,
+    ResponseOutparam, Scheme as WasiScheme,
+};
+use wasi::io::streams::InputStream;
+
+/// Handles with a [`SparqlServer`] a request of the [WASI HTTP](https://github.com/WebAssembly/wasi-http) `incoming-handler` interface.
+///
+/// The request and response bodies are streamed.
+/// There is no RocksDB storage in WebAssembly, the store must be an in-memory one.
+///
+/// ```no_run
+/// use oxigraph::store::Store;
+/// use oxigraph_server::{SparqlServer, handle_wasi_request};
+/// use wasi::http::types::{IncomingRequest, ResponseOutparam};
+///
+/// struct Component;
+///
+/// impl wasi::exports::http::incoming_handler::Guest for Component {
+///     fn handle(request: IncomingRequest, response_out: ResponseOutparam) {
+///         let server = SparqlServer::new(Store::new().unwrap()).with_read_only();
+///         handle_wasi_request(&server, request, response_out)
+///     }
+/// }
+///
+/// wasi::http::proxy::export!(Component);
+/// # fn main() {}
+/// ```
+pub fn handle_wasi_request(
+    server: &SparqlServer,
+    request: IncomingRequest,
+    response_out: ResponseOutparam,
+) {
+    let response = match request_from_wasi(request) {
+        Ok(mut request) => server.handle(&mut request),
+        Err(message) => Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(message.into())
+            .unwrap(),
+    };
+    if let Err(e) = write_response_to_wasi(response, response_out) {
+        // The response has already been started, the error can't be returned to the client
+        eprintln!("Failed to write the response: {e}");
+    }
+}
+
+fn request_from_wasi(request: IncomingRequest) -> Result<Request<Body>, String> {
+    let scheme = match request.scheme() {
+        Some(WasiScheme::Https) => "https".to_owned(),
+        Some(WasiScheme::Other(scheme)) => scheme,
+        Some(WasiScheme::Http) | None => "http".to_owned(),
+    };
+    let authority = request.authority().ok_or("The request has no authority")?;
+    let path_with_query = request.path_with_query().unwrap_or_else(|| "/".into());
+    let uri = Uri::try_from(format!("{scheme}://{authority}{path_with_query}"))
+        .map_err(|e| format!("Invalid request URI: {e}"))?;
+    let method = match request.method() {
+        WasiMethod::Get => Method::GET,
+        WasiMethod::Head => Method::HEAD,
+        WasiMethod::Post => Method::POST,
+        WasiMethod::Put => Method::PUT,
+        WasiMethod::Delete => Method::DELETE,
+        WasiMethod::Connect => Method::CONNECT,
+        WasiMethod::Options => Method::OPTIONS,
+        WasiMethod::Trace => Method::TRACE,
+        WasiMethod::Patch => Method::PATCH,
+        WasiMethod::Other(method) => Method::from_bytes(method.as_bytes())
+            .map_err(|e| format!("Invalid request method: {e}"))?,
+    };
+    let body = request
+        .consume()
+        .map_err(|()| "The request body has already been consumed")?;
+    let stream = body
+        .stream()
+        .map_err(|()| "The request body stream has already been consumed")?;
+    let mut builder = Request::builder().method(method).uri(uri);
+    for (name, value) in request.headers().entries() {
+        builder = builder.header(
+            HeaderName::try_from(name).map_err(|e| format!("Invalid header name: {e}"))?,
+            HeaderValue::from_bytes(&value).map_err(|e| format!("Invalid header value: {e}"))?,
+        );
+    }
+    builder
+        .body(Body::from_read(IncomingBodyReader {
+            stream,
+            _body: body,
+            _request: request,
+        }))
+        .map_err(|e| e.to_string())
+}
+
+fn write_response_to_wasi(
+    mut response: Response<Body>,
+    response_out: ResponseOutparam,
+) -> io::Result<()> {
+    let headers = Fields::new();
+    for (name, value) in response.headers() {
+        // Some headers like the hop-by-hop ones are forbidden by the runtime
+        if headers.append(name.as_str(), value.as_bytes()).is_err() {
+            eprintln!(
+                "The response header {name} is not allowed by the runtime and has been ignored"
+            );
+        }
+    }
+    let outgoing_response = OutgoingResponse::new(headers);
+    outgoing_response
+        .set_status_code(response.status().as_u16())
+        .map_err(|()| io::Error::other("Invalid status code"))?;
+    let outgoing_body = outgoing_response
+        .body()
+        .map_err(|()| io::Error::other("The response body has already been taken"))?;
+    ResponseOutparam::set(response_out, Ok(outgoing_response));
+    let mut stream = outgoing_body
+        .write()
+        .map_err(|()| io::Error::other("The response body stream has already been taken"))?;
+    io::copy(response.body_mut(), &mut stream)?;
+    Write::flush(&mut stream)?;
+    drop(stream);
+    OutgoingBody::finish(outgoing_body, None).map_err(io::Error::other)
+}
+
+/// The request body stream, that must be dropped before the body and the request it is from
+struct IncomingBodyReader {
+    stream: InputStream,
+    _body: IncomingBody,
+    _request: IncomingRequest,
+}
+
+impl io::Read for IncomingBodyReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        io::Read::read(&mut self.stream, buf)
+    }
+}
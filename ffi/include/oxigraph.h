@@ -0,0 +1,144 @@
+/* Oxigraph C API. Licensed under either of Apache License, Version 2.0 or MIT license at your option. */
+
+#ifndef OXIGRAPH_H
+#define OXIGRAPH_H
+
+/* Warning, this file is autogenerated by cbindgen from the oxigraph-ffi crate. Don't modify this manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Status returned by all the fallible functions.
+//
+// The values are part of the stable ABI: new variants might be added but the existing ones are never renumbered.
+// The message of the last error is available with [`oxigraph_last_error_message`].
+typedef enum OxigraphStatus {
+  // The call succeeded.
+  OXIGRAPH_STATUS_OK = 0,
+  // An argument is invalid: a null pointer, a string that is not UTF-8, an invalid IRI...
+  OXIGRAPH_STATUS_INVALID_ARGUMENT = 1,
+  // The SPARQL query, the SPARQL update or the RDF data is not syntactically valid.
+  OXIGRAPH_STATUS_SYNTAX = 2,
+  // The SPARQL query or update evaluation failed.
+  OXIGRAPH_STATUS_EVALUATION = 3,
+  // An error raised by the storage, like an I/O error or a corruption.
+  OXIGRAPH_STATUS_STORAGE = 4,
+  // The format is not supported or can't be used for the given data.
+  OXIGRAPH_STATUS_UNSUPPORTED_FORMAT = 5,
+  // The call panicked. This is a bug in Oxigraph.
+  OXIGRAPH_STATUS_PANIC = 6,
+} OxigraphStatus;
+
+// An RDF dataset store.
+//
+// It is an opaque type created with [`oxigraph_store_new`], [`oxigraph_store_open`] or [`oxigraph_store_open_read_only`] and freed with [`oxigraph_store_free`].
+// A store can be shared between threads.
+typedef struct OxigraphStore OxigraphStore;
+
+// A byte buffer allocated by Oxigraph.
+//
+// It must be freed with [`oxigraph_buffer_free`].
+typedef struct OxigraphBuffer {
+  // The buffer content. It is not NUL-terminated.
+  uint8_t *data;
+  // The number of bytes in the buffer.
+  size_t len;
+} OxigraphBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Frees a buffer returned by Oxigraph.
+//
+// # Safety
+//
+// The buffer must have been returned by an Oxigraph function and must not have been freed already.
+void oxigraph_buffer_free(struct OxigraphBuffer buffer);
+
+// Returns the error message of the last call done by the current thread.
+//
+// The message is an empty string if the last call succeeded.
+// The returned string is owned by Oxigraph and is valid until the next call from the same thread.
+const char *oxigraph_last_error_message(void);
+
+// Creates a new in-memory store.
+//
+// # Safety
+//
+// `store` must be valid for writes.
+enum OxigraphStatus oxigraph_store_new(struct OxigraphStore **store);
+
+// Opens a read-write store backed by RocksDB in the `path` directory.
+//
+// Only one read-write store can be opened on a given directory at a time.
+//
+// # Safety
+//
+// `path` must be a NUL-terminated string and `store` must be valid for writes.
+enum OxigraphStatus oxigraph_store_open(const char *path, struct OxigraphStore **store);
+
+// Opens a read-only store backed by RocksDB in the `path` directory.
+//
+// # Safety
+//
+// `path` must be a NUL-terminated string and `store` must be valid for writes.
+enum OxigraphStatus oxigraph_store_open_read_only(const char *path, struct OxigraphStore **store);
+
+// Frees a store. Does nothing if `store` is null.
+//
+// # Safety
+//
+// `store` must be null or a store returned by Oxigraph that has not been freed already.
+void oxigraph_store_free(struct OxigraphStore *store);
+
+// Evaluates a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) and writes its serialized results into `results`.
+//
+// `format` is a media type or a file extension.
+// It must be a [SPARQL results format](https://www.w3.org/TR/sparql11-results-json/) for `SELECT` and `ASK` queries and an RDF format for `CONSTRUCT` and `DESCRIBE` queries.
+// If it is null, SPARQL results JSON or N-Triples are used.
+//
+// # Safety
+//
+// `store` must be a valid store, `query` and `format` NUL-terminated strings and `results` must be valid for writes.
+enum OxigraphStatus oxigraph_store_query(const struct OxigraphStore *store,
+                                         const char *query,
+                                         const char *format,
+                                         struct OxigraphBuffer *results);
+
+// Executes a [SPARQL 1.1 update](https://www.w3.org/TR/sparql11-update/).
+//
+// The update is applied in a transaction.
+//
+// # Safety
+//
+// `store` must be a valid store and `update` a NUL-terminated string.
+enum OxigraphStatus oxigraph_store_update(const struct OxigraphStore *store, const char *update);
+
+// Loads an RDF file into the store.
+//
+// The file is loaded in a single transaction.
+// `format` is a media type or a file extension.
+// `base_iri` is the base IRI used to resolve relative IRIs and might be null.
+// `to_graph` is the IRI of the graph the triples are loaded into.
+// If it is null, the triples are loaded into the default graph.
+//
+// # Safety
+//
+// `store` must be a valid store, `data` must be valid for `data_len` bytes reads
+// and `format`, `base_iri` and `to_graph` must be null or NUL-terminated strings.
+enum OxigraphStatus oxigraph_store_load(const struct OxigraphStore *store,
+                                        const uint8_t *data,
+                                        size_t data_len,
+                                        const char *format,
+                                        const char *base_iri,
+                                        const char *to_graph);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* OXIGRAPH_H */
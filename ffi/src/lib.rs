@@ -0,0 +1,217 @@
+#![doc = include_str!("../README.md")]
+#![doc(test(attr(deny(warnings))))]
+#![doc(html_favicon_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+#![doc(html_logo_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+#![allow(unsafe_code)]
+
+mod store;
+
+pub use crate::store::*;
+use oxigraph::io::RdfParseError;
+use oxigraph::model::IriParseError;
+use oxigraph::sparql::{EvaluationError, SparqlSyntaxError};
+use oxigraph::store::{LoaderError, StorageError};
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+/// Status returned by all the fallible functions.
+///
+/// The values are part of the stable ABI: new variants might be added but the existing ones are never renumbered.
+/// The message of the last error is available with [`oxigraph_last_error_message`].
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OxigraphStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// An argument is invalid: a null pointer, a string that is not UTF-8, an invalid IRI...
+    InvalidArgument = 1,
+    /// The SPARQL query, the SPARQL update or the RDF data is not syntactically valid.
+    Syntax = 2,
+    /// The SPARQL query or update evaluation failed.
+    Evaluation = 3,
+    /// An error raised by the storage, like an I/O error or a corruption.
+    Storage = 4,
+    /// The format is not supported or can't be used for the given data.
+    UnsupportedFormat = 5,
+    /// The call panicked. This is a bug in Oxigraph.
+    Panic = 6,
+}
+
+/// A byte buffer allocated by Oxigraph.
+///
+/// It must be freed with [`oxigraph_buffer_free`].
+#[repr(C)]
+pub struct OxigraphBuffer {
+    /// The buffer content. It is not NUL-terminated.
+    pub data: *mut u8,
+    /// The number of bytes in the buffer.
+    pub len: usize,
+}
+
+impl From<Vec<u8>> for OxigraphBuffer {
+    fn from(data: Vec<u8>) -> Self {
+        let data = Box::into_raw(data.into_boxed_slice());
+        Self {
+            len: data.len(),
+            data: data.cast(),
+        }
+    }
+}
+
+/// Frees a buffer returned by Oxigraph.
+///
+/// # Safety
+///
+/// The buffer must have been returned by an Oxigraph function and must not have been freed already.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn oxigraph_buffer_free(buffer: OxigraphBuffer) {
+    if !buffer.data.is_null() {
+        // SAFETY: the buffer has been built from a boxed slice by `From<Vec<u8>>`
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)) });
+    }
+}
+
+thread_local! {
+    static LAST_ERROR_MESSAGE: RefCell<CString> = RefCell::default();
+}
+
+/// Returns the error message of the last call done by the current thread.
+///
+/// The message is an empty string if the last call succeeded.
+/// The returned string is owned by Oxigraph and is valid until the next call from the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn oxigraph_last_error_message() -> *const c_char {
+    LAST_ERROR_MESSAGE.with_borrow(|message| message.as_ptr())
+}
+
+struct Error {
+    status: OxigraphStatus,
+    message: String,
+}
+
+impl Error {
+    fn new(status: OxigraphStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(OxigraphStatus::InvalidArgument, message)
+    }
+}
+
+impl From<StorageError> for Error {
+    fn from(error: StorageError) -> Self {
+        Self::new(OxigraphStatus::Storage, error.to_string())
+    }
+}
+
+impl From<SparqlSyntaxError> for Error {
+    fn from(error: SparqlSyntaxError) -> Self {
+        Self::new(OxigraphStatus::Syntax, error.to_string())
+    }
+}
+
+impl From<RdfParseError> for Error {
+    fn from(error: RdfParseError) -> Self {
+        Self::new(OxigraphStatus::Syntax, error.to_string())
+    }
+}
+
+impl From<IriParseError> for Error {
+    fn from(error: IriParseError) -> Self {
+        Self::invalid_argument(error.to_string())
+    }
+}
+
+impl From<EvaluationError> for Error {
+    fn from(error: EvaluationError) -> Self {
+        match error {
+            EvaluationError::Parsing(error) => error.into(),
+            EvaluationError::Storage(error) => error.into(),
+            e => Self::new(OxigraphStatus::Evaluation, e.to_string()),
+        }
+    }
+}
+
+impl From<LoaderError> for Error {
+    fn from(error: LoaderError) -> Self {
+        match error {
+            LoaderError::Parsing(error) => error.into(),
+            LoaderError::Storage(error) => error.into(),
+            e @ LoaderError::InvalidBaseIri { .. } => Self::invalid_argument(e.to_string()),
+        }
+    }
+}
+
+/// Runs the function, stores its error message and converts panics into [`OxigraphStatus::Panic`]
+fn run(f: impl FnOnce() -> Result<(), Error>) -> OxigraphStatus {
+    let (status, message) = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => (OxigraphStatus::Ok, String::new()),
+        Ok(Err(error)) => (error.status, error.message),
+        Err(payload) => (OxigraphStatus::Panic, panic_message(payload.as_ref())),
+    };
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR_MESSAGE.set(message);
+    status
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).into()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Oxigraph panicked".into()
+    }
+}
+
+/// Reads a NUL-terminated UTF-8 string argument that might be null
+///
+/// # Safety
+///
+/// The pointer must be null or point to a NUL-terminated string valid for `'a`.
+unsafe fn optional_str_arg<'a>(value: *const c_char, name: &str) -> Result<Option<&'a str>, Error> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: the caller guarantees that the string is NUL-terminated and valid for 'a
+    unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .map(Some)
+        .map_err(|e| Error::invalid_argument(format!("The {name} is not valid UTF-8: {e}")))
+}
+
+/// Reads a NUL-terminated UTF-8 string argument that must not be null
+///
+/// # Safety
+///
+/// The pointer must be null or point to a NUL-terminated string valid for `'a`.
+unsafe fn str_arg<'a>(value: *const c_char, name: &str) -> Result<&'a str, Error> {
+    // SAFETY: same contract as this function
+    unsafe { optional_str_arg(value, name) }?
+        .ok_or_else(|| Error::invalid_argument(format!("The {name} must not be null")))
+}
+
+/// Writes an output argument
+///
+/// # Safety
+///
+/// The pointer must be null or valid for writes.
+unsafe fn write_out<T>(out: *mut T, value: T) -> Result<(), Error> {
+    if out.is_null() {
+        return Err(Error::invalid_argument(
+            "The output pointer must not be null",
+        ));
+    }
+    // SAFETY: the pointer is not null and the caller guarantees that it is valid for writes
+    unsafe {
+        out.write(value);
+    }
+    Ok(())
+}
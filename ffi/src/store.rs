@@ -0,0 +1,384 @@
+use crate::{Error, OxigraphBuffer, OxigraphStatus, optional_str_arg, run, str_arg, write_out};
+use oxigraph::io::{RdfFormat, RdfParser};
+use oxigraph::model::NamedNode;
+use oxigraph::sparql::QueryResults;
+use oxigraph::sparql::results::QueryResultsFormat;
+use oxigraph::store::Store;
+use std::ffi::c_char;
+use std::slice;
+
+/// An RDF dataset store.
+///
+/// It is an opaque type created with [`oxigraph_store_new`], [`oxigraph_store_open`] or [`oxigraph_store_open_read_only`] and freed with [`oxigraph_store_free`].
+/// A store can be shared between threads.
+pub struct OxigraphStore {
+    inner: Store,
+}
+
+/// Creates a new in-memory store.
+///
+/// # Safety
+///
+/// `store` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn oxigraph_store_new(store: *mut *mut OxigraphStore) -> OxigraphStatus {
+    run(|| {
+        let inner = Store::new()?;
+        // SAFETY: the caller guarantees that the pointer is valid for writes
+        unsafe { write_out(store, Box::into_raw(Box::new(OxigraphStore { inner }))) }
+    })
+}
+
+/// Opens a read-write store backed by RocksDB in the `path` directory.
+///
+/// Only one read-write store can be opened on a given directory at a time.
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string and `store` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn oxigraph_store_open(
+    path: *const c_char,
+    store: *mut *mut OxigraphStore,
+) -> OxigraphStatus {
+    run(|| {
+        // SAFETY: the caller guarantees that the string is NUL-terminated
+        let inner = Store::open(unsafe { str_arg(path, "path") }?)?;
+        // SAFETY: the caller guarantees that the pointer is valid for writes
+        unsafe { write_out(store, Box::into_raw(Box::new(OxigraphStore { inner }))) }
+    })
+}
+
+/// Opens a read-only store backed by RocksDB in the `path` directory.
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string and `store` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn oxigraph_store_open_read_only(
+    path: *const c_char,
+    store: *mut *mut OxigraphStore,
+) -> OxigraphStatus {
+    run(|| {
+        // SAFETY: the caller guarantees that the string is NUL-terminated
+        let inner = Store::open_read_only(unsafe { str_arg(path, "path") }?)?;
+        // SAFETY: the caller guarantees that the pointer is valid for writes
+        unsafe { write_out(store, Box::into_raw(Box::new(OxigraphStore { inner }))) }
+    })
+}
+
+/// Frees a store. Does nothing if `store` is null.
+///
+/// # Safety
+///
+/// `store` must be null or a store returned by Oxigraph that has not been freed already.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn oxigraph_store_free(store: *mut OxigraphStore) {
+    if !store.is_null() {
+        // SAFETY: the store has been allocated by `Box::into_raw`
+        drop(unsafe { Box::from_raw(store) });
+    }
+}
+
+/// Evaluates a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) and writes its serialized results into `results`.
+///
+/// `format` is a media type or a file extension.
+/// It must be a [SPARQL results format](https://www.w3.org/TR/sparql11-results-json/) for `SELECT` and `ASK` queries and an RDF format for `CONSTRUCT` and `DESCRIBE` queries.
+/// If it is null, SPARQL results JSON or N-Triples are used.
+///
+/// # Safety
+///
+/// `store` must be a valid store, `query` and `format` NUL-terminated strings and `results` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn oxigraph_store_query(
+    store: *const OxigraphStore,
+    query: *const c_char,
+    format: *const c_char,
+    results: *mut OxigraphBuffer,
+) -> OxigraphStatus {
+    run(|| {
+        // SAFETY: the caller guarantees that the store is valid
+        let store = unsafe { store_arg(store) }?;
+        // SAFETY: the caller guarantees that the strings are NUL-terminated
+        let (query, format) = unsafe {
+            (
+                str_arg(query, "query")?,
+                optional_str_arg(format, "format")?,
+            )
+        };
+        let output = match store.query(query)? {
+            QueryResults::Graph(triples) => {
+                let format = match format {
+                    Some(format) => RdfFormat::from_media_type(format)
+                        .or_else(|| RdfFormat::from_extension(format))
+                        .ok_or_else(|| unsupported_format(format, "CONSTRUCT and DESCRIBE"))?,
+                    None => RdfFormat::NTriples,
+                };
+                QueryResults::Graph(triples).write_graph(Vec::new(), format)?
+            }
+            results => {
+                let format = match format {
+                    Some(format) => QueryResultsFormat::from_media_type(format)
+                        .or_else(|| QueryResultsFormat::from_extension(format))
+                        .ok_or_else(|| unsupported_format(format, "SELECT and ASK"))?,
+                    None => QueryResultsFormat::Json,
+                };
+                results.write(Vec::new(), format)?
+            }
+        };
+        // SAFETY: the caller guarantees that the pointer is valid for writes
+        unsafe { write_out(results, output.into()) }
+    })
+}
+
+/// Executes a [SPARQL 1.1 update](https://www.w3.org/TR/sparql11-update/).
+///
+/// The update is applied in a transaction.
+///
+/// # Safety
+///
+/// `store` must be a valid store and `update` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn oxigraph_store_update(
+    store: *const OxigraphStore,
+    update: *const c_char,
+) -> OxigraphStatus {
+    run(|| {
+        // SAFETY: the caller guarantees that the store is valid
+        let store = unsafe { store_arg(store) }?;
+        // SAFETY: the caller guarantees that the string is NUL-terminated
+        store.update(unsafe { str_arg(update, "update") }?)?;
+        Ok(())
+    })
+}
+
+/// Loads an RDF file into the store.
+///
+/// The file is loaded in a single transaction.
+/// `format` is a media type or a file extension.
+/// `base_iri` is the base IRI used to resolve relative IRIs and might be null.
+/// `to_graph` is the IRI of the graph the triples are loaded into.
+/// If it is null, the triples are loaded into the default graph.
+///
+/// # Safety
+///
+/// `store` must be a valid store, `data` must be valid for `data_len` bytes reads
+/// and `format`, `base_iri` and `to_graph` must be null or NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn oxigraph_store_load(
+    store: *const OxigraphStore,
+    data: *const u8,
+    data_len: usize,
+    format: *const c_char,
+    base_iri: *const c_char,
+    to_graph: *const c_char,
+) -> OxigraphStatus {
+    run(|| {
+        // SAFETY: the caller guarantees that the store is valid
+        let store = unsafe { store_arg(store) }?;
+        // SAFETY: the caller guarantees that the strings are NUL-terminated
+        let (format, base_iri, to_graph) = unsafe {
+            (
+                str_arg(format, "format")?,
+                optional_str_arg(base_iri, "base IRI")?,
+                optional_str_arg(to_graph, "graph name")?,
+            )
+        };
+        let data = if data_len == 0 {
+            &[]
+        } else if data.is_null() {
+            return Err(Error::invalid_argument("The data must not be null"));
+        } else {
+            // SAFETY: the caller guarantees that the data is valid for data_len bytes
+            unsafe { slice::from_raw_parts(data, data_len) }
+        };
+        let format = RdfFormat::from_media_type(format)
+            .or_else(|| RdfFormat::from_extension(format))
+            .ok_or_else(|| {
+                Error::new(
+                    OxigraphStatus::UnsupportedFormat,
+                    format!("Not supported RDF format: {format}"),
+                )
+            })?;
+        let mut parser = RdfParser::from_format(format);
+        if let Some(base_iri) = base_iri {
+            parser = parser.with_base_iri(base_iri)?;
+        }
+        if let Some(to_graph) = to_graph {
+            parser = parser.with_default_graph(NamedNode::new(to_graph)?);
+        }
+        store.load_from_reader(parser, data)?;
+        Ok(())
+    })
+}
+
+/// Reads a store argument
+///
+/// # Safety
+///
+/// The pointer must be null or point to a valid store for `'a`.
+unsafe fn store_arg<'a>(store: *const OxigraphStore) -> Result<&'a Store, Error> {
+    // SAFETY: the caller guarantees that the pointer is null or valid
+    unsafe { store.as_ref() }
+        .map(|store| &store.inner)
+        .ok_or_else(|| Error::invalid_argument("The store must not be null"))
+}
+
+fn unsupported_format(format: &str, kind: &str) -> Error {
+    Error::new(
+        OxigraphStatus::UnsupportedFormat,
+        format!("The format {format} is not supported for {kind} query results"),
+    )
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn, clippy::undocumented_unsafe_blocks)]
+mod tests {
+    use super::*;
+    use crate::{oxigraph_buffer_free, oxigraph_last_error_message};
+    use std::error::Error;
+    use std::ffi::{CStr, CString};
+    use std::ptr;
+
+    struct TestStore(*mut OxigraphStore);
+
+    impl TestStore {
+        fn new() -> Self {
+            let mut store = ptr::null_mut();
+            assert_eq!(
+                unsafe { oxigraph_store_new(&raw mut store) },
+                OxigraphStatus::Ok
+            );
+            Self(store)
+        }
+
+        fn query(&self, query: &str, format: Option<&str>) -> (OxigraphStatus, String) {
+            let query = CString::new(query).unwrap();
+            let format = format.map(|f| CString::new(f).unwrap());
+            let mut results = OxigraphBuffer {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            let status = unsafe {
+                oxigraph_store_query(
+                    self.0,
+                    query.as_ptr(),
+                    format.as_ref().map_or(ptr::null(), |f| f.as_ptr()),
+                    &raw mut results,
+                )
+            };
+            if status != OxigraphStatus::Ok {
+                return (status, last_error_message());
+            }
+            let output = unsafe { slice::from_raw_parts(results.data, results.len) }.to_vec();
+            unsafe {
+                oxigraph_buffer_free(results);
+            }
+            (status, String::from_utf8(output).unwrap())
+        }
+    }
+
+    impl Drop for TestStore {
+        fn drop(&mut self) {
+            unsafe { oxigraph_store_free(self.0) }
+        }
+    }
+
+    fn last_error_message() -> String {
+        unsafe { CStr::from_ptr(oxigraph_last_error_message()) }
+            .to_str()
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn load_and_query() -> Result<(), Box<dyn Error>> {
+        let store = TestStore::new();
+        let data = "<s> <http://example.com/p> \"o\" .";
+        let format = CString::new("text/turtle")?;
+        let base_iri = CString::new("http://example.com/")?;
+        let to_graph = CString::new("http://example.com/g")?;
+        let status = unsafe {
+            oxigraph_store_load(
+                store.0,
+                data.as_ptr(),
+                data.len(),
+                format.as_ptr(),
+                base_iri.as_ptr(),
+                to_graph.as_ptr(),
+            )
+        };
+        assert_eq!(status, OxigraphStatus::Ok);
+        assert_eq!(last_error_message(), "");
+        assert_eq!(
+            store.query("SELECT ?s WHERE { GRAPH ?g { ?s ?p ?o } }", Some("csv")),
+            (OxigraphStatus::Ok, "s\r\nhttp://example.com/s\r\n".into())
+        );
+        assert_eq!(
+            store.query("ASK { ?s ?p ?o }", None),
+            (OxigraphStatus::Ok, "{\"head\":{},\"boolean\":false}".into())
+        );
+        assert_eq!(
+            store.query(
+                "CONSTRUCT { ?s ?p ?o } WHERE { GRAPH ?g { ?s ?p ?o } }",
+                Some("application/n-triples")
+            ),
+            (
+                OxigraphStatus::Ok,
+                "<http://example.com/s> <http://example.com/p> \"o\" .\n".into()
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update() -> Result<(), Box<dyn Error>> {
+        let store = TestStore::new();
+        let update =
+            CString::new("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 }")?;
+        assert_eq!(
+            unsafe { oxigraph_store_update(store.0, update.as_ptr()) },
+            OxigraphStatus::Ok
+        );
+        assert_eq!(
+            store.query("SELECT (COUNT(*) AS ?c) WHERE { ?s ?p ?o }", Some("tsv")),
+            (OxigraphStatus::Ok, "?c\n1\n".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> Result<(), Box<dyn Error>> {
+        let store = TestStore::new();
+        assert_eq!(store.query("SELECT", None).0, OxigraphStatus::Syntax);
+        assert_eq!(
+            store.query("ASK {}", Some("application/n-triples")).0,
+            OxigraphStatus::UnsupportedFormat
+        );
+        assert_eq!(
+            store.query("CONSTRUCT WHERE { ?s ?p ?o }", Some("srj")).0,
+            OxigraphStatus::UnsupportedFormat
+        );
+        assert_eq!(
+            unsafe { oxigraph_store_update(store.0, ptr::null()) },
+            OxigraphStatus::InvalidArgument
+        );
+        assert_eq!(last_error_message(), "The update must not be null");
+        let format = CString::new("nt")?;
+        let data = "<s> <p> <o> .";
+        assert_eq!(
+            unsafe {
+                oxigraph_store_load(
+                    store.0,
+                    data.as_ptr(),
+                    data.len(),
+                    format.as_ptr(),
+                    ptr::null(),
+                    ptr::null(),
+                )
+            },
+            OxigraphStatus::Syntax
+        );
+        Ok(())
+    }
+}
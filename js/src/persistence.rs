@@ -0,0 +1,178 @@
+//! Persistence of the store content in an [IndexedDB](https://developer.mozilla.org/docs/Web/API/IndexedDB_API) database.
+//!
+//! Each quad is a key of the `quads` object store, serialized as a N-Quads line.
+//! The content is fully loaded in memory when the store is opened
+//! and the changes of each committed transaction are then written to the database in a single IndexedDB transaction.
+use crate::format_err;
+use js_sys::{Array, Promise, Reflect};
+use oxigraph::io::RdfFormat;
+use oxigraph::store::{Store, StoreEvent};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc::Receiver;
+use wasm_bindgen::prelude::*;
+use web_sys::{IdbDatabase, IdbFactory, IdbRequest, IdbTransactionMode};
+
+const DATABASE_VERSION: u32 = 1;
+const QUADS_OBJECT_STORE: &str = "quads";
+
+pub struct IndexedDbPersistence {
+    database: IdbDatabase,
+    changes: Receiver<Vec<StoreEvent>>,
+    /// The error of the first failed write, reported by the next flush
+    write_error: Rc<RefCell<Option<JsValue>>>,
+}
+
+impl IndexedDbPersistence {
+    /// Opens the IndexedDB database `name`, creating it if it does not exist,
+    /// and calls `callback` with a store filled with its content and the persistence of this store.
+    pub fn open(
+        name: &str,
+        callback: impl FnOnce(Result<(Store, Self), JsValue>) -> Result<(), JsValue> + 'static,
+    ) -> Result<(), JsValue> {
+        let factory = Reflect::get(&js_sys::global(), &JsValue::from_str("indexedDB"))?;
+        if factory.is_undefined() || factory.is_null() {
+            return Err(format_err!(
+                "IndexedDB is not available in this JavaScript environment"
+            ));
+        }
+        let request = factory
+            .unchecked_into::<IdbFactory>()
+            .open_with_u32(name, DATABASE_VERSION)?;
+        let upgraded = request.clone();
+        let on_upgrade_needed = Closure::<dyn FnMut() -> Result<(), JsValue>>::new(move || {
+            upgraded
+                .result()?
+                .unchecked_into::<IdbDatabase>()
+                .create_object_store(QUADS_OBJECT_STORE)?;
+            Ok(())
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.as_ref().unchecked_ref()));
+        on_done(&request, move |database| {
+            drop(on_upgrade_needed);
+            let keys_request = database.and_then(|database| {
+                let database = database.unchecked_into::<IdbDatabase>();
+                let keys_request = database
+                    .transaction_with_str(QUADS_OBJECT_STORE)?
+                    .object_store(QUADS_OBJECT_STORE)?
+                    .get_all_keys()?;
+                Ok((database, keys_request))
+            });
+            match keys_request {
+                Ok((database, keys_request)) => {
+                    on_done(&keys_request, move |keys| {
+                        callback(keys.and_then(|keys| Self::load(database, &keys)))
+                    });
+                    Ok(())
+                }
+                Err(e) => callback(Err(e)),
+            }
+        });
+        Ok(())
+    }
+
+    fn load(database: IdbDatabase, keys: &JsValue) -> Result<(Store, Self), JsValue> {
+        let mut content = String::new();
+        for key in Array::from(keys) {
+            content.push_str(
+                &key.as_string()
+                    .ok_or_else(|| format_err!("The IndexedDB keys must be N-Quads strings"))?,
+            );
+            content.push('\n');
+        }
+        let store = Store::new().map_err(JsError::from)?;
+        store
+            .bulk_loader()
+            .load_from_reader(RdfFormat::NQuads, content.as_bytes())
+            .map_err(JsError::from)?;
+        let changes = store.watch().map_err(JsError::from)?;
+        Ok((
+            store,
+            Self {
+                database,
+                changes,
+                write_error: Rc::default(),
+            },
+        ))
+    }
+
+    /// Writes to the database the changes committed to the store since the last call
+    ///
+    /// The writes are done asynchronously, in the order of the transactions, and their errors are reported by [`Self::flush`].
+    pub fn write_changes(&self) -> Result<(), JsValue> {
+        for events in self.changes.try_iter() {
+            let transaction = self
+                .database
+                .transaction_with_str_and_mode(QUADS_OBJECT_STORE, IdbTransactionMode::Readwrite)?;
+            let quads = transaction.object_store(QUADS_OBJECT_STORE)?;
+            for event in events {
+                match event {
+                    StoreEvent::Inserted(quad) => {
+                        quads.put_with_key(&JsValue::NULL, &JsValue::from(format!("{quad} .")))?;
+                    }
+                    StoreEvent::Removed(quad) => {
+                        quads.delete(&JsValue::from(format!("{quad} .")))?;
+                    }
+                }
+            }
+            let write_error = Rc::clone(&self.write_error);
+            let done = transaction.clone();
+            // A single function is used for both events so that it is called exactly once and then freed
+            let handler = Closure::once_into_js(move || {
+                if let Some(error) = done.error() {
+                    write_error.borrow_mut().get_or_insert(error.into());
+                }
+            });
+            transaction.set_oncomplete(Some(handler.unchecked_ref()));
+            transaction.set_onabort(Some(handler.unchecked_ref()));
+        }
+        Ok(())
+    }
+
+    /// Returns a promise resolved when all the previous writes are done or rejected with the first write error
+    pub fn flush(&self) -> Result<Promise, JsValue> {
+        // A read-only transaction on the object store only starts when the previous read-write ones are finished
+        let transaction = self.database.transaction_with_str(QUADS_OBJECT_STORE)?;
+        Ok(Promise::new(&mut |resolve, reject| {
+            let write_error = Rc::clone(&self.write_error);
+            let done = transaction.clone();
+            let handler = Closure::once_into_js(move || {
+                if let Some(error) = write_error.take() {
+                    reject.call1(&JsValue::UNDEFINED, &error)
+                } else if let Some(error) = done.error() {
+                    reject.call1(&JsValue::UNDEFINED, &error)
+                } else {
+                    resolve.call0(&JsValue::UNDEFINED)
+                }
+                .map(|_| ())
+            });
+            transaction.set_oncomplete(Some(handler.unchecked_ref()));
+            transaction.set_onabort(Some(handler.unchecked_ref()));
+        }))
+    }
+}
+
+impl Drop for IndexedDbPersistence {
+    fn drop(&mut self) {
+        // The database is only closed once the pending writes are done
+        self.database.close();
+    }
+}
+
+/// Calls `callback` with the result of the IndexedDB request when it succeeds or with its error when it fails
+fn on_done(
+    request: &IdbRequest,
+    callback: impl FnOnce(Result<JsValue, JsValue>) -> Result<(), JsValue> + 'static,
+) {
+    let done = request.clone();
+    // A single function is used for both events so that it is called exactly once and then freed
+    let handler = Closure::once_into_js(move || {
+        callback(match done.error() {
+            Ok(Some(error)) => Err(error.into()),
+            Ok(None) => done.result(),
+            Err(error) => Err(error),
+        })
+    });
+    request.set_onsuccess(Some(handler.unchecked_ref()));
+    request.set_onerror(Some(handler.unchecked_ref()));
+}
@@ -1,13 +1,15 @@
 use crate::model::*;
+use crate::persistence::IndexedDbPersistence;
 use crate::{console_warn, format_err};
-use js_sys::{Array, Map, Reflect, try_iter};
-use oxigraph::io::{RdfFormat, RdfParser};
+use js_sys::{Array, Map, Promise, Reflect, try_iter};
+use oxigraph::io::{RdfFormat, RdfParser, RdfSerializer};
 use oxigraph::model::*;
-use oxigraph::sparql::results::QueryResultsFormat;
-use oxigraph::sparql::{Query, QueryOptions, QueryResults, Update};
+use oxigraph::sparql::results::{QueryResultsFormat, QueryResultsSerializer};
+use oxigraph::sparql::{EvaluationError, Query, QueryOptions, QueryResults, Update};
 use oxigraph::store::Store;
 #[cfg(feature = "geosparql")]
 use spargeo::register_geosparql_functions;
+use std::time::Duration;
 use wasm_bindgen::prelude::*;
 
 // We skip_typescript on specific wasm_bindgen macros and provide custom TypeScript types for parts of this module in order to have narrower types
@@ -21,6 +23,8 @@ export class Store {
 
     constructor(quads?: Iterable<Quad>);
 
+    static open(name: string): Promise<Store>;
+
     add(quad: Quad): void;
 
     delete(quad: Quad): void;
@@ -32,6 +36,8 @@ export class Store {
         }
     ): string;
 
+    flush(): Promise<void>;
+
     has(quad: Quad): boolean;
 
     load(
@@ -56,6 +62,7 @@ export class Store {
             default_graph?: BlankNode | DefaultGraph | NamedNode | Iterable<BlankNode | DefaultGraph | NamedNode>;
             named_graphs?: Iterable<BlankNode | NamedNode>;
             use_default_graph_as_union?: boolean;
+            timeout?: number;
         }
     ): boolean | Map<string, Term>[] | Quad[] | string;
 
@@ -71,6 +78,7 @@ export class Store {
 #[wasm_bindgen(js_name = Store, skip_typescript)]
 pub struct JsStore {
     store: Store,
+    persistence: Option<IndexedDbPersistence>,
 }
 
 #[wasm_bindgen(js_class = Store)]
@@ -81,6 +89,7 @@ impl JsStore {
 
         let store = Self {
             store: Store::new().map_err(JsError::from)?,
+            persistence: None,
         };
         if !quads.is_undefined() && !quads.is_null() {
             if let Some(quads) = try_iter(quads)? {
@@ -92,18 +101,48 @@ impl JsStore {
         Ok(store)
     }
 
+    pub fn open(name: &str) -> Promise {
+        Promise::new(&mut |resolve, reject| {
+            let failed = reject.clone();
+            if let Err(e) = IndexedDbPersistence::open(name, move |opened| {
+                match opened {
+                    Ok((store, persistence)) => resolve.call1(
+                        &JsValue::UNDEFINED,
+                        &Self {
+                            store,
+                            persistence: Some(persistence),
+                        }
+                        .into(),
+                    ),
+                    Err(e) => reject.call1(&JsValue::UNDEFINED, &e),
+                }
+                .map(|_| ())
+            }) {
+                failed.call1(&JsValue::UNDEFINED, &e).unwrap_throw();
+            }
+        })
+    }
+
+    pub fn flush(&self) -> Result<Promise, JsValue> {
+        if let Some(persistence) = &self.persistence {
+            persistence.flush()
+        } else {
+            Ok(Promise::resolve(&JsValue::UNDEFINED))
+        }
+    }
+
     pub fn add(&self, quad: &JsValue) -> Result<(), JsValue> {
         self.store
             .insert(&FROM_JS.with(|c| c.to_quad(quad))?)
             .map_err(JsError::from)?;
-        Ok(())
+        write_changes(self.persistence.as_ref())
     }
 
     pub fn delete(&self, quad: &JsValue) -> Result<(), JsValue> {
         self.store
             .remove(&FROM_JS.with(|c| c.to_quad(quad))?)
             .map_err(JsError::from)?;
-        Ok(())
+        write_changes(self.persistence.as_ref())
     }
 
     pub fn has(&self, quad: &JsValue) -> Result<bool, JsValue> {
@@ -171,6 +210,7 @@ impl JsStore {
         let mut results_format = None;
         let mut default_graph = None;
         let mut named_graphs = None;
+        let mut timeout = None;
         if !options.is_undefined() {
             base_iri = convert_base_iri(&Reflect::get(options, &JsValue::from_str("base_iri"))?)?;
 
@@ -212,6 +252,17 @@ impl JsStore {
                         .ok_or_else(|| format_err!("results_format option must be a string"))?,
                 );
             }
+
+            let js_timeout = Reflect::get(options, &JsValue::from_str("timeout"))?;
+            if !js_timeout.is_undefined() && !js_timeout.is_null() {
+                let timeout_millis = js_timeout
+                    .as_f64()
+                    .ok_or_else(|| format_err!("timeout option must be a number"))?;
+                timeout = Some(
+                    Duration::try_from_secs_f64(timeout_millis / 1000.)
+                        .map_err(|e| format_err!("Invalid timeout option: {}", e))?,
+                );
+            }
         }
 
         let mut query = Query::parse(query, base_iri.as_deref()).map_err(JsError::from)?;
@@ -225,34 +276,49 @@ impl JsStore {
             query.dataset_mut().set_available_named_graphs(named_graphs);
         }
 
-        #[cfg_attr(not(feature = "geosparql"), expect(unused_mut))]
         let mut options = QueryOptions::default();
         #[cfg(feature = "geosparql")]
         {
             options = register_geosparql_functions(options);
         }
+        if let Some(timeout) = timeout {
+            options = options.with_timeout(timeout);
+        }
 
         let results = self
             .store
             .query_opt(query, options)
-            .map_err(JsError::from)?;
+            .map_err(|e| map_query_evaluation_error(e, false))?;
 
+        // Tracks if some results have been computed before a timeout
+        let mut has_partial_results = false;
         Ok(match results {
             QueryResults::Solutions(solutions) => {
                 if let Some(results_format) = results_format {
                     let results_format = query_results_format(&results_format)?;
+                    let mut serializer = QueryResultsSerializer::from_format(results_format)
+                        .serialize_solutions_to_writer(Vec::new(), solutions.variables().to_vec())
+                        .map_err(JsError::from)?;
+                    for solution in solutions {
+                        serializer
+                            .serialize(
+                                &solution.map_err(|e| {
+                                    map_query_evaluation_error(e, has_partial_results)
+                                })?,
+                            )
+                            .map_err(JsError::from)?;
+                        has_partial_results = true;
+                    }
                     JsValue::from_str(
-                        &String::from_utf8(
-                            QueryResults::Solutions(solutions)
-                                .write(Vec::new(), results_format)
-                                .map_err(JsError::from)?,
-                        )
-                        .map_err(JsError::from)?,
+                        &String::from_utf8(serializer.finish().map_err(JsError::from)?)
+                            .map_err(JsError::from)?,
                     )
                 } else {
                     let results = Array::new();
                     for solution in solutions {
-                        let solution = solution.map_err(JsError::from)?;
+                        let solution = solution
+                            .map_err(|e| map_query_evaluation_error(e, has_partial_results))?;
+                        has_partial_results = true;
                         let result = Map::new();
                         for (variable, value) in solution.iter() {
                             result.set(
@@ -265,27 +331,33 @@ impl JsStore {
                     results.into()
                 }
             }
-            QueryResults::Graph(quads) => {
+            QueryResults::Graph(triples) => {
                 if let Some(results_format) = results_format {
                     let rdf_format = rdf_format(&results_format)?;
+                    let mut serializer =
+                        RdfSerializer::from_format(rdf_format).for_writer(Vec::new());
+                    for triple in triples {
+                        serializer
+                            .serialize_triple(
+                                &triple.map_err(|e| {
+                                    map_query_evaluation_error(e, has_partial_results)
+                                })?,
+                            )
+                            .map_err(JsError::from)?;
+                        has_partial_results = true;
+                    }
                     JsValue::from_str(
-                        &String::from_utf8(
-                            QueryResults::Graph(quads)
-                                .write_graph(Vec::new(), rdf_format)
-                                .map_err(JsError::from)?,
-                        )
-                        .map_err(JsError::from)?,
+                        &String::from_utf8(serializer.finish().map_err(JsError::from)?)
+                            .map_err(JsError::from)?,
                     )
                 } else {
                     let results = Array::new();
-                    for quad in quads {
-                        results.push(
-                            &JsQuad::from(
-                                quad.map_err(JsError::from)?
-                                    .in_graph(GraphName::DefaultGraph),
-                            )
-                            .into(),
-                        );
+                    for triple in triples {
+                        let triple = triple
+                            .map_err(|e| map_query_evaluation_error(e, has_partial_results))?;
+                        has_partial_results = true;
+                        results
+                            .push(&JsQuad::from(triple.in_graph(GraphName::DefaultGraph)).into());
                     }
                     results.into()
                 }
@@ -324,10 +396,10 @@ impl JsStore {
             options = register_geosparql_functions(options);
         }
 
-        Ok(self
-            .store
+        self.store
             .update_opt(update, options)
-            .map_err(JsError::from)?)
+            .map_err(JsError::from)?;
+        write_changes(self.persistence.as_ref())
     }
 
     pub fn load(
@@ -395,14 +467,16 @@ impl JsStore {
         } else if lenient {
             parser = parser.lenient();
         }
-        Ok(if no_transaction {
+        // The bulk loader changes are not reported to the persistence
+        if no_transaction && self.persistence.is_none() {
             self.store
                 .bulk_loader()
                 .load_from_reader(parser, data.as_bytes())
         } else {
             self.store.load_from_reader(parser, data.as_bytes())
         }
-        .map_err(JsError::from)?)
+        .map_err(JsError::from)?;
+        write_changes(self.persistence.as_ref())
     }
 
     pub fn dump(&self, options: &JsValue, from_graph_name: &JsValue) -> Result<String, JsValue> {
@@ -447,6 +521,31 @@ impl JsStore {
     }
 }
 
+/// Writes the committed changes to the IndexedDB database if the store has been opened from one
+fn write_changes(persistence: Option<&IndexedDbPersistence>) -> Result<(), JsValue> {
+    if let Some(persistence) = persistence {
+        persistence.write_changes()?;
+    }
+    Ok(())
+}
+
+/// Converts timeouts into a `QueryTimeoutError` error with a `partialResults` property.
+fn map_query_evaluation_error(error: EvaluationError, has_partial_results: bool) -> JsValue {
+    if !matches!(error, EvaluationError::Timeout) {
+        return JsError::from(error).into();
+    }
+    let js_error = js_sys::Error::new(&error.to_string());
+    js_error.set_name("QueryTimeoutError");
+    if let Err(e) = Reflect::set(
+        &js_error,
+        &JsValue::from_str("partialResults"),
+        &JsValue::from_bool(has_partial_results),
+    ) {
+        return e;
+    }
+    js_error.into()
+}
+
 fn rdf_format(format: &str) -> Result<RdfFormat, JsValue> {
     if format.contains('/') {
         RdfFormat::from_media_type(format)
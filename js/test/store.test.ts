@@ -169,6 +169,14 @@ describe("Store", () => {
             });
             assert.strictEqual("true", results);
         });
+
+        it("SELECT with timeout", () => {
+            const store = new Store([dataModel.quad(ex, ex, ex)]);
+            assert.throws(
+                () => store.query("SELECT * WHERE { ?s ?p ?o }", { timeout: 0 }),
+                { name: "QueryTimeoutError", partialResults: false },
+            );
+        });
     });
 
     describe("#update()", () => {
@@ -297,4 +305,35 @@ describe("Store", () => {
             );
         });
     });
+
+    describe("#open()", () => {
+        it("fails without IndexedDB", { skip: "indexedDB" in globalThis }, async () => {
+            await assert.rejects(Store.open("oxigraph-test"));
+        });
+
+        it(
+            "persists the changes between sessions",
+            { skip: !("indexedDB" in globalThis) },
+            async () => {
+                const store = await Store.open("oxigraph-test");
+                store.add(dataModel.quad(ex, ex, triple, ex));
+                store.update("INSERT DATA { <http://example.com/2> <http://example.com> 1 }");
+                store.delete(dataModel.quad(ex, ex, triple, ex));
+                await store.flush();
+
+                const reopened = await Store.open("oxigraph-test");
+                assert(!reopened.has(dataModel.quad(ex, ex, triple, ex)));
+                assert.strictEqual(
+                    reopened.dump({ format: "nt" }),
+                    '<http://example.com/2> <http://example.com> "1"^^<http://www.w3.org/2001/XMLSchema#integer> .\n',
+                );
+            },
+        );
+    });
+
+    describe("#flush()", () => {
+        it("resolves for a not persisted store", async () => {
+            await new Store([dataModel.quad(ex, ex, ex)]).flush();
+        });
+    });
 });
@@ -0,0 +1,138 @@
+use crate::OxigraphError;
+use crate::model::Quad;
+use oxigraph::io::{self, JsonLdProfileSet, RdfParser, RdfSerializer};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// RDF serialization formats.
+#[derive(uniffi::Enum, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RdfFormat {
+    /// [JSON-LD](https://www.w3.org/TR/json-ld/)
+    JsonLd,
+    /// [N3](https://w3c.github.io/N3/spec/)
+    N3,
+    /// [N-Quads](https://www.w3.org/TR/n-quads/)
+    NQuads,
+    /// [N-Triples](https://www.w3.org/TR/n-triples/)
+    NTriples,
+    /// [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/)
+    RdfXml,
+    /// [TriG](https://www.w3.org/TR/trig/)
+    TriG,
+    /// [Turtle](https://www.w3.org/TR/turtle/)
+    Turtle,
+}
+
+impl From<RdfFormat> for io::RdfFormat {
+    fn from(format: RdfFormat) -> Self {
+        match format {
+            RdfFormat::JsonLd => Self::JsonLd {
+                profile: JsonLdProfileSet::empty(),
+            },
+            RdfFormat::N3 => Self::N3,
+            RdfFormat::NQuads => Self::NQuads,
+            RdfFormat::NTriples => Self::NTriples,
+            RdfFormat::RdfXml => Self::RdfXml,
+            RdfFormat::TriG => Self::TriG,
+            RdfFormat::Turtle => Self::Turtle,
+        }
+    }
+}
+
+impl RdfFormat {
+    fn from_inner(format: io::RdfFormat) -> Option<Self> {
+        Some(match format {
+            io::RdfFormat::JsonLd { .. } => Self::JsonLd,
+            io::RdfFormat::N3 => Self::N3,
+            io::RdfFormat::NQuads => Self::NQuads,
+            io::RdfFormat::NTriples => Self::NTriples,
+            io::RdfFormat::RdfXml => Self::RdfXml,
+            io::RdfFormat::TriG => Self::TriG,
+            io::RdfFormat::Turtle => Self::Turtle,
+            _ => return None,
+        })
+    }
+}
+
+/// Looks for a known RDF format from a media type like `text/turtle`.
+#[uniffi::export]
+pub fn rdf_format_from_media_type(media_type: &str) -> Option<RdfFormat> {
+    io::RdfFormat::from_media_type(media_type).and_then(RdfFormat::from_inner)
+}
+
+/// Looks for a known RDF format from a file extension like `ttl`.
+#[uniffi::export]
+pub fn rdf_format_from_extension(extension: &str) -> Option<RdfFormat> {
+    io::RdfFormat::from_extension(extension).and_then(RdfFormat::from_inner)
+}
+
+/// Parses RDF graph and dataset serialization formats.
+///
+/// `base_iri` is used to resolve the relative IRIs.
+/// If `without_named_graphs` is set, the parser fails on named graphs.
+/// If `rename_blank_nodes` is set, the blank node ids are replaced with random ones.
+#[uniffi::export(default(base_iri = None, without_named_graphs = false, rename_blank_nodes = false))]
+pub fn parse(
+    input: &[u8],
+    format: RdfFormat,
+    base_iri: Option<String>,
+    without_named_graphs: bool,
+    rename_blank_nodes: bool,
+) -> Result<Vec<Arc<Quad>>, OxigraphError> {
+    let mut parser = RdfParser::from_format(format.into());
+    if let Some(base_iri) = base_iri {
+        parser = parser.with_base_iri(base_iri)?;
+    }
+    if without_named_graphs {
+        parser = parser.without_named_graphs();
+    }
+    if rename_blank_nodes {
+        parser = parser.rename_blank_nodes();
+    }
+    parser
+        .for_slice(input)
+        .map(|quad| Ok(Arc::new(quad?.into())))
+        .collect()
+}
+
+/// Serializes quads into an RDF format.
+///
+/// `prefixes` maps prefix names to prefix IRIs. They are used by the formats that support them like Turtle.
+/// `base_iri` is used to write relative IRIs.
+/// Serializing a quad that is not in the default graph into a graph format like Turtle fails.
+#[uniffi::export(default(prefixes = None, base_iri = None))]
+pub fn serialize(
+    input: Vec<Arc<Quad>>,
+    format: RdfFormat,
+    prefixes: Option<HashMap<String, String>>,
+    base_iri: Option<String>,
+) -> Result<Vec<u8>, OxigraphError> {
+    let mut serializer = rdf_serializer(format, prefixes, base_iri)?.for_writer(Vec::new());
+    for quad in &input {
+        serializer.serialize_quad(quad.as_ref())?;
+    }
+    Ok(serializer.finish()?)
+}
+
+pub fn rdf_serializer(
+    format: RdfFormat,
+    prefixes: Option<HashMap<String, String>>,
+    base_iri: Option<String>,
+) -> Result<RdfSerializer, OxigraphError> {
+    let mut serializer = RdfSerializer::from_format(format.into());
+    for (prefix_name, prefix_iri) in prefixes.unwrap_or_default() {
+        serializer = serializer
+            .with_prefix(&prefix_name, &prefix_iri)
+            .map_err(|e| {
+                OxigraphError::InvalidArgument(format!(
+                    "Invalid prefix {prefix_name} IRI '{prefix_iri}', {e}"
+                ))
+            })?;
+    }
+    if let Some(base_iri) = base_iri {
+        serializer = serializer.with_base_iri(&base_iri).map_err(|e| {
+            OxigraphError::InvalidArgument(format!("Invalid base IRI '{base_iri}', {e}"))
+        })?;
+    }
+    Ok(serializer)
+}
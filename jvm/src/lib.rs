@@ -0,0 +1,120 @@
+#![allow(clippy::implicit_hasher, clippy::needless_pass_by_value)]
+
+mod io;
+mod model;
+mod sparql;
+mod store;
+
+pub use crate::io::*;
+pub use crate::model::*;
+pub use crate::sparql::*;
+pub use crate::store::*;
+use oxigraph::io::{RdfParseError, RdfSyntaxError};
+use oxigraph::model::{BlankNodeIdParseError, IriParseError, LanguageTagParseError};
+use oxigraph::sparql::{EvaluationError, SparqlSyntaxError};
+use oxigraph::store::{LoaderError, SerializerError, StorageError};
+
+uniffi::setup_scaffolding!("oxigraph");
+
+/// An error raised by Oxigraph.
+///
+/// It is exposed as `OxigraphException` on the JVM.
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum OxigraphError {
+    /// An argument is invalid like an IRI, a blank node id or a language tag.
+    #[error("{0}")]
+    InvalidArgument(String),
+    /// The SPARQL query, the SPARQL update or the RDF data is not syntactically valid.
+    #[error("{0}")]
+    Syntax(String),
+    /// The SPARQL query or update evaluation failed.
+    #[error("{0}")]
+    Evaluation(String),
+    /// An error raised by the storage or during an I/O operation.
+    #[error("{0}")]
+    Storage(String),
+}
+
+impl From<IriParseError> for OxigraphError {
+    fn from(error: IriParseError) -> Self {
+        Self::InvalidArgument(error.to_string())
+    }
+}
+
+impl From<BlankNodeIdParseError> for OxigraphError {
+    fn from(error: BlankNodeIdParseError) -> Self {
+        Self::InvalidArgument(error.to_string())
+    }
+}
+
+impl From<LanguageTagParseError> for OxigraphError {
+    fn from(error: LanguageTagParseError) -> Self {
+        Self::InvalidArgument(error.to_string())
+    }
+}
+
+impl From<StorageError> for OxigraphError {
+    fn from(error: StorageError) -> Self {
+        Self::Storage(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for OxigraphError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Storage(error.to_string())
+    }
+}
+
+impl From<SparqlSyntaxError> for OxigraphError {
+    fn from(error: SparqlSyntaxError) -> Self {
+        Self::Syntax(error.to_string())
+    }
+}
+
+impl From<RdfParseError> for OxigraphError {
+    fn from(error: RdfParseError) -> Self {
+        match error {
+            RdfParseError::Io(error) => error.into(),
+            RdfParseError::Syntax(error) => error.into(),
+        }
+    }
+}
+
+impl From<RdfSyntaxError> for OxigraphError {
+    fn from(error: RdfSyntaxError) -> Self {
+        Self::Syntax(error.to_string())
+    }
+}
+
+impl From<EvaluationError> for OxigraphError {
+    fn from(error: EvaluationError) -> Self {
+        match error {
+            EvaluationError::Parsing(error) => error.into(),
+            EvaluationError::Storage(error) => error.into(),
+            EvaluationError::ResultsSerialization(error) => error.into(),
+            e => Self::Evaluation(e.to_string()),
+        }
+    }
+}
+
+impl From<LoaderError> for OxigraphError {
+    fn from(error: LoaderError) -> Self {
+        match error {
+            LoaderError::Parsing(error) => error.into(),
+            LoaderError::Storage(error) => error.into(),
+            LoaderError::InvalidBaseIri { .. } => Self::InvalidArgument(error.to_string()),
+        }
+    }
+}
+
+impl From<SerializerError> for OxigraphError {
+    fn from(error: SerializerError) -> Self {
+        match error {
+            SerializerError::Storage(error) => error.into(),
+            SerializerError::Io(error) => error.into(),
+            SerializerError::DatasetFormatExpected(_)
+            | SerializerError::LineBasedFormatExpected(_) => Self::InvalidArgument(error.to_string()),
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use crate::OxigraphError;
+use oxigraph::model;
+use std::fmt;
+use std::sync::Arc;
+
+/// An RDF [term](https://www.w3.org/TR/rdf12-concepts/#dfn-rdf-term).
+///
+/// The IRIs, blank node identifiers and language tags are validated when the term is used.
+#[derive(uniffi::Enum, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Term {
+    /// An RDF [IRI](https://www.w3.org/TR/rdf12-concepts/#dfn-iri).
+    NamedNode { value: String },
+    /// An RDF [blank node](https://www.w3.org/TR/rdf12-concepts/#dfn-blank-node) identified by its id.
+    BlankNode { value: String },
+    /// An RDF [literal](https://www.w3.org/TR/rdf12-concepts/#dfn-literal).
+    ///
+    /// `datatype` is the datatype IRI, it is ignored if `language` is set.
+    Literal {
+        value: String,
+        datatype: String,
+        language: Option<String>,
+        direction: Option<BaseDirection>,
+    },
+    /// An RDF [triple term](https://www.w3.org/TR/rdf12-concepts/#dfn-triple-term).
+    // Not named Triple because the Kotlin nested class would shadow the Triple class
+    #[expect(clippy::enum_variant_names)]
+    TripleTerm { triple: Arc<Triple> },
+}
+
+impl From<model::Term> for Term {
+    fn from(term: model::Term) -> Self {
+        match term {
+            model::Term::NamedNode(node) => node.into(),
+            model::Term::BlankNode(node) => node.into(),
+            model::Term::Literal(literal) => {
+                let datatype = literal.datatype().as_str().to_owned();
+                let (value, _, language, direction) = literal.destruct();
+                Self::Literal {
+                    value,
+                    datatype,
+                    language,
+                    direction: direction.map(Into::into),
+                }
+            }
+            model::Term::Triple(triple) => Self::TripleTerm {
+                triple: Arc::new(Triple { inner: *triple }),
+            },
+        }
+    }
+}
+
+impl From<model::NamedNode> for Term {
+    fn from(node: model::NamedNode) -> Self {
+        Self::NamedNode {
+            value: node.into_string(),
+        }
+    }
+}
+
+impl From<model::BlankNode> for Term {
+    fn from(node: model::BlankNode) -> Self {
+        Self::BlankNode {
+            value: node.into_string(),
+        }
+    }
+}
+
+impl From<model::NamedOrBlankNode> for Term {
+    fn from(node: model::NamedOrBlankNode) -> Self {
+        match node {
+            model::NamedOrBlankNode::NamedNode(node) => node.into(),
+            model::NamedOrBlankNode::BlankNode(node) => node.into(),
+        }
+    }
+}
+
+impl TryFrom<Term> for model::Term {
+    type Error = OxigraphError;
+
+    fn try_from(term: Term) -> Result<Self, OxigraphError> {
+        Ok(match term {
+            Term::NamedNode { value } => model::NamedNode::new(value)?.into(),
+            Term::BlankNode { value } => model::BlankNode::new(value)?.into(),
+            Term::Literal {
+                value,
+                datatype,
+                language,
+                direction,
+            } => match (language, direction) {
+                (Some(language), Some(direction)) => {
+                    model::Literal::new_directional_language_tagged_literal(
+                        value, language, direction,
+                    )?
+                    .into()
+                }
+                (Some(language), None) => {
+                    model::Literal::new_language_tagged_literal(value, language)?.into()
+                }
+                (None, Some(_)) => {
+                    return Err(OxigraphError::InvalidArgument(
+                        "A literal with a direction must have a language tag".into(),
+                    ));
+                }
+                (None, None) => {
+                    model::Literal::new_typed_literal(value, model::NamedNode::new(datatype)?)
+                        .into()
+                }
+            },
+            Term::TripleTerm { triple } => triple.inner.clone().into(),
+        })
+    }
+}
+
+/// Converts a term that must be a named node, `position` is used in the error message
+pub fn named_node(term: Term, position: &str) -> Result<model::NamedNode, OxigraphError> {
+    if let Term::NamedNode { value } = term {
+        Ok(model::NamedNode::new(value)?)
+    } else {
+        Err(OxigraphError::InvalidArgument(format!(
+            "The {position} must be a named node"
+        )))
+    }
+}
+
+/// Converts a term that must be a named node or a blank node, `position` is used in the error message
+pub fn named_or_blank_node(
+    term: Term,
+    position: &str,
+) -> Result<model::NamedOrBlankNode, OxigraphError> {
+    match term {
+        Term::NamedNode { value } => Ok(model::NamedNode::new(value)?.into()),
+        Term::BlankNode { value } => Ok(model::BlankNode::new(value)?.into()),
+        _ => Err(OxigraphError::InvalidArgument(format!(
+            "The {position} must be a named node or a blank node"
+        ))),
+    }
+}
+
+/// The initial text direction of a [directional language-tagged string](https://www.w3.org/TR/rdf12-concepts/#dfn-dir-lang-string).
+#[derive(uniffi::Enum, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BaseDirection {
+    Ltr,
+    Rtl,
+}
+
+impl From<model::BaseDirection> for BaseDirection {
+    fn from(direction: model::BaseDirection) -> Self {
+        match direction {
+            model::BaseDirection::Ltr => Self::Ltr,
+            model::BaseDirection::Rtl => Self::Rtl,
+        }
+    }
+}
+
+impl From<BaseDirection> for model::BaseDirection {
+    fn from(direction: BaseDirection) -> Self {
+        match direction {
+            BaseDirection::Ltr => Self::Ltr,
+            BaseDirection::Rtl => Self::Rtl,
+        }
+    }
+}
+
+/// The name of an RDF [graph](https://www.w3.org/TR/rdf12-concepts/#dfn-rdf-graph).
+#[derive(uniffi::Enum, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum GraphName {
+    /// A graph identified by an IRI.
+    NamedNode { value: String },
+    /// A graph identified by a blank node id.
+    BlankNode { value: String },
+    /// The [default graph](https://www.w3.org/TR/rdf12-concepts/#dfn-default-graph).
+    DefaultGraph,
+}
+
+impl From<model::GraphName> for GraphName {
+    fn from(graph_name: model::GraphName) -> Self {
+        match graph_name {
+            model::GraphName::NamedNode(node) => Self::NamedNode {
+                value: node.into_string(),
+            },
+            model::GraphName::BlankNode(node) => Self::BlankNode {
+                value: node.into_string(),
+            },
+            model::GraphName::DefaultGraph => Self::DefaultGraph,
+        }
+    }
+}
+
+impl From<model::NamedOrBlankNode> for GraphName {
+    fn from(node: model::NamedOrBlankNode) -> Self {
+        model::GraphName::from(node).into()
+    }
+}
+
+impl TryFrom<GraphName> for model::GraphName {
+    type Error = OxigraphError;
+
+    fn try_from(graph_name: GraphName) -> Result<Self, OxigraphError> {
+        Ok(match graph_name {
+            GraphName::NamedNode { value } => model::NamedNode::new(value)?.into(),
+            GraphName::BlankNode { value } => model::BlankNode::new(value)?.into(),
+            GraphName::DefaultGraph => Self::DefaultGraph,
+        })
+    }
+}
+
+/// Builds a blank node with a unique random id.
+#[uniffi::export]
+pub fn new_blank_node() -> Term {
+    model::BlankNode::default().into()
+}
+
+/// An RDF [triple](https://www.w3.org/TR/rdf12-concepts/#dfn-rdf-triple).
+///
+/// Its string representation follows the [N-Triples](https://www.w3.org/TR/n-triples/) syntax.
+#[derive(uniffi::Object, PartialEq, Eq, Hash, Debug)]
+#[uniffi::export(Display, Eq, Hash)]
+pub struct Triple {
+    inner: model::Triple,
+}
+
+#[uniffi::export]
+impl Triple {
+    /// Builds a triple. Fails if the subject is not a named node or a blank node or if the predicate is not a named node.
+    #[uniffi::constructor]
+    pub fn new(subject: Term, predicate: Term, object: Term) -> Result<Arc<Self>, OxigraphError> {
+        Ok(Arc::new(Self {
+            inner: model::Triple::new(
+                named_or_blank_node(subject, "subject")?,
+                named_node(predicate, "predicate")?,
+                model::Term::try_from(object)?,
+            ),
+        }))
+    }
+
+    pub fn subject(&self) -> Term {
+        self.inner.subject.clone().into()
+    }
+
+    pub fn predicate(&self) -> Term {
+        self.inner.predicate.clone().into()
+    }
+
+    pub fn object(&self) -> Term {
+        self.inner.object.clone().into()
+    }
+}
+
+impl fmt::Display for Triple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl From<model::Triple> for Triple {
+    fn from(inner: model::Triple) -> Self {
+        Self { inner }
+    }
+}
+
+impl From<&Triple> for model::Triple {
+    fn from(triple: &Triple) -> Self {
+        triple.inner.clone()
+    }
+}
+
+/// An RDF [triple](https://www.w3.org/TR/rdf12-concepts/#dfn-rdf-triple) in an [RDF dataset](https://www.w3.org/TR/rdf12-concepts/#dfn-rdf-dataset).
+///
+/// Its string representation follows the [N-Quads](https://www.w3.org/TR/n-quads/) syntax.
+#[derive(uniffi::Object, PartialEq, Eq, Hash, Debug)]
+#[uniffi::export(Display, Eq, Hash)]
+pub struct Quad {
+    inner: model::Quad,
+}
+
+#[uniffi::export]
+impl Quad {
+    /// Builds a quad. If `graph_name` is not set, the quad is in the default graph.
+    ///
+    /// Fails if the subject is not a named node or a blank node or if the predicate is not a named node.
+    #[uniffi::constructor(default(graph_name = None))]
+    pub fn new(
+        subject: Term,
+        predicate: Term,
+        object: Term,
+        graph_name: Option<GraphName>,
+    ) -> Result<Arc<Self>, OxigraphError> {
+        Ok(Arc::new(Self {
+            inner: model::Quad::new(
+                named_or_blank_node(subject, "subject")?,
+                named_node(predicate, "predicate")?,
+                model::Term::try_from(object)?,
+                graph_name.map_or(Ok(model::GraphName::DefaultGraph), TryInto::try_into)?,
+            ),
+        }))
+    }
+
+    pub fn subject(&self) -> Term {
+        self.inner.subject.clone().into()
+    }
+
+    pub fn predicate(&self) -> Term {
+        self.inner.predicate.clone().into()
+    }
+
+    pub fn object(&self) -> Term {
+        self.inner.object.clone().into()
+    }
+
+    pub fn graph_name(&self) -> GraphName {
+        self.inner.graph_name.clone().into()
+    }
+
+    /// The triple part of this quad.
+    pub fn triple(&self) -> Arc<Triple> {
+        Arc::new(model::Triple::from(self.inner.clone()).into())
+    }
+}
+
+impl fmt::Display for Quad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl From<model::Quad> for Quad {
+    fn from(inner: model::Quad) -> Self {
+        Self { inner }
+    }
+}
+
+impl From<&Quad> for model::Quad {
+    fn from(quad: &Quad) -> Self {
+        quad.inner.clone()
+    }
+}
+
+impl<'a> From<&'a Quad> for model::QuadRef<'a> {
+    fn from(quad: &'a Quad) -> Self {
+        quad.inner.as_ref()
+    }
+}
@@ -0,0 +1,119 @@
+use crate::OxigraphError;
+use crate::model::{Term, Triple};
+use oxigraph::sparql::results::{
+    QueryResultsFormat as InnerQueryResultsFormat, QueryResultsSerializer,
+};
+use oxigraph::sparql::{self, QuerySolution as InnerQuerySolution, Variable};
+use std::sync::Arc;
+
+/// The results of a SPARQL query.
+///
+/// The results are fully loaded in memory.
+#[derive(uniffi::Enum)]
+pub enum QueryResults {
+    /// The solutions of a `SELECT` query.
+    Solutions { solutions: Arc<QuerySolutions> },
+    /// The result of an `ASK` query.
+    Boolean { value: bool },
+    /// The triples returned by a `CONSTRUCT` or `DESCRIBE` query.
+    Graph { triples: Vec<Arc<Triple>> },
+}
+
+impl TryFrom<sparql::QueryResults> for QueryResults {
+    type Error = OxigraphError;
+
+    fn try_from(results: sparql::QueryResults) -> Result<Self, OxigraphError> {
+        Ok(match results {
+            sparql::QueryResults::Solutions(solutions) => Self::Solutions {
+                solutions: Arc::new(QuerySolutions {
+                    variables: solutions.variables().to_vec(),
+                    solutions: solutions
+                        .map(|solution| Ok(Arc::new(QuerySolution { inner: solution? })))
+                        .collect::<Result<_, OxigraphError>>()?,
+                }),
+            },
+            sparql::QueryResults::Boolean(value) => Self::Boolean { value },
+            sparql::QueryResults::Graph(triples) => Self::Graph {
+                triples: triples
+                    .map(|triple| Ok(Arc::new(triple?.into())))
+                    .collect::<Result<_, OxigraphError>>()?,
+            },
+        })
+    }
+}
+
+/// The solutions of a SPARQL `SELECT` query.
+#[derive(uniffi::Object)]
+pub struct QuerySolutions {
+    variables: Vec<Variable>,
+    solutions: Vec<Arc<QuerySolution>>,
+}
+
+#[uniffi::export]
+impl QuerySolutions {
+    /// The names of the variables of the solutions, without the `?` prefix.
+    pub fn variables(&self) -> Vec<String> {
+        self.variables.iter().map(|v| v.as_str().into()).collect()
+    }
+
+    pub fn solutions(&self) -> Vec<Arc<QuerySolution>> {
+        self.solutions.clone()
+    }
+
+    /// Serializes the solutions into a SPARQL results format.
+    pub fn serialize(&self, format: QueryResultsFormat) -> Result<Vec<u8>, OxigraphError> {
+        let mut serializer = QueryResultsSerializer::from_format(format.into())
+            .serialize_solutions_to_writer(Vec::new(), self.variables.clone())?;
+        for solution in &self.solutions {
+            serializer.serialize(&solution.inner)?;
+        }
+        Ok(serializer.finish()?)
+    }
+}
+
+/// A solution of a SPARQL `SELECT` query.
+#[derive(uniffi::Object)]
+pub struct QuerySolution {
+    inner: InnerQuerySolution,
+}
+
+#[uniffi::export]
+impl QuerySolution {
+    /// The value bound to the variable, the variable name is given without the `?` prefix.
+    pub fn get(&self, variable: &str) -> Option<Term> {
+        self.inner.get(variable).cloned().map(Into::into)
+    }
+
+    /// The values of all the solution variables, in the same order as [`QuerySolutions::variables`].
+    pub fn values(&self) -> Vec<Option<Term>> {
+        self.inner
+            .values()
+            .iter()
+            .map(|value| value.clone().map(Into::into))
+            .collect()
+    }
+}
+
+/// SPARQL query results serialization formats.
+#[derive(uniffi::Enum, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum QueryResultsFormat {
+    /// [SPARQL Query Results CSV Format](https://www.w3.org/TR/sparql11-results-csv-tsv/)
+    Csv,
+    /// [SPARQL Query Results JSON Format](https://www.w3.org/TR/sparql11-results-json/)
+    Json,
+    /// [SPARQL Query Results TSV Format](https://www.w3.org/TR/sparql11-results-csv-tsv/)
+    Tsv,
+    /// [SPARQL Query Results XML Format](https://www.w3.org/TR/rdf-sparql-XMLres/)
+    Xml,
+}
+
+impl From<QueryResultsFormat> for InnerQueryResultsFormat {
+    fn from(format: QueryResultsFormat) -> Self {
+        match format {
+            QueryResultsFormat::Csv => Self::Csv,
+            QueryResultsFormat::Json => Self::Json,
+            QueryResultsFormat::Tsv => Self::Tsv,
+            QueryResultsFormat::Xml => Self::Xml,
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use crate::OxigraphError;
+use crate::io::{RdfFormat, rdf_serializer};
+use crate::model::{GraphName, Quad, Term, named_node, named_or_blank_node};
+use crate::sparql::QueryResults;
+use oxigraph::io::RdfParser;
+use oxigraph::model;
+use oxigraph::sparql::{Query, Update};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// RDF store.
+///
+/// It encodes a [RDF dataset](https://www.w3.org/TR/rdf12-concepts/#dfn-rdf-dataset) and allows to query it using SPARQL.
+/// It is based on the [RocksDB](https://rocksdb.org/) key-value database.
+///
+/// This store ensures the "repeatable read" isolation level: the store only exposes changes that have
+/// been "committed" (i.e. no partial writes) and the exposed state does not change for the complete duration
+/// of a read operation (e.g. a SPARQL query) or a read/write operation (e.g. a SPARQL update).
+///
+/// It can be shared between threads.
+#[derive(uniffi::Object)]
+pub struct Store {
+    inner: oxigraph::store::Store,
+}
+
+#[uniffi::export]
+impl Store {
+    /// Opens a read-write store backed by RocksDB in the `path` directory or a new in-memory store if `path` is not set.
+    ///
+    /// Only one read-write store can be opened on a given directory at a time.
+    #[uniffi::constructor(default(path = None))]
+    pub fn new(path: Option<String>) -> Result<Arc<Self>, OxigraphError> {
+        Ok(Arc::new(Self {
+            inner: if let Some(path) = path {
+                oxigraph::store::Store::open(path)?
+            } else {
+                oxigraph::store::Store::new()?
+            },
+        }))
+    }
+
+    /// Opens a read-only store backed by RocksDB in the `path` directory.
+    #[uniffi::constructor]
+    pub fn read_only(path: String) -> Result<Arc<Self>, OxigraphError> {
+        Ok(Arc::new(Self {
+            inner: oxigraph::store::Store::open_read_only(path)?,
+        }))
+    }
+
+    /// Adds a quad to the store.
+    pub fn add(&self, quad: Arc<Quad>) -> Result<(), OxigraphError> {
+        self.inner.insert(quad.as_ref())?;
+        Ok(())
+    }
+
+    /// Adds atomically a set of quads to the store.
+    pub fn extend(&self, quads: Vec<Arc<Quad>>) -> Result<(), OxigraphError> {
+        self.inner
+            .extend(quads.iter().map(|quad| model::Quad::from(quad.as_ref())))?;
+        Ok(())
+    }
+
+    /// Removes a quad from the store.
+    pub fn remove(&self, quad: Arc<Quad>) -> Result<(), OxigraphError> {
+        self.inner.remove(quad.as_ref())?;
+        Ok(())
+    }
+
+    /// Checks if the store contains the quad.
+    pub fn contains(&self, quad: Arc<Quad>) -> Result<bool, OxigraphError> {
+        Ok(self.inner.contains(quad.as_ref())?)
+    }
+
+    /// Returns the number of quads in the store.
+    ///
+    /// This function executes a full scan.
+    pub fn len(&self) -> Result<u64, OxigraphError> {
+        Ok(self.inner.len()?.try_into().unwrap_or(u64::MAX))
+    }
+
+    pub fn is_empty(&self) -> Result<bool, OxigraphError> {
+        Ok(self.inner.is_empty()?)
+    }
+
+    /// Returns the quads matching the given pattern. `None` matches any term.
+    #[uniffi::method(default(graph_name = None))]
+    pub fn quads_for_pattern(
+        &self,
+        subject: Option<Term>,
+        predicate: Option<Term>,
+        object: Option<Term>,
+        graph_name: Option<GraphName>,
+    ) -> Result<Vec<Arc<Quad>>, OxigraphError> {
+        let subject = subject
+            .map(|s| named_or_blank_node(s, "subject"))
+            .transpose()?;
+        let predicate = predicate.map(|p| named_node(p, "predicate")).transpose()?;
+        let object = object.map(model::Term::try_from).transpose()?;
+        let graph_name = graph_name.map(model::GraphName::try_from).transpose()?;
+        self.inner
+            .quads_for_pattern(
+                subject.as_ref().map(Into::into),
+                predicate.as_ref().map(Into::into),
+                object.as_ref().map(Into::into),
+                graph_name.as_ref().map(Into::into),
+            )
+            .map(|quad| Ok(Arc::new(quad?.into())))
+            .collect()
+    }
+
+    /// Executes a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/).
+    ///
+    /// `base_iri` is used to resolve the relative IRIs of the query.
+    /// If `use_default_graph_as_union` is set, the query default graph is the union of all the store graphs.
+    #[uniffi::method(default(base_iri = None, use_default_graph_as_union = false))]
+    pub fn query(
+        &self,
+        query: &str,
+        base_iri: Option<String>,
+        use_default_graph_as_union: bool,
+    ) -> Result<QueryResults, OxigraphError> {
+        let mut query = Query::parse(query, base_iri.as_deref())?;
+        if use_default_graph_as_union {
+            query.dataset_mut().set_default_graph_as_union();
+        }
+        self.inner.query(query)?.try_into()
+    }
+
+    /// Executes a [SPARQL 1.1 update](https://www.w3.org/TR/sparql11-update/).
+    ///
+    /// Updates are applied in a transactional manner: either the full operation succeeds or nothing is written to the database.
+    #[uniffi::method(default(base_iri = None))]
+    pub fn update(&self, update: &str, base_iri: Option<String>) -> Result<(), OxigraphError> {
+        self.inner
+            .update(Update::parse(update, base_iri.as_deref())?)?;
+        Ok(())
+    }
+
+    /// Loads an RDF serialization into the store.
+    ///
+    /// Loads are applied in a transactional manner: either the full operation succeeds or nothing is written to the database.
+    /// `base_iri` is used to resolve the relative IRIs.
+    /// `to_graph` is the graph the triples are loaded into, the default graph if not set.
+    #[uniffi::method(default(base_iri = None, to_graph = None))]
+    pub fn load(
+        &self,
+        input: &[u8],
+        format: RdfFormat,
+        base_iri: Option<String>,
+        to_graph: Option<GraphName>,
+    ) -> Result<(), OxigraphError> {
+        let mut parser = RdfParser::from_format(format.into());
+        if let Some(base_iri) = base_iri {
+            parser = parser.with_base_iri(base_iri)?;
+        }
+        if let Some(to_graph) = to_graph {
+            parser = parser.with_default_graph(model::GraphName::try_from(to_graph)?);
+        }
+        self.inner.load_from_reader(parser, input)?;
+        Ok(())
+    }
+
+    /// Dumps the store content into an RDF serialization.
+    ///
+    /// If `from_graph` is set, only this graph is dumped and the format might be a graph format like Turtle.
+    /// `prefixes` maps prefix names to prefix IRIs and `base_iri` is used to write relative IRIs.
+    #[uniffi::method(default(from_graph = None, prefixes = None, base_iri = None))]
+    pub fn dump(
+        &self,
+        format: RdfFormat,
+        from_graph: Option<GraphName>,
+        prefixes: Option<HashMap<String, String>>,
+        base_iri: Option<String>,
+    ) -> Result<Vec<u8>, OxigraphError> {
+        let serializer = rdf_serializer(format, prefixes, base_iri)?;
+        Ok(if let Some(from_graph) = from_graph {
+            self.inner.dump_graph_to_writer(
+                &model::GraphName::try_from(from_graph)?,
+                serializer,
+                Vec::new(),
+            )?
+        } else {
+            self.inner.dump_to_writer(serializer, Vec::new())?
+        })
+    }
+
+    /// Returns the names of the store named graphs.
+    pub fn named_graphs(&self) -> Result<Vec<GraphName>, OxigraphError> {
+        self.inner
+            .named_graphs()
+            .map(|graph_name| Ok(graph_name?.into()))
+            .collect()
+    }
+
+    /// Clears the store by removing all its contents.
+    pub fn clear(&self) -> Result<(), OxigraphError> {
+        Ok(self.inner.clear()?)
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::{QueryResultsFormat, Triple, parse, serialize};
+    use std::slice;
+
+    fn ex(value: &str) -> Term {
+        Term::NamedNode {
+            value: format!("http://example.com/{value}"),
+        }
+    }
+
+    #[test]
+    #[expect(clippy::panic)]
+    fn add_and_query() -> Result<(), OxigraphError> {
+        let store = Store::new(None)?;
+        let literal = Term::Literal {
+            value: "foo".into(),
+            datatype: "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString".into(),
+            language: Some("en".into()),
+            direction: None,
+        };
+        let quad = Quad::new(ex("s"), ex("p"), literal.clone(), None)?;
+        store.add(Arc::clone(&quad))?;
+        assert!(store.contains(quad)?);
+        assert_eq!(store.len()?, 1);
+
+        let QueryResults::Solutions { solutions } =
+            store.query("SELECT ?o WHERE { ?s ?p ?o }", None, false)?
+        else {
+            panic!("SELECT queries return solutions")
+        };
+        assert_eq!(solutions.variables(), ["o"]);
+        assert_eq!(solutions.solutions()[0].get("o"), Some(literal));
+        assert_eq!(
+            solutions.serialize(QueryResultsFormat::Csv)?,
+            b"o\r\nfoo\r\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update_and_dump() -> Result<(), OxigraphError> {
+        let store = Store::new(None)?;
+        store.update(
+            "INSERT DATA { GRAPH <g> { <s> <p> <<( <s> <p> <o> )>> } }",
+            Some("http://example.com/".into()),
+        )?;
+        let graph = GraphName::NamedNode {
+            value: "http://example.com/g".into(),
+        };
+        assert_eq!(store.named_graphs()?, slice::from_ref(&graph));
+        let quads = store.quads_for_pattern(Some(ex("s")), None, None, Some(graph.clone()))?;
+        assert_eq!(
+            quads[0].object(),
+            Term::TripleTerm {
+                triple: Triple::new(ex("s"), ex("p"), ex("o"))?
+            }
+        );
+        let ntriples = store.dump(RdfFormat::NTriples, Some(graph), None, None)?;
+        assert_eq!(
+            ntriples,
+            b"<http://example.com/s> <http://example.com/p> <<( <http://example.com/s> <http://example.com/p> <http://example.com/o> )>> .\n"
+        );
+        assert_eq!(
+            serialize(
+                parse(&ntriples, RdfFormat::NTriples, None, false, false)?,
+                RdfFormat::NTriples,
+                None,
+                None
+            )?,
+            ntriples
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> Result<(), OxigraphError> {
+        let store = Store::new(None)?;
+        assert!(matches!(
+            store.query("SELECT", None, false),
+            Err(OxigraphError::Syntax(_))
+        ));
+        assert!(matches!(
+            Quad::new(
+                Term::Literal {
+                    value: "s".into(),
+                    datatype: "http://www.w3.org/2001/XMLSchema#string".into(),
+                    language: None,
+                    direction: None,
+                },
+                ex("p"),
+                ex("o"),
+                None
+            ),
+            Err(OxigraphError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            store.load(b"<s> <p> <o> .", RdfFormat::NTriples, None, None),
+            Err(OxigraphError::Syntax(_))
+        ));
+        Ok(())
+    }
+}
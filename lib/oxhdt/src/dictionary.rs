@@ -0,0 +1,175 @@
+//! The four sections dictionary mapping RDF terms to integer ids.
+//!
+//! Terms used both as subject and object are stored in the "shared" section and get the ids `1..=|shared|` in both positions.
+//! Terms only used as subject (resp. object) get the following ids in the "subjects" (resp. "objects") section.
+//! Predicates have their own id space.
+//!
+//! Terms are encoded as strings: IRIs without the enclosing `<>`, blank nodes as `_:id` and literals as unescaped `"value"`, `"value"@lang` or `"value"^^<datatype>`.
+
+use crate::encoding::Cursor;
+use crate::error::HdtSyntaxError;
+use crate::structures::{CONTROL_TYPE_DICTIONARY, ControlInfo, PfcSection};
+use oxrdf::vocab::xsd;
+use oxrdf::{BlankNode, Literal, NamedNode, NamedOrBlankNode, NamedOrBlankNodeRef, Term, TermRef};
+use std::io;
+
+pub const DICTIONARY_FOUR_FORMAT: &str = "<http://purl.org/HDT/hdt#dictionaryFour>";
+
+pub struct Dictionary {
+    shared: PfcSection,
+    subjects: PfcSection,
+    predicates: PfcSection,
+    objects: PfcSection,
+}
+
+impl Dictionary {
+    pub fn read(cursor: &mut Cursor<'_>) -> Result<Self, HdtSyntaxError> {
+        let control_info = ControlInfo::read(cursor, CONTROL_TYPE_DICTIONARY)?;
+        if control_info.format() != DICTIONARY_FOUR_FORMAT {
+            return Err(HdtSyntaxError::msg(format!(
+                "Unsupported HDT dictionary format {}, only {DICTIONARY_FOUR_FORMAT} is supported",
+                control_info.format()
+            )));
+        }
+        Ok(Self {
+            shared: PfcSection::read(cursor)?,
+            subjects: PfcSection::read(cursor)?,
+            predicates: PfcSection::read(cursor)?,
+            objects: PfcSection::read(cursor)?,
+        })
+    }
+
+    pub fn subject(
+        &self,
+        file: &[u8],
+        id: u64,
+        lenient: bool,
+    ) -> Result<NamedOrBlankNode, HdtSyntaxError> {
+        let value = if id <= self.shared.len() {
+            self.shared.extract(file, id)?
+        } else {
+            self.subjects.extract(file, id - self.shared.len())?
+        };
+        #[allow(
+            unreachable_patterns,
+            clippy::match_wildcard_for_single_variants,
+            clippy::allow_attributes
+        )]
+        match decode_term(value, lenient)? {
+            Term::NamedNode(node) => Ok(node.into()),
+            Term::BlankNode(node) => Ok(node.into()),
+            _ => Err(HdtSyntaxError::msg(
+                "HDT subjects must be IRIs or blank nodes",
+            )),
+        }
+    }
+
+    pub fn predicate(
+        &self,
+        file: &[u8],
+        id: u64,
+        lenient: bool,
+    ) -> Result<NamedNode, HdtSyntaxError> {
+        #[allow(
+            unreachable_patterns,
+            clippy::match_wildcard_for_single_variants,
+            clippy::allow_attributes
+        )]
+        match decode_term(self.predicates.extract(file, id)?, lenient)? {
+            Term::NamedNode(node) => Ok(node),
+            _ => Err(HdtSyntaxError::msg("HDT predicates must be IRIs")),
+        }
+    }
+
+    pub fn object(&self, file: &[u8], id: u64, lenient: bool) -> Result<Term, HdtSyntaxError> {
+        let value = if id <= self.shared.len() {
+            self.shared.extract(file, id)?
+        } else {
+            self.objects.extract(file, id - self.shared.len())?
+        };
+        decode_term(value, lenient)
+    }
+}
+
+fn decode_term(value: Vec<u8>, lenient: bool) -> Result<Term, HdtSyntaxError> {
+    let value = String::from_utf8(value)
+        .map_err(|_| HdtSyntaxError::msg("HDT dictionary strings must be valid UTF-8"))?;
+    if let Some(literal) = value.strip_prefix('"') {
+        let (lexical, suffix) = literal
+            .rsplit_once('"')
+            .ok_or_else(|| HdtSyntaxError::msg(format!("Unterminated HDT literal {value}")))?;
+        return Ok(if suffix.is_empty() {
+            Literal::new_simple_literal(lexical)
+        } else if let Some(language) = suffix.strip_prefix('@') {
+            if lenient {
+                Literal::new_language_tagged_literal_unchecked(
+                    lexical,
+                    language.to_ascii_lowercase(),
+                )
+            } else {
+                Literal::new_language_tagged_literal(lexical, language)
+                    .map_err(|e| HdtSyntaxError::invalid_language_tag(language.into(), e))?
+            }
+        } else if let Some(datatype) = suffix
+            .strip_prefix("^^<")
+            .and_then(|datatype| datatype.strip_suffix('>'))
+        {
+            Literal::new_typed_literal(lexical, decode_named_node(datatype, lenient)?)
+        } else {
+            return Err(HdtSyntaxError::msg(format!("Invalid HDT literal {value}")));
+        }
+        .into());
+    }
+    if let Some(id) = value.strip_prefix("_:") {
+        return Ok(if lenient {
+            BlankNode::new_unchecked(id)
+        } else {
+            BlankNode::new(id).map_err(|e| HdtSyntaxError::invalid_blank_node(id.into(), e))?
+        }
+        .into());
+    }
+    Ok(decode_named_node(&value, lenient)?.into())
+}
+
+fn decode_named_node(value: &str, lenient: bool) -> Result<NamedNode, HdtSyntaxError> {
+    if lenient {
+        Ok(NamedNode::new_unchecked(value))
+    } else {
+        NamedNode::new(value).map_err(|e| HdtSyntaxError::invalid_iri(value.into(), e))
+    }
+}
+
+pub fn encode_subject(subject: NamedOrBlankNodeRef<'_>) -> String {
+    match subject {
+        NamedOrBlankNodeRef::NamedNode(node) => node.as_str().into(),
+        NamedOrBlankNodeRef::BlankNode(node) => format!("_:{}", node.as_str()),
+    }
+}
+
+pub fn encode_object(object: TermRef<'_>) -> io::Result<String> {
+    #[allow(
+        unreachable_patterns,
+        clippy::match_wildcard_for_single_variants,
+        clippy::allow_attributes
+    )]
+    let value = match object {
+        TermRef::NamedNode(node) => node.as_str().into(),
+        TermRef::BlankNode(node) => format!("_:{}", node.as_str()),
+        TermRef::Literal(literal) => {
+            if let Some(language) = literal.language() {
+                format!("\"{}\"@{language}", literal.value())
+            } else if literal.datatype() == xsd::STRING {
+                format!("\"{}\"", literal.value())
+            } else {
+                format!("\"{}\"^^<{}>", literal.value(), literal.datatype().as_str())
+            }
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "HDT does not support triple terms",
+            ));
+        }
+    };
+    Ok(value)
+}
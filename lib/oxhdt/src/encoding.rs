@@ -0,0 +1,210 @@
+//! Primitive encodings of the HDT binary format: variable-length integers, checksums and a bounds-checked byte cursor.
+
+use crate::error::HdtSyntaxError;
+
+const CRC8_TABLE: [u8; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        #[expect(clippy::cast_possible_truncation)]
+        let mut crc = i as u8;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 0x80 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x07
+            };
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+const CRC16_TABLE: [u16; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        #[expect(clippy::cast_possible_truncation)]
+        let mut crc = i as u16;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 == 0 {
+                crc >> 1
+            } else {
+                (crc >> 1) ^ 0xA001
+            };
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        #[expect(clippy::cast_possible_truncation)]
+        let mut crc = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 == 0 {
+                crc >> 1
+            } else {
+                (crc >> 1) ^ 0x82F6_3B78
+            };
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-8/SMBUS, used to check the metadata of bitmaps, sequences and dictionary sections.
+pub fn crc8(data: &[u8]) -> u8 {
+    data.iter()
+        .fold(0, |crc, byte| CRC8_TABLE[usize::from(crc ^ byte)])
+}
+
+/// CRC-16/ARC, used to check the control information blocks.
+pub fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0, |crc, byte| {
+        (crc >> 8) ^ CRC16_TABLE[usize::from((crc ^ u16::from(*byte)) & 0xFF)]
+    })
+}
+
+/// CRC-32C, used to check the content of bitmaps, sequences and dictionary sections.
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, byte| {
+        (crc >> 8) ^ CRC32_TABLE[((crc ^ u32::from(*byte)) & 0xFF) as usize]
+    })
+}
+
+/// Writes a variable-length integer: 7 bits per byte, least significant bits first, the last byte having its high bit set.
+pub fn write_vbyte(output: &mut Vec<u8>, mut value: u64) {
+    while value > 0x7F {
+        output.push((value & 0x7F) as u8);
+        value >>= 7;
+    }
+    #[expect(clippy::cast_possible_truncation)]
+    output.push(value as u8 | 0x80);
+}
+
+/// Reads a byte slice while checking bounds.
+pub struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(data: &'a [u8], position: usize) -> Self {
+        Self { data, position }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The bytes read since the given position.
+    pub fn read_since(&self, start: usize) -> &'a [u8] {
+        &self.data[start..self.position]
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], HdtSyntaxError> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(HdtSyntaxError::unexpected_eof)?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, HdtSyntaxError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u16_le(&mut self) -> Result<u16, HdtSyntaxError> {
+        let mut buffer = [0; 2];
+        buffer.copy_from_slice(self.read_bytes(2)?);
+        Ok(u16::from_le_bytes(buffer))
+    }
+
+    pub fn read_u32_le(&mut self) -> Result<u32, HdtSyntaxError> {
+        let mut buffer = [0; 4];
+        buffer.copy_from_slice(self.read_bytes(4)?);
+        Ok(u32::from_le_bytes(buffer))
+    }
+
+    /// Reads bytes until a NUL byte and skips it.
+    pub fn read_nul_terminated(&mut self) -> Result<&'a [u8], HdtSyntaxError> {
+        let len = self
+            .data
+            .get(self.position..)
+            .and_then(|data| data.iter().position(|b| *b == 0))
+            .ok_or_else(HdtSyntaxError::unexpected_eof)?;
+        let bytes = self.read_bytes(len)?;
+        self.position += 1;
+        Ok(bytes)
+    }
+
+    pub fn read_vbyte(&mut self) -> Result<u64, HdtSyntaxError> {
+        let mut value = 0_u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8()?;
+            value |= u64::from(byte & 0x7F)
+                .checked_shl(shift)
+                .filter(|v| v >> shift == u64::from(byte & 0x7F))
+                .ok_or_else(|| HdtSyntaxError::msg("Too large variable-length integer"))?;
+            if byte & 0x80 != 0 {
+                return Ok(value);
+            }
+        }
+        Err(HdtSyntaxError::msg("Too large variable-length integer"))
+    }
+
+    pub fn read_vbyte_usize(&mut self) -> Result<usize, HdtSyntaxError> {
+        self.read_vbyte()?
+            .try_into()
+            .map_err(|_| HdtSyntaxError::msg("Too large variable-length integer"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc() {
+        assert_eq!(crc8(b"123456789"), 0xF4);
+        assert_eq!(crc16(b"123456789"), 0xBB3D);
+        assert_eq!(crc32(b"123456789"), 0xE306_9283);
+    }
+
+    #[test]
+    fn test_vbyte() {
+        for value in [
+            0,
+            1,
+            127,
+            128,
+            300,
+            0x3FFF,
+            0x4000,
+            u64::from(u32::MAX),
+            u64::MAX,
+        ] {
+            let mut buffer = Vec::new();
+            write_vbyte(&mut buffer, value);
+            let mut cursor = Cursor::new(&buffer, 0);
+            assert_eq!(cursor.read_vbyte().unwrap(), value);
+            assert_eq!(cursor.position(), buffer.len());
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use oxrdf::{BlankNodeIdParseError, IriParseError, LanguageTagParseError};
+use std::io;
+
+/// Error returned during HDT parsing.
+#[derive(Debug, thiserror::Error)]
+pub enum HdtParseError {
+    /// I/O error during parsing (file not found...).
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error in the file syntax.
+    #[error(transparent)]
+    Syntax(#[from] HdtSyntaxError),
+}
+
+impl From<HdtParseError> for io::Error {
+    #[inline]
+    fn from(error: HdtParseError) -> Self {
+        match error {
+            HdtParseError::Io(error) => error,
+            HdtParseError::Syntax(error) => error.into(),
+        }
+    }
+}
+
+/// An error in the syntax of the parsed file.
+///
+/// It is returned for unsupported or corrupted HDT structures and for invalid RDF terms in the dictionary.
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct HdtSyntaxError(#[from] SyntaxErrorKind);
+
+#[derive(Debug, thiserror::Error)]
+enum SyntaxErrorKind {
+    #[error("{0}")]
+    Msg(String),
+    #[error("invalid checksum of the {0}")]
+    InvalidChecksum(&'static str),
+    #[error("error while parsing IRI '{iri}': {error}")]
+    InvalidIri {
+        iri: String,
+        #[source]
+        error: IriParseError,
+    },
+    #[error("error while parsing blank node '{id}': {error}")]
+    InvalidBlankNode {
+        id: String,
+        #[source]
+        error: BlankNodeIdParseError,
+    },
+    #[error("error while parsing language tag '{tag}': {error}")]
+    InvalidLanguageTag {
+        tag: String,
+        #[source]
+        error: LanguageTagParseError,
+    },
+}
+
+impl HdtSyntaxError {
+    pub(crate) fn msg(msg: impl Into<String>) -> Self {
+        Self(SyntaxErrorKind::Msg(msg.into()))
+    }
+
+    pub(crate) fn unexpected_eof() -> Self {
+        Self::msg("Unexpected end of file")
+    }
+
+    pub(crate) fn invalid_checksum(structure: &'static str) -> Self {
+        Self(SyntaxErrorKind::InvalidChecksum(structure))
+    }
+
+    pub(crate) fn invalid_iri(iri: String, error: IriParseError) -> Self {
+        Self(SyntaxErrorKind::InvalidIri { iri, error })
+    }
+
+    pub(crate) fn invalid_blank_node(id: String, error: BlankNodeIdParseError) -> Self {
+        Self(SyntaxErrorKind::InvalidBlankNode { id, error })
+    }
+
+    pub(crate) fn invalid_language_tag(tag: String, error: LanguageTagParseError) -> Self {
+        Self(SyntaxErrorKind::InvalidLanguageTag { tag, error })
+    }
+}
+
+impl From<HdtSyntaxError> for io::Error {
+    #[inline]
+    fn from(error: HdtSyntaxError) -> Self {
+        Self::new(io::ErrorKind::InvalidData, error)
+    }
+}
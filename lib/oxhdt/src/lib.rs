@@ -0,0 +1,21 @@
+#![doc = include_str!("../README.md")]
+#![doc(test(attr(deny(warnings))))]
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![doc(html_favicon_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+#![doc(html_logo_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+
+mod dictionary;
+mod encoding;
+mod error;
+mod parser;
+mod serializer;
+mod structures;
+mod triples;
+
+pub use error::{HdtParseError, HdtSyntaxError};
+#[cfg(feature = "async-tokio")]
+pub use parser::TokioAsyncReaderHdtParser;
+pub use parser::{HdtParser, ReaderHdtParser, SliceHdtParser};
+#[cfg(feature = "async-tokio")]
+pub use serializer::TokioAsyncWriterHdtSerializer;
+pub use serializer::{HdtSerializer, WriterHdtSerializer};
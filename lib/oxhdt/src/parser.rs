@@ -0,0 +1,446 @@
+use crate::dictionary::Dictionary;
+use crate::encoding::Cursor;
+use crate::error::{HdtParseError, HdtSyntaxError};
+use crate::structures::{CONTROL_TYPE_GLOBAL, CONTROL_TYPE_HEADER, ControlInfo};
+use crate::triples::{BitmapTriples, TriplesPosition};
+use oxrdf::{NamedNode, NamedOrBlankNode, Triple};
+use std::io::Read;
+#[cfg(feature = "async-tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+pub const HDT_FORMAT: &str = "<http://purl.org/HDT/hdt#HDTv1>";
+
+/// A parser for [HDT](https://www.rdfhdt.org/hdt-binary-format/) files.
+///
+/// HDT is a compressed and indexed binary format for RDF graphs.
+/// Only the standard layout produced by the reference implementations is supported:
+/// a four sections dictionary using plain front coding and bitmap triples.
+///
+/// The full file is loaded in memory before the triples are decoded.
+///
+/// Count the number of people:
+/// ```
+/// use oxhdt::{HdtParser, HdtSerializer};
+/// use oxrdf::vocab::rdf;
+/// use oxrdf::{NamedNodeRef, TripleRef};
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_triple(TripleRef::new(
+///     NamedNodeRef::new("http://example.com/foo")?,
+///     rdf::TYPE,
+///     schema_person,
+/// ))?;
+/// let file = serializer.finish()?;
+///
+/// let mut count = 0;
+/// for triple in HdtParser::new().for_reader(file.as_slice()) {
+///     let triple = triple?;
+///     if triple.predicate == rdf::TYPE && triple.object == schema_person.into() {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(1, count);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+#[must_use]
+pub struct HdtParser {
+    lenient: bool,
+}
+
+impl HdtParser {
+    /// Builds a new [`HdtParser`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assumes the file is valid to make parsing faster.
+    ///
+    /// It will skip the validation of the IRIs, blank node identifiers and language tags stored in the dictionary.
+    /// The checksums of the file structures are still checked.
+    ///
+    /// Note that if the file is actually not valid, the parser might emit broken RDF.
+    #[inline]
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Parses a HDT file from a [`Read`] implementation.
+    ///
+    /// The full file is loaded in memory before decoding.
+    ///
+    /// Count the number of people:
+    /// ```
+    /// use oxhdt::{HdtParser, HdtSerializer};
+    /// use oxrdf::vocab::rdf;
+    /// use oxrdf::{NamedNodeRef, TripleRef};
+    ///
+    /// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+    /// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+    /// serializer.serialize_triple(TripleRef::new(
+    ///     NamedNodeRef::new("http://example.com/foo")?,
+    ///     rdf::TYPE,
+    ///     schema_person,
+    /// ))?;
+    /// let file = serializer.finish()?;
+    ///
+    /// let mut count = 0;
+    /// for triple in HdtParser::new().for_reader(file.as_slice()) {
+    ///     let triple = triple?;
+    ///     if triple.predicate == rdf::TYPE && triple.object == schema_person.into() {
+    ///         count += 1;
+    ///     }
+    /// }
+    /// assert_eq!(1, count);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_reader<R: Read>(self, reader: R) -> ReaderHdtParser<R> {
+        ReaderHdtParser {
+            reader: Some(reader),
+            data: Vec::new(),
+            inner: InternalHdtParser::new(self.lenient),
+        }
+    }
+
+    /// Parses a HDT file from a [`AsyncRead`] implementation.
+    ///
+    /// The full file is loaded in memory before decoding.
+    ///
+    /// Count the number of people:
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use oxhdt::{HdtParser, HdtSerializer};
+    /// use oxrdf::vocab::rdf;
+    /// use oxrdf::{NamedNodeRef, TripleRef};
+    ///
+    /// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+    /// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+    /// serializer.serialize_triple(TripleRef::new(
+    ///     NamedNodeRef::new("http://example.com/foo")?,
+    ///     rdf::TYPE,
+    ///     schema_person,
+    /// ))?;
+    /// let file = serializer.finish()?;
+    ///
+    /// let mut count = 0;
+    /// let mut parser = HdtParser::new().for_tokio_async_reader(file.as_slice());
+    /// while let Some(triple) = parser.next().await {
+    ///     let triple = triple?;
+    ///     if triple.predicate == rdf::TYPE && triple.object == schema_person.into() {
+    ///         count += 1;
+    ///     }
+    /// }
+    /// assert_eq!(1, count);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async-tokio")]
+    pub fn for_tokio_async_reader<R: AsyncRead + Unpin>(
+        self,
+        reader: R,
+    ) -> TokioAsyncReaderHdtParser<R> {
+        TokioAsyncReaderHdtParser {
+            reader: Some(reader),
+            data: Vec::new(),
+            inner: InternalHdtParser::new(self.lenient),
+        }
+    }
+
+    /// Parses a HDT file from a byte slice.
+    ///
+    /// Count the number of people:
+    /// ```
+    /// use oxhdt::{HdtParser, HdtSerializer};
+    /// use oxrdf::vocab::rdf;
+    /// use oxrdf::{NamedNodeRef, TripleRef};
+    ///
+    /// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+    /// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+    /// serializer.serialize_triple(TripleRef::new(
+    ///     NamedNodeRef::new("http://example.com/foo")?,
+    ///     rdf::TYPE,
+    ///     schema_person,
+    /// ))?;
+    /// let file = serializer.finish()?;
+    ///
+    /// let mut count = 0;
+    /// for triple in HdtParser::new().for_slice(&file) {
+    ///     let triple = triple?;
+    ///     if triple.predicate == rdf::TYPE && triple.object == schema_person.into() {
+    ///         count += 1;
+    ///     }
+    /// }
+    /// assert_eq!(1, count);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_slice(self, slice: &[u8]) -> SliceHdtParser<'_> {
+        SliceHdtParser {
+            slice,
+            inner: InternalHdtParser::new(self.lenient),
+        }
+    }
+}
+
+/// Parses a HDT file from a [`Read`] implementation.
+///
+/// Can be built using [`HdtParser::for_reader`].
+///
+/// Count the number of people:
+/// ```
+/// use oxhdt::{HdtParser, HdtSerializer};
+/// use oxrdf::vocab::rdf;
+/// use oxrdf::{NamedNodeRef, TripleRef};
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_triple(TripleRef::new(
+///     NamedNodeRef::new("http://example.com/foo")?,
+///     rdf::TYPE,
+///     schema_person,
+/// ))?;
+/// let file = serializer.finish()?;
+///
+/// let mut count = 0;
+/// for triple in HdtParser::new().for_reader(file.as_slice()) {
+///     let triple = triple?;
+///     if triple.predicate == rdf::TYPE && triple.object == schema_person.into() {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(1, count);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+pub struct ReaderHdtParser<R: Read> {
+    reader: Option<R>,
+    data: Vec<u8>,
+    inner: InternalHdtParser,
+}
+
+impl<R: Read> Iterator for ReaderHdtParser<R> {
+    type Item = Result<Triple, HdtParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(mut reader) = self.reader.take() {
+            if let Err(e) = reader.read_to_end(&mut self.data) {
+                self.inner.end();
+                return Some(Err(e.into()));
+            }
+        }
+        Some(self.inner.next(&self.data)?.map_err(Into::into))
+    }
+}
+
+/// Parses a HDT file from a [`AsyncRead`] implementation.
+///
+/// Can be built using [`HdtParser::for_tokio_async_reader`].
+///
+/// Count the number of people:
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use oxhdt::{HdtParser, HdtSerializer};
+/// use oxrdf::vocab::rdf;
+/// use oxrdf::{NamedNodeRef, TripleRef};
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_triple(TripleRef::new(
+///     NamedNodeRef::new("http://example.com/foo")?,
+///     rdf::TYPE,
+///     schema_person,
+/// ))?;
+/// let file = serializer.finish()?;
+///
+/// let mut count = 0;
+/// let mut parser = HdtParser::new().for_tokio_async_reader(file.as_slice());
+/// while let Some(triple) = parser.next().await {
+///     let triple = triple?;
+///     if triple.predicate == rdf::TYPE && triple.object == schema_person.into() {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(1, count);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async-tokio")]
+#[must_use]
+pub struct TokioAsyncReaderHdtParser<R: AsyncRead + Unpin> {
+    reader: Option<R>,
+    data: Vec<u8>,
+    inner: InternalHdtParser,
+}
+
+#[cfg(feature = "async-tokio")]
+impl<R: AsyncRead + Unpin> TokioAsyncReaderHdtParser<R> {
+    /// Reads the next triple or returns `None` if the file is finished.
+    pub async fn next(&mut self) -> Option<Result<Triple, HdtParseError>> {
+        if let Some(mut reader) = self.reader.take() {
+            if let Err(e) = reader.read_to_end(&mut self.data).await {
+                self.inner.end();
+                return Some(Err(e.into()));
+            }
+        }
+        Some(self.inner.next(&self.data)?.map_err(Into::into))
+    }
+}
+
+/// Parses a HDT file from a byte slice.
+///
+/// Can be built using [`HdtParser::for_slice`].
+///
+/// Count the number of people:
+/// ```
+/// use oxhdt::{HdtParser, HdtSerializer};
+/// use oxrdf::vocab::rdf;
+/// use oxrdf::{NamedNodeRef, TripleRef};
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_triple(TripleRef::new(
+///     NamedNodeRef::new("http://example.com/foo")?,
+///     rdf::TYPE,
+///     schema_person,
+/// ))?;
+/// let file = serializer.finish()?;
+///
+/// let mut count = 0;
+/// for triple in HdtParser::new().for_slice(&file) {
+///     let triple = triple?;
+///     if triple.predicate == rdf::TYPE && triple.object == schema_person.into() {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(1, count);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+pub struct SliceHdtParser<'a> {
+    slice: &'a [u8],
+    inner: InternalHdtParser,
+}
+
+impl Iterator for SliceHdtParser<'_> {
+    type Item = Result<Triple, HdtSyntaxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next(self.slice)
+    }
+}
+
+/// Decodes the triples of a HDT file, the file bytes being provided on each call.
+struct InternalHdtParser {
+    state: ParserState,
+    position: TriplesPosition,
+    decoder: TermDecoder,
+}
+
+enum ParserState {
+    Start,
+    Triples(Box<(Dictionary, BitmapTriples)>),
+    End,
+}
+
+impl InternalHdtParser {
+    fn new(lenient: bool) -> Self {
+        Self {
+            state: ParserState::Start,
+            position: TriplesPosition::default(),
+            decoder: TermDecoder {
+                lenient,
+                last_subject: None,
+                last_predicate: None,
+            },
+        }
+    }
+
+    fn end(&mut self) {
+        self.state = ParserState::End;
+    }
+
+    fn next(&mut self, file: &[u8]) -> Option<Result<Triple, HdtSyntaxError>> {
+        if matches!(self.state, ParserState::Start) {
+            match read_structure(file) {
+                Ok(structure) => self.state = ParserState::Triples(Box::new(structure)),
+                Err(e) => {
+                    self.end();
+                    return Some(Err(e));
+                }
+            }
+        }
+        let ParserState::Triples(structure) = &self.state else {
+            return None;
+        };
+        let (dictionary, triples) = &**structure;
+        let [s, p, o] = match self.position.next(triples, file)? {
+            Ok(ids) => ids,
+            Err(e) => {
+                self.end();
+                return Some(Err(e));
+            }
+        };
+        Some(self.decoder.decode_triple(dictionary, file, s, p, o))
+    }
+}
+
+struct TermDecoder {
+    lenient: bool,
+    // Triples are sorted by subject then predicate: we avoid decoding the same terms again and again
+    last_subject: Option<(u64, NamedOrBlankNode)>,
+    last_predicate: Option<(u64, NamedNode)>,
+}
+
+impl TermDecoder {
+    fn decode_triple(
+        &mut self,
+        dictionary: &Dictionary,
+        file: &[u8],
+        s: u64,
+        p: u64,
+        o: u64,
+    ) -> Result<Triple, HdtSyntaxError> {
+        let subject = match &self.last_subject {
+            Some((id, subject)) if *id == s => subject.clone(),
+            _ => {
+                let subject = dictionary.subject(file, s, self.lenient)?;
+                self.last_subject = Some((s, subject.clone()));
+                subject
+            }
+        };
+        let predicate = match &self.last_predicate {
+            Some((id, predicate)) if *id == p => predicate.clone(),
+            _ => {
+                let predicate = dictionary.predicate(file, p, self.lenient)?;
+                self.last_predicate = Some((p, predicate.clone()));
+                predicate
+            }
+        };
+        let object = dictionary.object(file, o, self.lenient)?;
+        Ok(Triple::new(subject, predicate, object))
+    }
+}
+
+fn read_structure(file: &[u8]) -> Result<(Dictionary, BitmapTriples), HdtSyntaxError> {
+    let mut cursor = Cursor::new(file, 0);
+    let global = ControlInfo::read(&mut cursor, CONTROL_TYPE_GLOBAL)?;
+    if global.format() != HDT_FORMAT {
+        return Err(HdtSyntaxError::msg(format!(
+            "Unsupported HDT format {}, only {HDT_FORMAT} is supported",
+            global.format()
+        )));
+    }
+    let header = ControlInfo::read(&mut cursor, CONTROL_TYPE_HEADER)?;
+    let header_len = header
+        .property("length")
+        .and_then(|length| length.parse().ok())
+        .ok_or_else(|| HdtSyntaxError::msg("The HDT header length is missing"))?;
+    cursor.read_bytes(header_len)?;
+    let dictionary = Dictionary::read(&mut cursor)?;
+    let triples = BitmapTriples::read(&mut cursor)?;
+    Ok((dictionary, triples))
+}
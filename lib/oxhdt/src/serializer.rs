@@ -0,0 +1,513 @@
+use crate::dictionary::{DICTIONARY_FOUR_FORMAT, encode_object, encode_subject};
+use crate::parser::HDT_FORMAT;
+use crate::structures::{
+    CONTROL_TYPE_DICTIONARY, CONTROL_TYPE_GLOBAL, CONTROL_TYPE_HEADER, ControlInfo, PfcSection,
+};
+use crate::triples::BitmapTriples;
+use oxrdf::vocab::rdf;
+use oxrdf::{
+    BlankNode, IriParseError, Literal, NamedNode, NamedNodeRef, NamedOrBlankNode, Term, Triple,
+    TripleRef,
+};
+use std::collections::HashMap;
+use std::io::{self, Write};
+#[cfg(feature = "async-tokio")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Number of strings per front coding block, the default of the reference implementations.
+const BLOCK_SIZE: usize = 16;
+
+/// A serializer for [HDT](https://www.rdfhdt.org/hdt-binary-format/) files.
+///
+/// It writes a four sections dictionary using plain front coding and bitmap triples sorted in subject, predicate, object order.
+/// As this requires all the triples to be sorted, they are kept in memory until [`finish`](WriterHdtSerializer::finish) is called.
+/// Duplicated triples are written only once.
+///
+/// ```
+/// use oxhdt::{HdtParser, HdtSerializer};
+/// use oxrdf::vocab::rdf;
+/// use oxrdf::{NamedNodeRef, TripleRef};
+///
+/// let triple = TripleRef::new(
+///     NamedNodeRef::new("http://example.com#me")?,
+///     rdf::TYPE,
+///     NamedNodeRef::new("http://schema.org/Person")?,
+/// );
+/// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_triple(triple)?;
+/// let file = serializer.finish()?;
+///
+/// assert_eq!(
+///     HdtParser::new()
+///         .for_slice(&file)
+///         .collect::<Result<Vec<_>, _>>()?,
+///     [triple.into_owned()]
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+#[must_use]
+pub struct HdtSerializer {
+    dataset_iri: Option<NamedNode>,
+}
+
+impl HdtSerializer {
+    /// Builds a new [`HdtSerializer`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the IRI identifying the dataset in the metadata stored in the HDT header.
+    ///
+    /// If not set, the dataset is identified by a blank node.
+    #[inline]
+    pub fn with_dataset_iri(
+        mut self,
+        dataset_iri: impl Into<String>,
+    ) -> Result<Self, IriParseError> {
+        self.dataset_iri = Some(NamedNode::new(dataset_iri)?);
+        Ok(self)
+    }
+
+    /// Writes a HDT file to a [`Write`] implementation.
+    ///
+    /// ```
+    /// use oxhdt::HdtSerializer;
+    /// use oxrdf::vocab::rdf;
+    /// use oxrdf::{NamedNodeRef, TripleRef};
+    ///
+    /// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+    /// serializer.serialize_triple(TripleRef::new(
+    ///     NamedNodeRef::new("http://example.com#me")?,
+    ///     rdf::TYPE,
+    ///     NamedNodeRef::new("http://schema.org/Person")?,
+    /// ))?;
+    /// assert!(serializer.finish()?.starts_with(b"$HDT"));
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_writer<W: Write>(self, writer: W) -> WriterHdtSerializer<W> {
+        WriterHdtSerializer {
+            writer,
+            inner: InnerHdtWriter::new(self),
+        }
+    }
+
+    /// Writes a HDT file to a [`AsyncWrite`] implementation.
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use oxhdt::HdtSerializer;
+    /// use oxrdf::vocab::rdf;
+    /// use oxrdf::{NamedNodeRef, TripleRef};
+    ///
+    /// let mut serializer = HdtSerializer::new().for_tokio_async_writer(Vec::new());
+    /// serializer
+    ///     .serialize_triple(TripleRef::new(
+    ///         NamedNodeRef::new("http://example.com#me")?,
+    ///         rdf::TYPE,
+    ///         NamedNodeRef::new("http://schema.org/Person")?,
+    ///     ))
+    ///     .await?;
+    /// assert!(serializer.finish().await?.starts_with(b"$HDT"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async-tokio")]
+    pub fn for_tokio_async_writer<W: AsyncWrite + Unpin>(
+        self,
+        writer: W,
+    ) -> TokioAsyncWriterHdtSerializer<W> {
+        TokioAsyncWriterHdtSerializer {
+            writer,
+            inner: InnerHdtWriter::new(self),
+        }
+    }
+}
+
+/// Writes a HDT file to a [`Write`] implementation.
+///
+/// Can be built using [`HdtSerializer::for_writer`].
+///
+/// <div class="warning">
+///
+/// Nothing is written before the [`finish`](WriterHdtSerializer::finish()) method is called.</div>
+///
+/// ```
+/// use oxhdt::HdtSerializer;
+/// use oxrdf::vocab::rdf;
+/// use oxrdf::{NamedNodeRef, TripleRef};
+///
+/// let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_triple(TripleRef::new(
+///     NamedNodeRef::new("http://example.com#me")?,
+///     rdf::TYPE,
+///     NamedNodeRef::new("http://schema.org/Person")?,
+/// ))?;
+/// assert!(serializer.finish()?.starts_with(b"$HDT"));
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+pub struct WriterHdtSerializer<W: Write> {
+    writer: W,
+    inner: InnerHdtWriter,
+}
+
+impl<W: Write> WriterHdtSerializer<W> {
+    /// Adds a triple to the file.
+    ///
+    /// Fails with an [`InvalidInput`](io::ErrorKind::InvalidInput) error if the triple is not representable in HDT.
+    pub fn serialize_triple<'a>(&mut self, t: impl Into<TripleRef<'a>>) -> io::Result<()> {
+        self.inner.serialize_triple(t.into())
+    }
+
+    /// Writes the file.
+    ///
+    /// Note that this function does not flush the writer. You need to do that if you are using a [`BufWriter`](io::BufWriter).
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(&self.inner.finish())?;
+        Ok(self.writer)
+    }
+}
+
+/// Writes a HDT file to a [`AsyncWrite`] implementation.
+///
+/// Can be built using [`HdtSerializer::for_tokio_async_writer`].
+///
+/// <div class="warning">
+///
+/// Nothing is written before the [`finish`](TokioAsyncWriterHdtSerializer::finish()) method is called.</div>
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use oxhdt::HdtSerializer;
+/// use oxrdf::vocab::rdf;
+/// use oxrdf::{NamedNodeRef, TripleRef};
+///
+/// let mut serializer = HdtSerializer::new().for_tokio_async_writer(Vec::new());
+/// serializer
+///     .serialize_triple(TripleRef::new(
+///         NamedNodeRef::new("http://example.com#me")?,
+///         rdf::TYPE,
+///         NamedNodeRef::new("http://schema.org/Person")?,
+///     ))
+///     .await?;
+/// assert!(serializer.finish().await?.starts_with(b"$HDT"));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async-tokio")]
+#[must_use]
+pub struct TokioAsyncWriterHdtSerializer<W: AsyncWrite + Unpin> {
+    writer: W,
+    inner: InnerHdtWriter,
+}
+
+#[cfg(feature = "async-tokio")]
+impl<W: AsyncWrite + Unpin> TokioAsyncWriterHdtSerializer<W> {
+    /// Adds a triple to the file.
+    ///
+    /// Fails with an [`InvalidInput`](io::ErrorKind::InvalidInput) error if the triple is not representable in HDT.
+    #[expect(clippy::unused_async)]
+    pub async fn serialize_triple<'a>(&mut self, t: impl Into<TripleRef<'a>>) -> io::Result<()> {
+        self.inner.serialize_triple(t.into())
+    }
+
+    /// Writes the file.
+    ///
+    /// Note that this function does not flush the writer. You need to do that if you are using a [`BufWriter`](tokio::io::BufWriter).
+    pub async fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(&self.inner.finish()).await?;
+        Ok(self.writer)
+    }
+}
+
+struct InnerHdtWriter {
+    dataset_iri: Option<NamedNode>,
+    terms: HashMap<String, usize>,
+    triples: Vec<[usize; 3]>,
+}
+
+impl InnerHdtWriter {
+    fn new(serializer: HdtSerializer) -> Self {
+        Self {
+            dataset_iri: serializer.dataset_iri,
+            terms: HashMap::new(),
+            triples: Vec::new(),
+        }
+    }
+
+    fn serialize_triple(&mut self, triple: TripleRef<'_>) -> io::Result<()> {
+        let subject = encode_subject(triple.subject);
+        let predicate = triple.predicate.as_str().to_owned();
+        let object = encode_object(triple.object)?;
+        if [&subject, &predicate, &object]
+            .iter()
+            .any(|value| value.contains('\0'))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "HDT does not support terms containing the NUL character",
+            ));
+        }
+        let triple = [
+            self.term_id(subject),
+            self.term_id(predicate),
+            self.term_id(object),
+        ];
+        self.triples.push(triple);
+        Ok(())
+    }
+
+    fn term_id(&mut self, term: String) -> usize {
+        let next_id = self.terms.len();
+        *self.terms.entry(term).or_insert(next_id)
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        let mut terms = vec![""; self.terms.len()];
+        for (term, id) in &self.terms {
+            terms[*id] = term;
+        }
+        let mut is_subject = vec![false; terms.len()];
+        let mut is_predicate = vec![false; terms.len()];
+        let mut is_object = vec![false; terms.len()];
+        for [s, p, o] in &self.triples {
+            is_subject[*s] = true;
+            is_predicate[*p] = true;
+            is_object[*o] = true;
+        }
+
+        // We build the dictionary sections
+        let sorted_section = |filter: &dyn Fn(usize) -> bool| {
+            let mut section = (0..terms.len()).filter(|i| filter(*i)).collect::<Vec<_>>();
+            section.sort_unstable_by_key(|i| terms[*i].as_bytes());
+            section
+        };
+        let shared = sorted_section(&|i| is_subject[i] && is_object[i]);
+        let subjects = sorted_section(&|i| is_subject[i] && !is_object[i]);
+        let predicates = sorted_section(&|i| is_predicate[i]);
+        let objects = sorted_section(&|i| is_object[i] && !is_subject[i]);
+        let mut subject_ids = vec![0; terms.len()];
+        let mut predicate_ids = vec![0; terms.len()];
+        let mut object_ids = vec![0; terms.len()];
+        for (id, term) in (1..).zip(&shared) {
+            subject_ids[*term] = id;
+            object_ids[*term] = id;
+        }
+        for (id, term) in (shared.len() as u64 + 1..).zip(&subjects) {
+            subject_ids[*term] = id;
+        }
+        for (id, term) in (1..).zip(&predicates) {
+            predicate_ids[*term] = id;
+        }
+        for (id, term) in (shared.len() as u64 + 1..).zip(&objects) {
+            object_ids[*term] = id;
+        }
+        let mut triples = self
+            .triples
+            .iter()
+            .map(|[s, p, o]| [subject_ids[*s], predicate_ids[*p], object_ids[*o]])
+            .collect::<Vec<_>>();
+        triples.sort_unstable();
+        triples.dedup();
+
+        let mut dictionary = Vec::new();
+        for section in [&shared, &subjects, &predicates, &objects] {
+            PfcSection::write(
+                &mut dictionary,
+                &section
+                    .iter()
+                    .map(|i| terms[*i].as_bytes())
+                    .collect::<Vec<_>>(),
+                BLOCK_SIZE,
+            );
+        }
+        let strings_size = [&shared, &subjects, &predicates, &objects]
+            .into_iter()
+            .flatten()
+            .map(|i| terms[*i].len() + 1)
+            .sum::<usize>();
+
+        let header = self.header(
+            triples.len(),
+            shared.len(),
+            subjects.len(),
+            predicates.len(),
+            objects.len(),
+            strings_size,
+        );
+
+        let mut output = Vec::new();
+        ControlInfo::write(&mut output, CONTROL_TYPE_GLOBAL, HDT_FORMAT, &[]);
+        ControlInfo::write(
+            &mut output,
+            CONTROL_TYPE_HEADER,
+            "ntriples",
+            &[("length", header.len().to_string())],
+        );
+        output.extend_from_slice(header.as_bytes());
+        ControlInfo::write(
+            &mut output,
+            CONTROL_TYPE_DICTIONARY,
+            DICTIONARY_FOUR_FORMAT,
+            &[
+                ("mapping", "1".into()),
+                ("sizeStrings", strings_size.to_string()),
+            ],
+        );
+        output.extend_from_slice(&dictionary);
+        BitmapTriples::write(&mut output, &triples);
+        output
+    }
+
+    /// Builds the N-Triples header with the same statistics as the reference implementations.
+    fn header(
+        &self,
+        triples: usize,
+        shared: usize,
+        subjects: usize,
+        predicates: usize,
+        objects: usize,
+        strings_size: usize,
+    ) -> String {
+        let dataset = self.dataset_iri.clone().map_or_else(
+            || NamedOrBlankNode::from(BlankNode::new_unchecked("dataset")),
+            Into::into,
+        );
+        let format = NamedOrBlankNode::from(BlankNode::new_unchecked("format"));
+        let dictionary = NamedOrBlankNode::from(BlankNode::new_unchecked("dictionary"));
+        let triples_node = NamedOrBlankNode::from(BlankNode::new_unchecked("triples"));
+        let hdt = |name: &str| NamedNode::new_unchecked(format!("http://purl.org/HDT/hdt#{name}"));
+        let void = |name: &str| NamedNode::new_unchecked(format!("http://rdfs.org/ns/void#{name}"));
+        let count = |value: usize| Term::from(Literal::new_simple_literal(value.to_string()));
+        let dc_format = NamedNodeRef::new_unchecked("http://purl.org/dc/terms/format");
+
+        let mut header = String::new();
+        for (subject, predicate, object) in [
+            (&dataset, rdf::TYPE.into_owned(), hdt("Dataset").into()),
+            (&dataset, rdf::TYPE.into_owned(), void("Dataset").into()),
+            (&dataset, void("triples"), count(triples)),
+            (&dataset, void("properties"), count(predicates)),
+            (&dataset, void("distinctSubjects"), count(shared + subjects)),
+            (&dataset, void("distinctObjects"), count(shared + objects)),
+            (&dataset, hdt("formatInformation"), format.clone().into()),
+            (&format, hdt("dictionary"), dictionary.clone().into()),
+            (&format, hdt("triples"), triples_node.clone().into()),
+            (
+                &dictionary,
+                dc_format.into_owned(),
+                hdt("dictionaryFour").into(),
+            ),
+            (
+                &dictionary,
+                hdt("dictionarynumSharedSubjectObject"),
+                count(shared),
+            ),
+            (&dictionary, hdt("dictionarymapping"), count(1)),
+            (
+                &dictionary,
+                hdt("dictionarysizeStrings"),
+                count(strings_size),
+            ),
+            (&dictionary, hdt("dictionaryblockSize"), count(BLOCK_SIZE)),
+            (
+                &triples_node,
+                dc_format.into_owned(),
+                hdt("triplesBitmap").into(),
+            ),
+            (&triples_node, hdt("triplesnumTriples"), count(triples)),
+            (
+                &triples_node,
+                hdt("triplesOrder"),
+                Literal::new_simple_literal("SPO").into(),
+            ),
+        ] {
+            header.push_str(&Triple::new(subject.clone(), predicate, object).to_string());
+            header.push_str(" .\n");
+        }
+        header
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::HdtParser;
+    use oxrdf::vocab::xsd;
+    use oxrdf::{LiteralRef, NamedNodeRef};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_roundtrip() -> io::Result<()> {
+        let mut triples = vec![
+            Triple::new(
+                NamedNode::new_unchecked("http://example.com/s"),
+                NamedNode::new_unchecked("http://example.com/p"),
+                BlankNode::new_unchecked("b"),
+            ),
+            Triple::new(
+                BlankNode::new_unchecked("b"),
+                NamedNode::new_unchecked("http://example.com/p"),
+                NamedNode::new_unchecked("http://example.com/s"),
+            ),
+            Triple::new(
+                BlankNode::new_unchecked("b"),
+                NamedNode::new_unchecked("http://example.com/p"),
+                Literal::new_simple_literal("foo \"bar\"\n"),
+            ),
+            Triple::new(
+                BlankNode::new_unchecked("b"),
+                NamedNode::new_unchecked("http://example.com/p"),
+                Literal::new_language_tagged_literal_unchecked("foo", "en"),
+            ),
+            Triple::new(
+                BlankNode::new_unchecked("b"),
+                NamedNode::new_unchecked("http://example.com/p"),
+                Literal::new_typed_literal("1", xsd::INTEGER),
+            ),
+        ];
+        for i in 0..100 {
+            triples.push(Triple::new(
+                NamedNode::new_unchecked(format!("http://example.com/s{}", i % 7)),
+                NamedNode::new_unchecked(format!("http://example.com/p{}", i % 3)),
+                Literal::new_simple_literal(i.to_string()),
+            ));
+        }
+
+        let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+        for triple in &triples {
+            serializer.serialize_triple(triple)?;
+        }
+        // Duplicates are written only once
+        serializer.serialize_triple(&triples[0])?;
+        let file = serializer.finish()?;
+
+        let parsed = HdtParser::new()
+            .for_slice(&file)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(parsed.len(), triples.len());
+        assert_eq!(
+            parsed.into_iter().collect::<HashSet<_>>(),
+            triples.into_iter().collect::<HashSet<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut serializer = HdtSerializer::new().for_writer(Vec::new());
+        assert!(
+            serializer
+                .serialize_triple(TripleRef::new(
+                    NamedNodeRef::new_unchecked("http://example.com/s"),
+                    NamedNodeRef::new_unchecked("http://example.com/p"),
+                    LiteralRef::new_simple_literal("a\0b"),
+                ))
+                .is_err()
+        );
+    }
+}
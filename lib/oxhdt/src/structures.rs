@@ -0,0 +1,355 @@
+//! The building blocks of HDT files: control information, bitmaps, sequences and front-coded string sections.
+//!
+//! Read structures only store positions inside of the file bytes so that they can be used with owned or borrowed data.
+
+use crate::encoding::{Cursor, crc8, crc16, crc32, write_vbyte};
+use crate::error::HdtSyntaxError;
+use std::ops::Range;
+use std::str;
+
+pub const CONTROL_TYPE_GLOBAL: u8 = 1;
+pub const CONTROL_TYPE_HEADER: u8 = 2;
+pub const CONTROL_TYPE_DICTIONARY: u8 = 3;
+pub const CONTROL_TYPE_TRIPLES: u8 = 4;
+
+const BITMAP_TYPE_PLAIN: u8 = 1;
+const SEQUENCE_TYPE_LOG: u8 = 1;
+const SECTION_TYPE_PFC: u8 = 2;
+
+/// A control information block, introducing each part of a HDT file.
+pub struct ControlInfo<'a> {
+    format: &'a str,
+    properties: &'a str,
+}
+
+impl<'a> ControlInfo<'a> {
+    pub fn read(cursor: &mut Cursor<'a>, expected_type: u8) -> Result<Self, HdtSyntaxError> {
+        let start = cursor.position();
+        if cursor.read_bytes(4)? != b"$HDT" {
+            return Err(HdtSyntaxError::msg(
+                "Invalid HDT control information: it must start with $HDT",
+            ));
+        }
+        let control_type = cursor.read_u8()?;
+        if control_type != expected_type {
+            return Err(HdtSyntaxError::msg(format!(
+                "Unexpected HDT control information type {control_type}, expecting {expected_type}"
+            )));
+        }
+        let format = str::from_utf8(cursor.read_nul_terminated()?).map_err(|_| {
+            HdtSyntaxError::msg("The HDT control information format must be valid UTF-8")
+        })?;
+        let properties = str::from_utf8(cursor.read_nul_terminated()?).map_err(|_| {
+            HdtSyntaxError::msg("The HDT control information properties must be valid UTF-8")
+        })?;
+        if crc16(cursor.read_since(start)) != cursor.read_u16_le()? {
+            return Err(HdtSyntaxError::invalid_checksum("control information"));
+        }
+        Ok(Self { format, properties })
+    }
+
+    pub fn format(&self) -> &'a str {
+        self.format
+    }
+
+    pub fn property(&self, key: &str) -> Option<&'a str> {
+        self.properties
+            .split(';')
+            .find_map(|property| property.strip_prefix(key)?.strip_prefix('='))
+    }
+
+    pub fn write(
+        output: &mut Vec<u8>,
+        control_type: u8,
+        format: &str,
+        properties: &[(&str, String)],
+    ) {
+        let start = output.len();
+        output.extend_from_slice(b"$HDT");
+        output.push(control_type);
+        output.extend_from_slice(format.as_bytes());
+        output.push(0);
+        for (key, value) in properties {
+            output.extend_from_slice(key.as_bytes());
+            output.push(b'=');
+            output.extend_from_slice(value.as_bytes());
+            output.push(b';');
+        }
+        output.push(0);
+        let crc = crc16(&output[start..]);
+        output.extend_from_slice(&crc.to_le_bytes());
+    }
+}
+
+/// A plain bitmap.
+pub struct Bitmap {
+    len: usize,
+    data: Range<usize>,
+}
+
+impl Bitmap {
+    pub fn read(cursor: &mut Cursor<'_>) -> Result<Self, HdtSyntaxError> {
+        let start = cursor.position();
+        let bitmap_type = cursor.read_u8()?;
+        if bitmap_type != BITMAP_TYPE_PLAIN {
+            return Err(HdtSyntaxError::msg(format!(
+                "Unsupported HDT bitmap type {bitmap_type}"
+            )));
+        }
+        let len = cursor.read_vbyte_usize()?;
+        if crc8(cursor.read_since(start)) != cursor.read_u8()? {
+            return Err(HdtSyntaxError::invalid_checksum("bitmap metadata"));
+        }
+        let data_start = cursor.position();
+        let data = cursor.read_bytes(len.div_ceil(8))?;
+        if crc32(data) != cursor.read_u32_le()? {
+            return Err(HdtSyntaxError::invalid_checksum("bitmap content"));
+        }
+        Ok(Self {
+            len,
+            data: data_start..data_start + data.len(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the bit at the given position, assuming it is lower than [`len`](Self::len).
+    pub fn get(&self, file: &[u8], index: usize) -> bool {
+        file[self.data.start + index / 8] >> (index % 8) & 1 == 1
+    }
+
+    pub fn write(output: &mut Vec<u8>, bits: &[bool]) {
+        let start = output.len();
+        output.push(BITMAP_TYPE_PLAIN);
+        write_vbyte(output, bits.len() as u64);
+        let crc = crc8(&output[start..]);
+        output.push(crc);
+        let data = bits
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |byte, (i, bit)| byte | (u8::from(*bit) << i))
+            })
+            .collect::<Vec<_>>();
+        output.extend_from_slice(&data);
+        output.extend_from_slice(&crc32(&data).to_le_bytes());
+    }
+}
+
+/// A sequence of integers all encoded with the same number of bits.
+pub struct LogSequence {
+    bits: usize,
+    len: usize,
+    data: Range<usize>,
+}
+
+impl LogSequence {
+    pub fn read(cursor: &mut Cursor<'_>) -> Result<Self, HdtSyntaxError> {
+        let start = cursor.position();
+        let sequence_type = cursor.read_u8()?;
+        if sequence_type != SEQUENCE_TYPE_LOG {
+            return Err(HdtSyntaxError::msg(format!(
+                "Unsupported HDT sequence type {sequence_type}"
+            )));
+        }
+        let bits = usize::from(cursor.read_u8()?);
+        if bits > 64 {
+            return Err(HdtSyntaxError::msg(format!(
+                "HDT sequences cannot have entries of {bits} bits"
+            )));
+        }
+        let len = cursor.read_vbyte_usize()?;
+        if crc8(cursor.read_since(start)) != cursor.read_u8()? {
+            return Err(HdtSyntaxError::invalid_checksum("sequence metadata"));
+        }
+        let data_start = cursor.position();
+        let data = cursor.read_bytes(
+            bits.checked_mul(len)
+                .ok_or_else(|| HdtSyntaxError::msg("Too large HDT sequence"))?
+                .div_ceil(8),
+        )?;
+        if crc32(data) != cursor.read_u32_le()? {
+            return Err(HdtSyntaxError::invalid_checksum("sequence content"));
+        }
+        Ok(Self {
+            bits,
+            len,
+            data: data_start..data_start + data.len(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the value at the given position, assuming it is lower than [`len`](Self::len).
+    pub fn get(&self, file: &[u8], index: usize) -> u64 {
+        if self.bits == 0 {
+            return 0;
+        }
+        let bit_position = index * self.bits;
+        let start = self.data.start + bit_position / 8;
+        let end = (start + 9).min(self.data.end);
+        let mut buffer = [0; 16];
+        buffer[..end - start].copy_from_slice(&file[start..end]);
+        let value = u128::from_le_bytes(buffer) >> (bit_position % 8);
+        #[expect(clippy::cast_possible_truncation)]
+        let value = value as u64;
+        if self.bits == 64 {
+            value
+        } else {
+            value & ((1 << self.bits) - 1)
+        }
+    }
+
+    pub fn write(output: &mut Vec<u8>, values: &[u64]) {
+        let bits = values
+            .iter()
+            .max()
+            .map_or(1, |max| (u64::BITS - max.leading_zeros()).max(1));
+        let start = output.len();
+        output.push(SEQUENCE_TYPE_LOG);
+        #[expect(clippy::cast_possible_truncation)]
+        output.push(bits as u8);
+        write_vbyte(output, values.len() as u64);
+        let crc = crc8(&output[start..]);
+        output.push(crc);
+        let bits = bits as usize;
+        let mut data = vec![0_u8; (bits * values.len()).div_ceil(8)];
+        for (i, value) in values.iter().enumerate() {
+            for bit in 0..bits {
+                if value >> bit & 1 == 1 {
+                    let position = i * bits + bit;
+                    data[position / 8] |= 1 << (position % 8);
+                }
+            }
+        }
+        output.extend_from_slice(&data);
+        output.extend_from_slice(&crc32(&data).to_le_bytes());
+    }
+}
+
+/// A dictionary section of sorted strings encoded with plain front coding.
+///
+/// Strings are grouped into blocks: the first string of each block is stored as is,
+/// the other ones as the length of the prefix shared with the previous string followed by the remaining suffix.
+pub struct PfcSection {
+    len: u64,
+    block_size: u64,
+    blocks: LogSequence,
+    data: Range<usize>,
+}
+
+impl PfcSection {
+    pub fn read(cursor: &mut Cursor<'_>) -> Result<Self, HdtSyntaxError> {
+        let start = cursor.position();
+        let section_type = cursor.read_u8()?;
+        if section_type != SECTION_TYPE_PFC {
+            return Err(HdtSyntaxError::msg(format!(
+                "Unsupported HDT dictionary section type {section_type}"
+            )));
+        }
+        let len = cursor.read_vbyte()?;
+        let data_len = cursor.read_vbyte_usize()?;
+        let block_size = cursor.read_vbyte()?;
+        if crc8(cursor.read_since(start)) != cursor.read_u8()? {
+            return Err(HdtSyntaxError::invalid_checksum(
+                "dictionary section metadata",
+            ));
+        }
+        if len > 0 && block_size == 0 {
+            return Err(HdtSyntaxError::msg(
+                "HDT dictionary sections must have a non zero block size",
+            ));
+        }
+        let blocks = LogSequence::read(cursor)?;
+        if len > 0 && (blocks.len() as u64) < len.div_ceil(block_size) {
+            return Err(HdtSyntaxError::msg(
+                "The HDT dictionary section has less blocks than its number of strings requires",
+            ));
+        }
+        let data_start = cursor.position();
+        let data = cursor.read_bytes(data_len)?;
+        if crc32(data) != cursor.read_u32_le()? {
+            return Err(HdtSyntaxError::invalid_checksum(
+                "dictionary section content",
+            ));
+        }
+        Ok(Self {
+            len,
+            block_size,
+            blocks,
+            data: data_start..data_start + data.len(),
+        })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns the string with the given 1-based id.
+    pub fn extract(&self, file: &[u8], id: u64) -> Result<Vec<u8>, HdtSyntaxError> {
+        if id == 0 || id > self.len {
+            return Err(HdtSyntaxError::msg(format!(
+                "The HDT dictionary id {id} is out of range"
+            )));
+        }
+        let index = id - 1;
+        let block = usize::try_from(index / self.block_size)
+            .map_err(|_| HdtSyntaxError::msg("Too large HDT dictionary id"))?;
+        let offset = usize::try_from(self.blocks.get(file, block))
+            .map_err(|_| HdtSyntaxError::msg("Too large HDT dictionary offset"))?;
+        let mut cursor = Cursor::new(&file[self.data.clone()], offset);
+        let mut value = cursor.read_nul_terminated()?.to_vec();
+        for _ in 0..index % self.block_size {
+            let prefix_len = cursor.read_vbyte_usize()?;
+            if prefix_len > value.len() {
+                return Err(HdtSyntaxError::msg(
+                    "Invalid front coding in a HDT dictionary section",
+                ));
+            }
+            value.truncate(prefix_len);
+            value.extend_from_slice(cursor.read_nul_terminated()?);
+        }
+        Ok(value)
+    }
+
+    /// Writes the given strings, that must be sorted and deduplicated.
+    pub fn write(output: &mut Vec<u8>, values: &[&[u8]], block_size: usize) {
+        let mut data = Vec::new();
+        let mut blocks = Vec::new();
+        let mut previous: &[u8] = &[];
+        for (i, value) in values.iter().enumerate() {
+            if i % block_size == 0 {
+                blocks.push(data.len() as u64);
+                data.extend_from_slice(value);
+            } else {
+                let prefix_len = previous
+                    .iter()
+                    .zip(value.iter())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                write_vbyte(&mut data, prefix_len as u64);
+                data.extend_from_slice(&value[prefix_len..]);
+            }
+            data.push(0);
+            previous = value;
+        }
+        blocks.push(data.len() as u64);
+
+        let start = output.len();
+        output.push(SECTION_TYPE_PFC);
+        write_vbyte(output, values.len() as u64);
+        write_vbyte(output, data.len() as u64);
+        write_vbyte(output, block_size as u64);
+        let crc = crc8(&output[start..]);
+        output.push(crc);
+        LogSequence::write(output, &blocks);
+        output.extend_from_slice(&data);
+        output.extend_from_slice(&crc32(&data).to_le_bytes());
+    }
+}
@@ -0,0 +1,147 @@
+//! The "bitmap triples" encoding of the triples as sorted adjacency lists.
+//!
+//! Triples are sorted by their first component (x), then by the second one (y), then by the last one (z).
+//! The x components are implicit: they are consecutive integers starting from 1.
+//! The y components are stored in a sequence with a bitmap marking the last y of each x.
+//! The z components are stored in a sequence with a bitmap marking the last z of each (x, y) pair.
+
+use crate::encoding::Cursor;
+use crate::error::HdtSyntaxError;
+use crate::structures::{Bitmap, CONTROL_TYPE_TRIPLES, ControlInfo, LogSequence};
+
+pub const TRIPLES_BITMAP_FORMAT: &str = "<http://purl.org/HDT/hdt#triplesBitmap>";
+pub const SPO_ORDER: u8 = 1;
+
+pub struct BitmapTriples {
+    order: u8,
+    bitmap_y: Bitmap,
+    bitmap_z: Bitmap,
+    sequence_y: LogSequence,
+    sequence_z: LogSequence,
+}
+
+impl BitmapTriples {
+    pub fn read(cursor: &mut Cursor<'_>) -> Result<Self, HdtSyntaxError> {
+        let control_info = ControlInfo::read(cursor, CONTROL_TYPE_TRIPLES)?;
+        if control_info.format() != TRIPLES_BITMAP_FORMAT {
+            return Err(HdtSyntaxError::msg(format!(
+                "Unsupported HDT triples format {}, only {TRIPLES_BITMAP_FORMAT} is supported",
+                control_info.format()
+            )));
+        }
+        let order = control_info
+            .property("order")
+            .and_then(|order| order.parse().ok())
+            .filter(|order| (1..=6).contains(order))
+            .ok_or_else(|| HdtSyntaxError::msg("Unsupported or missing HDT triples order"))?;
+        let bitmap_y = Bitmap::read(cursor)?;
+        let bitmap_z = Bitmap::read(cursor)?;
+        let sequence_y = LogSequence::read(cursor)?;
+        let sequence_z = LogSequence::read(cursor)?;
+        if bitmap_y.len() != sequence_y.len() || bitmap_z.len() != sequence_z.len() {
+            return Err(HdtSyntaxError::msg(
+                "The HDT triples bitmaps and sequences must have the same length",
+            ));
+        }
+        Ok(Self {
+            order,
+            bitmap_y,
+            bitmap_z,
+            sequence_y,
+            sequence_z,
+        })
+    }
+
+    /// Writes triples of ids that must be sorted in subject, predicate, object order and deduplicated.
+    pub fn write(output: &mut Vec<u8>, triples: &[[u64; 3]]) {
+        let mut bitmap_y = Vec::new();
+        let mut bitmap_z = Vec::new();
+        let mut sequence_y = Vec::new();
+        let mut sequence_z = Vec::new();
+        let mut previous: Option<[u64; 3]> = None;
+        for [s, p, o] in triples.iter().copied() {
+            match previous {
+                Some([previous_s, _, _]) if previous_s != s => {
+                    *bitmap_y.last_mut().unwrap() = true;
+                    *bitmap_z.last_mut().unwrap() = true;
+                    bitmap_y.push(false);
+                    sequence_y.push(p);
+                }
+                Some([_, previous_p, _]) if previous_p != p => {
+                    *bitmap_z.last_mut().unwrap() = true;
+                    bitmap_y.push(false);
+                    sequence_y.push(p);
+                }
+                Some(_) => (),
+                None => {
+                    bitmap_y.push(false);
+                    sequence_y.push(p);
+                }
+            }
+            bitmap_z.push(false);
+            sequence_z.push(o);
+            previous = Some([s, p, o]);
+        }
+        if let Some(last) = bitmap_y.last_mut() {
+            *last = true;
+        }
+        if let Some(last) = bitmap_z.last_mut() {
+            *last = true;
+        }
+
+        ControlInfo::write(
+            output,
+            CONTROL_TYPE_TRIPLES,
+            TRIPLES_BITMAP_FORMAT,
+            &[("order", SPO_ORDER.to_string())],
+        );
+        Bitmap::write(output, &bitmap_y);
+        Bitmap::write(output, &bitmap_z);
+        LogSequence::write(output, &sequence_y);
+        LogSequence::write(output, &sequence_z);
+    }
+}
+
+/// Position of an iteration over [`BitmapTriples`].
+#[derive(Default)]
+pub struct TriplesPosition {
+    x: u64,
+    y: usize,
+    z: usize,
+}
+
+impl TriplesPosition {
+    /// Returns the next triple of ids in subject, predicate, object order.
+    pub fn next(
+        &mut self,
+        triples: &BitmapTriples,
+        file: &[u8],
+    ) -> Option<Result<[u64; 3], HdtSyntaxError>> {
+        if self.z >= triples.sequence_z.len() {
+            return None;
+        }
+        if self.y >= triples.sequence_y.len() {
+            return Some(Err(HdtSyntaxError::msg(
+                "The HDT triples bitmaps are inconsistent with the sequences",
+            )));
+        }
+        let x = self.x + 1;
+        let y = triples.sequence_y.get(file, self.y);
+        let z = triples.sequence_z.get(file, self.z);
+        if triples.bitmap_z.get(file, self.z) {
+            if triples.bitmap_y.get(file, self.y) {
+                self.x += 1;
+            }
+            self.y += 1;
+        }
+        self.z += 1;
+        Some(Ok(match triples.order {
+            SPO_ORDER => [x, y, z],
+            2 => [x, z, y], // SOP
+            3 => [y, x, z], // PSO
+            4 => [z, x, y], // POS
+            5 => [y, z, x], // OSP
+            _ => [z, y, x], // OPS
+        }))
+    }
+}
@@ -0,0 +1,367 @@
+//! A lenient HTML parser building a minimal element tree.
+//!
+//! It does not implement the full HTML5 tree construction algorithm but handles what matters
+//! for metadata extraction: void elements, raw text elements, character references
+//! and the implicit end of the most common elements (`p`, `li`, `td`...).
+use std::ops::Range;
+
+pub struct Element {
+    /// The lowercase element name
+    pub name: String,
+    /// The attributes with lowercase names, in document order
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+    /// The language inherited from the `lang` and `xml:lang` attributes
+    pub language: Option<String>,
+    /// The position of the element content in the source document
+    pub inner: Range<usize>,
+}
+
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|(n, _)| n == name)
+    }
+
+    /// The concatenation of all the descendant text nodes
+    pub fn text_content(&self) -> String {
+        let mut text = String::new();
+        self.write_text_content(&mut text);
+        text
+    }
+
+    fn write_text_content(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                Node::Element(e) => e.write_text_content(text),
+                Node::Text(t) => text.push_str(t),
+            }
+        }
+    }
+
+    pub fn child_elements(&self) -> impl Iterator<Item = &Self> {
+        self.children.iter().filter_map(|c| match c {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Calls `callback` on this element and all its descendants in document order
+    pub fn for_each_element<'a>(&'a self, callback: &mut impl FnMut(&'a Self)) {
+        callback(self);
+        for child in self.child_elements() {
+            child.for_each_element(callback);
+        }
+    }
+}
+
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+const RAW_TEXT_ELEMENTS: [&str; 5] = ["script", "style", "textarea", "title", "xmp"];
+
+/// Elements implicitly closed when one of the given elements starts
+fn is_implicitly_closed_by(open: &str, starting: &str) -> bool {
+    match open {
+        "p" => matches!(
+            starting,
+            "address"
+                | "article"
+                | "aside"
+                | "blockquote"
+                | "div"
+                | "dl"
+                | "fieldset"
+                | "footer"
+                | "form"
+                | "h1"
+                | "h2"
+                | "h3"
+                | "h4"
+                | "h5"
+                | "h6"
+                | "header"
+                | "hr"
+                | "main"
+                | "nav"
+                | "ol"
+                | "p"
+                | "pre"
+                | "section"
+                | "table"
+                | "ul"
+        ),
+        "li" => starting == "li",
+        "dt" | "dd" => matches!(starting, "dt" | "dd"),
+        "option" => matches!(starting, "option" | "optgroup"),
+        "tr" => starting == "tr",
+        "td" | "th" => matches!(starting, "td" | "th" | "tr"),
+        "thead" | "tbody" => matches!(starting, "tbody" | "tfoot"),
+        _ => false,
+    }
+}
+
+/// Parses an HTML document and returns a synthetic root element containing the document nodes
+pub fn parse(html: &str) -> Element {
+    let mut stack = vec![Element {
+        name: String::new(),
+        attributes: Vec::new(),
+        children: Vec::new(),
+        language: None,
+        inner: 0..html.len(),
+    }];
+    let mut position = 0;
+    while position < html.len() {
+        let rest = &html[position..];
+        let Some(tag_start) = rest.find('<') else {
+            push_text(&mut stack, decode_character_references(rest));
+            break;
+        };
+        if tag_start > 0 {
+            push_text(&mut stack, decode_character_references(&rest[..tag_start]));
+        }
+        position += tag_start;
+        let rest = &html[position..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            position += comment.find("-->").map_or(rest.len(), |end| end + 7);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            position += rest.find('>').map_or(rest.len(), |end| end + 1);
+        } else if let Some(end_tag) = rest.strip_prefix("</") {
+            let name_len = end_tag
+                .find(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+                .unwrap_or(end_tag.len());
+            let name = end_tag[..name_len].to_ascii_lowercase();
+            let tag_end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            if let Some(index) = stack.iter().rposition(|e| e.name == name) {
+                if index > 0 {
+                    close_elements(&mut stack, index, position);
+                }
+            }
+            position += tag_end;
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let (mut element, self_closing, tag_len) = parse_start_tag(rest);
+            position += tag_len;
+            while stack.len() > 1
+                && stack
+                    .last()
+                    .is_some_and(|open| is_implicitly_closed_by(&open.name, &element.name))
+            {
+                let last = stack.len() - 1;
+                close_elements(&mut stack, last, position - tag_len);
+            }
+            element.language = match element
+                .attribute("xml:lang")
+                .or_else(|| element.attribute("lang"))
+            {
+                Some("") => None,
+                Some(language) => Some(language.into()),
+                None => stack.last().and_then(|parent| parent.language.clone()),
+            };
+            element.inner = position..position;
+            if RAW_TEXT_ELEMENTS.contains(&element.name.as_str()) && !self_closing {
+                let content = &html[position..];
+                let end = find_end_tag(content, &element.name).unwrap_or(content.len());
+                let text = &content[..end];
+                element.children.push(Node::Text(
+                    if matches!(element.name.as_str(), "script" | "style" | "xmp") {
+                        text.into()
+                    } else {
+                        decode_character_references(text)
+                    },
+                ));
+                element.inner = position..position + end;
+                position += end;
+                position += html[position..].find('>').map_or(0, |e| e + 1);
+                push_element(&mut stack, element);
+            } else if self_closing || VOID_ELEMENTS.contains(&element.name.as_str()) {
+                push_element(&mut stack, element);
+            } else {
+                stack.push(element);
+            }
+        } else {
+            push_text(&mut stack, "<".into());
+            position += 1;
+        }
+    }
+    close_elements(&mut stack, 1, html.len());
+    stack.pop().unwrap_or_else(|| unreachable!())
+}
+
+fn push_text(stack: &mut [Element], text: String) {
+    if let Some(parent) = stack.last_mut() {
+        if let Some(Node::Text(previous)) = parent.children.last_mut() {
+            previous.push_str(&text);
+        } else {
+            parent.children.push(Node::Text(text));
+        }
+    }
+}
+
+fn push_element(stack: &mut [Element], element: Element) {
+    if let Some(parent) = stack.last_mut() {
+        parent.children.push(Node::Element(element));
+    }
+}
+
+/// Closes all the elements of the stack from `index` and sets `end` as their content end
+fn close_elements(stack: &mut Vec<Element>, index: usize, end: usize) {
+    while stack.len() > index.max(1) {
+        let Some(mut element) = stack.pop() else {
+            break;
+        };
+        element.inner.end = end;
+        push_element(stack, element);
+    }
+}
+
+fn find_end_tag(content: &str, name: &str) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(start) = content[offset..].find("</") {
+        let start = offset + start;
+        let candidate = &content[start + 2..];
+        if candidate.len() >= name.len()
+            && candidate.is_char_boundary(name.len())
+            && candidate[..name.len()].eq_ignore_ascii_case(name)
+        {
+            return Some(start);
+        }
+        offset = start + 2;
+    }
+    None
+}
+
+/// Parses a start tag and returns the element, if it is self-closing and the tag length
+fn parse_start_tag(tag: &str) -> (Element, bool, usize) {
+    let mut position = 1;
+    let name_len = tag[position..]
+        .find(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+        .unwrap_or(tag.len() - position);
+    let name = tag[position..position + name_len].to_ascii_lowercase();
+    position += name_len;
+    let mut attributes = Vec::new();
+    let mut self_closing = false;
+    loop {
+        let rest = &tag[position..];
+        let trimmed = rest.trim_start_matches(|c: char| c.is_ascii_whitespace());
+        position += rest.len() - trimmed.len();
+        if trimmed.is_empty() {
+            break;
+        }
+        if trimmed.starts_with('>') {
+            position += 1;
+            break;
+        }
+        if let Some(after) = trimmed.strip_prefix("/>") {
+            self_closing = true;
+            position = tag.len() - after.len();
+            break;
+        }
+        if trimmed.starts_with('/') {
+            position += 1;
+            continue;
+        }
+        let attribute_name_len = trimmed
+            .find(|c: char| c.is_ascii_whitespace() || matches!(c, '=' | '>' | '/'))
+            .unwrap_or(trimmed.len())
+            .max(1);
+        let attribute_name = trimmed[..attribute_name_len].to_ascii_lowercase();
+        position += attribute_name_len;
+        let rest = &tag[position..];
+        let trimmed = rest.trim_start_matches(|c: char| c.is_ascii_whitespace());
+        let value = if let Some(value) = trimmed.strip_prefix('=') {
+            let value = value.trim_start_matches(|c: char| c.is_ascii_whitespace());
+            position = tag.len() - value.len();
+            if let Some(quote) = value.chars().next().filter(|c| matches!(c, '"' | '\'')) {
+                let end = value[1..].find(quote).map_or(value.len(), |e| e + 1);
+                position += (end + 1).min(value.len());
+                decode_character_references(&value[1..end])
+            } else {
+                let end = value
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .unwrap_or(value.len());
+                position += end;
+                decode_character_references(&value[..end])
+            }
+        } else {
+            String::new()
+        };
+        if !attributes.iter().any(|(n, _)| *n == attribute_name) {
+            attributes.push((attribute_name, value));
+        }
+    }
+    (
+        Element {
+            name,
+            attributes,
+            children: Vec::new(),
+            language: None,
+            inner: 0..0,
+        },
+        self_closing,
+        position,
+    )
+}
+
+/// Decodes the numeric and the most common named character references
+pub fn decode_character_references(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest[1..].find(';').and_then(|end| {
+            let reference = &rest[1..=end];
+            let c = if let Some(number) = reference.strip_prefix('#') {
+                if let Some(hex) = number.strip_prefix(['x', 'X']) {
+                    u32::from_str_radix(hex, 16).ok()
+                } else {
+                    number.parse().ok()
+                }
+                .and_then(char::from_u32)?
+            } else {
+                match reference {
+                    "amp" => '&',
+                    "lt" => '<',
+                    "gt" => '>',
+                    "quot" => '"',
+                    "apos" => '\'',
+                    "nbsp" => '\u{a0}',
+                    "copy" => '\u{a9}',
+                    "reg" => '\u{ae}',
+                    "trade" => '\u{2122}',
+                    "hellip" => '\u{2026}',
+                    "mdash" => '\u{2014}',
+                    "ndash" => '\u{2013}',
+                    "lsquo" => '\u{2018}',
+                    "rsquo" => '\u{2019}',
+                    "ldquo" => '\u{201c}',
+                    "rdquo" => '\u{201d}',
+                    "euro" => '\u{20ac}',
+                    _ => return None,
+                }
+            };
+            Some((c, end + 2))
+        });
+        if let Some((c, len)) = decoded {
+            result.push(c);
+            rest = &rest[len..];
+        } else {
+            result.push('&');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
@@ -0,0 +1,64 @@
+use oxiri::IriParseError;
+use oxjsonld::JsonLdSyntaxError;
+use std::io;
+
+/// Error returned during HTML parsing.
+#[derive(Debug, thiserror::Error)]
+pub enum HtmlParseError {
+    /// I/O error during parsing (file not found...).
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error in the file syntax.
+    #[error(transparent)]
+    Syntax(#[from] HtmlSyntaxError),
+}
+
+impl From<HtmlParseError> for io::Error {
+    #[inline]
+    fn from(error: HtmlParseError) -> Self {
+        match error {
+            HtmlParseError::Io(error) => error,
+            HtmlParseError::Syntax(error) => error.into(),
+        }
+    }
+}
+
+/// An error in the syntax of the parsed file.
+///
+/// HTML parsing is lenient: errors are only returned for invalid embedded data
+/// like a malformed JSON-LD script or an invalid base IRI.
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct HtmlSyntaxError(#[from] SyntaxErrorKind);
+
+#[derive(Debug, thiserror::Error)]
+enum SyntaxErrorKind {
+    #[error("error in an embedded JSON-LD script: {0}")]
+    JsonLd(#[from] JsonLdSyntaxError),
+    #[error("error while parsing IRI '{iri}': {error}")]
+    InvalidIri {
+        iri: String,
+        #[source]
+        error: IriParseError,
+    },
+}
+
+impl HtmlSyntaxError {
+    pub(crate) fn invalid_iri(iri: String, error: IriParseError) -> Self {
+        Self(SyntaxErrorKind::InvalidIri { iri, error })
+    }
+}
+
+impl From<JsonLdSyntaxError> for HtmlSyntaxError {
+    #[inline]
+    fn from(error: JsonLdSyntaxError) -> Self {
+        Self(SyntaxErrorKind::JsonLd(error))
+    }
+}
+
+impl From<HtmlSyntaxError> for io::Error {
+    #[inline]
+    fn from(error: HtmlSyntaxError) -> Self {
+        Self::new(io::ErrorKind::InvalidData, error)
+    }
+}
@@ -0,0 +1,15 @@
+#![doc = include_str!("../README.md")]
+#![doc(test(attr(deny(warnings))))]
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![doc(html_favicon_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+#![doc(html_logo_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+
+mod dom;
+mod error;
+mod microdata;
+mod parser;
+mod rdfa;
+mod utils;
+
+pub use error::{HtmlParseError, HtmlSyntaxError};
+pub use parser::{HtmlParser, ReaderHtmlParser, SliceHtmlParser};
@@ -0,0 +1,151 @@
+//! Implementation of the [Microdata to RDF](https://www.w3.org/TR/microdata-rdf/) mapping.
+//!
+//! Property names that are not absolute IRIs are resolved against the vocabulary of the item type,
+//! i.e. the type IRI up to its last `#` or `/`, like `http://schema.org/` for `http://schema.org/Person`.
+use crate::dom::Element;
+use crate::utils::{datetime_literal, plain_literal, resolve_iri};
+use oxiri::Iri;
+use oxrdf::vocab::rdf;
+use oxrdf::{BlankNode, NamedNode, NamedOrBlankNode, Term, Triple};
+use std::collections::HashMap;
+
+/// Extracts the microdata triples of the document
+pub fn extract(root: &Element, base: Option<&Iri<String>>) -> Vec<Triple> {
+    let mut ids = HashMap::new();
+    let mut top_level_items = Vec::new();
+    root.for_each_element(&mut |element| {
+        if let Some(id) = element.attribute("id") {
+            ids.entry(id).or_insert(element);
+        }
+        if element.has_attribute("itemscope") && !element.has_attribute("itemprop") {
+            top_level_items.push(element);
+        }
+    });
+    let mut extractor = MicrodataExtractor {
+        base,
+        ids,
+        items: HashMap::new(),
+        triples: Vec::new(),
+    };
+    for item in top_level_items {
+        extractor.item(item, None);
+    }
+    extractor.triples
+}
+
+struct MicrodataExtractor<'a> {
+    base: Option<&'a Iri<String>>,
+    ids: HashMap<&'a str, &'a Element>,
+    items: HashMap<*const Element, NamedOrBlankNode>,
+    triples: Vec<Triple>,
+}
+
+impl<'a> MicrodataExtractor<'a> {
+    fn item(&mut self, element: &'a Element, parent_vocabulary: Option<&str>) -> NamedOrBlankNode {
+        if let Some(subject) = self.items.get(&std::ptr::from_ref(element)) {
+            return subject.clone();
+        }
+        let subject: NamedOrBlankNode = element
+            .attribute("itemid")
+            .and_then(|id| resolve_iri(self.base, id))
+            .map_or_else(|| BlankNode::default().into(), Into::into);
+        self.items
+            .insert(std::ptr::from_ref(element), subject.clone());
+
+        let types = element
+            .attribute("itemtype")
+            .unwrap_or_default()
+            .split_ascii_whitespace()
+            .filter_map(|t| NamedNode::new(t).ok())
+            .collect::<Vec<_>>();
+        for t in &types {
+            self.triples
+                .push(Triple::new(subject.clone(), rdf::TYPE, t.clone()));
+        }
+        let vocabulary = types
+            .first()
+            .map(|t| {
+                let iri = t.as_str();
+                iri[..iri.rfind(['#', '/']).map_or(iri.len(), |i| i + 1)].to_owned()
+            })
+            .or_else(|| parent_vocabulary.map(ToOwned::to_owned));
+
+        let mut properties = Vec::new();
+        collect_properties(element, &mut properties);
+        for id in element
+            .attribute("itemref")
+            .unwrap_or_default()
+            .split_ascii_whitespace()
+        {
+            if let Some(referenced) = self.ids.get(id).copied() {
+                if referenced.has_attribute("itemprop") {
+                    properties.push(referenced);
+                }
+                if !referenced.has_attribute("itemscope") {
+                    collect_properties(referenced, &mut properties);
+                }
+            }
+        }
+
+        for property in properties {
+            let value = self.value(property, vocabulary.as_deref());
+            for name in property
+                .attribute("itemprop")
+                .unwrap_or_default()
+                .split_ascii_whitespace()
+            {
+                let predicate = if name.contains(':') {
+                    NamedNode::new(name).ok()
+                } else {
+                    vocabulary
+                        .as_ref()
+                        .and_then(|vocabulary| NamedNode::new(format!("{vocabulary}{name}")).ok())
+                };
+                if let Some(predicate) = predicate {
+                    self.triples
+                        .push(Triple::new(subject.clone(), predicate, value.clone()));
+                }
+            }
+        }
+        subject
+    }
+
+    fn value(&mut self, element: &'a Element, vocabulary: Option<&str>) -> Term {
+        if element.has_attribute("itemscope") {
+            return self.item(element, vocabulary).into();
+        }
+        let language = element.language.as_deref();
+        let iri_attribute = match element.name.as_str() {
+            "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => Some("src"),
+            "a" | "area" | "link" => Some("href"),
+            "object" => Some("data"),
+            _ => None,
+        };
+        if let Some(attribute) = iri_attribute {
+            let value = element.attribute(attribute).unwrap_or_default();
+            return resolve_iri(self.base, value)
+                .map_or_else(|| plain_literal(value, None).into(), Into::into);
+        }
+        match element.name.as_str() {
+            "meta" => plain_literal(element.attribute("content").unwrap_or_default(), language),
+            "data" | "meter" => plain_literal(element.attribute("value").unwrap_or_default(), None),
+            "time" if element.has_attribute("datetime") => {
+                datetime_literal(element.attribute("datetime").unwrap_or_default(), language)
+            }
+            _ => plain_literal(element.text_content(), language),
+        }
+        .into()
+    }
+}
+
+/// Adds the elements with an `itemprop` attribute under `element` without entering nested items
+fn collect_properties<'a>(element: &'a Element, properties: &mut Vec<&'a Element>) {
+    for child in element.child_elements() {
+        if child.has_attribute("itemprop") {
+            properties.push(child);
+        }
+        if !child.has_attribute("itemscope") {
+            collect_properties(child, properties);
+        }
+    }
+}
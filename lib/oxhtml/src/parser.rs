@@ -0,0 +1,318 @@
+use crate::dom;
+use crate::error::{HtmlParseError, HtmlSyntaxError};
+use crate::{microdata, rdfa};
+use oxiri::{Iri, IriParseError};
+use oxjsonld::JsonLdParser;
+use oxrdf::{BlankNode, GraphName, NamedNode, NamedOrBlankNode, Quad};
+use std::io::Read;
+use std::vec;
+
+/// A parser extracting RDF from HTML documents.
+///
+/// It extracts:
+/// * [RDFa 1.1 Core](https://www.w3.org/TR/rdfa-core/) annotations following the [HTML+RDFa 1.1](https://www.w3.org/TR/html-rdfa/) rules,
+/// * [HTML microdata](https://html.spec.whatwg.org/multipage/microdata.html) items following the [Microdata to RDF](https://www.w3.org/TR/microdata-rdf/) mapping,
+/// * the content of the `<script type="application/ld+json">` elements parsed as [JSON-LD](https://www.w3.org/TR/json-ld/).
+///
+/// The HTML parsing itself is lenient: malformed markup never fails the parsing.
+/// Errors are only returned for invalid embedded JSON-LD or an invalid `<base>` IRI.
+///
+/// Count the number of people:
+/// ```
+/// use oxhtml::HtmlParser;
+/// use oxrdf::NamedNodeRef;
+/// use oxrdf::vocab::rdf;
+///
+/// let file = br#"<html>
+///  <body vocab="http://schema.org/">
+///   <p typeof="Person" resource="http://example.com/foo"><span property="name">Foo</span></p>
+///   <p itemscope itemtype="http://schema.org/Person"><span itemprop="name">Bar</span></p>
+///   <script type="application/ld+json">{"@context": {"@vocab": "http://schema.org/"}, "@type": "Person", "name": "Baz"}</script>
+///  </body>
+/// </html>"#;
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut count = 0;
+/// for quad in HtmlParser::new().for_reader(file.as_ref()) {
+///     let quad = quad?;
+///     if quad.predicate == rdf::TYPE && quad.object == schema_person.into() {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(3, count);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+#[must_use]
+pub struct HtmlParser {
+    base: Option<Iri<String>>,
+    without_rdfa: bool,
+    without_microdata: bool,
+    without_json_ld: bool,
+}
+
+impl HtmlParser {
+    /// Builds a new [`HtmlParser`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the IRI of the document, used as base IRI and as RDFa default subject.
+    ///
+    /// It is overridden by the `<base href="...">` element if there is one.
+    #[inline]
+    pub fn with_base_iri(mut self, base_iri: impl Into<String>) -> Result<Self, IriParseError> {
+        self.base = Some(Iri::parse(base_iri.into())?);
+        Ok(self)
+    }
+
+    /// Does not extract the RDFa annotations.
+    #[inline]
+    pub fn without_rdfa(mut self) -> Self {
+        self.without_rdfa = true;
+        self
+    }
+
+    /// Does not extract the microdata items.
+    #[inline]
+    pub fn without_microdata(mut self) -> Self {
+        self.without_microdata = true;
+        self
+    }
+
+    /// Does not parse the embedded JSON-LD scripts.
+    #[inline]
+    pub fn without_json_ld(mut self) -> Self {
+        self.without_json_ld = true;
+        self
+    }
+
+    /// Parses a HTML file from a [`Read`] implementation.
+    ///
+    /// The full file is loaded in memory before extraction.
+    ///
+    /// Count the number of people:
+    /// ```
+    /// use oxhtml::HtmlParser;
+    /// use oxrdf::NamedNodeRef;
+    /// use oxrdf::vocab::rdf;
+    ///
+    /// let file = br#"<div vocab="http://schema.org/" typeof="Person"><span property="name">Foo</span></div>"#;
+    ///
+    /// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+    /// let mut count = 0;
+    /// for quad in HtmlParser::new().for_reader(file.as_ref()) {
+    ///     let quad = quad?;
+    ///     if quad.predicate == rdf::TYPE && quad.object == schema_person.into() {
+    ///         count += 1;
+    ///     }
+    /// }
+    /// assert_eq!(1, count);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_reader<R: Read>(self, reader: R) -> ReaderHtmlParser<R> {
+        ReaderHtmlParser {
+            reader: Some(reader),
+            parser: self,
+            results: Vec::new().into_iter(),
+        }
+    }
+
+    /// Parses a HTML file from a byte slice.
+    ///
+    /// Extracts the name of a person described with microdata:
+    /// ```
+    /// use oxhtml::HtmlParser;
+    /// use oxrdf::{LiteralRef, NamedNodeRef};
+    ///
+    /// let file = br#"<!DOCTYPE html>
+    /// <div itemscope itemtype="http://schema.org/Person" itemid="http://example.com/foo" lang="en">
+    ///  <p>Name: <span itemprop="name">Foo</span>
+    ///  <p>Born: <time itemprop="birthDate" datetime="2000-01-01">January 1st, 2000</time>
+    /// </div>"#;
+    ///
+    /// let quads = HtmlParser::new()
+    ///     .without_rdfa()
+    ///     .for_slice(file)
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert!(quads.iter().any(|q| {
+    ///     q.subject == NamedNodeRef::new_unchecked("http://example.com/foo").into()
+    ///         && q.predicate == NamedNodeRef::new_unchecked("http://schema.org/name")
+    ///         && q.object == LiteralRef::new_language_tagged_literal_unchecked("Foo", "en").into()
+    /// }));
+    /// assert_eq!(quads.len(), 3);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_slice(self, slice: &[u8]) -> SliceHtmlParser<'_> {
+        SliceHtmlParser {
+            slice: Some(slice),
+            parser: self,
+            results: Vec::new().into_iter(),
+        }
+    }
+
+    fn extract(&self, data: &[u8]) -> Vec<Result<Quad, HtmlSyntaxError>> {
+        let html = String::from_utf8_lossy(data);
+        let root = dom::parse(&html);
+        let mut results = Vec::new();
+
+        let mut base = self.base.clone();
+        let mut base_href = None;
+        root.for_each_element(&mut |element| {
+            if base_href.is_none() && element.name == "base" {
+                base_href = element.attribute("href");
+            }
+        });
+        if let Some(base_href) = base_href {
+            let resolved = if let Some(base) = &base {
+                base.resolve(base_href)
+            } else {
+                Iri::parse(base_href.to_owned())
+            };
+            match resolved {
+                Ok(resolved) => base = Some(resolved),
+                Err(e) => results.push(Err(HtmlSyntaxError::invalid_iri(base_href.into(), e))),
+            }
+        }
+
+        if !self.without_rdfa {
+            let document: NamedOrBlankNode = base
+                .as_ref()
+                .map(|base| NamedNode::new_unchecked(base.as_str()))
+                .map_or_else(|| BlankNode::default().into(), Into::into);
+            results.extend(
+                rdfa::extract(&root, &html, base.as_ref(), document)
+                    .into_iter()
+                    .map(|t| Ok(t.in_graph(GraphName::DefaultGraph))),
+            );
+        }
+        if !self.without_microdata {
+            results.extend(
+                microdata::extract(&root, base.as_ref())
+                    .into_iter()
+                    .map(|t| Ok(t.in_graph(GraphName::DefaultGraph))),
+            );
+        }
+        if !self.without_json_ld {
+            let mut scripts = Vec::new();
+            root.for_each_element(&mut |element| {
+                if element.name == "script"
+                    && element.attribute("type").is_some_and(|t| {
+                        t.split(';')
+                            .next()
+                            .unwrap_or_default()
+                            .trim()
+                            .eq_ignore_ascii_case("application/ld+json")
+                    })
+                {
+                    scripts.push(element.text_content());
+                }
+            });
+            for script in scripts {
+                let mut parser = JsonLdParser::new();
+                if let Some(base) = &base {
+                    parser = match parser.with_base_iri(base.as_str()) {
+                        Ok(parser) => parser,
+                        Err(e) => {
+                            results.push(Err(HtmlSyntaxError::invalid_iri(base.to_string(), e)));
+                            continue;
+                        }
+                    };
+                }
+                results.extend(
+                    parser
+                        .for_slice(script.as_bytes())
+                        .map(|q| q.map_err(Into::into)),
+                );
+            }
+        }
+        results
+    }
+}
+
+/// Parses a HTML file from a [`Read`] implementation.
+///
+/// Can be built using [`HtmlParser::for_reader`].
+///
+/// Count the number of people:
+/// ```
+/// use oxhtml::HtmlParser;
+/// use oxrdf::NamedNodeRef;
+/// use oxrdf::vocab::rdf;
+///
+/// let file = br#"<div vocab="http://schema.org/" typeof="Person"><span property="name">Foo</span></div>"#;
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut count = 0;
+/// for quad in HtmlParser::new().for_reader(file.as_ref()) {
+///     let quad = quad?;
+///     if quad.predicate == rdf::TYPE && quad.object == schema_person.into() {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(1, count);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+pub struct ReaderHtmlParser<R: Read> {
+    reader: Option<R>,
+    parser: HtmlParser,
+    results: vec::IntoIter<Result<Quad, HtmlSyntaxError>>,
+}
+
+impl<R: Read> Iterator for ReaderHtmlParser<R> {
+    type Item = Result<Quad, HtmlParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(mut reader) = self.reader.take() {
+            let mut data = Vec::new();
+            if let Err(e) = reader.read_to_end(&mut data) {
+                return Some(Err(e.into()));
+            }
+            self.results = self.parser.extract(&data).into_iter();
+        }
+        Some(self.results.next()?.map_err(Into::into))
+    }
+}
+
+/// Parses a HTML file from a byte slice.
+///
+/// Can be built using [`HtmlParser::for_slice`].
+///
+/// Count the number of people:
+/// ```
+/// use oxhtml::HtmlParser;
+/// use oxrdf::NamedNodeRef;
+/// use oxrdf::vocab::rdf;
+///
+/// let file = br#"<div vocab="http://schema.org/" typeof="Person"><span property="name">Foo</span></div>"#;
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut count = 0;
+/// for quad in HtmlParser::new().for_slice(file) {
+///     let quad = quad?;
+///     if quad.predicate == rdf::TYPE && quad.object == schema_person.into() {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(1, count);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+pub struct SliceHtmlParser<'a> {
+    slice: Option<&'a [u8]>,
+    parser: HtmlParser,
+    results: vec::IntoIter<Result<Quad, HtmlSyntaxError>>,
+}
+
+impl Iterator for SliceHtmlParser<'_> {
+    type Item = Result<Quad, HtmlSyntaxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(slice) = self.slice.take() {
+            self.results = self.parser.extract(slice).into_iter();
+        }
+        self.results.next()
+    }
+}
@@ -0,0 +1,578 @@
+//! Implementation of the [RDFa 1.1 Core processing sequence](https://www.w3.org/TR/rdfa-core/#s_sequence)
+//! with the [HTML+RDFa 1.1](https://www.w3.org/TR/html-rdfa/) extensions.
+use crate::dom::Element;
+use crate::utils::{datetime_literal, plain_literal, resolve_iri};
+use oxiri::Iri;
+use oxrdf::vocab::rdf;
+use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, NamedOrBlankNode, Term, Triple};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const XHV: &str = "http://www.w3.org/1999/xhtml/vocab#";
+const USES_VOCABULARY: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/ns/rdfa#usesVocabulary");
+
+/// Prefixes of the [RDFa 1.1 initial context](https://www.w3.org/2011/rdfa-context/rdfa-1.1)
+const INITIAL_PREFIXES: [(&str, &str); 46] = [
+    ("as", "https://www.w3.org/ns/activitystreams#"),
+    ("cc", "http://creativecommons.org/ns#"),
+    ("csvw", "http://www.w3.org/ns/csvw#"),
+    ("ctag", "http://commontag.org/ns#"),
+    ("dc", "http://purl.org/dc/terms/"),
+    ("dc11", "http://purl.org/dc/elements/1.1/"),
+    ("dcat", "http://www.w3.org/ns/dcat#"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("dqv", "http://www.w3.org/ns/dqv#"),
+    ("duv", "https://www.w3.org/ns/duv#"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("gr", "http://purl.org/goodrelations/v1#"),
+    ("grddl", "http://www.w3.org/2003/g/data-view#"),
+    ("ical", "http://www.w3.org/2002/12/cal/icaltzd#"),
+    ("jsonld", "http://www.w3.org/ns/json-ld#"),
+    ("ldp", "http://www.w3.org/ns/ldp#"),
+    ("ma", "http://www.w3.org/ns/ma-ont#"),
+    ("oa", "http://www.w3.org/ns/oa#"),
+    ("odrl", "http://www.w3.org/ns/odrl/2/"),
+    ("og", "http://ogp.me/ns#"),
+    ("org", "http://www.w3.org/ns/org#"),
+    ("owl", "http://www.w3.org/2002/07/owl#"),
+    ("prov", "http://www.w3.org/ns/prov#"),
+    ("qb", "http://purl.org/linked-data/cube#"),
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfa", "http://www.w3.org/ns/rdfa#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("rev", "http://purl.org/stuff/rev#"),
+    ("rif", "http://www.w3.org/2007/rif#"),
+    ("rr", "http://www.w3.org/ns/r2rml#"),
+    ("schema", "http://schema.org/"),
+    ("sd", "http://www.w3.org/ns/sparql-service-description#"),
+    ("sioc", "http://rdfs.org/sioc/ns#"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+    ("skosxl", "http://www.w3.org/2008/05/skos-xl#"),
+    ("sosa", "http://www.w3.org/ns/sosa/"),
+    ("ssn", "http://www.w3.org/ns/ssn/"),
+    ("time", "http://www.w3.org/2006/time#"),
+    ("v", "http://rdf.data-vocabulary.org/#"),
+    ("vcard", "http://www.w3.org/2006/vcard/ns#"),
+    ("void", "http://rdfs.org/ns/void#"),
+    ("wdr", "http://www.w3.org/2007/05/powder#"),
+    ("wdrs", "http://www.w3.org/2007/05/powder-s#"),
+    ("xhv", XHV),
+    ("xml", "http://www.w3.org/XML/1998/namespace"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+];
+
+/// Terms of the RDFa 1.1 initial context
+const INITIAL_TERMS: [(&str, &str); 3] = [
+    (
+        "describedby",
+        "http://www.w3.org/2007/05/powder-s#describedby",
+    ),
+    ("license", "http://www.w3.org/1999/xhtml/vocab#license"),
+    ("role", "http://www.w3.org/1999/xhtml/vocab#role"),
+];
+
+/// Extracts the RDFa triples of the document
+pub fn extract(
+    root: &Element,
+    source: &str,
+    base: Option<&Iri<String>>,
+    document: NamedOrBlankNode,
+) -> Vec<Triple> {
+    let mut processor = RdfaProcessor {
+        source,
+        base,
+        document: document.clone(),
+        blank_nodes: HashMap::new(),
+        triples: Vec::new(),
+    };
+    let context = EvaluationContext {
+        parent_subject: document,
+        parent_object: None,
+        incomplete_triples: Vec::new(),
+        prefixes: Rc::default(),
+        vocabulary: None,
+    };
+    let mut list_mapping = ListMapping::new();
+    for child in root.child_elements() {
+        processor.process(child, &context, &mut list_mapping, true);
+    }
+    processor.triples
+}
+
+#[derive(Clone)]
+struct EvaluationContext {
+    parent_subject: NamedOrBlankNode,
+    parent_object: Option<NamedOrBlankNode>,
+    incomplete_triples: Vec<(NamedNode, Direction)>,
+    prefixes: Rc<HashMap<String, String>>,
+    vocabulary: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Forward,
+    Reverse,
+    List,
+}
+
+type ListMapping = Vec<(NamedNode, Vec<Term>)>;
+
+fn list_entry(list_mapping: &mut ListMapping, predicate: NamedNode) -> &mut Vec<Term> {
+    let position = if let Some(position) = list_mapping.iter().position(|(p, _)| *p == predicate) {
+        position
+    } else {
+        list_mapping.push((predicate, Vec::new()));
+        list_mapping.len() - 1
+    };
+    &mut list_mapping[position].1
+}
+
+struct RdfaProcessor<'a> {
+    source: &'a str,
+    base: Option<&'a Iri<String>>,
+    document: NamedOrBlankNode,
+    blank_nodes: HashMap<String, BlankNode>,
+    triples: Vec<Triple>,
+}
+
+impl RdfaProcessor<'_> {
+    #[expect(clippy::too_many_lines)]
+    fn process(
+        &mut self,
+        element: &Element,
+        context: &EvaluationContext,
+        parent_list_mapping: &mut ListMapping,
+        is_root: bool,
+    ) {
+        let mut skip = false;
+        let mut new_subject = None;
+        let mut current_object = None;
+        let mut typed_resource = None;
+        let mut incomplete_triples = Vec::new();
+        let mut prefixes = Rc::clone(&context.prefixes);
+        let mut vocabulary = context.vocabulary.clone();
+        let language = element.language.as_deref();
+
+        // Steps 2 and 3: vocabulary and prefixes
+        if let Some(vocab) = element.attribute("vocab") {
+            vocabulary = if vocab.is_empty() {
+                None
+            } else {
+                resolve_iri(self.base, vocab).map(|vocab| {
+                    self.triples.push(Triple::new(
+                        self.document.clone(),
+                        USES_VOCABULARY,
+                        vocab.clone(),
+                    ));
+                    vocab.into_string()
+                })
+            };
+        }
+        for (name, value) in &element.attributes {
+            if let Some(prefix) = name.strip_prefix("xmlns:") {
+                Rc::make_mut(&mut prefixes).insert(prefix.to_ascii_lowercase(), value.clone());
+            }
+        }
+        if let Some(prefix_declarations) = element.attribute("prefix") {
+            let mut tokens = prefix_declarations.split_ascii_whitespace();
+            while let Some(prefix) = tokens.next() {
+                let Some(prefix) = prefix.strip_suffix(':') else {
+                    continue;
+                };
+                let Some(namespace) = tokens.next() else {
+                    break;
+                };
+                if !prefix.is_empty() && prefix != "_" {
+                    Rc::make_mut(&mut prefixes)
+                        .insert(prefix.to_ascii_lowercase(), namespace.into());
+                }
+            }
+        }
+        let scope = Scope {
+            prefixes: &prefixes,
+            vocabulary: vocabulary.as_deref(),
+        };
+
+        // HTML+RDFa: non-CURIE values of @rel and @rev are ignored if @property is present
+        let has_property = element.has_attribute("property");
+        let link_attribute = |name| {
+            let value = element.attribute(name)?;
+            if !has_property {
+                return Some(value.split_ascii_whitespace().collect::<Vec<_>>());
+            }
+            let values = value
+                .split_ascii_whitespace()
+                .filter(|v| v.contains(':'))
+                .collect::<Vec<_>>();
+            (!values.is_empty()).then_some(values)
+        };
+        let rel = link_attribute("rel");
+        let rev = link_attribute("rev");
+        let about = element
+            .attribute("about")
+            .and_then(|v| self.resolve_safe_curie_or_curie_or_iri(v, &scope));
+        let resource = element
+            .attribute("resource")
+            .and_then(|v| self.resolve_safe_curie_or_curie_or_iri(v, &scope))
+            .or_else(|| {
+                element
+                    .attribute("href")
+                    .and_then(|v| resolve_iri(self.base, v))
+                    .map(Into::into)
+            })
+            .or_else(|| {
+                element
+                    .attribute("src")
+                    .and_then(|v| resolve_iri(self.base, v))
+                    .map(Into::into)
+            });
+        let has_typeof = element.has_attribute("typeof");
+        let content = element.attribute("content");
+        let datatype = element.attribute("datatype");
+        let is_root_like = is_root || matches!(element.name.as_str(), "head" | "body");
+        let root_subject = || {
+            context
+                .parent_object
+                .clone()
+                .unwrap_or_else(|| self.document.clone())
+        };
+
+        if rel.is_none() && rev.is_none() {
+            // Step 5
+            if has_property && content.is_none() && datatype.is_none() {
+                new_subject = if about.is_some() {
+                    about.clone()
+                } else if is_root_like {
+                    Some(root_subject())
+                } else {
+                    context.parent_object.clone()
+                };
+                if has_typeof {
+                    typed_resource = Some(
+                        about
+                            .clone()
+                            .or_else(|| resource.clone())
+                            .unwrap_or_else(|| BlankNode::default().into()),
+                    );
+                    current_object.clone_from(&typed_resource);
+                }
+            } else {
+                new_subject = if about.is_some() || resource.is_some() {
+                    about.clone().or_else(|| resource.clone())
+                } else if is_root_like {
+                    Some(root_subject())
+                } else if has_typeof {
+                    Some(BlankNode::default().into())
+                } else {
+                    if !has_property {
+                        skip = true;
+                    }
+                    context.parent_object.clone()
+                };
+                if has_typeof {
+                    typed_resource.clone_from(&new_subject);
+                }
+            }
+        } else {
+            // Step 6
+            new_subject.clone_from(&about);
+            if has_typeof {
+                typed_resource.clone_from(&new_subject);
+            }
+            if new_subject.is_none() {
+                new_subject = if is_root_like {
+                    Some(root_subject())
+                } else {
+                    context.parent_object.clone()
+                };
+            }
+            current_object.clone_from(&resource);
+            if current_object.is_none() && has_typeof && about.is_none() {
+                current_object = Some(BlankNode::default().into());
+            }
+            if has_typeof && about.is_none() {
+                typed_resource.clone_from(&current_object);
+            }
+        }
+
+        // Step 7: types
+        if let (Some(typed_resource), Some(types)) = (&typed_resource, element.attribute("typeof"))
+        {
+            for value in types.split_ascii_whitespace() {
+                if let Some(t) = self.resolve_term_or_curie_or_absolute_iri(value, &scope) {
+                    self.triples
+                        .push(Triple::new(typed_resource.clone(), rdf::TYPE, t));
+                }
+            }
+        }
+
+        // Step 8: a new list mapping if the subject changed
+        let mut own_list_mapping =
+            (new_subject.is_some() && new_subject != context.parent_object).then(ListMapping::new);
+
+        // Steps 9 and 10: links
+        let in_list = element.has_attribute("inlist");
+        if let Some(subject) = &new_subject {
+            let rel_predicates = self.predicates(rel.as_deref(), &scope);
+            let rev_predicates = self.predicates(rev.as_deref(), &scope);
+            if let Some(object) = &current_object {
+                for predicate in rel_predicates {
+                    if in_list {
+                        list_entry(
+                            local_list_mapping(&mut own_list_mapping, parent_list_mapping),
+                            predicate,
+                        )
+                        .push(object.clone().into());
+                    } else {
+                        self.triples
+                            .push(Triple::new(subject.clone(), predicate, object.clone()));
+                    }
+                }
+                for predicate in rev_predicates {
+                    self.triples
+                        .push(Triple::new(object.clone(), predicate, subject.clone()));
+                }
+            } else if rel.is_some() || rev.is_some() {
+                for predicate in rel_predicates {
+                    if in_list {
+                        list_entry(
+                            local_list_mapping(&mut own_list_mapping, parent_list_mapping),
+                            predicate.clone(),
+                        );
+                        incomplete_triples.push((predicate, Direction::List));
+                    } else {
+                        incomplete_triples.push((predicate, Direction::Forward));
+                    }
+                }
+                for predicate in rev_predicates {
+                    incomplete_triples.push((predicate, Direction::Reverse));
+                }
+                current_object = Some(BlankNode::default().into());
+            }
+        }
+
+        // Step 11: properties
+        if let (Some(subject), Some(property)) = (&new_subject, element.attribute("property")) {
+            let datatype = datatype.map(|datatype| {
+                if datatype.is_empty() {
+                    None
+                } else {
+                    match self.resolve_term_or_curie_or_absolute_iri(datatype, &scope) {
+                        Some(NamedOrBlankNode::NamedNode(datatype)) => Some(datatype),
+                        _ => None,
+                    }
+                }
+            });
+            let datetime = element.attribute("datetime");
+            let value: Term = match datatype {
+                Some(Some(datatype))
+                    if datatype.as_ref() == rdf::XML_LITERAL || datatype.as_ref() == rdf::HTML =>
+                {
+                    Literal::new_typed_literal(&self.source[element.inner.clone()], datatype).into()
+                }
+                Some(Some(datatype)) => Literal::new_typed_literal(
+                    content
+                        .or(datetime)
+                        .map_or_else(|| element.text_content(), Into::into),
+                    datatype,
+                )
+                .into(),
+                Some(None) => plain_literal(
+                    content.map_or_else(|| element.text_content(), Into::into),
+                    language,
+                )
+                .into(),
+                None => {
+                    if let Some(content) = content {
+                        plain_literal(content, language).into()
+                    } else if let Some(datetime) = datetime {
+                        datetime_literal(datetime, language).into()
+                    } else if let (None, None, Some(resource)) = (&rel, &rev, &resource) {
+                        resource.clone().into()
+                    } else if let (Some(typed_resource), None) = (&typed_resource, &about) {
+                        typed_resource.clone().into()
+                    } else {
+                        plain_literal(element.text_content(), language).into()
+                    }
+                }
+            };
+            for predicate in self.predicates(
+                Some(&property.split_ascii_whitespace().collect::<Vec<_>>()),
+                &scope,
+            ) {
+                if in_list {
+                    list_entry(
+                        local_list_mapping(&mut own_list_mapping, parent_list_mapping),
+                        predicate,
+                    )
+                    .push(value.clone());
+                } else {
+                    self.triples
+                        .push(Triple::new(subject.clone(), predicate, value.clone()));
+                }
+            }
+        }
+
+        // Step 12: completion of the parent incomplete triples
+        if let (false, Some(subject)) = (skip, &new_subject) {
+            for (predicate, direction) in &context.incomplete_triples {
+                match direction {
+                    Direction::Forward => self.triples.push(Triple::new(
+                        context.parent_subject.clone(),
+                        predicate.clone(),
+                        subject.clone(),
+                    )),
+                    Direction::Reverse => self.triples.push(Triple::new(
+                        subject.clone(),
+                        predicate.clone(),
+                        context.parent_subject.clone(),
+                    )),
+                    Direction::List => list_entry(parent_list_mapping, predicate.clone())
+                        .push(subject.clone().into()),
+                }
+            }
+        }
+
+        // Step 13: children
+        let child_context = if skip {
+            EvaluationContext {
+                prefixes,
+                vocabulary,
+                ..context.clone()
+            }
+        } else {
+            EvaluationContext {
+                parent_subject: new_subject
+                    .clone()
+                    .unwrap_or_else(|| context.parent_subject.clone()),
+                parent_object: Some(
+                    current_object
+                        .or_else(|| new_subject.clone())
+                        .unwrap_or_else(|| context.parent_subject.clone()),
+                ),
+                incomplete_triples,
+                prefixes,
+                vocabulary,
+            }
+        };
+        for child in element.child_elements() {
+            self.process(
+                child,
+                &child_context,
+                local_list_mapping(&mut own_list_mapping, parent_list_mapping),
+                false,
+            );
+        }
+
+        // Step 14: lists
+        if let (Some(list_mapping), Some(subject)) = (own_list_mapping, new_subject) {
+            for (predicate, list) in list_mapping {
+                let mut head: Term = rdf::NIL.into();
+                for value in list.into_iter().rev() {
+                    let node = BlankNode::default();
+                    self.triples
+                        .push(Triple::new(node.clone(), rdf::FIRST, value));
+                    self.triples
+                        .push(Triple::new(node.clone(), rdf::REST, head));
+                    head = node.into();
+                }
+                self.triples
+                    .push(Triple::new(subject.clone(), predicate, head));
+            }
+        }
+    }
+
+    fn predicates(&mut self, values: Option<&[&str]>, scope: &Scope<'_>) -> Vec<NamedNode> {
+        values
+            .unwrap_or_default()
+            .iter()
+            .filter_map(
+                |value| match self.resolve_term_or_curie_or_absolute_iri(value, scope) {
+                    Some(NamedOrBlankNode::NamedNode(predicate)) => Some(predicate),
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+
+    fn resolve_curie(&mut self, value: &str, scope: &Scope<'_>) -> Option<NamedOrBlankNode> {
+        let (prefix, reference) = value.split_once(':')?;
+        if prefix == "_" {
+            return Some(
+                self.blank_nodes
+                    .entry(reference.into())
+                    .or_default()
+                    .clone()
+                    .into(),
+            );
+        }
+        let namespace = if prefix.is_empty() {
+            XHV
+        } else {
+            scope.namespace(&prefix.to_ascii_lowercase())?
+        };
+        NamedNode::new(format!("{namespace}{reference}"))
+            .ok()
+            .map(Into::into)
+    }
+
+    fn resolve_safe_curie_or_curie_or_iri(
+        &mut self,
+        value: &str,
+        scope: &Scope<'_>,
+    ) -> Option<NamedOrBlankNode> {
+        if let Some(safe_curie) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            return self.resolve_curie(safe_curie, scope);
+        }
+        self.resolve_curie(value, scope)
+            .or_else(|| resolve_iri(self.base, value).map(Into::into))
+    }
+
+    fn resolve_term_or_curie_or_absolute_iri(
+        &mut self,
+        value: &str,
+        scope: &Scope<'_>,
+    ) -> Option<NamedOrBlankNode> {
+        if !value.contains(':') {
+            if let Some(vocabulary) = scope.vocabulary {
+                return NamedNode::new(format!("{vocabulary}{value}"))
+                    .ok()
+                    .map(Into::into);
+            }
+            return INITIAL_TERMS
+                .iter()
+                .find(|(term, _)| term.eq_ignore_ascii_case(value))
+                .map(|(_, iri)| NamedNode::new_unchecked(*iri).into());
+        }
+        self.resolve_curie(value, scope)
+            .or_else(|| NamedNode::new(value).ok().map(Into::into))
+    }
+}
+
+fn local_list_mapping<'a>(
+    own_list_mapping: &'a mut Option<ListMapping>,
+    parent_list_mapping: &'a mut ListMapping,
+) -> &'a mut ListMapping {
+    if let Some(list_mapping) = own_list_mapping {
+        list_mapping
+    } else {
+        parent_list_mapping
+    }
+}
+
+struct Scope<'a> {
+    prefixes: &'a HashMap<String, String>,
+    vocabulary: Option<&'a str>,
+}
+
+impl Scope<'_> {
+    fn namespace(&self, prefix: &str) -> Option<&str> {
+        self.prefixes.get(prefix).map(String::as_str).or_else(|| {
+            INITIAL_PREFIXES
+                .iter()
+                .find(|(p, _)| *p == prefix)
+                .map(|(_, namespace)| *namespace)
+        })
+    }
+}
@@ -0,0 +1,60 @@
+use oxiri::Iri;
+use oxrdf::vocab::xsd;
+use oxrdf::{Literal, NamedNode, NamedNodeRef};
+
+/// Resolves an IRI against the base IRI if there is one, returns `None` if the IRI is invalid
+pub fn resolve_iri(base: Option<&Iri<String>>, iri: &str) -> Option<NamedNode> {
+    let iri = if let Some(base) = base {
+        base.resolve(iri).ok()?
+    } else {
+        Iri::parse(iri.to_owned()).ok()?
+    };
+    Some(NamedNode::new_unchecked(iri.into_inner()))
+}
+
+/// Builds a literal tagged with the given language if it is valid
+pub fn plain_literal(value: impl Into<String>, language: Option<&str>) -> Literal {
+    let value = value.into();
+    if let Some(language) = language {
+        if let Ok(literal) = Literal::new_language_tagged_literal(value.clone(), language) {
+            return literal;
+        }
+    }
+    Literal::new_simple_literal(value)
+}
+
+/// Guesses the XSD datatype of an HTML `datetime` attribute value
+fn datetime_datatype(value: &str) -> Option<NamedNodeRef<'static>> {
+    if value.starts_with('P') || value.starts_with("-P") {
+        return Some(xsd::DURATION);
+    }
+    if value.contains('T') {
+        return Some(xsd::DATE_TIME);
+    }
+    if value.contains(':') {
+        return Some(xsd::TIME);
+    }
+    let parts = value.strip_prefix('-').unwrap_or(value).split('-');
+    let mut count = 0;
+    for part in parts {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        count += 1;
+    }
+    match count {
+        1 => Some(xsd::G_YEAR),
+        2 => Some(xsd::G_YEAR_MONTH),
+        3 => Some(xsd::DATE),
+        _ => None,
+    }
+}
+
+/// Builds the literal of an HTML `datetime` attribute
+pub fn datetime_literal(value: &str, language: Option<&str>) -> Literal {
+    if let Some(datatype) = datetime_datatype(value) {
+        Literal::new_typed_literal(value, datatype)
+    } else {
+        plain_literal(value, language)
+    }
+}
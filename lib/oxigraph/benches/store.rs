@@ -8,6 +8,7 @@ use oxigraph::sparql::{Query, QueryOptions, QueryResults, Update};
 use oxigraph::store::Store;
 use rand::random;
 use std::env::temp_dir;
+use std::fmt::Write;
 use std::fs::{File, remove_dir_all};
 use std::io::Read;
 use std::path::{Path, PathBuf};
@@ -237,6 +238,46 @@ fn do_store_query_and_update(c: &mut Criterion, data_size: usize, without_ops: b
     }
 }
 
+fn store_property_paths(c: &mut Criterion) {
+    // A tree with a branching factor of 4 and a long chain to look for transitive closures in
+    let store = Store::new().unwrap();
+    let mut data = String::new();
+    for i in 1..87_381 {
+        writeln!(
+            data,
+            "<http://example.com/t{}> <http://example.com/p> <http://example.com/t{i}> .",
+            (i - 1) / 4
+        )
+        .unwrap();
+    }
+    for i in 1..10_000 {
+        writeln!(
+            data,
+            "<http://example.com/c{}> <http://example.com/p> <http://example.com/c{i}> .",
+            i - 1
+        )
+        .unwrap();
+    }
+    store
+        .load_from_reader(RdfFormat::NTriples, data.as_bytes())
+        .unwrap();
+    let operations = [
+        "ASK { <http://example.com/t0> <http://example.com/p>+ <http://example.com/t87380> }",
+        "ASK { <http://example.com/t0> <http://example.com/p>* <http://example.com/c9999> }",
+        "ASK { <http://example.com/c0> <http://example.com/p>+ <http://example.com/c9999> }",
+        "ASK { <http://example.com/t5> <http://example.com/p>+ <http://example.com/t0> }",
+    ]
+    .into_iter()
+    .map(|q| Operation::Query(Query::parse(q, None).unwrap()))
+    .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("store property paths");
+    group.sample_size(10);
+    group.bench_function("transitive closure with both ends bound in memory", |b| {
+        b.iter(|| run_operation(&store, &operations, true))
+    });
+}
+
 fn run_operation(store: &Store, operations: &[Operation], with_opts: bool) {
     let mut options = QueryOptions::default();
     if !with_opts {
@@ -292,7 +333,13 @@ fn sparql_parsing(c: &mut Criterion) {
 }
 
 criterion_group!(parse, parse_bsbm);
-criterion_group!(store, sparql_parsing, store_query_and_update, store_load);
+criterion_group!(
+    store,
+    sparql_parsing,
+    store_query_and_update,
+    store_load,
+    store_property_paths
+);
 
 criterion_main!(parse, store);
 
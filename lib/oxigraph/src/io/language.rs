@@ -0,0 +1,114 @@
+use crate::model::Literal;
+use std::fmt;
+
+/// Detects the language of a text.
+///
+/// It is used by [`BulkLoader::with_language_detector`](crate::store::BulkLoader::with_language_detector)
+/// to verify the language tags of the loaded literals.
+/// Implementations usually wrap a language identification library.
+///
+/// ```
+/// use oxigraph::io::{LanguageDetector, RdfFormat};
+/// use oxigraph::model::{GraphNameRef, Literal, NamedNodeRef, QuadRef};
+/// use oxigraph::store::Store;
+///
+/// /// Recognizes English from a few very common words
+/// struct CommonWordsDetector;
+///
+/// impl LanguageDetector for CommonWordsDetector {
+///     fn detect(&self, text: &str) -> Option<String> {
+///         text.split_whitespace()
+///             .any(|word| matches!(word, "the" | "is" | "and"))
+///             .then(|| "en".into())
+///     }
+/// }
+///
+/// let file = br#"<http://example.com/s> <http://example.com/p> "the cat is black"@fr ."#;
+/// let store = Store::new()?;
+/// store
+///     .bulk_loader()
+///     .with_language_detector(CommonWordsDetector)
+///     .on_language_tag_mismatch(|mismatch| eprintln!("{mismatch}"))
+///     .fix_language_tags()
+///     .load_from_reader(RdfFormat::NTriples, file.as_ref())?;
+/// let ex_s = NamedNodeRef::new("http://example.com/s")?;
+/// let ex_p = NamedNodeRef::new("http://example.com/p")?;
+/// assert!(store.contains(QuadRef::new(
+///     ex_s,
+///     ex_p,
+///     &Literal::new_language_tagged_literal("the cat is black", "en")?,
+///     GraphNameRef::DefaultGraph
+/// ))?);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+pub trait LanguageDetector {
+    /// Returns the [BCP47](https://tools.ietf.org/html/bcp47) tag of the language of `text`
+    /// or `None` if it can't be reliably detected, for example if the text is too short or mixes several languages.
+    fn detect(&self, text: &str) -> Option<String>;
+}
+
+/// A language-tagged literal whose tag does not match the language detected from its text.
+///
+/// The tag and the detected language match if they have the same primary language subtag,
+/// for example `en-GB` matches a detected `en`.
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct LanguageTagMismatch {
+    literal: Literal,
+    detected_language: String,
+}
+
+impl LanguageTagMismatch {
+    /// Checks the language tag of `literal` with `detector`,
+    /// returning `None` if it is not language-tagged, if the language can't be detected or if it matches.
+    pub(crate) fn check(literal: &Literal, detector: &dyn LanguageDetector) -> Option<Self> {
+        let language = literal.language()?;
+        let detected_language = detector.detect(literal.value())?;
+        if primary_language(language).eq_ignore_ascii_case(primary_language(&detected_language)) {
+            return None;
+        }
+        Some(Self {
+            literal: literal.clone(),
+            detected_language,
+        })
+    }
+
+    /// The literal as it is written in the input.
+    #[inline]
+    pub fn literal(&self) -> &Literal {
+        &self.literal
+    }
+
+    /// The language detected from the literal text.
+    #[inline]
+    pub fn detected_language(&self) -> &str {
+        &self.detected_language
+    }
+
+    /// The literal tagged with the detected language, `None` if the detected language is not a valid language tag.
+    pub fn fixed_literal(&self) -> Option<Literal> {
+        #[cfg(feature = "rdf-12")]
+        if let Some(direction) = self.literal.direction() {
+            return Literal::new_directional_language_tagged_literal(
+                self.literal.value(),
+                &self.detected_language,
+                direction,
+            )
+            .ok();
+        }
+        Literal::new_language_tagged_literal(self.literal.value(), &self.detected_language).ok()
+    }
+}
+
+impl fmt::Display for LanguageTagMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The literal {} seems to be in the language {}",
+            self.literal, self.detected_language
+        )
+    }
+}
+
+fn primary_language(tag: &str) -> &str {
+    tag.split_once('-').map_or(tag, |(primary, _)| primary)
+}
@@ -26,8 +26,11 @@
 //! assert_eq!(serializer.finish().unwrap(), ntriples_file);
 //! ```
 
+mod language;
+
+pub use language::{LanguageDetector, LanguageTagMismatch};
 pub use oxrdfio::{
     JsonLdProfile, JsonLdProfileSet, LoadedDocument, RdfFormat, RdfParseError, RdfParser,
-    RdfSerializer, RdfSyntaxError, ReaderQuadParser, SliceQuadParser, TextPosition,
-    WriterQuadSerializer,
+    RdfSerializer, RdfSyntaxError, ReaderQuadParser, Shard, ShardedQuadSerializer, SliceQuadParser,
+    SortedQuadSerializer, TextPosition, WriterQuadSerializer,
 };
@@ -7,6 +7,8 @@
 
 pub mod io;
 pub mod model;
+#[cfg(feature = "http-client")]
+pub mod remote;
 pub mod sparql;
 mod storage;
 pub mod store;
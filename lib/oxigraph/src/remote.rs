@@ -0,0 +1,343 @@
+//! Client for remote SPARQL endpoints.
+//!
+//! [`RemoteStore`] talks to any server implementing the [SPARQL 1.1 Protocol](https://www.w3.org/TR/sparql11-protocol/)
+//! and the [SPARQL 1.1 Graph Store HTTP Protocol](https://www.w3.org/TR/sparql11-http-rdf-update/).
+//!
+//! Usage example with an Oxigraph server:
+//! ```no_run
+//! use oxigraph::io::RdfFormat;
+//! use oxigraph::model::GraphNameRef;
+//! use oxigraph::remote::RemoteStore;
+//! use oxigraph::sparql::QueryResults;
+//!
+//! let store = RemoteStore::new("http://localhost:7878/query")
+//!     .with_update_endpoint("http://localhost:7878/update")
+//!     .with_graph_store_endpoint("http://localhost:7878/store");
+//!
+//! // insertion
+//! store.load_graph_from_reader(
+//!     RdfFormat::NTriples,
+//!     "<http://example.com> <http://example.com/p> \"1\" .".as_bytes(),
+//!     GraphNameRef::DefaultGraph,
+//! )?;
+//!
+//! // SPARQL query
+//! if let QueryResults::Solutions(mut solutions) = store.query("SELECT ?s WHERE { ?s ?p ?o }")? {
+//!     assert_eq!(
+//!         solutions.next().unwrap()?.get("s").map(ToString::to_string),
+//!         Some("<http://example.com>".into())
+//!     );
+//! }
+//! # Result::<_, Box<dyn std::error::Error>>::Ok(())
+//! ```
+
+use crate::io::{RdfFormat, RdfParser, RdfSerializer};
+use crate::model::{GraphNameRef, Triple};
+use crate::sparql::http::{
+    Client, fill_response_buf, percent_encode, query_results_format_from_response,
+    rdf_accept_header, rdf_format_from_response,
+};
+use crate::sparql::results::{QueryResultsParser, ReaderQueryResultsParserOutput};
+use crate::sparql::{
+    EvaluationError, HttpError, HttpRetryPolicy, Query, QueryResults, QueryTripleIter, Update,
+};
+use oxhttp::model::Method;
+use std::io::{BufReader, Read, Write};
+use std::time::Duration;
+
+/// A client for a remote SPARQL endpoint with the same API as [`Store`](crate::store::Store).
+///
+/// Queries are sent to the query endpoint with the [SPARQL 1.1 Protocol](https://www.w3.org/TR/sparql11-protocol/)
+/// and their results are parsed in a streaming way while being iterated.
+/// Updates are sent to the update endpoint, the query endpoint by default.
+/// Graphs are loaded and dumped using the [SPARQL 1.1 Graph Store HTTP Protocol](https://www.w3.org/TR/sparql11-http-rdf-update/)
+/// endpoint set with [`with_graph_store_endpoint`](Self::with_graph_store_endpoint).
+///
+/// Only the query and update strings are sent:
+/// the dataset changes done with [`Query::dataset_mut`] are ignored.
+///
+/// The HTTP errors are returned as [`EvaluationError::Http`] giving access to the requested URL and to the response status.
+///
+/// ```
+/// use oxigraph::model::GraphNameRef;
+/// use oxigraph::remote::RemoteStore;
+/// use oxigraph::sparql::{EvaluationError, HttpError};
+///
+/// // No graph store endpoint is set
+/// let store = RemoteStore::new("http://example.com/query");
+/// let error = store.remove_graph(GraphNameRef::DefaultGraph).unwrap_err();
+/// assert!(matches!(error, EvaluationError::Http(HttpError::InvalidRequest(_))));
+/// assert_eq!(error.code(), "http-invalid-request");
+/// ```
+#[derive(Clone)]
+pub struct RemoteStore {
+    query_endpoint: String,
+    update_endpoint: Option<String>,
+    graph_store_endpoint: Option<String>,
+    http_timeout: Option<Duration>,
+    http_redirection_limit: usize,
+    http_retry_policy: HttpRetryPolicy,
+    client: Client,
+}
+
+impl RemoteStore {
+    /// Builds a client for the given SPARQL query endpoint URL.
+    pub fn new(query_endpoint: impl Into<String>) -> Self {
+        Self {
+            query_endpoint: query_endpoint.into(),
+            update_endpoint: None,
+            graph_store_endpoint: None,
+            http_timeout: None,
+            http_redirection_limit: 0,
+            http_retry_policy: HttpRetryPolicy::new(0),
+            client: Client::new(None, 0),
+        }
+    }
+
+    /// Sets the SPARQL update endpoint URL.
+    ///
+    /// By default, the updates are sent to the query endpoint.
+    #[inline]
+    #[must_use]
+    pub fn with_update_endpoint(mut self, update_endpoint: impl Into<String>) -> Self {
+        self.update_endpoint = Some(update_endpoint.into());
+        self
+    }
+
+    /// Sets the [SPARQL 1.1 Graph Store HTTP Protocol](https://www.w3.org/TR/sparql11-http-rdf-update/) endpoint URL.
+    ///
+    /// It is required by [`load_graph_from_reader`](Self::load_graph_from_reader), [`dump_graph_to_writer`](Self::dump_graph_to_writer) and [`remove_graph`](Self::remove_graph).
+    #[inline]
+    #[must_use]
+    pub fn with_graph_store_endpoint(mut self, graph_store_endpoint: impl Into<String>) -> Self {
+        self.graph_store_endpoint = Some(graph_store_endpoint.into());
+        self
+    }
+
+    /// Sets a timeout for the HTTP requests.
+    #[inline]
+    #[must_use]
+    pub fn with_http_timeout(mut self, timeout: Duration) -> Self {
+        self.http_timeout = Some(timeout);
+        self.client = self.new_client();
+        self
+    }
+
+    /// Sets an upper bound to the number of HTTP redirections followed per HTTP request.
+    ///
+    /// By default, this value is `0`.
+    #[inline]
+    #[must_use]
+    pub fn with_http_redirection_limit(mut self, redirection_limit: usize) -> Self {
+        self.http_redirection_limit = redirection_limit;
+        self.client = self.new_client();
+        self
+    }
+
+    /// Sets how the HTTP requests are retried when they fail.
+    ///
+    /// By default, they are not retried.
+    /// See [`HttpRetryPolicy`] for the details.
+    #[inline]
+    #[must_use]
+    pub fn with_http_retry_policy(mut self, retry_policy: HttpRetryPolicy) -> Self {
+        self.http_retry_policy = retry_policy;
+        self.client = self.new_client();
+        self
+    }
+
+    fn new_client(&self) -> Client {
+        Client::new(self.http_timeout, self.http_redirection_limit)
+            .with_retry_policy(self.http_retry_policy)
+    }
+
+    /// Executes a [SPARQL query](https://www.w3.org/TR/sparql11-query/) on the remote endpoint.
+    ///
+    /// The solutions and the triples are parsed while the results are iterated.
+    pub fn query(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+    ) -> Result<QueryResults, EvaluationError> {
+        let query = spargebra::Query::from(query.try_into().map_err(Into::into)?);
+        let is_graph_query = matches!(
+            query,
+            spargebra::Query::Construct { .. } | spargebra::Query::Describe { .. }
+        );
+        let accept = if is_graph_query {
+            rdf_accept_header(&[RdfFormat::NTriples, RdfFormat::Turtle, RdfFormat::RdfXml])
+        } else {
+            "application/sparql-results+json, application/sparql-results+xml;q=0.9".into()
+        };
+        let (content_type, body) = self.client.post(
+            &self.query_endpoint,
+            query.to_string().into_bytes(),
+            "application/sparql-query",
+            &accept,
+        )?;
+        let mut body = BufReader::new(body);
+        if is_graph_query {
+            let format = rdf_format_from_response(
+                content_type,
+                &self.query_endpoint,
+                fill_response_buf(&mut body, &self.query_endpoint)?,
+            )?;
+            return Ok(QueryResults::Graph(QueryTripleIter::new(
+                RdfParser::from_format(format)
+                    .without_named_graphs()
+                    .for_reader(body)
+                    .map(|quad| Ok(Triple::from(quad?))),
+            )));
+        }
+        let format = query_results_format_from_response(
+            content_type,
+            fill_response_buf(&mut body, &self.query_endpoint)?,
+        )?;
+        Ok(
+            match QueryResultsParser::from_format(format).for_reader(body)? {
+                ReaderQueryResultsParserOutput::Solutions(solutions) => {
+                    QueryResults::Solutions(solutions.into())
+                }
+                ReaderQueryResultsParserOutput::Boolean(value) => QueryResults::Boolean(value),
+            },
+        )
+    }
+
+    /// Executes a [SPARQL update](https://www.w3.org/TR/sparql11-update/) on the remote endpoint.
+    pub fn update(
+        &self,
+        update: impl TryInto<Update, Error = impl Into<EvaluationError>>,
+    ) -> Result<(), EvaluationError> {
+        let update = update.try_into().map_err(Into::into)?;
+        self.client.post(
+            self.update_endpoint
+                .as_deref()
+                .unwrap_or(&self.query_endpoint),
+            update.to_string().into_bytes(),
+            "application/sparql-update",
+            "*/*",
+        )?;
+        Ok(())
+    }
+
+    /// Adds the triples of an RDF serialization to a graph of the remote store.
+    ///
+    /// The serialization is parsed locally and sent as N-Triples:
+    /// all the formats and options supported by [`RdfParser`] are available.
+    /// Parsing fails if the serialization contains named graphs.
+    pub fn load_graph_from_reader<'a>(
+        &self,
+        parser: impl Into<RdfParser>,
+        reader: impl Read,
+        to_graph_name: impl Into<GraphNameRef<'a>>,
+    ) -> Result<(), EvaluationError> {
+        let url = self.graph_url(to_graph_name.into())?;
+        let mut serializer = RdfSerializer::from_format(RdfFormat::NTriples).for_writer(Vec::new());
+        for quad in parser.into().without_named_graphs().for_reader(reader) {
+            serializer
+                .serialize_triple(&Triple::from(quad?))
+                .map_err(EvaluationError::ResultsSerialization)?;
+        }
+        let payload = serializer
+            .finish()
+            .map_err(EvaluationError::ResultsSerialization)?;
+        self.client.request(
+            &Method::POST,
+            &url,
+            Some((payload, RdfFormat::NTriples.media_type())),
+            "*/*",
+        )?;
+        Ok(())
+    }
+
+    /// Dumps a graph of the remote store into a file.
+    ///
+    /// The graph is parsed in a streaming way and serialized with the given serializer,
+    /// even if the remote endpoint returns another format.
+    pub fn dump_graph_to_writer<'a, W: Write>(
+        &self,
+        from_graph_name: impl Into<GraphNameRef<'a>>,
+        serializer: impl Into<RdfSerializer>,
+        writer: W,
+    ) -> Result<W, EvaluationError> {
+        let url = self.graph_url(from_graph_name.into())?;
+        let serializer = serializer.into();
+        let (content_type, body) = self.client.get(
+            &url,
+            &rdf_accept_header(&[serializer.format(), RdfFormat::NTriples]),
+        )?;
+        let mut body = BufReader::new(body);
+        let format =
+            rdf_format_from_response(content_type, &url, fill_response_buf(&mut body, &url)?)?;
+        let mut serializer = serializer.for_writer(writer);
+        for quad in RdfParser::from_format(format)
+            .without_named_graphs()
+            .for_reader(body)
+        {
+            serializer
+                .serialize_triple(&Triple::from(quad?))
+                .map_err(EvaluationError::ResultsSerialization)?;
+        }
+        serializer
+            .finish()
+            .map_err(EvaluationError::ResultsSerialization)
+    }
+
+    /// Removes a graph from the remote store.
+    ///
+    /// The default graph is cleared instead.
+    pub fn remove_graph<'a>(
+        &self,
+        graph_name: impl Into<GraphNameRef<'a>>,
+    ) -> Result<(), EvaluationError> {
+        let url = self.graph_url(graph_name.into())?;
+        self.client.request(&Method::DELETE, &url, None, "*/*")?;
+        Ok(())
+    }
+
+    fn graph_url(&self, graph_name: GraphNameRef<'_>) -> Result<String, EvaluationError> {
+        let mut url = self.graph_store_endpoint.clone().ok_or_else(|| {
+            HttpError::InvalidRequest("No graph store endpoint has been set".into())
+        })?;
+        url.push(if url.contains('?') { '&' } else { '?' });
+        match graph_name {
+            GraphNameRef::NamedNode(graph_name) => {
+                url.push_str("graph=");
+                percent_encode(graph_name.as_str(), &mut url);
+            }
+            GraphNameRef::DefaultGraph => url.push_str("default"),
+            GraphNameRef::BlankNode(_) => {
+                return Err(HttpError::InvalidRequest(
+                    "Blank node graphs can't be targeted with the graph store protocol".into(),
+                )
+                .into());
+            }
+        }
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::model::NamedNodeRef;
+
+    #[test]
+    fn test_graph_url() -> Result<(), EvaluationError> {
+        let store = RemoteStore::new("http://example.com/query")
+            .with_graph_store_endpoint("http://example.com/store");
+        assert_eq!(
+            store.graph_url(GraphNameRef::DefaultGraph)?,
+            "http://example.com/store?default"
+        );
+        assert_eq!(
+            store.graph_url(NamedNodeRef::new_unchecked("http://example.com/g?a=b c").into())?,
+            "http://example.com/store?graph=http%3A%2F%2Fexample.com%2Fg%3Fa%3Db%20c"
+        );
+        assert!(matches!(
+            RemoteStore::new("http://example.com/query").graph_url(GraphNameRef::DefaultGraph),
+            Err(EvaluationError::Http(HttpError::InvalidRequest(_)))
+        ));
+        Ok(())
+    }
+}
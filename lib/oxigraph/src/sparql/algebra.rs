@@ -3,6 +3,7 @@
 //! The root type for SPARQL queries is [`Query`] and the root type for updates is [`Update`].
 
 use crate::model::*;
+use crate::sparql::complexity::QueryComplexity;
 use spargebra::GraphUpdateOperation;
 use std::fmt;
 use std::str::FromStr;
@@ -57,6 +58,13 @@ impl Query {
     pub fn dataset_mut(&mut self) -> &mut QueryDataset {
         &mut self.dataset
     }
+
+    /// Computes complexity metrics of the query without evaluating it.
+    ///
+    /// See [`QueryComplexity`] for an example.
+    pub fn complexity(&self) -> QueryComplexity {
+        QueryComplexity::new(&self.inner)
+    }
 }
 
 impl fmt::Display for Query {
@@ -103,6 +111,16 @@ impl From<spargebra::Query> for Query {
     }
 }
 
+impl From<Query> for spargebra::Query {
+    /// Returns the query algebra.
+    ///
+    /// The changes made to the dataset with [`Query::dataset_mut`] are not kept.
+    #[inline]
+    fn from(query: Query) -> Self {
+        query.inner
+    }
+}
+
 /// A parsed [SPARQL update](https://www.w3.org/TR/sparql11-update/).
 ///
 /// ```
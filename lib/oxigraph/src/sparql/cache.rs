@@ -0,0 +1,194 @@
+use crate::model::Term;
+use crate::sparql::QueryOptions;
+use crate::sparql::algebra::{Query, QueryDataset};
+use crate::sparql::error::EvaluationError;
+use crate::sparql::model::{QueryResults, QuerySolutionIter};
+use oxrdf::Variable;
+use spareval::QuerySolutionIter as EvalQuerySolutionIter;
+use std::collections::HashMap;
+use std::iter::once;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The maximal number of solutions of a cached `SELECT` result, the bigger results are streamed without being cached
+const MAX_CACHED_SOLUTIONS: usize = 10_000;
+
+/// A LRU cache of the `SELECT` and `ASK` query results, only valid for a given store generation
+pub(crate) struct QueryResultsCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// The store generation of the cached results
+    generation: u64,
+    /// The last use tick of the entries
+    clock: u64,
+    entries: HashMap<CacheKey, (CachedResults, u64)>,
+}
+
+#[derive(Eq, PartialEq, Hash, Clone)]
+struct CacheKey {
+    query: String,
+    dataset: QueryDataset,
+}
+
+#[derive(Clone)]
+enum CachedResults {
+    Boolean(bool),
+    Solutions {
+        variables: Arc<[Variable]>,
+        solutions: Arc<[Vec<Option<Term>>]>,
+    },
+}
+
+impl QueryResultsCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the cached results of the query if the store has not been written since they have been computed,
+    /// else evaluates the query with `evaluate` and caches its results
+    ///
+    /// `generation` must be read before the store snapshot used by `evaluate` is taken.
+    pub fn get_or_evaluate(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+        options: QueryOptions,
+        generation: u64,
+        evaluate: impl FnOnce(Query, QueryOptions) -> Result<QueryResults, EvaluationError>,
+    ) -> Result<QueryResults, EvaluationError> {
+        let query = query.try_into().map_err(Into::into)?;
+        let Some(key) = CacheKey::new(&query, &options) else {
+            return evaluate(query, options);
+        };
+        if let Some(results) = self.get(&key, generation) {
+            return Ok(results.into());
+        }
+        match evaluate(query, options)? {
+            QueryResults::Boolean(value) => {
+                self.insert(key, generation, CachedResults::Boolean(value));
+                Ok(QueryResults::Boolean(value))
+            }
+            QueryResults::Solutions(solutions) => {
+                let variables = Arc::<[Variable]>::from(solutions.variables());
+                let mut iter = EvalQuerySolutionIter::from(solutions);
+                let mut solutions = Vec::new();
+                while let Some(solution) = iter.next() {
+                    match solution {
+                        Ok(solution) if solutions.len() < MAX_CACHED_SOLUTIONS => {
+                            solutions.push(solution)
+                        }
+                        next => {
+                            // Too big or failed: we return the results without caching them
+                            return Ok(QuerySolutionIter::from(EvalQuerySolutionIter::new(
+                                variables,
+                                solutions.into_iter().map(Ok).chain(once(next)).chain(iter),
+                            ))
+                            .into());
+                        }
+                    }
+                }
+                let results = CachedResults::Solutions {
+                    variables,
+                    solutions: solutions
+                        .iter()
+                        .map(|solution| solution.values().to_vec())
+                        .collect(),
+                };
+                self.insert(key, generation, results.clone());
+                Ok(results.into())
+            }
+            results @ QueryResults::Graph(_) => Ok(results),
+        }
+    }
+
+    fn get(&self, key: &CacheKey, generation: u64) -> Option<CachedResults> {
+        // The cache is always left in a consistent state, we ignore poisoning
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.generation != generation {
+            return None;
+        }
+        state.clock += 1;
+        let clock = state.clock;
+        let (results, last_use) = state.entries.get_mut(key)?;
+        *last_use = clock;
+        Some(results.clone())
+    }
+
+    fn insert(&self, key: CacheKey, generation: u64, results: CachedResults) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if generation < state.generation {
+            // The store has been written during the evaluation
+            return;
+        }
+        if generation > state.generation {
+            state.entries.clear();
+            state.generation = generation;
+        }
+        if state.entries.len() >= self.capacity && !state.entries.contains_key(&key) {
+            let least_recently_used = state
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_use))| *last_use)
+                .map(|(key, _)| key.clone());
+            if let Some(least_recently_used) = least_recently_used {
+                state.entries.remove(&least_recently_used);
+            }
+        }
+        if self.capacity > 0 {
+            state.clock += 1;
+            let clock = state.clock;
+            state.entries.insert(key, (results, clock));
+        }
+    }
+}
+
+impl CacheKey {
+    /// Builds the key of the query, `None` if its results should not be cached
+    fn new(query: &Query, options: &QueryOptions) -> Option<Self> {
+        if !matches!(
+            query.inner,
+            spargebra::Query::Select { .. } | spargebra::Query::Ask { .. }
+        ) || options.values.is_some()
+            || !options.substitutions.is_empty()
+            || options.progress.is_some()
+        {
+            return None;
+        }
+        let mut dataset = query.dataset.clone();
+        if let Some(default_graphs) = &options.default_graphs {
+            dataset.set_default_graph(default_graphs.clone());
+        }
+        if let Some(named_graphs) = &options.named_graphs {
+            dataset.set_available_named_graphs(named_graphs.clone());
+        }
+        Some(Self {
+            query: query.inner.to_string(),
+            dataset,
+        })
+    }
+}
+
+impl From<CachedResults> for QueryResults {
+    fn from(results: CachedResults) -> Self {
+        match results {
+            CachedResults::Boolean(value) => Self::Boolean(value),
+            CachedResults::Solutions {
+                variables,
+                solutions,
+            } => QuerySolutionIter::new(
+                variables,
+                (0..solutions.len()).filter_map(move |i| solutions.get(i).cloned().map(Ok)),
+            )
+            .into(),
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Static complexity analysis of SPARQL queries.
+
+use spargebra::algebra::{
+    AggregateExpression, Expression, GraphPattern, OrderExpression, PropertyPathExpression,
+};
+use std::cmp::max;
+
+/// Complexity metrics of a SPARQL query computed from its algebra without evaluating it.
+///
+/// It is returned by [`Query::complexity`](super::Query::complexity) and allows to reject expensive queries before their evaluation.
+///
+/// ```
+/// use oxigraph::sparql::{Query, QueryCostClass};
+///
+/// let query = Query::parse(
+///     "SELECT * WHERE { ?s <http://example.com/p> ?o OPTIONAL { ?o <http://example.com/knows>+ ?o2 } }",
+///     None,
+/// )?;
+/// let complexity = query.complexity();
+/// assert_eq!(complexity.triple_pattern_count(), 1);
+/// assert_eq!(complexity.optional_count(), 1);
+/// assert_eq!(complexity.path_operator_count(), 1);
+/// assert_eq!(complexity.cost_class(), QueryCostClass::Polynomial(3));
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct QueryComplexity {
+    triple_patterns: usize,
+    joins: usize,
+    optionals: usize,
+    path_operators: usize,
+    degree: usize,
+}
+
+impl QueryComplexity {
+    pub(super) fn new(query: &spargebra::Query) -> Self {
+        let mut complexity = Self::default();
+        let (spargebra::Query::Select { pattern, .. }
+        | spargebra::Query::Construct { pattern, .. }
+        | spargebra::Query::Describe { pattern, .. }
+        | spargebra::Query::Ask { pattern, .. }) = query;
+        complexity.degree = complexity.add_pattern(pattern);
+        complexity
+    }
+
+    /// The number of triple patterns, including the ones in sub-queries and `EXISTS` filters.
+    ///
+    /// The `CONSTRUCT` template is not counted.
+    #[inline]
+    pub fn triple_pattern_count(&self) -> usize {
+        self.triple_patterns
+    }
+
+    /// The number of joins between triple patterns, property paths and group graph patterns.
+    ///
+    /// `OPTIONAL` and `MINUS` are not counted.
+    #[inline]
+    pub fn join_count(&self) -> usize {
+        self.joins
+    }
+
+    /// The number of `OPTIONAL` operations.
+    #[inline]
+    pub fn optional_count(&self) -> usize {
+        self.optionals
+    }
+
+    /// The number of property path operators (`^`, `/`, `|`, `*`, `+`, `?` and `!`).
+    #[inline]
+    pub fn path_operator_count(&self) -> usize {
+        self.path_operators
+    }
+
+    /// An upper bound of the evaluation cost depending on the dataset size.
+    #[inline]
+    pub fn cost_class(&self) -> QueryCostClass {
+        match self.degree {
+            0 => QueryCostClass::Constant,
+            1 => QueryCostClass::Linear,
+            degree => QueryCostClass::Polynomial(degree),
+        }
+    }
+
+    /// Returns the degree of the polynomial bounding the number of the pattern solutions in the dataset size
+    fn add_pattern(&mut self, pattern: &GraphPattern) -> usize {
+        match pattern {
+            GraphPattern::Bgp { patterns } => {
+                self.triple_patterns += patterns.len();
+                self.joins += patterns.len().saturating_sub(1);
+                patterns.len()
+            }
+            GraphPattern::Path { path, .. } => self.add_path(path),
+            GraphPattern::Join { left, right } | GraphPattern::Lateral { left, right } => {
+                self.joins += 1;
+                self.add_pattern(left) + self.add_pattern(right)
+            }
+            GraphPattern::LeftJoin {
+                left,
+                right,
+                expression,
+            } => {
+                self.optionals += 1;
+                let mut degree = self.add_pattern(left) + self.add_pattern(right);
+                if let Some(expression) = expression {
+                    degree += self.add_expression(expression);
+                }
+                degree
+            }
+            GraphPattern::Minus { left, right } => self.add_pattern(left) + self.add_pattern(right),
+            GraphPattern::Union { left, right } => {
+                max(self.add_pattern(left), self.add_pattern(right))
+            }
+            GraphPattern::Filter { expr, inner } => {
+                self.add_pattern(inner) + self.add_expression(expr)
+            }
+            GraphPattern::Extend {
+                inner, expression, ..
+            } => self.add_pattern(inner) + self.add_expression(expression),
+            GraphPattern::Values { .. } => 0,
+            GraphPattern::OrderBy { inner, expression } => {
+                let mut degree = self.add_pattern(inner);
+                for expression in expression {
+                    let (OrderExpression::Asc(expression) | OrderExpression::Desc(expression)) =
+                        expression;
+                    degree += self.add_expression(expression);
+                }
+                degree
+            }
+            GraphPattern::Group {
+                inner, aggregates, ..
+            } => {
+                let mut degree = self.add_pattern(inner);
+                for (_, aggregate) in aggregates {
+                    if let AggregateExpression::FunctionCall { expr, .. } = aggregate {
+                        degree += self.add_expression(expr);
+                    }
+                }
+                degree
+            }
+            GraphPattern::Graph { inner, .. }
+            | GraphPattern::Project { inner, .. }
+            | GraphPattern::Distinct { inner }
+            | GraphPattern::Reduced { inner }
+            | GraphPattern::Slice { inner, .. }
+            | GraphPattern::Service { inner, .. } => self.add_pattern(inner),
+        }
+    }
+
+    fn add_path(&mut self, path: &PropertyPathExpression) -> usize {
+        match path {
+            PropertyPathExpression::NamedNode(_) => 1,
+            PropertyPathExpression::Reverse(inner) | PropertyPathExpression::ZeroOrOne(inner) => {
+                self.path_operators += 1;
+                self.add_path(inner)
+            }
+            PropertyPathExpression::Sequence(left, right) => {
+                self.path_operators += 1;
+                self.add_path(left) + self.add_path(right)
+            }
+            PropertyPathExpression::Alternative(left, right) => {
+                self.path_operators += 1;
+                max(self.add_path(left), self.add_path(right))
+            }
+            PropertyPathExpression::ZeroOrMore(inner)
+            | PropertyPathExpression::OneOrMore(inner) => {
+                // The transitive closure is computed from each node of the dataset
+                self.path_operators += 1;
+                self.add_path(inner) + 1
+            }
+            PropertyPathExpression::NegatedPropertySet(_) => {
+                self.path_operators += 1;
+                1
+            }
+        }
+    }
+
+    /// Returns the degree of the `EXISTS` patterns evaluated for each solution
+    fn add_expression(&mut self, expression: &Expression) -> usize {
+        match expression {
+            Expression::NamedNode(_)
+            | Expression::Literal(_)
+            | Expression::Variable(_)
+            | Expression::Bound(_) => 0,
+            Expression::Or(a, b)
+            | Expression::And(a, b)
+            | Expression::Equal(a, b)
+            | Expression::SameTerm(a, b)
+            | Expression::Greater(a, b)
+            | Expression::GreaterOrEqual(a, b)
+            | Expression::Less(a, b)
+            | Expression::LessOrEqual(a, b)
+            | Expression::Add(a, b)
+            | Expression::Subtract(a, b)
+            | Expression::Multiply(a, b)
+            | Expression::Divide(a, b) => max(self.add_expression(a), self.add_expression(b)),
+            Expression::UnaryPlus(e) | Expression::UnaryMinus(e) | Expression::Not(e) => {
+                self.add_expression(e)
+            }
+            Expression::Exists(pattern) => self.add_pattern(pattern),
+            Expression::If(a, b, c) => max(
+                self.add_expression(a),
+                max(self.add_expression(b), self.add_expression(c)),
+            ),
+            Expression::In(a, list) => list.iter().fold(self.add_expression(a), |degree, e| {
+                max(degree, self.add_expression(e))
+            }),
+            Expression::Coalesce(list) | Expression::FunctionCall(_, list) => list
+                .iter()
+                .fold(0, |degree, e| max(degree, self.add_expression(e))),
+        }
+    }
+}
+
+/// An upper bound of the evaluation cost of a query depending on the size of the queried dataset.
+///
+/// The classes are ordered from the cheapest to the most expensive one,
+/// so it is possible to write `complexity.cost_class() <= QueryCostClass::Polynomial(2)`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum QueryCostClass {
+    /// The query does not read the dataset.
+    Constant,
+    /// The query cost is at most linear in the dataset size, e.g. a single triple pattern.
+    Linear,
+    /// The query cost is at most polynomial in the dataset size with the given degree, e.g. joins of triple patterns.
+    Polynomial(usize),
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::sparql::Query;
+
+    #[test]
+    fn test_complexity() -> Result<(), Box<dyn std::error::Error>> {
+        let complexity = Query::parse("SELECT * WHERE { VALUES ?s { 1 } }", None)?.complexity();
+        assert_eq!(complexity.triple_pattern_count(), 0);
+        assert_eq!(complexity.cost_class(), QueryCostClass::Constant);
+
+        let complexity = Query::parse("ASK { ?s ?p ?o }", None)?.complexity();
+        assert_eq!(complexity.triple_pattern_count(), 1);
+        assert_eq!(complexity.join_count(), 0);
+        assert_eq!(complexity.cost_class(), QueryCostClass::Linear);
+
+        let complexity = Query::parse(
+            "SELECT * WHERE { ?s ?p ?o . ?o ?p2 ?o2 { ?a ?b ?c } UNION { ?a ?b ?c . ?c ?d ?e } }",
+            None,
+        )?
+        .complexity();
+        assert_eq!(complexity.triple_pattern_count(), 5);
+        assert_eq!(complexity.join_count(), 3);
+        assert_eq!(complexity.cost_class(), QueryCostClass::Polynomial(4));
+
+        let complexity = Query::parse(
+            "SELECT * WHERE { ?s (^<http://example.com/p>|!<http://example.com/r>)* ?o FILTER EXISTS { ?o ?p ?o2 OPTIONAL { ?o2 ?p ?o3 } } }",
+            None,
+        )?
+        .complexity();
+        assert_eq!(complexity.triple_pattern_count(), 2);
+        assert_eq!(complexity.optional_count(), 1);
+        assert_eq!(complexity.path_operator_count(), 4);
+        assert_eq!(complexity.cost_class(), QueryCostClass::Polynomial(4));
+        assert!(complexity.cost_class() > QueryCostClass::Polynomial(2));
+        Ok(())
+    }
+}
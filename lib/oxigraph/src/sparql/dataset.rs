@@ -1,4 +1,6 @@
 use crate::sparql::QueryDataset;
+use crate::sparql::QueryProgress;
+use crate::sparql::deadline::{Deadline, DeadlineIter};
 #[cfg(feature = "rdf-12")]
 use crate::storage::numeric_encoder::EncodedTriple;
 use crate::storage::numeric_encoder::{
@@ -22,39 +24,50 @@ pub struct DatasetView {
     reader: StorageReader,
     extra: RefCell<HashMap<StrHash, String, BuildHasherDefault<StrHashHasher>>>,
     dataset: EncodedDatasetSpec,
+    deadline: Option<Deadline>,
+    progress: Option<QueryProgress>,
 }
 
 impl DatasetView {
-    pub fn new(reader: StorageReader, dataset: &QueryDataset) -> Self {
+    pub fn new(
+        reader: StorageReader,
+        dataset: &QueryDataset,
+        deadline: Option<Deadline>,
+        progress: Option<QueryProgress>,
+    ) -> Self {
+        let str_hash_len = reader.str_hash_len();
         let dataset = EncodedDatasetSpec {
-            default: dataset
-                .default_graph_graphs()
-                .map(|graphs| graphs.iter().map(|g| g.as_ref().into()).collect::<Vec<_>>()),
-            named: dataset
-                .available_named_graphs()
-                .map(|graphs| graphs.iter().map(|g| g.as_ref().into()).collect::<Vec<_>>()),
+            default: dataset.default_graph_graphs().map(|graphs| {
+                graphs
+                    .iter()
+                    .map(|g| EncodedTerm::from(g.as_ref()).with_str_hash_len(str_hash_len))
+                    .collect::<Vec<_>>()
+            }),
+            named: dataset.available_named_graphs().map(|graphs| {
+                graphs
+                    .iter()
+                    .map(|g| EncodedTerm::from(g.as_ref()).with_str_hash_len(str_hash_len))
+                    .collect::<Vec<_>>()
+            }),
         };
         Self {
             reader,
             extra: RefCell::new(HashMap::default()),
             dataset,
+            deadline,
+            progress,
         }
     }
 
-    pub fn insert_str(&self, key: &StrHash, value: &str) {
-        if let Entry::Vacant(e) = self.extra.borrow_mut().entry(*key) {
+    pub fn insert_str(&self, key: StrHash, value: &str) {
+        if let Entry::Vacant(e) = self.extra.borrow_mut().entry(key) {
             if !matches!(self.reader.contains_str(key), Ok(true)) {
                 e.insert(value.to_owned());
             }
         }
     }
-}
 
-impl QueryableDataset for DatasetView {
-    type InternalTerm = EncodedTerm;
-    type Error = StorageError;
-
-    fn internal_quads_for_pattern(
+    fn encoded_quads_for_pattern(
         &self,
         subject: Option<&EncodedTerm>,
         predicate: Option<&EncodedTerm>,
@@ -191,9 +204,41 @@ impl QueryableDataset for DatasetView {
             )
         }
     }
+}
+
+impl QueryableDataset for DatasetView {
+    type InternalTerm = EncodedTerm;
+    type Error = StorageError;
+
+    fn internal_quads_for_pattern(
+        &self,
+        subject: Option<&EncodedTerm>,
+        predicate: Option<&EncodedTerm>,
+        object: Option<&EncodedTerm>,
+        graph_name: Option<Option<&EncodedTerm>>,
+    ) -> Box<dyn Iterator<Item = Result<InternalQuad<Self>, StorageError>>> {
+        let mut iter = self.encoded_quads_for_pattern(subject, predicate, object, graph_name);
+        if let Some(progress) = &self.progress {
+            progress.add_scan();
+            let progress = progress.clone();
+            iter = Box::new(iter.inspect(move |_| progress.add_read_quad()));
+        }
+        if let Some(deadline) = &self.deadline {
+            Box::new(DeadlineIter::new(iter, deadline.clone()))
+        } else {
+            iter
+        }
+    }
 
     fn internal_named_graphs(&self) -> Box<dyn Iterator<Item = Result<EncodedTerm, StorageError>>> {
-        Box::new(self.reader.named_graphs())
+        if let Some(deadline) = &self.deadline {
+            Box::new(DeadlineIter::new(
+                self.reader.named_graphs(),
+                deadline.clone(),
+            ))
+        } else {
+            Box::new(self.reader.named_graphs())
+        }
     }
 
     fn contains_internal_graph_name(&self, graph_name: &EncodedTerm) -> Result<bool, StorageError> {
@@ -201,7 +246,9 @@ impl QueryableDataset for DatasetView {
     }
 
     fn internalize_term(&self, term: Term) -> Result<EncodedTerm, StorageError> {
-        let encoded = term.as_ref().into();
+        // Encoded like the stored terms to be comparable with them
+        let encoded =
+            EncodedTerm::from(term.as_ref()).with_str_hash_len(self.reader.str_hash_len());
         insert_term(term.as_ref(), &encoded, &mut |key, value| {
             self.insert_str(key, value);
             Ok(())
@@ -308,8 +355,8 @@ impl QueryableDataset for DatasetView {
 }
 
 impl StrLookup for DatasetView {
-    fn get_str(&self, key: &StrHash) -> Result<Option<String>, StorageError> {
-        Ok(if let Some(value) = self.extra.borrow().get(key) {
+    fn get_str(&self, key: StrHash) -> Result<Option<String>, StorageError> {
+        Ok(if let Some(value) = self.extra.borrow().get(&key) {
             Some(value.clone())
         } else {
             self.reader.get_str(key)?
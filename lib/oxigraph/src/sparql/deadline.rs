@@ -0,0 +1,195 @@
+use crate::store::StorageError;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+#[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+use std::time::Instant;
+
+/// A token allowing to cancel a running query evaluation from another thread.
+///
+/// It is given to the evaluation with [`QueryOptions::with_cancellation_token`](super::QueryOptions::with_cancellation_token).
+/// Once [`cancel`](Self::cancel) is called, the evaluation fails with [`EvaluationError::Cancelled`](super::EvaluationError::Cancelled)
+/// the next time it reads data from the store.
+/// Cloning it returns a token cancelling the same evaluations.
+///
+/// ```
+/// use oxigraph::sparql::{EvaluationError, QueryCancellationToken, QueryOptions, QueryResults};
+/// use oxigraph::store::Store;
+///
+/// let store = Store::new()?;
+/// store.update("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 , 2 , 3 }")?;
+/// let token = QueryCancellationToken::new();
+/// if let QueryResults::Solutions(mut solutions) = store.query_opt(
+///     "SELECT * WHERE { ?s ?p ?o }",
+///     QueryOptions::default().with_cancellation_token(token.clone()),
+/// )? {
+///     assert!(solutions.next().unwrap().is_ok());
+///     token.cancel();
+///     assert!(matches!(solutions.next(), Some(Err(EvaluationError::Cancelled))));
+/// }
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Clone, Default)]
+pub struct QueryCancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl QueryCancellationToken {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the cancellation of the evaluations using this token.
+    #[inline]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Checks if [`cancel`](Self::cancel) has been called.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// The instant after which a query evaluation must stop and the token allowing to cancel it.
+#[derive(Clone, Default)]
+pub struct Deadline {
+    #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+    instant: Option<Instant>,
+    #[cfg(all(feature = "js", target_family = "wasm", target_os = "unknown"))]
+    epoch_millis: Option<f64>,
+    cancellation: Option<QueryCancellationToken>,
+}
+
+impl Deadline {
+    /// Stops the evaluation after `timeout` if it is earlier than the current deadline.
+    ///
+    /// A timeout too large to be represented is ignored.
+    #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        if let Some(instant) = Instant::now().checked_add(timeout) {
+            self.with_instant(instant)
+        } else {
+            self
+        }
+    }
+
+    #[cfg(all(feature = "js", target_family = "wasm", target_os = "unknown"))]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        let epoch_millis = js_sys::Date::now() + timeout.as_secs_f64() * 1000.;
+        self.epoch_millis = Some(
+            self.epoch_millis
+                .map_or(epoch_millis, |current| current.min(epoch_millis)),
+        );
+        self
+    }
+
+    /// Stops the evaluation at `instant` if it is earlier than the current deadline.
+    #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+    pub fn with_instant(mut self, instant: Instant) -> Self {
+        self.instant = Some(self.instant.map_or(instant, |current| current.min(instant)));
+        self
+    }
+
+    pub fn with_cancellation_token(mut self, token: QueryCancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Returns `None` if there is neither an instant nor a cancellation token to check.
+    pub fn into_option(self) -> Option<Self> {
+        #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+        let has_instant = self.instant.is_some();
+        #[cfg(all(feature = "js", target_family = "wasm", target_os = "unknown"))]
+        let has_instant = self.epoch_millis.is_some();
+        (has_instant || self.cancellation.is_some()).then_some(self)
+    }
+
+    #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+    fn is_reached(&self) -> bool {
+        self.instant
+            .is_some_and(|instant| Instant::now() >= instant)
+    }
+
+    #[cfg(all(feature = "js", target_family = "wasm", target_os = "unknown"))]
+    fn is_reached(&self) -> bool {
+        self.epoch_millis
+            .is_some_and(|epoch_millis| js_sys::Date::now() >= epoch_millis)
+    }
+
+    /// Returns an error if the evaluation has been cancelled or if the deadline is reached.
+    pub fn check(&self) -> Result<(), StorageError> {
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(QueryCancellationToken::is_cancelled)
+        {
+            Err(StorageError::Other(Box::new(CancelledError)))
+        } else if self.is_reached() {
+            Err(StorageError::Other(Box::new(TimeoutError)))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Wraps an iterator to fail with a timeout error after the deadline or a cancellation error once cancelled.
+pub struct DeadlineIter<I> {
+    inner: I,
+    deadline: Deadline,
+}
+
+impl<I> DeadlineIter<I> {
+    pub fn new(inner: I, deadline: Deadline) -> Self {
+        Self { inner, deadline }
+    }
+}
+
+impl<T, I: Iterator<Item = Result<T, StorageError>>> Iterator for DeadlineIter<I> {
+    type Item = Result<T, StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.deadline.check() {
+            return Some(Err(e));
+        }
+        self.inner.next()
+    }
+}
+
+/// Marker error raised from the storage layer when the query deadline is reached.
+#[derive(Debug)]
+pub struct TimeoutError;
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("The query evaluation timeout has been reached")
+    }
+}
+
+impl Error for TimeoutError {}
+
+/// Checks if the storage error has been raised because the query deadline has been reached.
+pub fn is_timeout(error: &StorageError) -> bool {
+    matches!(error, StorageError::Other(error) if error.is::<TimeoutError>())
+}
+
+/// Marker error raised from the storage layer when the query evaluation is cancelled.
+#[derive(Debug)]
+pub struct CancelledError;
+
+impl fmt::Display for CancelledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("The query evaluation has been cancelled")
+    }
+}
+
+impl Error for CancelledError {}
+
+/// Checks if the storage error has been raised because the query evaluation has been cancelled.
+pub fn is_cancelled(error: &StorageError) -> bool {
+    matches!(error, StorageError::Other(error) if error.is::<CancelledError>())
+}
@@ -1,7 +1,9 @@
 use crate::io::RdfParseError;
 use crate::model::NamedNode;
 use crate::sparql::SparqlSyntaxError;
+use crate::sparql::deadline::{is_cancelled, is_timeout};
 use crate::sparql::results::QueryResultsParseError as ResultsParseError;
+use crate::storage::rdf_parse_error_code;
 use crate::store::{CorruptionError, StorageError};
 use oxrdf::{Term, Variable};
 use spareval::QueryEvaluationError;
@@ -31,6 +33,9 @@ pub enum EvaluationError {
     /// Error during `SERVICE` evaluation
     #[error("{0}")]
     Service(#[source] Box<dyn Error + Send + Sync + 'static>),
+    /// Error during an HTTP request (`LOAD` operations and `RemoteStore` calls).
+    #[error(transparent)]
+    Http(#[from] HttpError),
     /// Error when `CREATE` tries to create an already existing graph
     #[error("The graph {0} already exists")]
     GraphAlreadyExists(NamedNode),
@@ -58,11 +63,57 @@ pub enum EvaluationError {
     /// If a variable present in the given initial substitution is not present in the `SELECT` part of the query
     #[error("The SPARQL query does not contains variable {0} in its SELECT projection")]
     NotExistingSubstitutedVariable(Variable),
+    /// The query evaluation timeout set with [`QueryOptions::with_timeout`](crate::sparql::QueryOptions::with_timeout) has been reached
+    #[error("The query evaluation timeout has been reached")]
+    Timeout,
+    /// The query evaluation has been cancelled using a [`QueryCancellationToken`](crate::sparql::QueryCancellationToken)
+    #[error("The query evaluation has been cancelled")]
+    Cancelled,
+    /// The query is not supported by [`IncrementalQuery`](crate::sparql::IncrementalQuery)
+    #[error("The query is not supported by the incremental evaluation: {0}")]
+    UnsupportedIncrementalQuery(String),
+    /// A term given with [`QueryOptions::with_values`](crate::sparql::QueryOptions::with_values) is not allowed in `VALUES` (e.g. a blank node)
+    #[error("{0} is not allowed in VALUES")]
+    InvalidValuesTerm(Term),
     #[doc(hidden)]
     #[error(transparent)]
     Unexpected(Box<dyn Error + Send + Sync>),
 }
 
+impl EvaluationError {
+    /// A stable identifier of the kind of error, for example `"timeout"` or `"graph-does-not-exist"`.
+    ///
+    /// It is not changed between releases and allows applications to branch on the failure kind
+    /// without matching the error message.
+    /// The storage and HTTP errors return the code of [`StorageError::code`] and [`HttpError::code`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Parsing(_) => "sparql-syntax",
+            Self::Storage(error) => error.code(),
+            Self::GraphParsing(error) => rdf_parse_error_code(error),
+            Self::ResultsParsing(ResultsParseError::Io(_)) => "io",
+            Self::ResultsParsing(ResultsParseError::Syntax(_)) => "results-syntax",
+            Self::ResultsSerialization(_) => "results-serialization",
+            Self::Service(_) => "service",
+            Self::Http(error) => error.code(),
+            Self::GraphAlreadyExists(_) => "graph-already-exists",
+            Self::GraphDoesNotExist(_) => "graph-does-not-exist",
+            Self::UnboundService => "unbound-service",
+            Self::InvalidServiceName(_) => "invalid-service-name",
+            Self::UnsupportedService(_) => "unsupported-service",
+            Self::UnsupportedContentType(_) => "unsupported-content-type",
+            Self::ServiceDoesNotReturnSolutions => "service-does-not-return-solutions",
+            Self::NotAGraph => "not-a-graph",
+            Self::NotExistingSubstitutedVariable(_) => "not-existing-substituted-variable",
+            Self::Timeout => "timeout",
+            Self::Cancelled => "cancelled",
+            Self::UnsupportedIncrementalQuery(_) => "unsupported-incremental-query",
+            Self::InvalidValuesTerm(_) => "invalid-values-term",
+            Self::Unexpected(_) => "unexpected",
+        }
+    }
+}
+
 impl From<Infallible> for EvaluationError {
     #[inline]
     fn from(error: Infallible) -> Self {
@@ -74,6 +125,8 @@ impl From<QueryEvaluationError> for EvaluationError {
     fn from(error: QueryEvaluationError) -> Self {
         match error {
             QueryEvaluationError::Dataset(error) => match error.downcast() {
+                Ok(error) if is_timeout(&error) => Self::Timeout,
+                Ok(error) if is_cancelled(&error) => Self::Cancelled,
                 Ok(error) => Self::Storage(*error),
                 Err(error) => Self::Unexpected(error),
             },
@@ -108,12 +161,15 @@ impl From<EvaluationError> for io::Error {
             EvaluationError::ResultsParsing(error) => error.into(),
             EvaluationError::ResultsSerialization(error) => error,
             EvaluationError::Storage(error) => error.into(),
+            EvaluationError::Http(error) => error.into(),
             EvaluationError::Service(error) | EvaluationError::Unexpected(error) => {
                 match error.downcast() {
                     Ok(error) => *error,
                     Err(error) => Self::other(error),
                 }
             }
+            EvaluationError::Timeout => Self::new(io::ErrorKind::TimedOut, error),
+            EvaluationError::Cancelled => Self::new(io::ErrorKind::Interrupted, error),
             EvaluationError::GraphAlreadyExists(_)
             | EvaluationError::GraphDoesNotExist(_)
             | EvaluationError::UnboundService
@@ -122,9 +178,104 @@ impl From<EvaluationError> for io::Error {
             | EvaluationError::UnsupportedContentType(_)
             | EvaluationError::ServiceDoesNotReturnSolutions
             | EvaluationError::NotAGraph
-            | EvaluationError::NotExistingSubstitutedVariable(_) => {
+            | EvaluationError::NotExistingSubstitutedVariable(_)
+            | EvaluationError::UnsupportedIncrementalQuery(_)
+            | EvaluationError::InvalidValuesTerm(_) => {
                 Self::new(io::ErrorKind::InvalidInput, error)
             }
         }
     }
 }
+
+/// An error raised by an HTTP request done by a `LOAD` operation, a `SERVICE` call or a `RemoteStore`.
+///
+/// The `SERVICE` call errors are wrapped into [`EvaluationError::Service`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum HttpError {
+    /// The request can't be built, for example because its URL is invalid.
+    #[error("Invalid HTTP request: {0}")]
+    InvalidRequest(#[source] Box<dyn Error + Send + Sync + 'static>),
+    /// The request could not be sent or its response could not be received, for example because of a network failure or a timeout.
+    #[error("The HTTP request to {url} failed: {source}")]
+    Request {
+        /// The requested URL.
+        url: String,
+        /// The I/O error.
+        source: io::Error,
+    },
+    /// The server returned a status that is not a success.
+    #[error("Error {status} returned by {url} with payload:\n{body}")]
+    Status {
+        /// The requested URL.
+        url: String,
+        /// The HTTP status code, like `404`.
+        status: u16,
+        /// The response body.
+        body: String,
+    },
+    /// The response headers are invalid.
+    #[error("Invalid HTTP response from {url}: {source}")]
+    InvalidResponse {
+        /// The requested URL.
+        url: String,
+        /// The error.
+        source: Box<dyn Error + Send + Sync + 'static>,
+    },
+    /// The response body is larger than the allowed size.
+    ///
+    /// See [`UpdateOptions::with_max_load_size`](super::UpdateOptions::with_max_load_size).
+    #[error("The response of {url} is larger than the maximal size of {max_size} bytes")]
+    PayloadTooLarge {
+        /// The requested URL.
+        url: String,
+        /// The maximal size in bytes.
+        max_size: u64,
+    },
+}
+
+impl HttpError {
+    /// The requested URL, if the request has been built.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Self::InvalidRequest(_) => None,
+            Self::Request { url, .. }
+            | Self::Status { url, .. }
+            | Self::InvalidResponse { url, .. }
+            | Self::PayloadTooLarge { url, .. } => Some(url),
+        }
+    }
+
+    /// The HTTP status code returned by the server if it is not a success.
+    pub fn status(&self) -> Option<u16> {
+        if let Self::Status { status, .. } = self {
+            Some(*status)
+        } else {
+            None
+        }
+    }
+
+    /// A stable identifier of the kind of error, see [`EvaluationError::code`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidRequest(_) => "http-invalid-request",
+            Self::Request { .. } => "http-request",
+            Self::Status { .. } => "http-status",
+            Self::InvalidResponse { .. } => "http-invalid-response",
+            Self::PayloadTooLarge { .. } => "http-payload-too-large",
+        }
+    }
+}
+
+impl From<HttpError> for io::Error {
+    #[inline]
+    fn from(error: HttpError) -> Self {
+        match error {
+            HttpError::InvalidRequest(_) => Self::new(io::ErrorKind::InvalidInput, error),
+            HttpError::Request { .. } | HttpError::Status { .. } => Self::other(error),
+            HttpError::InvalidResponse { .. } | HttpError::PayloadTooLarge { .. } => {
+                Self::new(io::ErrorKind::InvalidData, error)
+            }
+        }
+    }
+}
@@ -1,21 +1,41 @@
-use crate::sparql::EvaluationError;
-use oxhttp::model::header::{ACCEPT, CONTENT_TYPE};
-use oxhttp::model::{Body, Method, Request};
+use crate::io::RdfFormat;
+use crate::sparql::{EvaluationError, HttpError};
+use oxhttp::model::header::{ACCEPT, CONTENT_TYPE, RETRY_AFTER};
+#[cfg(not(feature = "http2-client"))]
+use oxhttp::model::Request;
+use oxhttp::model::{Body, Method, Response, StatusCode};
 use oxiri::Iri;
 use oxrdf::NamedNode;
 use sparesults::{QueryResultsFormat, QueryResultsParser, ReaderQueryResultsParserOutput};
 use spareval::{DefaultServiceHandler, QueryEvaluationError, QuerySolutionIter};
 use spargebra::algebra::GraphPattern;
-use std::io::{Error, ErrorKind, Read, Result};
+use std::cmp::Reverse;
+use std::io::{self, BufRead, BufReader, Read};
+#[cfg(feature = "http2-client")]
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
+#[cfg(feature = "http2-client")]
+use std::sync::Mutex;
+use std::thread::sleep;
 use std::time::Duration;
 
+/// HTTP client
+///
+/// By default, it is an HTTP 1.1 client built on `oxhttp` that does not support proxies.
+/// With the `http2-client` feature, it negotiates HTTP/2 with the HTTPS servers supporting it:
+/// the requests done at the same time to a server, like the parallel SERVICE calls, are then multiplexed over a single connection.
 #[derive(Clone)]
 pub struct Client {
+    #[cfg(not(feature = "http2-client"))]
     client: Arc<oxhttp::Client>,
+    /// The client only holds its connection pool, a panic can't leave it in an inconsistent state
+    #[cfg(feature = "http2-client")]
+    client: Arc<AssertUnwindSafe<reqwest::blocking::Client>>,
+    retry_policy: HttpRetryPolicy,
 }
 
 impl Client {
+    #[cfg(not(feature = "http2-client"))]
     pub fn new(timeout: Option<Duration>, redirection_limit: usize) -> Self {
         let mut client = oxhttp::Client::new()
             .with_redirection_limit(redirection_limit)
@@ -26,80 +46,499 @@ impl Client {
         }
         Self {
             client: Arc::new(client),
+            retry_policy: HttpRetryPolicy::new(0),
         }
     }
 
-    pub fn get(&self, url: &str, accept: &'static str) -> Result<(String, impl Read)> {
-        let request = Request::builder()
-            .uri(url)
-            .header(ACCEPT, accept)
-            .body(())
-            .map_err(invalid_input_error)?;
-        let response = self.client.request(request)?;
-        let status = response.status();
-        if !status.is_success() {
-            return Err(Error::other(format!(
-                "Error {} returned by {} with payload:\n{}",
-                status,
-                url,
-                response.into_body().to_string()?
-            )));
+    /// The clients are shared by all the evaluations with the same configuration to reuse their connections
+    #[cfg(feature = "http2-client")]
+    pub fn new(timeout: Option<Duration>, redirection_limit: usize) -> Self {
+        type SharedClient = Arc<AssertUnwindSafe<reqwest::blocking::Client>>;
+        static CLIENTS: Mutex<Vec<((Option<Duration>, usize), SharedClient)>> =
+            Mutex::new(Vec::new());
+        let mut clients = CLIENTS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let client = if let Some((_, client)) = clients
+            .iter()
+            .find(|(configuration, _)| *configuration == (timeout, redirection_limit))
+        {
+            Arc::clone(client)
+        } else {
+            let client = reqwest::blocking::Client::builder()
+                .user_agent(concat!("Oxigraph/", env!("CARGO_PKG_VERSION")))
+                .redirect(if redirection_limit == 0 {
+                    reqwest::redirect::Policy::none()
+                } else {
+                    reqwest::redirect::Policy::limited(redirection_limit)
+                })
+                .timeout(timeout)
+                .build()
+                .unwrap();
+            let client = Arc::new(AssertUnwindSafe(client));
+            clients.push(((timeout, redirection_limit), Arc::clone(&client)));
+            client
+        };
+        Self {
+            client,
+            retry_policy: HttpRetryPolicy::new(0),
         }
-        let content_type = response
-            .headers()
-            .get(CONTENT_TYPE)
-            .ok_or_else(|| invalid_data_error(format!("No Content-Type returned by {url}")))?
-            .to_str()
-            .map_err(invalid_data_error)?
-            .to_owned();
-        Ok((content_type, response.into_body()))
+    }
+
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: HttpRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn get(&self, url: &str, accept: &str) -> Result<(Option<String>, Body), HttpError> {
+        self.request(&Method::GET, url, None, accept)
     }
 
     pub fn post(
         &self,
         url: &str,
         payload: Vec<u8>,
-        content_type: &'static str,
-        accept: &'static str,
-    ) -> Result<(String, Body)> {
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri(url)
-            .header(ACCEPT, accept)
-            .header(CONTENT_TYPE, content_type)
-            .body(payload)
-            .map_err(invalid_input_error)?;
-        let response = self.client.request(request)?;
+        content_type: &str,
+        accept: &str,
+    ) -> Result<(Option<String>, Body), HttpError> {
+        self.request(&Method::POST, url, Some((payload, content_type)), accept)
+    }
+
+    /// Executes an HTTP request with an optional payload and its content type
+    ///
+    /// Returns the response content type and body, or an error if the response status is not a success.
+    pub fn request(
+        &self,
+        method: &Method,
+        url: &str,
+        payload: Option<(Vec<u8>, &str)>,
+        accept: &str,
+    ) -> Result<(Option<String>, Body), HttpError> {
+        self.request_with_headers(method, url, payload, accept, &[])
+    }
+
+    /// Same as [`request`](Self::request) but with some extra headers
+    ///
+    /// The request is retried following the [retry policy](HttpRetryPolicy).
+    pub fn request_with_headers(
+        &self,
+        method: &Method,
+        url: &str,
+        mut payload: Option<(Vec<u8>, &str)>,
+        accept: &str,
+        headers: &[(String, String)],
+    ) -> Result<(Option<String>, Body), HttpError> {
+        let mut retry = 0;
+        let response = loop {
+            let can_retry = retry < self.retry_policy.max_retries;
+            let response = self.send(
+                method,
+                url,
+                if can_retry {
+                    payload.clone()
+                } else {
+                    payload.take()
+                },
+                accept,
+                headers,
+            );
+            let retry_after = match &response {
+                Err(HttpError::Request { .. }) => None,
+                Ok(response)
+                    if matches!(
+                        response.status(),
+                        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+                    ) =>
+                {
+                    retry_after(response)
+                }
+                _ => break response?,
+            };
+            if !can_retry {
+                break response?;
+            }
+            sleep(self.retry_policy.backoff(retry, retry_after));
+            retry += 1;
+        };
         let status = response.status();
         if !status.is_success() {
-            return Err(Error::other(format!(
-                "Error {} returned by {} with payload:\n{}",
-                status,
-                url,
-                response.into_body().to_string()?
-            )));
+            return Err(HttpError::Status {
+                url: url.into(),
+                status: status.as_u16(),
+                body: response
+                    .into_body()
+                    .to_string()
+                    .map_err(|e| request_error(url, e))?,
+            });
         }
         let content_type = response
             .headers()
             .get(CONTENT_TYPE)
-            .ok_or_else(|| invalid_data_error(format!("No Content-Type returned by {url}")))?
-            .to_str()
-            .map_err(invalid_data_error)?
-            .to_owned();
+            .map(|content_type| content_type.to_str().map(ToOwned::to_owned))
+            .transpose()
+            .map_err(|e| HttpError::InvalidResponse {
+                url: url.into(),
+                source: Box::new(e),
+            })?;
         Ok((content_type, response.into_body()))
     }
+
+    #[cfg(not(feature = "http2-client"))]
+    fn send(
+        &self,
+        method: &Method,
+        url: &str,
+        payload: Option<(Vec<u8>, &str)>,
+        accept: &str,
+        headers: &[(String, String)],
+    ) -> Result<Response<Body>, HttpError> {
+        let mut request = Request::builder()
+            .method(method.clone())
+            .uri(url)
+            .header(ACCEPT, accept);
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some((payload, content_type)) = payload {
+            self.client.request(
+                request
+                    .header(CONTENT_TYPE, content_type)
+                    .body(payload)
+                    .map_err(|e| HttpError::InvalidRequest(Box::new(e)))?,
+            )
+        } else {
+            self.client.request(
+                request
+                    .body(())
+                    .map_err(|e| HttpError::InvalidRequest(Box::new(e)))?,
+            )
+        }
+        .map_err(|e| request_error(url, e))
+    }
+
+    #[cfg(feature = "http2-client")]
+    fn send(
+        &self,
+        method: &Method,
+        url: &str,
+        payload: Option<(Vec<u8>, &str)>,
+        accept: &str,
+        headers: &[(String, String)],
+    ) -> Result<Response<Body>, HttpError> {
+        let mut request = self
+            .client
+            .request(method.clone(), url)
+            .header(ACCEPT, accept);
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some((payload, content_type)) = payload {
+            request = request.header(CONTENT_TYPE, content_type).body(payload);
+        }
+        let response = request.send().map_err(|e| {
+            if e.is_builder() {
+                HttpError::InvalidRequest(Box::new(e))
+            } else {
+                request_error(url, io::Error::other(e))
+            }
+        })?;
+        let mut builder = Response::builder().status(response.status());
+        for (name, value) in response.headers() {
+            builder = builder.header(name, value);
+        }
+        builder
+            .body(Body::from_read(response))
+            .map_err(|e| HttpError::InvalidResponse {
+                url: url.into(),
+                source: Box::new(e),
+            })
+    }
+}
+
+/// The delay given in seconds by the `Retry-After` header of a response
+///
+/// The HTTP date format is not supported.
+fn retry_after(response: &Response<Body>) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    Some(Duration::from_secs(value.trim().parse().ok()?))
+}
+
+/// Retry policy of the HTTP requests done during SPARQL evaluation.
+///
+/// A request is retried if it fails because of a network error or a timeout,
+/// or if the server returns the `429 Too Many Requests` or `503 Service Unavailable` status.
+/// Before the n-th retry, the client waits for the duration given in seconds by the `Retry-After` response header
+/// or, if there is none, for `initial_backoff * 2^(n-1)`.
+/// The waiting duration is always bounded by the maximal backoff.
+///
+/// Note that update requests are retried too:
+/// an update might be applied twice if the network fails after the server received it.
+///
+/// ```
+/// use oxigraph::sparql::{HttpRetryPolicy, QueryOptions};
+/// use std::time::Duration;
+///
+/// let options = QueryOptions::default().with_http_retry_policy(
+///     HttpRetryPolicy::new(3)
+///         .with_initial_backoff(Duration::from_millis(200))
+///         .with_max_backoff(Duration::from_secs(10)),
+/// );
+/// # let _ = options;
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[must_use]
+pub struct HttpRetryPolicy {
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl HttpRetryPolicy {
+    /// Retries the requests at most `max_retries` times.
+    ///
+    /// By default, the initial backoff is 1s and the maximal backoff is 30s.
+    #[inline]
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    /// Sets the duration to wait before the first retry, the next ones doubling it.
+    #[inline]
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Sets the maximal duration to wait before a retry.
+    #[inline]
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// The duration to wait before the retry number `retry` (starting from 0)
+    fn backoff(&self, retry: usize, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| {
+                self.initial_backoff
+                    .saturating_mul(1_u32 << u32::try_from(retry).unwrap_or(31).min(31))
+            })
+            .min(self.max_backoff)
+    }
+}
+
+/// Fills the buffer of a response body, the `url` being the requested one
+pub fn fill_response_buf<'a>(
+    body: &'a mut BufReader<impl Read>,
+    url: &str,
+) -> Result<&'a [u8], HttpError> {
+    body.fill_buf().map_err(|e| request_error(url, e))
+}
+
+/// Builds the error of a failed request or of a failed response body read
+pub fn request_error(url: &str, error: io::Error) -> HttpError {
+    // The errors raised by SizeLimitedBody are kept as is
+    match error.downcast::<HttpError>() {
+        Ok(error) => error,
+        Err(error) => HttpError::Request {
+            url: url.into(),
+            source: error,
+        },
+    }
+}
+
+/// A response body failing with [`HttpError::PayloadTooLarge`] if it is larger than a maximal size
+pub struct SizeLimitedBody {
+    body: Body,
+    url: String,
+    max_size: u64,
+    remaining: u64,
+}
+
+impl SizeLimitedBody {
+    /// Fails early if the response `Content-Length` is already larger than `max_size`
+    pub fn new(body: Body, url: &str, max_size: Option<u64>) -> Result<Self, HttpError> {
+        let max_size = max_size.unwrap_or(u64::MAX);
+        if body.len().is_some_and(|len| len > max_size) {
+            return Err(HttpError::PayloadTooLarge {
+                url: url.into(),
+                max_size,
+            });
+        }
+        Ok(Self {
+            body,
+            url: url.into(),
+            max_size,
+            remaining: max_size,
+        })
+    }
+}
+
+impl Read for SizeLimitedBody {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.remaining == 0 {
+            // We check that the body is really finished
+            return if self.body.read(&mut [0])? == 0 {
+                Ok(0)
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    HttpError::PayloadTooLarge {
+                        url: self.url.clone(),
+                        max_size: self.max_size,
+                    },
+                ))
+            };
+        }
+        let len = usize::try_from(self.remaining).map_or(buf.len(), |r| r.min(buf.len()));
+        let read = self.body.read(&mut buf[..len])?;
+        self.remaining -= u64::try_from(read).map_err(io::Error::other)?;
+        Ok(read)
+    }
+}
+
+/// Configuration of the HTTP requests sent to some [SPARQL 1.1 Federated Query](https://www.w3.org/TR/sparql11-federated-query/) SERVICE endpoints.
+///
+/// It is registered for all the endpoints whose IRI starts with a given prefix
+/// using [`QueryOptions::with_http_service_endpoint`](super::QueryOptions::with_http_service_endpoint).
+/// By default, the queries are sent using POST requests with the `application/sparql-query` content type.
+///
+/// ```
+/// use oxigraph::sparql::{HttpServiceEndpoint, QueryOptions};
+/// use std::time::Duration;
+///
+/// let options = QueryOptions::default().with_http_service_endpoint(
+///     "https://example.com/sparql",
+///     HttpServiceEndpoint::new()
+///         .with_header("Authorization", "Bearer secret")
+///         .with_query_parameter("graph", "http://example.com/g")
+///         .with_timeout(Duration::from_secs(10))
+///         .with_get_method(),
+/// );
+/// # let _ = options;
+/// ```
+#[derive(Clone, Default)]
+#[must_use]
+pub struct HttpServiceEndpoint {
+    headers: Vec<(String, String)>,
+    query_parameters: Vec<(String, String)>,
+    timeout: Option<Duration>,
+    get_method: bool,
+}
+
+impl HttpServiceEndpoint {
+    /// Builds a configuration that does not change the default requests.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an HTTP header to the requests, for example an `Authorization` one.
+    #[inline]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Adds a parameter to the query component of the requested URL.
+    #[inline]
+    pub fn with_query_parameter(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.query_parameters.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets a timeout for the requests, replacing the one set with [`QueryOptions::with_http_timeout`](super::QueryOptions::with_http_timeout).
+    #[inline]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends the queries in the `query` URL parameter of GET requests instead of in the body of POST requests.
+    ///
+    /// It is useful with endpoints that only support GET requests or that cache their responses.
+    #[inline]
+    pub fn with_get_method(mut self) -> Self {
+        self.get_method = true;
+        self
+    }
+
+    /// The URL to request, `query` being given with the GET method
+    fn url(&self, service_name: &str, query: Option<&str>) -> String {
+        let mut url = service_name.to_owned();
+        let parameters = query.map(|query| ("query", query)).into_iter().chain(
+            self.query_parameters
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        for (name, value) in parameters {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            percent_encode(name, &mut url);
+            url.push('=');
+            percent_encode(value, &mut url);
+        }
+        url
+    }
 }
 
 pub struct HttpServiceHandler {
     client: Client,
+    default_endpoint: HttpServiceEndpoint,
+    /// The configured endpoint IRI prefixes, the longest first
+    endpoints: Vec<(String, HttpServiceEndpoint, Client)>,
 }
 
 impl HttpServiceHandler {
-    pub fn new(http_timeout: Option<Duration>, http_redirection_limit: usize) -> Self {
+    pub fn new(
+        http_timeout: Option<Duration>,
+        http_redirection_limit: usize,
+        http_retry_policy: HttpRetryPolicy,
+        endpoints: &[(String, HttpServiceEndpoint)],
+    ) -> Self {
+        let client =
+            Client::new(http_timeout, http_redirection_limit).with_retry_policy(http_retry_policy);
+        let mut endpoints = endpoints
+            .iter()
+            .map(|(prefix, endpoint)| {
+                let client = if endpoint.timeout.is_some() {
+                    Client::new(endpoint.timeout, http_redirection_limit)
+                        .with_retry_policy(http_retry_policy)
+                } else {
+                    client.clone()
+                };
+                (prefix.clone(), endpoint.clone(), client)
+            })
+            .collect::<Vec<_>>();
+        endpoints.sort_by_key(|(prefix, ..)| Reverse(prefix.len()));
         Self {
-            client: Client::new(http_timeout, http_redirection_limit),
+            client,
+            default_endpoint: HttpServiceEndpoint::default(),
+            endpoints,
         }
     }
+
+    /// The configuration and the client to use for a service
+    fn endpoint(&self, service_name: &str) -> (&HttpServiceEndpoint, &Client) {
+        self.endpoints
+            .iter()
+            .find(|(prefix, ..)| service_name.starts_with(prefix.as_str()))
+            .map_or(
+                (&self.default_endpoint, &self.client),
+                |(_, endpoint, client)| (endpoint, client),
+            )
+    }
 }
 
 impl DefaultServiceHandler for HttpServiceHandler {
@@ -110,27 +549,42 @@ impl DefaultServiceHandler for HttpServiceHandler {
         service_name: NamedNode,
         pattern: GraphPattern,
         base_iri: Option<String>,
-    ) -> std::result::Result<QuerySolutionIter, Self::Error> {
-        let (content_type, body) = self
-            .client
-            .post(
-                service_name.as_str(),
-                spargebra::Query::Select {
-                    dataset: None,
-                    pattern,
-                    base_iri: base_iri
-                        .map(Iri::parse)
-                        .transpose()
-                        .map_err(|e| EvaluationError::Service(Box::new(e)))?,
-                }
-                .to_string()
-                .into_bytes(),
-                "application/sparql-query",
-                "application/sparql-results+json, application/sparql-results+xml",
+    ) -> Result<QuerySolutionIter, Self::Error> {
+        let query = spargebra::Query::Select {
+            dataset: None,
+            pattern,
+            base_iri: base_iri
+                .map(Iri::parse)
+                .transpose()
+                .map_err(|e| EvaluationError::Service(Box::new(e)))?,
+        }
+        .to_string();
+        let (endpoint, client) = self.endpoint(service_name.as_str());
+        let accept = "application/sparql-results+json, application/sparql-results+xml";
+        let (content_type, body) = if endpoint.get_method {
+            client.request_with_headers(
+                &Method::GET,
+                &endpoint.url(service_name.as_str(), Some(&query)),
+                None,
+                accept,
+                &endpoint.headers,
+            )
+        } else {
+            client.request_with_headers(
+                &Method::POST,
+                &endpoint.url(service_name.as_str(), None),
+                Some((query.into_bytes(), "application/sparql-query")),
+                accept,
+                &endpoint.headers,
             )
-            .map_err(|e| EvaluationError::Service(Box::new(e)))?;
-        let format = QueryResultsFormat::from_media_type(&content_type)
-            .ok_or_else(|| EvaluationError::UnsupportedContentType(content_type))?;
+        }
+        .map_err(|e| EvaluationError::Service(Box::new(e)))?;
+        let mut body = BufReader::new(body);
+        let format = query_results_format_from_response(
+            content_type,
+            fill_response_buf(&mut body, service_name.as_str())
+                .map_err(|e| EvaluationError::Service(Box::new(e)))?,
+        )?;
         let ReaderQueryResultsParserOutput::Solutions(reader) =
             QueryResultsParser::from_format(format).for_reader(body)?
         else {
@@ -143,10 +597,299 @@ impl DefaultServiceHandler for HttpServiceHandler {
     }
 }
 
-fn invalid_data_error(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Error {
-    Error::new(ErrorKind::InvalidData, error)
+/// Builds an `Accept` header value with the given formats in decreasing order of preference
+pub fn rdf_accept_header(formats: &[RdfFormat]) -> String {
+    let mut header = String::new();
+    for (i, format) in formats.iter().enumerate() {
+        if i > 0 {
+            header.push_str(", ");
+        }
+        header.push_str(format.media_type());
+        if i > 0 {
+            header.push_str(";q=0.");
+            header.push_str(&10_usize.saturating_sub(i).max(1).to_string());
+        }
+    }
+    header
+}
+
+/// Finds the RDF format of an HTTP response
+///
+/// If the content type is missing or not an RDF one like `*/*` or `application/octet-stream`,
+/// the format is guessed from the URL extension and then from the beginning of the content.
+pub fn rdf_format_from_response(
+    content_type: Option<String>,
+    url: &str,
+    content_start: &[u8],
+) -> Result<RdfFormat, EvaluationError> {
+    if let Some(format) = content_type.as_deref().and_then(RdfFormat::from_media_type) {
+        return Ok(format);
+    }
+    url_extension(url)
+        .and_then(RdfFormat::from_extension)
+        .or_else(|| {
+            // Only the beginning of the response is known, Turtle and TriG are supersets of N-Triples and N-Quads
+            match RdfFormat::from_content_peek(content_start)? {
+                RdfFormat::NTriples => Some(RdfFormat::Turtle),
+                RdfFormat::NQuads => Some(RdfFormat::TriG),
+                format => Some(format),
+            }
+        })
+        .ok_or_else(|| EvaluationError::UnsupportedContentType(content_type.unwrap_or_default()))
 }
 
-fn invalid_input_error(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Error {
-    Error::new(ErrorKind::InvalidInput, error)
+/// Finds the query results format of an HTTP response, guessing it from the content if the content type is not a query results one
+pub fn query_results_format_from_response(
+    content_type: Option<String>,
+    content_start: &[u8],
+) -> Result<QueryResultsFormat, EvaluationError> {
+    if let Some(format) = content_type
+        .as_deref()
+        .and_then(QueryResultsFormat::from_media_type)
+    {
+        return Ok(format);
+    }
+    match trim_content_start(content_start).first() {
+        Some(b'{') => Ok(QueryResultsFormat::Json),
+        Some(b'<') => Ok(QueryResultsFormat::Xml),
+        Some(b'?') => Ok(QueryResultsFormat::Tsv),
+        _ => Err(EvaluationError::UnsupportedContentType(
+            content_type.unwrap_or_default(),
+        )),
+    }
+}
+
+/// Percent-encodes a value to be used in a URL query component
+pub fn percent_encode(value: &str, output: &mut String) {
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            output.push(char::from(b));
+        } else {
+            output.push('%');
+            for digit in [b >> 4, b & 0xF] {
+                output.extend(char::from_digit(digit.into(), 16).map(|c| c.to_ascii_uppercase()));
+            }
+        }
+    }
+}
+
+fn url_extension(url: &str) -> Option<&str> {
+    let path = url.split(['?', '#']).next()?;
+    let (_, file_name) = path.rsplit_once('/')?;
+    let (_, extension) = file_name.rsplit_once('.')?;
+    Some(extension)
+}
+
+fn trim_content_start(content_start: &[u8]) -> &[u8] {
+    content_start
+        .strip_prefix(b"\xEF\xBB\xBF")
+        .unwrap_or(content_start)
+        .trim_ascii_start()
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::io::JsonLdProfileSet;
+
+    #[test]
+    fn test_rdf_accept_header() {
+        assert_eq!(
+            rdf_accept_header(&[RdfFormat::Turtle, RdfFormat::NTriples, RdfFormat::RdfXml]),
+            "text/turtle, application/n-triples;q=0.9, application/rdf+xml;q=0.8"
+        );
+    }
+
+    #[test]
+    fn test_rdf_format_from_response() -> Result<(), EvaluationError> {
+        assert_eq!(
+            rdf_format_from_response(Some("text/turtle".into()), "http://example.com/a.nt", b"")?,
+            RdfFormat::Turtle
+        );
+        assert_eq!(
+            rdf_format_from_response(Some("*/*".into()), "http://example.com/a.nt?b=c", b"")?,
+            RdfFormat::NTriples
+        );
+        assert_eq!(
+            rdf_format_from_response(
+                Some("application/octet-stream".into()),
+                "http://example.com/a",
+                b"\xEF\xBB\xBF <?xml version=\"1.0\"?>"
+            )?,
+            RdfFormat::RdfXml
+        );
+        assert_eq!(
+            rdf_format_from_response(None, "http://example.com/a", b"PREFIX ex: <http://ex/>")?,
+            RdfFormat::Turtle
+        );
+        assert_eq!(
+            rdf_format_from_response(None, "http://example.com/a.b/c", b"{\"@id\": \"a\"}")?,
+            RdfFormat::JsonLd {
+                profile: JsonLdProfileSet::empty()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rdf_format_from_unsupported_response() {
+        assert!(matches!(
+            rdf_format_from_response(
+                Some("text/html".into()),
+                "http://example.com",
+                b"<!DOCTYPE html>"
+            ),
+            Err(EvaluationError::UnsupportedContentType(_))
+        ));
+    }
+
+    #[test]
+    fn test_size_limited_body() -> io::Result<()> {
+        let mut content = Vec::new();
+        SizeLimitedBody::new(Body::from(b"abc".to_vec()), "http://example.com", Some(3))?
+            .read_to_end(&mut content)?;
+        assert_eq!(content, b"abc");
+        assert!(matches!(
+            SizeLimitedBody::new(Body::from(b"abcd".to_vec()), "http://example.com", Some(3)),
+            Err(HttpError::PayloadTooLarge { max_size: 3, .. })
+        ));
+        // Without Content-Length the error is raised while reading
+        let error = SizeLimitedBody::new(
+            Body::from_read(io::Cursor::new(b"abcd")),
+            "http://example.com",
+            Some(3),
+        )?
+        .read_to_end(&mut Vec::new())
+        .unwrap_err();
+        assert!(matches!(
+            error.downcast::<HttpError>(),
+            Ok(HttpError::PayloadTooLarge { max_size: 3, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_http_service_endpoint_url() {
+        let endpoint = HttpServiceEndpoint::new().with_query_parameter("key", "a b&c");
+        assert_eq!(
+            endpoint.url("http://example.com/sparql", None),
+            "http://example.com/sparql?key=a%20b%26c"
+        );
+        assert_eq!(
+            endpoint.url("http://example.com/sparql?d=e", Some("SELECT * {}")),
+            "http://example.com/sparql?d=e&query=SELECT%20%2A%20%7B%7D&key=a%20b%26c"
+        );
+    }
+
+    #[test]
+    fn test_http_service_endpoint_selection() {
+        let handler = HttpServiceHandler::new(
+            None,
+            0,
+            HttpRetryPolicy::new(0),
+            &[
+                (
+                    "http://example.com/".into(),
+                    HttpServiceEndpoint::new().with_header("a", "1"),
+                ),
+                (
+                    "http://example.com/sparql".into(),
+                    HttpServiceEndpoint::new().with_header("b", "2"),
+                ),
+            ],
+        );
+        assert_eq!(
+            handler.endpoint("http://example.com/sparql/1").0.headers,
+            [("b".to_owned(), "2".to_owned())]
+        );
+        assert_eq!(
+            handler.endpoint("http://example.com/other").0.headers,
+            [("a".to_owned(), "1".to_owned())]
+        );
+        assert!(handler.endpoint("http://example.org/").0.headers.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "http2-client")]
+    fn test_parallel_service_calls() -> Result<(), Box<dyn std::error::Error>> {
+        let port = std::net::TcpListener::bind(("127.0.0.1", 0))?
+            .local_addr()?
+            .port();
+        let _server = oxhttp::Server::new(|request| {
+            Response::builder()
+                .header(CONTENT_TYPE, "application/sparql-results+json")
+                .body(Body::from(if request.method() == Method::POST {
+                    r#"{"head":{"vars":["s"]},"results":{"bindings":[{"s":{"type":"uri","value":"http://example.com/s"}}]}}"#
+                } else {
+                    r#"{"head":{"vars":["s"]},"results":{"bindings":[]}}"#
+                }))
+                .unwrap()
+        })
+        .bind(([127, 0, 0, 1], port))
+        .spawn()?;
+        let handler = HttpServiceHandler::new(None, 0, HttpRetryPolicy::new(0), &[]);
+        let service_name = NamedNode::new(format!("http://127.0.0.1:{port}/sparql"))?;
+        let counts = std::thread::scope(|scope| {
+            let calls = (0..4)
+                .map(|_| {
+                    scope.spawn(|| -> Result<_, EvaluationError> {
+                        Ok(handler
+                            .handle(
+                                service_name.clone(),
+                                GraphPattern::Bgp {
+                                    patterns: Vec::new(),
+                                },
+                                None,
+                            )?
+                            .count())
+                    })
+                })
+                .collect::<Vec<_>>();
+            calls
+                .into_iter()
+                .map(|call| call.join().unwrap())
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+        assert_eq!(counts, [1; 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = HttpRetryPolicy::new(10)
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_secs(1));
+        assert_eq!(policy.backoff(0, None), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2, None), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4, None), Duration::from_secs(1));
+        assert_eq!(policy.backoff(usize::MAX, None), Duration::from_secs(1));
+        assert_eq!(
+            policy.backoff(0, Some(Duration::from_millis(500))),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            policy.backoff(0, Some(Duration::from_secs(60))),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn test_retry_after() -> Result<(), oxhttp::model::Error> {
+        let response = |value: &str| {
+            Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .header(RETRY_AFTER, value)
+                .body(Body::empty())
+        };
+        assert_eq!(
+            retry_after(&response(" 120")?),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            retry_after(&response("Wed, 21 Oct 2015 07:28:00 GMT")?),
+            None
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,711 @@
+//! Incremental maintenance of SPARQL query results.
+//!
+//! This API is experimental and might change in future versions.
+
+use crate::model::{GraphName, GraphNameRef, NamedNode, NamedOrBlankNode, Quad, Term};
+use crate::sparql::{EvaluationError, Query, QueryResults, QuerySolution, Variable};
+use crate::store::Store;
+use rustc_hash::{FxHashMap, FxHashSet};
+use spargebra::algebra::GraphPattern;
+use spargebra::term::{BlankNode, NamedNodePattern, TermPattern};
+use std::sync::Arc;
+
+/// A `SELECT` query whose results are maintained under quad insertions and removals.
+///
+/// Instead of evaluating again the full query after each change,
+/// only the solutions that involve the inserted or removed quads are computed.
+///
+/// Only `SELECT` queries whose `WHERE` clause is a conjunction of triple patterns, optionally wrapped in `GRAPH` blocks,
+/// are supported, with or without `DISTINCT`.
+/// The query must not have a `FROM` or `FROM NAMED` clause.
+///
+/// This API is experimental and might change in future versions.
+///
+/// ```
+/// use oxigraph::model::*;
+/// use oxigraph::sparql::{ChangeSet, IncrementalQuery};
+/// use oxigraph::store::Store;
+///
+/// let store = Store::new()?;
+/// let mut query = IncrementalQuery::new(&store, "SELECT ?s WHERE { ?s ?p ?o }")?;
+/// assert_eq!(query.len(), 0);
+///
+/// // We insert a quad in the store and notify the query of the change
+/// let ex = NamedNodeRef::new("http://example.com")?;
+/// let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+/// let mut changes = ChangeSet::new();
+/// if store.insert(quad)? {
+///     changes.insert(quad);
+/// }
+/// let delta = query.apply(&store, &changes)?;
+/// assert_eq!(
+///     delta.added().map(|s| s["s"].clone()).collect::<Vec<_>>(),
+///     vec![ex.into_owned().into()]
+/// );
+/// assert_eq!(query.len(), 1);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+pub struct IncrementalQuery {
+    variables: Arc<[Variable]>,
+    /// For each projected variable, its position in the bindings if it is used in the patterns
+    projection: Vec<Option<usize>>,
+    patterns: Vec<QuadPattern>,
+    binding_len: usize,
+    distinct: bool,
+    /// The projected solutions with their number of derivations
+    solutions: FxHashMap<Vec<Option<Term>>, usize>,
+}
+
+impl IncrementalQuery {
+    /// Evaluates the query against the store and prepares it for incremental maintenance.
+    ///
+    /// Returns [`EvaluationError::UnsupportedIncrementalQuery`] if the query is not supported.
+    pub fn new(
+        store: &Store,
+        query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
+    ) -> Result<Self, EvaluationError> {
+        let query = query.try_into().map_err(Into::into)?;
+        let spargebra::Query::Select {
+            dataset,
+            pattern,
+            base_iri,
+        } = &query.inner
+        else {
+            return Err(unsupported("only SELECT queries are supported"));
+        };
+        if !query.dataset.is_default_dataset() {
+            return Err(unsupported("FROM and FROM NAMED are not supported"));
+        }
+        let (project, distinct) = if let GraphPattern::Distinct { inner } = pattern {
+            (&**inner, true)
+        } else {
+            (pattern, false)
+        };
+        let GraphPattern::Project { inner, variables } = project else {
+            return Err(unsupported(
+                "only projections of basic graph patterns are supported",
+            ));
+        };
+        let mut builder = PatternBuilder::default();
+        builder.add(inner, &GraphSlot::DefaultGraph)?;
+        if builder.patterns.is_empty() {
+            return Err(unsupported(
+                "the query must contain at least one triple pattern",
+            ));
+        }
+        let projection = variables
+            .iter()
+            .map(|v| builder.variables.get(v).copied())
+            .collect();
+
+        // We count all the derivations of each solution, even with DISTINCT, to be able to maintain them on removal
+        let QueryResults::Solutions(solutions) = store.query(Query {
+            inner: spargebra::Query::Select {
+                dataset: dataset.clone(),
+                pattern: project.clone(),
+                base_iri: base_iri.clone(),
+            },
+            dataset: query.dataset.clone(),
+        })?
+        else {
+            return Err(unsupported("only SELECT queries are supported"));
+        };
+        let mut counts = FxHashMap::<_, usize>::default();
+        for solution in solutions {
+            *counts.entry(solution?.values().to_vec()).or_default() += 1;
+        }
+
+        Ok(Self {
+            variables: variables.as_slice().into(),
+            projection,
+            patterns: builder.patterns,
+            binding_len: builder.variables.len() + builder.blank_nodes.len(),
+            distinct,
+            solutions: counts,
+        })
+    }
+
+    /// The variables of the query solutions.
+    #[inline]
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    /// The current solutions of the query.
+    ///
+    /// Without `DISTINCT`, a solution is returned as many times as it is derived.
+    pub fn solutions(&self) -> impl Iterator<Item = QuerySolution> + '_ {
+        self.solutions.iter().flat_map(|(values, count)| {
+            let count = if self.distinct { 1 } else { *count };
+            (0..count).map(|_| (Arc::clone(&self.variables), values.clone()).into())
+        })
+    }
+
+    /// The number of current solutions of the query.
+    pub fn len(&self) -> usize {
+        if self.distinct {
+            self.solutions.len()
+        } else {
+            self.solutions.values().sum()
+        }
+    }
+
+    /// Checks if the query currently has no solutions.
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+
+    /// Updates the query solutions after the given changes have been applied to the store.
+    ///
+    /// The store must already contain the changes: the inserted quads must have been added and the removed quads must have been removed.
+    /// Only the actual changes must be recorded, for example the quads for which [`Store::insert`] or [`Store::remove`] returned `true`.
+    ///
+    /// Returns the solutions that have been added and removed.
+    pub fn apply(
+        &mut self,
+        store: &Store,
+        changes: &ChangeSet,
+    ) -> Result<SolutionsDelta, EvaluationError> {
+        let mut differences = FxHashMap::default();
+        let no_quads = FxHashSet::default();
+        // The store before the changes is the current store without the inserted quads and with the removed quads,
+        // the store after the removals is the current store without the inserted quads.
+        let before = QuadSource {
+            store,
+            excluded: &changes.inserted,
+            extra: &changes.removed,
+        };
+        let after_removals = QuadSource {
+            store,
+            excluded: &changes.inserted,
+            extra: &no_quads,
+        };
+        let after = QuadSource {
+            store,
+            excluded: &no_quads,
+            extra: &no_quads,
+        };
+        self.collect_derivations(
+            &changes.removed,
+            &after_removals,
+            &before,
+            -1,
+            &mut differences,
+        )?;
+        self.collect_derivations(
+            &changes.inserted,
+            &after_removals,
+            &after,
+            1,
+            &mut differences,
+        )?;
+
+        let mut delta = SolutionsDelta {
+            variables: Arc::clone(&self.variables),
+            added: Vec::new(),
+            removed: Vec::new(),
+        };
+        for (values, difference) in differences {
+            let old_count = self.solutions.get(&values).copied().unwrap_or(0);
+            let new_count = old_count.saturating_add_signed(difference);
+            if self.distinct {
+                if old_count == 0 && new_count > 0 {
+                    delta.added.push(values.clone());
+                } else if old_count > 0 && new_count == 0 {
+                    delta.removed.push(values.clone());
+                }
+            } else if new_count > old_count {
+                for _ in old_count..new_count {
+                    delta.added.push(values.clone());
+                }
+            } else {
+                for _ in new_count..old_count {
+                    delta.removed.push(values.clone());
+                }
+            }
+            if new_count == 0 {
+                self.solutions.remove(&values);
+            } else {
+                self.solutions.insert(values, new_count);
+            }
+        }
+        Ok(delta)
+    }
+
+    /// Adds to `differences` the derivations that use at least one of the changed quads.
+    ///
+    /// To count each derivation only once, the pattern matching the first changed quad of the derivation is called the pivot.
+    /// The patterns before the pivot are matched against `lower` that must not contain the changed quads
+    /// and the patterns after the pivot against `upper` that must contain them.
+    fn collect_derivations(
+        &self,
+        changed: &FxHashSet<Quad>,
+        lower: &QuadSource<'_>,
+        upper: &QuadSource<'_>,
+        sign: isize,
+        differences: &mut FxHashMap<Vec<Option<Term>>, isize>,
+    ) -> Result<(), EvaluationError> {
+        let mut binding = vec![None; self.binding_len];
+        let mut bound = Vec::new();
+        for (pivot, pattern) in self.patterns.iter().enumerate() {
+            for quad in changed {
+                if pattern.unify(quad, &mut binding, &mut bound) {
+                    self.join(0, pivot, lower, upper, &mut binding, sign, differences)?;
+                    for i in bound.drain(..) {
+                        binding[i] = None;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn join(
+        &self,
+        index: usize,
+        pivot: usize,
+        lower: &QuadSource<'_>,
+        upper: &QuadSource<'_>,
+        binding: &mut [Option<Term>],
+        sign: isize,
+        differences: &mut FxHashMap<Vec<Option<Term>>, isize>,
+    ) -> Result<(), EvaluationError> {
+        if index == pivot {
+            return self.join(index + 1, pivot, lower, upper, binding, sign, differences);
+        }
+        let Some(pattern) = self.patterns.get(index) else {
+            let values = self
+                .projection
+                .iter()
+                .map(|i| i.and_then(|i| binding[i].clone()))
+                .collect();
+            *differences.entry(values).or_default() += sign;
+            return Ok(());
+        };
+        let source = if index < pivot { lower } else { upper };
+        let mut bound = Vec::new();
+        for quad in source.candidates(pattern, binding)? {
+            if pattern.unify(&quad, binding, &mut bound) {
+                self.join(index + 1, pivot, lower, upper, binding, sign, differences)?;
+                for i in bound.drain(..) {
+                    binding[i] = None;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A set of quad insertions and removals applied to a [`Store`].
+///
+/// Inserting a quad that has been removed in the same change set cancels the removal and reciprocally.
+#[derive(Default, Clone, Debug)]
+pub struct ChangeSet {
+    inserted: FxHashSet<Quad>,
+    removed: FxHashSet<Quad>,
+}
+
+impl ChangeSet {
+    /// Builds a new empty change set.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the insertion of a quad.
+    pub fn insert(&mut self, quad: impl Into<Quad>) {
+        let quad = quad.into();
+        if !self.removed.remove(&quad) {
+            self.inserted.insert(quad);
+        }
+    }
+
+    /// Records the removal of a quad.
+    pub fn remove(&mut self, quad: impl Into<Quad>) {
+        let quad = quad.into();
+        if !self.inserted.remove(&quad) {
+            self.removed.insert(quad);
+        }
+    }
+
+    /// Checks if the change set contains no change.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inserted.is_empty() && self.removed.is_empty()
+    }
+
+    /// Removes all the changes.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.inserted.clear();
+        self.removed.clear();
+    }
+}
+
+/// The solutions added and removed by a [`ChangeSet`], returned by [`IncrementalQuery::apply`].
+pub struct SolutionsDelta {
+    variables: Arc<[Variable]>,
+    added: Vec<Vec<Option<Term>>>,
+    removed: Vec<Vec<Option<Term>>>,
+}
+
+impl SolutionsDelta {
+    /// The variables of the solutions.
+    #[inline]
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    /// The solutions that have been added.
+    pub fn added(&self) -> impl Iterator<Item = QuerySolution> + '_ {
+        self.added
+            .iter()
+            .map(|values| (Arc::clone(&self.variables), values.clone()).into())
+    }
+
+    /// The solutions that have been removed.
+    pub fn removed(&self) -> impl Iterator<Item = QuerySolution> + '_ {
+        self.removed
+            .iter()
+            .map(|values| (Arc::clone(&self.variables), values.clone()).into())
+    }
+
+    /// Checks if no solution has been added or removed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn unsupported(reason: &str) -> EvaluationError {
+    EvaluationError::UnsupportedIncrementalQuery(reason.into())
+}
+
+#[derive(Default)]
+struct PatternBuilder {
+    patterns: Vec<QuadPattern>,
+    variables: FxHashMap<Variable, usize>,
+    blank_nodes: FxHashMap<BlankNode, usize>,
+}
+
+impl PatternBuilder {
+    fn add(
+        &mut self,
+        pattern: &GraphPattern,
+        graph_name: &GraphSlot,
+    ) -> Result<(), EvaluationError> {
+        match pattern {
+            GraphPattern::Bgp { patterns } => {
+                for pattern in patterns {
+                    let pattern = QuadPattern {
+                        subject: self.term_slot(&pattern.subject)?,
+                        predicate: self.named_node_slot(&pattern.predicate),
+                        object: self.term_slot(&pattern.object)?,
+                        graph_name: graph_name.clone(),
+                    };
+                    self.patterns.push(pattern);
+                }
+                Ok(())
+            }
+            GraphPattern::Join { left, right } => {
+                self.add(left, graph_name)?;
+                self.add(right, graph_name)
+            }
+            GraphPattern::Graph { name, inner } => {
+                let graph_name = match name {
+                    NamedNodePattern::NamedNode(name) => GraphSlot::NamedNode(name.clone()),
+                    NamedNodePattern::Variable(variable) => {
+                        GraphSlot::Variable(self.variable(variable))
+                    }
+                };
+                let pattern_count = self.patterns.len();
+                self.add(inner, &graph_name)?;
+                if self.patterns.len() == pattern_count {
+                    return Err(unsupported("empty GRAPH blocks are not supported"));
+                }
+                Ok(())
+            }
+            _ => Err(unsupported(
+                "only triple patterns, joins and GRAPH blocks are supported",
+            )),
+        }
+    }
+
+    #[cfg_attr(not(feature = "rdf-12"), expect(clippy::unnecessary_wraps))]
+    fn term_slot(&mut self, term: &TermPattern) -> Result<TermSlot, EvaluationError> {
+        Ok(match term {
+            TermPattern::NamedNode(node) => TermSlot::Constant(node.clone().into()),
+            TermPattern::Literal(literal) => TermSlot::Constant(literal.clone().into()),
+            TermPattern::BlankNode(node) => {
+                let len = self.variables.len() + self.blank_nodes.len();
+                TermSlot::Variable(*self.blank_nodes.entry(node.clone()).or_insert(len))
+            }
+            TermPattern::Variable(variable) => TermSlot::Variable(self.variable(variable)),
+            #[cfg(feature = "rdf-12")]
+            TermPattern::Triple(_) => return Err(unsupported("triple terms are not supported")),
+        })
+    }
+
+    fn named_node_slot(&mut self, term: &NamedNodePattern) -> TermSlot {
+        match term {
+            NamedNodePattern::NamedNode(node) => TermSlot::Constant(node.clone().into()),
+            NamedNodePattern::Variable(variable) => TermSlot::Variable(self.variable(variable)),
+        }
+    }
+
+    fn variable(&mut self, variable: &Variable) -> usize {
+        let len = self.variables.len() + self.blank_nodes.len();
+        *self.variables.entry(variable.clone()).or_insert(len)
+    }
+}
+
+enum TermSlot {
+    Constant(Term),
+    Variable(usize),
+}
+
+impl TermSlot {
+    fn value<'a>(&'a self, binding: &'a [Option<Term>]) -> Option<&'a Term> {
+        match self {
+            Self::Constant(term) => Some(term),
+            Self::Variable(i) => binding[*i].as_ref(),
+        }
+    }
+
+    fn unify(&self, term: Term, binding: &mut [Option<Term>], bound: &mut Vec<usize>) -> bool {
+        match self {
+            Self::Constant(constant) => *constant == term,
+            Self::Variable(i) => {
+                if let Some(value) = &binding[*i] {
+                    *value == term
+                } else {
+                    binding[*i] = Some(term);
+                    bound.push(*i);
+                    true
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+enum GraphSlot {
+    DefaultGraph,
+    NamedNode(NamedNode),
+    Variable(usize),
+}
+
+struct QuadPattern {
+    subject: TermSlot,
+    predicate: TermSlot,
+    object: TermSlot,
+    graph_name: GraphSlot,
+}
+
+impl QuadPattern {
+    /// Binds the pattern variables to the quad terms.
+    ///
+    /// The newly bound variables are added to `bound`. If the quad does not match, the binding is left unchanged.
+    fn unify(&self, quad: &Quad, binding: &mut [Option<Term>], bound: &mut Vec<usize>) -> bool {
+        let start = bound.len();
+        let matches = match (&self.graph_name, &quad.graph_name) {
+            (GraphSlot::DefaultGraph, GraphName::DefaultGraph) => true,
+            (GraphSlot::NamedNode(expected), GraphName::NamedNode(actual)) => expected == actual,
+            (GraphSlot::Variable(i), GraphName::NamedNode(actual)) => {
+                TermSlot::Variable(*i).unify(actual.clone().into(), binding, bound)
+            }
+            (GraphSlot::Variable(i), GraphName::BlankNode(actual)) => {
+                TermSlot::Variable(*i).unify(actual.clone().into(), binding, bound)
+            }
+            _ => false,
+        } && self
+            .subject
+            .unify(quad.subject.clone().into(), binding, bound)
+            && self
+                .predicate
+                .unify(quad.predicate.clone().into(), binding, bound)
+            && self.object.unify(quad.object.clone(), binding, bound);
+        if !matches {
+            for i in bound.drain(start..) {
+                binding[i] = None;
+            }
+        }
+        matches
+    }
+}
+
+/// The quads of the store with some quads excluded and some others added.
+struct QuadSource<'a> {
+    store: &'a Store,
+    excluded: &'a FxHashSet<Quad>,
+    extra: &'a FxHashSet<Quad>,
+}
+
+impl QuadSource<'_> {
+    /// Returns the quads that might match the pattern given the current binding.
+    fn candidates(
+        &self,
+        pattern: &QuadPattern,
+        binding: &[Option<Term>],
+    ) -> Result<Vec<Quad>, EvaluationError> {
+        let subject = match pattern.subject.value(binding) {
+            Some(term) => match NamedOrBlankNode::try_from(term.clone()) {
+                Ok(subject) => Some(subject),
+                Err(_) => return Ok(self.extra.iter().cloned().collect()),
+            },
+            None => None,
+        };
+        let predicate = match pattern.predicate.value(binding) {
+            Some(term) => match NamedNode::try_from(term.clone()) {
+                Ok(predicate) => Some(predicate),
+                Err(_) => return Ok(self.extra.iter().cloned().collect()),
+            },
+            None => None,
+        };
+        let graph_name = match &pattern.graph_name {
+            GraphSlot::DefaultGraph => Some(GraphName::DefaultGraph),
+            GraphSlot::NamedNode(name) => Some(name.clone().into()),
+            GraphSlot::Variable(i) => match &binding[*i] {
+                Some(term) => match NamedOrBlankNode::try_from(term.clone()) {
+                    Ok(name) => Some(name.into()),
+                    Err(_) => return Ok(self.extra.iter().cloned().collect()),
+                },
+                None => None,
+            },
+        };
+        let mut candidates = self.extra.iter().cloned().collect::<Vec<_>>();
+        for quad in self.store.quads_for_pattern(
+            subject.as_ref().map(Into::into),
+            predicate.as_ref().map(Into::into),
+            pattern.object.value(binding).map(Into::into),
+            graph_name.as_ref().map(GraphNameRef::from),
+        ) {
+            let quad = quad?;
+            if !self.excluded.contains(&quad) {
+                candidates.push(quad);
+            }
+        }
+        Ok(candidates)
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::model::{Literal, NamedNodeRef, QuadRef};
+
+    fn sorted_solutions(solutions: impl Iterator<Item = QuerySolution>) -> Vec<Vec<Option<Term>>> {
+        let mut solutions = solutions.map(|s| s.values().to_vec()).collect::<Vec<_>>();
+        solutions.sort_by_key(|s| format!("{s:?}"));
+        solutions
+    }
+
+    fn evaluate(store: &Store, query: &str) -> Result<Vec<Vec<Option<Term>>>, EvaluationError> {
+        let QueryResults::Solutions(solutions) = store.query(query)? else {
+            unreachable!()
+        };
+        Ok(sorted_solutions(
+            solutions.collect::<Result<Vec<_>, _>>()?.into_iter(),
+        ))
+    }
+
+    #[test]
+    fn test_maintenance_matches_evaluation() -> Result<(), EvaluationError> {
+        let queries = [
+            "SELECT * WHERE { ?s <http://example.com/p> ?o }",
+            "SELECT ?s ?o2 WHERE { ?s <http://example.com/p> ?o . ?o <http://example.com/p> ?o2 }",
+            "SELECT DISTINCT ?s WHERE { ?s ?p ?o . ?o ?p ?s }",
+            "SELECT ?s WHERE { ?s ?p [] }",
+            "SELECT ?g ?s WHERE { GRAPH ?g { ?s ?p ?o } ?s ?p ?o }",
+            "SELECT ?x WHERE { ?x ?p ?x }",
+        ];
+        let store = Store::new()?;
+        let nodes = (0..3)
+            .map(|i| NamedNode::new_unchecked(format!("http://example.com/{i}")))
+            .collect::<Vec<_>>();
+        let p = NamedNodeRef::new_unchecked("http://example.com/p");
+        let graph = GraphName::from(NamedNodeRef::new_unchecked("http://example.com/g"));
+        let mut incremental_queries = queries
+            .iter()
+            .map(|q| IncrementalQuery::new(&store, *q))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // We run a deterministic sequence of insertions and removals
+        let mut all_quads = Vec::new();
+        for s in &nodes {
+            for o in &nodes {
+                for g in [&GraphName::DefaultGraph, &graph] {
+                    all_quads.push(Quad::new(s.clone(), p, o.clone(), g.clone()));
+                }
+            }
+            all_quads.push(Quad::new(
+                s.clone(),
+                p,
+                Literal::from(1),
+                GraphName::DefaultGraph,
+            ));
+        }
+        for step in 0..6 {
+            let mut changes = ChangeSet::new();
+            for (i, quad) in all_quads.iter().enumerate() {
+                if (i * 7 + step * 3) % 5 < 2 {
+                    if store.insert(quad)? {
+                        changes.insert(quad.clone());
+                    }
+                } else if (i + step) % 4 == 0 && store.remove(quad)? {
+                    changes.remove(quad.clone());
+                }
+            }
+            for (query, incremental_query) in queries.iter().zip(&mut incremental_queries) {
+                let before = sorted_solutions(incremental_query.solutions());
+                let delta = incremental_query.apply(&store, &changes)?;
+                let mut expected = before;
+                for removed in delta.removed() {
+                    let position = expected
+                        .iter()
+                        .position(|s| s.as_slice() == removed.values());
+                    assert!(position.is_some(), "Removed a not existing solution");
+                    if let Some(position) = position {
+                        expected.remove(position);
+                    }
+                }
+                expected.extend(delta.added().map(|s| s.values().to_vec()));
+                expected.sort_by_key(|s| format!("{s:?}"));
+                let actual = evaluate(&store, query)?;
+                assert_eq!(sorted_solutions(incremental_query.solutions()), actual);
+                assert_eq!(expected, actual);
+                assert_eq!(incremental_query.len(), actual.len());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_change_set_cancellation() {
+        let ex = NamedNodeRef::new_unchecked("http://example.com");
+        let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+        let mut changes = ChangeSet::new();
+        changes.insert(quad);
+        changes.remove(quad);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_queries() -> Result<(), EvaluationError> {
+        let store = Store::new()?;
+        for query in [
+            "ASK { ?s ?p ?o }",
+            "SELECT * WHERE { ?s ?p ?o OPTIONAL { ?o ?p ?s } }",
+            "SELECT * FROM <http://example.com> WHERE { ?s ?p ?o }",
+            "SELECT * WHERE { ?s <http://example.com/p>+ ?o }",
+            "SELECT * WHERE {}",
+        ] {
+            assert!(matches!(
+                IncrementalQuery::new(&store, query),
+                Err(EvaluationError::UnsupportedIncrementalQuery(_))
+            ));
+        }
+        Ok(())
+    }
+}
@@ -3,57 +3,181 @@
 //! Stores execute SPARQL. See [`Store`](crate::store::Store::query()) for an example.
 
 mod algebra;
+mod cache;
+mod complexity;
 mod dataset;
+mod deadline;
 mod error;
 #[cfg(feature = "http-client")]
-mod http;
+pub(crate) mod http;
+mod incremental;
 mod model;
+mod progress;
 pub mod results;
+mod rewrite;
 mod service;
 mod update;
 
-use crate::model::{NamedNode, Term};
+#[cfg(feature = "http-client")]
+use crate::io::RdfFormat;
+use crate::model::{GraphName, NamedNode, NamedOrBlankNode, Term};
 pub use crate::sparql::algebra::{Query, QueryDataset, Update};
+pub(crate) use crate::sparql::cache::QueryResultsCache;
+pub use crate::sparql::complexity::{QueryComplexity, QueryCostClass};
 use crate::sparql::dataset::DatasetView;
-pub use crate::sparql::error::EvaluationError;
+use crate::sparql::deadline::Deadline;
+pub use crate::sparql::deadline::QueryCancellationToken;
+pub use crate::sparql::error::{EvaluationError, HttpError};
 #[cfg(feature = "http-client")]
 use crate::sparql::http::HttpServiceHandler;
-pub use crate::sparql::model::{QueryResults, QuerySolution, QuerySolutionIter, QueryTripleIter};
+#[cfg(feature = "http-client")]
+pub use crate::sparql::http::{HttpRetryPolicy, HttpServiceEndpoint};
+pub use crate::sparql::incremental::{ChangeSet, IncrementalQuery, SolutionsDelta};
+pub use crate::sparql::model::{
+    QueryResults, QueryResultsDigest, QuerySolution, QuerySolutionIter, QueryTripleIter,
+};
+pub use crate::sparql::progress::QueryProgress;
+pub(crate) use crate::sparql::rewrite::FullTextSearch;
+use crate::sparql::rewrite::rewrite_query;
+pub use crate::sparql::rewrite::{
+    IdentityResolution, QueryRewriter, SameAsExpansion, VocabularyMapping,
+};
 pub use crate::sparql::service::{DefaultServiceHandler, ServiceHandler};
 use crate::sparql::service::{WrappedDefaultServiceHandler, WrappedServiceHandler};
 pub(crate) use crate::sparql::update::evaluate_update;
 use crate::storage::StorageReader;
 pub use oxrdf::{Variable, VariableNameParseError};
 use spareval::QueryEvaluator;
-pub use spareval::QueryExplanation;
+pub use spareval::{IntegerOverflowBehavior, LiteralValueHandler, QueryExplanation, QueryPlanNode};
 pub use spargebra::SparqlSyntaxError;
-#[cfg(feature = "http-client")]
+use spargebra::algebra::GraphPattern;
+use spargebra::term::GroundTerm;
+use std::mem::take;
+use std::sync::Arc;
 use std::time::Duration;
+#[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+use std::time::Instant;
 
 pub(crate) fn evaluate_query(
     reader: StorageReader,
     query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
-    options: QueryOptions,
+    mut options: QueryOptions,
+    rewriters: &[Arc<dyn QueryRewriter>],
     run_stats: bool,
     substitutions: impl IntoIterator<Item = (Variable, Term)>,
 ) -> Result<(Result<QueryResults, EvaluationError>, QueryExplanation), EvaluationError> {
-    let query = query.try_into().map_err(Into::into)?;
-    let dataset = DatasetView::new(reader, &query.dataset);
+    let mut query = query.try_into().map_err(Into::into)?;
+    rewrite_query(&mut query.inner, rewriters);
+    if let Some(default_graphs) = options.default_graphs.take() {
+        query.dataset.set_default_graph(default_graphs);
+    }
+    if let Some(named_graphs) = options.named_graphs.take() {
+        query.dataset.set_available_named_graphs(named_graphs);
+    }
+    if let Some(values) = options.values.take() {
+        add_values(&mut query, values)?;
+    }
+    let mut deadline = Deadline::default();
+    if let Some(timeout) = options.timeout {
+        deadline = deadline.with_timeout(timeout);
+    }
+    #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+    if let Some(instant) = options.deadline {
+        deadline = deadline.with_instant(instant);
+    }
+    if let Some(token) = options.cancellation_token.take() {
+        deadline = deadline.with_cancellation_token(token);
+    }
+    let deadline = deadline.into_option();
+    let substitutions = take(&mut options.substitutions)
+        .into_iter()
+        .chain(substitutions);
+    let progress = options.progress.take();
+    if let Some(progress) = &progress {
+        progress.start();
+    }
+    let statistics = reader.statistics().cloned();
+    let dataset = DatasetView::new(reader, &query.dataset, deadline, progress.clone());
     let mut evaluator = options.into_evaluator();
+    if let Some(statistics) = statistics {
+        evaluator = evaluator.with_dataset_statistics(statistics);
+    }
     if run_stats {
         evaluator = evaluator.compute_statistics();
     }
     let (results, explanation) =
         evaluator.explain_with_substituted_variables(dataset, &query.inner, substitutions);
-    let results = results.map_err(Into::into).map(Into::into);
+    let results = results.map_err(Into::into).map(|results| {
+        if let Some(progress) = &progress {
+            progress.track(results).into()
+        } else {
+            results.into()
+        }
+    });
     Ok((results, explanation))
 }
 
+/// Joins the query with a `VALUES` block like a `VALUES` clause at the end of the query
+fn add_values(query: &mut Query, rows: Vec<Vec<(Variable, Term)>>) -> Result<(), EvaluationError> {
+    let mut variables = Vec::<Variable>::new();
+    let mut bindings = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut binding = vec![None; variables.len()];
+        for (variable, term) in row {
+            let position = if let Some(position) = variables.iter().position(|v| *v == variable) {
+                position
+            } else {
+                variables.push(variable);
+                variables.len() - 1
+            };
+            if binding.len() <= position {
+                binding.resize(position + 1, None);
+            }
+            binding[position] = Some(
+                GroundTerm::try_from(term.clone())
+                    .map_err(|()| EvaluationError::InvalidValuesTerm(term))?,
+            );
+        }
+        bindings.push(binding);
+    }
+    for binding in &mut bindings {
+        binding.resize(variables.len(), None);
+    }
+    let values = GraphPattern::Values {
+        variables,
+        bindings,
+    };
+    // We go below the solution modifiers
+    let (spargebra::Query::Select { pattern: root, .. }
+    | spargebra::Query::Construct { pattern: root, .. }
+    | spargebra::Query::Describe { pattern: root, .. }
+    | spargebra::Query::Ask { pattern: root, .. }) = &mut query.inner;
+    let mut pattern = root;
+    while let GraphPattern::Slice { inner, .. }
+    | GraphPattern::Distinct { inner }
+    | GraphPattern::Reduced { inner }
+    | GraphPattern::Project { inner, .. }
+    | GraphPattern::OrderBy { inner, .. } = pattern
+    {
+        pattern = inner;
+    }
+    *pattern = GraphPattern::Join {
+        left: Box::new(take(pattern)),
+        right: Box::new(values),
+    };
+    Ok(())
+}
+
 /// Options for SPARQL query evaluation.
 ///
 ///
 /// If the `"http-client"` optional feature is enabled,
 /// a simple HTTP 1.1 client is used to execute [SPARQL 1.1 Federated Query](https://www.w3.org/TR/sparql11-federated-query/) SERVICE calls.
+/// If the `"http2-client"` optional feature is enabled, HTTP/2 is used with the HTTPS endpoints supporting it
+/// and the parallel SERVICE calls enabled by [`with_service_parallelism`](Self::with_service_parallelism) are multiplexed over a single connection to the endpoint.
+/// The connections are kept open and reused by the next queries.
+/// To reduce the number of requests of federated queries with selective joins,
+/// use [`with_service_batch_size`](Self::with_service_batch_size).
 ///
 /// Usage example disabling the federated query support:
 /// ```
@@ -74,7 +198,22 @@ pub struct QueryOptions {
     #[cfg(feature = "http-client")]
     http_redirection_limit: usize,
     #[cfg(feature = "http-client")]
+    http_retry_policy: HttpRetryPolicy,
+    #[cfg(feature = "http-client")]
     with_http_default_service_handler: bool,
+    #[cfg(feature = "http-client")]
+    http_accepted_rdf_formats: Vec<RdfFormat>,
+    #[cfg(feature = "http-client")]
+    http_service_endpoints: Vec<(String, HttpServiceEndpoint)>,
+    timeout: Option<Duration>,
+    #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+    deadline: Option<Instant>,
+    cancellation_token: Option<QueryCancellationToken>,
+    default_graphs: Option<Vec<GraphName>>,
+    named_graphs: Option<Vec<NamedOrBlankNode>>,
+    values: Option<Vec<Vec<(Variable, Term)>>>,
+    substitutions: Vec<(Variable, Term)>,
+    progress: Option<QueryProgress>,
     inner: QueryEvaluator,
 }
 
@@ -157,6 +296,288 @@ impl QueryOptions {
         self
     }
 
+    /// Sets how the HTTP requests done during SPARQL evaluation are retried when they fail.
+    ///
+    /// By default, they are not retried.
+    /// See [`HttpRetryPolicy`] for an example.
+    #[cfg(feature = "http-client")]
+    #[inline]
+    #[must_use]
+    pub fn with_http_retry_policy(mut self, retry_policy: HttpRetryPolicy) -> Self {
+        self.http_retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the RDF formats advertised in the `Accept` header when dereferencing IRIs for the `LOAD` operation, in decreasing order of preference.
+    ///
+    /// By default, N-Triples, Turtle and RDF/XML are accepted.
+    /// Responses in other formats are still parsed if the format is supported.
+    /// If the response content type is missing or is not an RDF one like `*/*` or `application/octet-stream`,
+    /// the format is guessed from the IRI extension and then from the beginning of the content.
+    ///
+    /// ```
+    /// use oxigraph::io::RdfFormat;
+    /// use oxigraph::sparql::QueryOptions;
+    ///
+    /// let options = QueryOptions::default()
+    ///     .with_http_accepted_rdf_formats([RdfFormat::Turtle, RdfFormat::RdfXml]);
+    /// # let _ = options;
+    /// ```
+    #[cfg(feature = "http-client")]
+    #[inline]
+    #[must_use]
+    pub fn with_http_accepted_rdf_formats(
+        mut self,
+        formats: impl IntoIterator<Item = RdfFormat>,
+    ) -> Self {
+        self.http_accepted_rdf_formats = formats.into_iter().collect();
+        self
+    }
+
+    /// Configures the HTTP requests sent to the SERVICE endpoints whose IRI starts with `iri_prefix`,
+    /// for example to authenticate to them.
+    ///
+    /// If several prefixes match an endpoint IRI, the longest one is used.
+    /// Registering the same prefix again replaces its configuration.
+    /// It has no effect on the services evaluated by a [`ServiceHandler`] or a [`DefaultServiceHandler`].
+    ///
+    /// See [`HttpServiceEndpoint`] for an example.
+    #[cfg(feature = "http-client")]
+    #[inline]
+    #[must_use]
+    pub fn with_http_service_endpoint(
+        mut self,
+        iri_prefix: impl Into<String>,
+        endpoint: HttpServiceEndpoint,
+    ) -> Self {
+        let iri_prefix = iri_prefix.into();
+        self.http_service_endpoints
+            .retain(|(prefix, _)| *prefix != iri_prefix);
+        self.http_service_endpoints.push((iri_prefix, endpoint));
+        self
+    }
+
+    /// Sets a maximal duration for the query evaluation.
+    ///
+    /// The deadline is checked each time the evaluation reads data from the store.
+    /// When it is reached, the evaluation fails with [`EvaluationError::Timeout`],
+    /// possibly after some solutions have already been returned by the results iterator.
+    ///
+    /// ```
+    /// use oxigraph::sparql::{EvaluationError, QueryOptions, QueryResults};
+    /// use oxigraph::store::Store;
+    /// use std::time::Duration;
+    ///
+    /// let store = Store::new()?;
+    /// store.update("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 , 2 , 3 }")?;
+    /// if let QueryResults::Solutions(mut solutions) = store.query_opt(
+    ///     "SELECT * WHERE { ?s ?p ?o }",
+    ///     QueryOptions::default().with_timeout(Duration::ZERO),
+    /// )? {
+    ///     assert!(matches!(solutions.next(), Some(Err(EvaluationError::Timeout))));
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets an instant after which the query evaluation must stop.
+    ///
+    /// It behaves like [`with_timeout`](Self::with_timeout) but with an absolute instant,
+    /// allowing to share the same deadline between several queries.
+    /// If both are set, the earliest one applies.
+    ///
+    /// ```
+    /// use oxigraph::sparql::{EvaluationError, QueryOptions, QueryResults};
+    /// use oxigraph::store::Store;
+    /// use std::time::Instant;
+    ///
+    /// let store = Store::new()?;
+    /// store.update("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 , 2 , 3 }")?;
+    /// if let QueryResults::Solutions(mut solutions) = store.query_opt(
+    ///     "SELECT * WHERE { ?s ?p ?o }",
+    ///     QueryOptions::default().with_deadline(Instant::now()),
+    /// )? {
+    ///     assert!(matches!(solutions.next(), Some(Err(EvaluationError::Timeout))));
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+    #[inline]
+    #[must_use]
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Allows to cancel the query evaluation with the given [`QueryCancellationToken`].
+    ///
+    /// The token is checked each time the evaluation reads data from the store.
+    /// Once it is cancelled, the evaluation fails with [`EvaluationError::Cancelled`].
+    ///
+    /// See [`QueryCancellationToken`] for an example.
+    #[inline]
+    #[must_use]
+    pub fn with_cancellation_token(mut self, token: QueryCancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Sets the list of graphs the query default graph is the union of.
+    ///
+    /// It overrides the query `FROM` clauses and allows to restrict the data visible to a query without editing or trusting its text.
+    /// See [`QueryDataset::set_default_graph`].
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::sparql::{EvaluationError, QueryOptions, QueryResults};
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// store.update(
+    ///     "INSERT DATA {
+    ///         GRAPH <http://example.com/g1> { <http://example.com/s> <http://example.com/p> 1 }
+    ///         GRAPH <http://example.com/g2> { <http://example.com/s> <http://example.com/p> 2 }
+    ///     }",
+    /// )?;
+    /// if let QueryResults::Solutions(solutions) = store.query_opt(
+    ///     "SELECT ?o FROM <http://example.com/g2> WHERE { ?s ?p ?o }",
+    ///     QueryOptions::default().with_default_graphs([NamedNode::new("http://example.com/g1")?]),
+    /// )? {
+    ///     assert_eq!(
+    ///         solutions
+    ///             .map(|s| Ok(s?.get("o").cloned()))
+    ///             .collect::<Result<Vec<_>, EvaluationError>>()?,
+    ///         vec![Some(Literal::from(1).into())]
+    ///     );
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_default_graphs(
+        mut self,
+        graphs: impl IntoIterator<Item = impl Into<GraphName>>,
+    ) -> Self {
+        self.default_graphs = Some(graphs.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the list of named graphs available to the query, for example in `GRAPH` blocks.
+    ///
+    /// It overrides the query `FROM NAMED` clauses and allows to restrict the data visible to a query without editing or trusting its text.
+    /// See [`QueryDataset::set_available_named_graphs`].
+    #[inline]
+    #[must_use]
+    pub fn with_named_graphs(
+        mut self,
+        graphs: impl IntoIterator<Item = impl Into<NamedOrBlankNode>>,
+    ) -> Self {
+        self.named_graphs = Some(graphs.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Joins the query with the given solutions, like a `VALUES` clause at the end of the query.
+    ///
+    /// Each row is a list of bindings, the variables not bound in a row are left unbound.
+    /// It allows to join external data, like a spreadsheet, with the store content without loading it first.
+    /// Blank nodes are not allowed and make the evaluation fail with [`EvaluationError::InvalidValuesTerm`].
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::sparql::{EvaluationError, QueryOptions, QueryResults, Variable};
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// store.update(
+    ///     "INSERT DATA { <http://example.com/a> <http://example.com/p> 1 . <http://example.com/b> <http://example.com/p> 2 }",
+    /// )?;
+    /// if let QueryResults::Solutions(solutions) = store.query_opt(
+    ///     "SELECT ?o WHERE { ?s <http://example.com/p> ?o }",
+    ///     QueryOptions::default().with_values([[(
+    ///         Variable::new("s")?,
+    ///         NamedNode::new("http://example.com/b")?.into(),
+    ///     )]]),
+    /// )? {
+    ///     assert_eq!(
+    ///         solutions
+    ///             .map(|s| Ok(s?.get("o").cloned()))
+    ///             .collect::<Result<Vec<_>, EvaluationError>>()?,
+    ///         vec![Some(Literal::from(2).into())]
+    ///     );
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_values(
+        mut self,
+        rows: impl IntoIterator<Item = impl IntoIterator<Item = (Variable, Term)>>,
+    ) -> Self {
+        self.values = Some(
+            rows.into_iter()
+                .map(|row| row.into_iter().collect())
+                .collect(),
+        );
+        self
+    }
+
+    /// Binds some variables to the given terms before evaluating the query.
+    ///
+    /// Substitution follows [RDF-dev SEP-0007](https://github.com/w3c/sparql-dev/blob/main/SEP/SEP-0007/sep-0007.md):
+    /// unlike [`with_values`](Self::with_values), the terms are set in the query before its evaluation,
+    /// allowing to efficiently run the same query for many seed resources.
+    /// The variables must be returned by the query, if not the evaluation fails with [`EvaluationError::NotExistingSubstitutedVariable`].
+    /// Calling this method several times adds substitutions.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::sparql::{Query, QueryOptions, QueryResults, Variable};
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// store.update(
+    ///     "INSERT DATA { <http://example.com/a> <http://example.com/p> 1 . <http://example.com/b> <http://example.com/p> 2 }",
+    /// )?;
+    /// let query = Query::parse("SELECT ?s ?o WHERE { ?s <http://example.com/p> ?o }", None)?;
+    /// for (seed, value) in [("http://example.com/a", 1), ("http://example.com/b", 2)] {
+    ///     if let QueryResults::Solutions(mut solutions) = store.query_opt(
+    ///         query.clone(),
+    ///         QueryOptions::default()
+    ///             .with_substitutions([(Variable::new("s")?, NamedNode::new(seed)?.into())]),
+    ///     )? {
+    ///         assert_eq!(
+    ///             solutions.next().unwrap()?.get("o"),
+    ///             Some(&Literal::from(value).into())
+    ///         );
+    ///     }
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_substitutions(
+        mut self,
+        substitutions: impl IntoIterator<Item = (Variable, Term)>,
+    ) -> Self {
+        self.substitutions.extend(substitutions);
+        self
+    }
+
+    /// Reports the evaluation progress in the given [`QueryProgress`].
+    ///
+    /// See [`QueryProgress`] for an example.
+    #[inline]
+    #[must_use]
+    pub fn with_progress(mut self, progress: QueryProgress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
     #[cfg_attr(not(feature = "http-client"), expect(unused_mut))]
     fn into_evaluator(mut self) -> QueryEvaluator {
         #[cfg(feature = "http-client")]
@@ -166,6 +587,8 @@ impl QueryOptions {
                 .with_default_service_handler(HttpServiceHandler::new(
                     self.http_timeout,
                     self.http_redirection_limit,
+                    self.http_retry_policy,
+                    &self.http_service_endpoints,
                 ))
         }
         self.inner
@@ -206,6 +629,120 @@ impl QueryOptions {
         self
     }
 
+    /// Adds a handler for the values of the literals of a custom datatype.
+    ///
+    /// It is used to compare these literals in operators, `ORDER BY`, `MIN` and `MAX`.
+    /// See [`LiteralValueHandler`] for an example.
+    #[inline]
+    #[must_use]
+    pub fn with_literal_value_handler(
+        mut self,
+        datatype: NamedNode,
+        handler: impl LiteralValueHandler + 'static,
+    ) -> Self {
+        self.inner = self.inner.with_literal_value_handler(datatype, handler);
+        self
+    }
+
+    /// Sets what happens when an arithmetic operation on `xsd:integer` values overflows.
+    ///
+    /// By default, the overflow is an evaluation error and the result is left unbound.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::model::vocab::xsd;
+    /// use oxigraph::sparql::{IntegerOverflowBehavior, QueryOptions, QueryResults};
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// if let QueryResults::Solutions(mut solutions) = store.query_opt(
+    ///     "SELECT (9223372036854775807 + 1 AS ?v) WHERE {}",
+    ///     QueryOptions::default()
+    ///         .with_integer_overflow_behavior(IntegerOverflowBehavior::PromoteToDecimal),
+    /// )? {
+    ///     assert_eq!(
+    ///         solutions.next().unwrap()?.get("v"),
+    ///         Some(&Literal::new_typed_literal("9223372036854775808", xsd::DECIMAL).into())
+    ///     );
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_integer_overflow_behavior(mut self, behavior: IntegerOverflowBehavior) -> Self {
+        self.inner = self.inner.with_integer_overflow_behavior(behavior);
+        self
+    }
+
+    /// Evaluates the joins with a [SPARQL 1.1 Federated Query](https://www.w3.org/TR/sparql11-federated-query/) SERVICE call as bind joins:
+    /// the other side of the join is evaluated first and its bindings are sent to the service in `VALUES` blocks of at most `batch_size` rows.
+    ///
+    /// It massively reduces the number of results returned by the service for selective joins.
+    /// The [`ServiceHandler`] and [`DefaultServiceHandler`] then get graph patterns joined with a `VALUES` block.
+    ///
+    /// By default, the SERVICE patterns are sent without any binding.
+    ///
+    /// ```
+    /// use oxigraph::sparql::QueryOptions;
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// store.query_opt(
+    ///     "SELECT * WHERE { ?s a ?type SERVICE <https://query.wikidata.org/sparql> { ?s ?p ?o } }",
+    ///     QueryOptions::default()
+    ///         .with_service_batch_size(100)
+    ///         .with_service_parallelism(4),
+    /// )?;
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_service_batch_size(mut self, batch_size: usize) -> Self {
+        self.inner = self.inner.with_service_batch_size(batch_size);
+        self
+    }
+
+    /// Sets the maximal number of SERVICE calls of a bind join executed at the same time.
+    ///
+    /// It is only used if bind joins are enabled with [`with_service_batch_size`](Self::with_service_batch_size).
+    /// By default, the calls are executed one after the other.
+    #[inline]
+    #[must_use]
+    pub fn with_service_parallelism(mut self, parallelism: usize) -> Self {
+        self.inner = self.inner.with_service_parallelism(parallelism);
+        self
+    }
+
+    /// Binds the given variable to the name of the [SPARQL 1.1 Federated Query](https://www.w3.org/TR/sparql11-federated-query/) SERVICE each solution comes from.
+    ///
+    /// It allows to weigh the integrated data by the trust in their source.
+    /// The variable must be explicitly selected in the `SELECT` clause (`SELECT *` does not include it)
+    /// or used in the `GROUP BY` clause or the `CONSTRUCT` template, for example to annotate the returned triples with their source.
+    /// If a solution combines the results of several SERVICE calls, the variable is bound to the name of the first one in the query.
+    ///
+    /// ```no_run
+    /// use oxigraph::model::Variable;
+    /// use oxigraph::sparql::QueryOptions;
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// store.query_opt(
+    ///     "SELECT ?s ?source WHERE {
+    ///         { SERVICE <https://query.wikidata.org/sparql> { ?s a ?type } }
+    ///         UNION
+    ///         { SERVICE <https://dbpedia.org/sparql> { ?s a ?type } }
+    ///     }",
+    ///     QueryOptions::default().with_service_source_variable(Variable::new("source")?),
+    /// )?;
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_service_source_variable(mut self, variable: Variable) -> Self {
+        self.inner = self.inner.with_service_source_variable(variable);
+        self
+    }
+
     #[doc(hidden)]
     #[inline]
     #[must_use]
@@ -223,7 +760,26 @@ impl Default for QueryOptions {
             #[cfg(feature = "http-client")]
             http_redirection_limit: 0,
             #[cfg(feature = "http-client")]
+            http_retry_policy: HttpRetryPolicy::new(0),
+            #[cfg(feature = "http-client")]
             with_http_default_service_handler: true,
+            #[cfg(feature = "http-client")]
+            http_accepted_rdf_formats: vec![
+                RdfFormat::NTriples,
+                RdfFormat::Turtle,
+                RdfFormat::RdfXml,
+            ],
+            #[cfg(feature = "http-client")]
+            http_service_endpoints: Vec::new(),
+            timeout: None,
+            #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+            deadline: None,
+            cancellation_token: None,
+            default_graphs: None,
+            named_graphs: None,
+            values: None,
+            substitutions: Vec::new(),
+            progress: None,
             inner: QueryEvaluator::new(),
         }
     }
@@ -233,11 +789,123 @@ impl Default for QueryOptions {
 #[derive(Clone, Default)]
 pub struct UpdateOptions {
     query_options: QueryOptions,
+    max_load_size: Option<u64>,
+}
+
+impl UpdateOptions {
+    /// Sets the maximal size in bytes of the documents fetched by the `LOAD` operations.
+    ///
+    /// The documents are streamed into the store without being fully buffered in memory.
+    /// A larger document makes the `LOAD` operation fail with [`HttpError::PayloadTooLarge`],
+    /// before reading its content if the server advertises its length.
+    /// There is no limit by default.
+    ///
+    /// ```
+    /// use oxigraph::sparql::{QueryOptions, UpdateOptions};
+    ///
+    /// let options = UpdateOptions::from(QueryOptions::default()).with_max_load_size(1 << 30);
+    /// # let _ = options;
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_max_load_size(mut self, max_size: u64) -> Self {
+        self.max_load_size = Some(max_size);
+        self
+    }
 }
 
 impl From<QueryOptions> for UpdateOptions {
     #[inline]
     fn from(query_options: QueryOptions) -> Self {
-        Self { query_options }
+        Self {
+            query_options,
+            max_load_size: None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::model::{BlankNode, Literal};
+    use crate::store::Store;
+
+    #[test]
+    fn test_with_values() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Store::new()?;
+        store.update("INSERT DATA { <http://example.com/a> <http://example.com/p> 1 , 2 . <http://example.com/b> <http://example.com/p> 3 }")?;
+        let s = Variable::new("s")?;
+        let label = Variable::new("label")?;
+        let a = Term::from(NamedNode::new("http://example.com/a")?);
+        let b = Term::from(NamedNode::new("http://example.com/b")?);
+        let QueryResults::Solutions(solutions) = store.query_opt(
+            "SELECT ?o ?label WHERE { ?s <http://example.com/p> ?o } ORDER BY DESC(?o) LIMIT 2",
+            QueryOptions::default().with_values([
+                vec![(s.clone(), a), (label.clone(), Literal::from("A").into())],
+                vec![(s.clone(), b)],
+            ]),
+        )?
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            solutions
+                .map(|s| {
+                    let s = s?;
+                    Ok((s.get("o").cloned(), s.get("label").cloned()))
+                })
+                .collect::<Result<Vec<_>, EvaluationError>>()?,
+            vec![
+                (Some(Literal::from(3).into()), None),
+                (
+                    Some(Literal::from(2).into()),
+                    Some(Literal::from("A").into())
+                )
+            ]
+        );
+        assert!(matches!(
+            store.query_opt(
+                "ASK { ?s ?p ?o }",
+                QueryOptions::default().with_values([[(s, BlankNode::default().into())]])
+            ),
+            Err(EvaluationError::InvalidValuesTerm(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_substitutions() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Store::new()?;
+        store.update("INSERT DATA { <http://example.com/a> <http://example.com/p> 1 , 2 . <http://example.com/b> <http://example.com/p> 3 }")?;
+        let s = Variable::new("s")?;
+        let a = Term::from(NamedNode::new("http://example.com/a")?);
+        let QueryResults::Solutions(solutions) = store.query_opt(
+            "SELECT ?s ?o WHERE { ?s <http://example.com/p> ?o } ORDER BY ?o",
+            QueryOptions::default().with_substitutions([(s.clone(), a.clone())]),
+        )?
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            solutions
+                .map(|s| {
+                    let s = s?;
+                    Ok((s.get("s").cloned(), s.get("o").cloned()))
+                })
+                .collect::<Result<Vec<_>, EvaluationError>>()?,
+            vec![
+                (Some(a.clone()), Some(Literal::from(1).into())),
+                (Some(a.clone()), Some(Literal::from(2).into()))
+            ]
+        );
+        assert!(matches!(
+            store.query_opt(
+                "SELECT ?o WHERE { ?s <http://example.com/p> ?o }",
+                QueryOptions::default().with_substitutions([(s, a)])
+            ),
+            Err(EvaluationError::NotExistingSubstitutedVariable(_))
+        ));
+        Ok(())
     }
 }
@@ -5,11 +5,14 @@ use crate::sparql::results::{
     QueryResultsFormat, QueryResultsParseError, QueryResultsParser, QueryResultsSerializer,
     ReaderQueryResultsParserOutput, ReaderSolutionsParser,
 };
+use siphasher::sip128::{Hasher128, SipHasher24};
 pub use sparesults::QuerySolution;
 use spareval::{
     QueryEvaluationError, QueryResults as EvalQueryResults,
     QuerySolutionIter as EvalQuerySolutionIter, QueryTripleIter as EvalQueryTripleIter,
 };
+use std::fmt;
+use std::hash::Hasher;
 use std::io::{Read, Write};
 use std::sync::Arc;
 
@@ -138,6 +141,218 @@ impl QueryResults {
             Err(EvaluationError::NotAGraph)
         }
     }
+
+    /// Computes a digest of the results that does not depend on their order.
+    ///
+    /// The results are consumed in a streaming way: the digest is computed with constant memory.
+    /// Two results with the same digest are very likely equal, making it usable to detect if the results of a query changed
+    /// (e.g. as an HTTP `ETag`) without having to store or transfer them.
+    ///
+    /// The blank node identifiers are ignored: all blank nodes are considered equal.
+    /// Use [`QueryResults::ordered_digest`] to also take the results order into account.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
+    /// store.insert(QuadRef::new(ex, ex, ex, ex))?;
+    ///
+    /// assert_eq!(
+    ///     store.query("SELECT ?g WHERE { GRAPH ?g {} } ORDER BY ?g")?.digest()?,
+    ///     store.query("SELECT ?g WHERE { GRAPH ?g {} } ORDER BY DESC(?g)")?.digest()?
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn digest(self) -> Result<QueryResultsDigest, EvaluationError> {
+        self.compute_digest(false)
+    }
+
+    /// Computes a digest of the results that depends on their order.
+    ///
+    /// See [`QueryResults::digest`] for the order-independent version.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// store.insert(QuadRef::new(
+    ///     NamedNodeRef::new("http://example.com/a")?,
+    ///     NamedNodeRef::new("http://example.com/p")?,
+    ///     NamedNodeRef::new("http://example.com/b")?,
+    ///     GraphNameRef::DefaultGraph,
+    /// ))?;
+    ///
+    /// assert_ne!(
+    ///     store
+    ///         .query("SELECT ?v WHERE { { ?v ?p ?o } UNION { ?s ?p ?v } } ORDER BY ?v")?
+    ///         .ordered_digest()?,
+    ///     store
+    ///         .query("SELECT ?v WHERE { { ?v ?p ?o } UNION { ?s ?p ?v } } ORDER BY DESC(?v)")?
+    ///         .ordered_digest()?
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn ordered_digest(self) -> Result<QueryResultsDigest, EvaluationError> {
+        self.compute_digest(true)
+    }
+
+    fn compute_digest(self, ordered: bool) -> Result<QueryResultsDigest, EvaluationError> {
+        let mut digest = DigestBuilder::new(ordered);
+        match self {
+            Self::Boolean(value) => {
+                digest.write_header(b"boolean");
+                digest.add_row(|hasher| hasher.write_u8(value.into()));
+            }
+            Self::Solutions(solutions) => {
+                digest.write_header(b"solutions");
+                for variable in solutions.variables() {
+                    digest.write_header(variable.as_str().as_bytes());
+                }
+                for solution in solutions {
+                    let solution = solution?;
+                    digest.add_row(|hasher| {
+                        for value in solution.values() {
+                            if let Some(value) = value {
+                                write_digest_term(hasher, value.as_ref());
+                            } else {
+                                hasher.write_u8(DIGEST_UNBOUND);
+                            }
+                        }
+                    });
+                }
+            }
+            Self::Graph(triples) => {
+                digest.write_header(b"graph");
+                for triple in triples {
+                    let triple = triple?;
+                    digest.add_row(|hasher| write_digest_triple(hasher, triple.as_ref()));
+                }
+            }
+        }
+        Ok(digest.finish())
+    }
+}
+
+/// A digest of [`QueryResults`] computed by [`QueryResults::digest`] or [`QueryResults::ordered_digest`].
+///
+/// Its string representation is a 32 characters hexadecimal string.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+pub struct QueryResultsDigest {
+    value: u128,
+}
+
+impl QueryResultsDigest {
+    /// The digest as bytes.
+    #[inline]
+    pub fn to_be_bytes(self) -> [u8; 16] {
+        self.value.to_be_bytes()
+    }
+}
+
+impl fmt::Display for QueryResultsDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.value)
+    }
+}
+
+/// Markers written between the strings to make the digest input unambiguous (they are never found in UTF-8)
+const DIGEST_SEPARATOR: u8 = 0xFF;
+const DIGEST_UNBOUND: u8 = 0xFE;
+#[cfg(feature = "rdf-12")]
+const DIGEST_TRIPLE: u8 = 0xFD;
+
+struct DigestBuilder {
+    ordered: bool,
+    header: SipHasher24,
+    rows: SipHasher24,
+    rows_sum: u128,
+    rows_count: u64,
+}
+
+impl DigestBuilder {
+    fn new(ordered: bool) -> Self {
+        Self {
+            ordered,
+            header: SipHasher24::new(),
+            rows: SipHasher24::new(),
+            rows_sum: 0,
+            rows_count: 0,
+        }
+    }
+
+    fn write_header(&mut self, value: &[u8]) {
+        self.header.write(value);
+        self.header.write_u8(DIGEST_SEPARATOR);
+    }
+
+    fn add_row(&mut self, write: impl FnOnce(&mut SipHasher24)) {
+        let mut hasher = SipHasher24::new();
+        write(&mut hasher);
+        let row = u128::from(hasher.finish128());
+        if self.ordered {
+            self.rows.write_u128(row);
+        } else {
+            // Addition is commutative so the order does not matter while duplicates are still counted
+            self.rows_sum = self.rows_sum.wrapping_add(row);
+        }
+        self.rows_count += 1;
+    }
+
+    fn finish(mut self) -> QueryResultsDigest {
+        self.header.write_u64(self.rows_count);
+        self.header.write_u128(if self.ordered {
+            u128::from(self.rows.finish128())
+        } else {
+            self.rows_sum
+        });
+        QueryResultsDigest {
+            value: u128::from(self.header.finish128()),
+        }
+    }
+}
+
+fn write_digest_term(hasher: &mut SipHasher24, term: TermRef<'_>) {
+    match term {
+        TermRef::NamedNode(node) => {
+            hasher.write_u8(b'<');
+            hasher.write(node.as_str().as_bytes());
+        }
+        TermRef::BlankNode(_) => hasher.write_u8(b'_'),
+        TermRef::Literal(literal) => {
+            hasher.write_u8(b'"');
+            hasher.write(literal.value().as_bytes());
+            hasher.write_u8(DIGEST_SEPARATOR);
+            hasher.write(literal.datatype().as_str().as_bytes());
+            if let Some(language) = literal.language() {
+                hasher.write_u8(DIGEST_SEPARATOR);
+                hasher.write(language.as_bytes());
+            }
+            #[cfg(feature = "rdf-12")]
+            if let Some(direction) = literal.direction() {
+                hasher.write_u8(DIGEST_SEPARATOR);
+                hasher.write(match direction {
+                    BaseDirection::Ltr => b"ltr",
+                    BaseDirection::Rtl => b"rtl",
+                });
+            }
+        }
+        #[cfg(feature = "rdf-12")]
+        TermRef::Triple(triple) => {
+            hasher.write_u8(DIGEST_TRIPLE);
+            write_digest_triple(hasher, triple.as_ref());
+        }
+    }
+    hasher.write_u8(DIGEST_SEPARATOR);
+}
+
+fn write_digest_triple(hasher: &mut SipHasher24, triple: TripleRef<'_>) {
+    write_digest_term(hasher, triple.subject.into());
+    write_digest_term(hasher, triple.predicate.into());
+    write_digest_term(hasher, triple.object);
 }
 
 impl From<EvalQueryResults> for QueryResults {
@@ -281,6 +496,17 @@ pub struct QueryTripleIter {
     inner: EvalQueryTripleIter,
 }
 
+impl QueryTripleIter {
+    /// Construct a new iterator of triples from an iterator of triples or errors
+    pub fn new(iter: impl Iterator<Item = Result<Triple, EvaluationError>> + 'static) -> Self {
+        Self {
+            inner: EvalQueryTripleIter::new(
+                iter.map(|t| t.map_err(|e| QueryEvaluationError::Service(Box::new(e)))),
+            ),
+        }
+    }
+}
+
 impl From<EvalQueryTripleIter> for QueryTripleIter {
     #[inline]
     fn from(inner: EvalQueryTripleIter) -> Self {
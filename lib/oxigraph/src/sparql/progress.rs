@@ -0,0 +1,195 @@
+use spareval::{
+    QueryResults as EvalQueryResults, QuerySolutionIter as EvalQuerySolutionIter,
+    QueryTripleIter as EvalQueryTripleIter,
+};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+#[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+use std::time::Instant;
+
+/// A live view on the progress of a query evaluation.
+///
+/// It is given to the evaluation with [`QueryOptions::with_progress`](super::QueryOptions::with_progress)
+/// and might be polled from another thread while the results are consumed,
+/// for example to display a row counter for long-running queries.
+/// Cloning it returns a view on the same evaluation.
+///
+/// ```
+/// use oxigraph::sparql::{QueryOptions, QueryProgress, QueryResults};
+/// use oxigraph::store::Store;
+///
+/// let store = Store::new()?;
+/// store.update("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 , 2 , 3 }")?;
+/// let progress = QueryProgress::new();
+/// if let QueryResults::Solutions(solutions) = store.query_opt(
+///     "SELECT * WHERE { ?s ?p ?o }",
+///     QueryOptions::default().with_progress(progress.clone()),
+/// )? {
+///     assert!(!progress.is_finished());
+///     for solution in solutions {
+///         solution?;
+///     }
+/// }
+/// assert_eq!(progress.result_count(), 3);
+/// assert_eq!(progress.scan_count(), 1);
+/// assert_eq!(progress.read_quad_count(), 3);
+/// assert!(progress.is_finished());
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Clone, Default)]
+pub struct QueryProgress {
+    inner: Arc<ProgressState>,
+}
+
+#[derive(Default)]
+struct ProgressState {
+    results: AtomicU64,
+    scans: AtomicU64,
+    read_quads: AtomicU64,
+    finished: AtomicBool,
+    #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+    start: OnceLock<Instant>,
+    #[cfg(all(feature = "js", target_family = "wasm", target_os = "unknown"))]
+    start: OnceLock<f64>,
+    #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+    end: OnceLock<Instant>,
+    #[cfg(all(feature = "js", target_family = "wasm", target_os = "unknown"))]
+    end: OnceLock<f64>,
+}
+
+impl QueryProgress {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of solutions, or triples for `CONSTRUCT` and `DESCRIBE` queries, returned so far.
+    #[inline]
+    pub fn result_count(&self) -> u64 {
+        self.inner.results.load(Ordering::Relaxed)
+    }
+
+    /// The number of lookups of a quad pattern done in the store so far.
+    ///
+    /// Each evaluation of a triple pattern or of a property path step is a lookup.
+    #[inline]
+    pub fn scan_count(&self) -> u64 {
+        self.inner.scans.load(Ordering::Relaxed)
+    }
+
+    /// The number of quads read from the store so far.
+    #[inline]
+    pub fn read_quad_count(&self) -> u64 {
+        self.inner.read_quads.load(Ordering::Relaxed)
+    }
+
+    /// The evaluation duration so far or `None` if the evaluation has not started yet.
+    ///
+    /// It stops growing when the evaluation is finished.
+    pub fn elapsed(&self) -> Option<Duration> {
+        let start = *self.inner.start.get()?;
+        #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+        {
+            Some(
+                self.inner
+                    .end
+                    .get()
+                    .copied()
+                    .unwrap_or_else(Instant::now)
+                    .saturating_duration_since(start),
+            )
+        }
+        #[cfg(all(feature = "js", target_family = "wasm", target_os = "unknown"))]
+        {
+            let end = self
+                .inner
+                .end
+                .get()
+                .copied()
+                .unwrap_or_else(js_sys::Date::now);
+            Some(Duration::from_secs_f64((end - start).max(0.) / 1000.))
+        }
+    }
+
+    /// Checks if all the results have been returned or if the results iterator has been dropped.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.inner.finished.load(Ordering::Acquire)
+    }
+
+    pub(super) fn start(&self) {
+        #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+        self.inner.start.get_or_init(Instant::now);
+        #[cfg(all(feature = "js", target_family = "wasm", target_os = "unknown"))]
+        self.inner.start.get_or_init(js_sys::Date::now);
+    }
+
+    fn finish(&self) {
+        #[cfg(not(all(feature = "js", target_family = "wasm", target_os = "unknown")))]
+        self.inner.end.get_or_init(Instant::now);
+        #[cfg(all(feature = "js", target_family = "wasm", target_os = "unknown"))]
+        self.inner.end.get_or_init(js_sys::Date::now);
+        self.inner.finished.store(true, Ordering::Release);
+    }
+
+    pub(super) fn add_scan(&self) {
+        self.inner.scans.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn add_read_quad(&self) {
+        self.inner.read_quads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts the results while they are consumed
+    pub(super) fn track(&self, results: EvalQueryResults) -> EvalQueryResults {
+        match results {
+            EvalQueryResults::Solutions(solutions) => {
+                EvalQueryResults::Solutions(EvalQuerySolutionIter::new(
+                    solutions.variables().into(),
+                    ProgressIter {
+                        inner: solutions,
+                        progress: self.clone(),
+                    },
+                ))
+            }
+            EvalQueryResults::Boolean(value) => {
+                self.finish();
+                EvalQueryResults::Boolean(value)
+            }
+            EvalQueryResults::Graph(triples) => {
+                EvalQueryResults::Graph(EvalQueryTripleIter::new(ProgressIter {
+                    inner: triples,
+                    progress: self.clone(),
+                }))
+            }
+        }
+    }
+}
+
+/// Counts the items of an iterator in a [`QueryProgress`] and marks it as finished at the end
+struct ProgressIter<I> {
+    inner: I,
+    progress: QueryProgress,
+}
+
+impl<T, E, I: Iterator<Item = Result<T, E>>> Iterator for ProgressIter<I> {
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(result) = self.inner.next() else {
+            self.progress.finish();
+            return None;
+        };
+        if result.is_ok() {
+            self.progress.inner.results.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(result)
+    }
+}
+
+impl<I> Drop for ProgressIter<I> {
+    fn drop(&mut self) {
+        self.progress.finish();
+    }
+}
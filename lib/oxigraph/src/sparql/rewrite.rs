@@ -0,0 +1,713 @@
+use crate::model::{BlankNode, NamedNode};
+use crate::storage::Storage;
+use spargebra::algebra::{
+    AggregateExpression, Expression, GraphPattern, OrderExpression, PropertyPathExpression,
+};
+use spargebra::term::{GroundTerm, NamedNodePattern, TermPattern, TriplePattern};
+use std::collections::{HashMap, HashSet};
+use std::mem::take;
+use std::sync::Arc;
+
+const OWL_SAME_AS: &str = "http://www.w3.org/2002/07/owl#sameAs";
+const TEXT_SEARCH: &str = "http://oxigraph.org/text#search";
+
+/// A rewriting of the SPARQL queries applied before their evaluation.
+///
+/// The rewriter is called on each triple pattern and property path of the query `WHERE` clause, including in `EXISTS` filters.
+/// The `SERVICE` calls and the `CONSTRUCT` templates are not rewritten.
+///
+/// It is registered with [`Store::with_query_rewriter`](crate::store::Store::with_query_rewriter).
+/// See [`VocabularyMapping`] and [`SameAsExpansion`] for built-in rewriters.
+pub trait QueryRewriter: Send + Sync {
+    /// Rewrites a triple pattern of the query.
+    ///
+    /// By default, it is kept as is.
+    fn rewrite_triple_pattern(&self, pattern: TriplePattern) -> GraphPattern {
+        GraphPattern::Bgp {
+            patterns: vec![pattern],
+        }
+    }
+
+    /// Rewrites a property path pattern of the query.
+    ///
+    /// By default, it is kept as is.
+    fn rewrite_path_pattern(
+        &self,
+        subject: TermPattern,
+        path: PropertyPathExpression,
+        object: TermPattern,
+    ) -> GraphPattern {
+        GraphPattern::Path {
+            subject,
+            path,
+            object,
+        }
+    }
+}
+
+/// A [`QueryRewriter`] replacing some IRIs of the query patterns by other IRIs.
+///
+/// It allows legacy queries to keep working after a vocabulary migration.
+/// The IRIs are only replaced in the query patterns: the `CONSTRUCT` templates still use the old vocabulary.
+///
+/// ```
+/// use oxigraph::model::{Literal, NamedNode};
+/// use oxigraph::sparql::{QueryResults, VocabularyMapping};
+/// use oxigraph::store::Store;
+///
+/// let store = Store::new()?.with_query_rewriter(VocabularyMapping::new().with_mapping(
+///     NamedNode::new("http://xmlns.com/foaf/0.1/name")?,
+///     NamedNode::new("http://schema.org/name")?,
+/// ));
+/// store.update("INSERT DATA { <http://example.com/a> <http://schema.org/name> \"a\" }")?;
+/// if let QueryResults::Solutions(mut solutions) =
+///     store.query("SELECT ?n WHERE { ?s <http://xmlns.com/foaf/0.1/name> ?n }")?
+/// {
+///     assert_eq!(
+///         solutions.next().unwrap()?.get("n"),
+///         Some(&Literal::from("a").into())
+///     );
+/// }
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+pub struct VocabularyMapping {
+    mapping: HashMap<NamedNode, NamedNode>,
+}
+
+impl VocabularyMapping {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces `from` by `to` in the query patterns.
+    #[inline]
+    #[must_use]
+    pub fn with_mapping(mut self, from: NamedNode, to: NamedNode) -> Self {
+        self.mapping.insert(from, to);
+        self
+    }
+
+    fn map_iri(&self, iri: NamedNode) -> NamedNode {
+        self.mapping.get(&iri).cloned().unwrap_or(iri)
+    }
+
+    fn map_term(&self, term: TermPattern) -> TermPattern {
+        match term {
+            TermPattern::NamedNode(iri) => self.map_iri(iri).into(),
+            #[cfg(feature = "rdf-12")]
+            TermPattern::Triple(triple) => TermPattern::Triple(Box::new(TriplePattern {
+                subject: self.map_term(triple.subject),
+                predicate: self.map_predicate(triple.predicate),
+                object: self.map_term(triple.object),
+            })),
+            other => other,
+        }
+    }
+
+    fn map_predicate(&self, predicate: NamedNodePattern) -> NamedNodePattern {
+        match predicate {
+            NamedNodePattern::NamedNode(iri) => self.map_iri(iri).into(),
+            NamedNodePattern::Variable(variable) => variable.into(),
+        }
+    }
+
+    fn map_path(&self, path: PropertyPathExpression) -> PropertyPathExpression {
+        map_path(
+            path,
+            &|iri| PropertyPathExpression::NamedNode(self.map_iri(iri)),
+            &|set| set.into_iter().map(|iri| self.map_iri(iri)).collect(),
+        )
+    }
+}
+
+impl QueryRewriter for VocabularyMapping {
+    fn rewrite_triple_pattern(&self, pattern: TriplePattern) -> GraphPattern {
+        GraphPattern::Bgp {
+            patterns: vec![TriplePattern {
+                subject: self.map_term(pattern.subject),
+                predicate: self.map_predicate(pattern.predicate),
+                object: self.map_term(pattern.object),
+            }],
+        }
+    }
+
+    fn rewrite_path_pattern(
+        &self,
+        subject: TermPattern,
+        path: PropertyPathExpression,
+        object: TermPattern,
+    ) -> GraphPattern {
+        GraphPattern::Path {
+            subject: self.map_term(subject),
+            path: self.map_path(path),
+            object: self.map_term(object),
+        }
+    }
+}
+
+/// A [`QueryRewriter`] making the given predicates match across `owl:sameAs` links.
+///
+/// A `?s ex:p ?o` pattern is rewritten to `?s (owl:sameAs|^owl:sameAs)*/ex:p/(owl:sameAs|^owl:sameAs)* ?o`
+/// if `ex:p` is one of the given predicates.
+///
+/// <div class="warning">The closure is computed at each evaluation: if neither the subject nor the object is bound,
+/// the pattern evaluation iterates over all the terms of the dataset.</div>
+///
+/// ```
+/// use oxigraph::model::{Literal, NamedNode};
+/// use oxigraph::sparql::{QueryResults, SameAsExpansion};
+/// use oxigraph::store::Store;
+///
+/// let store = Store::new()?.with_query_rewriter(
+///     SameAsExpansion::new([NamedNode::new("http://schema.org/name")?]),
+/// );
+/// store.update(
+///     "INSERT DATA {
+///         <http://example.com/a> <http://www.w3.org/2002/07/owl#sameAs> <http://example.org/a> .
+///         <http://example.org/a> <http://schema.org/name> \"a\"
+///     }",
+/// )?;
+/// if let QueryResults::Solutions(mut solutions) =
+///     store.query("SELECT ?n WHERE { <http://example.com/a> <http://schema.org/name> ?n }")?
+/// {
+///     assert_eq!(
+///         solutions.next().unwrap()?.get("n"),
+///         Some(&Literal::from("a").into())
+///     );
+/// }
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+pub struct SameAsExpansion {
+    predicates: HashSet<NamedNode>,
+}
+
+impl SameAsExpansion {
+    #[inline]
+    pub fn new(predicates: impl IntoIterator<Item = NamedNode>) -> Self {
+        Self {
+            predicates: predicates.into_iter().collect(),
+        }
+    }
+
+    fn expand(&self, predicate: NamedNode) -> PropertyPathExpression {
+        if !self.predicates.contains(&predicate) {
+            return predicate.into();
+        }
+        around_identity_closure(
+            predicate.into(),
+            &identity_closure(NamedNode::new_unchecked(OWL_SAME_AS)),
+        )
+    }
+}
+
+impl QueryRewriter for SameAsExpansion {
+    fn rewrite_triple_pattern(&self, pattern: TriplePattern) -> GraphPattern {
+        match pattern.predicate {
+            NamedNodePattern::NamedNode(predicate) if self.predicates.contains(&predicate) => {
+                GraphPattern::Path {
+                    subject: pattern.subject,
+                    path: self.expand(predicate),
+                    object: pattern.object,
+                }
+            }
+            predicate => GraphPattern::Bgp {
+                patterns: vec![TriplePattern {
+                    subject: pattern.subject,
+                    predicate,
+                    object: pattern.object,
+                }],
+            },
+        }
+    }
+
+    fn rewrite_path_pattern(
+        &self,
+        subject: TermPattern,
+        path: PropertyPathExpression,
+        object: TermPattern,
+    ) -> GraphPattern {
+        GraphPattern::Path {
+            subject,
+            path: map_path(path, &|iri| self.expand(iri), &|set| set),
+            object,
+        }
+    }
+}
+
+/// A [`QueryRewriter`] treating the resources linked by `owl:sameAs` (or another identity predicate) as a single node.
+///
+/// It is an identity resolution layer for datasets integrated from multiple sources:
+/// the identity links are followed in both directions and transitively on both ends of all the triple patterns and property paths,
+/// so a pattern matches the triples of all the aliases of its subject and object.
+/// Unlike [`SameAsExpansion`], all the predicates are expanded.
+/// The patterns using the identity predicate itself are kept as is.
+///
+/// Each alias of a resource is returned in a different solution when the subject or the object is not bound:
+/// use `SELECT DISTINCT` and bind the resources of interest to avoid duplicates.
+///
+/// <div class="warning">The closure is computed at each evaluation: the queries are slower, especially with many identity links.</div>
+///
+/// ```
+/// use oxigraph::model::{Literal, NamedNode};
+/// use oxigraph::sparql::{IdentityResolution, QueryResults};
+/// use oxigraph::store::Store;
+///
+/// let store = Store::new()?.with_query_rewriter(IdentityResolution::new().with_identity_predicate(
+///     NamedNode::new("http://www.w3.org/2004/02/skos/core#exactMatch")?,
+/// ));
+/// store.update(
+///     "INSERT DATA {
+///         <http://example.com/a> <http://www.w3.org/2004/02/skos/core#exactMatch> <http://example.org/a> .
+///         <http://example.org/a> <http://schema.org/name> \"a\"
+///     }",
+/// )?;
+/// if let QueryResults::Solutions(mut solutions) = store.query(
+///     "SELECT ?p ?o WHERE { <http://example.com/a> ?p ?o FILTER(?p = <http://schema.org/name>) }",
+/// )? {
+///     assert_eq!(
+///         solutions.next().unwrap()?.get("o"),
+///         Some(&Literal::from("a").into())
+///     );
+/// }
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Clone)]
+pub struct IdentityResolution {
+    identity_predicate: NamedNode,
+    closure: PropertyPathExpression,
+}
+
+impl IdentityResolution {
+    /// Builds an identity resolution using `owl:sameAs`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the predicate linking the aliases of a resource, `owl:sameAs` by default.
+    #[inline]
+    #[must_use]
+    pub fn with_identity_predicate(mut self, predicate: NamedNode) -> Self {
+        self.closure = identity_closure(predicate.clone());
+        self.identity_predicate = predicate;
+        self
+    }
+
+    fn expand(&self, predicate: NamedNode) -> PropertyPathExpression {
+        if predicate == self.identity_predicate {
+            return predicate.into();
+        }
+        around_identity_closure(predicate.into(), &self.closure)
+    }
+}
+
+impl Default for IdentityResolution {
+    fn default() -> Self {
+        let identity_predicate = NamedNode::new_unchecked(OWL_SAME_AS);
+        Self {
+            closure: identity_closure(identity_predicate.clone()),
+            identity_predicate,
+        }
+    }
+}
+
+impl QueryRewriter for IdentityResolution {
+    fn rewrite_triple_pattern(&self, pattern: TriplePattern) -> GraphPattern {
+        match pattern.predicate {
+            NamedNodePattern::NamedNode(predicate) if predicate == self.identity_predicate => {
+                GraphPattern::Bgp {
+                    patterns: vec![TriplePattern {
+                        subject: pattern.subject,
+                        predicate: predicate.into(),
+                        object: pattern.object,
+                    }],
+                }
+            }
+            NamedNodePattern::NamedNode(predicate) => GraphPattern::Path {
+                subject: pattern.subject,
+                path: self.expand(predicate),
+                object: pattern.object,
+            },
+            NamedNodePattern::Variable(predicate) => {
+                // Property paths do not support variables: we join the closures to the triple pattern
+                let subject_alias = TermPattern::from(BlankNode::default());
+                let object_alias = TermPattern::from(BlankNode::default());
+                let triple = GraphPattern::Bgp {
+                    patterns: vec![TriplePattern {
+                        subject: subject_alias.clone(),
+                        predicate: predicate.into(),
+                        object: object_alias.clone(),
+                    }],
+                };
+                let subject_closure = GraphPattern::Path {
+                    subject: pattern.subject,
+                    path: self.closure.clone(),
+                    object: subject_alias,
+                };
+                let object_closure = GraphPattern::Path {
+                    subject: object_alias,
+                    path: self.closure.clone(),
+                    object: pattern.object,
+                };
+                GraphPattern::Join {
+                    left: Box::new(GraphPattern::Join {
+                        left: Box::new(triple),
+                        right: Box::new(subject_closure),
+                    }),
+                    right: Box::new(object_closure),
+                }
+            }
+        }
+    }
+
+    fn rewrite_path_pattern(
+        &self,
+        subject: TermPattern,
+        path: PropertyPathExpression,
+        object: TermPattern,
+    ) -> GraphPattern {
+        GraphPattern::Path {
+            subject,
+            path: map_path(path, &|iri| self.expand(iri), &|set| set),
+            object,
+        }
+    }
+}
+
+/// Evaluates the `text:search` magic predicate using the full-text index of the storage.
+///
+/// See [`Store::with_full_text_index`](crate::store::Store::with_full_text_index).
+pub(crate) struct FullTextSearch {
+    storage: Storage,
+}
+
+impl FullTextSearch {
+    pub(crate) fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+}
+
+impl QueryRewriter for FullTextSearch {
+    fn rewrite_triple_pattern(&self, pattern: TriplePattern) -> GraphPattern {
+        let (NamedNodePattern::NamedNode(predicate), TermPattern::Literal(query)) =
+            (&pattern.predicate, &pattern.object)
+        else {
+            return GraphPattern::Bgp {
+                patterns: vec![pattern],
+            };
+        };
+        if predicate.as_str() != TEXT_SEARCH {
+            return GraphPattern::Bgp {
+                patterns: vec![pattern],
+            };
+        }
+        let matches = self.storage.full_text_search(query.value());
+        match pattern.subject {
+            TermPattern::Variable(variable) => GraphPattern::Values {
+                variables: vec![variable],
+                bindings: matches
+                    .into_iter()
+                    .map(|(literal, _)| vec![Some(GroundTerm::Literal(literal))])
+                    .collect(),
+            },
+            TermPattern::Literal(literal) => GraphPattern::Values {
+                variables: Vec::new(),
+                bindings: if matches.iter().any(|(l, _)| *l == literal) {
+                    vec![Vec::new()]
+                } else {
+                    Vec::new()
+                },
+            },
+            // Only literals are indexed
+            _ => GraphPattern::Values {
+                variables: Vec::new(),
+                bindings: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Builds `(predicate|^predicate)*`
+fn identity_closure(predicate: NamedNode) -> PropertyPathExpression {
+    let predicate = PropertyPathExpression::NamedNode(predicate);
+    PropertyPathExpression::ZeroOrMore(Box::new(PropertyPathExpression::Alternative(
+        Box::new(predicate.clone()),
+        Box::new(PropertyPathExpression::Reverse(Box::new(predicate))),
+    )))
+}
+
+/// Builds `closure/link/closure`
+fn around_identity_closure(
+    link: PropertyPathExpression,
+    closure: &PropertyPathExpression,
+) -> PropertyPathExpression {
+    PropertyPathExpression::Sequence(
+        Box::new(closure.clone()),
+        Box::new(PropertyPathExpression::Sequence(
+            Box::new(link),
+            Box::new(closure.clone()),
+        )),
+    )
+}
+
+/// Applies the rewriters to the query `WHERE` clause
+pub(crate) fn rewrite_query(query: &mut spargebra::Query, rewriters: &[Arc<dyn QueryRewriter>]) {
+    if rewriters.is_empty() {
+        return;
+    }
+    let (spargebra::Query::Select { pattern, .. }
+    | spargebra::Query::Construct { pattern, .. }
+    | spargebra::Query::Describe { pattern, .. }
+    | spargebra::Query::Ask { pattern, .. }) = query;
+    for rewriter in rewriters {
+        rewrite_pattern(pattern, rewriter.as_ref());
+    }
+}
+
+fn rewrite_pattern(pattern: &mut GraphPattern, rewriter: &dyn QueryRewriter) {
+    match pattern {
+        GraphPattern::Bgp { patterns } => {
+            let mut bgp = Vec::new();
+            let mut others = Vec::new();
+            for triple in take(patterns) {
+                match rewriter.rewrite_triple_pattern(triple) {
+                    GraphPattern::Bgp { patterns } => bgp.extend(patterns),
+                    other => others.push(other),
+                }
+            }
+            *pattern =
+                others
+                    .into_iter()
+                    .fold(GraphPattern::Bgp { patterns: bgp }, |left, right| {
+                        GraphPattern::Join {
+                            left: Box::new(left),
+                            right: Box::new(right),
+                        }
+                    });
+        }
+        GraphPattern::Path { .. } => {
+            let GraphPattern::Path {
+                subject,
+                path,
+                object,
+            } = take(pattern)
+            else {
+                unreachable!()
+            };
+            *pattern = rewriter.rewrite_path_pattern(subject, path, object);
+        }
+        GraphPattern::Join { left, right }
+        | GraphPattern::Lateral { left, right }
+        | GraphPattern::Minus { left, right }
+        | GraphPattern::Union { left, right } => {
+            rewrite_pattern(left, rewriter);
+            rewrite_pattern(right, rewriter);
+        }
+        GraphPattern::LeftJoin {
+            left,
+            right,
+            expression,
+        } => {
+            rewrite_pattern(left, rewriter);
+            rewrite_pattern(right, rewriter);
+            if let Some(expression) = expression {
+                rewrite_expression(expression, rewriter);
+            }
+        }
+        GraphPattern::Filter { expr, inner } => {
+            rewrite_pattern(inner, rewriter);
+            rewrite_expression(expr, rewriter);
+        }
+        GraphPattern::Extend {
+            inner, expression, ..
+        } => {
+            rewrite_pattern(inner, rewriter);
+            rewrite_expression(expression, rewriter);
+        }
+        GraphPattern::OrderBy { inner, expression } => {
+            rewrite_pattern(inner, rewriter);
+            for expression in expression {
+                let (OrderExpression::Asc(expression) | OrderExpression::Desc(expression)) =
+                    expression;
+                rewrite_expression(expression, rewriter);
+            }
+        }
+        GraphPattern::Group {
+            inner, aggregates, ..
+        } => {
+            rewrite_pattern(inner, rewriter);
+            for (_, aggregate) in aggregates {
+                if let AggregateExpression::FunctionCall { expr, .. } = aggregate {
+                    rewrite_expression(expr, rewriter);
+                }
+            }
+        }
+        GraphPattern::Graph { inner, .. }
+        | GraphPattern::Project { inner, .. }
+        | GraphPattern::Distinct { inner }
+        | GraphPattern::Reduced { inner }
+        | GraphPattern::Slice { inner, .. } => rewrite_pattern(inner, rewriter),
+        GraphPattern::Values { .. } | GraphPattern::Service { .. } => (),
+    }
+}
+
+fn rewrite_expression(expression: &mut Expression, rewriter: &dyn QueryRewriter) {
+    match expression {
+        Expression::NamedNode(_)
+        | Expression::Literal(_)
+        | Expression::Variable(_)
+        | Expression::Bound(_) => (),
+        Expression::Or(a, b)
+        | Expression::And(a, b)
+        | Expression::Equal(a, b)
+        | Expression::SameTerm(a, b)
+        | Expression::Greater(a, b)
+        | Expression::GreaterOrEqual(a, b)
+        | Expression::Less(a, b)
+        | Expression::LessOrEqual(a, b)
+        | Expression::Add(a, b)
+        | Expression::Subtract(a, b)
+        | Expression::Multiply(a, b)
+        | Expression::Divide(a, b) => {
+            rewrite_expression(a, rewriter);
+            rewrite_expression(b, rewriter);
+        }
+        Expression::UnaryPlus(e) | Expression::UnaryMinus(e) | Expression::Not(e) => {
+            rewrite_expression(e, rewriter)
+        }
+        Expression::Exists(pattern) => rewrite_pattern(pattern, rewriter),
+        Expression::If(a, b, c) => {
+            rewrite_expression(a, rewriter);
+            rewrite_expression(b, rewriter);
+            rewrite_expression(c, rewriter);
+        }
+        Expression::In(a, list) => {
+            rewrite_expression(a, rewriter);
+            for e in list {
+                rewrite_expression(e, rewriter);
+            }
+        }
+        Expression::Coalesce(list) | Expression::FunctionCall(_, list) => {
+            for e in list {
+                rewrite_expression(e, rewriter);
+            }
+        }
+    }
+}
+
+fn map_path(
+    path: PropertyPathExpression,
+    map_iri: &impl Fn(NamedNode) -> PropertyPathExpression,
+    map_negated_set: &impl Fn(Vec<NamedNode>) -> Vec<NamedNode>,
+) -> PropertyPathExpression {
+    let map =
+        |path: Box<PropertyPathExpression>| Box::new(map_path(*path, map_iri, map_negated_set));
+    match path {
+        PropertyPathExpression::NamedNode(iri) => map_iri(iri),
+        PropertyPathExpression::Reverse(inner) => PropertyPathExpression::Reverse(map(inner)),
+        PropertyPathExpression::Sequence(left, right) => {
+            PropertyPathExpression::Sequence(map(left), map(right))
+        }
+        PropertyPathExpression::Alternative(left, right) => {
+            PropertyPathExpression::Alternative(map(left), map(right))
+        }
+        PropertyPathExpression::ZeroOrMore(inner) => PropertyPathExpression::ZeroOrMore(map(inner)),
+        PropertyPathExpression::OneOrMore(inner) => PropertyPathExpression::OneOrMore(map(inner)),
+        PropertyPathExpression::ZeroOrOne(inner) => PropertyPathExpression::ZeroOrOne(map(inner)),
+        PropertyPathExpression::NegatedPropertySet(set) => {
+            PropertyPathExpression::NegatedPropertySet(map_negated_set(set))
+        }
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::sparql::QueryResults;
+    use crate::store::Store;
+    use std::error::Error;
+
+    fn store() -> Result<Store, Box<dyn Error>> {
+        let store = Store::new()?.with_query_rewriter(VocabularyMapping::new().with_mapping(
+            NamedNode::new("http://example.com/old")?,
+            NamedNode::new("http://example.com/new")?,
+        ));
+        store.update("INSERT DATA { <http://example.com/a> <http://example.com/new> <http://example.com/b> }")?;
+        Ok(store)
+    }
+
+    fn ask(store: &Store, query: &str) -> Result<bool, Box<dyn Error>> {
+        let QueryResults::Boolean(result) = store.query(query)? else {
+            return Err("ASK query expected".into());
+        };
+        Ok(result)
+    }
+
+    #[test]
+    fn test_vocabulary_mapping() -> Result<(), Box<dyn Error>> {
+        let store = store()?;
+        assert!(ask(
+            &store,
+            "ASK { <http://example.com/a> <http://example.com/old> ?o }"
+        )?);
+        assert!(ask(
+            &store,
+            "ASK { <http://example.com/a> <http://example.com/old>+ ?o }"
+        )?);
+        assert!(ask(
+            &store,
+            "ASK { ?s ?p ?o FILTER EXISTS { ?s <http://example.com/old> ?o } }"
+        )?);
+        assert!(!ask(&store, "ASK { ?s !<http://example.com/old> ?o }")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_vocabulary_mapping_in_transaction() -> Result<(), Box<dyn Error>> {
+        let store = store()?;
+        let result = store.transaction(|transaction| {
+            transaction.query("ASK { <http://example.com/a> <http://example.com/old> ?o }")
+        })?;
+        assert!(matches!(result, QueryResults::Boolean(true)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_identity_resolution() -> Result<(), Box<dyn Error>> {
+        let store = Store::new()?.with_query_rewriter(IdentityResolution::new());
+        store.update(
+            "PREFIX owl: <http://www.w3.org/2002/07/owl#>
+            INSERT DATA {
+                <http://example.com/a> owl:sameAs <http://example.org/a> .
+                <http://example.net/a> owl:sameAs <http://example.org/a> .
+                <http://example.net/a> <http://example.com/p> <http://example.com/b> .
+                <http://example.com/b> owl:sameAs <http://example.org/b> .
+            }",
+        )?;
+        assert!(ask(
+            &store,
+            "ASK { <http://example.com/a> <http://example.com/p> <http://example.org/b> }"
+        )?);
+        assert!(ask(
+            &store,
+            "ASK { <http://example.com/a> ?p <http://example.org/b> FILTER(?p = <http://example.com/p>) }"
+        )?);
+        assert!(ask(
+            &store,
+            "ASK { <http://example.org/b> ^<http://example.com/p> <http://example.com/a> }"
+        )?);
+        assert!(!ask(
+            &store,
+            "ASK { <http://example.com/a> <http://www.w3.org/2002/07/owl#sameAs> <http://example.net/a> }"
+        )?);
+        let QueryResults::Solutions(solutions) = store.query("SELECT * WHERE { ?s ?p ?o }")? else {
+            return Err("SELECT query expected".into());
+        };
+        assert_eq!(solutions.variables().len(), 3);
+        Ok(())
+    }
+}
@@ -178,3 +178,98 @@ fn query_from_pattern(
         dataset: QueryDataset::new(),
     })
 }
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::model::{GraphNameRef, NamedNodeRef, QuadRef, Term};
+    use crate::sparql::{EvaluationError, QueryOptions};
+    use crate::store::Store;
+    use std::io;
+
+    struct StoreServiceHandler(Store);
+
+    impl ServiceHandler for StoreServiceHandler {
+        type Error = EvaluationError;
+
+        fn handle(&self, query: Query) -> Result<QueryResults, Self::Error> {
+            self.0.query(query)
+        }
+    }
+
+    /// Rejects all the services that are not explicitly configured, like an unauthorized endpoint would
+    struct RejectingServiceHandler;
+
+    impl DefaultServiceHandler for RejectingServiceHandler {
+        type Error = io::Error;
+
+        fn handle(&self, service_name: NamedNode, _: Query) -> Result<QueryResults, io::Error> {
+            Err(io::Error::other(format!(
+                "Access to {service_name} is not allowed"
+            )))
+        }
+    }
+
+    fn query_options(service: &Store) -> Result<QueryOptions, Box<dyn Error>> {
+        Ok(QueryOptions::default()
+            .with_service_handler(
+                NamedNodeRef::new("http://example.com/service")?,
+                StoreServiceHandler(service.clone()),
+            )
+            .with_default_service_handler(RejectingServiceHandler))
+    }
+
+    fn solutions(results: QueryResults) -> Result<Vec<Option<Term>>, Box<dyn Error>> {
+        let QueryResults::Solutions(solutions) = results else {
+            return Err("Solutions expected".into());
+        };
+        Ok(solutions
+            .map(|s| Ok(s?.get("s").cloned()))
+            .collect::<Result<_, EvaluationError>>()?)
+    }
+
+    #[test]
+    fn test_service_handler_routing() -> Result<(), Box<dyn Error>> {
+        let ex = NamedNodeRef::new("http://example.com/s")?;
+        let service = Store::new()?;
+        service.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
+        let store = Store::new()?;
+
+        let results = store.query_opt(
+            "SELECT ?s WHERE { SERVICE <http://example.com/service> { ?s ?p ?o } }",
+            query_options(&service)?,
+        )?;
+        assert_eq!(solutions(results)?, [Some(ex.into())]);
+
+        let error = store
+            .query_opt(
+                "SELECT ?s WHERE { SERVICE <http://example.com/other> { ?s ?p ?o } }",
+                query_options(&service)?,
+            )
+            .and_then(|results| {
+                let QueryResults::Solutions(mut solutions) = results else {
+                    return Ok(());
+                };
+                solutions.try_for_each(|s| s.map(drop))
+            })
+            .err()
+            .map(|e| e.to_string());
+        assert!(
+            error
+                .is_some_and(|e| e.contains("Access to <http://example.com/other> is not allowed"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_silent_service_handler_error() -> Result<(), Box<dyn Error>> {
+        let store = Store::new()?;
+        let results = store.query_opt(
+            "SELECT ?s WHERE { SERVICE SILENT <http://example.com/other> { ?s ?p ?o } }",
+            query_options(&Store::new()?)?,
+        )?;
+        assert_eq!(solutions(results)?, [None]);
+        Ok(())
+    }
+}
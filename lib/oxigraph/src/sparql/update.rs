@@ -1,10 +1,15 @@
 #[cfg(feature = "http-client")]
-use crate::io::{RdfFormat, RdfParser};
+use crate::io::{RdfParseError, RdfParser};
 use crate::model::{GraphName as OxGraphName, GraphNameRef, Quad as OxQuad};
+#[cfg(feature = "http-client")]
+use crate::sparql::HttpError;
 use crate::sparql::algebra::QueryDataset;
 use crate::sparql::dataset::DatasetView;
 #[cfg(feature = "http-client")]
-use crate::sparql::http::Client;
+use crate::sparql::http::{
+    Client, SizeLimitedBody, fill_response_buf, rdf_accept_header, rdf_format_from_response,
+    request_error,
+};
 use crate::sparql::{EvaluationError, Update, UpdateOptions};
 use crate::storage::StorageWriter;
 use oxiri::Iri;
@@ -23,22 +28,32 @@ use spargebra::term::{
 use spargebra::term::{GroundTriple, GroundTriplePattern, Triple, TriplePattern};
 use spargebra::{GraphUpdateOperation, Query};
 #[cfg(feature = "http-client")]
-use std::io::Read;
+use std::io::{BufReader, Read};
+use std::sync::Arc;
 
 pub fn evaluate_update<'a, 'b: 'a>(
     transaction: &'a mut StorageWriter<'b>,
     update: &Update,
     options: &UpdateOptions,
 ) -> Result<(), EvaluationError> {
+    let mut query_evaluator = options.query_options.clone().into_evaluator();
+    if let Some(statistics) = transaction.statistics() {
+        query_evaluator = query_evaluator.with_dataset_statistics(Arc::clone(statistics));
+    }
     SimpleUpdateEvaluator {
         transaction,
         base_iri: update.inner.base_iri.clone(),
-        query_evaluator: options.query_options.clone().into_evaluator(),
+        query_evaluator,
         #[cfg(feature = "http-client")]
         client: Client::new(
             options.query_options.http_timeout,
             options.query_options.http_redirection_limit,
-        ),
+        )
+        .with_retry_policy(options.query_options.http_retry_policy),
+        #[cfg(feature = "http-client")]
+        http_accept: rdf_accept_header(&options.query_options.http_accepted_rdf_formats),
+        #[cfg(feature = "http-client")]
+        max_load_size: options.max_load_size,
     }
     .eval_all(&update.inner.operations, &update.using_datasets)
 }
@@ -49,6 +64,10 @@ struct SimpleUpdateEvaluator<'a, 'b> {
     query_evaluator: QueryEvaluator,
     #[cfg(feature = "http-client")]
     client: Client,
+    #[cfg(feature = "http-client")]
+    http_accept: String,
+    #[cfg(feature = "http-client")]
+    max_load_size: Option<u64>,
 }
 
 impl<'a, 'b: 'a> SimpleUpdateEvaluator<'a, 'b> {
@@ -124,7 +143,7 @@ impl<'a, 'b: 'a> SimpleUpdateEvaluator<'a, 'b> {
         algebra: &GraphPattern,
     ) -> Result<(), EvaluationError> {
         let QueryResults::Solutions(solutions) = self.query_evaluator.clone().execute(
-            DatasetView::new(self.transaction.reader(), using),
+            DatasetView::new(self.transaction.reader(), using, None, None),
             &Query::Select {
                 dataset: None,
                 pattern: algebra.clone(),
@@ -155,20 +174,24 @@ impl<'a, 'b: 'a> SimpleUpdateEvaluator<'a, 'b> {
 
     #[cfg(feature = "http-client")]
     fn eval_load(&mut self, from: &NamedNode, to: &GraphName) -> Result<(), EvaluationError> {
-        let (content_type, body) = self
-            .client
-            .get(
-                from.as_str(),
-                "application/n-triples, text/turtle, application/rdf+xml",
-            )
-            .map_err(|e| EvaluationError::Service(Box::new(e)))?;
-        let format = RdfFormat::from_media_type(&content_type)
-            .ok_or_else(|| EvaluationError::UnsupportedContentType(content_type))?;
+        let (content_type, body) = self.client.get(from.as_str(), &self.http_accept)?;
+        // The body is streamed to the parser and then to the transaction
+        let mut body = BufReader::new(SizeLimitedBody::new(
+            body,
+            from.as_str(),
+            self.max_load_size,
+        )?);
+        let format = rdf_format_from_response(
+            content_type,
+            from.as_str(),
+            fill_response_buf(&mut body, from.as_str())?,
+        )?;
         let to_graph_name = match to {
             GraphName::NamedNode(graph_name) => graph_name.into(),
             GraphName::DefaultGraph => GraphNameRef::DefaultGraph,
         };
         let client = self.client.clone();
+        let max_load_size = self.max_load_size;
         let parser = RdfParser::from_format(format)
             .rename_blank_nodes()
             .without_named_graphs()
@@ -177,21 +200,22 @@ impl<'a, 'b: 'a> SimpleUpdateEvaluator<'a, 'b> {
             .map_err(|e| EvaluationError::Unexpected(format!("Invalid URL: {from}: {e}").into()))?
             .for_reader(body)
             .with_document_loader(move |url| {
-                let (content_type, mut body) = client.get(
+                let (content_type, body) = client.get(
                     url,
                     "application/n-triples, text/turtle, application/rdf+xml, application/ld+json",
                 )?;
+                let mut body = SizeLimitedBody::new(body, url, max_load_size)?;
                 let mut content = Vec::new();
-                body.read_to_end(&mut content)?;
+                body.read_to_end(&mut content)
+                    .map_err(|e| request_error(url, e))?;
                 Ok(LoadedDocument {
                     url: url.into(),
+                    format: rdf_format_from_response(content_type, url, &content)?,
                     content,
-                    format: RdfFormat::from_media_type(&content_type)
-                        .ok_or_else(|| EvaluationError::UnsupportedContentType(content_type))?,
                 })
             });
         for q in parser {
-            self.transaction.insert(q?.as_ref())?;
+            self.transaction.insert(q.map_err(load_error)?.as_ref())?;
         }
         Ok(())
     }
@@ -469,3 +493,15 @@ impl<'a, 'b: 'a> SimpleUpdateEvaluator<'a, 'b> {
         })
     }
 }
+
+/// Keeps the HTTP errors raised while reading the `LOAD` response body
+#[cfg(feature = "http-client")]
+fn load_error(error: RdfParseError) -> EvaluationError {
+    match error {
+        RdfParseError::Io(error) => match error.downcast::<HttpError>() {
+            Ok(error) => error.into(),
+            Err(error) => RdfParseError::Io(error).into(),
+        },
+        RdfParseError::Syntax(_) => error.into(),
+    }
+}
@@ -1,7 +1,7 @@
 use crate::storage::error::{CorruptionError, StorageError};
 #[cfg(feature = "rdf-12")]
 use crate::storage::numeric_encoder::EncodedTriple;
-use crate::storage::numeric_encoder::{EncodedQuad, EncodedTerm, StrHash};
+use crate::storage::numeric_encoder::{EncodedQuad, EncodedTerm, STR_HASH_LEN, StrHash};
 use crate::storage::small_string::SmallString;
 use oxsdatatypes::*;
 use std::io::Read;
@@ -9,7 +9,8 @@ use std::mem::size_of;
 #[cfg(feature = "rdf-12")]
 use std::sync::Arc;
 
-pub const WRITTEN_TERM_MAX_SIZE: usize = size_of::<u8>() + 2 * size_of::<StrHash>();
+// Small strings are never smaller than string hashes
+pub const WRITTEN_TERM_MAX_SIZE: usize = size_of::<u8>() + 2 * size_of::<SmallString>();
 
 // Encoded term type blocks
 // 1-7: usual named nodes (except prefixes c.f. later)
@@ -37,7 +38,7 @@ const TYPE_FLOAT_LITERAL: u8 = 30;
 const TYPE_DOUBLE_LITERAL: u8 = 31;
 const TYPE_INTEGER_LITERAL: u8 = 32;
 const TYPE_DECIMAL_LITERAL: u8 = 33;
-const TYPE_DATE_TIME_LITERAL: u8 = 34;
+pub const TYPE_DATE_TIME_LITERAL: u8 = 34;
 const TYPE_TIME_LITERAL: u8 = 35;
 const TYPE_DATE_LITERAL: u8 = 36;
 const TYPE_G_YEAR_MONTH_LITERAL: u8 = 37;
@@ -82,34 +83,38 @@ pub enum QuadEncoding {
 }
 
 impl QuadEncoding {
-    pub fn decode(self, mut buffer: &[u8]) -> Result<EncodedQuad, StorageError> {
+    pub fn decode(
+        self,
+        mut buffer: &[u8],
+        str_hash_len: usize,
+    ) -> Result<EncodedQuad, StorageError> {
         match self {
-            Self::Spog => buffer.read_spog_quad(),
-            Self::Posg => buffer.read_posg_quad(),
-            Self::Ospg => buffer.read_ospg_quad(),
-            Self::Gspo => buffer.read_gspo_quad(),
-            Self::Gpos => buffer.read_gpos_quad(),
-            Self::Gosp => buffer.read_gosp_quad(),
-            Self::Dspo => buffer.read_dspo_quad(),
-            Self::Dpos => buffer.read_dpos_quad(),
-            Self::Dosp => buffer.read_dosp_quad(),
+            Self::Spog => buffer.read_spog_quad(str_hash_len),
+            Self::Posg => buffer.read_posg_quad(str_hash_len),
+            Self::Ospg => buffer.read_ospg_quad(str_hash_len),
+            Self::Gspo => buffer.read_gspo_quad(str_hash_len),
+            Self::Gpos => buffer.read_gpos_quad(str_hash_len),
+            Self::Gosp => buffer.read_gosp_quad(str_hash_len),
+            Self::Dspo => buffer.read_dspo_quad(str_hash_len),
+            Self::Dpos => buffer.read_dpos_quad(str_hash_len),
+            Self::Dosp => buffer.read_dosp_quad(str_hash_len),
         }
     }
 }
 
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-pub fn decode_term(mut buffer: &[u8]) -> Result<EncodedTerm, StorageError> {
-    buffer.read_term()
+pub fn decode_term(mut buffer: &[u8], str_hash_len: usize) -> Result<EncodedTerm, StorageError> {
+    buffer.read_term(str_hash_len)
 }
 
 pub trait TermReader {
-    fn read_term(&mut self) -> Result<EncodedTerm, StorageError>;
+    fn read_term(&mut self, str_hash_len: usize) -> Result<EncodedTerm, StorageError>;
 
-    fn read_spog_quad(&mut self) -> Result<EncodedQuad, StorageError> {
-        let subject = self.read_term()?;
-        let predicate = self.read_term()?;
-        let object = self.read_term()?;
-        let graph_name = self.read_term()?;
+    fn read_spog_quad(&mut self, str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+        let subject = self.read_term(str_hash_len)?;
+        let predicate = self.read_term(str_hash_len)?;
+        let object = self.read_term(str_hash_len)?;
+        let graph_name = self.read_term(str_hash_len)?;
         Ok(EncodedQuad {
             subject,
             predicate,
@@ -118,11 +123,11 @@ pub trait TermReader {
         })
     }
 
-    fn read_posg_quad(&mut self) -> Result<EncodedQuad, StorageError> {
-        let predicate = self.read_term()?;
-        let object = self.read_term()?;
-        let subject = self.read_term()?;
-        let graph_name = self.read_term()?;
+    fn read_posg_quad(&mut self, str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+        let predicate = self.read_term(str_hash_len)?;
+        let object = self.read_term(str_hash_len)?;
+        let subject = self.read_term(str_hash_len)?;
+        let graph_name = self.read_term(str_hash_len)?;
         Ok(EncodedQuad {
             subject,
             predicate,
@@ -131,11 +136,11 @@ pub trait TermReader {
         })
     }
 
-    fn read_ospg_quad(&mut self) -> Result<EncodedQuad, StorageError> {
-        let object = self.read_term()?;
-        let subject = self.read_term()?;
-        let predicate = self.read_term()?;
-        let graph_name = self.read_term()?;
+    fn read_ospg_quad(&mut self, str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+        let object = self.read_term(str_hash_len)?;
+        let subject = self.read_term(str_hash_len)?;
+        let predicate = self.read_term(str_hash_len)?;
+        let graph_name = self.read_term(str_hash_len)?;
         Ok(EncodedQuad {
             subject,
             predicate,
@@ -144,11 +149,11 @@ pub trait TermReader {
         })
     }
 
-    fn read_gspo_quad(&mut self) -> Result<EncodedQuad, StorageError> {
-        let graph_name = self.read_term()?;
-        let subject = self.read_term()?;
-        let predicate = self.read_term()?;
-        let object = self.read_term()?;
+    fn read_gspo_quad(&mut self, str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+        let graph_name = self.read_term(str_hash_len)?;
+        let subject = self.read_term(str_hash_len)?;
+        let predicate = self.read_term(str_hash_len)?;
+        let object = self.read_term(str_hash_len)?;
         Ok(EncodedQuad {
             subject,
             predicate,
@@ -157,11 +162,11 @@ pub trait TermReader {
         })
     }
 
-    fn read_gpos_quad(&mut self) -> Result<EncodedQuad, StorageError> {
-        let graph_name = self.read_term()?;
-        let predicate = self.read_term()?;
-        let object = self.read_term()?;
-        let subject = self.read_term()?;
+    fn read_gpos_quad(&mut self, str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+        let graph_name = self.read_term(str_hash_len)?;
+        let predicate = self.read_term(str_hash_len)?;
+        let object = self.read_term(str_hash_len)?;
+        let subject = self.read_term(str_hash_len)?;
         Ok(EncodedQuad {
             subject,
             predicate,
@@ -170,11 +175,11 @@ pub trait TermReader {
         })
     }
 
-    fn read_gosp_quad(&mut self) -> Result<EncodedQuad, StorageError> {
-        let graph_name = self.read_term()?;
-        let object = self.read_term()?;
-        let subject = self.read_term()?;
-        let predicate = self.read_term()?;
+    fn read_gosp_quad(&mut self, str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+        let graph_name = self.read_term(str_hash_len)?;
+        let object = self.read_term(str_hash_len)?;
+        let subject = self.read_term(str_hash_len)?;
+        let predicate = self.read_term(str_hash_len)?;
         Ok(EncodedQuad {
             subject,
             predicate,
@@ -183,10 +188,10 @@ pub trait TermReader {
         })
     }
 
-    fn read_dspo_quad(&mut self) -> Result<EncodedQuad, StorageError> {
-        let subject = self.read_term()?;
-        let predicate = self.read_term()?;
-        let object = self.read_term()?;
+    fn read_dspo_quad(&mut self, str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+        let subject = self.read_term(str_hash_len)?;
+        let predicate = self.read_term(str_hash_len)?;
+        let object = self.read_term(str_hash_len)?;
         Ok(EncodedQuad {
             subject,
             predicate,
@@ -195,10 +200,10 @@ pub trait TermReader {
         })
     }
 
-    fn read_dpos_quad(&mut self) -> Result<EncodedQuad, StorageError> {
-        let predicate = self.read_term()?;
-        let object = self.read_term()?;
-        let subject = self.read_term()?;
+    fn read_dpos_quad(&mut self, str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+        let predicate = self.read_term(str_hash_len)?;
+        let object = self.read_term(str_hash_len)?;
+        let subject = self.read_term(str_hash_len)?;
         Ok(EncodedQuad {
             subject,
             predicate,
@@ -207,10 +212,10 @@ pub trait TermReader {
         })
     }
 
-    fn read_dosp_quad(&mut self) -> Result<EncodedQuad, StorageError> {
-        let object = self.read_term()?;
-        let subject = self.read_term()?;
-        let predicate = self.read_term()?;
+    fn read_dosp_quad(&mut self, str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+        let object = self.read_term(str_hash_len)?;
+        let subject = self.read_term(str_hash_len)?;
+        let predicate = self.read_term(str_hash_len)?;
         Ok(EncodedQuad {
             subject,
             predicate,
@@ -221,16 +226,13 @@ pub trait TermReader {
 }
 
 impl<R: Read> TermReader for R {
-    fn read_term(&mut self) -> Result<EncodedTerm, StorageError> {
+    fn read_term(&mut self, str_hash_len: usize) -> Result<EncodedTerm, StorageError> {
         let mut type_buffer = [0];
         self.read_exact(&mut type_buffer)?;
         match type_buffer[0] {
             TYPE_NAMED_NODE_ID => {
-                let mut buffer = [0; 16];
-                self.read_exact(&mut buffer)?;
-                Ok(EncodedTerm::NamedNode {
-                    iri_id: StrHash::from_be_bytes(buffer),
-                })
+                let buffer = read_str_hash(self, str_hash_len)?;
+                Ok(EncodedTerm::NamedNode { iri_id: buffer })
             }
             TYPE_NUMERICAL_BLANK_NODE_ID => {
                 let mut id = [0; 16];
@@ -245,11 +247,8 @@ impl<R: Read> TermReader for R {
                 ))
             }
             TYPE_BIG_BLANK_NODE_ID => {
-                let mut buffer = [0; 16];
-                self.read_exact(&mut buffer)?;
-                Ok(EncodedTerm::BigBlankNode {
-                    id_id: StrHash::from_be_bytes(buffer),
-                })
+                let buffer = read_str_hash(self, str_hash_len)?;
+                Ok(EncodedTerm::BigBlankNode { id_id: buffer })
             }
             TYPE_SMALL_SMALL_LANG_STRING_LITERAL => {
                 let mut language_buffer = [0; 16];
@@ -264,35 +263,31 @@ impl<R: Read> TermReader for R {
                 })
             }
             TYPE_SMALL_BIG_LANG_STRING_LITERAL => {
-                let mut language_buffer = [0; 16];
-                self.read_exact(&mut language_buffer)?;
+                let language_buffer = read_str_hash(self, str_hash_len)?;
                 let mut value_buffer = [0; 16];
                 self.read_exact(&mut value_buffer)?;
                 Ok(EncodedTerm::SmallBigLangStringLiteral {
                     value: SmallString::from_be_bytes(value_buffer)
                         .map_err(CorruptionError::new)?,
-                    language_id: StrHash::from_be_bytes(language_buffer),
+                    language_id: language_buffer,
                 })
             }
             TYPE_BIG_SMALL_LANG_STRING_LITERAL => {
                 let mut language_buffer = [0; 16];
                 self.read_exact(&mut language_buffer)?;
-                let mut value_buffer = [0; 16];
-                self.read_exact(&mut value_buffer)?;
+                let value_buffer = read_str_hash(self, str_hash_len)?;
                 Ok(EncodedTerm::BigSmallLangStringLiteral {
-                    value_id: StrHash::from_be_bytes(value_buffer),
+                    value_id: value_buffer,
                     language: SmallString::from_be_bytes(language_buffer)
                         .map_err(CorruptionError::new)?,
                 })
             }
             TYPE_BIG_BIG_LANG_STRING_LITERAL => {
-                let mut language_buffer = [0; 16];
-                self.read_exact(&mut language_buffer)?;
-                let mut value_buffer = [0; 16];
-                self.read_exact(&mut value_buffer)?;
+                let language_buffer = read_str_hash(self, str_hash_len)?;
+                let value_buffer = read_str_hash(self, str_hash_len)?;
                 Ok(EncodedTerm::BigBigLangStringLiteral {
-                    value_id: StrHash::from_be_bytes(value_buffer),
-                    language_id: StrHash::from_be_bytes(language_buffer),
+                    value_id: value_buffer,
+                    language_id: language_buffer,
                 })
             }
             #[cfg(feature = "rdf-12")]
@@ -310,37 +305,33 @@ impl<R: Read> TermReader for R {
             }
             #[cfg(feature = "rdf-12")]
             TYPE_RTL_SMALL_BIG_DIR_LANG_STRING_LITERAL => {
-                let mut language_buffer = [0; 16];
-                self.read_exact(&mut language_buffer)?;
+                let language_buffer = read_str_hash(self, str_hash_len)?;
                 let mut value_buffer = [0; 16];
                 self.read_exact(&mut value_buffer)?;
                 Ok(EncodedTerm::RtlSmallBigDirLangStringLiteral {
                     value: SmallString::from_be_bytes(value_buffer)
                         .map_err(CorruptionError::new)?,
-                    language_id: StrHash::from_be_bytes(language_buffer),
+                    language_id: language_buffer,
                 })
             }
             #[cfg(feature = "rdf-12")]
             TYPE_RTL_BIG_SMALL_DIR_LANG_STRING_LITERAL => {
                 let mut language_buffer = [0; 16];
                 self.read_exact(&mut language_buffer)?;
-                let mut value_buffer = [0; 16];
-                self.read_exact(&mut value_buffer)?;
+                let value_buffer = read_str_hash(self, str_hash_len)?;
                 Ok(EncodedTerm::RtlBigSmallDirLangStringLiteral {
-                    value_id: StrHash::from_be_bytes(value_buffer),
+                    value_id: value_buffer,
                     language: SmallString::from_be_bytes(language_buffer)
                         .map_err(CorruptionError::new)?,
                 })
             }
             #[cfg(feature = "rdf-12")]
             TYPE_RTL_BIG_BIG_DIR_LANG_STRING_LITERAL => {
-                let mut language_buffer = [0; 16];
-                self.read_exact(&mut language_buffer)?;
-                let mut value_buffer = [0; 16];
-                self.read_exact(&mut value_buffer)?;
+                let language_buffer = read_str_hash(self, str_hash_len)?;
+                let value_buffer = read_str_hash(self, str_hash_len)?;
                 Ok(EncodedTerm::RtlBigBigDirLangStringLiteral {
-                    value_id: StrHash::from_be_bytes(value_buffer),
-                    language_id: StrHash::from_be_bytes(language_buffer),
+                    value_id: value_buffer,
+                    language_id: language_buffer,
                 })
             }
             #[cfg(feature = "rdf-12")]
@@ -358,58 +349,51 @@ impl<R: Read> TermReader for R {
             }
             #[cfg(feature = "rdf-12")]
             TYPE_LTR_SMALL_BIG_DIR_LANG_STRING_LITERAL => {
-                let mut language_buffer = [0; 16];
-                self.read_exact(&mut language_buffer)?;
+                let language_buffer = read_str_hash(self, str_hash_len)?;
                 let mut value_buffer = [0; 16];
                 self.read_exact(&mut value_buffer)?;
                 Ok(EncodedTerm::LtrSmallBigDirLangStringLiteral {
                     value: SmallString::from_be_bytes(value_buffer)
                         .map_err(CorruptionError::new)?,
-                    language_id: StrHash::from_be_bytes(language_buffer),
+                    language_id: language_buffer,
                 })
             }
             #[cfg(feature = "rdf-12")]
             TYPE_LTR_BIG_SMALL_DIR_LANG_STRING_LITERAL => {
                 let mut language_buffer = [0; 16];
                 self.read_exact(&mut language_buffer)?;
-                let mut value_buffer = [0; 16];
-                self.read_exact(&mut value_buffer)?;
+                let value_buffer = read_str_hash(self, str_hash_len)?;
                 Ok(EncodedTerm::LtrBigSmallDirLangStringLiteral {
-                    value_id: StrHash::from_be_bytes(value_buffer),
+                    value_id: value_buffer,
                     language: SmallString::from_be_bytes(language_buffer)
                         .map_err(CorruptionError::new)?,
                 })
             }
             #[cfg(feature = "rdf-12")]
             TYPE_LTR_BIG_BIG_DIR_LANG_STRING_LITERAL => {
-                let mut language_buffer = [0; 16];
-                self.read_exact(&mut language_buffer)?;
-                let mut value_buffer = [0; 16];
-                self.read_exact(&mut value_buffer)?;
+                let language_buffer = read_str_hash(self, str_hash_len)?;
+                let value_buffer = read_str_hash(self, str_hash_len)?;
                 Ok(EncodedTerm::LtrBigBigDirLangStringLiteral {
-                    value_id: StrHash::from_be_bytes(value_buffer),
-                    language_id: StrHash::from_be_bytes(language_buffer),
+                    value_id: value_buffer,
+                    language_id: language_buffer,
                 })
             }
             TYPE_SMALL_TYPED_LITERAL => {
-                let mut datatype_buffer = [0; 16];
-                self.read_exact(&mut datatype_buffer)?;
+                let datatype_buffer = read_str_hash(self, str_hash_len)?;
                 let mut value_buffer = [0; 16];
                 self.read_exact(&mut value_buffer)?;
                 Ok(EncodedTerm::SmallTypedLiteral {
-                    datatype_id: StrHash::from_be_bytes(datatype_buffer),
+                    datatype_id: datatype_buffer,
                     value: SmallString::from_be_bytes(value_buffer)
                         .map_err(CorruptionError::new)?,
                 })
             }
             TYPE_BIG_TYPED_LITERAL => {
-                let mut datatype_buffer = [0; 16];
-                self.read_exact(&mut datatype_buffer)?;
-                let mut value_buffer = [0; 16];
-                self.read_exact(&mut value_buffer)?;
+                let datatype_buffer = read_str_hash(self, str_hash_len)?;
+                let value_buffer = read_str_hash(self, str_hash_len)?;
                 Ok(EncodedTerm::BigTypedLiteral {
-                    datatype_id: StrHash::from_be_bytes(datatype_buffer),
-                    value_id: StrHash::from_be_bytes(value_buffer),
+                    datatype_id: datatype_buffer,
+                    value_id: value_buffer,
                 })
             }
             TYPE_SMALL_STRING_LITERAL => {
@@ -420,11 +404,8 @@ impl<R: Read> TermReader for R {
                 ))
             }
             TYPE_BIG_STRING_LITERAL => {
-                let mut buffer = [0; 16];
-                self.read_exact(&mut buffer)?;
-                Ok(EncodedTerm::BigStringLiteral {
-                    value_id: StrHash::from_be_bytes(buffer),
-                })
+                let buffer = read_str_hash(self, str_hash_len)?;
+                Ok(EncodedTerm::BigStringLiteral { value_id: buffer })
             }
             TYPE_BOOLEAN_LITERAL_TRUE => Ok(EncodedTerm::BooleanLiteral(true.into())),
             TYPE_BOOLEAN_LITERAL_FALSE => Ok(EncodedTerm::BooleanLiteral(false.into())),
@@ -517,93 +498,105 @@ impl<R: Read> TermReader for R {
             }
             #[cfg(feature = "rdf-12")]
             TYPE_TRIPLE | TYPE_STAR_TRIPLE => Ok(EncodedTerm::Triple(Arc::new(EncodedTriple {
-                subject: self.read_term()?,
-                predicate: self.read_term()?,
-                object: self.read_term()?,
+                subject: self.read_term(str_hash_len)?,
+                predicate: self.read_term(str_hash_len)?,
+                object: self.read_term(str_hash_len)?,
             }))),
             _ => Err(CorruptionError::msg("the term buffer has an invalid type id").into()),
         }
     }
 }
 
-pub fn write_spog_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, &quad.subject);
-    write_term(sink, &quad.predicate);
-    write_term(sink, &quad.object);
-    write_term(sink, &quad.graph_name);
+/// Reads a string identifier written with only its first `str_hash_len` bytes, the other ones are set to 0
+fn read_str_hash(reader: &mut impl Read, str_hash_len: usize) -> Result<StrHash, StorageError> {
+    let mut buffer = [0; STR_HASH_LEN];
+    reader.read_exact(&mut buffer[..str_hash_len])?;
+    Ok(StrHash::from_be_bytes(buffer))
+}
+
+pub fn write_spog_quad(sink: &mut Vec<u8>, quad: &EncodedQuad, str_hash_len: usize) {
+    write_term(sink, &quad.subject, str_hash_len);
+    write_term(sink, &quad.predicate, str_hash_len);
+    write_term(sink, &quad.object, str_hash_len);
+    write_term(sink, &quad.graph_name, str_hash_len);
 }
 
-pub fn write_posg_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, &quad.predicate);
-    write_term(sink, &quad.object);
-    write_term(sink, &quad.subject);
-    write_term(sink, &quad.graph_name);
+pub fn write_posg_quad(sink: &mut Vec<u8>, quad: &EncodedQuad, str_hash_len: usize) {
+    write_term(sink, &quad.predicate, str_hash_len);
+    write_term(sink, &quad.object, str_hash_len);
+    write_term(sink, &quad.subject, str_hash_len);
+    write_term(sink, &quad.graph_name, str_hash_len);
 }
 
-pub fn write_ospg_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, &quad.object);
-    write_term(sink, &quad.subject);
-    write_term(sink, &quad.predicate);
-    write_term(sink, &quad.graph_name);
+pub fn write_ospg_quad(sink: &mut Vec<u8>, quad: &EncodedQuad, str_hash_len: usize) {
+    write_term(sink, &quad.object, str_hash_len);
+    write_term(sink, &quad.subject, str_hash_len);
+    write_term(sink, &quad.predicate, str_hash_len);
+    write_term(sink, &quad.graph_name, str_hash_len);
 }
 
-pub fn write_gspo_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, &quad.graph_name);
-    write_term(sink, &quad.subject);
-    write_term(sink, &quad.predicate);
-    write_term(sink, &quad.object);
+pub fn write_gspo_quad(sink: &mut Vec<u8>, quad: &EncodedQuad, str_hash_len: usize) {
+    write_term(sink, &quad.graph_name, str_hash_len);
+    write_term(sink, &quad.subject, str_hash_len);
+    write_term(sink, &quad.predicate, str_hash_len);
+    write_term(sink, &quad.object, str_hash_len);
 }
 
-pub fn write_gpos_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, &quad.graph_name);
-    write_term(sink, &quad.predicate);
-    write_term(sink, &quad.object);
-    write_term(sink, &quad.subject);
+pub fn write_gpos_quad(sink: &mut Vec<u8>, quad: &EncodedQuad, str_hash_len: usize) {
+    write_term(sink, &quad.graph_name, str_hash_len);
+    write_term(sink, &quad.predicate, str_hash_len);
+    write_term(sink, &quad.object, str_hash_len);
+    write_term(sink, &quad.subject, str_hash_len);
 }
 
-pub fn write_gosp_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, &quad.graph_name);
-    write_term(sink, &quad.object);
-    write_term(sink, &quad.subject);
-    write_term(sink, &quad.predicate);
+pub fn write_gosp_quad(sink: &mut Vec<u8>, quad: &EncodedQuad, str_hash_len: usize) {
+    write_term(sink, &quad.graph_name, str_hash_len);
+    write_term(sink, &quad.object, str_hash_len);
+    write_term(sink, &quad.subject, str_hash_len);
+    write_term(sink, &quad.predicate, str_hash_len);
 }
 
-pub fn write_spo_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, &quad.subject);
-    write_term(sink, &quad.predicate);
-    write_term(sink, &quad.object);
+pub fn write_spo_quad(sink: &mut Vec<u8>, quad: &EncodedQuad, str_hash_len: usize) {
+    write_term(sink, &quad.subject, str_hash_len);
+    write_term(sink, &quad.predicate, str_hash_len);
+    write_term(sink, &quad.object, str_hash_len);
 }
 
-pub fn write_pos_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, &quad.predicate);
-    write_term(sink, &quad.object);
-    write_term(sink, &quad.subject);
+pub fn write_pos_quad(sink: &mut Vec<u8>, quad: &EncodedQuad, str_hash_len: usize) {
+    write_term(sink, &quad.predicate, str_hash_len);
+    write_term(sink, &quad.object, str_hash_len);
+    write_term(sink, &quad.subject, str_hash_len);
 }
 
-pub fn write_osp_quad(sink: &mut Vec<u8>, quad: &EncodedQuad) {
-    write_term(sink, &quad.object);
-    write_term(sink, &quad.subject);
-    write_term(sink, &quad.predicate);
+pub fn write_osp_quad(sink: &mut Vec<u8>, quad: &EncodedQuad, str_hash_len: usize) {
+    write_term(sink, &quad.object, str_hash_len);
+    write_term(sink, &quad.subject, str_hash_len);
+    write_term(sink, &quad.predicate, str_hash_len);
 }
 
-pub fn encode_term(t: &EncodedTerm) -> Vec<u8> {
+pub fn encode_term(t: &EncodedTerm, str_hash_len: usize) -> Vec<u8> {
     let mut vec = Vec::with_capacity(WRITTEN_TERM_MAX_SIZE);
-    write_term(&mut vec, t);
+    write_term(&mut vec, t, str_hash_len);
     vec
 }
 
-pub fn encode_term_pair(t1: &EncodedTerm, t2: &EncodedTerm) -> Vec<u8> {
+pub fn encode_term_pair(t1: &EncodedTerm, t2: &EncodedTerm, str_hash_len: usize) -> Vec<u8> {
     let mut vec = Vec::with_capacity(2 * WRITTEN_TERM_MAX_SIZE);
-    write_term(&mut vec, t1);
-    write_term(&mut vec, t2);
+    write_term(&mut vec, t1, str_hash_len);
+    write_term(&mut vec, t2, str_hash_len);
     vec
 }
 
-pub fn encode_term_triple(t1: &EncodedTerm, t2: &EncodedTerm, t3: &EncodedTerm) -> Vec<u8> {
+pub fn encode_term_triple(
+    t1: &EncodedTerm,
+    t2: &EncodedTerm,
+    t3: &EncodedTerm,
+    str_hash_len: usize,
+) -> Vec<u8> {
     let mut vec = Vec::with_capacity(3 * WRITTEN_TERM_MAX_SIZE);
-    write_term(&mut vec, t1);
-    write_term(&mut vec, t2);
-    write_term(&mut vec, t3);
+    write_term(&mut vec, t1, str_hash_len);
+    write_term(&mut vec, t2, str_hash_len);
+    write_term(&mut vec, t3, str_hash_len);
     vec
 }
 
@@ -612,21 +605,23 @@ pub fn encode_term_quad(
     t2: &EncodedTerm,
     t3: &EncodedTerm,
     t4: &EncodedTerm,
+    str_hash_len: usize,
 ) -> Vec<u8> {
     let mut vec = Vec::with_capacity(4 * WRITTEN_TERM_MAX_SIZE);
-    write_term(&mut vec, t1);
-    write_term(&mut vec, t2);
-    write_term(&mut vec, t3);
-    write_term(&mut vec, t4);
+    write_term(&mut vec, t1, str_hash_len);
+    write_term(&mut vec, t2, str_hash_len);
+    write_term(&mut vec, t3, str_hash_len);
+    write_term(&mut vec, t4, str_hash_len);
     vec
 }
 
-pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
+/// Writes the term keeping only the first `str_hash_len` bytes of its string identifiers
+pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm, str_hash_len: usize) {
     match term {
         EncodedTerm::DefaultGraph => (),
         EncodedTerm::NamedNode { iri_id } => {
             sink.push(TYPE_NAMED_NODE_ID);
-            sink.extend_from_slice(&iri_id.to_be_bytes());
+            sink.extend_from_slice(&iri_id.to_be_bytes()[..str_hash_len]);
         }
         EncodedTerm::NumericalBlankNode { id } => {
             sink.push(TYPE_NUMERICAL_BLANK_NODE_ID);
@@ -638,7 +633,7 @@ pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
         }
         EncodedTerm::BigBlankNode { id_id } => {
             sink.push(TYPE_BIG_BLANK_NODE_ID);
-            sink.extend_from_slice(&id_id.to_be_bytes());
+            sink.extend_from_slice(&id_id.to_be_bytes()[..str_hash_len]);
         }
         EncodedTerm::SmallStringLiteral(value) => {
             sink.push(TYPE_SMALL_STRING_LITERAL);
@@ -646,7 +641,7 @@ pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
         }
         EncodedTerm::BigStringLiteral { value_id } => {
             sink.push(TYPE_BIG_STRING_LITERAL);
-            sink.extend_from_slice(&value_id.to_be_bytes());
+            sink.extend_from_slice(&value_id.to_be_bytes()[..str_hash_len]);
         }
         EncodedTerm::SmallSmallLangStringLiteral { value, language } => {
             sink.push(TYPE_SMALL_SMALL_LANG_STRING_LITERAL);
@@ -655,21 +650,21 @@ pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
         }
         EncodedTerm::SmallBigLangStringLiteral { value, language_id } => {
             sink.push(TYPE_SMALL_BIG_LANG_STRING_LITERAL);
-            sink.extend_from_slice(&language_id.to_be_bytes());
+            sink.extend_from_slice(&language_id.to_be_bytes()[..str_hash_len]);
             sink.extend_from_slice(&value.to_be_bytes());
         }
         EncodedTerm::BigSmallLangStringLiteral { value_id, language } => {
             sink.push(TYPE_BIG_SMALL_LANG_STRING_LITERAL);
             sink.extend_from_slice(&language.to_be_bytes());
-            sink.extend_from_slice(&value_id.to_be_bytes());
+            sink.extend_from_slice(&value_id.to_be_bytes()[..str_hash_len]);
         }
         EncodedTerm::BigBigLangStringLiteral {
             value_id,
             language_id,
         } => {
             sink.push(TYPE_BIG_BIG_LANG_STRING_LITERAL);
-            sink.extend_from_slice(&language_id.to_be_bytes());
-            sink.extend_from_slice(&value_id.to_be_bytes());
+            sink.extend_from_slice(&language_id.to_be_bytes()[..str_hash_len]);
+            sink.extend_from_slice(&value_id.to_be_bytes()[..str_hash_len]);
         }
         #[cfg(feature = "rdf-12")]
         EncodedTerm::LtrSmallSmallDirLangStringLiteral { value, language } => {
@@ -680,14 +675,14 @@ pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
         #[cfg(feature = "rdf-12")]
         EncodedTerm::LtrSmallBigDirLangStringLiteral { value, language_id } => {
             sink.push(TYPE_LTR_SMALL_BIG_DIR_LANG_STRING_LITERAL);
-            sink.extend_from_slice(&language_id.to_be_bytes());
+            sink.extend_from_slice(&language_id.to_be_bytes()[..str_hash_len]);
             sink.extend_from_slice(&value.to_be_bytes());
         }
         #[cfg(feature = "rdf-12")]
         EncodedTerm::LtrBigSmallDirLangStringLiteral { value_id, language } => {
             sink.push(TYPE_LTR_BIG_SMALL_DIR_LANG_STRING_LITERAL);
             sink.extend_from_slice(&language.to_be_bytes());
-            sink.extend_from_slice(&value_id.to_be_bytes());
+            sink.extend_from_slice(&value_id.to_be_bytes()[..str_hash_len]);
         }
         #[cfg(feature = "rdf-12")]
         EncodedTerm::LtrBigBigDirLangStringLiteral {
@@ -695,8 +690,8 @@ pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
             language_id,
         } => {
             sink.push(TYPE_LTR_BIG_BIG_DIR_LANG_STRING_LITERAL);
-            sink.extend_from_slice(&language_id.to_be_bytes());
-            sink.extend_from_slice(&value_id.to_be_bytes());
+            sink.extend_from_slice(&language_id.to_be_bytes()[..str_hash_len]);
+            sink.extend_from_slice(&value_id.to_be_bytes()[..str_hash_len]);
         }
         #[cfg(feature = "rdf-12")]
         EncodedTerm::RtlSmallSmallDirLangStringLiteral { value, language } => {
@@ -707,14 +702,14 @@ pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
         #[cfg(feature = "rdf-12")]
         EncodedTerm::RtlSmallBigDirLangStringLiteral { value, language_id } => {
             sink.push(TYPE_RTL_SMALL_BIG_DIR_LANG_STRING_LITERAL);
-            sink.extend_from_slice(&language_id.to_be_bytes());
+            sink.extend_from_slice(&language_id.to_be_bytes()[..str_hash_len]);
             sink.extend_from_slice(&value.to_be_bytes());
         }
         #[cfg(feature = "rdf-12")]
         EncodedTerm::RtlBigSmallDirLangStringLiteral { value_id, language } => {
             sink.push(TYPE_RTL_BIG_SMALL_DIR_LANG_STRING_LITERAL);
             sink.extend_from_slice(&language.to_be_bytes());
-            sink.extend_from_slice(&value_id.to_be_bytes());
+            sink.extend_from_slice(&value_id.to_be_bytes()[..str_hash_len]);
         }
         #[cfg(feature = "rdf-12")]
         EncodedTerm::RtlBigBigDirLangStringLiteral {
@@ -722,12 +717,12 @@ pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
             language_id,
         } => {
             sink.push(TYPE_RTL_BIG_BIG_DIR_LANG_STRING_LITERAL);
-            sink.extend_from_slice(&language_id.to_be_bytes());
-            sink.extend_from_slice(&value_id.to_be_bytes());
+            sink.extend_from_slice(&language_id.to_be_bytes()[..str_hash_len]);
+            sink.extend_from_slice(&value_id.to_be_bytes()[..str_hash_len]);
         }
         EncodedTerm::SmallTypedLiteral { value, datatype_id } => {
             sink.push(TYPE_SMALL_TYPED_LITERAL);
-            sink.extend_from_slice(&datatype_id.to_be_bytes());
+            sink.extend_from_slice(&datatype_id.to_be_bytes()[..str_hash_len]);
             sink.extend_from_slice(&value.to_be_bytes());
         }
         EncodedTerm::BigTypedLiteral {
@@ -735,8 +730,8 @@ pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
             datatype_id,
         } => {
             sink.push(TYPE_BIG_TYPED_LITERAL);
-            sink.extend_from_slice(&datatype_id.to_be_bytes());
-            sink.extend_from_slice(&value_id.to_be_bytes());
+            sink.extend_from_slice(&datatype_id.to_be_bytes()[..str_hash_len]);
+            sink.extend_from_slice(&value_id.to_be_bytes()[..str_hash_len]);
         }
         EncodedTerm::BooleanLiteral(value) => sink.push(if bool::from(*value) {
             TYPE_BOOLEAN_LITERAL_TRUE
@@ -806,9 +801,9 @@ pub fn write_term(sink: &mut Vec<u8>, term: &EncodedTerm) {
         #[cfg(feature = "rdf-12")]
         EncodedTerm::Triple(value) => {
             sink.push(TYPE_TRIPLE);
-            write_term(sink, &value.subject);
-            write_term(sink, &value.predicate);
-            write_term(sink, &value.object);
+            write_term(sink, &value.subject, str_hash_len);
+            write_term(sink, &value.predicate, str_hash_len);
+            write_term(sink, &value.object, str_hash_len);
         }
     }
 }
@@ -827,8 +822,8 @@ mod tests {
     }
 
     impl StrLookup for MemoryStrStore {
-        fn get_str(&self, key: &StrHash) -> Result<Option<String>, StorageError> {
-            Ok(self.id2str.borrow().get(key).cloned())
+        fn get_str(&self, key: StrHash) -> Result<Option<String>, StorageError> {
+            Ok(self.id2str.borrow().get(&key).cloned())
         }
     }
 
@@ -841,10 +836,10 @@ mod tests {
             .unwrap();
         }
 
-        fn insert_str(&self, key: &StrHash, value: &str) {
+        fn insert_str(&self, key: StrHash, value: &str) {
             self.id2str
                 .borrow_mut()
-                .entry(*key)
+                .entry(key)
                 .or_insert_with(|| value.to_owned());
         }
     }
@@ -976,9 +971,14 @@ mod tests {
             assert_eq!(encoded, term.as_ref().into());
             assert_eq!(term, store.decode_term(&encoded).unwrap());
 
-            let mut buffer = Vec::new();
-            write_term(&mut buffer, &encoded);
-            assert_eq!(encoded, buffer.as_slice().read_term().unwrap());
+            for str_hash_len in [STR_HASH_LEN, 8] {
+                let mut buffer = Vec::new();
+                write_term(&mut buffer, &encoded, str_hash_len);
+                assert_eq!(
+                    encoded.clone().with_str_hash_len(str_hash_len),
+                    buffer.as_slice().read_term(str_hash_len).unwrap()
+                );
+            }
         }
     }
 }
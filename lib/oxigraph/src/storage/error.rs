@@ -1,9 +1,10 @@
 use crate::io::{RdfFormat, RdfParseError};
 use crate::storage::numeric_encoder::EncodedTerm;
+use crate::storage::{GraphQuota, GraphUsage};
 use oxiri::IriParseError;
-use oxrdf::TermRef;
+use oxrdf::{NamedOrBlankNode, TermRef};
 use std::error::Error;
-use std::io;
+use std::{fmt, io};
 
 /// An error related to storage operations (reads, writes...).
 #[derive(Debug, thiserror::Error)]
@@ -15,17 +16,49 @@ pub enum StorageError {
     /// Error related to data corruption.
     #[error(transparent)]
     Corruption(#[from] CorruptionError),
+    /// The write has been rejected because the storage is stalling the writes.
+    #[error(transparent)]
+    WriteStall(#[from] WriteStallError),
+    /// The write has been rejected because it would make a graph exceed its quota.
+    #[error(transparent)]
+    GraphQuotaExceeded(#[from] GraphQuotaExceededError),
+    /// The write has been rejected because two different strings would get the same compact identifier.
+    #[error(transparent)]
+    TermIdCollision(#[from] TermIdCollisionError),
     #[doc(hidden)]
     #[error("{0}")]
     Other(#[source] Box<dyn Error + Send + Sync + 'static>),
 }
 
+impl StorageError {
+    /// A stable identifier of the kind of error: `"io"`, `"corruption"`, `"write-stall"`, `"graph-quota-exceeded"`,
+    /// `"term-id-collision"` or `"other"`.
+    ///
+    /// It is not changed between releases and allows applications to branch on the failure kind
+    /// without matching the error message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "io",
+            Self::Corruption(_) => "corruption",
+            Self::WriteStall(_) => "write-stall",
+            Self::GraphQuotaExceeded(_) => "graph-quota-exceeded",
+            Self::TermIdCollision(_) => "term-id-collision",
+            Self::Other(_) => "other",
+        }
+    }
+}
+
 impl From<StorageError> for io::Error {
     #[inline]
     fn from(error: StorageError) -> Self {
         match error {
             StorageError::Io(error) => error,
             StorageError::Corruption(error) => error.into(),
+            StorageError::WriteStall(error) => Self::new(io::ErrorKind::ResourceBusy, error),
+            StorageError::GraphQuotaExceeded(error) => {
+                Self::new(io::ErrorKind::QuotaExceeded, error)
+            }
+            StorageError::TermIdCollision(error) => Self::new(io::ErrorKind::InvalidInput, error),
             StorageError::Other(error) => Self::other(error),
         }
     }
@@ -79,6 +112,155 @@ impl From<CorruptionError> for io::Error {
     }
 }
 
+/// An error returned when a write is rejected because the on-disk storage is stalling the writes to let its compaction catch up.
+///
+/// It is only returned by stores set up with [`Store::with_write_stall_errors`](crate::store::Store::with_write_stall_errors),
+/// the other stores slow down or block the writes instead.
+/// The write can be retried once the compaction has progressed.
+#[derive(Debug)]
+pub struct WriteStallError {
+    pending_compaction_bytes: Option<u64>,
+}
+
+impl WriteStallError {
+    #[inline]
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub(crate) fn new(pending_compaction_bytes: Option<u64>) -> Self {
+        Self {
+            pending_compaction_bytes,
+        }
+    }
+
+    /// The estimated number of bytes the compaction has to rewrite to get the storage back to normal, if known.
+    #[inline]
+    pub fn pending_compaction_bytes(&self) -> Option<u64> {
+        self.pending_compaction_bytes
+    }
+}
+
+impl fmt::Display for WriteStallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("The write has been rejected because the storage is stalling writes to let its compaction catch up")?;
+        if let Some(pending_compaction_bytes) = self.pending_compaction_bytes {
+            write!(f, " ({pending_compaction_bytes} bytes pending compaction)")?;
+        }
+        f.write_str(", retry later")
+    }
+}
+
+impl Error for WriteStallError {}
+
+/// An error returned when an insertion is rejected because it would make a graph exceed its [quota](GraphQuota).
+///
+/// See [`Store::with_graph_quota`](crate::store::Store::with_graph_quota).
+#[derive(Debug)]
+pub struct GraphQuotaExceededError {
+    graph_name: NamedOrBlankNode,
+    quota: GraphQuota,
+    usage: GraphUsage,
+}
+
+impl GraphQuotaExceededError {
+    #[inline]
+    pub(crate) fn new(graph_name: NamedOrBlankNode, quota: GraphQuota, usage: GraphUsage) -> Self {
+        Self {
+            graph_name,
+            quota,
+            usage,
+        }
+    }
+
+    /// The name of the graph whose quota would be exceeded.
+    #[inline]
+    pub fn graph_name(&self) -> &NamedOrBlankNode {
+        &self.graph_name
+    }
+
+    /// The quota of the graph.
+    #[inline]
+    pub fn quota(&self) -> GraphQuota {
+        self.quota
+    }
+
+    /// The size of the graph before the rejected insertion.
+    #[inline]
+    pub fn usage(&self) -> GraphUsage {
+        self.usage
+    }
+}
+
+impl fmt::Display for GraphQuotaExceededError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The insertion has been rejected because the graph {} would exceed its quota of",
+            self.graph_name
+        )?;
+        if let Some(max_quads) = self.quota.max_quads() {
+            write!(f, " {max_quads} quads")?;
+        }
+        if let Some(max_bytes) = self.quota.max_bytes() {
+            if self.quota.max_quads().is_some() {
+                f.write_str(" or")?;
+            }
+            write!(f, " {max_bytes} bytes")?;
+        }
+        write!(
+            f,
+            ", it already contains {} quads and {} bytes",
+            self.usage.quads(),
+            self.usage.bytes()
+        )
+    }
+}
+
+impl Error for GraphQuotaExceededError {}
+
+/// An error returned when a string would get the same identifier as another string already in a store
+/// created with [compact term identifiers](crate::store::StoreOptions::with_compact_term_ids).
+///
+/// The compact identifiers are 64 bits hashes, the strings are never silently merged if their hashes collide.
+#[derive(Debug)]
+pub struct TermIdCollisionError {
+    value: String,
+    existing_value: String,
+}
+
+impl TermIdCollisionError {
+    #[inline]
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub(crate) fn new(value: impl Into<String>, existing_value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            existing_value: existing_value.into(),
+        }
+    }
+
+    /// The string that has been rejected.
+    #[inline]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The string already in the store with the same identifier.
+    #[inline]
+    pub fn existing_value(&self) -> &str {
+        &self.existing_value
+    }
+}
+
+impl fmt::Display for TermIdCollisionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The write has been rejected because the string '{}' has the same compact identifier as the string '{}' already in the store",
+            self.value, self.existing_value
+        )
+    }
+}
+
+impl Error for TermIdCollisionError {}
+
 /// An error raised while loading a file into a [`Store`](crate::store::Store).
 #[derive(Debug, thiserror::Error)]
 pub enum LoaderError {
@@ -99,6 +281,18 @@ pub enum LoaderError {
     },
 }
 
+impl LoaderError {
+    /// A stable identifier of the kind of error: `"io"`, `"rdf-syntax"`, `"invalid-base-iri"`
+    /// or the [code of the storage error](StorageError::code).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Parsing(error) => rdf_parse_error_code(error),
+            Self::Storage(error) => error.code(),
+            Self::InvalidBaseIri { .. } => "invalid-base-iri",
+        }
+    }
+}
+
 impl From<LoaderError> for io::Error {
     #[inline]
     fn from(error: LoaderError) -> Self {
@@ -124,6 +318,22 @@ pub enum SerializerError {
     /// A format compatible with [RDF dataset](https://www.w3.org/TR/rdf11-concepts/#dfn-rdf-dataset) is required.
     #[error("A RDF format supporting datasets was expected, {0} found")]
     DatasetFormatExpected(RdfFormat),
+    /// A line-based format like N-Triples or N-Quads is required.
+    #[error("A line-based RDF format like N-Triples or N-Quads was expected, {0} found")]
+    LineBasedFormatExpected(RdfFormat),
+}
+
+impl SerializerError {
+    /// A stable identifier of the kind of error: `"io"`, `"dataset-format-expected"`,
+    /// `"line-based-format-expected"` or the [code of the storage error](StorageError::code).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "io",
+            Self::Storage(error) => error.code(),
+            Self::DatasetFormatExpected(_) => "dataset-format-expected",
+            Self::LineBasedFormatExpected(_) => "line-based-format-expected",
+        }
+    }
 }
 
 impl From<SerializerError> for io::Error {
@@ -132,9 +342,17 @@ impl From<SerializerError> for io::Error {
         match error {
             SerializerError::Storage(error) => error.into(),
             SerializerError::Io(error) => error,
-            SerializerError::DatasetFormatExpected(_) => {
+            SerializerError::DatasetFormatExpected(_)
+            | SerializerError::LineBasedFormatExpected(_) => {
                 Self::new(io::ErrorKind::InvalidInput, error.to_string())
             }
         }
     }
 }
+
+pub(crate) fn rdf_parse_error_code(error: &RdfParseError) -> &'static str {
+    match error {
+        RdfParseError::Io(_) => "io",
+        RdfParseError::Syntax(_) => "rdf-syntax",
+    }
+}
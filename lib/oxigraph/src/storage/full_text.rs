@@ -0,0 +1,288 @@
+//! In-memory full-text index of the string literals of some predicates.
+
+use crate::model::vocab::xsd;
+use crate::model::{Literal, LiteralRef, NamedNode, QuadRef, Term, TermRef};
+use crate::storage::numeric_encoder::{Decoder, EncodedTerm};
+use crate::storage::{StorageError, StorageReader};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
+use std::sync::Arc;
+
+/// Inverted index from the lowercased words to the literals containing them
+pub struct FullTextIndex {
+    predicates: Arc<HashSet<NamedNode>>,
+    words: BTreeMap<String, HashMap<usize, usize>>,
+    ids: HashMap<Literal, usize>,
+    literals: Vec<Option<IndexedLiteral>>,
+    free_ids: Vec<usize>,
+}
+
+struct IndexedLiteral {
+    literal: Literal,
+    /// Number of quads with an indexed predicate having this literal as object
+    quad_count: usize,
+    word_count: usize,
+}
+
+impl FullTextIndex {
+    pub fn new(predicates: HashSet<NamedNode>) -> Self {
+        Self {
+            predicates: Arc::new(predicates),
+            words: BTreeMap::new(),
+            ids: HashMap::new(),
+            literals: Vec::new(),
+            free_ids: Vec::new(),
+        }
+    }
+
+    pub fn predicates(&self) -> &Arc<HashSet<NamedNode>> {
+        &self.predicates
+    }
+
+    /// Indexes again all the quads of the reader with an indexed predicate
+    pub fn rebuild(&mut self, reader: &StorageReader) -> Result<(), StorageError> {
+        self.words.clear();
+        self.ids.clear();
+        self.literals.clear();
+        self.free_ids.clear();
+        for predicate in Arc::clone(&self.predicates).iter() {
+            let predicate =
+                EncodedTerm::from(predicate.as_ref()).with_str_hash_len(reader.str_hash_len());
+            for quad in reader.quads_for_pattern(None, Some(&predicate), None, None) {
+                let quad = quad?;
+                if let Term::Literal(literal) = reader.decode_term(&quad.object)? {
+                    if is_string_literal(literal.as_ref()) {
+                        self.insert(literal.as_ref());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the changes of a committed transaction, `reader` is used if the index must be rebuilt
+    pub fn apply(
+        &mut self,
+        changes: FullTextChanges,
+        reader: impl FnOnce() -> StorageReader,
+    ) -> Result<(), StorageError> {
+        if changes.rebuild {
+            return self.rebuild(&reader());
+        }
+        for (literal, is_insertion) in changes.changes {
+            if is_insertion {
+                self.insert(literal.as_ref());
+            } else {
+                self.remove(literal.as_ref());
+            }
+        }
+        Ok(())
+    }
+
+    /// Records a new quad with `literal` as object
+    fn insert(&mut self, literal: LiteralRef<'_>) {
+        if let Some(id) = self.ids.get(&literal.into_owned()) {
+            if let Some(indexed) = &mut self.literals[*id] {
+                indexed.quad_count += 1;
+            }
+            return;
+        }
+        let id = self.free_ids.pop().unwrap_or(self.literals.len());
+        let mut word_count = 0;
+        for word in tokenize(literal.value()) {
+            word_count += 1;
+            *self.words.entry(word).or_default().entry(id).or_default() += 1;
+        }
+        let indexed = IndexedLiteral {
+            literal: literal.into_owned(),
+            quad_count: 1,
+            word_count,
+        };
+        if id == self.literals.len() {
+            self.literals.push(Some(indexed));
+        } else {
+            self.literals[id] = Some(indexed);
+        }
+        self.ids.insert(literal.into_owned(), id);
+    }
+
+    /// Records the removal of a quad with `literal` as object
+    fn remove(&mut self, literal: LiteralRef<'_>) {
+        let literal = literal.into_owned();
+        let Some(id) = self.ids.get(&literal).copied() else {
+            return;
+        };
+        if let Some(indexed) = &mut self.literals[id] {
+            indexed.quad_count -= 1;
+            if indexed.quad_count > 0 {
+                return;
+            }
+        }
+        for word in tokenize(literal.value()) {
+            if let Some(literal_ids) = self.words.get_mut(&word) {
+                literal_ids.remove(&id);
+                if literal_ids.is_empty() {
+                    self.words.remove(&word);
+                }
+            }
+        }
+        self.ids.remove(&literal);
+        self.literals[id] = None;
+        self.free_ids.push(id);
+    }
+
+    /// Returns the literals containing all the words of the query with their score, the best matches first
+    ///
+    /// A query word ending with `*` matches all the words starting with it.
+    /// The score is the share of the literal words matched by the query.
+    #[expect(clippy::cast_precision_loss)]
+    pub fn search(&self, query: &str) -> Vec<(Literal, f64)> {
+        let mut matches: Option<HashMap<usize, usize>> = None;
+        for query_word in query.split_whitespace() {
+            let (prefix, is_prefix) = if let Some(prefix) = query_word.strip_suffix('*') {
+                (prefix, true)
+            } else {
+                (query_word, false)
+            };
+            for word in tokenize(prefix) {
+                let mut word_matches = HashMap::<usize, usize>::new();
+                if is_prefix {
+                    for (_, literal_ids) in self
+                        .words
+                        .range::<str, _>((Bound::Included(word.as_str()), Bound::Unbounded))
+                        .take_while(|(w, _)| w.starts_with(&word))
+                    {
+                        for (id, count) in literal_ids {
+                            *word_matches.entry(*id).or_default() += count;
+                        }
+                    }
+                } else if let Some(literal_ids) = self.words.get(&word) {
+                    word_matches.clone_from(literal_ids);
+                }
+                matches = Some(if let Some(matches) = matches {
+                    matches
+                        .into_iter()
+                        .filter_map(|(id, count)| Some((id, count + word_matches.get(&id)?)))
+                        .collect()
+                } else {
+                    word_matches
+                });
+            }
+        }
+        let mut results = matches
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(id, count)| {
+                let indexed = self.literals.get(id)?.as_ref()?;
+                Some((
+                    indexed.literal.clone(),
+                    (count as f64 / indexed.word_count.max(1) as f64).min(1.),
+                ))
+            })
+            .collect::<Vec<_>>();
+        results.sort_by(|(a_literal, a_score), (b_literal, b_score)| {
+            b_score
+                .total_cmp(a_score)
+                .then_with(|| a_literal.value().cmp(b_literal.value()))
+        });
+        results
+    }
+}
+
+/// The changes to the indexed quads done by a transaction, applied to the index after the commit
+#[derive(Default)]
+pub struct FullTextChanges {
+    /// The literals of the inserted (`true`) or removed (`false`) quads in the order of the operations
+    changes: Vec<(Literal, bool)>,
+    rebuild: bool,
+}
+
+impl FullTextChanges {
+    /// Records an inserted (`is_insertion`) or removed quad if its predicate is indexed
+    pub fn record(
+        &mut self,
+        predicates: &HashSet<NamedNode>,
+        quad: QuadRef<'_>,
+        is_insertion: bool,
+    ) {
+        let TermRef::Literal(literal) = quad.object else {
+            return;
+        };
+        if is_string_literal(literal) && predicates.contains(&quad.predicate.into_owned()) {
+            self.changes.push((literal.into_owned(), is_insertion));
+        }
+    }
+
+    /// Records an operation removing a set of quads: the index is then fully rebuilt
+    pub fn rebuild(&mut self) {
+        self.rebuild = true;
+        self.changes.clear();
+    }
+
+    /// The number of recorded changes, to [truncate](Self::truncate) to when rolling back to a savepoint
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Forgets the changes recorded after the first `len` ones
+    ///
+    /// A rebuild requested since is kept, it is valid whatever the changes.
+    pub fn truncate(&mut self, len: usize) {
+        self.changes.truncate(len);
+    }
+}
+
+fn is_string_literal(literal: LiteralRef<'_>) -> bool {
+    literal.datatype() == xsd::STRING || literal.language().is_some()
+}
+
+/// Splits the text into lowercased alphanumeric words
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search() {
+        let mut index = FullTextIndex::new(HashSet::new());
+        index.insert(LiteralRef::new_simple_literal("The City of Light"));
+        index.insert(LiteralRef::new_language_tagged_literal_unchecked(
+            "Lighthouse keeper",
+            "en",
+        ));
+        index.insert(LiteralRef::new_simple_literal("The city"));
+        assert_eq!(
+            index.search("city"),
+            [
+                (Literal::new_simple_literal("The city"), 0.5),
+                (Literal::new_simple_literal("The City of Light"), 0.25)
+            ]
+        );
+        assert_eq!(
+            index.search("LIGHT city"),
+            [(Literal::new_simple_literal("The City of Light"), 0.5)]
+        );
+        assert_eq!(index.search("light*").len(), 2);
+        assert!(index.search("dark").is_empty());
+        assert!(index.search("").is_empty());
+
+        index.insert(LiteralRef::new_simple_literal("The city"));
+        index.remove(LiteralRef::new_simple_literal("The city"));
+        assert_eq!(index.search("city").len(), 2);
+        index.remove(LiteralRef::new_simple_literal("The city"));
+        assert_eq!(index.search("city").len(), 1);
+        index.insert(LiteralRef::new_simple_literal("City"));
+        assert_eq!(
+            index.search("city"),
+            [
+                (Literal::new_simple_literal("City"), 1.),
+                (Literal::new_simple_literal("The City of Light"), 0.25)
+            ]
+        );
+    }
+}
@@ -1,9 +1,9 @@
 use crate::model::{GraphNameRef, NamedOrBlankNodeRef, QuadRef, TermRef};
-use crate::storage::CorruptionError;
 pub use crate::storage::error::StorageError;
 use crate::storage::numeric_encoder::{
     Decoder, EncodedQuad, EncodedTerm, StrHash, StrHashHasher, StrLookup, insert_term,
 };
+use crate::storage::{BulkLoadProgress, CorruptionError};
 use dashmap::iter::Iter;
 use dashmap::mapref::entry::Entry;
 use dashmap::{DashMap, DashSet};
@@ -15,6 +15,7 @@ use std::hash::{BuildHasherDefault, Hash, Hasher};
 use std::mem::transmute;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::Instant;
 
 /// In-memory storage working with MVCC
 ///
@@ -166,6 +167,38 @@ impl MemoryStorageReader {
             .is_some_and(|node| self.is_node_in_range(&node))
     }
 
+    /// Estimates the number of quads matching the pattern from the number of quads ever added with each pattern term
+    ///
+    /// It is the smallest of these numbers, so it is an upper bound if no quad has been removed.
+    pub fn approximate_len_for_pattern(
+        &self,
+        subject: Option<&EncodedTerm>,
+        predicate: Option<&EncodedTerm>,
+        object: Option<&EncodedTerm>,
+        graph_name: Option<&EncodedTerm>,
+    ) -> usize {
+        fn get_count(
+            map: &DashMap<EncodedTerm, (Weak<QuadListNode>, u64), BuildHasherDefault<FxHasher>>,
+            term: Option<&EncodedTerm>,
+        ) -> Option<u64> {
+            Some(map.view(term?, |_, (_, count)| *count).unwrap_or(0))
+        }
+
+        let content = &self.storage.content;
+        [
+            get_count(&content.last_quad_by_subject, subject),
+            get_count(&content.last_quad_by_predicate, predicate),
+            get_count(&content.last_quad_by_object, object),
+            get_count(&content.last_quad_by_graph_name, graph_name),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+        .map_or(content.quad_set.len(), |count| {
+            count.try_into().unwrap_or(usize::MAX)
+        })
+    }
+
     pub fn quads_for_pattern(
         &self,
         subject: Option<&EncodedTerm>,
@@ -260,8 +293,8 @@ impl MemoryStorageReader {
             .is_some_and(|range| self.is_in_range(&range))
     }
 
-    pub fn contains_str(&self, key: &StrHash) -> bool {
-        self.storage.id2str.contains_key(key)
+    pub fn contains_str(&self, key: StrHash) -> bool {
+        self.storage.id2str.contains_key(&key)
     }
 
     /// Validates that all the storage invariants held in the data
@@ -446,8 +479,8 @@ impl MemoryStorageReader {
 }
 
 impl StrLookup for MemoryStorageReader {
-    fn get_str(&self, key: &StrHash) -> Result<Option<String>, StorageError> {
-        Ok(self.storage.id2str.view(key, |_, v| v.clone()))
+    fn get_str(&self, key: StrHash) -> Result<Option<String>, StorageError> {
+        Ok(self.storage.id2str.view(&key, |_, v| v.clone()))
     }
 }
 
@@ -465,6 +498,28 @@ impl MemoryStorageWriter<'_> {
         }
     }
 
+    /// Returns the position in the transaction log to roll back to with [`rollback_to_savepoint`](Self::rollback_to_savepoint)
+    pub fn savepoint(&self) -> usize {
+        self.log.len()
+    }
+
+    /// Undoes the operations done since the log had the length `savepoint`
+    pub fn rollback_to_savepoint(&mut self, savepoint: usize) {
+        // Each log entry is an addition or a removal in the transaction, the inverse operation undoes it
+        for operation in self.log.drain(savepoint..).rev() {
+            match operation {
+                LogEntry::QuadNode(node) => {
+                    node.range.lock().unwrap().toggle(self.transaction_id);
+                }
+                LogEntry::Graph(graph_name) => {
+                    if let Some(mut entry) = self.storage.content.graphs.get_mut(&graph_name) {
+                        entry.value_mut().toggle(self.transaction_id);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn insert(&mut self, quad: QuadRef<'_>) -> bool {
         let encoded: EncodedQuad = quad.into();
         if let Some(node) = self
@@ -609,11 +664,11 @@ impl MemoryStorageWriter<'_> {
         .unwrap()
     }
 
-    fn insert_str(&self, key: &StrHash, value: &str) {
+    fn insert_str(&self, key: StrHash, value: &str) {
         let inserted = self
             .storage
             .id2str
-            .entry(*key)
+            .entry(key)
             .or_insert_with(|| value.into());
         debug_assert_eq!(*inserted, value, "Hash conflict for two strings");
     }
@@ -793,11 +848,11 @@ impl Iterator for MemoryDecodingGraphIterator {
 #[must_use]
 pub struct MemoryStorageBulkLoader {
     storage: MemoryStorage,
-    hooks: Vec<Box<dyn Fn(u64)>>,
+    hooks: Vec<Box<dyn Fn(BulkLoadProgress)>>,
 }
 
 impl MemoryStorageBulkLoader {
-    pub fn on_progress(mut self, callback: impl Fn(u64) + 'static) -> Self {
+    pub fn on_progress(mut self, callback: impl Fn(BulkLoadProgress) + 'static) -> Self {
         self.hooks.push(Box::new(callback));
         self
     }
@@ -810,6 +865,8 @@ impl MemoryStorageBulkLoader {
         // We lock content here to make sure there is not a transaction committing at the same time
         let _transaction_lock = self.storage.transaction_counter.lock().unwrap();
         let mut done_counter = 0;
+        // The clock is only read if needed
+        let start = (!self.hooks.is_empty()).then(Instant::now);
         let version_id = self.storage.version_counter.load(Ordering::Acquire) + 1;
         let mut log = Vec::new();
         for quad in quads {
@@ -821,9 +878,12 @@ impl MemoryStorageBulkLoader {
             .insert(quad?.as_ref());
             log.clear();
             done_counter += 1;
-            if done_counter % 1_000_000 == 0 {
-                for hook in &self.hooks {
-                    hook(done_counter);
+            if let Some(start) = start {
+                if done_counter % 1_000_000 == 0 {
+                    let progress = BulkLoadProgress::new(done_counter, 0, start.elapsed());
+                    for hook in &self.hooks {
+                        hook(progress);
+                    }
                 }
             }
         }
@@ -967,6 +1027,15 @@ impl VersionRange {
         }
     }
 
+    /// Removes the element if it is present in the transaction and adds it otherwise
+    fn toggle(&mut self, transaction_id: usize) {
+        if self.contains(transaction_id) {
+            self.remove(transaction_id);
+        } else {
+            self.add(transaction_id);
+        }
+    }
+
     fn upgrade_transaction(&mut self, transaction_id: usize, version_id: usize) {
         match self {
             VersionRange::Empty => (),
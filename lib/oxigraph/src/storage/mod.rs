@@ -1,35 +1,688 @@
-use crate::model::{GraphNameRef, NamedOrBlankNodeRef, QuadRef};
-pub use crate::storage::error::{CorruptionError, LoaderError, SerializerError, StorageError};
+use crate::model::{
+    GraphName, GraphNameRef, Literal, NamedNode, NamedOrBlankNode, NamedOrBlankNodeRef, QuadRef,
+    TripleRef,
+};
+pub(crate) use crate::storage::error::rdf_parse_error_code;
+pub use crate::storage::error::{
+    CorruptionError, GraphQuotaExceededError, LoaderError, SerializerError, StorageError,
+    TermIdCollisionError, WriteStallError,
+};
+use crate::storage::full_text::{FullTextChanges, FullTextIndex};
 use crate::storage::memory::{
     MemoryDecodingGraphIterator, MemoryStorage, MemoryStorageBulkLoader, MemoryStorageReader,
     MemoryStorageWriter, QuadIterator,
 };
-use crate::storage::numeric_encoder::{EncodedQuad, EncodedTerm, StrHash, StrLookup};
+use crate::storage::numeric_encoder::{
+    Decoder, EncodedQuad, EncodedTerm, STR_HASH_LEN, StrHash, StrLookup,
+};
+pub use crate::storage::owl_rl::OwlRlInference;
+use crate::storage::owl_rl::materialize_owl_rl;
+pub use crate::storage::rdfs::RdfsInference;
+use crate::storage::rdfs::{RdfsChanges, RdfsReasoner};
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 use crate::storage::rocksdb::{
     RocksDbChainedDecodingQuadIterator, RocksDbDecodingGraphIterator, RocksDbStorage,
     RocksDbStorageBulkLoader, RocksDbStorageReader, RocksDbStorageWriter,
 };
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+use crate::storage::rocksdb_wrapper::BackupEngine;
+use crate::storage::statistics::compute_statistics;
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+use crate::storage::statistics::decode_statistics;
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+use crate::storage::statistics::encode_statistics;
 use oxrdf::Quad;
+use oxsdatatypes::DateTime;
+use spareval::DatasetStatistics;
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::ops::{Bound, RangeBounds};
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 use std::path::Path;
-
+use std::path::PathBuf;
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+use std::sync::MutexGuard;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, SystemTime};
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-mod binary_encoder;
+use std::{fmt, io};
+
+#[cfg_attr(
+    not(all(not(target_family = "wasm"), feature = "rocksdb")),
+    expect(dead_code)
+)]
+pub mod binary_encoder;
 mod error;
+mod full_text;
 mod memory;
 pub mod numeric_encoder;
+mod owl_rl;
+mod rdfs;
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 mod rocksdb;
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 mod rocksdb_wrapper;
+mod rules;
 pub mod small_string;
+mod statistics;
+
+/// Number of matching quads under which [`StorageReader::approximate_len_for_pattern`] counts them exactly
+const APPROXIMATE_LEN_EXACT_LIMIT: usize = 1024;
+
+/// How the commits to an on-disk [`Store`](crate::store::Store) are persisted.
+///
+/// See [`Store::with_durability`](crate::store::Store::with_durability).
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+#[non_exhaustive]
+pub enum Durability {
+    /// Each commit waits for the write-ahead log to be synced to the disk.
+    ///
+    /// The concurrent commits are grouped together and share the same sync.
+    /// No committed data is lost on a system crash or a power failure.
+    Sync,
+    /// The commits are written to the operating system buffers without waiting for the disk (the default).
+    ///
+    /// No committed data is lost if the process crashes but the last commits might be lost on a system crash or a power failure.
+    #[default]
+    Async,
+    /// Like [`Async`](Self::Async) but the write-ahead log is also synced to the disk at the given interval in the background.
+    ///
+    /// It bounds the duration of the commits that might be lost on a system crash or a power failure
+    /// while keeping the throughput of the small commits close to [`Async`](Self::Async).
+    Interval(Duration),
+}
+
+/// How much memory and how many threads an on-disk [`Store`](crate::store::Store) uses.
+///
+/// See [`Store::open_with_profile`](crate::store::Store::open_with_profile).
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+#[non_exhaustive]
+pub enum StorageProfile {
+    /// Large block caches and write buffers and background work on all the CPU cores (the default).
+    #[default]
+    Default,
+    /// Small block caches and write buffers and a single background compaction thread.
+    ///
+    /// It fits devices with little memory like Raspberry Pis or CI runners
+    /// at the cost of slower reads and writes on large datasets.
+    Small,
+}
+
+/// A 256 bits key encrypting the files of an on-disk [`Store`](crate::store::Store) with AES-256 in counter mode.
+///
+/// See [`StoreOptions::with_encryption_key`].
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+#[derive(Eq, PartialEq, Clone, Hash)]
+pub struct EncryptionKey([u8; 32]);
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+impl EncryptionKey {
+    /// Builds a key from its 32 bytes.
+    #[inline]
+    pub fn new(key: [u8; 32]) -> Self {
+        Self(key)
+    }
+
+    /// Generates a new random key.
+    #[inline]
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+
+    /// The 32 bytes of the key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+impl From<[u8; 32]> for EncryptionKey {
+    #[inline]
+    fn from(key: [u8; 32]) -> Self {
+        Self(key)
+    }
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // We never print the key itself
+        f.debug_tuple("EncryptionKey").finish_non_exhaustive()
+    }
+}
+
+/// The options to open an on-disk [`Store`](crate::store::Store).
+///
+/// See [`Store::open_with_options`](crate::store::Store::open_with_options).
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct StoreOptions {
+    profile: StorageProfile,
+    encryption_key: Option<EncryptionKey>,
+    compact_term_ids: bool,
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+impl StoreOptions {
+    /// Sets how much memory and how many threads the store uses.
+    #[inline]
+    pub fn with_profile(mut self, profile: StorageProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Encrypts all the database files, including the write-ahead log, with the given key.
+    ///
+    /// The same key must be given each time the store is opened.
+    #[inline]
+    pub fn with_encryption_key(mut self, key: EncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// Identifies the IRIs, blank nodes and literals that are not inlined in the index keys with 64 bits hashes instead of 128 bits ones.
+    ///
+    /// It makes the indexes smaller and reduces the cache pressure, for example on embedded or IoT devices,
+    /// but the inserts of strings whose hashes collide with the one of a string already in the store
+    /// fail with a [`StorageError::TermIdCollision`] error.
+    /// Collisions are unlikely under a few millions of distinct strings.
+    ///
+    /// The identifier size is saved in the store when it is created and the store keeps it afterward:
+    /// this option is ignored when opening an existing store.
+    #[inline]
+    pub fn with_compact_term_ids(mut self) -> Self {
+        self.compact_term_ids = true;
+        self
+    }
+}
+
+/// The progress of a [`BulkLoader`](crate::store::BulkLoader) operation.
+///
+/// See [`BulkLoader::on_detailed_progress`](crate::store::BulkLoader::on_detailed_progress).
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+pub struct BulkLoadProgress {
+    loaded_quads: u64,
+    written_bytes: u64,
+    elapsed: Duration,
+}
+
+impl BulkLoadProgress {
+    pub(crate) fn new(loaded_quads: u64, written_bytes: u64, elapsed: Duration) -> Self {
+        Self {
+            loaded_quads,
+            written_bytes,
+            elapsed,
+        }
+    }
+
+    /// The number of quads loaded so far.
+    #[inline]
+    pub fn loaded_quads(&self) -> u64 {
+        self.loaded_quads
+    }
+
+    /// The number of bytes written to the disk so far, including the temporary files.
+    ///
+    /// It is always 0 for in-memory stores.
+    #[inline]
+    pub fn written_bytes(&self) -> u64 {
+        self.written_bytes
+    }
+
+    /// The time elapsed since the beginning of the load operation.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The average number of quads loaded per second since the beginning of the load operation.
+    #[inline]
+    #[expect(clippy::cast_precision_loss)]
+    pub fn quads_per_second(&self) -> f64 {
+        let elapsed = self.elapsed.as_secs_f64();
+        if elapsed > 0. {
+            self.loaded_quads as f64 / elapsed
+        } else {
+            0.
+        }
+    }
+}
+
+/// A directory storing incremental backups of on-disk [`Store`](crate::store::Store)s.
+///
+/// Each backup only copies the database files that are not already stored in the repository
+/// so regular backups of large stores are cheap.
+///
+/// See [`Store::incremental_backup`](crate::store::Store::incremental_backup).
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+pub struct BackupRepository {
+    engine: Mutex<BackupEngine>,
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+impl BackupRepository {
+    /// Opens the backup repository in the given directory, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Ok(Self {
+            engine: Mutex::new(BackupEngine::open(path.as_ref())?),
+        })
+    }
+
+    /// Lists the backups stored in the repository, the oldest first.
+    pub fn backups(&self) -> Result<Vec<BackupInfo>, StorageError> {
+        Ok(self.engine()?.backups())
+    }
+
+    /// Checks that the files of the backup `id` are present and that their checksums are valid.
+    pub fn verify(&self, id: u32) -> Result<(), StorageError> {
+        self.engine()?.verify(id)
+    }
+
+    /// Restores the backup `id` into the `target_directory`.
+    ///
+    /// The restored database can then be opened using [`Store::open`](crate::store::Store::open).
+    /// <div class="warning">The content of the `target_directory` is overridden.</div>
+    pub fn restore(&self, id: u32, target_directory: impl AsRef<Path>) -> Result<(), StorageError> {
+        self.engine()?.restore(id, target_directory.as_ref())
+    }
+
+    /// Restores the most recent backup into the `target_directory`.
+    pub fn restore_latest(&self, target_directory: impl AsRef<Path>) -> Result<(), StorageError> {
+        let engine = self.engine()?;
+        let Some(latest) = engine.backups().last().map(BackupInfo::id) else {
+            return Err(StorageError::Other(
+                "The backup repository does not contain any backup".into(),
+            ));
+        };
+        engine.restore(latest, target_directory.as_ref())
+    }
+
+    /// Deletes the oldest backups, only keeping the `backups_to_keep` most recent ones.
+    ///
+    /// Files shared with the kept backups are not removed.
+    pub fn purge_old_backups(&self, backups_to_keep: u32) -> Result<(), StorageError> {
+        self.engine()?.purge_old_backups(backups_to_keep)
+    }
+
+    pub(crate) fn engine(&self) -> Result<MutexGuard<'_, BackupEngine>, StorageError> {
+        self.engine
+            .lock()
+            .map_err(|_| io::Error::other("Mutex poisoned").into())
+    }
+}
+
+/// A backup stored in a [`BackupRepository`].
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+pub struct BackupInfo {
+    id: u32,
+    timestamp: SystemTime,
+    size: u64,
+    file_count: u32,
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+impl BackupInfo {
+    pub(crate) fn new(id: u32, timestamp: SystemTime, size: u64, file_count: u32) -> Self {
+        Self {
+            id,
+            timestamp,
+            size,
+            file_count,
+        }
+    }
+
+    /// The identifier of the backup, increasing with each new backup.
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The time at which the backup has been created.
+    #[inline]
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// The size in bytes of the backup files, including the ones shared with other backups.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The number of files of the backup, including the ones shared with other backups.
+    #[inline]
+    pub fn file_count(&self) -> u32 {
+        self.file_count
+    }
+}
+
+/// The maximal size of a named graph of a [`Store`](crate::store::Store).
+///
+/// See [`Store::with_graph_quota`](crate::store::Store::with_graph_quota).
+///
+/// ```
+/// use oxigraph::store::GraphQuota;
+///
+/// let quota = GraphQuota::default()
+///     .with_max_quads(10_000)
+///     .with_max_bytes(1_000_000);
+/// assert_eq!(quota.max_quads(), Some(10_000));
+/// ```
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+pub struct GraphQuota {
+    max_quads: Option<usize>,
+    max_bytes: Option<usize>,
+}
+
+impl GraphQuota {
+    /// Limits the number of quads in the graph.
+    #[inline]
+    #[must_use]
+    pub fn with_max_quads(mut self, max_quads: usize) -> Self {
+        self.max_quads = Some(max_quads);
+        self
+    }
+
+    /// Limits the size of the graph in bytes, as counted by [`GraphUsage::bytes`].
+    #[inline]
+    #[must_use]
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// The maximal number of quads in the graph, if limited.
+    #[inline]
+    pub fn max_quads(&self) -> Option<usize> {
+        self.max_quads
+    }
+
+    /// The maximal size of the graph in bytes, if limited.
+    #[inline]
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    fn allows(&self, usage: GraphUsage) -> bool {
+        self.max_quads.is_none_or(|max| usage.quads <= max)
+            && self.max_bytes.is_none_or(|max| usage.bytes <= max)
+    }
+}
+
+/// Statistics about the storage of a [`Store`](crate::store::Store), useful for capacity planning.
+///
+/// See [`Store::storage_stats`](crate::store::Store::storage_stats).
+#[derive(Eq, PartialEq, Debug, Clone, Hash, Default)]
+pub struct StorageStats {
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    column_families: Vec<ColumnFamilyStats>,
+    graphs: Vec<(GraphName, usize)>,
+}
+
+impl StorageStats {
+    /// The statistics of each RocksDB column family of an on-disk store.
+    ///
+    /// The quads are stored in the `spog`, `posg`, `ospg`, `gspo`, `gpos` and `gosp` indexes for the named graphs
+    /// and in the `dspo`, `dpos` and `dosp` indexes for the default graph.
+    /// The strings are stored in `id2str` and the named graphs in `graphs`.
+    ///
+    /// It is empty for in-memory stores.
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    #[inline]
+    pub fn column_families(&self) -> &[ColumnFamilyStats] {
+        &self.column_families
+    }
+
+    /// The total size in bytes of the data files of an on-disk store.
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    #[inline]
+    pub fn disk_size(&self) -> u64 {
+        self.column_families
+            .iter()
+            .map(ColumnFamilyStats::disk_size)
+            .sum()
+    }
+
+    /// The number of quads of each graph, starting with the default graph.
+    ///
+    /// The numbers are estimated from the size of the indexes for on-disk stores and exact for in-memory stores.
+    #[inline]
+    pub fn graphs(&self) -> &[(GraphName, usize)] {
+        &self.graphs
+    }
+
+    /// The number of quads in the store, estimated like the [graph sizes](Self::graphs).
+    #[inline]
+    pub fn approximate_len(&self) -> usize {
+        self.graphs.iter().map(|(_, len)| len).sum()
+    }
+}
+
+/// The statistics of a RocksDB column family of an on-disk [`Store`](crate::store::Store).
+///
+/// See [`StorageStats::column_families`].
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+pub struct ColumnFamilyStats {
+    name: &'static str,
+    disk_size: u64,
+    memory_size: u64,
+    approximate_key_count: u64,
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+impl ColumnFamilyStats {
+    pub(crate) fn new(
+        name: &'static str,
+        disk_size: u64,
+        memory_size: u64,
+        approximate_key_count: u64,
+    ) -> Self {
+        Self {
+            name,
+            disk_size,
+            memory_size,
+            approximate_key_count,
+        }
+    }
+
+    /// The name of the column family like `spog` or `id2str`.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The size in bytes of the data files of the column family.
+    #[inline]
+    pub fn disk_size(&self) -> u64 {
+        self.disk_size
+    }
+
+    /// The size in bytes of the in-memory write buffers of the column family.
+    #[inline]
+    pub fn memory_size(&self) -> u64 {
+        self.memory_size
+    }
+
+    /// The estimated number of keys in the column family.
+    #[inline]
+    pub fn approximate_key_count(&self) -> u64 {
+        self.approximate_key_count
+    }
+}
+
+/// The size of a graph of a [`Store`](crate::store::Store).
+///
+/// See [`Store::graph_usage`](crate::store::Store::graph_usage).
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+pub struct GraphUsage {
+    quads: usize,
+    bytes: usize,
+}
+
+impl GraphUsage {
+    /// The number of quads in the graph.
+    #[inline]
+    pub fn quads(&self) -> usize {
+        self.quads
+    }
+
+    /// The size in bytes of the [N-Triples](https://www.w3.org/TR/n-triples/) serialization of the graph triples.
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    fn add(&mut self, triple: TripleRef<'_>) {
+        self.quads += 1;
+        self.bytes += ntriples_size(triple);
+    }
+
+    fn remove(&mut self, triple: TripleRef<'_>) {
+        self.quads = self.quads.saturating_sub(1);
+        self.bytes = self.bytes.saturating_sub(ntriples_size(triple));
+    }
+}
+
+/// The size of the N-Triples line of `triple`, including the final ` .` and line jump
+fn ntriples_size(triple: TripleRef<'_>) -> usize {
+    triple.to_string().len() + 3
+}
+
+/// The graph quotas indexed by encoded graph name
+type GraphQuotas = HashMap<EncodedTerm, (NamedOrBlankNode, GraphQuota)>;
+
+/// The last computed statistics shared between the clones of the storage
+type SharedStatistics = Arc<RwLock<Option<Arc<DatasetStatistics>>>>;
+
+/// The full-text index, if enabled, shared between the clones of the storage
+type SharedFullTextIndex = Arc<RwLock<Option<FullTextIndex>>>;
+
+/// The RDFS reasoner, if enabled, shared between the clones of the storage
+type SharedRdfsReasoner = Arc<RwLock<Option<Arc<RdfsReasoner>>>>;
+
+/// The OWL 2 RL inference configuration, if enabled, shared between the clones of the storage
+type SharedOwlRlInference = Arc<RwLock<Option<OwlRlInference>>>;
+
+/// The channels to send the committed changes to, shared between the clones of the storage
+type SharedWatchers = Arc<Mutex<Vec<Sender<Vec<StoreEvent>>>>>;
+
+/// The expiration times of the quads inserted with a time-to-live
+type Expirations = HashMap<EncodedQuad, SystemTime>;
+
+/// The expiration times of the committed quads, shared between the clones of the storage
+type SharedExpirations = Arc<RwLock<Arc<Expirations>>>;
+
+/// The quads that were expired when a [`StorageReader`] has been created
+#[derive(Clone)]
+struct ExpirationFilter {
+    expirations: Arc<Expirations>,
+    now: SystemTime,
+}
+
+impl ExpirationFilter {
+    /// Returns `None` if there is no quad with an expiration time
+    fn new(expirations: Arc<Expirations>) -> Option<Self> {
+        (!expirations.is_empty()).then(|| Self {
+            expirations,
+            now: SystemTime::now(),
+        })
+    }
+
+    fn is_expired(&self, quad: &EncodedQuad) -> bool {
+        self.expirations
+            .get(quad)
+            .is_some_and(|expiration| *expiration <= self.now)
+    }
+
+    fn expired_quads(&self) -> impl Iterator<Item = &EncodedQuad> {
+        self.expirations
+            .iter()
+            .filter(|(_, expiration)| **expiration <= self.now)
+            .map(|(quad, _)| quad)
+    }
+}
+
+/// The changes to the expiration times done by a transaction
+#[derive(Default)]
+struct ExpirationChanges {
+    /// The new expiration times, `None` making the quad permanent
+    changes: HashMap<EncodedQuad, Option<SystemTime>>,
+    /// The previous values of the entries of `changes`, the most recent last, to roll back to a savepoint
+    #[expect(clippy::option_option)]
+    undo_log: Vec<(EncodedQuad, Option<Option<SystemTime>>)>,
+}
+
+impl ExpirationChanges {
+    /// The expiration time set by the transaction, `None` if it has not been changed
+    #[expect(clippy::option_option)]
+    fn get(&self, quad: &EncodedQuad) -> Option<Option<SystemTime>> {
+        self.changes.get(quad).copied()
+    }
+
+    fn set(&mut self, quad: EncodedQuad, expiration: Option<SystemTime>) {
+        let previous = self.changes.insert(quad.clone(), expiration);
+        self.undo_log.push((quad, previous));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn quads(&self) -> impl Iterator<Item = &EncodedQuad> {
+        self.changes.keys()
+    }
+
+    /// The number of recorded changes, to [truncate](Self::truncate) to when rolling back to a savepoint
+    fn len(&self) -> usize {
+        self.undo_log.len()
+    }
+
+    /// Undoes the changes recorded after the first `len` ones
+    fn truncate(&mut self, len: usize) {
+        for (quad, previous) in self.undo_log.drain(len..).rev() {
+            if let Some(previous) = previous {
+                self.changes.insert(quad, previous);
+            } else {
+                self.changes.remove(&quad);
+            }
+        }
+    }
+}
+
+/// A change committed to a [`Store`](crate::store::Store).
+///
+/// See [`Store::watch`](crate::store::Store::watch).
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub enum StoreEvent {
+    /// The quad has been inserted into the store.
+    Inserted(Quad),
+    /// The quad has been removed from the store.
+    Removed(Quad),
+}
 
 /// Low level storage primitives
 #[derive(Clone)]
 pub struct Storage {
     kind: StorageKind,
+    graph_quotas: Arc<GraphQuotas>,
+    statistics: SharedStatistics,
+    full_text_index: SharedFullTextIndex,
+    rdfs_reasoner: SharedRdfsReasoner,
+    owl_rl_inference: SharedOwlRlInference,
+    watchers: SharedWatchers,
+    expirations: SharedExpirations,
+    /// Incremented after each write, shared between the clones of the storage
+    generation: Arc<AtomicU64>,
 }
 
 #[derive(Clone)]
@@ -44,52 +697,463 @@ impl Storage {
     pub fn new() -> Result<Self, StorageError> {
         Ok(Self {
             kind: StorageKind::Memory(MemoryStorage::new()),
+            graph_quotas: Arc::default(),
+            statistics: SharedStatistics::default(),
+            full_text_index: SharedFullTextIndex::default(),
+            rdfs_reasoner: SharedRdfsReasoner::default(),
+            owl_rl_inference: SharedOwlRlInference::default(),
+            watchers: SharedWatchers::default(),
+            expirations: SharedExpirations::default(),
+            generation: Arc::default(),
         })
     }
 
     #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-    pub fn open(path: &Path) -> Result<Self, StorageError> {
+    pub fn open(path: &Path, options: StoreOptions) -> Result<Self, StorageError> {
+        let StoreOptions {
+            profile,
+            encryption_key,
+            compact_term_ids,
+        } = options;
+        Self::from_rocksdb(RocksDbStorage::open(
+            path,
+            profile,
+            encryption_key.as_ref(),
+            compact_term_ids,
+        )?)
+    }
+
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn rotate_encryption_key(
+        path: &Path,
+        old_key: &EncryptionKey,
+        new_key: &EncryptionKey,
+    ) -> Result<(), StorageError> {
+        RocksDbStorage::rotate_encryption_key(path, old_key, new_key)
+    }
+
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn open_read_only(path: &Path) -> Result<Self, StorageError> {
+        Self::from_rocksdb(RocksDbStorage::open_read_only(path)?)
+    }
+
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn open_secondary(
+        primary_path: &Path,
+        secondary_path: &Path,
+    ) -> Result<Self, StorageError> {
+        Self::from_rocksdb(RocksDbStorage::open_secondary(
+            primary_path,
+            secondary_path,
+        )?)
+    }
+
+    /// Wraps the RocksDB storage, loading the statistics it contains
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    fn from_rocksdb(storage: RocksDbStorage) -> Result<Self, StorageError> {
+        let statistics = storage
+            .statistics()?
+            .map(|statistics| decode_statistics(&statistics))
+            .transpose()?
+            .map(Arc::new);
+        let expirations = Arc::new(RwLock::new(Arc::new(storage.expirations()?)));
         Ok(Self {
-            kind: StorageKind::RocksDb(RocksDbStorage::open(path)?),
+            kind: StorageKind::RocksDb(storage),
+            graph_quotas: Arc::default(),
+            statistics: Arc::new(RwLock::new(statistics)),
+            full_text_index: SharedFullTextIndex::default(),
+            rdfs_reasoner: SharedRdfsReasoner::default(),
+            owl_rl_inference: SharedOwlRlInference::default(),
+            watchers: SharedWatchers::default(),
+            expirations,
+            generation: Arc::default(),
         })
     }
 
     #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-    pub fn open_read_only(path: &Path) -> Result<Self, StorageError> {
+    pub fn with_durability(self, durability: Durability) -> Result<Self, StorageError> {
         Ok(Self {
-            kind: StorageKind::RocksDb(RocksDbStorage::open_read_only(path)?),
+            kind: match self.kind {
+                StorageKind::RocksDb(storage) => {
+                    StorageKind::RocksDb(storage.with_durability(durability)?)
+                }
+                StorageKind::Memory(storage) => StorageKind::Memory(storage),
+            },
+            graph_quotas: self.graph_quotas,
+            statistics: self.statistics,
+            full_text_index: self.full_text_index,
+            rdfs_reasoner: self.rdfs_reasoner,
+            owl_rl_inference: self.owl_rl_inference,
+            watchers: self.watchers,
+            expirations: self.expirations,
+            generation: self.generation,
+        })
+    }
+
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn with_write_stall_errors(self) -> Result<Self, StorageError> {
+        Ok(Self {
+            kind: match self.kind {
+                StorageKind::RocksDb(storage) => {
+                    StorageKind::RocksDb(storage.with_write_stall_errors()?)
+                }
+                StorageKind::Memory(storage) => StorageKind::Memory(storage),
+            },
+            graph_quotas: self.graph_quotas,
+            statistics: self.statistics,
+            full_text_index: self.full_text_index,
+            rdfs_reasoner: self.rdfs_reasoner,
+            owl_rl_inference: self.owl_rl_inference,
+            watchers: self.watchers,
+            expirations: self.expirations,
+            generation: self.generation,
         })
     }
 
+    pub fn with_graph_quota(mut self, graph_name: NamedOrBlankNode, quota: GraphQuota) -> Self {
+        let encoded_graph_name =
+            EncodedTerm::from(graph_name.as_ref()).with_str_hash_len(self.str_hash_len());
+        Arc::make_mut(&mut self.graph_quotas).insert(encoded_graph_name, (graph_name, quota));
+        self
+    }
+
+    /// The number of bytes of the string identifiers of the storage, persisted when it is created
+    ///
+    /// All the terms given to the storage must be [encoded with it](EncodedTerm::with_str_hash_len)
+    /// to be equal to the ones it returns.
+    pub fn str_hash_len(&self) -> usize {
+        match &self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageKind::RocksDb(storage) => storage.str_hash_len(),
+            StorageKind::Memory(_) => STR_HASH_LEN,
+        }
+    }
+
+    /// The number of writes done since the storage has been opened
+    ///
+    /// It only grows and is bumped after the changes are visible to the new snapshots.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    fn bump_generation(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub fn graph_quotas(&self) -> impl Iterator<Item = (&NamedOrBlankNode, GraphQuota)> {
+        self.graph_quotas
+            .values()
+            .map(|(graph_name, quota)| (graph_name, *quota))
+    }
+
     pub fn snapshot(&self) -> StorageReader {
+        let statistics = self.current_statistics();
+        let expired = ExpirationFilter::new(self.current_expirations());
         match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageKind::RocksDb(storage) => StorageReader {
                 kind: StorageReaderKind::RocksDb(storage.snapshot()),
+                statistics,
+                expired,
             },
             StorageKind::Memory(storage) => StorageReader {
                 kind: StorageReaderKind::Memory(storage.snapshot()),
+                statistics,
+                expired,
             },
         }
     }
 
+    fn current_expirations(&self) -> Arc<Expirations> {
+        // The expirations are only updated after the commits, we ignore poisoning
+        Arc::clone(
+            &self
+                .expirations
+                .read()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// Applies the expiration time changes of a committed transaction
+    fn update_expirations(&self, changes: ExpirationChanges) -> Result<(), StorageError> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let mut expirations = self
+            .expirations
+            .write()
+            .map_err(|_| StorageError::Other("The quad expirations lock is poisoned".into()))?;
+        let expirations = Arc::make_mut(&mut expirations);
+        for (quad, expiration) in changes.changes {
+            if let Some(expiration) = expiration {
+                expirations.insert(quad, expiration);
+            } else {
+                expirations.remove(&quad);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the expired quads and returns their number
+    pub fn purge_expired(&self) -> Result<usize, StorageError> {
+        let Some(expired) = ExpirationFilter::new(self.current_expirations()) else {
+            return Ok(0);
+        };
+        self.transaction(|mut writer| {
+            let mut removed = 0;
+            for quad in expired.expired_quads() {
+                if writer.remove_if_expired(quad, expired.now)? {
+                    removed += 1;
+                }
+            }
+            Ok::<_, StorageError>(removed)
+        })
+    }
+
+    /// Computes the statistics of the current content, persists them and uses them for the next snapshots
+    pub fn update_statistics(&self) -> Result<(), StorageError> {
+        let statistics = compute_statistics(&self.snapshot())?;
+        match &self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageKind::RocksDb(storage) => {
+                storage.save_statistics(&encode_statistics(&statistics))?;
+            }
+            StorageKind::Memory(_) => (),
+        }
+        *self
+            .statistics
+            .write()
+            .map_err(|_| StorageError::Other("The statistics lock is poisoned".into()))? =
+            Some(Arc::new(statistics));
+        Ok(())
+    }
+
+    fn current_statistics(&self) -> Option<Arc<DatasetStatistics>> {
+        // The statistics are only a hint for the optimizer, we ignore poisoning
+        self.statistics
+            .read()
+            .map_or_else(|e| e.into_inner().clone(), |statistics| statistics.clone())
+    }
+
+    /// Indexes the string literals that are objects of the given predicates, replacing the previous full-text index
+    pub fn set_full_text_index(&self, predicates: HashSet<NamedNode>) -> Result<(), StorageError> {
+        let mut full_text_index = self
+            .full_text_index
+            .write()
+            .map_err(|_| StorageError::Other("The full-text index lock is poisoned".into()))?;
+        let mut index = FullTextIndex::new(predicates);
+        index.rebuild(&self.snapshot())?;
+        *full_text_index = Some(index);
+        self.bump_generation();
+        Ok(())
+    }
+
+    /// Searches the literals matching the query in the full-text index, empty if there is no index
+    pub fn full_text_search(&self, query: &str) -> Vec<(Literal, f64)> {
+        // The index is only updated after the commits, we ignore poisoning
+        let index = self
+            .full_text_index
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        index
+            .as_ref()
+            .map(|index| index.search(query))
+            .unwrap_or_default()
+    }
+
+    fn full_text_predicates(&self) -> Option<Arc<HashSet<NamedNode>>> {
+        self.full_text_index
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .map(|index| Arc::clone(index.predicates()))
+    }
+
+    /// Applies the changes of a committed transaction to the full-text index if there is one
+    fn update_full_text_index(&self, changes: FullTextChanges) -> Result<(), StorageError> {
+        let mut full_text_index = self
+            .full_text_index
+            .write()
+            .map_err(|_| StorageError::Other("The full-text index lock is poisoned".into()))?;
+        if let Some(index) = full_text_index.as_mut() {
+            index.apply(changes, || self.snapshot())?;
+        }
+        Ok(())
+    }
+
+    /// Materializes the RDFS entailments and keeps them up to date according to the given configuration
+    pub fn set_rdfs_inference(&self, inference: RdfsInference) -> Result<(), StorageError> {
+        let reasoner = RdfsReasoner::new(inference, &self.snapshot())?;
+        self.transaction(|mut writer| reasoner.materialize(&mut writer))?;
+        *self
+            .rdfs_reasoner
+            .write()
+            .map_err(|_| StorageError::Other("The RDFS reasoner lock is poisoned".into()))? =
+            Some(Arc::new(reasoner));
+        Ok(())
+    }
+
+    /// Materializes again all the RDFS entailments if the inference is enabled
+    pub fn materialize_rdfs_inference(&self) -> Result<(), StorageError> {
+        if let Some(reasoner) = self.rdfs_reasoner() {
+            self.set_rdfs_inference(reasoner.inference().clone())?;
+        }
+        Ok(())
+    }
+
+    /// Materializes the OWL 2 RL entailments into the graph given by the configuration
+    pub fn set_owl_rl_inference(&self, inference: OwlRlInference) -> Result<(), StorageError> {
+        self.transaction(|mut writer| materialize_owl_rl(&inference, &mut writer))?;
+        *self
+            .owl_rl_inference
+            .write()
+            .map_err(|_| StorageError::Other("The OWL 2 RL inference lock is poisoned".into()))? =
+            Some(inference);
+        Ok(())
+    }
+
+    /// Materializes again all the OWL 2 RL entailments if the inference is enabled
+    pub fn materialize_owl_rl_inference(&self) -> Result<(), StorageError> {
+        let inference = self
+            .owl_rl_inference
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some(inference) = inference {
+            self.transaction(|mut writer| materialize_owl_rl(&inference, &mut writer))?;
+        }
+        Ok(())
+    }
+
+    fn rdfs_reasoner(&self) -> Option<Arc<RdfsReasoner>> {
+        self.rdfs_reasoner
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Updates the RDFS entailments after a committed transaction in a new transaction
+    fn update_rdfs_inference(
+        &self,
+        reasoner: &RdfsReasoner,
+        changes: &RdfsChanges,
+    ) -> Result<(), StorageError> {
+        if changes.needs_rebuild() {
+            self.materialize_rdfs_inference()
+        } else if changes.is_empty() {
+            Ok(())
+        } else {
+            self.transaction(|mut writer| reasoner.update(&mut writer, changes))
+        }
+    }
+
+    /// Returns a channel receiving the changes of each transaction committed after this call
+    pub fn watch(&self) -> Result<Receiver<Vec<StoreEvent>>, StorageError> {
+        let (sender, receiver) = channel();
+        self.watchers
+            .lock()
+            .map_err(|_| StorageError::Other("The store watchers lock is poisoned".into()))?
+            .push(sender);
+        Ok(receiver)
+    }
+
+    fn has_watchers(&self) -> bool {
+        self.watchers
+            .lock()
+            .is_ok_and(|watchers| !watchers.is_empty())
+    }
+
+    /// Sends the changes of a committed transaction to the watchers, forgetting the ones that have been dropped
+    fn notify_watchers(&self, events: &[StoreEvent]) -> Result<(), StorageError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.watchers
+            .lock()
+            .map_err(|_| StorageError::Other("The store watchers lock is poisoned".into()))?
+            .retain(|watcher| watcher.send(events.to_vec()).is_ok());
+        Ok(())
+    }
+
     pub fn transaction<T, E: Error + 'static + From<StorageError>>(
         &self,
         f: impl for<'a> Fn(StorageWriter<'a>) -> Result<T, E>,
     ) -> Result<T, E> {
-        match &self.kind {
+        let full_text_predicates = self.full_text_predicates();
+        let full_text_changes = RefCell::new(FullTextChanges::default());
+        // The entailments of the non-incremental inference are only computed on demand
+        let rdfs_reasoner = self
+            .rdfs_reasoner()
+            .filter(|reasoner| reasoner.inference().is_incremental());
+        let rdfs_changes = RefCell::new(RdfsChanges::default());
+        let events = self.has_watchers().then(|| RefCell::new(Vec::new()));
+        let expirations = self.current_expirations();
+        let expiration_changes = RefCell::new(ExpirationChanges::default());
+        let result = match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageKind::RocksDb(storage) => storage.transaction(|transaction| {
+                // The transaction might be retried
+                full_text_changes.take();
+                rdfs_changes.take();
+                if let Some(events) = &events {
+                    events.take();
+                }
+                expiration_changes.take();
                 f(StorageWriter {
                     kind: StorageWriterKind::RocksDb(transaction),
+                    graph_quotas: &self.graph_quotas,
+                    graph_usages: HashMap::new(),
+                    statistics: self.current_statistics(),
+                    full_text_predicates: full_text_predicates.as_deref(),
+                    full_text_changes: &full_text_changes,
+                    rdfs_graph_name: rdfs_reasoner.as_deref().map(RdfsReasoner::graph_name),
+                    rdfs_changes: &rdfs_changes,
+                    events: events.as_ref(),
+                    expirations: &expirations,
+                    expiration_changes: &expiration_changes,
+                    savepoints: Vec::new(),
                 })
             }),
             StorageKind::Memory(storage) => storage.transaction(|transaction| {
+                full_text_changes.take();
+                rdfs_changes.take();
+                if let Some(events) = &events {
+                    events.take();
+                }
+                expiration_changes.take();
                 f(StorageWriter {
                     kind: StorageWriterKind::Memory(transaction),
+                    graph_quotas: &self.graph_quotas,
+                    graph_usages: HashMap::new(),
+                    statistics: self.current_statistics(),
+                    full_text_predicates: full_text_predicates.as_deref(),
+                    full_text_changes: &full_text_changes,
+                    rdfs_graph_name: rdfs_reasoner.as_deref().map(RdfsReasoner::graph_name),
+                    rdfs_changes: &rdfs_changes,
+                    events: events.as_ref(),
+                    expirations: &expirations,
+                    expiration_changes: &expiration_changes,
+                    savepoints: Vec::new(),
                 })
             }),
+        }?;
+        let expirations_result = self.update_expirations(expiration_changes.into_inner());
+        let full_text_result = if full_text_predicates.is_some() {
+            self.update_full_text_index(full_text_changes.into_inner())
+        } else {
+            Ok(())
+        };
+        // Bumped after the full-text index update to not have results using the old index with the new generation
+        self.bump_generation();
+        expirations_result?;
+        full_text_result?;
+        if let Some(events) = events {
+            self.notify_watchers(&events.into_inner())?;
         }
+        if let Some(reasoner) = rdfs_reasoner {
+            self.update_rdfs_inference(&reasoner, &rdfs_changes.into_inner())?;
+        }
+        Ok(result)
     }
 
     #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
@@ -101,8 +1165,10 @@ impl Storage {
         }
     }
 
+    /// Removes the expired quads and compacts the storage
     #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
     pub fn compact(&self) -> Result<(), StorageError> {
+        self.purge_expired()?;
         match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageKind::RocksDb(storage) => storage.compact(),
@@ -110,6 +1176,29 @@ impl Storage {
         }
     }
 
+    pub fn storage_stats(&self) -> Result<StorageStats, StorageError> {
+        let reader = self.snapshot();
+        let mut graphs = vec![(
+            GraphName::DefaultGraph,
+            reader.approximate_graph_len(&EncodedTerm::DefaultGraph)?,
+        )];
+        for graph_name in reader.named_graphs() {
+            let graph_name = graph_name?;
+            graphs.push((
+                reader.decode_named_or_blank_node(&graph_name)?.into(),
+                reader.approximate_graph_len(&graph_name)?,
+            ));
+        }
+        Ok(StorageStats {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            column_families: match &self.kind {
+                StorageKind::RocksDb(storage) => storage.column_family_stats(),
+                StorageKind::Memory(_) => Vec::new(),
+            },
+            graphs,
+        })
+    }
+
     #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
     pub fn backup(&self, target_directory: &Path) -> Result<(), StorageError> {
         match &self.kind {
@@ -121,21 +1210,67 @@ impl Storage {
         }
     }
 
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn incremental_backup(
+        &self,
+        repository: &BackupRepository,
+    ) -> Result<BackupInfo, StorageError> {
+        match &self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageKind::RocksDb(storage) => storage.incremental_backup(&mut *repository.engine()?),
+            StorageKind::Memory(_) => Err(StorageError::Other(
+                "It is not possible to backup an in-memory database".into(),
+            )),
+        }
+    }
+
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn try_catch_up_with_primary(&self) -> Result<(), StorageError> {
+        match &self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageKind::RocksDb(storage) => {
+                let result = storage.try_catch_up_with_primary().and_then(|()| {
+                    *self.expirations.write().map_err(|_| {
+                        StorageError::Other("The quad expirations lock is poisoned".into())
+                    })? = Arc::new(storage.expirations()?);
+                    self.rebuild_full_text_index()
+                });
+                self.bump_generation();
+                result
+            }
+            StorageKind::Memory(_) => Err(StorageError::Other(
+                "It is not possible to catch up with a primary from an in-memory database".into(),
+            )),
+        }
+    }
+
     pub fn bulk_loader(&self) -> StorageBulkLoader {
         match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageKind::RocksDb(storage) => StorageBulkLoader {
                 kind: StorageBulkLoaderKind::RocksDb(storage.bulk_loader()),
+                storage: self.clone(),
             },
             StorageKind::Memory(storage) => StorageBulkLoader {
                 kind: StorageBulkLoaderKind::Memory(storage.bulk_loader()),
+                storage: self.clone(),
             },
         }
     }
+
+    /// Indexes again the current content in the full-text index if there is one
+    fn rebuild_full_text_index(&self) -> Result<(), StorageError> {
+        let mut changes = FullTextChanges::default();
+        changes.rebuild();
+        self.update_full_text_index(changes)
+    }
 }
 
 pub struct StorageReader {
     kind: StorageReaderKind,
+    statistics: Option<Arc<DatasetStatistics>>,
+    /// The expired quads to hide, `None` if there is no quad with an expiration time
+    expired: Option<ExpirationFilter>,
 }
 
 enum StorageReaderKind {
@@ -149,15 +1284,51 @@ enum StorageReaderKind {
     expect(clippy::unnecessary_wraps)
 )]
 impl StorageReader {
-    pub fn len(&self) -> Result<usize, StorageError> {
+    /// See [`Storage::str_hash_len`]
+    pub fn str_hash_len(&self) -> usize {
         match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-            StorageReaderKind::RocksDb(reader) => reader.len(),
-            StorageReaderKind::Memory(reader) => Ok(reader.len()),
+            StorageReaderKind::RocksDb(reader) => reader.str_hash_len(),
+            StorageReaderKind::Memory(_) => STR_HASH_LEN,
+        }
+    }
+
+    /// Encodes a term like the terms stored in the storage
+    pub fn encode_term(&self, term: impl Into<EncodedTerm>) -> EncodedTerm {
+        term.into().with_str_hash_len(self.str_hash_len())
+    }
+
+    /// Encodes a quad like the quads stored in the storage
+    pub fn encode_quad(&self, quad: QuadRef<'_>) -> EncodedQuad {
+        EncodedQuad::from(quad).with_str_hash_len(self.str_hash_len())
+    }
+
+    pub fn len(&self) -> Result<usize, StorageError> {
+        let len = match &self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageReaderKind::RocksDb(reader) => reader.len()?,
+            StorageReaderKind::Memory(reader) => reader.len(),
+        };
+        let Some(expired) = &self.expired else {
+            return Ok(len);
+        };
+        let mut expired_len = 0;
+        for quad in expired.expired_quads() {
+            if self.contains_stored(quad)? {
+                expired_len += 1;
+            }
         }
+        Ok(len - expired_len)
     }
 
     pub fn is_empty(&self) -> Result<bool, StorageError> {
+        if self.expired.is_some() {
+            return Ok(self
+                .quads_for_pattern(None, None, None, None)
+                .next()
+                .transpose()?
+                .is_none());
+        }
         match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageReaderKind::RocksDb(reader) => reader.is_empty(),
@@ -166,6 +1337,18 @@ impl StorageReader {
     }
 
     pub fn contains(&self, quad: &EncodedQuad) -> Result<bool, StorageError> {
+        if self
+            .expired
+            .as_ref()
+            .is_some_and(|expired| expired.is_expired(quad))
+        {
+            return Ok(false);
+        }
+        self.contains_stored(quad)
+    }
+
+    /// Returns if the quad is stored, even if it is expired
+    fn contains_stored(&self, quad: &EncodedQuad) -> Result<bool, StorageError> {
         match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageReaderKind::RocksDb(reader) => reader.contains(quad),
@@ -190,6 +1373,31 @@ impl StorageReader {
                     reader.quads_for_pattern(subject, predicate, object, graph_name),
                 ),
             },
+            expired: self.expired.clone(),
+        }
+    }
+
+    /// Quads with the given predicate whose object is a `xsd:dateTime` literal in the range
+    ///
+    /// The on-disk storage only reads the index keys in the range.
+    pub fn quads_for_date_time_range(
+        &self,
+        predicate: &EncodedTerm,
+        range: DateTimeRange,
+        graph_name: Option<&EncodedTerm>,
+    ) -> DecodingQuadIterator {
+        DecodingQuadIterator {
+            kind: match &self.kind {
+                #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+                StorageReaderKind::RocksDb(reader) => DecodingQuadIteratorKind::RocksDb(
+                    reader.quads_for_date_time_range(predicate, range, graph_name),
+                ),
+                StorageReaderKind::Memory(reader) => DecodingQuadIteratorKind::MemoryDateTimeRange(
+                    reader.quads_for_pattern(None, Some(predicate), None, graph_name),
+                    range,
+                ),
+            },
+            expired: self.expired.clone(),
         }
     }
 
@@ -213,7 +1421,7 @@ impl StorageReader {
         }
     }
 
-    pub fn contains_str(&self, key: &StrHash) -> Result<bool, StorageError> {
+    pub fn contains_str(&self, key: StrHash) -> Result<bool, StorageError> {
         match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageReaderKind::RocksDb(reader) => reader.contains_str(key),
@@ -221,6 +1429,69 @@ impl StorageReader {
         }
     }
 
+    /// Computes the size of a graph by scanning it
+    pub fn graph_usage(&self, graph_name: &EncodedTerm) -> Result<GraphUsage, StorageError> {
+        let mut usage = GraphUsage::default();
+        for quad in self.quads_for_pattern(None, None, None, Some(graph_name)) {
+            let quad = self.decode_quad(&quad?)?;
+            usage.add(quad.as_ref().into());
+        }
+        Ok(usage)
+    }
+
+    /// Returns the number of quads in the graph, estimated from the index sizes for on-disk storages
+    pub fn approximate_graph_len(&self, graph_name: &EncodedTerm) -> Result<usize, StorageError> {
+        match &self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageReaderKind::RocksDb(reader) => reader.approximate_graph_len(graph_name),
+            StorageReaderKind::Memory(reader) => Ok(reader
+                .quads_for_pattern(None, None, None, Some(graph_name))
+                .count()),
+        }
+    }
+
+    /// Returns the number of quads matching the pattern
+    ///
+    /// The count is exact if there are less than [`APPROXIMATE_LEN_EXACT_LIMIT`] matching quads.
+    /// Otherwise, it is estimated from the index sizes or the counters maintained by the storage.
+    pub fn approximate_len_for_pattern(
+        &self,
+        subject: Option<&EncodedTerm>,
+        predicate: Option<&EncodedTerm>,
+        object: Option<&EncodedTerm>,
+        graph_name: Option<&EncodedTerm>,
+    ) -> Result<usize, StorageError> {
+        let mut exact_len = 0;
+        for quad in self
+            .quads_for_pattern(subject, predicate, object, graph_name)
+            .take(APPROXIMATE_LEN_EXACT_LIMIT)
+        {
+            quad?;
+            exact_len += 1;
+        }
+        if exact_len < APPROXIMATE_LEN_EXACT_LIMIT {
+            return Ok(exact_len);
+        }
+        let approximate_len = match &self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageReaderKind::RocksDb(reader) => {
+                reader.approximate_len_for_pattern(subject, predicate, object, graph_name)?
+            }
+            StorageReaderKind::Memory(reader) => {
+                reader.approximate_len_for_pattern(subject, predicate, object, graph_name)
+            }
+        };
+        // We know that there are at least APPROXIMATE_LEN_EXACT_LIMIT matching quads
+        Ok(approximate_len.max(APPROXIMATE_LEN_EXACT_LIMIT))
+    }
+
+    /// The statistics about the storage content to use for query optimization, if they have been computed
+    ///
+    /// They might be outdated.
+    pub fn statistics(&self) -> Option<&Arc<DatasetStatistics>> {
+        self.statistics.as_ref()
+    }
+
     /// Validates that all the storage invariants held in the data
     pub fn validate(&self) -> Result<(), StorageError> {
         match &self.kind {
@@ -231,24 +1502,98 @@ impl StorageReader {
     }
 }
 
+/// A range of `xsd:dateTime` values, compared by their position on the timeline
+///
+/// Values without timezone are placed on the timeline as if they were in UTC.
+#[derive(Clone, Copy)]
+pub struct DateTimeRange {
+    // Inclusive bounds on the timeline values as stored in the index keys, None if the range is empty
+    bounds: Option<(i128, i128)>,
+}
+
+impl DateTimeRange {
+    pub fn new(range: impl RangeBounds<DateTime>) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(value) => Some(timeline_value(*value)),
+            Bound::Excluded(value) => timeline_value(*value).checked_add(1),
+            Bound::Unbounded => Some(i128::MIN),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(value) => Some(timeline_value(*value)),
+            Bound::Excluded(value) => timeline_value(*value).checked_sub(1),
+            Bound::Unbounded => Some(i128::MAX),
+        };
+        Self {
+            bounds: start.zip(end).filter(|(start, end)| start <= end),
+        }
+    }
+
+    fn contains(&self, value: DateTime) -> bool {
+        self.bounds
+            .is_some_and(|(start, end)| (start..=end).contains(&timeline_value(value)))
+    }
+
+    /// Inclusive ranges of the big endian encodings of the timeline values
+    ///
+    /// Negative values are encoded in two's complement and so sorted after the positive ones.
+    #[cfg_attr(
+        any(target_family = "wasm", not(feature = "rocksdb")),
+        expect(dead_code)
+    )]
+    fn encoded_ranges(self) -> impl Iterator<Item = ([u8; 16], [u8; 16])> {
+        self.bounds
+            .into_iter()
+            .flat_map(|(start, end)| {
+                [
+                    (end >= 0).then(|| (start.max(0), end)),
+                    (start < 0).then(|| (start, end.min(-1))),
+                ]
+            })
+            .flatten()
+            .map(|(start, end)| (start.to_be_bytes(), end.to_be_bytes()))
+    }
+}
+
+fn timeline_value(value: DateTime) -> i128 {
+    let mut timeline_value = [0; 16];
+    timeline_value.copy_from_slice(&value.to_be_bytes()[..16]);
+    i128::from_be_bytes(timeline_value)
+}
+
 pub struct DecodingQuadIterator {
     kind: DecodingQuadIteratorKind,
+    expired: Option<ExpirationFilter>,
 }
 
 enum DecodingQuadIteratorKind {
     #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
     RocksDb(RocksDbChainedDecodingQuadIterator),
     Memory(QuadIterator),
+    MemoryDateTimeRange(QuadIterator, DateTimeRange),
 }
 
 impl Iterator for DecodingQuadIterator {
     type Item = Result<EncodedQuad, StorageError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match &mut self.kind {
-            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-            DecodingQuadIteratorKind::RocksDb(iter) => iter.next(),
-            DecodingQuadIteratorKind::Memory(iter) => iter.next().map(Ok),
+        loop {
+            let quad = match &mut self.kind {
+                #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+                DecodingQuadIteratorKind::RocksDb(iter) => iter.next()?,
+                DecodingQuadIteratorKind::Memory(iter) => Ok(iter.next()?),
+                DecodingQuadIteratorKind::MemoryDateTimeRange(iter, range) => {
+                    Ok(iter.find(|quad| match quad.object {
+                        EncodedTerm::DateTimeLiteral(value) => range.contains(value),
+                        _ => false,
+                    })?)
+                }
+            };
+            if let (Ok(quad), Some(expired)) = (&quad, &self.expired) {
+                if expired.is_expired(quad) {
+                    continue;
+                }
+            }
+            return Some(quad);
         }
     }
 }
@@ -276,7 +1621,7 @@ impl Iterator for DecodingGraphIterator {
 }
 
 impl StrLookup for StorageReader {
-    fn get_str(&self, key: &StrHash) -> Result<Option<String>, StorageError> {
+    fn get_str(&self, key: StrHash) -> Result<Option<String>, StorageError> {
         match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageReaderKind::RocksDb(reader) => reader.get_str(key),
@@ -287,6 +1632,39 @@ impl StrLookup for StorageReader {
 
 pub struct StorageWriter<'a> {
     kind: StorageWriterKind<'a>,
+    graph_quotas: &'a GraphQuotas,
+    /// The sizes of the graphs with a quota written by the transaction, computed on their first insertion
+    graph_usages: HashMap<EncodedTerm, GraphUsage>,
+    statistics: Option<Arc<DatasetStatistics>>,
+    /// The predicates indexed by the full-text index if there is one
+    full_text_predicates: Option<&'a HashSet<NamedNode>>,
+    /// The changes to apply to the full-text index after the commit
+    full_text_changes: &'a RefCell<FullTextChanges>,
+    /// The graph of the incrementally maintained RDFS entailments if there is one
+    rdfs_graph_name: Option<&'a EncodedTerm>,
+    /// The changes to update the RDFS entailments from after the commit
+    rdfs_changes: &'a RefCell<RdfsChanges>,
+    /// The changes to send to the watchers after the commit if there are watchers
+    events: Option<&'a RefCell<Vec<StoreEvent>>>,
+    /// The expiration times of the quads when the transaction started
+    expirations: &'a Arc<Expirations>,
+    /// The changes to the expiration times to apply after the commit
+    expiration_changes: &'a RefCell<ExpirationChanges>,
+    /// The savepoints of the transaction with their identifiers, the most recent last
+    savepoints: Vec<(u64, Savepoint)>,
+}
+
+/// The next savepoint identifier, shared by all the transactions so that a savepoint can't be confused with one of another transaction
+static NEXT_SAVEPOINT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The lengths of the change logs of a [`StorageWriter`] to truncate to when rolling back to a savepoint
+struct Savepoint {
+    /// The memory storage transaction log
+    memory_log: usize,
+    full_text_changes: usize,
+    rdfs_changes: usize,
+    events: usize,
+    expiration_changes: usize,
 }
 
 enum StorageWriterKind<'a> {
@@ -305,18 +1683,293 @@ impl StorageWriter<'_> {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageWriterKind::RocksDb(writer) => StorageReader {
                 kind: StorageReaderKind::RocksDb(writer.reader()),
+                statistics: self.statistics.clone(),
+                expired: ExpirationFilter::new(Arc::clone(self.expirations)),
             },
             StorageWriterKind::Memory(writer) => StorageReader {
                 kind: StorageReaderKind::Memory(writer.reader()),
+                statistics: self.statistics.clone(),
+                expired: ExpirationFilter::new(Arc::clone(self.expirations)),
             },
         }
     }
 
+    /// See [`Storage::str_hash_len`]
+    pub fn str_hash_len(&self) -> usize {
+        match &self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageWriterKind::RocksDb(writer) => writer.str_hash_len(),
+            StorageWriterKind::Memory(_) => STR_HASH_LEN,
+        }
+    }
+
+    /// The statistics about the storage content when the transaction started, see [`StorageReader::statistics`]
+    pub fn statistics(&self) -> Option<&Arc<DatasetStatistics>> {
+        self.statistics.as_ref()
+    }
+
+    /// Adds a savepoint and returns its identifier to use with [`rollback_to_savepoint`](Self::rollback_to_savepoint)
+    ///
+    /// The identifiers are never reused, even by other transactions.
+    pub fn savepoint(&mut self) -> u64 {
+        let memory_log = match &mut self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageWriterKind::RocksDb(writer) => {
+                writer.set_savepoint();
+                0
+            }
+            StorageWriterKind::Memory(writer) => writer.savepoint(),
+        };
+        let id = NEXT_SAVEPOINT_ID.fetch_add(1, Ordering::Relaxed);
+        self.savepoints.push((
+            id,
+            Savepoint {
+                memory_log,
+                full_text_changes: self.full_text_changes.borrow().len(),
+                rdfs_changes: self.rdfs_changes.borrow().len(),
+                events: self.events.map_or(0, |events| events.borrow().len()),
+                expiration_changes: self.expiration_changes.borrow().len(),
+            },
+        ));
+        id
+    }
+
+    /// Undoes the writes done since the savepoint `id` was added
+    ///
+    /// The savepoint is kept and the ones added after it are removed.
+    /// Fails if the savepoint has been removed or has been added by another transaction.
+    pub fn rollback_to_savepoint(&mut self, id: u64) -> Result<(), StorageError> {
+        let Some(position) = self
+            .savepoints
+            .iter()
+            .position(|(savepoint_id, _)| *savepoint_id == id)
+        else {
+            return Err(StorageError::Other(
+                "The savepoint has been discarded by a rollback to a previous savepoint or belongs to another transaction".into(),
+            ));
+        };
+        let savepoint = &self.savepoints[position].1;
+        match &mut self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageWriterKind::RocksDb(writer) => {
+                // RocksDB savepoints are a stack: we pop the ones added after the savepoint and the savepoint itself
+                for _ in position..self.savepoints.len() {
+                    writer.rollback_to_savepoint()?;
+                }
+                writer.set_savepoint();
+            }
+            StorageWriterKind::Memory(writer) => {
+                writer.rollback_to_savepoint(savepoint.memory_log);
+            }
+        }
+        self.full_text_changes
+            .borrow_mut()
+            .truncate(savepoint.full_text_changes);
+        self.rdfs_changes
+            .borrow_mut()
+            .truncate(savepoint.rdfs_changes);
+        if let Some(events) = self.events {
+            events.borrow_mut().truncate(savepoint.events);
+        }
+        self.expiration_changes
+            .borrow_mut()
+            .truncate(savepoint.expiration_changes);
+        // The graph usages are computed again on the next insertions
+        self.graph_usages.clear();
+        self.savepoints.truncate(position + 1);
+        Ok(())
+    }
+
     pub fn insert(&mut self, quad: QuadRef<'_>) -> Result<bool, StorageError> {
+        self.insert_with_expiration(quad, None)
+    }
+
+    /// Inserts a quad that expires after `ttl`, the expiration time is replaced if the quad is already in the store
+    pub fn insert_with_ttl(
+        &mut self,
+        quad: QuadRef<'_>,
+        ttl: Duration,
+    ) -> Result<bool, StorageError> {
+        let expiration = SystemTime::now()
+            .checked_add(ttl)
+            .ok_or_else(|| StorageError::Other("The quad time-to-live is too large".into()))?;
+        self.insert_with_expiration(quad, Some(expiration))
+    }
+
+    fn insert_with_expiration(
+        &mut self,
+        quad: QuadRef<'_>,
+        expiration: Option<SystemTime>,
+    ) -> Result<bool, StorageError> {
+        // The quota is checked first to not record the expiration time of a rejected quad
+        if !self.graph_quotas.is_empty() && !self.check_graph_quota(quad)? {
+            // The quad is already in the store
+            self.replace_expiration(quad, expiration)?;
+            return Ok(false);
+        }
+        let inserted = match &mut self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageWriterKind::RocksDb(writer) => writer.insert(quad)?,
+            StorageWriterKind::Memory(writer) => writer.insert(quad),
+        };
+        let previous_expiration = self.replace_expiration(quad, expiration)?;
+        // An expired quad is inserted again even if it is still stored
+        let inserted = inserted || is_expired(previous_expiration);
+        if inserted {
+            self.record_full_text_change(quad, true);
+            self.record_rdfs_change(quad);
+            self.record_event(|| StoreEvent::Inserted(quad.into_owned()));
+        }
+        Ok(inserted)
+    }
+
+    /// The expiration time of the quad, taking into account the changes done by the transaction
+    fn expiration(&self, quad: &EncodedQuad) -> Option<SystemTime> {
+        if let Some(expiration) = self.expiration_changes.borrow().get(quad) {
+            return expiration;
+        }
+        self.expirations.get(quad).copied()
+    }
+
+    fn set_expiration(
+        &mut self,
+        quad: EncodedQuad,
+        expiration: Option<SystemTime>,
+    ) -> Result<(), StorageError> {
         match &mut self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-            StorageWriterKind::RocksDb(writer) => writer.insert(quad),
-            StorageWriterKind::Memory(writer) => Ok(writer.insert(quad)),
+            StorageWriterKind::RocksDb(writer) => writer.set_expiration(&quad, expiration)?,
+            StorageWriterKind::Memory(_) => (),
+        }
+        self.expiration_changes.borrow_mut().set(quad, expiration);
+        Ok(())
+    }
+
+    /// Sets the expiration time of the quad, `None` making it permanent, and returns the previous one
+    fn replace_expiration(
+        &mut self,
+        quad: QuadRef<'_>,
+        expiration: Option<SystemTime>,
+    ) -> Result<Option<SystemTime>, StorageError> {
+        if expiration.is_none()
+            && self.expirations.is_empty()
+            && self.expiration_changes.borrow().is_empty()
+        {
+            // Fast path: no quad has an expiration time
+            return Ok(None);
+        }
+        let quad = EncodedQuad::from(quad).with_str_hash_len(self.str_hash_len());
+        let previous_expiration = self.expiration(&quad);
+        if previous_expiration != expiration {
+            self.set_expiration(quad, expiration)?;
+        }
+        Ok(previous_expiration)
+    }
+
+    /// Removes the expiration times of the quads matching `filter` before they are removed by a clear operation
+    fn remove_expirations(
+        &mut self,
+        filter: impl Fn(&EncodedQuad) -> bool,
+    ) -> Result<(), StorageError> {
+        let quads = {
+            let changes = self.expiration_changes.borrow();
+            self.expirations
+                .keys()
+                .chain(changes.quads())
+                .filter(|quad| filter(quad))
+                .cloned()
+                .collect::<HashSet<_>>()
+        };
+        for quad in quads {
+            if self.expiration(&quad).is_some() {
+                self.set_expiration(quad, None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the quad if it is expired at `now` and returns if it has been removed
+    fn remove_if_expired(
+        &mut self,
+        quad: &EncodedQuad,
+        now: SystemTime,
+    ) -> Result<bool, StorageError> {
+        if self
+            .expiration(quad)
+            .is_none_or(|expiration| expiration > now)
+        {
+            // The expiration time has been changed since the expired quads have been listed
+            return Ok(false);
+        }
+        self.set_expiration(quad.clone(), None)?;
+        let quad = self.reader().decode_quad(quad)?;
+        let removed = match &mut self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageWriterKind::RocksDb(writer) => writer.remove(quad.as_ref())?,
+            StorageWriterKind::Memory(writer) => writer.remove(quad.as_ref()),
+        };
+        if removed {
+            self.record_full_text_change(quad.as_ref(), false);
+            self.record_rdfs_change(quad.as_ref());
+        }
+        Ok(removed)
+    }
+
+    fn record_event(&self, event: impl FnOnce() -> StoreEvent) {
+        if let Some(events) = self.events {
+            events.borrow_mut().push(event());
+        }
+    }
+
+    /// Records the removal of the quads of `graph_name` (or of all the graphs if `None`) matching `filter`
+    /// before they are removed by a clear operation
+    fn record_removals(
+        &self,
+        graph_name: Option<&EncodedTerm>,
+        filter: impl Fn(&EncodedQuad) -> bool,
+    ) -> Result<(), StorageError> {
+        let Some(events) = self.events else {
+            return Ok(());
+        };
+        let reader = self.reader();
+        for quad in reader.quads_for_pattern(None, None, None, graph_name) {
+            let quad = quad?;
+            if filter(&quad) {
+                events
+                    .borrow_mut()
+                    .push(StoreEvent::Removed(reader.decode_quad(&quad)?));
+            }
+        }
+        Ok(())
+    }
+
+    fn record_full_text_change(&self, quad: QuadRef<'_>, is_insertion: bool) {
+        if let Some(predicates) = self.full_text_predicates {
+            self.full_text_changes
+                .borrow_mut()
+                .record(predicates, quad, is_insertion);
+        }
+    }
+
+    fn record_rdfs_change(&self, quad: QuadRef<'_>) {
+        if let Some(graph_name) = self.rdfs_graph_name {
+            self.rdfs_changes
+                .borrow_mut()
+                .record(graph_name, quad, self.str_hash_len());
+        }
+    }
+
+    /// Records that the RDFS entailments must be materialized again after the commit
+    fn rebuild_rdfs_inference(&self) {
+        if self.rdfs_graph_name.is_some() {
+            self.rdfs_changes.borrow_mut().rebuild();
+        }
+    }
+
+    /// Records that the full-text index must be rebuilt after the commit
+    fn rebuild_full_text_index(&self) {
+        if self.full_text_predicates.is_some() {
+            self.full_text_changes.borrow_mut().rebuild();
         }
     }
 
@@ -331,15 +1984,66 @@ impl StorageWriter<'_> {
         }
     }
 
+    /// Checks that inserting `quad` keeps its graph within its quota and records the new graph size
+    ///
+    /// Returns `false` if the quad is already in the store.
+    fn check_graph_quota(&mut self, quad: QuadRef<'_>) -> Result<bool, StorageError> {
+        let graph_name = EncodedTerm::from(quad.graph_name).with_str_hash_len(self.str_hash_len());
+        let Some((quota_graph_name, quota)) = self.graph_quotas.get(&graph_name) else {
+            return Ok(true);
+        };
+        let reader = self.reader();
+        if reader.contains(&reader.encode_quad(quad))? {
+            return Ok(false);
+        }
+        let usage = match self.graph_usages.entry(graph_name) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let usage = reader.graph_usage(entry.key())?;
+                entry.insert(usage)
+            }
+        };
+        let mut new_usage = *usage;
+        new_usage.add(quad.into());
+        if !quota.allows(new_usage) {
+            return Err(
+                GraphQuotaExceededError::new(quota_graph_name.clone(), *quota, *usage).into(),
+            );
+        }
+        *usage = new_usage;
+        Ok(true)
+    }
+
     pub fn remove(&mut self, quad: QuadRef<'_>) -> Result<bool, StorageError> {
-        match &mut self.kind {
+        let expiration = self.replace_expiration(quad, None)?;
+        let removed = match &mut self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-            StorageWriterKind::RocksDb(writer) => writer.remove(quad),
-            StorageWriterKind::Memory(writer) => Ok(writer.remove(quad)),
+            StorageWriterKind::RocksDb(writer) => writer.remove(quad)?,
+            StorageWriterKind::Memory(writer) => writer.remove(quad),
+        };
+        // An expired quad is already not in the store anymore
+        let removed = removed && !is_expired(expiration);
+        if removed {
+            self.record_full_text_change(quad, false);
+            self.record_rdfs_change(quad);
+            self.record_event(|| StoreEvent::Removed(quad.into_owned()));
+            let graph_name =
+                EncodedTerm::from(quad.graph_name).with_str_hash_len(self.str_hash_len());
+            if let Some(usage) = self.graph_usages.get_mut(&graph_name) {
+                usage.remove(quad.into());
+            }
         }
+        Ok(removed)
     }
 
     pub fn clear_graph(&mut self, graph_name: GraphNameRef<'_>) -> Result<(), StorageError> {
+        self.graph_usages.clear();
+        self.rebuild_full_text_index();
+        self.rebuild_rdfs_inference();
+        self.record_removals(Some(&graph_name.into()), |_| true)?;
+        let encoded_graph_name =
+            EncodedTerm::from(graph_name).with_str_hash_len(self.str_hash_len());
+        self.remove_expirations(|quad| quad.graph_name == encoded_graph_name)?;
         match &mut self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageWriterKind::RocksDb(writer) => writer.clear_graph(graph_name),
@@ -351,6 +2055,11 @@ impl StorageWriter<'_> {
     }
 
     pub fn clear_all_named_graphs(&mut self) -> Result<(), StorageError> {
+        self.graph_usages.clear();
+        self.rebuild_full_text_index();
+        self.rebuild_rdfs_inference();
+        self.record_removals(None, |quad| !quad.graph_name.is_default_graph())?;
+        self.remove_expirations(|quad| !quad.graph_name.is_default_graph())?;
         match &mut self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageWriterKind::RocksDb(writer) => writer.clear_all_named_graphs(),
@@ -362,6 +2071,11 @@ impl StorageWriter<'_> {
     }
 
     pub fn clear_all_graphs(&mut self) -> Result<(), StorageError> {
+        self.graph_usages.clear();
+        self.rebuild_full_text_index();
+        self.rebuild_rdfs_inference();
+        self.record_removals(None, |_| true)?;
+        self.remove_expirations(|_| true)?;
         match &mut self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageWriterKind::RocksDb(writer) => writer.clear_all_graphs(),
@@ -376,6 +2090,13 @@ impl StorageWriter<'_> {
         &mut self,
         graph_name: NamedOrBlankNodeRef<'_>,
     ) -> Result<bool, StorageError> {
+        self.graph_usages.clear();
+        self.rebuild_full_text_index();
+        self.rebuild_rdfs_inference();
+        self.record_removals(Some(&graph_name.into()), |_| true)?;
+        let encoded_graph_name =
+            EncodedTerm::from(graph_name).with_str_hash_len(self.str_hash_len());
+        self.remove_expirations(|quad| quad.graph_name == encoded_graph_name)?;
         match &mut self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageWriterKind::RocksDb(writer) => writer.remove_named_graph(graph_name),
@@ -384,6 +2105,11 @@ impl StorageWriter<'_> {
     }
 
     pub fn remove_all_named_graphs(&mut self) -> Result<(), StorageError> {
+        self.graph_usages.clear();
+        self.rebuild_full_text_index();
+        self.rebuild_rdfs_inference();
+        self.record_removals(None, |quad| !quad.graph_name.is_default_graph())?;
+        self.remove_expirations(|quad| !quad.graph_name.is_default_graph())?;
         match &mut self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageWriterKind::RocksDb(writer) => writer.remove_all_named_graphs(),
@@ -395,6 +2121,11 @@ impl StorageWriter<'_> {
     }
 
     pub fn clear(&mut self) -> Result<(), StorageError> {
+        self.graph_usages.clear();
+        self.rebuild_full_text_index();
+        self.rebuild_rdfs_inference();
+        self.record_removals(None, |_| true)?;
+        self.remove_expirations(|_| true)?;
         match &mut self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageWriterKind::RocksDb(writer) => writer.clear(),
@@ -406,9 +2137,16 @@ impl StorageWriter<'_> {
     }
 }
 
+/// Returns if the expiration time is in the past
+fn is_expired(expiration: Option<SystemTime>) -> bool {
+    expiration.is_some_and(|expiration| expiration <= SystemTime::now())
+}
+
 #[must_use]
 pub struct StorageBulkLoader {
     kind: StorageBulkLoaderKind,
+    /// Used to rebuild the full-text index and the materialized entailments after the load
+    storage: Storage,
 }
 
 enum StorageBulkLoaderKind {
@@ -426,9 +2164,11 @@ impl StorageBulkLoader {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageBulkLoaderKind::RocksDb(loader) => Self {
                 kind: StorageBulkLoaderKind::RocksDb(loader.with_num_threads(num_threads)),
+                storage: self.storage,
             },
             StorageBulkLoaderKind::Memory(loader) => Self {
                 kind: StorageBulkLoaderKind::Memory(loader),
+                storage: self.storage,
             },
         }
     }
@@ -441,21 +2181,59 @@ impl StorageBulkLoader {
                 kind: StorageBulkLoaderKind::RocksDb(
                     loader.with_max_memory_size_in_megabytes(max_memory_size),
                 ),
+                storage: self.storage,
             },
             StorageBulkLoaderKind::Memory(loader) => Self {
                 kind: StorageBulkLoaderKind::Memory(loader),
+                storage: self.storage,
             },
         }
     }
 
-    pub fn on_progress(self, callback: impl Fn(u64) + 'static) -> Self {
+    pub fn on_progress(self, callback: impl Fn(BulkLoadProgress) + 'static) -> Self {
         match self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
             StorageBulkLoaderKind::RocksDb(loader) => Self {
                 kind: StorageBulkLoaderKind::RocksDb(loader.on_progress(callback)),
+                storage: self.storage,
             },
             StorageBulkLoaderKind::Memory(loader) => Self {
                 kind: StorageBulkLoaderKind::Memory(loader.on_progress(callback)),
+                storage: self.storage,
+            },
+        }
+    }
+
+    #[cfg_attr(
+        not(all(not(target_family = "wasm"), feature = "rocksdb")),
+        expect(unused_variables, clippy::needless_pass_by_value)
+    )]
+    pub fn with_external_sort_directory(self, directory: PathBuf) -> Self {
+        match self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageBulkLoaderKind::RocksDb(loader) => Self {
+                kind: StorageBulkLoaderKind::RocksDb(
+                    loader.with_external_sort_directory(directory),
+                ),
+                storage: self.storage,
+            },
+            StorageBulkLoaderKind::Memory(loader) => Self {
+                kind: StorageBulkLoaderKind::Memory(loader),
+                storage: self.storage,
+            },
+        }
+    }
+
+    pub fn reproducible(self) -> Self {
+        match self.kind {
+            #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+            StorageBulkLoaderKind::RocksDb(loader) => Self {
+                kind: StorageBulkLoaderKind::RocksDb(loader.reproducible()),
+                storage: self.storage,
+            },
+            StorageBulkLoaderKind::Memory(loader) => Self {
+                kind: StorageBulkLoaderKind::Memory(loader),
+                storage: self.storage,
             },
         }
     }
@@ -464,10 +2242,16 @@ impl StorageBulkLoader {
         &self,
         quads: impl IntoIterator<Item = Result<Quad, EI>>,
     ) -> Result<(), EO> {
-        match &self.kind {
+        let result = match &self.kind {
             #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-            StorageBulkLoaderKind::RocksDb(loader) => loader.load(quads),
-            StorageBulkLoaderKind::Memory(loader) => loader.load(quads),
+            StorageBulkLoaderKind::RocksDb(loader) => loader.load::<EI, EO>(quads),
+            StorageBulkLoaderKind::Memory(loader) => loader.load::<EI, EO>(quads),
         }
+        .and_then(|()| Ok(self.storage.rebuild_full_text_index()?))
+        .and_then(|()| Ok(self.storage.materialize_rdfs_inference()?))
+        .and_then(|()| Ok(self.storage.materialize_owl_rl_inference()?));
+        // Some quads might have been written even if the load failed
+        self.storage.bump_generation();
+        result
     }
 }
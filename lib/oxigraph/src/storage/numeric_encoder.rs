@@ -12,9 +12,16 @@ use std::str;
 #[cfg(feature = "rdf-12")]
 use std::sync::Arc;
 
+/// The size in bytes of the string identifiers
+pub const STR_HASH_LEN: usize = 16;
+
+/// A string identifier, the 128 bits SipHash of the string
+///
+/// Stores created with compact term identifiers only use the first 8 bytes of the identifiers, the other ones being set to 0:
+/// the identifiers of the terms given to them must be [truncated](Self::truncate) to be compared to the stored ones.
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub struct StrHash {
-    hash: [u8; 16],
+    hash: [u8; STR_HASH_LEN],
 }
 
 impl StrHash {
@@ -27,21 +34,32 @@ impl StrHash {
     }
 
     #[inline]
-    pub fn from_be_bytes(hash: [u8; 16]) -> Self {
+    pub fn from_be_bytes(hash: [u8; STR_HASH_LEN]) -> Self {
         Self { hash }
     }
 
     #[inline]
-    pub fn to_be_bytes(self) -> [u8; 16] {
+    pub fn to_be_bytes(self) -> [u8; STR_HASH_LEN] {
         self.hash
     }
+
+    /// Keeps only the first `str_hash_len` bytes of the identifier, the other ones are set to 0
+    #[inline]
+    #[must_use]
+    pub fn truncate(mut self, str_hash_len: usize) -> Self {
+        self.hash[str_hash_len..].fill(0);
+        self
+    }
 }
 
 impl Hash for StrHash {
     #[inline]
     #[expect(clippy::host_endian_bytes)]
     fn hash<H: Hasher>(&self, state: &mut H) {
-        state.write_u128(u128::from_ne_bytes(self.hash))
+        // Only the first 8 bytes, the other ones of the truncated identifiers are always 0
+        let mut prefix = [0; 8];
+        prefix.copy_from_slice(&self.hash[..8]);
+        state.write_u64(u64::from_ne_bytes(prefix));
     }
 }
 
@@ -460,6 +478,104 @@ impl EncodedTerm {
     pub fn is_default_graph(&self) -> bool {
         matches!(self, Self::DefaultGraph)
     }
+
+    /// [Truncates](StrHash::truncate) the string identifiers of the term to `str_hash_len` bytes,
+    /// the identifier size of the storage the term is compared with
+    #[must_use]
+    pub fn with_str_hash_len(self, str_hash_len: usize) -> Self {
+        if str_hash_len == STR_HASH_LEN {
+            return self;
+        }
+        let t = |id: StrHash| id.truncate(str_hash_len);
+        match self {
+            Self::NamedNode { iri_id } => Self::NamedNode { iri_id: t(iri_id) },
+            Self::BigBlankNode { id_id } => Self::BigBlankNode { id_id: t(id_id) },
+            Self::BigStringLiteral { value_id } => Self::BigStringLiteral {
+                value_id: t(value_id),
+            },
+            Self::SmallBigLangStringLiteral { value, language_id } => {
+                Self::SmallBigLangStringLiteral {
+                    value,
+                    language_id: t(language_id),
+                }
+            }
+            Self::BigSmallLangStringLiteral { value_id, language } => {
+                Self::BigSmallLangStringLiteral {
+                    value_id: t(value_id),
+                    language,
+                }
+            }
+            Self::BigBigLangStringLiteral {
+                value_id,
+                language_id,
+            } => Self::BigBigLangStringLiteral {
+                value_id: t(value_id),
+                language_id: t(language_id),
+            },
+            #[cfg(feature = "rdf-12")]
+            Self::LtrSmallBigDirLangStringLiteral { value, language_id } => {
+                Self::LtrSmallBigDirLangStringLiteral {
+                    value,
+                    language_id: t(language_id),
+                }
+            }
+            #[cfg(feature = "rdf-12")]
+            Self::LtrBigSmallDirLangStringLiteral { value_id, language } => {
+                Self::LtrBigSmallDirLangStringLiteral {
+                    value_id: t(value_id),
+                    language,
+                }
+            }
+            #[cfg(feature = "rdf-12")]
+            Self::LtrBigBigDirLangStringLiteral {
+                value_id,
+                language_id,
+            } => Self::LtrBigBigDirLangStringLiteral {
+                value_id: t(value_id),
+                language_id: t(language_id),
+            },
+            #[cfg(feature = "rdf-12")]
+            Self::RtlSmallBigDirLangStringLiteral { value, language_id } => {
+                Self::RtlSmallBigDirLangStringLiteral {
+                    value,
+                    language_id: t(language_id),
+                }
+            }
+            #[cfg(feature = "rdf-12")]
+            Self::RtlBigSmallDirLangStringLiteral { value_id, language } => {
+                Self::RtlBigSmallDirLangStringLiteral {
+                    value_id: t(value_id),
+                    language,
+                }
+            }
+            #[cfg(feature = "rdf-12")]
+            Self::RtlBigBigDirLangStringLiteral {
+                value_id,
+                language_id,
+            } => Self::RtlBigBigDirLangStringLiteral {
+                value_id: t(value_id),
+                language_id: t(language_id),
+            },
+            Self::SmallTypedLiteral { value, datatype_id } => Self::SmallTypedLiteral {
+                value,
+                datatype_id: t(datatype_id),
+            },
+            Self::BigTypedLiteral {
+                value_id,
+                datatype_id,
+            } => Self::BigTypedLiteral {
+                value_id: t(value_id),
+                datatype_id: t(datatype_id),
+            },
+            #[cfg(feature = "rdf-12")]
+            Self::Triple(triple) => Self::Triple(Arc::new(EncodedTriple::new(
+                triple.subject.clone().with_str_hash_len(str_hash_len),
+                triple.predicate.clone().with_str_hash_len(str_hash_len),
+                triple.object.clone().with_str_hash_len(str_hash_len),
+            ))),
+            term => term,
+        }
+    }
 }
 impl From<NamedNodeRef<'_>> for EncodedTerm {
     fn from(named_node: NamedNodeRef<'_>) -> Self {
@@ -721,6 +837,17 @@ impl EncodedQuad {
             graph_name,
         }
     }
+
+    /// See [`EncodedTerm::with_str_hash_len`]
+    #[must_use]
+    pub fn with_str_hash_len(self, str_hash_len: usize) -> Self {
+        Self {
+            subject: self.subject.with_str_hash_len(str_hash_len),
+            predicate: self.predicate.with_str_hash_len(str_hash_len),
+            object: self.object.with_str_hash_len(str_hash_len),
+            graph_name: self.graph_name.with_str_hash_len(str_hash_len),
+        }
+    }
 }
 
 impl From<QuadRef<'_>> for EncodedQuad {
@@ -735,10 +862,10 @@ impl From<QuadRef<'_>> for EncodedQuad {
 }
 
 pub trait StrLookup {
-    fn get_str(&self, key: &StrHash) -> Result<Option<String>, StorageError>;
+    fn get_str(&self, key: StrHash) -> Result<Option<String>, StorageError>;
 }
 
-pub fn insert_term<F: FnMut(&StrHash, &str) -> Result<(), StorageError>>(
+pub fn insert_term<F: FnMut(StrHash, &str) -> Result<(), StorageError>>(
     term: TermRef<'_>,
     encoded: &EncodedTerm,
     insert_str: &mut F,
@@ -746,24 +873,24 @@ pub fn insert_term<F: FnMut(&StrHash, &str) -> Result<(), StorageError>>(
     match term {
         TermRef::NamedNode(node) => {
             if let EncodedTerm::NamedNode { iri_id } = encoded {
-                insert_str(iri_id, node.as_str())
+                insert_str(*iri_id, node.as_str())
             } else {
                 Err(CorruptionError::from_encoded_term(encoded, &term).into())
             }
         }
         TermRef::BlankNode(node) => match encoded {
-            EncodedTerm::BigBlankNode { id_id } => insert_str(id_id, node.as_str()),
+            EncodedTerm::BigBlankNode { id_id } => insert_str(*id_id, node.as_str()),
             EncodedTerm::SmallBlankNode(..) | EncodedTerm::NumericalBlankNode { .. } => Ok(()),
             _ => Err(CorruptionError::from_encoded_term(encoded, &term).into()),
         },
         TermRef::Literal(literal) => match encoded {
             EncodedTerm::BigStringLiteral { value_id }
             | EncodedTerm::BigSmallLangStringLiteral { value_id, .. } => {
-                insert_str(value_id, literal.value())
+                insert_str(*value_id, literal.value())
             }
             EncodedTerm::SmallBigLangStringLiteral { language_id, .. } => {
                 if let Some(language) = literal.language() {
-                    insert_str(language_id, language)
+                    insert_str(*language_id, language)
                 } else {
                     Err(CorruptionError::from_encoded_term(encoded, &term).into())
                 }
@@ -772,9 +899,9 @@ pub fn insert_term<F: FnMut(&StrHash, &str) -> Result<(), StorageError>>(
                 value_id,
                 language_id,
             } => {
-                insert_str(value_id, literal.value())?;
+                insert_str(*value_id, literal.value())?;
                 if let Some(language) = literal.language() {
-                    insert_str(language_id, language)
+                    insert_str(*language_id, language)
                 } else {
                     Err(CorruptionError::from_encoded_term(encoded, &term).into())
                 }
@@ -782,13 +909,13 @@ pub fn insert_term<F: FnMut(&StrHash, &str) -> Result<(), StorageError>>(
             #[cfg(feature = "rdf-12")]
             EncodedTerm::RtlBigSmallDirLangStringLiteral { value_id, .. }
             | EncodedTerm::LtrBigSmallDirLangStringLiteral { value_id, .. } => {
-                insert_str(value_id, literal.value())
+                insert_str(*value_id, literal.value())
             }
             #[cfg(feature = "rdf-12")]
             EncodedTerm::RtlSmallBigDirLangStringLiteral { language_id, .. }
             | EncodedTerm::LtrSmallBigDirLangStringLiteral { language_id, .. } => {
                 if let Some(language) = literal.language() {
-                    insert_str(language_id, language)
+                    insert_str(*language_id, language)
                 } else {
                     Err(CorruptionError::from_encoded_term(encoded, &term).into())
                 }
@@ -802,22 +929,22 @@ pub fn insert_term<F: FnMut(&StrHash, &str) -> Result<(), StorageError>>(
                 value_id,
                 language_id,
             } => {
-                insert_str(value_id, literal.value())?;
+                insert_str(*value_id, literal.value())?;
                 if let Some(language) = literal.language() {
-                    insert_str(language_id, language)
+                    insert_str(*language_id, language)
                 } else {
                     Err(CorruptionError::from_encoded_term(encoded, &term).into())
                 }
             }
             EncodedTerm::SmallTypedLiteral { datatype_id, .. } => {
-                insert_str(datatype_id, literal.datatype().as_str())
+                insert_str(*datatype_id, literal.datatype().as_str())
             }
             EncodedTerm::BigTypedLiteral {
                 value_id,
                 datatype_id,
             } => {
-                insert_str(value_id, literal.value())?;
-                insert_str(datatype_id, literal.datatype().as_str())
+                insert_str(*value_id, literal.value())?;
+                insert_str(*datatype_id, literal.datatype().as_str())
             }
             EncodedTerm::SmallStringLiteral(..)
             | EncodedTerm::SmallSmallLangStringLiteral { .. }
@@ -1009,20 +1136,20 @@ impl<S: StrLookup> Decoder for S {
                 Err(CorruptionError::msg("The default graph tag is not a valid term").into())
             }
             EncodedTerm::NamedNode { iri_id } => {
-                Ok(NamedNode::new_unchecked(get_required_str(self, iri_id)?).into())
+                Ok(NamedNode::new_unchecked(get_required_str(self, *iri_id)?).into())
             }
             EncodedTerm::NumericalBlankNode { id } => {
                 Ok(BlankNode::new_from_unique_id(u128::from_be_bytes(*id)).into())
             }
             EncodedTerm::SmallBlankNode(id) => Ok(BlankNode::new_unchecked(id.as_str()).into()),
             EncodedTerm::BigBlankNode { id_id } => {
-                Ok(BlankNode::new_unchecked(get_required_str(self, id_id)?).into())
+                Ok(BlankNode::new_unchecked(get_required_str(self, *id_id)?).into())
             }
             EncodedTerm::SmallStringLiteral(value) => {
                 Ok(Literal::new_simple_literal(*value).into())
             }
             EncodedTerm::BigStringLiteral { value_id } => {
-                Ok(Literal::new_simple_literal(get_required_str(self, value_id)?).into())
+                Ok(Literal::new_simple_literal(get_required_str(self, *value_id)?).into())
             }
             EncodedTerm::SmallSmallLangStringLiteral { value, language } => {
                 Ok(Literal::new_language_tagged_literal_unchecked(*value, *language).into())
@@ -1030,13 +1157,13 @@ impl<S: StrLookup> Decoder for S {
             EncodedTerm::SmallBigLangStringLiteral { value, language_id } => {
                 Ok(Literal::new_language_tagged_literal_unchecked(
                     *value,
-                    get_required_str(self, language_id)?,
+                    get_required_str(self, *language_id)?,
                 )
                 .into())
             }
             EncodedTerm::BigSmallLangStringLiteral { value_id, language } => {
                 Ok(Literal::new_language_tagged_literal_unchecked(
-                    get_required_str(self, value_id)?,
+                    get_required_str(self, *value_id)?,
                     *language,
                 )
                 .into())
@@ -1045,8 +1172,8 @@ impl<S: StrLookup> Decoder for S {
                 value_id,
                 language_id,
             } => Ok(Literal::new_language_tagged_literal_unchecked(
-                get_required_str(self, value_id)?,
-                get_required_str(self, language_id)?,
+                get_required_str(self, *value_id)?,
+                get_required_str(self, *language_id)?,
             )
             .into()),
             #[cfg(feature = "rdf-12")]
@@ -1062,7 +1189,7 @@ impl<S: StrLookup> Decoder for S {
             EncodedTerm::LtrSmallBigDirLangStringLiteral { value, language_id } => {
                 Ok(Literal::new_directional_language_tagged_literal_unchecked(
                     *value,
-                    get_required_str(self, language_id)?,
+                    get_required_str(self, *language_id)?,
                     BaseDirection::Ltr,
                 )
                 .into())
@@ -1070,7 +1197,7 @@ impl<S: StrLookup> Decoder for S {
             #[cfg(feature = "rdf-12")]
             EncodedTerm::LtrBigSmallDirLangStringLiteral { value_id, language } => {
                 Ok(Literal::new_directional_language_tagged_literal_unchecked(
-                    get_required_str(self, value_id)?,
+                    get_required_str(self, *value_id)?,
                     *language,
                     BaseDirection::Ltr,
                 )
@@ -1081,8 +1208,8 @@ impl<S: StrLookup> Decoder for S {
                 value_id,
                 language_id,
             } => Ok(Literal::new_directional_language_tagged_literal_unchecked(
-                get_required_str(self, value_id)?,
-                get_required_str(self, language_id)?,
+                get_required_str(self, *value_id)?,
+                get_required_str(self, *language_id)?,
                 BaseDirection::Ltr,
             )
             .into()),
@@ -1099,7 +1226,7 @@ impl<S: StrLookup> Decoder for S {
             EncodedTerm::RtlSmallBigDirLangStringLiteral { value, language_id } => {
                 Ok(Literal::new_directional_language_tagged_literal_unchecked(
                     *value,
-                    get_required_str(self, language_id)?,
+                    get_required_str(self, *language_id)?,
                     BaseDirection::Rtl,
                 )
                 .into())
@@ -1107,7 +1234,7 @@ impl<S: StrLookup> Decoder for S {
             #[cfg(feature = "rdf-12")]
             EncodedTerm::RtlBigSmallDirLangStringLiteral { value_id, language } => {
                 Ok(Literal::new_directional_language_tagged_literal_unchecked(
-                    get_required_str(self, value_id)?,
+                    get_required_str(self, *value_id)?,
                     *language,
                     BaseDirection::Rtl,
                 )
@@ -1118,15 +1245,15 @@ impl<S: StrLookup> Decoder for S {
                 value_id,
                 language_id,
             } => Ok(Literal::new_directional_language_tagged_literal_unchecked(
-                get_required_str(self, value_id)?,
-                get_required_str(self, language_id)?,
+                get_required_str(self, *value_id)?,
+                get_required_str(self, *language_id)?,
                 BaseDirection::Rtl,
             )
             .into()),
             EncodedTerm::SmallTypedLiteral { value, datatype_id } => {
                 Ok(Literal::new_typed_literal(
                     *value,
-                    NamedNode::new_unchecked(get_required_str(self, datatype_id)?),
+                    NamedNode::new_unchecked(get_required_str(self, *datatype_id)?),
                 )
                 .into())
             }
@@ -1134,8 +1261,8 @@ impl<S: StrLookup> Decoder for S {
                 value_id,
                 datatype_id,
             } => Ok(Literal::new_typed_literal(
-                get_required_str(self, value_id)?,
-                NamedNode::new_unchecked(get_required_str(self, datatype_id)?),
+                get_required_str(self, *value_id)?,
+                NamedNode::new_unchecked(get_required_str(self, *datatype_id)?),
             )
             .into()),
             EncodedTerm::BooleanLiteral(value) => Ok(Literal::from(*value).into()),
@@ -1160,7 +1287,7 @@ impl<S: StrLookup> Decoder for S {
     }
 }
 
-fn get_required_str<L: StrLookup>(lookup: &L, id: &StrHash) -> Result<String, StorageError> {
+fn get_required_str<L: StrLookup>(lookup: &L, id: StrHash) -> Result<String, StorageError> {
     Ok(lookup.get_str(id)?.ok_or_else(|| {
         CorruptionError::new(format!(
             "Not able to find the string with id {id:?} in the string store"
@@ -1188,11 +1315,17 @@ impl Hasher for StrHashHasher {
     fn write_u128(&mut self, i: u128) {
         self.value = i as u64;
     }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.value = i;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::hash::{BuildHasher, BuildHasherDefault};
     #[cfg(target_pointer_width = "64")]
     use std::mem::{align_of, size_of};
 
@@ -1213,6 +1346,25 @@ mod tests {
         assert_eq!(StrHash::from_be_bytes(FOO_HASH).to_be_bytes(), FOO_HASH);
     }
 
+    #[test]
+    fn truncated_str_hash() {
+        let foo = StrHash::new("foo");
+        let truncated = foo.truncate(8);
+        assert_eq!(truncated.to_be_bytes()[..8], foo.to_be_bytes()[..8]);
+        assert_eq!(truncated.to_be_bytes()[8..], [0; 8]);
+        assert_eq!(foo.truncate(STR_HASH_LEN), foo);
+        // The identifiers of different widths are never equal
+        assert_ne!(foo, truncated);
+        assert_eq!(truncated, StrHash::new("foo").truncate(8));
+        assert_ne!(truncated, StrHash::new("bar").truncate(8));
+
+        let hasher = BuildHasherDefault::<StrHashHasher>::default();
+        assert_eq!(
+            hasher.hash_one(truncated),
+            hasher.hash_one(StrHash::new("foo").truncate(8))
+        );
+    }
+
     #[cfg(target_pointer_width = "64")]
     #[test]
     fn test_size_and_alignment() {
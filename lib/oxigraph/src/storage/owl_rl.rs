@@ -0,0 +1,486 @@
+//! Materialization of the OWL 2 RL entailments of the store content into a dedicated graph.
+
+use crate::model::vocab::{rdf, rdfs, xsd};
+use crate::model::{LiteralRef, NamedNodeRef, NamedOrBlankNode, NamedOrBlankNodeRef};
+use crate::storage::numeric_encoder::EncodedTerm;
+use crate::storage::rules::{Fact, FactSet, Rule, RuleAtom, RuleTerm, write_entailments};
+use crate::storage::{StorageError, StorageWriter};
+use std::collections::HashSet;
+
+mod owl {
+    //! The [OWL 2](https://www.w3.org/TR/owl2-syntax/) vocabulary terms used by the rules.
+    use crate::model::NamedNodeRef;
+
+    pub const ALL_VALUES_FROM: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#allValuesFrom");
+    pub const EQUIVALENT_CLASS: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#equivalentClass");
+    pub const EQUIVALENT_PROPERTY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#equivalentProperty");
+    pub const FUNCTIONAL_PROPERTY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#FunctionalProperty");
+    pub const HAS_VALUE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#hasValue");
+    pub const INTERSECTION_OF: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#intersectionOf");
+    pub const INVERSE_FUNCTIONAL_PROPERTY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#InverseFunctionalProperty");
+    pub const INVERSE_OF: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#inverseOf");
+    pub const MAX_CARDINALITY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#maxCardinality");
+    pub const ONE_OF: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#oneOf");
+    pub const ON_PROPERTY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#onProperty");
+    pub const PROPERTY_CHAIN_AXIOM: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#propertyChainAxiom");
+    pub const SAME_AS: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#sameAs");
+    pub const SOME_VALUES_FROM: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#someValuesFrom");
+    pub const SYMMETRIC_PROPERTY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#SymmetricProperty");
+    pub const THING: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#Thing");
+    pub const TRANSITIVE_PROPERTY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#TransitiveProperty");
+    pub const UNION_OF: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#unionOf");
+}
+
+/// The IRIs the rules might write even if they are not in the store
+const VOCABULARY: [NamedNodeRef<'static>; 6] = [
+    rdf::TYPE,
+    rdfs::SUB_CLASS_OF,
+    rdfs::SUB_PROPERTY_OF,
+    owl::EQUIVALENT_CLASS,
+    owl::EQUIVALENT_PROPERTY,
+    owl::SAME_AS,
+];
+
+/// The [OWL 2 RL](https://www.w3.org/TR/owl2-profiles/#OWL_2_RL) entailments a [`Store`](crate::store::Store) materializes.
+///
+/// See [`Store::with_owl_rl_inference`](crate::store::Store::with_owl_rl_inference).
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct OwlRlInference {
+    graph_name: NamedOrBlankNode,
+}
+
+impl OwlRlInference {
+    /// Materializes the entailments into the named graph `graph_name`.
+    ///
+    /// They are computed when the inference is enabled, after each bulk load
+    /// and on [`Store::materialize_owl_rl_inference`](crate::store::Store::materialize_owl_rl_inference) calls.
+    #[inline]
+    pub fn new(graph_name: impl Into<NamedOrBlankNode>) -> Self {
+        Self {
+            graph_name: graph_name.into(),
+        }
+    }
+
+    /// The graph the entailments are written to.
+    #[inline]
+    pub fn graph_name(&self) -> NamedOrBlankNodeRef<'_> {
+        self.graph_name.as_ref()
+    }
+}
+
+/// Replaces the content of the inference graph with all the entailments
+///
+/// The quads outside of the inference graph are loaded in memory and saturated with the OWL 2 RL rules.
+pub fn materialize_owl_rl(
+    inference: &OwlRlInference,
+    writer: &mut StorageWriter<'_>,
+) -> Result<(), StorageError> {
+    let reader = writer.reader();
+    let str_hash_len = reader.str_hash_len();
+    let graph_name = EncodedTerm::from(inference.graph_name()).with_str_hash_len(str_hash_len);
+    let mut facts = FactSet::default();
+    for quad in reader.quads_for_pattern(None, None, None, None) {
+        let quad = quad?;
+        if quad.graph_name != graph_name {
+            facts.insert((quad.subject, quad.predicate, quad.object));
+        }
+    }
+    let asserted = facts.len();
+    let mut rules = rules();
+    let axioms = list_rules(&facts, &mut rules, str_hash_len);
+    for axiom in axioms {
+        facts.insert(axiom);
+    }
+    let rules = rules
+        .into_iter()
+        .map(|rule| rule.with_str_hash_len(str_hash_len))
+        .collect::<Vec<_>>();
+    facts.saturate(&rules);
+    let same_as = EncodedTerm::from(owl::SAME_AS).with_str_hash_len(str_hash_len);
+    let entailed = facts
+        .iter()
+        .skip(asserted)
+        .filter(|(s, p, o)| !(*p == same_as && s == o))
+        .cloned()
+        .collect::<HashSet<Fact>>();
+    write_entailments(
+        writer,
+        &reader,
+        inference.graph_name(),
+        None,
+        entailed,
+        &VOCABULARY,
+    )
+}
+
+const X: RuleTerm = RuleTerm::Variable(0);
+const Y: RuleTerm = RuleTerm::Variable(1);
+const Z: RuleTerm = RuleTerm::Variable(2);
+const P: RuleTerm = RuleTerm::Variable(3);
+const Q: RuleTerm = RuleTerm::Variable(4);
+const C: RuleTerm = RuleTerm::Variable(5);
+const D: RuleTerm = RuleTerm::Variable(6);
+
+fn atom(
+    subject: impl Into<RuleTerm>,
+    predicate: impl Into<RuleTerm>,
+    object: impl Into<RuleTerm>,
+) -> RuleAtom {
+    [subject.into(), predicate.into(), object.into()]
+}
+
+/// The OWL 2 RL rules not depending on RDF lists
+///
+/// The rules named `eq-ref`, `scm-cls`, `scm-dp`, `scm-op` only deriving trivial triples
+/// and the rules detecting inconsistencies are not included.
+fn rules() -> Vec<Rule> {
+    vec![
+        // eq-sym
+        Rule::new([atom(X, owl::SAME_AS, Y)], [atom(Y, owl::SAME_AS, X)]),
+        // eq-trans
+        Rule::new(
+            [atom(X, owl::SAME_AS, Y), atom(Y, owl::SAME_AS, Z)],
+            [atom(X, owl::SAME_AS, Z)],
+        ),
+        // eq-rep-s
+        Rule::new([atom(X, owl::SAME_AS, Y), atom(X, P, Z)], [atom(Y, P, Z)]),
+        // eq-rep-p
+        Rule::new([atom(P, owl::SAME_AS, Q), atom(X, P, Y)], [atom(X, Q, Y)]),
+        // eq-rep-o
+        Rule::new([atom(X, owl::SAME_AS, Y), atom(Z, P, X)], [atom(Z, P, Y)]),
+        // prp-dom
+        Rule::new(
+            [atom(P, rdfs::DOMAIN, C), atom(X, P, Y)],
+            [atom(X, rdf::TYPE, C)],
+        ),
+        // prp-rng
+        Rule::new(
+            [atom(P, rdfs::RANGE, C), atom(X, P, Y)],
+            [atom(Y, rdf::TYPE, C)],
+        ),
+        // prp-fp
+        Rule::new(
+            [
+                atom(P, rdf::TYPE, owl::FUNCTIONAL_PROPERTY),
+                atom(X, P, Y),
+                atom(X, P, Z),
+            ],
+            [atom(Y, owl::SAME_AS, Z)],
+        ),
+        // prp-ifp
+        Rule::new(
+            [
+                atom(P, rdf::TYPE, owl::INVERSE_FUNCTIONAL_PROPERTY),
+                atom(X, P, Z),
+                atom(Y, P, Z),
+            ],
+            [atom(X, owl::SAME_AS, Y)],
+        ),
+        // prp-symp
+        Rule::new(
+            [atom(P, rdf::TYPE, owl::SYMMETRIC_PROPERTY), atom(X, P, Y)],
+            [atom(Y, P, X)],
+        ),
+        // prp-trp
+        Rule::new(
+            [
+                atom(P, rdf::TYPE, owl::TRANSITIVE_PROPERTY),
+                atom(X, P, Y),
+                atom(Y, P, Z),
+            ],
+            [atom(X, P, Z)],
+        ),
+        // prp-spo1
+        Rule::new(
+            [atom(P, rdfs::SUB_PROPERTY_OF, Q), atom(X, P, Y)],
+            [atom(X, Q, Y)],
+        ),
+        // prp-eqp1
+        Rule::new(
+            [atom(P, owl::EQUIVALENT_PROPERTY, Q), atom(X, P, Y)],
+            [atom(X, Q, Y)],
+        ),
+        // prp-eqp2
+        Rule::new(
+            [atom(P, owl::EQUIVALENT_PROPERTY, Q), atom(X, Q, Y)],
+            [atom(X, P, Y)],
+        ),
+        // prp-inv1
+        Rule::new(
+            [atom(P, owl::INVERSE_OF, Q), atom(X, P, Y)],
+            [atom(Y, Q, X)],
+        ),
+        // prp-inv2
+        Rule::new(
+            [atom(P, owl::INVERSE_OF, Q), atom(X, Q, Y)],
+            [atom(Y, P, X)],
+        ),
+        // cls-svf1
+        Rule::new(
+            [
+                atom(C, owl::SOME_VALUES_FROM, D),
+                atom(C, owl::ON_PROPERTY, P),
+                atom(X, P, Y),
+                atom(Y, rdf::TYPE, D),
+            ],
+            [atom(X, rdf::TYPE, C)],
+        ),
+        // cls-svf2
+        Rule::new(
+            [
+                atom(C, owl::SOME_VALUES_FROM, owl::THING),
+                atom(C, owl::ON_PROPERTY, P),
+                atom(X, P, Y),
+            ],
+            [atom(X, rdf::TYPE, C)],
+        ),
+        // cls-avf
+        Rule::new(
+            [
+                atom(C, owl::ALL_VALUES_FROM, D),
+                atom(C, owl::ON_PROPERTY, P),
+                atom(X, rdf::TYPE, C),
+                atom(X, P, Y),
+            ],
+            [atom(Y, rdf::TYPE, D)],
+        ),
+        // cls-hv1
+        Rule::new(
+            [
+                atom(C, owl::HAS_VALUE, Y),
+                atom(C, owl::ON_PROPERTY, P),
+                atom(X, rdf::TYPE, C),
+            ],
+            [atom(X, P, Y)],
+        ),
+        // cls-hv2
+        Rule::new(
+            [
+                atom(C, owl::HAS_VALUE, Y),
+                atom(C, owl::ON_PROPERTY, P),
+                atom(X, P, Y),
+            ],
+            [atom(X, rdf::TYPE, C)],
+        ),
+        // cls-maxc2
+        Rule::new(
+            [
+                atom(
+                    C,
+                    owl::MAX_CARDINALITY,
+                    RuleTerm::Constant(
+                        LiteralRef::new_typed_literal("1", xsd::NON_NEGATIVE_INTEGER).into(),
+                    ),
+                ),
+                atom(C, owl::ON_PROPERTY, P),
+                atom(X, rdf::TYPE, C),
+                atom(X, P, Y),
+                atom(X, P, Z),
+            ],
+            [atom(Y, owl::SAME_AS, Z)],
+        ),
+        // cax-sco
+        Rule::new(
+            [atom(C, rdfs::SUB_CLASS_OF, D), atom(X, rdf::TYPE, C)],
+            [atom(X, rdf::TYPE, D)],
+        ),
+        // cax-eqc1
+        Rule::new(
+            [atom(C, owl::EQUIVALENT_CLASS, D), atom(X, rdf::TYPE, C)],
+            [atom(X, rdf::TYPE, D)],
+        ),
+        // cax-eqc2
+        Rule::new(
+            [atom(C, owl::EQUIVALENT_CLASS, D), atom(X, rdf::TYPE, D)],
+            [atom(X, rdf::TYPE, C)],
+        ),
+        // scm-sco
+        Rule::new(
+            [
+                atom(X, rdfs::SUB_CLASS_OF, Y),
+                atom(Y, rdfs::SUB_CLASS_OF, Z),
+            ],
+            [atom(X, rdfs::SUB_CLASS_OF, Z)],
+        ),
+        // scm-eqc1
+        Rule::new(
+            [atom(C, owl::EQUIVALENT_CLASS, D)],
+            [
+                atom(C, rdfs::SUB_CLASS_OF, D),
+                atom(D, rdfs::SUB_CLASS_OF, C),
+            ],
+        ),
+        // scm-eqc2
+        Rule::new(
+            [
+                atom(C, rdfs::SUB_CLASS_OF, D),
+                atom(D, rdfs::SUB_CLASS_OF, C),
+            ],
+            [atom(C, owl::EQUIVALENT_CLASS, D)],
+        ),
+        // scm-spo
+        Rule::new(
+            [
+                atom(X, rdfs::SUB_PROPERTY_OF, Y),
+                atom(Y, rdfs::SUB_PROPERTY_OF, Z),
+            ],
+            [atom(X, rdfs::SUB_PROPERTY_OF, Z)],
+        ),
+        // scm-eqp1
+        Rule::new(
+            [atom(P, owl::EQUIVALENT_PROPERTY, Q)],
+            [
+                atom(P, rdfs::SUB_PROPERTY_OF, Q),
+                atom(Q, rdfs::SUB_PROPERTY_OF, P),
+            ],
+        ),
+        // scm-eqp2
+        Rule::new(
+            [
+                atom(P, rdfs::SUB_PROPERTY_OF, Q),
+                atom(Q, rdfs::SUB_PROPERTY_OF, P),
+            ],
+            [atom(P, owl::EQUIVALENT_PROPERTY, Q)],
+        ),
+        // scm-dom1
+        Rule::new(
+            [atom(P, rdfs::DOMAIN, C), atom(C, rdfs::SUB_CLASS_OF, D)],
+            [atom(P, rdfs::DOMAIN, D)],
+        ),
+        // scm-dom2
+        Rule::new(
+            [atom(Q, rdfs::DOMAIN, C), atom(P, rdfs::SUB_PROPERTY_OF, Q)],
+            [atom(P, rdfs::DOMAIN, C)],
+        ),
+        // scm-rng1
+        Rule::new(
+            [atom(P, rdfs::RANGE, C), atom(C, rdfs::SUB_CLASS_OF, D)],
+            [atom(P, rdfs::RANGE, D)],
+        ),
+        // scm-rng2
+        Rule::new(
+            [atom(Q, rdfs::RANGE, C), atom(P, rdfs::SUB_PROPERTY_OF, Q)],
+            [atom(P, rdfs::RANGE, C)],
+        ),
+    ]
+}
+
+/// Instantiates the OWL 2 RL rules using RDF lists with the lists of the asserted facts
+///
+/// Supports `prp-spo2`, `cls-int1`, `cls-int2`, `cls-uni`, `cls-oo`, `scm-int` and `scm-uni`.
+/// Returns the facts derived by the rules without body.
+/// The facts are encoded with `str_hash_len` bytes string identifiers.
+fn list_rules(facts: &FactSet, rules: &mut Vec<Rule>, str_hash_len: usize) -> Vec<Fact> {
+    let encode = |node: NamedNodeRef<'_>| EncodedTerm::from(node).with_str_hash_len(str_hash_len);
+    let property_chain_axiom = encode(owl::PROPERTY_CHAIN_AXIOM);
+    let intersection_of = encode(owl::INTERSECTION_OF);
+    let union_of = encode(owl::UNION_OF);
+    let one_of = encode(owl::ONE_OF);
+    let rdf_type = encode(rdf::TYPE);
+    let sub_class_of = encode(rdfs::SUB_CLASS_OF);
+    let mut axioms = Vec::new();
+    for (subject, predicate, object) in facts.iter() {
+        if ![&property_chain_axiom, &intersection_of, &union_of, &one_of].contains(&predicate) {
+            continue;
+        }
+        let Some(list) = read_list(facts, object, str_hash_len) else {
+            continue;
+        };
+        let subject_term = RuleTerm::Constant(subject.clone());
+        if *predicate == property_chain_axiom {
+            if list.is_empty() {
+                continue;
+            }
+            // prp-spo2
+            let body = list
+                .iter()
+                .enumerate()
+                .map(|(i, property)| {
+                    atom(
+                        RuleTerm::Variable(i),
+                        RuleTerm::Constant(property.clone()),
+                        RuleTerm::Variable(i + 1),
+                    )
+                })
+                .collect::<Vec<_>>();
+            rules.push(Rule::new(
+                body,
+                [atom(
+                    RuleTerm::Variable(0),
+                    subject_term,
+                    RuleTerm::Variable(list.len()),
+                )],
+            ));
+        } else if *predicate == intersection_of {
+            // cls-int1
+            rules.push(Rule::new(
+                list.iter()
+                    .map(|class| atom(X, rdf::TYPE, RuleTerm::Constant(class.clone())))
+                    .collect::<Vec<_>>(),
+                [atom(X, rdf::TYPE, subject_term.clone())],
+            ));
+            for class in list {
+                // cls-int2
+                rules.push(Rule::new(
+                    [atom(X, rdf::TYPE, subject_term.clone())],
+                    [atom(X, rdf::TYPE, RuleTerm::Constant(class.clone()))],
+                ));
+                // scm-int
+                axioms.push((subject.clone(), sub_class_of.clone(), class));
+            }
+        } else if *predicate == union_of {
+            for class in list {
+                // cls-uni
+                rules.push(Rule::new(
+                    [atom(X, rdf::TYPE, RuleTerm::Constant(class.clone()))],
+                    [atom(X, rdf::TYPE, subject_term.clone())],
+                ));
+                // scm-uni
+                axioms.push((class, sub_class_of.clone(), subject.clone()));
+            }
+        } else {
+            // cls-oo
+            for member in list {
+                axioms.push((member, rdf_type.clone(), subject.clone()));
+            }
+        }
+    }
+    axioms
+}
+
+/// Reads the elements of the RDF list starting at `head`, `None` if it is not a well-formed list
+fn read_list(facts: &FactSet, head: &EncodedTerm, str_hash_len: usize) -> Option<Vec<EncodedTerm>> {
+    let first = EncodedTerm::from(rdf::FIRST).with_str_hash_len(str_hash_len);
+    let rest = EncodedTerm::from(rdf::REST).with_str_hash_len(str_hash_len);
+    let nil = EncodedTerm::from(rdf::NIL).with_str_hash_len(str_hash_len);
+    let mut elements = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = head.clone();
+    while current != nil {
+        if !visited.insert(current.clone()) {
+            return None; // Cycle
+        }
+        elements.push(facts.objects(&current, &first).next()?.clone());
+        let next = facts.objects(&current, &rest).next()?.clone();
+        current = next;
+    }
+    Some(elements)
+}
@@ -0,0 +1,398 @@
+//! Materialization of the RDFS entailments of the store content into a dedicated graph.
+
+use crate::model::vocab::{rdf, rdfs};
+use crate::model::{NamedNodeRef, NamedOrBlankNode, NamedOrBlankNodeRef, QuadRef};
+use crate::storage::numeric_encoder::EncodedTerm;
+use crate::storage::rules::{Fact, is_resource, write_entailments};
+use crate::storage::{StorageError, StorageReader, StorageWriter};
+use std::collections::{HashMap, HashSet};
+
+/// The [RDFS entailments](https://www.w3.org/TR/rdf11-mt/#rdfs-entailment) a [`Store`](crate::store::Store) materializes.
+///
+/// See [`Store::with_rdfs_inference`](crate::store::Store::with_rdfs_inference).
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct RdfsInference {
+    graph_name: NamedOrBlankNode,
+    incremental: bool,
+}
+
+impl RdfsInference {
+    /// Materializes the entailments into the named graph `graph_name`.
+    ///
+    /// They are computed when the inference is enabled, after each bulk load
+    /// and on [`Store::materialize_rdfs_inference`](crate::store::Store::materialize_rdfs_inference) calls.
+    #[inline]
+    pub fn new(graph_name: impl Into<NamedOrBlankNode>) -> Self {
+        Self {
+            graph_name: graph_name.into(),
+            incremental: false,
+        }
+    }
+
+    /// Also updates the entailments after each commit, removing the ones that are not entailed anymore.
+    #[inline]
+    #[must_use]
+    pub fn incremental(mut self) -> Self {
+        self.incremental = true;
+        self
+    }
+
+    /// The graph the entailments are written to.
+    #[inline]
+    pub fn graph_name(&self) -> NamedOrBlankNodeRef<'_> {
+        self.graph_name.as_ref()
+    }
+
+    /// If the entailments are updated after each commit.
+    #[inline]
+    pub fn is_incremental(&self) -> bool {
+        self.incremental
+    }
+}
+
+/// The strict super classes or super properties of each class or property, or the classes given by the domains or ranges of each property
+type TermSets = HashMap<EncodedTerm, HashSet<EncodedTerm>>;
+
+/// Computes the entailments of the `rdfs2`, `rdfs3`, `rdfs5`, `rdfs7`, `rdfs9` and `rdfs11` rules
+/// from the schema read when it is built
+pub struct RdfsReasoner {
+    inference: RdfsInference,
+    graph_name: EncodedTerm,
+    rdf_type: EncodedTerm,
+    sub_class_of: EncodedTerm,
+    sub_property_of: EncodedTerm,
+    super_classes: TermSets,
+    super_properties: TermSets,
+    /// Includes the domains of the super properties and the super classes of the domains
+    domains: TermSets,
+    /// Includes the ranges of the super properties and the super classes of the ranges
+    ranges: TermSets,
+}
+
+impl RdfsReasoner {
+    /// Reads the schema from the quads outside of the inference graph
+    pub fn new(inference: RdfsInference, reader: &StorageReader) -> Result<Self, StorageError> {
+        let str_hash_len = reader.str_hash_len();
+        let graph_name =
+            EncodedTerm::from(inference.graph_name.as_ref()).with_str_hash_len(str_hash_len);
+        let schema_edges = |predicate: NamedNodeRef<'_>| {
+            let mut edges = TermSets::new();
+            for quad in reader.quads_for_pattern(None, Some(&predicate.into()), None, None) {
+                let quad = quad?;
+                if quad.graph_name != graph_name && is_resource(&quad.object) {
+                    edges.entry(quad.subject).or_default().insert(quad.object);
+                }
+            }
+            Ok::<_, StorageError>(edges)
+        };
+        let super_classes = transitive_closure(&schema_edges(rdfs::SUB_CLASS_OF)?);
+        let super_properties = transitive_closure(&schema_edges(rdfs::SUB_PROPERTY_OF)?);
+        let domains = inherit(
+            &schema_edges(rdfs::DOMAIN)?,
+            &super_properties,
+            &super_classes,
+        );
+        let ranges = inherit(
+            &schema_edges(rdfs::RANGE)?,
+            &super_properties,
+            &super_classes,
+        );
+        Ok(Self {
+            inference,
+            graph_name,
+            rdf_type: EncodedTerm::from(rdf::TYPE).with_str_hash_len(str_hash_len),
+            sub_class_of: EncodedTerm::from(rdfs::SUB_CLASS_OF).with_str_hash_len(str_hash_len),
+            sub_property_of: EncodedTerm::from(rdfs::SUB_PROPERTY_OF)
+                .with_str_hash_len(str_hash_len),
+            super_classes,
+            super_properties,
+            domains,
+            ranges,
+        })
+    }
+
+    pub fn inference(&self) -> &RdfsInference {
+        &self.inference
+    }
+
+    pub fn graph_name(&self) -> &EncodedTerm {
+        &self.graph_name
+    }
+
+    /// Replaces the content of the inference graph with all the entailments
+    pub fn materialize(&self, writer: &mut StorageWriter<'_>) -> Result<(), StorageError> {
+        let reader = writer.reader();
+        let predicates = [&self.rdf_type, &self.sub_class_of, &self.sub_property_of]
+            .into_iter()
+            .chain(self.super_properties.keys())
+            .chain(self.domains.keys())
+            .chain(self.ranges.keys())
+            .collect::<HashSet<_>>();
+        let mut entailed = HashSet::new();
+        for predicate in predicates {
+            for quad in reader.quads_for_pattern(None, Some(predicate), None, None) {
+                let quad = quad?;
+                if quad.graph_name != self.graph_name {
+                    self.derive(
+                        &quad.subject,
+                        &quad.predicate,
+                        &quad.object,
+                        &mut |s, p, o| {
+                            entailed.insert((s.clone(), p.clone(), o.clone()));
+                        },
+                    );
+                }
+            }
+        }
+        self.replace(writer, &reader, None, entailed)
+    }
+
+    /// Updates the entailments about the subjects and objects of the changed triples
+    pub fn update(
+        &self,
+        writer: &mut StorageWriter<'_>,
+        changes: &RdfsChanges,
+    ) -> Result<(), StorageError> {
+        let mut nodes = HashSet::new();
+        for (subject, predicate, object) in &changes.triples {
+            nodes.insert(subject);
+            if is_resource(object) && self.ranges.contains_key(predicate) {
+                nodes.insert(object);
+            }
+        }
+        for node in nodes {
+            self.update_node(writer, node)?;
+        }
+        Ok(())
+    }
+
+    /// Computes again the entailments with `node` as subject
+    ///
+    /// They are only derived from the triples with `node` as subject or as object of a property with a range.
+    fn update_node(
+        &self,
+        writer: &mut StorageWriter<'_>,
+        node: &EncodedTerm,
+    ) -> Result<(), StorageError> {
+        let reader = writer.reader();
+        let mut entailed = HashSet::new();
+        for quad in reader.quads_for_pattern(Some(node), None, None, None) {
+            let quad = quad?;
+            if quad.graph_name != self.graph_name {
+                self.derive(
+                    &quad.subject,
+                    &quad.predicate,
+                    &quad.object,
+                    &mut |s, p, o| {
+                        if s == node {
+                            entailed.insert((s.clone(), p.clone(), o.clone()));
+                        }
+                    },
+                );
+            }
+        }
+        for (property, classes) in &self.ranges {
+            for quad in reader.quads_for_pattern(None, Some(property), Some(node), None) {
+                if quad?.graph_name != self.graph_name {
+                    for class in classes {
+                        entailed.insert((node.clone(), self.rdf_type.clone(), class.clone()));
+                    }
+                    break;
+                }
+            }
+        }
+        self.replace(writer, &reader, Some(node), entailed)
+    }
+
+    /// Calls `emit` on the triples entailed by the given one
+    fn derive(
+        &self,
+        subject: &EncodedTerm,
+        predicate: &EncodedTerm,
+        object: &EncodedTerm,
+        emit: &mut impl FnMut(&EncodedTerm, &EncodedTerm, &EncodedTerm),
+    ) {
+        // rdfs2
+        for class in self.domains.get(predicate).into_iter().flatten() {
+            emit(subject, &self.rdf_type, class);
+        }
+        if !is_resource(object) {
+            for property in self.super_properties.get(predicate).into_iter().flatten() {
+                emit(subject, property, object); // rdfs7
+            }
+            return;
+        }
+        // rdfs3
+        for class in self.ranges.get(predicate).into_iter().flatten() {
+            emit(object, &self.rdf_type, class);
+        }
+        for property in [predicate]
+            .into_iter()
+            .chain(self.super_properties.get(predicate).into_iter().flatten())
+        {
+            if property != predicate {
+                emit(subject, property, object); // rdfs7
+            }
+            // rdfs5, rdfs9 and rdfs11
+            let super_terms = if *property == self.rdf_type || *property == self.sub_class_of {
+                &self.super_classes
+            } else if *property == self.sub_property_of {
+                &self.super_properties
+            } else {
+                continue;
+            };
+            for super_term in super_terms.get(object).into_iter().flatten() {
+                emit(subject, property, super_term);
+            }
+        }
+    }
+
+    /// Makes the quads of the inference graph with the given subject (or all of them if `None`)
+    /// the entailed triples not already in another graph
+    fn replace(
+        &self,
+        writer: &mut StorageWriter<'_>,
+        reader: &StorageReader,
+        subject: Option<&EncodedTerm>,
+        mut entailed: HashSet<Fact>,
+    ) -> Result<(), StorageError> {
+        let mut asserted = Vec::new();
+        for triple in &entailed {
+            if self.is_asserted(reader, triple)? {
+                asserted.push(triple.clone());
+            }
+        }
+        for triple in asserted {
+            entailed.remove(&triple);
+        }
+        write_entailments(
+            writer,
+            reader,
+            self.inference.graph_name(),
+            subject,
+            entailed,
+            &[rdf::TYPE, rdfs::SUB_CLASS_OF, rdfs::SUB_PROPERTY_OF],
+        )
+    }
+
+    /// Checks if the triple is in a graph other than the inference graph
+    fn is_asserted(
+        &self,
+        reader: &StorageReader,
+        (subject, predicate, object): &Fact,
+    ) -> Result<bool, StorageError> {
+        for quad in reader.quads_for_pattern(Some(subject), Some(predicate), Some(object), None) {
+            if quad?.graph_name != self.graph_name {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// The changes done by a transaction, used to update the entailments after the commit
+#[derive(Default)]
+pub struct RdfsChanges {
+    /// The inserted and removed triples outside of the inference graph, except the schema ones
+    triples: Vec<Fact>,
+    rebuild: bool,
+}
+
+impl RdfsChanges {
+    /// Records an inserted or removed quad if it is outside of the inference graph `graph_name`
+    ///
+    /// The quad terms are encoded with `str_hash_len` bytes string identifiers, like `graph_name`.
+    /// A change to the schema requires to materialize all the entailments again.
+    pub fn record(&mut self, graph_name: &EncodedTerm, quad: QuadRef<'_>, str_hash_len: usize) {
+        if self.rebuild
+            || EncodedTerm::from(quad.graph_name).with_str_hash_len(str_hash_len) == *graph_name
+        {
+            return;
+        }
+        if [
+            rdfs::SUB_CLASS_OF,
+            rdfs::SUB_PROPERTY_OF,
+            rdfs::DOMAIN,
+            rdfs::RANGE,
+        ]
+        .contains(&quad.predicate)
+        {
+            self.rebuild();
+        } else {
+            self.triples.push((
+                EncodedTerm::from(quad.subject).with_str_hash_len(str_hash_len),
+                EncodedTerm::from(quad.predicate).with_str_hash_len(str_hash_len),
+                EncodedTerm::from(quad.object).with_str_hash_len(str_hash_len),
+            ));
+        }
+    }
+
+    /// Records an operation removing a set of quads: all the entailments are then materialized again
+    pub fn rebuild(&mut self) {
+        self.rebuild = true;
+        self.triples.clear();
+    }
+
+    pub fn needs_rebuild(&self) -> bool {
+        self.rebuild
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.rebuild && self.triples.is_empty()
+    }
+
+    /// The number of recorded changes, to [truncate](Self::truncate) to when rolling back to a savepoint
+    pub fn len(&self) -> usize {
+        self.triples.len()
+    }
+
+    /// Forgets the changes recorded after the first `len` ones
+    ///
+    /// A rebuild requested since is kept, it is valid whatever the changes.
+    pub fn truncate(&mut self, len: usize) {
+        self.triples.truncate(len);
+    }
+}
+
+/// Computes the terms reachable from each term of `edges`, except itself
+fn transitive_closure(edges: &TermSets) -> TermSets {
+    edges
+        .keys()
+        .map(|start| {
+            let mut reachable = HashSet::new();
+            let mut to_visit = vec![start];
+            while let Some(term) = to_visit.pop() {
+                for next in edges.get(term).into_iter().flatten() {
+                    if reachable.insert(next.clone()) {
+                        to_visit.push(next);
+                    }
+                }
+            }
+            reachable.remove(start);
+            (start.clone(), reachable)
+        })
+        .filter(|(_, reachable)| !reachable.is_empty())
+        .collect()
+}
+
+/// Adds to the classes of each property the ones of its super properties and their super classes
+fn inherit(classes: &TermSets, super_properties: &TermSets, super_classes: &TermSets) -> TermSets {
+    classes
+        .keys()
+        .chain(super_properties.keys())
+        .map(|property| {
+            let mut all_classes = HashSet::new();
+            for property in [property]
+                .into_iter()
+                .chain(super_properties.get(property).into_iter().flatten())
+            {
+                for class in classes.get(property).into_iter().flatten() {
+                    all_classes.insert(class.clone());
+                    all_classes.extend(super_classes.get(class).into_iter().flatten().cloned());
+                }
+            }
+            (property.clone(), all_classes)
+        })
+        .filter(|(_, all_classes)| !all_classes.is_empty())
+        .collect()
+}
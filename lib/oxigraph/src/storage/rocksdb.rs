@@ -4,29 +4,42 @@ use crate::model::vocab::rdf;
 use crate::model::{BlankNode, GraphName, Term, Triple};
 use crate::model::{GraphNameRef, NamedOrBlankNodeRef, Quad, QuadRef, TermRef};
 use crate::storage::binary_encoder::{
-    QuadEncoding, TYPE_STAR_TRIPLE, WRITTEN_TERM_MAX_SIZE, decode_term, encode_term,
-    encode_term_pair, encode_term_quad, encode_term_triple, write_gosp_quad, write_gpos_quad,
-    write_gspo_quad, write_osp_quad, write_ospg_quad, write_pos_quad, write_posg_quad,
-    write_spo_quad, write_spog_quad, write_term,
+    QuadEncoding, TYPE_DATE_TIME_LITERAL, TYPE_STAR_TRIPLE, TermReader, WRITTEN_TERM_MAX_SIZE,
+    decode_term, encode_term, encode_term_pair, encode_term_quad, encode_term_triple,
+    write_gosp_quad, write_gpos_quad, write_gspo_quad, write_osp_quad, write_ospg_quad,
+    write_pos_quad, write_posg_quad, write_spo_quad, write_spog_quad, write_term,
 };
+use crate::storage::error::TermIdCollisionError;
 pub use crate::storage::error::{CorruptionError, StorageError};
 use crate::storage::numeric_encoder::{
-    Decoder, EncodedQuad, EncodedTerm, StrHash, StrHashHasher, StrLookup, insert_term,
+    Decoder, EncodedQuad, EncodedTerm, STR_HASH_LEN, StrHash, StrHashHasher, StrLookup, insert_term,
 };
 use crate::storage::rocksdb_wrapper::{
-    ColumnFamily, ColumnFamilyDefinition, Db, Iter, Reader, Transaction,
+    BackupEngine, ColumnFamily, ColumnFamilyDefinition, Db, EncryptedEnv, Iter, Reader,
+    SstFileWriter, Transaction, prefix_upper_bound,
 };
+use crate::storage::{
+    BackupInfo, BulkLoadProgress, ColumnFamilyStats, DateTimeRange, Durability, EncryptionKey,
+    StorageProfile,
+};
+use rand::random;
 use rustc_hash::{FxBuildHasher, FxHashSet};
 #[cfg(feature = "rdf-12")]
 use siphasher::sip128::{Hasher128, SipHasher24};
-use std::collections::{HashMap, VecDeque};
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
 use std::error::Error;
+use std::fs::{self, File};
 use std::hash::BuildHasherDefault;
 #[cfg(feature = "rdf-12")]
 use std::hash::Hash;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use std::mem::{swap, take};
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{io, thread};
 
 const LATEST_STORAGE_VERSION: u64 = 2;
@@ -42,12 +55,20 @@ const DPOS_CF: &str = "dpos";
 const DOSP_CF: &str = "dosp";
 const GRAPHS_CF: &str = "graphs";
 const DEFAULT_CF: &str = "default";
+/// Prefix of the keys of the default column family storing the quad expiration times
+const EXPIRATION_KEY_PREFIX: &[u8] = b"oxexpiration";
 const DEFAULT_BULK_LOAD_BATCH_SIZE: usize = 1_000_000;
+/// Size of the string identifiers of the stores created with compact term identifiers
+const COMPACT_STR_HASH_LEN: usize = 8;
 
 /// Low level storage primitives
 #[derive(Clone)]
 pub struct RocksDbStorage {
     db: Db,
+    /// Number of bytes of the string identifiers written in the keys
+    str_hash_len: usize,
+    /// Held while checking that a compact string identifier is not already used by another string and inserting it
+    id2str_lock: Arc<Mutex<()>>,
     default_cf: ColumnFamily,
     id2str_cf: ColumnFamily,
     spog_cf: ColumnFamily,
@@ -63,15 +84,160 @@ pub struct RocksDbStorage {
 }
 
 impl RocksDbStorage {
-    pub fn open(path: &Path) -> Result<Self, StorageError> {
-        Self::setup(Db::open_read_write(path, Self::column_families())?)
+    pub fn open(
+        path: &Path,
+        profile: StorageProfile,
+        encryption_key: Option<&EncryptionKey>,
+        compact_term_ids: bool,
+    ) -> Result<Self, StorageError> {
+        Self::open_with_str_hash_len(
+            if compact_term_ids {
+                COMPACT_STR_HASH_LEN
+            } else {
+                STR_HASH_LEN
+            },
+            |str_hash_len| {
+                Db::open_read_write(
+                    path,
+                    Self::column_families(str_hash_len),
+                    profile,
+                    encryption_key,
+                )
+            },
+        )
+    }
+
+    /// Re-encrypts the store in `path` with a new key
+    ///
+    /// The database is copied into a sibling directory using a backup engine that reads the files with the old key
+    /// and writes them with the new one, then the copy replaces the original directory.
+    pub fn rotate_encryption_key(
+        path: &Path,
+        old_key: &EncryptionKey,
+        new_key: &EncryptionKey,
+    ) -> Result<(), StorageError> {
+        let backup_path = sibling_path(path, "key-rotation-backup")?;
+        let new_path = sibling_path(path, "key-rotation")?;
+        let old_path = sibling_path(path, "key-rotation-old")?;
+        for temporary_path in [&backup_path, &new_path, &old_path] {
+            if temporary_path.exists() {
+                return Err(StorageError::Other(
+                    format!(
+                        "{} already exists, it might be a leftover of an interrupted key rotation",
+                        temporary_path.display()
+                    )
+                    .into(),
+                ));
+            }
+        }
+        let result = (|| {
+            let id = {
+                // We open the store to validate the key and to replay the write-ahead log
+                let storage = Self::open(path, StorageProfile::default(), Some(old_key), false)?;
+                let mut engine = BackupEngine::open_with_envs(
+                    &backup_path,
+                    EncryptedEnv::new(old_key),
+                    EncryptedEnv::new(new_key),
+                )?;
+                storage.db.create_backup(&mut engine)?
+            };
+            BackupEngine::open_with_envs(
+                &backup_path,
+                EncryptedEnv::new(new_key),
+                EncryptedEnv::new(new_key),
+            )?
+            .restore(id, &new_path)
+        })();
+        if backup_path.exists() {
+            fs::remove_dir_all(&backup_path)?;
+        }
+        if let Err(e) = result {
+            if new_path.exists() {
+                fs::remove_dir_all(&new_path)?;
+            }
+            return Err(e);
+        }
+        fs::rename(path, &old_path)?;
+        fs::rename(&new_path, path)?;
+        fs::remove_dir_all(&old_path)?;
+        Ok(())
     }
 
     pub fn open_read_only(path: &Path) -> Result<Self, StorageError> {
-        Self::setup(Db::open_read_only(path, Self::column_families())?)
+        Self::open_with_str_hash_len(STR_HASH_LEN, |str_hash_len| {
+            Db::open_read_only(path, Self::column_families(str_hash_len))
+        })
     }
 
-    fn column_families() -> Vec<ColumnFamilyDefinition> {
+    pub fn open_secondary(
+        primary_path: &Path,
+        secondary_path: &Path,
+    ) -> Result<Self, StorageError> {
+        Self::open_with_str_hash_len(STR_HASH_LEN, |str_hash_len| {
+            Db::open_secondary(
+                primary_path,
+                secondary_path,
+                Self::column_families(str_hash_len),
+            )
+        })
+    }
+
+    /// Opens the database with the string identifiers size saved in it
+    ///
+    /// The prefix extractors of the column families depend on this size,
+    /// so the database is opened again if it is not the expected one.
+    /// New databases get `str_hash_len` bytes identifiers.
+    fn open_with_str_hash_len(
+        str_hash_len: usize,
+        open: impl Fn(usize) -> Result<Db, StorageError>,
+    ) -> Result<Self, StorageError> {
+        let db = open(str_hash_len)?;
+        match Self::saved_str_hash_len(&db)? {
+            Some(saved_str_hash_len) if saved_str_hash_len != str_hash_len => {
+                drop(db);
+                Self::setup(open(saved_str_hash_len)?, saved_str_hash_len)
+            }
+            Some(_) => Self::setup(db, str_hash_len),
+            None => {
+                db.insert(
+                    &db.column_family(DEFAULT_CF)?,
+                    b"oxstrhashlen",
+                    &u64::try_from(str_hash_len)
+                        .map_err(CorruptionError::new)?
+                        .to_be_bytes(),
+                )?;
+                Self::setup(db, str_hash_len)
+            }
+        }
+    }
+
+    /// The size of the string identifiers of the database, `None` if the database is new
+    fn saved_str_hash_len(db: &Db) -> Result<Option<usize>, StorageError> {
+        let default_cf = db.column_family(DEFAULT_CF)?;
+        let Some(len) = db.get(&default_cf, b"oxstrhashlen")? else {
+            // Databases created before the key was introduced use the full identifiers
+            return Ok(db
+                .get(&default_cf, b"oxversion")?
+                .is_some()
+                .then_some(STR_HASH_LEN));
+        };
+        let len = u64::from_be_bytes(len.as_ref().try_into().map_err(|e| {
+            CorruptionError::new(format!(
+                "Error while parsing the string identifier size key: {e}"
+            ))
+        })?);
+        match usize::try_from(len) {
+            Ok(len @ (STR_HASH_LEN | COMPACT_STR_HASH_LEN)) => Ok(Some(len)),
+            _ => Err(CorruptionError::msg(format!(
+                "The RocksDB database is using unsupported {len} bytes string identifiers"
+            ))
+            .into()),
+        }
+    }
+
+    fn column_families(str_hash_len: usize) -> Vec<ColumnFamilyDefinition> {
+        // Size of the encoding of a named node
+        let node_prefix_size = 1 + str_hash_len;
         vec![
             ColumnFamilyDefinition {
                 name: ID2STR_CF,
@@ -82,13 +248,13 @@ impl RocksDbStorage {
             ColumnFamilyDefinition {
                 name: SPOG_CF,
                 use_iter: true,
-                min_prefix_size: 17, // named or blank node start
+                min_prefix_size: node_prefix_size, // named or blank node start
                 unordered_writes: false,
             },
             ColumnFamilyDefinition {
                 name: POSG_CF,
                 use_iter: true,
-                min_prefix_size: 17, // named node start
+                min_prefix_size: node_prefix_size, // named node start
                 unordered_writes: false,
             },
             ColumnFamilyDefinition {
@@ -100,31 +266,31 @@ impl RocksDbStorage {
             ColumnFamilyDefinition {
                 name: GSPO_CF,
                 use_iter: true,
-                min_prefix_size: 17, // named or blank node start
+                min_prefix_size: node_prefix_size, // named or blank node start
                 unordered_writes: false,
             },
             ColumnFamilyDefinition {
                 name: GPOS_CF,
                 use_iter: true,
-                min_prefix_size: 17, // named or blank node start
+                min_prefix_size: node_prefix_size, // named or blank node start
                 unordered_writes: false,
             },
             ColumnFamilyDefinition {
                 name: GOSP_CF,
                 use_iter: true,
-                min_prefix_size: 17, // named or blank node start
+                min_prefix_size: node_prefix_size, // named or blank node start
                 unordered_writes: false,
             },
             ColumnFamilyDefinition {
                 name: DSPO_CF,
                 use_iter: true,
-                min_prefix_size: 17, // named or blank node start
+                min_prefix_size: node_prefix_size, // named or blank node start
                 unordered_writes: false,
             },
             ColumnFamilyDefinition {
                 name: DPOS_CF,
                 use_iter: true,
-                min_prefix_size: 17, // named or blank node start
+                min_prefix_size: node_prefix_size, // named or blank node start
                 unordered_writes: false,
             },
             ColumnFamilyDefinition {
@@ -136,13 +302,13 @@ impl RocksDbStorage {
             ColumnFamilyDefinition {
                 name: GRAPHS_CF,
                 use_iter: true,
-                min_prefix_size: 17, // named or blank node start
+                min_prefix_size: node_prefix_size, // named or blank node start
                 unordered_writes: false,
             },
         ]
     }
 
-    fn setup(db: Db) -> Result<Self, StorageError> {
+    fn setup(db: Db, str_hash_len: usize) -> Result<Self, StorageError> {
         let this = Self {
             default_cf: db.column_family(DEFAULT_CF)?,
             id2str_cf: db.column_family(ID2STR_CF)?,
@@ -157,6 +323,8 @@ impl RocksDbStorage {
             dosp_cf: db.column_family(DOSP_CF)?,
             graphs_cf: db.column_family(GRAPHS_CF)?,
             db,
+            str_hash_len,
+            id2str_lock: Arc::default(),
         };
         this.migrate()?;
         Ok(this)
@@ -175,7 +343,7 @@ impl RocksDbStorage {
             }
             let mut graph_names = graph_names
                 .into_iter()
-                .map(|g| encode_term(&g))
+                .map(|g| encode_term(&g, self.str_hash_len))
                 .collect::<Vec<_>>();
             graph_names.sort_unstable();
             let mut stt_file = self.db.new_sst_file()?;
@@ -309,6 +477,47 @@ impl RocksDbStorage {
         self.db.flush()
     }
 
+    /// The serialized statistics saved with [`save_statistics`](Self::save_statistics)
+    pub fn statistics(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self
+            .db
+            .get(&self.default_cf, b"oxstatistics")?
+            .map(|statistics| statistics.as_ref().to_vec()))
+    }
+
+    pub fn save_statistics(&self, statistics: &[u8]) -> Result<(), StorageError> {
+        self.db
+            .insert(&self.default_cf, b"oxstatistics", statistics)
+    }
+
+    /// The expiration times of the quads inserted with a time-to-live
+    pub fn expirations(&self) -> Result<HashMap<EncodedQuad, SystemTime>, StorageError> {
+        let mut expirations = HashMap::new();
+        let mut iter = self
+            .db
+            .snapshot()
+            .scan_prefix(&self.default_cf, EXPIRATION_KEY_PREFIX)?;
+        while let (Some(key), Some(value)) = (iter.key(), iter.value()) {
+            expirations.insert(
+                decode_expiration_key(key, self.str_hash_len)?,
+                decode_expiration_value(value)?,
+            );
+            iter.next();
+        }
+        iter.status()?;
+        Ok(expirations)
+    }
+
+    pub fn with_durability(mut self, durability: Durability) -> Result<Self, StorageError> {
+        self.db = self.db.with_durability(durability)?;
+        Ok(self)
+    }
+
+    pub fn with_write_stall_errors(mut self) -> Result<Self, StorageError> {
+        self.db = self.db.with_write_stall_errors()?;
+        Ok(self)
+    }
+
     pub fn snapshot(&self) -> RocksDbStorageReader {
         RocksDbStorageReader {
             reader: self.db.snapshot(),
@@ -316,6 +525,11 @@ impl RocksDbStorage {
         }
     }
 
+    /// The number of bytes of the string identifiers written in the keys
+    pub fn str_hash_len(&self) -> usize {
+        self.str_hash_len
+    }
+
     pub fn transaction<T, E: Error + 'static + From<StorageError>>(
         &self,
         f: impl for<'a> Fn(RocksDbStorageWriter<'a>) -> Result<T, E>,
@@ -347,26 +561,135 @@ impl RocksDbStorage {
         self.db.compact(&self.id2str_cf)
     }
 
+    pub fn column_family_stats(&self) -> Vec<ColumnFamilyStats> {
+        [
+            (DEFAULT_CF, &self.default_cf),
+            (ID2STR_CF, &self.id2str_cf),
+            (SPOG_CF, &self.spog_cf),
+            (POSG_CF, &self.posg_cf),
+            (OSPG_CF, &self.ospg_cf),
+            (GSPO_CF, &self.gspo_cf),
+            (GPOS_CF, &self.gpos_cf),
+            (GOSP_CF, &self.gosp_cf),
+            (DSPO_CF, &self.dspo_cf),
+            (DPOS_CF, &self.dpos_cf),
+            (DOSP_CF, &self.dosp_cf),
+            (GRAPHS_CF, &self.graphs_cf),
+        ]
+        .into_iter()
+        .map(|(name, column_family)| {
+            ColumnFamilyStats::new(
+                name,
+                self.db
+                    .property_int(column_family, c"rocksdb.total-sst-files-size")
+                    .unwrap_or(0),
+                self.db
+                    .property_int(column_family, c"rocksdb.cur-size-all-mem-tables")
+                    .unwrap_or(0),
+                self.db
+                    .property_int(column_family, c"rocksdb.estimate-num-keys")
+                    .unwrap_or(0),
+            )
+        })
+        .collect()
+    }
+
     pub fn backup(&self, target_directory: &Path) -> Result<(), StorageError> {
         self.db.backup(target_directory)
     }
 
+    pub fn incremental_backup(
+        &self,
+        engine: &mut BackupEngine,
+    ) -> Result<BackupInfo, StorageError> {
+        if self.db.is_encrypted() {
+            return Err(StorageError::Other(
+                "Incremental backups of encrypted stores are not supported, use Store::backup instead"
+                    .into(),
+            ));
+        }
+        let id = self.db.create_backup(engine)?;
+        engine
+            .backups()
+            .into_iter()
+            .find(|backup| backup.id() == id)
+            .ok_or_else(|| {
+                StorageError::Other(
+                    format!("The backup {id} has not been found after its creation").into(),
+                )
+            })
+    }
+
+    pub fn try_catch_up_with_primary(&self) -> Result<(), StorageError> {
+        self.db.try_catch_up_with_primary()
+    }
+
     pub fn bulk_loader(&self) -> RocksDbStorageBulkLoader {
         RocksDbStorageBulkLoader {
             storage: self.clone(),
             hooks: Vec::new(),
             num_threads: None,
             max_memory_size: None,
+            reproducible: false,
+            external_sort_directory: None,
         }
     }
 }
 
+/// Encodes the quad after [`EXPIRATION_KEY_PREFIX`], the graph name is omitted for the default graph
+fn encode_expiration_key(quad: &EncodedQuad, str_hash_len: usize) -> Vec<u8> {
+    let mut key = Vec::with_capacity(EXPIRATION_KEY_PREFIX.len() + 4 * WRITTEN_TERM_MAX_SIZE);
+    key.extend_from_slice(EXPIRATION_KEY_PREFIX);
+    write_spo_quad(&mut key, quad, str_hash_len);
+    write_term(&mut key, &quad.graph_name, str_hash_len);
+    key
+}
+
+fn decode_expiration_key(key: &[u8], str_hash_len: usize) -> Result<EncodedQuad, StorageError> {
+    let mut buffer = key
+        .strip_prefix(EXPIRATION_KEY_PREFIX)
+        .ok_or_else(|| CorruptionError::new("Invalid quad expiration key"))?;
+    Ok(EncodedQuad {
+        subject: buffer.read_term(str_hash_len)?,
+        predicate: buffer.read_term(str_hash_len)?,
+        object: buffer.read_term(str_hash_len)?,
+        graph_name: if buffer.is_empty() {
+            EncodedTerm::DefaultGraph
+        } else {
+            buffer.read_term(str_hash_len)?
+        },
+    })
+}
+
+/// Encodes the expiration time as big endian milliseconds since the Unix epoch
+fn encode_expiration_value(expiration: SystemTime) -> [u8; 8] {
+    expiration
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| {
+            duration.as_millis().try_into().unwrap_or(u64::MAX)
+        })
+        .to_be_bytes()
+}
+
+fn decode_expiration_value(value: &[u8]) -> Result<SystemTime, StorageError> {
+    let millis = u64::from_be_bytes(value.try_into().map_err(|e| {
+        CorruptionError::new(format!("Error while parsing a quad expiration time: {e}"))
+    })?);
+    UNIX_EPOCH
+        .checked_add(Duration::from_millis(millis))
+        .ok_or_else(|| CorruptionError::new("Invalid quad expiration time").into())
+}
+
 pub struct RocksDbStorageReader {
     reader: Reader,
     storage: RocksDbStorage,
 }
 
 impl RocksDbStorageReader {
+    pub fn str_hash_len(&self) -> usize {
+        self.storage.str_hash_len
+    }
+
     pub fn len(&self) -> Result<usize, StorageError> {
         Ok(self.reader.len(&self.storage.gspo_cf)? + self.reader.len(&self.storage.dspo_cf)?)
     }
@@ -376,13 +699,174 @@ impl RocksDbStorageReader {
             && self.reader.is_empty(&self.storage.dspo_cf)?)
     }
 
+    /// Estimates the number of quads of the graph from the share of the graph in the size of the gspo index
+    pub fn approximate_graph_len(&self, graph_name: &EncodedTerm) -> Result<usize, StorageError> {
+        if graph_name.is_default_graph() {
+            self.approximate_prefix_len(&self.storage.dspo_cf, &[])
+        } else {
+            self.approximate_prefix_len(
+                &self.storage.gspo_cf,
+                &encode_term(graph_name, self.storage.str_hash_len),
+            )
+        }
+    }
+
+    /// Estimates the number of quads matching the pattern from the share of its ranges in the size of the indexes
+    pub fn approximate_len_for_pattern(
+        &self,
+        subject: Option<&EncodedTerm>,
+        predicate: Option<&EncodedTerm>,
+        object: Option<&EncodedTerm>,
+        graph_name: Option<&EncodedTerm>,
+    ) -> Result<usize, StorageError> {
+        let storage = &self.storage;
+        let mut len = 0;
+        if graph_name.is_none_or(EncodedTerm::is_default_graph) {
+            let (column_family, prefix) = match (subject, predicate, object) {
+                (Some(subject), Some(predicate), Some(object)) => (
+                    &storage.dspo_cf,
+                    encode_term_triple(subject, predicate, object, storage.str_hash_len),
+                ),
+                (Some(subject), Some(predicate), None) => (
+                    &storage.dspo_cf,
+                    encode_term_pair(subject, predicate, storage.str_hash_len),
+                ),
+                (Some(subject), None, Some(object)) => (
+                    &storage.dosp_cf,
+                    encode_term_pair(object, subject, storage.str_hash_len),
+                ),
+                (Some(subject), None, None) => {
+                    (&storage.dspo_cf, encode_term(subject, storage.str_hash_len))
+                }
+                (None, Some(predicate), Some(object)) => (
+                    &storage.dpos_cf,
+                    encode_term_pair(predicate, object, storage.str_hash_len),
+                ),
+                (None, Some(predicate), None) => (
+                    &storage.dpos_cf,
+                    encode_term(predicate, storage.str_hash_len),
+                ),
+                (None, None, Some(object)) => {
+                    (&storage.dosp_cf, encode_term(object, storage.str_hash_len))
+                }
+                (None, None, None) => (&storage.dspo_cf, Vec::new()),
+            };
+            len += self.approximate_prefix_len(column_family, &prefix)?;
+        }
+        if let Some(graph_name) = graph_name {
+            if !graph_name.is_default_graph() {
+                let (column_family, prefix) = match (subject, predicate, object) {
+                    (Some(subject), Some(predicate), Some(object)) => (
+                        &storage.gspo_cf,
+                        encode_term_quad(
+                            graph_name,
+                            subject,
+                            predicate,
+                            object,
+                            storage.str_hash_len,
+                        ),
+                    ),
+                    (Some(subject), Some(predicate), None) => (
+                        &storage.gspo_cf,
+                        encode_term_triple(graph_name, subject, predicate, storage.str_hash_len),
+                    ),
+                    (Some(subject), None, Some(object)) => (
+                        &storage.gosp_cf,
+                        encode_term_triple(graph_name, object, subject, storage.str_hash_len),
+                    ),
+                    (Some(subject), None, None) => (
+                        &storage.gspo_cf,
+                        encode_term_pair(graph_name, subject, storage.str_hash_len),
+                    ),
+                    (None, Some(predicate), Some(object)) => (
+                        &storage.gpos_cf,
+                        encode_term_triple(graph_name, predicate, object, storage.str_hash_len),
+                    ),
+                    (None, Some(predicate), None) => (
+                        &storage.gpos_cf,
+                        encode_term_pair(graph_name, predicate, storage.str_hash_len),
+                    ),
+                    (None, None, Some(object)) => (
+                        &storage.gosp_cf,
+                        encode_term_pair(graph_name, object, storage.str_hash_len),
+                    ),
+                    (None, None, None) => (
+                        &storage.gspo_cf,
+                        encode_term(graph_name, storage.str_hash_len),
+                    ),
+                };
+                len += self.approximate_prefix_len(column_family, &prefix)?;
+            }
+        } else {
+            let (column_family, prefix) = match (subject, predicate, object) {
+                (Some(subject), Some(predicate), Some(object)) => (
+                    &storage.spog_cf,
+                    encode_term_triple(subject, predicate, object, storage.str_hash_len),
+                ),
+                (Some(subject), Some(predicate), None) => (
+                    &storage.spog_cf,
+                    encode_term_pair(subject, predicate, storage.str_hash_len),
+                ),
+                (Some(subject), None, Some(object)) => (
+                    &storage.ospg_cf,
+                    encode_term_pair(object, subject, storage.str_hash_len),
+                ),
+                (Some(subject), None, None) => {
+                    (&storage.spog_cf, encode_term(subject, storage.str_hash_len))
+                }
+                (None, Some(predicate), Some(object)) => (
+                    &storage.posg_cf,
+                    encode_term_pair(predicate, object, storage.str_hash_len),
+                ),
+                (None, Some(predicate), None) => (
+                    &storage.posg_cf,
+                    encode_term(predicate, storage.str_hash_len),
+                ),
+                (None, None, Some(object)) => {
+                    (&storage.ospg_cf, encode_term(object, storage.str_hash_len))
+                }
+                (None, None, None) => (&storage.gspo_cf, Vec::new()),
+            };
+            len += self.approximate_prefix_len(column_family, &prefix)?;
+        }
+        Ok(len)
+    }
+
+    /// Estimates the number of keys starting with the prefix from the share of their range in the size of the column family
+    fn approximate_prefix_len(
+        &self,
+        column_family: &ColumnFamily,
+        prefix: &[u8],
+    ) -> Result<usize, StorageError> {
+        let db = &self.storage.db;
+        let key_count = db
+            .property_int(column_family, c"rocksdb.estimate-num-keys")
+            .unwrap_or(0);
+        if prefix.is_empty() {
+            return Ok(key_count.try_into().unwrap_or(usize::MAX));
+        }
+        // All the encoded terms start with a type byte lower than u8::MAX
+        let total_size = db.approximate_size(column_family, &[], &[u8::MAX])?;
+        if total_size == 0 {
+            return Ok(0);
+        }
+        let limit = prefix_upper_bound(prefix)
+            .ok_or_else(|| CorruptionError::new("Invalid encoded term prefix"))?;
+        let size = db.approximate_size(column_family, prefix, &limit)?;
+        Ok(
+            (u128::from(key_count) * u128::from(size) / u128::from(total_size))
+                .try_into()
+                .unwrap_or(usize::MAX),
+        )
+    }
+
     pub fn contains(&self, quad: &EncodedQuad) -> Result<bool, StorageError> {
         let mut buffer = Vec::with_capacity(4 * WRITTEN_TERM_MAX_SIZE);
         if quad.graph_name.is_default_graph() {
-            write_spo_quad(&mut buffer, quad);
+            write_spo_quad(&mut buffer, quad, self.storage.str_hash_len);
             Ok(self.reader.contains_key(&self.storage.dspo_cf, &buffer)?)
         } else {
-            write_gspo_quad(&mut buffer, quad);
+            write_gspo_quad(&mut buffer, quad, self.storage.str_hash_len);
             Ok(self.reader.contains_key(&self.storage.gspo_cf, &buffer)?)
         }
     }
@@ -460,8 +944,8 @@ impl RocksDbStorageReader {
 
     fn quads_for_subject(&self, subject: &EncodedTerm) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::pair(
-            self.dspo_quads(&encode_term(subject)),
-            self.spog_quads(&encode_term(subject)),
+            self.dspo_quads(&encode_term(subject, self.storage.str_hash_len)),
+            self.spog_quads(&encode_term(subject, self.storage.str_hash_len)),
         )
     }
 
@@ -471,8 +955,16 @@ impl RocksDbStorageReader {
         predicate: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::pair(
-            self.dspo_quads(&encode_term_pair(subject, predicate)),
-            self.spog_quads(&encode_term_pair(subject, predicate)),
+            self.dspo_quads(&encode_term_pair(
+                subject,
+                predicate,
+                self.storage.str_hash_len,
+            )),
+            self.spog_quads(&encode_term_pair(
+                subject,
+                predicate,
+                self.storage.str_hash_len,
+            )),
         )
     }
 
@@ -483,8 +975,18 @@ impl RocksDbStorageReader {
         object: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::pair(
-            self.dspo_quads(&encode_term_triple(subject, predicate, object)),
-            self.spog_quads(&encode_term_triple(subject, predicate, object)),
+            self.dspo_quads(&encode_term_triple(
+                subject,
+                predicate,
+                object,
+                self.storage.str_hash_len,
+            )),
+            self.spog_quads(&encode_term_triple(
+                subject,
+                predicate,
+                object,
+                self.storage.str_hash_len,
+            )),
         )
     }
 
@@ -494,15 +996,23 @@ impl RocksDbStorageReader {
         object: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::pair(
-            self.dosp_quads(&encode_term_pair(object, subject)),
-            self.ospg_quads(&encode_term_pair(object, subject)),
+            self.dosp_quads(&encode_term_pair(
+                object,
+                subject,
+                self.storage.str_hash_len,
+            )),
+            self.ospg_quads(&encode_term_pair(
+                object,
+                subject,
+                self.storage.str_hash_len,
+            )),
         )
     }
 
     fn quads_for_predicate(&self, predicate: &EncodedTerm) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::pair(
-            self.dpos_quads(&encode_term(predicate)),
-            self.posg_quads(&encode_term(predicate)),
+            self.dpos_quads(&encode_term(predicate, self.storage.str_hash_len)),
+            self.posg_quads(&encode_term(predicate, self.storage.str_hash_len)),
         )
     }
 
@@ -512,15 +1022,23 @@ impl RocksDbStorageReader {
         object: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::pair(
-            self.dpos_quads(&encode_term_pair(predicate, object)),
-            self.posg_quads(&encode_term_pair(predicate, object)),
+            self.dpos_quads(&encode_term_pair(
+                predicate,
+                object,
+                self.storage.str_hash_len,
+            )),
+            self.posg_quads(&encode_term_pair(
+                predicate,
+                object,
+                self.storage.str_hash_len,
+            )),
         )
     }
 
     fn quads_for_object(&self, object: &EncodedTerm) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::pair(
-            self.dosp_quads(&encode_term(object)),
-            self.ospg_quads(&encode_term(object)),
+            self.dosp_quads(&encode_term(object, self.storage.str_hash_len)),
+            self.ospg_quads(&encode_term(object, self.storage.str_hash_len)),
         )
     }
 
@@ -528,7 +1046,7 @@ impl RocksDbStorageReader {
         RocksDbChainedDecodingQuadIterator::new(if graph_name.is_default_graph() {
             self.dspo_quads(&Vec::default())
         } else {
-            self.gspo_quads(&encode_term(graph_name))
+            self.gspo_quads(&encode_term(graph_name, self.storage.str_hash_len))
         })
     }
 
@@ -538,9 +1056,13 @@ impl RocksDbStorageReader {
         graph_name: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::new(if graph_name.is_default_graph() {
-            self.dspo_quads(&encode_term(subject))
+            self.dspo_quads(&encode_term(subject, self.storage.str_hash_len))
         } else {
-            self.gspo_quads(&encode_term_pair(graph_name, subject))
+            self.gspo_quads(&encode_term_pair(
+                graph_name,
+                subject,
+                self.storage.str_hash_len,
+            ))
         })
     }
 
@@ -551,9 +1073,18 @@ impl RocksDbStorageReader {
         graph_name: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::new(if graph_name.is_default_graph() {
-            self.dspo_quads(&encode_term_pair(subject, predicate))
+            self.dspo_quads(&encode_term_pair(
+                subject,
+                predicate,
+                self.storage.str_hash_len,
+            ))
         } else {
-            self.gspo_quads(&encode_term_triple(graph_name, subject, predicate))
+            self.gspo_quads(&encode_term_triple(
+                graph_name,
+                subject,
+                predicate,
+                self.storage.str_hash_len,
+            ))
         })
     }
 
@@ -565,9 +1096,20 @@ impl RocksDbStorageReader {
         graph_name: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::new(if graph_name.is_default_graph() {
-            self.dspo_quads(&encode_term_triple(subject, predicate, object))
+            self.dspo_quads(&encode_term_triple(
+                subject,
+                predicate,
+                object,
+                self.storage.str_hash_len,
+            ))
         } else {
-            self.gspo_quads(&encode_term_quad(graph_name, subject, predicate, object))
+            self.gspo_quads(&encode_term_quad(
+                graph_name,
+                subject,
+                predicate,
+                object,
+                self.storage.str_hash_len,
+            ))
         })
     }
 
@@ -578,9 +1120,18 @@ impl RocksDbStorageReader {
         graph_name: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::new(if graph_name.is_default_graph() {
-            self.dosp_quads(&encode_term_pair(object, subject))
+            self.dosp_quads(&encode_term_pair(
+                object,
+                subject,
+                self.storage.str_hash_len,
+            ))
         } else {
-            self.gosp_quads(&encode_term_triple(graph_name, object, subject))
+            self.gosp_quads(&encode_term_triple(
+                graph_name,
+                object,
+                subject,
+                self.storage.str_hash_len,
+            ))
         })
     }
 
@@ -590,9 +1141,13 @@ impl RocksDbStorageReader {
         graph_name: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::new(if graph_name.is_default_graph() {
-            self.dpos_quads(&encode_term(predicate))
+            self.dpos_quads(&encode_term(predicate, self.storage.str_hash_len))
         } else {
-            self.gpos_quads(&encode_term_pair(graph_name, predicate))
+            self.gpos_quads(&encode_term_pair(
+                graph_name,
+                predicate,
+                self.storage.str_hash_len,
+            ))
         })
     }
 
@@ -603,9 +1158,18 @@ impl RocksDbStorageReader {
         graph_name: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::new(if graph_name.is_default_graph() {
-            self.dpos_quads(&encode_term_pair(predicate, object))
+            self.dpos_quads(&encode_term_pair(
+                predicate,
+                object,
+                self.storage.str_hash_len,
+            ))
         } else {
-            self.gpos_quads(&encode_term_triple(graph_name, predicate, object))
+            self.gpos_quads(&encode_term_triple(
+                graph_name,
+                predicate,
+                object,
+                self.storage.str_hash_len,
+            ))
         })
     }
 
@@ -615,21 +1179,102 @@ impl RocksDbStorageReader {
         graph_name: &EncodedTerm,
     ) -> RocksDbChainedDecodingQuadIterator {
         RocksDbChainedDecodingQuadIterator::new(if graph_name.is_default_graph() {
-            self.dosp_quads(&encode_term(object))
+            self.dosp_quads(&encode_term(object, self.storage.str_hash_len))
         } else {
-            self.gosp_quads(&encode_term_pair(graph_name, object))
+            self.gosp_quads(&encode_term_pair(
+                graph_name,
+                object,
+                self.storage.str_hash_len,
+            ))
+        })
+    }
+
+    pub fn quads_for_date_time_range(
+        &self,
+        predicate: &EncodedTerm,
+        range: DateTimeRange,
+        graph_name: Option<&EncodedTerm>,
+    ) -> RocksDbChainedDecodingQuadIterator {
+        // The objects are right after the predicate in the pos indexes
+        // and the xsd:dateTime literals are encoded as their type followed by their big endian timeline value
+        let mut iters = Vec::new();
+        match graph_name {
+            None => {
+                let prefix = encode_term(predicate, self.storage.str_hash_len);
+                iters.extend(self.date_time_range_quads(
+                    &self.storage.dpos_cf,
+                    &prefix,
+                    range,
+                    QuadEncoding::Dpos,
+                ));
+                iters.extend(self.date_time_range_quads(
+                    &self.storage.posg_cf,
+                    &prefix,
+                    range,
+                    QuadEncoding::Posg,
+                ));
+            }
+            Some(graph_name) if graph_name.is_default_graph() => {
+                iters.extend(self.date_time_range_quads(
+                    &self.storage.dpos_cf,
+                    &encode_term(predicate, self.storage.str_hash_len),
+                    range,
+                    QuadEncoding::Dpos,
+                ));
+            }
+            Some(graph_name) => {
+                iters.extend(self.date_time_range_quads(
+                    &self.storage.gpos_cf,
+                    &encode_term_pair(graph_name, predicate, self.storage.str_hash_len),
+                    range,
+                    QuadEncoding::Gpos,
+                ));
+            }
+        }
+        RocksDbChainedDecodingQuadIterator::many(iters)
+    }
+
+    fn date_time_range_quads(
+        &self,
+        column_family: &ColumnFamily,
+        prefix: &[u8],
+        range: DateTimeRange,
+        encoding: QuadEncoding,
+    ) -> impl Iterator<Item = RocksDbDecodingQuadIterator> {
+        range.encoded_ranges().map(move |(start, end)| {
+            let mut lower_bound = prefix.to_vec();
+            lower_bound.push(TYPE_DATE_TIME_LITERAL);
+            lower_bound.extend_from_slice(&start);
+            let mut upper_bound = prefix.to_vec();
+            if let Some(end) = u128::from_be_bytes(end).checked_add(1) {
+                upper_bound.push(TYPE_DATE_TIME_LITERAL);
+                upper_bound.extend_from_slice(&end.to_be_bytes());
+            } else {
+                upper_bound.push(TYPE_DATE_TIME_LITERAL + 1);
+            }
+            RocksDbDecodingQuadIterator {
+                iter: self
+                    .reader
+                    .scan_range(column_family, &lower_bound, &upper_bound)
+                    .unwrap(), // TODO: propagate error?
+                encoding,
+                str_hash_len: self.storage.str_hash_len,
+            }
         })
     }
 
     pub fn named_graphs(&self) -> RocksDbDecodingGraphIterator {
         RocksDbDecodingGraphIterator {
             iter: self.reader.iter(&self.storage.graphs_cf).unwrap(), // TODO: propagate error?
+            str_hash_len: self.storage.str_hash_len,
         }
     }
 
     pub fn contains_named_graph(&self, graph_name: &EncodedTerm) -> Result<bool, StorageError> {
-        self.reader
-            .contains_key(&self.storage.graphs_cf, &encode_term(graph_name))
+        self.reader.contains_key(
+            &self.storage.graphs_cf,
+            &encode_term(graph_name, self.storage.str_hash_len),
+        )
     }
 
     fn spog_quads(&self, prefix: &[u8]) -> RocksDbDecodingQuadIterator {
@@ -677,13 +1322,15 @@ impl RocksDbStorageReader {
         RocksDbDecodingQuadIterator {
             iter: self.reader.scan_prefix(column_family, prefix).unwrap(), // TODO: propagate error?
             encoding,
+            str_hash_len: self.storage.str_hash_len,
         }
     }
 
-    pub fn contains_str(&self, key: &StrHash) -> Result<bool, StorageError> {
-        self.storage
-            .db
-            .contains_key(&self.storage.id2str_cf, &key.to_be_bytes())
+    pub fn contains_str(&self, key: StrHash) -> Result<bool, StorageError> {
+        self.storage.db.contains_key(
+            &self.storage.id2str_cf,
+            &key.to_be_bytes()[..self.storage.str_hash_len],
+        )
     }
 
     /// Validates that all the storage invariants held in the data
@@ -701,13 +1348,23 @@ impl RocksDbStorageReader {
             self.decode_quad(&spo)?; // We ensure that the quad is readable
             if !self.storage.db.contains_key(
                 &self.storage.dpos_cf,
-                &encode_term_triple(&spo.predicate, &spo.object, &spo.subject),
+                &encode_term_triple(
+                    &spo.predicate,
+                    &spo.object,
+                    &spo.subject,
+                    self.storage.str_hash_len,
+                ),
             )? {
                 return Err(CorruptionError::new("Quad in dspo and not in dpos").into());
             }
             if !self.storage.db.contains_key(
                 &self.storage.dosp_cf,
-                &encode_term_triple(&spo.object, &spo.subject, &spo.predicate),
+                &encode_term_triple(
+                    &spo.object,
+                    &spo.subject,
+                    &spo.predicate,
+                    self.storage.str_hash_len,
+                ),
             )? {
                 return Err(CorruptionError::new("Quad in dspo and not in dosp").into());
             }
@@ -736,6 +1393,7 @@ impl RocksDbStorageReader {
                     &gspo.predicate,
                     &gspo.object,
                     &gspo.subject,
+                    self.storage.str_hash_len,
                 ),
             )? {
                 return Err(CorruptionError::new("Quad in gspo and not in gpos").into());
@@ -747,6 +1405,7 @@ impl RocksDbStorageReader {
                     &gspo.object,
                     &gspo.subject,
                     &gspo.predicate,
+                    self.storage.str_hash_len,
                 ),
             )? {
                 return Err(CorruptionError::new("Quad in gspo and not in gosp").into());
@@ -758,6 +1417,7 @@ impl RocksDbStorageReader {
                     &gspo.predicate,
                     &gspo.object,
                     &gspo.graph_name,
+                    self.storage.str_hash_len,
                 ),
             )? {
                 return Err(CorruptionError::new("Quad in gspo and not in spog").into());
@@ -769,6 +1429,7 @@ impl RocksDbStorageReader {
                     &gspo.object,
                     &gspo.subject,
                     &gspo.graph_name,
+                    self.storage.str_hash_len,
                 ),
             )? {
                 return Err(CorruptionError::new("Quad in gspo and not in posg").into());
@@ -780,15 +1441,15 @@ impl RocksDbStorageReader {
                     &gspo.subject,
                     &gspo.predicate,
                     &gspo.graph_name,
+                    self.storage.str_hash_len,
                 ),
             )? {
                 return Err(CorruptionError::new("Quad in gspo and not in ospg").into());
             }
-            if !self
-                .storage
-                .db
-                .contains_key(&self.storage.graphs_cf, &encode_term(&gspo.graph_name))?
-            {
+            if !self.storage.db.contains_key(
+                &self.storage.graphs_cf,
+                &encode_term(&gspo.graph_name, self.storage.str_hash_len),
+            )? {
                 return Err(
                     CorruptionError::new("Quad graph name in gspo and not in graphs").into(),
                 );
@@ -799,22 +1460,24 @@ impl RocksDbStorageReader {
 }
 
 pub struct RocksDbChainedDecodingQuadIterator {
-    first: RocksDbDecodingQuadIterator,
-    second: Option<RocksDbDecodingQuadIterator>,
+    first: Option<RocksDbDecodingQuadIterator>,
+    others: std::vec::IntoIter<RocksDbDecodingQuadIterator>,
 }
 
 impl RocksDbChainedDecodingQuadIterator {
     fn new(first: RocksDbDecodingQuadIterator) -> Self {
-        Self {
-            first,
-            second: None,
-        }
+        Self::many(vec![first])
     }
 
     fn pair(first: RocksDbDecodingQuadIterator, second: RocksDbDecodingQuadIterator) -> Self {
+        Self::many(vec![first, second])
+    }
+
+    fn many(iters: Vec<RocksDbDecodingQuadIterator>) -> Self {
+        let mut others = iters.into_iter();
         Self {
-            first,
-            second: Some(second),
+            first: others.next(),
+            others,
         }
     }
 }
@@ -823,12 +1486,11 @@ impl Iterator for RocksDbChainedDecodingQuadIterator {
     type Item = Result<EncodedQuad, StorageError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(result) = self.first.next() {
-            Some(result)
-        } else if let Some(second) = &mut self.second {
-            second.next()
-        } else {
-            None
+        loop {
+            if let Some(result) = self.first.as_mut()?.next() {
+                return Some(result);
+            }
+            self.first = self.others.next();
         }
     }
 }
@@ -836,6 +1498,7 @@ impl Iterator for RocksDbChainedDecodingQuadIterator {
 struct RocksDbDecodingQuadIterator {
     iter: Iter,
     encoding: QuadEncoding,
+    str_hash_len: usize,
 }
 
 impl Iterator for RocksDbDecodingQuadIterator {
@@ -845,7 +1508,7 @@ impl Iterator for RocksDbDecodingQuadIterator {
         if let Err(e) = self.iter.status() {
             return Some(Err(e));
         }
-        let term = self.encoding.decode(self.iter.key()?);
+        let term = self.encoding.decode(self.iter.key()?, self.str_hash_len);
         self.iter.next();
         Some(term)
     }
@@ -853,6 +1516,7 @@ impl Iterator for RocksDbDecodingQuadIterator {
 
 pub struct RocksDbDecodingGraphIterator {
     iter: Iter,
+    str_hash_len: usize,
 }
 
 impl Iterator for RocksDbDecodingGraphIterator {
@@ -862,18 +1526,21 @@ impl Iterator for RocksDbDecodingGraphIterator {
         if let Err(e) = self.iter.status() {
             return Some(Err(e));
         }
-        let term = decode_term(self.iter.key()?);
+        let term = decode_term(self.iter.key()?, self.str_hash_len);
         self.iter.next();
         Some(term)
     }
 }
 
 impl StrLookup for RocksDbStorageReader {
-    fn get_str(&self, key: &StrHash) -> Result<Option<String>, StorageError> {
+    fn get_str(&self, key: StrHash) -> Result<Option<String>, StorageError> {
         Ok(self
             .storage
             .db
-            .get(&self.storage.id2str_cf, &key.to_be_bytes())?
+            .get(
+                &self.storage.id2str_cf,
+                &key.to_be_bytes()[..self.storage.str_hash_len],
+            )?
             .map(|v| String::from_utf8(v.into()))
             .transpose()
             .map_err(CorruptionError::new)?)
@@ -894,11 +1561,41 @@ impl RocksDbStorageWriter<'_> {
         }
     }
 
+    pub fn str_hash_len(&self) -> usize {
+        self.storage.str_hash_len
+    }
+
+    pub fn set_savepoint(&mut self) {
+        self.transaction.set_savepoint();
+    }
+
+    pub fn rollback_to_savepoint(&mut self) -> Result<(), StorageError> {
+        self.transaction.rollback_to_savepoint()
+    }
+
+    /// Sets the time after which the quad is expired, or makes it permanent if `None`
+    pub fn set_expiration(
+        &mut self,
+        quad: &EncodedQuad,
+        expiration: Option<SystemTime>,
+    ) -> Result<(), StorageError> {
+        let key = encode_expiration_key(quad, self.storage.str_hash_len);
+        if let Some(expiration) = expiration {
+            self.transaction.insert(
+                &self.storage.default_cf,
+                &key,
+                &encode_expiration_value(expiration),
+            )
+        } else {
+            self.transaction.remove(&self.storage.default_cf, &key)
+        }
+    }
+
     pub fn insert(&mut self, quad: QuadRef<'_>) -> Result<bool, StorageError> {
         let encoded = quad.into();
         self.buffer.clear();
         let result = if quad.graph_name.is_default_graph() {
-            write_spo_quad(&mut self.buffer, &encoded);
+            write_spo_quad(&mut self.buffer, &encoded, self.storage.str_hash_len);
             if self
                 .transaction
                 .contains_key_for_update(&self.storage.dspo_cf, &self.buffer)?
@@ -909,12 +1606,12 @@ impl RocksDbStorageWriter<'_> {
                     .insert_empty(&self.storage.dspo_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_pos_quad(&mut self.buffer, &encoded);
+                write_pos_quad(&mut self.buffer, &encoded, self.storage.str_hash_len);
                 self.transaction
                     .insert_empty(&self.storage.dpos_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_osp_quad(&mut self.buffer, &encoded);
+                write_osp_quad(&mut self.buffer, &encoded, self.storage.str_hash_len);
                 self.transaction
                     .insert_empty(&self.storage.dosp_cf, &self.buffer)?;
 
@@ -924,7 +1621,7 @@ impl RocksDbStorageWriter<'_> {
                 true
             }
         } else {
-            write_spog_quad(&mut self.buffer, &encoded);
+            write_spog_quad(&mut self.buffer, &encoded, self.storage.str_hash_len);
             if self
                 .transaction
                 .contains_key_for_update(&self.storage.spog_cf, &self.buffer)?
@@ -935,27 +1632,27 @@ impl RocksDbStorageWriter<'_> {
                     .insert_empty(&self.storage.spog_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_posg_quad(&mut self.buffer, &encoded);
+                write_posg_quad(&mut self.buffer, &encoded, self.storage.str_hash_len);
                 self.transaction
                     .insert_empty(&self.storage.posg_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_ospg_quad(&mut self.buffer, &encoded);
+                write_ospg_quad(&mut self.buffer, &encoded, self.storage.str_hash_len);
                 self.transaction
                     .insert_empty(&self.storage.ospg_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_gspo_quad(&mut self.buffer, &encoded);
+                write_gspo_quad(&mut self.buffer, &encoded, self.storage.str_hash_len);
                 self.transaction
                     .insert_empty(&self.storage.gspo_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_gpos_quad(&mut self.buffer, &encoded);
+                write_gpos_quad(&mut self.buffer, &encoded, self.storage.str_hash_len);
                 self.transaction
                     .insert_empty(&self.storage.gpos_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_gosp_quad(&mut self.buffer, &encoded);
+                write_gosp_quad(&mut self.buffer, &encoded, self.storage.str_hash_len);
                 self.transaction
                     .insert_empty(&self.storage.gosp_cf, &self.buffer)?;
 
@@ -964,7 +1661,11 @@ impl RocksDbStorageWriter<'_> {
                 self.insert_term(quad.object, &encoded.object)?;
 
                 self.buffer.clear();
-                write_term(&mut self.buffer, &encoded.graph_name);
+                write_term(
+                    &mut self.buffer,
+                    &encoded.graph_name,
+                    self.storage.str_hash_len,
+                );
                 if !self
                     .transaction
                     .contains_key_for_update(&self.storage.graphs_cf, &self.buffer)?
@@ -986,7 +1687,11 @@ impl RocksDbStorageWriter<'_> {
         let encoded_graph_name = graph_name.into();
 
         self.buffer.clear();
-        write_term(&mut self.buffer, &encoded_graph_name);
+        write_term(
+            &mut self.buffer,
+            &encoded_graph_name,
+            self.storage.str_hash_len,
+        );
         let result = if self
             .transaction
             .contains_key_for_update(&self.storage.graphs_cf, &self.buffer)?
@@ -1021,19 +1726,37 @@ impl RocksDbStorageWriter<'_> {
         }
     }
 
-    fn insert_str(&mut self, key: &StrHash, value: &str) -> Result<(), StorageError> {
-        if self
-            .storage
-            .db
-            .contains_key(&self.storage.id2str_cf, &key.to_be_bytes())?
-        {
-            return Ok(());
+    fn insert_str(&mut self, key: StrHash, value: &str) -> Result<(), StorageError> {
+        let key = &key.to_be_bytes()[..self.storage.str_hash_len];
+        if self.storage.str_hash_len == STR_HASH_LEN {
+            if self.storage.db.contains_key(&self.storage.id2str_cf, key)? {
+                return Ok(());
+            }
+        } else {
+            // The compact identifiers might collide, we check that the stored string is the same
+            let _lock = self
+                .storage
+                .id2str_lock
+                .lock()
+                .map_err(|_| io::Error::other("Mutex poisoned"))?;
+            if let Some(existing_value) = self.storage.db.get(&self.storage.id2str_cf, key)? {
+                return if existing_value.as_ref() == value.as_bytes() {
+                    Ok(())
+                } else {
+                    Err(
+                        TermIdCollisionError::new(value, String::from_utf8_lossy(&existing_value))
+                            .into(),
+                    )
+                };
+            }
+            return self
+                .storage
+                .db
+                .insert(&self.storage.id2str_cf, key, value.as_bytes());
         }
-        self.storage.db.insert(
-            &self.storage.id2str_cf,
-            &key.to_be_bytes(),
-            value.as_bytes(),
-        )
+        self.storage
+            .db
+            .insert(&self.storage.id2str_cf, key, value.as_bytes())
     }
 
     pub fn remove(&mut self, quad: QuadRef<'_>) -> Result<bool, StorageError> {
@@ -1043,7 +1766,7 @@ impl RocksDbStorageWriter<'_> {
     fn remove_encoded(&mut self, quad: &EncodedQuad) -> Result<bool, StorageError> {
         self.buffer.clear();
         let result = if quad.graph_name.is_default_graph() {
-            write_spo_quad(&mut self.buffer, quad);
+            write_spo_quad(&mut self.buffer, quad, self.storage.str_hash_len);
 
             if self
                 .transaction
@@ -1053,12 +1776,12 @@ impl RocksDbStorageWriter<'_> {
                     .remove(&self.storage.dspo_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_pos_quad(&mut self.buffer, quad);
+                write_pos_quad(&mut self.buffer, quad, self.storage.str_hash_len);
                 self.transaction
                     .remove(&self.storage.dpos_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_osp_quad(&mut self.buffer, quad);
+                write_osp_quad(&mut self.buffer, quad, self.storage.str_hash_len);
                 self.transaction
                     .remove(&self.storage.dosp_cf, &self.buffer)?;
                 true
@@ -1066,7 +1789,7 @@ impl RocksDbStorageWriter<'_> {
                 false
             }
         } else {
-            write_spog_quad(&mut self.buffer, quad);
+            write_spog_quad(&mut self.buffer, quad, self.storage.str_hash_len);
 
             if self
                 .transaction
@@ -1076,27 +1799,27 @@ impl RocksDbStorageWriter<'_> {
                     .remove(&self.storage.spog_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_posg_quad(&mut self.buffer, quad);
+                write_posg_quad(&mut self.buffer, quad, self.storage.str_hash_len);
                 self.transaction
                     .remove(&self.storage.posg_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_ospg_quad(&mut self.buffer, quad);
+                write_ospg_quad(&mut self.buffer, quad, self.storage.str_hash_len);
                 self.transaction
                     .remove(&self.storage.ospg_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_gspo_quad(&mut self.buffer, quad);
+                write_gspo_quad(&mut self.buffer, quad, self.storage.str_hash_len);
                 self.transaction
                     .remove(&self.storage.gspo_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_gpos_quad(&mut self.buffer, quad);
+                write_gpos_quad(&mut self.buffer, quad, self.storage.str_hash_len);
                 self.transaction
                     .remove(&self.storage.gpos_cf, &self.buffer)?;
 
                 self.buffer.clear();
-                write_gosp_quad(&mut self.buffer, quad);
+                write_gosp_quad(&mut self.buffer, quad, self.storage.str_hash_len);
                 self.transaction
                     .remove(&self.storage.gosp_cf, &self.buffer)?;
                 true
@@ -1114,7 +1837,11 @@ impl RocksDbStorageWriter<'_> {
             }
         } else {
             self.buffer.clear();
-            write_term(&mut self.buffer, &graph_name.into());
+            write_term(
+                &mut self.buffer,
+                &graph_name.into(),
+                self.storage.str_hash_len,
+            );
             if self
                 .transaction
                 .contains_key_for_update(&self.storage.graphs_cf, &self.buffer)?
@@ -1154,7 +1881,7 @@ impl RocksDbStorageWriter<'_> {
         graph_name: &EncodedTerm,
     ) -> Result<bool, StorageError> {
         self.buffer.clear();
-        write_term(&mut self.buffer, graph_name);
+        write_term(&mut self.buffer, graph_name, self.storage.str_hash_len);
         let result = if self
             .transaction
             .contains_key_for_update(&self.storage.graphs_cf, &self.buffer)?
@@ -1164,7 +1891,7 @@ impl RocksDbStorageWriter<'_> {
                 self.remove_encoded(&quad?)?;
             }
             self.buffer.clear();
-            write_term(&mut self.buffer, graph_name);
+            write_term(&mut self.buffer, graph_name, self.storage.str_hash_len);
             self.transaction
                 .remove(&self.storage.graphs_cf, &self.buffer)?;
             true
@@ -1195,9 +1922,11 @@ impl RocksDbStorageWriter<'_> {
 #[must_use]
 pub struct RocksDbStorageBulkLoader {
     storage: RocksDbStorage,
-    hooks: Vec<Box<dyn Fn(u64)>>,
+    hooks: Vec<Box<dyn Fn(BulkLoadProgress)>>,
     num_threads: Option<usize>,
     max_memory_size: Option<usize>,
+    reproducible: bool,
+    external_sort_directory: Option<PathBuf>,
 }
 
 impl RocksDbStorageBulkLoader {
@@ -1211,11 +1940,21 @@ impl RocksDbStorageBulkLoader {
         self
     }
 
-    pub fn on_progress(mut self, callback: impl Fn(u64) + 'static) -> Self {
+    pub fn on_progress(mut self, callback: impl Fn(BulkLoadProgress) + 'static) -> Self {
         self.hooks.push(Box::new(callback));
         self
     }
 
+    pub fn reproducible(mut self) -> Self {
+        self.reproducible = true;
+        self
+    }
+
+    pub fn with_external_sort_directory(mut self, directory: PathBuf) -> Self {
+        self.external_sort_directory = Some(directory);
+        self
+    }
+
     pub fn load<EI, EO: From<StorageError> + From<EI>>(
         &self,
         quads: impl IntoIterator<Item = Result<Quad, EI>>,
@@ -1237,7 +1976,12 @@ impl RocksDbStorageBulkLoader {
             )
             .into());
         }
-        let done_counter = Mutex::new(0);
+        let external_sort = self
+            .external_sort_directory
+            .as_deref()
+            .map(ExternalSort::new)
+            .transpose()?;
+        let counters = LoadCounters::new();
         let mut done_and_displayed_counter = 0;
         thread::scope(|thread_scope| {
             let mut threads = VecDeque::with_capacity(num_threads - 1);
@@ -1250,7 +1994,8 @@ impl RocksDbStorageBulkLoader {
                         &mut buffer,
                         &mut threads,
                         thread_scope,
-                        &done_counter,
+                        &counters,
+                        external_sort.as_ref(),
                         &mut done_and_displayed_counter,
                         num_threads,
                         batch_size,
@@ -1261,17 +2006,23 @@ impl RocksDbStorageBulkLoader {
                 &mut buffer,
                 &mut threads,
                 thread_scope,
-                &done_counter,
+                &counters,
+                external_sort.as_ref(),
                 &mut done_and_displayed_counter,
                 num_threads,
                 batch_size,
             )?;
             for thread in threads {
                 map_thread_result(thread.join()).map_err(StorageError::Io)??;
-                self.on_possible_progress(&done_counter, &mut done_and_displayed_counter)?;
+                self.on_possible_progress(&counters, &mut done_and_displayed_counter);
             }
-            Ok(())
-        })
+            Ok::<_, EO>(())
+        })?;
+        if let Some(external_sort) = &external_sort {
+            external_sort.ingest(&self.storage, num_threads, self.reproducible, &counters)?;
+            self.report_progress(&counters);
+        }
+        Ok(())
     }
 
     fn spawn_load_thread<'scope>(
@@ -1279,43 +2030,73 @@ impl RocksDbStorageBulkLoader {
         buffer: &mut Vec<Quad>,
         threads: &mut VecDeque<thread::ScopedJoinHandle<'scope, Result<(), StorageError>>>,
         thread_scope: &'scope thread::Scope<'scope, '_>,
-        done_counter: &'scope Mutex<u64>,
+        counters: &'scope LoadCounters,
+        external_sort: Option<&'scope ExternalSort>,
         done_and_displayed_counter: &mut u64,
         num_threads: usize,
         batch_size: usize,
     ) -> Result<(), StorageError> {
-        self.on_possible_progress(done_counter, done_and_displayed_counter)?;
-        // We avoid to have too many threads
-        if threads.len() >= num_threads {
+        self.on_possible_progress(counters, done_and_displayed_counter);
+        // We avoid to have too many threads.
+        // In reproducible mode the batches are ingested one after the other to get the same files in the same order.
+        let max_loading_threads = if self.reproducible { 1 } else { num_threads };
+        if threads.len() >= max_loading_threads {
             if let Some(thread) = threads.pop_front() {
                 map_thread_result(thread.join()).map_err(StorageError::Io)??;
-                self.on_possible_progress(done_counter, done_and_displayed_counter)?;
+                self.on_possible_progress(counters, done_and_displayed_counter);
             }
         }
         let mut buffer_to_load = Vec::with_capacity(batch_size);
         swap(buffer, &mut buffer_to_load);
         let storage = &self.storage;
+        let reproducible = self.reproducible;
         threads.push_back(thread_scope.spawn(move || {
-            FileBulkLoader::new(storage, batch_size).load(buffer_to_load, done_counter)
+            FileBulkLoader::new(storage, batch_size, reproducible, counters, external_sort)
+                .load(buffer_to_load)
         }));
         Ok(())
     }
 
-    fn on_possible_progress(
-        &self,
-        done: &Mutex<u64>,
-        done_and_displayed: &mut u64,
-    ) -> Result<(), StorageError> {
-        let new_counter = *done
-            .lock()
-            .map_err(|_| io::Error::other("Mutex poisoned"))?;
+    fn on_possible_progress(&self, counters: &LoadCounters, done_and_displayed: &mut u64) {
+        let new_counter = counters.loaded_quads.load(Ordering::Relaxed);
         let display_step = DEFAULT_BULK_LOAD_BATCH_SIZE as u64;
         if new_counter / display_step > *done_and_displayed / display_step {
-            for hook in &self.hooks {
-                hook(new_counter);
-            }
+            self.report_progress(counters);
         }
         *done_and_displayed = new_counter;
+    }
+
+    fn report_progress(&self, counters: &LoadCounters) {
+        let progress = BulkLoadProgress::new(
+            counters.loaded_quads.load(Ordering::Relaxed),
+            counters.written_bytes.load(Ordering::Relaxed),
+            counters.start.elapsed(),
+        );
+        for hook in &self.hooks {
+            hook(progress);
+        }
+    }
+}
+
+/// The counters shared between the bulk loader threads
+struct LoadCounters {
+    start: Instant,
+    loaded_quads: AtomicU64,
+    written_bytes: AtomicU64,
+}
+
+impl LoadCounters {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            loaded_quads: AtomicU64::new(0),
+            written_bytes: AtomicU64::new(0),
+        }
+    }
+
+    fn add_written_file(&self, path: &Path) -> Result<(), StorageError> {
+        self.written_bytes
+            .fetch_add(fs::metadata(path)?.len(), Ordering::Relaxed);
         Ok(())
     }
 }
@@ -1326,10 +2107,19 @@ struct FileBulkLoader<'a> {
     quads: FxHashSet<EncodedQuad>,
     triples: FxHashSet<EncodedQuad>,
     graphs: FxHashSet<EncodedTerm>,
+    reproducible: bool,
+    counters: &'a LoadCounters,
+    external_sort: Option<&'a ExternalSort>,
 }
 
 impl<'a> FileBulkLoader<'a> {
-    fn new(storage: &'a RocksDbStorage, batch_size: usize) -> Self {
+    fn new(
+        storage: &'a RocksDbStorage,
+        batch_size: usize,
+        reproducible: bool,
+        counters: &'a LoadCounters,
+        external_sort: Option<&'a ExternalSort>,
+    ) -> Self {
         Self {
             storage,
             id2str: HashMap::with_capacity_and_hasher(
@@ -1339,17 +2129,19 @@ impl<'a> FileBulkLoader<'a> {
             quads: FxHashSet::with_capacity_and_hasher(batch_size, FxBuildHasher),
             triples: FxHashSet::with_capacity_and_hasher(batch_size, FxBuildHasher),
             graphs: FxHashSet::default(),
+            reproducible,
+            counters,
+            external_sort,
         }
     }
 
-    fn load(&mut self, quads: Vec<Quad>, counter: &Mutex<u64>) -> Result<(), StorageError> {
+    fn load(&mut self, quads: Vec<Quad>) -> Result<(), StorageError> {
         self.encode(quads)?;
         let size = self.triples.len() + self.quads.len();
         self.save()?;
-        *counter
-            .lock()
-            .map_err(|_| io::Error::other("Mutex poisoned"))? +=
-            size.try_into().unwrap_or(u64::MAX);
+        self.counters
+            .loaded_quads
+            .fetch_add(size.try_into().unwrap_or(u64::MAX), Ordering::Relaxed);
         Ok(())
     }
 
@@ -1388,6 +2180,7 @@ impl<'a> FileBulkLoader<'a> {
     }
 
     fn save(&mut self) -> Result<(), StorageError> {
+        let str_hash_len = self.storage.str_hash_len;
         let mut to_load = Vec::new();
 
         // id2str
@@ -1397,37 +2190,38 @@ impl<'a> FileBulkLoader<'a> {
                 .map(|(k, v)| (k.to_be_bytes(), v))
                 .collect::<Vec<_>>();
             id2str.sort_unstable();
-            let mut id2str_sst = self.storage.db.new_sst_file()?;
-            for (k, v) in id2str {
-                id2str_sst.insert(&k, v.as_bytes())?;
+            if str_hash_len == STR_HASH_LEN {
+                to_load.push((
+                    &self.storage.id2str_cf,
+                    self.write_sorted(
+                        id2str
+                            .iter()
+                            .map(|(k, v)| (&k[..str_hash_len], v.as_bytes())),
+                    )?,
+                ));
+            } else {
+                self.save_compact_id2str(&id2str)?;
             }
-            to_load.push((&self.storage.id2str_cf, id2str_sst.finish()?));
         }
 
         if !self.triples.is_empty() {
             to_load.push((
                 &self.storage.dspo_cf,
-                self.build_sst_for_keys(
-                    self.triples.iter().map(|quad| {
-                        encode_term_triple(&quad.subject, &quad.predicate, &quad.object)
-                    }),
-                )?,
+                self.write_sorted_keys(self.triples.iter().map(|quad| {
+                    encode_term_triple(&quad.subject, &quad.predicate, &quad.object, str_hash_len)
+                }))?,
             ));
             to_load.push((
                 &self.storage.dpos_cf,
-                self.build_sst_for_keys(
-                    self.triples.iter().map(|quad| {
-                        encode_term_triple(&quad.predicate, &quad.object, &quad.subject)
-                    }),
-                )?,
+                self.write_sorted_keys(self.triples.iter().map(|quad| {
+                    encode_term_triple(&quad.predicate, &quad.object, &quad.subject, str_hash_len)
+                }))?,
             ));
             to_load.push((
                 &self.storage.dosp_cf,
-                self.build_sst_for_keys(
-                    self.triples.iter().map(|quad| {
-                        encode_term_triple(&quad.object, &quad.subject, &quad.predicate)
-                    }),
-                )?,
+                self.write_sorted_keys(self.triples.iter().map(|quad| {
+                    encode_term_triple(&quad.object, &quad.subject, &quad.predicate, str_hash_len)
+                }))?,
             ));
             self.triples.clear();
         }
@@ -1435,80 +2229,134 @@ impl<'a> FileBulkLoader<'a> {
         if !self.quads.is_empty() {
             to_load.push((
                 &self.storage.graphs_cf,
-                self.build_sst_for_keys(self.graphs.iter().map(encode_term))?,
+                self.write_sorted_keys(self.graphs.iter().map(|g| encode_term(g, str_hash_len)))?,
             ));
             self.graphs.clear();
 
             to_load.push((
                 &self.storage.gspo_cf,
-                self.build_sst_for_keys(self.quads.iter().map(|quad| {
+                self.write_sorted_keys(self.quads.iter().map(|quad| {
                     encode_term_quad(
                         &quad.graph_name,
                         &quad.subject,
                         &quad.predicate,
                         &quad.object,
+                        str_hash_len,
                     )
                 }))?,
             ));
             to_load.push((
                 &self.storage.gpos_cf,
-                self.build_sst_for_keys(self.quads.iter().map(|quad| {
+                self.write_sorted_keys(self.quads.iter().map(|quad| {
                     encode_term_quad(
                         &quad.graph_name,
                         &quad.predicate,
                         &quad.object,
                         &quad.subject,
+                        str_hash_len,
                     )
                 }))?,
             ));
             to_load.push((
                 &self.storage.gosp_cf,
-                self.build_sst_for_keys(self.quads.iter().map(|quad| {
+                self.write_sorted_keys(self.quads.iter().map(|quad| {
                     encode_term_quad(
                         &quad.graph_name,
                         &quad.object,
                         &quad.subject,
                         &quad.predicate,
+                        str_hash_len,
                     )
                 }))?,
             ));
             to_load.push((
                 &self.storage.spog_cf,
-                self.build_sst_for_keys(self.quads.iter().map(|quad| {
+                self.write_sorted_keys(self.quads.iter().map(|quad| {
                     encode_term_quad(
                         &quad.subject,
                         &quad.predicate,
                         &quad.object,
                         &quad.graph_name,
+                        str_hash_len,
                     )
                 }))?,
             ));
             to_load.push((
                 &self.storage.posg_cf,
-                self.build_sst_for_keys(self.quads.iter().map(|quad| {
+                self.write_sorted_keys(self.quads.iter().map(|quad| {
                     encode_term_quad(
                         &quad.predicate,
                         &quad.object,
                         &quad.subject,
                         &quad.graph_name,
+                        str_hash_len,
                     )
                 }))?,
             ));
             to_load.push((
                 &self.storage.ospg_cf,
-                self.build_sst_for_keys(self.quads.iter().map(|quad| {
+                self.write_sorted_keys(self.quads.iter().map(|quad| {
                     encode_term_quad(
                         &quad.object,
                         &quad.subject,
                         &quad.predicate,
                         &quad.graph_name,
+                        str_hash_len,
                     )
                 }))?,
             ));
             self.quads.clear();
         }
 
-        self.storage.db.insert_stt_files(&to_load)
+        if let Some(external_sort) = self.external_sort {
+            external_sort.add_runs(to_load)
+        } else {
+            self.storage.db.insert_stt_files(&to_load)
+        }
+    }
+
+    /// Checks that the compact string identifiers are not already used by other strings and ingests the new ones
+    ///
+    /// The check and the ingestion are done while holding the storage lock,
+    /// so the strings are ingested immediately instead of going through the external sort.
+    fn save_compact_id2str(
+        &self,
+        id2str: &[([u8; STR_HASH_LEN], Box<str>)],
+    ) -> Result<(), StorageError> {
+        let str_hash_len = self.storage.str_hash_len;
+        let _lock = self
+            .storage
+            .id2str_lock
+            .lock()
+            .map_err(|_| io::Error::other("Mutex poisoned"))?;
+        let mut sst = None;
+        for (key, value) in id2str {
+            let key = &key[..str_hash_len];
+            if let Some(existing_value) = self.storage.db.get(&self.storage.id2str_cf, key)? {
+                if existing_value.as_ref() != value.as_bytes() {
+                    return Err(TermIdCollisionError::new(
+                        &**value,
+                        String::from_utf8_lossy(&existing_value),
+                    )
+                    .into());
+                }
+                continue;
+            }
+            if sst.is_none() {
+                sst = Some(self.new_sst_file()?);
+            }
+            if let Some(sst) = &mut sst {
+                sst.insert(key, value.as_bytes())?;
+            }
+        }
+        let Some(sst) = sst else {
+            return Ok(());
+        };
+        let path = sst.finish()?;
+        self.counters.add_written_file(&path)?;
+        self.storage
+            .db
+            .insert_stt_files(&[(&self.storage.id2str_cf, path)])
     }
 
     fn insert_term(
@@ -1516,23 +2364,291 @@ impl<'a> FileBulkLoader<'a> {
         term: TermRef<'_>,
         encoded: &EncodedTerm,
     ) -> Result<(), StorageError> {
+        let str_hash_len = self.storage.str_hash_len;
         insert_term(term, encoded, &mut |key, value| {
-            self.id2str.entry(*key).or_insert_with(|| value.into());
+            match self.id2str.entry(written_str_hash(key, str_hash_len)) {
+                Entry::Occupied(entry) => {
+                    if **entry.get() != *value {
+                        return Err(TermIdCollisionError::new(value, &**entry.get()).into());
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(value.into());
+                }
+            }
             Ok(())
         })
     }
 
-    fn build_sst_for_keys(
+    fn write_sorted_keys(
         &self,
         values: impl Iterator<Item = Vec<u8>>,
     ) -> Result<PathBuf, StorageError> {
         let mut values = values.collect::<Vec<_>>();
         values.sort_unstable();
-        let mut sst = self.storage.db.new_sst_file()?;
-        for value in values {
-            sst.insert_empty(&value)?;
+        self.write_sorted(values.iter().map(|value| (value.as_slice(), [].as_slice())))
+    }
+
+    /// Writes the sorted entries to a new SST file or, if the external sort is enabled, to a new run file
+    fn write_sorted<'b>(
+        &self,
+        entries: impl IntoIterator<Item = (&'b [u8], &'b [u8])>,
+    ) -> Result<PathBuf, StorageError> {
+        let path = if let Some(external_sort) = self.external_sort {
+            let mut run = external_sort.new_run()?;
+            for (key, value) in entries {
+                run.insert(key, value)?;
+            }
+            run.finish()?
+        } else {
+            let mut sst = self.new_sst_file()?;
+            for (key, value) in entries {
+                sst.insert(key, value)?;
+            }
+            sst.finish()?
+        };
+        self.counters.add_written_file(&path)?;
+        Ok(path)
+    }
+
+    fn new_sst_file(&self) -> Result<SstFileWriter, StorageError> {
+        if self.reproducible {
+            self.storage.db.new_reproducible_sst_file()
+        } else {
+            self.storage.db.new_sst_file()
+        }
+    }
+}
+
+/// The string identifier as it is read back from a store only writing its first `str_hash_len` bytes
+fn written_str_hash(key: StrHash, str_hash_len: usize) -> StrHash {
+    let mut bytes = key.to_be_bytes();
+    bytes[str_hash_len..].fill(0);
+    StrHash::from_be_bytes(bytes)
+}
+
+/// The maximal number of runs merged at the same time by the external sort, to bound the number of open files
+const MAX_MERGED_RUNS: usize = 64;
+
+/// Sorted runs spilled to a temporary directory by the bulk loader threads
+///
+/// They are merged into a single SST file per column family at the end of the load.
+/// The temporary directory is removed on drop.
+struct ExternalSort {
+    directory: PathBuf,
+    runs: Mutex<Vec<(ColumnFamily, PathBuf)>>,
+}
+
+impl ExternalSort {
+    fn new(parent: &Path) -> Result<Self, StorageError> {
+        let directory = parent.join(format!("oxigraph-bulk-load-{}", random::<u128>()));
+        fs::create_dir_all(&directory)?;
+        Ok(Self {
+            directory,
+            runs: Mutex::default(),
+        })
+    }
+
+    fn new_run(&self) -> Result<RunWriter, StorageError> {
+        RunWriter::create(self.directory.join(random::<u128>().to_string()))
+    }
+
+    fn add_runs(&self, runs: Vec<(&ColumnFamily, PathBuf)>) -> Result<(), StorageError> {
+        self.runs
+            .lock()
+            .map_err(|_| io::Error::other("Mutex poisoned"))?
+            .extend(runs.into_iter().map(|(cf, path)| (cf.clone(), path)));
+        Ok(())
+    }
+
+    /// Merges the runs of each column family into a single SST file and ingests them all at once
+    fn ingest(
+        &self,
+        storage: &RocksDbStorage,
+        num_threads: usize,
+        reproducible: bool,
+        counters: &LoadCounters,
+    ) -> Result<(), StorageError> {
+        let mut runs_by_cf = HashMap::<_, Vec<_>>::new();
+        for (cf, path) in take(
+            &mut *self
+                .runs
+                .lock()
+                .map_err(|_| io::Error::other("Mutex poisoned"))?,
+        ) {
+            runs_by_cf.entry(cf).or_default().push(path);
+        }
+        let jobs = Mutex::new(runs_by_cf.into_iter().collect::<Vec<_>>());
+        let ssts = Mutex::new(Vec::new());
+        thread::scope(|thread_scope| {
+            let (jobs, ssts) = (&jobs, &ssts);
+            let threads = (0..num_threads)
+                .map(|_| {
+                    thread_scope.spawn(move || -> Result<(), StorageError> {
+                        loop {
+                            let Some((cf, runs)) = jobs
+                                .lock()
+                                .map_err(|_| io::Error::other("Mutex poisoned"))?
+                                .pop()
+                            else {
+                                return Ok(());
+                            };
+                            let sst = self.merge_into_sst(storage, runs, reproducible, counters)?;
+                            ssts.lock()
+                                .map_err(|_| io::Error::other("Mutex poisoned"))?
+                                .push((cf, sst));
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            for thread in threads {
+                map_thread_result(thread.join()).map_err(StorageError::Io)??;
+            }
+            Ok::<_, StorageError>(())
+        })?;
+        let ssts = ssts
+            .into_inner()
+            .map_err(|_| io::Error::other("Mutex poisoned"))?;
+        storage.db.insert_stt_files(
+            &ssts
+                .iter()
+                .map(|(cf, path)| (cf, path.clone()))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn merge_into_sst(
+        &self,
+        storage: &RocksDbStorage,
+        mut runs: Vec<PathBuf>,
+        reproducible: bool,
+        counters: &LoadCounters,
+    ) -> Result<PathBuf, StorageError> {
+        // We first merge the runs into bigger runs until there are few enough of them to open them all together
+        while runs.len() > MAX_MERGED_RUNS {
+            runs = runs
+                .chunks(MAX_MERGED_RUNS)
+                .map(|chunk| -> Result<PathBuf, StorageError> {
+                    let mut run = self.new_run()?;
+                    merge_runs(chunk, |key, value| run.insert(key, value))?;
+                    let path = run.finish()?;
+                    counters.add_written_file(&path)?;
+                    Ok(path)
+                })
+                .collect::<Result<_, _>>()?;
         }
-        sst.finish()
+        let mut sst = if reproducible {
+            storage.db.new_reproducible_sst_file()
+        } else {
+            storage.db.new_sst_file()
+        }?;
+        merge_runs(&runs, |key, value| sst.insert(key, value))?;
+        let path = sst.finish()?;
+        counters.add_written_file(&path)?;
+        Ok(path)
+    }
+}
+
+impl Drop for ExternalSort {
+    fn drop(&mut self) {
+        // Best effort: the runs are only temporary files
+        drop(fs::remove_dir_all(&self.directory));
+    }
+}
+
+/// Merges the sorted `runs` into `insert`, skipping the duplicated keys, and removes them
+fn merge_runs(
+    runs: &[PathBuf],
+    mut insert: impl FnMut(&[u8], &[u8]) -> Result<(), StorageError>,
+) -> Result<(), StorageError> {
+    let mut readers = runs
+        .iter()
+        .map(|path| RunReader::open(path))
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (i, reader) in readers.iter_mut().enumerate() {
+        if let Some((key, value)) = reader.next()? {
+            heap.push(Reverse((key, i, value)));
+        }
+    }
+    let mut last_key = None;
+    while let Some(Reverse((key, i, value))) = heap.pop() {
+        if let Some(reader) = readers.get_mut(i) {
+            if let Some((next_key, next_value)) = reader.next()? {
+                heap.push(Reverse((next_key, i, next_value)));
+            }
+        }
+        if last_key.as_ref() != Some(&key) {
+            insert(key.as_slice(), value.as_slice())?;
+            last_key = Some(key);
+        }
+    }
+    for path in runs {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Writes a sorted run as a sequence of length-prefixed keys and values
+struct RunWriter {
+    writer: BufWriter<File>,
+    path: PathBuf,
+}
+
+impl RunWriter {
+    fn create(path: PathBuf) -> Result<Self, StorageError> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(&path)?),
+            path,
+        })
+    }
+
+    fn insert(&mut self, key: &[u8], value: &[u8]) -> Result<(), StorageError> {
+        for bytes in [key, value] {
+            let len = u32::try_from(bytes.len()).map_err(io::Error::other)?;
+            self.writer.write_all(&len.to_be_bytes())?;
+            self.writer.write_all(bytes)?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<PathBuf, StorageError> {
+        self.writer.flush()?;
+        Ok(self.path)
+    }
+}
+
+struct RunReader {
+    reader: BufReader<File>,
+}
+
+impl RunReader {
+    fn open(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(File::open(path)?),
+        })
+    }
+
+    fn next(&mut self) -> io::Result<Option<(Vec<u8>, Vec<u8>)>> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let key = self.read_bytes()?;
+        let value = self.read_bytes()?;
+        Ok(Some((key, value)))
+    }
+
+    fn read_bytes(&mut self) -> io::Result<Vec<u8>> {
+        let mut len = [0; 4];
+        self.reader.read_exact(&mut len)?;
+        let mut bytes = vec![
+            0;
+            u32::from_be_bytes(len)
+                .try_into()
+                .map_err(io::Error::other)?
+        ];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
     }
 }
 
@@ -1545,3 +2661,16 @@ fn map_thread_result<R>(result: thread::Result<R>) -> io::Result<R> {
         })
     })
 }
+
+/// Builds the path `<path>.<suffix>` next to `path`
+fn sibling_path(path: &Path, suffix: &str) -> Result<PathBuf, StorageError> {
+    let mut file_name = path
+        .file_name()
+        .ok_or_else(|| {
+            StorageError::Other(format!("{} is not a valid store path", path.display()).into())
+        })?
+        .to_owned();
+    file_name.push(".");
+    file_name.push(suffix);
+    Ok(path.with_file_name(file_name))
+}
@@ -8,8 +8,11 @@
     clippy::unwrap_in_result
 )]
 
-use crate::storage::error::{CorruptionError, StorageError};
-use libc::c_void;
+use crate::storage::error::{CorruptionError, StorageError, WriteStallError};
+use crate::storage::{BackupInfo, Durability, EncryptionKey, StorageProfile};
+use aes::Aes256;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use libc::{c_char, c_void};
 use oxrocksdb_sys::*;
 use rand::random;
 use std::borrow::Borrow;
@@ -22,10 +25,17 @@ use std::marker::PhantomData;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::rc::{Rc, Weak};
+use std::sync::mpsc::{RecvTimeoutError, Sender, channel};
 use std::sync::{Arc, OnceLock};
-use std::thread::{available_parallelism, yield_now};
+use std::thread::{self, available_parallelism, yield_now};
+use std::time::{Duration, UNIX_EPOCH};
 use std::{fmt, io, ptr, slice};
 
+/// Size of the block caches of the small storage profile
+const SMALL_BLOCK_CACHE_SIZE: usize = 8 * 1024 * 1024;
+/// Maximal size of all the write buffers of the small storage profile
+const SMALL_WRITE_BUFFERS_SIZE: usize = 32 * 1024 * 1024;
+
 macro_rules! ffi_result {
     ( $($function:ident)::*( $arg1:expr $(, $arg:expr)* $(,)? ) ) => {{
         let mut status = rocksdb_status_t {
@@ -53,6 +63,9 @@ pub struct ColumnFamilyDefinition {
 #[derive(Clone)]
 pub struct Db {
     inner: DbKind,
+    sync_writes: bool,
+    write_stall_errors: bool,
+    wal_syncer: Option<Arc<WalSyncer>>,
 }
 
 #[derive(Clone)]
@@ -68,6 +81,9 @@ struct RwDbHandler {
     transactiondb_options: *mut rocksdb_transactiondb_options_t,
     read_options: *mut rocksdb_readoptions_t,
     write_options: *mut rocksdb_writeoptions_t,
+    sync_write_options: *mut rocksdb_writeoptions_t,
+    no_slowdown_write_options: *mut rocksdb_writeoptions_t,
+    no_slowdown_sync_write_options: *mut rocksdb_writeoptions_t,
     flush_options: *mut rocksdb_flushoptions_t,
     env_options: *mut rocksdb_envoptions_t,
     ingest_external_file_options: *mut rocksdb_ingestexternalfileoptions_t,
@@ -77,6 +93,8 @@ struct RwDbHandler {
     cf_handles: Vec<*mut rocksdb_column_family_handle_t>,
     cf_options: Vec<*mut rocksdb_options_t>,
     path: PathBuf,
+    /// Dropped after the database is closed
+    env: Option<EncryptedEnv>,
 }
 
 unsafe impl Send for RwDbHandler {}
@@ -95,6 +113,9 @@ impl Drop for RwDbHandler {
             }
             rocksdb_readoptions_destroy(self.read_options);
             rocksdb_writeoptions_destroy(self.write_options);
+            rocksdb_writeoptions_destroy(self.sync_write_options);
+            rocksdb_writeoptions_destroy(self.no_slowdown_write_options);
+            rocksdb_writeoptions_destroy(self.no_slowdown_sync_write_options);
             rocksdb_flushoptions_destroy(self.flush_options);
             rocksdb_envoptions_destroy(self.env_options);
             rocksdb_ingestexternalfileoptions_destroy(self.ingest_external_file_options);
@@ -114,6 +135,7 @@ struct RoDbHandler {
     column_family_names: Vec<&'static str>,
     cf_handles: Vec<*mut rocksdb_column_family_handle_t>,
     cf_options: Vec<*mut rocksdb_options_t>,
+    is_secondary: bool,
 }
 
 unsafe impl Send for RoDbHandler {}
@@ -140,10 +162,16 @@ impl Db {
     pub fn open_read_write(
         path: &Path,
         column_families: Vec<ColumnFamilyDefinition>,
+        profile: StorageProfile,
+        encryption_key: Option<&EncryptionKey>,
     ) -> Result<Self, StorageError> {
         let c_path = path_to_cstring(path)?;
+        let env = encryption_key.map(EncryptedEnv::new);
         unsafe {
-            let options = Self::db_options(true)?;
+            let options = Self::db_options(true, profile)?;
+            if let Some(env) = &env {
+                rocksdb_options_set_env(options, env.0);
+            }
             rocksdb_options_set_create_if_missing(options, 1);
             rocksdb_options_set_create_missing_column_families(options, 1);
             rocksdb_options_set_compression(options, rocksdb_lz4_compression.try_into().unwrap());
@@ -157,6 +185,9 @@ impl Db {
                 block_based_table_options,
                 16,
             );
+            if profile == StorageProfile::Small {
+                set_small_block_cache(block_based_table_options);
+            }
             rocksdb_options_set_block_based_table_factory(options, block_based_table_options);
             #[cfg(feature = "rocksdb-debug")]
             {
@@ -166,7 +197,7 @@ impl Db {
             }
 
             let (column_family_names, c_column_family_names, cf_options) =
-                Self::column_families_names_and_options(column_families, options);
+                Self::column_families_names_and_options(column_families, options, profile);
             let mut cf_handles: Vec<*mut rocksdb_column_family_handle_t> =
                 vec![ptr::null_mut(); column_family_names.len()];
             let c_num_column_families = c_column_family_names.len().try_into().unwrap();
@@ -219,6 +250,28 @@ impl Db {
                 "rocksdb_writeoptions_create returned null"
             );
 
+            let sync_write_options = rocksdb_writeoptions_create();
+            assert!(
+                !sync_write_options.is_null(),
+                "rocksdb_writeoptions_create returned null"
+            );
+            rocksdb_writeoptions_set_sync(sync_write_options, 1);
+
+            let no_slowdown_write_options = rocksdb_writeoptions_create();
+            assert!(
+                !no_slowdown_write_options.is_null(),
+                "rocksdb_writeoptions_create returned null"
+            );
+            rocksdb_writeoptions_set_no_slowdown(no_slowdown_write_options, 1);
+
+            let no_slowdown_sync_write_options = rocksdb_writeoptions_create();
+            assert!(
+                !no_slowdown_sync_write_options.is_null(),
+                "rocksdb_writeoptions_create returned null"
+            );
+            rocksdb_writeoptions_set_sync(no_slowdown_sync_write_options, 1);
+            rocksdb_writeoptions_set_no_slowdown(no_slowdown_sync_write_options, 1);
+
             let transaction_options = rocksdb_transaction_options_create();
             assert!(
                 !transaction_options.is_null(),
@@ -258,6 +311,9 @@ impl Db {
                     transactiondb_options,
                     read_options,
                     write_options,
+                    sync_write_options,
+                    no_slowdown_write_options,
+                    no_slowdown_sync_write_options,
                     flush_options,
                     env_options,
                     ingest_external_file_options,
@@ -267,7 +323,11 @@ impl Db {
                     cf_handles,
                     cf_options,
                     path: path.into(),
+                    env,
                 })),
+                sync_writes: false,
+                write_stall_errors: false,
+                wal_syncer: None,
             })
         }
     }
@@ -278,9 +338,13 @@ impl Db {
     ) -> Result<Self, StorageError> {
         unsafe {
             let c_path = path_to_cstring(path)?;
-            let options = Self::db_options(true)?;
+            let options = Self::db_options(true, StorageProfile::Default)?;
             let (column_family_names, c_column_family_names, cf_options) =
-                Self::column_families_names_and_options(column_families, options);
+                Self::column_families_names_and_options(
+                    column_families,
+                    options,
+                    StorageProfile::Default,
+                );
             let mut cf_handles: Vec<*mut rocksdb_column_family_handle_t> =
                 vec![ptr::null_mut(); column_family_names.len()];
             let c_num_column_families = c_column_family_names.len().try_into().unwrap();
@@ -328,21 +392,117 @@ impl Db {
                     column_family_names,
                     cf_handles,
                     cf_options,
+                    is_secondary: false,
+                })),
+                sync_writes: false,
+                write_stall_errors: false,
+                wal_syncer: None,
+            })
+        }
+    }
+
+    pub fn open_secondary(
+        primary_path: &Path,
+        secondary_path: &Path,
+        column_families: Vec<ColumnFamilyDefinition>,
+    ) -> Result<Self, StorageError> {
+        unsafe {
+            let c_primary_path = path_to_cstring(primary_path)?;
+            let c_secondary_path = path_to_cstring(secondary_path)?;
+            // Secondary instances must keep all files open
+            let options = Self::db_options(false, StorageProfile::Default)?;
+            let (column_family_names, c_column_family_names, cf_options) =
+                Self::column_families_names_and_options(
+                    column_families,
+                    options,
+                    StorageProfile::Default,
+                );
+            let mut cf_handles: Vec<*mut rocksdb_column_family_handle_t> =
+                vec![ptr::null_mut(); column_family_names.len()];
+            let c_num_column_families = c_column_family_names.len().try_into().unwrap();
+            let db = ffi_result!(rocksdb_open_as_secondary_column_families_with_status(
+                options,
+                c_primary_path.as_ptr(),
+                c_secondary_path.as_ptr(),
+                c_num_column_families,
+                c_column_family_names
+                    .iter()
+                    .map(|cf| cf.as_ptr())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
+                cf_options.as_ptr().cast(),
+                cf_handles.as_mut_ptr(),
+            ))
+            .map_err(|e| {
+                for cf_option in &cf_options {
+                    rocksdb_options_destroy(*cf_option);
+                }
+                rocksdb_options_destroy(options);
+                e
+            })?;
+            assert!(
+                !db.is_null(),
+                "rocksdb_open_as_secondary_column_families_with_status returned null"
+            );
+            for handle in &cf_handles {
+                assert!(
+                    !handle.is_null(),
+                    "rocksdb_open_as_secondary_column_families_with_status returned a null column family"
+                );
+            }
+            let read_options = rocksdb_readoptions_create();
+            assert!(
+                !read_options.is_null(),
+                "rocksdb_readoptions_create returned null"
+            );
+
+            Ok(Self {
+                inner: DbKind::ReadOnly(Arc::new(RoDbHandler {
+                    db,
+                    options,
+                    read_options,
+                    column_family_names,
+                    cf_handles,
+                    cf_options,
+                    is_secondary: true,
                 })),
+                sync_writes: false,
+                write_stall_errors: false,
+                wal_syncer: None,
             })
         }
     }
 
-    fn db_options(limit_max_open_files: bool) -> Result<*mut rocksdb_options_t, StorageError> {
+    fn db_options(
+        limit_max_open_files: bool,
+        profile: StorageProfile,
+    ) -> Result<*mut rocksdb_options_t, StorageError> {
         static ROCKSDB_ENV: OnceLock<UnsafeEnv> = OnceLock::new();
         unsafe {
             let options = rocksdb_options_create();
             assert!(!options.is_null(), "rocksdb_options_create returned null");
-            rocksdb_options_optimize_level_style_compaction(options, 512 * 1024 * 1024);
-            rocksdb_options_increase_parallelism(
-                options,
-                available_parallelism()?.get().try_into().unwrap(),
-            );
+            match profile {
+                StorageProfile::Default => {
+                    rocksdb_options_optimize_level_style_compaction(options, 512 * 1024 * 1024);
+                    rocksdb_options_increase_parallelism(
+                        options,
+                        available_parallelism()?.get().try_into().unwrap(),
+                    );
+                }
+                StorageProfile::Small => {
+                    rocksdb_options_optimize_level_style_compaction(
+                        options,
+                        SMALL_WRITE_BUFFERS_SIZE.try_into().unwrap(),
+                    );
+                    // A single thread for the compactions and another one for the flushes
+                    rocksdb_options_increase_parallelism(options, 1);
+                    rocksdb_options_set_max_background_jobs(options, 2);
+                    rocksdb_options_set_max_subcompactions(options, 1);
+                    rocksdb_options_set_max_write_buffer_number(options, 2);
+                    // The write buffers of all the column families share the same budget
+                    rocksdb_options_set_db_write_buffer_size(options, SMALL_WRITE_BUFFERS_SIZE);
+                }
+            }
             if limit_max_open_files {
                 if let Some(available_fd) = available_file_descriptors()? {
                     if available_fd < 96 {
@@ -382,6 +542,7 @@ impl Db {
     fn column_families_names_and_options(
         mut column_families: Vec<ColumnFamilyDefinition>,
         base_options: *mut rocksdb_options_t,
+        profile: StorageProfile,
     ) -> (Vec<&'static str>, Vec<CString>, Vec<*mut rocksdb_options_t>) {
         if !column_families.iter().any(|c| c.name == "default") {
             column_families.push(ColumnFamilyDefinition {
@@ -402,7 +563,15 @@ impl Db {
             .map(|cf| unsafe {
                 let options = rocksdb_options_create_copy(base_options);
                 if !cf.use_iter {
-                    rocksdb_options_optimize_for_point_lookup(options, 128);
+                    rocksdb_options_optimize_for_point_lookup(
+                        options,
+                        match profile {
+                            StorageProfile::Default => 128,
+                            StorageProfile::Small => {
+                                (SMALL_BLOCK_CACHE_SIZE / (1024 * 1024)).try_into().unwrap()
+                            }
+                        },
+                    );
                 }
                 if cf.min_prefix_size > 0 {
                     rocksdb_options_set_prefix_extractor(
@@ -463,6 +632,63 @@ impl Db {
         }
     }
 
+    /// Sets how the commits are persisted
+    pub fn with_durability(mut self, durability: Durability) -> Result<Self, StorageError> {
+        let DbKind::ReadWrite(db) = &self.inner else {
+            return Err(StorageError::Other(
+                "Durability can only be set on read-write instances".into(),
+            ));
+        };
+        (self.sync_writes, self.wal_syncer) = match durability {
+            Durability::Sync => (true, None),
+            Durability::Async => (false, None),
+            Durability::Interval(interval) => {
+                (false, Some(Arc::new(WalSyncer::start(db, interval)?)))
+            }
+        };
+        Ok(self)
+    }
+
+    /// Makes the writes fail instead of being slowed down or stopped during write stalls
+    pub fn with_write_stall_errors(mut self) -> Result<Self, StorageError> {
+        if !matches!(self.inner, DbKind::ReadWrite(_)) {
+            return Err(StorageError::Other(
+                "Write stall errors can only be enabled on read-write instances".into(),
+            ));
+        }
+        self.write_stall_errors = true;
+        Ok(self)
+    }
+
+    fn write_options(&self, db: &RwDbHandler) -> *mut rocksdb_writeoptions_t {
+        match (self.sync_writes, self.write_stall_errors) {
+            (false, false) => db.write_options,
+            (true, false) => db.sync_write_options,
+            (false, true) => db.no_slowdown_write_options,
+            (true, true) => db.no_slowdown_sync_write_options,
+        }
+    }
+
+    /// Converts the status of a failed write, reporting the write stalls with the current compaction backlog
+    fn write_error(&self, db: &RwDbHandler, status: ErrorStatus) -> StorageError {
+        // With no_slowdown, RocksDB returns an Incomplete status instead of delaying or stopping the write
+        if self.write_stall_errors
+            && status.0.code == rocksdb_status_code_t_rocksdb_status_code_incomplete
+        {
+            let mut pending_compaction_bytes = 0;
+            let pending_compaction_bytes = (unsafe {
+                rocksdb_transactiondb_property_int(
+                    db.db,
+                    c"rocksdb.estimate-pending-compaction-bytes".as_ptr(),
+                    &raw mut pending_compaction_bytes,
+                )
+            } == 0)
+                .then_some(pending_compaction_bytes);
+            return WriteStallError::new(pending_compaction_bytes).into();
+        }
+        status.into()
+    }
+
     pub fn transaction<T, E: Error + 'static + From<StorageError>>(
         &self,
         f: impl for<'a> Fn(Transaction<'a>) -> Result<T, E>,
@@ -477,7 +703,7 @@ impl Db {
             let transaction = unsafe {
                 let transaction = rocksdb_transaction_begin(
                     db.db,
-                    db.write_options,
+                    self.write_options(db),
                     db.transaction_options,
                     ptr::null_mut(),
                 );
@@ -505,7 +731,7 @@ impl Db {
                         rocksdb_transaction_destroy(transaction);
                         rocksdb_readoptions_destroy(read_options);
                         rocksdb_free(snapshot as *mut c_void);
-                        r.map_err(StorageError::from)?; // We make sure to also run destructors if the commit fails
+                        r.map_err(|e| self.write_error(db, e))?; // We make sure to also run destructors if the commit fails
                     }
                     return Ok(result);
                 }
@@ -595,14 +821,15 @@ impl Db {
         unsafe {
             ffi_result!(rocksdb_transactiondb_put_cf_with_status(
                 db.db,
-                db.write_options,
+                self.write_options(db),
                 column_family.0,
                 key.as_ptr().cast(),
                 key.len(),
                 value.as_ptr().cast(),
                 value.len(),
             ))
-        }?;
+        }
+        .map_err(|e| self.write_error(db, e))?;
         Ok(())
     }
 
@@ -643,15 +870,89 @@ impl Db {
         Ok(())
     }
 
+    /// Returns the value of an integer RocksDB property of the column family like `rocksdb.estimate-num-keys`
+    pub fn property_int(&self, column_family: &ColumnFamily, name: &CStr) -> Option<u64> {
+        let mut value = 0;
+        (match &self.inner {
+            DbKind::ReadOnly(db) => unsafe {
+                rocksdb_property_int_cf(db.db, column_family.0, name.as_ptr(), &raw mut value)
+            },
+            DbKind::ReadWrite(db) => unsafe {
+                rocksdb_transactiondb_property_int_cf(
+                    db.db,
+                    column_family.0,
+                    name.as_ptr(),
+                    &raw mut value,
+                )
+            },
+        } == 0)
+            .then_some(value)
+    }
+
+    /// Returns the approximate size on disk and in memory of the keys between `start` (included) and `limit` (excluded)
+    pub fn approximate_size(
+        &self,
+        column_family: &ColumnFamily,
+        start: &[u8],
+        limit: &[u8],
+    ) -> Result<u64, StorageError> {
+        Ok(match &self.inner {
+            DbKind::ReadOnly(db) => unsafe {
+                ffi_result!(rocksdb_approximate_size_cf_with_status(
+                    db.db,
+                    column_family.0,
+                    start.as_ptr().cast(),
+                    start.len(),
+                    limit.as_ptr().cast(),
+                    limit.len(),
+                ))
+            },
+            DbKind::ReadWrite(db) => unsafe {
+                ffi_result!(rocksdb_transactiondb_approximate_size_cf_with_status(
+                    db.db,
+                    column_family.0,
+                    start.as_ptr().cast(),
+                    start.len(),
+                    limit.as_ptr().cast(),
+                    limit.len(),
+                ))
+            },
+        }?)
+    }
+
     pub fn new_sst_file(&self) -> Result<SstFileWriter, StorageError> {
         let DbKind::ReadWrite(db) = &self.inner else {
             return Err(StorageError::Other(
                 "SST creation is only possible on read-write instances".into(),
             ));
         };
+        unsafe { Self::new_sst_file_with_options(db, db.options) }
+    }
+
+    /// Creates a SST file that does not contain the host name in its properties
+    pub fn new_reproducible_sst_file(&self) -> Result<SstFileWriter, StorageError> {
+        let DbKind::ReadWrite(db) = &self.inner else {
+            return Err(StorageError::Other(
+                "SST creation is only possible on read-write instances".into(),
+            ));
+        };
+        unsafe {
+            let options = rocksdb_options_create_copy(db.options);
+            rocksdb_options_set_db_host_id(options, c"".as_ptr());
+            // The writer copies the options it needs
+            let writer = Self::new_sst_file_with_options(db, options);
+            rocksdb_options_destroy(options);
+            writer
+        }
+    }
+
+    unsafe fn new_sst_file_with_options(
+        db: &RwDbHandler,
+        options: *const rocksdb_options_t,
+    ) -> Result<SstFileWriter, StorageError> {
         let path = db.path.join(random::<u128>().to_string());
         unsafe {
-            let writer = rocksdb_sstfilewriter_create(db.env_options, db.options);
+            let writer = rocksdb_sstfilewriter_create(db.env_options, options);
             ffi_result!(rocksdb_sstfilewriter_open_with_status(
                 writer,
                 path_to_cstring(&path)?.as_ptr()
@@ -706,6 +1007,21 @@ impl Db {
         Ok(())
     }
 
+    pub fn try_catch_up_with_primary(&self) -> Result<(), StorageError> {
+        let DbKind::ReadOnly(db) = &self.inner else {
+            return Err(StorageError::Other(
+                "Catching up with the primary is only possible on secondary instances".into(),
+            ));
+        };
+        if !db.is_secondary {
+            return Err(StorageError::Other(
+                "Catching up with the primary is only possible on secondary instances".into(),
+            ));
+        }
+        unsafe { ffi_result!(rocksdb_try_catch_up_with_primary_with_status(db.db)) }?;
+        Ok(())
+    }
+
     pub fn backup(&self, target_directory: &Path) -> Result<(), StorageError> {
         let path = path_to_cstring(target_directory)?;
         match &self.inner {
@@ -721,6 +1037,175 @@ impl Db {
         }?;
         Ok(())
     }
+
+    /// If the database files are encrypted
+    pub fn is_encrypted(&self) -> bool {
+        match &self.inner {
+            DbKind::ReadOnly(_) => false,
+            DbKind::ReadWrite(db) => db.env.is_some(),
+        }
+    }
+
+    pub fn create_backup(&self, engine: &mut BackupEngine) -> Result<u32, StorageError> {
+        Ok(match &self.inner {
+            DbKind::ReadOnly(db) => unsafe {
+                ffi_result!(rocksdb_create_new_backup_with_status(db.db, engine.engine))
+            },
+            DbKind::ReadWrite(db) => unsafe {
+                ffi_result!(rocksdb_transactiondb_create_new_backup_with_status(
+                    db.db,
+                    engine.engine
+                ))
+            },
+        }?)
+    }
+}
+
+/// A RocksDB backup engine storing incremental backups in a directory
+pub struct BackupEngine {
+    engine: *mut rocksdb_backup_engine_t,
+    /// Dropped after the engine is closed
+    _envs: Vec<EncryptedEnv>,
+}
+
+unsafe impl Send for BackupEngine {}
+
+impl Drop for BackupEngine {
+    fn drop(&mut self) {
+        unsafe {
+            rocksdb_backup_engine_close(self.engine);
+        }
+    }
+}
+
+impl BackupEngine {
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        let c_path = path_to_cstring(path)?;
+        let engine =
+            unsafe { ffi_result!(rocksdb_backup_engine_open_with_status(c_path.as_ptr())) }?;
+        assert!(
+            !engine.is_null(),
+            "rocksdb_backup_engine_open_with_status returned null"
+        );
+        Ok(Self {
+            engine,
+            _envs: Vec::new(),
+        })
+    }
+
+    /// Opens a backup engine reading and writing the database files with `db_env`
+    /// and the backup files with `backup_env`
+    pub fn open_with_envs(
+        path: &Path,
+        db_env: EncryptedEnv,
+        backup_env: EncryptedEnv,
+    ) -> Result<Self, StorageError> {
+        let c_path = path_to_cstring(path)?;
+        let engine = unsafe {
+            ffi_result!(rocksdb_backup_engine_open_with_envs_with_status(
+                c_path.as_ptr(),
+                db_env.0,
+                backup_env.0
+            ))
+        }?;
+        assert!(
+            !engine.is_null(),
+            "rocksdb_backup_engine_open_with_envs_with_status returned null"
+        );
+        Ok(Self {
+            engine,
+            _envs: vec![db_env, backup_env],
+        })
+    }
+
+    pub fn backups(&self) -> Vec<BackupInfo> {
+        unsafe {
+            let info = rocksdb_backup_engine_get_backup_info(self.engine);
+            assert!(
+                !info.is_null(),
+                "rocksdb_backup_engine_get_backup_info returned null"
+            );
+            let backups = (0..rocksdb_backup_engine_info_count(info))
+                .map(|i| {
+                    BackupInfo::new(
+                        rocksdb_backup_engine_info_backup_id(info, i),
+                        UNIX_EPOCH
+                            + Duration::from_secs(
+                                rocksdb_backup_engine_info_timestamp(info, i)
+                                    .try_into()
+                                    .unwrap_or(0),
+                            ),
+                        rocksdb_backup_engine_info_size(info, i),
+                        rocksdb_backup_engine_info_number_files(info, i),
+                    )
+                })
+                .collect();
+            rocksdb_backup_engine_info_destroy(info);
+            backups
+        }
+    }
+
+    pub fn verify(&self, id: u32) -> Result<(), StorageError> {
+        unsafe {
+            ffi_result!(rocksdb_backup_engine_verify_backup_with_status(
+                self.engine,
+                id
+            ))
+        }?;
+        Ok(())
+    }
+
+    pub fn restore(&self, id: u32, target_directory: &Path) -> Result<(), StorageError> {
+        let c_path = path_to_cstring(target_directory)?;
+        unsafe {
+            ffi_result!(rocksdb_backup_engine_restore_db_from_backup_with_status(
+                self.engine,
+                id,
+                c_path.as_ptr()
+            ))
+        }?;
+        Ok(())
+    }
+
+    pub fn purge_old_backups(&mut self, backups_to_keep: u32) -> Result<(), StorageError> {
+        unsafe {
+            ffi_result!(rocksdb_backup_engine_purge_old_backups_with_status(
+                self.engine,
+                backups_to_keep
+            ))
+        }?;
+        Ok(())
+    }
+}
+
+/// Syncs the write-ahead log to disk at a fixed interval in a background thread
+///
+/// The thread stops when this struct or the database are dropped.
+struct WalSyncer {
+    _stop: Sender<()>,
+}
+
+impl WalSyncer {
+    fn start(db: &Arc<RwDbHandler>, interval: Duration) -> Result<Self, StorageError> {
+        let (stop, stopped) = channel::<()>();
+        let db = Arc::downgrade(db);
+        thread::Builder::new()
+            .name("Oxigraph WAL syncer".into())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    let Some(db) = db.upgrade() else {
+                        return;
+                    };
+                    if unsafe { ffi_result!(rocksdb_transactiondb_flush_wal_with_status(db.db, 1)) }
+                        .is_err()
+                    {
+                        // RocksDB stops accepting writes after a failed sync, the writes are going to report the error
+                        return;
+                    }
+                }
+            })?;
+        Ok(Self { _stop: stop })
+    }
 }
 
 // It is fine to not keep a lifetime: there is no way to use this type without the database being still in scope.
@@ -836,20 +1321,25 @@ impl Reader {
         column_family: &ColumnFamily,
         prefix: &[u8],
     ) -> Result<Iter, StorageError> {
-        // We generate the upper bound
-        let upper_bound = {
-            let mut bound = prefix.to_vec();
-            let mut found = false;
-            for c in bound.iter_mut().rev() {
-                if *c < u8::MAX {
-                    *c += 1;
-                    found = true;
-                    break;
-                }
-            }
-            found.then_some(bound)
-        };
+        self.scan(column_family, prefix, prefix_upper_bound(prefix))
+    }
+
+    /// Iterates on the keys greater or equal to `lower_bound` and strictly lower than `upper_bound`
+    pub fn scan_range(
+        &self,
+        column_family: &ColumnFamily,
+        lower_bound: &[u8],
+        upper_bound: &[u8],
+    ) -> Result<Iter, StorageError> {
+        self.scan(column_family, lower_bound, Some(upper_bound.to_vec()))
+    }
 
+    fn scan(
+        &self,
+        column_family: &ColumnFamily,
+        lower_bound: &[u8],
+        upper_bound: Option<Vec<u8>>,
+    ) -> Result<Iter, StorageError> {
         unsafe {
             let options = rocksdb_readoptions_create_copy(self.options);
             assert!(
@@ -880,10 +1370,10 @@ impl Reader {
                 }
             };
             assert!(!iter.is_null(), "rocksdb_create_iterator returned null");
-            if prefix.is_empty() {
+            if lower_bound.is_empty() {
                 rocksdb_iter_seek_to_first(iter);
             } else {
-                rocksdb_iter_seek(iter, prefix.as_ptr().cast(), prefix.len());
+                rocksdb_iter_seek(iter, lower_bound.as_ptr().cast(), lower_bound.len());
             }
             let is_currently_valid = rocksdb_iter_valid(iter) != 0;
             Ok(Iter {
@@ -995,6 +1485,21 @@ impl Transaction<'_> {
         }
         Ok(())
     }
+
+    /// Adds a savepoint on top of the savepoint stack of the transaction
+    pub fn set_savepoint(&mut self) {
+        unsafe { rocksdb_transaction_set_savepoint(*self.inner) }
+    }
+
+    /// Undoes the writes done since the last savepoint and removes it from the savepoint stack
+    pub fn rollback_to_savepoint(&mut self) -> Result<(), StorageError> {
+        unsafe {
+            ffi_result!(rocksdb_transaction_rollback_to_savepoint_with_status(
+                *self.inner
+            ))?;
+        }
+        Ok(())
+    }
 }
 
 pub struct PinnableSlice(*mut rocksdb_pinnableslice_t);
@@ -1127,6 +1632,18 @@ impl Iter {
             None
         }
     }
+
+    pub fn value(&self) -> Option<&[u8]> {
+        if self.is_valid() {
+            unsafe {
+                let mut len = 0;
+                let val = rocksdb_iter_value(self.inner, &raw mut len);
+                Some(slice::from_raw_parts(val.cast(), len))
+            }
+        } else {
+            None
+        }
+    }
 }
 
 pub struct SstFileWriter {
@@ -1232,10 +1749,89 @@ impl From<ErrorStatus> for StorageError {
 
 struct UnsafeEnv(*mut rocksdb_env_t);
 
+/// A RocksDB environment encrypting the files with AES-256 in counter mode
+pub struct EncryptedEnv(*mut rocksdb_env_t);
+
+// The cipher is immutable and RocksDB environments are thread-safe
+unsafe impl Send for EncryptedEnv {}
+unsafe impl Sync for EncryptedEnv {}
+
+impl EncryptedEnv {
+    pub fn new(key: &EncryptionKey) -> Self {
+        let cipher = Box::new(Aes256::new(key.as_bytes().into()));
+        let env = unsafe {
+            rocksdb_create_encrypted_env(
+                Box::into_raw(cipher).cast(),
+                Some(drop_aes_cipher),
+                AES_BLOCK_SIZE,
+                Some(aes_encrypt_block),
+                Some(aes_decrypt_block),
+            )
+        };
+        assert!(!env.is_null(), "rocksdb_create_encrypted_env returned null");
+        Self(env)
+    }
+}
+
+impl Drop for EncryptedEnv {
+    fn drop(&mut self) {
+        unsafe {
+            rocksdb_env_destroy(self.0);
+        }
+    }
+}
+
+const AES_BLOCK_SIZE: usize = 16;
+
+unsafe extern "C" fn drop_aes_cipher(cipher: *mut c_void) {
+    drop(unsafe { Box::from_raw(cipher.cast::<Aes256>()) });
+}
+
+unsafe extern "C" fn aes_encrypt_block(cipher: *mut c_void, block: *mut c_char) {
+    unsafe { &*cipher.cast::<Aes256>() }.encrypt_block(unsafe { aes_block(block) });
+}
+
+unsafe extern "C" fn aes_decrypt_block(cipher: *mut c_void, block: *mut c_char) {
+    unsafe { &*cipher.cast::<Aes256>() }.decrypt_block(unsafe { aes_block(block) });
+}
+
+unsafe fn aes_block<'a>(block: *mut c_char) -> &'a mut aes::Block {
+    aes::Block::from_mut_slice(unsafe {
+        slice::from_raw_parts_mut(block.cast::<u8>(), AES_BLOCK_SIZE)
+    })
+}
+
 // Hack for OnceCell. OK because only written in OnceCell and used in a thread-safe way by RocksDB
 unsafe impl Send for UnsafeEnv {}
 unsafe impl Sync for UnsafeEnv {}
 
+/// Replaces the default block cache of the table options by a small one
+unsafe fn set_small_block_cache(
+    block_based_table_options: *mut rocksdb_block_based_table_options_t,
+) {
+    unsafe {
+        let cache = rocksdb_cache_create_lru(SMALL_BLOCK_CACHE_SIZE);
+        assert!(!cache.is_null(), "rocksdb_cache_create_lru returned null");
+        rocksdb_block_based_options_set_block_cache(block_based_table_options, cache);
+        // The table options keep a reference to the cache
+        rocksdb_cache_destroy(cache);
+    }
+}
+
+/// Returns the smallest key greater than all the keys starting with `prefix`, if it exists
+pub fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut bound = prefix.to_vec();
+    let mut found = false;
+    for c in bound.iter_mut().rev() {
+        if *c < u8::MAX {
+            *c += 1;
+            found = true;
+            break;
+        }
+    }
+    found.then_some(bound)
+}
+
 fn path_to_cstring(path: &Path) -> Result<CString, StorageError> {
     Ok(CString::new(path.to_str().ok_or_else(|| {
         io::Error::new(
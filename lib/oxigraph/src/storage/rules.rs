@@ -0,0 +1,333 @@
+//! A forward-chaining engine saturating a set of triples with Horn rules,
+//! and the helpers to write the derived triples into the store.
+
+use crate::model::{NamedNodeRef, NamedOrBlankNodeRef, Quad};
+use crate::storage::numeric_encoder::{Decoder, EncodedQuad, EncodedTerm, StrHash, StrLookup};
+use crate::storage::{StorageError, StorageReader, StorageWriter};
+use std::collections::{HashMap, HashSet};
+
+/// A triple the rules are applied on
+pub type Fact = (EncodedTerm, EncodedTerm, EncodedTerm);
+
+/// A term of a [`Rule`] triple pattern
+#[derive(Clone)]
+pub enum RuleTerm {
+    Variable(usize),
+    Constant(EncodedTerm),
+}
+
+impl From<NamedNodeRef<'_>> for RuleTerm {
+    fn from(node: NamedNodeRef<'_>) -> Self {
+        Self::Constant(node.into())
+    }
+}
+
+impl RuleTerm {
+    #[must_use]
+    fn with_str_hash_len(self, str_hash_len: usize) -> Self {
+        match self {
+            Self::Variable(variable) => Self::Variable(variable),
+            Self::Constant(term) => Self::Constant(term.with_str_hash_len(str_hash_len)),
+        }
+    }
+}
+
+/// A triple pattern of a [`Rule`]
+pub type RuleAtom = [RuleTerm; 3];
+
+/// Derives the `head` triples for each binding of the variables matching all the `body` triple patterns
+///
+/// All the variables of the head must be in the body.
+pub struct Rule {
+    body: Vec<RuleAtom>,
+    head: Vec<RuleAtom>,
+    variables: usize,
+}
+
+impl Rule {
+    pub fn new(body: impl Into<Vec<RuleAtom>>, head: impl Into<Vec<RuleAtom>>) -> Self {
+        let body = body.into();
+        let variables = body
+            .iter()
+            .flatten()
+            .filter_map(|term| match term {
+                RuleTerm::Variable(variable) => Some(variable + 1),
+                RuleTerm::Constant(_) => None,
+            })
+            .max()
+            .unwrap_or(0);
+        Self {
+            body,
+            head: head.into(),
+            variables,
+        }
+    }
+
+    /// Encodes the constants with `str_hash_len` bytes string identifiers, see [`EncodedTerm::with_str_hash_len`]
+    #[must_use]
+    pub fn with_str_hash_len(self, str_hash_len: usize) -> Self {
+        let encode_atoms = |atoms: Vec<RuleAtom>| {
+            atoms
+                .into_iter()
+                .map(|atom| atom.map(|term| term.with_str_hash_len(str_hash_len)))
+                .collect()
+        };
+        Self {
+            body: encode_atoms(self.body),
+            head: encode_atoms(self.head),
+            variables: self.variables,
+        }
+    }
+}
+
+/// The variable values of a partial match of a [`Rule`] body
+type Binding = Vec<Option<EncodedTerm>>;
+
+/// A set of triples indexed by subject, predicate and object
+#[derive(Default)]
+pub struct FactSet {
+    /// The facts in insertion order
+    facts: Vec<Fact>,
+    ids: HashMap<Fact, usize>,
+    by_subject: HashMap<EncodedTerm, Vec<usize>>,
+    by_predicate: HashMap<EncodedTerm, Vec<usize>>,
+    by_object: HashMap<EncodedTerm, Vec<usize>>,
+}
+
+impl FactSet {
+    /// Adds the fact, returns `false` if it was already in the set
+    pub fn insert(&mut self, fact: Fact) -> bool {
+        if self.ids.contains_key(&fact) {
+            return false;
+        }
+        let id = self.facts.len();
+        self.by_subject.entry(fact.0.clone()).or_default().push(id);
+        self.by_predicate
+            .entry(fact.1.clone())
+            .or_default()
+            .push(id);
+        self.by_object.entry(fact.2.clone()).or_default().push(id);
+        self.ids.insert(fact.clone(), id);
+        self.facts.push(fact);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.facts.len()
+    }
+
+    /// The facts in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = &Fact> {
+        self.facts.iter()
+    }
+
+    /// The objects of the facts with the given subject and predicate
+    pub fn objects<'a>(
+        &'a self,
+        subject: &EncodedTerm,
+        predicate: &'a EncodedTerm,
+    ) -> impl Iterator<Item = &'a EncodedTerm> {
+        self.by_subject
+            .get(subject)
+            .into_iter()
+            .flatten()
+            .map(|id| &self.facts[*id])
+            .filter(move |(_, p, _)| p == predicate)
+            .map(|(_, _, o)| o)
+    }
+
+    /// Applies the rules until no new fact is derived
+    ///
+    /// Each fact is matched once against each body pattern, the other patterns being matched against all the facts.
+    pub fn saturate(&mut self, rules: &[Rule]) {
+        let mut next = 0;
+        while let Some(fact) = self.facts.get(next).cloned() {
+            next += 1;
+            let mut derived = Vec::new();
+            for rule in rules {
+                for (i, atom) in rule.body.iter().enumerate() {
+                    let mut binding = vec![None; rule.variables];
+                    if unify(atom, &fact, &mut binding) {
+                        let remaining = (0..rule.body.len()).filter(|j| *j != i).collect();
+                        self.join(rule, remaining, &mut binding, &mut derived);
+                    }
+                }
+            }
+            for fact in derived {
+                self.insert(fact);
+            }
+        }
+    }
+
+    /// Matches the `remaining` body patterns and instantiates the head for each complete binding
+    fn join(
+        &self,
+        rule: &Rule,
+        mut remaining: Vec<usize>,
+        binding: &mut Binding,
+        derived: &mut Vec<Fact>,
+    ) {
+        // We match first the pattern with the most bound terms
+        let Some((position, _)) = remaining.iter().enumerate().max_by_key(|(_, i)| {
+            rule.body[**i]
+                .iter()
+                .filter(|term| resolve(term, binding).is_some())
+                .count()
+        }) else {
+            for atom in &rule.head {
+                if let (Some(s), Some(p), Some(o)) = (
+                    resolve(&atom[0], binding),
+                    resolve(&atom[1], binding),
+                    resolve(&atom[2], binding),
+                ) {
+                    if is_resource(s) && matches!(p, EncodedTerm::NamedNode { .. }) {
+                        derived.push((s.clone(), p.clone(), o.clone()));
+                    }
+                }
+            }
+            return;
+        };
+        let atom = &rule.body[remaining.swap_remove(position)];
+        let candidates = if let Some(subject) = resolve(&atom[0], binding) {
+            self.by_subject.get(subject)
+        } else if let Some(object) = resolve(&atom[2], binding) {
+            self.by_object.get(object)
+        } else if let Some(predicate) = resolve(&atom[1], binding) {
+            self.by_predicate.get(predicate)
+        } else {
+            None
+        };
+        let is_unbound = atom.iter().all(|term| resolve(term, binding).is_none());
+        let mut match_fact = |fact: &Fact| {
+            let saved = binding.clone();
+            if unify(atom, fact, binding) {
+                self.join(rule, remaining.clone(), binding, derived);
+            }
+            *binding = saved;
+        };
+        if let Some(candidates) = candidates {
+            for id in candidates {
+                match_fact(&self.facts[*id]);
+            }
+        } else if is_unbound {
+            for fact in &self.facts {
+                match_fact(fact);
+            }
+        }
+    }
+}
+
+/// The value of the term given the binding, `None` if it is an unbound variable
+fn resolve<'a>(term: &'a RuleTerm, binding: &'a Binding) -> Option<&'a EncodedTerm> {
+    match term {
+        RuleTerm::Variable(variable) => binding[*variable].as_ref(),
+        RuleTerm::Constant(constant) => Some(constant),
+    }
+}
+
+/// Extends the binding to make the pattern match the fact, returns `false` if it is not possible
+///
+/// The binding might be partially extended if the match fails.
+fn unify(atom: &RuleAtom, (s, p, o): &Fact, binding: &mut Binding) -> bool {
+    for (term, value) in atom.iter().zip([s, p, o]) {
+        match term {
+            RuleTerm::Variable(variable) => match &binding[*variable] {
+                Some(bound) => {
+                    if bound != value {
+                        return false;
+                    }
+                }
+                None => binding[*variable] = Some(value.clone()),
+            },
+            RuleTerm::Constant(constant) => {
+                if constant != value {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Returns if the term is an IRI or a blank node, i.e. might be the subject of a triple
+pub fn is_resource(term: &EncodedTerm) -> bool {
+    matches!(
+        term,
+        EncodedTerm::NamedNode { .. }
+            | EncodedTerm::NumericalBlankNode { .. }
+            | EncodedTerm::SmallBlankNode(_)
+            | EncodedTerm::BigBlankNode { .. }
+    )
+}
+
+/// Makes the quads of `graph_name` with the given subject (or all of them if `None`) the given triples
+///
+/// The IRIs of `vocabulary` might be used by the triples even if they are not in the store yet.
+pub fn write_entailments(
+    writer: &mut StorageWriter<'_>,
+    reader: &StorageReader,
+    graph_name: NamedOrBlankNodeRef<'_>,
+    subject: Option<&EncodedTerm>,
+    mut entailed: HashSet<Fact>,
+    vocabulary: &[NamedNodeRef<'static>],
+) -> Result<(), StorageError> {
+    let mut to_remove = Vec::new();
+    for quad in reader.quads_for_pattern(subject, None, None, Some(&graph_name.into())) {
+        let quad = quad?;
+        if !entailed.remove(&(
+            quad.subject.clone(),
+            quad.predicate.clone(),
+            quad.object.clone(),
+        )) {
+            to_remove.push(reader.decode_quad(&quad)?);
+        }
+    }
+    let decoder = VocabularyLookup {
+        reader,
+        vocabulary: vocabulary
+            .iter()
+            .map(|iri| {
+                (
+                    StrHash::new(iri.as_str()).truncate(reader.str_hash_len()),
+                    iri.as_str(),
+                )
+            })
+            .collect(),
+    };
+    let to_insert = entailed
+        .into_iter()
+        .map(|(s, p, o)| {
+            // The graph name might not be in the string store yet
+            let quad =
+                decoder.decode_quad(&EncodedQuad::new(s, p, o, EncodedTerm::DefaultGraph))?;
+            Ok(Quad::new(
+                quad.subject,
+                quad.predicate,
+                quad.object,
+                graph_name.into_owned(),
+            ))
+        })
+        .collect::<Result<Vec<_>, StorageError>>()?;
+    for quad in &to_remove {
+        writer.remove(quad.as_ref())?;
+    }
+    for quad in &to_insert {
+        writer.insert(quad.as_ref())?;
+    }
+    Ok(())
+}
+
+/// Looks up the strings in the vocabulary and then in the store
+struct VocabularyLookup<'a> {
+    reader: &'a StorageReader,
+    vocabulary: HashMap<StrHash, &'static str>,
+}
+
+impl StrLookup for VocabularyLookup<'_> {
+    fn get_str(&self, key: StrHash) -> Result<Option<String>, StorageError> {
+        if let Some(value) = self.vocabulary.get(&key) {
+            return Ok(Some((*value).into()));
+        }
+        self.reader.get_str(key)
+    }
+}
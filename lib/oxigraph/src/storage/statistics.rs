@@ -0,0 +1,183 @@
+//! Statistics about the store content used by the query optimizer.
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+use crate::storage::CorruptionError;
+use crate::storage::numeric_encoder::{Decoder, EncodedTerm};
+use crate::storage::{StorageError, StorageReader};
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+use oxrdf::NamedNode;
+use spareval::{DatasetStatistics, PredicateStatistics};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+
+/// Number of hashes kept by [`DistinctCounter`], the standard error of its estimation is around 6%
+const DISTINCT_COUNTER_SIZE: usize = 256;
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+/// Version of the binary serialization of the statistics
+const STATISTICS_FORMAT_VERSION: u8 = 0;
+
+/// Computes the statistics by scanning all the quads of the store
+///
+/// The number of distinct subjects and objects are estimations to keep the memory usage bounded.
+pub fn compute_statistics(reader: &StorageReader) -> Result<DatasetStatistics, StorageError> {
+    let mut quad_count = 0;
+    let mut predicates = HashMap::<EncodedTerm, PredicateCounter>::new();
+    for quad in reader.quads_for_pattern(None, None, None, None) {
+        let quad = quad?;
+        quad_count += 1;
+        let counter = predicates.entry(quad.predicate).or_default();
+        counter.count += 1;
+        counter.subjects.insert(&quad.subject);
+        counter.objects.insert(&quad.object);
+    }
+    let mut statistics = DatasetStatistics::new(quad_count);
+    for (predicate, counter) in predicates {
+        statistics.insert_predicate(
+            reader.decode_named_node(&predicate)?,
+            PredicateStatistics::new(
+                counter.count,
+                counter.subjects.estimate(),
+                counter.objects.estimate(),
+            ),
+        );
+    }
+    Ok(statistics)
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+/// Serializes the statistics to be persisted in the storage
+pub fn encode_statistics(statistics: &DatasetStatistics) -> Vec<u8> {
+    let mut buffer = vec![STATISTICS_FORMAT_VERSION];
+    write_usize(&mut buffer, statistics.quad_count());
+    for (predicate, predicate_statistics) in statistics.predicates() {
+        write_usize(&mut buffer, predicate.as_str().len());
+        buffer.extend_from_slice(predicate.as_str().as_bytes());
+        write_usize(&mut buffer, predicate_statistics.count());
+        write_usize(&mut buffer, predicate_statistics.distinct_subject_count());
+        write_usize(&mut buffer, predicate_statistics.distinct_object_count());
+    }
+    buffer
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+/// Parses statistics serialized by [`encode_statistics`]
+pub fn decode_statistics(mut buffer: &[u8]) -> Result<DatasetStatistics, StorageError> {
+    let Some((&STATISTICS_FORMAT_VERSION, rest)) = buffer.split_first() else {
+        return Err(CorruptionError::msg("Unsupported version of the stored statistics").into());
+    };
+    buffer = rest;
+    let mut statistics = DatasetStatistics::new(read_usize(&mut buffer)?);
+    while !buffer.is_empty() {
+        let len = read_usize(&mut buffer)?;
+        let Some((predicate, rest)) = buffer.split_at_checked(len) else {
+            return Err(CorruptionError::msg("Truncated stored statistics").into());
+        };
+        let predicate = NamedNode::new(String::from_utf8(predicate.to_vec()).map_err(|e| {
+            CorruptionError::new(format!("Invalid predicate in the stored statistics: {e}"))
+        })?)
+        .map_err(|e| {
+            CorruptionError::new(format!("Invalid predicate in the stored statistics: {e}"))
+        })?;
+        buffer = rest;
+        statistics.insert_predicate(
+            predicate,
+            PredicateStatistics::new(
+                read_usize(&mut buffer)?,
+                read_usize(&mut buffer)?,
+                read_usize(&mut buffer)?,
+            ),
+        );
+    }
+    Ok(statistics)
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn write_usize(buffer: &mut Vec<u8>, value: usize) {
+    buffer.extend_from_slice(&u64::try_from(value).unwrap_or(u64::MAX).to_be_bytes());
+}
+
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn read_usize(buffer: &mut &[u8]) -> Result<usize, StorageError> {
+    let Some((value, rest)) = buffer.split_first_chunk::<8>() else {
+        return Err(CorruptionError::msg("Truncated stored statistics").into());
+    };
+    *buffer = rest;
+    Ok(usize::try_from(u64::from_be_bytes(*value)).unwrap_or(usize::MAX))
+}
+
+#[derive(Default)]
+struct PredicateCounter {
+    count: usize,
+    subjects: DistinctCounter,
+    objects: DistinctCounter,
+}
+
+/// Estimates the number of distinct terms from the smallest values of their hashes ("k minimum values" sketch)
+#[derive(Default)]
+struct DistinctCounter {
+    hashes: BTreeSet<u64>,
+}
+
+impl DistinctCounter {
+    fn insert(&mut self, term: &EncodedTerm) {
+        let mut hasher = DefaultHasher::new();
+        term.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.hashes.len() < DISTINCT_COUNTER_SIZE {
+            self.hashes.insert(hash);
+        } else if self.hashes.last().is_some_and(|max| hash < *max) && self.hashes.insert(hash) {
+            self.hashes.pop_last();
+        }
+    }
+
+    fn estimate(&self) -> usize {
+        match self.hashes.last() {
+            Some(max) if self.hashes.len() >= DISTINCT_COUNTER_SIZE => {
+                // The hashes are uniformly distributed so max / u64::MAX ~ (k - 1) / distinct count
+                let estimate = ((DISTINCT_COUNTER_SIZE as u128 - 1) << 64) / (u128::from(*max) + 1);
+                usize::try_from(estimate).unwrap_or(usize::MAX)
+            }
+            _ => self.hashes.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Literal;
+
+    #[test]
+    fn test_distinct_counter() {
+        let mut counter = DistinctCounter::default();
+        for i in 0..100 {
+            counter.insert(&Literal::from(i % 10).as_ref().into());
+        }
+        assert_eq!(counter.estimate(), 10);
+
+        let mut counter = DistinctCounter::default();
+        for i in 0..100_000 {
+            counter.insert(&Literal::from(i).as_ref().into());
+        }
+        let estimate = counter.estimate();
+        assert!((70_000..130_000).contains(&estimate), "{estimate}");
+    }
+
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    #[test]
+    fn test_statistics_serialization() {
+        let mut statistics = DatasetStatistics::new(10);
+        statistics.insert_predicate(
+            NamedNode::new_unchecked("http://example.com/p"),
+            PredicateStatistics::new(10, 2, 5),
+        );
+        assert_eq!(
+            decode_statistics(&encode_statistics(&statistics)).unwrap(),
+            statistics
+        );
+        decode_statistics(&encode_statistics(&statistics)[..20]).unwrap_err();
+        decode_statistics(&[]).unwrap_err();
+    }
+}
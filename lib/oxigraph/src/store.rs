@@ -25,23 +25,65 @@
 //! };
 //! # Result::<_, Box<dyn std::error::Error>>::Ok(())
 //! ```
-use crate::io::{RdfParseError, RdfParser, RdfSerializer};
+use crate::io::{LanguageDetector, LanguageTagMismatch, RdfParseError, RdfParser, RdfSerializer};
+#[cfg(not(target_family = "wasm"))]
+use crate::io::{RdfFormat, WriterQuadSerializer};
+use crate::model::dataset::DatasetDiff;
 use crate::model::*;
 use crate::sparql::{
-    EvaluationError, Query, QueryExplanation, QueryOptions, QueryResults, Update, UpdateOptions,
-    evaluate_query, evaluate_update,
+    EvaluationError, FullTextSearch, Query, QueryExplanation, QueryOptions, QueryResults,
+    QueryResultsCache, QueryRewriter, Update, UpdateOptions, evaluate_query, evaluate_update,
+};
+use crate::storage::binary_encoder::{TermReader, encode_term};
+use crate::storage::numeric_encoder::{Decoder, EncodedTerm, STR_HASH_LEN, StrHash, StrLookup};
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+pub use crate::storage::{
+    BackupInfo, BackupRepository, ColumnFamilyStats, Durability, EncryptionKey, StorageProfile,
+    StoreOptions,
+};
+pub use crate::storage::{
+    BulkLoadProgress, CorruptionError, GraphQuota, GraphQuotaExceededError, GraphUsage,
+    LoaderError, OwlRlInference, RdfsInference, SerializerError, StorageError, StorageStats,
+    StoreEvent, TermIdCollisionError, WriteStallError,
 };
-use crate::storage::numeric_encoder::{Decoder, EncodedQuad, EncodedTerm};
-pub use crate::storage::{CorruptionError, LoaderError, SerializerError, StorageError};
 use crate::storage::{
-    DecodingGraphIterator, DecodingQuadIterator, Storage, StorageBulkLoader, StorageReader,
-    StorageWriter,
+    DateTimeRange, DecodingGraphIterator, DecodingQuadIterator, Storage, StorageBulkLoader,
+    StorageReader, StorageWriter,
 };
+use oxsdatatypes::DateTime;
+use siphasher::sip128::{Hasher128, SipHasher24};
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
-use std::io::{Read, Write};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, Read, Write};
+#[cfg(not(target_family = "wasm"))]
+use std::mem::{replace, take};
+#[cfg(not(target_family = "wasm"))]
+use std::num::NonZeroUsize;
+use std::ops::RangeBounds;
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 use std::path::Path;
+use std::path::PathBuf;
+#[cfg(not(target_family = "wasm"))]
+use std::sync::Mutex;
+use std::sync::mpsc::Receiver;
+#[cfg(not(target_family = "wasm"))]
+use std::sync::mpsc::sync_channel;
+use std::sync::{Arc, Barrier};
+#[cfg(not(target_family = "wasm"))]
+use std::thread;
+use std::time::Duration;
+
+/// Number of quad changes written in a single transaction by [`Store::transform_quads`]
+const TRANSFORM_BATCH_SIZE: usize = 10_000;
+/// Number of quads sent at once by a [`ParallelDumper`] thread to the writer
+#[cfg(not(target_family = "wasm"))]
+const DUMP_BATCH_SIZE: usize = 10_000;
+/// Number of batches a [`ParallelDumper`] thread might read ahead of the writer
+#[cfg(not(target_family = "wasm"))]
+const DUMP_CHANNEL_CAPACITY: usize = 4;
 
 /// An on-disk [RDF dataset](https://www.w3.org/TR/rdf11-concepts/#dfn-rdf-dataset).
 /// Allows to query and update it using SPARQL.
@@ -82,6 +124,9 @@ use std::path::Path;
 #[derive(Clone)]
 pub struct Store {
     storage: Storage,
+    blank_node_scope: BlankNodeScope,
+    query_rewriters: Vec<Arc<dyn QueryRewriter>>,
+    query_results_cache: Option<Arc<QueryResultsCache>>,
 }
 
 impl Store {
@@ -89,6 +134,9 @@ impl Store {
     pub fn new() -> Result<Self, StorageError> {
         Ok(Self {
             storage: Storage::new()?,
+            blank_node_scope: BlankNodeScope::default(),
+            query_rewriters: Vec::new(),
+            query_results_cache: None,
         })
     }
 
@@ -99,11 +147,87 @@ impl Store {
     /// use [`Store::open_read_only`].
     #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
     pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::open_with_profile(path, StorageProfile::Default)
+    }
+
+    /// Opens a read-write [`Store`] like [`Store::open`] but with the given memory and threads profile.
+    ///
+    /// [`StorageProfile::Small`] uses small block caches and write buffers and a single background compaction thread
+    /// to run on devices with little memory like Raspberry Pis or CI runners.
+    ///
+    /// ```no_run
+    /// use oxigraph::store::{StorageProfile, Store};
+    ///
+    /// let store = Store::open_with_profile("example.db", StorageProfile::Small)?;
+    /// store.validate()?;
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn open_with_profile(
+        path: impl AsRef<Path>,
+        profile: StorageProfile,
+    ) -> Result<Self, StorageError> {
+        Self::open_with_options(path, StoreOptions::default().with_profile(profile))
+    }
+
+    /// Opens a read-write [`Store`] like [`Store::open`] but with the given options.
+    ///
+    /// If an [`EncryptionKey`] is set, all the database files, including the write-ahead log, are encrypted at rest
+    /// with AES-256 in counter mode and the same key must be given each time the store is opened.
+    /// Backups made with [`Store::backup`] are encrypted with the same key.
+    /// The temporary files of the [bulk loader](Store::bulk_loader) are not encrypted.
+    ///
+    /// ```no_run
+    /// use oxigraph::store::{EncryptionKey, StorageProfile, Store, StoreOptions};
+    ///
+    /// let key = EncryptionKey::new([42; 32]);
+    /// let store = Store::open_with_options(
+    ///     "example.db",
+    ///     StoreOptions::default()
+    ///         .with_profile(StorageProfile::Small)
+    ///         .with_encryption_key(key),
+    /// )?;
+    /// store.validate()?;
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn open_with_options(
+        path: impl AsRef<Path>,
+        options: StoreOptions,
+    ) -> Result<Self, StorageError> {
         Ok(Self {
-            storage: Storage::open(path.as_ref())?,
+            storage: Storage::open(path.as_ref(), options)?,
+            blank_node_scope: BlankNodeScope::default(),
+            query_rewriters: Vec::new(),
+            query_results_cache: None,
         })
     }
 
+    /// Re-encrypts the on-disk [`Store`] in `path` from `old_key` to `new_key`.
+    ///
+    /// The store must not be opened while its key is rotated.
+    /// The database is first copied with the new key into a sibling directory that then replaces the original one,
+    /// so enough disk space for a second copy of the database is required.
+    ///
+    /// ```no_run
+    /// use oxigraph::store::{EncryptionKey, Store, StoreOptions};
+    ///
+    /// let old_key = EncryptionKey::new([1; 32]);
+    /// let new_key = EncryptionKey::random();
+    /// Store::rotate_encryption_key("example.db", &old_key, &new_key)?;
+    /// let store =
+    ///     Store::open_with_options("example.db", StoreOptions::default().with_encryption_key(new_key))?;
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn rotate_encryption_key(
+        path: impl AsRef<Path>,
+        old_key: &EncryptionKey,
+        new_key: &EncryptionKey,
+    ) -> Result<(), StorageError> {
+        Storage::rotate_encryption_key(path.as_ref(), old_key, new_key)
+    }
+
     /// Opens a read-only [`Store`] from disk.
     ///
     /// Opening as read-only while having an other process writing the database is undefined behavior.
@@ -111,9 +235,398 @@ impl Store {
     pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self, StorageError> {
         Ok(Self {
             storage: Storage::open_read_only(path.as_ref())?,
+            blank_node_scope: BlankNodeScope::default(),
+            query_rewriters: Vec::new(),
+            query_results_cache: None,
+        })
+    }
+
+    /// Opens a read-only secondary [`Store`] following a read-write primary [`Store`] opened with [`Store::open`].
+    ///
+    /// Unlike [`Store::open_read_only`], the primary can keep writing while the secondary is open.
+    /// The secondary only sees the primary changes after a call to [`Store::try_catch_up_with_primary`].
+    ///
+    /// `secondary_path` is a directory owned by the secondary to store its own log files.
+    /// It must not be used by an other secondary.
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn open_secondary(
+        primary_path: impl AsRef<Path>,
+        secondary_path: impl AsRef<Path>,
+    ) -> Result<Self, StorageError> {
+        Ok(Self {
+            storage: Storage::open_secondary(primary_path.as_ref(), secondary_path.as_ref())?,
+            blank_node_scope: BlankNodeScope::default(),
+            query_rewriters: Vec::new(),
+            query_results_cache: None,
         })
     }
 
+    /// Sets how the blank nodes of the files loaded with [`load_from_reader`](Self::load_from_reader)
+    /// and the [`bulk_loader`](Self::bulk_loader) are identified.
+    ///
+    /// By default, they are renamed to fresh blank nodes for each loaded file.
+    /// The setting only applies to this [`Store`] handle and the ones cloned from it.
+    ///
+    /// ```
+    /// use oxigraph::io::RdfFormat;
+    /// use oxigraph::store::{BlankNodeScope, Store};
+    ///
+    /// let file = b"_:a <http://example.com/p> <http://example.com/o> .";
+    ///
+    /// let store = Store::new()?.with_blank_node_scope(BlankNodeScope::Store);
+    /// store.load_from_reader(RdfFormat::NTriples, file.as_ref())?;
+    /// store.load_from_reader(RdfFormat::NTriples, file.as_ref())?;
+    /// assert_eq!(store.len()?, 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[must_use]
+    pub fn with_blank_node_scope(mut self, scope: BlankNodeScope) -> Self {
+        self.blank_node_scope = scope;
+        self
+    }
+
+    /// Adds a rewriting applied to the SPARQL queries before their evaluation.
+    ///
+    /// The rewriters are applied in the order they are added to the queries evaluated with this [`Store`] handle,
+    /// the ones cloned from it and their transactions. SPARQL updates are not rewritten.
+    /// See [`VocabularyMapping`](crate::sparql::VocabularyMapping) for an example.
+    #[must_use]
+    pub fn with_query_rewriter(mut self, rewriter: impl QueryRewriter + 'static) -> Self {
+        self.query_rewriters.push(Arc::new(rewriter));
+        self.unshare_query_results_cache();
+        self
+    }
+
+    /// Caches the results of the last `capacity` distinct `SELECT` and `ASK` queries evaluated with [`query`](Self::query)
+    /// or [`query_opt`](Self::query_opt), evicting the least recently used ones first.
+    ///
+    /// The results are keyed by the query string and the dataset specification
+    /// and are dropped as soon as the store is written, so the same query against an unchanged store is not evaluated again.
+    /// The other [`QueryOptions`] are not part of the key: queries using custom functions, `SERVICE` calls
+    /// or non-deterministic functions like `RAND()` or `NOW()` might get the results of a previous evaluation.
+    /// Queries with `VALUES` bindings, substitutions or a progress view and the results of more than 10,000 solutions are not cached.
+    ///
+    /// The cache is shared with the handles cloned from this one.
+    ///
+    /// ```
+    /// use oxigraph::sparql::QueryResults;
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?.with_query_results_cache(100);
+    /// let query = "ASK { ?s ?p ?o }";
+    /// assert!(matches!(store.query(query)?, QueryResults::Boolean(false)));
+    /// store.update("INSERT DATA { <http://example.com/s> <http://example.com/p> <http://example.com/o> }")?;
+    /// // The write invalidated the cached result
+    /// assert!(matches!(store.query(query)?, QueryResults::Boolean(true)));
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[must_use]
+    pub fn with_query_results_cache(mut self, capacity: usize) -> Self {
+        self.query_results_cache = Some(Arc::new(QueryResultsCache::new(capacity)));
+        self
+    }
+
+    /// The cached results are computed with the query rewriters, a handle with other rewriters needs its own cache
+    fn unshare_query_results_cache(&mut self) {
+        if let Some(cache) = &mut self.query_results_cache {
+            *cache = Arc::new(QueryResultsCache::new(cache.capacity()));
+        }
+    }
+
+    /// Sets how the commits are persisted on disk.
+    ///
+    /// By default, [`Durability::Async`] is used.
+    /// [`Durability::Sync`] groups the concurrent commits into a single write-ahead log sync
+    /// and [`Durability::Interval`] syncs the write-ahead log in the background,
+    /// trading the latency of the small commits against the data that might be lost on a system crash.
+    ///
+    /// It only applies to the writes done through the returned [`Store`] and its clones,
+    /// and has no effect on in-memory stores.
+    ///
+    /// ```no_run
+    /// use oxigraph::store::{Durability, Store};
+    /// use std::time::Duration;
+    ///
+    /// let store = Store::open("example.db")?
+    ///     .with_durability(Durability::Interval(Duration::from_millis(100)))?;
+    /// store.update("INSERT DATA { <http://example.com> <http://example.com> <http://example.com> }")?;
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn with_durability(mut self, durability: Durability) -> Result<Self, StorageError> {
+        self.storage = self.storage.with_durability(durability)?;
+        Ok(self)
+    }
+
+    /// Makes the writes fail with a [`StorageError::WriteStall`] error instead of waiting
+    /// when RocksDB slows down or stops the writes because its compaction is lagging behind.
+    ///
+    /// The error gives the number of bytes pending compaction so the callers can back off, alert or shed load
+    /// instead of observing an unexplained latency.
+    ///
+    /// It only applies to the writes done through the returned [`Store`] and its clones,
+    /// and has no effect on in-memory stores and on the [bulk loader](Self::bulk_loader).
+    ///
+    /// ```no_run
+    /// use oxigraph::store::{StorageError, Store};
+    ///
+    /// let store = Store::open("example.db")?.with_write_stall_errors()?;
+    /// match store.update("INSERT DATA { <http://example.com> <http://example.com> <http://example.com> }") {
+    ///     Err(oxigraph::sparql::EvaluationError::Storage(StorageError::WriteStall(e))) => {
+    ///         eprintln!("Retrying later, {:?} bytes pending compaction", e.pending_compaction_bytes())
+    ///     }
+    ///     result => result?,
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn with_write_stall_errors(mut self) -> Result<Self, StorageError> {
+        self.storage = self.storage.with_write_stall_errors()?;
+        Ok(self)
+    }
+
+    /// Sets the maximal number of quads or size of a named graph.
+    ///
+    /// The insertions that would make the graph exceed its quota fail with a [`StorageError::GraphQuotaExceeded`] error,
+    /// making the whole transaction or SPARQL update fail.
+    /// The graph size is computed by scanning it on the first insertion into it of each transaction.
+    /// Quads already in the graph when the quota is set are kept, even if they exceed it.
+    ///
+    /// It only applies to the writes done through the returned [`Store`] and its clones,
+    /// and not to the [bulk loader](Self::bulk_loader).
+    /// The quotas are not persisted.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::{GraphQuota, StorageError, Store};
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::new()?.with_graph_quota(ex, GraphQuota::default().with_max_quads(1));
+    /// store.insert(QuadRef::new(ex, ex, ex, ex))?;
+    /// assert!(matches!(
+    ///     store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)),
+    ///     Ok(true)
+    /// ));
+    /// assert!(matches!(
+    ///     store.insert(QuadRef::new(ex, ex, &Literal::from(1), ex)),
+    ///     Err(StorageError::GraphQuotaExceeded(_))
+    /// ));
+    /// assert_eq!(store.graph_usage(ex)?.quads(), 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[must_use]
+    pub fn with_graph_quota<'a>(
+        mut self,
+        graph_name: impl Into<NamedOrBlankNodeRef<'a>>,
+        quota: GraphQuota,
+    ) -> Self {
+        self.storage = self
+            .storage
+            .with_graph_quota(graph_name.into().into_owned(), quota);
+        self
+    }
+
+    /// Returns the named graphs with a [quota](Self::with_graph_quota) and their quota.
+    pub fn graph_quotas(&self) -> impl Iterator<Item = (&NamedOrBlankNode, GraphQuota)> {
+        self.storage.graph_quotas()
+    }
+
+    /// Returns the number of quads and the size of a named graph.
+    ///
+    /// The graph is fully scanned.
+    /// See [`with_graph_quota`](Self::with_graph_quota) for an example.
+    pub fn graph_usage<'a>(
+        &self,
+        graph_name: impl Into<NamedOrBlankNodeRef<'a>>,
+    ) -> Result<GraphUsage, StorageError> {
+        let reader = self.storage.snapshot();
+        reader.graph_usage(&reader.encode_term(graph_name.into()))
+    }
+
+    /// Maintains an in-memory full-text index of the string literals that are objects of the given predicates.
+    ///
+    /// The literals are split into lowercased alphanumeric words.
+    /// In the SPARQL queries, the `?literal <http://oxigraph.org/text#search> "query"` magic triple pattern
+    /// binds `?literal` to the indexed literals containing all the words of the query,
+    /// a query word ending with `*` matching all the words starting with it.
+    /// Join it with the triples containing the literal to get their subjects, like in `?s schema:name ?literal`.
+    /// The query must be a constant literal and the index is looked up once at the query start.
+    ///
+    /// The index is built by scanning the quads with the given predicates and is then updated after each commit,
+    /// bulk load and clear operations rebuilding it.
+    /// It is shared by all the clones of this [`Store`] and replaces the previous index. It is not persisted.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::sparql::QueryResults;
+    /// use oxigraph::store::Store;
+    ///
+    /// let name = NamedNodeRef::new("http://schema.org/name")?;
+    /// let store = Store::new()?.with_full_text_index([name])?;
+    /// let paris = NamedNodeRef::new("http://example.com/paris")?;
+    /// store.insert(QuadRef::new(
+    ///     paris,
+    ///     name,
+    ///     &Literal::new_language_tagged_literal("Paris, City of Light", "en")?,
+    ///     GraphNameRef::DefaultGraph,
+    /// ))?;
+    /// if let QueryResults::Solutions(mut solutions) = store.query(
+    ///     "SELECT ?s WHERE { ?s <http://schema.org/name> ?name . ?name <http://oxigraph.org/text#search> \"light\" }",
+    /// )? {
+    ///     assert_eq!(solutions.next().unwrap()?.get("s"), Some(&paris.into_owned().into()));
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn with_full_text_index<'a>(
+        mut self,
+        predicates: impl IntoIterator<Item = impl Into<NamedNodeRef<'a>>>,
+    ) -> Result<Self, StorageError> {
+        self.storage.set_full_text_index(
+            predicates
+                .into_iter()
+                .map(|predicate| predicate.into().into_owned())
+                .collect(),
+        )?;
+        // It must see the magic triple patterns before the other rewriters
+        self.query_rewriters
+            .insert(0, Arc::new(FullTextSearch::new(self.storage.clone())));
+        self.unshare_query_results_cache();
+        Ok(self)
+    }
+
+    /// Materializes the [RDFS entailments](https://www.w3.org/TR/rdf11-mt/#rdfs-entailment) of the store content into a dedicated named graph.
+    ///
+    /// The entailments of `rdfs:subClassOf`, `rdfs:subPropertyOf`, `rdfs:domain` and `rdfs:range` are computed
+    /// from the triples of all the graphs except the inference one, the triples already in the store are not duplicated.
+    /// Query the union of the graphs (see [`QueryDataset::set_default_graph_as_union`](crate::sparql::QueryDataset::set_default_graph_as_union))
+    /// or the inference graph to get them, e.g. to match `?x a ex:SuperClass` with the instances of the subclasses.
+    ///
+    /// The entailments are computed now, after each bulk load and on [`materialize_rdfs_inference`](Self::materialize_rdfs_inference) calls.
+    /// If the inference is [incremental](RdfsInference::incremental), they are also updated after each commit:
+    /// the entailments about the subjects and objects of the changed triples are computed again,
+    /// and all of them if the schema has changed or a clear operation has been done.
+    /// The inference is shared by all the clones of this [`Store`] and replaces the previous one. It is not persisted.
+    ///
+    /// ```
+    /// use oxigraph::model::vocab::{rdf, rdfs};
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::{RdfsInference, Store};
+    ///
+    /// let inferred = NamedNode::new("http://example.com/inferred")?;
+    /// let store = Store::new()?.with_rdfs_inference(RdfsInference::new(inferred.clone()).incremental())?;
+    /// let city = NamedNodeRef::new("http://example.com/City")?;
+    /// let place = NamedNodeRef::new("http://example.com/Place")?;
+    /// let paris = NamedNodeRef::new("http://example.com/paris")?;
+    /// store.insert(QuadRef::new(city, rdfs::SUB_CLASS_OF, place, GraphNameRef::DefaultGraph))?;
+    /// store.insert(QuadRef::new(paris, rdf::TYPE, city, GraphNameRef::DefaultGraph))?;
+    /// assert!(store.contains(QuadRef::new(paris, rdf::TYPE, place, &inferred))?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn with_rdfs_inference(self, inference: RdfsInference) -> Result<Self, StorageError> {
+        self.storage.set_rdfs_inference(inference)?;
+        Ok(self)
+    }
+
+    /// Computes again all the [RDFS entailments](Self::with_rdfs_inference), replacing the content of the inference graph.
+    ///
+    /// It does nothing if the RDFS inference is not enabled.
+    ///
+    /// ```
+    /// use oxigraph::model::vocab::{rdf, rdfs};
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::{RdfsInference, Store};
+    ///
+    /// let inferred = NamedNode::new("http://example.com/inferred")?;
+    /// let store = Store::new()?.with_rdfs_inference(RdfsInference::new(inferred.clone()))?;
+    /// let city = NamedNodeRef::new("http://example.com/City")?;
+    /// let place = NamedNodeRef::new("http://example.com/Place")?;
+    /// let paris = NamedNodeRef::new("http://example.com/paris")?;
+    /// store.insert(QuadRef::new(city, rdfs::SUB_CLASS_OF, place, GraphNameRef::DefaultGraph))?;
+    /// store.insert(QuadRef::new(paris, rdf::TYPE, city, GraphNameRef::DefaultGraph))?;
+    /// assert!(!store.contains(QuadRef::new(paris, rdf::TYPE, place, &inferred))?);
+    /// store.materialize_rdfs_inference()?;
+    /// assert!(store.contains(QuadRef::new(paris, rdf::TYPE, place, &inferred))?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn materialize_rdfs_inference(&self) -> Result<(), StorageError> {
+        self.storage.materialize_rdfs_inference()
+    }
+
+    /// Materializes the [OWL 2 RL](https://www.w3.org/TR/owl2-profiles/#OWL_2_RL) entailments of the store content into a dedicated named graph.
+    ///
+    /// The OWL 2 RL rules are applied on the triples of all the graphs except the inference one until no new triple is derived.
+    /// They include the RDFS ones, `owl:sameAs` equality, inverse, symmetric, transitive, functional and equivalent properties,
+    /// property chains, equivalent classes, `owl:intersectionOf`, `owl:unionOf`, `owl:oneOf`
+    /// and the `owl:hasValue`, `owl:someValuesFrom`, `owl:allValuesFrom` and `owl:maxCardinality 1` restrictions.
+    /// The rules only deriving trivial triples like `?x owl:sameAs ?x` and the ones detecting inconsistencies are not applied.
+    /// The RDF lists used by the ontology must be asserted.
+    ///
+    /// The entailments are computed now, after each bulk load and on [`materialize_owl_rl_inference`](Self::materialize_owl_rl_inference) calls.
+    /// The whole store content is loaded in memory to compute them.
+    /// Query the union of the graphs or the inference graph to get them, like with [`with_rdfs_inference`](Self::with_rdfs_inference).
+    /// The inference is shared by all the clones of this [`Store`] and replaces the previous one. It is not persisted.
+    ///
+    /// ```
+    /// use oxigraph::io::RdfFormat;
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::{OwlRlInference, Store};
+    ///
+    /// let store = Store::new()?;
+    /// store.load_from_reader(
+    ///     RdfFormat::Turtle,
+    ///     b"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+    ///     @prefix ex: <http://example.com/> .
+    ///     ex:partOf a owl:TransitiveProperty .
+    ///     ex:paris ex:partOf ex:france .
+    ///     ex:france ex:partOf ex:europe ."
+    ///         .as_slice(),
+    /// )?;
+    /// let inferred = NamedNode::new("http://example.com/inferred")?;
+    /// let store = store.with_owl_rl_inference(OwlRlInference::new(inferred.clone()))?;
+    /// assert!(store.contains(QuadRef::new(
+    ///     NamedNodeRef::new("http://example.com/paris")?,
+    ///     NamedNodeRef::new("http://example.com/partOf")?,
+    ///     NamedNodeRef::new("http://example.com/europe")?,
+    ///     &inferred
+    /// ))?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn with_owl_rl_inference(self, inference: OwlRlInference) -> Result<Self, StorageError> {
+        self.storage.set_owl_rl_inference(inference)?;
+        Ok(self)
+    }
+
+    /// Computes again all the [OWL 2 RL entailments](Self::with_owl_rl_inference), replacing the content of the inference graph.
+    ///
+    /// It does nothing if the OWL 2 RL inference is not enabled.
+    pub fn materialize_owl_rl_inference(&self) -> Result<(), StorageError> {
+        self.storage.materialize_owl_rl_inference()
+    }
+
+    /// Returns the literals of the [full-text index](Self::with_full_text_index) containing all the words of the query.
+    ///
+    /// Each literal comes with a score between 0 and 1, the share of its words matched by the query, the best matches first.
+    /// It is empty if the store has no full-text index.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let name = NamedNodeRef::new("http://schema.org/name")?;
+    /// let store = Store::new()?.with_full_text_index([name])?;
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// store.insert(QuadRef::new(ex, name, LiteralRef::from("Lighthouse"), GraphNameRef::DefaultGraph))?;
+    /// store.insert(QuadRef::new(ex, name, LiteralRef::from("Light"), GraphNameRef::DefaultGraph))?;
+    /// assert_eq!(
+    ///     store.full_text_search("light*"),
+    ///     [(Literal::from("Light"), 1.), (Literal::from("Lighthouse"), 1.)]
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn full_text_search(&self, query: &str) -> Vec<(Literal, f64)> {
+        self.storage.full_text_search(query)
+    }
+
     /// Executes a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/).
     ///
     /// Usage example:
@@ -172,6 +685,14 @@ impl Store {
         query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
         options: QueryOptions,
     ) -> Result<QueryResults, EvaluationError> {
+        if let Some(cache) = &self.query_results_cache {
+            // The generation is read before the snapshot is taken to never cache outdated results
+            let generation = self.storage.generation();
+            return cache.get_or_evaluate(query, options, generation, |query, options| {
+                let (results, _) = self.explain_query_opt(query, options, false)?;
+                results
+            });
+        }
         let (results, _) = self.explain_query_opt(query, options, false)?;
         results
     }
@@ -284,6 +805,7 @@ impl Store {
             self.storage.snapshot(),
             query,
             options,
+            &self.query_rewriters,
             with_stats,
             substitutions,
         )
@@ -320,103 +842,383 @@ impl Store {
         let reader = self.storage.snapshot();
         QuadIter {
             iter: reader.quads_for_pattern(
-                subject.map(EncodedTerm::from).as_ref(),
-                predicate.map(EncodedTerm::from).as_ref(),
-                object.map(EncodedTerm::from).as_ref(),
-                graph_name.map(EncodedTerm::from).as_ref(),
+                subject.map(|s| reader.encode_term(s)).as_ref(),
+                predicate.map(|p| reader.encode_term(p)).as_ref(),
+                object.map(|o| reader.encode_term(o)).as_ref(),
+                graph_name.map(|g| reader.encode_term(g)).as_ref(),
             ),
             reader,
         }
     }
 
-    /// Returns all the quads contained in the store.
+    /// Retrieves the quads with the given predicate whose object is a `xsd:dateTime` literal in the given range.
+    ///
+    /// The values are compared by their position on the timeline, the ones without timezone are placed as if they were in UTC.
+    /// On-disk stores seek to the start of the range in their indexes sorted by object and stop at its end
+    /// instead of reading all the quads with the predicate,
+    /// making it suitable to read a time window of measurements stored as RDF.
     ///
     /// Usage example:
     /// ```
     /// use oxigraph::model::*;
     /// use oxigraph::store::Store;
+    /// use oxsdatatypes::DateTime;
     ///
     /// let store = Store::new()?;
+    /// let sensor = NamedNodeRef::new("http://example.com/sensor")?;
+    /// let measured_at = NamedNodeRef::new("http://example.com/measuredAt")?;
+    /// for time in ["2024-01-01T00:00:00Z", "2024-01-02T00:00:00Z", "2024-01-03T00:00:00Z"] {
+    ///     store.insert(QuadRef::new(
+    ///         sensor,
+    ///         measured_at,
+    ///         &Literal::from(time.parse::<DateTime>()?),
+    ///         GraphNameRef::DefaultGraph,
+    ///     ))?;
+    /// }
     ///
-    /// // insertion
-    /// let ex = NamedNode::new("http://example.com")?;
-    /// let quad = Quad::new(ex.clone(), ex.clone(), ex.clone(), GraphName::DefaultGraph);
-    /// store.insert(&quad)?;
-    ///
-    /// // quad filter by object
-    /// let results = store.iter().collect::<Result<Vec<_>, _>>()?;
-    /// assert_eq!(vec![quad], results);
+    /// let start = "2024-01-01T12:00:00Z".parse::<DateTime>()?;
+    /// let end = "2024-01-03T00:00:00Z".parse::<DateTime>()?;
+    /// let results = store
+    ///     .quads_for_date_time_range(measured_at, start..end, None)
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(results.len(), 1);
     /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
     /// ```
-    pub fn iter(&self) -> QuadIter {
-        self.quads_for_pattern(None, None, None, None)
+    pub fn quads_for_date_time_range(
+        &self,
+        predicate: NamedNodeRef<'_>,
+        range: impl RangeBounds<DateTime>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> QuadIter {
+        let reader = self.storage.snapshot();
+        QuadIter {
+            iter: reader.quads_for_date_time_range(
+                &reader.encode_term(predicate),
+                DateTimeRange::new(range),
+                graph_name.map(|g| reader.encode_term(g)).as_ref(),
+            ),
+            reader,
+        }
     }
 
-    /// Checks if this store contains a given quad.
+    /// Returns a fast estimation of the number of quads matching a pattern.
+    ///
+    /// The count is exact for patterns matching less than a thousand quads.
+    /// For larger patterns, it is estimated from the size of the index ranges storing the matching quads for on-disk stores
+    /// and from the number of quads added with each of the pattern terms for in-memory stores.
+    /// It is cheap even on huge stores, unlike counting the results of [`quads_for_pattern`](Self::quads_for_pattern).
     ///
     /// Usage example:
     /// ```
     /// use oxigraph::model::*;
     /// use oxigraph::store::Store;
     ///
-    /// let ex = NamedNodeRef::new("http://example.com")?;
-    /// let quad = QuadRef::new(ex, ex, ex, ex);
-    ///
     /// let store = Store::new()?;
-    /// assert!(!store.contains(quad)?);
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let name = NamedNodeRef::new("http://schema.org/name")?;
+    /// store.insert(QuadRef::new(ex, name, LiteralRef::new_simple_literal("foo"), GraphNameRef::DefaultGraph))?;
+    /// store.insert(QuadRef::new(ex, name, LiteralRef::new_simple_literal("bar"), GraphNameRef::DefaultGraph))?;
     ///
-    /// store.insert(quad)?;
-    /// assert!(store.contains(quad)?);
+    /// // Roughly how many quads use this predicate?
+    /// assert_eq!(store.estimated_quad_count(None, Some(name), None, None)?, 2);
     /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
     /// ```
-    pub fn contains<'a>(&self, quad: impl Into<QuadRef<'a>>) -> Result<bool, StorageError> {
-        let quad = EncodedQuad::from(quad.into());
-        self.storage.snapshot().contains(&quad)
+    pub fn estimated_quad_count(
+        &self,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<usize, StorageError> {
+        let reader = self.storage.snapshot();
+        reader.approximate_len_for_pattern(
+            subject.map(|s| reader.encode_term(s)).as_ref(),
+            predicate.map(|p| reader.encode_term(p)).as_ref(),
+            object.map(|o| reader.encode_term(o)).as_ref(),
+            graph_name.map(|g| reader.encode_term(g)).as_ref(),
+        )
     }
 
-    /// Returns the number of quads in the store.
-    ///
-    /// <div class="warning">This function executes a full scan.</div>
+    /// Returns all the quads contained in the store.
     ///
     /// Usage example:
     /// ```
     /// use oxigraph::model::*;
     /// use oxigraph::store::Store;
     ///
-    /// let ex = NamedNodeRef::new("http://example.com")?;
     /// let store = Store::new()?;
-    /// store.insert(QuadRef::new(ex, ex, ex, ex))?;
-    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
-    /// assert_eq!(2, store.len()?);
+    ///
+    /// // insertion
+    /// let ex = NamedNode::new("http://example.com")?;
+    /// let quad = Quad::new(ex.clone(), ex.clone(), ex.clone(), GraphName::DefaultGraph);
+    /// store.insert(&quad)?;
+    ///
+    /// // quad filter by object
+    /// let results = store.iter().collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(vec![quad], results);
     /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
     /// ```
-    pub fn len(&self) -> Result<usize, StorageError> {
-        self.storage.snapshot().len()
+    pub fn iter(&self) -> QuadIter {
+        self.quads_for_pattern(None, None, None, None)
     }
 
-    /// Returns if the store is empty.
+    /// Splits the quads matching a pattern into `shards` disjoint [`QuadShard`]s that can be scanned on separate threads.
+    ///
+    /// The split follows the graph partitioning of the store: each graph is assigned to a shard.
+    /// If there are fewer graphs than shards, the graphs are further split by subject.
+    ///
+    /// Each shard reads the store state at the moment its iteration starts.
+    /// Writes done concurrently to the scan might hence be seen by some shards only.
+    ///
+    /// At least one shard is always returned.
     ///
     /// Usage example:
     /// ```
     /// use oxigraph::model::*;
     /// use oxigraph::store::Store;
+    /// use std::thread;
     ///
     /// let store = Store::new()?;
-    /// assert!(store.is_empty()?);
-    ///
     /// let ex = NamedNodeRef::new("http://example.com")?;
-    /// store.insert(QuadRef::new(ex, ex, ex, ex))?;
-    /// assert!(!store.is_empty()?);
+    /// for i in 0..10 {
+    ///     store.insert(QuadRef::new(ex, ex, &Literal::from(i), GraphNameRef::DefaultGraph))?;
+    /// }
+    ///
+    /// // We count the quads using 4 threads
+    /// let shards = store.parallel_scan(None, None, None, None, 4)?;
+    /// let count = thread::scope(|s| {
+    ///     let handles = shards
+    ///         .into_iter()
+    ///         .map(|shard| s.spawn(move || shard.iter().count()))
+    ///         .collect::<Vec<_>>();
+    ///     handles.into_iter().map(|h| h.join().unwrap()).sum::<usize>()
+    /// });
+    /// assert_eq!(count, 10);
     /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
     /// ```
-    pub fn is_empty(&self) -> Result<bool, StorageError> {
-        self.storage.snapshot().is_empty()
-    }
-
-    /// Executes a transaction.
-    ///
-    /// Transactions ensure the "repeatable read" isolation level: the store only exposes changes that have
-    /// been "committed" (i.e. no partial writes) and the exposed state does not change for the complete duration
+    pub fn parallel_scan(
+        &self,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+        graph_name: Option<GraphNameRef<'_>>,
+        shards: usize,
+    ) -> Result<Vec<QuadShard>, StorageError> {
+        let shard_count = shards.max(1);
+        let reader = self.storage.snapshot();
+        let graph_names = if let Some(graph_name) = graph_name {
+            vec![reader.encode_term(graph_name)]
+        } else {
+            let mut graph_names = vec![EncodedTerm::DefaultGraph];
+            for graph_name in reader.named_graphs() {
+                graph_names.push(graph_name?);
+            }
+            graph_names
+        };
+        let buckets_per_graph = shard_count.div_ceil(graph_names.len());
+        let mut shards = (0..shard_count)
+            .map(|_| QuadShard {
+                storage: self.storage.clone(),
+                subject: subject.map(|s| reader.encode_term(s)),
+                predicate: predicate.map(|p| reader.encode_term(p)),
+                object: object.map(|o| reader.encode_term(o)),
+                parts: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        let parts = graph_names.into_iter().flat_map(|graph_name| {
+            (0..buckets_per_graph).map(move |bucket| QuadShardPart {
+                graph_name: graph_name.clone(),
+                bucket,
+                bucket_count: buckets_per_graph,
+            })
+        });
+        for (i, part) in parts.enumerate() {
+            shards[i % shard_count].parts.push(part);
+        }
+        Ok(shards)
+    }
+
+    /// Applies a function to all the quads of the store, or only to the quads of a given graph, and writes back the changes.
+    ///
+    /// The function returns the quad to store instead of the given one or `None` if the quad should be removed.
+    /// Returning the given quad unchanged leaves it as is.
+    /// It is useful for large cleanups like namespace migrations or datatype fixes.
+    ///
+    /// The quads are read in parallel using [`parallel_scan`](Self::parallel_scan) from a state of the store taken before any change.
+    /// The changes are written in batched transactions, so the whole operation is not atomic.
+    /// The function should leave the quads it returns unchanged if called on them,
+    /// otherwise the result might depend on the order in which the quads are processed.
+    ///
+    /// Returns the number of removed or replaced quads.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// let old = NamedNodeRef::new("http://old.example.com/s")?;
+    /// let new = NamedNodeRef::new("http://new.example.com/s")?;
+    /// store.insert(QuadRef::new(old, old, old, GraphNameRef::DefaultGraph))?;
+    ///
+    /// // We migrate the IRIs to a new namespace
+    /// let changed = store.transform_quads(None, |quad| {
+    ///     let migrate = |node: &NamedNode| {
+    ///         NamedNode::new_unchecked(
+    ///             node.as_str()
+    ///                 .replace("http://old.example.com/", "http://new.example.com/"),
+    ///         )
+    ///     };
+    ///     let NamedOrBlankNode::NamedNode(subject) = &quad.subject else {
+    ///         return Some(quad);
+    ///     };
+    ///     Some(Quad::new(
+    ///         migrate(subject),
+    ///         migrate(&quad.predicate),
+    ///         quad.object,
+    ///         quad.graph_name,
+    ///     ))
+    /// })?;
+    /// assert_eq!(changed, 1);
+    /// assert!(store.contains(QuadRef::new(new, new, old, GraphNameRef::DefaultGraph))?);
+    /// assert_eq!(store.len()?, 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn transform_quads(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        f: impl Fn(Quad) -> Option<Quad> + Sync,
+    ) -> Result<usize, StorageError> {
+        #[cfg(not(target_family = "wasm"))]
+        let thread_count = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        #[cfg(target_family = "wasm")]
+        let thread_count = 1;
+        let shards = self.parallel_scan(None, None, None, graph_name, thread_count)?;
+        // All the shards must start reading the store before any change is written
+        let barrier = Barrier::new(shards.len());
+        let transform_shard = |shard: &QuadShard| -> Result<usize, StorageError> {
+            let iter = shard.iter();
+            barrier.wait();
+            let mut changed = 0;
+            let mut batch = Vec::new();
+            for quad in iter {
+                let quad = quad?;
+                let new_quad = f(quad.clone());
+                if new_quad.as_ref() != Some(&quad) {
+                    batch.push((quad, new_quad));
+                    if batch.len() >= TRANSFORM_BATCH_SIZE {
+                        changed += self.write_transformed_quads(&mut batch)?;
+                    }
+                }
+            }
+            changed += self.write_transformed_quads(&mut batch)?;
+            Ok(changed)
+        };
+        #[cfg(not(target_family = "wasm"))]
+        {
+            thread::scope(|scope| {
+                let handles = shards
+                    .iter()
+                    .map(|shard| scope.spawn(|| transform_shard(shard)))
+                    .collect::<Vec<_>>();
+                let mut changed = 0;
+                for handle in handles {
+                    changed += handle.join().map_err(|_| {
+                        StorageError::Other("A quad transformation thread panicked".into())
+                    })??;
+                }
+                Ok(changed)
+            })
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            shards.iter().map(transform_shard).sum()
+        }
+    }
+
+    fn write_transformed_quads(
+        &self,
+        batch: &mut Vec<(Quad, Option<Quad>)>,
+    ) -> Result<usize, StorageError> {
+        self.transaction(|mut transaction| {
+            for (old_quad, new_quad) in batch.iter() {
+                transaction.remove(old_quad)?;
+                if let Some(new_quad) = new_quad {
+                    transaction.insert(new_quad)?;
+                }
+            }
+            Result::<_, StorageError>::Ok(())
+        })?;
+        let len = batch.len();
+        batch.clear();
+        Ok(len)
+    }
+
+    /// Checks if this store contains a given quad.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let quad = QuadRef::new(ex, ex, ex, ex);
+    ///
+    /// let store = Store::new()?;
+    /// assert!(!store.contains(quad)?);
+    ///
+    /// store.insert(quad)?;
+    /// assert!(store.contains(quad)?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn contains<'a>(&self, quad: impl Into<QuadRef<'a>>) -> Result<bool, StorageError> {
+        let reader = self.storage.snapshot();
+        reader.contains(&reader.encode_quad(quad.into()))
+    }
+
+    /// Returns the number of quads in the store.
+    ///
+    /// <div class="warning">This function executes a full scan.</div>
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::new()?;
+    /// store.insert(QuadRef::new(ex, ex, ex, ex))?;
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
+    /// assert_eq!(2, store.len()?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn len(&self) -> Result<usize, StorageError> {
+        self.storage.snapshot().len()
+    }
+
+    /// Returns if the store is empty.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// assert!(store.is_empty()?);
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// store.insert(QuadRef::new(ex, ex, ex, ex))?;
+    /// assert!(!store.is_empty()?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn is_empty(&self) -> Result<bool, StorageError> {
+        self.storage.snapshot().is_empty()
+    }
+
+    /// Executes a transaction.
+    ///
+    /// Transactions ensure the "repeatable read" isolation level: the store only exposes changes that have
+    /// been "committed" (i.e. no partial writes) and the exposed state does not change for the complete duration
     /// of a read operation (e.g. a SPARQL query) or a read/write operation (e.g. a SPARQL update).
     ///
     /// Usage example:
@@ -442,7 +1244,47 @@ impl Store {
         &self,
         f: impl for<'a> Fn(Transaction<'a>) -> Result<T, E>,
     ) -> Result<T, E> {
-        self.storage.transaction(|writer| f(Transaction { writer }))
+        self.storage.transaction(|writer| {
+            f(Transaction {
+                writer,
+                blank_node_scope: self.blank_node_scope,
+                query_rewriters: &self.query_rewriters,
+            })
+        })
+    }
+
+    /// Subscribes to the changes committed to the store.
+    ///
+    /// The returned channel receives, for each transaction committed after this call and changing the store content,
+    /// the list of the quads it has inserted and removed, in the order of the operations.
+    /// It is shared by all the clones of this [`Store`] and the store stops sending to it when it is dropped.
+    /// It allows to keep downstream systems like search indexes or caches in sync with the store without polling it.
+    ///
+    /// <div class="warning">The changes done by the bulk loader are not reported.</div>
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::{Store, StoreEvent};
+    ///
+    /// let store = Store::new()?;
+    /// let changes = store.watch()?;
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+    /// store.insert(quad)?;
+    /// store.remove(quad)?;
+    /// assert_eq!(
+    ///     changes.try_recv()?,
+    ///     [StoreEvent::Inserted(quad.into_owned())]
+    /// );
+    /// assert_eq!(
+    ///     changes.try_recv()?,
+    ///     [StoreEvent::Removed(quad.into_owned())]
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn watch(&self) -> Result<Receiver<Vec<StoreEvent>>, StorageError> {
+        self.storage.watch()
     }
 
     /// Executes a [SPARQL 1.1 update](https://www.w3.org/TR/sparql11-update/).
@@ -536,9 +1378,9 @@ impl Store {
         parser: impl Into<RdfParser>,
         reader: impl Read,
     ) -> Result<(), LoaderError> {
-        let quads = parser
-            .into()
-            .rename_blank_nodes()
+        let quads = self
+            .blank_node_scope
+            .configure(parser.into())
             .for_reader(reader)
             .collect::<Result<Vec<_>, _>>()?;
         self.storage.transaction(move |mut t| {
@@ -675,6 +1517,19 @@ impl Store {
         Ok(serializer.finish()?)
     }
 
+    /// Creates a [`ParallelDumper`] to dump the store using multiple threads.
+    ///
+    /// It is much faster than [`dump_to_writer`](Self::dump_to_writer) on large stores.
+    /// See [`ParallelDumper`] for an usage example.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn parallel_dumper(&self) -> ParallelDumper {
+        ParallelDumper {
+            store: self.clone(),
+            num_threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            unordered: false,
+        }
+    }
+
     /// Returns all the store named graphs.
     ///
     /// Usage example:
@@ -717,8 +1572,64 @@ impl Store {
         &self,
         graph_name: impl Into<NamedOrBlankNodeRef<'a>>,
     ) -> Result<bool, StorageError> {
-        let graph_name = EncodedTerm::from(graph_name.into());
-        self.storage.snapshot().contains_named_graph(&graph_name)
+        let reader = self.storage.snapshot();
+        reader.contains_named_graph(&reader.encode_term(graph_name.into()))
+    }
+
+    /// Returns the compact identifier of a term in the store dictionary.
+    ///
+    /// Returns `None` if the term is not known by the store.
+    /// Terms small enough to be inlined in their identifier (short strings, numbers, dates...) are always returned.
+    ///
+    /// Identifiers are derived from the term content: they are stable for the lifetime of the store
+    /// and can be used by external indexes (full-text search, vectors...) to reference store terms.
+    /// Use [`Store::term_from_id`] to get the term back.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::new()?;
+    /// assert_eq!(store.term_id(ex)?, None);
+    ///
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
+    /// let id = store.term_id(ex)?.unwrap();
+    /// assert_eq!(store.term_from_id(&id)?, Some(ex.into()));
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn term_id<'a>(
+        &self,
+        term: impl Into<TermRef<'a>>,
+    ) -> Result<Option<TermId>, StorageError> {
+        let encoded = EncodedTerm::from(term.into());
+        Ok(
+            decode_stored_term(&self.storage.snapshot(), &encoded)?.map(|_| TermId {
+                bytes: encode_term(&encoded, STR_HASH_LEN).into(),
+            }),
+        )
+    }
+
+    /// Returns the term identified by an identifier returned by [`Store::term_id`].
+    ///
+    /// Returns `None` if the term is not known by the store.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::{Store, TermId};
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::new()?;
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
+    ///
+    /// let id = TermId::from_bytes(store.term_id(ex)?.unwrap().as_bytes()).unwrap();
+    /// assert_eq!(store.term_from_id(&id)?, Some(ex.into()));
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn term_from_id(&self, id: &TermId) -> Result<Option<Term>, StorageError> {
+        decode_stored_term(&self.storage.snapshot(), &id.encoded()?)
     }
 
     /// Inserts a graph into this store.
@@ -834,6 +1745,7 @@ impl Store {
     /// Optimizes the database for future workload.
     ///
     /// Useful to call after a batch upload or another similar operation.
+    /// The expired quads are removed first (see [`Store::purge_expired`]).
     ///
     /// <div class="warning">Can take hours on huge databases.</div>
     #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
@@ -841,6 +1753,94 @@ impl Store {
         self.storage.compact()
     }
 
+    /// Removes from the storage the quads inserted with [`Transaction::insert_with_ttl`] whose time-to-live is over.
+    ///
+    /// The expired quads are already not returned by the reads, this method frees the space they use.
+    /// Returns the number of removed quads.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    /// use std::time::Duration;
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+    /// let store = Store::new()?;
+    /// store.transaction(|mut t| t.insert_with_ttl(quad, Duration::ZERO))?;
+    /// assert!(store.is_empty()?);
+    /// assert_eq!(store.purge_expired()?, 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn purge_expired(&self) -> Result<usize, StorageError> {
+        self.storage.purge_expired()
+    }
+
+    /// Returns statistics about the storage, useful for capacity planning.
+    ///
+    /// They contain the number of quads of each graph and, for on-disk stores, the disk usage of each index.
+    /// For on-disk stores, the numbers of quads are estimations computed from the index sizes
+    /// and so are cheap to get even on huge databases.
+    ///
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::new()?;
+    /// store.insert(QuadRef::new(ex, ex, ex, ex))?;
+    /// let stats = store.storage_stats()?;
+    /// assert_eq!(stats.approximate_len(), 1);
+    /// assert_eq!(
+    ///     stats.graphs(),
+    ///     [(GraphName::DefaultGraph, 0), (ex.into_owned().into(), 1)]
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn storage_stats(&self) -> Result<StorageStats, StorageError> {
+        self.storage.storage_stats()
+    }
+
+    /// Checks that all the indexes of the store contain the same quads and that all the stored terms are readable.
+    ///
+    /// A [`CorruptionError`] is returned if it is not the case.
+    /// It is useful to run after a crash or a hardware failure.
+    ///
+    /// <div class="warning">All the indexes are fully scanned so it can take hours on huge databases.</div>
+    pub fn check_integrity(&self) -> Result<(), StorageError> {
+        self.storage.snapshot().validate()
+    }
+
+    /// Computes statistics about the store content and uses them to optimize the next SPARQL queries and updates.
+    ///
+    /// The statistics contain the number of quads and, for each predicate, its number of quads and estimations of its number of distinct subjects and objects.
+    /// The query optimizer uses them to choose the join order and the join algorithms instead of its default heuristics.
+    /// They are not updated when the store content changes: this method should be called again after large changes, for example after a bulk load.
+    /// With an on-disk store, they are persisted and loaded again when the store is opened.
+    ///
+    /// <div class="warning">It scans the full store content.</div>
+    ///
+    /// ```
+    /// use oxigraph::io::RdfFormat;
+    /// use oxigraph::store::Store;
+    ///
+    /// let store = Store::new()?;
+    /// store.load_from_reader(
+    ///     RdfFormat::NTriples,
+    ///     b"<http://example.com/s> <http://example.com/p> <http://example.com/o> .".as_slice(),
+    /// )?;
+    /// store.update_statistics()?;
+    /// let (_, explanation) = store.explain_query_opt(
+    ///     "SELECT * WHERE { ?s <http://example.com/p> ?o }",
+    ///     Default::default(),
+    ///     false,
+    /// )?;
+    /// assert_eq!(explanation.plan().estimated_result_count(), 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn update_statistics(&self) -> Result<(), StorageError> {
+        self.storage.update_statistics()
+    }
+
     /// Creates database backup into the `target_directory`.
     ///
     /// After its creation, the backup is usable using [`Store::open`]
@@ -864,6 +1864,83 @@ impl Store {
         self.storage.backup(target_directory.as_ref())
     }
 
+    /// Adds a new backup of the database to a [`BackupRepository`].
+    ///
+    /// Only the database files that are not already in the repository are copied,
+    /// making regular backups of large databases cheap.
+    /// The repository keeps all backup generations: any of them can be verified using [`BackupRepository::verify`]
+    /// and restored into a new directory using [`BackupRepository::restore`].
+    ///
+    /// <div class="warning">
+    ///
+    /// Backups are only possible for on-disk databases created using [`Store::open`].
+    /// Encrypted databases are not supported, use [`Store::backup`] instead.</div>
+    ///
+    /// Usage example:
+    /// ```no_run
+    /// use oxigraph::store::{BackupRepository, Store};
+    ///
+    /// let store = Store::open("data")?;
+    /// let repository = BackupRepository::open("backups")?;
+    /// let backup = store.incremental_backup(&repository)?;
+    /// repository.verify(backup.id())?;
+    /// repository.purge_old_backups(7)?;
+    ///
+    /// // Later, restores the database as it was when the backup was made
+    /// repository.restore(backup.id(), "restored")?;
+    /// let restored = Store::open("restored")?;
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn incremental_backup(
+        &self,
+        repository: &BackupRepository,
+    ) -> Result<BackupInfo, StorageError> {
+        self.storage.incremental_backup(repository)
+    }
+
+    /// Creates a copy of the database into the `target_directory` and opens it as a new writable [`Store`].
+    ///
+    /// The fork starts with the current content of the database and then evolves independently from it:
+    /// changes written to one store are not visible from the other one.
+    ///
+    /// It is built on top of [`Store::backup`]: if the target directory is in the same file system as the current database,
+    /// the immutable database files are shared using hard links and forking is cheap even for huge databases.
+    ///
+    /// <div class="warning">
+    ///
+    /// Forks are only possible for on-disk databases created using [`Store::open`].</div>
+    ///
+    /// <div class="warning">An error is raised if the `target_directory` already exists.</div>
+    ///
+    /// Usage example:
+    /// ```no_run
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::open("data")?;
+    /// let fork = store.fork("experiment")?;
+    /// fork.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
+    /// assert!(!store.contains(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn fork(&self, target_directory: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let target_directory = target_directory.as_ref();
+        self.backup(target_directory)?;
+        Self::open(target_directory)
+    }
+
+    /// Makes a secondary [`Store`] opened with [`Store::open_secondary`] see the changes written to its primary since the last call.
+    ///
+    /// It replays the primary write-ahead log and is cheap if there are only a few new changes.
+    /// An error is raised if the store is not a secondary.
+    #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+    pub fn try_catch_up_with_primary(&self) -> Result<(), StorageError> {
+        self.storage.try_catch_up_with_primary()
+    }
+
     /// Creates a bulk loader allowing to load at lot of data quickly into the store.
     ///
     /// Usage example:
@@ -890,13 +1967,19 @@ impl Store {
         BulkLoader {
             storage: self.storage.bulk_loader(),
             on_parse_error: None,
+            blank_node_scope: self.blank_node_scope,
+            reproducible_seed: None,
+            load_count: Cell::new(0),
+            language_detector: None,
+            on_language_tag_mismatch: None,
+            fix_language_tags: false,
         }
     }
 
     /// Validates that all the store invariants held in the data
     #[doc(hidden)]
     pub fn validate(&self) -> Result<(), StorageError> {
-        self.storage.snapshot().validate()
+        self.check_integrity()
     }
 }
 
@@ -919,11 +2002,39 @@ impl IntoIterator for &Store {
     }
 }
 
+/// How the blank nodes of the loaded files are identified in a [`Store`].
+///
+/// See [`Store::with_blank_node_scope`].
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+#[non_exhaustive]
+pub enum BlankNodeScope {
+    /// Each loaded file gets its own fresh blank nodes (the default).
+    ///
+    /// Loading twice the same file with blank nodes duplicates the triples using them.
+    #[default]
+    Document,
+    /// The blank node labels of the loaded files are kept and shared by all the loads.
+    ///
+    /// Blank nodes with the same label in different files or in different loads of the same file are the same blank node.
+    Store,
+}
+
+impl BlankNodeScope {
+    fn configure(self, parser: RdfParser) -> RdfParser {
+        match self {
+            Self::Document => parser.rename_blank_nodes(),
+            Self::Store => parser,
+        }
+    }
+}
+
 /// An object to do operations during a transaction.
 ///
 /// See [`Store::transaction`] for a more detailed description.
 pub struct Transaction<'a> {
     writer: StorageWriter<'a>,
+    blank_node_scope: BlankNodeScope,
+    query_rewriters: &'a [Arc<dyn QueryRewriter>],
 }
 
 impl Transaction<'_> {
@@ -1002,7 +2113,14 @@ impl Transaction<'_> {
         query: impl TryInto<Query, Error = impl Into<EvaluationError>>,
         options: QueryOptions,
     ) -> Result<QueryResults, EvaluationError> {
-        let (results, _) = evaluate_query(self.writer.reader(), query, options, false, [])?;
+        let (results, _) = evaluate_query(
+            self.writer.reader(),
+            query,
+            options,
+            self.query_rewriters,
+            false,
+            [],
+        )?;
         results
     }
 
@@ -1037,10 +2155,10 @@ impl Transaction<'_> {
         let reader = self.writer.reader();
         QuadIter {
             iter: reader.quads_for_pattern(
-                subject.map(EncodedTerm::from).as_ref(),
-                predicate.map(EncodedTerm::from).as_ref(),
-                object.map(EncodedTerm::from).as_ref(),
-                graph_name.map(EncodedTerm::from).as_ref(),
+                subject.map(|s| reader.encode_term(s)).as_ref(),
+                predicate.map(|p| reader.encode_term(p)).as_ref(),
+                object.map(|o| reader.encode_term(o)).as_ref(),
+                graph_name.map(|g| reader.encode_term(g)).as_ref(),
             ),
             reader,
         }
@@ -1053,8 +2171,8 @@ impl Transaction<'_> {
 
     /// Checks if this store contains a given quad.
     pub fn contains<'b>(&self, quad: impl Into<QuadRef<'b>>) -> Result<bool, StorageError> {
-        let quad = EncodedQuad::from(quad.into());
-        self.writer.reader().contains(&quad)
+        let reader = self.writer.reader();
+        reader.contains(&reader.encode_quad(quad.into()))
     }
 
     /// Returns the number of quads in the store.
@@ -1152,7 +2270,11 @@ impl Transaction<'_> {
         parser: impl Into<RdfParser>,
         reader: impl Read,
     ) -> Result<(), LoaderError> {
-        for quad in parser.into().rename_blank_nodes().for_reader(reader) {
+        for quad in self
+            .blank_node_scope
+            .configure(parser.into())
+            .for_reader(reader)
+        {
             self.insert(quad?.as_ref())?;
         }
         Ok(())
@@ -1179,9 +2301,42 @@ impl Transaction<'_> {
         self.writer.insert(quad.into())
     }
 
-    /// Adds a set of quads to this store.
-    pub fn extend<'b>(
-        &mut self,
+    /// Adds a quad to this store that expires after the time-to-live `ttl`.
+    ///
+    /// Once expired, the quad is not returned by the reads anymore.
+    /// It is physically removed by [`Store::purge_expired`] or [`Store::optimize`].
+    /// If the quad is already in the store, its expiration time is replaced.
+    /// Inserting it again with [`Transaction::insert`] makes it permanent.
+    ///
+    /// Returns `true` if the quad was not already in the store.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    /// use std::time::Duration;
+    ///
+    /// let ex = NamedNodeRef::new_unchecked("http://example.com");
+    /// let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+    ///
+    /// let store = Store::new()?;
+    /// store.transaction(|mut transaction| {
+    ///     transaction.insert_with_ttl(quad, Duration::from_secs(3600))
+    /// })?;
+    /// assert!(store.contains(quad)?);
+    /// # Result::<_,oxigraph::store::StorageError>::Ok(())
+    /// ```
+    pub fn insert_with_ttl<'b>(
+        &mut self,
+        quad: impl Into<QuadRef<'b>>,
+        ttl: Duration,
+    ) -> Result<bool, StorageError> {
+        self.writer.insert_with_ttl(quad.into(), ttl)
+    }
+
+    /// Adds a set of quads to this store.
+    pub fn extend<'b>(
+        &mut self,
         quads: impl IntoIterator<Item = impl Into<QuadRef<'b>>>,
     ) -> Result<(), StorageError> {
         for quad in quads {
@@ -1213,6 +2368,36 @@ impl Transaction<'_> {
         self.writer.remove(quad.into())
     }
 
+    /// Applies the changes of a [`DatasetDiff`] to this store, the deletions first.
+    ///
+    /// Blank nodes are matched using their identifiers.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let ex = NamedNodeRef::new_unchecked("http://example.com");
+    /// let old = QuadRef::new(ex, ex, LiteralRef::from("old"), GraphNameRef::DefaultGraph);
+    /// let new = QuadRef::new(ex, ex, LiteralRef::from("new"), GraphNameRef::DefaultGraph);
+    /// let diff = Dataset::from_iter([old]).diff(&Dataset::from_iter([new]));
+    ///
+    /// let store = Store::new()?;
+    /// store.insert(old)?;
+    /// store.transaction(|mut transaction| transaction.apply_diff(&diff))?;
+    /// assert_eq!(store.iter().collect::<Result<Vec<_>, _>>()?, [new.into_owned()]);
+    /// # Result::<_,oxigraph::store::StorageError>::Ok(())
+    /// ```
+    pub fn apply_diff(&mut self, diff: &DatasetDiff) -> Result<(), StorageError> {
+        for quad in diff.deletions() {
+            self.writer.remove(quad.as_ref())?;
+        }
+        for quad in diff.insertions() {
+            self.writer.insert(quad.as_ref())?;
+        }
+        Ok(())
+    }
+
     /// Returns all the store named graphs.
     pub fn named_graphs(&self) -> GraphNameIter {
         let reader = self.writer.reader();
@@ -1227,9 +2412,8 @@ impl Transaction<'_> {
         &self,
         graph_name: impl Into<NamedOrBlankNodeRef<'b>>,
     ) -> Result<bool, StorageError> {
-        self.writer
-            .reader()
-            .contains_named_graph(&EncodedTerm::from(graph_name.into()))
+        let reader = self.writer.reader();
+        reader.contains_named_graph(&reader.encode_term(graph_name.into()))
     }
 
     /// Inserts a graph into this store.
@@ -1328,6 +2512,43 @@ impl Transaction<'_> {
     pub fn clear(&mut self) -> Result<(), StorageError> {
         self.writer.clear()
     }
+
+    /// Marks the current state of the transaction to be able to undo the following writes with [`rollback_to`](Self::rollback_to).
+    ///
+    /// Savepoints allow to try a part of the transaction and to undo only this part if it fails.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    ///
+    /// let a = NamedNodeRef::new_unchecked("http://example.com/a");
+    /// let b = NamedNodeRef::new_unchecked("http://example.com/b");
+    /// let store = Store::new()?;
+    /// store.transaction(|mut transaction| {
+    ///     transaction.insert(QuadRef::new(a, a, a, GraphNameRef::DefaultGraph))?;
+    ///     let savepoint = transaction.savepoint();
+    ///     transaction.insert(QuadRef::new(b, b, b, GraphNameRef::DefaultGraph))?;
+    ///     transaction.rollback_to(&savepoint)
+    /// })?;
+    /// assert!(store.contains(QuadRef::new(a, a, a, GraphNameRef::DefaultGraph))?);
+    /// assert!(!store.contains(QuadRef::new(b, b, b, GraphNameRef::DefaultGraph))?);
+    /// # Result::<_,oxigraph::store::StorageError>::Ok(())
+    /// ```
+    pub fn savepoint(&mut self) -> Savepoint {
+        Savepoint {
+            id: self.writer.savepoint(),
+        }
+    }
+
+    /// Undoes all the writes done since the [`savepoint`](Self::savepoint) has been created.
+    ///
+    /// The savepoint can be rolled back to again later.
+    /// The savepoints created after it are discarded: rolling back to them returns an error.
+    /// Rolling back to a savepoint of another transaction also returns an error.
+    pub fn rollback_to(&mut self, savepoint: &Savepoint) -> Result<(), StorageError> {
+        self.writer.rollback_to_savepoint(savepoint.id)
+    }
 }
 
 impl IntoIterator for &Transaction<'_> {
@@ -1340,6 +2561,79 @@ impl IntoIterator for &Transaction<'_> {
     }
 }
 
+/// A state of a [`Transaction`] to roll back to.
+///
+/// See [`Transaction::savepoint`].
+#[derive(Debug)]
+#[must_use]
+pub struct Savepoint {
+    id: u64,
+}
+
+/// A compact identifier of a term in a [`Store`].
+///
+/// It is a short byte sequence (at most 33 bytes) built with [`Store::term_id`].
+/// It is derived from the term content and does not change for the lifetime of the store.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Clone)]
+pub struct TermId {
+    bytes: Box<[u8]>,
+}
+
+impl TermId {
+    /// Builds an identifier from its byte representation returned by [`TermId::as_bytes`].
+    ///
+    /// Returns `None` if the bytes are not a valid identifier.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut buffer = bytes;
+        buffer.read_term(STR_HASH_LEN).ok()?;
+        buffer.is_empty().then(|| Self {
+            bytes: bytes.into(),
+        })
+    }
+
+    /// The byte representation of the identifier.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn encoded(&self) -> Result<EncodedTerm, StorageError> {
+        let mut buffer = self.bytes.as_ref();
+        buffer.read_term(STR_HASH_LEN)
+    }
+}
+
+/// Decodes a term, returns `None` if some of its strings are not in the store
+fn decode_stored_term(
+    reader: &StorageReader,
+    encoded: &EncodedTerm,
+) -> Result<Option<Term>, StorageError> {
+    struct MissingStrLookup<'a> {
+        reader: &'a StorageReader,
+        missing: Cell<bool>,
+    }
+
+    impl StrLookup for MissingStrLookup<'_> {
+        fn get_str(&self, key: StrHash) -> Result<Option<String>, StorageError> {
+            let value = self.reader.get_str(key)?;
+            if value.is_none() {
+                self.missing.set(true);
+            }
+            Ok(value)
+        }
+    }
+
+    let lookup = MissingStrLookup {
+        reader,
+        missing: Cell::new(false),
+    };
+    match lookup.decode_term(encoded) {
+        Ok(term) => Ok(Some(term)),
+        Err(_) if lookup.missing.get() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
 /// An iterator returning the quads contained in a [`Store`].
 pub struct QuadIter {
     iter: DecodingQuadIterator,
@@ -1357,6 +2651,93 @@ impl Iterator for QuadIter {
     }
 }
 
+/// A part of the quads of a [`Store`] returned by [`Store::parallel_scan`].
+///
+/// It can be sent to another thread to be scanned there with [`iter`](Self::iter).
+pub struct QuadShard {
+    storage: Storage,
+    subject: Option<EncodedTerm>,
+    predicate: Option<EncodedTerm>,
+    object: Option<EncodedTerm>,
+    parts: Vec<QuadShardPart>,
+}
+
+/// The quads of a graph whose subject hash falls into a given bucket
+#[derive(Clone)]
+struct QuadShardPart {
+    graph_name: EncodedTerm,
+    bucket: usize,
+    bucket_count: usize,
+}
+
+impl QuadShard {
+    /// Returns the quads of this shard.
+    pub fn iter(&self) -> QuadShardIter {
+        QuadShardIter {
+            reader: self.storage.snapshot(),
+            subject: self.subject.clone(),
+            predicate: self.predicate.clone(),
+            object: self.object.clone(),
+            parts: self.parts.clone().into_iter(),
+            current: None,
+        }
+    }
+}
+
+impl IntoIterator for &QuadShard {
+    type Item = Result<Quad, StorageError>;
+    type IntoIter = QuadShardIter;
+
+    fn into_iter(self) -> QuadShardIter {
+        self.iter()
+    }
+}
+
+/// An iterator returning the quads of a [`QuadShard`].
+pub struct QuadShardIter {
+    reader: StorageReader,
+    subject: Option<EncodedTerm>,
+    predicate: Option<EncodedTerm>,
+    object: Option<EncodedTerm>,
+    parts: std::vec::IntoIter<QuadShardPart>,
+    current: Option<(DecodingQuadIterator, QuadShardPart)>,
+}
+
+impl Iterator for QuadShardIter {
+    type Item = Result<Quad, StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((iter, part)) = &mut self.current {
+                for quad in iter.by_ref() {
+                    let quad = match quad {
+                        Ok(quad) => quad,
+                        Err(error) => return Some(Err(error)),
+                    };
+                    if part.bucket_count > 1 {
+                        let mut hasher = DefaultHasher::new();
+                        quad.subject.hash(&mut hasher);
+                        if hasher.finish() % (part.bucket_count as u64) != part.bucket as u64 {
+                            continue;
+                        }
+                    }
+                    return Some(self.reader.decode_quad(&quad));
+                }
+            }
+            let part = self.parts.next()?;
+            self.current = Some((
+                self.reader.quads_for_pattern(
+                    self.subject.as_ref(),
+                    self.predicate.as_ref(),
+                    self.object.as_ref(),
+                    Some(&part.graph_name),
+                ),
+                part,
+            ));
+        }
+    }
+}
+
 /// An iterator returning the graph names contained in a [`Store`].
 pub struct GraphNameIter {
     iter: DecodingGraphIterator,
@@ -1379,6 +2760,300 @@ impl Iterator for GraphNameIter {
     }
 }
 
+/// A dumper writing the content of a [`Store`] using multiple threads.
+///
+/// The quads are read in parallel using [`Store::parallel_scan`] from a state of the store taken when the dump starts.
+///
+/// By default the output is ordered: the quads are sent to a single serializer in the same order for the same store content.
+/// The output order is not the one of [`Store::dump_to_writer`] but the quads of each part of the store are kept together,
+/// so any format can be used.
+/// With [`unordered`](Self::unordered), each thread serializes its quads itself and the chunks are written as soon as they are ready.
+/// It is only possible with line-based formats like [N-Triples](RdfFormat::NTriples) or [N-Quads](RdfFormat::NQuads).
+///
+/// Usage example:
+/// ```
+/// use oxigraph::io::RdfFormat;
+/// use oxigraph::model::*;
+/// use oxigraph::store::Store;
+///
+/// let store = Store::new()?;
+/// let ex = NamedNodeRef::new("http://example.com")?;
+/// store.insert(QuadRef::new(ex, ex, ex, ex))?;
+///
+/// let buffer = store
+///     .parallel_dumper()
+///     .with_num_threads(4)
+///     .unordered()
+///     .dump_to_writer(RdfFormat::NQuads, Vec::new())?;
+/// assert_eq!(
+///     buffer,
+///     b"<http://example.com> <http://example.com> <http://example.com> <http://example.com> .\n"
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[cfg(not(target_family = "wasm"))]
+#[must_use]
+pub struct ParallelDumper {
+    store: Store,
+    num_threads: usize,
+    unordered: bool,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl ParallelDumper {
+    /// Sets the number of threads reading the store.
+    ///
+    /// The default value is the available parallelism of the system.
+    pub fn with_num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads.max(1);
+        self
+    }
+
+    /// Writes the serialized quads as soon as they are ready, in no particular order.
+    ///
+    /// Only line-based formats like [N-Triples](RdfFormat::NTriples) or [N-Quads](RdfFormat::NQuads) are supported in this mode.
+    pub fn unordered(mut self) -> Self {
+        self.unordered = true;
+        self
+    }
+
+    /// Dumps the store into a file like [`Store::dump_to_writer`].
+    pub fn dump_to_writer<W: Write>(
+        &self,
+        serializer: impl Into<RdfSerializer>,
+        writer: W,
+    ) -> Result<W, SerializerError> {
+        let serializer = serializer.into();
+        if !serializer.format().supports_datasets() {
+            return Err(SerializerError::DatasetFormatExpected(serializer.format()));
+        }
+        self.dump(None, serializer, writer)
+    }
+
+    /// Dumps a store graph into a file like [`Store::dump_graph_to_writer`].
+    pub fn dump_graph_to_writer<'a, W: Write>(
+        &self,
+        from_graph_name: impl Into<GraphNameRef<'a>>,
+        serializer: impl Into<RdfSerializer>,
+        writer: W,
+    ) -> Result<W, SerializerError> {
+        self.dump(Some(from_graph_name.into()), serializer.into(), writer)
+    }
+
+    /// Dumps each graph of the store into its own file, the graphs being dumped in parallel.
+    ///
+    /// `make_writer` is called with the graph name to build the writer the graph is dumped into.
+    /// The default graph is only dumped if it is not empty.
+    /// Each graph is read from the state of the store at the moment its dump starts.
+    ///
+    /// Usage example:
+    /// ```
+    /// use oxigraph::io::RdfFormat;
+    /// use oxigraph::model::*;
+    /// use oxigraph::store::Store;
+    /// use std::collections::HashMap;
+    /// use std::sync::Mutex;
+    ///
+    /// let store = Store::new()?;
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// store.insert(QuadRef::new(ex, ex, ex, ex))?;
+    ///
+    /// let files = Mutex::new(HashMap::new());
+    /// store.parallel_dumper().dump_graphs(RdfFormat::NTriples, |graph_name| {
+    ///     Ok(Writer(graph_name.into_owned(), Vec::new(), &files))
+    /// })?;
+    /// assert_eq!(
+    ///     files.into_inner().unwrap(),
+    ///     HashMap::from([(
+    ///         ex.into(),
+    ///         b"<http://example.com> <http://example.com> <http://example.com> .\n".to_vec()
+    ///     )])
+    /// );
+    ///
+    /// // A writer saving its content into the map on flush
+    /// struct Writer<'a>(GraphName, Vec<u8>, &'a Mutex<HashMap<GraphName, Vec<u8>>>);
+    ///
+    /// impl std::io::Write for Writer<'_> {
+    ///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    ///         self.1.write(buf)
+    ///     }
+    ///
+    ///     fn flush(&mut self) -> std::io::Result<()> {
+    ///         self.2.lock().unwrap().insert(self.0.clone(), self.1.clone());
+    ///         Ok(())
+    ///     }
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn dump_graphs<W: Write>(
+        &self,
+        serializer: impl Into<RdfSerializer>,
+        make_writer: impl Fn(GraphNameRef<'_>) -> io::Result<W> + Sync,
+    ) -> Result<(), SerializerError> {
+        let serializer = serializer.into();
+        let mut graph_names = Vec::new();
+        if self
+            .store
+            .quads_for_pattern(None, None, None, Some(GraphNameRef::DefaultGraph))
+            .next()
+            .is_some()
+        {
+            graph_names.push(GraphName::DefaultGraph);
+        }
+        for graph_name in self.store.named_graphs() {
+            graph_names.push(graph_name?.into());
+        }
+        let graph_names = Mutex::new(graph_names.into_iter());
+        let dump_graphs = || -> Result<(), SerializerError> {
+            loop {
+                let Some(graph_name) = graph_names
+                    .lock()
+                    .map_err(|_| StorageError::Other("The graph queue lock is poisoned".into()))?
+                    .next()
+                else {
+                    return Ok(());
+                };
+                let writer = make_writer(graph_name.as_ref())?;
+                self.store
+                    .dump_graph_to_writer(&graph_name, serializer.clone(), writer)?
+                    .flush()?;
+            }
+        };
+        thread::scope(|scope| {
+            let handles = (0..self.num_threads)
+                .map(|_| scope.spawn(dump_graphs))
+                .collect::<Vec<_>>();
+            for handle in handles {
+                handle
+                    .join()
+                    .map_err(|_| StorageError::Other("A graph dump thread panicked".into()))??;
+            }
+            Ok(())
+        })
+    }
+
+    fn dump<W: Write>(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        serializer: RdfSerializer,
+        mut writer: W,
+    ) -> Result<W, SerializerError> {
+        let shards = self
+            .store
+            .parallel_scan(None, None, None, graph_name, self.num_threads)?;
+        // All the shards must read the same state of the store
+        let barrier = &Barrier::new(shards.len());
+        let as_triples = graph_name.is_some();
+        if self.unordered {
+            if !matches!(serializer.format(), RdfFormat::NTriples | RdfFormat::NQuads) {
+                return Err(SerializerError::LineBasedFormatExpected(
+                    serializer.format(),
+                ));
+            }
+            thread::scope(|scope| {
+                let (sender, receiver) = sync_channel(shards.len() * DUMP_CHANNEL_CAPACITY);
+                let handles = shards
+                    .iter()
+                    .map(|shard| {
+                        let sender = sender.clone();
+                        let serializer = serializer.clone();
+                        scope.spawn(move || -> Result<(), SerializerError> {
+                            let iter = shard.iter();
+                            barrier.wait();
+                            let mut chunk = serializer.clone().for_writer(Vec::new());
+                            let mut chunk_len = 0;
+                            for quad in iter {
+                                serialize_dumped_quad(&mut chunk, &quad?, as_triples)?;
+                                chunk_len += 1;
+                                if chunk_len >= DUMP_BATCH_SIZE {
+                                    let full_chunk = replace(
+                                        &mut chunk,
+                                        serializer.clone().for_writer(Vec::new()),
+                                    );
+                                    chunk_len = 0;
+                                    sender
+                                        .send(full_chunk.finish()?)
+                                        .map_err(|_| dump_writer_stopped())?;
+                                }
+                            }
+                            sender
+                                .send(chunk.finish()?)
+                                .map_err(|_| dump_writer_stopped())?;
+                            Ok(())
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                drop(sender);
+                for chunk in receiver {
+                    writer.write_all(&chunk)?;
+                }
+                for handle in handles {
+                    handle
+                        .join()
+                        .map_err(|_| StorageError::Other("A dump thread panicked".into()))??;
+                }
+                Ok(writer)
+            })
+        } else {
+            thread::scope(|scope| {
+                let shards = shards
+                    .iter()
+                    .map(|shard| {
+                        let (sender, receiver) = sync_channel(DUMP_CHANNEL_CAPACITY);
+                        let handle = scope.spawn(move || -> Result<(), StorageError> {
+                            let iter = shard.iter();
+                            barrier.wait();
+                            let mut batch = Vec::with_capacity(DUMP_BATCH_SIZE);
+                            for quad in iter {
+                                batch.push(quad?);
+                                if batch.len() >= DUMP_BATCH_SIZE {
+                                    sender
+                                        .send(take(&mut batch))
+                                        .map_err(|_| dump_writer_stopped())?;
+                                }
+                            }
+                            sender.send(batch).map_err(|_| dump_writer_stopped())?;
+                            Ok(())
+                        });
+                        (receiver, handle)
+                    })
+                    .collect::<Vec<_>>();
+                let mut serializer = serializer.for_writer(writer);
+                for (receiver, handle) in shards {
+                    for batch in receiver {
+                        for quad in &batch {
+                            serialize_dumped_quad(&mut serializer, quad, as_triples)?;
+                        }
+                    }
+                    handle
+                        .join()
+                        .map_err(|_| StorageError::Other("A dump thread panicked".into()))??;
+                }
+                Ok(serializer.finish()?)
+            })
+        }
+    }
+}
+
+/// The error of a [`ParallelDumper`] thread when the writer has stopped, because of an error it has already returned
+#[cfg(not(target_family = "wasm"))]
+fn dump_writer_stopped() -> StorageError {
+    StorageError::Other("The dump writer has stopped".into())
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn serialize_dumped_quad<W: Write>(
+    serializer: &mut WriterQuadSerializer<W>,
+    quad: &Quad,
+    as_triple: bool,
+) -> io::Result<()> {
+    if as_triple {
+        serializer.serialize_triple(quad.as_ref())
+    } else {
+        serializer.serialize_quad(quad)
+    }
+}
+
 /// A bulk loader allowing to load at lot of data quickly into the store.
 ///
 /// <div class="warning">The operations provided here are not atomic.
@@ -1415,6 +3090,12 @@ impl Iterator for GraphNameIter {
 pub struct BulkLoader {
     storage: StorageBulkLoader,
     on_parse_error: Option<Box<dyn Fn(RdfParseError) -> Result<(), RdfParseError>>>,
+    blank_node_scope: BlankNodeScope,
+    reproducible_seed: Option<u64>,
+    load_count: Cell<u64>,
+    language_detector: Option<Box<dyn LanguageDetector>>,
+    on_language_tag_mismatch: Option<Box<dyn Fn(LanguageTagMismatch)>>,
+    fix_language_tags: bool,
 }
 
 impl BulkLoader {
@@ -1446,10 +3127,54 @@ impl BulkLoader {
 
     /// Adds a `callback` evaluated from time to time with the number of loaded triples.
     pub fn on_progress(mut self, callback: impl Fn(u64) + 'static) -> Self {
+        self.storage = self
+            .storage
+            .on_progress(move |progress| callback(progress.loaded_quads()));
+        self
+    }
+
+    /// Adds a `callback` evaluated from time to time with the number of loaded quads,
+    /// the loading speed and the number of bytes written to the disk.
+    ///
+    /// ```
+    /// use oxigraph::io::RdfFormat;
+    /// use oxigraph::store::Store;
+    ///
+    /// let file = b"<http://example.com> <http://example.com> <http://example.com> .";
+    /// let store = Store::new()?;
+    /// store
+    ///     .bulk_loader()
+    ///     .on_detailed_progress(|progress| {
+    ///         println!(
+    ///             "{} quads loaded ({} quads/s), {} bytes written",
+    ///             progress.loaded_quads(),
+    ///             progress.quads_per_second().round(),
+    ///             progress.written_bytes()
+    ///         )
+    ///     })
+    ///     .load_from_reader(RdfFormat::NTriples, file.as_ref())?;
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn on_detailed_progress(mut self, callback: impl Fn(BulkLoadProgress) + 'static) -> Self {
         self.storage = self.storage.on_progress(callback);
         self
     }
 
+    /// Sorts the loaded data on disk in `directory` instead of ingesting each batch of quads into the store.
+    ///
+    /// The sorted batches are spilled to temporary files in a new subdirectory of `directory`,
+    /// then merged in parallel into a single storage file per index that is ingested at the end of the load.
+    /// This keeps the memory usage bounded by [`with_max_memory_size_in_megabytes`](Self::with_max_memory_size_in_megabytes)
+    /// and avoids the costly compaction of many overlapping storage files when loading datasets larger than the RAM.
+    /// The data is only visible in the store when the load operation has succeeded.
+    ///
+    /// `directory` should have enough free space to store about twice the size of the loaded data.
+    /// It has no effect on in-memory stores.
+    pub fn with_external_sort(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.storage = self.storage.with_external_sort_directory(directory.into());
+        self
+    }
+
     /// Adds a `callback` catching all parse errors and choosing if the parsing should continue
     /// by returning `Ok` or fail by returning `Err`.
     ///
@@ -1462,6 +3187,78 @@ impl BulkLoader {
         self
     }
 
+    /// Makes the bulk loader output reproducible: loading the same files in the same order
+    /// with the same `seed` and options into a new store writes the same storage files.
+    ///
+    /// This is useful to build container images with pre-loaded datasets. In this mode:
+    /// * the blank nodes of the files loaded with [`load_from_reader`](Self::load_from_reader) get identifiers
+    ///   derived from `seed` and from their order of appearance instead of random ones.
+    ///   Use a different `seed` for each bulk loader loading into the same store to keep the blank nodes of their files distinct.
+    /// * the batches of quads are written to the storage one after the other in the input order
+    ///   (parsing is still done in parallel of the writes).
+    /// * the storage files do not contain the host name.
+    ///
+    /// With [`BlankNodeScope::Store`] the blank node labels are kept as they are,
+    /// so the anonymous blank nodes like Turtle `[]` still get random identifiers.
+    /// RocksDB also writes a random session identifier in the properties of each storage file.
+    ///
+    /// ```
+    /// use oxigraph::io::RdfFormat;
+    /// use oxigraph::store::Store;
+    ///
+    /// let file = b"<http://example.com/s> <http://example.com/p> [ <http://example.com/p> _:b ] .";
+    /// let store1 = Store::new()?;
+    /// store1
+    ///     .bulk_loader()
+    ///     .with_reproducible_output(42)
+    ///     .load_from_reader(RdfFormat::Turtle, file.as_ref())?;
+    /// let store2 = Store::new()?;
+    /// store2
+    ///     .bulk_loader()
+    ///     .with_reproducible_output(42)
+    ///     .load_from_reader(RdfFormat::Turtle, file.as_ref())?;
+    /// assert_eq!(
+    ///     store1.iter().collect::<Result<Vec<_>, _>>()?,
+    ///     store2.iter().collect::<Result<Vec<_>, _>>()?
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn with_reproducible_output(mut self, seed: u64) -> Self {
+        self.storage = self.storage.reproducible();
+        self.reproducible_seed = Some(seed);
+        self
+    }
+
+    /// Verifies the language tags of the loaded literals against the language `detector` finds in their text.
+    ///
+    /// The mismatches are reported to the [`on_language_tag_mismatch`](Self::on_language_tag_mismatch) callback
+    /// and fixed if [`fix_language_tags`](Self::fix_language_tags) is set.
+    /// See [`LanguageDetector`] for an example.
+    pub fn with_language_detector(mut self, detector: impl LanguageDetector + 'static) -> Self {
+        self.language_detector = Some(Box::new(detector));
+        self
+    }
+
+    /// Adds a `callback` evaluated on each literal whose language tag does not match the language detected from its text.
+    ///
+    /// It requires a detector set with [`with_language_detector`](Self::with_language_detector).
+    pub fn on_language_tag_mismatch(
+        mut self,
+        callback: impl Fn(LanguageTagMismatch) + 'static,
+    ) -> Self {
+        self.on_language_tag_mismatch = Some(Box::new(callback));
+        self
+    }
+
+    /// Replaces the language tags that do not match the language detected from the literal text by the detected language.
+    ///
+    /// It requires a detector set with [`with_language_detector`](Self::with_language_detector).
+    /// By default, the literals are loaded as they are.
+    pub fn fix_language_tags(mut self) -> Self {
+        self.fix_language_tags = true;
+        self
+    }
+
     /// Loads a file using the bulk loader.
     ///
     /// This function is optimized for large dataset loading speed. For small files, [`Store::load_from_reader`] might be more convenient.
@@ -1510,26 +3307,37 @@ impl BulkLoader {
         parser: impl Into<RdfParser>,
         reader: impl Read,
     ) -> Result<(), LoaderError> {
-        self.load_ok_quads(
-            parser
-                .into()
-                .rename_blank_nodes()
-                .for_reader(reader)
-                .filter_map(|r| match r {
-                    Ok(q) => Some(Ok(q)),
-                    Err(e) => {
-                        if let Some(callback) = &self.on_parse_error {
-                            if let Err(e) = callback(e) {
-                                Some(Err(e))
-                            } else {
-                                None
-                            }
-                        } else {
-                            Some(Err(e))
-                        }
+        let mut blank_node_mapper = self
+            .reproducible_seed
+            .filter(|_| self.blank_node_scope == BlankNodeScope::Document)
+            .map(|seed| {
+                let load_id = self.load_count.get();
+                self.load_count.set(load_id + 1);
+                ReproducibleBlankNodeMapper::new(seed, load_id)
+            });
+        let parser = if blank_node_mapper.is_some() {
+            parser.into() // The mapper takes care of the renaming
+        } else {
+            self.blank_node_scope.configure(parser.into())
+        };
+        self.load_ok_quads(parser.for_reader(reader).filter_map(|r| match r {
+            Ok(q) => Some(Ok(if let Some(mapper) = &mut blank_node_mapper {
+                mapper.map_quad(q)
+            } else {
+                q
+            })),
+            Err(e) => {
+                if let Some(callback) = &self.on_parse_error {
+                    if let Err(e) = callback(e) {
+                        Some(Err(e))
+                    } else {
+                        None
                     }
-                }),
-        )
+                } else {
+                    Some(Err(e))
+                }
+            }
+        }))
     }
 
     /// Adds a set of quads using the bulk loader.
@@ -1557,8 +3365,93 @@ impl BulkLoader {
         &self,
         quads: impl IntoIterator<Item = Result<impl Into<Quad>, EI>>,
     ) -> Result<(), EO> {
-        self.storage
-            .load(quads.into_iter().map(|q| q.map(Into::into)))
+        self.storage.load(
+            quads
+                .into_iter()
+                .map(|q| q.map(|q| self.check_language_tag(q.into()))),
+        )
+    }
+
+    fn check_language_tag(&self, mut quad: Quad) -> Quad {
+        let (Some(detector), Term::Literal(literal)) = (&self.language_detector, &quad.object)
+        else {
+            return quad;
+        };
+        let Some(mismatch) = LanguageTagMismatch::check(literal, detector.as_ref()) else {
+            return quad;
+        };
+        if self.fix_language_tags {
+            if let Some(fixed) = mismatch.fixed_literal() {
+                quad.object = fixed.into();
+            }
+        }
+        if let Some(callback) = &self.on_language_tag_mismatch {
+            callback(mismatch);
+        }
+        quad
+    }
+}
+
+/// Renames blank nodes to identifiers derived from a seed and from their order of appearance
+struct ReproducibleBlankNodeMapper {
+    seed: u64,
+    load_id: u64,
+    blank_nodes: HashMap<BlankNode, BlankNode>,
+}
+
+impl ReproducibleBlankNodeMapper {
+    fn new(seed: u64, load_id: u64) -> Self {
+        Self {
+            seed,
+            load_id,
+            blank_nodes: HashMap::new(),
+        }
+    }
+
+    fn map_blank_node(&mut self, node: BlankNode) -> BlankNode {
+        let position = self.blank_nodes.len();
+        self.blank_nodes
+            .entry(node)
+            .or_insert_with(|| {
+                let mut hasher = SipHasher24::new_with_keys(self.seed, self.load_id);
+                position.hash(&mut hasher);
+                BlankNode::new_from_unique_id(hasher.finish128().into())
+            })
+            .clone()
+    }
+
+    fn map_subject(&mut self, node: NamedOrBlankNode) -> NamedOrBlankNode {
+        match node {
+            NamedOrBlankNode::NamedNode(node) => node.into(),
+            NamedOrBlankNode::BlankNode(node) => self.map_blank_node(node).into(),
+        }
+    }
+
+    fn map_term(&mut self, term: Term) -> Term {
+        match term {
+            Term::NamedNode(node) => node.into(),
+            Term::BlankNode(node) => self.map_blank_node(node).into(),
+            Term::Literal(literal) => literal.into(),
+            #[cfg(feature = "rdf-12")]
+            Term::Triple(triple) => Triple::new(
+                self.map_subject(triple.subject),
+                triple.predicate,
+                self.map_term(triple.object),
+            )
+            .into(),
+        }
+    }
+
+    fn map_quad(&mut self, quad: Quad) -> Quad {
+        Quad::new(
+            self.map_subject(quad.subject),
+            quad.predicate,
+            self.map_term(quad.object),
+            match quad.graph_name {
+                GraphName::BlankNode(node) => self.map_blank_node(node).into(),
+                graph_name => graph_name,
+            },
+        )
     }
 }
 
@@ -1571,6 +3464,118 @@ mod tests {
     fn test_send_sync() {
         fn is_send_sync<T: Send + Sync>() {}
         is_send_sync::<Store>();
+        is_send_sync::<QuadShard>();
+    }
+
+    #[test]
+    fn test_parallel_scan() -> Result<(), StorageError> {
+        use crate::model::*;
+
+        let store = Store::new()?;
+        let p = NamedNodeRef::new_unchecked("http://example.com/p");
+        let mut expected = Vec::new();
+        for g in 0..3 {
+            let graph_name = if g == 0 {
+                GraphName::DefaultGraph
+            } else {
+                NamedNode::new_unchecked(format!("http://example.com/g{g}")).into()
+            };
+            for s in 0..20 {
+                let quad = Quad::new(
+                    NamedNode::new_unchecked(format!("http://example.com/s{s}")),
+                    p,
+                    Literal::from(s),
+                    graph_name.clone(),
+                );
+                store.insert(&quad)?;
+                expected.push(quad);
+            }
+        }
+        expected.sort_by_key(ToString::to_string);
+
+        for shard_count in [0, 1, 2, 3, 5, 8] {
+            let shards = store.parallel_scan(None, Some(p), None, None, shard_count)?;
+            assert_eq!(shards.len(), shard_count.max(1));
+            let mut actual = Vec::new();
+            for shard in &shards {
+                for quad in shard {
+                    actual.push(quad?);
+                }
+            }
+            actual.sort_by_key(ToString::to_string);
+            assert_eq!(actual, expected);
+        }
+
+        let shards = store.parallel_scan(None, None, None, Some(GraphNameRef::DefaultGraph), 4)?;
+        assert_eq!(shards.iter().map(|s| s.iter().count()).sum::<usize>(), 20);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transform_quads() -> Result<(), StorageError> {
+        use crate::model::vocab::xsd;
+        use crate::model::*;
+
+        let store = Store::new()?;
+        let p = NamedNodeRef::new_unchecked("http://example.com/p");
+        let g = NamedNodeRef::new_unchecked("http://example.com/g");
+        for i in 0..100 {
+            let s = NamedNode::new_unchecked(format!("http://example.com/s{i}"));
+            store.insert(QuadRef::new(
+                &s,
+                p,
+                &Literal::new_typed_literal(i.to_string(), xsd::STRING),
+                GraphNameRef::DefaultGraph,
+            ))?;
+            store.insert(QuadRef::new(
+                &s,
+                p,
+                &Literal::new_typed_literal(i.to_string(), xsd::STRING),
+                g,
+            ))?;
+        }
+
+        // We fix the datatype of the default graph literals and drop the odd ones
+        let changed = store.transform_quads(Some(GraphNameRef::DefaultGraph), |quad| {
+            let Term::Literal(literal) = &quad.object else {
+                return Some(quad);
+            };
+            let value = literal.value().parse::<i64>().ok()?;
+            if value % 2 == 1 {
+                return None;
+            }
+            Some(Quad::new(
+                quad.subject,
+                quad.predicate,
+                Literal::from(value),
+                quad.graph_name,
+            ))
+        })?;
+        assert_eq!(changed, 100);
+        assert_eq!(store.len()?, 150);
+        for i in 0..100 {
+            let s = NamedNode::new_unchecked(format!("http://example.com/s{i}"));
+            assert_eq!(
+                store.contains(QuadRef::new(
+                    &s,
+                    p,
+                    &Literal::from(i),
+                    GraphNameRef::DefaultGraph
+                ))?,
+                i % 2 == 0
+            );
+            assert!(store.contains(QuadRef::new(
+                &s,
+                p,
+                &Literal::new_typed_literal(i.to_string(), xsd::STRING),
+                g
+            ))?);
+        }
+
+        // The identity does not change anything
+        assert_eq!(store.transform_quads(None, Some)?, 0);
+        assert_eq!(store.len()?, 150);
+        Ok(())
     }
 
     #[test]
@@ -2,25 +2,43 @@
 #![allow(clippy::panic_in_result_fn)]
 
 use oxigraph::io::RdfFormat;
-use oxigraph::model::vocab::{rdf, xsd};
+use oxigraph::model::dataset::CanonicalizationAlgorithm;
+use oxigraph::model::vocab::{rdf, rdfs, xsd};
 use oxigraph::model::*;
-use oxigraph::store::Store;
+use oxigraph::sparql::{
+    EvaluationError, QueryCancellationToken, QueryOptions, QueryPlanNode, QueryResults,
+};
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+use oxigraph::store::{BackupRepository, EncryptionKey, StorageProfile, StoreOptions};
+use oxigraph::store::{
+    BlankNodeScope, GraphQuota, OwlRlInference, RdfsInference, StorageError, Store, StoreEvent,
+    TermId,
+};
+use oxsdatatypes::DateTime;
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 use rand::random;
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 use std::env::temp_dir;
 use std::error::Error;
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
-use std::fs::{File, create_dir_all, remove_dir_all};
-#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+use std::fs::{File, create_dir_all, read, read_dir, remove_dir_all};
+#[cfg(not(target_family = "wasm"))]
 use std::io::Write;
 use std::iter::empty;
 #[cfg(all(target_os = "linux", feature = "rocksdb"))]
 use std::iter::once;
+#[cfg(not(target_family = "wasm"))]
+use std::mem::take;
+use std::ops::Bound;
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 use std::path::{Path, PathBuf};
 #[cfg(all(target_os = "linux", feature = "rocksdb"))]
 use std::process::Command;
+use std::sync::Arc;
+#[cfg(not(target_family = "wasm"))]
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 #[expect(clippy::non_ascii_literal)]
 const DATA: &str = r#"
@@ -132,6 +150,19 @@ fn test_load_graph_on_disk() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_load_graph_on_disk_with_small_profile() -> Result<(), Box<dyn Error>> {
+    let dir = TempDir::default();
+    let store = Store::open_with_profile(&dir.0, StorageProfile::Small)?;
+    store.load_from_reader(RdfFormat::Turtle, DATA.as_bytes())?;
+    for q in quads(GraphNameRef::DefaultGraph) {
+        assert!(store.contains(q)?);
+    }
+    store.validate()?;
+    Ok(())
+}
+
 #[test]
 fn test_bulk_load_graph() -> Result<(), Box<dyn Error>> {
     let store = Store::new()?;
@@ -160,6 +191,52 @@ fn test_bulk_load_graph_on_disk() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_bulk_load_on_disk_with_external_sort() -> Result<(), Box<dyn Error>> {
+    let dir = TempDir::default();
+    let sort_dir = TempDir::default();
+    create_dir_all(&sort_dir.0)?;
+    let store = Store::open(&dir.0)?;
+    let written_bytes = Arc::new(AtomicUsize::new(0));
+    let progress_written_bytes = Arc::clone(&written_bytes);
+    let ex = NamedNode::new("http://example.com")?;
+    let graph = NamedNode::new("http://example.com/g")?;
+    // Small batches to get multiple sorted runs per index, with duplicates between them
+    store
+        .bulk_loader()
+        .with_max_memory_size_in_megabytes(20)
+        .with_external_sort(&sort_dir.0)
+        .on_detailed_progress(move |progress| {
+            progress_written_bytes.store(
+                progress.written_bytes().try_into().unwrap(),
+                Ordering::Relaxed,
+            )
+        })
+        .load_quads((0..50_000).map(|i| {
+            Quad::new(
+                ex.clone(),
+                ex.clone(),
+                Literal::from(i % 25_000),
+                if i % 2 == 0 {
+                    GraphName::DefaultGraph
+                } else {
+                    graph.clone().into()
+                },
+            )
+        }))?;
+    assert_eq!(store.len()?, 25_000);
+    assert!(store.contains(QuadRef::new(&ex, &ex, &Literal::from(24_999), &graph))?);
+    assert!(written_bytes.load(Ordering::Relaxed) > 0);
+    assert_eq!(
+        sort_dir.0.read_dir()?.count(),
+        0,
+        "the temporary files must be removed"
+    );
+    store.validate()?;
+    Ok(())
+}
+
 #[test]
 fn test_bulk_load_graph_lenient() -> Result<(), Box<dyn Error>> {
     let store = Store::new()?;
@@ -178,6 +255,42 @@ fn test_bulk_load_graph_lenient() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_bulk_load_reproducible() -> Result<(), Box<dyn Error>> {
+    let data = "<http://example.com/s> <http://example.com/p> [ <http://example.com/p> _:b ] .";
+    let load = |seed| -> Result<Vec<Quad>, Box<dyn Error>> {
+        let store = Store::new()?;
+        let loader = store.bulk_loader().with_reproducible_output(seed);
+        loader.load_from_reader(RdfFormat::Turtle, data.as_bytes())?;
+        loader.load_from_reader(RdfFormat::Turtle, data.as_bytes())?;
+        store.validate()?;
+        Ok(store.iter().collect::<Result<_, _>>()?)
+    };
+    let quads = load(1)?;
+    assert_eq!(quads.len(), 4); // Each load gets its own blank nodes
+    assert_eq!(quads, load(1)?);
+    assert_ne!(quads, load(2)?);
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_bulk_load_reproducible_on_disk() -> Result<(), Box<dyn Error>> {
+    let data = "<http://example.com/s> <http://example.com/p> [ <http://example.com/p> _:b ] .";
+    let load = || -> Result<Vec<Quad>, Box<dyn Error>> {
+        let dir = TempDir::default();
+        let store = Store::open(&dir.0)?;
+        store
+            .bulk_loader()
+            .with_reproducible_output(1)
+            .load_from_reader(RdfFormat::Turtle, data.as_bytes())?;
+        store.validate()?;
+        Ok(store.iter().collect::<Result<_, _>>()?)
+    };
+    assert_eq!(load()?, load()?);
+    Ok(())
+}
+
 #[test]
 fn test_bulk_load_empty() -> Result<(), Box<dyn Error>> {
     let store = Store::new()?;
@@ -229,6 +342,20 @@ fn test_load_graph_generates_new_blank_nodes() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_load_graph_keeps_blank_nodes_in_store_scope() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?.with_blank_node_scope(BlankNodeScope::Store);
+    let file = "_:a <http://example.com/p> <http://example.com/p> .";
+    store.load_from_reader(RdfFormat::NTriples, file.as_bytes())?;
+    store
+        .bulk_loader()
+        .load_from_reader(RdfFormat::NTriples, file.as_bytes())?;
+    store.transaction(|mut t| t.load_from_reader(RdfFormat::NTriples, file.as_bytes()))?;
+    assert_eq!(store.len()?, 1);
+    store.validate()?;
+    Ok(())
+}
+
 #[test]
 fn test_dump_graph() -> Result<(), Box<dyn Error>> {
     let store = Store::new()?;
@@ -260,6 +387,309 @@ fn test_dump_dataset() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+#[cfg(not(target_family = "wasm"))]
+fn test_parallel_dump() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?;
+    let ex = NamedNodeRef::new_unchecked("http://example.com");
+    let graphs = [
+        GraphName::DefaultGraph,
+        NamedNode::new_unchecked("http://example.com/g1").into(),
+        NamedNode::new_unchecked("http://example.com/g2").into(),
+    ];
+    for (i, graph_name) in (0..25_000).zip(graphs.iter().cycle()) {
+        store.insert(QuadRef::new(ex, ex, &Literal::from(i), graph_name))?;
+    }
+
+    // Ordered dump with a format with prefixes
+    for num_threads in [1, 3, 8] {
+        let buffer = store
+            .parallel_dumper()
+            .with_num_threads(num_threads)
+            .dump_to_writer(RdfFormat::TriG, Vec::new())?;
+        let dumped = Store::new()?;
+        dumped.load_from_reader(RdfFormat::TriG, buffer.as_slice())?;
+        assert_eq!(dumped.len()?, 25_000);
+        for quad in &store {
+            assert!(dumped.contains(&quad?)?);
+        }
+    }
+
+    // Unordered dump
+    let buffer = store
+        .parallel_dumper()
+        .with_num_threads(4)
+        .unordered()
+        .dump_to_writer(RdfFormat::NQuads, Vec::new())?;
+    assert_eq!(buffer.into_iter().filter(|c| *c == b'\n').count(), 25_000);
+    let buffer = store.parallel_dumper().unordered().dump_graph_to_writer(
+        &graphs[1],
+        RdfFormat::NTriples,
+        Vec::new(),
+    )?;
+    assert_eq!(buffer.into_iter().filter(|c| *c == b'\n').count(), 8333);
+    store
+        .parallel_dumper()
+        .unordered()
+        .dump_to_writer(RdfFormat::TriG, Vec::new())
+        .unwrap_err();
+
+    // One file per graph
+    let files = Mutex::new(Vec::new());
+    store
+        .parallel_dumper()
+        .dump_graphs(RdfFormat::NTriples, |graph_name| {
+            Ok(GraphFile(graph_name.into_owned(), Vec::new(), &files))
+        })?;
+    let mut files = files.into_inner().map_err(|e| e.to_string())?;
+    files.sort_by_key(|(graph_name, _)| graphs.iter().position(|g| g == graph_name));
+    assert_eq!(files.len(), 3);
+    for ((graph_name, content), expected_graph_name) in files.into_iter().zip(&graphs) {
+        assert_eq!(&graph_name, expected_graph_name);
+        let dumped = Store::new()?;
+        dumped.load_from_reader(RdfFormat::NTriples, content.as_slice())?;
+        assert_eq!(
+            dumped.len()?,
+            store
+                .quads_for_pattern(None, None, None, Some(graph_name.as_ref()))
+                .count()
+        );
+    }
+    Ok(())
+}
+
+/// A writer saving the dump of a graph when flushed
+#[cfg(not(target_family = "wasm"))]
+struct GraphFile<'a>(GraphName, Vec<u8>, &'a Mutex<Vec<(GraphName, Vec<u8>)>>);
+
+#[cfg(not(target_family = "wasm"))]
+impl Write for GraphFile<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.1.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.2
+            .lock()
+            .map_err(|_| std::io::Error::other("The lock is poisoned"))?
+            .push((self.0.clone(), take(&mut self.1)));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_estimated_quad_count() -> Result<(), Box<dyn Error>> {
+    check_estimated_quad_count(&Store::new()?, true)
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_estimated_quad_count_on_disk() -> Result<(), Box<dyn Error>> {
+    let dir = TempDir::default();
+    let store = Store::open(&dir)?;
+    check_estimated_quad_count(&store, false)?;
+    store.optimize()?;
+    check_estimated_quad_count(&store, false)
+}
+
+fn check_estimated_quad_count(store: &Store, exact: bool) -> Result<(), Box<dyn Error>> {
+    let ex = NamedNodeRef::new_unchecked("http://example.com");
+    let p = NamedNodeRef::new_unchecked("http://example.com/p");
+    let g = NamedNodeRef::new_unchecked("http://example.com/g");
+    if store.is_empty()? {
+        store.bulk_loader().load_quads((0..5_000).map(|i| {
+            let s = NamedNode::new_unchecked(format!("http://example.com/s{}", i % 10));
+            Quad::new(s, p, Literal::from(i), if i % 2 == 0 { ex } else { g })
+        }))?;
+        store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
+    }
+
+    // Small patterns are counted exactly
+    assert_eq!(
+        store.estimated_quad_count(Some(ex.into()), None, None, None)?,
+        1
+    );
+    assert_eq!(
+        store.estimated_quad_count(None, Some(ex), Some(ex.into()), None)?,
+        1
+    );
+    assert_eq!(
+        store.estimated_quad_count(None, None, None, Some(GraphNameRef::DefaultGraph))?,
+        1
+    );
+    assert_eq!(
+        store.estimated_quad_count(
+            Some(NamedNodeRef::new_unchecked("http://example.com/s1").into()),
+            None,
+            None,
+            Some(g.into())
+        )?,
+        500
+    );
+    assert_eq!(store.estimated_quad_count(None, Some(g), None, None)?, 0);
+
+    // Large patterns are estimated
+    for (estimate, expected) in [
+        (store.estimated_quad_count(None, None, None, None)?, 5_001),
+        (
+            store.estimated_quad_count(None, Some(p), None, None)?,
+            5_000,
+        ),
+        (
+            store.estimated_quad_count(None, Some(p), None, Some(g.into()))?,
+            2_500,
+        ),
+    ] {
+        if exact {
+            assert_eq!(estimate, expected);
+        } else {
+            assert!(
+                (1024..=2 * expected).contains(&estimate),
+                "{estimate} is not close to {expected}"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_quads_for_date_time_range() -> Result<(), Box<dyn Error>> {
+    check_quads_for_date_time_range(&Store::new()?)
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_quads_for_date_time_range_on_disk() -> Result<(), Box<dyn Error>> {
+    let dir = TempDir::default();
+    check_quads_for_date_time_range(&Store::open(&dir)?)
+}
+
+fn check_quads_for_date_time_range(store: &Store) -> Result<(), Box<dyn Error>> {
+    let sensor = NamedNodeRef::new_unchecked("http://example.com/sensor");
+    let at = NamedNodeRef::new_unchecked("http://example.com/at");
+    let g = NamedNodeRef::new_unchecked("http://example.com/g");
+    let date_time = |value: &str| value.parse::<DateTime>();
+    let times = [
+        "-0044-03-15T12:00:00Z",
+        "2024-01-01T00:00:00Z",
+        "2024-01-01T01:00:00+02:00",
+        "2024-01-01T06:00:00",
+        "2024-01-02T00:00:00Z",
+    ];
+    for (i, time) in times.into_iter().enumerate() {
+        store.insert(QuadRef::new(
+            sensor,
+            at,
+            &Literal::from(date_time(time)?),
+            if i % 2 == 0 {
+                GraphNameRef::DefaultGraph
+            } else {
+                g.into()
+            },
+        ))?;
+    }
+    store.insert(QuadRef::new(
+        sensor,
+        at,
+        LiteralRef::new_typed_literal("2024-01-01", xsd::DATE),
+        GraphNameRef::DefaultGraph,
+    ))?;
+    store.insert(QuadRef::new(
+        sensor,
+        sensor,
+        &Literal::from(date_time("2024-01-01T00:00:00Z")?),
+        GraphNameRef::DefaultGraph,
+    ))?;
+
+    let results = |range: (Bound<DateTime>, Bound<DateTime>),
+                   graph_name: Option<GraphNameRef<'_>>|
+     -> Result<Vec<Literal>, Box<dyn Error>> {
+        let mut results = store
+            .quads_for_date_time_range(at, range, graph_name)
+            .map(|q| match q?.object {
+                Term::Literal(l) => Ok(l),
+                o => Err(format!("{o} is not a literal").into()),
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+        results.sort_by_key(Literal::to_string);
+        Ok(results)
+    };
+    let literals = |values: &[&str]| -> Result<Vec<Literal>, Box<dyn Error>> {
+        let mut literals = values
+            .iter()
+            .map(|v| Ok(Literal::from(date_time(v)?)))
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+        literals.sort_by_key(Literal::to_string);
+        Ok(literals)
+    };
+
+    assert_eq!(
+        results((Bound::Unbounded, Bound::Unbounded), None)?,
+        literals(&times)?
+    );
+    // 01:00:00+02:00 is before 00:00:00Z and the value without timezone is read as UTC
+    assert_eq!(
+        results(
+            (
+                Bound::Included(date_time("2024-01-01T00:00:00Z")?),
+                Bound::Excluded(date_time("2024-01-02T00:00:00Z")?)
+            ),
+            None
+        )?,
+        literals(&["2024-01-01T00:00:00Z", "2024-01-01T06:00:00"])?
+    );
+    assert_eq!(
+        results(
+            (
+                Bound::Excluded(date_time("2024-01-01T00:00:00Z")?),
+                Bound::Included(date_time("2024-01-02T00:00:00Z")?)
+            ),
+            None
+        )?,
+        literals(&["2024-01-01T06:00:00", "2024-01-02T00:00:00Z"])?
+    );
+    // Negative timeline values are after the positive ones in the index keys
+    assert_eq!(
+        results(
+            (
+                Bound::Unbounded,
+                Bound::Included(date_time("2024-01-01T00:00:00Z")?)
+            ),
+            None
+        )?,
+        literals(&[
+            "-0044-03-15T12:00:00Z",
+            "2024-01-01T01:00:00+02:00",
+            "2024-01-01T00:00:00Z"
+        ])?
+    );
+    assert_eq!(
+        results(
+            (
+                Bound::Unbounded,
+                Bound::Excluded(date_time("2024-01-02T00:00:00Z")?)
+            ),
+            Some(GraphNameRef::DefaultGraph)
+        )?,
+        literals(&["-0044-03-15T12:00:00Z", "2024-01-01T01:00:00+02:00"])?
+    );
+    assert_eq!(
+        results((Bound::Unbounded, Bound::Unbounded), Some(g.into()))?,
+        literals(&["2024-01-01T00:00:00Z", "2024-01-01T06:00:00"])?
+    );
+    assert_eq!(
+        results(
+            (
+                Bound::Included(date_time("2024-01-02T00:00:00Z")?),
+                Bound::Excluded(date_time("2024-01-01T00:00:00Z")?)
+            ),
+            None
+        )?,
+        Vec::<Literal>::new()
+    );
+    Ok(())
+}
+
 #[test]
 fn test_snapshot_isolation_iterator() -> Result<(), Box<dyn Error>> {
     let quad = QuadRef::new(
@@ -280,6 +710,27 @@ fn test_snapshot_isolation_iterator() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_term_ids() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?;
+    store
+        .bulk_loader()
+        .load_quads(quads(GraphNameRef::DefaultGraph))?;
+    for quad in quads(GraphNameRef::DefaultGraph) {
+        for term in [quad.subject.into(), quad.predicate.into(), quad.object] {
+            let id = store.term_id(term)?.ok_or("term not found")?;
+            assert_eq!(TermId::from_bytes(id.as_bytes()), Some(id.clone()));
+            assert_eq!(store.term_from_id(&id)?, Some(term.into_owned()));
+        }
+    }
+    assert_eq!(
+        store.term_id(NamedNodeRef::new("http://example.com/not-in-store")?)?,
+        None
+    );
+    assert_eq!(TermId::from_bytes(&[]), None);
+    Ok(())
+}
+
 #[test]
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 fn test_snapshot_isolation_iterator_on_disk() -> Result<(), Box<dyn Error>> {
@@ -395,6 +846,123 @@ fn test_backup() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_incremental_backup() -> Result<(), Box<dyn Error>> {
+    let quad = QuadRef::new(
+        NamedNodeRef::new_unchecked("http://example.com/s"),
+        NamedNodeRef::new_unchecked("http://example.com/p"),
+        NamedNodeRef::new_unchecked("http://example.com/o"),
+        GraphNameRef::DefaultGraph,
+    );
+    let other_quad = QuadRef::new(
+        NamedNodeRef::new_unchecked("http://example.com/s"),
+        NamedNodeRef::new_unchecked("http://example.com/p"),
+        NamedNodeRef::new_unchecked("http://example.com/o2"),
+        GraphNameRef::DefaultGraph,
+    );
+    let store_dir = TempDir::default();
+    let repository_dir = TempDir::default();
+    let first_restore_dir = TempDir::default();
+    let latest_restore_dir = TempDir::default();
+
+    let store = Store::open(&store_dir)?;
+    let repository = BackupRepository::open(&repository_dir)?;
+    store.insert(quad)?;
+    let first = store.incremental_backup(&repository)?;
+    store.insert(other_quad)?;
+    let second = store.incremental_backup(&repository)?;
+    assert!(second.id() > first.id());
+    assert_eq!(repository.backups()?, [first, second]);
+    repository.verify(first.id())?;
+    repository.verify(second.id())?;
+
+    repository.restore(first.id(), &first_restore_dir)?;
+    let restored = Store::open_read_only(&first_restore_dir.0)?;
+    restored.validate()?;
+    assert!(restored.contains(quad)?);
+    assert!(!restored.contains(other_quad)?);
+
+    repository.restore_latest(&latest_restore_dir)?;
+    let restored = Store::open_read_only(&latest_restore_dir.0)?;
+    assert!(restored.contains(other_quad)?);
+
+    repository.purge_old_backups(1)?;
+    assert_eq!(repository.backups()?, [second]);
+    repository.verify(second.id())?;
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_incremental_backup_on_in_memory() -> Result<(), Box<dyn Error>> {
+    let repository_dir = TempDir::default();
+    let repository = BackupRepository::open(&repository_dir)?;
+    Store::new()?.incremental_backup(&repository).unwrap_err();
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_fork() -> Result<(), Box<dyn Error>> {
+    let quad = QuadRef::new(
+        NamedNodeRef::new_unchecked("http://example.com/s"),
+        NamedNodeRef::new_unchecked("http://example.com/p"),
+        NamedNodeRef::new_unchecked("http://example.com/o"),
+        GraphNameRef::DefaultGraph,
+    );
+    let other_quad = QuadRef::new(
+        NamedNodeRef::new_unchecked("http://example.com/s"),
+        NamedNodeRef::new_unchecked("http://example.com/p"),
+        NamedNodeRef::new_unchecked("http://example.com/o2"),
+        GraphNameRef::DefaultGraph,
+    );
+    let store_dir = TempDir::default();
+    let fork_dir = TempDir::default();
+
+    let store = Store::open(&store_dir)?;
+    store.insert(quad)?;
+    let fork = store.fork(&fork_dir)?;
+    fork.validate()?;
+    assert!(fork.contains(quad)?);
+
+    fork.remove(quad)?;
+    store.insert(other_quad)?;
+    assert!(store.contains(quad)?);
+    assert!(store.contains(other_quad)?);
+    assert!(!fork.contains(quad)?);
+    assert!(!fork.contains(other_quad)?);
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_fork_on_in_memory() -> Result<(), Box<dyn Error>> {
+    let fork_dir = TempDir::default();
+    assert!(Store::new()?.fork(&fork_dir).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_storage_stats_on_disk() -> Result<(), Box<dyn Error>> {
+    let dir = TempDir::default();
+    let store = Store::open(&dir)?;
+    store
+        .bulk_loader()
+        .load_quads(quads(NamedNodeRef::new_unchecked(
+            "http://www.wikidata.org/wiki/Special:EntityData/Q90",
+        )))?;
+    store.optimize()?;
+    store.check_integrity()?;
+    let stats = store.storage_stats()?;
+    assert_eq!(stats.column_families().len(), 12);
+    assert!(stats.disk_size() > 0);
+    assert_eq!(stats.graphs().len(), 2);
+    assert!(stats.approximate_len() > 0);
+    Ok(())
+}
+
 #[test]
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 fn test_bad_backup() -> Result<(), Box<dyn Error>> {
@@ -528,6 +1096,35 @@ fn test_read_only() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_secondary() -> Result<(), Box<dyn Error>> {
+    let quad = QuadRef::new(
+        NamedNodeRef::new_unchecked("http://example.com/s"),
+        NamedNodeRef::new_unchecked("http://example.com/p"),
+        NamedNodeRef::new_unchecked("http://example.com/o"),
+        GraphNameRef::DefaultGraph,
+    );
+    let primary_dir = TempDir::default();
+    let secondary_dir = TempDir::default();
+
+    let primary = Store::open(&primary_dir)?;
+    let secondary = Store::open_secondary(&primary_dir, &secondary_dir)?;
+    assert!(secondary.is_empty()?);
+
+    // The secondary only sees the new quad after catching up
+    primary.insert(quad)?;
+    assert!(!secondary.contains(quad)?);
+    secondary.try_catch_up_with_primary()?;
+    assert!(secondary.contains(quad)?);
+    secondary.validate()?;
+
+    // Writes are not allowed
+    secondary.insert(quad).unwrap_err();
+    primary.try_catch_up_with_primary().unwrap_err();
+    Ok(())
+}
+
 #[test]
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 fn test_open_read_only_bad_dir() -> Result<(), Box<dyn Error>> {
@@ -557,6 +1154,874 @@ fn reset_dir(dir: &str) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_explain_query_plan() -> Result<(), Box<dyn Error>> {
+    fn nodes<'a>(node: QueryPlanNode<'a>, output: &mut Vec<QueryPlanNode<'a>>) {
+        output.push(node);
+        for child in node.children() {
+            nodes(child, output);
+        }
+    }
+
+    let store = Store::new()?;
+    store.load_from_reader(RdfFormat::Turtle, DATA.as_bytes())?;
+    let (results, explanation) = store.explain_query_opt(
+        "SELECT ?name WHERE { ?s a <http://schema.org/City> ; <http://schema.org/name> ?name }",
+        QueryOptions::default(),
+        true,
+    )?;
+    let QueryResults::Solutions(solutions) = results? else {
+        return Err("Solutions expected".into());
+    };
+    assert_eq!(solutions.collect::<Result<Vec<_>, _>>()?.len(), 2);
+
+    let plan = explanation.plan();
+    assert_eq!(plan.result_count(), Some(2));
+    let mut all_nodes = Vec::new();
+    nodes(plan, &mut all_nodes);
+    assert!(all_nodes.len() > 2);
+    for node in all_nodes {
+        assert!(!node.label().is_empty());
+        assert!(node.estimated_result_count() > 0);
+        assert!(node.result_count().is_some());
+        assert!(node.duration().is_some());
+    }
+
+    let mut json = Vec::new();
+    explanation.write_in_json(&mut json)?;
+    assert!(String::from_utf8(json)?.contains("\"estimated number of results\":"));
+    Ok(())
+}
+
+#[test]
+fn test_query_cancellation_and_deadline() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?;
+    store.load_from_reader(RdfFormat::Turtle, DATA.as_bytes())?;
+    let query = "SELECT * WHERE { ?s ?p ?o }";
+
+    let token = QueryCancellationToken::new();
+    token.cancel();
+    let QueryResults::Solutions(mut solutions) = store.query_opt(
+        query,
+        QueryOptions::default().with_cancellation_token(token.clone()),
+    )?
+    else {
+        return Err("Solutions expected".into());
+    };
+    let error = solutions.next().ok_or("An error is expected")?.unwrap_err();
+    assert!(matches!(error, EvaluationError::Cancelled));
+    assert_eq!(error.code(), "cancelled");
+
+    // The earliest of the timeout and of the deadline applies
+    let QueryResults::Solutions(mut solutions) = store.query_opt(
+        query,
+        QueryOptions::default()
+            .with_timeout(Duration::from_secs(3600))
+            .with_deadline(Instant::now())
+            .with_cancellation_token(QueryCancellationToken::new()),
+    )?
+    else {
+        return Err("Solutions expected".into());
+    };
+    assert!(matches!(
+        solutions.next(),
+        Some(Err(EvaluationError::Timeout))
+    ));
+
+    let QueryResults::Solutions(solutions) = store.query_opt(
+        query,
+        QueryOptions::default()
+            .with_deadline(Instant::now() + Duration::from_secs(3600))
+            .with_cancellation_token(QueryCancellationToken::new()),
+    )?
+    else {
+        return Err("Solutions expected".into());
+    };
+    assert!(solutions.count() > 0);
+    Ok(())
+}
+
+#[test]
+fn test_closed_transitive_property_paths() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?;
+    store.load_from_reader(
+        RdfFormat::TriG,
+        b"@prefix ex: <http://example.com/> .
+        ex:a ex:p ex:b , ex:c .
+        ex:b ex:p ex:d .
+        ex:c ex:p ex:d .
+        ex:d ex:p ex:a .
+        ex:e ex:p ex:f .
+        ex:g { ex:a ex:p ex:b , ex:c . ex:b ex:p ex:d . ex:c ex:p ex:d . }"
+            .as_slice(),
+    )?;
+    for (query, expected) in [
+        ("ASK { ex:a ex:p+ ex:d }", true),
+        ("ASK { ex:a ex:p* ex:d }", true),
+        ("ASK { ex:a ex:p+ ex:a }", true),
+        ("ASK { ex:a ex:p* ex:a }", true),
+        ("ASK { ex:d ex:p+ ex:c }", true),
+        ("ASK { ex:a ex:p+ ex:f }", false),
+        ("ASK { ex:f ex:p* ex:a }", false),
+        ("ASK { ex:a ex:p+ ex:e }", false),
+        ("ASK { GRAPH ex:g { ex:a ex:p+ ex:d } }", true),
+        ("ASK { GRAPH ex:g { ex:d ex:p+ ex:a } }", false),
+    ] {
+        let QueryResults::Boolean(result) =
+            store.query(format!("PREFIX ex: <http://example.com/> {query}").as_str())?
+        else {
+            return Err("Boolean expected".into());
+        };
+        assert_eq!(result, expected, "{query}");
+    }
+    // Each graph is returned only once even if there are multiple paths in it
+    let QueryResults::Solutions(solutions) = store.query(
+        "PREFIX ex: <http://example.com/> SELECT ?g WHERE { GRAPH ?g { ex:a ex:p+ ex:d } }",
+    )?
+    else {
+        return Err("Solutions expected".into());
+    };
+    let graphs = solutions
+        .map(|s| Ok(s?.get("g").cloned()))
+        .collect::<Result<Vec<_>, EvaluationError>>()?;
+    assert_eq!(
+        graphs,
+        [Some(NamedNode::new("http://example.com/g")?.into())]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_full_text_index() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?;
+    store.load_from_reader(RdfFormat::Turtle, DATA.as_bytes())?;
+    let store = store.with_full_text_index([NamedNodeRef::new("http://schema.org/name")?])?;
+    let search = |query: &str| -> Result<Vec<Term>, Box<dyn Error>> {
+        let QueryResults::Solutions(solutions) = store.query(
+            format!(
+                "SELECT ?s WHERE {{ ?s <http://schema.org/name> ?name . ?name <http://oxigraph.org/text#search> \"{query}\" }} ORDER BY ?s"
+            )
+            .as_str(),
+        )?
+        else {
+            return Err("Solutions expected".into());
+        };
+        Ok(solutions
+            .map(|s| Ok(s?.get("s").cloned()))
+            .collect::<Result<Option<Vec<_>>, EvaluationError>>()?
+            .ok_or("?s must be bound")?)
+    };
+    let paris = vec![Term::from(NamedNode::new(
+        "http://www.wikidata.org/entity/Q90",
+    )?)];
+    assert_eq!(search("ville LUMI\u{C8}RE")?, paris);
+    assert_eq!(search("lum*")?, paris);
+    assert_eq!(search("ville sombre")?, []);
+
+    // The index follows the updates
+    store.update(
+        "PREFIX schema: <http://schema.org/> INSERT DATA { <http://example.com/lyon> schema:name \"La ville des lumi\u{e8}res\" }",
+    )?;
+    assert_eq!(search("ville")?.len(), 2);
+    store.update("PREFIX schema: <http://schema.org/> DELETE WHERE { ?s schema:name \"la ville lumi\u{e8}re\"@fr }")?;
+    assert_eq!(search("ville")?.len(), 1);
+    assert!(store.full_text_search("lumi\u{e8}re").is_empty());
+    store.bulk_loader().load_quads([Quad::new(
+        NamedNode::new("http://example.com/lille")?,
+        NamedNode::new("http://schema.org/name")?,
+        Literal::new_language_tagged_literal("Lille, ville du nord", "fr")?,
+        GraphName::DefaultGraph,
+    )])?;
+    assert_eq!(search("ville")?.len(), 2);
+    store.clear()?;
+    assert_eq!(search("ville")?, []);
+    assert!(store.full_text_search("ville").is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_incremental_rdfs_inference() -> Result<(), Box<dyn Error>> {
+    let inferred = NamedNode::new("http://example.com/inferred")?;
+    let store = Store::new()?;
+    store.load_from_reader(
+        RdfFormat::Turtle,
+        "
+        @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+        @prefix ex: <http://example.com/> .
+        ex:City rdfs:subClassOf ex:Place .
+        ex:Place rdfs:subClassOf ex:Thing .
+        ex:capital rdfs:subPropertyOf ex:contains ; rdfs:domain ex:Country .
+        ex:contains rdfs:range ex:Place .
+        ex:france ex:capital ex:paris .
+        ex:lyon a ex:City .
+        "
+        .as_bytes(),
+    )?;
+    let store = store.with_rdfs_inference(RdfsInference::new(inferred.clone()).incremental())?;
+    let ex = |name: &str| NamedNode::new(format!("http://example.com/{name}"));
+    let is_inferred = |s: &NamedNode, p: NamedNodeRef<'_>, o: &NamedNode| {
+        store.contains(QuadRef::new(s, p, o, &inferred))
+    };
+    let (france, paris, lyon) = (ex("france")?, ex("paris")?, ex("lyon")?);
+    assert!(is_inferred(&france, rdf::TYPE, &ex("Country")?)?);
+    assert!(is_inferred(&france, ex("contains")?.as_ref(), &paris)?);
+    assert!(is_inferred(&paris, rdf::TYPE, &ex("Place")?)?);
+    assert!(is_inferred(&paris, rdf::TYPE, &ex("Thing")?)?);
+    assert!(is_inferred(&lyon, rdf::TYPE, &ex("Thing")?)?);
+    assert!(is_inferred(
+        &ex("City")?,
+        rdfs::SUB_CLASS_OF,
+        &ex("Thing")?
+    )?);
+    assert!(!is_inferred(&lyon, rdf::TYPE, &ex("City")?)?);
+
+    // The entailments follow the updates
+    store.update("PREFIX ex: <http://example.com/> INSERT DATA { ex:paris a ex:City }")?;
+    assert!(is_inferred(&paris, rdf::TYPE, &ex("Place")?)?);
+    store
+        .update("PREFIX ex: <http://example.com/> DELETE DATA { ex:france ex:capital ex:paris }")?;
+    assert!(!is_inferred(&france, rdf::TYPE, &ex("Country")?)?);
+    assert!(!is_inferred(&france, ex("contains")?.as_ref(), &paris)?);
+    assert!(is_inferred(&paris, rdf::TYPE, &ex("Place")?)?); // Still entailed by ex:paris a ex:City
+    store.update("PREFIX ex: <http://example.com/> DELETE DATA { ex:paris a ex:City }")?;
+    assert!(!is_inferred(&paris, rdf::TYPE, &ex("Place")?)?);
+
+    // A schema change materializes again all the entailments
+    store.update(
+        "PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#> PREFIX ex: <http://example.com/> DELETE DATA { ex:Place rdfs:subClassOf ex:Thing }",
+    )?;
+    assert!(!is_inferred(&lyon, rdf::TYPE, &ex("Thing")?)?);
+    assert!(is_inferred(&lyon, rdf::TYPE, &ex("Place")?)?);
+    assert_eq!(
+        store
+            .quads_for_pattern(None, None, None, Some(inferred.as_ref().into()))
+            .count(),
+        1
+    );
+    Ok(())
+}
+
+#[test]
+fn test_rdfs_inference_on_load() -> Result<(), Box<dyn Error>> {
+    let inferred = NamedNode::new("http://example.com/inferred")?;
+    let store = Store::new()?.with_rdfs_inference(RdfsInference::new(inferred.clone()))?;
+    let city = NamedNode::new("http://example.com/City")?;
+    let place = NamedNode::new("http://example.com/Place")?;
+    let lyon = NamedNode::new("http://example.com/lyon")?;
+    store.bulk_loader().load_quads([
+        Quad::new(
+            city.clone(),
+            rdfs::SUB_CLASS_OF,
+            place.clone(),
+            GraphName::DefaultGraph,
+        ),
+        Quad::new(lyon.clone(), rdf::TYPE, city, GraphName::DefaultGraph),
+    ])?;
+    let entailment = QuadRef::new(&lyon, rdf::TYPE, &place, &inferred);
+    assert!(store.contains(entailment)?);
+    let QueryResults::Boolean(result) = store.query(
+        "ASK { GRAPH <http://example.com/inferred> { <http://example.com/lyon> a <http://example.com/Place> } }",
+    )?
+    else {
+        return Err("Boolean expected".into());
+    };
+    assert!(result);
+
+    // The entailments are only computed on demand
+    store.remove(QuadRef::new(
+        &lyon,
+        rdf::TYPE,
+        &NamedNode::new("http://example.com/City")?,
+        GraphNameRef::DefaultGraph,
+    ))?;
+    assert!(store.contains(entailment)?);
+    store.materialize_rdfs_inference()?;
+    assert!(!store.contains(entailment)?);
+    Ok(())
+}
+
+#[test]
+fn test_owl_rl_inference() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?;
+    store.load_from_reader(
+        RdfFormat::Turtle,
+        "
+        @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+        @prefix owl: <http://www.w3.org/2002/07/owl#> .
+        @prefix ex: <http://example.com/> .
+        ex:hasParent owl:inverseOf ex:hasChild .
+        ex:hasSibling a owl:SymmetricProperty .
+        ex:hasUncle owl:propertyChainAxiom ( ex:hasParent ex:hasBrother ) .
+        ex:hasBrother rdfs:domain ex:Person .
+        ex:French owl:equivalentClass [ owl:onProperty ex:nationality ; owl:hasValue ex:france ] .
+        ex:FrenchPerson owl:intersectionOf ( ex:French ex:Person ) .
+        ex:bob ex:hasChild ex:alice ; ex:hasBrother ex:charles ; ex:hasSibling ex:dave .
+        ex:alice ex:nationality ex:france .
+        ex:alice owl:sameAs ex:alicia .
+        "
+        .as_bytes(),
+    )?;
+    let inferred = NamedNode::new("http://example.com/inferred")?;
+    let store = store.with_owl_rl_inference(OwlRlInference::new(inferred.clone()))?;
+    let ex = |name: &str| NamedNode::new(format!("http://example.com/{name}"));
+    let is_inferred = |s: &NamedNode, p: &NamedNode, o: &NamedNode| {
+        store.contains(QuadRef::new(s, p, o, &inferred))
+    };
+    let (alice, bob) = (ex("alice")?, ex("bob")?);
+    let rdf_type = rdf::TYPE.into_owned();
+    assert!(is_inferred(&alice, &ex("hasParent")?, &bob)?);
+    assert!(is_inferred(&ex("dave")?, &ex("hasSibling")?, &bob)?);
+    assert!(is_inferred(&alice, &ex("hasUncle")?, &ex("charles")?)?);
+    assert!(is_inferred(&bob, &rdf_type, &ex("Person")?)?);
+    assert!(is_inferred(&alice, &rdf_type, &ex("French")?)?);
+    assert!(!is_inferred(&alice, &rdf_type, &ex("FrenchPerson")?)?);
+    assert!(is_inferred(
+        &ex("alicia")?,
+        &ex("hasUncle")?,
+        &ex("charles")?
+    )?);
+    assert!(is_inferred(
+        &ex("alicia")?,
+        &NamedNode::new("http://www.w3.org/2002/07/owl#sameAs")?,
+        &alice
+    )?);
+
+    // The entailments are only computed on demand
+    store.insert(QuadRef::new(
+        &alice,
+        &ex("hasBrother")?,
+        &ex("eve")?,
+        GraphNameRef::DefaultGraph,
+    ))?;
+    assert!(!is_inferred(&alice, &rdf_type, &ex("FrenchPerson")?)?);
+    store.materialize_owl_rl_inference()?;
+    assert!(is_inferred(&alice, &rdf_type, &ex("FrenchPerson")?)?);
+    store.remove(QuadRef::new(
+        &bob,
+        &ex("hasChild")?,
+        &alice,
+        GraphNameRef::DefaultGraph,
+    ))?;
+    store.materialize_owl_rl_inference()?;
+    assert!(!is_inferred(&alice, &ex("hasUncle")?, &ex("charles")?)?);
+    Ok(())
+}
+
+#[test]
+fn test_owl_rl_inference_without_vocabulary_in_store() -> Result<(), Box<dyn Error>> {
+    let inferred = NamedNode::new("http://example.com/inferred")?;
+    let store = Store::new()?.with_owl_rl_inference(OwlRlInference::new(inferred.clone()))?;
+    let person = NamedNode::new("http://example.com/Person")?;
+    let bob = NamedNode::new("http://example.com/bob")?;
+    let name = NamedNode::new("http://schema.org/name")?;
+    store.bulk_loader().load_quads([
+        Quad::new(
+            name.clone(),
+            rdfs::DOMAIN,
+            person.clone(),
+            GraphName::DefaultGraph,
+        ),
+        Quad::new(
+            bob.clone(),
+            name,
+            Literal::from("Bob"),
+            GraphName::DefaultGraph,
+        ),
+    ])?;
+    assert!(store.contains(QuadRef::new(&bob, rdf::TYPE, &person, &inferred))?);
+    Ok(())
+}
+
+#[test]
+fn test_apply_diff() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?;
+    store.load_from_reader(RdfFormat::Turtle, DATA.as_bytes())?;
+    store.insert(QuadRef::new(
+        &BlankNode::new("b")?,
+        NamedNodeRef::new("http://schema.org/name")?,
+        LiteralRef::from("Blank"),
+        NamedNodeRef::new("http://example.com/g")?,
+    ))?;
+    let old = store.iter().collect::<Result<Dataset, _>>()?;
+    let mut new = Dataset::new();
+    new.insert(QuadRef::new(
+        &BlankNode::new("c")?,
+        NamedNodeRef::new("http://schema.org/name")?,
+        LiteralRef::from("New"),
+        GraphNameRef::DefaultGraph,
+    ));
+    let diff = old.diff(&new);
+
+    let other_store = Store::new()?;
+    other_store.extend(old.iter())?;
+    other_store.transaction(|mut transaction| transaction.apply_diff(&diff))?;
+    assert_eq!(other_store.iter().collect::<Result<Dataset, _>>()?, new);
+
+    // The SPARQL update does not rely on the blank node identifiers
+    store.update(spargebra::Update::try_from(&diff).map_err(|()| "unexpected blank node")?)?;
+    let mut result = store.iter().collect::<Result<Dataset, _>>()?;
+    result.canonicalize(CanonicalizationAlgorithm::Unstable);
+    new.canonicalize(CanonicalizationAlgorithm::Unstable);
+    assert_eq!(result, new);
+    Ok(())
+}
+
+fn estimated_name_count(store: &Store) -> Result<usize, Box<dyn Error>> {
+    let (_, explanation) = store.explain_query_opt(
+        "SELECT ?name WHERE { ?s <http://schema.org/name> ?name }",
+        QueryOptions::default(),
+        false,
+    )?;
+    Ok(explanation.plan().estimated_result_count())
+}
+
+#[test]
+fn test_update_statistics() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?;
+    store.load_from_reader(RdfFormat::Turtle, DATA.as_bytes())?;
+    assert_ne!(estimated_name_count(&store)?, 2);
+    store.update_statistics()?;
+    assert_eq!(estimated_name_count(&store)?, 2);
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_update_statistics_on_disk() -> Result<(), Box<dyn Error>> {
+    let dir = TempDir::default();
+    {
+        let store = Store::open(&dir)?;
+        store.load_from_reader(RdfFormat::Turtle, DATA.as_bytes())?;
+        store.update_statistics()?;
+        assert_eq!(estimated_name_count(&store)?, 2);
+    }
+    assert_eq!(estimated_name_count(&Store::open(&dir)?)?, 2);
+    assert_eq!(estimated_name_count(&Store::open_read_only(&dir)?)?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_savepoints() -> Result<(), Box<dyn Error>> {
+    check_savepoints(&Store::new()?)
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_savepoints_on_disk() -> Result<(), Box<dyn Error>> {
+    let dir = TempDir::default();
+    check_savepoints(&Store::open(&dir)?)
+}
+
+fn check_savepoints(store: &Store) -> Result<(), Box<dyn Error>> {
+    let ex = NamedNodeRef::new_unchecked("http://example.com");
+    let g = NamedNodeRef::new_unchecked("http://example.com/g");
+    let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+    let other_quad = QuadRef::new(ex, ex, ex, g);
+    store.insert(quad)?;
+    let changes = store.watch()?;
+
+    store.transaction(|mut t| {
+        let outer = t.savepoint();
+        t.remove(quad)?;
+        t.insert(other_quad)?;
+        let inner = t.savepoint();
+        t.clear()?;
+        assert!(t.is_empty()?);
+        t.rollback_to(&inner)?;
+        assert!(!t.contains(quad)?);
+        assert!(t.contains(other_quad)?);
+        t.rollback_to(&outer)?;
+        assert!(t.contains(quad)?);
+        assert!(!t.contains(other_quad)?);
+        assert!(!t.contains_named_graph(g)?);
+        // Rolled back by the rollback to the outer savepoint
+        t.rollback_to(&inner).unwrap_err();
+        // A newer savepoint does not take the place of the discarded one
+        let newer = t.savepoint();
+        t.insert(other_quad)?;
+        t.rollback_to(&inner).unwrap_err();
+        assert!(t.contains(other_quad)?);
+        t.rollback_to(&newer)?;
+        assert!(!t.contains(other_quad)?);
+        // The savepoint is kept after a rollback
+        t.insert(other_quad)?;
+        t.rollback_to(&outer)?;
+        t.insert_named_graph(g)?;
+        Result::<_, StorageError>::Ok(())
+    })?;
+    // The savepoints of a transaction can't be used by another one
+    let savepoint = store.transaction(|mut t| Result::<_, StorageError>::Ok(t.savepoint()))?;
+    store.transaction(|mut t| {
+        let _own = t.savepoint();
+        t.rollback_to(&savepoint).unwrap_err();
+        Result::<_, StorageError>::Ok(())
+    })?;
+    assert!(store.contains(quad)?);
+    assert!(!store.contains(other_quad)?);
+    assert!(store.contains_named_graph(g)?);
+    assert_eq!(store.len()?, 1);
+    // Only the changes kept are reported
+    changes.try_recv().unwrap_err();
+    store.validate()?;
+    Ok(())
+}
+
+#[test]
+fn test_ttl() -> Result<(), Box<dyn Error>> {
+    check_ttl(&Store::new()?)
+}
+
+#[test]
+fn test_ttl_graph_quota() -> Result<(), Box<dyn Error>> {
+    let ex = NamedNodeRef::new_unchecked("http://example.com");
+    let g = NamedNodeRef::new_unchecked("http://example.com/g");
+    let kept = QuadRef::new(ex, ex, ex, g);
+    let rejected = QuadRef::new(ex, ex, g, g);
+    let store = Store::new()?.with_graph_quota(g, GraphQuota::default().with_max_quads(1));
+    store.insert(kept)?;
+    store.transaction(|mut t| {
+        assert!(matches!(
+            t.insert_with_ttl(rejected, Duration::ZERO),
+            Err(StorageError::GraphQuotaExceeded(_))
+        ));
+        Ok::<_, StorageError>(())
+    })?;
+    // The expiration time of the rejected quad has not been recorded
+    store.bulk_loader().load_quads([rejected.into_owned()])?;
+    assert!(store.contains(rejected)?);
+    assert_eq!(store.purge_expired()?, 0);
+    assert_eq!(store.len()?, 2);
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_ttl_on_disk() -> Result<(), Box<dyn Error>> {
+    let ex = NamedNodeRef::new_unchecked("http://example.com");
+    let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+    let dir = TempDir::default();
+    check_ttl(&Store::open(&dir)?)?;
+    {
+        let store = Store::open(&dir)?;
+        store.transaction(|mut t| t.insert_with_ttl(quad, Duration::ZERO))?;
+    }
+    // The expiration times are persisted
+    let store = Store::open(&dir)?;
+    assert!(!store.contains(quad)?);
+    store.optimize()?;
+    assert_eq!(store.purge_expired()?, 0);
+    store.validate()?;
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_encryption() -> Result<(), Box<dyn Error>> {
+    let ex = NamedNodeRef::new_unchecked("http://example.com");
+    let secret = LiteralRef::new_simple_literal("a secret that must not be written in clear");
+    let quad = QuadRef::new(ex, ex, secret, GraphNameRef::DefaultGraph);
+    let old_key = EncryptionKey::random();
+    let new_key = EncryptionKey::random();
+    let dir = TempDir::default();
+    let backup_dir = TempDir::default();
+    {
+        let store = Store::open_with_options(
+            &dir,
+            StoreOptions::default().with_encryption_key(old_key.clone()),
+        )?;
+        store.insert(quad)?;
+        // Incremental backups would copy the encrypted files without their encryption prefix
+        store
+            .incremental_backup(&BackupRepository::open(&backup_dir)?)
+            .unwrap_err();
+    }
+    for entry in read_dir(&dir)? {
+        let path = entry?.path();
+        if path.is_file() {
+            assert!(
+                !read(&path)?
+                    .windows(secret.value().len())
+                    .any(|w| w == secret.value().as_bytes()),
+                "{} is not encrypted",
+                path.display()
+            );
+        }
+    }
+    assert!(Store::open(&dir).is_err());
+    Store::rotate_encryption_key(&dir, &old_key, &new_key)?;
+    assert!(
+        Store::open_with_options(&dir, StoreOptions::default().with_encryption_key(old_key))
+            .is_err()
+    );
+    let store =
+        Store::open_with_options(&dir, StoreOptions::default().with_encryption_key(new_key))?;
+    assert!(store.contains(quad)?);
+    store.validate()?;
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
+fn test_compact_term_ids() -> Result<(), Box<dyn Error>> {
+    let dir = TempDir::default();
+    {
+        let store =
+            Store::open_with_options(&dir, StoreOptions::default().with_compact_term_ids())?;
+        store
+            .bulk_loader()
+            .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())?;
+        store.load_from_reader(RdfFormat::TriG, GRAPH_DATA.as_bytes())?;
+        store.validate()?;
+    }
+    // The identifier size is kept when the store is opened again without the option
+    let store = Store::open(&dir)?;
+    for q in quads(GraphNameRef::DefaultGraph) {
+        assert!(store.contains(q)?);
+    }
+    for q in quads(NamedNodeRef::new_unchecked(
+        "http://www.wikidata.org/wiki/Special:EntityData/Q90",
+    )) {
+        assert!(store.contains(q)?);
+    }
+    let QueryResults::Boolean(result) = store.query(
+        "ASK { <http://www.wikidata.org/entity/Q90> <http://schema.org/name> \"la ville lumi\u{E8}re\"@fr }",
+    )?
+    else {
+        return Err("Boolean expected".into());
+    };
+    assert!(result);
+    let graph = NamedNodeRef::new_unchecked("http://www.wikidata.org/wiki/Special:EntityData/Q90");
+    assert!(store.contains_named_graph(graph)?);
+    let paris = NamedNodeRef::new_unchecked("http://www.wikidata.org/entity/Q90");
+    assert_eq!(
+        store
+            .quads_for_pattern(Some(paris.into()), None, None, Some(graph.into()))
+            .count(),
+        NUMBER_OF_TRIPLES
+    );
+    store.insert(QuadRef::new(
+        NamedNodeRef::new_unchecked("http://example.com/s"),
+        NamedNodeRef::new_unchecked("http://example.com/p"),
+        LiteralRef::new_simple_literal("a new string inserted after reopening"),
+        GraphNameRef::DefaultGraph,
+    ))?;
+    assert_eq!(store.len()?, 2 * NUMBER_OF_TRIPLES + 1);
+    store.validate()?;
+    Ok(())
+}
+
+fn check_ttl(store: &Store) -> Result<(), Box<dyn Error>> {
+    let ex = NamedNodeRef::new_unchecked("http://example.com");
+    let g = NamedNodeRef::new_unchecked("http://example.com/g");
+    let expired = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+    let alive = QuadRef::new(ex, ex, g, GraphNameRef::DefaultGraph);
+    let cleared = QuadRef::new(ex, ex, ex, g);
+
+    store.transaction(|mut t| {
+        assert!(t.insert_with_ttl(expired, Duration::ZERO)?);
+        assert!(t.insert_with_ttl(alive, Duration::from_secs(3600))?);
+        t.insert_with_ttl(cleared, Duration::ZERO)
+    })?;
+    assert!(!store.contains(expired)?);
+    assert!(store.contains(alive)?);
+    assert_eq!(store.len()?, 1);
+    assert_eq!(
+        store.iter().collect::<Result<Vec<_>, _>>()?,
+        [alive.into_owned()]
+    );
+
+    // Removing an expired quad is a no-op
+    assert!(!store.remove(expired)?);
+    store.transaction(|mut t| t.insert_with_ttl(expired, Duration::ZERO))?;
+    // A regular insertion makes the quad permanent
+    assert!(store.insert(expired)?);
+    assert!(store.contains(expired)?);
+    // Clearing the graph also forgets the expiration time
+    store.clear_graph(g)?;
+    assert!(store.insert(cleared)?);
+    assert!(store.contains(cleared)?);
+
+    assert_eq!(store.purge_expired()?, 0);
+    store.transaction(|mut t| t.insert_with_ttl(cleared, Duration::ZERO))?;
+    assert_eq!(store.purge_expired()?, 1);
+    assert_eq!(store.len()?, 2);
+    store.remove(alive)?;
+    store.remove(expired)?;
+    assert!(store.is_empty()?);
+    store.validate()?;
+    Ok(())
+}
+
+#[test]
+fn test_watch() -> Result<(), Box<dyn Error>> {
+    let ex = NamedNodeRef::new_unchecked("http://example.com");
+    let g = NamedNodeRef::new_unchecked("http://example.com/g");
+    let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+    let other_quad = QuadRef::new(ex, ex, ex, g);
+    let store = Store::new()?;
+    let changes = store.clone().watch()?;
+
+    store.transaction(|mut t| {
+        t.insert(quad)?;
+        t.insert(other_quad)?;
+        t.insert(quad)
+    })?;
+    assert_eq!(
+        changes.try_recv()?,
+        [
+            StoreEvent::Inserted(quad.into_owned()),
+            StoreEvent::Inserted(other_quad.into_owned())
+        ]
+    );
+
+    // Rolled back transactions and transactions without changes are not reported
+    store
+        .transaction(|mut t| {
+            t.remove(quad)?;
+            Err::<(), _>(StorageError::Other("rollback".into()))
+        })
+        .unwrap_err();
+    store.remove(QuadRef::new(g, g, g, g))?;
+    changes.try_recv().unwrap_err();
+
+    store.update("CLEAR ALL")?;
+    let mut removed = changes.try_recv()?;
+    removed.sort_by_key(|event| format!("{event:?}"));
+    assert_eq!(
+        removed,
+        [
+            StoreEvent::Removed(quad.into_owned()),
+            StoreEvent::Removed(other_quad.into_owned())
+        ]
+    );
+
+    // The bulk loads are not reported
+    store.bulk_loader().load_quads([quad.into_owned()])?;
+    changes.try_recv().unwrap_err();
+
+    drop(changes);
+    store.insert(other_quad)?;
+    Ok(())
+}
+
+#[test]
+fn test_query_results_cache() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?.with_query_results_cache(10);
+    store.load_from_reader(RdfFormat::Turtle, DATA.as_bytes())?;
+    let evaluations = Arc::new(AtomicUsize::new(0));
+    let count_names = |options: QueryOptions| -> Result<usize, Box<dyn Error>> {
+        let evaluations = Arc::clone(&evaluations);
+        let QueryResults::Solutions(solutions) = store.query_opt(
+            "SELECT ?name (<http://example.com/count>(?name) AS ?c) WHERE { ?s <http://schema.org/name> ?name }",
+            options.with_custom_function(NamedNode::new("http://example.com/count")?, move |_| {
+                evaluations.fetch_add(1, Ordering::Relaxed);
+                None
+            }),
+        )?
+        else {
+            return Err("Solutions expected".into());
+        };
+        Ok(solutions.collect::<Result<Vec<_>, _>>()?.len())
+    };
+    assert_eq!(count_names(QueryOptions::default())?, 2);
+    assert_eq!(evaluations.load(Ordering::Relaxed), 2);
+    assert_eq!(count_names(QueryOptions::default())?, 2);
+    assert_eq!(
+        evaluations.load(Ordering::Relaxed),
+        2,
+        "the query must not be evaluated again"
+    );
+
+    // The dataset is part of the key
+    assert_eq!(
+        count_names(
+            QueryOptions::default().with_default_graphs([NamedNode::new("http://example.com/g")?])
+        )?,
+        0
+    );
+
+    // The writes invalidate the cache
+    store.update(
+        "PREFIX schema: <http://schema.org/> INSERT DATA { <http://example.com/lyon> schema:name \"Lyon\" }",
+    )?;
+    assert_eq!(count_names(QueryOptions::default())?, 3);
+    assert_eq!(evaluations.load(Ordering::Relaxed), 5);
+    store.bulk_loader().load_quads([Quad::new(
+        NamedNode::new("http://example.com/lille")?,
+        NamedNode::new("http://schema.org/name")?,
+        Literal::from("Lille"),
+        GraphName::DefaultGraph,
+    )])?;
+    assert_eq!(count_names(QueryOptions::default())?, 4);
+    store.clone().clear()?;
+    assert_eq!(count_names(QueryOptions::default())?, 0);
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "rdf-12")]
+fn test_triple_term_functions() -> Result<(), Box<dyn Error>> {
+    let store = Store::new()?;
+    store.update(
+        "PREFIX ex: <http://example.com/> INSERT DATA { ex:s ex:p ex:o ~ ex:r {| ex:source ex:g |} }",
+    )?;
+    let triple = Term::from(Triple::new(
+        NamedNode::new("http://example.com/s")?,
+        NamedNode::new("http://example.com/p")?,
+        NamedNode::new("http://example.com/o")?,
+    ));
+
+    // Destructuring
+    let QueryResults::Solutions(mut solutions) = store.query(
+        "PREFIX ex: <http://example.com/> PREFIX rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> SELECT ?t (SUBJECT(?t) AS ?s) (PREDICATE(?t) AS ?p) (OBJECT(?t) AS ?o) WHERE { ex:r rdf:reifies ?t FILTER(isTRIPLE(?t)) }",
+    )?
+    else {
+        return Err("Solutions expected".into());
+    };
+    let solution = solutions.next().ok_or("A solution is expected")??;
+    assert_eq!(solution.get("t"), Some(&triple));
+    assert_eq!(
+        solution.get("s"),
+        Some(&NamedNode::new("http://example.com/s")?.into())
+    );
+    assert_eq!(
+        solution.get("p"),
+        Some(&NamedNode::new("http://example.com/p")?.into())
+    );
+    assert_eq!(
+        solution.get("o"),
+        Some(&NamedNode::new("http://example.com/o")?.into())
+    );
+    assert!(solutions.next().is_none());
+
+    // Construction
+    assert!(matches!(
+        store.query(
+            "PREFIX ex: <http://example.com/> PREFIX rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> ASK { ?r rdf:reifies ?t FILTER(?t = TRIPLE(ex:s, ex:p, ex:o) && ?t = <<(ex:s ex:p ex:o)>>) }"
+        )?,
+        QueryResults::Boolean(true)
+    ));
+
+    // Reified triple syntax
+    let QueryResults::Solutions(solutions) = store.query(
+        "PREFIX ex: <http://example.com/> SELECT ?r ?g WHERE { << ex:s ex:p ex:o ~ ?r >> ex:source ?g }",
+    )?
+    else {
+        return Err("Solutions expected".into());
+    };
+    let solutions = solutions.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(solutions.len(), 1);
+    assert_eq!(
+        solutions[0].get("r"),
+        Some(&NamedNode::new("http://example.com/r")?.into())
+    );
+    assert_eq!(
+        solutions[0].get("g"),
+        Some(&NamedNode::new("http://example.com/g")?.into())
+    );
+    Ok(())
+}
+
 #[cfg(all(not(target_family = "wasm"), feature = "rocksdb"))]
 struct TempDir(PathBuf);
 
@@ -0,0 +1,53 @@
+
+use codspeed_criterion_compat::{Criterion, Throughput, criterion_group, criterion_main};
+use oxjsonld::{JsonLdParser, JsonLdProfile};
+use std::fmt::Write;
+
+/// A document with a lot of node objects using the same keys and types
+fn uniform_document(count: usize) -> Vec<u8> {
+    let mut data = String::from(
+        r#"{"@context":{"@vocab":"http://schema.org/","ex":"http://example.com/","knows":{"@type":"@id"}},"@graph":["#,
+    );
+    for i in 0..count {
+        if i > 0 {
+            data.push(',');
+        }
+        write!(
+            data,
+            r#"{{"@type":["Person","ex:Agent"],"@id":"ex:{i}","name":"Person {i}","ex:age":{},"knows":"ex:{}"}}"#,
+            i % 100,
+            (i + 1) % count
+        )
+        .unwrap();
+    }
+    data.push_str("]}");
+    data.into_bytes()
+}
+
+fn parse_uniform_document(c: &mut Criterion) {
+    let data = uniform_document(10_000);
+    let mut group = c.benchmark_group("oxjsonld");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("parse uniform document", |b| {
+        b.iter(|| {
+            for quad in JsonLdParser::new().for_slice(&data) {
+                quad.unwrap();
+            }
+        })
+    });
+    group.bench_function("parse uniform document with streaming", |b| {
+        b.iter(|| {
+            for quad in JsonLdParser::new()
+                .with_profile(JsonLdProfile::Streaming)
+                .for_slice(&data)
+            {
+                quad.unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(parser, parse_uniform_document);
+
+criterion_main!(parser);
@@ -1,23 +1,16 @@
 use crate::error::{JsonLdErrorCode, JsonLdSyntaxError};
+use crate::loader::JsonLdDocumentLoader;
 use crate::{JsonLdProcessingMode, JsonLdProfile, JsonLdProfileSet};
 use json_event_parser::{JsonEvent, JsonSyntaxError, SliceJsonParser};
 use oxiri::Iri;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
-use std::error::Error;
-use std::panic::{RefUnwindSafe, UnwindSafe};
 use std::slice;
 use std::sync::{Arc, Mutex};
 
-type LoadDocumentCallback = dyn Fn(
-        &str,
-        &JsonLdLoadDocumentOptions,
-    ) -> Result<JsonLdRemoteDocument, Box<dyn Error + Send + Sync>>
-    + Send
-    + Sync
-    + UnwindSafe
-    + RefUnwindSafe;
+/// The already loaded remote contexts with their document URL
+pub type RemoteContextCache = Mutex<HashMap<String, (Option<Iri<String>>, JsonNode)>>;
 
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub enum JsonNode {
@@ -75,8 +68,8 @@ pub struct JsonLdContextProcessor {
     pub processing_mode: JsonLdProcessingMode,
     pub lenient: bool, // Custom option to ignore invalid base IRIs
     pub max_context_recursion: usize,
-    pub remote_context_cache: Arc<Mutex<HashMap<String, (Option<Iri<String>>, JsonNode)>>>,
-    pub load_document_callback: Option<Arc<LoadDocumentCallback>>,
+    pub remote_context_cache: Arc<RemoteContextCache>,
+    pub document_loader: Option<Arc<dyn JsonLdDocumentLoader>>,
 }
 
 /// Used to pass various options to the LoadDocumentCallback.
@@ -1381,13 +1374,13 @@ impl JsonLdContextProcessor {
         }
 
         // 5.2.5)
-        let Some(load_document_callback) = &self.load_document_callback else {
+        let Some(document_loader) = &self.document_loader else {
             return Err(JsonLdSyntaxError::msg_and_code(
-                "No LoadDocumentCallback has been set to load remote contexts",
+                "No document loader has been set to load remote contexts",
                 JsonLdErrorCode::LoadingRemoteContextFailed,
             ));
         };
-        let context_document = match load_document_callback(
+        let context_document = match document_loader.load_document(
             url,
             &JsonLdLoadDocumentOptions {
                 request_profile: JsonLdProfile::Context.into(),
@@ -1466,6 +1459,33 @@ fn is_keyword(value: &str) -> bool {
     )
 }
 
+/// Parses a JSON document into owned events, without the final [`JsonEvent::Eof`]
+pub fn json_slice_to_events(data: &[u8]) -> Result<Vec<JsonEvent<'static>>, JsonSyntaxError> {
+    let mut parser = SliceJsonParser::new(data);
+    let mut events = Vec::new();
+    loop {
+        match parser.parse_next()? {
+            JsonEvent::Eof => return Ok(events),
+            event => events.push(into_owned_event(event)),
+        }
+    }
+}
+
+pub fn into_owned_event(event: JsonEvent<'_>) -> JsonEvent<'static> {
+    match event {
+        JsonEvent::String(value) => JsonEvent::String(value.into_owned().into()),
+        JsonEvent::Number(value) => JsonEvent::Number(value.into_owned().into()),
+        JsonEvent::Boolean(value) => JsonEvent::Boolean(value),
+        JsonEvent::Null => JsonEvent::Null,
+        JsonEvent::StartArray => JsonEvent::StartArray,
+        JsonEvent::EndArray => JsonEvent::EndArray,
+        JsonEvent::StartObject => JsonEvent::StartObject,
+        JsonEvent::EndObject => JsonEvent::EndObject,
+        JsonEvent::ObjectKey(key) => JsonEvent::ObjectKey(key.into_owned().into()),
+        JsonEvent::Eof => JsonEvent::Eof,
+    }
+}
+
 fn json_slice_to_node(data: &[u8]) -> Result<JsonNode, JsonSyntaxError> {
     let mut parser = SliceJsonParser::new(data);
     json_node_from_events(std::iter::from_fn(|| match parser.parse_next() {
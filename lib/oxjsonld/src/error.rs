@@ -117,6 +117,8 @@ pub enum JsonLdErrorCode {
     ContextOverflow,
     /// A cycle in IRI mappings has been detected.
     CyclicIriMapping,
+    /// An invalid value for @embed has been found in a frame.
+    InvalidEmbedValue,
     /// An @id entry was encountered whose value was not a string.
     InvalidIdValue,
     /// An invalid value for @import has been found.
@@ -150,6 +152,8 @@ pub enum JsonLdErrorCode {
     InvalidContextNullification,
     /// The value of the default language is not a string or null and thus invalid.
     InvalidDefaultLanguage,
+    /// A frame is not valid.
+    InvalidFrame,
     /// A local context contains a term that has an invalid or missing IRI mapping.
     InvalidIriMapping,
     /// An invalid JSON literal was detected.
@@ -205,6 +209,9 @@ pub enum JsonLdErrorCode {
     IriConfusedWithPrefix,
     /// A keyword redefinition has been detected.
     KeywordRedefinition,
+    /// A list of lists was detected.
+    /// Lists of lists are only supported in JSON-LD 1.1.
+    ListOfLists,
     /// The document could not be loaded or parsed as JSON.
     LoadingDocumentFailed,
     /// There was a problem encountered loading a remote context.
@@ -222,6 +229,7 @@ impl fmt::Display for JsonLdErrorCode {
             Self::ConflictingIndexes => "conflicting indexes",
             Self::ContextOverflow => "context overflow",
             Self::CyclicIriMapping => "cyclic IRI mapping",
+            Self::InvalidEmbedValue => "invalid @embed value",
             Self::InvalidIdValue => "invalid @id value",
             Self::InvalidImportValue => "invalid @import value",
             Self::InvalidIncludedValue => "invalid @included value",
@@ -238,6 +246,7 @@ impl fmt::Display for JsonLdErrorCode {
             Self::InvalidContextEntry => "invalid context entry",
             Self::InvalidContextNullification => "invalid context nullification",
             Self::InvalidDefaultLanguage => "invalid default language",
+            Self::InvalidFrame => "invalid frame",
             Self::InvalidIriMapping => "invalid IRI mapping",
             Self::InvalidJsonLiteral => "invalid JSON literal",
             Self::InvalidKeywordAlias => "invalid keyword alias",
@@ -262,6 +271,7 @@ impl fmt::Display for JsonLdErrorCode {
             Self::InvalidVocabMapping => "invalid vocab mapping",
             Self::IriConfusedWithPrefix => "IRI confused with prefix",
             Self::KeywordRedefinition => "keyword redefinition",
+            Self::ListOfLists => "list of lists",
             Self::LoadingDocumentFailed => "loading document failed",
             Self::LoadingRemoteContextFailed => "loading remote context failed",
             Self::ProcessingModeConflict => "processing mode conflict",
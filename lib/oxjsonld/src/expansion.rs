@@ -1,17 +1,19 @@
 use crate::context::{
-    JsonLdContext, JsonLdContextProcessor, JsonLdLoadDocumentOptions, JsonLdRemoteDocument,
-    has_keyword_form, json_node_from_events,
+    JsonLdContext, JsonLdContextProcessor, JsonNode, RemoteContextCache, has_keyword_form,
+    json_node_from_events,
 };
 use crate::error::JsonLdErrorCode;
+use crate::loader::JsonLdDocumentLoader;
 use crate::profile::JsonLdProcessingMode;
 use crate::{JsonLdSyntaxError, MAX_CONTEXT_RECURSION};
 use json_event_parser::JsonEvent;
 use oxiri::Iri;
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::error::Error;
-use std::panic::{RefUnwindSafe, UnwindSafe};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+
+/// Maximal number of IRI expansions memoized for each active context
+const MAX_EXPANDED_IRI_CACHE_SIZE: usize = 1024;
 
 pub enum JsonLdEvent {
     StartObject {
@@ -28,6 +30,7 @@ pub enum JsonLdEvent {
         value: JsonLdValue,
         r#type: Option<String>,
         language: Option<String>,
+        direction: Option<&'static str>,
     },
     StartGraph,
     EndGraph,
@@ -41,6 +44,8 @@ pub enum JsonLdValue {
     String(String),
     Number(String),
     Boolean(bool),
+    /// A `@json` value in its canonical serialization
+    Json(String),
 }
 
 enum JsonLdExpansionState {
@@ -102,18 +107,35 @@ enum JsonLdExpansionState {
         r#type: Option<String>,
         value: Option<JsonLdValue>,
         language: Option<String>,
+        direction: Option<&'static str>,
     },
     ValueValue {
         r#type: Option<String>,
         language: Option<String>,
+        direction: Option<&'static str>,
     },
     ValueLanguage {
         r#type: Option<String>,
         value: Option<JsonLdValue>,
+        direction: Option<&'static str>,
     },
     ValueType {
         value: Option<JsonLdValue>,
         language: Option<String>,
+        direction: Option<&'static str>,
+    },
+    ValueDirection {
+        r#type: Option<String>,
+        value: Option<JsonLdValue>,
+        language: Option<String>,
+    },
+    /// Buffers a `@json` value
+    JsonLiteral {
+        buffer: Vec<JsonEvent<'static>>,
+        depth: usize,
+        in_value_object: bool,
+        language: Option<String>,
+        direction: Option<&'static str>,
     },
     Index,
     Graph,
@@ -125,9 +147,12 @@ enum JsonLdExpansionState {
     IndexContainer {
         active_property: Option<String>,
     },
-    LanguageContainer,
+    LanguageContainer {
+        direction: Option<&'static str>,
+    },
     LanguageContainerValue {
         language: String,
+        direction: Option<&'static str>,
         is_array: bool,
     },
     Skip {
@@ -138,7 +163,7 @@ enum JsonLdExpansionState {
 /// Applies the [Expansion Algorithm](https://www.w3.org/TR/json-ld-api/#expansion-algorithms)
 pub struct JsonLdExpansionConverter {
     state: Vec<JsonLdExpansionState>,
-    context: Vec<(JsonLdContext, usize)>,
+    context: Vec<(JsonLdContext, usize, ExpandedIriCache)>,
     is_end: bool,
     streaming: bool,
     lenient: bool,
@@ -153,6 +178,8 @@ impl JsonLdExpansionConverter {
         streaming: bool,
         lenient: bool,
         processing_mode: JsonLdProcessingMode,
+        remote_context_cache: Arc<RemoteContextCache>,
+        document_loader: Option<Arc<dyn JsonLdDocumentLoader>>,
     ) -> Self {
         Self {
             state: vec![JsonLdExpansionState::Element {
@@ -161,7 +188,11 @@ impl JsonLdExpansionConverter {
                 container: &[],
                 reverse: false,
             }],
-            context: vec![(JsonLdContext::new_empty(base_url.clone()), 0)],
+            context: vec![(
+                JsonLdContext::new_empty(base_url.clone()),
+                0,
+                ExpandedIriCache::default(),
+            )],
             is_end: false,
             streaming,
             lenient,
@@ -170,8 +201,8 @@ impl JsonLdExpansionConverter {
                 processing_mode,
                 lenient,
                 max_context_recursion: MAX_CONTEXT_RECURSION,
-                remote_context_cache: Arc::new(Mutex::new(HashMap::new())), /* TODO: share in the parser */
-                load_document_callback: None,
+                remote_context_cache,
+                document_loader,
             },
         }
     }
@@ -180,19 +211,8 @@ impl JsonLdExpansionConverter {
         self.is_end
     }
 
-    pub fn with_load_document_callback(
-        mut self,
-        callback: impl Fn(
-            &str,
-            &JsonLdLoadDocumentOptions,
-        ) -> Result<JsonLdRemoteDocument, Box<dyn Error + Send + Sync>>
-        + Send
-        + Sync
-        + UnwindSafe
-        + RefUnwindSafe
-        + 'static,
-    ) -> Self {
-        self.context_processor.load_document_callback = Some(Arc::new(callback));
+    pub fn with_document_loader(mut self, document_loader: Arc<dyn JsonLdDocumentLoader>) -> Self {
+        self.context_processor.document_loader = Some(document_loader);
         self
     }
 
@@ -220,6 +240,24 @@ impl JsonLdExpansionConverter {
                 container,
                 reverse,
             } => {
+                if !is_array && self.has_json_type_mapping(active_property.as_deref()) {
+                    // 13.7.2)
+                    if reverse {
+                        errors.push(JsonLdSyntaxError::msg_and_code(
+                            "Literals are not allowed inside of reverse properties",
+                            JsonLdErrorCode::InvalidReversePropertyValue,
+                        ))
+                    }
+                    self.state.push(JsonLdExpansionState::JsonLiteral {
+                        buffer: Vec::new(),
+                        depth: 0,
+                        in_value_object: false,
+                        language: None,
+                        direction: None,
+                    });
+                    self.convert_event(event, results, errors);
+                    return;
+                }
                 match event {
                     JsonEvent::Null => {
                         // 1)
@@ -268,6 +306,7 @@ impl JsonLdExpansionConverter {
                                 container,
                                 reverse,
                             });
+                            self.check_not_list_of_lists(errors);
                         }
                         if container.contains(&"@list") {
                             if reverse {
@@ -310,10 +349,17 @@ impl JsonLdExpansionConverter {
                                 .push(JsonLdExpansionState::IndexContainer { active_property });
                             return;
                         } else if container.contains(&"@language") {
-                            self.state.push(JsonLdExpansionState::LanguageContainer);
+                            // 13.7.2)
+                            let direction = self.direction_mapping(active_property.as_deref());
+                            self.state
+                                .push(JsonLdExpansionState::LanguageContainer { direction });
                             return;
                         }
                         self.push_same_context();
+                        if self.streaming {
+                            // We can't know yet if it is a node object, we assume it is
+                            self.enter_node_object(active_property.as_deref(), true, errors);
+                        }
                         self.state.push(if self.streaming {
                             JsonLdExpansionState::ObjectOrContainerStartStreaming {
                                 active_property,
@@ -383,6 +429,8 @@ impl JsonLdExpansionConverter {
                     let mut id_data = None;
                     let mut graph_data = Vec::new();
                     let mut other_data = Vec::with_capacity(buffer.len());
+                    let mut has_value = false;
+                    let key_count = buffer.len();
                     for (key, value) in buffer {
                         let expanded = self.expand_iri(key.as_str().into(), false, true, errors);
                         match expanded.as_deref() {
@@ -407,9 +455,15 @@ impl JsonLdExpansionConverter {
                             Some("@graph") => {
                                 graph_data.push((key, value));
                             }
+                            Some("@value") => {
+                                has_value = true;
+                                other_data.push((key, value));
+                            }
                             _ => other_data.push((key, value)),
                         }
                     }
+                    let is_node_object = !(has_value || key_count == 1 && id_data.is_some());
+                    self.enter_node_object(active_property.as_deref(), is_node_object, errors);
                     self.state
                         .push(JsonLdExpansionState::ObjectOrContainerStartStreaming {
                             active_property,
@@ -472,6 +526,7 @@ impl JsonLdExpansionConverter {
                             }
                             "@list" => {
                                 if active_property.is_some() {
+                                    self.check_not_list_of_lists(errors);
                                     if reverse {
                                         errors.push(JsonLdSyntaxError::msg_and_code(
                                             "Lists are not allowed inside of reverse properties",
@@ -626,7 +681,7 @@ impl JsonLdExpansionConverter {
                                     reverse,
                                 });
                             }
-                            "@value" | "@language" => {
+                            "@value" | "@language" | "@direction" => {
                                 if types.len() > 1 {
                                     errors.push(JsonLdSyntaxError::msg_and_code(
                                         "Only a single @type is allowed when @value is present",
@@ -649,6 +704,7 @@ impl JsonLdExpansionConverter {
                                     r#type: types.into_iter().next(),
                                     value: None,
                                     language: None,
+                                    direction: None,
                                 });
                                 self.convert_event(JsonEvent::ObjectKey(key), results, errors);
                             }
@@ -689,7 +745,9 @@ impl JsonLdExpansionConverter {
                                 self.state.push(JsonLdExpansionState::Index);
                             }
                             _ => {
-                                results.push(JsonLdEvent::StartObject { types });
+                                results.push(JsonLdEvent::StartObject {
+                                    types: node_types(types, errors),
+                                });
                                 let has_emitted_id = id.is_some();
                                 if let Some(id) = id {
                                     results.push(JsonLdEvent::Id(id));
@@ -714,7 +772,9 @@ impl JsonLdExpansionConverter {
                     }
                 }
                 JsonEvent::EndObject => {
-                    results.push(JsonLdEvent::StartObject { types });
+                    results.push(JsonLdEvent::StartObject {
+                        types: node_types(types, errors),
+                    });
                     if let Some(id) = id {
                         results.push(JsonLdEvent::Id(id));
                     }
@@ -757,8 +817,11 @@ impl JsonLdExpansionConverter {
                     }
                     JsonEvent::String(value) => {
                         // 13.4.4.4)
-                        if let Some(iri) = self.expand_iri(value, true, true, errors) {
-                            if has_keyword_form(&iri) {
+                        if let Some(iri) = self.expand_iri(value.clone(), true, true, errors) {
+                            if iri == "@json" && self.is_json_allowed() {
+                                // Only valid for value objects, checked later
+                                types.push(iri.into());
+                            } else if has_keyword_form(&iri) {
                                 errors.push(JsonLdSyntaxError::msg(format!(
                                     "{iri} is not a valid value for @type"
                                 )));
@@ -766,6 +829,8 @@ impl JsonLdExpansionConverter {
                                 types.push(iri.into());
                             }
                         }
+                        // 11.2)
+                        self.apply_type_scoped_context(&value, errors);
                         if is_array {
                             self.state.push(JsonLdExpansionState::ObjectType {
                                 types,
@@ -1137,6 +1202,7 @@ impl JsonLdExpansionConverter {
                 r#type,
                 value,
                 language,
+                direction,
             } => {
                 match event {
                     JsonEvent::ObjectKey(key) => {
@@ -1152,6 +1218,7 @@ impl JsonLdExpansionConverter {
                                             r#type,
                                             value,
                                             language,
+                                            direction,
                                         });
                                         self.state
                                             .push(JsonLdExpansionState::Skip { is_array: false });
@@ -1159,6 +1226,7 @@ impl JsonLdExpansionConverter {
                                         self.state.push(JsonLdExpansionState::ValueValue {
                                             r#type,
                                             language,
+                                            direction,
                                         });
                                     }
                                 }
@@ -1172,6 +1240,7 @@ impl JsonLdExpansionConverter {
                                             r#type,
                                             value,
                                             language,
+                                            direction,
                                         });
                                         self.state
                                             .push(JsonLdExpansionState::Skip { is_array: false });
@@ -1179,6 +1248,29 @@ impl JsonLdExpansionConverter {
                                         self.state.push(JsonLdExpansionState::ValueLanguage {
                                             r#type,
                                             value,
+                                            direction,
+                                        });
+                                    }
+                                }
+                                "@direction" => {
+                                    if direction.is_some() {
+                                        errors.push(JsonLdSyntaxError::msg_and_code(
+                                            "@direction cannot be set multiple times",
+                                            JsonLdErrorCode::CollidingKeywords,
+                                        ));
+                                        self.state.push(JsonLdExpansionState::Value {
+                                            r#type,
+                                            value,
+                                            language,
+                                            direction,
+                                        });
+                                        self.state
+                                            .push(JsonLdExpansionState::Skip { is_array: false });
+                                    } else {
+                                        self.state.push(JsonLdExpansionState::ValueDirection {
+                                            r#type,
+                                            value,
+                                            language,
                                         });
                                     }
                                 }
@@ -1198,6 +1290,7 @@ impl JsonLdExpansionConverter {
                                             r#type,
                                             value,
                                             language,
+                                            direction,
                                         });
                                         self.state
                                             .push(JsonLdExpansionState::Skip { is_array: false });
@@ -1205,6 +1298,7 @@ impl JsonLdExpansionConverter {
                                         self.state.push(JsonLdExpansionState::ValueType {
                                             value,
                                             language,
+                                            direction,
                                         });
                                     }
                                 }
@@ -1217,6 +1311,7 @@ impl JsonLdExpansionConverter {
                                         r#type,
                                         value,
                                         language,
+                                        direction,
                                     });
                                     self.state
                                         .push(JsonLdExpansionState::Skip { is_array: false });
@@ -1226,6 +1321,7 @@ impl JsonLdExpansionConverter {
                                         r#type,
                                         value,
                                         language,
+                                        direction,
                                     });
                                     self.state.push(JsonLdExpansionState::Index);
                                 }
@@ -1240,6 +1336,7 @@ impl JsonLdExpansionConverter {
                                         r#type,
                                         value,
                                         language,
+                                        direction,
                                     });
                                     self.state
                                         .push(JsonLdExpansionState::Skip { is_array: false });
@@ -1250,6 +1347,7 @@ impl JsonLdExpansionConverter {
                                         r#type,
                                         value,
                                         language,
+                                        direction,
                                     });
                                     self.state
                                         .push(JsonLdExpansionState::Skip { is_array: false });
@@ -1260,12 +1358,19 @@ impl JsonLdExpansionConverter {
                                 r#type,
                                 value,
                                 language,
+                                direction,
                             });
                             self.state
                                 .push(JsonLdExpansionState::Skip { is_array: false });
                         }
                     }
                     JsonEvent::EndObject => {
+                        let (value, r#type) = if r#type.as_deref() == Some("@json") {
+                            // The value has been read before @type
+                            (value.map(to_json_value), None)
+                        } else {
+                            (value, r#type)
+                        };
                         if let Some(value) = value {
                             let mut is_valid = true;
                             if language.is_some() && r#type.is_some() {
@@ -1282,6 +1387,20 @@ impl JsonLdExpansionConverter {
                                 ));
                                 is_valid = false;
                             }
+                            if direction.is_some() && r#type.is_some() {
+                                errors.push(JsonLdSyntaxError::msg_and_code(
+                                    "@type and @direction cannot be used together",
+                                    JsonLdErrorCode::InvalidValueObject,
+                                ));
+                                is_valid = false;
+                            }
+                            if direction.is_some() && !matches!(value, JsonLdValue::String(_)) {
+                                errors.push(JsonLdSyntaxError::msg_and_code(
+                                    "@direction can be used only on a string @value",
+                                    JsonLdErrorCode::InvalidLanguageTaggedValue,
+                                ));
+                                is_valid = false;
+                            }
                             if let Some(r#type) = &r#type {
                                 if r#type.starts_with("_:") {
                                     errors.push(JsonLdSyntaxError::msg_and_code(
@@ -1304,6 +1423,7 @@ impl JsonLdExpansionConverter {
                                     value,
                                     r#type,
                                     language,
+                                    direction,
                                 })
                             }
                         }
@@ -1319,26 +1439,44 @@ impl JsonLdExpansionConverter {
                     | JsonEvent::Eof => unreachable!(),
                 }
             }
-            JsonLdExpansionState::ValueValue { r#type, language } => match event {
+            JsonLdExpansionState::ValueValue {
+                r#type,
+                language,
+                direction,
+            } => match event {
+                _ if r#type.as_deref() == Some("@json") => {
+                    self.state.push(JsonLdExpansionState::JsonLiteral {
+                        buffer: Vec::new(),
+                        depth: 0,
+                        in_value_object: true,
+                        language,
+                        direction,
+                    });
+                    self.convert_event(event, results, errors);
+                }
                 JsonEvent::Null => self.state.push(JsonLdExpansionState::Value {
                     r#type,
                     value: None,
                     language,
+                    direction,
                 }),
                 JsonEvent::Number(value) => self.state.push(JsonLdExpansionState::Value {
                     r#type,
                     value: Some(JsonLdValue::Number(value.into())),
                     language,
+                    direction,
                 }),
                 JsonEvent::Boolean(value) => self.state.push(JsonLdExpansionState::Value {
                     r#type,
                     value: Some(JsonLdValue::Boolean(value)),
                     language,
+                    direction,
                 }),
                 JsonEvent::String(value) => self.state.push(JsonLdExpansionState::Value {
                     r#type,
                     value: Some(JsonLdValue::String(value.into())),
                     language,
+                    direction,
                 }),
                 _ => {
                     errors.push(JsonLdSyntaxError::msg_and_code(
@@ -1349,18 +1487,24 @@ impl JsonLdExpansionConverter {
                         r#type,
                         value: None,
                         language,
+                        direction,
                     });
                     self.state
                         .push(JsonLdExpansionState::Skip { is_array: false });
                     self.convert_event(event, results, errors);
                 }
             },
-            JsonLdExpansionState::ValueLanguage { value, r#type } => {
+            JsonLdExpansionState::ValueLanguage {
+                value,
+                r#type,
+                direction,
+            } => {
                 if let JsonEvent::String(language) = event {
                     self.state.push(JsonLdExpansionState::Value {
                         r#type,
                         value,
                         language: Some(language.into()),
+                        direction,
                     })
                 } else {
                     errors.push(JsonLdSyntaxError::msg_and_code(
@@ -1371,17 +1515,22 @@ impl JsonLdExpansionConverter {
                         r#type,
                         value,
                         language: None,
+                        direction,
                     });
                     self.state
                         .push(JsonLdExpansionState::Skip { is_array: false });
                     self.convert_event(event, results, errors);
                 }
             }
-            JsonLdExpansionState::ValueType { value, language } => {
+            JsonLdExpansionState::ValueType {
+                value,
+                language,
+                direction,
+            } => {
                 if let JsonEvent::String(t) = event {
                     let mut r#type = self.expand_iri(t, true, true, errors);
                     if let Some(iri) = &r#type {
-                        if has_keyword_form(iri) {
+                        if has_keyword_form(iri) && !(iri == "@json" && self.is_json_allowed()) {
                             errors.push(JsonLdSyntaxError::msg_and_code(
                                 format!("{iri} is not a valid value for @type"),
                                 JsonLdErrorCode::InvalidTypedValue,
@@ -1393,6 +1542,7 @@ impl JsonLdExpansionConverter {
                         r#type: r#type.map(Into::into),
                         value,
                         language,
+                        direction,
                     })
                 } else {
                     errors.push(JsonLdSyntaxError::msg_and_code(
@@ -1403,12 +1553,107 @@ impl JsonLdExpansionConverter {
                         r#type: None,
                         value,
                         language,
+                        direction,
                     });
                     self.state
                         .push(JsonLdExpansionState::Skip { is_array: false });
                     self.convert_event(event, results, errors);
                 }
             }
+            JsonLdExpansionState::ValueDirection {
+                r#type,
+                value,
+                language,
+            } => {
+                let direction = match event {
+                    JsonEvent::Null => None,
+                    JsonEvent::String(direction) => match direction.as_ref() {
+                        "ltr" => Some("ltr"),
+                        "rtl" => Some("rtl"),
+                        _ => {
+                            errors.push(JsonLdSyntaxError::msg_and_code(
+                                format!(
+                                    "@direction value must be 'ltr' or 'rtl', found '{direction}'"
+                                ),
+                                JsonLdErrorCode::InvalidBaseDirection,
+                            ));
+                            None
+                        }
+                    },
+                    _ => {
+                        errors.push(JsonLdSyntaxError::msg_and_code(
+                            "@direction value must be a string or null",
+                            JsonLdErrorCode::InvalidBaseDirection,
+                        ));
+                        self.state.push(JsonLdExpansionState::Value {
+                            r#type,
+                            value,
+                            language,
+                            direction: None,
+                        });
+                        self.state
+                            .push(JsonLdExpansionState::Skip { is_array: false });
+                        self.convert_event(event, results, errors);
+                        return;
+                    }
+                };
+                self.state.push(JsonLdExpansionState::Value {
+                    r#type,
+                    value,
+                    language,
+                    direction,
+                })
+            }
+            JsonLdExpansionState::JsonLiteral {
+                mut buffer,
+                mut depth,
+                in_value_object,
+                language,
+                direction,
+            } => {
+                match event {
+                    JsonEvent::StartArray | JsonEvent::StartObject => depth += 1,
+                    JsonEvent::EndArray | JsonEvent::EndObject => depth -= 1,
+                    _ => (),
+                }
+                buffer.push(to_owned_event(event));
+                if depth > 0 {
+                    self.state.push(JsonLdExpansionState::JsonLiteral {
+                        buffer,
+                        depth,
+                        in_value_object,
+                        language,
+                        direction,
+                    });
+                    return;
+                }
+                let value = match json_node_from_events(buffer.into_iter().map(Ok)) {
+                    Ok(node) => {
+                        let mut value = String::new();
+                        write_canonical_json(&node, &mut value);
+                        Some(JsonLdValue::Json(value))
+                    }
+                    Err(e) => {
+                        errors.push(e.into());
+                        None
+                    }
+                };
+                if in_value_object {
+                    self.state.push(JsonLdExpansionState::Value {
+                        r#type: None,
+                        value,
+                        language,
+                        direction,
+                    });
+                } else if let Some(value) = value {
+                    results.push(JsonLdEvent::Value {
+                        value,
+                        r#type: None,
+                        language: None,
+                        direction: None,
+                    });
+                }
+            }
             JsonLdExpansionState::Index => {
                 if let JsonEvent::String(_) = event {
                     // TODO: properly emit if we implement expansion output
@@ -1499,24 +1744,31 @@ impl JsonLdExpansionConverter {
                 }
                 _ => unreachable!(),
             },
-            JsonLdExpansionState::LanguageContainer => match event {
+            JsonLdExpansionState::LanguageContainer { direction } => match event {
                 JsonEvent::EndObject => (),
                 JsonEvent::ObjectKey(language) => {
-                    self.state.push(JsonLdExpansionState::LanguageContainer);
+                    self.state
+                        .push(JsonLdExpansionState::LanguageContainer { direction });
                     self.state
                         .push(JsonLdExpansionState::LanguageContainerValue {
                             language: language.into(),
+                            direction,
                             is_array: false,
                         })
                 }
                 _ => unreachable!(),
             },
-            JsonLdExpansionState::LanguageContainerValue { language, is_array } => match event {
+            JsonLdExpansionState::LanguageContainerValue {
+                language,
+                direction,
+                is_array,
+            } => match event {
                 JsonEvent::Null => {
                     if is_array {
                         self.state
                             .push(JsonLdExpansionState::LanguageContainerValue {
                                 language,
+                                direction,
                                 is_array,
                             });
                     }
@@ -1526,6 +1778,7 @@ impl JsonLdExpansionConverter {
                         self.state
                             .push(JsonLdExpansionState::LanguageContainerValue {
                                 language: language.clone(),
+                                direction,
                                 is_array,
                             });
                     }
@@ -1536,12 +1789,14 @@ impl JsonLdExpansionConverter {
                             && self.expand_iri(language.as_str().into(), false, false, errors)
                                 != Some("@none".into()))
                         .then_some(language),
+                        direction,
                     })
                 }
                 JsonEvent::StartArray => {
                     self.state
                         .push(JsonLdExpansionState::LanguageContainerValue {
                             language,
+                            direction,
                             is_array: true,
                         });
                     if is_array {
@@ -1559,6 +1814,7 @@ impl JsonLdExpansionConverter {
                         self.state
                             .push(JsonLdExpansionState::LanguageContainerValue {
                                 language,
+                                direction,
                                 is_array,
                             });
                     }
@@ -1614,19 +1870,64 @@ impl JsonLdExpansionConverter {
         vocab: bool,
         errors: &mut Vec<JsonLdSyntaxError>,
     ) -> Option<Cow<'a, str>> {
-        self.context_processor.expand_iri(
-            &mut self
-                .context
-                .last_mut()
-                .expect("The context stack must not be empty")
-                .0,
+        let (active_context, _, cache) = self
+            .context
+            .last_mut()
+            .expect("The context stack must not be empty");
+        if !vocab {
+            // The @id values are usually all different, no need to memoize them
+            return self.context_processor.expand_iri(
+                active_context,
+                value,
+                document_relative,
+                vocab,
+                None,
+                &mut HashMap::new(),
+                errors,
+            );
+        }
+        // Without local context, the expansion only depends on the active context and does not fail
+        let cache = &mut cache.vocab_relative[usize::from(document_relative)];
+        if let Some(expanded) = cache.get(value.as_ref()) {
+            return expanded.clone().map(Into::into);
+        }
+        let key = (cache.len() < MAX_EXPANDED_IRI_CACHE_SIZE).then(|| value.to_string());
+        let expanded = self.context_processor.expand_iri(
+            active_context,
             value,
             document_relative,
             vocab,
             None,
             &mut HashMap::new(),
             errors,
-        )
+        );
+        if let Some(key) = key {
+            cache.insert(key, expanded.as_deref().map(str::to_owned));
+        }
+        expanded
+    }
+
+    /// Raises an error if the value being expanded is a list item and lists of lists are not supported
+    fn check_not_list_of_lists(&self, errors: &mut Vec<JsonLdSyntaxError>) {
+        if self.context_processor.processing_mode != JsonLdProcessingMode::JsonLd1_0 {
+            return;
+        }
+        let mut states = self.state.iter().rev();
+        if matches!(
+            states.next(),
+            Some(JsonLdExpansionState::Element { is_array: true, .. })
+        ) && matches!(
+            states.next(),
+            Some(JsonLdExpansionState::ListOrSetContainer {
+                end_event: Some(JsonLdEvent::EndList),
+                ..
+            })
+        ) {
+            errors.push(JsonLdSyntaxError::msg_and_code(
+                "Lists of lists are not supported in JSON-LD 1.0",
+                JsonLdErrorCode::ListOfLists,
+            ))
+        }
     }
 
     fn on_literal_value(
@@ -1653,7 +1954,27 @@ impl JsonLdExpansionConverter {
             }
         }
         if let Some(active_property) = &active_property {
+            // The property-scoped context only applies while expanding the value
+            let scoped_context =
+                self.scoped_context(active_property)
+                    .map(|(local_context, base_url)| {
+                        self.process_scoped_context(
+                            local_context,
+                            base_url.as_ref(),
+                            true,
+                            true,
+                            errors,
+                        )
+                    });
+            let has_scoped_context = scoped_context.is_some();
+            if let Some(scoped_context) = scoped_context {
+                self.context
+                    .push((scoped_context, 1, ExpandedIriCache::default()));
+            }
             self.expand_value(active_property, value, reverse, results, errors);
+            if has_scoped_context {
+                self.pop_context();
+            }
         }
         if is_array {
             self.state.push(JsonLdExpansionState::Element {
@@ -1669,6 +1990,22 @@ impl JsonLdExpansionConverter {
         }
     }
 
+    /// `@json` is only a keyword in JSON-LD 1.1
+    fn is_json_allowed(&self) -> bool {
+        self.context_processor.processing_mode != JsonLdProcessingMode::JsonLd1_0
+    }
+
+    fn has_json_type_mapping(&self, active_property: Option<&str>) -> bool {
+        active_property.is_some_and(|active_property| {
+            self.context()
+                .term_definitions
+                .get(active_property)
+                .is_some_and(|term_definition| {
+                    term_definition.type_mapping.as_deref() == Some("@json")
+                })
+        })
+    }
+
     /// [Value Expansion](https://www.w3.org/TR/json-ld-api/#value-expansion)
     fn expand_value(
         &mut self,
@@ -1681,6 +2018,7 @@ impl JsonLdExpansionConverter {
         let active_context = self.context();
         let mut r#type = None;
         let mut language = None;
+        let mut direction = None;
         if let Some(term_definition) = active_context.term_definitions.get(active_property) {
             if let Some(type_mapping) = &term_definition.type_mapping {
                 match type_mapping.as_ref() {
@@ -1714,16 +2052,20 @@ impl JsonLdExpansionConverter {
                 }
             }
             // 5)
-            if matches!(value, JsonLdValue::String(_)) {
+            if r#type.is_none() && matches!(value, JsonLdValue::String(_)) {
                 language = term_definition
                     .language_mapping
                     .clone()
                     .unwrap_or_else(|| active_context.default_language.clone());
+                direction = term_definition
+                    .direction_mapping
+                    .unwrap_or(active_context.default_direction);
             }
         } else {
             // 5)
             if matches!(value, JsonLdValue::String(_)) && language.is_none() {
                 language.clone_from(&active_context.default_language);
+                direction = active_context.default_direction;
             }
         }
         if reverse {
@@ -1736,9 +2078,19 @@ impl JsonLdExpansionConverter {
             value,
             r#type,
             language,
+            direction,
         });
     }
 
+    /// The direction of the strings of a property, from its term definition or the default one
+    fn direction_mapping(&self, active_property: Option<&str>) -> Option<&'static str> {
+        let active_context = self.context();
+        active_property
+            .and_then(|active_property| active_context.term_definitions.get(active_property))
+            .and_then(|term_definition| term_definition.direction_mapping)
+            .unwrap_or(active_context.default_direction)
+    }
+
     pub fn context(&self) -> &JsonLdContext {
         &self
             .context
@@ -1769,24 +2121,241 @@ impl JsonLdExpansionConverter {
             true,
             errors,
         );
-        if let Some((last_context, last_count)) = self.context.pop() {
+        self.replace_context(context);
+    }
+
+    /// Replaces the context of the current object
+    fn replace_context(&mut self, context: JsonLdContext) {
+        if let Some((last_context, last_count, cache)) = self.context.pop() {
             if last_count > 1 {
-                self.context.push((last_context, last_count - 1));
+                self.context.push((last_context, last_count - 1, cache));
+            }
+        }
+        self.context.push((context, 1, ExpandedIriCache::default()));
+    }
+
+    /// Sets the context of a new object, steps 7 and 8 of the [Expansion Algorithm](https://www.w3.org/TR/json-ld-api/#expansion-algorithm)
+    ///
+    /// The non-propagated contexts (type-scoped contexts) are reverted for node objects
+    /// and the context scoped to the property the object is the value of is applied.
+    fn enter_node_object(
+        &mut self,
+        active_property: Option<&str>,
+        is_node_object: bool,
+        errors: &mut Vec<JsonLdSyntaxError>,
+    ) {
+        let property_scoped_context = active_property.and_then(|p| self.scoped_context(p));
+        if is_node_object {
+            if let Some(previous_context) = self.context().previous_context.clone() {
+                self.replace_context(*previous_context);
             }
         }
-        self.context.push((context, 1));
+        if let Some((local_context, base_url)) = property_scoped_context {
+            let context =
+                self.process_scoped_context(local_context, base_url.as_ref(), true, true, errors);
+            self.replace_context(context);
+        }
+    }
+
+    /// Applies the context scoped to a type, step 11 of the [Expansion Algorithm](https://www.w3.org/TR/json-ld-api/#expansion-algorithm)
+    ///
+    /// It is not propagated to the nested node objects.
+    fn apply_type_scoped_context(&mut self, r#type: &str, errors: &mut Vec<JsonLdSyntaxError>) {
+        let Some((local_context, base_url)) = self.scoped_context(r#type) else {
+            return;
+        };
+        let context =
+            self.process_scoped_context(local_context, base_url.as_ref(), false, false, errors);
+        self.replace_context(context);
+    }
+
+    /// The local context and the base URL of a term definition with a scoped context
+    fn scoped_context(&self, term: &str) -> Option<(JsonNode, Option<Iri<String>>)> {
+        let term_definition = self.context().term_definitions.get(term)?;
+        Some((
+            term_definition.context.clone()?,
+            term_definition.base_url.clone(),
+        ))
+    }
+
+    fn process_scoped_context(
+        &self,
+        local_context: JsonNode,
+        base_url: Option<&Iri<String>>,
+        override_protected: bool,
+        propagate: bool,
+        errors: &mut Vec<JsonLdSyntaxError>,
+    ) -> JsonLdContext {
+        self.context_processor.process_context(
+            self.context(),
+            local_context,
+            base_url,
+            &mut Vec::new(),
+            override_protected,
+            propagate,
+            true,
+            errors,
+        )
     }
 
     fn pop_context(&mut self) {
-        let Some((last_context, mut last_count)) = self.context.pop() else {
+        let Some((last_context, mut last_count, cache)) = self.context.pop() else {
             return;
         };
         last_count -= 1;
         if last_count > 0 || self.context.is_empty() {
             // We always keep a context to allow reading the root context at the end of the document
-            self.context.push((last_context, last_count));
+            self.context.push((last_context, last_count, cache));
+        }
+    }
+}
+
+/// Memoized [IRI expansions](https://www.w3.org/TR/json-ld-api/#iri-expansion) of an active context
+///
+/// Large documents often use the same few keys and types in all their node objects.
+#[derive(Default)]
+struct ExpandedIriCache {
+    /// The expansions relative to the vocabulary mapping, indexed by the `document_relative` flag
+    vocab_relative: [HashMap<String, Option<String>>; 2],
+}
+
+/// Removes `@json` from the types of a node object
+fn node_types(mut types: Vec<String>, errors: &mut Vec<JsonLdSyntaxError>) -> Vec<String> {
+    if types.iter().any(|t| t == "@json") {
+        errors.push(JsonLdSyntaxError::msg_and_code(
+            "@json is only allowed as the @type of value objects",
+            JsonLdErrorCode::InvalidTypeValue,
+        ));
+        types.retain(|t| t != "@json");
+    }
+    types
+}
+
+/// Converts a scalar value to a `@json` value
+fn to_json_value(value: JsonLdValue) -> JsonLdValue {
+    let mut output = String::new();
+    match value {
+        JsonLdValue::String(value) => write_canonical_json_string(&value, &mut output),
+        JsonLdValue::Number(value) => output.push_str(&canonicalize_json_number(&value)),
+        JsonLdValue::Boolean(value) => output.push_str(if value { "true" } else { "false" }),
+        JsonLdValue::Json(value) => output = value,
+    }
+    JsonLdValue::Json(output)
+}
+
+/// Serializes JSON following the [JSON Canonicalization Scheme](https://www.rfc-editor.org/rfc/rfc8785)
+fn write_canonical_json(node: &JsonNode, output: &mut String) {
+    match node {
+        JsonNode::String(value) => write_canonical_json_string(value, output),
+        JsonNode::Number(value) => output.push_str(&canonicalize_json_number(value)),
+        JsonNode::Boolean(value) => output.push_str(if *value { "true" } else { "false" }),
+        JsonNode::Null => output.push_str("null"),
+        JsonNode::Array(values) => {
+            output.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_canonical_json(value, output);
+            }
+            output.push(']');
+        }
+        JsonNode::Object(entries) => {
+            // The keys are sorted by UTF-16 code units
+            let mut entries = entries.iter().collect::<Vec<_>>();
+            entries.sort_unstable_by(|(key1, _), (key2, _)| {
+                key1.encode_utf16().cmp(key2.encode_utf16())
+            });
+            output.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_canonical_json_string(key, output);
+                output.push(':');
+                write_canonical_json(value, output);
+            }
+            output.push('}');
+        }
+    }
+}
+
+fn write_canonical_json_string(value: &str, output: &mut String) {
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\u{08}' => output.push_str("\\b"),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\u{0C}' => output.push_str("\\f"),
+            '\r' => output.push_str("\\r"),
+            '\0'..' ' => {
+                output.push_str(if c < '\u{10}' { "\\u000" } else { "\\u001" });
+                output.extend(char::from_digit(u32::from(c) % 16, 16));
+            }
+            _ => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+/// Serializes a JSON number like ECMAScript `Number.prototype.toString`, as required by the JSON Canonicalization Scheme
+fn canonicalize_json_number(value: &str) -> String {
+    let Ok(number) = value.parse::<f64>() else {
+        return value.into();
+    };
+    if !number.is_finite() {
+        return value.into();
+    }
+    if number == 0. {
+        return "0".into();
+    }
+    // We get the shortest digits and the exponent
+    let scientific = format!("{:e}", number.abs());
+    let Some((mantissa, exponent)) = scientific.split_once('e') else {
+        return value.into();
+    };
+    let Ok(exponent) = exponent.parse::<i64>() else {
+        return value.into();
+    };
+    let digits = mantissa.replace('.', "");
+    let Ok(digits_count) = i64::try_from(digits.len()) else {
+        return value.into();
+    };
+    // The position of the decimal point in the digits
+    let point = exponent + 1;
+    let mut output = String::new();
+    if number < 0. {
+        output.push('-');
+    }
+    if digits_count <= point && point <= 21 {
+        output.push_str(&digits);
+        output.extend((digits_count..point).map(|_| '0'));
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(usize::try_from(point).unwrap_or_default());
+        output.push_str(integer);
+        output.push('.');
+        output.push_str(fraction);
+    } else if -6 < point && point <= 0 {
+        output.push_str("0.");
+        output.extend((point..0).map(|_| '0'));
+        output.push_str(&digits);
+    } else {
+        let (first, others) = digits.split_at(1);
+        output.push_str(first);
+        if !others.is_empty() {
+            output.push('.');
+            output.push_str(others);
+        }
+        output.push('e');
+        if point > 0 {
+            output.push('+');
         }
+        output.push_str(&(point - 1).to_string());
     }
+    output
 }
 
 fn to_owned_event(event: JsonEvent<'_>) -> JsonEvent<'static> {
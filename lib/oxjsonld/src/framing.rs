@@ -0,0 +1,1488 @@
+use crate::context::{
+    JsonLdContext, JsonLdContextProcessor, JsonLdTermDefinition, JsonNode, json_node_from_events,
+    json_slice_to_events,
+};
+use crate::error::{JsonLdErrorCode, JsonLdSyntaxError};
+use crate::from_rdf::{
+    CompactionContext, compact_iri, compact_value, compact_vocab_iri, keyword_alias,
+    local_context_processor, select_term,
+};
+use json_event_parser::{JsonEvent, WriterJsonSerializer};
+use oxrdf::vocab::{rdf, xsd};
+use oxrdf::{
+    BlankNodeRef, GraphNameRef, Literal, NamedNodeRef, NamedOrBlankNodeRef, QuadRef, TermRef,
+};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// The name of the graph merging all the graphs of the dataset
+const MERGED_GRAPH: &str = "@merged";
+/// The name of the default graph in the node map
+const DEFAULT_GRAPH: &str = "@default";
+
+/// A frame matching all the nodes
+static WILDCARD_FRAME: NodeFrame = NodeFrame {
+    ids: None,
+    types: None,
+    default_type: None,
+    properties: BTreeMap::new(),
+    graph: None,
+    embed: None,
+    explicit: None,
+    require_all: None,
+    omit_default: None,
+    default: None,
+};
+
+/// A [JSON-LD frame](https://www.w3.org/TR/json-ld11-framing/) reshaping RDF data into a specific JSON tree.
+///
+/// The nodes matching the frame on their `@id`, their `@type` or their properties are written at the top level,
+/// with the nodes they reference embedded according to the `@embed`, `@explicit`, `@requireAll` and `@omitDefault` flags.
+/// The output is compacted against the `@context` of the frame.
+///
+/// Lists are not converted to `@list` values.
+/// Frames using `@reverse`, `@list`, `@included` or nested contexts are not supported.
+///
+/// ```
+/// use oxjsonld::{JsonLdFrame, JsonLdParser};
+///
+/// let file = br#"{
+///     "@context": {"@vocab": "http://schema.org/"},
+///     "@graph": [
+///         {"@id": "http://example.com/foo", "@type": "Person", "name": "Foo", "knows": {"@id": "http://example.com/bar"}},
+///         {"@id": "http://example.com/bar", "@type": "Person", "name": "Bar"}
+///     ]
+/// }"#;
+/// let quads = JsonLdParser::new()
+///     .for_slice(file)
+///     .collect::<Result<Vec<_>, _>>()?;
+///
+/// let frame: JsonLdFrame = r#"{
+///     "@context": {"@vocab": "http://schema.org/"},
+///     "@id": "http://example.com/foo"
+/// }"#
+/// .parse()?;
+/// assert_eq!(
+///     String::from_utf8(frame.frame(&quads, Vec::new())?)?,
+///     r#"{"@context":{"@vocab":"http://schema.org/"},"@id":"http://example.com/foo","@type":"Person","knows":{"@id":"http://example.com/bar","@type":"Person","name":"Bar"},"name":"Foo"}"#
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Clone)]
+pub struct JsonLdFrame {
+    context: CompactionContext,
+    frame: NodeFrame,
+}
+
+impl JsonLdFrame {
+    /// Frames the given quads and writes the result to a [`Write`] implementation.
+    ///
+    /// The nodes are matched against the merge of all the graphs of the dataset.
+    pub fn frame<'a, W: Write>(
+        &self,
+        quads: impl IntoIterator<Item = impl Into<QuadRef<'a>>>,
+        writer: W,
+    ) -> io::Result<W> {
+        let graphs = node_map(quads)?;
+        let mut framer = Framer {
+            graphs: &graphs,
+            graph: MERGED_GRAPH,
+            embedded: HashSet::new(),
+            stack: Vec::new(),
+        };
+        let mut nodes = Vec::new();
+        if let Some(merged) = graphs.get(MERGED_GRAPH) {
+            framer.match_frame(
+                merged.keys().map(String::as_str),
+                &self.frame,
+                self.frame.flags(),
+                &mut nodes,
+                true,
+            );
+        }
+
+        let mut writer_state = FramedWriter {
+            context: &self.context.active,
+            blank_node_counts: HashMap::new(),
+        };
+        for node in &nodes {
+            writer_state.count_blank_nodes(node);
+        }
+        let mut output = vec![JsonEvent::StartObject];
+        if !self.context.source.is_empty() {
+            output.push(JsonEvent::ObjectKey("@context".into()));
+            output.extend(self.context.source.iter().cloned());
+        }
+        if let [node] = nodes.as_slice() {
+            writer_state.write_node_entries(node, &mut output)?;
+        } else {
+            output.push(JsonEvent::ObjectKey(keyword_alias(
+                &self.context.active,
+                "@graph",
+            )));
+            output.push(JsonEvent::StartArray);
+            for node in &nodes {
+                writer_state.write_node(node, &mut output)?;
+            }
+            output.push(JsonEvent::EndArray);
+        }
+        output.push(JsonEvent::EndObject);
+
+        let mut serializer = WriterJsonSerializer::new(writer);
+        for event in output {
+            serializer.serialize_event(event)?;
+        }
+        serializer.finish()
+    }
+}
+
+impl FromStr for JsonLdFrame {
+    type Err = JsonLdSyntaxError;
+
+    fn from_str(frame: &str) -> Result<Self, JsonLdSyntaxError> {
+        let events = json_slice_to_events(frame.as_bytes())?;
+        let context = if let Some(source) = context_events(&events) {
+            CompactionContext::new(source)?
+        } else {
+            CompactionContext {
+                source: Vec::new(),
+                active: JsonLdContext::new_empty(None),
+            }
+        };
+        let frame = match json_node_from_events(events.into_iter().map(Ok))? {
+            JsonNode::Object(frame) => frame,
+            JsonNode::Array(frames) if frames.len() == 1 => {
+                let Some(JsonNode::Object(frame)) = frames.into_iter().next() else {
+                    return Err(invalid_frame("A JSON-LD frame must be a single object"));
+                };
+                frame
+            }
+            _ => return Err(invalid_frame("A JSON-LD frame must be a single object")),
+        };
+        let frame = FrameParser {
+            processor: local_context_processor(),
+            context: context.active.clone(),
+        }
+        .parse_node_frame(frame, true)?;
+        Ok(Self { context, frame })
+    }
+}
+
+/// Extracts the events of the top-level `@context` value
+fn context_events(events: &[JsonEvent<'static>]) -> Option<Vec<JsonEvent<'static>>> {
+    // The frame might be wrapped into an array
+    let frame_depth = if matches!(events.first(), Some(JsonEvent::StartArray)) {
+        2
+    } else {
+        1
+    };
+    let mut depth = 0;
+    let mut iter = events.iter();
+    while let Some(event) = iter.next() {
+        match event {
+            JsonEvent::StartObject | JsonEvent::StartArray => depth += 1,
+            JsonEvent::EndObject | JsonEvent::EndArray => depth -= 1,
+            JsonEvent::ObjectKey(key) if depth == frame_depth && key == "@context" => {
+                let mut value = Vec::new();
+                let mut value_depth = 0;
+                for event in iter.by_ref() {
+                    value.push(event.clone());
+                    match event {
+                        JsonEvent::StartObject | JsonEvent::StartArray => value_depth += 1,
+                        JsonEvent::EndObject | JsonEvent::EndArray => value_depth -= 1,
+                        _ => (),
+                    }
+                    if value_depth == 0 {
+                        return Some(value);
+                    }
+                }
+                return None;
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// A frame node with its keys expanded
+#[derive(Clone, Default)]
+struct NodeFrame {
+    ids: Option<Pattern>,
+    types: Option<Pattern>,
+    default_type: Option<String>,
+    properties: BTreeMap<String, PropertyFrame>,
+    graph: Option<Box<NodeFrame>>,
+    embed: Option<Embed>,
+    explicit: Option<bool>,
+    require_all: Option<bool>,
+    omit_default: Option<bool>,
+    default: Option<JsonNode>,
+}
+
+impl NodeFrame {
+    fn flags(&self) -> FrameFlags {
+        FrameFlags {
+            embed: self.embed.unwrap_or(Embed::Once),
+            explicit: self.explicit.unwrap_or(false),
+            require_all: self.require_all.unwrap_or(false),
+        }
+    }
+
+    /// Checks if the frame only contains `@id`
+    fn is_reference(&self) -> bool {
+        self.ids.is_some()
+            && self.types.is_none()
+            && self.default_type.is_none()
+            && self.properties.is_empty()
+            && self.graph.is_none()
+            && self.embed.is_none()
+            && self.explicit.is_none()
+            && self.require_all.is_none()
+            && self.omit_default.is_none()
+            && self.default.is_none()
+    }
+}
+
+#[derive(Clone)]
+enum PropertyFrame {
+    /// `[]`: the property must not be set
+    MatchNone,
+    Node(NodeFrame),
+    Value(ValuePattern),
+}
+
+#[derive(Clone, Default)]
+struct ValuePattern {
+    value: Option<Pattern>,
+    r#type: Option<Pattern>,
+    language: Option<Pattern>,
+}
+
+impl ValuePattern {
+    /// [Value Pattern Matching](https://www.w3.org/TR/json-ld11-framing/#value-matching)
+    fn matches(&self, literal: &Literal) -> bool {
+        if self.value.is_none() && self.r#type.is_none() && self.language.is_none() {
+            return true;
+        }
+        let value_matches = match &self.value {
+            Some(Pattern::Wildcard) => true,
+            Some(Pattern::Values(values)) => values.iter().any(|value| value == literal.value()),
+            Some(Pattern::MatchNone) | None => false,
+        };
+        let datatype = (literal.language().is_none() && literal.datatype() != xsd::STRING)
+            .then(|| literal.datatype().as_str());
+        value_matches
+            && optional_pattern_matches(self.r#type.as_ref(), datatype, |a, b| a == b)
+            && optional_pattern_matches(
+                self.language.as_ref(),
+                literal.language(),
+                str::eq_ignore_ascii_case,
+            )
+    }
+}
+
+fn optional_pattern_matches(
+    pattern: Option<&Pattern>,
+    value: Option<&str>,
+    eq: impl Fn(&str, &str) -> bool,
+) -> bool {
+    match (pattern, value) {
+        (None | Some(Pattern::MatchNone), None) | (Some(Pattern::Wildcard), Some(_)) => true,
+        (Some(Pattern::Values(values)), Some(value)) => {
+            values.iter().any(|expected| eq(expected, value))
+        }
+        _ => false,
+    }
+}
+
+#[derive(Clone)]
+enum Pattern {
+    /// `{}`
+    Wildcard,
+    /// `[]`
+    MatchNone,
+    Values(Vec<String>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Embed {
+    Always,
+    Once,
+    Never,
+}
+
+#[derive(Clone, Copy)]
+struct FrameFlags {
+    embed: Embed,
+    explicit: bool,
+    require_all: bool,
+}
+
+/// Expands the keys and the values of a frame
+struct FrameParser {
+    processor: JsonLdContextProcessor,
+    context: JsonLdContext,
+}
+
+impl FrameParser {
+    fn parse_node_frame(
+        &mut self,
+        frame: HashMap<String, JsonNode>,
+        is_top_level: bool,
+    ) -> Result<NodeFrame, JsonLdSyntaxError> {
+        let mut result = NodeFrame::default();
+        for (key, expanded_key, value) in self.expand_keys(frame)? {
+            match expanded_key.as_str() {
+                "@context" => {
+                    if !is_top_level {
+                        return Err(JsonLdSyntaxError::msg(
+                            "@context is only supported at the top level of JSON-LD frames",
+                        ));
+                    }
+                }
+                "@id" => {
+                    result.ids = Some(self.parse_pattern(value, "@id", |parser, id| {
+                        parser.expand_iri(&id, true, false)
+                    })?)
+                }
+                "@type" => self.parse_types(value, &mut result)?,
+                "@embed" => result.embed = Some(parse_embed(&value)?),
+                "@explicit" => result.explicit = Some(parse_flag(&value, "@explicit")?),
+                "@requireAll" => result.require_all = Some(parse_flag(&value, "@requireAll")?),
+                "@omitDefault" => result.omit_default = Some(parse_flag(&value, "@omitDefault")?),
+                "@default" => result.default = Some(value),
+                "@graph" => {
+                    let frame = match value {
+                        JsonNode::Object(frame) => Some(frame),
+                        JsonNode::Array(frames) => frames.into_iter().find_map(|frame| {
+                            if let JsonNode::Object(frame) = frame {
+                                Some(frame)
+                            } else {
+                                None
+                            }
+                        }),
+                        _ => None,
+                    };
+                    result.graph = Some(Box::new(if let Some(frame) = frame {
+                        self.parse_node_frame(frame, false)?
+                    } else {
+                        NodeFrame::default()
+                    }));
+                }
+                "@reverse" | "@list" | "@included" | "@nest" => {
+                    return Err(JsonLdSyntaxError::msg(format!(
+                        "{expanded_key} is not supported in JSON-LD frames yet"
+                    )));
+                }
+                _ if expanded_key.starts_with('@') => (),
+                _ => {
+                    let property_frame = self.parse_property_frame(&key, value)?;
+                    result.properties.insert(expanded_key, property_frame);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn parse_property_frame(
+        &mut self,
+        key: &str,
+        value: JsonNode,
+    ) -> Result<PropertyFrame, JsonLdSyntaxError> {
+        let value = match value {
+            JsonNode::Array(values) => {
+                let Some(value) = values.into_iter().next() else {
+                    return Ok(PropertyFrame::MatchNone);
+                };
+                value
+            }
+            other => other,
+        };
+        let lexical_value = match value {
+            JsonNode::Object(object) => {
+                let entries = self.expand_keys(object)?;
+                return if entries.iter().any(|(_, key, _)| key == "@value") {
+                    self.parse_value_pattern(entries).map(PropertyFrame::Value)
+                } else {
+                    self.parse_node_frame(
+                        entries
+                            .into_iter()
+                            .map(|(key, _, value)| (key, value))
+                            .collect(),
+                        false,
+                    )
+                    .map(PropertyFrame::Node)
+                };
+            }
+            JsonNode::String(value) | JsonNode::Number(value) => value,
+            JsonNode::Boolean(value) => value.to_string(),
+            JsonNode::Null => {
+                return Err(invalid_frame(format!(
+                    "null is not a valid frame for the property {key}"
+                )));
+            }
+            JsonNode::Array(_) => {
+                return Err(invalid_frame(format!(
+                    "Arrays of arrays are not valid frames for the property {key}"
+                )));
+            }
+        };
+        // The value is a shortcut for a value pattern or a node reference
+        let type_mapping = self
+            .context
+            .term_definitions
+            .get(key)
+            .and_then(|definition| definition.type_mapping.clone());
+        Ok(match type_mapping.as_deref() {
+            Some(type_mapping @ ("@id" | "@vocab")) => PropertyFrame::Node(NodeFrame {
+                ids: Some(Pattern::Values(
+                    self.expand_iri(&lexical_value, true, type_mapping == "@vocab")?
+                        .into_iter()
+                        .collect(),
+                )),
+                ..NodeFrame::default()
+            }),
+            _ => PropertyFrame::Value(ValuePattern {
+                value: Some(Pattern::Values(vec![lexical_value])),
+                r#type: type_mapping.map(|datatype| Pattern::Values(vec![datatype])),
+                language: None,
+            }),
+        })
+    }
+
+    fn parse_value_pattern(
+        &mut self,
+        entries: Vec<(String, String, JsonNode)>,
+    ) -> Result<ValuePattern, JsonLdSyntaxError> {
+        let mut pattern = ValuePattern::default();
+        for (_, key, value) in entries {
+            match key.as_str() {
+                "@value" => {
+                    pattern.value =
+                        Some(self.parse_pattern(value, "@value", |_, value| Ok(Some(value)))?)
+                }
+                "@type" => {
+                    pattern.r#type = Some(self.parse_pattern(value, "@type", |parser, value| {
+                        parser.expand_iri(&value, true, true)
+                    })?)
+                }
+                "@language" => {
+                    pattern.language =
+                        Some(self.parse_pattern(value, "@language", |_, value| Ok(Some(value)))?)
+                }
+                _ => (),
+            }
+        }
+        Ok(pattern)
+    }
+
+    fn parse_types(
+        &mut self,
+        value: JsonNode,
+        frame: &mut NodeFrame,
+    ) -> Result<(), JsonLdSyntaxError> {
+        let values = match value {
+            JsonNode::Array(values) if values.is_empty() => {
+                frame.types = Some(Pattern::MatchNone);
+                return Ok(());
+            }
+            JsonNode::Array(values) => values,
+            other => vec![other],
+        };
+        let mut types = Vec::new();
+        for value in values {
+            match value {
+                JsonNode::String(value) => {
+                    types.extend(self.expand_iri(&value, true, true)?);
+                }
+                JsonNode::Object(object) if object.is_empty() => {
+                    frame.types = Some(Pattern::Wildcard);
+                    return Ok(());
+                }
+                JsonNode::Object(mut object) => {
+                    let Some(JsonNode::String(default)) = object.remove("@default") else {
+                        return Err(invalid_frame(
+                            "The @type values of a frame must be strings, {} or {\"@default\": ...}",
+                        ));
+                    };
+                    frame.default_type = self.expand_iri(&default, true, true)?;
+                }
+                _ => {
+                    return Err(invalid_frame(
+                        "The @type values of a frame must be strings, {} or {\"@default\": ...}",
+                    ));
+                }
+            }
+        }
+        frame.types = Some(Pattern::Values(types));
+        Ok(())
+    }
+
+    fn parse_pattern(
+        &mut self,
+        value: JsonNode,
+        keyword: &str,
+        mut map: impl FnMut(&mut Self, String) -> Result<Option<String>, JsonLdSyntaxError>,
+    ) -> Result<Pattern, JsonLdSyntaxError> {
+        let values = match value {
+            JsonNode::Array(values) if values.is_empty() => return Ok(Pattern::MatchNone),
+            JsonNode::Array(values) => values,
+            other => vec![other],
+        };
+        let mut result = Vec::new();
+        for value in values {
+            let value = match value {
+                JsonNode::Object(object) if object.is_empty() => return Ok(Pattern::Wildcard),
+                JsonNode::String(value) | JsonNode::Number(value) => value,
+                JsonNode::Boolean(value) => value.to_string(),
+                _ => {
+                    return Err(invalid_frame(format!(
+                        "Invalid {keyword} value in a frame, only strings, {{}} and [] are allowed"
+                    )));
+                }
+            };
+            result.extend(map(self, value)?);
+        }
+        Ok(Pattern::Values(result))
+    }
+
+    /// Expands the keys of an object, dropping the ones that are not IRIs or keywords
+    fn expand_keys(
+        &mut self,
+        object: HashMap<String, JsonNode>,
+    ) -> Result<Vec<(String, String, JsonNode)>, JsonLdSyntaxError> {
+        let mut entries = Vec::with_capacity(object.len());
+        for (key, value) in object {
+            if is_framing_keyword(&key) {
+                entries.push((key.clone(), key, value));
+            } else if let Some(expanded_key) = self.expand_iri(&key, false, true)? {
+                if expanded_key.starts_with('@') || expanded_key.contains(':') {
+                    entries.push((key, expanded_key, value));
+                }
+            }
+        }
+        entries.sort_unstable_by(|(_, key1, _), (_, key2, _)| key1.cmp(key2));
+        Ok(entries)
+    }
+
+    fn expand_iri(
+        &mut self,
+        value: &str,
+        document_relative: bool,
+        vocab: bool,
+    ) -> Result<Option<String>, JsonLdSyntaxError> {
+        let mut errors = Vec::new();
+        let iri = self
+            .processor
+            .expand_iri(
+                &mut self.context,
+                value.into(),
+                document_relative,
+                vocab,
+                None,
+                &mut HashMap::new(),
+                &mut errors,
+            )
+            .map(Cow::into_owned);
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error);
+        }
+        Ok(iri)
+    }
+}
+
+/// The keywords only allowed in frames
+fn is_framing_keyword(key: &str) -> bool {
+    matches!(
+        key,
+        "@default" | "@embed" | "@explicit" | "@omitDefault" | "@requireAll"
+    )
+}
+
+fn parse_embed(value: &JsonNode) -> Result<Embed, JsonLdSyntaxError> {
+    match value {
+        JsonNode::String(value) => match value.as_str() {
+            "@always" => return Ok(Embed::Always),
+            "@once" => return Ok(Embed::Once),
+            "@never" => return Ok(Embed::Never),
+            _ => (),
+        },
+        JsonNode::Boolean(true) => return Ok(Embed::Once),
+        JsonNode::Boolean(false) => return Ok(Embed::Never),
+        _ => (),
+    }
+    Err(JsonLdSyntaxError::msg_and_code(
+        "@embed must be one of @always, @once, @never, true or false",
+        JsonLdErrorCode::InvalidEmbedValue,
+    ))
+}
+
+fn parse_flag(value: &JsonNode, keyword: &str) -> Result<bool, JsonLdSyntaxError> {
+    if let JsonNode::Boolean(value) = value {
+        Ok(*value)
+    } else {
+        Err(invalid_frame(format!("{keyword} value must be a boolean")))
+    }
+}
+
+fn invalid_frame(msg: impl Into<String>) -> JsonLdSyntaxError {
+    JsonLdSyntaxError::msg_and_code(msg, JsonLdErrorCode::InvalidFrame)
+}
+
+/// The nodes of each graph, by graph name and then by node identifier
+type NodeMap = BTreeMap<String, BTreeMap<String, Node>>;
+
+#[derive(Default)]
+struct Node {
+    types: Vec<String>,
+    properties: BTreeMap<String, Vec<NodeValue>>,
+}
+
+#[derive(PartialEq, Eq)]
+enum NodeValue {
+    Node(String),
+    Literal(Literal),
+}
+
+/// Builds the [node map](https://www.w3.org/TR/json-ld11-api/#node-map-generation) of the dataset
+/// with an extra `@merged` graph merging all the graphs
+fn node_map<'a>(quads: impl IntoIterator<Item = impl Into<QuadRef<'a>>>) -> io::Result<NodeMap> {
+    let mut graphs = NodeMap::new();
+    graphs.insert(MERGED_GRAPH.into(), BTreeMap::new());
+    for quad in quads {
+        let quad = quad.into();
+        let subject = node_id(quad.subject);
+        #[allow(
+            unreachable_patterns,
+            clippy::match_wildcard_for_single_variants,
+            clippy::allow_attributes
+        )]
+        let object = match quad.object {
+            TermRef::NamedNode(object) => NodeValue::Node(object.as_str().into()),
+            TermRef::BlankNode(object) => NodeValue::Node(format!("_:{}", object.as_str())),
+            TermRef::Literal(object) => NodeValue::Literal(object.into_owned()),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "JSON-LD does not support RDF 1.2 yet",
+                ));
+            }
+        };
+        let graph_name = match quad.graph_name {
+            GraphNameRef::NamedNode(graph_name) => graph_name.as_str().into(),
+            GraphNameRef::BlankNode(graph_name) => format!("_:{}", graph_name.as_str()),
+            GraphNameRef::DefaultGraph => DEFAULT_GRAPH.into(),
+        };
+        if graph_name != DEFAULT_GRAPH {
+            // The graph name is a node of the default graph
+            for graph in [DEFAULT_GRAPH, MERGED_GRAPH] {
+                graphs
+                    .entry(graph.into())
+                    .or_default()
+                    .entry(graph_name.clone())
+                    .or_default();
+            }
+        }
+        for graph in [graph_name, MERGED_GRAPH.into()] {
+            let nodes = graphs.entry(graph).or_default();
+            if quad.predicate == rdf::TYPE {
+                if let NodeValue::Node(object) = &object {
+                    let node = nodes.entry(subject.clone()).or_default();
+                    if !node.types.contains(object) {
+                        node.types.push(object.clone());
+                    }
+                    continue;
+                }
+            }
+            if let NodeValue::Node(object) = &object {
+                // The referenced nodes are also in the node map
+                nodes.entry(object.clone()).or_default();
+            }
+            let node = nodes.entry(subject.clone()).or_default();
+            let values = node
+                .properties
+                .entry(quad.predicate.as_str().into())
+                .or_default();
+            if !values.contains(&object) {
+                values.push(match &object {
+                    NodeValue::Node(object) => NodeValue::Node(object.clone()),
+                    NodeValue::Literal(object) => NodeValue::Literal(object.clone()),
+                });
+            }
+        }
+    }
+    Ok(graphs)
+}
+
+fn node_id(node: NamedOrBlankNodeRef<'_>) -> String {
+    match node {
+        NamedOrBlankNodeRef::NamedNode(node) => node.as_str().into(),
+        NamedOrBlankNodeRef::BlankNode(node) => format!("_:{}", node.as_str()),
+    }
+}
+
+/// A node in the framing output
+struct FramedNode<'a> {
+    id: &'a str,
+    types: Vec<&'a str>,
+    graph: Vec<FramedNode<'a>>,
+    properties: BTreeMap<&'a str, Vec<FramedValue<'a>>>,
+}
+
+impl FramedNode<'_> {
+    fn is_reference(&self) -> bool {
+        self.types.is_empty() && self.graph.is_empty() && self.properties.is_empty()
+    }
+}
+
+enum FramedValue<'a> {
+    Node(FramedNode<'a>),
+    Literal(&'a Literal),
+    /// The `@default` value of the frame, `None` for `null`
+    Default(Option<&'a JsonNode>),
+}
+
+/// The [Framing Algorithm](https://www.w3.org/TR/json-ld11-framing/#framing-algorithm) state
+struct Framer<'a> {
+    graphs: &'a NodeMap,
+    /// The graph the nodes are looked for in
+    graph: &'a str,
+    /// The nodes already embedded, by graph name and node identifier
+    embedded: HashSet<(&'a str, &'a str)>,
+    /// The nodes that are being embedded, to avoid cycles
+    stack: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Framer<'a> {
+    fn match_frame(
+        &mut self,
+        ids: impl IntoIterator<Item = &'a str>,
+        frame: &'a NodeFrame,
+        flags: FrameFlags,
+        output: &mut Vec<FramedNode<'a>>,
+        is_top_level: bool,
+    ) {
+        let Some(nodes) = self.graphs.get(self.graph) else {
+            return;
+        };
+        for id in ids {
+            let Some((id, node)) = nodes.get_key_value(id) else {
+                continue;
+            };
+            let id = id.as_str();
+            if !self.filter_node(node, id, frame, flags.require_all) {
+                continue;
+            }
+            if is_top_level {
+                self.embedded.clear();
+            }
+            let mut framed = FramedNode {
+                id,
+                types: Vec::new(),
+                graph: Vec::new(),
+                properties: BTreeMap::new(),
+            };
+            let graph = self.graph;
+            if flags.embed == Embed::Never
+                || self.stack.contains(&(graph, id))
+                || (flags.embed == Embed::Once && !self.embedded.insert((graph, id)))
+            {
+                // We only output a reference
+                output.push(framed);
+                continue;
+            }
+            self.stack.push((graph, id));
+
+            // The node is also the name of a graph
+            if let Some((graph_name, graph_nodes)) = self.graphs.get_key_value(id) {
+                let subframe = frame.graph.as_deref();
+                if subframe.is_some() || graph != MERGED_GRAPH {
+                    self.graph = graph_name;
+                    let subframe = subframe.unwrap_or(&WILDCARD_FRAME);
+                    self.match_frame(
+                        graph_nodes.keys().map(String::as_str),
+                        subframe,
+                        subframe.flags(),
+                        &mut framed.graph,
+                        false,
+                    );
+                    self.graph = graph;
+                }
+            }
+
+            framed.types = node.types.iter().map(String::as_str).collect();
+            if framed.types.is_empty() {
+                if let Some(default_type) = &frame.default_type {
+                    framed.types.push(default_type);
+                }
+            }
+            for (property, values) in &node.properties {
+                let subframe = frame.properties.get(property);
+                if flags.explicit && subframe.is_none() {
+                    continue;
+                }
+                let mut framed_values = Vec::new();
+                for value in values {
+                    match (value, subframe) {
+                        (_, Some(PropertyFrame::MatchNone)) => (),
+                        (NodeValue::Node(object), subframe) => {
+                            let (subframe, subflags) = match subframe {
+                                Some(PropertyFrame::Node(subframe)) => (subframe, subframe.flags()),
+                                Some(PropertyFrame::Value(_)) => {
+                                    (&WILDCARD_FRAME, WILDCARD_FRAME.flags())
+                                }
+                                // Implicit frame with the same flags
+                                _ => (&WILDCARD_FRAME, flags),
+                            };
+                            let mut nodes = Vec::new();
+                            self.match_frame(
+                                [object.as_str()],
+                                subframe,
+                                subflags,
+                                &mut nodes,
+                                false,
+                            );
+                            framed_values.extend(nodes.into_iter().map(FramedValue::Node));
+                        }
+                        (NodeValue::Literal(literal), Some(PropertyFrame::Value(pattern))) => {
+                            if pattern.matches(literal) {
+                                framed_values.push(FramedValue::Literal(literal));
+                            }
+                        }
+                        (NodeValue::Literal(literal), _) => {
+                            framed_values.push(FramedValue::Literal(literal));
+                        }
+                    }
+                }
+                if !framed_values.is_empty() {
+                    framed.properties.insert(property.as_str(), framed_values);
+                }
+            }
+
+            // Default values
+            for (property, subframe) in &frame.properties {
+                let (default, omit_default) = match subframe {
+                    PropertyFrame::Node(subframe) => {
+                        (subframe.default.as_ref(), subframe.omit_default)
+                    }
+                    PropertyFrame::MatchNone | PropertyFrame::Value(_) => (None, None),
+                };
+                if !omit_default.unwrap_or(false)
+                    && !framed.properties.contains_key(property.as_str())
+                {
+                    framed
+                        .properties
+                        .insert(property.as_str(), vec![FramedValue::Default(default)]);
+                }
+            }
+
+            output.push(framed);
+            self.stack.pop();
+        }
+    }
+
+    /// [Frame Matching](https://www.w3.org/TR/json-ld11-framing/#frame-matching)
+    fn filter_node(&self, node: &Node, id: &str, frame: &NodeFrame, require_all: bool) -> bool {
+        let mut is_wildcard = true;
+        let mut matches_some = false;
+        if let Some(ids) = &frame.ids {
+            let matches = match ids {
+                Pattern::Values(ids) => ids.iter().any(|expected| expected == id),
+                Pattern::Wildcard | Pattern::MatchNone => true,
+            };
+            if !require_all || !matches {
+                return matches;
+            }
+            matches_some = true;
+        }
+        if let Some(types) = &frame.types {
+            is_wildcard = false;
+            let matches = match types {
+                Pattern::MatchNone => {
+                    if !node.types.is_empty() {
+                        return false;
+                    }
+                    true
+                }
+                Pattern::Wildcard => !node.types.is_empty(),
+                Pattern::Values(types) => {
+                    let matches = frame.default_type.is_some()
+                        || types.iter().any(|t| node.types.contains(t));
+                    if !require_all {
+                        return matches;
+                    }
+                    matches
+                }
+            };
+            if !matches && require_all {
+                return false;
+            }
+            matches_some |= matches;
+        }
+        for (property, property_frame) in &frame.properties {
+            is_wildcard = false;
+            let values = node.properties.get(property).map_or(&[][..], Vec::as_slice);
+            let matches = match property_frame {
+                PropertyFrame::MatchNone => {
+                    if !values.is_empty() {
+                        return false;
+                    }
+                    true
+                }
+                PropertyFrame::Value(pattern) => values.iter().any(|value| {
+                    if let NodeValue::Literal(literal) = value {
+                        pattern.matches(literal)
+                    } else {
+                        false
+                    }
+                }),
+                PropertyFrame::Node(subframe) => {
+                    if values.is_empty() && subframe.default.is_some() {
+                        // The default value will be used
+                        continue;
+                    }
+                    if subframe.is_reference() {
+                        values.iter().any(|value| {
+                            if let NodeValue::Node(object) = value {
+                                self.filter_node(&Node::default(), object, subframe, require_all)
+                            } else {
+                                false
+                            }
+                        })
+                    } else {
+                        !values.is_empty()
+                    }
+                }
+            };
+            if !matches && require_all {
+                return false;
+            }
+            matches_some |= matches;
+        }
+        is_wildcard || matches_some
+    }
+}
+
+/// Compacts the framing output
+struct FramedWriter<'a> {
+    context: &'a JsonLdContext,
+    /// The number of occurrences of the blank nodes, the ones used only once are not written
+    blank_node_counts: HashMap<&'a str, usize>,
+}
+
+impl<'a> FramedWriter<'a> {
+    fn count_blank_nodes(&mut self, node: &FramedNode<'a>) {
+        if node.id.starts_with("_:") {
+            *self.blank_node_counts.entry(node.id).or_default() += 1;
+        }
+        for node in &node.graph {
+            self.count_blank_nodes(node);
+        }
+        for values in node.properties.values() {
+            for value in values {
+                if let FramedValue::Node(node) = value {
+                    self.count_blank_nodes(node);
+                }
+            }
+        }
+    }
+
+    fn is_pruned(&self, id: &str) -> bool {
+        id.starts_with("_:")
+            && self
+                .blank_node_counts
+                .get(id)
+                .is_none_or(|count| *count <= 1)
+    }
+
+    fn write_node(&self, node: &FramedNode<'a>, output: &mut Vec<JsonEvent<'a>>) -> io::Result<()> {
+        output.push(JsonEvent::StartObject);
+        self.write_node_entries(node, output)?;
+        output.push(JsonEvent::EndObject);
+        Ok(())
+    }
+
+    fn write_node_entries(
+        &self,
+        node: &FramedNode<'a>,
+        output: &mut Vec<JsonEvent<'a>>,
+    ) -> io::Result<()> {
+        if !self.is_pruned(node.id) {
+            output.push(JsonEvent::ObjectKey(keyword_alias(self.context, "@id")));
+            output.push(JsonEvent::String(if node.id.starts_with("_:") {
+                node.id.into()
+            } else {
+                compact_iri(self.context, node.id, false)
+            }));
+        }
+        if !node.types.is_empty() {
+            output.push(JsonEvent::ObjectKey(keyword_alias(self.context, "@type")));
+            if let [t] = node.types.as_slice() {
+                output.push(JsonEvent::String(compact_vocab_iri(self.context, t)));
+            } else {
+                output.push(JsonEvent::StartArray);
+                for t in &node.types {
+                    output.push(JsonEvent::String(compact_vocab_iri(self.context, t)));
+                }
+                output.push(JsonEvent::EndArray);
+            }
+        }
+        if !node.graph.is_empty() {
+            output.push(JsonEvent::ObjectKey(keyword_alias(self.context, "@graph")));
+            output.push(JsonEvent::StartArray);
+            for node in &node.graph {
+                self.write_node(node, output)?;
+            }
+            output.push(JsonEvent::EndArray);
+        }
+
+        // We group the values by key
+        let mut entries = Vec::<(Cow<'a, str>, bool, Vec<Vec<JsonEvent<'a>>>)>::new();
+        for (property, values) in &node.properties {
+            for value in values {
+                let (key, definition, chunks) = self.compact_property_value(property, value)?;
+                let force_array = definition
+                    .is_some_and(|definition| definition.container_mapping.contains(&"@set"))
+                    || matches!(value, FramedValue::Default(Some(JsonNode::Array(_))));
+                if let Some((_, entry_force_array, entry_chunks)) = entries
+                    .iter_mut()
+                    .find(|(entry_key, _, _)| *entry_key == key)
+                {
+                    *entry_force_array |= force_array;
+                    entry_chunks.extend(chunks);
+                } else {
+                    entries.push((key, force_array, chunks));
+                }
+            }
+        }
+        for (key, force_array, chunks) in entries {
+            output.push(JsonEvent::ObjectKey(key));
+            if chunks.len() == 1 && !force_array {
+                output.extend(chunks.into_iter().flatten());
+            } else {
+                output.push(JsonEvent::StartArray);
+                output.extend(chunks.into_iter().flatten());
+                output.push(JsonEvent::EndArray);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the key, its definition and the JSON values of a property value
+    fn compact_property_value(
+        &self,
+        property: &'a str,
+        value: &FramedValue<'a>,
+    ) -> io::Result<(
+        Cow<'a, str>,
+        Option<&'a JsonLdTermDefinition>,
+        Vec<Vec<JsonEvent<'a>>>,
+    )> {
+        let mut events = Vec::new();
+        let (key, definition) = match value {
+            FramedValue::Literal(literal) => {
+                let value = TermRef::from(literal.as_ref());
+                let (key, definition) = self.select_key(property, value);
+                compact_value(self.context, definition, value, &mut events)?;
+                (key, definition)
+            }
+            FramedValue::Node(node) => {
+                let value = if let Some(id) = node.id.strip_prefix("_:") {
+                    BlankNodeRef::new_unchecked(id).into()
+                } else {
+                    NamedNodeRef::new_unchecked(node.id).into()
+                };
+                let (key, definition) = self.select_key(property, value);
+                if !node.is_reference() {
+                    self.write_node(node, &mut events)?;
+                } else if self.is_pruned(node.id) {
+                    events.push(JsonEvent::StartObject);
+                    events.push(JsonEvent::EndObject);
+                } else {
+                    compact_value(self.context, definition, value, &mut events)?;
+                }
+                (key, definition)
+            }
+            FramedValue::Default(value) => {
+                let key = compact_vocab_iri(self.context, property);
+                let definition = self.context.term_definitions.get(key.as_ref());
+                return Ok((
+                    key,
+                    definition,
+                    match value {
+                        Some(JsonNode::Array(values)) => values
+                            .iter()
+                            .map(|value| {
+                                let mut events = Vec::new();
+                                json_node_events(value, &mut events);
+                                events
+                            })
+                            .collect(),
+                        Some(value) => {
+                            json_node_events(value, &mut events);
+                            vec![events]
+                        }
+                        None => vec![vec![JsonEvent::Null]],
+                    },
+                ));
+            }
+        };
+        Ok((key, definition, vec![events]))
+    }
+
+    fn select_key(
+        &self,
+        property: &'a str,
+        value: TermRef<'_>,
+    ) -> (Cow<'a, str>, Option<&'a JsonLdTermDefinition>) {
+        if let Some((term, definition)) = select_term(self.context, property, value) {
+            (term.into(), Some(definition))
+        } else {
+            (compact_iri(self.context, property, true), None)
+        }
+    }
+}
+
+/// Writes a JSON value given in the frame, `"@null"` being `null`
+fn json_node_events<'a>(node: &'a JsonNode, output: &mut Vec<JsonEvent<'a>>) {
+    match node {
+        JsonNode::String(value) if value == "@null" => output.push(JsonEvent::Null),
+        JsonNode::String(value) => output.push(JsonEvent::String(value.as_str().into())),
+        JsonNode::Number(value) => output.push(JsonEvent::Number(value.as_str().into())),
+        JsonNode::Boolean(value) => output.push(JsonEvent::Boolean(*value)),
+        JsonNode::Null => output.push(JsonEvent::Null),
+        JsonNode::Array(values) => {
+            output.push(JsonEvent::StartArray);
+            for value in values {
+                json_node_events(value, output);
+            }
+            output.push(JsonEvent::EndArray);
+        }
+        JsonNode::Object(entries) => {
+            let mut entries = entries.iter().collect::<Vec<_>>();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            output.push(JsonEvent::StartObject);
+            for (key, value) in entries {
+                output.push(JsonEvent::ObjectKey(key.as_str().into()));
+                json_node_events(value, output);
+            }
+            output.push(JsonEvent::EndObject);
+        }
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::JsonLdParser;
+    use crate::context::json_slice_to_events;
+    use std::error::Error;
+
+    const LIBRARY: &str = r#"{
+        "@context": {
+            "dc": "http://purl.org/dc/elements/1.1/",
+            "ex": "http://example.org/vocab#",
+            "ex:contains": {"@type": "@id"}
+        },
+        "@graph": [
+            {
+                "@id": "http://example.org/library",
+                "@type": "ex:Library",
+                "ex:contains": "http://example.org/library/the-republic"
+            },
+            {
+                "@id": "http://example.org/library/the-republic",
+                "@type": "ex:Book",
+                "dc:creator": "Plato",
+                "dc:title": "The Republic",
+                "ex:contains": "http://example.org/library/the-republic#introduction"
+            },
+            {
+                "@id": "http://example.org/library/the-republic#introduction",
+                "@type": "ex:Chapter",
+                "dc:description": "An introductory chapter on The Republic.",
+                "dc:title": "The Introduction"
+            }
+        ]
+    }"#;
+
+    fn assert_framing(input: &str, frame: &str, expected: &str) -> Result<(), Box<dyn Error>> {
+        let quads = JsonLdParser::new()
+            .for_slice(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()?;
+        let output = frame.parse::<JsonLdFrame>()?.frame(&quads, Vec::new())?;
+        assert_eq!(
+            json_node_from_events(json_slice_to_events(&output)?.into_iter().map(Ok))?,
+            json_node_from_events(
+                json_slice_to_events(expected.as_bytes())?
+                    .into_iter()
+                    .map(Ok)
+            )?,
+            "Unexpected output {}",
+            String::from_utf8_lossy(&output)
+        );
+        Ok(())
+    }
+
+    /// W3C framing test suite #t0001
+    #[test]
+    fn test_library() -> Result<(), Box<dyn Error>> {
+        assert_framing(
+            LIBRARY,
+            r#"{
+                "@context": {"dc": "http://purl.org/dc/elements/1.1/", "ex": "http://example.org/vocab#"},
+                "@type": "ex:Library",
+                "ex:contains": {"@type": "ex:Book", "ex:contains": {"@type": "ex:Chapter"}}
+            }"#,
+            r#"{
+                "@context": {"dc": "http://purl.org/dc/elements/1.1/", "ex": "http://example.org/vocab#"},
+                "@id": "http://example.org/library",
+                "@type": "ex:Library",
+                "ex:contains": {
+                    "@id": "http://example.org/library/the-republic",
+                    "@type": "ex:Book",
+                    "dc:creator": "Plato",
+                    "dc:title": "The Republic",
+                    "ex:contains": {
+                        "@id": "http://example.org/library/the-republic#introduction",
+                        "@type": "ex:Chapter",
+                        "dc:description": "An introductory chapter on The Republic.",
+                        "dc:title": "The Introduction"
+                    }
+                }
+            }"#,
+        )
+    }
+
+    /// W3C framing test suite #t0004 and #t0005
+    #[test]
+    fn test_explicit_and_embed_never() -> Result<(), Box<dyn Error>> {
+        assert_framing(
+            LIBRARY,
+            r#"{
+                "@context": {"dc": "http://purl.org/dc/elements/1.1/", "ex": "http://example.org/vocab#"},
+                "@type": "ex:Book",
+                "@explicit": true,
+                "dc:title": {},
+                "ex:contains": {"@embed": "@never"}
+            }"#,
+            r#"{
+                "@context": {"dc": "http://purl.org/dc/elements/1.1/", "ex": "http://example.org/vocab#"},
+                "@id": "http://example.org/library/the-republic",
+                "@type": "ex:Book",
+                "dc:title": "The Republic",
+                "ex:contains": {"@id": "http://example.org/library/the-republic#introduction"}
+            }"#,
+        )
+    }
+
+    /// W3C framing test suite #t0003 and #t0029
+    #[test]
+    fn test_defaults() -> Result<(), Box<dyn Error>> {
+        assert_framing(
+            LIBRARY,
+            r#"{
+                "@context": {"dc": "http://purl.org/dc/elements/1.1/", "ex": "http://example.org/vocab#"},
+                "@type": "ex:Chapter",
+                "dc:creator": {"@default": "Unknown"},
+                "dc:subject": {},
+                "ex:pages": {"@omitDefault": true}
+            }"#,
+            r#"{
+                "@context": {"dc": "http://purl.org/dc/elements/1.1/", "ex": "http://example.org/vocab#"},
+                "@id": "http://example.org/library/the-republic#introduction",
+                "@type": "ex:Chapter",
+                "dc:creator": "Unknown",
+                "dc:description": "An introductory chapter on The Republic.",
+                "dc:subject": null,
+                "dc:title": "The Introduction"
+            }"#,
+        )
+    }
+
+    /// W3C framing test suite #t0020 and #t0021
+    #[test]
+    fn test_type_patterns() -> Result<(), Box<dyn Error>> {
+        let input = r#"{
+            "@context": {"@vocab": "http://example.org/"},
+            "@graph": [
+                {"@id": "http://example.org/a", "@type": "T", "p": "a"},
+                {"@id": "http://example.org/b", "p": "b"}
+            ]
+        }"#;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@type": {}}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@id": "http://example.org/a", "@type": "T", "p": "a"}"#,
+        )?;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@type": [], "p": {}}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@id": "http://example.org/b", "p": "b"}"#,
+        )?;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@id": "http://example.org/b", "@type": {"@default": "D"}}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@id": "http://example.org/b", "@type": "D", "p": "b"}"#,
+        )
+    }
+
+    /// W3C framing test suite #t0026 and #t0063
+    #[test]
+    fn test_property_patterns() -> Result<(), Box<dyn Error>> {
+        let input = r#"{
+            "@context": {"@vocab": "http://example.org/"},
+            "@graph": [
+                {"@id": "http://example.org/a", "p": {"@value": "x", "@language": "en"}, "q": "a"},
+                {"@id": "http://example.org/b", "p": "x"},
+                {"@id": "http://example.org/c", "q": "c"}
+            ]
+        }"#;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "p": {"@value": "x", "@language": "EN"}}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@id": "http://example.org/a", "p": {"@value": "x", "@language": "en"}, "q": "a"}"#,
+        )?;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "p": [], "q": {}}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@id": "http://example.org/c", "p": null, "q": "c"}"#,
+        )?;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@requireAll": true, "p": {}, "q": {}}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@id": "http://example.org/a", "p": {"@value": "x", "@language": "en"}, "q": "a"}"#,
+        )?;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "p": {}, "q": {}}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@graph": [
+                {"@id": "http://example.org/a", "p": {"@value": "x", "@language": "en"}, "q": "a"},
+                {"@id": "http://example.org/b", "p": "x", "q": null},
+                {"@id": "http://example.org/c", "p": null, "q": "c"}
+            ]}"#,
+        )
+    }
+
+    /// W3C framing test suite #t0010 and #tp020
+    #[test]
+    fn test_embedding() -> Result<(), Box<dyn Error>> {
+        let input = r#"{
+            "@context": {"@vocab": "http://example.org/"},
+            "@graph": [
+                {"@id": "http://example.org/a", "@type": "Top", "p1": {"@id": "_:b"}, "p2": {"@id": "_:b"}},
+                {"@id": "_:b", "name": "B", "loop": {"@id": "http://example.org/a"}},
+                {"@id": "http://example.org/c", "@type": "Top", "p1": {"@id": "_:d", "name": "D"}}
+            ]
+        }"#;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@type": "Top"}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@graph": [
+                {
+                    "@id": "http://example.org/a",
+                    "@type": "Top",
+                    "p1": {"@id": "_:b", "name": "B", "loop": {"@id": "http://example.org/a"}},
+                    "p2": {"@id": "_:b"}
+                },
+                {"@id": "http://example.org/c", "@type": "Top", "p1": {"name": "D"}}
+            ]}"#,
+        )?;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@type": "Top", "@embed": "@always"}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@graph": [
+                {
+                    "@id": "http://example.org/a",
+                    "@type": "Top",
+                    "p1": {"@id": "_:b", "name": "B", "loop": {"@id": "http://example.org/a"}},
+                    "p2": {"@id": "_:b", "name": "B", "loop": {"@id": "http://example.org/a"}}
+                },
+                {"@id": "http://example.org/c", "@type": "Top", "p1": {"name": "D"}}
+            ]}"#,
+        )
+    }
+
+    /// W3C framing test suite #t0046
+    #[test]
+    fn test_graph() -> Result<(), Box<dyn Error>> {
+        let input = r#"{
+            "@context": {"@vocab": "http://example.org/"},
+            "@id": "http://example.org/g",
+            "@type": "Graph",
+            "@graph": {"@id": "http://example.org/a", "p": "a"}
+        }"#;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@type": "Graph", "@graph": {}}"#,
+            r#"{
+                "@context": {"@vocab": "http://example.org/"},
+                "@id": "http://example.org/g",
+                "@type": "Graph",
+                "@graph": [{"@id": "http://example.org/a", "p": "a"}]
+            }"#,
+        )?;
+        assert_framing(
+            input,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@type": "Graph"}"#,
+            r#"{"@context": {"@vocab": "http://example.org/"}, "@id": "http://example.org/g", "@type": "Graph"}"#,
+        )
+    }
+
+    #[test]
+    fn test_compaction() -> Result<(), Box<dyn Error>> {
+        assert_framing(
+            LIBRARY,
+            r#"{
+                "@context": {
+                    "id": "@id",
+                    "type": "@type",
+                    "ex": "http://example.org/vocab#",
+                    "title": "http://purl.org/dc/elements/1.1/title",
+                    "contains": {"@id": "ex:contains", "@type": "@id", "@container": "@set"}
+                },
+                "type": "ex:Book",
+                "contains": {"@embed": "@never"}
+            }"#,
+            r#"{
+                "@context": {
+                    "id": "@id",
+                    "type": "@type",
+                    "ex": "http://example.org/vocab#",
+                    "title": "http://purl.org/dc/elements/1.1/title",
+                    "contains": {"@id": "ex:contains", "@type": "@id", "@container": "@set"}
+                },
+                "id": "http://example.org/library/the-republic",
+                "type": "ex:Book",
+                "http://purl.org/dc/elements/1.1/creator": "Plato",
+                "title": "The Republic",
+                "contains": ["http://example.org/library/the-republic#introduction"]
+            }"#,
+        )
+    }
+
+    #[test]
+    fn test_invalid_frames() {
+        for (frame, code) in [
+            (r#"{"@embed": "@link"}"#, "invalid @embed value"),
+            (r#"{"@explicit": "yes"}"#, "invalid frame"),
+            ("[{}, {}]", "invalid frame"),
+            (r#"{"@type": [1]}"#, "invalid frame"),
+        ] {
+            assert_eq!(
+                frame
+                    .parse::<JsonLdFrame>()
+                    .err()
+                    .and_then(|error| error.code())
+                    .map(|code| code.to_string())
+                    .as_deref(),
+                Some(code),
+                "{frame}"
+            );
+        }
+        assert!(r#"{"ex:p": {"@reverse": {}}}"#.parse::<JsonLdFrame>().is_err());
+    }
+}
@@ -1,17 +1,25 @@
+use crate::context::{
+    JsonLdContext, JsonLdContextProcessor, JsonLdTermDefinition, into_owned_event,
+    json_node_from_events, json_slice_to_events,
+};
+use crate::error::JsonLdSyntaxError;
+use crate::{JsonLdProcessingMode, JsonLdProfile, JsonLdProfileSet, MAX_CONTEXT_RECURSION};
 #[cfg(feature = "async-tokio")]
 use json_event_parser::TokioAsyncWriterJsonSerializer;
 use json_event_parser::{JsonEvent, WriterJsonSerializer};
 use oxiri::{Iri, IriParseError};
 #[cfg(feature = "rdf-12")]
 use oxrdf::BaseDirection;
-use oxrdf::vocab::xsd;
+use oxrdf::vocab::{rdf, xsd};
 use oxrdf::{
-    GraphName, GraphNameRef, NamedNode, NamedOrBlankNode, NamedOrBlankNodeRef, QuadRef, TermRef,
+    GraphName, GraphNameRef, NamedNodeRef, NamedOrBlankNode, NamedOrBlankNodeRef, QuadRef, TermRef,
 };
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet};
 use std::io;
 use std::io::Write;
+use std::sync::Arc;
 #[cfg(feature = "async-tokio")]
 use tokio::io::AsyncWrite;
 
@@ -23,6 +31,10 @@ use tokio::io::AsyncWrite;
 /// to be a streaming serializer but aims at being close to it.
 /// Features like `@json` and `@list` generation are not implemented.
 ///
+/// The output is in expanded form unless a context is given with [`with_context`](Self::with_context).
+/// The `rdf:type` triples are only written with `@type` when a context is given
+/// or when the [streaming profile](Self::with_profile) is set.
+///
 /// ```
 /// use oxrdf::{GraphNameRef, LiteralRef, NamedNodeRef, QuadRef};
 /// use oxrdf::vocab::rdf;
@@ -52,6 +64,49 @@ use tokio::io::AsyncWrite;
 pub struct JsonLdSerializer {
     prefixes: BTreeMap<String, String>,
     base_iri: Option<Iri<String>>,
+    context: Option<Box<CompactionContext>>,
+    profile: JsonLdProfileSet,
+}
+
+/// A context the output is compacted against
+#[derive(Clone)]
+pub struct CompactionContext {
+    /// The context as it is written in the output
+    pub source: Vec<JsonEvent<'static>>,
+    /// The processed context
+    pub active: JsonLdContext,
+}
+
+impl CompactionContext {
+    /// Processes the events of a `@context` value
+    pub fn new(source: Vec<JsonEvent<'static>>) -> Result<Self, JsonLdSyntaxError> {
+        let mut errors = Vec::new();
+        let active = local_context_processor().process_context(
+            &JsonLdContext::new_empty(None),
+            json_node_from_events(source.iter().cloned().map(Ok))?,
+            None,
+            &mut Vec::new(),
+            false,
+            true,
+            true,
+            &mut errors,
+        );
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error);
+        }
+        Ok(Self { source, active })
+    }
+}
+
+/// A JSON-LD 1.1 context processor that does not load remote contexts
+pub fn local_context_processor() -> JsonLdContextProcessor {
+    JsonLdContextProcessor {
+        processing_mode: JsonLdProcessingMode::JsonLd1_1,
+        lenient: false,
+        max_context_recursion: MAX_CONTEXT_RECURSION,
+        remote_context_cache: Arc::default(),
+        document_loader: None,
+    }
 }
 
 impl JsonLdSerializer {
@@ -61,6 +116,8 @@ impl JsonLdSerializer {
         Self {
             prefixes: BTreeMap::new(),
             base_iri: None,
+            context: None,
+            profile: JsonLdProfileSet::empty(),
         }
     }
 
@@ -112,6 +169,80 @@ impl JsonLdSerializer {
         Ok(self)
     }
 
+    /// Compacts the output against the given [`@context`](https://www.w3.org/TR/json-ld/#the-context) value.
+    ///
+    /// The context is written as is in the output.
+    /// It is used to write the properties and types with terms, compact IRIs or IRIs relative to `@vocab`,
+    /// the `@id` values with compact IRIs or IRIs relative to `@base`
+    /// and the values as plain strings if the term definitions or the default language allow it.
+    /// The keyword aliases are used and the property values are only wrapped in an array if there are several of them
+    /// or if the term has a `@set` container.
+    ///
+    /// It replaces the context built from [`with_prefix`](Self::with_prefix) and [`with_base_iri`](Self::with_base_iri).
+    /// Remote contexts are not supported.
+    ///
+    /// <div class="warning">The output does not follow the Streaming JSON-LD profile anymore: `@type` is written after `@id`.
+    /// Set the [streaming profile](Self::with_profile) to get it back.</div>
+    ///
+    /// ```
+    /// use oxrdf::{GraphNameRef, LiteralRef, NamedNodeRef, QuadRef};
+    /// use oxrdf::vocab::rdf;
+    /// use oxjsonld::JsonLdSerializer;
+    ///
+    /// let mut serializer = JsonLdSerializer::new()
+    ///     .with_context(r#"{"@vocab": "http://schema.org/", "ex": "http://example.com/", "knows": {"@type": "@id"}}"#)?
+    ///     .for_writer(Vec::new());
+    /// let me = NamedNodeRef::new("http://example.com/me")?;
+    /// let knows = NamedNodeRef::new("http://schema.org/knows")?;
+    /// serializer.serialize_quad(QuadRef::new(me, rdf::TYPE, NamedNodeRef::new("http://schema.org/Person")?, GraphNameRef::DefaultGraph))?;
+    /// serializer.serialize_quad(QuadRef::new(me, NamedNodeRef::new("http://schema.org/name")?, LiteralRef::new_simple_literal("Foo"), GraphNameRef::DefaultGraph))?;
+    /// serializer.serialize_quad(QuadRef::new(me, knows, NamedNodeRef::new("http://example.com/bar")?, GraphNameRef::DefaultGraph))?;
+    /// serializer.serialize_quad(QuadRef::new(me, knows, NamedNodeRef::new("http://example.com/baz")?, GraphNameRef::DefaultGraph))?;
+    /// assert_eq!(
+    ///     r#"{"@context":{"@vocab":"http://schema.org/","ex":"http://example.com/","knows":{"@type":"@id"}},"@graph":[{"@id":"ex:me","@type":"Person","name":"Foo","knows":["ex:bar","ex:baz"]}]}"#,
+    ///     String::from_utf8(serializer.finish()?)?
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn with_context(mut self, context: &str) -> Result<Self, JsonLdSyntaxError> {
+        self.context = Some(Box::new(CompactionContext::new(json_slice_to_events(
+            context.as_bytes(),
+        )?)?));
+        Ok(self)
+    }
+
+    /// Follows the given profile(s) during serialization.
+    ///
+    /// If the [Streaming JSON-LD](https://www.w3.org/TR/json-ld11-streaming/) profile ([`JsonLdProfile::Streaming`]) is set,
+    /// the `rdf:type` triples are written with `@type` before `@id` as required by the profile
+    /// and the output can be parsed with the same profile.
+    /// The quads are still written as they arrive, without buffering:
+    /// a type given after other properties of a subject starts a new node object for the subject.
+    ///
+    /// ```
+    /// use oxrdf::{GraphNameRef, LiteralRef, NamedNodeRef, QuadRef};
+    /// use oxrdf::vocab::rdf;
+    /// use oxjsonld::{JsonLdProfile, JsonLdSerializer};
+    ///
+    /// let mut serializer = JsonLdSerializer::new()
+    ///     .with_context(r#"{"@vocab": "http://schema.org/"}"#)?
+    ///     .with_profile(JsonLdProfile::Streaming)
+    ///     .for_writer(Vec::new());
+    /// let me = NamedNodeRef::new("http://example.com/me")?;
+    /// serializer.serialize_quad(QuadRef::new(me, rdf::TYPE, NamedNodeRef::new("http://schema.org/Person")?, GraphNameRef::DefaultGraph))?;
+    /// serializer.serialize_quad(QuadRef::new(me, NamedNodeRef::new("http://schema.org/name")?, LiteralRef::new_simple_literal("Foo"), GraphNameRef::DefaultGraph))?;
+    /// assert_eq!(
+    ///     r#"{"@context":{"@vocab":"http://schema.org/"},"@graph":[{"@type":"Person","@id":"http://example.com/me","name":"Foo"}]}"#,
+    ///     String::from_utf8(serializer.finish()?)?
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_profile(mut self, profile: impl Into<JsonLdProfileSet>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
     /// Serializes a JSON-LD file to a [`Write`] implementation.
     ///
     /// This writer does unbuffered writes.
@@ -194,10 +325,15 @@ impl JsonLdSerializer {
             started: false,
             current_graph_name: None,
             current_subject: None,
-            current_predicate: None,
-            emitted_predicates: BTreeSet::new(),
+            pending_id: None,
+            current_key: None,
+            is_current_key_array: false,
+            pending_value: None,
+            emitted_keys: BTreeSet::new(),
             prefixes: self.prefixes,
             base_iri: self.base_iri,
+            context: self.context,
+            streaming: self.profile.contains(JsonLdProfile::Streaming),
         }
     }
 }
@@ -327,10 +463,19 @@ pub struct InnerJsonLdWriter {
     started: bool,
     current_graph_name: Option<GraphName>,
     current_subject: Option<NamedOrBlankNode>,
-    current_predicate: Option<NamedNode>,
-    emitted_predicates: BTreeSet<String>,
+    /// The `@id` of the current subject, written after its `@type` in streaming mode
+    pending_id: Option<String>,
+    current_key: Option<String>,
+    /// If the values of the current key are written in an array
+    is_current_key_array: bool,
+    /// The first value of the current key, kept until we know if an array is needed
+    pending_value: Option<Vec<JsonEvent<'static>>>,
+    emitted_keys: BTreeSet<String>,
     prefixes: BTreeMap<String, String>,
     base_iri: Option<Iri<String>>,
+    context: Option<Box<CompactionContext>>,
+    /// If the Streaming JSON-LD profile is followed
+    streaming: bool,
 }
 
 impl InnerJsonLdWriter {
@@ -345,13 +490,14 @@ impl InnerJsonLdWriter {
         }
 
         let quad = quad.into();
+        let (key, value, force_array) = self.key_and_value(quad.predicate, quad.object)?;
+        let is_type = self.is_type(quad.predicate, quad.object);
         if self
             .current_graph_name
             .as_ref()
             .is_some_and(|graph_name| graph_name.as_ref() != quad.graph_name)
         {
-            output.push(JsonEvent::EndArray);
-            output.push(JsonEvent::EndObject);
+            self.close_subject(output);
             if self
                 .current_graph_name
                 .as_ref()
@@ -362,61 +508,58 @@ impl InnerJsonLdWriter {
             }
             self.current_graph_name = None;
             self.current_subject = None;
-            self.current_predicate = None;
-            self.emitted_predicates.clear();
+            self.emitted_keys.clear();
         } else if self
             .current_subject
             .as_ref()
             .is_some_and(|subject| subject.as_ref() != quad.subject)
             || self
-                .current_predicate
+                .current_key
                 .as_ref()
-                .is_some_and(|predicate| predicate.as_ref() != quad.predicate)
-                && self.emitted_predicates.contains(quad.predicate.as_str())
+                .is_some_and(|current_key| *current_key != key)
+                && self.emitted_keys.contains(key.as_ref())
+            || self.streaming
+                && is_type
+                && self.current_subject.is_some()
+                && self.current_key.as_deref() != Some(key.as_ref())
         {
-            output.push(JsonEvent::EndArray);
-            output.push(JsonEvent::EndObject);
+            // In streaming mode, @type must be the first key
+            self.close_subject(output);
             self.current_subject = None;
-            self.emitted_predicates.clear();
-            self.current_predicate = None;
+            self.emitted_keys.clear();
         } else if self
-            .current_predicate
+            .current_key
             .as_ref()
-            .is_some_and(|predicate| predicate.as_ref() != quad.predicate)
+            .is_some_and(|current_key| *current_key != key)
         {
-            output.push(JsonEvent::EndArray);
-            if let Some(current_predicate) = self.current_predicate.take() {
-                self.emitted_predicates
-                    .insert(current_predicate.into_string());
-            }
+            self.close_key(output);
         }
 
         if self.current_graph_name.is_none() {
             if !quad.graph_name.is_default_graph() {
                 // We open a new graph name
                 output.push(JsonEvent::StartObject);
-                output.push(JsonEvent::ObjectKey("@id".into()));
+                output.push(JsonEvent::ObjectKey(self.keyword("@id")));
                 output.push(JsonEvent::String(self.id_value(match quad.graph_name {
                     GraphNameRef::NamedNode(iri) => iri.into(),
                     GraphNameRef::BlankNode(bnode) => bnode.into(),
                     GraphNameRef::DefaultGraph => unreachable!(),
                 })));
-                output.push(JsonEvent::ObjectKey("@graph".into()));
+                output.push(JsonEvent::ObjectKey(self.keyword("@graph")));
                 output.push(JsonEvent::StartArray);
             }
             self.current_graph_name = Some(quad.graph_name.into_owned());
         }
 
-        // We open a new subject block if useful (ie. new subject or already used predicate)
+        // We open a new subject block if useful (ie. new subject or already used key)
         if self.current_subject.is_none() {
             output.push(JsonEvent::StartObject);
-            output.push(JsonEvent::ObjectKey("@id".into()));
             #[allow(
                 unreachable_patterns,
                 clippy::match_wildcard_for_single_variants,
                 clippy::allow_attributes
             )]
-            output.push(JsonEvent::String(self.id_value(match quad.subject {
+            let id = self.id_value(match quad.subject {
                 NamedOrBlankNodeRef::NamedNode(iri) => iri.into(),
                 NamedOrBlankNodeRef::BlankNode(bnode) => bnode.into(),
                 _ => {
@@ -425,42 +568,163 @@ impl InnerJsonLdWriter {
                         "JSON-LD does not support RDF 1.2 yet",
                     ));
                 }
-            })));
+            });
+            if self.streaming && is_type {
+                self.pending_id = Some(id.into_owned());
+            } else {
+                output.push(JsonEvent::ObjectKey(self.keyword("@id")));
+                output.push(JsonEvent::String(id));
+            }
             self.current_subject = Some(quad.subject.into_owned());
         }
 
-        // We open a predicate key
-        if self.current_predicate.is_none() {
-            output.push(JsonEvent::ObjectKey(
-                // TODO: use @type
-                quad.predicate.as_str().into(), // TODO: prefixes including @vocab
-            ));
+        // We open a key
+        if self.current_key.is_none() {
+            if !is_type {
+                self.write_pending_id(output);
+            }
+            output.push(JsonEvent::ObjectKey(key.clone()));
+            if force_array {
+                output.push(JsonEvent::StartArray);
+                self.is_current_key_array = true;
+            }
+            self.current_key = Some(key.into_owned());
+        }
+
+        if self.is_current_key_array {
+            output.extend(value);
+        } else if let Some(pending_value) = self.pending_value.take() {
             output.push(JsonEvent::StartArray);
-            self.current_predicate = Some(quad.predicate.into_owned());
+            output.extend(pending_value);
+            output.extend(value);
+            self.is_current_key_array = true;
+        } else {
+            self.pending_value = Some(value.into_iter().map(into_owned_event).collect());
+        }
+        Ok(())
+    }
+
+    /// If the predicate and the object are written with `@type`
+    fn is_type(&self, predicate: NamedNodeRef<'_>, object: TermRef<'_>) -> bool {
+        predicate == rdf::TYPE
+            && matches!(object, TermRef::NamedNode(_) | TermRef::BlankNode(_))
+            && (self.context.is_some() || self.streaming)
+    }
+
+    /// Returns the object key and value to use for a predicate and an object,
+    /// and if the value must always be written in an array
+    fn key_and_value<'a>(
+        &self,
+        predicate: NamedNodeRef<'a>,
+        object: TermRef<'a>,
+    ) -> io::Result<(Cow<'a, str>, Vec<JsonEvent<'a>>, bool)> {
+        let mut value = Vec::new();
+        let Some(context) = &self.context else {
+            // TODO: prefixes including @vocab
+            if self.is_type(predicate, object) {
+                #[allow(clippy::match_wildcard_for_single_variants, clippy::allow_attributes)]
+                match object {
+                    TermRef::NamedNode(object) => {
+                        value.push(JsonEvent::String(Self::type_value(object.into())))
+                    }
+                    TermRef::BlankNode(object) => {
+                        value.push(JsonEvent::String(Self::type_value(object.into())))
+                    }
+                    _ => (),
+                }
+                return Ok(("@type".into(), value, true));
+            }
+            self.serialize_term(object, &mut value)?;
+            return Ok((predicate.as_str().into(), value, true));
+        };
+        let context = &context.active;
+        if predicate == rdf::TYPE {
+            #[allow(clippy::match_wildcard_for_single_variants, clippy::allow_attributes)]
+            match object {
+                TermRef::NamedNode(object) => {
+                    value.push(JsonEvent::String(compact_vocab_iri(
+                        context,
+                        object.as_str(),
+                    )));
+                    return Ok((keyword_alias(context, "@type"), value, false));
+                }
+                TermRef::BlankNode(object) => {
+                    value.push(JsonEvent::String(object.to_string().into()));
+                    return Ok((keyword_alias(context, "@type"), value, false));
+                }
+                _ => (),
+            }
+        }
+        Ok(
+            if let Some((term, definition)) = select_term(context, predicate.as_str(), object) {
+                compact_value(context, Some(definition), object, &mut value)?;
+                (
+                    term.to_owned().into(),
+                    value,
+                    definition.container_mapping.contains(&"@set"),
+                )
+            } else {
+                compact_value(context, None, object, &mut value)?;
+                (compact_iri(context, predicate.as_str(), true), value, false)
+            },
+        )
+    }
+
+    /// Closes the current key, writing its pending value or closing its array
+    fn close_key(&mut self, output: &mut Vec<JsonEvent<'_>>) {
+        if let Some(pending_value) = self.pending_value.take() {
+            output.extend(pending_value);
+        } else if self.is_current_key_array {
+            output.push(JsonEvent::EndArray);
         }
+        self.is_current_key_array = false;
+        if let Some(current_key) = self.current_key.take() {
+            self.emitted_keys.insert(current_key);
+        }
+    }
+
+    /// Writes the `@id` of the current subject if it has been delayed
+    fn write_pending_id(&mut self, output: &mut Vec<JsonEvent<'_>>) {
+        if let Some(id) = self.pending_id.take() {
+            output.push(JsonEvent::ObjectKey(self.keyword("@id")));
+            output.push(JsonEvent::String(id.into()));
+        }
+    }
+
+    /// Closes the current subject node object
+    fn close_subject(&mut self, output: &mut Vec<JsonEvent<'_>>) {
+        self.close_key(output);
+        self.write_pending_id(output);
+        output.push(JsonEvent::EndObject);
+    }
 
-        self.serialize_term(quad.object, output)
+    fn has_context(&self) -> bool {
+        self.context.is_some() || self.base_iri.is_some() || !self.prefixes.is_empty()
     }
 
     fn serialize_start(&self, output: &mut Vec<JsonEvent<'_>>) {
-        if self.base_iri.is_some() || !self.prefixes.is_empty() {
+        if self.has_context() {
             output.push(JsonEvent::StartObject);
             output.push(JsonEvent::ObjectKey("@context".into()));
-            output.push(JsonEvent::StartObject);
-            if let Some(base_iri) = &self.base_iri {
-                output.push(JsonEvent::ObjectKey("@base".into()));
-                output.push(JsonEvent::String(base_iri.to_string().into()));
-            }
-            for (prefix_name, prefix_iri) in &self.prefixes {
-                output.push(JsonEvent::ObjectKey(if prefix_name.is_empty() {
-                    "@vocab".into()
-                } else {
-                    prefix_name.clone().into()
-                }));
-                output.push(JsonEvent::String(prefix_iri.clone().into()));
+            if let Some(context) = &self.context {
+                output.extend(context.source.iter().cloned());
+            } else {
+                output.push(JsonEvent::StartObject);
+                if let Some(base_iri) = &self.base_iri {
+                    output.push(JsonEvent::ObjectKey("@base".into()));
+                    output.push(JsonEvent::String(base_iri.to_string().into()));
+                }
+                for (prefix_name, prefix_iri) in &self.prefixes {
+                    output.push(JsonEvent::ObjectKey(if prefix_name.is_empty() {
+                        "@vocab".into()
+                    } else {
+                        prefix_name.clone().into()
+                    }));
+                    output.push(JsonEvent::String(prefix_iri.clone().into()));
+                }
+                output.push(JsonEvent::EndObject);
             }
-            output.push(JsonEvent::EndObject);
-            output.push(JsonEvent::ObjectKey("@graph".into()));
+            output.push(JsonEvent::ObjectKey(self.keyword("@graph")));
         }
         output.push(JsonEvent::StartArray);
     }
@@ -523,18 +787,11 @@ impl InnerJsonLdWriter {
     fn id_value<'a>(&self, id: NamedOrBlankNodeRef<'a>) -> Cow<'a, str> {
         match id {
             NamedOrBlankNodeRef::NamedNode(iri) => {
-                if let Some(base_iri) = &self.base_iri {
-                    if let Ok(relative) = base_iri.relativize(&Iri::parse_unchecked(iri.as_str())) {
-                        let relative = relative.into_inner();
-                        // We check the relative IRI is not considered as absolute by IRI expansion
-                        if !relative.split_once(':').is_some_and(|(prefix, suffix)| {
-                            prefix == "_" || suffix.starts_with("//")
-                        }) {
-                            return relative.into();
-                        }
-                    }
+                if let Some(context) = &self.context {
+                    compact_iri(&context.active, iri.as_str(), false)
+                } else {
+                    relative_iri(self.base_iri.as_ref(), iri.as_str())
                 }
-                iri.as_str().into()
             }
             NamedOrBlankNodeRef::BlankNode(bnode) => bnode.to_string().into(),
         }
@@ -547,15 +804,20 @@ impl InnerJsonLdWriter {
         }
     }
 
+    fn keyword(&self, keyword: &'static str) -> Cow<'static, str> {
+        if let Some(context) = &self.context {
+            keyword_alias(&context.active, keyword)
+        } else {
+            keyword.into()
+        }
+    }
+
     fn finish(&mut self, output: &mut Vec<JsonEvent<'static>>) {
         if !self.started {
             self.serialize_start(output);
         }
-        if self.current_predicate.is_some() {
-            output.push(JsonEvent::EndArray)
-        }
         if self.current_subject.is_some() {
-            output.push(JsonEvent::EndObject)
+            self.close_subject(output);
         }
         if self
             .current_graph_name
@@ -566,8 +828,438 @@ impl InnerJsonLdWriter {
             output.push(JsonEvent::EndObject)
         }
         output.push(JsonEvent::EndArray);
-        if self.base_iri.is_some() || !self.prefixes.is_empty() {
+        if self.has_context() {
             output.push(JsonEvent::EndObject);
         }
     }
 }
+
+/// Finds the best term to use as key for a property with the given value.
+///
+/// It is a simplified version of the [Term Selection algorithm](https://www.w3.org/TR/json-ld-api/#term-selection):
+/// the terms whose definition allows to write the value as a plain string are preferred
+/// to the terms without type, language and direction mappings.
+/// The terms with a scoped context or a container other than `@set` are never used.
+pub fn select_term<'c>(
+    context: &'c JsonLdContext,
+    property: &str,
+    value: TermRef<'_>,
+) -> Option<(&'c str, &'c JsonLdTermDefinition)> {
+    context
+        .term_definitions
+        .iter()
+        .filter_map(|(term, definition)| {
+            if definition.reverse_property
+                || definition.context.is_some()
+                || definition.iri_mapping.as_ref()?.as_deref()? != property
+                || !definition
+                    .container_mapping
+                    .iter()
+                    .all(|container| *container == "@set")
+            {
+                return None;
+            }
+            let is_generic = definition.type_mapping.is_none()
+                && definition.language_mapping.is_none()
+                && definition.direction_mapping.is_none();
+            let rank = if is_plain_value(context, Some(definition), value) {
+                u8::from(!is_generic)
+            } else if is_generic {
+                0
+            } else {
+                return None;
+            };
+            Some((rank, term.as_str(), definition))
+        })
+        .min_by(|(rank1, term1, _), (rank2, term2, _)| {
+            rank2.cmp(rank1).then_with(|| compare_terms(term1, term2))
+        })
+        .map(|(_, term, definition)| (term, definition))
+}
+
+/// Checks if the value can be written as a plain string under a term with the given definition
+#[allow(
+    unreachable_patterns,
+    clippy::match_wildcard_for_single_variants,
+    clippy::allow_attributes
+)]
+fn is_plain_value(
+    context: &JsonLdContext,
+    definition: Option<&JsonLdTermDefinition>,
+    value: TermRef<'_>,
+) -> bool {
+    let type_mapping = definition.and_then(|definition| definition.type_mapping.as_deref());
+    match value {
+        TermRef::NamedNode(_) | TermRef::BlankNode(_) => {
+            matches!(type_mapping, Some("@id" | "@vocab"))
+        }
+        TermRef::Literal(literal) => {
+            if let Some(type_mapping) = type_mapping {
+                return literal.language().is_none() && literal.datatype().as_str() == type_mapping;
+            }
+            if literal.language().is_none() && literal.datatype() != xsd::STRING {
+                return false;
+            }
+            // The plain string gets the language and direction of the term or of the context
+            let language = definition
+                .and_then(|definition| definition.language_mapping.as_ref())
+                .map_or(context.default_language.as_deref(), Option::as_deref);
+            let direction = definition
+                .and_then(|definition| definition.direction_mapping)
+                .unwrap_or(context.default_direction);
+            #[cfg(feature = "rdf-12")]
+            let same_direction = literal.direction().map(|direction| match direction {
+                BaseDirection::Ltr => "ltr",
+                BaseDirection::Rtl => "rtl",
+            }) == direction;
+            #[cfg(not(feature = "rdf-12"))]
+            let same_direction = direction.is_none();
+            same_direction
+                && match (literal.language(), language) {
+                    (Some(literal_language), Some(language)) => {
+                        literal_language.eq_ignore_ascii_case(language)
+                    }
+                    (None, None) => true,
+                    _ => false,
+                }
+        }
+        _ => false,
+    }
+}
+
+/// Writes a value compacted for a term with the given definition
+pub fn compact_value<'a>(
+    context: &JsonLdContext,
+    definition: Option<&JsonLdTermDefinition>,
+    value: TermRef<'a>,
+    output: &mut Vec<JsonEvent<'a>>,
+) -> io::Result<()> {
+    let is_plain = is_plain_value(context, definition, value);
+    #[allow(
+        unreachable_patterns,
+        clippy::match_wildcard_for_single_variants,
+        clippy::allow_attributes
+    )]
+    match value {
+        TermRef::NamedNode(iri) => {
+            let vocab = definition
+                .is_some_and(|definition| definition.type_mapping.as_deref() == Some("@vocab"));
+            let iri = if vocab {
+                compact_vocab_iri(context, iri.as_str())
+            } else {
+                compact_iri(context, iri.as_str(), false)
+            };
+            if is_plain {
+                output.push(JsonEvent::String(iri));
+            } else {
+                output.push(JsonEvent::StartObject);
+                output.push(JsonEvent::ObjectKey(keyword_alias(context, "@id")));
+                output.push(JsonEvent::String(iri));
+                output.push(JsonEvent::EndObject);
+            }
+        }
+        TermRef::BlankNode(bnode) => {
+            if is_plain {
+                output.push(JsonEvent::String(bnode.to_string().into()));
+            } else {
+                output.push(JsonEvent::StartObject);
+                output.push(JsonEvent::ObjectKey(keyword_alias(context, "@id")));
+                output.push(JsonEvent::String(bnode.to_string().into()));
+                output.push(JsonEvent::EndObject);
+            }
+        }
+        TermRef::Literal(literal) => {
+            if is_plain {
+                output.push(JsonEvent::String(literal.value().into()));
+            } else {
+                output.push(JsonEvent::StartObject);
+                if let Some(language) = literal.language() {
+                    output.push(JsonEvent::ObjectKey(keyword_alias(context, "@language")));
+                    output.push(JsonEvent::String(language.into()));
+                    #[cfg(feature = "rdf-12")]
+                    if let Some(direction) = literal.direction() {
+                        output.push(JsonEvent::ObjectKey(keyword_alias(context, "@direction")));
+                        output.push(JsonEvent::String(
+                            match direction {
+                                BaseDirection::Ltr => "ltr",
+                                BaseDirection::Rtl => "rtl",
+                            }
+                            .into(),
+                        ));
+                    }
+                } else if literal.datatype() != xsd::STRING {
+                    output.push(JsonEvent::ObjectKey(keyword_alias(context, "@type")));
+                    output.push(JsonEvent::String(compact_vocab_iri(
+                        context,
+                        literal.datatype().as_str(),
+                    )));
+                }
+                output.push(JsonEvent::ObjectKey(keyword_alias(context, "@value")));
+                output.push(JsonEvent::String(literal.value().into()));
+                output.push(JsonEvent::EndObject);
+            }
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "JSON-LD does not support RDF 1.2 yet",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Compacts an IRI used as a type or a `@vocab` typed value, using the terms mapped to it if any
+pub fn compact_vocab_iri<'a>(context: &JsonLdContext, iri: &'a str) -> Cow<'a, str> {
+    context
+        .term_definitions
+        .iter()
+        .filter(|(_, definition)| {
+            !definition.reverse_property
+                && definition.context.is_none()
+                && definition
+                    .iri_mapping
+                    .as_ref()
+                    .is_some_and(|mapping| mapping.as_deref() == Some(iri))
+        })
+        .map(|(term, _)| term)
+        .min_by(|term1, term2| compare_terms(term1, term2))
+        .map_or_else(
+            || compact_iri(context, iri, true),
+            |term| term.clone().into(),
+        )
+}
+
+/// [IRI Compaction](https://www.w3.org/TR/json-ld-api/#iri-compaction) without the terms
+///
+/// If `vocab` is true, the IRI is made relative to `@vocab`, else to `@base`.
+pub fn compact_iri<'a>(context: &JsonLdContext, iri: &'a str, vocab: bool) -> Cow<'a, str> {
+    if vocab {
+        if let Some(suffix) = context
+            .vocabulary_mapping
+            .as_deref()
+            .and_then(|vocabulary_mapping| iri.strip_prefix(vocabulary_mapping))
+        {
+            if !suffix.is_empty() && !context.term_definitions.contains_key(suffix) {
+                return suffix.into();
+            }
+        }
+    }
+    if let Some(compact_iri) = context
+        .term_definitions
+        .iter()
+        .filter_map(|(term, definition)| {
+            if !definition.prefix_flag {
+                return None;
+            }
+            let suffix = iri.strip_prefix(definition.iri_mapping.as_ref()?.as_deref()?)?;
+            if suffix.is_empty() || suffix.starts_with("//") {
+                return None;
+            }
+            let compact_iri = format!("{term}:{suffix}");
+            (!context.term_definitions.contains_key(&compact_iri)).then_some(compact_iri)
+        })
+        .min_by(|iri1, iri2| compare_terms(iri1, iri2))
+    {
+        return compact_iri.into();
+    }
+    if vocab {
+        iri.into()
+    } else {
+        relative_iri(context.base_iri.as_ref(), iri)
+    }
+}
+
+fn relative_iri<'a>(base_iri: Option<&Iri<String>>, iri: &'a str) -> Cow<'a, str> {
+    if let Some(base_iri) = base_iri {
+        if let Ok(relative) = base_iri.relativize(&Iri::parse_unchecked(iri)) {
+            let relative = relative.into_inner();
+            // We check the relative IRI is not considered as absolute by IRI expansion
+            if !relative
+                .split_once(':')
+                .is_some_and(|(prefix, suffix)| prefix == "_" || suffix.starts_with("//"))
+            {
+                return relative.into();
+            }
+        }
+    }
+    iri.into()
+}
+
+/// The shortest term that is an alias of the keyword or the keyword itself
+pub fn keyword_alias(context: &JsonLdContext, keyword: &'static str) -> Cow<'static, str> {
+    context
+        .term_definitions
+        .iter()
+        .filter(|(_, definition)| {
+            definition
+                .iri_mapping
+                .as_ref()
+                .is_some_and(|mapping| mapping.as_deref() == Some(keyword))
+        })
+        .map(|(term, _)| term)
+        .min_by(|term1, term2| compare_terms(term1, term2))
+        .map_or_else(|| keyword.into(), |term| term.clone().into())
+}
+
+/// Orders the terms by length and then lexicographically
+fn compare_terms(term1: &str, term2: &str) -> Ordering {
+    term1.len().cmp(&term2.len()).then_with(|| term1.cmp(term2))
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use crate::JsonLdParser;
+    use oxrdf::{Literal, NamedNode, Quad};
+    use std::error::Error;
+
+    #[test]
+    fn test_compaction() -> Result<(), Box<dyn Error>> {
+        let context = r#"{
+            "@vocab": "http://schema.org/",
+            "@language": "en",
+            "ex": "http://example.com/",
+            "id": "@id",
+            "type": "@type",
+            "homepage": {"@id": "http://schema.org/url", "@type": "@id"},
+            "born": {"@id": "http://schema.org/birthDate", "@type": "http://www.w3.org/2001/XMLSchema#date"},
+            "nick": {"@id": "http://schema.org/alternateName", "@container": "@set"}
+        }"#;
+        let ex = |name: &str| NamedNode::new_unchecked(format!("http://example.com/{name}"));
+        let schema = |name: &str| NamedNode::new_unchecked(format!("http://schema.org/{name}"));
+        let quads = [
+            Quad::new(
+                ex("me"),
+                rdf::TYPE,
+                schema("Person"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                ex("me"),
+                schema("name"),
+                Literal::new_language_tagged_literal_unchecked("Foo", "en"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                ex("me"),
+                schema("name"),
+                Literal::new_language_tagged_literal_unchecked("Toto", "fr"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                ex("me"),
+                schema("description"),
+                Literal::new_simple_literal("A person"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                ex("me"),
+                schema("url"),
+                NamedNode::new_unchecked("http://foo.com/"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                ex("me"),
+                schema("url"),
+                Literal::new_simple_literal("foo.com"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                ex("me"),
+                schema("birthDate"),
+                Literal::new_typed_literal("2000-01-01", xsd::DATE),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                ex("me"),
+                schema("alternateName"),
+                Literal::new_language_tagged_literal_unchecked("F", "en"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(
+                ex("me"),
+                schema("knows"),
+                ex("other"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(ex("s"), schema("age"), Literal::from(12), ex("g")),
+        ];
+        let mut serializer = JsonLdSerializer::new()
+            .with_context(context)?
+            .for_writer(Vec::new());
+        for quad in &quads {
+            serializer.serialize_quad(quad)?;
+        }
+        let output = String::from_utf8(serializer.finish()?)?;
+        assert!(output.ends_with(
+            r#""@graph":[{"id":"ex:me","type":"Person","name":["Foo",{"@language":"fr","@value":"Toto"}],"description":{"@value":"A person"},"homepage":"http://foo.com/","url":{"@value":"foo.com"},"born":"2000-01-01","nick":["F"],"knows":{"id":"ex:other"}},{"id":"ex:g","@graph":[{"id":"ex:s","age":{"type":"http://www.w3.org/2001/XMLSchema#integer","@value":"12"}}]}]}"#
+        ));
+
+        let mut parsed = JsonLdParser::new()
+            .for_slice(output.as_bytes())
+            .map(|quad| quad.map(|quad| quad.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        parsed.sort();
+        let mut expected = quads.iter().map(Quad::to_string).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(parsed, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_streaming_profile() -> Result<(), Box<dyn Error>> {
+        let ex = |name: &str| NamedNode::new_unchecked(format!("http://example.com/{name}"));
+        let quads = [
+            Quad::new(ex("s"), rdf::TYPE, ex("A"), GraphName::DefaultGraph),
+            Quad::new(ex("s"), rdf::TYPE, ex("B"), GraphName::DefaultGraph),
+            Quad::new(
+                ex("s"),
+                ex("p"),
+                Literal::new_simple_literal("x"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(ex("s"), rdf::TYPE, ex("C"), GraphName::DefaultGraph),
+            Quad::new(
+                ex("o"),
+                ex("p"),
+                Literal::new_simple_literal("y"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(ex("o"), rdf::TYPE, ex("A"), ex("g")),
+        ];
+        let mut serializer = JsonLdSerializer::new()
+            .with_profile(JsonLdProfile::Streaming)
+            .for_writer(Vec::new());
+        for quad in &quads {
+            serializer.serialize_quad(quad)?;
+        }
+        let output = String::from_utf8(serializer.finish()?)?;
+        assert_eq!(
+            output,
+            r#"[{"@type":["http://example.com/A","http://example.com/B"],"@id":"http://example.com/s","http://example.com/p":[{"@value":"x"}]},{"@type":["http://example.com/C"],"@id":"http://example.com/s"},{"@id":"http://example.com/o","http://example.com/p":[{"@value":"y"}]},{"@id":"http://example.com/g","@graph":[{"@type":["http://example.com/A"],"@id":"http://example.com/o"}]}]"#
+        );
+
+        let mut parsed = JsonLdParser::new()
+            .with_profile(JsonLdProfile::Streaming)
+            .for_slice(output.as_bytes())
+            .map(|quad| quad.map(|quad| quad.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        parsed.sort();
+        let mut expected = quads.iter().map(Quad::to_string).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(parsed, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_context() {
+        assert!(JsonLdSerializer::new().with_context("{").is_err());
+        assert!(
+            JsonLdSerializer::new()
+                .with_context(r#"{"@vocab": 1}"#)
+                .is_err()
+        );
+    }
+}
@@ -7,20 +7,34 @@
 mod context;
 mod error;
 mod expansion;
+mod framing;
 mod from_rdf;
+mod loader;
 mod profile;
+mod rdfjson;
 mod to_rdf;
 
 pub use context::{JsonLdLoadDocumentOptions, JsonLdRemoteDocument};
 pub use error::{JsonLdErrorCode, JsonLdParseError, JsonLdSyntaxError, TextPosition};
+pub use framing::JsonLdFrame;
 #[cfg(feature = "async-tokio")]
 pub use from_rdf::TokioAsyncWriterJsonLdSerializer;
 pub use from_rdf::{JsonLdSerializer, WriterJsonLdSerializer};
-#[doc(hidden)]
-pub use profile::JsonLdProcessingMode;
+#[cfg(feature = "http-client")]
+pub use loader::HttpJsonLdDocumentLoader;
+pub use loader::JsonLdDocumentLoader;
+pub use profile::{JsonLdProcessingMode, JsonLdRdfDirection};
 pub use profile::{JsonLdProfile, JsonLdProfileSet};
+pub use rdfjson::{
+    RdfJsonParser, RdfJsonSerializer, ReaderRdfJsonParser, SliceRdfJsonParser,
+    WriterRdfJsonSerializer,
+};
+#[cfg(feature = "async-tokio")]
+pub use rdfjson::{TokioAsyncReaderRdfJsonParser, TokioAsyncWriterRdfJsonSerializer};
 #[cfg(feature = "async-tokio")]
 pub use to_rdf::TokioAsyncReaderJsonLdParser;
-pub use to_rdf::{JsonLdParser, JsonLdPrefixesIter, ReaderJsonLdParser, SliceJsonLdParser};
+pub use to_rdf::{
+    JsonLdParser, JsonLdPrefixesIter, LowLevelJsonLdParser, ReaderJsonLdParser, SliceJsonLdParser,
+};
 
 const MAX_CONTEXT_RECURSION: usize = 8;
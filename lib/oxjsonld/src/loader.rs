@@ -0,0 +1,225 @@
+use crate::context::{JsonLdLoadDocumentOptions, JsonLdRemoteDocument};
+#[cfg(feature = "http-client")]
+use oxhttp::model::header::{ACCEPT, CONTENT_TYPE, LOCATION};
+#[cfg(feature = "http-client")]
+use oxhttp::model::{Method, Request};
+#[cfg(feature = "http-client")]
+use oxiri::Iri;
+use std::error::Error;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+#[cfg(feature = "http-client")]
+use std::time::Duration;
+
+/// Loads the remote documents referenced from a JSON-LD document, like the `@context` URLs.
+///
+/// It corresponds to the [`LoadDocumentCallback` from the algorithm specification](https://www.w3.org/TR/json-ld-api/#loaddocumentcallback).
+/// It is implemented for closures with the same signature as [`load_document`](Self::load_document)
+/// and, with the `http-client` feature, by [`HttpJsonLdDocumentLoader`] that fetches the documents using HTTP.
+///
+/// ```
+/// use oxjsonld::{JsonLdLoadDocumentOptions, JsonLdParser, JsonLdRemoteDocument};
+///
+/// let file = br#"{
+///     "@context": "http://example.com/context.jsonld",
+///     "@id": "http://example.com/foo",
+///     "schema:name": "Foo"
+/// }"#;
+///
+/// let parser = JsonLdParser::new().with_document_loader(|url: &str, _: &JsonLdLoadDocumentOptions| {
+///     assert_eq!(url, "http://example.com/context.jsonld");
+///     Ok(JsonLdRemoteDocument {
+///         document: br#"{"@context":{"schema": "http://schema.org/"}}"#.to_vec(),
+///         document_url: url.into(),
+///     })
+/// });
+/// let quads = parser.for_slice(file).collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(quads[0].predicate.as_str(), "http://schema.org/name");
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+pub trait JsonLdDocumentLoader: Send + Sync + UnwindSafe + RefUnwindSafe {
+    /// Loads the document at the given URL.
+    fn load_document(
+        &self,
+        url: &str,
+        options: &JsonLdLoadDocumentOptions,
+    ) -> Result<JsonLdRemoteDocument, Box<dyn Error + Send + Sync>>;
+}
+
+impl<
+    F: Fn(
+            &str,
+            &JsonLdLoadDocumentOptions,
+        ) -> Result<JsonLdRemoteDocument, Box<dyn Error + Send + Sync>>
+        + Send
+        + Sync
+        + UnwindSafe
+        + RefUnwindSafe,
+> JsonLdDocumentLoader for F
+{
+    #[inline]
+    fn load_document(
+        &self,
+        url: &str,
+        options: &JsonLdLoadDocumentOptions,
+    ) -> Result<JsonLdRemoteDocument, Box<dyn Error + Send + Sync>> {
+        self(url, options)
+    }
+}
+
+/// A [`JsonLdDocumentLoader`] fetching the documents using HTTP(S).
+///
+/// It follows the redirections, the [`document_url`](JsonLdRemoteDocument::document_url) being the URL of the last one,
+/// and only accepts the JSON responses.
+///
+/// ```no_run
+/// use oxjsonld::{HttpJsonLdDocumentLoader, JsonLdParser};
+/// use std::time::Duration;
+///
+/// let file = br#"{"@context": "https://schema.org/", "@id": "http://example.com/foo", "name": "Foo"}"#;
+/// let parser = JsonLdParser::new().with_document_loader(
+///     HttpJsonLdDocumentLoader::new().with_timeout(Duration::from_secs(10)),
+/// );
+/// for quad in parser.for_slice(file) {
+///     println!("{}", quad?);
+/// }
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[cfg(feature = "http-client")]
+#[must_use]
+pub struct HttpJsonLdDocumentLoader {
+    client: oxhttp::Client,
+    redirection_limit: usize,
+}
+
+#[cfg(feature = "http-client")]
+impl HttpJsonLdDocumentLoader {
+    /// Builds a new loader following at most 8 redirections and without timeout.
+    #[expect(clippy::unwrap_used)]
+    pub fn new() -> Self {
+        Self {
+            client: oxhttp::Client::new()
+                .with_user_agent(concat!("OxJSON-LD/", env!("CARGO_PKG_VERSION")))
+                .unwrap(),
+            redirection_limit: 8,
+        }
+    }
+
+    /// Sets the timeout of each HTTP request.
+    #[inline]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = self.client.with_global_timeout(timeout);
+        self
+    }
+
+    /// Sets the maximal number of HTTP redirections followed to load a document.
+    #[inline]
+    pub fn with_redirection_limit(mut self, redirection_limit: usize) -> Self {
+        self.redirection_limit = redirection_limit;
+        self
+    }
+}
+
+#[cfg(feature = "http-client")]
+impl Default for HttpJsonLdDocumentLoader {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The loader has no state that a panic could leave inconsistent
+#[cfg(feature = "http-client")]
+impl UnwindSafe for HttpJsonLdDocumentLoader {}
+
+#[cfg(feature = "http-client")]
+impl RefUnwindSafe for HttpJsonLdDocumentLoader {}
+
+#[cfg(feature = "http-client")]
+impl JsonLdDocumentLoader for HttpJsonLdDocumentLoader {
+    fn load_document(
+        &self,
+        url: &str,
+        options: &JsonLdLoadDocumentOptions,
+    ) -> Result<JsonLdRemoteDocument, Box<dyn Error + Send + Sync>> {
+        let mut accept = String::new();
+        for profile in options.request_profile {
+            accept.push_str("application/ld+json;profile=");
+            accept.push_str(profile.iri());
+            accept.push_str(", ");
+        }
+        accept.push_str("application/ld+json, application/json;q=0.9");
+
+        let mut url = Iri::parse(url.to_owned())?;
+        for _ in 0..=self.redirection_limit {
+            let response = self.client.request(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(url.as_str())
+                    .header(ACCEPT, &accept)
+                    .body(())?,
+            )?;
+            let status = response.status();
+            if status.is_redirection() {
+                let location = response
+                    .headers()
+                    .get(LOCATION)
+                    .ok_or_else(|| format!("The redirection from {url} has no Location header"))?
+                    .to_str()?;
+                url = url.resolve(location)?;
+                continue;
+            }
+            if !status.is_success() {
+                return Err(format!("Error {status} returned by {url}").into());
+            }
+            let content_type = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .map(|content_type| content_type.to_str())
+                .transpose()?
+                .unwrap_or_default();
+            if !is_json_media_type(content_type) {
+                return Err(format!(
+                    "{url} returned the content type '{content_type}' that is not a JSON one"
+                )
+                .into());
+            }
+            return Ok(JsonLdRemoteDocument {
+                document: response.into_body().to_vec()?,
+                document_url: url.into_inner(),
+            });
+        }
+        Err(format!(
+            "More than {} redirections while loading {url}",
+            self.redirection_limit
+        )
+        .into())
+    }
+}
+
+#[cfg(feature = "http-client")]
+fn is_json_media_type(content_type: &str) -> bool {
+    let media_type = content_type
+        .split_once(';')
+        .map_or(content_type, |(media_type, _)| media_type)
+        .trim();
+    media_type.eq_ignore_ascii_case("application/json")
+        || media_type.eq_ignore_ascii_case("application/ld+json")
+        || media_type
+            .get(media_type.len().saturating_sub(5)..)
+            .is_some_and(|suffix| suffix.eq_ignore_ascii_case("+json"))
+}
+
+#[cfg(test)]
+#[cfg(feature = "http-client")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_json_media_type() {
+        assert!(is_json_media_type("application/ld+json"));
+        assert!(is_json_media_type("Application/JSON; charset=utf-8"));
+        assert!(is_json_media_type("application/activity+json"));
+        assert!(!is_json_media_type("text/html"));
+        assert!(!is_json_media_type(""));
+    }
+}
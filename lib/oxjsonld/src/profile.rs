@@ -238,9 +238,11 @@ impl BitOrAssign<JsonLdProfile> for JsonLdProfileSet {
 /// JSON-LD [processing mode](https://www.w3.org/TR/json-ld11/#dfn-processing-mode)
 #[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
 pub enum JsonLdProcessingMode {
+    /// [JSON-LD 1.0](https://www.w3.org/TR/2014/REC-json-ld-20140116/)
     #[default]
-    JsonLd1_0,
-    JsonLd1_1, // TODO: Move to 1.1 when implemented
+    JsonLd1_0, // TODO: Move to 1.1 when fully implemented
+    /// [JSON-LD 1.1](https://www.w3.org/TR/json-ld11/)
+    JsonLd1_1,
 }
 
 impl JsonLdProcessingMode {
@@ -278,3 +280,20 @@ impl JsonLdProcessingMode {
         }
     }
 }
+
+/// How the [base direction](https://www.w3.org/TR/json-ld11/#base-direction) of the strings is represented in RDF.
+///
+/// It corresponds to the [`rdfDirection` option from the algorithm specification](https://www.w3.org/TR/json-ld11-api/#dom-jsonldoptions-rdfdirection).
+///
+/// This enumeration is non exhaustive. New representations might be added in the future.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+#[non_exhaustive]
+pub enum JsonLdRdfDirection {
+    /// [Directional language-tagged strings](https://www.w3.org/TR/rdf12-concepts/#dfn-dir-lang-string) (`rdf:dirLangString`).
+    ///
+    /// They require the `rdf-12` feature, the direction is dropped without it.
+    #[default]
+    DirLangString,
+    /// Literals with a `https://www.w3.org/ns/i18n#{language}_{direction}` datatype.
+    I18nDatatype,
+}
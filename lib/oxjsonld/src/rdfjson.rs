@@ -0,0 +1,776 @@
+use crate::error::{JsonLdParseError, JsonLdSyntaxError};
+use json_event_parser::{JsonEvent, ReaderJsonParser, SliceJsonParser, WriterJsonSerializer};
+#[cfg(feature = "async-tokio")]
+use json_event_parser::{TokioAsyncReaderJsonParser, TokioAsyncWriterJsonSerializer};
+use oxiri::Iri;
+use oxrdf::vocab::xsd;
+use oxrdf::{
+    BlankNode, Literal, NamedNode, NamedOrBlankNode, NamedOrBlankNodeRef, Term, TermRef, Triple,
+    TripleRef,
+};
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::mem;
+#[cfg(feature = "async-tokio")]
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// A [RDF/JSON](https://www.w3.org/TR/rdf-json/) streaming parser.
+///
+/// RDF/JSON is a legacy JSON serialization of RDF graphs where each subject is a key of the root object,
+/// each predicate a key of the subject object and each object a JSON object with `type`, `value`, `lang` and `datatype` keys.
+///
+/// Count the number of people:
+/// ```
+/// use oxjsonld::RdfJsonParser;
+/// use oxrdf::NamedNodeRef;
+/// use oxrdf::vocab::rdf;
+///
+/// let file = br#"{
+///     "http://example.com/foo": {
+///         "http://www.w3.org/1999/02/22-rdf-syntax-ns#type": [{"type": "uri", "value": "http://schema.org/Person"}],
+///         "http://schema.org/name": [{"type": "literal", "value": "Foo", "lang": "en"}]
+///     },
+///     "_:bar": {
+///         "http://www.w3.org/1999/02/22-rdf-syntax-ns#type": [{"type": "uri", "value": "http://schema.org/Person"}]
+///     }
+/// }"#;
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut count = 0;
+/// for triple in RdfJsonParser::new().for_reader(file.as_ref()) {
+///     let triple = triple?;
+///     if triple.predicate == rdf::TYPE && triple.object == schema_person.into() {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(2, count);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+#[must_use]
+pub struct RdfJsonParser {
+    lenient: bool,
+}
+
+impl RdfJsonParser {
+    /// Builds a new [`RdfJsonParser`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assumes the file is valid to make parsing faster.
+    ///
+    /// It will skip some validations.
+    ///
+    /// Note that if the file is actually not valid, the parser might emit broken RDF.
+    #[inline]
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Parses a RDF/JSON file from a [`Read`] implementation.
+    ///
+    /// ```
+    /// use oxjsonld::RdfJsonParser;
+    ///
+    /// let file = br#"{"http://example.com/s": {"http://example.com/p": [{"type": "bnode", "value": "_:o"}]}}"#;
+    ///
+    /// let triples = RdfJsonParser::new()
+    ///     .for_reader(file.as_ref())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(triples.len(), 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_reader<R: Read>(self, reader: R) -> ReaderRdfJsonParser<R> {
+        ReaderRdfJsonParser {
+            inner: self.into_internal(),
+            json_parser: ReaderJsonParser::new(reader),
+        }
+    }
+
+    /// Parses a RDF/JSON file from a [`AsyncRead`] implementation.
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use oxjsonld::RdfJsonParser;
+    ///
+    /// let file = br#"{"http://example.com/s": {"http://example.com/p": [{"type": "bnode", "value": "_:o"}]}}"#;
+    ///
+    /// let mut parser = RdfJsonParser::new().for_tokio_async_reader(file.as_ref());
+    /// let mut count = 0;
+    /// while let Some(triple) = parser.next().await {
+    ///     triple?;
+    ///     count += 1;
+    /// }
+    /// assert_eq!(count, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async-tokio")]
+    pub fn for_tokio_async_reader<R: AsyncRead + Unpin>(
+        self,
+        reader: R,
+    ) -> TokioAsyncReaderRdfJsonParser<R> {
+        TokioAsyncReaderRdfJsonParser {
+            inner: self.into_internal(),
+            json_parser: TokioAsyncReaderJsonParser::new(reader),
+        }
+    }
+
+    /// Parses a RDF/JSON file from a byte slice.
+    ///
+    /// ```
+    /// use oxjsonld::RdfJsonParser;
+    ///
+    /// let file = br#"{"http://example.com/s": {"http://example.com/p": [{"type": "bnode", "value": "_:o"}]}}"#;
+    ///
+    /// let triples = RdfJsonParser::new()
+    ///     .for_slice(file)
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(triples.len(), 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_slice(self, slice: &[u8]) -> SliceRdfJsonParser<'_> {
+        SliceRdfJsonParser {
+            inner: self.into_internal(),
+            json_parser: SliceJsonParser::new(slice),
+        }
+    }
+
+    fn into_internal(self) -> InternalRdfJsonParser {
+        InternalRdfJsonParser {
+            state: RdfJsonState::Start,
+            subject: None,
+            predicate: None,
+            object: RdfJsonObject::default(),
+            lenient: self.lenient,
+            is_end: false,
+        }
+    }
+}
+
+/// Parses a RDF/JSON file from a [`Read`] implementation.
+///
+/// Can be built using [`RdfJsonParser::for_reader`].
+#[must_use]
+pub struct ReaderRdfJsonParser<R: Read> {
+    inner: InternalRdfJsonParser,
+    json_parser: ReaderJsonParser<R>,
+}
+
+impl<R: Read> Iterator for ReaderRdfJsonParser<R> {
+    type Item = Result<Triple, JsonLdParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.inner.is_end {
+            let event = match self.json_parser.parse_next() {
+                Ok(event) => event,
+                Err(e) => {
+                    self.inner.is_end = true;
+                    return Some(Err(e.into()));
+                }
+            };
+            if let Some(result) = self.inner.parse_event(event) {
+                return Some(result.map_err(Into::into));
+            }
+        }
+        None
+    }
+}
+
+/// Parses a RDF/JSON file from a [`AsyncRead`] implementation.
+///
+/// Can be built using [`RdfJsonParser::for_tokio_async_reader`].
+#[cfg(feature = "async-tokio")]
+#[must_use]
+pub struct TokioAsyncReaderRdfJsonParser<R: AsyncRead + Unpin> {
+    inner: InternalRdfJsonParser,
+    json_parser: TokioAsyncReaderJsonParser<R>,
+}
+
+#[cfg(feature = "async-tokio")]
+impl<R: AsyncRead + Unpin> TokioAsyncReaderRdfJsonParser<R> {
+    /// Reads the next triple or returns `None` if the file is finished.
+    pub async fn next(&mut self) -> Option<Result<Triple, JsonLdParseError>> {
+        while !self.inner.is_end {
+            let event = match self.json_parser.parse_next().await {
+                Ok(event) => event,
+                Err(e) => {
+                    self.inner.is_end = true;
+                    return Some(Err(e.into()));
+                }
+            };
+            if let Some(result) = self.inner.parse_event(event) {
+                return Some(result.map_err(Into::into));
+            }
+        }
+        None
+    }
+}
+
+/// Parses a RDF/JSON file from a byte slice.
+///
+/// Can be built using [`RdfJsonParser::for_slice`].
+#[must_use]
+pub struct SliceRdfJsonParser<'a> {
+    inner: InternalRdfJsonParser,
+    json_parser: SliceJsonParser<'a>,
+}
+
+impl Iterator for SliceRdfJsonParser<'_> {
+    type Item = Result<Triple, JsonLdSyntaxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.inner.is_end {
+            let event = match self.json_parser.parse_next() {
+                Ok(event) => event,
+                Err(e) => {
+                    self.inner.is_end = true;
+                    return Some(Err(e.into()));
+                }
+            };
+            if let Some(result) = self.inner.parse_event(event) {
+                return Some(result);
+            }
+        }
+        None
+    }
+}
+
+enum RdfJsonState {
+    Start,
+    Subjects,
+    Subject,
+    Predicates,
+    Predicate,
+    Objects,
+    Object,
+    ObjectValue(String),
+    End,
+}
+
+#[derive(Default)]
+struct RdfJsonObject {
+    kind: Option<String>,
+    value: Option<String>,
+    language: Option<String>,
+    datatype: Option<String>,
+}
+
+struct InternalRdfJsonParser {
+    state: RdfJsonState,
+    /// `None` if the current subject is invalid
+    subject: Option<NamedOrBlankNode>,
+    /// `None` if the current predicate is invalid
+    predicate: Option<NamedNode>,
+    object: RdfJsonObject,
+    lenient: bool,
+    is_end: bool,
+}
+
+impl InternalRdfJsonParser {
+    fn parse_event(&mut self, event: JsonEvent<'_>) -> Option<Result<Triple, JsonLdSyntaxError>> {
+        let (new_state, result) = match (mem::replace(&mut self.state, RdfJsonState::End), event) {
+            (RdfJsonState::Start, JsonEvent::StartObject)
+            | (RdfJsonState::Predicates, JsonEvent::EndObject) => (RdfJsonState::Subjects, None),
+            (RdfJsonState::Subjects, JsonEvent::ObjectKey(key)) => match self.parse_subject(&key) {
+                Ok(subject) => {
+                    self.subject = Some(subject);
+                    (RdfJsonState::Subject, None)
+                }
+                Err(e) => {
+                    self.subject = None;
+                    (RdfJsonState::Subject, Some(Err(e)))
+                }
+            },
+            (RdfJsonState::Subjects, JsonEvent::EndObject) => (RdfJsonState::End, None),
+            (RdfJsonState::Subject, JsonEvent::StartObject)
+            | (RdfJsonState::Objects, JsonEvent::EndArray) => (RdfJsonState::Predicates, None),
+            (RdfJsonState::Predicates, JsonEvent::ObjectKey(key)) => {
+                match self.parse_iri(key.into_owned()) {
+                    Ok(predicate) => {
+                        self.predicate = Some(predicate);
+                        (RdfJsonState::Predicate, None)
+                    }
+                    Err(e) => {
+                        self.predicate = None;
+                        (RdfJsonState::Predicate, Some(Err(e)))
+                    }
+                }
+            }
+            (RdfJsonState::Predicate, JsonEvent::StartArray) => (RdfJsonState::Objects, None),
+            (RdfJsonState::Objects, JsonEvent::StartObject) => {
+                self.object = RdfJsonObject::default();
+                (RdfJsonState::Object, None)
+            }
+            (RdfJsonState::Object, JsonEvent::ObjectKey(key)) => {
+                (RdfJsonState::ObjectValue(key.into_owned()), None)
+            }
+            (RdfJsonState::Object, JsonEvent::EndObject) => {
+                let object = mem::take(&mut self.object);
+                let result = match (&self.subject, &self.predicate) {
+                    (Some(subject), Some(predicate)) => Some(
+                        self.build_object(object)
+                            .map(|object| Triple::new(subject.clone(), predicate.clone(), object)),
+                    ),
+                    _ => None, // The error has already been returned
+                };
+                (RdfJsonState::Objects, result)
+            }
+            (RdfJsonState::ObjectValue(key), JsonEvent::String(value)) => {
+                let field = match key.as_str() {
+                    "type" => Some(&mut self.object.kind),
+                    "value" => Some(&mut self.object.value),
+                    "lang" => Some(&mut self.object.language),
+                    "datatype" => Some(&mut self.object.datatype),
+                    _ => None,
+                };
+                let error = match field {
+                    Some(field) if field.is_none() => {
+                        *field = Some(value.into_owned());
+                        None
+                    }
+                    Some(_) => Some(format!("The RDF/JSON object key '{key}' is duplicated")),
+                    None => Some(format!(
+                        "Unexpected RDF/JSON object key '{key}', only type, value, lang and datatype are allowed"
+                    )),
+                };
+                (
+                    RdfJsonState::Object,
+                    error.map(|e| Err(JsonLdSyntaxError::msg(e))),
+                )
+            }
+            (RdfJsonState::End, JsonEvent::Eof) => {
+                self.is_end = true;
+                (RdfJsonState::End, None)
+            }
+            (state, _) => {
+                self.is_end = true;
+                (
+                    RdfJsonState::End,
+                    Some(Err(JsonLdSyntaxError::msg(format!(
+                        "Invalid RDF/JSON structure, {} was expected",
+                        state.expected()
+                    )))),
+                )
+            }
+        };
+        self.state = new_state;
+        result
+    }
+
+    fn parse_subject(&self, value: &str) -> Result<NamedOrBlankNode, JsonLdSyntaxError> {
+        Ok(if value.starts_with("_:") {
+            self.parse_blank_node(value)?.into()
+        } else {
+            self.parse_iri(value.to_owned())?.into()
+        })
+    }
+
+    fn build_object(&self, object: RdfJsonObject) -> Result<Term, JsonLdSyntaxError> {
+        let value = object
+            .value
+            .ok_or_else(|| JsonLdSyntaxError::msg("RDF/JSON objects must have a value"))?;
+        match object.kind.as_deref() {
+            Some("uri") => Ok(self.parse_iri(value)?.into()),
+            Some("bnode") => Ok(self.parse_blank_node(&value)?.into()),
+            Some("literal") => Ok(match (object.language, object.datatype) {
+                (Some(_), Some(_)) => {
+                    return Err(JsonLdSyntaxError::msg(
+                        "RDF/JSON literals cannot have both a lang and a datatype",
+                    ));
+                }
+                (Some(language), None) => {
+                    if self.lenient {
+                        Literal::new_language_tagged_literal_unchecked(
+                            value,
+                            language.to_ascii_lowercase(),
+                        )
+                    } else {
+                        Literal::new_language_tagged_literal(value, &language).map_err(|e| {
+                            JsonLdSyntaxError::msg(format!(
+                                "Invalid language tag '{language}': {e}"
+                            ))
+                        })?
+                    }
+                }
+                (None, Some(datatype)) => {
+                    Literal::new_typed_literal(value, self.parse_iri(datatype)?)
+                }
+                (None, None) => Literal::new_simple_literal(value),
+            }
+            .into()),
+            Some(kind) => Err(JsonLdSyntaxError::msg(format!(
+                "Unsupported RDF/JSON object type '{kind}', it must be uri, bnode or literal"
+            ))),
+            None => Err(JsonLdSyntaxError::msg("RDF/JSON objects must have a type")),
+        }
+    }
+
+    fn parse_iri(&self, iri: String) -> Result<NamedNode, JsonLdSyntaxError> {
+        Ok(NamedNode::new_unchecked(if self.lenient {
+            iri
+        } else {
+            Iri::parse(iri.clone())
+                .map_err(|e| JsonLdSyntaxError::msg(format!("Invalid IRI '{iri}': {e}")))?
+                .into_inner()
+        }))
+    }
+
+    fn parse_blank_node(&self, value: &str) -> Result<BlankNode, JsonLdSyntaxError> {
+        let id = value.strip_prefix("_:").ok_or_else(|| {
+            JsonLdSyntaxError::msg(format!(
+                "RDF/JSON blank nodes must start with '_:', found '{value}'"
+            ))
+        })?;
+        if self.lenient {
+            Ok(BlankNode::new_unchecked(id))
+        } else {
+            BlankNode::new(id).map_err(|e| {
+                JsonLdSyntaxError::msg(format!("Invalid blank node identifier '{id}': {e}"))
+            })
+        }
+    }
+}
+
+impl RdfJsonState {
+    fn expected(&self) -> &'static str {
+        match self {
+            Self::Start => "a root object",
+            Self::Subjects => "a subject key",
+            Self::Subject => "a predicates object",
+            Self::Predicates => "a predicate key",
+            Self::Predicate => "an objects array",
+            Self::Objects => "an object",
+            Self::Object => "an object key",
+            Self::ObjectValue(_) => "a string value",
+            Self::End => "the end of the file",
+        }
+    }
+}
+
+/// A [RDF/JSON](https://www.w3.org/TR/rdf-json/) serializer.
+///
+/// The triples are buffered until [`finish`](WriterRdfJsonSerializer::finish) is called
+/// because RDF/JSON requires all the triples with the same subject to be written together.
+/// The subjects and predicates are written in lexicographic order.
+///
+/// ```
+/// use oxjsonld::RdfJsonSerializer;
+/// use oxrdf::vocab::rdf;
+/// use oxrdf::{LiteralRef, NamedNodeRef, TripleRef};
+///
+/// let mut serializer = RdfJsonSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_triple(TripleRef::new(
+///     NamedNodeRef::new("http://example.com#me")?,
+///     rdf::TYPE,
+///     NamedNodeRef::new("http://schema.org/Person")?,
+/// ))?;
+/// serializer.serialize_triple(TripleRef::new(
+///     NamedNodeRef::new("http://example.com#me")?,
+///     NamedNodeRef::new("http://schema.org/name")?,
+///     LiteralRef::new_language_tagged_literal_unchecked("Foo Bar", "en"),
+/// ))?;
+/// assert_eq!(
+///     br#"{"http://example.com#me":{"http://schema.org/name":[{"type":"literal","value":"Foo Bar","lang":"en"}],"http://www.w3.org/1999/02/22-rdf-syntax-ns#type":[{"type":"uri","value":"http://schema.org/Person"}]}}"#,
+///     serializer.finish()?.as_slice()
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+#[must_use]
+#[expect(clippy::empty_structs_with_brackets)]
+pub struct RdfJsonSerializer {}
+
+impl RdfJsonSerializer {
+    /// Builds a new [`RdfJsonSerializer`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Serializes a RDF/JSON file to a [`Write`] implementation.
+    ///
+    /// ```
+    /// use oxjsonld::RdfJsonSerializer;
+    /// use oxrdf::{BlankNodeRef, NamedNodeRef, TripleRef};
+    ///
+    /// let mut serializer = RdfJsonSerializer::new().for_writer(Vec::new());
+    /// serializer.serialize_triple(TripleRef::new(
+    ///     BlankNodeRef::new("me")?,
+    ///     NamedNodeRef::new("http://schema.org/knows")?,
+    ///     BlankNodeRef::new("you")?,
+    /// ))?;
+    /// assert_eq!(
+    ///     br#"{"_:me":{"http://schema.org/knows":[{"type":"bnode","value":"_:you"}]}}"#,
+    ///     serializer.finish()?.as_slice()
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_writer<W: Write>(self, writer: W) -> WriterRdfJsonSerializer<W> {
+        WriterRdfJsonSerializer {
+            writer: WriterJsonSerializer::new(writer),
+            inner: self.inner_writer(),
+        }
+    }
+
+    /// Serializes a RDF/JSON file to a [`AsyncWrite`] implementation.
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use oxjsonld::RdfJsonSerializer;
+    /// use oxrdf::{BlankNodeRef, NamedNodeRef, TripleRef};
+    ///
+    /// let mut serializer = RdfJsonSerializer::new().for_tokio_async_writer(Vec::new());
+    /// serializer.serialize_triple(TripleRef::new(
+    ///     BlankNodeRef::new("me")?,
+    ///     NamedNodeRef::new("http://schema.org/knows")?,
+    ///     BlankNodeRef::new("you")?,
+    /// ))?;
+    /// assert_eq!(
+    ///     br#"{"_:me":{"http://schema.org/knows":[{"type":"bnode","value":"_:you"}]}}"#,
+    ///     serializer.finish().await?.as_slice()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async-tokio")]
+    pub fn for_tokio_async_writer<W: AsyncWrite + Unpin>(
+        self,
+        writer: W,
+    ) -> TokioAsyncWriterRdfJsonSerializer<W> {
+        TokioAsyncWriterRdfJsonSerializer {
+            writer: TokioAsyncWriterJsonSerializer::new(writer),
+            inner: self.inner_writer(),
+        }
+    }
+
+    #[expect(clippy::unused_self)]
+    fn inner_writer(self) -> InnerRdfJsonWriter {
+        InnerRdfJsonWriter {
+            triples: BTreeMap::new(),
+        }
+    }
+}
+
+/// Serializes a RDF/JSON file to a [`Write`] implementation.
+///
+/// Can be built using [`RdfJsonSerializer::for_writer`].
+#[must_use]
+pub struct WriterRdfJsonSerializer<W: Write> {
+    writer: WriterJsonSerializer<W>,
+    inner: InnerRdfJsonWriter,
+}
+
+impl<W: Write> WriterRdfJsonSerializer<W> {
+    /// Serializes an extra triple.
+    ///
+    /// The triple is only written when [`finish`](Self::finish) is called.
+    pub fn serialize_triple<'a>(&mut self, t: impl Into<TripleRef<'a>>) -> io::Result<()> {
+        self.inner.serialize_triple(t)
+    }
+
+    /// Writes all the triples and returns the underlying [`Write`].
+    pub fn finish(mut self) -> io::Result<W> {
+        for event in self.inner.finish() {
+            self.writer.serialize_event(event)?;
+        }
+        self.writer.finish()
+    }
+}
+
+/// Serializes a RDF/JSON file to a [`AsyncWrite`] implementation.
+///
+/// Can be built using [`RdfJsonSerializer::for_tokio_async_writer`].
+#[cfg(feature = "async-tokio")]
+#[must_use]
+pub struct TokioAsyncWriterRdfJsonSerializer<W: AsyncWrite + Unpin> {
+    writer: TokioAsyncWriterJsonSerializer<W>,
+    inner: InnerRdfJsonWriter,
+}
+
+#[cfg(feature = "async-tokio")]
+impl<W: AsyncWrite + Unpin> TokioAsyncWriterRdfJsonSerializer<W> {
+    /// Serializes an extra triple.
+    ///
+    /// The triple is only written when [`finish`](Self::finish) is called.
+    pub fn serialize_triple<'a>(&mut self, t: impl Into<TripleRef<'a>>) -> io::Result<()> {
+        self.inner.serialize_triple(t)
+    }
+
+    /// Writes all the triples and returns the underlying [`AsyncWrite`].
+    pub async fn finish(mut self) -> io::Result<W> {
+        for event in self.inner.finish() {
+            self.writer.serialize_event(event).await?;
+        }
+        self.writer.finish()
+    }
+}
+
+struct InnerRdfJsonWriter {
+    /// The objects by subject and predicate, the subjects are serialized with their RDF/JSON syntax
+    triples: BTreeMap<String, BTreeMap<String, Vec<Term>>>,
+}
+
+impl InnerRdfJsonWriter {
+    fn serialize_triple<'a>(&mut self, t: impl Into<TripleRef<'a>>) -> io::Result<()> {
+        let triple = t.into();
+        #[allow(
+            unreachable_patterns,
+            clippy::match_wildcard_for_single_variants,
+            clippy::allow_attributes
+        )]
+        match triple.object {
+            #[cfg(feature = "rdf-12")]
+            TermRef::Literal(literal) if literal.direction().is_some() => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "RDF/JSON does not support directional language-tagged strings",
+                ));
+            }
+            TermRef::NamedNode(_) | TermRef::BlankNode(_) | TermRef::Literal(_) => (),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "RDF/JSON only supports named, blank or literal objects",
+                ));
+            }
+        }
+        let subject = match triple.subject {
+            NamedOrBlankNodeRef::NamedNode(node) => node.as_str().to_owned(),
+            NamedOrBlankNodeRef::BlankNode(node) => node.to_string(),
+        };
+        self.triples
+            .entry(subject)
+            .or_default()
+            .entry(triple.predicate.as_str().to_owned())
+            .or_default()
+            .push(triple.object.into_owned());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Vec<JsonEvent<'static>> {
+        let mut output = vec![JsonEvent::StartObject];
+        for (subject, predicates) in mem::take(&mut self.triples) {
+            output.push(JsonEvent::ObjectKey(subject.into()));
+            output.push(JsonEvent::StartObject);
+            for (predicate, objects) in predicates {
+                output.push(JsonEvent::ObjectKey(predicate.into()));
+                output.push(JsonEvent::StartArray);
+                for object in objects {
+                    Self::serialize_object(object, &mut output);
+                }
+                output.push(JsonEvent::EndArray);
+            }
+            output.push(JsonEvent::EndObject);
+        }
+        output.push(JsonEvent::EndObject);
+        output
+    }
+
+    fn serialize_object(object: Term, output: &mut Vec<JsonEvent<'static>>) {
+        output.push(JsonEvent::StartObject);
+        output.push(JsonEvent::ObjectKey("type".into()));
+        #[allow(
+            unreachable_patterns,
+            clippy::match_wildcard_for_single_variants,
+            clippy::allow_attributes
+        )]
+        match object {
+            Term::NamedNode(node) => {
+                output.push(JsonEvent::String("uri".into()));
+                output.push(JsonEvent::ObjectKey("value".into()));
+                output.push(JsonEvent::String(node.into_string().into()));
+            }
+            Term::BlankNode(node) => {
+                output.push(JsonEvent::String("bnode".into()));
+                output.push(JsonEvent::ObjectKey("value".into()));
+                output.push(JsonEvent::String(node.to_string().into()));
+            }
+            Term::Literal(literal) => {
+                output.push(JsonEvent::String("literal".into()));
+                output.push(JsonEvent::ObjectKey("value".into()));
+                output.push(JsonEvent::String(literal.value().to_owned().into()));
+                if let Some(language) = literal.language() {
+                    output.push(JsonEvent::ObjectKey("lang".into()));
+                    output.push(JsonEvent::String(language.to_owned().into()));
+                } else if literal.datatype() != xsd::STRING {
+                    output.push(JsonEvent::ObjectKey("datatype".into()));
+                    output.push(JsonEvent::String(
+                        literal.datatype().as_str().to_owned().into(),
+                    ));
+                }
+            }
+            _ => (), // Already checked in serialize_triple
+        }
+        output.push(JsonEvent::EndObject);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxrdf::{BlankNodeRef, Graph, LiteralRef, NamedNodeRef};
+
+    #[test]
+    fn test_round_trip() {
+        let mut graph = Graph::new();
+        let s = NamedNodeRef::new_unchecked("http://example.com/s");
+        let p = NamedNodeRef::new_unchecked("http://example.com/p");
+        let b = BlankNodeRef::new_unchecked("b1");
+        graph.insert(TripleRef::new(s, p, b));
+        graph.insert(TripleRef::new(b, p, s));
+        graph.insert(TripleRef::new(
+            b,
+            p,
+            LiteralRef::new_simple_literal("a \"b\""),
+        ));
+        graph.insert(TripleRef::new(
+            s,
+            p,
+            LiteralRef::new_language_tagged_literal_unchecked("foo", "en"),
+        ));
+        graph.insert(TripleRef::new(
+            s,
+            p,
+            LiteralRef::new_typed_literal("1", xsd::INTEGER),
+        ));
+        let mut serializer = RdfJsonSerializer::new().for_writer(Vec::new());
+        for triple in &graph {
+            serializer.serialize_triple(triple).unwrap();
+        }
+        let output = serializer.finish().unwrap();
+        let parsed = RdfJsonParser::new()
+            .for_slice(&output)
+            .collect::<Result<Graph, _>>()
+            .unwrap();
+        assert_eq!(parsed, graph);
+    }
+
+    #[test]
+    fn test_invalid() {
+        for file in [
+            "[]",
+            r#"{"s": {}}"#,
+            r#"{"http://example.com/s": {"http://example.com/p": {}}}"#,
+            r#"{"http://example.com/s": {"http://example.com/p": [{"value": "o"}]}}"#,
+            r#"{"http://example.com/s": {"http://example.com/p": [{"type": "foo", "value": "o"}]}}"#,
+            r#"{"http://example.com/s": {"http://example.com/p": [{"type": "bnode", "value": "o"}]}}"#,
+            r#"{"http://example.com/s": {"http://example.com/p": [{"type": "literal", "value": "o", "lang": "en", "datatype": "http://example.com/d"}]}}"#,
+            r#"{"http://example.com/s": {"http://example.com/p": [{"type": "literal", "value": 1}]}}"#,
+            r#"{"http://example.com/s": {"http://example.com/p": [{"type": "literal", "value": "o", "foo": "bar"}]}}"#,
+            r#"{"http://example.com/s": {"#,
+        ] {
+            assert!(
+                RdfJsonParser::new()
+                    .for_slice(file.as_bytes())
+                    .any(|r| r.is_err()),
+                "{file} should be invalid"
+            );
+        }
+    }
+}
@@ -1,25 +1,37 @@
-use crate::context::{JsonLdLoadDocumentOptions, JsonLdRemoteDocument, JsonLdTermDefinition};
+use crate::context::{
+    JsonLdLoadDocumentOptions, JsonLdRemoteDocument, JsonLdTermDefinition, RemoteContextCache,
+};
 use crate::error::{JsonLdParseError, JsonLdSyntaxError};
 use crate::expansion::{JsonLdEvent, JsonLdExpansionConverter, JsonLdValue};
-use crate::profile::{JsonLdProcessingMode, JsonLdProfile, JsonLdProfileSet};
+use crate::loader::JsonLdDocumentLoader;
+use crate::profile::{JsonLdProcessingMode, JsonLdProfile, JsonLdProfileSet, JsonLdRdfDirection};
 #[cfg(feature = "async-tokio")]
 use json_event_parser::TokioAsyncReaderJsonParser;
-use json_event_parser::{JsonEvent, ReaderJsonParser, SliceJsonParser};
+use json_event_parser::{
+    JsonEvent, LowLevelJsonParser, LowLevelJsonParserResult, ReaderJsonParser, SliceJsonParser,
+};
 use oxiri::{Iri, IriParseError};
+#[cfg(feature = "rdf-12")]
+use oxrdf::BaseDirection;
 use oxrdf::vocab::{rdf, xsd};
 use oxrdf::{BlankNode, GraphName, Literal, NamedNode, NamedNodeRef, NamedOrBlankNode, Quad};
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Write;
 use std::io::Read;
 use std::panic::{RefUnwindSafe, UnwindSafe};
 use std::str;
+use std::sync::Arc;
 #[cfg(feature = "async-tokio")]
 use tokio::io::AsyncRead;
 
+/// Maximal number of validated property and type IRIs kept by the parser
+const MAX_VOCAB_NAMED_NODES_CACHE_SIZE: usize = 1024;
+
 /// A [JSON-LD](https://www.w3.org/TR/json-ld/) parser.
 ///
 /// The parser is a work in progress.
-/// Only JSON-LD 1.0 is supported at the moment. JSON-LD 1.1 is not supported yet.
+/// JSON-LD 1.0 is supported and JSON-LD 1.1 is partially supported using [`with_processing_mode`](JsonLdParser::with_processing_mode).
 ///
 /// The parser supports two modes:
 /// - regular JSON-LD parsing that needs to buffer the full file into memory.
@@ -64,7 +76,10 @@ pub struct JsonLdParser {
     processing_mode: JsonLdProcessingMode,
     lenient: bool,
     profile: JsonLdProfileSet,
+    rdf_direction: JsonLdRdfDirection,
     base: Option<Iri<String>>,
+    document_loader: Option<Arc<dyn JsonLdDocumentLoader>>,
+    remote_context_cache: Arc<RemoteContextCache>,
 }
 
 impl JsonLdParser {
@@ -127,13 +142,77 @@ impl JsonLdParser {
     }
 
     /// Set the [processing mode](https://www.w3.org/TR/json-ld11/#dfn-processing-mode) of the parser.
+    ///
+    /// By default, JSON-LD 1.0 is used.
+    /// The JSON-LD 1.1 mode enables the 1.1 context features like `@import`, `@propagate`, `@protected`
+    /// and the contexts scoped to properties and types.
+    ///
+    /// ```
+    /// use oxjsonld::{JsonLdParser, JsonLdProcessingMode};
+    ///
+    /// let file = br#"{
+    ///     "@context": {
+    ///         "@vocab": "http://example.com/",
+    ///         "Person": {"@context": {"name": "http://schema.org/name"}},
+    ///         "knows": {"@context": {"@vocab": "http://xmlns.com/foaf/0.1/"}}
+    ///     },
+    ///     "@type": "Person",
+    ///     "name": "Foo",
+    ///     "knows": {"name": "Bar"}
+    /// }"#;
+    ///
+    /// let mut predicates = JsonLdParser::new()
+    ///     .with_processing_mode(JsonLdProcessingMode::JsonLd1_1)
+    ///     .for_slice(file)
+    ///     .map(|quad| Ok(quad?.predicate.into_string()))
+    ///     .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+    /// predicates.sort();
+    /// assert_eq!(
+    ///     predicates,
+    ///     [
+    ///         "http://example.com/knows",
+    ///         "http://schema.org/name",
+    ///         "http://www.w3.org/1999/02/22-rdf-syntax-ns#type",
+    ///         "http://xmlns.com/foaf/0.1/name"
+    ///     ]
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
     #[inline]
-    #[doc(hidden)] // TODO: expose after implementing JSON-LD 1.1
     pub fn with_processing_mode(mut self, processing_mode: JsonLdProcessingMode) -> Self {
         self.processing_mode = processing_mode;
         self
     }
 
+    /// Sets how the strings with a [base direction](https://www.w3.org/TR/json-ld11/#base-direction) are converted to RDF.
+    ///
+    /// By default, they are [directional language-tagged strings](JsonLdRdfDirection::DirLangString).
+    ///
+    /// ```
+    /// use oxjsonld::{JsonLdParser, JsonLdProcessingMode, JsonLdRdfDirection};
+    ///
+    /// let file = br#"{
+    ///     "@id": "http://example.com/foo",
+    ///     "http://schema.org/name": {"@value": "Foo", "@language": "en", "@direction": "ltr"}
+    /// }"#;
+    ///
+    /// let quads = JsonLdParser::new()
+    ///     .with_processing_mode(JsonLdProcessingMode::JsonLd1_1)
+    ///     .with_rdf_direction(JsonLdRdfDirection::I18nDatatype)
+    ///     .for_slice(file)
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(
+    ///     quads[0].object.to_string(),
+    ///     "\"Foo\"^^<https://www.w3.org/ns/i18n#en_ltr>"
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_rdf_direction(mut self, rdf_direction: JsonLdRdfDirection) -> Self {
+        self.rdf_direction = rdf_direction;
+        self
+    }
+
     /// Base IRI to use when expanding the document.
     ///
     /// It corresponds to the [`base` option from the algorithm specification](https://www.w3.org/TR/json-ld-api/#dom-jsonldoptions-base).
@@ -143,6 +222,22 @@ impl JsonLdParser {
         Ok(self)
     }
 
+    /// Sets the [`JsonLdDocumentLoader`] used to load the remote documents like the `@context` URLs.
+    ///
+    /// It corresponds to the [`documentLoader` option from the algorithm specification](https://www.w3.org/TR/json-ld11-api/#dom-jsonldoptions-documentloader).
+    /// The loaded contexts are cached and shared between all the documents parsed by this parser and its clones.
+    /// Without loader, documents referencing remote contexts fail to parse.
+    ///
+    /// Use [`HttpJsonLdDocumentLoader`](crate::HttpJsonLdDocumentLoader) (`http-client` feature) to fetch them using HTTP.
+    #[inline]
+    pub fn with_document_loader(
+        mut self,
+        document_loader: impl JsonLdDocumentLoader + 'static,
+    ) -> Self {
+        self.document_loader = Some(Arc::new(document_loader));
+        self
+    }
+
     /// Parses a JSON-LD file from a [`Read`] implementation.
     ///
     /// Count the number of people:
@@ -280,6 +375,56 @@ impl JsonLdParser {
         }
     }
 
+    /// Allows to parse a JSON-LD file by using a low-level API.
+    ///
+    /// It is useful to parse data incrementally, for example from asynchronous sources without blocking.
+    ///
+    /// Count the number of people:
+    /// ```
+    /// use oxjsonld::JsonLdParser;
+    /// use oxrdf::NamedNodeRef;
+    /// use oxrdf::vocab::rdf;
+    ///
+    /// let file: [&[u8]; 3] = [
+    ///     br#"{"@context": {"schema": "http://schema.org/"}, "@graph": ["#,
+    ///     br#"{"@type": "schema:Person", "@id": "http://example.com/foo", "schema:name": "Foo"},"#,
+    ///     br#"{"@type": "schema:Person", "schema:name": "Bar"}]}"#,
+    /// ];
+    ///
+    /// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+    /// let mut count = 0;
+    /// let mut parser = JsonLdParser::new().low_level();
+    /// let mut file_chunks = file.iter();
+    /// while !parser.is_end() {
+    ///     // We feed more data to the parser
+    ///     if let Some(chunk) = file_chunks.next() {
+    ///         parser.extend_from_slice(chunk);
+    ///     } else {
+    ///         parser.end(); // It's finished
+    ///     }
+    ///     // We read as many quads from the parser as possible
+    ///     while let Some(quad) = parser.parse_next() {
+    ///         let quad = quad?;
+    ///         if quad.predicate == rdf::TYPE && quad.object == schema_person.into() {
+    ///             count += 1;
+    ///         }
+    ///     }
+    /// }
+    /// assert_eq!(2, count);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn low_level(self) -> LowLevelJsonLdParser {
+        LowLevelJsonLdParser {
+            results: Vec::new(),
+            errors: Vec::new(),
+            inner: self.into_inner(),
+            json_parser: LowLevelJsonParser::new(),
+            buffer: Vec::new(),
+            buffer_start: 0,
+            is_ending: false,
+        }
+    }
+
     fn into_inner(self) -> InternalJsonLdParser {
         InternalJsonLdParser {
             expansion: JsonLdExpansionConverter::new(
@@ -287,11 +432,15 @@ impl JsonLdParser {
                 self.profile.contains(JsonLdProfile::Streaming),
                 self.lenient,
                 self.processing_mode,
+                self.remote_context_cache,
+                self.document_loader,
             ),
             expended_events: Vec::new(),
             to_rdf: JsonLdToRdfConverter {
                 state: vec![JsonLdToRdfState::Graph(Some(GraphName::DefaultGraph))],
                 lenient: self.lenient,
+                rdf_direction: self.rdf_direction,
+                vocab_named_nodes: HashMap::new(),
             },
             json_error: false,
         }
@@ -418,7 +567,10 @@ impl<R: Read> ReaderJsonLdParser<R> {
         + RefUnwindSafe
         + 'static,
     ) -> Self {
-        self.inner.expansion = self.inner.expansion.with_load_document_callback(callback);
+        self.inner.expansion = self
+            .inner
+            .expansion
+            .with_document_loader(Arc::new(callback));
         self
     }
 
@@ -746,7 +898,10 @@ impl SliceJsonLdParser<'_> {
         + RefUnwindSafe
         + 'static,
     ) -> Self {
-        self.inner.expansion = self.inner.expansion.with_load_document_callback(callback);
+        self.inner.expansion = self
+            .inner
+            .expansion
+            .with_document_loader(Arc::new(callback));
         self
     }
 
@@ -814,6 +969,133 @@ impl SliceJsonLdParser<'_> {
     }
 }
 
+/// Parses a JSON-LD file by using a low-level API.
+///
+/// Can be built using [`JsonLdParser::low_level`].
+///
+/// Count the number of people:
+/// ```
+/// use oxjsonld::JsonLdParser;
+/// use oxrdf::NamedNodeRef;
+/// use oxrdf::vocab::rdf;
+///
+/// let file: [&[u8]; 3] = [
+///     br#"{"@context": {"schema": "http://schema.org/"}, "@graph": ["#,
+///     br#"{"@type": "schema:Person", "@id": "http://example.com/foo", "schema:name": "Foo"},"#,
+///     br#"{"@type": "schema:Person", "schema:name": "Bar"}]}"#,
+/// ];
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut count = 0;
+/// let mut parser = JsonLdParser::new().low_level();
+/// let mut file_chunks = file.iter();
+/// while !parser.is_end() {
+///     // We feed more data to the parser
+///     if let Some(chunk) = file_chunks.next() {
+///         parser.extend_from_slice(chunk);
+///     } else {
+///         parser.end(); // It's finished
+///     }
+///     // We read as many quads from the parser as possible
+///     while let Some(quad) = parser.parse_next() {
+///         let quad = quad?;
+///         if quad.predicate == rdf::TYPE && quad.object == schema_person.into() {
+///             count += 1;
+///         }
+///     }
+/// }
+/// assert_eq!(2, count);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+pub struct LowLevelJsonLdParser {
+    results: Vec<Quad>,
+    errors: Vec<JsonLdSyntaxError>,
+    inner: InternalJsonLdParser,
+    json_parser: LowLevelJsonParser,
+    buffer: Vec<u8>,
+    buffer_start: usize,
+    is_ending: bool,
+}
+
+impl LowLevelJsonLdParser {
+    /// Adds some extra bytes to the parser. Should be called when [`parse_next`](Self::parse_next) returns [`None`] and there is still unread data.
+    pub fn extend_from_slice(&mut self, other: &[u8]) {
+        // We drop the already parsed bytes
+        self.buffer.drain(..self.buffer_start);
+        self.buffer_start = 0;
+        self.buffer.extend_from_slice(other);
+    }
+
+    /// Tell the parser that the file is finished.
+    ///
+    /// This triggers the parsing of the final bytes and might lead [`parse_next`](Self::parse_next) to return some extra values.
+    pub fn end(&mut self) {
+        self.is_ending = true;
+    }
+
+    /// Returns if the parsing is finished i.e. [`end`](Self::end) has been called and [`parse_next`](Self::parse_next) is always going to return `None`.
+    pub fn is_end(&self) -> bool {
+        self.results.is_empty() && self.errors.is_empty() && self.inner.is_end()
+    }
+
+    /// Attempt to parse a new quad from the already provided data.
+    ///
+    /// Returns [`None`] if the parsing is finished or more data is required.
+    /// If it is the case more data should be fed using [`extend_from_slice`](Self::extend_from_slice).
+    pub fn parse_next(&mut self) -> Option<Result<Quad, JsonLdSyntaxError>> {
+        loop {
+            if let Some(error) = self.errors.pop() {
+                return Some(Err(error));
+            } else if let Some(quad) = self.results.pop() {
+                return Some(Ok(quad));
+            } else if self.inner.is_end() {
+                return None;
+            }
+            let LowLevelJsonParserResult {
+                consumed_bytes,
+                event,
+            } = self
+                .json_parser
+                .parse_next(&self.buffer[self.buffer_start..], self.is_ending);
+            match event {
+                Some(Ok(event)) => {
+                    self.inner
+                        .parse_event(event, &mut self.results, &mut self.errors);
+                }
+                Some(Err(e)) => {
+                    self.inner.json_error = true;
+                    self.errors.push(e.into());
+                }
+                None => {
+                    self.buffer_start += consumed_bytes;
+                    if consumed_bytes == 0 {
+                        return None; // We need more data
+                    }
+                    continue;
+                }
+            }
+            self.buffer_start += consumed_bytes;
+            // We make sure to have data in the right order
+            self.results.reverse();
+            self.errors.reverse();
+        }
+    }
+
+    /// The list of IRI prefixes considered at the current step of the parsing.
+    ///
+    /// See [`SliceJsonLdParser::prefixes`].
+    pub fn prefixes(&self) -> JsonLdPrefixesIter<'_> {
+        self.inner.prefixes()
+    }
+
+    /// The base IRI considered at the current step of the parsing.
+    ///
+    /// See [`SliceJsonLdParser::base_iri`].
+    pub fn base_iri(&self) -> Option<&str> {
+        self.inner.base_iri()
+    }
+}
+
 /// Iterator on the file prefixes.
 ///
 /// See [`ReaderJsonLdParser::prefixes`].
@@ -902,6 +1184,9 @@ enum JsonLdToRdfState {
 struct JsonLdToRdfConverter {
     state: Vec<JsonLdToRdfState>,
     lenient: bool,
+    rdf_direction: JsonLdRdfDirection,
+    /// The already validated properties and types, they are usually shared by a lot of objects
+    vocab_named_nodes: HashMap<String, NamedNode>,
 }
 
 impl JsonLdToRdfConverter {
@@ -978,14 +1263,12 @@ impl JsonLdToRdfConverter {
                 JsonLdEvent::EndObject => (),
                 JsonLdEvent::StartProperty { name, reverse } => {
                     self.state.push(JsonLdToRdfState::Object(id));
-                    self.state.push(JsonLdToRdfState::Property {
-                        id: if self.has_defined_last_predicate() {
-                            self.convert_named_node(name)
-                        } else {
-                            None // We do not want to emit if one of the parent property is not emitted
-                        },
-                        reverse,
-                    });
+                    let id = if self.has_defined_last_predicate() {
+                        self.convert_vocab_named_node(name)
+                    } else {
+                        None // We do not want to emit if one of the parent property is not emitted
+                    };
+                    self.state.push(JsonLdToRdfState::Property { id, reverse });
                 }
                 JsonLdEvent::StartGraph => {
                     let graph_name = id.clone().map(Into::into);
@@ -1004,11 +1287,9 @@ impl JsonLdToRdfConverter {
             JsonLdToRdfState::Property { .. } => match event {
                 JsonLdEvent::StartObject { types } => {
                     self.state.push(state);
+                    let types = self.convert_types(types);
                     self.state.push(JsonLdToRdfState::StartObject {
-                        types: types
-                            .into_iter()
-                            .filter_map(|t| self.convert_named_or_blank_node(t))
-                            .collect(),
+                        types,
                         buffer: Vec::new(),
                         nesting: 0,
                     });
@@ -1017,10 +1298,11 @@ impl JsonLdToRdfConverter {
                     value,
                     r#type,
                     language,
+                    direction,
                 } => {
                     self.state.push(state);
                     self.emit_quad_for_new_literal(
-                        self.convert_literal(value, language, r#type),
+                        self.convert_literal(value, language, direction, r#type),
                         results,
                     )
                 }
@@ -1042,11 +1324,9 @@ impl JsonLdToRdfConverter {
             JsonLdToRdfState::List(current_node) => match event {
                 JsonLdEvent::StartObject { types } => {
                     self.add_new_list_node_state(current_node, results);
+                    let types = self.convert_types(types);
                     self.state.push(JsonLdToRdfState::StartObject {
-                        types: types
-                            .into_iter()
-                            .filter_map(|t| self.convert_named_or_blank_node(t))
-                            .collect(),
+                        types,
                         buffer: Vec::new(),
                         nesting: 0,
                     })
@@ -1055,10 +1335,11 @@ impl JsonLdToRdfConverter {
                     value,
                     r#type,
                     language,
+                    direction,
                 } => {
                     self.add_new_list_node_state(current_node, results);
                     self.emit_quad_for_new_literal(
-                        self.convert_literal(value, language, r#type),
+                        self.convert_literal(value, language, direction, r#type),
                         results,
                     )
                 }
@@ -1098,11 +1379,9 @@ impl JsonLdToRdfConverter {
             JsonLdToRdfState::Graph(_) => match event {
                 JsonLdEvent::StartObject { types } => {
                     self.state.push(state);
+                    let types = self.convert_types(types);
                     self.state.push(JsonLdToRdfState::StartObject {
-                        types: types
-                            .into_iter()
-                            .filter_map(|t| self.convert_named_or_blank_node(t))
-                            .collect(),
+                        types,
                         buffer: Vec::new(),
                         nesting: 0,
                     });
@@ -1217,10 +1496,37 @@ impl JsonLdToRdfConverter {
         }
     }
 
+    /// Same as [`convert_named_node`](Self::convert_named_node) but memoizes the validated IRIs
+    fn convert_vocab_named_node(&mut self, value: String) -> Option<NamedNode> {
+        if let Some(node) = self.vocab_named_nodes.get(&value) {
+            return Some(node.clone());
+        }
+        let node = self.convert_named_node(value)?;
+        if self.vocab_named_nodes.len() < MAX_VOCAB_NAMED_NODES_CACHE_SIZE {
+            self.vocab_named_nodes
+                .insert(node.as_str().into(), node.clone());
+        }
+        Some(node)
+    }
+
+    fn convert_types(&mut self, types: Vec<String>) -> Vec<NamedOrBlankNode> {
+        types
+            .into_iter()
+            .filter_map(|t| {
+                if t.starts_with("_:") {
+                    self.convert_named_or_blank_node(t)
+                } else {
+                    self.convert_vocab_named_node(t).map(Into::into)
+                }
+            })
+            .collect()
+    }
+
     fn convert_literal(
         &self,
         value: JsonLdValue,
         language: Option<String>,
+        direction: Option<&str>,
         r#type: Option<String>,
     ) -> Option<Literal> {
         let r#type = if let Some(t) = r#type {
@@ -1230,6 +1536,45 @@ impl JsonLdToRdfConverter {
         };
         Some(match value {
             JsonLdValue::String(value) => {
+                if let Some(direction) = direction {
+                    if r#type.is_some() {
+                        return None; // Expansion already returns an error
+                    }
+                    match self.rdf_direction {
+                        JsonLdRdfDirection::DirLangString =>
+                        {
+                            #[cfg(feature = "rdf-12")]
+                            if let Some(language) = &language {
+                                let direction = if direction == "rtl" {
+                                    BaseDirection::Rtl
+                                } else {
+                                    BaseDirection::Ltr
+                                };
+                                return if self.lenient {
+                                    Some(
+                                        Literal::new_directional_language_tagged_literal_unchecked(
+                                            value, language, direction,
+                                        ),
+                                    )
+                                } else {
+                                    Literal::new_directional_language_tagged_literal(
+                                        value, language, direction,
+                                    )
+                                    .ok()
+                                };
+                            }
+                        }
+                        JsonLdRdfDirection::I18nDatatype => {
+                            return Some(Literal::new_typed_literal(
+                                value,
+                                NamedNode::new_unchecked(format!(
+                                    "https://www.w3.org/ns/i18n#{}_{direction}",
+                                    language.unwrap_or_default().to_ascii_lowercase()
+                                )),
+                            ));
+                        }
+                    }
+                }
                 if let Some(language) = language {
                     if r#type.is_some_and(|t| t != rdf::LANG_STRING) {
                         return None; // Expansion already returns an error
@@ -1274,6 +1619,16 @@ impl JsonLdToRdfConverter {
                     r#type.unwrap_or_else(|| xsd::BOOLEAN.into()),
                 )
             }
+            JsonLdValue::Json(value) => {
+                if language.is_some() {
+                    return None; // Expansion already returns an error
+                }
+                Literal::new_typed_literal(
+                    value,
+                    // rdf:JSON is only in oxrdf vocabulary with RDF 1.2
+                    NamedNodeRef::new_unchecked("http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON"),
+                )
+            }
         })
     }
 
@@ -1414,8 +1769,348 @@ fn canonicalize_json_number(value: &str, always_double: bool) -> Option<RdfJsonN
 }
 
 #[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
 mod tests {
     use super::*;
+    use crate::error::JsonLdErrorCode;
+
+    fn parse_json_ld_1_1(file: &[u8], streaming: bool) -> Result<Vec<String>, JsonLdSyntaxError> {
+        let mut parser = JsonLdParser::new().with_processing_mode(JsonLdProcessingMode::JsonLd1_1);
+        if streaming {
+            parser = parser.with_profile(JsonLdProfile::Streaming);
+        }
+        let mut quads = parser
+            .for_slice(file)
+            .map(|quad| quad.map(|quad| quad.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        quads.sort();
+        Ok(quads)
+    }
+
+    #[test]
+    fn test_property_scoped_context() -> Result<(), JsonLdSyntaxError> {
+        let file = br#"{
+            "@context": {
+                "@vocab": "http://example.com/",
+                "knows": {"@context": {"@vocab": "http://xmlns.com/foaf/0.1/"}}
+            },
+            "@id": "http://example.com/a",
+            "name": "A",
+            "knows": {"@id": "http://example.com/b", "name": "B", "knows": {"@id": "http://example.com/c", "name": "C"}}
+        }"#;
+        for streaming in [false, true] {
+            assert_eq!(
+                parse_json_ld_1_1(file, streaming)?,
+                [
+                    "<http://example.com/a> <http://example.com/knows> <http://example.com/b>",
+                    "<http://example.com/a> <http://example.com/name> \"A\"",
+                    "<http://example.com/b> <http://example.com/knows> <http://example.com/c>",
+                    "<http://example.com/b> <http://xmlns.com/foaf/0.1/name> \"B\"",
+                    "<http://example.com/c> <http://xmlns.com/foaf/0.1/name> \"C\"",
+                ]
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_type_scoped_context() -> Result<(), JsonLdSyntaxError> {
+        let file = br#"{
+            "@context": {
+                "@vocab": "http://example.com/",
+                "Person": {"@context": {"@vocab": "http://schema.org/"}},
+                "Agent": {"@context": {"@vocab": "http://xmlns.com/foaf/0.1/", "@propagate": true}}
+            },
+            "@type": "Person",
+            "@id": "http://example.com/a",
+            "name": "A",
+            "knows": {
+                "@type": "Agent",
+                "@id": "http://example.com/b",
+                "name": "B",
+                "knows": {"@id": "http://example.com/c", "name": "C"}
+            }
+        }"#;
+        for streaming in [false, true] {
+            assert_eq!(
+                parse_json_ld_1_1(file, streaming)?,
+                [
+                    "<http://example.com/a> <http://schema.org/knows> <http://example.com/b>",
+                    "<http://example.com/a> <http://schema.org/name> \"A\"",
+                    "<http://example.com/a> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.com/Person>",
+                    "<http://example.com/b> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.com/Agent>",
+                    "<http://example.com/b> <http://xmlns.com/foaf/0.1/knows> <http://example.com/c>",
+                    "<http://example.com/b> <http://xmlns.com/foaf/0.1/name> \"B\"",
+                    "<http://example.com/c> <http://xmlns.com/foaf/0.1/name> \"C\"",
+                ]
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_named_graphs() -> Result<(), JsonLdSyntaxError> {
+        let file = br#"{
+            "@context": {"@vocab": "http://example.com/"},
+            "@graph": [
+                {"@id": "http://example.com/a", "name": "A"},
+                {
+                    "@id": "http://example.com/g1",
+                    "name": "G1",
+                    "@graph": {
+                        "@id": "http://example.com/b",
+                        "name": "B",
+                        "knows": {
+                            "@id": "http://example.com/g2",
+                            "@graph": [{"@id": "http://example.com/c", "name": "C"}]
+                        }
+                    }
+                }
+            ]
+        }"#;
+        for streaming in [false, true] {
+            assert_eq!(
+                parse_json_ld_1_1(file, streaming)?,
+                [
+                    "<http://example.com/a> <http://example.com/name> \"A\"",
+                    "<http://example.com/b> <http://example.com/knows> <http://example.com/g2> <http://example.com/g1>",
+                    "<http://example.com/b> <http://example.com/name> \"B\" <http://example.com/g1>",
+                    "<http://example.com/c> <http://example.com/name> \"C\" <http://example.com/g2>",
+                    "<http://example.com/g1> <http://example.com/name> \"G1\"",
+                ]
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_and_set_containers() -> Result<(), JsonLdSyntaxError> {
+        let file = br#"{
+            "@context": {
+                "@vocab": "http://example.com/",
+                "list": {"@container": "@list"},
+                "set": {"@container": "@set"}
+            },
+            "@id": "http://example.com/s",
+            "list": [1, 2],
+            "empty": {"@list": []},
+            "set": [3, 4]
+        }"#;
+        for streaming in [false, true] {
+            let quads = parse_json_ld_1_1(file, streaming)?;
+            assert_eq!(
+                quads.iter().filter(|quad| quad.contains("#first")).count(),
+                2
+            );
+            assert!(quads.contains(&"<http://example.com/s> <http://example.com/empty> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil>".into()));
+            assert!(quads.contains(&"<http://example.com/s> <http://example.com/set> \"3\"^^<http://www.w3.org/2001/XMLSchema#integer>".into()));
+            assert!(quads.contains(&"<http://example.com/s> <http://example.com/set> \"4\"^^<http://www.w3.org/2001/XMLSchema#integer>".into()));
+        }
+        assert_eq!(
+            JsonLdParser::new()
+                .with_processing_mode(JsonLdProcessingMode::JsonLd1_0)
+                .for_slice(file)
+                .collect::<Result<Vec<_>, _>>()?
+                .len(),
+            8
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_of_lists() -> Result<(), JsonLdSyntaxError> {
+        let container = br#"{
+            "@context": {"list": {"@id": "http://example.com/list", "@container": "@list"}},
+            "@id": "http://example.com/s",
+            "list": [[1, 2], 3]
+        }"#;
+        let object = br#"{
+            "@id": "http://example.com/s",
+            "http://example.com/list": {"@list": [{"@list": [1, 2]}, 3]}
+        }"#;
+        for file in [container.as_slice(), object.as_slice()] {
+            for streaming in [false, true] {
+                let quads = parse_json_ld_1_1(file, streaming)?;
+                assert_eq!(
+                    quads.iter().filter(|quad| quad.contains("#first")).count(),
+                    4
+                );
+
+                let mut parser =
+                    JsonLdParser::new().with_processing_mode(JsonLdProcessingMode::JsonLd1_0);
+                if streaming {
+                    parser = parser.with_profile(JsonLdProfile::Streaming);
+                }
+                assert!(matches!(
+                    parser
+                        .for_slice(file)
+                        .find_map(Result::err)
+                        .and_then(|error| error.code()),
+                    Some(JsonLdErrorCode::ListOfLists)
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_low_level_byte_by_byte() -> Result<(), JsonLdSyntaxError> {
+        let file = br#"{
+            "@context": {"@vocab": "http://example.com/"},
+            "@id": "http://example.com/s",
+            "name": ["a", {"@value": "b", "@language": "en"}],
+            "knows": {"@id": "http://example.com/o", "name": "c"}
+        }"#;
+        let mut parser = JsonLdParser::new().low_level();
+        let mut quads = Vec::new();
+        for byte in file {
+            parser.extend_from_slice(&[*byte]);
+            while let Some(quad) = parser.parse_next() {
+                quads.push(quad?.to_string());
+            }
+        }
+        parser.end();
+        while let Some(quad) = parser.parse_next() {
+            quads.push(quad?.to_string());
+        }
+        assert!(parser.is_end());
+        assert_eq!(
+            quads,
+            JsonLdParser::new()
+                .for_slice(file)
+                .map(|quad| quad.map(|quad| quad.to_string()))
+                .collect::<Result<Vec<_>, _>>()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_reverse_properties() -> Result<(), JsonLdSyntaxError> {
+        let file = br#"{
+            "@context": {
+                "@vocab": "http://example.com/",
+                "children": {"@reverse": "http://example.com/parent"}
+            },
+            "@id": "http://example.com/a",
+            "@reverse": {"knows": [{"@id": "http://example.com/b"}, {"@id": "http://example.com/c", "name": "C"}]},
+            "children": {"@id": "http://example.com/d"}
+        }"#;
+        for streaming in [false, true] {
+            assert_eq!(
+                parse_json_ld_1_1(file, streaming)?,
+                [
+                    "<http://example.com/b> <http://example.com/knows> <http://example.com/a>",
+                    "<http://example.com/c> <http://example.com/knows> <http://example.com/a>",
+                    "<http://example.com/c> <http://example.com/name> \"C\"",
+                    "<http://example.com/d> <http://example.com/parent> <http://example.com/a>",
+                ]
+            );
+        }
+        assert!(matches!(
+            JsonLdParser::new()
+                .for_slice(br#"{"@reverse": {"http://example.com/p": "literal"}}"#)
+                .find_map(Result::err)
+                .and_then(|error| error.code()),
+            Some(JsonLdErrorCode::InvalidReversePropertyValue)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_literals() -> Result<(), JsonLdSyntaxError> {
+        let file = br#"{
+            "@context": {
+                "@vocab": "http://example.com/",
+                "json": {"@type": "@json"}
+            },
+            "@id": "http://example.com/s",
+            "json": [{"b": [true, null], "a": 1.0}, "a\nb", null],
+            "value": {"@type": "@json", "@value": [1e21, 0.000001, "\u0001"]}
+        }"#;
+        for streaming in [false, true] {
+            assert_eq!(
+                parse_json_ld_1_1(file, streaming)?,
+                [
+                    r#"<http://example.com/s> <http://example.com/json> "[{\"a\":1,\"b\":[true,null]},\"a\\nb\",null]"^^<http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON>"#,
+                    r#"<http://example.com/s> <http://example.com/value> "[1e+21,0.000001,\"\\u0001\"]"^^<http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON>"#,
+                ]
+            );
+        }
+        // Without streaming, @type might be after @value
+        assert_eq!(
+            parse_json_ld_1_1(
+                br#"{"@id": "http://example.com/s", "http://example.com/value": {"@value": {"a": 1}, "@type": "@json"}}"#,
+                false
+            )?,
+            [
+                r#"<http://example.com/s> <http://example.com/value> "{\"a\":1}"^^<http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON>"#
+            ]
+        );
+        assert!(
+            JsonLdParser::new()
+                .with_processing_mode(JsonLdProcessingMode::JsonLd1_0)
+                .for_slice(file)
+                .any(|quad| quad.is_err())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_base_direction() -> Result<(), JsonLdSyntaxError> {
+        let file = br#"{
+            "@context": {
+                "@vocab": "http://example.com/",
+                "@language": "ar",
+                "@direction": "rtl",
+                "label": {"@container": "@language", "@direction": "ltr"}
+            },
+            "@id": "http://example.com/s",
+            "name": "a",
+            "title": {"@value": "b", "@direction": "ltr"},
+            "label": {"en": "c"}
+        }"#;
+        for streaming in [false, true] {
+            let mut parser = JsonLdParser::new()
+                .with_processing_mode(JsonLdProcessingMode::JsonLd1_1)
+                .with_rdf_direction(JsonLdRdfDirection::I18nDatatype);
+            if streaming {
+                parser = parser.with_profile(JsonLdProfile::Streaming);
+            }
+            let mut quads = parser
+                .for_slice(file)
+                .map(|quad| quad.map(|quad| quad.to_string()))
+                .collect::<Result<Vec<_>, _>>()?;
+            quads.sort();
+            assert_eq!(
+                quads,
+                [
+                    "<http://example.com/s> <http://example.com/label> \"c\"^^<https://www.w3.org/ns/i18n#en_ltr>",
+                    "<http://example.com/s> <http://example.com/name> \"a\"^^<https://www.w3.org/ns/i18n#ar_rtl>",
+                    "<http://example.com/s> <http://example.com/title> \"b\"^^<https://www.w3.org/ns/i18n#_ltr>",
+                ]
+            );
+
+            #[cfg(feature = "rdf-12")]
+            assert_eq!(
+                parse_json_ld_1_1(file, streaming)?,
+                [
+                    "<http://example.com/s> <http://example.com/label> \"c\"@en--ltr",
+                    "<http://example.com/s> <http://example.com/name> \"a\"@ar--rtl",
+                    "<http://example.com/s> <http://example.com/title> \"b\"",
+                ]
+            );
+            #[cfg(not(feature = "rdf-12"))]
+            assert_eq!(
+                parse_json_ld_1_1(file, streaming)?,
+                [
+                    "<http://example.com/s> <http://example.com/label> \"c\"@en",
+                    "<http://example.com/s> <http://example.com/name> \"a\"@ar",
+                    "<http://example.com/s> <http://example.com/title> \"b\"",
+                ]
+            );
+        }
+        Ok(())
+    }
 
     #[test]
     fn test_canonicalize_json_number() {
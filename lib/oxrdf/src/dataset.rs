@@ -575,6 +575,129 @@ impl Dataset {
                     .map(|(from, to)| (from, BlankNode::new_from_unique_id(to.into())))
                     .collect()
             }
+            #[cfg(feature = "rdfc-10")]
+            CanonicalizationAlgorithm::Rdfc10 { hash_algorithm } => {
+                rdfc10::canonical_blank_node_ids(self.iter(), hash_algorithm)
+                    .into_iter()
+                    .filter_map(|(from, to)| {
+                        Some((
+                            InternedBlankNode::encoded_from(from, &self.interner)?,
+                            BlankNode::new_unchecked(to),
+                        ))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns the [canonical N-Quads](https://www.w3.org/TR/rdf-canon/#canonical-quads) serialization of the dataset.
+    ///
+    /// The blank nodes are [canonicalized](Self::canonicalize) with the given algorithm
+    /// and the quads are sorted in Unicode code point order.
+    /// With the [RDFC-1.0](CanonicalizationAlgorithm::Rdfc10) algorithm, it is the canonical form of the dataset
+    /// to hash or sign.
+    ///
+    /// ```
+    /// use oxrdf::dataset::CanonicalizationAlgorithm;
+    /// use oxrdf::*;
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let mut dataset = Dataset::new();
+    /// dataset.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph));
+    /// dataset.insert(QuadRef::new(ex, ex, LiteralRef::from("foo"), ex));
+    /// assert_eq!(
+    ///     dataset.to_canonical_n_quads(CanonicalizationAlgorithm::Unstable),
+    ///     "<http://example.com> <http://example.com> \"foo\" <http://example.com> .\n<http://example.com> <http://example.com> <http://example.com> .\n"
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn to_canonical_n_quads(&self, algorithm: CanonicalizationAlgorithm) -> String {
+        let mut dataset = self.clone();
+        dataset.canonicalize(algorithm);
+        let mut lines = dataset
+            .iter()
+            .map(|quad| format!("{quad} .\n"))
+            .collect::<Vec<_>>();
+        lines.sort_unstable();
+        lines.concat()
+    }
+
+    /// Checks if the two datasets are [isomorphic](https://www.w3.org/TR/rdf11-concepts/#dfn-dataset-isomorphism),
+    /// i.e. are equal up to a renaming of their blank nodes.
+    ///
+    /// It is much faster than comparing [canonicalized](Self::canonicalize) datasets:
+    /// the blank nodes are matched using iterative color refinement
+    /// and only the blank nodes that can't be distinguished this way lead to a backtracking search.
+    ///
+    /// ```
+    /// use oxrdf::*;
+    ///
+    /// let iri = NamedNodeRef::new("http://example.com")?;
+    /// let [a, b, c, d] = ["a", "b", "c", "d"].map(BlankNode::new_unchecked);
+    ///
+    /// let dataset1 = Dataset::from_iter([
+    ///     QuadRef::new(&a, iri, &b, GraphNameRef::DefaultGraph),
+    ///     QuadRef::new(&b, iri, &a, &c),
+    /// ]);
+    /// let dataset2 = Dataset::from_iter([
+    ///     QuadRef::new(&c, iri, &d, GraphNameRef::DefaultGraph),
+    ///     QuadRef::new(&d, iri, &c, &a),
+    /// ]);
+    /// let dataset3 = Dataset::from_iter([
+    ///     QuadRef::new(&c, iri, &d, GraphNameRef::DefaultGraph),
+    ///     QuadRef::new(&c, iri, &d, &a),
+    /// ]);
+    /// assert!(dataset1.is_isomorphic(&dataset2));
+    /// assert!(!dataset1.is_isomorphic(&dataset3));
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    ///
+    /// <div class="warning">The worst-case complexity is still exponential in the number of blank nodes,
+    /// but it is only reached on very regular structures that are not isomorphic.</div>
+    pub fn is_isomorphic(&self, other: &Self) -> bool {
+        isomorphism::are_isomorphic(self, other)
+    }
+
+    /// Returns the changes to apply to this dataset to get the `other` dataset.
+    ///
+    /// Blank nodes are compared using their identifiers.
+    /// To diff datasets with blank nodes, first [canonicalize](Self::canonicalize) both datasets with the same algorithm.
+    ///
+    /// ```
+    /// use oxrdf::*;
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let old = QuadRef::new(ex, ex, LiteralRef::from("old"), GraphNameRef::DefaultGraph);
+    /// let new = QuadRef::new(ex, ex, LiteralRef::from("new"), GraphNameRef::DefaultGraph);
+    /// let dataset1 = Dataset::from_iter([old]);
+    /// let dataset2 = Dataset::from_iter([new]);
+    ///
+    /// let diff = dataset1.diff(&dataset2);
+    /// assert_eq!(diff.deletions(), [old.into_owned()]);
+    /// assert_eq!(diff.insertions(), [new.into_owned()]);
+    ///
+    /// let mut dataset = dataset1.clone();
+    /// diff.apply_to(&mut dataset);
+    /// assert_eq!(dataset, dataset2);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn diff(&self, other: &Self) -> DatasetDiff {
+        let mut deletions = self
+            .iter()
+            .filter(|quad| !other.contains(*quad))
+            .map(QuadRef::into_owned)
+            .collect::<Vec<_>>();
+        let mut insertions = other
+            .iter()
+            .filter(|quad| !self.contains(*quad))
+            .map(QuadRef::into_owned)
+            .collect::<Vec<_>>();
+        // We use the N-Quads order to get a deterministic output
+        deletions.sort_by_cached_key(Quad::to_string);
+        insertions.sort_by_cached_key(Quad::to_string);
+        DatasetDiff {
+            deletions,
+            insertions,
         }
     }
 
@@ -1563,6 +1686,45 @@ type QuadsPerBlankNode = HashMap<
     )>,
 >;
 
+/// The changes between two [`Dataset`]s, built by [`Dataset::diff`].
+///
+/// The quads are sorted in the N-Quads order and the deletions must be applied before the insertions.
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct DatasetDiff {
+    deletions: Vec<Quad>,
+    insertions: Vec<Quad>,
+}
+
+impl DatasetDiff {
+    /// The quads to remove.
+    #[inline]
+    pub fn deletions(&self) -> &[Quad] {
+        &self.deletions
+    }
+
+    /// The quads to add.
+    #[inline]
+    pub fn insertions(&self) -> &[Quad] {
+        &self.insertions
+    }
+
+    /// Checks if there is no change.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.deletions.is_empty() && self.insertions.is_empty()
+    }
+
+    /// Applies the changes to a dataset.
+    pub fn apply_to(&self, dataset: &mut Dataset) {
+        for quad in &self.deletions {
+            dataset.remove(quad);
+        }
+        for quad in &self.insertions {
+            dataset.insert(quad);
+        }
+    }
+}
+
 /// An algorithm used to canonicalize graph and datasets.
 ///
 /// See [`Graph::canonicalize`] and [`Dataset::canonicalize`].
@@ -1574,6 +1736,26 @@ pub enum CanonicalizationAlgorithm {
     /// <div class="warning">The canonicalization algorithm is not stable and canonical blank node ids might change between Oxigraph version.</div>
     #[default]
     Unstable,
+    /// The [RDF Dataset Canonicalization (RDFC-1.0)](https://www.w3.org/TR/rdf-canon/) algorithm.
+    ///
+    /// The canonical blank node ids are `c14n0`, `c14n1`...
+    #[cfg(feature = "rdfc-10")]
+    Rdfc10 {
+        /// The hash algorithm used by the canonicalization algorithm.
+        hash_algorithm: CanonicalizationHashAlgorithm,
+    },
+}
+
+/// A hash algorithm used by the [RDFC-1.0](CanonicalizationAlgorithm::Rdfc10) canonicalization algorithm.
+#[cfg(feature = "rdfc-10")]
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum CanonicalizationHashAlgorithm {
+    /// [SHA-256](https://csrc.nist.gov/pubs/fips/180-4/upd1/final), the default one.
+    #[default]
+    Sha256,
+    /// [SHA-384](https://csrc.nist.gov/pubs/fips/180-4/upd1/final).
+    Sha384,
 }
 
 #[cfg(test)]
@@ -1612,4 +1794,146 @@ mod tests {
         dataset2.canonicalize(CanonicalizationAlgorithm::Unstable);
         assert_eq!(dataset, dataset2);
     }
+
+    fn ring(ids: &[String], graph_name: GraphNameRef<'_>) -> Dataset {
+        let next = NamedNodeRef::new_unchecked("http://example.com/next");
+        ids.iter()
+            .zip(ids.iter().cycle().skip(1))
+            .map(|(from, to)| {
+                QuadRef::new(
+                    &BlankNode::new_unchecked(from),
+                    next,
+                    &BlankNode::new_unchecked(to),
+                    graph_name,
+                )
+                .into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_is_isomorphic() {
+        let ids = |prefix: &str, range: std::ops::Range<usize>| {
+            range.map(|i| format!("{prefix}{i}")).collect::<Vec<_>>()
+        };
+        // A big cycle of blank nodes
+        let dataset1 = ring(&ids("a", 0..500), GraphNameRef::DefaultGraph);
+        let mut shifted = ids("b", 0..500);
+        shifted.rotate_left(250);
+        let dataset2 = ring(&shifted, GraphNameRef::DefaultGraph);
+        assert!(dataset1.is_isomorphic(&dataset2));
+        assert!(dataset2.is_isomorphic(&dataset1));
+
+        // Two cycles of 3 blank nodes are not a cycle of 6 blank nodes even if each blank node has the same neighborhood
+        let mut two_rings = ring(&ids("a", 0..3), GraphNameRef::DefaultGraph);
+        two_rings.extend(&ring(&ids("a", 3..6), GraphNameRef::DefaultGraph));
+        let one_ring = ring(&ids("b", 0..6), GraphNameRef::DefaultGraph);
+        assert!(!two_rings.is_isomorphic(&one_ring));
+        assert!(two_rings.is_isomorphic(&two_rings.clone()));
+
+        // Blank node graph names
+        let graph = BlankNode::new_unchecked("g");
+        let in_graph = ring(&ids("a", 0..4), graph.as_ref().into());
+        let in_other_graph = ring(&ids("a", 0..4), BlankNode::default().as_ref().into());
+        assert!(in_graph.is_isomorphic(&in_other_graph));
+        assert!(!in_graph.is_isomorphic(&ring(&ids("a", 0..4), GraphNameRef::DefaultGraph)));
+    }
+
+    #[test]
+    fn test_diff() {
+        let ex = NamedNodeRef::new_unchecked("http://example.com");
+        let graph = NamedNodeRef::new_unchecked("http://example.com/g");
+        let quad_a = QuadRef::new(ex, ex, LiteralRef::from("a"), graph);
+        let quad_b = QuadRef::new(ex, ex, LiteralRef::from("b"), GraphNameRef::DefaultGraph);
+        let quad_c = QuadRef::new(ex, ex, LiteralRef::from("c"), graph);
+        let quad_d = QuadRef::new(ex, ex, LiteralRef::from("d"), GraphNameRef::DefaultGraph);
+        let dataset1 = Dataset::from_iter([quad_c, quad_a, quad_b]);
+        let dataset2 = Dataset::from_iter([quad_d, quad_b]);
+        assert!(dataset1.diff(&dataset1).is_empty());
+        let diff = dataset1.diff(&dataset2);
+        assert_eq!(diff.deletions(), [quad_a.into_owned(), quad_c.into_owned()]);
+        assert_eq!(diff.insertions(), [quad_d.into_owned()]);
+        let mut dataset = dataset1.clone();
+        diff.apply_to(&mut dataset);
+        assert_eq!(dataset, dataset2);
+        dataset2.diff(&dataset1).apply_to(&mut dataset);
+        assert_eq!(dataset, dataset1);
+    }
+
+    #[cfg(feature = "rdfc-10")]
+    fn rdfc10_quad<'a>(s: &'a BlankNode, p: &'a str, o: TermRef<'a>) -> QuadRef<'a> {
+        QuadRef::new(
+            s,
+            NamedNodeRef::new_unchecked(p),
+            o,
+            GraphNameRef::DefaultGraph,
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "rdfc-10")]
+    fn test_rdfc10_spec_example() {
+        let p = NamedNodeRef::new_unchecked("http://example.com/#p");
+        let u = NamedNodeRef::new_unchecked("http://example.com/#u");
+        let e0 = BlankNode::new_unchecked("e0");
+        let e1 = BlankNode::new_unchecked("e1");
+        let mut dataset = Dataset::new();
+        dataset.insert(QuadRef::new(
+            p,
+            NamedNodeRef::new_unchecked("http://example.com/#q"),
+            &e0,
+            GraphNameRef::DefaultGraph,
+        ));
+        dataset.insert(QuadRef::new(
+            p,
+            NamedNodeRef::new_unchecked("http://example.com/#r"),
+            &e1,
+            GraphNameRef::DefaultGraph,
+        ));
+        dataset.insert(rdfc10_quad(&e0, "http://example.com/#s", u.into()));
+        dataset.insert(rdfc10_quad(&e1, "http://example.com/#t", u.into()));
+        assert_eq!(
+            dataset.to_canonical_n_quads(CanonicalizationAlgorithm::Rdfc10 {
+                hash_algorithm: CanonicalizationHashAlgorithm::Sha256
+            }),
+            "<http://example.com/#p> <http://example.com/#q> _:c14n0 .\n\
+             <http://example.com/#p> <http://example.com/#r> _:c14n1 .\n\
+             _:c14n0 <http://example.com/#s> <http://example.com/#u> .\n\
+             _:c14n1 <http://example.com/#t> <http://example.com/#u> .\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rdfc-10")]
+    fn test_rdfc10_symmetric() {
+        // Two rings of three blank nodes, only distinguishable with n-degree hashes
+        let ring = |names: [&str; 6]| {
+            let nodes = names.map(BlankNode::new_unchecked);
+            let mut dataset = Dataset::new();
+            for ring in nodes.chunks(3) {
+                for (i, node) in ring.iter().enumerate() {
+                    dataset.insert(rdfc10_quad(
+                        node,
+                        "http://example.com/#next",
+                        (&ring[(i + 1) % 3]).into(),
+                    ));
+                }
+            }
+            dataset
+        };
+        let dataset1 = ring(["a", "b", "c", "d", "e", "f"]);
+        let dataset2 = ring(["z", "x", "y", "w", "u", "v"]);
+        for hash_algorithm in [
+            CanonicalizationHashAlgorithm::Sha256,
+            CanonicalizationHashAlgorithm::Sha384,
+        ] {
+            let algorithm = CanonicalizationAlgorithm::Rdfc10 { hash_algorithm };
+            let canonical = dataset1.to_canonical_n_quads(algorithm);
+            assert_eq!(canonical, dataset2.to_canonical_n_quads(algorithm));
+            assert_eq!(canonical.lines().count(), 6);
+            for i in 0..6 {
+                assert!(canonical.contains(&format!("_:c14n{i} ")));
+            }
+        }
+    }
 }
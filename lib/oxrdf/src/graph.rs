@@ -26,6 +26,8 @@
 //! See also [`Dataset`] if you want to get support of multiple RDF graphs at the same time.
 
 pub use crate::dataset::CanonicalizationAlgorithm;
+#[cfg(feature = "rdfc-10")]
+pub use crate::dataset::CanonicalizationHashAlgorithm;
 use crate::dataset::*;
 use crate::*;
 use std::fmt;
@@ -217,6 +219,21 @@ impl Graph {
     pub fn canonicalize(&mut self, algorithm: CanonicalizationAlgorithm) {
         self.dataset.canonicalize(algorithm)
     }
+
+    /// Returns the canonical N-Triples serialization of the graph.
+    ///
+    /// See [`Dataset::to_canonical_n_quads`].
+    pub fn to_canonical_n_triples(&self, algorithm: CanonicalizationAlgorithm) -> String {
+        self.dataset.to_canonical_n_quads(algorithm)
+    }
+
+    /// Checks if the two graphs are [isomorphic](https://www.w3.org/TR/rdf11-concepts/#graph-isomorphism),
+    /// i.e. are equal up to a renaming of their blank nodes.
+    ///
+    /// See [`Dataset::is_isomorphic`].
+    pub fn is_isomorphic(&self, other: &Self) -> bool {
+        self.dataset.is_isomorphic(&other.dataset)
+    }
 }
 
 impl PartialEq for Graph {
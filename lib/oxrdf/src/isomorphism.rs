@@ -0,0 +1,286 @@
+//! Dataset isomorphism check based on color refinement and individualization.
+//!
+//! Each blank node gets a color that is iteratively refined with the colors of its neighbors until the partition is stable.
+//! If some blank nodes still share a color, one of them is matched in turn with each candidate of the other dataset
+//! by giving them the same fresh color, and the refinement starts again.
+//! Once all colors are distinct, the only candidate mapping is checked against the quads.
+
+use crate::{
+    BlankNode, BlankNodeRef, Dataset, GraphName, GraphNameRef, NamedOrBlankNode,
+    NamedOrBlankNodeRef, Quad, QuadRef, Term, TermRef,
+};
+#[cfg(feature = "rdf-12")]
+use crate::{Triple, TripleRef};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// Checks if the two datasets are isomorphic.
+pub fn are_isomorphic(a: &Dataset, b: &Dataset) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let a = BlankNodeQuads::new(a);
+    let b = BlankNodeQuads::new(b);
+    // The datasets have the same size so the ground quads are the same if they are all in the other dataset
+    if a.quads.len() != b.quads.len()
+        || a.blank_nodes.len() != b.blank_nodes.len()
+        || !a.ground_quads.iter().all(|quad| b.dataset.contains(*quad))
+    {
+        return false;
+    }
+    let quads = b.quads.iter().copied().collect::<HashSet<_>>();
+    search(
+        &a,
+        &b,
+        &quads,
+        vec![0; a.blank_nodes.len()],
+        vec![0; b.blank_nodes.len()],
+    )
+}
+
+/// The quads with blank nodes of a dataset, indexed by blank node
+struct BlankNodeQuads<'a> {
+    dataset: &'a Dataset,
+    quads: Vec<QuadRef<'a>>,
+    ground_quads: Vec<QuadRef<'a>>,
+    blank_nodes: Vec<BlankNodeRef<'a>>,
+    ids: HashMap<BlankNodeRef<'a>, usize>,
+    quads_per_blank_node: Vec<Vec<usize>>,
+}
+
+impl<'a> BlankNodeQuads<'a> {
+    fn new(dataset: &'a Dataset) -> Self {
+        let mut result = Self {
+            dataset,
+            quads: Vec::new(),
+            ground_quads: Vec::new(),
+            blank_nodes: Vec::new(),
+            ids: HashMap::new(),
+            quads_per_blank_node: Vec::new(),
+        };
+        let mut blank_nodes = Vec::new();
+        for quad in dataset {
+            add_quad_blank_nodes(quad, &mut blank_nodes);
+            if blank_nodes.is_empty() {
+                result.ground_quads.push(quad);
+                continue;
+            }
+            let quad_id = result.quads.len();
+            result.quads.push(quad);
+            for blank_node in blank_nodes.drain(..) {
+                let id = *result.ids.entry(blank_node).or_insert_with(|| {
+                    result.blank_nodes.push(blank_node);
+                    result.quads_per_blank_node.push(Vec::new());
+                    result.blank_nodes.len() - 1
+                });
+                if result.quads_per_blank_node[id].last() != Some(&quad_id) {
+                    result.quads_per_blank_node[id].push(quad_id);
+                }
+            }
+        }
+        result
+    }
+
+    /// Computes the new color of each blank node from its color and the colors of its neighbors
+    fn refine(&self, colors: &[u64]) -> Vec<u64> {
+        let mut signatures = Vec::new();
+        self.quads_per_blank_node
+            .iter()
+            .enumerate()
+            .map(|(id, quads)| {
+                signatures.extend(quads.iter().map(|quad_id| {
+                    let mut hasher = DefaultHasher::new();
+                    self.hash_quad(self.quads[*quad_id], id, colors, &mut hasher);
+                    hasher.finish()
+                }));
+                signatures.sort_unstable();
+                let mut hasher = DefaultHasher::new();
+                (colors[id], &signatures).hash(&mut hasher);
+                signatures.clear();
+                hasher.finish()
+            })
+            .collect()
+    }
+
+    fn hash_quad(&self, quad: QuadRef<'_>, id: usize, colors: &[u64], hasher: &mut impl Hasher) {
+        self.hash_term(quad.subject.into(), id, colors, hasher);
+        quad.predicate.hash(hasher);
+        self.hash_term(quad.object, id, colors, hasher);
+        match quad.graph_name {
+            GraphNameRef::NamedNode(node) => self.hash_term(node.into(), id, colors, hasher),
+            GraphNameRef::BlankNode(node) => self.hash_term(node.into(), id, colors, hasher),
+            GraphNameRef::DefaultGraph => 0_u8.hash(hasher),
+        }
+    }
+
+    fn hash_term(&self, term: TermRef<'_>, id: usize, colors: &[u64], hasher: &mut impl Hasher) {
+        match term {
+            TermRef::NamedNode(node) => (1_u8, node).hash(hasher),
+            TermRef::BlankNode(node) => {
+                let node_id = self.ids[&node];
+                if node_id == id {
+                    2_u8.hash(hasher)
+                } else {
+                    (3_u8, colors[node_id]).hash(hasher)
+                }
+            }
+            TermRef::Literal(literal) => (4_u8, literal).hash(hasher),
+            #[cfg(feature = "rdf-12")]
+            TermRef::Triple(triple) => {
+                5_u8.hash(hasher);
+                self.hash_term(triple.subject.as_ref().into(), id, colors, hasher);
+                triple.predicate.hash(hasher);
+                self.hash_term(triple.object.as_ref(), id, colors, hasher);
+            }
+        }
+    }
+}
+
+/// Refines the colors and then individualizes the blank nodes until all the colors are distinct
+fn search(
+    a: &BlankNodeQuads<'_>,
+    b: &BlankNodeQuads<'_>,
+    b_quads: &HashSet<QuadRef<'_>>,
+    mut a_colors: Vec<u64>,
+    mut b_colors: Vec<u64>,
+) -> bool {
+    if !refine(a, b, &mut a_colors, &mut b_colors) {
+        return false;
+    }
+    let mut cells = HashMap::<_, (Vec<_>, Vec<_>)>::new();
+    for (id, color) in a_colors.iter().enumerate() {
+        cells.entry(*color).or_default().0.push(id);
+    }
+    for (id, color) in b_colors.iter().enumerate() {
+        cells.entry(*color).or_default().1.push(id);
+    }
+    // We individualize a blank node of the smallest non-singleton cell to limit the branching
+    let Some((a_cell, b_cell)) = cells
+        .into_values()
+        .filter(|(a_cell, _)| a_cell.len() > 1)
+        .min_by_key(|(a_cell, _)| a_cell.len())
+    else {
+        return is_isomorphism(a, b, b_quads, &a_colors, &b_colors);
+    };
+    let a_id = a_cell[0];
+    let mut hasher = DefaultHasher::new();
+    (a_colors[a_id], a_cell.len()).hash(&mut hasher);
+    let new_color = hasher.finish();
+    b_cell.into_iter().any(|b_id| {
+        let mut a_colors = a_colors.clone();
+        let mut b_colors = b_colors.clone();
+        a_colors[a_id] = new_color;
+        b_colors[b_id] = new_color;
+        search(a, b, b_quads, a_colors, b_colors)
+    })
+}
+
+/// Refines the colors until the partition is stable, returns `false` if the two datasets get different colors
+fn refine(
+    a: &BlankNodeQuads<'_>,
+    b: &BlankNodeQuads<'_>,
+    a_colors: &mut Vec<u64>,
+    b_colors: &mut Vec<u64>,
+) -> bool {
+    let mut color_count = None;
+    loop {
+        *a_colors = a.refine(a_colors);
+        *b_colors = b.refine(b_colors);
+        let mut a_sorted = a_colors.clone();
+        a_sorted.sort_unstable();
+        let mut b_sorted = b_colors.clone();
+        b_sorted.sort_unstable();
+        if a_sorted != b_sorted {
+            return false;
+        }
+        a_sorted.dedup();
+        if color_count == Some(a_sorted.len()) {
+            return true;
+        }
+        color_count = Some(a_sorted.len());
+    }
+}
+
+/// Checks that mapping the blank nodes with the same color is an isomorphism
+fn is_isomorphism(
+    a: &BlankNodeQuads<'_>,
+    b: &BlankNodeQuads<'_>,
+    b_quads: &HashSet<QuadRef<'_>>,
+    a_colors: &[u64],
+    b_colors: &[u64],
+) -> bool {
+    let b_ids = b_colors
+        .iter()
+        .enumerate()
+        .map(|(id, color)| (*color, id))
+        .collect::<HashMap<_, _>>();
+    let mapping = a
+        .blank_nodes
+        .iter()
+        .zip(a_colors)
+        .map(|(node, color)| (*node, b.blank_nodes[b_ids[color]].into_owned()))
+        .collect::<HashMap<_, _>>();
+    a.quads
+        .iter()
+        .all(|quad| b_quads.contains(&map_quad(*quad, &mapping).as_ref()))
+}
+
+fn map_quad(quad: QuadRef<'_>, mapping: &HashMap<BlankNodeRef<'_>, BlankNode>) -> Quad {
+    Quad {
+        subject: map_subject(quad.subject, mapping),
+        predicate: quad.predicate.into_owned(),
+        object: map_term(quad.object, mapping),
+        graph_name: match quad.graph_name {
+            GraphNameRef::BlankNode(node) => GraphName::BlankNode(mapping[&node].clone()),
+            graph_name => graph_name.into_owned(),
+        },
+    }
+}
+
+fn map_subject(
+    subject: NamedOrBlankNodeRef<'_>,
+    mapping: &HashMap<BlankNodeRef<'_>, BlankNode>,
+) -> NamedOrBlankNode {
+    match subject {
+        NamedOrBlankNodeRef::BlankNode(node) => mapping[&node].clone().into(),
+        NamedOrBlankNodeRef::NamedNode(node) => node.into_owned().into(),
+    }
+}
+
+fn map_term(term: TermRef<'_>, mapping: &HashMap<BlankNodeRef<'_>, BlankNode>) -> Term {
+    match term {
+        TermRef::BlankNode(node) => mapping[&node].clone().into(),
+        #[cfg(feature = "rdf-12")]
+        TermRef::Triple(triple) => Triple {
+            subject: map_subject(triple.subject.as_ref(), mapping),
+            predicate: triple.predicate.clone(),
+            object: map_term(triple.object.as_ref(), mapping),
+        }
+        .into(),
+        other => other.into_owned(),
+    }
+}
+
+fn add_quad_blank_nodes<'a>(quad: QuadRef<'a>, blank_nodes: &mut Vec<BlankNodeRef<'a>>) {
+    add_term_blank_nodes(quad.subject.into(), blank_nodes);
+    add_term_blank_nodes(quad.object, blank_nodes);
+    if let GraphNameRef::BlankNode(node) = quad.graph_name {
+        blank_nodes.push(node);
+    }
+}
+
+fn add_term_blank_nodes<'a>(term: TermRef<'a>, blank_nodes: &mut Vec<BlankNodeRef<'a>>) {
+    match term {
+        TermRef::BlankNode(node) => blank_nodes.push(node),
+        #[cfg(feature = "rdf-12")]
+        TermRef::Triple(triple) => add_triple_blank_nodes(triple.as_ref(), blank_nodes),
+        _ => (),
+    }
+}
+
+#[cfg(feature = "rdf-12")]
+fn add_triple_blank_nodes<'a>(triple: TripleRef<'a>, blank_nodes: &mut Vec<BlankNodeRef<'a>>) {
+    add_term_blank_nodes(triple.subject.into(), blank_nodes);
+    add_term_blank_nodes(triple.object, blank_nodes);
+}
@@ -8,9 +8,15 @@ mod blank_node;
 pub mod dataset;
 pub mod graph;
 mod interning;
+mod isomorphism;
 mod literal;
 mod named_node;
 mod parser;
+#[cfg(feature = "rdfc-10")]
+mod rdfc10;
+#[cfg(feature = "serde")]
+pub mod serde_str;
+mod term_interner;
 mod triple;
 mod variable;
 pub mod vocab;
@@ -23,6 +29,7 @@ pub use crate::literal::BaseDirection;
 pub use crate::literal::{Literal, LiteralRef};
 pub use crate::named_node::{NamedNode, NamedNodeRef};
 pub use crate::parser::TermParseError;
+pub use crate::term_interner::TermInterner;
 pub use crate::triple::{
     GraphName, GraphNameRef, NamedOrBlankNode, NamedOrBlankNodeRef, Quad, QuadRef, Term, TermRef,
     Triple, TripleRef, TryFromTermError,
@@ -1,4 +1,5 @@
 use crate::named_node::{NamedNode, NamedNodeRef};
+use crate::term_interner::SharedString;
 use crate::vocab::{rdf, xsd};
 use oxilangtag::{LanguageTag, LanguageTagParseError};
 #[cfg(feature = "oxsdatatypes")]
@@ -38,19 +39,19 @@ pub struct Literal(LiteralContent);
 
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
 enum LiteralContent {
-    String(String),
+    String(SharedString),
     LanguageTaggedString {
-        value: String,
-        language: String,
+        value: SharedString,
+        language: SharedString,
     },
     #[cfg(feature = "rdf-12")]
     DirectionalLanguageTaggedString {
-        value: String,
-        language: String,
+        value: SharedString,
+        language: SharedString,
         direction: BaseDirection,
     },
     TypedLiteral {
-        value: String,
+        value: SharedString,
         datatype: NamedNode,
     },
 }
@@ -59,14 +60,17 @@ impl Literal {
     /// Builds an RDF [simple literal](https://www.w3.org/TR/rdf11-concepts/#dfn-simple-literal).
     #[inline]
     pub fn new_simple_literal(value: impl Into<String>) -> Self {
-        Self(LiteralContent::String(value.into()))
+        Self(LiteralContent::String(SharedString::Owned(value.into())))
     }
 
     /// Builds an RDF [literal](https://www.w3.org/TR/rdf11-concepts/#dfn-literal) with a [datatype](https://www.w3.org/TR/rdf11-concepts/#dfn-datatype-iri).
     #[inline]
     pub fn new_typed_literal(value: impl Into<String>, datatype: impl Into<NamedNode>) -> Self {
-        let value = value.into();
-        let datatype = datatype.into();
+        Self::new_shared_typed_literal(SharedString::Owned(value.into()), datatype.into())
+    }
+
+    #[inline]
+    pub(crate) fn new_shared_typed_literal(value: SharedString, datatype: NamedNode) -> Self {
         Self(if datatype == xsd::STRING {
             LiteralContent::String(value)
         } else {
@@ -100,10 +104,18 @@ impl Literal {
         value: impl Into<String>,
         language: impl Into<String>,
     ) -> Self {
-        Self(LiteralContent::LanguageTaggedString {
-            value: value.into(),
-            language: language.into(),
-        })
+        Self::new_shared_language_tagged_literal(
+            SharedString::Owned(value.into()),
+            SharedString::Owned(language.into()),
+        )
+    }
+
+    #[inline]
+    pub(crate) fn new_shared_language_tagged_literal(
+        value: SharedString,
+        language: SharedString,
+    ) -> Self {
+        Self(LiteralContent::LanguageTaggedString { value, language })
     }
 
     /// Builds an RDF [directional language-tagged string](https://www.w3.org/TR/rdf12-concepts/#dfn-dir-lang-string).
@@ -136,11 +148,25 @@ impl Literal {
         value: impl Into<String>,
         language: impl Into<String>,
         direction: impl Into<BaseDirection>,
+    ) -> Self {
+        Self::new_shared_directional_language_tagged_literal(
+            SharedString::Owned(value.into()),
+            SharedString::Owned(language.into()),
+            direction.into(),
+        )
+    }
+
+    #[cfg(feature = "rdf-12")]
+    #[inline]
+    pub(crate) fn new_shared_directional_language_tagged_literal(
+        value: SharedString,
+        language: SharedString,
+        direction: BaseDirection,
     ) -> Self {
         Self(LiteralContent::DirectionalLanguageTaggedString {
-            value: value.into(),
-            language: language.into(),
-            direction: direction.into(),
+            value,
+            language,
+            direction,
         })
     }
 
@@ -224,16 +250,26 @@ impl Literal {
         Option<BaseDirection>,
     ) {
         match self.0 {
-            LiteralContent::String(s) => (s, None, None, None),
-            LiteralContent::LanguageTaggedString { value, language } => {
-                (value, None, Some(language), None)
-            }
+            LiteralContent::String(s) => (s.into_string(), None, None, None),
+            LiteralContent::LanguageTaggedString { value, language } => (
+                value.into_string(),
+                None,
+                Some(language.into_string()),
+                None,
+            ),
             LiteralContent::DirectionalLanguageTaggedString {
                 value,
                 language,
                 direction,
-            } => (value, None, Some(language), Some(direction)),
-            LiteralContent::TypedLiteral { value, datatype } => (value, Some(datatype), None, None),
+            } => (
+                value.into_string(),
+                None,
+                Some(language.into_string()),
+                Some(direction),
+            ),
+            LiteralContent::TypedLiteral { value, datatype } => {
+                (value.into_string(), Some(datatype), None, None)
+            }
         }
     }
 
@@ -242,11 +278,13 @@ impl Literal {
     #[inline]
     pub fn destruct(self) -> (String, Option<NamedNode>, Option<String>) {
         match self.0 {
-            LiteralContent::String(s) => (s, None, None),
+            LiteralContent::String(s) => (s.into_string(), None, None),
             LiteralContent::LanguageTaggedString { value, language } => {
-                (value, None, Some(language))
+                (value.into_string(), None, Some(language.into_string()))
+            }
+            LiteralContent::TypedLiteral { value, datatype } => {
+                (value.into_string(), Some(datatype), None)
             }
-            LiteralContent::TypedLiteral { value, datatype } => (value, Some(datatype), None),
         }
     }
 }
@@ -261,21 +299,21 @@ impl fmt::Display for Literal {
 impl<'a> From<&'a str> for Literal {
     #[inline]
     fn from(value: &'a str) -> Self {
-        Self(LiteralContent::String(value.into()))
+        Self(LiteralContent::String(SharedString::Owned(value.into())))
     }
 }
 
 impl From<String> for Literal {
     #[inline]
     fn from(value: String) -> Self {
-        Self(LiteralContent::String(value))
+        Self(LiteralContent::String(value.into()))
     }
 }
 
 impl<'a> From<Cow<'a, str>> for Literal {
     #[inline]
     fn from(value: Cow<'a, str>) -> Self {
-        Self(LiteralContent::String(value.into()))
+        Self(LiteralContent::String(SharedString::Owned(value.into())))
     }
 }
 
@@ -283,7 +321,7 @@ impl From<bool> for Literal {
     #[inline]
     fn from(value: bool) -> Self {
         Self(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: value.to_string().into(),
             datatype: xsd::BOOLEAN.into(),
         })
     }
@@ -293,7 +331,7 @@ impl From<i128> for Literal {
     #[inline]
     fn from(value: i128) -> Self {
         Self(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: value.to_string().into(),
             datatype: xsd::INTEGER.into(),
         })
     }
@@ -303,7 +341,7 @@ impl From<i64> for Literal {
     #[inline]
     fn from(value: i64) -> Self {
         Self(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: value.to_string().into(),
             datatype: xsd::INTEGER.into(),
         })
     }
@@ -313,7 +351,7 @@ impl From<i32> for Literal {
     #[inline]
     fn from(value: i32) -> Self {
         Self(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: value.to_string().into(),
             datatype: xsd::INTEGER.into(),
         })
     }
@@ -323,7 +361,7 @@ impl From<i16> for Literal {
     #[inline]
     fn from(value: i16) -> Self {
         Self(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: value.to_string().into(),
             datatype: xsd::INTEGER.into(),
         })
     }
@@ -333,7 +371,7 @@ impl From<u64> for Literal {
     #[inline]
     fn from(value: u64) -> Self {
         Self(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: value.to_string().into(),
             datatype: xsd::INTEGER.into(),
         })
     }
@@ -343,7 +381,7 @@ impl From<u32> for Literal {
     #[inline]
     fn from(value: u32) -> Self {
         Self(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: value.to_string().into(),
             datatype: xsd::INTEGER.into(),
         })
     }
@@ -353,7 +391,7 @@ impl From<u16> for Literal {
     #[inline]
     fn from(value: u16) -> Self {
         Self(LiteralContent::TypedLiteral {
-            value: value.to_string(),
+            value: value.to_string().into(),
             datatype: xsd::INTEGER.into(),
         })
     }
@@ -369,7 +407,8 @@ impl From<f32> for Literal {
                 "-INF".to_owned()
             } else {
                 value.to_string()
-            },
+            }
+            .into(),
             datatype: xsd::FLOAT.into(),
         })
     }
@@ -385,7 +424,8 @@ impl From<f64> for Literal {
                 "-INF".to_owned()
             } else {
                 value.to_string()
-            },
+            }
+            .into(),
             datatype: xsd::DOUBLE.into(),
         })
     }
@@ -678,11 +718,11 @@ impl<'a> LiteralRef<'a> {
     #[inline]
     pub fn into_owned(self) -> Literal {
         Literal(match self.0 {
-            LiteralRefContent::String(value) => LiteralContent::String(value.to_owned()),
+            LiteralRefContent::String(value) => LiteralContent::String(value.to_owned().into()),
             LiteralRefContent::LanguageTaggedString { value, language } => {
                 LiteralContent::LanguageTaggedString {
-                    value: value.to_owned(),
-                    language: language.to_owned(),
+                    value: value.to_owned().into(),
+                    language: language.to_owned().into(),
                 }
             }
             #[cfg(feature = "rdf-12")]
@@ -691,12 +731,12 @@ impl<'a> LiteralRef<'a> {
                 language,
                 direction,
             } => LiteralContent::DirectionalLanguageTaggedString {
-                value: value.to_owned(),
-                language: language.to_owned(),
+                value: value.to_owned().into(),
+                language: language.to_owned().into(),
                 direction,
             },
             LiteralRefContent::TypedLiteral { value, datatype } => LiteralContent::TypedLiteral {
-                value: value.to_owned(),
+                value: value.to_owned().into(),
                 datatype: datatype.into_owned(),
             },
         })
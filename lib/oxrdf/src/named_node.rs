@@ -1,3 +1,4 @@
+use crate::term_interner::SharedString;
 use oxiri::{Iri, IriParseError};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
@@ -18,7 +19,7 @@ use std::fmt;
 /// ```
 #[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Hash)]
 pub struct NamedNode {
-    iri: String,
+    iri: SharedString,
 }
 
 impl NamedNode {
@@ -39,7 +40,12 @@ impl NamedNode {
     /// [`NamedNode::new()`] is a safe version of this constructor and should be used for untrusted data.
     #[inline]
     pub fn new_unchecked(iri: impl Into<String>) -> Self {
-        Self { iri: iri.into() }
+        Self::new_shared(SharedString::Owned(iri.into()))
+    }
+
+    #[inline]
+    pub(crate) fn new_shared(iri: SharedString) -> Self {
+        Self { iri }
     }
 
     #[inline]
@@ -49,7 +55,7 @@ impl NamedNode {
 
     #[inline]
     pub fn into_string(self) -> String {
-        self.iri
+        self.iri.into_string()
     }
 
     #[inline]
@@ -222,9 +228,7 @@ impl PartialOrd<NamedNodeRef<'_>> for NamedNode {
 impl From<Iri<String>> for NamedNode {
     #[inline]
     fn from(iri: Iri<String>) -> Self {
-        Self {
-            iri: iri.into_inner(),
-        }
+        Self::new_unchecked(iri.into_inner())
     }
 }
 
@@ -282,7 +286,7 @@ mod tests {
     fn named_node_construction() {
         assert_eq!(
             "http://example.org/",
-            NamedNode::new("http://example.org/").unwrap().iri
+            NamedNode::new("http://example.org/").unwrap().as_str()
         );
     }
 
@@ -0,0 +1,348 @@
+//! Implementation of the [RDF Dataset Canonicalization (RDFC-1.0)](https://www.w3.org/TR/rdf-canon/) algorithm.
+
+use crate::dataset::CanonicalizationHashAlgorithm;
+use crate::{BlankNodeRef, GraphNameRef, NamedOrBlankNodeRef, QuadRef, TermRef};
+use sha2::{Digest, Sha256, Sha384};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Returns the canonical identifiers of the blank nodes of the quads, without the `_:` prefix.
+pub fn canonical_blank_node_ids<'a>(
+    quads: impl IntoIterator<Item = QuadRef<'a>>,
+    hash_algorithm: CanonicalizationHashAlgorithm,
+) -> HashMap<BlankNodeRef<'a>, String> {
+    // 4.4.3 step 2
+    let mut quads_per_blank_node = HashMap::<_, Vec<_>>::new();
+    for quad in quads {
+        for (blank_node, _) in blank_nodes(quad) {
+            let quads = quads_per_blank_node.entry(blank_node).or_default();
+            if quads.last() != Some(&quad) {
+                quads.push(quad);
+            }
+        }
+    }
+    let mut state = CanonicalizationState {
+        hash_algorithm,
+        first_degree_hashes: HashMap::new(),
+        canonical_issuer: IdentifierIssuer::new("c14n"),
+    };
+    // 4.4.3 step 3
+    let mut hash_to_blank_nodes = BTreeMap::<_, Vec<_>>::new();
+    for (blank_node, quads) in &quads_per_blank_node {
+        let hash = state.hash_first_degree_quads(*blank_node, quads);
+        state.first_degree_hashes.insert(*blank_node, hash.clone());
+        hash_to_blank_nodes
+            .entry(hash)
+            .or_default()
+            .push(*blank_node);
+    }
+    // 4.4.3 step 4
+    let mut shared_hash_blank_nodes = Vec::new();
+    for blank_nodes in hash_to_blank_nodes.into_values() {
+        if let [blank_node] = blank_nodes.as_slice() {
+            state.canonical_issuer.issue(*blank_node);
+        } else {
+            shared_hash_blank_nodes.push(blank_nodes);
+        }
+    }
+    // 4.4.3 step 5
+    for blank_nodes in shared_hash_blank_nodes {
+        let mut hash_path_list = Vec::new();
+        for blank_node in blank_nodes {
+            if state.canonical_issuer.get(blank_node).is_some() {
+                continue;
+            }
+            let mut temporary_issuer = IdentifierIssuer::new("b");
+            temporary_issuer.issue(blank_node);
+            hash_path_list.push(state.hash_n_degree_quads(
+                blank_node,
+                temporary_issuer,
+                &quads_per_blank_node,
+            ));
+        }
+        hash_path_list.sort_by(|(h1, _), (h2, _)| h1.cmp(h2));
+        for (_, issuer) in hash_path_list {
+            for blank_node in issuer.issued_order {
+                state.canonical_issuer.issue(blank_node);
+            }
+        }
+    }
+    state.canonical_issuer.issued
+}
+
+struct CanonicalizationState<'a> {
+    hash_algorithm: CanonicalizationHashAlgorithm,
+    first_degree_hashes: HashMap<BlankNodeRef<'a>, String>,
+    canonical_issuer: IdentifierIssuer<'a>,
+}
+
+impl<'a> CanonicalizationState<'a> {
+    /// 4.6 Hash First Degree Quads
+    fn hash_first_degree_quads(
+        &self,
+        reference: BlankNodeRef<'_>,
+        quads: &[QuadRef<'_>],
+    ) -> String {
+        let mut lines = quads
+            .iter()
+            .map(|quad| {
+                let mut line = String::new();
+                write_quad(*quad, &mut line, &|blank_node| {
+                    if blank_node == reference { "a" } else { "z" }
+                });
+                line
+            })
+            .collect::<Vec<_>>();
+        lines.sort_unstable();
+        self.hash(&lines.concat())
+    }
+
+    /// 4.7 Hash Related Blank Node
+    fn hash_related_blank_node(
+        &self,
+        related: BlankNodeRef<'a>,
+        quad: QuadRef<'_>,
+        issuer: &IdentifierIssuer<'a>,
+        position: char,
+    ) -> String {
+        let mut input = String::from(position);
+        if position != 'g' {
+            write!(input, "{}", quad.predicate).unwrap();
+        }
+        if let Some(id) = self
+            .canonical_issuer
+            .get(related)
+            .or_else(|| issuer.get(related))
+        {
+            input.push_str("_:");
+            input.push_str(id);
+        } else {
+            input.push_str(&self.first_degree_hashes[&related]);
+        }
+        self.hash(&input)
+    }
+
+    /// 4.8 Hash N-Degree Quads
+    fn hash_n_degree_quads(
+        &self,
+        identifier: BlankNodeRef<'a>,
+        mut issuer: IdentifierIssuer<'a>,
+        quads_per_blank_node: &HashMap<BlankNodeRef<'a>, Vec<QuadRef<'a>>>,
+    ) -> (String, IdentifierIssuer<'a>) {
+        // Steps 1 to 3
+        let mut related_hash_to_blank_nodes = BTreeMap::<_, Vec<_>>::new();
+        for quad in &quads_per_blank_node[&identifier] {
+            for (related, position) in blank_nodes(*quad) {
+                if related != identifier {
+                    related_hash_to_blank_nodes
+                        .entry(self.hash_related_blank_node(related, *quad, &issuer, position))
+                        .or_default()
+                        .push(related);
+                }
+            }
+        }
+        // Steps 4 and 5
+        let mut data_to_hash = String::new();
+        for (related_hash, blank_nodes) in related_hash_to_blank_nodes {
+            data_to_hash.push_str(&related_hash);
+            let mut chosen_path = String::new();
+            let mut chosen_issuer = None;
+            'permutations: for permutation in permutations(&blank_nodes) {
+                let mut issuer_copy = issuer.clone();
+                let mut path = String::new();
+                let mut recursion_list = Vec::new();
+                for related in permutation {
+                    if let Some(id) = self.canonical_issuer.get(related) {
+                        path.push_str("_:");
+                        path.push_str(id);
+                    } else {
+                        if issuer_copy.get(related).is_none() {
+                            recursion_list.push(related);
+                        }
+                        path.push_str("_:");
+                        path.push_str(issuer_copy.issue(related));
+                    }
+                    if is_worse_path(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+                for related in recursion_list {
+                    let (hash, mut result_issuer) =
+                        self.hash_n_degree_quads(related, issuer_copy, quads_per_blank_node);
+                    path.push_str("_:");
+                    path.push_str(result_issuer.issue(related));
+                    path.push('<');
+                    path.push_str(&hash);
+                    path.push('>');
+                    issuer_copy = result_issuer;
+                    if is_worse_path(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+                if chosen_path.is_empty() || path < chosen_path {
+                    chosen_path = path;
+                    chosen_issuer = Some(issuer_copy);
+                }
+            }
+            data_to_hash.push_str(&chosen_path);
+            if let Some(chosen_issuer) = chosen_issuer {
+                issuer = chosen_issuer;
+            }
+        }
+        // Step 6
+        (self.hash(&data_to_hash), issuer)
+    }
+
+    fn hash(&self, data: &str) -> String {
+        match self.hash_algorithm {
+            CanonicalizationHashAlgorithm::Sha256 => hex(&Sha256::digest(data)),
+            CanonicalizationHashAlgorithm::Sha384 => hex(&Sha384::digest(data)),
+        }
+    }
+}
+
+/// 4.5 Issue Identifier
+#[derive(Clone)]
+struct IdentifierIssuer<'a> {
+    prefix: &'static str,
+    issued: HashMap<BlankNodeRef<'a>, String>,
+    issued_order: Vec<BlankNodeRef<'a>>,
+}
+
+impl<'a> IdentifierIssuer<'a> {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            issued: HashMap::new(),
+            issued_order: Vec::new(),
+        }
+    }
+
+    fn get(&self, blank_node: BlankNodeRef<'a>) -> Option<&str> {
+        self.issued.get(&blank_node).map(String::as_str)
+    }
+
+    fn issue(&mut self, blank_node: BlankNodeRef<'a>) -> &str {
+        if !self.issued.contains_key(&blank_node) {
+            let id = format!("{}{}", self.prefix, self.issued_order.len());
+            self.issued_order.push(blank_node);
+            self.issued.insert(blank_node, id);
+        }
+        &self.issued[&blank_node]
+    }
+}
+
+/// Checks the 4.8.3 step 5.4.4.3 and 5.4.5.5 conditions to skip to the next permutation
+fn is_worse_path(path: &str, chosen_path: &str) -> bool {
+    !chosen_path.is_empty() && path.len() >= chosen_path.len() && path > chosen_path
+}
+
+/// The blank nodes of the quad with their position (`s`, `o` or `g`)
+///
+/// The blank nodes in triple terms are considered to be in the position of the triple term.
+fn blank_nodes(quad: QuadRef<'_>) -> Vec<(BlankNodeRef<'_>, char)> {
+    let mut blank_nodes = Vec::new();
+    if let NamedOrBlankNodeRef::BlankNode(blank_node) = quad.subject {
+        blank_nodes.push((blank_node, 's'));
+    }
+    add_term_blank_nodes(quad.object, 'o', &mut blank_nodes);
+    if let GraphNameRef::BlankNode(blank_node) = quad.graph_name {
+        blank_nodes.push((blank_node, 'g'));
+    }
+    blank_nodes
+}
+
+fn add_term_blank_nodes<'a>(
+    term: TermRef<'a>,
+    position: char,
+    blank_nodes: &mut Vec<(BlankNodeRef<'a>, char)>,
+) {
+    match term {
+        TermRef::BlankNode(blank_node) => blank_nodes.push((blank_node, position)),
+        #[cfg(feature = "rdf-12")]
+        TermRef::Triple(triple) => {
+            if let NamedOrBlankNodeRef::BlankNode(blank_node) = triple.subject.as_ref() {
+                blank_nodes.push((blank_node, position));
+            }
+            add_term_blank_nodes(triple.object.as_ref(), position, blank_nodes);
+        }
+        TermRef::NamedNode(_) | TermRef::Literal(_) => (),
+    }
+}
+
+/// Writes the quad in canonical N-Quads with the blank node ids given by `id`
+fn write_quad(
+    quad: QuadRef<'_>,
+    output: &mut String,
+    id: &impl Fn(BlankNodeRef<'_>) -> &'static str,
+) {
+    match quad.subject {
+        NamedOrBlankNodeRef::NamedNode(node) => write!(output, "{node}").unwrap(),
+        NamedOrBlankNodeRef::BlankNode(node) => write!(output, "_:{}", id(node)).unwrap(),
+    }
+    write!(output, " {} ", quad.predicate).unwrap();
+    write_term(quad.object, output, id);
+    match quad.graph_name {
+        GraphNameRef::NamedNode(node) => write!(output, " {node}").unwrap(),
+        GraphNameRef::BlankNode(node) => write!(output, " _:{}", id(node)).unwrap(),
+        GraphNameRef::DefaultGraph => (),
+    }
+    output.push_str(" .\n");
+}
+
+fn write_term(
+    term: TermRef<'_>,
+    output: &mut String,
+    id: &impl Fn(BlankNodeRef<'_>) -> &'static str,
+) {
+    match term {
+        TermRef::BlankNode(node) => write!(output, "_:{}", id(node)).unwrap(),
+        #[cfg(feature = "rdf-12")]
+        TermRef::Triple(triple) => {
+            output.push_str("<<( ");
+            match triple.subject.as_ref() {
+                NamedOrBlankNodeRef::NamedNode(node) => write!(output, "{node}").unwrap(),
+                NamedOrBlankNodeRef::BlankNode(node) => write!(output, "_:{}", id(node)).unwrap(),
+            }
+            write!(output, " {} ", triple.predicate).unwrap();
+            write_term(triple.object.as_ref(), output, id);
+            output.push_str(" )>>");
+        }
+        TermRef::NamedNode(_) | TermRef::Literal(_) => write!(output, "{term}").unwrap(),
+    }
+}
+
+/// All the permutations of the elements
+fn permutations<T: Copy>(elements: &[T]) -> impl Iterator<Item = Vec<T>> + '_ {
+    let mut indexes = (0..elements.len()).collect::<Vec<_>>();
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let permutation = indexes.iter().map(|i| elements[*i]).collect();
+        // We compute the next permutation in lexicographic order
+        if let Some(pivot) = indexes
+            .windows(2)
+            .rposition(|w| matches!(w, [a, b] if a < b))
+        {
+            let successor = indexes
+                .iter()
+                .rposition(|i| *i > indexes[pivot])
+                .unwrap_or(pivot + 1);
+            indexes.swap(pivot, successor);
+            indexes[pivot + 1..].reverse();
+        } else {
+            done = true;
+        }
+        Some(permutation)
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        write!(output, "{byte:02x}").unwrap();
+    }
+    output
+}
@@ -0,0 +1,93 @@
+//! Serializes the RDF terms, triples and quads as strings using their N-Triples and N-Quads syntax.
+//!
+//! The default [`Serialize`] and [`Deserialize`] implementations of the RDF model use structured values.
+//! This module is meant to be used with the `#[serde(with = "...")]` attribute to get string values instead.
+//!
+//! ```
+//! use oxrdf::{Literal, NamedNode, Term};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Statement {
+//!     #[serde(with = "oxrdf::serde_str")]
+//!     subject: NamedNode,
+//!     #[serde(with = "oxrdf::serde_str")]
+//!     object: Term,
+//! }
+//!
+//! let statement = Statement {
+//!     subject: NamedNode::new("http://example.com/s")?,
+//!     object: Literal::new_simple_literal("o").into(),
+//! };
+//! let json = serde_json::to_string(&statement)?;
+//! assert_eq!(json, r#"{"subject":"<http://example.com/s>","object":"\"o\""}"#);
+//! assert_eq!(serde_json::from_str::<Statement>(&json)?, statement);
+//! # Result::<_, Box<dyn std::error::Error>>::Ok(())
+//! ```
+
+use serde::{Deserialize, Deserializer, Serializer, de};
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Serializes a value using its [`Display`] implementation.
+pub fn serialize<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// Deserializes a value using its [`FromStr`] implementation.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    Cow::<'de, str>::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BlankNode, GraphName, Literal, NamedNode, Quad, Triple};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Value {
+        #[serde(with = "super")]
+        node: BlankNode,
+        #[serde(with = "super")]
+        literal: Literal,
+        #[serde(with = "super")]
+        triple: Triple,
+        #[serde(with = "super")]
+        quad: Quad,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let triple = Triple::new(
+            BlankNode::new_unchecked("s"),
+            NamedNode::new_unchecked("http://example.com/p"),
+            Literal::new_language_tagged_literal_unchecked("o", "en"),
+        );
+        let value = Value {
+            node: BlankNode::new_unchecked("b"),
+            literal: Literal::new_typed_literal(
+                "1",
+                NamedNode::new_unchecked("http://www.w3.org/2001/XMLSchema#integer"),
+            ),
+            triple: triple.clone(),
+            quad: triple.in_graph(GraphName::NamedNode(NamedNode::new_unchecked(
+                "http://example.com/g",
+            ))),
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            json,
+            r#"{"node":"_:b","literal":"\"1\"^^<http://www.w3.org/2001/XMLSchema#integer>","triple":"_:s <http://example.com/p> \"o\"@en","quad":"_:s <http://example.com/p> \"o\"@en <http://example.com/g>"}"#
+        );
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+        serde_json::from_str::<Value>(r#"{"node":"b"}"#).unwrap_err();
+    }
+}
@@ -0,0 +1,265 @@
+//! Sharing of the strings of RDF terms between term values.
+
+use crate::{
+    GraphName, GraphNameRef, Literal, LiteralRef, NamedNode, NamedNodeRef, NamedOrBlankNode,
+    NamedOrBlankNodeRef, Quad, QuadRef, Term, TermRef, Triple, TripleRef,
+};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A string owned by a term or shared between terms built by a [`TermInterner`].
+#[derive(Clone)]
+pub(crate) enum SharedString {
+    Owned(String),
+    Shared(Arc<str>),
+}
+
+impl SharedString {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Owned(value) => value,
+            Self::Shared(value) => value,
+        }
+    }
+
+    #[inline]
+    pub fn into_string(self) -> String {
+        match self {
+            Self::Owned(value) => value,
+            Self::Shared(value) => value.as_ref().into(),
+        }
+    }
+}
+
+impl Deref for SharedString {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<String> for SharedString {
+    #[inline]
+    fn from(value: String) -> Self {
+        Self::Owned(value)
+    }
+}
+
+impl PartialEq for SharedString {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SharedString {}
+
+impl PartialOrd for SharedString {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SharedString {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for SharedString {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for SharedString {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// Deduplicates the strings of RDF terms.
+///
+/// The terms built by the interner share their IRIs, literal values and language tags
+/// with all the other terms built by the same interner or by its clones, making them cheap to clone.
+/// It allows to reduce a lot the memory used by repetitive data kept as owned terms, for example in a `Vec<Quad>`.
+///
+/// Blank nodes are not interned.
+/// Note that [`Graph`](crate::Graph) and [`Dataset`](crate::Dataset) already deduplicate their strings internally.
+///
+/// ```
+/// use oxrdf::{NamedNodeRef, QuadRef, TermInterner};
+///
+/// let ex = NamedNodeRef::new("http://example.com")?;
+/// let mut interner = TermInterner::new();
+/// let quad1 = interner.intern_quad(QuadRef::new(ex, ex, ex, ex));
+/// let quad2 = interner.intern_quad(QuadRef::new(ex, ex, ex, ex));
+/// assert_eq!(quad1, quad2);
+/// assert_eq!(interner.len(), 1); // a single string is stored
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TermInterner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl TermInterner {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of distinct strings stored in the interner.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    fn intern_str(&mut self, value: &str) -> SharedString {
+        SharedString::Shared(if let Some(value) = self.strings.get(value) {
+            Arc::clone(value)
+        } else {
+            let value = Arc::<str>::from(value);
+            self.strings.insert(Arc::clone(&value));
+            value
+        })
+    }
+
+    /// Builds an owned [`NamedNode`] sharing its IRI with the other nodes with the same IRI.
+    pub fn intern_named_node<'a>(&mut self, node: impl Into<NamedNodeRef<'a>>) -> NamedNode {
+        NamedNode::new_shared(self.intern_str(node.into().as_str()))
+    }
+
+    /// Builds an owned [`Literal`] sharing its strings with the other interned terms.
+    pub fn intern_literal<'a>(&mut self, literal: impl Into<LiteralRef<'a>>) -> Literal {
+        let literal = literal.into();
+        let value = self.intern_str(literal.value());
+        #[cfg(feature = "rdf-12")]
+        if let Some(direction) = literal.direction() {
+            let language = self.intern_str(literal.language().unwrap_or_default());
+            return Literal::new_shared_directional_language_tagged_literal(
+                value, language, direction,
+            );
+        }
+        if let Some(language) = literal.language() {
+            let language = self.intern_str(language);
+            Literal::new_shared_language_tagged_literal(value, language)
+        } else {
+            Literal::new_shared_typed_literal(value, self.intern_named_node(literal.datatype()))
+        }
+    }
+
+    pub fn intern_named_or_blank_node<'a>(
+        &mut self,
+        node: impl Into<NamedOrBlankNodeRef<'a>>,
+    ) -> NamedOrBlankNode {
+        match node.into() {
+            NamedOrBlankNodeRef::NamedNode(node) => self.intern_named_node(node).into(),
+            NamedOrBlankNodeRef::BlankNode(node) => node.into_owned().into(),
+        }
+    }
+
+    pub fn intern_term<'a>(&mut self, term: impl Into<TermRef<'a>>) -> Term {
+        match term.into() {
+            TermRef::NamedNode(node) => self.intern_named_node(node).into(),
+            TermRef::BlankNode(node) => node.into_owned().into(),
+            TermRef::Literal(literal) => self.intern_literal(literal).into(),
+            #[cfg(feature = "rdf-12")]
+            TermRef::Triple(triple) => self.intern_triple(triple.as_ref()).into(),
+        }
+    }
+
+    pub fn intern_triple<'a>(&mut self, triple: impl Into<TripleRef<'a>>) -> Triple {
+        let triple = triple.into();
+        Triple {
+            subject: self.intern_named_or_blank_node(triple.subject),
+            predicate: self.intern_named_node(triple.predicate),
+            object: self.intern_term(triple.object),
+        }
+    }
+
+    pub fn intern_graph_name<'a>(&mut self, graph_name: impl Into<GraphNameRef<'a>>) -> GraphName {
+        match graph_name.into() {
+            GraphNameRef::NamedNode(node) => self.intern_named_node(node).into(),
+            GraphNameRef::BlankNode(node) => node.into_owned().into(),
+            GraphNameRef::DefaultGraph => GraphName::DefaultGraph,
+        }
+    }
+
+    pub fn intern_quad<'a>(&mut self, quad: impl Into<QuadRef<'a>>) -> Quad {
+        let quad = quad.into();
+        Quad {
+            subject: self.intern_named_or_blank_node(quad.subject),
+            predicate: self.intern_named_node(quad.predicate),
+            object: self.intern_term(quad.object),
+            graph_name: self.intern_graph_name(quad.graph_name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlankNode;
+    use crate::vocab::xsd;
+
+    #[test]
+    fn test_intern_shares_strings() {
+        let mut interner = TermInterner::new();
+        let node1 = interner.intern_named_node(NamedNodeRef::new_unchecked("http://example.com"));
+        let node2 = interner.intern_named_node(&NamedNode::new_unchecked("http://example.com"));
+        assert_eq!(node1, node2);
+        assert!(std::ptr::eq(node1.as_str(), node2.as_str()));
+        assert!(std::ptr::eq(node1.as_str(), node1.clone().as_str()));
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn test_intern_is_transparent() {
+        let mut interner = TermInterner::new();
+        let terms: [Term; 5] = [
+            NamedNode::new_unchecked("http://example.com").into(),
+            BlankNode::new_unchecked("b").into(),
+            Literal::new_simple_literal("foo").into(),
+            Literal::new_typed_literal("1", xsd::INTEGER).into(),
+            Literal::new_language_tagged_literal_unchecked("foo", "en").into(),
+        ];
+        for term in terms {
+            let interned = interner.intern_term(&term);
+            assert_eq!(interned, term);
+            assert_eq!(interned.to_string(), term.to_string());
+            assert_eq!(interned.as_ref(), term.as_ref());
+        }
+        assert_eq!(
+            interner.intern_literal(LiteralRef::new_typed_literal("foo", xsd::STRING)),
+            Literal::new_simple_literal("foo")
+        );
+        #[cfg(feature = "rdf-12")]
+        {
+            use crate::BaseDirection;
+
+            let literal = Literal::new_directional_language_tagged_literal_unchecked(
+                "foo",
+                "en",
+                BaseDirection::Rtl,
+            );
+            assert_eq!(interner.intern_literal(&literal), literal);
+        }
+    }
+}
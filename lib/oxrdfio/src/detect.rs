@@ -0,0 +1,519 @@
+//! Detection of the RDF format from the first bytes of a file.
+
+use crate::format::RdfFormat;
+use oxjsonld::JsonLdProfileSet;
+use std::io::{self, Read};
+#[cfg(feature = "async-tokio")]
+use std::pin::Pin;
+#[cfg(feature = "async-tokio")]
+use std::task::{Context, Poll};
+#[cfg(feature = "async-tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+
+/// Number of bytes read from the beginning of the file to detect its format
+const PEEK_SIZE: usize = 4096;
+
+pub fn detect_format(data: &[u8]) -> Option<RdfFormat> {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data); // UTF-8 BOM
+    if data.starts_with(b"$HDT") {
+        return Some(RdfFormat::Hdt);
+    }
+    let data = data.trim_ascii_start();
+    match data.first()? {
+        b'<' if data.starts_with(b"<?") || data.starts_with(b"<!") => detect_xml_format(data),
+        b'<' => detect_xml_format(data).or_else(|| detect_turtle_family_format(data)),
+        b'{' | b'[' => detect_json_format(data).or_else(|| detect_turtle_family_format(data)),
+        _ => detect_turtle_family_format(data),
+    }
+}
+
+/// Detects RDF/XML and TriX from the name of the root element.
+///
+/// Without an XML declaration, the root element must be `rdf:RDF` or `TriX` to avoid confusion with IRIs.
+fn detect_xml_format(mut data: &[u8]) -> Option<RdfFormat> {
+    let mut has_prolog = false;
+    loop {
+        data = data.trim_ascii_start();
+        let end = if data.starts_with(b"<?") {
+            find(data, b"?>").map(|i| i + 2)
+        } else if data.starts_with(b"<!--") {
+            find(data, b"-->").map(|i| i + 3)
+        } else if data.starts_with(b"<!") {
+            if doctype_root_name(data).is_some_and(|name| name.eq_ignore_ascii_case(b"html")) {
+                return None;
+            }
+            find(data, b">").map(|i| i + 1)
+        } else {
+            break;
+        };
+        let Some(end) = end else {
+            // The prolog is longer than the peeked data
+            return Some(RdfFormat::RdfXml);
+        };
+        data = &data[end..];
+        has_prolog = true;
+    }
+    let Some(name) = data.strip_prefix(b"<") else {
+        return has_prolog.then_some(RdfFormat::RdfXml);
+    };
+    let name_len = name
+        .iter()
+        .position(|c| c.is_ascii_whitespace() || matches!(c, b'>' | b'/'))
+        .unwrap_or(name.len());
+    let name = &name[..name_len];
+    if name.is_empty()
+        || !name
+            .iter()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, b':' | b'_' | b'-' | b'.'))
+    {
+        return None;
+    }
+    let local_name = name.rsplit(|c| *c == b':').next()?;
+    if local_name == b"TriX" {
+        Some(RdfFormat::TriX)
+    } else if local_name == b"RDF" || (has_prolog && !local_name.eq_ignore_ascii_case(b"html")) {
+        Some(RdfFormat::RdfXml)
+    } else {
+        None
+    }
+}
+
+/// The root element name declared by a `<!DOCTYPE` declaration
+fn doctype_root_name(data: &[u8]) -> Option<&[u8]> {
+    let keyword = data.get(2..9)?;
+    if !keyword.eq_ignore_ascii_case(b"DOCTYPE") {
+        return None;
+    }
+    let name = data[9..].trim_ascii_start();
+    let len = name
+        .iter()
+        .position(|c| c.is_ascii_whitespace() || matches!(c, b'>' | b'['))
+        .unwrap_or(name.len());
+    Some(&name[..len])
+}
+
+/// Detects JSON-LD and RDF/JSON.
+///
+/// RDF/JSON is recognized from its subject IRI, predicate IRI, array of objects structure.
+fn detect_json_format(data: &[u8]) -> Option<RdfFormat> {
+    let json_ld = RdfFormat::JsonLd {
+        profile: JsonLdProfileSet::empty(),
+    };
+    if let Some(data) = data.strip_prefix(b"[") {
+        // A JSON-LD array of nodes
+        return matches!(data.trim_ascii_start().first(), Some(b'{' | b']')).then_some(json_ld);
+    }
+    let data = data.strip_prefix(b"{")?.trim_ascii_start();
+    match data.first()? {
+        b'}' => return Some(json_ld),
+        b'"' => (),
+        _ => return None,
+    }
+    let is_rdf_json = (|| {
+        let (subject, data) = json_object_key(data)?;
+        if subject.starts_with(b"@") {
+            return None;
+        }
+        let data = data.strip_prefix(b"{")?.trim_ascii_start();
+        let (predicate, data) = json_object_key(data)?;
+        if predicate.starts_with(b"@") || !predicate.contains(&b':') {
+            return None;
+        }
+        is_rdf_json_object(data.strip_prefix(b"[")?.trim_ascii_start())
+    })()
+    .is_some();
+    Some(if is_rdf_json {
+        RdfFormat::RdfJson
+    } else {
+        json_ld
+    })
+}
+
+/// Checks that the data starts with a complete RDF/JSON object: an object of strings with `type` and `value` members
+fn is_rdf_json_object(data: &[u8]) -> Option<()> {
+    let mut data = data.strip_prefix(b"{")?.trim_ascii_start();
+    let (mut has_type, mut has_value) = (false, false);
+    loop {
+        let (key, value) = json_object_key(data)?;
+        match key {
+            b"type" => has_type = true,
+            b"value" => has_value = true,
+            _ => (),
+        }
+        data = json_string(value)?.1.trim_ascii_start();
+        if let Some(next) = data.strip_prefix(b",") {
+            data = next.trim_ascii_start();
+        } else {
+            return (has_type && has_value && data.starts_with(b"}")).then_some(());
+        }
+    }
+}
+
+/// Reads a JSON object key and the following `:`, returns the key and the following value
+fn json_object_key(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (key, data) = json_string(data)?;
+    let value = data
+        .trim_ascii_start()
+        .strip_prefix(b":")?
+        .trim_ascii_start();
+    Some((key, value))
+}
+
+/// Reads a JSON string, returns its escaped content and the data after it
+fn json_string(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let data = data.strip_prefix(b"\"")?;
+    let mut i = 0;
+    while *data.get(i)? != b'"' {
+        i += if data[i] == b'\\' { 2 } else { 1 };
+    }
+    Some((&data[..i], data.get(i + 1..)?))
+}
+
+/// Detects N-Triples, N-Quads, Turtle and TriG.
+///
+/// If all the statements have the N-Triples or N-Quads shape, one of these formats is returned.
+/// Turtle is returned otherwise, or TriG if a `{` is found.
+fn detect_turtle_family_format(data: &[u8]) -> Option<RdfFormat> {
+    let turtle_family = || {
+        Some(if data.contains(&b'{') {
+            RdfFormat::TriG
+        } else {
+            RdfFormat::Turtle
+        })
+    };
+    let mut lines = data.split(|c| *c == b'\n').peekable();
+    let mut has_quads = false;
+    let mut has_statements = false;
+    while let Some(line) = lines.next() {
+        let is_last = lines.peek().is_none();
+        let line = line.trim_ascii();
+        if line.is_empty() || line.starts_with(b"#") {
+            continue;
+        }
+        match nquads_line_term_count(line) {
+            Some(3) => has_statements = true,
+            Some(4) => {
+                has_statements = true;
+                has_quads = true;
+            }
+            // The last line might have been cut by the peek, we ignore it if possible
+            _ if is_last && has_statements => break,
+            _ => {
+                return if is_turtle_start(line) {
+                    turtle_family()
+                } else {
+                    None
+                };
+            }
+        }
+    }
+    has_statements.then_some(if has_quads {
+        RdfFormat::NQuads
+    } else {
+        RdfFormat::NTriples
+    })
+}
+
+fn is_turtle_start(line: &[u8]) -> bool {
+    let starts_with_keyword = |keyword: &[u8]| {
+        line.get(..keyword.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(keyword))
+            && line.get(keyword.len()).is_some_and(u8::is_ascii_whitespace)
+    };
+    starts_with_keyword(b"@prefix")
+        || starts_with_keyword(b"@base")
+        || starts_with_keyword(b"prefix")
+        || starts_with_keyword(b"base")
+        || starts_with_keyword(b"graph")
+        || line
+            .first()
+            .is_some_and(|c| matches!(c, b'<' | b'_' | b'[' | b'(' | b'{' | b':'))
+        || line.first().is_some_and(u8::is_ascii_alphabetic)
+            && line
+                .iter()
+                .take_while(|c| !c.is_ascii_whitespace())
+                .any(|c| *c == b':') // A prefixed name
+}
+
+/// Returns the number of terms of the line if it is a N-Triples or N-Quads statement
+fn nquads_line_term_count(mut line: &[u8]) -> Option<usize> {
+    let mut count = 0;
+    loop {
+        line = line.trim_ascii_start();
+        if let Some(rest) = line.strip_prefix(b".") {
+            let rest = rest.trim_ascii_start();
+            return (matches!(count, 3 | 4) && (rest.is_empty() || rest.starts_with(b"#")))
+                .then_some(count);
+        }
+        line = nquads_term(line)?;
+        count += 1;
+    }
+}
+
+/// Reads a N-Triples or N-Quads term and returns the rest of the line
+fn nquads_term(line: &[u8]) -> Option<&[u8]> {
+    if let Some(mut rest) = line.strip_prefix(b"<<(") {
+        // RDF 1.2 triple term
+        for _ in 0..3 {
+            rest = nquads_term(rest.trim_ascii_start())?;
+        }
+        return rest.trim_ascii_start().strip_prefix(b")>>");
+    }
+    if let Some(rest) = line.strip_prefix(b"<") {
+        let end = rest
+            .iter()
+            .position(|c| matches!(c, b'>' | b'<' | b'"' | b' ' | b'\t'))?;
+        return (rest[end] == b'>').then(|| &rest[end + 1..]);
+    }
+    if let Some(rest) = line.strip_prefix(b"_:") {
+        let end = rest
+            .iter()
+            .position(|c| c.is_ascii_whitespace() || matches!(c, b'.' | b')'))
+            .unwrap_or(rest.len());
+        return (end > 0).then(|| &rest[end..]);
+    }
+    let rest = line.strip_prefix(b"\"")?;
+    let mut i = 0;
+    while *rest.get(i)? != b'"' {
+        i += if rest[i] == b'\\' { 2 } else { 1 };
+    }
+    let rest = &rest[i + 1..];
+    if let Some(rest) = rest.strip_prefix(b"^^") {
+        return nquads_term(rest).filter(|_| rest.starts_with(b"<"));
+    }
+    if let Some(rest) = rest.strip_prefix(b"@") {
+        let end = rest
+            .iter()
+            .position(|c| !c.is_ascii_alphanumeric() && *c != b'-')
+            .unwrap_or(rest.len());
+        return (end > 0).then(|| &rest[end..]);
+    }
+    Some(rest)
+}
+
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Reads the beginning of the file to detect its format.
+///
+/// The read error if any is returned by the [`PeekedReader`] after the bytes read.
+pub fn peek(reader: &mut impl Read) -> (Vec<u8>, Option<io::Error>) {
+    let mut buffer = vec![0; PEEK_SIZE];
+    let mut len = 0;
+    while len < PEEK_SIZE {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => {
+                buffer.truncate(len);
+                return (buffer, Some(e));
+            }
+        }
+    }
+    buffer.truncate(len);
+    (buffer, None)
+}
+
+#[cfg(feature = "async-tokio")]
+pub async fn peek_tokio(reader: &mut (impl AsyncRead + Unpin)) -> (Vec<u8>, Option<io::Error>) {
+    let mut buffer = vec![0; PEEK_SIZE];
+    let mut len = 0;
+    while len < PEEK_SIZE {
+        match reader.read(&mut buffer[len..]).await {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => {
+                buffer.truncate(len);
+                return (buffer, Some(e));
+            }
+        }
+    }
+    buffer.truncate(len);
+    (buffer, None)
+}
+
+/// A reader returning first the bytes read to detect the format, then the possible read error, then the rest of the file.
+pub struct PeekedReader<R> {
+    peeked: Vec<u8>,
+    position: usize,
+    error: Option<io::Error>,
+    inner: R,
+}
+
+impl<R> PeekedReader<R> {
+    pub fn new(peeked: Vec<u8>, error: Option<io::Error>, inner: R) -> Self {
+        Self {
+            peeked,
+            position: 0,
+            error,
+            inner,
+        }
+    }
+
+    /// Copies the peeked bytes not returned yet into `buffer`
+    fn read_peeked(&mut self, buffer: &mut [u8]) -> usize {
+        let peeked = &self.peeked[self.position..];
+        let len = peeked.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&peeked[..len]);
+        self.position += len;
+        if self.position == self.peeked.len() {
+            self.peeked = Vec::new(); // We free the memory
+            self.position = 0;
+        }
+        len
+    }
+}
+
+impl<R: Read> Read for PeekedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position < self.peeked.len() {
+            return Ok(self.read_peeked(buf));
+        }
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<R: AsyncRead + Unpin> AsyncRead for PeekedReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.position < this.peeked.len() {
+            let len = this.read_peeked(buf.initialize_unfilled());
+            buf.advance(len);
+            return Poll::Ready(Ok(()));
+        }
+        if let Some(error) = this.error.take() {
+            return Poll::Ready(Err(error));
+        }
+        Pin::new(&mut this.inner).poll_read(cx, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PeekedReader, detect_format, peek};
+    use crate::format::RdfFormat;
+    use oxjsonld::JsonLdProfileSet;
+    use std::io::{self, Read};
+
+    #[test]
+    fn test_detect_format() {
+        let json_ld = RdfFormat::JsonLd {
+            profile: JsonLdProfileSet::empty(),
+        };
+        for (data, format) in [
+            ("", None),
+            ("  \n", None),
+            ("foo bar!", None),
+            ("$HDT", Some(RdfFormat::Hdt)),
+            (
+                "<?xml version=\"1.0\"?>\n<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"/>",
+                Some(RdfFormat::RdfXml),
+            ),
+            (
+                "<!-- comment --><rdf:Description/>",
+                Some(RdfFormat::RdfXml),
+            ),
+            ("<rdf:RDF>", Some(RdfFormat::RdfXml)),
+            (
+                "\u{feff}<TriX xmlns=\"http://www.w3.org/2004/03/trix/trix-1/\">",
+                Some(RdfFormat::TriX),
+            ),
+            ("<!DOCTYPE html><html>", None),
+            ("<!DOCTYPE html>", None),
+            ("<!doctype HTML>\n", None),
+            ("<?xml version=\"1.0\"?>", Some(RdfFormat::RdfXml)),
+            ("{}", Some(json_ld)),
+            ("[{\"@id\": \"http://example.com\"}]", Some(json_ld)),
+            ("{\"@context\": {}}", Some(json_ld)),
+            (
+                "{\"http://example.com/s\": {\"@id\": \"http://example.com/o\"}}",
+                Some(json_ld),
+            ),
+            (
+                "{ \"http://example.com/s\" : { \"http://example.com/p\" : [ { \"type\": \"uri\", \"value\": \"http://example.com/o\" } ] } }",
+                Some(RdfFormat::RdfJson),
+            ),
+            (
+                "{\"http://example.com/s\": {\"http://example.com/p\": [{\"value\": \"o\", \"lang\": \"en\", \"type\": \"literal\"}]}}",
+                Some(RdfFormat::RdfJson),
+            ),
+            (
+                "{\"http://schema.org/author\": {\"http://schema.org/name\": [\"x\"]}}",
+                Some(json_ld),
+            ),
+            (
+                "{\"http://example.com/s\": {\"http://example.com/p\": [{\"@value\": \"o\", \"@type\": \"http://example.com/t\"}]}}",
+                Some(json_ld),
+            ),
+            (
+                "# comment\n<http://example.com/s> <http://example.com/p> \"o\\\"\"@en .\n_:s <http://example.com/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n",
+                Some(RdfFormat::NTriples),
+            ),
+            (
+                "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .",
+                Some(RdfFormat::NQuads),
+            ),
+            (
+                "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n<http://example.com/s> <http://exa",
+                Some(RdfFormat::NTriples),
+            ),
+            (
+                "<http://example.com/s> <http://example.com/p> <<( _:s <http://example.com/p> \"o\" )>> .",
+                Some(RdfFormat::NTriples),
+            ),
+            (
+                "@prefix ex: <http://example.com/> .\nex:s ex:p ex:o .",
+                Some(RdfFormat::Turtle),
+            ),
+            (
+                "PREFIX ex: <http://example.com/>\nex:g { ex:s ex:p ex:o }",
+                Some(RdfFormat::TriG),
+            ),
+            (
+                "<http://example.com/s> a <http://example.com/C> .",
+                Some(RdfFormat::Turtle),
+            ),
+            ("[ <http://example.com/p> 1 ] .", Some(RdfFormat::Turtle)),
+        ] {
+            assert_eq!(
+                detect_format(data.as_bytes()),
+                format,
+                "Wrong format for {data}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_peeked_reader() {
+        let mut reader = b"foo bar".as_slice();
+        let (peeked, error) = peek(&mut reader);
+        assert_eq!(peeked, b"foo bar");
+        assert!(error.is_none());
+        let mut result = Vec::new();
+        PeekedReader::new(b"foo ".to_vec(), None, b"bar".as_slice())
+            .read_to_end(&mut result)
+            .unwrap();
+        assert_eq!(result, b"foo bar");
+        let mut reader = PeekedReader::new(
+            b"foo".to_vec(),
+            Some(io::Error::other("error")),
+            b"bar".as_slice(),
+        );
+        let mut buffer = [0; 2];
+        assert_eq!(reader.read(&mut buffer).unwrap(), 2);
+        assert_eq!(reader.read(&mut buffer).unwrap(), 1);
+        reader.read(&mut buffer).unwrap_err();
+        assert_eq!(reader.read(&mut buffer).unwrap(), 2);
+    }
+}
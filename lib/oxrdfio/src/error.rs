@@ -42,6 +42,16 @@ impl From<oxrdfxml::RdfXmlParseError> for RdfParseError {
     }
 }
 
+impl From<oxhdt::HdtParseError> for RdfParseError {
+    #[inline]
+    fn from(error: oxhdt::HdtParseError) -> Self {
+        match error {
+            oxhdt::HdtParseError::Syntax(e) => Self::Syntax(e.into()),
+            oxhdt::HdtParseError::Io(e) => Self::Io(e),
+        }
+    }
+}
+
 impl From<RdfParseError> for io::Error {
     #[inline]
     fn from(error: RdfParseError) -> Self {
@@ -66,6 +76,8 @@ enum SyntaxErrorKind {
     Turtle(#[from] oxttl::TurtleSyntaxError),
     #[error(transparent)]
     RdfXml(#[from] oxrdfxml::RdfXmlSyntaxError),
+    #[error(transparent)]
+    Hdt(#[from] oxhdt::HdtSyntaxError),
     #[error("{0}")]
     Msg(&'static str),
 }
@@ -103,7 +115,7 @@ impl RdfSyntaxError {
                     },
                 )
             }
-            SyntaxErrorKind::RdfXml(_) | SyntaxErrorKind::Msg(_) => None,
+            SyntaxErrorKind::RdfXml(_) | SyntaxErrorKind::Hdt(_) | SyntaxErrorKind::Msg(_) => None,
         }
     }
 
@@ -133,6 +145,13 @@ impl From<oxrdfxml::RdfXmlSyntaxError> for RdfSyntaxError {
     }
 }
 
+impl From<oxhdt::HdtSyntaxError> for RdfSyntaxError {
+    #[inline]
+    fn from(error: oxhdt::HdtSyntaxError) -> Self {
+        Self(SyntaxErrorKind::Hdt(error))
+    }
+}
+
 impl From<RdfSyntaxError> for io::Error {
     #[inline]
     fn from(error: RdfSyntaxError) -> Self {
@@ -140,6 +159,7 @@ impl From<RdfSyntaxError> for io::Error {
             SyntaxErrorKind::JsonLd(error) => error.into(),
             SyntaxErrorKind::Turtle(error) => error.into(),
             SyntaxErrorKind::RdfXml(error) => error.into(),
+            SyntaxErrorKind::Hdt(error) => error.into(),
             SyntaxErrorKind::Msg(msg) => Self::new(io::ErrorKind::InvalidData, msg),
         }
     }
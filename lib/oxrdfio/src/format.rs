@@ -1,3 +1,4 @@
+use crate::detect::detect_format;
 use oxjsonld::{JsonLdProfile, JsonLdProfileSet};
 use std::fmt;
 
@@ -7,16 +8,22 @@ use std::fmt;
 #[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
 #[non_exhaustive]
 pub enum RdfFormat {
+    /// [HDT](https://www.rdfhdt.org/hdt-binary-format/)
+    Hdt,
     /// [N3](https://w3c.github.io/N3/spec/)
     N3,
     /// [N-Quads](https://www.w3.org/TR/n-quads/)
     NQuads,
     /// [N-Triples](https://www.w3.org/TR/n-triples/)
     NTriples,
+    /// [RDF/JSON](https://www.w3.org/TR/rdf-json/)
+    RdfJson,
     /// [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/)
     RdfXml,
     /// [TriG](https://www.w3.org/TR/trig/)
     TriG,
+    /// [TriX](https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html)
+    TriX,
     /// [Turtle](https://www.w3.org/TR/turtle/)
     Turtle,
     /// [JSON-LD](https://www.w3.org/TR/json-ld/)
@@ -38,11 +45,14 @@ impl RdfFormat {
     pub const fn iri(self) -> &'static str {
         match self {
             Self::JsonLd { .. } => "https://www.w3.org/ns/formats/data/JSON-LD",
+            Self::Hdt => "http://purl.org/HDT/hdt#HDTv1",
             Self::N3 => "http://www.w3.org/ns/formats/N3",
             Self::NQuads => "http://www.w3.org/ns/formats/N-Quads",
             Self::NTriples => "http://www.w3.org/ns/formats/N-Triples",
+            Self::RdfJson => "http://www.w3.org/ns/formats/RDF_JSON",
             Self::RdfXml => "http://www.w3.org/ns/formats/RDF_XML",
             Self::TriG => "http://www.w3.org/ns/formats/TriG",
+            Self::TriX => "http://www.w3.org/2004/03/trix/trix-1/",
             Self::Turtle => "http://www.w3.org/ns/formats/Turtle",
         }
     }
@@ -65,11 +75,14 @@ impl RdfFormat {
                     "application/ld+json"
                 }
             }
+            Self::Hdt => "application/vnd.hdt",
             Self::N3 => "text/n3",
             Self::NQuads => "application/n-quads",
             Self::NTriples => "application/n-triples",
+            Self::RdfJson => "application/rdf+json",
             Self::RdfXml => "application/rdf+xml",
             Self::TriG => "application/trig",
+            Self::TriX => "application/trix",
             Self::Turtle => "text/turtle",
         }
     }
@@ -85,11 +98,14 @@ impl RdfFormat {
     pub const fn file_extension(self) -> &'static str {
         match self {
             Self::JsonLd { .. } => "jsonld",
+            Self::Hdt => "hdt",
             Self::N3 => "n3",
             Self::NQuads => "nq",
             Self::NTriples => "nt",
+            Self::RdfJson => "rj",
             Self::RdfXml => "rdf",
             Self::TriG => "trig",
+            Self::TriX => "trix",
             Self::Turtle => "ttl",
         }
     }
@@ -112,11 +128,14 @@ impl RdfFormat {
                     "JSON-LD"
                 }
             }
+            Self::Hdt => "HDT",
             Self::N3 => "N3",
             Self::NQuads => "N-Quads",
             Self::NTriples => "N-Triples",
+            Self::RdfJson => "RDF/JSON",
             Self::RdfXml => "RDF/XML",
             Self::TriG => "TriG",
+            Self::TriX => "TriX",
             Self::Turtle => "Turtle",
         }
     }
@@ -131,7 +150,10 @@ impl RdfFormat {
     /// ```
     #[inline]
     pub const fn supports_datasets(self) -> bool {
-        matches!(self, Self::JsonLd { .. } | Self::NQuads | Self::TriG)
+        matches!(
+            self,
+            Self::JsonLd { .. } | Self::NQuads | Self::TriG | Self::TriX
+        )
     }
 
     #[deprecated(note = "All format will soon support RDF 1.2", since = "0.2.0")]
@@ -169,7 +191,7 @@ impl RdfFormat {
     /// ```
     #[inline]
     pub fn from_media_type(media_type: &str) -> Option<Self> {
-        const MEDIA_SUBTYPES: [(&str, RdfFormat); 14] = [
+        const MEDIA_SUBTYPES: [(&str, RdfFormat); 17] = [
             (
                 "activity+json",
                 RdfFormat::JsonLd {
@@ -200,9 +222,12 @@ impl RdfFormat {
             ("nquads", RdfFormat::NQuads),
             ("ntriples", RdfFormat::NTriples),
             ("plain", RdfFormat::NTriples),
+            ("rdf+json", RdfFormat::RdfJson),
             ("rdf+xml", RdfFormat::RdfXml),
             ("trig", RdfFormat::TriG),
+            ("trix", RdfFormat::TriX),
             ("turtle", RdfFormat::Turtle),
+            ("vnd.hdt", RdfFormat::Hdt),
             ("xml", RdfFormat::RdfXml),
         ];
         const UTF8_CHARSETS: [&str; 3] = ["ascii", "utf8", "utf-8"];
@@ -274,7 +299,7 @@ impl RdfFormat {
     /// ```
     #[inline]
     pub fn from_extension(extension: &str) -> Option<Self> {
-        const EXTENSIONS: [(&str, RdfFormat); 10] = [
+        const EXTENSIONS: [(&str, RdfFormat); 13] = [
             (
                 "json",
                 RdfFormat::JsonLd {
@@ -287,11 +312,14 @@ impl RdfFormat {
                     profile: JsonLdProfileSet::empty(),
                 },
             ),
+            ("hdt", RdfFormat::Hdt),
             ("n3", RdfFormat::N3),
             ("nq", RdfFormat::NQuads),
             ("nt", RdfFormat::NTriples),
             ("rdf", RdfFormat::RdfXml),
+            ("rj", RdfFormat::RdfJson),
             ("trig", RdfFormat::TriG),
+            ("trix", RdfFormat::TriX),
             ("ttl", RdfFormat::Turtle),
             ("txt", RdfFormat::NTriples),
             ("xml", RdfFormat::RdfXml),
@@ -303,6 +331,36 @@ impl RdfFormat {
         }
         None
     }
+
+    /// Guesses the format from the first bytes of a file.
+    ///
+    /// It is useful when the file has no extension and no media type is available.
+    /// A few kilobytes from the beginning of the file are enough to detect the format.
+    /// The detection is based on heuristics like the presence of an XML declaration, a JSON object, `@prefix` or the shape of the first lines.
+    /// The data must be a valid serialization for the returned format to be correct.
+    ///
+    /// [N3](RdfFormat::N3) is never returned, [Turtle](RdfFormat::Turtle) or [TriG](RdfFormat::TriG) are returned instead.
+    ///
+    /// Example:
+    /// ```
+    /// use oxrdfio::RdfFormat;
+    ///
+    /// assert_eq!(
+    ///     RdfFormat::from_content_peek(b"@prefix schema: <http://schema.org/> ."),
+    ///     Some(RdfFormat::Turtle)
+    /// );
+    /// assert_eq!(
+    ///     RdfFormat::from_content_peek(
+    ///         b"<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> ."
+    ///     ),
+    ///     Some(RdfFormat::NQuads)
+    /// );
+    /// assert_eq!(RdfFormat::from_content_peek(b"foo"), None);
+    /// ```
+    #[inline]
+    pub fn from_content_peek(data: &[u8]) -> Option<Self> {
+        detect_format(data)
+    }
 }
 
 impl fmt::Display for RdfFormat {
@@ -327,6 +385,18 @@ mod tests {
             RdfFormat::from_media_type("application/x-turtle"),
             Some(RdfFormat::Turtle)
         );
+        assert_eq!(
+            RdfFormat::from_media_type("application/vnd.hdt"),
+            Some(RdfFormat::Hdt)
+        );
+        assert_eq!(
+            RdfFormat::from_media_type("application/trix"),
+            Some(RdfFormat::TriX)
+        );
+        assert_eq!(
+            RdfFormat::from_media_type("application/rdf+json"),
+            Some(RdfFormat::RdfJson)
+        );
         assert_eq!(
             RdfFormat::from_media_type("application/ld+json"),
             Some(RdfFormat::JsonLd {
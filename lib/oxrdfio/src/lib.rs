@@ -4,11 +4,13 @@
 #![doc(html_favicon_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
 #![doc(html_logo_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
 
+mod detect;
 mod document;
 mod error;
 mod format;
 mod parser;
 mod serializer;
+mod sorted;
 
 pub use document::LoadedDocument;
 pub use error::{RdfParseError, RdfSyntaxError, TextPosition};
@@ -19,4 +21,5 @@ pub use parser::TokioAsyncReaderQuadParser;
 pub use parser::{RdfParser, ReaderQuadParser, SliceQuadParser};
 #[cfg(feature = "async-tokio")]
 pub use serializer::TokioAsyncWriterQuadSerializer;
-pub use serializer::{RdfSerializer, WriterQuadSerializer};
+pub use serializer::{RdfSerializer, Shard, ShardedQuadSerializer, WriterQuadSerializer};
+pub use sorted::SortedQuadSerializer;
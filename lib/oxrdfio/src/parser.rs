@@ -1,18 +1,31 @@
 //! Utilities to read RDF graphs and datasets.
 
+#[cfg(feature = "async-tokio")]
+use crate::detect::peek_tokio;
+use crate::detect::{PeekedReader, peek};
 pub use crate::error::RdfParseError;
 use crate::format::RdfFormat;
 use crate::{LoadedDocument, RdfSyntaxError};
 #[cfg(feature = "async-tokio")]
+use oxhdt::TokioAsyncReaderHdtParser;
+use oxhdt::{HdtParser, ReaderHdtParser, SliceHdtParser};
+#[cfg(feature = "async-tokio")]
 use oxjsonld::TokioAsyncReaderJsonLdParser;
+#[cfg(feature = "async-tokio")]
+use oxjsonld::TokioAsyncReaderRdfJsonParser;
 use oxjsonld::{
-    JsonLdParser, JsonLdPrefixesIter, JsonLdProfileSet, JsonLdRemoteDocument, ReaderJsonLdParser,
-    SliceJsonLdParser,
+    JsonLdParser, JsonLdPrefixesIter, JsonLdProfileSet, JsonLdRemoteDocument, RdfJsonParser,
+    ReaderJsonLdParser, ReaderRdfJsonParser, SliceJsonLdParser, SliceRdfJsonParser,
+};
+use oxrdf::{
+    BlankNode, GraphName, IriParseError, NamedOrBlankNode, Quad, Term, TermInterner, Triple,
+};
+use oxrdfxml::{
+    RdfXmlParser, RdfXmlPrefixesIter, ReaderRdfXmlParser, ReaderTriXParser, SliceRdfXmlParser,
+    SliceTriXParser, TriXParser,
 };
-use oxrdf::{BlankNode, GraphName, IriParseError, NamedOrBlankNode, Quad, Term, Triple};
 #[cfg(feature = "async-tokio")]
-use oxrdfxml::TokioAsyncReaderRdfXmlParser;
-use oxrdfxml::{RdfXmlParser, RdfXmlPrefixesIter, ReaderRdfXmlParser, SliceRdfXmlParser};
+use oxrdfxml::{TokioAsyncReaderRdfXmlParser, TokioAsyncReaderTriXParser};
 #[cfg(feature = "async-tokio")]
 use oxttl::n3::TokioAsyncReaderN3Parser;
 use oxttl::n3::{N3Parser, N3PrefixesIter, N3Quad, N3Term, ReaderN3Parser, SliceN3Parser};
@@ -31,6 +44,7 @@ use oxttl::turtle::{ReaderTurtleParser, SliceTurtleParser, TurtleParser, TurtleP
 use std::collections::HashMap;
 use std::error::Error;
 use std::io::Read;
+use std::mem;
 use std::panic::{RefUnwindSafe, UnwindSafe};
 #[cfg(feature = "async-tokio")]
 use tokio::io::AsyncRead;
@@ -38,18 +52,24 @@ use tokio::io::AsyncRead;
 /// Parsers for RDF serialization formats.
 ///
 /// It currently supports the following formats:
+/// * [HDT](https://www.rdfhdt.org/hdt-binary-format/) ([`RdfFormat::Hdt`])
 /// * [JSON-LD 1.0](https://www.w3.org/TR/json-ld/) ([`RdfFormat::JsonLd`])
 /// * [N3](https://w3c.github.io/N3/spec/) ([`RdfFormat::N3`])
 /// * [N-Quads](https://www.w3.org/TR/n-quads/) ([`RdfFormat::NQuads`])
 /// * [N-Triples](https://www.w3.org/TR/n-triples/) ([`RdfFormat::NTriples`])
+/// * [RDF/JSON](https://www.w3.org/TR/rdf-json/) ([`RdfFormat::RdfJson`])
 /// * [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/) ([`RdfFormat::RdfXml`])
 /// * [TriG](https://www.w3.org/TR/trig/) ([`RdfFormat::TriG`])
+/// * [TriX](https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html) ([`RdfFormat::TriX`])
 /// * [Turtle](https://www.w3.org/TR/turtle/) ([`RdfFormat::Turtle`])
 ///
 /// Note the useful options:
 /// - [`with_base_iri`](Self::with_base_iri) to resolve the relative IRIs.
 /// - [`rename_blank_nodes`](Self::rename_blank_nodes) to rename the blank nodes to auto-generated numbers to avoid conflicts when merging RDF graphs together.
 /// - [`without_named_graphs`](Self::without_named_graphs) to parse a single graph.
+/// - [`with_term_interner`](Self::with_term_interner) to share the strings of the returned terms and save memory.
+/// - [`lenient_recovery`](Self::lenient_recovery) to parse what is valid in a file and collect all its syntax errors.
+/// - [`detect_format`](Self::detect_format) to guess the format from the file content if it is not known.
 /// - [`unchecked`](Self::unchecked) to skip some validations if the file is already known to be valid.
 ///
 /// ```
@@ -67,21 +87,31 @@ use tokio::io::AsyncRead;
 /// ```
 #[must_use]
 #[derive(Clone)]
+#[expect(clippy::struct_excessive_bools)]
 pub struct RdfParser {
     inner: RdfParserKind,
     default_graph: GraphName,
     without_named_graphs: bool,
     rename_blank_nodes: bool,
+    term_interner: Option<TermInterner>,
+    lenient_recovery: bool,
+    detect_format: bool,
+    base_iri: Option<String>,
+    lenient: bool,
+    parallelism: Option<usize>,
 }
 
 #[derive(Clone)]
 enum RdfParserKind {
+    Hdt(HdtParser),
     JsonLd(JsonLdParser, JsonLdProfileSet),
     N3(N3Parser),
     NQuads(NQuadsParser),
     NTriples(NTriplesParser),
+    RdfJson(RdfJsonParser),
     RdfXml(RdfXmlParser),
     TriG(TriGParser),
+    TriX(TriXParser),
     Turtle(TurtleParser),
 }
 
@@ -91,19 +121,28 @@ impl RdfParser {
     pub fn from_format(format: RdfFormat) -> Self {
         Self {
             inner: match format {
+                RdfFormat::Hdt => RdfParserKind::Hdt(HdtParser::new()),
                 RdfFormat::JsonLd { profile } => {
                     RdfParserKind::JsonLd(JsonLdParser::new().with_profile(profile), profile)
                 }
                 RdfFormat::N3 => RdfParserKind::N3(N3Parser::new()),
                 RdfFormat::NQuads => RdfParserKind::NQuads(NQuadsParser::new()),
                 RdfFormat::NTriples => RdfParserKind::NTriples(NTriplesParser::new()),
+                RdfFormat::RdfJson => RdfParserKind::RdfJson(RdfJsonParser::new()),
                 RdfFormat::RdfXml => RdfParserKind::RdfXml(RdfXmlParser::new()),
                 RdfFormat::TriG => RdfParserKind::TriG(TriGParser::new()),
+                RdfFormat::TriX => RdfParserKind::TriX(TriXParser::new()),
                 RdfFormat::Turtle => RdfParserKind::Turtle(TurtleParser::new()),
             },
             default_graph: GraphName::DefaultGraph,
             without_named_graphs: false,
             rename_blank_nodes: false,
+            term_interner: None,
+            lenient_recovery: false,
+            detect_format: false,
+            base_iri: None,
+            lenient: false,
+            parallelism: None,
         }
     }
 
@@ -119,12 +158,15 @@ impl RdfParser {
     /// ```
     pub fn format(&self) -> RdfFormat {
         match &self.inner {
+            RdfParserKind::Hdt(_) => RdfFormat::Hdt,
             RdfParserKind::JsonLd(_, profile) => RdfFormat::JsonLd { profile: *profile },
             RdfParserKind::N3(_) => RdfFormat::N3,
             RdfParserKind::NQuads(_) => RdfFormat::NQuads,
             RdfParserKind::NTriples(_) => RdfFormat::NTriples,
+            RdfParserKind::RdfJson(_) => RdfFormat::RdfJson,
             RdfParserKind::RdfXml(_) => RdfFormat::RdfXml,
             RdfParserKind::TriG(_) => RdfFormat::TriG,
+            RdfParserKind::TriX(_) => RdfFormat::TriX,
             RdfParserKind::Turtle(_) => RdfFormat::Turtle,
         }
     }
@@ -147,15 +189,22 @@ impl RdfParser {
     /// ```
     #[inline]
     pub fn with_base_iri(mut self, base_iri: impl Into<String>) -> Result<Self, IriParseError> {
+        let base_iri = base_iri.into();
         self.inner = match self.inner {
-            RdfParserKind::JsonLd(p, f) => RdfParserKind::JsonLd(p.with_base_iri(base_iri)?, f),
-            RdfParserKind::N3(p) => RdfParserKind::N3(p.with_base_iri(base_iri)?),
+            RdfParserKind::Hdt(p) => RdfParserKind::Hdt(p),
+            RdfParserKind::JsonLd(p, f) => {
+                RdfParserKind::JsonLd(p.with_base_iri(base_iri.as_str())?, f)
+            }
+            RdfParserKind::N3(p) => RdfParserKind::N3(p.with_base_iri(base_iri.as_str())?),
             RdfParserKind::NTriples(p) => RdfParserKind::NTriples(p),
             RdfParserKind::NQuads(p) => RdfParserKind::NQuads(p),
-            RdfParserKind::RdfXml(p) => RdfParserKind::RdfXml(p.with_base_iri(base_iri)?),
-            RdfParserKind::TriG(p) => RdfParserKind::TriG(p.with_base_iri(base_iri)?),
-            RdfParserKind::Turtle(p) => RdfParserKind::Turtle(p.with_base_iri(base_iri)?),
+            RdfParserKind::RdfJson(p) => RdfParserKind::RdfJson(p),
+            RdfParserKind::RdfXml(p) => RdfParserKind::RdfXml(p.with_base_iri(base_iri.as_str())?),
+            RdfParserKind::TriG(p) => RdfParserKind::TriG(p.with_base_iri(base_iri.as_str())?),
+            RdfParserKind::TriX(p) => RdfParserKind::TriX(p),
+            RdfParserKind::Turtle(p) => RdfParserKind::Turtle(p.with_base_iri(base_iri.as_str())?),
         };
+        self.base_iri = Some(base_iri);
         Ok(self)
     }
 
@@ -226,6 +275,30 @@ impl RdfParser {
         self
     }
 
+    /// Makes the returned quads share the strings of their IRIs and literals using the given [`TermInterner`].
+    ///
+    /// This reduces a lot the memory used by the quads if they are kept in memory.
+    /// Quads from parsers given clones of the same interner also share their strings.
+    ///
+    /// ```
+    /// use oxrdfio::{RdfFormat, RdfParser};
+    /// use oxrdf::TermInterner;
+    ///
+    /// let file = "<http://example.com/s> <http://example.com/p> <http://example.com/s> .";
+    ///
+    /// let quads = RdfParser::from_format(RdfFormat::NTriples)
+    ///     .with_term_interner(TermInterner::new())
+    ///     .for_reader(file.as_bytes())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(quads[0].subject.to_string(), "<http://example.com/s>");
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_term_interner(mut self, term_interner: TermInterner) -> Self {
+        self.term_interner = Some(term_interner);
+        self
+    }
+
     /// Assumes the file is valid to make parsing faster.
     ///
     /// It will skip some validations.
@@ -233,18 +306,118 @@ impl RdfParser {
     /// Note that if the file is actually not valid, the parser might emit broken RDF.
     #[inline]
     pub fn lenient(mut self) -> Self {
+        self.lenient = true;
         self.inner = match self.inner {
+            RdfParserKind::Hdt(p) => RdfParserKind::Hdt(p.lenient()),
             RdfParserKind::JsonLd(p, f) => RdfParserKind::JsonLd(p.lenient(), f),
             RdfParserKind::N3(p) => RdfParserKind::N3(p.lenient()),
             RdfParserKind::NTriples(p) => RdfParserKind::NTriples(p.lenient()),
             RdfParserKind::NQuads(p) => RdfParserKind::NQuads(p.lenient()),
+            RdfParserKind::RdfJson(p) => RdfParserKind::RdfJson(p.lenient()),
             RdfParserKind::RdfXml(p) => RdfParserKind::RdfXml(p.lenient()),
             RdfParserKind::TriG(p) => RdfParserKind::TriG(p.lenient()),
+            RdfParserKind::TriX(p) => RdfParserKind::TriX(p.lenient()),
             RdfParserKind::Turtle(p) => RdfParserKind::Turtle(p.lenient()),
         };
         self
     }
 
+    /// Does not stop on syntax errors but skips the invalid statement and continues to the next one.
+    ///
+    /// The syntax errors are not returned by the parser anymore but collected with their location
+    /// and available from the `syntax_errors` method of the parsers.
+    /// I/O errors are still returned.
+    ///
+    /// Recovery is supported by the [N3](RdfFormat::N3), [N-Quads](RdfFormat::NQuads), [N-Triples](RdfFormat::NTriples),
+    /// [TriG](RdfFormat::TriG) and [Turtle](RdfFormat::Turtle) parsers.
+    /// With the other formats, the parsing ends at the first syntax error that is then collected.
+    ///
+    /// ```
+    /// use oxrdfio::{RdfFormat, RdfParser};
+    ///
+    /// let file = "<http://example.com/s> <http://example.com/p> <http://example.com/o> .
+    /// <http://example.com/s> <http://example.com/p> .
+    /// <http://example.com/s> <http://example.com/p> <http://example.com/o2> .";
+    ///
+    /// let mut parser = RdfParser::from_format(RdfFormat::Turtle)
+    ///     .lenient_recovery()
+    ///     .for_reader(file.as_bytes());
+    /// let quads = parser.by_ref().collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(quads.len(), 2);
+    /// let errors = parser.syntax_errors();
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].location().unwrap().start.line, 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn lenient_recovery(mut self) -> Self {
+        self.lenient_recovery = true;
+        self
+    }
+
+    /// Parses the file read by [`for_reader`](Self::for_reader) using `parallelism` worker threads.
+    ///
+    /// The file is split into chunks of lines that are parsed in parallel but the quads are still returned in the file order.
+    /// It allows to speed up the ingestion of big dumps if the parser is the bottleneck.
+    ///
+    /// Only the [N-Quads](RdfFormat::NQuads) and [N-Triples](RdfFormat::NTriples) parsers support parallel parsing,
+    /// this option is ignored by the other formats and by the other parsing methods.
+    /// A value of `0` or `1` disables parallel parsing.
+    ///
+    /// ```
+    /// use oxrdfio::{RdfFormat, RdfParser};
+    ///
+    /// let file = "<http://example.com/s> <http://example.com/p> <http://example.com/o> .
+    /// <http://example.com/s> <http://example.com/p> <http://example.com/o2> .";
+    ///
+    /// let quads = RdfParser::from_format(RdfFormat::NTriples)
+    ///     .with_parallelism(4)
+    ///     .for_reader(file.as_bytes())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(quads.len(), 2);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = Some(parallelism);
+        self.inner = match self.inner {
+            RdfParserKind::NTriples(p) => RdfParserKind::NTriples(p.with_parallelism(parallelism)),
+            RdfParserKind::NQuads(p) => RdfParserKind::NQuads(p.with_parallelism(parallelism)),
+            inner => inner,
+        };
+        self
+    }
+
+    /// Guesses the format from the first bytes of the file using [`RdfFormat::from_content_peek`].
+    ///
+    /// It allows to parse files without a known extension or HTTP responses without a `Content-Type` header.
+    /// The format given to [`from_format`](Self::from_format) is used if the format cannot be guessed.
+    ///
+    /// Because only the beginning of the file is looked at, N-Triples is parsed as N-Quads and Turtle as TriG,
+    /// and a [N3](RdfFormat::N3) parser is kept if the file looks like Turtle.
+    /// [`for_reader`](Self::for_reader) reads the first bytes when called
+    /// and [`for_tokio_async_reader`](Self::for_tokio_async_reader) when the first quad is requested.
+    ///
+    /// ```
+    /// use oxrdfio::{RdfFormat, RdfParser};
+    ///
+    /// let file = "<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .";
+    ///
+    /// let quads = RdfParser::from_format(RdfFormat::Turtle)
+    ///     .detect_format()
+    ///     .for_reader(file.as_bytes())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// assert_eq!(quads.len(), 1);
+    /// assert_eq!(quads[0].graph_name.to_string(), "<http://example.com/g>");
+    /// # std::io::Result::Ok(())
+    /// ```
+    #[inline]
+    pub fn detect_format(mut self) -> Self {
+        self.detect_format = true;
+        self
+    }
+
     #[deprecated(note = "Use `lenient()` instead", since = "0.2.0")]
     #[inline]
     pub fn unchecked(self) -> Self {
@@ -268,22 +441,29 @@ impl RdfParser {
     /// assert_eq!(quads[0].subject.to_string(), "<http://example.com/s>");
     /// # std::io::Result::Ok(())
     /// ```
-    pub fn for_reader<R: Read>(self, reader: R) -> ReaderQuadParser<R> {
+    pub fn for_reader<R: Read>(mut self, mut reader: R) -> ReaderQuadParser<R> {
+        let reader = if self.detect_format {
+            let (peeked, error) = peek(&mut reader);
+            self = self.with_detected_format(&peeked);
+            PeekedReader::new(peeked, error, reader)
+        } else {
+            PeekedReader::new(Vec::new(), None, reader)
+        };
+        let mapper = self.quad_mapper();
         ReaderQuadParser {
             inner: match self.inner {
+                RdfParserKind::Hdt(p) => ReaderQuadParserKind::Hdt(p.for_reader(reader)),
                 RdfParserKind::JsonLd(p, _) => ReaderQuadParserKind::JsonLd(p.for_reader(reader)),
                 RdfParserKind::N3(p) => ReaderQuadParserKind::N3(p.for_reader(reader)),
                 RdfParserKind::NQuads(p) => ReaderQuadParserKind::NQuads(p.for_reader(reader)),
                 RdfParserKind::NTriples(p) => ReaderQuadParserKind::NTriples(p.for_reader(reader)),
+                RdfParserKind::RdfJson(p) => ReaderQuadParserKind::RdfJson(p.for_reader(reader)),
                 RdfParserKind::RdfXml(p) => ReaderQuadParserKind::RdfXml(p.for_reader(reader)),
                 RdfParserKind::TriG(p) => ReaderQuadParserKind::TriG(p.for_reader(reader)),
+                RdfParserKind::TriX(p) => ReaderQuadParserKind::TriX(p.for_reader(reader)),
                 RdfParserKind::Turtle(p) => ReaderQuadParserKind::Turtle(p.for_reader(reader)),
             },
-            mapper: QuadMapper {
-                default_graph: self.default_graph.clone(),
-                without_named_graphs: self.without_named_graphs,
-                blank_node_map: self.rename_blank_nodes.then(HashMap::new),
-            },
+            mapper,
         }
     }
 
@@ -308,38 +488,56 @@ impl RdfParser {
     /// ```
     #[cfg(feature = "async-tokio")]
     pub fn for_tokio_async_reader<R: AsyncRead + Unpin>(
-        self,
+        mut self,
         reader: R,
     ) -> TokioAsyncReaderQuadParser<R> {
+        let mapper = self.quad_mapper();
         TokioAsyncReaderQuadParser {
-            inner: match self.inner {
-                RdfParserKind::JsonLd(p, _) => {
-                    TokioAsyncReaderQuadParserKind::JsonLd(p.for_tokio_async_reader(reader))
-                }
-                RdfParserKind::N3(p) => {
-                    TokioAsyncReaderQuadParserKind::N3(p.for_tokio_async_reader(reader))
-                }
-                RdfParserKind::NQuads(p) => {
-                    TokioAsyncReaderQuadParserKind::NQuads(p.for_tokio_async_reader(reader))
-                }
-                RdfParserKind::NTriples(p) => {
-                    TokioAsyncReaderQuadParserKind::NTriples(p.for_tokio_async_reader(reader))
-                }
-                RdfParserKind::RdfXml(p) => {
-                    TokioAsyncReaderQuadParserKind::RdfXml(p.for_tokio_async_reader(reader))
-                }
-                RdfParserKind::TriG(p) => {
-                    TokioAsyncReaderQuadParserKind::TriG(p.for_tokio_async_reader(reader))
-                }
-                RdfParserKind::Turtle(p) => {
-                    TokioAsyncReaderQuadParserKind::Turtle(p.for_tokio_async_reader(reader))
-                }
-            },
-            mapper: QuadMapper {
-                default_graph: self.default_graph.clone(),
-                without_named_graphs: self.without_named_graphs,
-                blank_node_map: self.rename_blank_nodes.then(HashMap::new),
+            inner: if self.detect_format {
+                TokioAsyncReaderQuadParserKind::Detect(Some(Box::new((self, reader))))
+            } else {
+                self.tokio_async_reader_kind(PeekedReader::new(Vec::new(), None, reader))
             },
+            mapper,
+        }
+    }
+
+    #[cfg(feature = "async-tokio")]
+    fn tokio_async_reader_kind<R: AsyncRead + Unpin>(
+        self,
+        reader: PeekedReader<R>,
+    ) -> TokioAsyncReaderQuadParserKind<R> {
+        match self.inner {
+            RdfParserKind::Hdt(p) => {
+                TokioAsyncReaderQuadParserKind::Hdt(p.for_tokio_async_reader(reader))
+            }
+            RdfParserKind::JsonLd(p, _) => {
+                TokioAsyncReaderQuadParserKind::JsonLd(p.for_tokio_async_reader(reader))
+            }
+            RdfParserKind::N3(p) => {
+                TokioAsyncReaderQuadParserKind::N3(p.for_tokio_async_reader(reader))
+            }
+            RdfParserKind::NQuads(p) => {
+                TokioAsyncReaderQuadParserKind::NQuads(p.for_tokio_async_reader(reader))
+            }
+            RdfParserKind::NTriples(p) => {
+                TokioAsyncReaderQuadParserKind::NTriples(p.for_tokio_async_reader(reader))
+            }
+            RdfParserKind::RdfJson(p) => {
+                TokioAsyncReaderQuadParserKind::RdfJson(p.for_tokio_async_reader(reader))
+            }
+            RdfParserKind::RdfXml(p) => {
+                TokioAsyncReaderQuadParserKind::RdfXml(p.for_tokio_async_reader(reader))
+            }
+            RdfParserKind::TriG(p) => {
+                TokioAsyncReaderQuadParserKind::TriG(p.for_tokio_async_reader(reader))
+            }
+            RdfParserKind::TriX(p) => {
+                TokioAsyncReaderQuadParserKind::TriX(p.for_tokio_async_reader(reader))
+            }
+            RdfParserKind::Turtle(p) => {
+                TokioAsyncReaderQuadParserKind::Turtle(p.for_tokio_async_reader(reader))
+            }
         }
     }
 
@@ -358,22 +556,93 @@ impl RdfParser {
     /// assert_eq!(quads[0].subject.to_string(), "<http://example.com/s>");
     /// # std::io::Result::Ok(())
     /// ```
-    pub fn for_slice(self, slice: &[u8]) -> SliceQuadParser<'_> {
+    pub fn for_slice(mut self, slice: &[u8]) -> SliceQuadParser<'_> {
+        self = self.with_detected_format(slice);
+        let mapper = self.quad_mapper();
         SliceQuadParser {
             inner: match self.inner {
+                RdfParserKind::Hdt(p) => SliceQuadParserKind::Hdt(p.for_slice(slice)),
                 RdfParserKind::JsonLd(p, _) => SliceQuadParserKind::JsonLd(p.for_slice(slice)),
                 RdfParserKind::N3(p) => SliceQuadParserKind::N3(p.for_slice(slice)),
                 RdfParserKind::NQuads(p) => SliceQuadParserKind::NQuads(p.for_slice(slice)),
                 RdfParserKind::NTriples(p) => SliceQuadParserKind::NTriples(p.for_slice(slice)),
+                RdfParserKind::RdfJson(p) => SliceQuadParserKind::RdfJson(p.for_slice(slice)),
                 RdfParserKind::RdfXml(p) => SliceQuadParserKind::RdfXml(p.for_slice(slice)),
                 RdfParserKind::TriG(p) => SliceQuadParserKind::TriG(p.for_slice(slice)),
+                RdfParserKind::TriX(p) => SliceQuadParserKind::TriX(p.for_slice(slice)),
                 RdfParserKind::Turtle(p) => SliceQuadParserKind::Turtle(p.for_slice(slice)),
             },
-            mapper: QuadMapper {
-                default_graph: self.default_graph.clone(),
-                without_named_graphs: self.without_named_graphs,
-                blank_node_map: self.rename_blank_nodes.then(HashMap::new),
-            },
+            mapper,
+        }
+    }
+
+    fn quad_mapper(&mut self) -> QuadMapper {
+        QuadMapper {
+            default_graph: self.default_graph.clone(),
+            without_named_graphs: self.without_named_graphs,
+            blank_node_map: self.rename_blank_nodes.then(HashMap::new),
+            term_interner: self.term_interner.take(),
+            recovery: self.lenient_recovery.then(|| ErrorRecovery {
+                errors: Vec::new(),
+                can_continue: self.supports_recovery(),
+                stopped: false,
+            }),
+        }
+    }
+
+    fn supports_recovery(&self) -> bool {
+        matches!(
+            self.inner,
+            RdfParserKind::N3(_)
+                | RdfParserKind::NQuads(_)
+                | RdfParserKind::NTriples(_)
+                | RdfParserKind::TriG(_)
+                | RdfParserKind::Turtle(_)
+        )
+    }
+
+    /// Switches to the format guessed from the first bytes of the file if [`detect_format`](Self::detect_format) is enabled
+    fn with_detected_format(self, data: &[u8]) -> Self {
+        if !self.detect_format {
+            return self;
+        }
+        let Some(detected) = RdfFormat::from_content_peek(data) else {
+            return self;
+        };
+        let format = self.format();
+        if mem::discriminant(&detected) == mem::discriminant(&format) {
+            return self; // We keep the given options like the JSON-LD profile
+        }
+        let detected = match detected {
+            RdfFormat::NTriples | RdfFormat::Turtle | RdfFormat::TriG
+                if format == RdfFormat::N3 =>
+            {
+                return self;
+            }
+            // The rest of the file might contain named graphs
+            RdfFormat::NTriples => RdfFormat::NQuads,
+            RdfFormat::Turtle => RdfFormat::TriG,
+            other => other,
+        };
+        if detected == format {
+            return self;
+        }
+        let mut parser = Self::from_format(detected);
+        if let Some(base_iri) = &self.base_iri {
+            parser = match parser.with_base_iri(base_iri.as_str()) {
+                Ok(parser) => parser,
+                Err(_) => return self,
+            };
+        }
+        if self.lenient {
+            parser = parser.lenient();
+        }
+        if let Some(parallelism) = self.parallelism {
+            parser = parser.with_parallelism(parallelism);
+        }
+        Self {
+            inner: parser.inner,
+            ..self
         }
     }
 }
@@ -410,53 +679,86 @@ pub struct ReaderQuadParser<R: Read> {
 }
 
 enum ReaderQuadParserKind<R: Read> {
-    JsonLd(ReaderJsonLdParser<R>),
-    N3(ReaderN3Parser<R>),
-    NQuads(ReaderNQuadsParser<R>),
-    NTriples(ReaderNTriplesParser<R>),
-    RdfXml(ReaderRdfXmlParser<R>),
-    TriG(ReaderTriGParser<R>),
-    Turtle(ReaderTurtleParser<R>),
+    Hdt(ReaderHdtParser<PeekedReader<R>>),
+    JsonLd(ReaderJsonLdParser<PeekedReader<R>>),
+    N3(ReaderN3Parser<PeekedReader<R>>),
+    NQuads(ReaderNQuadsParser<PeekedReader<R>>),
+    NTriples(ReaderNTriplesParser<PeekedReader<R>>),
+    RdfJson(ReaderRdfJsonParser<PeekedReader<R>>),
+    RdfXml(ReaderRdfXmlParser<PeekedReader<R>>),
+    TriG(ReaderTriGParser<PeekedReader<R>>),
+    TriX(ReaderTriXParser<PeekedReader<R>>),
+    Turtle(ReaderTurtleParser<PeekedReader<R>>),
 }
 
 impl<R: Read> Iterator for ReaderQuadParser<R> {
     type Item = Result<Quad, RdfParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(match &mut self.inner {
-            ReaderQuadParserKind::JsonLd(parser) => match parser.next()? {
-                Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
-                Err(e) => Err(e.into()),
-            },
-            ReaderQuadParserKind::N3(parser) => match parser.next()? {
-                Ok(quad) => self.mapper.map_n3_quad(quad).map_err(Into::into),
-                Err(e) => Err(e.into()),
-            },
-            ReaderQuadParserKind::NQuads(parser) => match parser.next()? {
-                Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
-                Err(e) => Err(e.into()),
-            },
-            ReaderQuadParserKind::NTriples(parser) => match parser.next()? {
-                Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
-                Err(e) => Err(e.into()),
-            },
-            ReaderQuadParserKind::RdfXml(parser) => match parser.next()? {
-                Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
-                Err(e) => Err(e.into()),
-            },
-            ReaderQuadParserKind::TriG(parser) => match parser.next()? {
-                Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
-                Err(e) => Err(e.into()),
-            },
-            ReaderQuadParserKind::Turtle(parser) => match parser.next()? {
-                Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
-                Err(e) => Err(e.into()),
-            },
-        })
+        while !self.mapper.is_stopped() {
+            let result = match &mut self.inner {
+                ReaderQuadParserKind::Hdt(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                ReaderQuadParserKind::JsonLd(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                ReaderQuadParserKind::N3(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_n3_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                ReaderQuadParserKind::NQuads(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                ReaderQuadParserKind::NTriples(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                ReaderQuadParserKind::RdfJson(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                ReaderQuadParserKind::RdfXml(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                ReaderQuadParserKind::TriG(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                ReaderQuadParserKind::TriX(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                ReaderQuadParserKind::Turtle(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+            };
+            match result {
+                Err(RdfParseError::Syntax(error)) => {
+                    if let Err(error) = self.mapper.recover(error) {
+                        return Some(Err(error.into()));
+                    }
+                }
+                other => return Some(other),
+            }
+        }
+        None
     }
 }
 
 impl<R: Read> ReaderQuadParser<R> {
+    /// The syntax errors encountered until now if [`RdfParser::lenient_recovery`] is enabled.
+    ///
+    /// It is always empty if the error recovery is not enabled because the errors are returned by the parser.
+    pub fn syntax_errors(&self) -> &[RdfSyntaxError] {
+        self.mapper.syntax_errors()
+    }
+
     /// The list of IRI prefixes considered at the current step of the parsing.
     ///
     /// This method returns (prefix name, prefix value) tuples.
@@ -492,9 +794,11 @@ impl<R: Read> ReaderQuadParser<R> {
                 ReaderQuadParserKind::TriG(p) => PrefixesIterKind::TriG(p.prefixes()),
                 ReaderQuadParserKind::Turtle(p) => PrefixesIterKind::Turtle(p.prefixes()),
                 ReaderQuadParserKind::RdfXml(p) => PrefixesIterKind::RdfXml(p.prefixes()),
-                ReaderQuadParserKind::NQuads(_) | ReaderQuadParserKind::NTriples(_) => {
-                    PrefixesIterKind::None
-                }
+                ReaderQuadParserKind::Hdt(_)
+                | ReaderQuadParserKind::NQuads(_)
+                | ReaderQuadParserKind::NTriples(_)
+                | ReaderQuadParserKind::RdfJson(_)
+                | ReaderQuadParserKind::TriX(_) => PrefixesIterKind::None,
             },
         }
     }
@@ -525,7 +829,11 @@ impl<R: Read> ReaderQuadParser<R> {
             ReaderQuadParserKind::TriG(p) => p.base_iri(),
             ReaderQuadParserKind::Turtle(p) => p.base_iri(),
             ReaderQuadParserKind::RdfXml(p) => p.base_iri(),
-            ReaderQuadParserKind::NQuads(_) | ReaderQuadParserKind::NTriples(_) => None,
+            ReaderQuadParserKind::Hdt(_)
+            | ReaderQuadParserKind::NQuads(_)
+            | ReaderQuadParserKind::NTriples(_)
+            | ReaderQuadParserKind::RdfJson(_)
+            | ReaderQuadParserKind::TriX(_) => None,
         }
     }
 
@@ -629,48 +937,93 @@ pub struct TokioAsyncReaderQuadParser<R: AsyncRead + Unpin> {
 
 #[cfg(feature = "async-tokio")]
 enum TokioAsyncReaderQuadParserKind<R: AsyncRead + Unpin> {
-    JsonLd(TokioAsyncReaderJsonLdParser<R>),
-    N3(TokioAsyncReaderN3Parser<R>),
-    NQuads(TokioAsyncReaderNQuadsParser<R>),
-    NTriples(TokioAsyncReaderNTriplesParser<R>),
-    RdfXml(TokioAsyncReaderRdfXmlParser<R>),
-    TriG(TokioAsyncReaderTriGParser<R>),
-    Turtle(TokioAsyncReaderTurtleParser<R>),
+    Hdt(TokioAsyncReaderHdtParser<PeekedReader<R>>),
+    JsonLd(TokioAsyncReaderJsonLdParser<PeekedReader<R>>),
+    N3(TokioAsyncReaderN3Parser<PeekedReader<R>>),
+    NQuads(TokioAsyncReaderNQuadsParser<PeekedReader<R>>),
+    NTriples(TokioAsyncReaderNTriplesParser<PeekedReader<R>>),
+    RdfJson(TokioAsyncReaderRdfJsonParser<PeekedReader<R>>),
+    RdfXml(TokioAsyncReaderRdfXmlParser<PeekedReader<R>>),
+    TriG(TokioAsyncReaderTriGParser<PeekedReader<R>>),
+    TriX(TokioAsyncReaderTriXParser<PeekedReader<R>>),
+    Turtle(TokioAsyncReaderTurtleParser<PeekedReader<R>>),
+    /// The format is detected when the first quad is requested
+    Detect(Option<Box<(RdfParser, R)>>),
 }
 
 #[cfg(feature = "async-tokio")]
 impl<R: AsyncRead + Unpin> TokioAsyncReaderQuadParser<R> {
     pub async fn next(&mut self) -> Option<Result<Quad, RdfParseError>> {
-        Some(match &mut self.inner {
-            TokioAsyncReaderQuadParserKind::JsonLd(parser) => match parser.next().await? {
-                Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
-                Err(e) => Err(e.into()),
-            },
-            TokioAsyncReaderQuadParserKind::N3(parser) => match parser.next().await? {
-                Ok(quad) => self.mapper.map_n3_quad(quad).map_err(Into::into),
-                Err(e) => Err(e.into()),
-            },
-            TokioAsyncReaderQuadParserKind::NQuads(parser) => match parser.next().await? {
-                Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
-                Err(e) => Err(e.into()),
-            },
-            TokioAsyncReaderQuadParserKind::NTriples(parser) => match parser.next().await? {
-                Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
-                Err(e) => Err(e.into()),
-            },
-            TokioAsyncReaderQuadParserKind::RdfXml(parser) => match parser.next().await? {
-                Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
-                Err(e) => Err(e.into()),
-            },
-            TokioAsyncReaderQuadParserKind::TriG(parser) => match parser.next().await? {
-                Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
-                Err(e) => Err(e.into()),
-            },
-            TokioAsyncReaderQuadParserKind::Turtle(parser) => match parser.next().await? {
-                Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
-                Err(e) => Err(e.into()),
-            },
-        })
+        if let TokioAsyncReaderQuadParserKind::Detect(to_detect) = &mut self.inner {
+            let (parser, mut reader) = *to_detect.take()?;
+            let (peeked, error) = peek_tokio(&mut reader).await;
+            let parser = parser.with_detected_format(&peeked);
+            if let Some(recovery) = &mut self.mapper.recovery {
+                recovery.can_continue = parser.supports_recovery();
+            }
+            self.inner = parser.tokio_async_reader_kind(PeekedReader::new(peeked, error, reader));
+        }
+        while !self.mapper.is_stopped() {
+            let result = match &mut self.inner {
+                TokioAsyncReaderQuadParserKind::Hdt(parser) => match parser.next().await? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::JsonLd(parser) => match parser.next().await? {
+                    Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::N3(parser) => match parser.next().await? {
+                    Ok(quad) => self.mapper.map_n3_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::NQuads(parser) => match parser.next().await? {
+                    Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::NTriples(parser) => match parser.next().await? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::RdfJson(parser) => match parser.next().await? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::RdfXml(parser) => match parser.next().await? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::TriG(parser) => match parser.next().await? {
+                    Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::TriX(parser) => match parser.next().await? {
+                    Ok(quad) => self.mapper.map_quad(quad).map_err(Into::into),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::Turtle(parser) => match parser.next().await? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                TokioAsyncReaderQuadParserKind::Detect(_) => return None,
+            };
+            match result {
+                Err(RdfParseError::Syntax(error)) => {
+                    if let Err(error) = self.mapper.recover(error) {
+                        return Some(Err(error.into()));
+                    }
+                }
+                other => return Some(other),
+            }
+        }
+        None
+    }
+
+    /// The syntax errors encountered until now if [`RdfParser::lenient_recovery`] is enabled.
+    ///
+    /// It is always empty if the error recovery is not enabled because the errors are returned by the parser.
+    pub fn syntax_errors(&self) -> &[RdfSyntaxError] {
+        self.mapper.syntax_errors()
     }
 
     /// The list of IRI prefixes considered at the current step of the parsing.
@@ -712,8 +1065,12 @@ impl<R: AsyncRead + Unpin> TokioAsyncReaderQuadParser<R> {
                 TokioAsyncReaderQuadParserKind::TriG(p) => PrefixesIterKind::TriG(p.prefixes()),
                 TokioAsyncReaderQuadParserKind::Turtle(p) => PrefixesIterKind::Turtle(p.prefixes()),
                 TokioAsyncReaderQuadParserKind::RdfXml(p) => PrefixesIterKind::RdfXml(p.prefixes()),
-                TokioAsyncReaderQuadParserKind::NQuads(_)
-                | TokioAsyncReaderQuadParserKind::NTriples(_) => PrefixesIterKind::None,
+                TokioAsyncReaderQuadParserKind::Hdt(_)
+                | TokioAsyncReaderQuadParserKind::NQuads(_)
+                | TokioAsyncReaderQuadParserKind::NTriples(_)
+                | TokioAsyncReaderQuadParserKind::RdfJson(_)
+                | TokioAsyncReaderQuadParserKind::TriX(_)
+                | TokioAsyncReaderQuadParserKind::Detect(_) => PrefixesIterKind::None,
             },
         }
     }
@@ -749,8 +1106,12 @@ impl<R: AsyncRead + Unpin> TokioAsyncReaderQuadParser<R> {
             TokioAsyncReaderQuadParserKind::TriG(p) => p.base_iri(),
             TokioAsyncReaderQuadParserKind::Turtle(p) => p.base_iri(),
             TokioAsyncReaderQuadParserKind::RdfXml(p) => p.base_iri(),
-            TokioAsyncReaderQuadParserKind::NQuads(_)
-            | TokioAsyncReaderQuadParserKind::NTriples(_) => None,
+            TokioAsyncReaderQuadParserKind::Hdt(_)
+            | TokioAsyncReaderQuadParserKind::NQuads(_)
+            | TokioAsyncReaderQuadParserKind::NTriples(_)
+            | TokioAsyncReaderQuadParserKind::RdfJson(_)
+            | TokioAsyncReaderQuadParserKind::TriX(_)
+            | TokioAsyncReaderQuadParserKind::Detect(_) => None,
         }
     }
 }
@@ -779,12 +1140,15 @@ pub struct SliceQuadParser<'a> {
 }
 
 enum SliceQuadParserKind<'a> {
+    Hdt(SliceHdtParser<'a>),
     JsonLd(SliceJsonLdParser<'a>),
     N3(SliceN3Parser<'a>),
     NQuads(SliceNQuadsParser<'a>),
     NTriples(SliceNTriplesParser<'a>),
+    RdfJson(SliceRdfJsonParser<'a>),
     RdfXml(SliceRdfXmlParser<'a>),
     TriG(SliceTriGParser<'a>),
+    TriX(SliceTriXParser<'a>),
     Turtle(SliceTurtleParser<'a>),
 }
 
@@ -792,36 +1156,59 @@ impl Iterator for SliceQuadParser<'_> {
     type Item = Result<Quad, RdfSyntaxError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(match &mut self.inner {
-            SliceQuadParserKind::JsonLd(parser) => match parser.next()? {
-                Ok(quad) => self.mapper.map_quad(quad),
-                Err(e) => Err(e.into()),
-            },
-            SliceQuadParserKind::N3(parser) => match parser.next()? {
-                Ok(quad) => self.mapper.map_n3_quad(quad),
-                Err(e) => Err(e.into()),
-            },
-            SliceQuadParserKind::NQuads(parser) => match parser.next()? {
-                Ok(quad) => self.mapper.map_quad(quad),
-                Err(e) => Err(e.into()),
-            },
-            SliceQuadParserKind::NTriples(parser) => match parser.next()? {
-                Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
-                Err(e) => Err(e.into()),
-            },
-            SliceQuadParserKind::RdfXml(parser) => match parser.next()? {
-                Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
-                Err(e) => Err(e.into()),
-            },
-            SliceQuadParserKind::TriG(parser) => match parser.next()? {
-                Ok(quad) => self.mapper.map_quad(quad),
-                Err(e) => Err(e.into()),
-            },
-            SliceQuadParserKind::Turtle(parser) => match parser.next()? {
-                Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
-                Err(e) => Err(e.into()),
-            },
-        })
+        while !self.mapper.is_stopped() {
+            let result = match &mut self.inner {
+                SliceQuadParserKind::Hdt(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                SliceQuadParserKind::JsonLd(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_quad(quad),
+                    Err(e) => Err(e.into()),
+                },
+                SliceQuadParserKind::N3(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_n3_quad(quad),
+                    Err(e) => Err(e.into()),
+                },
+                SliceQuadParserKind::NQuads(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_quad(quad),
+                    Err(e) => Err(e.into()),
+                },
+                SliceQuadParserKind::NTriples(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                SliceQuadParserKind::RdfJson(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                SliceQuadParserKind::RdfXml(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+                SliceQuadParserKind::TriG(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_quad(quad),
+                    Err(e) => Err(e.into()),
+                },
+                SliceQuadParserKind::TriX(parser) => match parser.next()? {
+                    Ok(quad) => self.mapper.map_quad(quad),
+                    Err(e) => Err(e.into()),
+                },
+                SliceQuadParserKind::Turtle(parser) => match parser.next()? {
+                    Ok(triple) => Ok(self.mapper.map_triple_to_quad(triple)),
+                    Err(e) => Err(e.into()),
+                },
+            };
+            match result {
+                Err(error) => {
+                    if let Err(error) = self.mapper.recover(error) {
+                        return Some(Err(error));
+                    }
+                }
+                other => return Some(other),
+            }
+        }
+        None
     }
 }
 
@@ -895,6 +1282,13 @@ impl SliceQuadParser<'_> {
         self
     }
 
+    /// The syntax errors encountered until now if [`RdfParser::lenient_recovery`] is enabled.
+    ///
+    /// It is always empty if the error recovery is not enabled because the errors are returned by the parser.
+    pub fn syntax_errors(&self) -> &[RdfSyntaxError] {
+        self.mapper.syntax_errors()
+    }
+
     /// The list of IRI prefixes considered at the current step of the parsing.
     ///
     /// This method returns (prefix name, prefix value) tuples.
@@ -930,9 +1324,11 @@ impl SliceQuadParser<'_> {
                 SliceQuadParserKind::TriG(p) => PrefixesIterKind::TriG(p.prefixes()),
                 SliceQuadParserKind::Turtle(p) => PrefixesIterKind::Turtle(p.prefixes()),
                 SliceQuadParserKind::RdfXml(p) => PrefixesIterKind::RdfXml(p.prefixes()),
-                SliceQuadParserKind::NQuads(_) | SliceQuadParserKind::NTriples(_) => {
-                    PrefixesIterKind::None
-                }
+                SliceQuadParserKind::Hdt(_)
+                | SliceQuadParserKind::NQuads(_)
+                | SliceQuadParserKind::NTriples(_)
+                | SliceQuadParserKind::RdfJson(_)
+                | SliceQuadParserKind::TriX(_) => PrefixesIterKind::None,
             },
         }
     }
@@ -963,7 +1359,11 @@ impl SliceQuadParser<'_> {
             SliceQuadParserKind::TriG(p) => p.base_iri(),
             SliceQuadParserKind::Turtle(p) => p.base_iri(),
             SliceQuadParserKind::RdfXml(p) => p.base_iri(),
-            SliceQuadParserKind::NQuads(_) | SliceQuadParserKind::NTriples(_) => None,
+            SliceQuadParserKind::Hdt(_)
+            | SliceQuadParserKind::NQuads(_)
+            | SliceQuadParserKind::NTriples(_)
+            | SliceQuadParserKind::RdfJson(_)
+            | SliceQuadParserKind::TriX(_) => None,
         }
     }
 }
@@ -1016,9 +1416,40 @@ struct QuadMapper {
     default_graph: GraphName,
     without_named_graphs: bool,
     blank_node_map: Option<HashMap<BlankNode, BlankNode>>,
+    term_interner: Option<TermInterner>,
+    recovery: Option<ErrorRecovery>,
+}
+
+struct ErrorRecovery {
+    errors: Vec<RdfSyntaxError>,
+    /// If the format parser is able to continue after a syntax error
+    can_continue: bool,
+    stopped: bool,
 }
 
 impl QuadMapper {
+    fn is_stopped(&self) -> bool {
+        self.recovery
+            .as_ref()
+            .is_some_and(|recovery| recovery.stopped)
+    }
+
+    /// Collects the syntax error if the error recovery is enabled, returns it back if not
+    fn recover(&mut self, error: RdfSyntaxError) -> Result<(), RdfSyntaxError> {
+        let Some(recovery) = &mut self.recovery else {
+            return Err(error);
+        };
+        recovery.errors.push(error);
+        recovery.stopped = !recovery.can_continue;
+        Ok(())
+    }
+
+    fn syntax_errors(&self) -> &[RdfSyntaxError] {
+        self.recovery
+            .as_ref()
+            .map_or(&[], |recovery| &recovery.errors)
+    }
+
     fn map_blank_node(&mut self, node: BlankNode) -> BlankNode {
         if let Some(blank_node_map) = &mut self.blank_node_map {
             blank_node_map
@@ -1075,21 +1506,31 @@ impl QuadMapper {
         }
     }
 
+    fn intern_quad(&mut self, quad: Quad) -> Quad {
+        if let Some(term_interner) = &mut self.term_interner {
+            term_interner.intern_quad(&quad)
+        } else {
+            quad
+        }
+    }
+
     fn map_quad(&mut self, quad: Quad) -> Result<Quad, RdfSyntaxError> {
-        Ok(Quad {
+        let quad = Quad {
             subject: self.map_subject(quad.subject),
             predicate: quad.predicate,
             object: self.map_term(quad.object),
             graph_name: self.map_graph_name(quad.graph_name)?,
-        })
+        };
+        Ok(self.intern_quad(quad))
     }
 
     fn map_triple_to_quad(&mut self, triple: Triple) -> Quad {
-        self.map_triple(triple).in_graph(self.default_graph.clone())
+        let quad = self.map_triple(triple).in_graph(self.default_graph.clone());
+        self.intern_quad(quad)
     }
 
     fn map_n3_quad(&mut self, quad: N3Quad) -> Result<Quad, RdfSyntaxError> {
-        Ok(Quad {
+        let quad = Quad {
             subject: match quad.subject {
                 N3Term::NamedNode(s) => Ok(s.into()),
                 N3Term::BlankNode(s) => Ok(self.map_blank_node(s).into()),
@@ -1131,6 +1572,83 @@ impl QuadMapper {
                 )),
             }?,
             graph_name: self.map_graph_name(quad.graph_name)?,
-        })
+        };
+        Ok(self.intern_quad(quad))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lenient_recovery() {
+        let file = b"<http://example.com/s> <http://example.com/p> \"o\" .
+<http://example.com/s> <http://example.com/p> <http://example.com/bad iri> .
+<http://example.com/s> <http://example.com/p> <http://example.com/o> <http://example.com/g> .
+_:s <http://example.com/p> .
+<http://example.com/s> <http://example.com/p> _:o .";
+        let mut parser = RdfParser::from_format(RdfFormat::NQuads)
+            .lenient_recovery()
+            .for_slice(file);
+        assert_eq!(parser.by_ref().map(Result::unwrap).count(), 3);
+        let error_lines = parser
+            .syntax_errors()
+            .iter()
+            .map(|e| e.location().unwrap().start.line)
+            .collect::<Vec<_>>();
+        assert_eq!(error_lines, [1, 3]);
+
+        // Formats without recovery stop at the first error
+        let mut parser = RdfParser::from_format(RdfFormat::RdfXml)
+            .lenient_recovery()
+            .for_slice(b"<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"><rdf:Description><foo");
+        assert_eq!(parser.by_ref().count(), 0);
+        assert_eq!(parser.syntax_errors().len(), 1);
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let file = b"<?xml version=\"1.0\"?>
+<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">
+  <rdf:Description rdf:about=\"s\"><rdf:value>o</rdf:value></rdf:Description>
+</rdf:RDF>";
+        let parser = RdfParser::from_format(RdfFormat::Turtle)
+            .with_base_iri("http://example.com/")
+            .unwrap()
+            .detect_format();
+        let quads = parser
+            .clone()
+            .for_slice(file)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(quads.len(), 1);
+        assert_eq!(quads[0].subject.to_string(), "<http://example.com/s>");
+        let quads = parser
+            .for_reader(file.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(quads.len(), 1);
+        assert_eq!(quads[0].subject.to_string(), "<http://example.com/s>");
+
+        // The N3 parser is kept for Turtle-like files
+        let quads = RdfParser::from_format(RdfFormat::N3)
+            .detect_format()
+            .for_reader(b"@prefix : <http://example.com/> .\n:s :p { :s :p :o } .".as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(quads.len(), 2);
+    }
+
+    #[cfg(feature = "async-tokio")]
+    #[tokio::test]
+    async fn test_detect_format_tokio() {
+        let file = b"{\"http://example.com/s\": {\"http://example.com/p\": [{\"type\": \"literal\", \"value\": \"o\"}]}}";
+        let mut parser = RdfParser::from_format(RdfFormat::NTriples)
+            .detect_format()
+            .for_tokio_async_reader(file.as_slice());
+        let quad = parser.next().await.unwrap().unwrap();
+        assert_eq!(quad.object.to_string(), "\"o\"");
+        assert!(parser.next().await.is_none());
     }
 }
@@ -1,13 +1,20 @@
 //! Utilities to write RDF graphs and datasets.
 
 use crate::format::RdfFormat;
+use crate::sorted::SortedQuadSerializer;
 #[cfg(feature = "async-tokio")]
-use oxjsonld::TokioAsyncWriterJsonLdSerializer;
-use oxjsonld::{JsonLdProfile, JsonLdSerializer, WriterJsonLdSerializer};
-use oxrdf::{GraphNameRef, IriParseError, QuadRef, TripleRef};
+use oxhdt::TokioAsyncWriterHdtSerializer;
+use oxhdt::{HdtSerializer, WriterHdtSerializer};
+use oxjsonld::{
+    JsonLdProfile, JsonLdSerializer, RdfJsonSerializer, WriterJsonLdSerializer,
+    WriterRdfJsonSerializer,
+};
 #[cfg(feature = "async-tokio")]
-use oxrdfxml::TokioAsyncWriterRdfXmlSerializer;
-use oxrdfxml::{RdfXmlSerializer, WriterRdfXmlSerializer};
+use oxjsonld::{TokioAsyncWriterJsonLdSerializer, TokioAsyncWriterRdfJsonSerializer};
+use oxrdf::{GraphName, GraphNameRef, IriParseError, QuadRef, TripleRef};
+use oxrdfxml::{RdfXmlSerializer, TriXSerializer, WriterRdfXmlSerializer, WriterTriXSerializer};
+#[cfg(feature = "async-tokio")]
+use oxrdfxml::{TokioAsyncWriterRdfXmlSerializer, TokioAsyncWriterTriXSerializer};
 #[cfg(feature = "async-tokio")]
 use oxttl::nquads::TokioAsyncWriterNQuadsSerializer;
 use oxttl::nquads::{NQuadsSerializer, WriterNQuadsSerializer};
@@ -21,18 +28,23 @@ use oxttl::trig::{TriGSerializer, WriterTriGSerializer};
 use oxttl::turtle::TokioAsyncWriterTurtleSerializer;
 use oxttl::turtle::{TurtleSerializer, WriterTurtleSerializer};
 use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 #[cfg(feature = "async-tokio")]
 use tokio::io::AsyncWrite;
 
 /// A serializer for RDF serialization formats.
 ///
 /// It currently supports the following formats:
+/// * [HDT](https://www.rdfhdt.org/hdt-binary-format/) ([`RdfFormat::Hdt`])
 /// * [JSON-LD](https://www.w3.org/TR/json-ld/) ([`RdfFormat::JsonLd`])
 /// * [N3](https://w3c.github.io/N3/spec/) ([`RdfFormat::N3`])
 /// * [N-Quads](https://www.w3.org/TR/n-quads/) ([`RdfFormat::NQuads`])
 /// * [canonical](https://www.w3.org/TR/n-triples/#canonical-ntriples) [N-Triples](https://www.w3.org/TR/n-triples/) ([`RdfFormat::NTriples`])
+/// * [RDF/JSON](https://www.w3.org/TR/rdf-json/) ([`RdfFormat::RdfJson`])
 /// * [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/) ([`RdfFormat::RdfXml`])
 /// * [TriG](https://www.w3.org/TR/trig/) ([`RdfFormat::TriG`])
+/// * [TriX](https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html) ([`RdfFormat::TriX`])
 /// * [Turtle](https://www.w3.org/TR/turtle/) ([`RdfFormat::Turtle`])
 ///
 /// ```
@@ -57,11 +69,14 @@ pub struct RdfSerializer {
 
 #[derive(Clone)]
 enum RdfSerializerKind {
+    Hdt(HdtSerializer),
     JsonLd(JsonLdSerializer),
     NQuads(NQuadsSerializer),
     NTriples(NTriplesSerializer),
+    RdfJson(RdfJsonSerializer),
     RdfXml(RdfXmlSerializer),
     TriG(TriGSerializer),
+    TriX(TriXSerializer),
     Turtle(TurtleSerializer),
 }
 
@@ -71,11 +86,16 @@ impl RdfSerializer {
     pub fn from_format(format: RdfFormat) -> Self {
         Self {
             inner: match format {
-                RdfFormat::JsonLd { .. } => RdfSerializerKind::JsonLd(JsonLdSerializer::new()),
+                RdfFormat::Hdt => RdfSerializerKind::Hdt(HdtSerializer::new()),
+                RdfFormat::JsonLd { profile } => {
+                    RdfSerializerKind::JsonLd(JsonLdSerializer::new().with_profile(profile))
+                }
                 RdfFormat::NQuads => RdfSerializerKind::NQuads(NQuadsSerializer::new()),
                 RdfFormat::NTriples => RdfSerializerKind::NTriples(NTriplesSerializer::new()),
+                RdfFormat::RdfJson => RdfSerializerKind::RdfJson(RdfJsonSerializer::new()),
                 RdfFormat::RdfXml => RdfSerializerKind::RdfXml(RdfXmlSerializer::new()),
                 RdfFormat::TriG => RdfSerializerKind::TriG(TriGSerializer::new()),
+                RdfFormat::TriX => RdfSerializerKind::TriX(TriXSerializer::new()),
                 RdfFormat::Turtle | RdfFormat::N3 => {
                     RdfSerializerKind::Turtle(TurtleSerializer::new())
                 }
@@ -95,13 +115,16 @@ impl RdfSerializer {
     /// ```
     pub fn format(&self) -> RdfFormat {
         match &self.inner {
+            RdfSerializerKind::Hdt(_) => RdfFormat::Hdt,
             RdfSerializerKind::JsonLd(_) => RdfFormat::JsonLd {
                 profile: JsonLdProfile::Streaming.into(), // TODO: also expanded?
             },
             RdfSerializerKind::NQuads(_) => RdfFormat::NQuads,
             RdfSerializerKind::NTriples(_) => RdfFormat::NTriples,
+            RdfSerializerKind::RdfJson(_) => RdfFormat::RdfJson,
             RdfSerializerKind::RdfXml(_) => RdfFormat::RdfXml,
             RdfSerializerKind::TriG(_) => RdfFormat::TriG,
+            RdfSerializerKind::TriX(_) => RdfFormat::TriX,
             RdfSerializerKind::Turtle(_) => RdfFormat::Turtle,
         }
     }
@@ -134,15 +157,18 @@ impl RdfSerializer {
         prefix_iri: impl Into<String>,
     ) -> Result<Self, IriParseError> {
         self.inner = match self.inner {
+            RdfSerializerKind::Hdt(s) => RdfSerializerKind::Hdt(s),
             RdfSerializerKind::JsonLd(s) => RdfSerializerKind::JsonLd(s),
             RdfSerializerKind::NQuads(s) => RdfSerializerKind::NQuads(s),
             RdfSerializerKind::NTriples(s) => RdfSerializerKind::NTriples(s),
+            RdfSerializerKind::RdfJson(s) => RdfSerializerKind::RdfJson(s),
             RdfSerializerKind::RdfXml(s) => {
                 RdfSerializerKind::RdfXml(s.with_prefix(prefix_name, prefix_iri)?)
             }
             RdfSerializerKind::TriG(s) => {
                 RdfSerializerKind::TriG(s.with_prefix(prefix_name, prefix_iri)?)
             }
+            RdfSerializerKind::TriX(s) => RdfSerializerKind::TriX(s),
             RdfSerializerKind::Turtle(s) => {
                 RdfSerializerKind::Turtle(s.with_prefix(prefix_name, prefix_iri)?)
             }
@@ -175,16 +201,55 @@ impl RdfSerializer {
     #[inline]
     pub fn with_base_iri(mut self, base_iri: impl Into<String>) -> Result<Self, IriParseError> {
         self.inner = match self.inner {
+            RdfSerializerKind::Hdt(s) => RdfSerializerKind::Hdt(s),
             RdfSerializerKind::JsonLd(s) => RdfSerializerKind::JsonLd(s),
             RdfSerializerKind::NQuads(s) => RdfSerializerKind::NQuads(s),
             RdfSerializerKind::NTriples(s) => RdfSerializerKind::NTriples(s),
+            RdfSerializerKind::RdfJson(s) => RdfSerializerKind::RdfJson(s),
             RdfSerializerKind::RdfXml(s) => RdfSerializerKind::RdfXml(s.with_base_iri(base_iri)?),
             RdfSerializerKind::TriG(s) => RdfSerializerKind::TriG(s.with_base_iri(base_iri)?),
+            RdfSerializerKind::TriX(s) => RdfSerializerKind::TriX(s),
             RdfSerializerKind::Turtle(s) => RdfSerializerKind::Turtle(s.with_base_iri(base_iri)?),
         };
         Ok(self)
     }
 
+    /// If the format supports it, writes the file in a more human-readable way.
+    ///
+    /// Only the [Turtle](RdfFormat::Turtle) and [TriG](RdfFormat::TriG) serializers support it.
+    /// The triples are grouped by subject and predicate,
+    /// the blank nodes used only once are written inline and the RDF collections are written using the `( ... )` syntax.
+    /// The quads are buffered and only written when the serializer is finished.
+    ///
+    /// ```
+    /// use oxrdf::{BlankNodeRef, NamedNodeRef, TripleRef};
+    /// use oxrdfio::{RdfFormat, RdfSerializer};
+    ///
+    /// let s = NamedNodeRef::new("http://example.com/s")?;
+    /// let p = NamedNodeRef::new("http://example.com/p")?;
+    /// let o = BlankNodeRef::new("o")?;
+    /// let mut serializer = RdfSerializer::from_format(RdfFormat::Turtle)
+    ///     .with_prefix("ex", "http://example.com/")?
+    ///     .pretty()
+    ///     .for_writer(Vec::new());
+    /// serializer.serialize_triple(TripleRef::new(s, p, o))?;
+    /// serializer.serialize_triple(TripleRef::new(o, p, s))?;
+    /// assert_eq!(
+    ///     serializer.finish()?,
+    ///     b"@prefix ex: <http://example.com/> .\nex:s ex:p [ ex:p ex:s ] .\n"
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn pretty(mut self) -> Self {
+        self.inner = match self.inner {
+            RdfSerializerKind::TriG(s) => RdfSerializerKind::TriG(s.pretty()),
+            RdfSerializerKind::Turtle(s) => RdfSerializerKind::Turtle(s.pretty()),
+            inner => inner,
+        };
+        self
+    }
+
     /// Serializes to a [`Write`] implementation.
     ///
     /// <div class="warning">
@@ -212,6 +277,7 @@ impl RdfSerializer {
     pub fn for_writer<W: Write>(self, writer: W) -> WriterQuadSerializer<W> {
         WriterQuadSerializer {
             inner: match self.inner {
+                RdfSerializerKind::Hdt(s) => WriterQuadSerializerKind::Hdt(s.for_writer(writer)),
                 RdfSerializerKind::JsonLd(s) => {
                     WriterQuadSerializerKind::JsonLd(s.for_writer(writer))
                 }
@@ -221,10 +287,14 @@ impl RdfSerializer {
                 RdfSerializerKind::NTriples(s) => {
                     WriterQuadSerializerKind::NTriples(s.for_writer(writer))
                 }
+                RdfSerializerKind::RdfJson(s) => {
+                    WriterQuadSerializerKind::RdfJson(s.for_writer(writer))
+                }
                 RdfSerializerKind::RdfXml(s) => {
                     WriterQuadSerializerKind::RdfXml(s.for_writer(writer))
                 }
                 RdfSerializerKind::TriG(s) => WriterQuadSerializerKind::TriG(s.for_writer(writer)),
+                RdfSerializerKind::TriX(s) => WriterQuadSerializerKind::TriX(s.for_writer(writer)),
                 RdfSerializerKind::Turtle(s) => {
                     WriterQuadSerializerKind::Turtle(s.for_writer(writer))
                 }
@@ -232,6 +302,73 @@ impl RdfSerializer {
         }
     }
 
+    /// Serializes to multiple [`Write`] implementations, each one getting a full document.
+    ///
+    /// `open_shard` is called to get the writer of each new shard.
+    /// The shards are split by size with [`with_max_shard_size`](ShardedQuadSerializer::with_max_shard_size)
+    /// and/or by graph with [`with_shard_per_graph`](ShardedQuadSerializer::with_shard_per_graph).
+    /// Each shard is written with the same prefixes and base IRI.
+    ///
+    /// ```
+    /// use oxrdf::{NamedNodeRef, TripleRef};
+    /// use oxrdfio::{RdfFormat, RdfSerializer};
+    ///
+    /// let mut serializer = RdfSerializer::from_format(RdfFormat::NTriples)
+    ///     .for_shards(|_| Ok(Vec::new()))
+    ///     .with_max_shard_size(100);
+    /// for o in ["http://example.com/o1", "http://example.com/o2", "http://example.com/o3"] {
+    ///     serializer.serialize_triple(TripleRef::new(
+    ///         NamedNodeRef::new("http://example.com/s")?,
+    ///         NamedNodeRef::new("http://example.com/p")?,
+    ///         NamedNodeRef::new(o)?,
+    ///     ))?;
+    /// }
+    /// let shards = serializer.finish()?;
+    /// assert_eq!(shards.len(), 2);
+    /// assert_eq!(
+    ///     shards[1],
+    ///     b"<http://example.com/s> <http://example.com/p> <http://example.com/o3> .\n"
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_shards<W: Write, F: FnMut(Shard<'_>) -> io::Result<W>>(
+        self,
+        open_shard: F,
+    ) -> ShardedQuadSerializer<W, F> {
+        ShardedQuadSerializer {
+            serializer: self,
+            open_shard,
+            max_shard_size: None,
+            shard_per_graph: false,
+            current: None,
+            finished: Vec::new(),
+        }
+    }
+
+    /// Serializes to a [`Write`] implementation the quads sorted by graph name, subject, predicate and object.
+    ///
+    /// It makes the output stable across runs for the same set of quads, so it can be diffed,
+    /// without canonicalizing the blank nodes.
+    /// See [`SortedQuadSerializer`] for the details of the order.
+    ///
+    /// ```
+    /// use oxrdf::{GraphNameRef, NamedNodeRef, QuadRef};
+    /// use oxrdfio::{RdfFormat, RdfSerializer};
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let mut serializer = RdfSerializer::from_format(RdfFormat::NQuads).for_sorted_writer(Vec::new());
+    /// serializer.serialize_quad(QuadRef::new(ex, ex, ex, ex))?;
+    /// serializer.serialize_quad(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
+    /// assert_eq!(
+    ///     serializer.finish()?,
+    ///     b"<http://example.com> <http://example.com> <http://example.com> .\n<http://example.com> <http://example.com> <http://example.com> <http://example.com> .\n"
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_sorted_writer<W: Write>(self, writer: W) -> SortedQuadSerializer<W> {
+        SortedQuadSerializer::new(self, writer)
+    }
+
     /// Serializes to a Tokio [`AsyncWrite`] implementation.
     ///
     /// <div class="warning">
@@ -266,6 +403,9 @@ impl RdfSerializer {
     ) -> TokioAsyncWriterQuadSerializer<W> {
         TokioAsyncWriterQuadSerializer {
             inner: match self.inner {
+                RdfSerializerKind::Hdt(s) => {
+                    TokioAsyncWriterQuadSerializerKind::Hdt(s.for_tokio_async_writer(writer))
+                }
                 RdfSerializerKind::JsonLd(s) => {
                     TokioAsyncWriterQuadSerializerKind::JsonLd(s.for_tokio_async_writer(writer))
                 }
@@ -275,12 +415,18 @@ impl RdfSerializer {
                 RdfSerializerKind::NTriples(s) => {
                     TokioAsyncWriterQuadSerializerKind::NTriples(s.for_tokio_async_writer(writer))
                 }
+                RdfSerializerKind::RdfJson(s) => {
+                    TokioAsyncWriterQuadSerializerKind::RdfJson(s.for_tokio_async_writer(writer))
+                }
                 RdfSerializerKind::RdfXml(s) => {
                     TokioAsyncWriterQuadSerializerKind::RdfXml(s.for_tokio_async_writer(writer))
                 }
                 RdfSerializerKind::TriG(s) => {
                     TokioAsyncWriterQuadSerializerKind::TriG(s.for_tokio_async_writer(writer))
                 }
+                RdfSerializerKind::TriX(s) => {
+                    TokioAsyncWriterQuadSerializerKind::TriX(s.for_tokio_async_writer(writer))
+                }
                 RdfSerializerKind::Turtle(s) => {
                     TokioAsyncWriterQuadSerializerKind::Turtle(s.for_tokio_async_writer(writer))
                 }
@@ -327,11 +473,14 @@ pub struct WriterQuadSerializer<W: Write> {
 }
 
 enum WriterQuadSerializerKind<W: Write> {
+    Hdt(WriterHdtSerializer<W>),
     JsonLd(WriterJsonLdSerializer<W>),
     NQuads(WriterNQuadsSerializer<W>),
     NTriples(WriterNTriplesSerializer<W>),
+    RdfJson(WriterRdfJsonSerializer<W>),
     RdfXml(WriterRdfXmlSerializer<W>),
     TriG(WriterTriGSerializer<W>),
+    TriX(WriterTriXSerializer<W>),
     Turtle(WriterTurtleSerializer<W>),
 }
 
@@ -339,15 +488,22 @@ impl<W: Write> WriterQuadSerializer<W> {
     /// Serializes a [`QuadRef`]
     pub fn serialize_quad<'a>(&mut self, quad: impl Into<QuadRef<'a>>) -> io::Result<()> {
         match &mut self.inner {
+            WriterQuadSerializerKind::Hdt(serializer) => {
+                serializer.serialize_triple(to_triple(quad)?)
+            }
             WriterQuadSerializerKind::JsonLd(serializer) => serializer.serialize_quad(quad),
             WriterQuadSerializerKind::NQuads(serializer) => serializer.serialize_quad(quad),
             WriterQuadSerializerKind::NTriples(serializer) => {
                 serializer.serialize_triple(to_triple(quad)?)
             }
+            WriterQuadSerializerKind::RdfJson(serializer) => {
+                serializer.serialize_triple(to_triple(quad)?)
+            }
             WriterQuadSerializerKind::RdfXml(serializer) => {
                 serializer.serialize_triple(to_triple(quad)?)
             }
             WriterQuadSerializerKind::TriG(serializer) => serializer.serialize_quad(quad),
+            WriterQuadSerializerKind::TriX(serializer) => serializer.serialize_quad(quad),
             WriterQuadSerializerKind::Turtle(serializer) => {
                 serializer.serialize_triple(to_triple(quad)?)
             }
@@ -364,11 +520,14 @@ impl<W: Write> WriterQuadSerializer<W> {
     /// Note that this function does not flush the writer. You need to do that if you are using a [`BufWriter`](io::BufWriter).
     pub fn finish(self) -> io::Result<W> {
         Ok(match self.inner {
+            WriterQuadSerializerKind::Hdt(serializer) => serializer.finish()?,
             WriterQuadSerializerKind::JsonLd(serializer) => serializer.finish()?,
             WriterQuadSerializerKind::NQuads(serializer) => serializer.finish(),
             WriterQuadSerializerKind::NTriples(serializer) => serializer.finish(),
+            WriterQuadSerializerKind::RdfJson(serializer) => serializer.finish()?,
             WriterQuadSerializerKind::RdfXml(serializer) => serializer.finish()?,
             WriterQuadSerializerKind::TriG(serializer) => serializer.finish()?,
+            WriterQuadSerializerKind::TriX(serializer) => serializer.finish()?,
             WriterQuadSerializerKind::Turtle(serializer) => serializer.finish()?,
         })
     }
@@ -411,11 +570,14 @@ pub struct TokioAsyncWriterQuadSerializer<W: AsyncWrite + Unpin> {
 
 #[cfg(feature = "async-tokio")]
 enum TokioAsyncWriterQuadSerializerKind<W: AsyncWrite + Unpin> {
+    Hdt(TokioAsyncWriterHdtSerializer<W>),
     JsonLd(TokioAsyncWriterJsonLdSerializer<W>),
     NQuads(TokioAsyncWriterNQuadsSerializer<W>),
     NTriples(TokioAsyncWriterNTriplesSerializer<W>),
+    RdfJson(TokioAsyncWriterRdfJsonSerializer<W>),
     RdfXml(TokioAsyncWriterRdfXmlSerializer<W>),
     TriG(TokioAsyncWriterTriGSerializer<W>),
+    TriX(TokioAsyncWriterTriXSerializer<W>),
     Turtle(TokioAsyncWriterTurtleSerializer<W>),
 }
 
@@ -424,6 +586,9 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterQuadSerializer<W> {
     /// Serializes a [`QuadRef`]
     pub async fn serialize_quad<'a>(&mut self, quad: impl Into<QuadRef<'a>>) -> io::Result<()> {
         match &mut self.inner {
+            TokioAsyncWriterQuadSerializerKind::Hdt(serializer) => {
+                serializer.serialize_triple(to_triple(quad)?).await
+            }
             TokioAsyncWriterQuadSerializerKind::JsonLd(serializer) => {
                 serializer.serialize_quad(quad).await
             }
@@ -433,12 +598,18 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterQuadSerializer<W> {
             TokioAsyncWriterQuadSerializerKind::NTriples(serializer) => {
                 serializer.serialize_triple(to_triple(quad)?).await
             }
+            TokioAsyncWriterQuadSerializerKind::RdfJson(serializer) => {
+                serializer.serialize_triple(to_triple(quad)?)
+            }
             TokioAsyncWriterQuadSerializerKind::RdfXml(serializer) => {
                 serializer.serialize_triple(to_triple(quad)?).await
             }
             TokioAsyncWriterQuadSerializerKind::TriG(serializer) => {
                 serializer.serialize_quad(quad).await
             }
+            TokioAsyncWriterQuadSerializerKind::TriX(serializer) => {
+                serializer.serialize_quad(quad).await
+            }
             TokioAsyncWriterQuadSerializerKind::Turtle(serializer) => {
                 serializer.serialize_triple(to_triple(quad)?).await
             }
@@ -459,17 +630,180 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterQuadSerializer<W> {
     /// Note that this function does not flush the writer. You need to do that if you are using a [`BufWriter`](io::BufWriter).
     pub async fn finish(self) -> io::Result<W> {
         Ok(match self.inner {
+            TokioAsyncWriterQuadSerializerKind::Hdt(serializer) => serializer.finish().await?,
             TokioAsyncWriterQuadSerializerKind::JsonLd(serializer) => serializer.finish().await?,
             TokioAsyncWriterQuadSerializerKind::NQuads(serializer) => serializer.finish(),
             TokioAsyncWriterQuadSerializerKind::NTriples(serializer) => serializer.finish(),
+            TokioAsyncWriterQuadSerializerKind::RdfJson(serializer) => serializer.finish().await?,
             TokioAsyncWriterQuadSerializerKind::RdfXml(serializer) => serializer.finish().await?,
             TokioAsyncWriterQuadSerializerKind::TriG(serializer) => serializer.finish().await?,
+            TokioAsyncWriterQuadSerializerKind::TriX(serializer) => serializer.finish().await?,
             TokioAsyncWriterQuadSerializerKind::Turtle(serializer) => serializer.finish().await?,
         })
     }
 }
 
-fn to_triple<'a>(quad: impl Into<QuadRef<'a>>) -> io::Result<TripleRef<'a>> {
+/// A shard of the output of a [`ShardedQuadSerializer`].
+#[derive(Debug, Clone, Copy)]
+pub struct Shard<'a> {
+    /// Position of the shard in the output, starting at 0
+    pub index: usize,
+    /// Graph of the quads of the shard if the output is split by graph
+    pub graph_name: Option<GraphNameRef<'a>>,
+}
+
+/// Serializes quads or triples to multiple [`Write`] implementations, each one getting a full document.
+///
+/// Can be built using [`RdfSerializer::for_shards`].
+///
+/// <div class="warning">
+///
+/// Do not forget to run the [`finish`](ShardedQuadSerializer::finish()) method to properly write the last bytes of the last shard.</div>
+///
+/// Splitting by graph, one file per named graph:
+/// ```
+/// use oxrdf::{GraphNameRef, NamedNodeRef, QuadRef};
+/// use oxrdfio::{RdfFormat, RdfSerializer};
+///
+/// let ex = NamedNodeRef::new("http://example.com")?;
+/// let mut graphs = Vec::new();
+/// let mut serializer = RdfSerializer::from_format(RdfFormat::NTriples)
+///     .for_shards(|shard| {
+///         graphs.push(shard.graph_name.map(GraphNameRef::into_owned));
+///         Ok(Vec::new())
+///     })
+///     .with_shard_per_graph();
+/// serializer.serialize_quad(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))?;
+/// serializer.serialize_quad(QuadRef::new(ex, ex, ex, ex))?;
+/// let shards = serializer.finish()?;
+/// assert_eq!(
+///     graphs,
+///     [Some(GraphNameRef::DefaultGraph.into()), Some(ex.into())]
+/// );
+/// assert_eq!(
+///     shards[1],
+///     b"<http://example.com> <http://example.com> <http://example.com> .\n"
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+pub struct ShardedQuadSerializer<W: Write, F: FnMut(Shard<'_>) -> io::Result<W>> {
+    serializer: RdfSerializer,
+    open_shard: F,
+    max_shard_size: Option<u64>,
+    shard_per_graph: bool,
+    current: Option<CurrentShard<W>>,
+    finished: Vec<W>,
+}
+
+struct CurrentShard<W: Write> {
+    serializer: WriterQuadSerializer<SizeCountingWriter<W>>,
+    graph_name: Option<GraphName>,
+    size: Arc<AtomicU64>,
+}
+
+impl<W: Write, F: FnMut(Shard<'_>) -> io::Result<W>> ShardedQuadSerializer<W, F> {
+    /// Starts a new shard once the current one is at least `max_size` bytes long.
+    ///
+    /// The shards might be larger than `max_size` by the size of a quad and of the end of the document.
+    #[inline]
+    pub fn with_max_shard_size(mut self, max_size: u64) -> Self {
+        self.max_shard_size = Some(max_size);
+        self
+    }
+
+    /// Writes the quads of each graph in their own shards.
+    ///
+    /// A new shard is started each time the graph changes: the quads should be grouped by graph.
+    /// If the format does not support datasets, the quads are written as triples.
+    #[inline]
+    pub fn with_shard_per_graph(mut self) -> Self {
+        self.shard_per_graph = true;
+        self
+    }
+
+    /// Serializes a [`QuadRef`]
+    pub fn serialize_quad<'a>(&mut self, quad: impl Into<QuadRef<'a>>) -> io::Result<()> {
+        let quad = quad.into();
+        let graph_name = self.shard_per_graph.then_some(quad.graph_name);
+        if let Some(current) = &self.current {
+            if current.graph_name.as_ref().map(GraphName::as_ref) != graph_name
+                || self
+                    .max_shard_size
+                    .is_some_and(|max_size| current.size.load(Ordering::Relaxed) >= max_size)
+            {
+                self.finish_shard()?;
+            }
+        }
+        let mut current = if let Some(current) = self.current.take() {
+            current
+        } else {
+            self.open_shard(graph_name)?
+        };
+        let result = if self.shard_per_graph && !self.serializer.format().supports_datasets() {
+            current.serializer.serialize_triple(quad)
+        } else {
+            current.serializer.serialize_quad(quad)
+        };
+        self.current = Some(current);
+        result
+    }
+
+    /// Serializes a [`TripleRef`]
+    pub fn serialize_triple<'a>(&mut self, triple: impl Into<TripleRef<'a>>) -> io::Result<()> {
+        self.serialize_quad(triple.into().in_graph(GraphNameRef::DefaultGraph))
+    }
+
+    /// Writes the last bytes of the last shard and returns the writers of all the shards
+    ///
+    /// Note that this function does not flush the writers. You need to do that if you are using a [`BufWriter`](io::BufWriter).
+    pub fn finish(mut self) -> io::Result<Vec<W>> {
+        self.finish_shard()?;
+        Ok(self.finished)
+    }
+
+    fn open_shard(&mut self, graph_name: Option<GraphNameRef<'_>>) -> io::Result<CurrentShard<W>> {
+        let writer = (self.open_shard)(Shard {
+            index: self.finished.len(),
+            graph_name,
+        })?;
+        let size = Arc::new(AtomicU64::new(0));
+        Ok(CurrentShard {
+            serializer: self.serializer.clone().for_writer(SizeCountingWriter {
+                inner: writer,
+                size: Arc::clone(&size),
+            }),
+            graph_name: graph_name.map(GraphNameRef::into_owned),
+            size,
+        })
+    }
+
+    fn finish_shard(&mut self) -> io::Result<()> {
+        if let Some(current) = self.current.take() {
+            self.finished.push(current.serializer.finish()?.inner);
+        }
+        Ok(())
+    }
+}
+
+struct SizeCountingWriter<W: Write> {
+    inner: W,
+    size: Arc<AtomicU64>,
+}
+
+impl<W: Write> Write for SizeCountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.size.fetch_add(written as u64, Ordering::Relaxed);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub(crate) fn to_triple<'a>(quad: impl Into<QuadRef<'a>>) -> io::Result<TripleRef<'a>> {
     let quad = quad.into();
     if quad.graph_name.is_default_graph() {
         Ok(quad.into())
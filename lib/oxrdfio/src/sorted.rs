@@ -0,0 +1,242 @@
+//! Serialization of quads in a canonical order using an external sort.
+
+use crate::serializer::{RdfSerializer, to_triple};
+use oxrdf::{GraphNameRef, Quad, QuadRef, TripleRef};
+use oxttl::nquads::{NQuadsParser, NQuadsSerializer, ReaderNQuadsParser};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::{env, process};
+
+/// Default amount of memory used to sort the quads before writing sorted runs to temporary files
+const DEFAULT_MAX_MEMORY_SIZE: usize = 256 * 1024 * 1024;
+
+/// Counter to give a unique name to the temporary files of the process
+static RUN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// The graph name, subject, predicate and object of a quad in N-Quads syntax, the default graph being the empty string
+type SortKey = [String; 4];
+
+/// Serializes quads or triples sorted by graph name, subject, predicate and object to a [`Write`] implementation.
+///
+/// The terms are compared using their N-Quads serialization and the quads of the default graph come first.
+/// Duplicated quads are written only once.
+/// The quads are sorted in memory and, if they do not fit in [the memory limit](Self::with_max_memory_size),
+/// in sorted runs written to temporary files that are merged by [`finish`](Self::finish).
+///
+/// Can be built using [`RdfSerializer::for_sorted_writer`].
+///
+/// <div class="warning">
+///
+/// Nothing is written before the [`finish`](SortedQuadSerializer::finish()) method is called.</div>
+///
+/// ```
+/// use oxrdf::{NamedNodeRef, TripleRef};
+/// use oxrdfio::{RdfFormat, RdfSerializer};
+///
+/// let mut serializer = RdfSerializer::from_format(RdfFormat::NTriples)
+///     .for_sorted_writer(Vec::new())
+///     .with_max_memory_size(100);
+/// for s in ["http://example.com/s2", "http://example.com/s1", "http://example.com/s2"] {
+///     serializer.serialize_triple(TripleRef::new(
+///         NamedNodeRef::new(s)?,
+///         NamedNodeRef::new("http://example.com/p")?,
+///         NamedNodeRef::new("http://example.com/o")?,
+///     ))?;
+/// }
+/// assert_eq!(
+///     serializer.finish()?,
+///     b"<http://example.com/s1> <http://example.com/p> <http://example.com/o> .\n<http://example.com/s2> <http://example.com/p> <http://example.com/o> .\n"
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+pub struct SortedQuadSerializer<W: Write> {
+    serializer: RdfSerializer,
+    writer: W,
+    max_memory_size: usize,
+    temp_dir: Option<PathBuf>,
+    buffer: Vec<(SortKey, Quad)>,
+    buffer_size: usize,
+    runs: Vec<SortRun>,
+}
+
+impl<W: Write> SortedQuadSerializer<W> {
+    pub(crate) fn new(serializer: RdfSerializer, writer: W) -> Self {
+        Self {
+            serializer,
+            writer,
+            max_memory_size: DEFAULT_MAX_MEMORY_SIZE,
+            temp_dir: None,
+            buffer: Vec::new(),
+            buffer_size: 0,
+            runs: Vec::new(),
+        }
+    }
+
+    /// Sets a rough idea of the maximal amount of memory in bytes used to sort the quads in memory.
+    ///
+    /// When it is reached, the sorted quads are written to a temporary file.
+    /// By default, 256MB are used.
+    #[inline]
+    pub fn with_max_memory_size(mut self, max_size: usize) -> Self {
+        self.max_memory_size = max_size;
+        self
+    }
+
+    /// Sets the directory in which the temporary files are written.
+    ///
+    /// By default, the [system temporary directory](env::temp_dir) is used.
+    #[inline]
+    pub fn with_temp_dir(mut self, temp_dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = Some(temp_dir.into());
+        self
+    }
+
+    /// Serializes a [`QuadRef`]
+    pub fn serialize_quad<'a>(&mut self, quad: impl Into<QuadRef<'a>>) -> io::Result<()> {
+        let quad = quad.into();
+        if !self.serializer.format().supports_datasets() {
+            to_triple(quad)?;
+        }
+        let key = sort_key(quad);
+        // The key and the quad contain the same strings
+        self.buffer_size +=
+            2 * key.iter().map(String::len).sum::<usize>() + size_of::<(SortKey, Quad)>();
+        self.buffer.push((key, quad.into_owned()));
+        if self.buffer_size >= self.max_memory_size {
+            self.write_run()?;
+        }
+        Ok(())
+    }
+
+    /// Serializes a [`TripleRef`]
+    pub fn serialize_triple<'a>(&mut self, triple: impl Into<TripleRef<'a>>) -> io::Result<()> {
+        self.serialize_quad(triple.into().in_graph(GraphNameRef::DefaultGraph))
+    }
+
+    /// Writes the sorted quads and returns the underlying writer.
+    ///
+    /// Note that this function does not flush the writer. You need to do that if you are using a [`BufWriter`](io::BufWriter).
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.runs.is_empty() {
+            sort_and_deduplicate(&mut self.buffer);
+            let mut serializer = self.serializer.for_writer(self.writer);
+            for (_, quad) in &self.buffer {
+                serializer.serialize_quad(quad)?;
+            }
+            return serializer.finish();
+        }
+        if !self.buffer.is_empty() {
+            self.write_run()?;
+        }
+
+        // We merge the sorted runs
+        let mut readers = self
+            .runs
+            .iter()
+            .map(|run| {
+                Ok(NQuadsParser::new()
+                    .lenient()
+                    .for_reader(BufReader::new(File::open(&run.path)?)))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut heads = Vec::with_capacity(readers.len());
+        let mut heap = BinaryHeap::with_capacity(readers.len());
+        for (i, reader) in readers.iter_mut().enumerate() {
+            let head = next_quad(reader)?;
+            if let Some(quad) = &head {
+                heap.push(Reverse((sort_key(quad.as_ref()), i)));
+            }
+            heads.push(head);
+        }
+        let mut serializer = self.serializer.for_writer(self.writer);
+        let mut last_key = None;
+        while let Some(Reverse((key, i))) = heap.pop() {
+            let Some(quad) = heads[i].take() else {
+                continue;
+            };
+            if let Some(next) = next_quad(&mut readers[i])? {
+                heap.push(Reverse((sort_key(next.as_ref()), i)));
+                heads[i] = Some(next);
+            }
+            if last_key.as_ref() != Some(&key) {
+                serializer.serialize_quad(&quad)?;
+                last_key = Some(key);
+            }
+        }
+        serializer.finish()
+    }
+
+    /// Writes the buffered quads sorted to a new temporary file
+    fn write_run(&mut self) -> io::Result<()> {
+        sort_and_deduplicate(&mut self.buffer);
+        let (run, file) = SortRun::create(&self.temp_dir.clone().unwrap_or_else(env::temp_dir))?;
+        let mut serializer = NQuadsSerializer::new().for_writer(BufWriter::new(file));
+        for (_, quad) in self.buffer.drain(..) {
+            serializer.serialize_quad(&quad)?;
+        }
+        serializer
+            .finish()
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?;
+        self.buffer_size = 0;
+        self.runs.push(run);
+        Ok(())
+    }
+}
+
+/// A temporary file containing sorted quads, removed on drop
+struct SortRun {
+    path: PathBuf,
+}
+
+impl SortRun {
+    fn create(temp_dir: &Path) -> io::Result<(Self, File)> {
+        loop {
+            let path = temp_dir.join(format!(
+                "oxrdfio-sort-{}-{}.nq",
+                process::id(),
+                RUN_COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            match File::create_new(&path) {
+                Ok(file) => return Ok((Self { path }, file)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => (),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for SortRun {
+    fn drop(&mut self) {
+        // The file is only removed on a best effort basis
+        drop(fs::remove_file(&self.path));
+    }
+}
+
+fn sort_key(quad: QuadRef<'_>) -> SortKey {
+    [
+        if quad.graph_name.is_default_graph() {
+            String::new()
+        } else {
+            quad.graph_name.to_string()
+        },
+        quad.subject.to_string(),
+        quad.predicate.to_string(),
+        quad.object.to_string(),
+    ]
+}
+
+fn sort_and_deduplicate(buffer: &mut Vec<(SortKey, Quad)>) {
+    buffer.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    buffer.dedup_by(|(a, _), (b, _)| a == b);
+}
+
+fn next_quad(reader: &mut ReaderNQuadsParser<BufReader<File>>) -> io::Result<Option<Quad>> {
+    reader.next().transpose().map_err(io::Error::from)
+}
@@ -7,6 +7,7 @@
 mod error;
 mod parser;
 mod serializer;
+mod trix;
 mod utils;
 
 pub use error::{RdfXmlParseError, RdfXmlSyntaxError};
@@ -16,3 +17,8 @@ pub use parser::{RdfXmlParser, RdfXmlPrefixesIter, ReaderRdfXmlParser, SliceRdfX
 #[cfg(feature = "async-tokio")]
 pub use serializer::TokioAsyncWriterRdfXmlSerializer;
 pub use serializer::{RdfXmlSerializer, WriterRdfXmlSerializer};
+pub use trix::{
+    ReaderTriXParser, SliceTriXParser, TriXParser, TriXSerializer, WriterTriXSerializer,
+};
+#[cfg(feature = "async-tokio")]
+pub use trix::{TokioAsyncReaderTriXParser, TokioAsyncWriterTriXSerializer};
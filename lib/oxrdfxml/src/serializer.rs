@@ -517,7 +517,7 @@ impl InnerRdfXmlWriter {
 }
 
 #[cfg(feature = "async-tokio")]
-fn map_err(error: quick_xml::Error) -> io::Error {
+pub(crate) fn map_err(error: quick_xml::Error) -> io::Error {
     if let quick_xml::Error::Io(error) = error {
         Arc::try_unwrap(error).unwrap_or_else(|error| io::Error::new(error.kind(), error))
     } else {
@@ -0,0 +1,973 @@
+use crate::error::{RdfXmlParseError, RdfXmlSyntaxError};
+#[cfg(feature = "async-tokio")]
+use crate::serializer::map_err;
+use oxilangtag::LanguageTag;
+use oxiri::Iri;
+use oxrdf::vocab::xsd;
+use oxrdf::{
+    BlankNode, GraphName, GraphNameRef, Literal, NamedNode, NamedOrBlankNode, Quad, QuadRef, Term,
+    TermRef,
+};
+use quick_xml::events::*;
+use quick_xml::name::ResolveResult;
+use quick_xml::{Error, NsReader, Writer};
+use std::io::{self, BufReader, Read, Write};
+#[cfg(feature = "async-tokio")]
+use tokio::io::{AsyncRead, AsyncWrite, BufReader as AsyncBufReader};
+
+const TRIX_NAMESPACE: &str = "http://www.w3.org/2004/03/trix/trix-1/";
+
+/// A [TriX](https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html) streaming parser.
+///
+/// TriX is a XML serialization of RDF datasets where each graph is a `<graph>` element containing `<triple>` elements.
+///
+/// Elements without namespace are also accepted because some TriX writers do not set the TriX namespace.
+/// The errors are reported as [`RdfXmlParseError`] and [`RdfXmlSyntaxError`].
+///
+/// Count the number of people:
+/// ```
+/// use oxrdf::NamedNodeRef;
+/// use oxrdf::vocab::rdf;
+/// use oxrdfxml::TriXParser;
+///
+/// let file = br#"<?xml version="1.0"?>
+/// <TriX xmlns="http://www.w3.org/2004/03/trix/trix-1/">
+///  <graph>
+///   <uri>http://example.com/graph</uri>
+///   <triple>
+///    <uri>http://example.com/foo</uri>
+///    <uri>http://www.w3.org/1999/02/22-rdf-syntax-ns#type</uri>
+///    <uri>http://schema.org/Person</uri>
+///   </triple>
+///   <triple>
+///    <uri>http://example.com/foo</uri>
+///    <uri>http://schema.org/name</uri>
+///    <plainLiteral xml:lang="en">Foo</plainLiteral>
+///   </triple>
+///  </graph>
+/// </TriX>"#;
+///
+/// let schema_person = NamedNodeRef::new("http://schema.org/Person")?;
+/// let mut count = 0;
+/// for quad in TriXParser::new().for_reader(file.as_ref()) {
+///     let quad = quad?;
+///     if quad.predicate == rdf::TYPE && quad.object == schema_person.into() {
+///         count += 1;
+///     }
+/// }
+/// assert_eq!(1, count);
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+#[must_use]
+pub struct TriXParser {
+    lenient: bool,
+}
+
+impl TriXParser {
+    /// Builds a new [`TriXParser`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assumes the file is valid to make parsing faster.
+    ///
+    /// It will skip some validations.
+    ///
+    /// Note that if the file is actually not valid, the parser might emit broken RDF.
+    #[inline]
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Parses a TriX file from a [`Read`] implementation.
+    ///
+    /// ```
+    /// use oxrdfxml::TriXParser;
+    ///
+    /// let file = br#"<TriX xmlns="http://www.w3.org/2004/03/trix/trix-1/">
+    ///  <graph>
+    ///   <triple>
+    ///    <id>foo</id>
+    ///    <uri>http://schema.org/name</uri>
+    ///    <plainLiteral>Foo</plainLiteral>
+    ///   </triple>
+    ///  </graph>
+    /// </TriX>"#;
+    ///
+    /// let quads = TriXParser::new()
+    ///     .for_reader(file.as_ref())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(quads.len(), 1);
+    /// assert!(quads[0].graph_name.is_default_graph());
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_reader<R: Read>(self, reader: R) -> ReaderTriXParser<R> {
+        ReaderTriXParser {
+            results: Vec::new(),
+            parser: self.into_internal(BufReader::new(reader)),
+            reader_buffer: Vec::default(),
+        }
+    }
+
+    /// Parses a TriX file from a [`AsyncRead`] implementation.
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use oxrdfxml::TriXParser;
+    ///
+    /// let file = br#"<TriX xmlns="http://www.w3.org/2004/03/trix/trix-1/">
+    ///  <graph>
+    ///   <triple>
+    ///    <id>foo</id>
+    ///    <uri>http://schema.org/name</uri>
+    ///    <plainLiteral>Foo</plainLiteral>
+    ///   </triple>
+    ///  </graph>
+    /// </TriX>"#;
+    ///
+    /// let mut parser = TriXParser::new().for_tokio_async_reader(file.as_ref());
+    /// let mut count = 0;
+    /// while let Some(quad) = parser.next().await {
+    ///     quad?;
+    ///     count += 1;
+    /// }
+    /// assert_eq!(count, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async-tokio")]
+    pub fn for_tokio_async_reader<R: AsyncRead + Unpin>(
+        self,
+        reader: R,
+    ) -> TokioAsyncReaderTriXParser<R> {
+        TokioAsyncReaderTriXParser {
+            results: Vec::new(),
+            parser: self.into_internal(AsyncBufReader::new(reader)),
+            reader_buffer: Vec::default(),
+        }
+    }
+
+    /// Parses a TriX file from a byte slice.
+    ///
+    /// ```
+    /// use oxrdfxml::TriXParser;
+    ///
+    /// let file = br#"<TriX xmlns="http://www.w3.org/2004/03/trix/trix-1/">
+    ///  <graph>
+    ///   <triple>
+    ///    <id>foo</id>
+    ///    <uri>http://schema.org/name</uri>
+    ///    <plainLiteral>Foo</plainLiteral>
+    ///   </triple>
+    ///  </graph>
+    /// </TriX>"#;
+    ///
+    /// let quads = TriXParser::new()
+    ///     .for_slice(file)
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(quads.len(), 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_slice(self, slice: &[u8]) -> SliceTriXParser<'_> {
+        SliceTriXParser {
+            results: Vec::new(),
+            parser: self.into_internal(slice),
+            reader_buffer: Vec::default(),
+        }
+    }
+
+    fn into_internal<T>(self, reader: T) -> InternalTriXParser<T> {
+        let mut reader = NsReader::from_reader(reader);
+        reader.config_mut().expand_empty_elements = true;
+        InternalTriXParser {
+            reader,
+            state: vec![TriXState::Doc],
+            is_end: false,
+            lenient: self.lenient,
+        }
+    }
+}
+
+/// Parses a TriX file from a [`Read`] implementation.
+///
+/// Can be built using [`TriXParser::for_reader`].
+#[must_use]
+pub struct ReaderTriXParser<R: Read> {
+    results: Vec<Quad>,
+    parser: InternalTriXParser<BufReader<R>>,
+    reader_buffer: Vec<u8>,
+}
+
+impl<R: Read> Iterator for ReaderTriXParser<R> {
+    type Item = Result<Quad, RdfXmlParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(quad) = self.results.pop() {
+                return Some(Ok(quad));
+            } else if self.parser.is_end {
+                return None;
+            }
+            if let Err(e) = self.parse_step() {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl<R: Read> ReaderTriXParser<R> {
+    /// The current byte position in the input data.
+    pub fn buffer_position(&self) -> u64 {
+        self.parser.reader.buffer_position()
+    }
+
+    fn parse_step(&mut self) -> Result<(), RdfXmlParseError> {
+        self.reader_buffer.clear();
+        let event = self
+            .parser
+            .reader
+            .read_event_into(&mut self.reader_buffer)?;
+        self.parser.parse_event(event, &mut self.results)
+    }
+}
+
+/// Parses a TriX file from a [`AsyncRead`] implementation.
+///
+/// Can be built using [`TriXParser::for_tokio_async_reader`].
+#[cfg(feature = "async-tokio")]
+#[must_use]
+pub struct TokioAsyncReaderTriXParser<R: AsyncRead + Unpin> {
+    results: Vec<Quad>,
+    parser: InternalTriXParser<AsyncBufReader<R>>,
+    reader_buffer: Vec<u8>,
+}
+
+#[cfg(feature = "async-tokio")]
+impl<R: AsyncRead + Unpin> TokioAsyncReaderTriXParser<R> {
+    /// Reads the next quad or returns `None` if the file is finished.
+    pub async fn next(&mut self) -> Option<Result<Quad, RdfXmlParseError>> {
+        loop {
+            if let Some(quad) = self.results.pop() {
+                return Some(Ok(quad));
+            } else if self.parser.is_end {
+                return None;
+            }
+            if let Err(e) = self.parse_step().await {
+                return Some(Err(e));
+            }
+        }
+    }
+
+    /// The current byte position in the input data.
+    pub fn buffer_position(&self) -> u64 {
+        self.parser.reader.buffer_position()
+    }
+
+    async fn parse_step(&mut self) -> Result<(), RdfXmlParseError> {
+        self.reader_buffer.clear();
+        let event = self
+            .parser
+            .reader
+            .read_event_into_async(&mut self.reader_buffer)
+            .await?;
+        self.parser.parse_event(event, &mut self.results)
+    }
+}
+
+/// Parses a TriX file from a byte slice.
+///
+/// Can be built using [`TriXParser::for_slice`].
+#[must_use]
+pub struct SliceTriXParser<'a> {
+    results: Vec<Quad>,
+    parser: InternalTriXParser<&'a [u8]>,
+    reader_buffer: Vec<u8>,
+}
+
+impl Iterator for SliceTriXParser<'_> {
+    type Item = Result<Quad, RdfXmlSyntaxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(quad) = self.results.pop() {
+                return Some(Ok(quad));
+            } else if self.parser.is_end {
+                return None;
+            }
+            if let Err(RdfXmlParseError::Syntax(e)) = self.parse_step() {
+                // I/O errors can't happen
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl SliceTriXParser<'_> {
+    /// The current byte position in the input data.
+    pub fn buffer_position(&self) -> u64 {
+        self.parser.reader.buffer_position()
+    }
+
+    fn parse_step(&mut self) -> Result<(), RdfXmlParseError> {
+        self.reader_buffer.clear();
+        let event = self
+            .parser
+            .reader
+            .read_event_into(&mut self.reader_buffer)?;
+        self.parser.parse_event(event, &mut self.results)
+    }
+}
+
+enum TermKind {
+    Uri,
+    Id,
+    PlainLiteral { language: Option<String> },
+    TypedLiteral { datatype: NamedNode },
+}
+
+enum TriXState {
+    Doc,
+    TriX,
+    Graph {
+        name: Option<GraphName>,
+        has_triple: bool,
+    },
+    Triple {
+        terms: Vec<Term>,
+    },
+    Term {
+        kind: TermKind,
+        value: String,
+    },
+}
+
+struct InternalTriXParser<R> {
+    reader: NsReader<R>,
+    state: Vec<TriXState>,
+    is_end: bool,
+    lenient: bool,
+}
+
+impl<R> InternalTriXParser<R> {
+    fn parse_event(
+        &mut self,
+        event: Event<'_>,
+        results: &mut Vec<Quad>,
+    ) -> Result<(), RdfXmlParseError> {
+        match event {
+            Event::Start(event) => self.parse_start_event(&event),
+            Event::End(_) => {
+                if let Some(current_state) = self.state.pop() {
+                    self.end_state(current_state, results)?;
+                }
+                Ok(())
+            }
+            Event::Empty(_) => Err(RdfXmlSyntaxError::msg(
+                "The expand_empty_elements option must be enabled",
+            )
+            .into()),
+            Event::Text(event) => self.parse_text(&event.unescape()?),
+            Event::CData(event) => self.parse_text(&self.reader.decoder().decode(&event)?),
+            Event::Comment(_) | Event::PI(_) | Event::DocType(_) => Ok(()),
+            Event::Decl(decl) => {
+                if let Some(encoding) = decl.encoding() {
+                    if !encoding?.eq_ignore_ascii_case(b"utf-8") {
+                        return Err(RdfXmlSyntaxError::msg(
+                            "Only UTF-8 is supported by the TriX parser",
+                        )
+                        .into());
+                    }
+                }
+                Ok(())
+            }
+            Event::Eof => {
+                if self.state.len() > 1 {
+                    return Err(RdfXmlSyntaxError::msg(
+                        "Unexpected end of file, the TriX element is not closed",
+                    )
+                    .into());
+                }
+                self.is_end = true;
+                Ok(())
+            }
+        }
+    }
+
+    fn parse_start_event(&mut self, event: &BytesStart<'_>) -> Result<(), RdfXmlParseError> {
+        let (namespace, local_name) = self.reader.resolve_element(event.name());
+        let is_trix_element = match namespace {
+            ResolveResult::Bound(namespace) => namespace.as_ref() == TRIX_NAMESPACE.as_bytes(),
+            ResolveResult::Unbound => true,
+            ResolveResult::Unknown(_) => false,
+        };
+        let local_name = local_name.as_ref();
+        let term_kind = if is_trix_element {
+            self.parse_term_kind(local_name, event)?
+        } else {
+            None
+        };
+        let new_state = match (self.state.last_mut(), term_kind) {
+            (Some(TriXState::Doc), None) if is_trix_element && local_name == b"TriX" => {
+                TriXState::TriX
+            }
+            (Some(TriXState::TriX), None) if is_trix_element && local_name == b"graph" => {
+                TriXState::Graph {
+                    name: None,
+                    has_triple: false,
+                }
+            }
+            (Some(TriXState::Graph { has_triple, .. }), None)
+                if is_trix_element && local_name == b"triple" =>
+            {
+                *has_triple = true;
+                TriXState::Triple {
+                    terms: Vec::with_capacity(3),
+                }
+            }
+            (
+                Some(TriXState::Graph {
+                    name: None,
+                    has_triple: false,
+                }),
+                Some(kind @ (TermKind::Uri | TermKind::Id)),
+            )
+            | (Some(TriXState::Triple { .. }), Some(kind)) => TriXState::Term {
+                kind,
+                value: String::new(),
+            },
+            _ => {
+                return Err(RdfXmlSyntaxError::msg(format!(
+                    "Unexpected element <{}>",
+                    self.reader.decoder().decode(event.name().as_ref())?
+                ))
+                .into());
+            }
+        };
+        self.state.push(new_state);
+        Ok(())
+    }
+
+    fn parse_term_kind(
+        &self,
+        local_name: &[u8],
+        event: &BytesStart<'_>,
+    ) -> Result<Option<TermKind>, RdfXmlParseError> {
+        Ok(Some(match local_name {
+            b"uri" => TermKind::Uri,
+            b"id" => TermKind::Id,
+            b"plainLiteral" => {
+                let mut language = None;
+                for attribute in event.attributes() {
+                    let attribute = attribute.map_err(Error::InvalidAttr)?;
+                    if attribute.key.as_ref() == b"xml:lang" {
+                        let tag = attribute
+                            .decode_and_unescape_value(self.reader.decoder())?
+                            .to_ascii_lowercase();
+                        language = Some(if self.lenient {
+                            tag
+                        } else {
+                            LanguageTag::parse(tag.clone())
+                                .map_err(|error| {
+                                    RdfXmlSyntaxError::invalid_language_tag(tag, error)
+                                })?
+                                .into_inner()
+                        });
+                    }
+                }
+                TermKind::PlainLiteral { language }
+            }
+            b"typedLiteral" => {
+                let mut datatype = None;
+                for attribute in event.attributes() {
+                    let attribute = attribute.map_err(Error::InvalidAttr)?;
+                    if attribute.key.as_ref() == b"datatype" {
+                        datatype = Some(
+                            self.parse_iri(
+                                attribute
+                                    .decode_and_unescape_value(self.reader.decoder())?
+                                    .into_owned(),
+                            )?,
+                        );
+                    }
+                }
+                TermKind::TypedLiteral {
+                    datatype: datatype.ok_or_else(|| {
+                        RdfXmlSyntaxError::msg("<typedLiteral> must have a datatype attribute")
+                    })?,
+                }
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    fn parse_text(&mut self, text: &str) -> Result<(), RdfXmlParseError> {
+        if let Some(TriXState::Term { value, .. }) = self.state.last_mut() {
+            value.push_str(text);
+            Ok(())
+        } else if text.bytes().all(is_whitespace) {
+            Ok(())
+        } else {
+            Err(RdfXmlSyntaxError::msg(format!("Unexpected text event: '{text}'")).into())
+        }
+    }
+
+    #[allow(
+        unreachable_patterns,
+        clippy::match_wildcard_for_single_variants,
+        clippy::allow_attributes
+    )]
+    fn end_state(
+        &mut self,
+        state: TriXState,
+        results: &mut Vec<Quad>,
+    ) -> Result<(), RdfXmlParseError> {
+        match state {
+            TriXState::Term { kind, value } => {
+                let term = self.build_term(kind, value)?;
+                match self.state.last_mut() {
+                    Some(TriXState::Graph { name, .. }) => {
+                        *name = Some(match term {
+                            Term::NamedNode(node) => node.into(),
+                            Term::BlankNode(node) => node.into(),
+                            _ => {
+                                return Err(RdfXmlSyntaxError::msg(
+                                    "TriX graph names must be IRIs or blank nodes",
+                                )
+                                .into());
+                            }
+                        })
+                    }
+                    Some(TriXState::Triple { terms }) => terms.push(term),
+                    _ => (),
+                }
+            }
+            TriXState::Triple { terms } => {
+                let Ok([subject, predicate, object]) = <[Term; 3]>::try_from(terms) else {
+                    return Err(RdfXmlSyntaxError::msg(
+                        "TriX triples must contain exactly three terms",
+                    )
+                    .into());
+                };
+                let subject = match subject {
+                    Term::NamedNode(node) => NamedOrBlankNode::from(node),
+                    Term::BlankNode(node) => node.into(),
+                    _ => {
+                        return Err(RdfXmlSyntaxError::msg(
+                            "TriX triple subjects must be IRIs or blank nodes",
+                        )
+                        .into());
+                    }
+                };
+                let Term::NamedNode(predicate) = predicate else {
+                    return Err(
+                        RdfXmlSyntaxError::msg("TriX triple predicates must be IRIs").into(),
+                    );
+                };
+                let graph_name = match self.state.last() {
+                    Some(TriXState::Graph {
+                        name: Some(name), ..
+                    }) => name.clone(),
+                    _ => GraphName::DefaultGraph,
+                };
+                results.push(Quad::new(subject, predicate, object, graph_name));
+            }
+            TriXState::Doc | TriXState::TriX | TriXState::Graph { .. } => (),
+        }
+        Ok(())
+    }
+
+    fn build_term(&self, kind: TermKind, value: String) -> Result<Term, RdfXmlSyntaxError> {
+        Ok(match kind {
+            TermKind::Uri => self.parse_iri(value.trim().to_owned())?.into(),
+            TermKind::Id => {
+                let id = value.trim();
+                if self.lenient {
+                    BlankNode::new_unchecked(id)
+                } else {
+                    BlankNode::new(id).map_err(|_| {
+                        RdfXmlSyntaxError::msg(format!("{id} is not a valid blank node identifier"))
+                    })?
+                }
+                .into()
+            }
+            TermKind::PlainLiteral {
+                language: Some(language),
+            } => Literal::new_language_tagged_literal_unchecked(value, language).into(),
+            TermKind::PlainLiteral { language: None } => Literal::new_simple_literal(value).into(),
+            TermKind::TypedLiteral { datatype } => {
+                Literal::new_typed_literal(value, datatype).into()
+            }
+        })
+    }
+
+    fn parse_iri(&self, iri: String) -> Result<NamedNode, RdfXmlSyntaxError> {
+        Ok(NamedNode::new_unchecked(if self.lenient {
+            iri
+        } else {
+            Iri::parse(iri.clone())
+                .map_err(|error| RdfXmlSyntaxError::invalid_iri(iri, error))?
+                .into_inner()
+        }))
+    }
+}
+
+fn is_whitespace(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// A [TriX](https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html) serializer.
+///
+/// Consecutive quads in the same graph are written in the same `<graph>` element.
+///
+/// ```
+/// use oxrdf::{LiteralRef, NamedNodeRef, QuadRef};
+/// use oxrdfxml::TriXSerializer;
+///
+/// let mut serializer = TriXSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_quad(QuadRef::new(
+///     NamedNodeRef::new("http://example.com#me")?,
+///     NamedNodeRef::new("http://schema.org/name")?,
+///     LiteralRef::new_language_tagged_literal_unchecked("Foo Bar", "en"),
+///     NamedNodeRef::new("http://example.com")?,
+/// ))?;
+/// assert_eq!(
+///     b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<TriX xmlns=\"http://www.w3.org/2004/03/trix/trix-1/\">\n\t<graph>\n\t\t<uri>http://example.com</uri>\n\t\t<triple>\n\t\t\t<uri>http://example.com#me</uri>\n\t\t\t<uri>http://schema.org/name</uri>\n\t\t\t<plainLiteral xml:lang=\"en\">Foo Bar</plainLiteral>\n\t\t</triple>\n\t</graph>\n</TriX>",
+///     serializer.finish()?.as_slice()
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+#[must_use]
+#[expect(clippy::empty_structs_with_brackets)]
+pub struct TriXSerializer {}
+
+impl TriXSerializer {
+    /// Builds a new [`TriXSerializer`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Serializes a TriX file to a [`Write`] implementation.
+    ///
+    /// This writer does unbuffered writes.
+    ///
+    /// ```
+    /// use oxrdf::{GraphNameRef, NamedNodeRef, QuadRef};
+    /// use oxrdfxml::TriXSerializer;
+    ///
+    /// let mut serializer = TriXSerializer::new().for_writer(Vec::new());
+    /// serializer.serialize_quad(QuadRef::new(
+    ///     NamedNodeRef::new("http://example.com#me")?,
+    ///     NamedNodeRef::new("http://schema.org/knows")?,
+    ///     NamedNodeRef::new("http://example.com#you")?,
+    ///     GraphNameRef::DefaultGraph,
+    /// ))?;
+    /// assert_eq!(
+    ///     b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<TriX xmlns=\"http://www.w3.org/2004/03/trix/trix-1/\">\n\t<graph>\n\t\t<triple>\n\t\t\t<uri>http://example.com#me</uri>\n\t\t\t<uri>http://schema.org/knows</uri>\n\t\t\t<uri>http://example.com#you</uri>\n\t\t</triple>\n\t</graph>\n</TriX>",
+    ///     serializer.finish()?.as_slice()
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_writer<W: Write>(self, writer: W) -> WriterTriXSerializer<W> {
+        WriterTriXSerializer {
+            writer: Writer::new_with_indent(writer, b'\t', 1),
+            inner: self.inner_writer(),
+        }
+    }
+
+    /// Serializes a TriX file to a [`AsyncWrite`] implementation.
+    ///
+    /// This writer does unbuffered writes.
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use oxrdf::{GraphNameRef, NamedNodeRef, QuadRef};
+    /// use oxrdfxml::TriXSerializer;
+    ///
+    /// let mut serializer = TriXSerializer::new().for_tokio_async_writer(Vec::new());
+    /// serializer.serialize_quad(QuadRef::new(
+    ///     NamedNodeRef::new("http://example.com#me")?,
+    ///     NamedNodeRef::new("http://schema.org/knows")?,
+    ///     NamedNodeRef::new("http://example.com#you")?,
+    ///     GraphNameRef::DefaultGraph,
+    /// )).await?;
+    /// assert_eq!(
+    ///     b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<TriX xmlns=\"http://www.w3.org/2004/03/trix/trix-1/\">\n\t<graph>\n\t\t<triple>\n\t\t\t<uri>http://example.com#me</uri>\n\t\t\t<uri>http://schema.org/knows</uri>\n\t\t\t<uri>http://example.com#you</uri>\n\t\t</triple>\n\t</graph>\n</TriX>",
+    ///     serializer.finish().await?.as_slice()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async-tokio")]
+    pub fn for_tokio_async_writer<W: AsyncWrite + Unpin>(
+        self,
+        writer: W,
+    ) -> TokioAsyncWriterTriXSerializer<W> {
+        TokioAsyncWriterTriXSerializer {
+            writer: Writer::new_with_indent(writer, b'\t', 1),
+            inner: self.inner_writer(),
+        }
+    }
+
+    #[expect(clippy::unused_self)]
+    fn inner_writer(self) -> InnerTriXWriter {
+        InnerTriXWriter {
+            current_graph: None,
+        }
+    }
+}
+
+/// Serializes a TriX file to a [`Write`] implementation.
+///
+/// Can be built using [`TriXSerializer::for_writer`].
+#[must_use]
+pub struct WriterTriXSerializer<W: Write> {
+    writer: Writer<W>,
+    inner: InnerTriXWriter,
+}
+
+impl<W: Write> WriterTriXSerializer<W> {
+    /// Serializes an extra quad.
+    pub fn serialize_quad<'a>(&mut self, q: impl Into<QuadRef<'a>>) -> io::Result<()> {
+        let mut buffer = Vec::new();
+        self.inner.serialize_quad(q, &mut buffer)?;
+        self.flush_buffer(&mut buffer)
+    }
+
+    /// Ends the write process and returns the underlying [`Write`].
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut buffer = Vec::new();
+        self.inner.finish(&mut buffer);
+        self.flush_buffer(&mut buffer)?;
+        Ok(self.writer.into_inner())
+    }
+
+    fn flush_buffer(&mut self, buffer: &mut Vec<Event<'_>>) -> io::Result<()> {
+        for event in buffer.drain(0..) {
+            self.writer.write_event(event)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serializes a TriX file to a [`AsyncWrite`] implementation.
+///
+/// Can be built using [`TriXSerializer::for_tokio_async_writer`].
+#[cfg(feature = "async-tokio")]
+#[must_use]
+pub struct TokioAsyncWriterTriXSerializer<W: AsyncWrite + Unpin> {
+    writer: Writer<W>,
+    inner: InnerTriXWriter,
+}
+
+#[cfg(feature = "async-tokio")]
+impl<W: AsyncWrite + Unpin> TokioAsyncWriterTriXSerializer<W> {
+    /// Serializes an extra quad.
+    pub async fn serialize_quad<'a>(&mut self, q: impl Into<QuadRef<'a>>) -> io::Result<()> {
+        let mut buffer = Vec::new();
+        self.inner.serialize_quad(q, &mut buffer)?;
+        self.flush_buffer(&mut buffer).await
+    }
+
+    /// Ends the write process and returns the underlying [`AsyncWrite`].
+    pub async fn finish(mut self) -> io::Result<W> {
+        let mut buffer = Vec::new();
+        self.inner.finish(&mut buffer);
+        self.flush_buffer(&mut buffer).await?;
+        Ok(self.writer.into_inner())
+    }
+
+    async fn flush_buffer(&mut self, buffer: &mut Vec<Event<'_>>) -> io::Result<()> {
+        for event in buffer.drain(0..) {
+            self.writer
+                .write_event_async(event)
+                .await
+                .map_err(map_err)?;
+        }
+        Ok(())
+    }
+}
+
+struct InnerTriXWriter {
+    current_graph: Option<GraphName>,
+}
+
+impl InnerTriXWriter {
+    fn serialize_quad<'a>(
+        &mut self,
+        q: impl Into<QuadRef<'a>>,
+        output: &mut Vec<Event<'a>>,
+    ) -> io::Result<()> {
+        let quad = q.into();
+        if let Some(current_graph) = &self.current_graph {
+            if current_graph.as_ref() != quad.graph_name {
+                output.push(Event::End(BytesEnd::new("graph")));
+                Self::write_graph_start(quad.graph_name, output);
+                self.current_graph = Some(quad.graph_name.into_owned());
+            }
+        } else {
+            Self::write_start(output);
+            Self::write_graph_start(quad.graph_name, output);
+            self.current_graph = Some(quad.graph_name.into_owned());
+        }
+        output.push(Event::Start(BytesStart::new("triple")));
+        Self::write_term(quad.subject.into(), output)?;
+        Self::write_term(quad.predicate.into(), output)?;
+        Self::write_term(quad.object, output)?;
+        output.push(Event::End(BytesEnd::new("triple")));
+        Ok(())
+    }
+
+    fn write_start(output: &mut Vec<Event<'_>>) {
+        output.push(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)));
+        output.push(Event::Start(
+            BytesStart::new("TriX").with_attributes([("xmlns", TRIX_NAMESPACE)]),
+        ));
+    }
+
+    fn write_graph_start<'a>(graph_name: GraphNameRef<'a>, output: &mut Vec<Event<'a>>) {
+        output.push(Event::Start(BytesStart::new("graph")));
+        match graph_name {
+            GraphNameRef::NamedNode(node) => Self::write_element("uri", node.as_str(), output),
+            GraphNameRef::BlankNode(node) => Self::write_element("id", node.as_str(), output),
+            GraphNameRef::DefaultGraph => (),
+        }
+    }
+
+    fn write_term<'a>(term: TermRef<'a>, output: &mut Vec<Event<'a>>) -> io::Result<()> {
+        #[allow(
+            unreachable_patterns,
+            clippy::match_wildcard_for_single_variants,
+            clippy::allow_attributes
+        )]
+        match term {
+            TermRef::NamedNode(node) => Self::write_element("uri", node.as_str(), output),
+            TermRef::BlankNode(node) => Self::write_element("id", node.as_str(), output),
+            TermRef::Literal(literal) => {
+                let start = if let Some(language) = literal.language() {
+                    BytesStart::new("plainLiteral").with_attributes([("xml:lang", language)])
+                } else if literal.datatype() == xsd::STRING {
+                    BytesStart::new("plainLiteral")
+                } else {
+                    BytesStart::new("typedLiteral")
+                        .with_attributes([("datatype", literal.datatype().as_str())])
+                };
+                let end = start.to_end().into_owned();
+                output.push(Event::Start(start));
+                output.push(Event::Text(BytesText::new(literal.value())));
+                output.push(Event::End(end));
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "TriX only supports named, blank or literal terms",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn write_element<'a>(name: &'a str, value: &'a str, output: &mut Vec<Event<'a>>) {
+        output.push(Event::Start(BytesStart::new(name)));
+        output.push(Event::Text(BytesText::new(value)));
+        output.push(Event::End(BytesEnd::new(name)));
+    }
+
+    fn finish(&mut self, output: &mut Vec<Event<'static>>) {
+        if self.current_graph.is_some() {
+            output.push(Event::End(BytesEnd::new("graph")));
+        } else {
+            Self::write_start(output);
+        }
+        output.push(Event::End(BytesEnd::new("TriX")));
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use oxrdf::{BlankNodeRef, Dataset, LiteralRef, NamedNodeRef};
+    use std::error::Error;
+
+    #[test]
+    fn test_round_trip() -> Result<(), Box<dyn Error>> {
+        let mut dataset = Dataset::new();
+        let s = NamedNodeRef::new("http://example.com/s")?;
+        let p = NamedNodeRef::new("http://example.com/p")?;
+        let g = NamedNodeRef::new("http://example.com/g")?;
+        let b = BlankNodeRef::new("b1")?;
+        dataset.insert(QuadRef::new(s, p, b, GraphNameRef::DefaultGraph));
+        dataset.insert(QuadRef::new(
+            b,
+            p,
+            LiteralRef::new_simple_literal("a <b> & c"),
+            g,
+        ));
+        dataset.insert(QuadRef::new(
+            s,
+            p,
+            LiteralRef::new_language_tagged_literal_unchecked("foo", "en"),
+            b,
+        ));
+        dataset.insert(QuadRef::new(
+            s,
+            p,
+            LiteralRef::new_typed_literal("1", xsd::INTEGER),
+            g,
+        ));
+        let mut serializer = TriXSerializer::new().for_writer(Vec::new());
+        for quad in &dataset {
+            serializer.serialize_quad(quad)?;
+        }
+        let output = serializer.finish()?;
+        let parsed = TriXParser::new()
+            .for_slice(&output)
+            .collect::<Result<Dataset, _>>()?;
+        assert_eq!(parsed, dataset);
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_namespace() -> Result<(), Box<dyn Error>> {
+        let quads = TriXParser::new()
+            .for_slice(b"<TriX><graph><uri>http://example.com/g</uri><triple><uri>http://example.com/s</uri><uri>http://example.com/p</uri><typedLiteral datatype=\"http://www.w3.org/2001/XMLSchema#integer\">1</typedLiteral></triple></graph></TriX>")
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            quads,
+            [Quad::new(
+                NamedNode::new("http://example.com/s")?,
+                NamedNode::new("http://example.com/p")?,
+                Literal::new_typed_literal("1", xsd::INTEGER),
+                NamedNode::new("http://example.com/g")?,
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() {
+        for file in [
+            "<TriX><graph><triple><uri>http://example.com/s</uri><uri>http://example.com/p</uri></triple></graph></TriX>",
+            "<TriX><graph><triple><plainLiteral>s</plainLiteral><uri>http://example.com/p</uri><uri>http://example.com/o</uri></triple></graph></TriX>",
+            "<TriX><graph><triple><uri>http://example.com/s</uri><id>p</id><uri>http://example.com/o</uri></triple></graph></TriX>",
+            "<TriX><graph><triple><uri>s</uri><uri>http://example.com/p</uri><uri>http://example.com/o</uri></triple></graph></TriX>",
+            "<TriX><graph><triple><uri>http://example.com/s</uri><uri>http://example.com/p</uri><typedLiteral>o</typedLiteral></triple></graph></TriX>",
+            "<TriX><foo/></TriX>",
+            "<TriX><graph>",
+        ] {
+            assert!(
+                TriXParser::new()
+                    .for_slice(file.as_bytes())
+                    .any(|r| r.is_err()),
+                "{file} should be invalid"
+            );
+        }
+    }
+}
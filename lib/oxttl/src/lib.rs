@@ -10,6 +10,8 @@ mod line_formats;
 pub mod n3;
 pub mod nquads;
 pub mod ntriples;
+mod parallel;
+pub mod rdfpatch;
 mod terse;
 mod toolkit;
 pub mod trig;
@@ -18,6 +20,7 @@ pub mod turtle;
 pub use crate::n3::N3Parser;
 pub use crate::nquads::{NQuadsParser, NQuadsSerializer};
 pub use crate::ntriples::{NTriplesParser, NTriplesSerializer};
+pub use crate::rdfpatch::{RdfPatchParser, RdfPatchSerializer};
 pub use crate::toolkit::{TextPosition, TurtleParseError, TurtleSyntaxError};
 pub use crate::trig::{TriGParser, TriGSerializer};
 pub use crate::turtle::{TurtleParser, TurtleSerializer};
@@ -4,9 +4,10 @@
 use crate::MIN_PARALLEL_CHUNK_SIZE;
 use crate::chunker::get_ntriples_file_chunks;
 use crate::line_formats::NQuadsRecognizer;
+use crate::parallel::MaybeParallelReaderIterator;
 #[cfg(feature = "async-tokio")]
 use crate::toolkit::TokioAsyncReaderIterator;
-use crate::toolkit::{Parser, ReaderIterator, SliceIterator, TurtleParseError, TurtleSyntaxError};
+use crate::toolkit::{Parser, SliceIterator, TurtleParseError, TurtleSyntaxError};
 use oxrdf::{Quad, QuadRef};
 use std::io::{self, Read, Write};
 #[cfg(feature = "async-tokio")]
@@ -39,6 +40,7 @@ use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 #[must_use]
 pub struct NQuadsParser {
     lenient: bool,
+    parallelism: usize,
 }
 
 impl NQuadsParser {
@@ -59,6 +61,34 @@ impl NQuadsParser {
         self
     }
 
+    /// Parses the file read by [`for_reader`](Self::for_reader) using `parallelism` worker threads.
+    ///
+    /// The file is split into chunks of lines that are parsed in parallel
+    /// but the quads are still returned in the file order.
+    /// It allows to speed up the parsing of big files if the parser is the bottleneck.
+    ///
+    /// It does not apply to the other parsing methods. A value of `0` or `1` disables parallel parsing.
+    /// To dynamically specify the parallelism, use e.g. [`std::thread::available_parallelism`].
+    ///
+    /// ```
+    /// use oxttl::NQuadsParser;
+    ///
+    /// let file = br#"<http://example.com/foo> <http://schema.org/name> "Foo" .
+    /// <http://example.com/bar> <http://schema.org/name> "Bar" ."#;
+    ///
+    /// let quads = NQuadsParser::new()
+    ///     .with_parallelism(4)
+    ///     .for_reader(file.as_ref())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(quads.len(), 2);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
     #[deprecated(note = "Use `lenient()` instead", since = "0.2.0")]
     #[inline]
     pub fn unchecked(self) -> Self {
@@ -90,7 +120,7 @@ impl NQuadsParser {
     /// ```
     pub fn for_reader<R: Read>(self, reader: R) -> ReaderNQuadsParser<R> {
         ReaderNQuadsParser {
-            inner: self.low_level().parser.for_reader(reader),
+            inner: MaybeParallelReaderIterator::new(reader, self.parallelism, true, self.lenient),
         }
     }
 
@@ -276,7 +306,7 @@ impl NQuadsParser {
 /// ```
 #[must_use]
 pub struct ReaderNQuadsParser<R: Read> {
-    inner: ReaderIterator<R, NQuadsRecognizer>,
+    inner: MaybeParallelReaderIterator<R>,
 }
 
 impl<R: Read> Iterator for ReaderNQuadsParser<R> {
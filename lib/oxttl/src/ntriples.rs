@@ -4,9 +4,10 @@
 use crate::MIN_PARALLEL_CHUNK_SIZE;
 use crate::chunker::get_ntriples_file_chunks;
 use crate::line_formats::NQuadsRecognizer;
+use crate::parallel::MaybeParallelReaderIterator;
 #[cfg(feature = "async-tokio")]
 use crate::toolkit::TokioAsyncReaderIterator;
-use crate::toolkit::{Parser, ReaderIterator, SliceIterator, TurtleParseError, TurtleSyntaxError};
+use crate::toolkit::{Parser, SliceIterator, TurtleParseError, TurtleSyntaxError};
 use oxrdf::{Triple, TripleRef};
 use std::io::{self, Read, Write};
 #[cfg(feature = "async-tokio")]
@@ -39,6 +40,7 @@ use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 #[must_use]
 pub struct NTriplesParser {
     lenient: bool,
+    parallelism: usize,
 }
 
 impl NTriplesParser {
@@ -59,6 +61,34 @@ impl NTriplesParser {
         self
     }
 
+    /// Parses the file read by [`for_reader`](Self::for_reader) using `parallelism` worker threads.
+    ///
+    /// The file is split into chunks of lines that are parsed in parallel
+    /// but the triples are still returned in the file order.
+    /// It allows to speed up the parsing of big files if the parser is the bottleneck.
+    ///
+    /// It does not apply to the other parsing methods. A value of `0` or `1` disables parallel parsing.
+    /// To dynamically specify the parallelism, use e.g. [`std::thread::available_parallelism`].
+    ///
+    /// ```
+    /// use oxttl::NTriplesParser;
+    ///
+    /// let file = br#"<http://example.com/foo> <http://schema.org/name> "Foo" .
+    /// <http://example.com/bar> <http://schema.org/name> "Bar" ."#;
+    ///
+    /// let triples = NTriplesParser::new()
+    ///     .with_parallelism(4)
+    ///     .for_reader(file.as_ref())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(triples.len(), 2);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
     #[deprecated(note = "Use `lenient()` instead", since = "0.2.0")]
     #[inline]
     pub fn unchecked(self) -> Self {
@@ -90,7 +120,7 @@ impl NTriplesParser {
     /// ```
     pub fn for_reader<R: Read>(self, reader: R) -> ReaderNTriplesParser<R> {
         ReaderNTriplesParser {
-            inner: self.low_level().parser.for_reader(reader),
+            inner: MaybeParallelReaderIterator::new(reader, self.parallelism, false, self.lenient),
         }
     }
 
@@ -276,7 +306,7 @@ impl NTriplesParser {
 /// ```
 #[must_use]
 pub struct ReaderNTriplesParser<R: Read> {
-    inner: ReaderIterator<R, NQuadsRecognizer>,
+    inner: MaybeParallelReaderIterator<R>,
 }
 
 impl<R: Read> Iterator for ReaderNTriplesParser<R> {
@@ -683,6 +713,7 @@ impl LowLevelNTriplesSerializer {
 mod tests {
     use super::*;
     use oxrdf::{Literal, NamedNode};
+    use std::fmt::Write as _;
 
     #[test]
     fn lenient_parsing() {
@@ -700,4 +731,33 @@ mod tests {
             )]
         )
     }
+
+    #[test]
+    fn parallel_parsing() {
+        let mut file = String::new();
+        for i in 0..50_000 {
+            if i % 20_000 == 10 {
+                file.push_str("<http://example.com/s> <http://example.com/p> <bad iri> .\n");
+            } else {
+                writeln!(
+                    file,
+                    "<http://example.com/s{i}> <http://example.com/p> \"{i}\" ."
+                )
+                .unwrap();
+            }
+        }
+        file.push_str("<http://example.com/s> <http://example.com/p> \"end\" .");
+        let sequential = NTriplesParser::new()
+            .for_reader(file.as_bytes())
+            .map(|r| r.map_err(|e| e.to_string()))
+            .collect::<Vec<_>>();
+        let parallel = NTriplesParser::new()
+            .with_parallelism(4)
+            .for_reader(file.as_bytes())
+            .map(|r| r.map_err(|e| e.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(parallel.len(), 50_001);
+        assert_eq!(parallel.iter().filter(|r| r.is_err()).count(), 3);
+        assert_eq!(parallel, sequential);
+    }
 }
@@ -0,0 +1,220 @@
+//! Parallel parsing of N-Triples and N-Quads from a [`Read`] implementation.
+//!
+//! The file is read by the caller thread and split into chunks ending at a line jump.
+//! The chunks are parsed by a pool of worker threads and the results are returned in the file order.
+
+use crate::line_formats::NQuadsRecognizer;
+use crate::toolkit::{ReaderIterator, TextPosition, TurtleParseError, TurtleSyntaxError};
+use oxrdf::Quad;
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::vec::IntoIter;
+use std::{mem, thread};
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+const READ_SIZE: usize = 64 * 1024;
+
+struct Job {
+    data: Vec<u8>,
+    results: Sender<ParsedChunk>,
+}
+
+struct ParsedChunk {
+    results: Vec<Result<Quad, TurtleSyntaxError>>,
+    line_count: u64,
+    byte_count: u64,
+}
+
+pub struct ParallelReaderIterator<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    is_reader_ended: bool,
+    /// A read error to return once the chunks read before it have been returned
+    read_error: Option<io::Error>,
+    jobs: Sender<Job>,
+    /// The receivers are only accessed through `&mut self`, the mutex only makes the iterator `Sync`
+    pending: VecDeque<Mutex<Receiver<ParsedChunk>>>,
+    max_pending: usize,
+    current: IntoIter<Result<Quad, TurtleSyntaxError>>,
+    /// Position of the start of the current chunk in the file
+    current_line: u64,
+    current_offset: u64,
+    /// Position of the start of the next chunk in the file
+    next_line: u64,
+    next_offset: u64,
+}
+
+impl<R: Read> ParallelReaderIterator<R> {
+    pub fn new(reader: R, parallelism: usize, with_graph_name: bool, lenient: bool) -> Self {
+        let (jobs, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..parallelism {
+            let receiver = Arc::clone(&receiver);
+            thread::spawn(move || {
+                loop {
+                    // The lock is released as soon as a job is received
+                    let Ok(job) = receiver.lock().unwrap().recv() else {
+                        return; // The iterator has been dropped
+                    };
+                    let parsed = ParsedChunk {
+                        line_count: memchr::memchr_iter(b'\n', &job.data).count() as u64,
+                        byte_count: job.data.len() as u64,
+                        results: NQuadsRecognizer::new_parser(
+                            job.data.as_slice(),
+                            true,
+                            with_graph_name,
+                            lenient,
+                        )
+                        .into_iter()
+                        .collect(),
+                    };
+                    if job.results.send(parsed).is_err() {
+                        return; // The iterator has been dropped
+                    }
+                }
+            });
+        }
+        Self {
+            reader,
+            buffer: Vec::new(),
+            is_reader_ended: false,
+            read_error: None,
+            jobs,
+            pending: VecDeque::new(),
+            max_pending: 2 * parallelism,
+            current: Vec::new().into_iter(),
+            current_line: 0,
+            current_offset: 0,
+            next_line: 0,
+            next_offset: 0,
+        }
+    }
+
+    /// Reads the next chunk of the file, ending with a line jump except at the end of the file
+    fn read_chunk(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut searched_len = 0;
+        loop {
+            if self.buffer.len() >= CHUNK_SIZE {
+                if let Some(end) = memchr::memrchr(b'\n', &self.buffer[searched_len..]) {
+                    let remaining = self.buffer.split_off(searched_len + end + 1);
+                    return Ok(Some(mem::replace(&mut self.buffer, remaining)));
+                }
+                searched_len = self.buffer.len();
+            }
+            let len = self.buffer.len();
+            self.buffer.resize(len + READ_SIZE, 0);
+            let read = loop {
+                match self.reader.read(&mut self.buffer[len..]) {
+                    Ok(read) => break read,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                    Err(e) => {
+                        self.buffer.truncate(len);
+                        return Err(e);
+                    }
+                }
+            };
+            self.buffer.truncate(len + read);
+            if read == 0 {
+                self.is_reader_ended = true;
+                return Ok((!self.buffer.is_empty()).then(|| mem::take(&mut self.buffer)));
+            }
+        }
+    }
+
+    /// Moves the error location from the chunk to the file
+    fn shift_error(&self, error: &TurtleSyntaxError) -> TurtleSyntaxError {
+        let shift = |position: TextPosition| TextPosition {
+            line: position.line + self.current_line,
+            column: position.column,
+            offset: position.offset + self.current_offset,
+        };
+        let location = error.location();
+        TurtleSyntaxError::new(shift(location.start)..shift(location.end), error.message())
+    }
+}
+
+impl<R: Read> Iterator for ParallelReaderIterator<R> {
+    type Item = Result<Quad, TurtleParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.current.next() {
+                return Some(result.map_err(|e| self.shift_error(&e).into()));
+            }
+            while !self.is_reader_ended && self.pending.len() < self.max_pending {
+                match self.read_chunk() {
+                    Ok(Some(data)) => {
+                        let (results, receiver) = channel();
+                        if self.jobs.send(Job { data, results }).is_err() {
+                            return Some(Err(io::Error::other(
+                                "The parsing worker threads have stopped",
+                            )
+                            .into()));
+                        }
+                        self.pending.push_back(Mutex::new(receiver));
+                    }
+                    Ok(None) => (),
+                    Err(e) => {
+                        self.is_reader_ended = true;
+                        self.read_error = Some(e);
+                    }
+                }
+            }
+            if self.pending.is_empty() {
+                return Some(Err(self.read_error.take()?.into()));
+            }
+            let receiver = self
+                .pending
+                .pop_front()?
+                .into_inner()
+                .unwrap_or_else(PoisonError::into_inner);
+            let Ok(parsed) = receiver.recv() else {
+                return Some(Err(
+                    io::Error::other("A parsing worker thread has stopped").into()
+                ));
+            };
+            self.current = parsed.results.into_iter();
+            self.current_line = self.next_line;
+            self.current_offset = self.next_offset;
+            self.next_line += parsed.line_count;
+            self.next_offset += parsed.byte_count;
+        }
+    }
+}
+
+/// A N-Triples or N-Quads parser from a [`Read`] implementation, parallel if more than one thread is requested
+pub enum MaybeParallelReaderIterator<R: Read> {
+    Sequential(ReaderIterator<R, NQuadsRecognizer>),
+    Parallel(ParallelReaderIterator<R>),
+}
+
+impl<R: Read> MaybeParallelReaderIterator<R> {
+    pub fn new(reader: R, parallelism: usize, with_graph_name: bool, lenient: bool) -> Self {
+        if parallelism > 1 {
+            Self::Parallel(ParallelReaderIterator::new(
+                reader,
+                parallelism,
+                with_graph_name,
+                lenient,
+            ))
+        } else {
+            Self::Sequential(
+                NQuadsRecognizer::new_parser(Vec::new(), false, with_graph_name, lenient)
+                    .for_reader(reader),
+            )
+        }
+    }
+}
+
+impl<R: Read> Iterator for MaybeParallelReaderIterator<R> {
+    type Item = Result<Quad, TurtleParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Sequential(inner) => inner.next(),
+            Self::Parallel(inner) => inner.next(),
+        }
+    }
+}
@@ -0,0 +1,625 @@
+//! A [RDF Patch](https://afs.github.io/rdf-delta/rdf-patch.html) parser implemented by [`RdfPatchParser`]
+//! and a serializer implemented by [`RdfPatchSerializer`].
+
+use crate::line_formats::NQuadsRecognizer;
+use crate::toolkit::{TextPosition, TurtleParseError, TurtleSyntaxError};
+use oxrdf::dataset::DatasetDiff;
+use oxrdf::{Quad, QuadRef};
+use std::io::{self, BufRead, BufReader, Read, Write};
+#[cfg(feature = "async-tokio")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// A change read from a RDF Patch file by [`RdfPatchParser`].
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum RdfPatchChange {
+    /// The addition of a quad (`A` row).
+    Addition(Quad),
+    /// The deletion of a quad (`D` row).
+    Deletion(Quad),
+    /// The start of a transaction (`TX` row).
+    TransactionStart,
+    /// The commit of the current transaction (`TC` row).
+    TransactionCommit,
+    /// The abort of the current transaction (`TA` row): the changes since its start must be discarded.
+    TransactionAbort,
+}
+
+/// A [RDF Patch](https://afs.github.io/rdf-delta/rdf-patch.html) parser.
+///
+/// Each row must be on its own line and the terms must be written like in [N-Quads](https://www.w3.org/TR/n-quads/):
+/// prefixed names and the `R` repeated terms shorthand are not supported.
+/// The header (`H`) and prefix (`PA` and `PD`) rows are ignored.
+///
+/// ```
+/// use oxrdf::NamedNodeRef;
+/// use oxttl::rdfpatch::RdfPatchChange;
+/// use oxttl::RdfPatchParser;
+///
+/// let file = br#"TX .
+/// D <http://example.com/s> <http://example.com/p> "old" .
+/// A <http://example.com/s> <http://example.com/p> "new" <http://example.com/g> .
+/// TC ."#;
+///
+/// let changes = RdfPatchParser::new()
+///     .for_slice(file)
+///     .collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(changes.len(), 4);
+/// assert_eq!(changes[0], RdfPatchChange::TransactionStart);
+/// let ex = NamedNodeRef::new("http://example.com/s")?;
+/// let RdfPatchChange::Deletion(quad) = &changes[1] else {
+///     panic!("a deletion is expected")
+/// };
+/// assert_eq!(quad.subject, ex.into());
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+#[must_use]
+pub struct RdfPatchParser {
+    lenient: bool,
+}
+
+impl RdfPatchParser {
+    /// Builds a new [`RdfPatchParser`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assumes the file is valid to make parsing faster.
+    ///
+    /// It will skip some validations.
+    ///
+    /// Note that if the file is actually not valid, the parser might emit broken RDF.
+    #[inline]
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Parses a RDF Patch file from a [`Read`] implementation.
+    ///
+    /// ```
+    /// use oxttl::rdfpatch::RdfPatchChange;
+    /// use oxttl::RdfPatchParser;
+    ///
+    /// let file = "H id <uuid:0686c69d-8f89-4496-acb5-744f0157a8db> .\nA _:s <http://example.com/p> _:o .\n";
+    ///
+    /// let mut additions = 0;
+    /// for change in RdfPatchParser::new().for_reader(file.as_bytes()) {
+    ///     if let RdfPatchChange::Addition(_) = change? {
+    ///         additions += 1;
+    ///     }
+    /// }
+    /// assert_eq!(1, additions);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_reader<R: Read>(self, reader: R) -> ReaderRdfPatchParser<R> {
+        ReaderRdfPatchParser {
+            reader: BufReader::new(reader),
+            buffer: Vec::new(),
+            lenient: self.lenient,
+            line: 0,
+            offset: 0,
+        }
+    }
+
+    /// Parses a RDF Patch file from a byte slice.
+    ///
+    /// ```
+    /// use oxttl::RdfPatchParser;
+    ///
+    /// let file = b"TX .\nA <http://example.com/s> <http://example.com/p> <http://example.com/o> .\nTA .\n";
+    ///
+    /// assert_eq!(3, RdfPatchParser::new().for_slice(file).count());
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_slice(self, slice: &[u8]) -> SliceRdfPatchParser<'_> {
+        SliceRdfPatchParser {
+            data: slice,
+            lenient: self.lenient,
+            line: 0,
+            offset: 0,
+        }
+    }
+}
+
+/// Parses a RDF Patch file from a [`Read`] implementation.
+///
+/// Can be built using [`RdfPatchParser::for_reader`].
+#[must_use]
+pub struct ReaderRdfPatchParser<R: Read> {
+    reader: BufReader<R>,
+    buffer: Vec<u8>,
+    lenient: bool,
+    line: u64,
+    offset: u64,
+}
+
+impl<R: Read> Iterator for ReaderRdfPatchParser<R> {
+    type Item = Result<RdfPatchChange, TurtleParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buffer.clear();
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) => (),
+                Err(e) => return Some(Err(e.into())),
+            }
+            let result = parse_row(&self.buffer, self.line, self.offset, self.lenient);
+            self.line += 1;
+            self.offset += self.buffer.len() as u64;
+            if let Some(result) = result {
+                return Some(result.map_err(Into::into));
+            }
+        }
+    }
+}
+
+/// Parses a RDF Patch file from a byte slice.
+///
+/// Can be built using [`RdfPatchParser::for_slice`].
+#[must_use]
+pub struct SliceRdfPatchParser<'a> {
+    data: &'a [u8],
+    lenient: bool,
+    line: u64,
+    offset: u64,
+}
+
+impl Iterator for SliceRdfPatchParser<'_> {
+    type Item = Result<RdfPatchChange, TurtleSyntaxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.data.is_empty() {
+            let end = memchr::memchr(b'\n', self.data).map_or(self.data.len(), |i| i + 1);
+            let (row, rest) = self.data.split_at(end);
+            self.data = rest;
+            let result = parse_row(row, self.line, self.offset, self.lenient);
+            self.line += 1;
+            self.offset += row.len() as u64;
+            if result.is_some() {
+                return result;
+            }
+        }
+        None
+    }
+}
+
+/// Parses a row of the file, returns `None` if it does not contain a change
+fn parse_row(
+    row: &[u8],
+    line: u64,
+    offset: u64,
+    lenient: bool,
+) -> Option<Result<RdfPatchChange, TurtleSyntaxError>> {
+    let position = |i: usize| TextPosition {
+        line,
+        column: row[..i].iter().filter(|c| (**c & 0xC0) != 0x80).count() as u64,
+        offset: offset + i as u64,
+    };
+    let error = |message: String| {
+        TurtleSyntaxError::new(position(0)..position(row.trim_ascii_end().len()), message)
+    };
+    let start = row.len() - row.trim_ascii_start().len();
+    let trimmed = row.trim_ascii();
+    if trimmed.is_empty() || trimmed.starts_with(b"#") {
+        return None;
+    }
+    let keyword_len = trimmed
+        .iter()
+        .position(u8::is_ascii_whitespace)
+        .unwrap_or(trimmed.len());
+    let keyword = &trimmed[..keyword_len];
+    let rest_start = start + keyword_len;
+    Some(match keyword {
+        b"A" | b"D" => {
+            let mut quads =
+                NQuadsRecognizer::new_parser(&row[rest_start..], true, true, lenient).into_iter();
+            match (quads.next(), quads.next()) {
+                (Some(Ok(quad)), None) => Ok(if keyword == b"A" {
+                    RdfPatchChange::Addition(quad)
+                } else {
+                    RdfPatchChange::Deletion(quad)
+                }),
+                (Some(Err(e)), _) => {
+                    // We move the error location from the quad to the file
+                    let shift = |p: TextPosition| TextPosition {
+                        line: line + p.line,
+                        column: if p.line == 0 {
+                            position(rest_start).column + p.column
+                        } else {
+                            p.column
+                        },
+                        offset: position(rest_start).offset + p.offset,
+                    };
+                    let location = e.location();
+                    Err(TurtleSyntaxError::new(
+                        shift(location.start)..shift(location.end),
+                        e.message(),
+                    ))
+                }
+                (None, _) => Err(error("A quad is expected after A and D".into())),
+                (Some(Ok(_)), Some(_)) => Err(error("Only one quad is allowed per row".into())),
+            }
+        }
+        b"TX" | b"TC" | b"TA" => {
+            if matches!(row[rest_start..].trim_ascii(), b"" | b".") {
+                Ok(match keyword {
+                    b"TX" => RdfPatchChange::TransactionStart,
+                    b"TC" => RdfPatchChange::TransactionCommit,
+                    _ => RdfPatchChange::TransactionAbort,
+                })
+            } else {
+                Err(error(format!(
+                    "Unexpected content after {}",
+                    String::from_utf8_lossy(keyword)
+                )))
+            }
+        }
+        b"H" | b"PA" | b"PD" => return None,
+        _ => Err(error(format!(
+            "Unexpected RDF Patch row kind '{}', A, D, H, PA, PD, TX, TC or TA are expected",
+            String::from_utf8_lossy(keyword)
+        ))),
+    })
+}
+
+/// A [RDF Patch](https://afs.github.io/rdf-delta/rdf-patch.html) serializer.
+///
+/// ```
+/// use oxrdf::{Dataset, GraphNameRef, LiteralRef, NamedNodeRef, QuadRef};
+/// use oxttl::RdfPatchSerializer;
+///
+/// let ex = NamedNodeRef::new("http://example.com")?;
+/// let old = Dataset::from_iter([QuadRef::new(ex, ex, LiteralRef::from("old"), ex)]);
+/// let new = Dataset::from_iter([QuadRef::new(ex, ex, LiteralRef::from("new"), GraphNameRef::DefaultGraph)]);
+///
+/// let mut serializer = RdfPatchSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_diff(&old.diff(&new))?;
+/// assert_eq!(
+///     b"D <http://example.com> <http://example.com> \"old\" <http://example.com> .\nA <http://example.com> <http://example.com> \"new\" .\n",
+///     serializer.finish().as_slice()
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default, Clone)]
+#[must_use]
+#[expect(clippy::empty_structs_with_brackets)]
+pub struct RdfPatchSerializer {}
+
+impl RdfPatchSerializer {
+    /// Builds a new [`RdfPatchSerializer`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Writes a RDF Patch file to a [`Write`] implementation.
+    ///
+    /// ```
+    /// use oxrdf::{NamedNodeRef, QuadRef};
+    /// use oxrdf::vocab::rdf;
+    /// use oxttl::RdfPatchSerializer;
+    ///
+    /// let mut serializer = RdfPatchSerializer::new().for_writer(Vec::new());
+    /// serializer.serialize_addition(QuadRef::new(
+    ///     NamedNodeRef::new("http://example.com#me")?,
+    ///     rdf::TYPE,
+    ///     NamedNodeRef::new("http://schema.org/Person")?,
+    ///     NamedNodeRef::new("http://example.com")?,
+    /// ))?;
+    /// assert_eq!(
+    ///     b"A <http://example.com#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> <http://example.com> .\n",
+    ///     serializer.finish().as_slice()
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn for_writer<W: Write>(self, writer: W) -> WriterRdfPatchSerializer<W> {
+        WriterRdfPatchSerializer {
+            writer,
+            low_level_writer: self.low_level(),
+        }
+    }
+
+    /// Writes a RDF Patch file to a [`AsyncWrite`] implementation.
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use oxrdf::{NamedNodeRef, QuadRef};
+    /// use oxrdf::vocab::rdf;
+    /// use oxttl::RdfPatchSerializer;
+    ///
+    /// let mut serializer = RdfPatchSerializer::new().for_tokio_async_writer(Vec::new());
+    /// serializer.serialize_deletion(QuadRef::new(
+    ///     NamedNodeRef::new("http://example.com#me")?,
+    ///     rdf::TYPE,
+    ///     NamedNodeRef::new("http://schema.org/Person")?,
+    ///     NamedNodeRef::new("http://example.com")?,
+    /// )).await?;
+    /// assert_eq!(
+    ///     b"D <http://example.com#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> <http://example.com> .\n",
+    ///     serializer.finish().as_slice()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async-tokio")]
+    pub fn for_tokio_async_writer<W: AsyncWrite + Unpin>(
+        self,
+        writer: W,
+    ) -> TokioAsyncWriterRdfPatchSerializer<W> {
+        TokioAsyncWriterRdfPatchSerializer {
+            writer,
+            low_level_writer: self.low_level(),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Builds a low-level RDF Patch writer.
+    ///
+    /// ```
+    /// use oxrdf::{NamedNodeRef, QuadRef};
+    /// use oxrdf::vocab::rdf;
+    /// use oxttl::RdfPatchSerializer;
+    ///
+    /// let mut buf = Vec::new();
+    /// let mut serializer = RdfPatchSerializer::new().low_level();
+    /// serializer.serialize_addition(QuadRef::new(
+    ///     NamedNodeRef::new("http://example.com#me")?,
+    ///     rdf::TYPE,
+    ///     NamedNodeRef::new("http://schema.org/Person")?,
+    ///     NamedNodeRef::new("http://example.com")?,
+    /// ), &mut buf)?;
+    /// assert_eq!(
+    ///     b"A <http://example.com#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> <http://example.com> .\n",
+    ///     buf.as_slice()
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[expect(clippy::unused_self)]
+    pub fn low_level(self) -> LowLevelRdfPatchSerializer {
+        LowLevelRdfPatchSerializer {}
+    }
+}
+
+/// Writes a RDF Patch file to a [`Write`] implementation.
+///
+/// Can be built using [`RdfPatchSerializer::for_writer`].
+///
+/// ```
+/// use oxrdf::{NamedNodeRef, QuadRef};
+/// use oxrdf::vocab::rdf;
+/// use oxttl::RdfPatchSerializer;
+///
+/// let mut serializer = RdfPatchSerializer::new().for_writer(Vec::new());
+/// serializer.serialize_addition(QuadRef::new(
+///     NamedNodeRef::new("http://example.com#me")?,
+///     rdf::TYPE,
+///     NamedNodeRef::new("http://schema.org/Person")?,
+///     NamedNodeRef::new("http://example.com")?,
+/// ))?;
+/// assert_eq!(
+///     b"A <http://example.com#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> <http://example.com> .\n",
+///     serializer.finish().as_slice()
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+pub struct WriterRdfPatchSerializer<W: Write> {
+    writer: W,
+    low_level_writer: LowLevelRdfPatchSerializer,
+}
+
+impl<W: Write> WriterRdfPatchSerializer<W> {
+    /// Writes the addition of a quad.
+    pub fn serialize_addition<'a>(&mut self, q: impl Into<QuadRef<'a>>) -> io::Result<()> {
+        self.low_level_writer
+            .serialize_addition(q, &mut self.writer)
+    }
+
+    /// Writes the deletion of a quad.
+    pub fn serialize_deletion<'a>(&mut self, q: impl Into<QuadRef<'a>>) -> io::Result<()> {
+        self.low_level_writer
+            .serialize_deletion(q, &mut self.writer)
+    }
+
+    /// Writes all the changes of a [`DatasetDiff`], the deletions first.
+    pub fn serialize_diff(&mut self, diff: &DatasetDiff) -> io::Result<()> {
+        self.low_level_writer.serialize_diff(diff, &mut self.writer)
+    }
+
+    /// Ends the write process and returns the underlying [`Write`].
+    pub fn finish(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes a RDF Patch file to a [`AsyncWrite`] implementation.
+///
+/// Can be built using [`RdfPatchSerializer::for_tokio_async_writer`].
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use oxrdf::{NamedNodeRef, QuadRef};
+/// use oxrdf::vocab::rdf;
+/// use oxttl::RdfPatchSerializer;
+///
+/// let mut serializer = RdfPatchSerializer::new().for_tokio_async_writer(Vec::new());
+/// serializer.serialize_addition(QuadRef::new(
+///     NamedNodeRef::new("http://example.com#me")?,
+///     rdf::TYPE,
+///     NamedNodeRef::new("http://schema.org/Person")?,
+///     NamedNodeRef::new("http://example.com")?,
+/// )).await?;
+/// assert_eq!(
+///     b"A <http://example.com#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> <http://example.com> .\n",
+///     serializer.finish().as_slice()
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async-tokio")]
+#[must_use]
+pub struct TokioAsyncWriterRdfPatchSerializer<W: AsyncWrite + Unpin> {
+    writer: W,
+    low_level_writer: LowLevelRdfPatchSerializer,
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "async-tokio")]
+impl<W: AsyncWrite + Unpin> TokioAsyncWriterRdfPatchSerializer<W> {
+    /// Writes the addition of a quad.
+    pub async fn serialize_addition<'a>(&mut self, q: impl Into<QuadRef<'a>>) -> io::Result<()> {
+        self.low_level_writer
+            .serialize_addition(q, &mut self.buffer)?;
+        self.flush_buffer().await
+    }
+
+    /// Writes the deletion of a quad.
+    pub async fn serialize_deletion<'a>(&mut self, q: impl Into<QuadRef<'a>>) -> io::Result<()> {
+        self.low_level_writer
+            .serialize_deletion(q, &mut self.buffer)?;
+        self.flush_buffer().await
+    }
+
+    /// Writes all the changes of a [`DatasetDiff`], the deletions first.
+    pub async fn serialize_diff(&mut self, diff: &DatasetDiff) -> io::Result<()> {
+        for quad in diff.deletions() {
+            self.serialize_deletion(quad).await?;
+        }
+        for quad in diff.insertions() {
+            self.serialize_addition(quad).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush_buffer(&mut self) -> io::Result<()> {
+        self.writer.write_all(&self.buffer).await?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Ends the write process and returns the underlying [`Write`].
+    pub fn finish(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes a RDF Patch file by using a low-level API.
+///
+/// Can be built using [`RdfPatchSerializer::low_level`].
+///
+/// ```
+/// use oxrdf::{NamedNodeRef, QuadRef};
+/// use oxrdf::vocab::rdf;
+/// use oxttl::RdfPatchSerializer;
+///
+/// let mut buf = Vec::new();
+/// let mut serializer = RdfPatchSerializer::new().low_level();
+/// serializer.serialize_deletion(QuadRef::new(
+///     NamedNodeRef::new("http://example.com#me")?,
+///     rdf::TYPE,
+///     NamedNodeRef::new("http://schema.org/Person")?,
+///     NamedNodeRef::new("http://example.com")?,
+/// ), &mut buf)?;
+/// assert_eq!(
+///     b"D <http://example.com#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> <http://example.com> .\n",
+///     buf.as_slice()
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[expect(clippy::empty_structs_with_brackets)]
+pub struct LowLevelRdfPatchSerializer {}
+
+impl LowLevelRdfPatchSerializer {
+    /// Writes the addition of a quad.
+    #[expect(clippy::unused_self)]
+    pub fn serialize_addition<'a>(
+        &mut self,
+        q: impl Into<QuadRef<'a>>,
+        mut writer: impl Write,
+    ) -> io::Result<()> {
+        writeln!(writer, "A {} .", q.into())
+    }
+
+    /// Writes the deletion of a quad.
+    #[expect(clippy::unused_self)]
+    pub fn serialize_deletion<'a>(
+        &mut self,
+        q: impl Into<QuadRef<'a>>,
+        mut writer: impl Write,
+    ) -> io::Result<()> {
+        writeln!(writer, "D {} .", q.into())
+    }
+
+    /// Writes all the changes of a [`DatasetDiff`], the deletions first.
+    pub fn serialize_diff(&mut self, diff: &DatasetDiff, mut writer: impl Write) -> io::Result<()> {
+        for quad in diff.deletions() {
+            self.serialize_deletion(quad, &mut writer)?;
+        }
+        for quad in diff.insertions() {
+            self.serialize_addition(quad, &mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxrdf::{GraphName, Literal, NamedNode};
+
+    #[test]
+    fn parse_changes() {
+        let file = "# comment\nH id <uuid:1> .\nTX .\nA <http://example.com/s> <http://example.com/p> \"o\" .\n  D <http://example.com/s> <http://example.com/p> \"o\" <http://example.com/g> .\nTC .\n\n";
+        let quad = |graph_name: GraphName| {
+            Quad::new(
+                NamedNode::new_unchecked("http://example.com/s"),
+                NamedNode::new_unchecked("http://example.com/p"),
+                Literal::from("o"),
+                graph_name,
+            )
+        };
+        let expected = [
+            RdfPatchChange::TransactionStart,
+            RdfPatchChange::Addition(quad(GraphName::DefaultGraph)),
+            RdfPatchChange::Deletion(quad(
+                NamedNode::new_unchecked("http://example.com/g").into(),
+            )),
+            RdfPatchChange::TransactionCommit,
+        ];
+        assert_eq!(
+            RdfPatchParser::new()
+                .for_slice(file.as_bytes())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            RdfPatchParser::new()
+                .for_reader(file.as_bytes())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let errors = RdfPatchParser::new()
+            .for_slice(b"TX .\nX <http://example.com/s> .\nA <http://example.com/s> <http://example.com/p> <bad iri> .\nA .\nTC foo\n")
+            .filter_map(Result::err)
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors[0].location().start.line, 1);
+        let location = errors[1].location();
+        assert_eq!(location.start.line, 2);
+        assert_eq!(location.start.column, 48);
+        assert_eq!(location.start.offset, 80);
+        assert_eq!(errors[2].location().start.line, 3);
+        assert_eq!(errors[3].location().start.line, 4);
+    }
+}
@@ -34,32 +34,14 @@ impl TriGRecognizerContext {
     }
 }
 
-impl RuleRecognizer for TriGRecognizer {
-    type TokenRecognizer = N3Lexer;
-    type Output = Quad;
-    type Context = TriGRecognizerContext;
-
-    fn error_recovery_state(mut self) -> Self {
-        self.stack.clear();
-        self.cur_subject.clear();
-        self.cur_predicate.clear();
-        self.cur_object.clear();
-        #[cfg(feature = "rdf-12")]
-        self.cur_reifier.clear();
-        self.cur_graph = GraphName::DefaultGraph;
-        self
-    }
-
-    fn recognize_next(
+impl TriGRecognizer {
+    fn recognize_token(
         mut self,
-        token: TokenOrLineJump<N3Token<'_>>,
+        token: N3Token<'_>,
         context: &mut TriGRecognizerContext,
         results: &mut Vec<Quad>,
         errors: &mut Vec<RuleRecognizerError>,
     ) -> Self {
-        let TokenOrLineJump::Token(token) = token else {
-            return self;
-        };
         if let Some(rule) = self.stack.pop() {
             match rule {
                 // [1] 	trigDoc 	::= 	(directive | block)*
@@ -133,8 +115,7 @@ impl RuleRecognizer for TriGRecognizer {
                     if token == N3Token::Punctuation(".") {
                         self
                     } else {
-                        errors.push("A dot is expected at the end of statements".into());
-                        self.recognize_next(TokenOrLineJump::Token(token), context, results, errors)
+                        self.error(errors, "A dot is expected at the end of statements")
                     }
                 }
                 TriGState::BaseExpectIri => {
@@ -329,18 +310,27 @@ impl RuleRecognizer for TriGRecognizer {
                             self
                         }
                         _ => {
-                            errors.push(
-                                "A '}' or a '.' is expected at the end of a graph block".into(),
-                            );
-                            self.recognize_next(
-                                TokenOrLineJump::Token(token),
-                                context,
-                                results,
+                            // We recover inside of the graph block
+                            self.stack.push(TriGState::WrappedGraphPossibleEnd);
+                            self.error(
                                 errors,
+                                "A '}' or a '.' is expected at the end of a graph block",
                             )
                         }
                     }
                 }
+                TriGState::RecoverInWrappedGraph => match token {
+                    N3Token::Punctuation(".") => {
+                        self.stack.push(TriGState::WrappedGraphPossibleEnd);
+                        self.stack.push(TriGState::Triples);
+                        self
+                    }
+                    N3Token::Punctuation("}") => self,
+                    _ => {
+                        self.stack.push(TriGState::RecoverInWrappedGraph);
+                        self
+                    }
+                },
                 // [16] triples 	::= 	(subject predicateObjectList) | (blankNodePropertyList predicateObjectList?) | (reifiedTriple predicateObjectList?)
                 // [20] 	subject 	::= 	iri | BlankNode | collection
                 TriGState::Triples => match token {
@@ -1156,7 +1146,6 @@ impl RuleRecognizer for TriGRecognizer {
                 }
             }
         } else if token == N3Token::Punctuation(".") || token == N3Token::Punctuation("}") {
-            // TODO: be smarter depending if we are in '{' or not
             self.stack.push(TriGState::TriGDoc);
             self
         } else {
@@ -1164,43 +1153,6 @@ impl RuleRecognizer for TriGRecognizer {
         }
     }
 
-    fn recognize_end(
-        mut self,
-        _context: &mut TriGRecognizerContext,
-        results: &mut Vec<Self::Output>,
-        errors: &mut Vec<RuleRecognizerError>,
-    ) {
-        match &*self.stack {
-            [] | [TriGState::TriGDoc] => {
-                debug_assert!(
-                    self.cur_subject.is_empty(),
-                    "The cur_subject stack must be empty if the state stack is empty"
-                );
-                debug_assert!(
-                    self.cur_predicate.is_empty(),
-                    "The cur_predicate stack must be empty if the state stack is empty"
-                );
-                debug_assert!(
-                    self.cur_object.is_empty(),
-                    "The cur_object stack must be empty if the state stack is empty"
-                );
-            }
-            [.., TriGState::LiteralPossibleSuffix { value, emit: true }] => {
-                self.cur_object
-                    .push(Literal::new_simple_literal(value).into());
-                self.emit_quad(results);
-                errors.push("Triples should be followed by a dot".into())
-            }
-            _ => errors.push("Unexpected end".into()), // TODO
-        }
-    }
-
-    fn lexer_options(context: &TriGRecognizerContext) -> &N3LexerOptions {
-        &context.lexer_options
-    }
-}
-
-impl TriGRecognizer {
     pub fn new_parser<B>(
         data: B,
         is_ending: bool,
@@ -1237,16 +1189,34 @@ impl TriGRecognizer {
 
     #[must_use]
     fn error(
-        mut self,
+        self,
         errors: &mut Vec<RuleRecognizerError>,
         msg: impl Into<RuleRecognizerError>,
     ) -> Self {
         errors.push(msg.into());
+        self.recovery_state()
+    }
+
+    /// Skips the tokens until the end of the current statement
+    ///
+    /// If the statement is inside a graph block, the parsing continues inside it.
+    fn recovery_state(mut self) -> Self {
+        let in_wrapped_graph = self
+            .stack
+            .iter()
+            .any(|state| matches!(state, TriGState::WrappedGraphPossibleEnd));
         self.stack.clear();
         self.cur_subject.clear();
         self.cur_predicate.clear();
         self.cur_object.clear();
-        self.cur_graph = GraphName::DefaultGraph;
+        #[cfg(feature = "rdf-12")]
+        self.cur_reifier.clear();
+        if in_wrapped_graph {
+            self.stack.push(TriGState::TriGDoc);
+            self.stack.push(TriGState::RecoverInWrappedGraph);
+        } else {
+            self.cur_graph = GraphName::DefaultGraph;
+        }
         self
     }
 
@@ -1260,6 +1230,86 @@ impl TriGRecognizer {
     }
 }
 
+impl RuleRecognizer for TriGRecognizer {
+    type TokenRecognizer = N3Lexer;
+    type Output = Quad;
+    type Context = TriGRecognizerContext;
+
+    fn error_recovery_state(self) -> Self {
+        self.recovery_state()
+    }
+
+    fn recognize_next(
+        self,
+        token: TokenOrLineJump<N3Token<'_>>,
+        context: &mut TriGRecognizerContext,
+        results: &mut Vec<Quad>,
+        errors: &mut Vec<RuleRecognizerError>,
+    ) -> Self {
+        let TokenOrLineJump::Token(token) = token else {
+            return self;
+        };
+        let error_count = errors.len();
+        let statement_end = match token {
+            N3Token::Punctuation(p @ ("." | "}")) => Some(p),
+            _ => None,
+        };
+        let this = self.recognize_token(token, context, results, errors);
+        if let Some(statement_end) = statement_end {
+            if errors.len() > error_count
+                && matches!(
+                    this.stack.last(),
+                    None | Some(TriGState::RecoverInWrappedGraph)
+                )
+            {
+                // The error has been raised by the end of the statement itself, we do not skip the next statement
+                return this.recognize_token(
+                    N3Token::Punctuation(statement_end),
+                    context,
+                    results,
+                    errors,
+                );
+            }
+        }
+        this
+    }
+
+    fn recognize_end(
+        mut self,
+        _context: &mut TriGRecognizerContext,
+        results: &mut Vec<Self::Output>,
+        errors: &mut Vec<RuleRecognizerError>,
+    ) {
+        match &*self.stack {
+            [] | [TriGState::TriGDoc] => {
+                debug_assert!(
+                    self.cur_subject.is_empty(),
+                    "The cur_subject stack must be empty if the state stack is empty"
+                );
+                debug_assert!(
+                    self.cur_predicate.is_empty(),
+                    "The cur_predicate stack must be empty if the state stack is empty"
+                );
+                debug_assert!(
+                    self.cur_object.is_empty(),
+                    "The cur_object stack must be empty if the state stack is empty"
+                );
+            }
+            [.., TriGState::LiteralPossibleSuffix { value, emit: true }] => {
+                self.cur_object
+                    .push(Literal::new_simple_literal(value).into());
+                self.emit_quad(results);
+                errors.push("Triples should be followed by a dot".into())
+            }
+            _ => errors.push("Unexpected end".into()), // TODO
+        }
+    }
+
+    fn lexer_options(context: &TriGRecognizerContext) -> &N3LexerOptions {
+        &context.lexer_options
+    }
+}
+
 #[derive(Debug)]
 enum TriGState {
     TriGDoc,
@@ -1280,6 +1330,7 @@ enum TriGState {
     },
     WrappedGraph,
     WrappedGraphPossibleEnd,
+    RecoverInWrappedGraph,
     GraphName,
     GraphNameAnonEnd,
     Triples,
@@ -9,12 +9,12 @@ use crate::toolkit::{Parser, ReaderIterator, SliceIterator, TurtleParseError, Tu
 use oxiri::{Iri, IriParseError};
 use oxrdf::vocab::{rdf, xsd};
 use oxrdf::{
-    GraphName, GraphNameRef, LiteralRef, NamedNode, NamedNodeRef, NamedOrBlankNode, Quad, QuadRef,
-    TermRef,
+    BlankNodeRef, GraphName, GraphNameRef, LiteralRef, NamedNode, NamedNodeRef, NamedOrBlankNode,
+    NamedOrBlankNodeRef, Quad, QuadRef, TermRef,
 };
 use std::borrow::Cow;
-use std::collections::hash_map::Iter;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::hash_map::{Entry, Iter};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::io::{self, Read, Write};
 #[cfg(feature = "async-tokio")]
@@ -752,6 +752,7 @@ impl<'a> Iterator for TriGPrefixesIter<'a> {
 pub struct TriGSerializer {
     base_iri: Option<Iri<String>>,
     prefixes: BTreeMap<String, String>,
+    pretty: bool,
 }
 
 impl TriGSerializer {
@@ -761,6 +762,7 @@ impl TriGSerializer {
         Self {
             base_iri: None,
             prefixes: BTreeMap::new(),
+            pretty: false,
         }
     }
 
@@ -806,6 +808,40 @@ impl TriGSerializer {
         Ok(self)
     }
 
+    /// Writes the quads in a more human-readable way.
+    ///
+    /// The quads are buffered and only written when the serializer is finished:
+    /// * the triples are grouped by graph, subject and predicate using `;` and `,`,
+    /// * the blank nodes used only once as object are written inline using the `[ ... ]` syntax,
+    /// * the well-formed RDF collections are written using the `( ... )` syntax.
+    ///
+    /// ```
+    /// use oxttl::{TriGParser, TriGSerializer};
+    ///
+    /// let file = r#"@prefix ex: <http://example.com/> .
+    /// ex:s ex:p ex:o1 .
+    /// ex:s2 ex:p ex:o .
+    /// ex:s ex:p ex:o2 ; ex:list ( 1 2 ) ; ex:knows [ ex:name "o" ] ."#;
+    ///
+    /// let mut serializer = TriGSerializer::new()
+    ///     .with_prefix("ex", "http://example.com/")?
+    ///     .pretty()
+    ///     .for_writer(Vec::new());
+    /// for quad in TriGParser::new().for_slice(file.as_bytes()) {
+    ///     serializer.serialize_quad(&quad?)?;
+    /// }
+    /// assert_eq!(
+    ///     "@prefix ex: <http://example.com/> .\nex:s ex:p ex:o1 , ex:o2 ;\n\tex:list ( 1 2 ) ;\n\tex:knows [ ex:name \"o\" ] .\nex:s2 ex:p ex:o .\n",
+    ///     String::from_utf8(serializer.finish()?)?
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn pretty(mut self) -> Self {
+        self.pretty = true;
+        self
+    }
+
     /// Writes a TriG file to a [`Write`] implementation.
     ///
     /// ```
@@ -908,6 +944,7 @@ impl TriGSerializer {
             prelude_written: false,
             current_graph_name: GraphName::DefaultGraph,
             current_subject_predicate: None,
+            buffered_quads: self.pretty.then(Vec::new),
         }
     }
 }
@@ -1046,6 +1083,8 @@ pub struct LowLevelTriGSerializer {
     prelude_written: bool,
     current_graph_name: GraphName,
     current_subject_predicate: Option<(NamedOrBlankNode, NamedNode)>,
+    /// The quads to write when finishing in pretty mode
+    buffered_quads: Option<Vec<Quad>>,
 }
 
 impl LowLevelTriGSerializer {
@@ -1055,20 +1094,12 @@ impl LowLevelTriGSerializer {
         q: impl Into<QuadRef<'a>>,
         mut writer: impl Write,
     ) -> io::Result<()> {
-        if !self.prelude_written {
-            self.prelude_written = true;
-            if let Some(base_iri) = &self.base_iri {
-                writeln!(writer, "@base <{base_iri}> .")?;
-            }
-            for (prefix_iri, prefix_name) in &self.prefixes {
-                writeln!(
-                    writer,
-                    "@prefix {prefix_name}: <{}> .",
-                    relative_iri(prefix_iri, &self.base_iri)
-                )?;
-            }
-        }
         let q = q.into();
+        if let Some(buffered_quads) = &mut self.buffered_quads {
+            buffered_quads.push(q.into_owned());
+            return Ok(());
+        }
+        self.write_prelude(&mut writer)?;
         if q.graph_name == self.current_graph_name.as_ref() {
             if let Some((current_subject, current_predicate)) =
                 self.current_subject_predicate.take()
@@ -1152,6 +1183,24 @@ impl LowLevelTriGSerializer {
         }
     }
 
+    fn write_prelude(&mut self, mut writer: impl Write) -> io::Result<()> {
+        if self.prelude_written {
+            return Ok(());
+        }
+        self.prelude_written = true;
+        if let Some(base_iri) = &self.base_iri {
+            writeln!(writer, "@base <{base_iri}> .")?;
+        }
+        for (prefix_iri, prefix_name) in &self.prefixes {
+            writeln!(
+                writer,
+                "@prefix {prefix_name}: <{}> .",
+                relative_iri(prefix_iri, &self.base_iri)
+            )?;
+        }
+        Ok(())
+    }
+
     fn predicate<'a>(&'a self, named_node: impl Into<NamedNodeRef<'a>>) -> TurtlePredicate<'a> {
         TurtlePredicate {
             named_node: named_node.into(),
@@ -1170,6 +1219,13 @@ impl LowLevelTriGSerializer {
 
     /// Finishes to write the file.
     pub fn finish(&mut self, mut writer: impl Write) -> io::Result<()> {
+        if let Some(quads) = self.buffered_quads.take() {
+            if !quads.is_empty() {
+                self.write_prelude(&mut writer)?;
+                PrettySerializer::new(self, &quads).write(writer)?;
+            }
+            return Ok(());
+        }
         if self.current_subject_predicate.is_some() {
             writeln!(writer, " .")?;
         }
@@ -1180,6 +1236,250 @@ impl LowLevelTriGSerializer {
     }
 }
 
+/// The triples of a graph grouped by subject and predicate, in the order of their first use
+#[derive(Default)]
+struct PrettyGraph<'a> {
+    subjects: Vec<NamedOrBlankNodeRef<'a>>,
+    descriptions: HashMap<NamedOrBlankNodeRef<'a>, Vec<(NamedNodeRef<'a>, Vec<TermRef<'a>>)>>,
+}
+
+/// Writes the buffered quads of the pretty mode
+struct PrettySerializer<'a> {
+    serializer: &'a LowLevelTriGSerializer,
+    graphs: Vec<(GraphNameRef<'a>, PrettyGraph<'a>)>,
+    /// Blank nodes used once as object in the same graph as their description, they are written inline
+    inlinable: HashSet<BlankNodeRef<'a>>,
+    /// Blank nodes already written or being written
+    written: HashSet<BlankNodeRef<'a>>,
+}
+
+impl<'a> PrettySerializer<'a> {
+    fn new(serializer: &'a LowLevelTriGSerializer, quads: &'a [Quad]) -> Self {
+        let mut graphs = Vec::<(GraphNameRef<'a>, PrettyGraph<'a>)>::new();
+        let mut graph_ids = HashMap::new();
+        let mut object_counts = HashMap::<BlankNodeRef<'a>, usize>::new();
+        let mut blank_node_graphs = HashMap::new();
+        let mut not_inlinable = HashSet::new();
+        let mut seen = HashSet::new();
+        for quad in quads {
+            let quad = quad.as_ref();
+            if !seen.insert(quad) {
+                continue;
+            }
+            let graph_id = *graph_ids.entry(quad.graph_name).or_insert_with(|| {
+                graphs.push((quad.graph_name, PrettyGraph::default()));
+                graphs.len() - 1
+            });
+            let graph = &mut graphs[graph_id].1;
+            let predicates = match graph.descriptions.entry(quad.subject) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e) => {
+                    graph.subjects.push(quad.subject);
+                    e.insert(Vec::new())
+                }
+            };
+            if let Some((_, objects)) = predicates.iter_mut().find(|(p, _)| *p == quad.predicate) {
+                objects.push(quad.object);
+            } else {
+                predicates.push((quad.predicate, vec![quad.object]));
+            }
+
+            // A blank node can only be written inline if all its uses are in the same graph
+            let mut used_blank_nodes = Vec::new();
+            if let NamedOrBlankNodeRef::BlankNode(subject) = quad.subject {
+                used_blank_nodes.push(subject);
+            }
+            match quad.object {
+                TermRef::BlankNode(object) => {
+                    *object_counts.entry(object).or_default() += 1;
+                    used_blank_nodes.push(object);
+                }
+                #[cfg(feature = "rdf-12")]
+                TermRef::Triple(triple) => add_triple_blank_nodes(triple, &mut not_inlinable),
+                _ => (),
+            }
+            if let GraphNameRef::BlankNode(graph_name) = quad.graph_name {
+                not_inlinable.insert(graph_name);
+            }
+            for blank_node in used_blank_nodes {
+                if *blank_node_graphs
+                    .entry(blank_node)
+                    .or_insert(quad.graph_name)
+                    != quad.graph_name
+                {
+                    not_inlinable.insert(blank_node);
+                }
+            }
+        }
+        for (_, graph) in &mut graphs {
+            for predicates in graph.descriptions.values_mut() {
+                // rdf:type first, like with the "a" shortcut
+                predicates.sort_by_key(|(p, _)| *p != rdf::TYPE);
+            }
+        }
+        Self {
+            serializer,
+            graphs,
+            inlinable: object_counts
+                .into_iter()
+                .filter(|(node, count)| *count == 1 && !not_inlinable.contains(node))
+                .map(|(node, _)| node)
+                .collect(),
+            written: HashSet::new(),
+        }
+    }
+
+    fn write(mut self, mut writer: impl Write) -> io::Result<()> {
+        for (graph_name, graph) in std::mem::take(&mut self.graphs) {
+            let indent = if let Some(graph_name) = match graph_name {
+                GraphNameRef::NamedNode(g) => Some(TermRef::from(g)),
+                GraphNameRef::BlankNode(g) => Some(g.into()),
+                GraphNameRef::DefaultGraph => None,
+            } {
+                writeln!(writer, "{} {{", self.serializer.term(graph_name))?;
+                "\t"
+            } else {
+                ""
+            };
+            // The inlinable blank nodes are written by the description using them
+            // but the ones in a cycle of inlinable blank nodes have to be written at the top level
+            for with_inlinable in [false, true] {
+                for subject in &graph.subjects {
+                    if let NamedOrBlankNodeRef::BlankNode(subject) = subject {
+                        if self.inlinable.contains(subject) != with_inlinable
+                            || !self.written.insert(*subject)
+                        {
+                            continue;
+                        }
+                    } else if with_inlinable {
+                        continue;
+                    }
+                    write!(writer, "{indent}{} ", self.serializer.term(*subject))?;
+                    self.write_predicate_objects(
+                        &graph,
+                        *subject,
+                        &format!(" ;\n{indent}\t"),
+                        &mut writer,
+                    )?;
+                    writeln!(writer, " .")?;
+                }
+            }
+            if !indent.is_empty() {
+                writeln!(writer, "}}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_predicate_objects(
+        &mut self,
+        graph: &PrettyGraph<'a>,
+        subject: NamedOrBlankNodeRef<'a>,
+        separator: &str,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        let Some(predicates) = graph.descriptions.get(&subject) else {
+            return Ok(());
+        };
+        for (i, (predicate, objects)) in predicates.iter().enumerate() {
+            if i > 0 {
+                write!(writer, "{separator}")?;
+            }
+            write!(writer, "{} ", self.serializer.predicate(*predicate))?;
+            for (j, object) in objects.iter().enumerate() {
+                if j > 0 {
+                    write!(writer, " , ")?;
+                }
+                self.write_object(graph, *object, writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_object(
+        &mut self,
+        graph: &PrettyGraph<'a>,
+        object: TermRef<'a>,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        let TermRef::BlankNode(node) = object else {
+            return write!(writer, "{}", self.serializer.term(object));
+        };
+        if !self.inlinable.contains(&node) || self.written.contains(&node) {
+            return write!(writer, "{}", self.serializer.term(object));
+        }
+        if let Some((items, nodes)) = self.collection(graph, node) {
+            self.written.extend(nodes);
+            write!(writer, "(")?;
+            for item in items {
+                write!(writer, " ")?;
+                self.write_object(graph, item, writer)?;
+            }
+            return write!(writer, " )");
+        }
+        self.written.insert(node);
+        if !graph.descriptions.contains_key(&node.into()) {
+            return write!(writer, "[]");
+        }
+        write!(writer, "[ ")?;
+        self.write_predicate_objects(graph, node.into(), " ; ", writer)?;
+        write!(writer, " ]")
+    }
+
+    /// Returns the items and the nodes of the collection starting with the given node if it is well-formed
+    fn collection(
+        &self,
+        graph: &PrettyGraph<'a>,
+        mut node: BlankNodeRef<'a>,
+    ) -> Option<(Vec<TermRef<'a>>, Vec<BlankNodeRef<'a>>)> {
+        let mut items = Vec::new();
+        let mut nodes = Vec::new();
+        loop {
+            if !self.inlinable.contains(&node)
+                || self.written.contains(&node)
+                || nodes.contains(&node)
+            {
+                return None;
+            }
+            nodes.push(node);
+            let [(p1, o1), (p2, o2)] = graph.descriptions.get(&node.into())?.as_slice() else {
+                return None;
+            };
+            let ([first], [rest]) = (match (*p1, *p2) {
+                (rdf::FIRST, rdf::REST) => Some((o1.as_slice(), o2.as_slice())),
+                (rdf::REST, rdf::FIRST) => Some((o2.as_slice(), o1.as_slice())),
+                _ => None,
+            })?
+            else {
+                return None;
+            };
+            items.push(*first);
+            match rest {
+                TermRef::NamedNode(rdf::NIL) => return Some((items, nodes)),
+                TermRef::BlankNode(rest) => node = *rest,
+                _ => return None,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rdf-12")]
+fn add_triple_blank_nodes<'a>(
+    triple: &'a oxrdf::Triple,
+    blank_nodes: &mut HashSet<BlankNodeRef<'a>>,
+) {
+    if let NamedOrBlankNode::BlankNode(subject) = &triple.subject {
+        blank_nodes.insert(subject.as_ref());
+    }
+    match &triple.object {
+        oxrdf::Term::BlankNode(object) => {
+            blank_nodes.insert(object.as_ref());
+        }
+        oxrdf::Term::Triple(triple) => add_triple_blank_nodes(triple, blank_nodes),
+        _ => (),
+    }
+}
+
 struct TurtlePredicate<'a> {
     named_node: NamedNodeRef<'a>,
     prefixes: &'a BTreeMap<String, String>,
@@ -1489,4 +1789,72 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_write_pretty() {
+        let file = "@prefix ex: <http://example.com/> .
+@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+ex:s ex:p [ ex:q [] ] , _:shared ; a ex:C ; ex:l ( ex:a ( 1 ) ) .
+_:c1 ex:p _:c2 .
+_:c2 ex:p _:c1 .
+_:shared ex:p ex:o .
+ex:t ex:p _:shared .
+ex:g { ex:s ex:p _:x . _:x ex:p ex:o . ex:s ex:p _:y . }
+_:y ex:p ex:o .
+ex:bad ex:l [ rdf:first 1 ; rdf:rest 2 ] .";
+        let quads = TriGParser::new()
+            .for_slice(file.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut serializer = TriGSerializer::new()
+            .with_prefix("ex", "http://example.com/")
+            .unwrap()
+            .pretty()
+            .for_writer(Vec::new());
+        for quad in &quads {
+            serializer.serialize_quad(quad).unwrap();
+        }
+        let output = String::from_utf8(serializer.finish().unwrap()).unwrap();
+        assert_eq!(
+            output,
+            "@prefix ex: <http://example.com/> .\nex:s a ex:C ;\n\tex:p [ ex:q [] ] , _:shared ;\n\tex:l ( ex:a ( 1 ) ) .\n_:shared ex:p ex:o .\nex:t ex:p _:shared .\n_:y ex:p ex:o .\nex:bad ex:l [ <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> 1 ; <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> 2 ] .\n_:c1 ex:p [ ex:p _:c1 ] .\nex:g {\n\tex:s ex:p [ ex:p ex:o ] , _:y .\n}\n"
+        );
+        let output_quads = TriGParser::new()
+            .for_slice(output.as_bytes())
+            .collect::<Result<oxrdf::Dataset, _>>()
+            .unwrap();
+        assert!(output_quads.is_isomorphic(&quads.into_iter().collect()));
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        let file = "@prefix ex: <http://example.com/> .
+ex:s1 ex:p ex:o ex:bad .
+ex:s2 ex:p .
+ex:s3 ex:p ex:o .
+ex:g {
+    ex:s4 ex:p \"bad\" ex:o .
+    ex:s5 ex:p ex:o .
+}
+ex:s6 ex:p ex:o .";
+        let mut subjects = Vec::new();
+        let mut error_lines = Vec::new();
+        for result in TriGParser::new().for_slice(file.as_bytes()) {
+            match result {
+                Ok(quad) => subjects.push(format!("{} {}", quad.subject, quad.graph_name)),
+                Err(error) => error_lines.push(error.location().start.line),
+            }
+        }
+        assert_eq!(
+            subjects,
+            [
+                "<http://example.com/s1> DEFAULT",
+                "<http://example.com/s3> DEFAULT",
+                "<http://example.com/s4> <http://example.com/g>",
+                "<http://example.com/s5> <http://example.com/g>",
+                "<http://example.com/s6> DEFAULT"
+            ]
+        );
+        assert_eq!(error_lines, [1, 2, 5]);
+    }
 }
@@ -865,6 +865,51 @@ impl TurtleSerializer {
         Ok(self)
     }
 
+    /// Writes the triples in a more human-readable way.
+    ///
+    /// The triples are buffered and only written when the serializer is finished:
+    /// * the triples are grouped by subject and predicate using `;` and `,`,
+    /// * the blank nodes used only once as object are written inline using the `[ ... ]` syntax,
+    /// * the well-formed RDF collections are written using the `( ... )` syntax.
+    ///
+    /// ```
+    /// use oxrdf::vocab::rdf;
+    /// use oxrdf::{BlankNodeRef, NamedNodeRef, TripleRef};
+    /// use oxttl::TurtleSerializer;
+    ///
+    /// let me = NamedNodeRef::new("http://example.com#me")?;
+    /// let address = BlankNodeRef::new("address")?;
+    /// let mut serializer = TurtleSerializer::new()
+    ///     .with_prefix("schema", "http://schema.org/")?
+    ///     .pretty()
+    ///     .for_writer(Vec::new());
+    /// serializer.serialize_triple(TripleRef::new(
+    ///     me,
+    ///     NamedNodeRef::new("http://schema.org/address")?,
+    ///     address,
+    /// ))?;
+    /// serializer.serialize_triple(TripleRef::new(
+    ///     address,
+    ///     NamedNodeRef::new("http://schema.org/postalCode")?,
+    ///     NamedNodeRef::new("http://example.com/postalCode")?,
+    /// ))?;
+    /// serializer.serialize_triple(TripleRef::new(
+    ///     me,
+    ///     rdf::TYPE,
+    ///     NamedNodeRef::new("http://schema.org/Person")?,
+    /// ))?;
+    /// assert_eq!(
+    ///     b"@prefix schema: <http://schema.org/> .\n<http://example.com#me> a schema:Person ;\n\tschema:address [ schema:postalCode <http://example.com/postalCode> ] .\n",
+    ///     serializer.finish()?.as_slice()
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn pretty(mut self) -> Self {
+        self.inner = self.inner.pretty();
+        self
+    }
+
     /// Writes a Turtle file to a [`Write`] implementation.
     ///
     /// ```
@@ -0,0 +1,992 @@
+//! Serialization of query solutions in the [Apache Arrow IPC streaming format](https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format).
+
+use oxrdf::vocab::xsd;
+use oxrdf::{Term, TermRef, Variable, VariableRef};
+use oxsdatatypes::{Boolean, Date, DateTime, Decimal, Double, Integer};
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// The default number of solutions in each Arrow record batch.
+const DEFAULT_BATCH_SIZE: usize = 8192;
+
+/// Arrow metadata version 5
+const METADATA_VERSION: i16 = 4;
+
+/// A serializer of [SPARQL query](https://www.w3.org/TR/sparql11-query/) solutions into the [Apache Arrow IPC streaming format](https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format).
+///
+/// The output can be read directly by DataFrame libraries like [pandas](https://pandas.pydata.org/) (via `pyarrow.ipc.open_stream`) or [Polars](https://pola.rs/) (via `polars.read_ipc_stream`).
+///
+/// Each variable is a nullable column whose type is inferred from the values of the first record batch:
+/// * `xsd:boolean` values are written as `Boolean`,
+/// * `xsd:integer` and its derived datatypes as `Int64`,
+/// * `xsd:decimal` as `Decimal128(38, 18)`,
+/// * `xsd:double` and `xsd:float` as `Float64`,
+/// * `xsd:date` as `Date32`,
+/// * `xsd:dateTime` as `Timestamp(Microsecond)`, in the UTC timezone if the values have a timezone.
+///
+/// Numeric columns with mixed datatypes are widened to `Decimal128` or `Float64`.
+/// All other columns are written as `Utf8` with the IRIs, the literal lexical forms and the blank node identifiers prefixed by `_:`.
+/// If a value in a following record batch does not fit into its column type, the serialization fails:
+/// use [`with_batch_size`](Self::with_batch_size) to infer the types from more solutions.
+///
+/// ```
+/// use oxrdf::{Literal, Variable, VariableRef};
+/// use sparesults::ArrowSolutionsSerializer;
+/// use std::iter::once;
+///
+/// let mut buffer = Vec::new();
+/// let mut serializer = ArrowSolutionsSerializer::new()
+///     .serialize_solutions_to_writer(&mut buffer, vec![Variable::new("count")?]);
+/// serializer.serialize(once((VariableRef::new("count")?, &Literal::from(42))))?;
+/// serializer.finish()?;
+/// // The stream starts with the schema message
+/// assert!(buffer.starts_with(&[0xFF, 0xFF, 0xFF, 0xFF]));
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+#[derive(Clone)]
+pub struct ArrowSolutionsSerializer {
+    batch_size: usize,
+}
+
+impl ArrowSolutionsSerializer {
+    /// Builds a new serializer writing record batches of 8192 solutions.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Sets the maximal number of solutions in each record batch.
+    ///
+    /// The column types are inferred from the first record batch.
+    #[inline]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Returns a `WriterArrowSolutionsSerializer` allowing writing query solutions into the given [`Write`] implementation.
+    ///
+    /// <div class="warning">
+    ///
+    /// Do not forget to run the [`finish`](WriterArrowSolutionsSerializer::finish()) method to write the last record batch and the end of the stream.</div>
+    pub fn serialize_solutions_to_writer<W: Write>(
+        self,
+        writer: W,
+        variables: Vec<Variable>,
+    ) -> WriterArrowSolutionsSerializer<W> {
+        WriterArrowSolutionsSerializer {
+            writer,
+            batch_size: self.batch_size,
+            column_types: None,
+            columns: vec![Vec::new(); variables.len()],
+            variables,
+        }
+    }
+}
+
+impl Default for ArrowSolutionsSerializer {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Allows writing query solutions into a [`Write`] implementation in the Arrow IPC streaming format.
+///
+/// Could be built using an [`ArrowSolutionsSerializer`].
+///
+/// <div class="warning">
+///
+/// Do not forget to run the [`finish`](WriterArrowSolutionsSerializer::finish()) method to write the last record batch and the end of the stream.</div>
+#[must_use]
+pub struct WriterArrowSolutionsSerializer<W: Write> {
+    writer: W,
+    batch_size: usize,
+    variables: Vec<Variable>,
+    /// Set when the schema is written
+    column_types: Option<Vec<ColumnType>>,
+    /// The values of the current record batch, one vector per variable
+    columns: Vec<Vec<Option<Term>>>,
+}
+
+impl<W: Write> WriterArrowSolutionsSerializer<W> {
+    /// Writes a solution.
+    ///
+    /// The solutions are buffered and written by record batches.
+    pub fn serialize<'a>(
+        &mut self,
+        solution: impl IntoIterator<Item = (impl Into<VariableRef<'a>>, impl Into<TermRef<'a>>)>,
+    ) -> io::Result<()> {
+        let mut values = vec![None; self.variables.len()];
+        for (variable, value) in solution {
+            let variable = variable.into();
+            if let Some(position) = self.variables.iter().position(|v| *v == variable) {
+                values[position] = Some(value.into().into_owned());
+            }
+        }
+        for (column, value) in self.columns.iter_mut().zip(values) {
+            column.push(value);
+        }
+        if self.row_count() >= self.batch_size {
+            self.write_batch()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the last record batch and the end of the stream.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.column_types.is_none() || self.row_count() > 0 {
+            self.write_batch()?;
+        }
+        // End of stream marker
+        self.writer
+            .write_all(&[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0])?;
+        Ok(self.writer)
+    }
+
+    fn row_count(&self) -> usize {
+        self.columns.first().map_or(0, Vec::len)
+    }
+
+    fn write_batch(&mut self) -> io::Result<()> {
+        let row_count = self.row_count();
+        let column_types = if let Some(column_types) = &self.column_types {
+            column_types
+        } else {
+            let column_types = self
+                .columns
+                .iter()
+                .map(|column| infer_column_type(column))
+                .collect::<Vec<_>>();
+            write_message(
+                &mut self.writer,
+                schema_message(&self.variables, &column_types),
+                &[],
+            )?;
+            &*self.column_types.insert(column_types)
+        };
+        if row_count == 0 {
+            return Ok(());
+        }
+        let mut nodes = Vec::with_capacity(self.columns.len());
+        let mut buffers = Vec::new();
+        for ((column, column_type), variable) in
+            self.columns.iter().zip(column_types).zip(&self.variables)
+        {
+            let (null_count, column_buffers) = encode_column(column, *column_type, variable)?;
+            nodes.push([to_i64(row_count)?, to_i64(null_count)?]);
+            buffers.extend(column_buffers);
+        }
+        let mut body = Vec::new();
+        let mut buffer_locations = Vec::with_capacity(buffers.len());
+        for buffer in buffers {
+            buffer_locations.push([to_i64(body.len())?, to_i64(buffer.len())?]);
+            body.extend_from_slice(&buffer);
+            pad(&mut body, 8);
+        }
+        write_message(
+            &mut self.writer,
+            record_batch_message(
+                to_i64(row_count)?,
+                nodes,
+                buffer_locations,
+                to_i64(body.len())?,
+            ),
+            &body,
+        )?;
+        for column in &mut self.columns {
+            column.clear();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+enum ColumnType {
+    Boolean,
+    Integer,
+    Decimal,
+    Double,
+    Date,
+    DateTime,
+    DateTimeWithTimezone,
+    String,
+}
+
+impl ColumnType {
+    /// The smallest type able to store the values of both types
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Integer, Self::Decimal) | (Self::Decimal, Self::Integer) => Self::Decimal,
+            (Self::Integer | Self::Decimal, Self::Double)
+            | (Self::Double, Self::Integer | Self::Decimal) => Self::Double,
+            _ => Self::String,
+        }
+    }
+}
+
+/// A value converted to its native representation
+enum Value<'a> {
+    Boolean(bool),
+    Integer(Integer),
+    Decimal(Decimal),
+    Double(Double),
+    /// Days since the UNIX epoch
+    Date(i32),
+    /// Microseconds since the UNIX epoch
+    DateTime(i64),
+    /// Microseconds since the UNIX epoch in UTC
+    DateTimeWithTimezone(i64),
+    String(Cow<'a, str>),
+}
+
+impl<'a> Value<'a> {
+    fn new(term: &'a Term) -> Self {
+        let literal = match term {
+            Term::NamedNode(node) => return Self::String(node.as_str().into()),
+            Term::BlankNode(node) => return Self::String(format!("_:{}", node.as_str()).into()),
+            Term::Literal(literal) => literal,
+            #[cfg(feature = "sparql-12")]
+            Term::Triple(triple) => return Self::String(triple.to_string().into()),
+        };
+        let value = literal.value();
+        let datatype = literal.datatype();
+        let native = if datatype == xsd::BOOLEAN {
+            Boolean::from_str(value)
+                .ok()
+                .map(|v| Self::Boolean(v.into()))
+        } else if datatype == xsd::INTEGER
+            || datatype == xsd::LONG
+            || datatype == xsd::INT
+            || datatype == xsd::SHORT
+            || datatype == xsd::BYTE
+            || datatype == xsd::NON_NEGATIVE_INTEGER
+            || datatype == xsd::NON_POSITIVE_INTEGER
+            || datatype == xsd::POSITIVE_INTEGER
+            || datatype == xsd::NEGATIVE_INTEGER
+            || datatype == xsd::UNSIGNED_LONG
+            || datatype == xsd::UNSIGNED_INT
+            || datatype == xsd::UNSIGNED_SHORT
+            || datatype == xsd::UNSIGNED_BYTE
+        {
+            // Integers larger than 64 bits are kept as decimals
+            Integer::from_str(value)
+                .ok()
+                .map(Self::Integer)
+                .or_else(|| Decimal::from_str(value).ok().map(Self::Decimal))
+        } else if datatype == xsd::DECIMAL {
+            Decimal::from_str(value).ok().map(Self::Decimal)
+        } else if datatype == xsd::DOUBLE || datatype == xsd::FLOAT {
+            Double::from_str(value).ok().map(Self::Double)
+        } else if datatype == xsd::DATE {
+            Date::from_str(value)
+                .ok()
+                .and_then(|date| days_since_epoch(date.year(), date.month(), date.day()))
+                .and_then(|days| i32::try_from(days).ok())
+                .map(Self::Date)
+        } else if datatype == xsd::DATE_TIME || datatype == xsd::DATE_TIME_STAMP {
+            DateTime::from_str(value).ok().and_then(|date_time| {
+                let micros = micros_since_epoch(date_time)?;
+                Some(if date_time.timezone().is_some() {
+                    Self::DateTimeWithTimezone(micros)
+                } else {
+                    Self::DateTime(micros)
+                })
+            })
+        } else {
+            None
+        };
+        native.unwrap_or_else(|| Self::String(value.into()))
+    }
+
+    fn column_type(&self) -> ColumnType {
+        match self {
+            Self::Boolean(_) => ColumnType::Boolean,
+            Self::Integer(_) => ColumnType::Integer,
+            Self::Decimal(_) => ColumnType::Decimal,
+            Self::Double(_) => ColumnType::Double,
+            Self::Date(_) => ColumnType::Date,
+            Self::DateTime(_) => ColumnType::DateTime,
+            Self::DateTimeWithTimezone(_) => ColumnType::DateTimeWithTimezone,
+            Self::String(_) => ColumnType::String,
+        }
+    }
+}
+
+fn infer_column_type(column: &[Option<Term>]) -> ColumnType {
+    column
+        .iter()
+        .flatten()
+        .map(|term| Value::new(term).column_type())
+        .reduce(ColumnType::merge)
+        .unwrap_or(ColumnType::String)
+}
+
+/// Returns the null count and the Arrow buffers of the column
+fn encode_column(
+    column: &[Option<Term>],
+    column_type: ColumnType,
+    variable: &Variable,
+) -> io::Result<(usize, Vec<Vec<u8>>)> {
+    let mut validity = vec![0; column.len().div_ceil(8)];
+    let mut null_count = 0;
+    let mut values = Vec::new();
+    let mut string_offsets = Vec::new();
+    if column_type == ColumnType::Boolean {
+        values.resize(column.len().div_ceil(8), 0);
+    } else if column_type == ColumnType::String {
+        string_offsets.extend_from_slice(&0_i32.to_le_bytes());
+    }
+    for (i, term) in column.iter().enumerate() {
+        let Some(term) = term else {
+            null_count += 1;
+            match column_type {
+                ColumnType::Boolean => (),
+                ColumnType::Integer
+                | ColumnType::Double
+                | ColumnType::DateTime
+                | ColumnType::DateTimeWithTimezone => values.extend_from_slice(&[0; 8]),
+                ColumnType::Decimal => values.extend_from_slice(&[0; 16]),
+                ColumnType::Date => values.extend_from_slice(&[0; 4]),
+                ColumnType::String => {
+                    string_offsets.extend_from_slice(&to_i32(values.len())?.to_le_bytes())
+                }
+            }
+            continue;
+        };
+        validity[i / 8] |= 1 << (i % 8);
+        match (column_type, Value::new(term)) {
+            (ColumnType::Boolean, Value::Boolean(value)) => {
+                if value {
+                    values[i / 8] |= 1 << (i % 8);
+                }
+            }
+            (ColumnType::Integer, Value::Integer(value)) => {
+                values.extend_from_slice(&i64::from(value).to_le_bytes());
+            }
+            (ColumnType::Decimal, Value::Integer(value)) => {
+                values.extend_from_slice(&decimal_to_le_bytes(value.into()));
+            }
+            (ColumnType::Decimal, Value::Decimal(value)) => {
+                values.extend_from_slice(&decimal_to_le_bytes(value));
+            }
+            (ColumnType::Double, Value::Integer(value)) => {
+                values.extend_from_slice(&f64::from(Double::from(value)).to_le_bytes());
+            }
+            (ColumnType::Double, Value::Decimal(value)) => {
+                values.extend_from_slice(&f64::from(Double::from(value)).to_le_bytes());
+            }
+            (ColumnType::Double, Value::Double(value)) => {
+                values.extend_from_slice(&f64::from(value).to_le_bytes());
+            }
+            (ColumnType::Date, Value::Date(value)) => {
+                values.extend_from_slice(&value.to_le_bytes());
+            }
+            (ColumnType::DateTime, Value::DateTime(value))
+            | (ColumnType::DateTimeWithTimezone, Value::DateTimeWithTimezone(value)) => {
+                values.extend_from_slice(&value.to_le_bytes());
+            }
+            (ColumnType::String, value) => {
+                let value = match value {
+                    Value::String(value) => value,
+                    // The literal has been parsed as a native value
+                    _ => match term {
+                        Term::Literal(literal) => literal.value().into(),
+                        _ => term.to_string().into(),
+                    },
+                };
+                values.extend_from_slice(value.as_bytes());
+                string_offsets.extend_from_slice(&to_i32(values.len())?.to_le_bytes());
+            }
+            (column_type, _) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "The value {term} of {variable} does not fit into the {column_type:?} Arrow column type inferred from the first solutions, use a larger batch size"
+                    ),
+                ));
+            }
+        }
+    }
+    Ok((
+        null_count,
+        if column_type == ColumnType::String {
+            vec![validity, string_offsets, values]
+        } else {
+            vec![validity, values]
+        },
+    ))
+}
+
+fn decimal_to_le_bytes(value: Decimal) -> [u8; 16] {
+    // The decimals are stored as 128 bits integers with 18 fractional digits like Decimal128(38, 18)
+    i128::from_be_bytes(value.to_be_bytes()).to_le_bytes()
+}
+
+/// Number of days from 1970-01-01 in the proleptic Gregorian calendar
+fn days_since_epoch(year: i64, month: u8, day: u8) -> Option<i64> {
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era.checked_mul(146_097)?
+        .checked_add(day_of_era)?
+        .checked_sub(719_468)
+}
+
+fn micros_since_epoch(date_time: DateTime) -> Option<i64> {
+    let days = days_since_epoch(date_time.year(), date_time.month(), date_time.day())?;
+    let minutes = i128::from(days) * 1440
+        + i128::from(date_time.hour()) * 60
+        + i128::from(date_time.minute());
+    // Decimals are 128 bits integers with 18 fractional digits
+    let mut micros = minutes
+        .checked_mul(60_000_000)?
+        .checked_add(i128::from_be_bytes(date_time.second().to_be_bytes()) / 1_000_000_000_000)?;
+    if let Some(timezone) = date_time.timezone() {
+        micros = micros.checked_sub(
+            i128::from_be_bytes(timezone.as_seconds().to_be_bytes()) / 1_000_000_000_000,
+        )?;
+    }
+    i64::try_from(micros).ok()
+}
+
+fn to_i64(value: usize) -> io::Result<i64> {
+    i64::try_from(value).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "The Arrow record batch is too large",
+        )
+    })
+}
+
+fn to_i32(value: usize) -> io::Result<i32> {
+    i32::try_from(value).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "The strings of an Arrow record batch column must be smaller than 2GB, use a smaller batch size",
+        )
+    })
+}
+
+fn schema_message(variables: &[Variable], column_types: &[ColumnType]) -> FlatBufferObject {
+    let fields = variables
+        .iter()
+        .zip(column_types)
+        .map(|(variable, column_type)| {
+            let (type_id, type_table) = match column_type {
+                ColumnType::Boolean => (6, Vec::new()),
+                // Int { bitWidth: 64, is_signed: true }
+                ColumnType::Integer => (
+                    2,
+                    vec![
+                        (0, FlatBufferField::I32(64)),
+                        (1, FlatBufferField::Bool(true)),
+                    ],
+                ),
+                // Decimal { precision: 38, scale: 18, bitWidth: 128 }
+                ColumnType::Decimal => (
+                    7,
+                    vec![
+                        (0, FlatBufferField::I32(38)),
+                        (1, FlatBufferField::I32(18)),
+                        (2, FlatBufferField::I32(128)),
+                    ],
+                ),
+                // FloatingPoint { precision: DOUBLE }
+                ColumnType::Double => (3, vec![(0, FlatBufferField::I16(2))]),
+                // Date { unit: DAY }
+                ColumnType::Date => (8, vec![(0, FlatBufferField::I16(0))]),
+                // Timestamp { unit: MICROSECOND }
+                ColumnType::DateTime => (10, vec![(0, FlatBufferField::I16(2))]),
+                ColumnType::DateTimeWithTimezone => (
+                    10,
+                    vec![
+                        (0, FlatBufferField::I16(2)),
+                        (
+                            1,
+                            FlatBufferField::Object(FlatBufferObject::String("UTC".into())),
+                        ),
+                    ],
+                ),
+                ColumnType::String => (5, Vec::new()),
+            };
+            FlatBufferObject::Table(vec![
+                (
+                    0,
+                    FlatBufferField::Object(FlatBufferObject::String(variable.as_str().into())),
+                ),
+                (1, FlatBufferField::Bool(true)),
+                (2, FlatBufferField::U8(type_id)),
+                (
+                    3,
+                    FlatBufferField::Object(FlatBufferObject::Table(type_table)),
+                ),
+                // Readers require the children vector even if empty
+                (
+                    5,
+                    FlatBufferField::Object(FlatBufferObject::Tables(Vec::new())),
+                ),
+            ])
+        })
+        .collect();
+    let schema = FlatBufferObject::Table(vec![
+        (0, FlatBufferField::I16(0)), // Little endian
+        (1, FlatBufferField::Object(FlatBufferObject::Tables(fields))),
+    ]);
+    message(1, schema, 0)
+}
+
+fn record_batch_message(
+    length: i64,
+    nodes: Vec<[i64; 2]>,
+    buffers: Vec<[i64; 2]>,
+    body_length: i64,
+) -> FlatBufferObject {
+    let record_batch = FlatBufferObject::Table(vec![
+        (0, FlatBufferField::I64(length)),
+        (1, FlatBufferField::Object(FlatBufferObject::Structs(nodes))),
+        (
+            2,
+            FlatBufferField::Object(FlatBufferObject::Structs(buffers)),
+        ),
+    ]);
+    message(3, record_batch, body_length)
+}
+
+fn message(header_type: u8, header: FlatBufferObject, body_length: i64) -> FlatBufferObject {
+    FlatBufferObject::Table(vec![
+        (0, FlatBufferField::I16(METADATA_VERSION)),
+        (1, FlatBufferField::U8(header_type)),
+        (2, FlatBufferField::Object(header)),
+        (3, FlatBufferField::I64(body_length)),
+    ])
+}
+
+/// Writes an encapsulated message: continuation marker, metadata size, metadata and body
+fn write_message(
+    writer: &mut impl Write,
+    message: FlatBufferObject,
+    body: &[u8],
+) -> io::Result<()> {
+    let mut metadata = message.finish();
+    pad(&mut metadata, 8);
+    writer.write_all(&[0xFF, 0xFF, 0xFF, 0xFF])?;
+    writer.write_all(&to_i32(metadata.len())?.to_le_bytes())?;
+    writer.write_all(&metadata)?;
+    writer.write_all(body)
+}
+
+fn pad(buffer: &mut Vec<u8>, alignment: usize) {
+    buffer.resize(buffer.len().next_multiple_of(alignment), 0);
+}
+
+/// A [FlatBuffers](https://flatbuffers.dev/) table field
+enum FlatBufferField {
+    Bool(bool),
+    U8(u8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Object(FlatBufferObject),
+}
+
+impl FlatBufferField {
+    fn size(&self) -> usize {
+        match self {
+            Self::Bool(_) | Self::U8(_) => 1,
+            Self::I16(_) => 2,
+            Self::I32(_) | Self::Object(_) => 4,
+            Self::I64(_) => 8,
+        }
+    }
+}
+
+/// A [FlatBuffers](https://flatbuffers.dev/) object referenced by an offset
+///
+/// It is written front to back: the objects are always written after the offsets pointing to them.
+enum FlatBufferObject {
+    String(String),
+    /// Fields with their ids
+    Table(Vec<(u16, FlatBufferField)>),
+    Tables(Vec<FlatBufferObject>),
+    /// Vector of structs made of two 64 bits integers like Arrow `FieldNode` and `Buffer`
+    Structs(Vec<[i64; 2]>),
+}
+
+impl FlatBufferObject {
+    /// Serializes the object as the root of a buffer
+    fn finish(self) -> Vec<u8> {
+        let mut buffer = vec![0; 4];
+        let position = self.write(&mut buffer);
+        set_offset(&mut buffer, 0, position);
+        buffer
+    }
+
+    /// Writes the object and returns its position
+    fn write(self, buffer: &mut Vec<u8>) -> usize {
+        match self {
+            Self::String(value) => {
+                pad(buffer, 4);
+                let position = buffer.len();
+                write_len(buffer, value.len());
+                buffer.extend_from_slice(value.as_bytes());
+                buffer.push(0);
+                position
+            }
+            Self::Structs(values) => {
+                // The struct values must be aligned on 8 bytes
+                while buffer.len() % 8 != 4 {
+                    buffer.push(0);
+                }
+                let position = buffer.len();
+                write_len(buffer, values.len());
+                for [a, b] in values {
+                    buffer.extend_from_slice(&a.to_le_bytes());
+                    buffer.extend_from_slice(&b.to_le_bytes());
+                }
+                position
+            }
+            Self::Tables(values) => {
+                pad(buffer, 4);
+                let position = buffer.len();
+                write_len(buffer, values.len());
+                let offsets_start = buffer.len();
+                buffer.resize(offsets_start + 4 * values.len(), 0);
+                for (i, value) in values.into_iter().enumerate() {
+                    let value_position = value.write(buffer);
+                    set_offset(buffer, offsets_start + 4 * i, value_position);
+                }
+                position
+            }
+            Self::Table(mut fields) => {
+                // Largest fields first to keep them aligned
+                fields.sort_by_key(|(_, field)| Reverse(field.size()));
+                let slot_count = fields
+                    .iter()
+                    .map(|(id, _)| usize::from(*id) + 1)
+                    .max()
+                    .unwrap_or(0);
+                let mut slots = vec![0_u16; slot_count];
+                let mut table_size = 4; // Offset to the vtable
+                for (id, field) in &fields {
+                    slots[usize::from(*id)] = to_u16(table_size);
+                    table_size += field.size();
+                }
+
+                // The vtable
+                pad(buffer, 2);
+                let vtable_position = buffer.len();
+                buffer.extend_from_slice(&to_u16(4 + 2 * slot_count).to_le_bytes());
+                buffer.extend_from_slice(&to_u16(table_size).to_le_bytes());
+                for slot in slots {
+                    buffer.extend_from_slice(&slot.to_le_bytes());
+                }
+
+                // The table itself, aligned such that its 64 bits fields are aligned
+                if fields.first().is_some_and(|(_, field)| field.size() == 8) {
+                    while buffer.len() % 8 != 4 {
+                        buffer.push(0);
+                    }
+                } else {
+                    pad(buffer, 4);
+                }
+                let position = buffer.len();
+                buffer.extend_from_slice(
+                    &i32::try_from(position - vtable_position)
+                        .unwrap_or(i32::MAX)
+                        .to_le_bytes(),
+                );
+                let mut objects = Vec::new();
+                for (_, field) in fields {
+                    match field {
+                        FlatBufferField::Bool(value) => buffer.push(value.into()),
+                        FlatBufferField::U8(value) => buffer.push(value),
+                        FlatBufferField::I16(value) => {
+                            buffer.extend_from_slice(&value.to_le_bytes());
+                        }
+                        FlatBufferField::I32(value) => {
+                            buffer.extend_from_slice(&value.to_le_bytes());
+                        }
+                        FlatBufferField::I64(value) => {
+                            buffer.extend_from_slice(&value.to_le_bytes());
+                        }
+                        FlatBufferField::Object(object) => {
+                            objects.push((buffer.len(), object));
+                            buffer.extend_from_slice(&[0; 4]);
+                        }
+                    }
+                }
+                for (offset_position, object) in objects {
+                    let object_position = object.write(buffer);
+                    set_offset(buffer, offset_position, object_position);
+                }
+                position
+            }
+        }
+    }
+}
+
+fn write_len(buffer: &mut Vec<u8>, len: usize) {
+    buffer.extend_from_slice(&u32::try_from(len).unwrap_or(u32::MAX).to_le_bytes());
+}
+
+/// Sets the unsigned offset at `position` to point to `target`
+fn set_offset(buffer: &mut [u8], position: usize, target: usize) {
+    let offset = u32::try_from(target - position).unwrap_or(u32::MAX);
+    buffer[position..position + 4].copy_from_slice(&offset.to_le_bytes());
+}
+
+fn to_u16(value: usize) -> u16 {
+    u16::try_from(value).unwrap_or(u16::MAX)
+}
+
+#[cfg(test)]
+#[expect(clippy::panic_in_result_fn)]
+mod tests {
+    use super::*;
+    use oxrdf::{BlankNode, Literal, NamedNode};
+
+    #[test]
+    fn test_days_since_epoch() {
+        assert_eq!(days_since_epoch(1970, 1, 1), Some(0));
+        assert_eq!(days_since_epoch(2000, 3, 1), Some(11017));
+        assert_eq!(days_since_epoch(1969, 12, 31), Some(-1));
+    }
+
+    #[test]
+    fn test_micros_since_epoch() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            micros_since_epoch(DateTime::from_str("1970-01-01T00:00:01.5Z")?),
+            Some(1_500_000)
+        );
+        assert_eq!(
+            micros_since_epoch(DateTime::from_str("1970-01-01T02:00:00+02:00")?),
+            Some(0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_infer_column_type() {
+        let column =
+            |terms: Vec<Term>| infer_column_type(&terms.into_iter().map(Some).collect::<Vec<_>>());
+        assert_eq!(
+            column(vec![Literal::from(1).into(), Literal::from(2).into()]),
+            ColumnType::Integer
+        );
+        assert_eq!(
+            column(vec![Literal::from(1).into(), Literal::from(2.5).into()]),
+            ColumnType::Double
+        );
+        assert_eq!(
+            column(vec![
+                Literal::from(1).into(),
+                Literal::new_typed_literal("1.5", xsd::DECIMAL).into()
+            ]),
+            ColumnType::Decimal
+        );
+        assert_eq!(
+            column(vec![
+                Literal::new_typed_literal("2020-01-01T00:00:00Z", xsd::DATE_TIME).into()
+            ]),
+            ColumnType::DateTimeWithTimezone
+        );
+        assert_eq!(
+            column(vec![
+                Literal::from(1).into(),
+                NamedNode::new_unchecked("http://example.com").into()
+            ]),
+            ColumnType::String
+        );
+        assert_eq!(
+            column(vec![Literal::new_typed_literal("foo", xsd::INTEGER).into()]),
+            ColumnType::String
+        );
+        assert_eq!(infer_column_type(&[None]), ColumnType::String);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let foo = Variable::new_unchecked("foo");
+        let bar = Variable::new_unchecked("bar");
+        let mut serializer = ArrowSolutionsSerializer::new()
+            .serialize_solutions_to_writer(Vec::new(), vec![foo.clone(), bar.clone()]);
+        serializer
+            .serialize([(&foo, &Term::from(Literal::from(1)))])
+            .unwrap();
+        serializer
+            .serialize([
+                (&foo, &Term::from(Literal::from(2))),
+                (&bar, &Term::from(BlankNode::new_unchecked("b"))),
+            ])
+            .unwrap();
+        let output = serializer.finish().unwrap();
+
+        let (schema, body, output) = read_message(&output);
+        assert!(body.is_empty());
+        let schema = read_root(schema);
+        assert_eq!(read_u8(schema, 1), Some(1)); // Schema header
+        let fields = read_tables(read_table(read_table(schema, 2).unwrap(), 1));
+        assert_eq!(fields.len(), 2);
+        assert_eq!(read_string(fields[0], 0), Some("foo"));
+        assert_eq!(read_u8(fields[0], 2), Some(2)); // Int
+        assert_eq!(read_string(fields[1], 0), Some("bar"));
+        assert_eq!(read_u8(fields[1], 2), Some(5)); // Utf8
+
+        let (record_batch, body, output) = read_message(output);
+        let record_batch = read_root(record_batch);
+        assert_eq!(read_u8(record_batch, 1), Some(3)); // RecordBatch header
+        let record_batch = read_table(record_batch, 2).unwrap();
+        assert_eq!(read_i64(record_batch, 0), Some(2));
+        let buffers = read_structs(record_batch, 2);
+        assert_eq!(buffers.len(), 5);
+        let buffer = |i: usize| {
+            let [offset, length] = buffers[i];
+            &body[usize::try_from(offset).unwrap()..usize::try_from(offset + length).unwrap()]
+        };
+        assert_eq!(buffer(0), [0b11]);
+        assert_eq!(buffer(1), [1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(buffer(2), [0b10]);
+        assert_eq!(buffer(3), [0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0]);
+        assert_eq!(buffer(4), b"_:b");
+        assert_eq!(output, [0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_serialize_type_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+        let foo = Variable::new("foo")?;
+        let mut serializer = ArrowSolutionsSerializer::new()
+            .with_batch_size(1)
+            .serialize_solutions_to_writer(Vec::new(), vec![foo.clone()]);
+        serializer.serialize([(&foo, &Term::from(Literal::from(true)))])?;
+        serializer
+            .serialize([(&foo, &Term::from(Literal::from("foo")))])
+            .unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_empty() {
+        let output = ArrowSolutionsSerializer::new()
+            .serialize_solutions_to_writer(Vec::new(), vec![Variable::new_unchecked("foo")])
+            .finish()
+            .unwrap();
+        let (_, _, output) = read_message(&output);
+        assert_eq!(output, [0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]);
+    }
+
+    /// Returns the metadata, the body and the rest of the stream
+    fn read_message(data: &[u8]) -> (&[u8], &[u8], &[u8]) {
+        let (marker, data) = data.split_first_chunk::<4>().unwrap();
+        assert_eq!(*marker, [0xFF; 4]);
+        let (metadata_len, data) = data.split_first_chunk::<4>().unwrap();
+        let (metadata, data) =
+            data.split_at(usize::try_from(u32::from_le_bytes(*metadata_len)).unwrap());
+        let body_len = read_i64(read_root(metadata), 3).unwrap_or(0);
+        let (body, data) = data.split_at(usize::try_from(body_len).unwrap());
+        (metadata, body, data)
+    }
+
+    /// A table is the buffer and its position
+    type Table<'a> = (&'a [u8], usize);
+
+    fn read_u32(buffer: &[u8], position: usize) -> usize {
+        usize::try_from(u32::from_le_bytes(
+            buffer[position..position + 4].try_into().unwrap(),
+        ))
+        .unwrap()
+    }
+
+    fn read_root(buffer: &[u8]) -> Table<'_> {
+        (buffer, read_u32(buffer, 0))
+    }
+
+    fn field_position((buffer, position): Table<'_>, id: usize) -> Option<usize> {
+        let vtable = usize::try_from(
+            i64::try_from(position).unwrap()
+                - i64::from(i32::from_le_bytes(
+                    buffer[position..position + 4].try_into().unwrap(),
+                )),
+        )
+        .unwrap();
+        let vtable_len = usize::from(u16::from_le_bytes(
+            buffer[vtable..vtable + 2].try_into().unwrap(),
+        ));
+        if 4 + 2 * id >= vtable_len {
+            return None;
+        }
+        let offset = usize::from(u16::from_le_bytes(
+            buffer[vtable + 4 + 2 * id..vtable + 6 + 2 * id]
+                .try_into()
+                .unwrap(),
+        ));
+        (offset != 0).then_some(position + offset)
+    }
+
+    fn read_u8(table: Table<'_>, id: usize) -> Option<u8> {
+        Some(table.0[field_position(table, id)?])
+    }
+
+    fn read_i64(table: Table<'_>, id: usize) -> Option<i64> {
+        let position = field_position(table, id)?;
+        assert_eq!(position % 8, 0);
+        Some(i64::from_le_bytes(
+            table.0[position..position + 8].try_into().unwrap(),
+        ))
+    }
+
+    fn read_offset(table: Table<'_>, id: usize) -> Option<usize> {
+        let position = field_position(table, id)?;
+        Some(position + read_u32(table.0, position))
+    }
+
+    fn read_table<'a>(table: Table<'a>, id: usize) -> Option<Table<'a>> {
+        Some((table.0, read_offset(table, id)?))
+    }
+
+    fn read_string<'a>(table: Table<'a>, id: usize) -> Option<&'a str> {
+        let position = read_offset(table, id)?;
+        let len = read_u32(table.0, position);
+        std::str::from_utf8(&table.0[position + 4..position + 4 + len]).ok()
+    }
+
+    fn read_tables(table: Option<Table<'_>>) -> Vec<Table<'_>> {
+        let (buffer, position) = table.unwrap();
+        (0..read_u32(buffer, position))
+            .map(|i| {
+                let offset_position = position + 4 + 4 * i;
+                (buffer, offset_position + read_u32(buffer, offset_position))
+            })
+            .collect()
+    }
+
+    fn read_structs(table: Table<'_>, id: usize) -> Vec<[i64; 2]> {
+        let Some(position) = read_offset(table, id) else {
+            return Vec::new();
+        };
+        assert_eq!((position + 4) % 8, 0);
+        (0..read_u32(table.0, position))
+            .map(|i| {
+                let start = position + 4 + 16 * i;
+                [
+                    i64::from_le_bytes(table.0[start..start + 8].try_into().unwrap()),
+                    i64::from_le_bytes(table.0[start + 8..start + 16].try_into().unwrap()),
+                ]
+            })
+            .collect()
+    }
+}
@@ -24,6 +24,7 @@ pub async fn tokio_async_write_boolean_csv_result<W: AsyncWrite + Unpin>(
     writer
         .write_all(if value { b"true" } else { b"false" })
         .await?;
+    writer.flush().await?;
     Ok(writer)
 }
 
@@ -74,6 +75,7 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterCsvSolutionsSerializer<W> {
         let mut buffer = String::new();
         let inner = InnerCsvSolutionsSerializer::start(&mut buffer, variables);
         writer.write_all(buffer.as_bytes()).await?;
+        writer.flush().await?;
         buffer.clear();
         Ok(Self {
             inner,
@@ -88,12 +90,14 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterCsvSolutionsSerializer<W> {
     ) -> io::Result<()> {
         self.inner.write(&mut self.buffer, solution);
         self.writer.write_all(self.buffer.as_bytes()).await?;
+        self.writer.flush().await?;
         self.buffer.clear();
         Ok(())
     }
 
-    pub fn finish(self) -> W {
-        self.writer
+    pub async fn finish(mut self) -> io::Result<W> {
+        self.writer.flush().await?;
+        Ok(self.writer)
     }
 }
 
@@ -225,6 +229,7 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterTsvSolutionsSerializer<W> {
         let mut buffer = String::new();
         let inner = InnerTsvSolutionsSerializer::start(&mut buffer, variables);
         writer.write_all(buffer.as_bytes()).await?;
+        writer.flush().await?;
         buffer.clear();
         Ok(Self {
             inner,
@@ -239,12 +244,14 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterTsvSolutionsSerializer<W> {
     ) -> io::Result<()> {
         self.inner.write(&mut self.buffer, solution);
         self.writer.write_all(self.buffer.as_bytes()).await?;
+        self.writer.flush().await?;
         self.buffer.clear();
         Ok(())
     }
 
-    pub fn finish(self) -> W {
-        self.writer
+    pub async fn finish(mut self) -> io::Result<W> {
+        self.writer.flush().await?;
+        Ok(self.writer)
     }
 }
 
@@ -0,0 +1,437 @@
+//! Implementation of [`QuerySolution::deserialize`] using [Serde](https://serde.rs/).
+
+use crate::solution::QuerySolution;
+use oxrdf::{LiteralRef, TermRef};
+use oxsdatatypes::{Boolean, Double, Float};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, Deserializer, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use std::fmt;
+use std::str::FromStr;
+
+/// An error raised by [`QuerySolution::deserialize`].
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct SolutionDeserializationError(String);
+
+impl de::Error for SolutionDeserializationError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Maps a solution to a map from the variable names to their values,
+/// or to a sequence of the values in the variables order.
+pub struct SolutionDeserializer<'a> {
+    pub solution: &'a QuerySolution,
+}
+
+impl<'de> Deserializer<'de> for SolutionDeserializer<'de> {
+    type Error = SolutionDeserializationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(SeqDeserializer::new(self.solution.values().iter().map(
+            |value| TermDeserializer {
+                term: value.as_ref().map(Into::into),
+            },
+        )))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(MapDeserializer::new(self.solution.iter().map(
+            |(variable, value)| {
+                (
+                    variable.as_str(),
+                    TermDeserializer {
+                        term: Some(value.as_ref()),
+                    },
+                )
+            },
+        )))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct enum identifier ignored_any
+    }
+}
+
+/// Maps a term (`None` if unbound) to:
+/// * its value (IRI, blank node identifier or literal lexical form) if a string or a unit enum is requested,
+/// * the parsed literal lexical form if a boolean or a number is requested,
+/// * the same structure as the [`Term`](oxrdf::Term) Serde serialization otherwise.
+struct TermDeserializer<'a> {
+    term: Option<TermRef<'a>>,
+}
+
+impl<'a> TermDeserializer<'a> {
+    fn term(&self) -> Result<TermRef<'a>, SolutionDeserializationError> {
+        self.term
+            .ok_or_else(|| de::Error::custom("the variable is not bound"))
+    }
+
+    fn literal(&self) -> Result<LiteralRef<'a>, SolutionDeserializationError> {
+        match self.term()? {
+            TermRef::Literal(literal) => Ok(literal),
+            term => Err(de::Error::custom(format!("{term} is not a literal"))),
+        }
+    }
+
+    fn parse<T: FromStr>(&self) -> Result<T, SolutionDeserializationError>
+    where
+        T::Err: fmt::Display,
+    {
+        let literal = self.literal()?;
+        literal
+            .value()
+            .parse()
+            .map_err(|e| de::Error::custom(format!("Invalid literal {literal}: {e}")))
+    }
+
+    fn value(&self) -> Result<&'a str, SolutionDeserializationError> {
+        Ok(match self.term()? {
+            TermRef::NamedNode(node) => node.as_str(),
+            TermRef::BlankNode(node) => node.as_str(),
+            TermRef::Literal(literal) => literal.value(),
+            #[cfg(feature = "sparql-12")]
+            TermRef::Triple(triple) => {
+                return Err(de::Error::custom(format!(
+                    "The triple {triple} can't be converted to a string"
+                )));
+            }
+        })
+    }
+}
+
+impl<'de> IntoDeserializer<'de, SolutionDeserializationError> for TermDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident: $type:ty),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse::<$type>()?.into())
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for TermDeserializer<'de> {
+    type Error = SolutionDeserializationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let Some(term) = self.term else {
+            return visitor.visit_none();
+        };
+        let mut fields = Vec::new();
+        match term {
+            TermRef::NamedNode(node) => {
+                fields.push(("type", FieldDeserializer::String("uri")));
+                fields.push(("value", FieldDeserializer::String(node.as_str())));
+            }
+            TermRef::BlankNode(node) => {
+                fields.push(("type", FieldDeserializer::String("bnode")));
+                fields.push(("value", FieldDeserializer::String(node.as_str())));
+            }
+            TermRef::Literal(literal) => {
+                fields.push(("type", FieldDeserializer::String("literal")));
+                fields.push(("value", FieldDeserializer::String(literal.value())));
+                if let Some(language) = literal.language() {
+                    fields.push(("language", FieldDeserializer::String(language)));
+                    #[cfg(feature = "sparql-12")]
+                    if let Some(direction) = literal.direction() {
+                        fields.push((
+                            "direction",
+                            FieldDeserializer::String(match direction {
+                                oxrdf::BaseDirection::Ltr => "ltr",
+                                oxrdf::BaseDirection::Rtl => "rtl",
+                            }),
+                        ));
+                    }
+                } else {
+                    fields.push((
+                        "datatype",
+                        FieldDeserializer::String(literal.datatype().as_str()),
+                    ));
+                }
+            }
+            #[cfg(feature = "sparql-12")]
+            TermRef::Triple(triple) => {
+                fields.push(("type", FieldDeserializer::String("triple")));
+                fields.push((
+                    "subject",
+                    FieldDeserializer::Term(triple.subject.as_ref().into()),
+                ));
+                fields.push((
+                    "predicate",
+                    FieldDeserializer::Term(triple.predicate.as_ref().into()),
+                ));
+                fields.push(("object", FieldDeserializer::Term(triple.object.as_ref())));
+            }
+        }
+        visitor.visit_map(MapDeserializer::new(fields.into_iter()))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.term.is_some() {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.term.is_some() {
+            Err(de::Error::custom("the variable is bound"))
+        } else {
+            visitor.visit_unit()
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bool(self.parse::<Boolean>()?.into())
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+        deserialize_f32 => visit_f32: Float,
+        deserialize_f64 => visit_f64: Double,
+        deserialize_char => visit_char: char
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.value()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.value()?.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        bytes byte_buf unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// A field of the Serde representation of a term
+enum FieldDeserializer<'a> {
+    String(&'a str),
+    #[cfg_attr(not(feature = "sparql-12"), expect(dead_code))]
+    Term(TermRef<'a>),
+}
+
+impl<'de> IntoDeserializer<'de, SolutionDeserializationError> for FieldDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for FieldDeserializer<'de> {
+    type Error = SolutionDeserializationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Self::String(value) => visitor.visit_borrowed_str(value),
+            Self::Term(term) => TermDeserializer { term: Some(term) }.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self {
+            Self::String(value) => visitor.visit_enum(value.into_deserializer()),
+            Self::Term(term) => {
+                TermDeserializer { term: Some(term) }.deserialize_enum(name, variants, visitor)
+            }
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple tuple_struct
+        map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxrdf::vocab::xsd;
+    use oxrdf::{BlankNode, Literal, NamedNode, Term, Variable};
+    use serde::Deserialize;
+
+    fn solution(values: Vec<Option<Term>>) -> QuerySolution {
+        QuerySolution::from((
+            (0..values.len())
+                .map(|i| Variable::new_unchecked(format!("v{i}")))
+                .collect::<Vec<_>>(),
+            values,
+        ))
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    enum Status {
+        #[serde(rename = "http://example.com/active")]
+        Active,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Row {
+        v0: Term,
+        v1: BlankNode,
+        v2: bool,
+        v3: f64,
+        v4: i32,
+        v5: Status,
+        #[serde(default)]
+        v6: Option<String>,
+    }
+
+    #[test]
+    fn test_struct() {
+        let iri = NamedNode::new_unchecked("http://example.com/active");
+        let values = vec![
+            Some(Literal::new_language_tagged_literal_unchecked("foo", "en").into()),
+            Some(BlankNode::new_unchecked("b").into()),
+            Some(Literal::new_typed_literal("1", xsd::BOOLEAN).into()),
+            Some(Literal::new_typed_literal("INF", xsd::DOUBLE).into()),
+            Some(Literal::new_typed_literal("+12", xsd::INTEGER).into()),
+            Some(iri.into()),
+            None,
+        ];
+        assert_eq!(
+            solution(values.clone()).deserialize::<Row>().unwrap(),
+            Row {
+                v0: Literal::new_language_tagged_literal_unchecked("foo", "en").into(),
+                v1: BlankNode::new_unchecked("b"),
+                v2: true,
+                v3: f64::INFINITY,
+                v4: 12,
+                v5: Status::Active,
+                v6: None,
+            }
+        );
+        assert_eq!(
+            solution(values)
+                .deserialize::<Vec<Option<Term>>>()
+                .unwrap()
+                .len(),
+            7
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let value = solution(vec![Some(Literal::from("foo").into())]);
+        value.deserialize::<(i32,)>().unwrap_err();
+        value.deserialize::<(NamedNode,)>().unwrap_err();
+        solution(vec![None]).deserialize::<(String,)>().unwrap_err();
+        solution(vec![Some(
+            NamedNode::new_unchecked("http://example.com").into(),
+        )])
+        .deserialize::<(bool,)>()
+        .unwrap_err();
+    }
+
+    #[test]
+    #[cfg(feature = "sparql-12")]
+    fn test_triple() {
+        let triple = oxrdf::Triple::new(
+            NamedNode::new_unchecked("http://example.com/s"),
+            NamedNode::new_unchecked("http://example.com/p"),
+            Literal::new_directional_language_tagged_literal_unchecked(
+                "o",
+                "en",
+                oxrdf::BaseDirection::Rtl,
+            ),
+        );
+        assert_eq!(
+            solution(vec![Some(triple.clone().into())])
+                .deserialize::<(Term,)>()
+                .unwrap(),
+            (triple.into(),)
+        );
+    }
+}
@@ -5,14 +5,16 @@
 use crate::error::{QueryResultsParseError, QueryResultsSyntaxError};
 use json_event_parser::{JsonEvent, ReaderJsonParser, SliceJsonParser, WriterJsonSerializer};
 #[cfg(feature = "async-tokio")]
-use json_event_parser::{TokioAsyncReaderJsonParser, TokioAsyncWriterJsonSerializer};
+use json_event_parser::{
+    LowLevelJsonSerializer, TokioAsyncReaderJsonParser, TokioAsyncWriterJsonSerializer,
+};
 use oxrdf::vocab::{rdf, xsd};
 use oxrdf::*;
 use std::collections::HashMap;
 use std::io::{self, Read, Write};
 use std::mem::take;
 #[cfg(feature = "async-tokio")]
-use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 
 pub fn write_boolean_json_result<W: Write>(writer: W, value: bool) -> io::Result<W> {
     let mut serializer = WriterJsonSerializer::new(writer);
@@ -31,7 +33,9 @@ pub async fn tokio_async_write_boolean_json_result<W: AsyncWrite + Unpin>(
     for event in inner_write_boolean_json_result(value) {
         serializer.serialize_event(event).await?;
     }
-    serializer.finish()
+    let mut writer = serializer.finish()?;
+    writer.flush().await?;
+    Ok(writer)
 }
 
 fn inner_write_boolean_json_result(value: bool) -> [JsonEvent<'static>; 7] {
@@ -90,43 +94,50 @@ impl<W: Write> WriterJsonSolutionsSerializer<W> {
 #[cfg(feature = "async-tokio")]
 pub struct TokioAsyncWriterJsonSolutionsSerializer<W: AsyncWrite + Unpin> {
     inner: InnerJsonSolutionsSerializer,
-    serializer: TokioAsyncWriterJsonSerializer<W>,
+    serializer: LowLevelJsonSerializer,
+    writer: W,
+    buffer: Vec<u8>,
 }
 
 #[cfg(feature = "async-tokio")]
 impl<W: AsyncWrite + Unpin> TokioAsyncWriterJsonSolutionsSerializer<W> {
     pub async fn start(writer: W, variables: &[Variable]) -> io::Result<Self> {
-        let mut serializer = TokioAsyncWriterJsonSerializer::new(writer);
-        let mut buffer = Vec::with_capacity(48);
-        let inner = InnerJsonSolutionsSerializer::start(&mut buffer, variables);
-        Self::do_write(&mut serializer, buffer).await?;
-        Ok(Self { inner, serializer })
+        let mut output = Vec::with_capacity(48);
+        let inner = InnerJsonSolutionsSerializer::start(&mut output, variables);
+        let mut serializer = Self {
+            inner,
+            serializer: LowLevelJsonSerializer::new(),
+            writer,
+            buffer: Vec::new(),
+        };
+        serializer.do_write(output).await?;
+        Ok(serializer)
     }
 
     pub async fn serialize<'a>(
         &mut self,
         solution: impl IntoIterator<Item = (VariableRef<'a>, TermRef<'a>)>,
     ) -> io::Result<()> {
-        let mut buffer = Vec::with_capacity(48);
-        self.inner.write(&mut buffer, solution);
-        Self::do_write(&mut self.serializer, buffer).await
+        let mut output = Vec::with_capacity(48);
+        self.inner.write(&mut output, solution);
+        self.do_write(output).await
     }
 
     pub async fn finish(mut self) -> io::Result<W> {
-        let mut buffer = Vec::with_capacity(4);
-        self.inner.finish(&mut buffer);
-        Self::do_write(&mut self.serializer, buffer).await?;
-        self.serializer.finish()
+        let mut output = Vec::with_capacity(4);
+        self.inner.finish(&mut output);
+        self.do_write(output).await?;
+        Ok(self.writer)
     }
 
-    async fn do_write(
-        serializer: &mut TokioAsyncWriterJsonSerializer<W>,
-        output: Vec<JsonEvent<'_>>,
-    ) -> io::Result<()> {
+    /// Writes the events in a single write and flushes the writer so that they are not kept in a buffer
+    async fn do_write(&mut self, output: Vec<JsonEvent<'_>>) -> io::Result<()> {
         for event in output {
-            serializer.serialize_event(event).await?;
+            self.serializer.serialize_event(event, &mut self.buffer)?;
         }
-        Ok(())
+        self.writer.write_all(&self.buffer).await?;
+        self.buffer.clear();
+        self.writer.flush().await
     }
 }
 
@@ -166,7 +177,7 @@ impl InnerJsonSolutionsSerializer {
     }
 
     #[expect(clippy::unused_self)]
-    fn finish(self, output: &mut Vec<JsonEvent<'_>>) {
+    fn finish(&self, output: &mut Vec<JsonEvent<'_>>) {
         output.push(JsonEvent::EndArray);
         output.push(JsonEvent::EndObject);
         output.push(JsonEvent::EndObject);
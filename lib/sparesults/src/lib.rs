@@ -4,7 +4,10 @@
 #![doc(html_favicon_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
 #![doc(html_logo_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
 
+mod arrow;
 mod csv;
+#[cfg(feature = "serde")]
+mod deserializer;
 mod error;
 mod format;
 mod json;
@@ -13,6 +16,9 @@ mod serializer;
 pub mod solution;
 mod xml;
 
+pub use crate::arrow::{ArrowSolutionsSerializer, WriterArrowSolutionsSerializer};
+#[cfg(feature = "serde")]
+pub use crate::deserializer::SolutionDeserializationError;
 pub use crate::error::{QueryResultsParseError, QueryResultsSyntaxError, TextPosition};
 pub use crate::format::QueryResultsFormat;
 pub use crate::parser::{
@@ -310,9 +310,8 @@ impl<W: Write> WriterSolutionsSerializer<W> {
 ///
 /// Do not forget to run the [`finish`](TokioAsyncWriterSolutionsSerializer::finish()) method to properly write the last bytes of the file.</div>
 ///
-/// <div class="warning">
-///
-/// This writer does unbuffered writes. You might want to use [`BufWriter`](tokio::io::BufWriter) to avoid that.</div>
+/// The writer is flushed after the header and after each solution so that huge results are streamed to the client as they are computed.
+/// You might want to wrap it in a [`BufWriter`](tokio::io::BufWriter) to merge the small writes of each solution.
 ///
 /// Example in TSV (the API is the same for JSON, CSV and XML):
 /// ```
@@ -354,7 +353,7 @@ enum TokioAsyncWriterSolutionsSerializerKind<W: AsyncWrite + Unpin> {
 
 #[cfg(feature = "async-tokio")]
 impl<W: AsyncWrite + Unpin> TokioAsyncWriterSolutionsSerializer<W> {
-    /// Writes a solution.
+    /// Writes a solution and flushes the writer.
     ///
     /// Example in JSON (the API is the same for XML, CSV and TSV):
     /// ```
@@ -400,8 +399,47 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterSolutionsSerializer<W> {
         match self.formatter {
             TokioAsyncWriterSolutionsSerializerKind::Xml(serializer) => serializer.finish().await,
             TokioAsyncWriterSolutionsSerializerKind::Json(serializer) => serializer.finish().await,
-            TokioAsyncWriterSolutionsSerializerKind::Csv(serializer) => Ok(serializer.finish()),
-            TokioAsyncWriterSolutionsSerializerKind::Tsv(serializer) => Ok(serializer.finish()),
+            TokioAsyncWriterSolutionsSerializerKind::Csv(serializer) => serializer.finish().await,
+            TokioAsyncWriterSolutionsSerializerKind::Tsv(serializer) => serializer.finish().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "async-tokio")]
+    #[tokio::test]
+    async fn tokio_async_write_flushes_each_solution() {
+        use super::*;
+        use oxrdf::{LiteralRef, Variable, VariableRef};
+        use std::iter::once;
+        use tokio::io::BufWriter;
+
+        for format in [
+            QueryResultsFormat::Json,
+            QueryResultsFormat::Xml,
+            QueryResultsFormat::Csv,
+            QueryResultsFormat::Tsv,
+        ] {
+            let mut writer = BufWriter::new(Vec::new());
+            let mut serializer = QueryResultsSerializer::from_format(format)
+                .serialize_solutions_to_tokio_async_write(
+                    &mut writer,
+                    vec![Variable::new_unchecked("foo")],
+                )
+                .await
+                .unwrap();
+            serializer
+                .serialize(once((
+                    VariableRef::new_unchecked("foo"),
+                    LiteralRef::from("test"),
+                )))
+                .await
+                .unwrap();
+            drop(serializer);
+            assert!(writer.buffer().is_empty(), "{format} is not flushed");
+            let output = String::from_utf8_lossy(writer.get_ref());
+            assert!(output.contains("test"), "{format} misses the solution");
         }
     }
 }
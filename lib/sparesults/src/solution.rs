@@ -1,6 +1,10 @@
 //! Definition of [`QuerySolution`] structure and associated utility constructions.
 
+#[cfg(feature = "serde")]
+use crate::deserializer::{SolutionDeserializationError, SolutionDeserializer};
 use oxrdf::{Term, Variable, VariableRef};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
 use std::fmt;
 use std::iter::Zip;
 use std::ops::Index;
@@ -144,6 +148,52 @@ impl QuerySolution {
     pub fn variables(&self) -> &[Variable] {
         &self.variables
     }
+
+    /// Deserializes the solution using [Serde](https://serde.rs/).
+    ///
+    /// The solution is seen as a map from the variable names to their bound values, or as the sequence of its values.
+    /// Each value is deserialized as:
+    /// * its IRI, blank node identifier or literal lexical form if a string or a unit enum variant is expected,
+    /// * the parsed literal lexical form if a boolean or a number is expected,
+    /// * the RDF term itself if a type like [`NamedNode`](oxrdf::NamedNode), [`Literal`](oxrdf::Literal) or [`Term`] is expected.
+    ///
+    /// ```
+    /// use oxrdf::{Literal, NamedNode, Variable};
+    /// use serde::Deserialize;
+    /// use sparesults::QuerySolution;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Person<'a> {
+    ///     id: NamedNode,
+    ///     name: &'a str,
+    ///     age: Option<u8>,
+    /// }
+    ///
+    /// let solution = QuerySolution::from((
+    ///     vec![Variable::new("id")?, Variable::new("name")?, Variable::new("age")?],
+    ///     vec![
+    ///         Some(NamedNode::new("http://example.com/foo")?.into()),
+    ///         Some(Literal::from("Foo").into()),
+    ///         None,
+    ///     ],
+    /// ));
+    /// let person: Person<'_> = solution.deserialize()?;
+    /// assert_eq!(person.id.as_str(), "http://example.com/foo");
+    /// assert_eq!(person.name, "Foo");
+    /// assert_eq!(person.age, None);
+    ///
+    /// let (id, name, age): (String, Literal, Option<u8>) = solution.deserialize()?;
+    /// assert_eq!(id, "http://example.com/foo");
+    /// assert_eq!(name, Literal::from("Foo"));
+    /// assert_eq!(age, None);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn deserialize<'a, T: Deserialize<'a>>(
+        &'a self,
+    ) -> Result<T, SolutionDeserializationError> {
+        T::deserialize(SolutionDeserializer { solution: self })
+    }
 }
 
 impl<V: Into<Arc<[Variable]>>, S: Into<Vec<Option<Term>>>> From<(V, S)> for QuerySolution {
@@ -16,7 +16,7 @@ use std::mem::take;
 #[cfg(feature = "async-tokio")]
 use std::sync::Arc;
 #[cfg(feature = "async-tokio")]
-use tokio::io::{AsyncRead, AsyncWrite, BufReader as AsyncBufReader};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader as AsyncBufReader};
 
 pub fn write_boolean_xml_result<W: Write>(writer: W, value: bool) -> io::Result<W> {
     let mut writer = Writer::new(writer);
@@ -38,7 +38,9 @@ pub async fn tokio_async_write_boolean_xml_result<W: AsyncWrite + Unpin>(
             .await
             .map_err(map_xml_error)?;
     }
-    Ok(writer.into_inner())
+    let mut writer = writer.into_inner();
+    writer.flush().await?;
+    Ok(writer)
 }
 
 fn inner_write_boolean_xml_result(value: bool) -> [Event<'static>; 8] {
@@ -127,6 +129,7 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterXmlSolutionsSerializer<W> {
         Ok(self.writer.into_inner())
     }
 
+    /// Writes the events and flushes the writer so that they are not kept in a buffer
     async fn do_write(writer: &mut Writer<W>, output: Vec<Event<'_>>) -> io::Result<()> {
         for event in output {
             writer
@@ -134,7 +137,7 @@ impl<W: AsyncWrite + Unpin> TokioAsyncWriterXmlSolutionsSerializer<W> {
                 .await
                 .map_err(map_xml_error)?;
         }
-        Ok(())
+        writer.get_mut().flush().await
     }
 }
 
@@ -1,10 +1,11 @@
-use crate::CustomFunctionRegistry;
 #[cfg(feature = "sparql-12")]
 use crate::dataset::ExpressionTriple;
 use crate::dataset::{ExpressionTerm, InternalQuad, QueryableDataset};
 use crate::error::QueryEvaluationError;
+use crate::literal::LiteralValueHandlerRegistry;
 use crate::model::{QuerySolutionIter, QueryTripleIter};
-use crate::service::ServiceHandlerRegistry;
+use crate::service::{ServiceBatching, ServiceHandlerRegistry};
+use crate::{CustomFunctionRegistry, IntegerOverflowBehavior};
 use json_event_parser::{JsonEvent, WriterJsonSerializer};
 use md5::{Digest, Md5};
 use oxiri::Iri;
@@ -30,14 +31,16 @@ use sparopt::algebra::{
     AggregateExpression, Expression, GraphPattern, JoinAlgorithm, LeftJoinAlgorithm,
     MinusAlgorithm, OrderExpression,
 };
+use sparopt::{DatasetStatistics, Optimizer};
 use std::borrow::Cow;
 use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use std::iter::{Peekable, empty, once};
+use std::mem::take;
 use std::rc::Rc;
 use std::sync::Arc;
-use std::{fmt, io};
+use std::{fmt, io, thread};
 // TODO: make expression raise error when relevant (storage I/O)
 
 const REGEX_SIZE_LIMIT: usize = 1_000_000;
@@ -236,7 +239,11 @@ pub struct SimpleEvaluator<D: QueryableDataset> {
     now: DateTime,
     service_handler: Rc<ServiceHandlerRegistry>,
     custom_functions: Rc<CustomFunctionRegistry>,
+    literal_value_handlers: Rc<LiteralValueHandlerRegistry>,
+    dataset_statistics: Option<Arc<DatasetStatistics>>,
     run_stats: bool,
+    integer_overflow_behavior: IntegerOverflowBehavior,
+    service_batching: Option<ServiceBatching>,
 }
 
 impl<D: QueryableDataset> SimpleEvaluator<D> {
@@ -245,7 +252,11 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
         base_iri: Option<Rc<Iri<String>>>,
         service_handler: Rc<ServiceHandlerRegistry>,
         custom_functions: Rc<CustomFunctionRegistry>,
+        literal_value_handlers: Rc<LiteralValueHandlerRegistry>,
+        dataset_statistics: Option<Arc<DatasetStatistics>>,
         run_stats: bool,
+        integer_overflow_behavior: IntegerOverflowBehavior,
+        service_batching: Option<ServiceBatching>,
     ) -> Self {
         Self {
             dataset: EvalDataset {
@@ -255,7 +266,11 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
             now: DateTime::now(),
             service_handler,
             custom_functions,
+            literal_value_handlers,
+            dataset_statistics,
             run_stats,
+            integer_overflow_behavior,
+            service_batching,
         }
     }
 
@@ -406,6 +421,11 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
         let stats = Rc::new(EvalNodeWithStats {
             label: eval_node_label(pattern),
             children: stat_children,
+            estimated_cardinality: if let Some(statistics) = &self.dataset_statistics {
+                Optimizer::estimate_cardinality_with_statistics(pattern, statistics)
+            } else {
+                Optimizer::estimate_cardinality(pattern)
+            },
             exec_count: Cell::new(0),
             exec_duration: Cell::new(self.run_stats.then(DayTimeDuration::default)),
         });
@@ -1029,6 +1049,15 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
                 right,
                 algorithm,
             } => {
+                if let Some(evaluator) = self.bind_join_evaluator(
+                    left,
+                    right,
+                    algorithm,
+                    encoded_variables,
+                    stat_children,
+                ) {
+                    return evaluator;
+                }
                 let (left, left_stats) = self.graph_pattern_evaluator(left, encoded_variables);
                 stat_children.push(left_stats);
                 let (right, right_stats) = self.graph_pattern_evaluator(right, encoded_variables);
@@ -1339,6 +1368,7 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
                         ),
                     })
                     .collect::<Vec<_>>();
+                let literal_value_handlers = Rc::clone(&self.literal_value_handlers);
                 Rc::new(move |from| {
                     let mut errors = Vec::default();
                     let mut values = child(from)
@@ -1354,16 +1384,22 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
                         for comp in &by {
                             match comp {
                                 ComparatorFunction::Asc(expression) => {
-                                    match cmp_terms(expression(a).as_ref(), expression(b).as_ref())
-                                    {
+                                    match cmp_terms(
+                                        expression(a).as_ref(),
+                                        expression(b).as_ref(),
+                                        &literal_value_handlers,
+                                    ) {
                                         Ordering::Greater => return Ordering::Greater,
                                         Ordering::Less => return Ordering::Less,
                                         Ordering::Equal => (),
                                     }
                                 }
                                 ComparatorFunction::Desc(expression) => {
-                                    match cmp_terms(expression(a).as_ref(), expression(b).as_ref())
-                                    {
+                                    match cmp_terms(
+                                        expression(a).as_ref(),
+                                        expression(b).as_ref(),
+                                        &literal_value_handlers,
+                                    ) {
                                         Ordering::Greater => return Ordering::Less,
                                         Ordering::Less => return Ordering::Greater,
                                         Ordering::Equal => (),
@@ -1612,6 +1648,111 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
         Ok(encode_bindings(self.dataset.clone(), variables, iter))
     }
 
+    /// Builds a bind join if bind joins are enabled and one side of the join is a SERVICE call
+    ///
+    /// The other side is evaluated first and its bindings are sent to the service in VALUES blocks.
+    fn bind_join_evaluator(
+        &self,
+        left: &GraphPattern,
+        right: &GraphPattern,
+        algorithm: &JoinAlgorithm,
+        encoded_variables: &mut Vec<Variable>,
+        stat_children: &mut Vec<Rc<EvalNodeWithStats>>,
+    ) -> Option<Rc<dyn Fn(InternalTuple<D>) -> InternalTuplesIterator<D>>> {
+        let batching = self.service_batching?;
+        let JoinAlgorithm::HashBuildLeftProbeRight { keys } = algorithm;
+        if keys.is_empty() {
+            return None;
+        }
+        let (service_on_right, service_name, inner, silent) = match (left, right) {
+            (
+                _,
+                GraphPattern::Service {
+                    name: NamedNodePattern::NamedNode(name),
+                    inner,
+                    silent,
+                },
+            ) => (true, name.clone(), inner, *silent),
+            (
+                GraphPattern::Service {
+                    name: NamedNodePattern::NamedNode(name),
+                    inner,
+                    silent,
+                },
+                _,
+            ) => (false, name.clone(), inner, *silent),
+            _ => return None,
+        };
+        let (left, left_stats) = self.graph_pattern_evaluator(left, encoded_variables);
+        stat_children.push(left_stats);
+        let (right, right_stats) = self.graph_pattern_evaluator(right, encoded_variables);
+        stat_children.push(right_stats);
+        let outer = if service_on_right { left } else { right };
+        let keys = keys
+            .iter()
+            .map(|v| (encode_variable(encoded_variables, v), v.clone()))
+            .collect::<Rc<[_]>>();
+        let graph_pattern = Rc::new(spargebra::algebra::GraphPattern::from(inner.as_ref()));
+        let variables = Rc::from(encoded_variables.as_slice());
+        let eval = self.clone();
+        Some(Rc::new(move |from| {
+            Box::new(BindJoinIterator {
+                eval: eval.clone(),
+                outer: outer(from),
+                service_name: service_name.clone(),
+                graph_pattern: Rc::clone(&graph_pattern),
+                keys: Rc::clone(&keys),
+                variables: Rc::clone(&variables),
+                silent,
+                batching,
+                current: Box::new(empty()),
+            })
+        }))
+    }
+
+    /// Sends the given patterns to the service, each one in its own thread if there are more than one
+    fn call_service_batches(
+        &self,
+        service_name: &NamedNode,
+        patterns: Vec<spargebra::algebra::GraphPattern>,
+    ) -> Vec<Result<QuerySolutionIter, QueryEvaluationError>> {
+        let base_iri = self.base_iri.as_ref().map(ToString::to_string);
+        if patterns.len() <= 1 {
+            return patterns
+                .into_iter()
+                .map(|pattern| {
+                    self.service_handler
+                        .handle(service_name.clone(), pattern, base_iri.clone())
+                })
+                .collect();
+        }
+        let service_handler: &ServiceHandlerRegistry = &self.service_handler;
+        thread::scope(|scope| {
+            let calls = patterns
+                .into_iter()
+                .map(|pattern| {
+                    let service_name = service_name.clone();
+                    let base_iri = base_iri.clone();
+                    // The solutions are collected in the thread because the iterators are not Send
+                    scope.spawn(move || -> Result<_, QueryEvaluationError> {
+                        let iter = service_handler.handle(service_name, pattern, base_iri)?;
+                        let variables = Arc::<[Variable]>::from(iter.variables());
+                        Ok((variables, iter.collect::<Vec<_>>()))
+                    })
+                })
+                .collect::<Vec<_>>();
+            calls
+                .into_iter()
+                .map(|call| {
+                    let (variables, solutions) = call
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+                    Ok(QuerySolutionIter::new(variables, solutions.into_iter()))
+                })
+                .collect()
+        })
+    }
+
     fn accumulator_builder(
         &self,
         expression: &AggregateExpression,
@@ -1667,66 +1808,86 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
                     }
                 }
                 AggregateFunction::Sum => {
+                    let integer_overflow_behavior = self.integer_overflow_behavior;
                     let evaluator =
                         self.expression_evaluator(expr, encoded_variables, stat_children);
                     if *distinct {
                         Box::new(move || AccumulatorWrapper::DistinctExpression {
                             evaluator: Rc::clone(&evaluator),
                             seen: FxHashSet::default(),
-                            accumulator: Some(Box::new(SumAccumulator::default())),
+                            accumulator: Some(Box::new(SumAccumulator::new(
+                                integer_overflow_behavior,
+                            ))),
                         })
                     } else {
                         Box::new(move || AccumulatorWrapper::Expression {
                             evaluator: Rc::clone(&evaluator),
-                            accumulator: Some(Box::new(SumAccumulator::default())),
+                            accumulator: Some(Box::new(SumAccumulator::new(
+                                integer_overflow_behavior,
+                            ))),
                         })
                     }
                 }
                 AggregateFunction::Min => {
+                    let literal_value_handlers = Rc::clone(&self.literal_value_handlers);
                     let evaluator =
                         self.expression_evaluator(expr, encoded_variables, stat_children);
                     if *distinct {
                         Box::new(move || AccumulatorWrapper::DistinctExpression {
                             evaluator: Rc::clone(&evaluator),
                             seen: FxHashSet::default(),
-                            accumulator: Some(Box::new(MinAccumulator::default())),
+                            accumulator: Some(Box::new(MinAccumulator::new(Rc::clone(
+                                &literal_value_handlers,
+                            )))),
                         })
                     } else {
                         Box::new(move || AccumulatorWrapper::Expression {
                             evaluator: Rc::clone(&evaluator),
-                            accumulator: Some(Box::new(MinAccumulator::default())),
+                            accumulator: Some(Box::new(MinAccumulator::new(Rc::clone(
+                                &literal_value_handlers,
+                            )))),
                         })
                     }
                 }
                 AggregateFunction::Max => {
+                    let literal_value_handlers = Rc::clone(&self.literal_value_handlers);
                     let evaluator =
                         self.expression_evaluator(expr, encoded_variables, stat_children);
                     if *distinct {
                         Box::new(move || AccumulatorWrapper::DistinctExpression {
                             evaluator: Rc::clone(&evaluator),
                             seen: FxHashSet::default(),
-                            accumulator: Some(Box::new(MaxAccumulator::default())),
+                            accumulator: Some(Box::new(MaxAccumulator::new(Rc::clone(
+                                &literal_value_handlers,
+                            )))),
                         })
                     } else {
                         Box::new(move || AccumulatorWrapper::Expression {
                             evaluator: Rc::clone(&evaluator),
-                            accumulator: Some(Box::new(MaxAccumulator::default())),
+                            accumulator: Some(Box::new(MaxAccumulator::new(Rc::clone(
+                                &literal_value_handlers,
+                            )))),
                         })
                     }
                 }
                 AggregateFunction::Avg => {
+                    let integer_overflow_behavior = self.integer_overflow_behavior;
                     let evaluator =
                         self.expression_evaluator(expr, encoded_variables, stat_children);
                     if *distinct {
                         Box::new(move || AccumulatorWrapper::DistinctExpression {
                             evaluator: Rc::clone(&evaluator),
                             seen: FxHashSet::default(),
-                            accumulator: Some(Box::new(AvgAccumulator::default())),
+                            accumulator: Some(Box::new(AvgAccumulator::new(
+                                integer_overflow_behavior,
+                            ))),
                         })
                     } else {
                         Box::new(move || AccumulatorWrapper::Expression {
                             evaluator: Rc::clone(&evaluator),
-                            accumulator: Some(Box::new(AvgAccumulator::default())),
+                            accumulator: Some(Box::new(AvgAccumulator::new(
+                                integer_overflow_behavior,
+                            ))),
                         })
                     }
                 }
@@ -1939,7 +2100,10 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
             Expression::Equal(a, b) => {
                 let a = self.expression_evaluator(a, encoded_variables, stat_children);
                 let b = self.expression_evaluator(b, encoded_variables, stat_children);
-                Rc::new(move |tuple| equals(&a(tuple)?, &b(tuple)?).map(Into::into))
+                let literal_value_handlers = Rc::clone(&self.literal_value_handlers);
+                Rc::new(move |tuple| {
+                    equals(&a(tuple)?, &b(tuple)?, &literal_value_handlers).map(Into::into)
+                })
             }
             Expression::SameTerm(a, b) => {
                 if let (Some(a), Some(b)) = (
@@ -1956,16 +2120,22 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
             Expression::Greater(a, b) => {
                 let a = self.expression_evaluator(a, encoded_variables, stat_children);
                 let b = self.expression_evaluator(b, encoded_variables, stat_children);
+                let literal_value_handlers = Rc::clone(&self.literal_value_handlers);
                 Rc::new(move |tuple| {
-                    Some((partial_cmp(&a(tuple)?, &b(tuple)?)? == Ordering::Greater).into())
+                    Some(
+                        (partial_cmp(&a(tuple)?, &b(tuple)?, &literal_value_handlers)?
+                            == Ordering::Greater)
+                            .into(),
+                    )
                 })
             }
             Expression::GreaterOrEqual(a, b) => {
                 let a = self.expression_evaluator(a, encoded_variables, stat_children);
                 let b = self.expression_evaluator(b, encoded_variables, stat_children);
+                let literal_value_handlers = Rc::clone(&self.literal_value_handlers);
                 Rc::new(move |tuple| {
                     Some(
-                        match partial_cmp(&a(tuple)?, &b(tuple)?)? {
+                        match partial_cmp(&a(tuple)?, &b(tuple)?, &literal_value_handlers)? {
                             Ordering::Greater | Ordering::Equal => true,
                             Ordering::Less => false,
                         }
@@ -1976,16 +2146,22 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
             Expression::Less(a, b) => {
                 let a = self.expression_evaluator(a, encoded_variables, stat_children);
                 let b = self.expression_evaluator(b, encoded_variables, stat_children);
+                let literal_value_handlers = Rc::clone(&self.literal_value_handlers);
                 Rc::new(move |tuple| {
-                    Some((partial_cmp(&a(tuple)?, &b(tuple)?)? == Ordering::Less).into())
+                    Some(
+                        (partial_cmp(&a(tuple)?, &b(tuple)?, &literal_value_handlers)?
+                            == Ordering::Less)
+                            .into(),
+                    )
                 })
             }
             Expression::LessOrEqual(a, b) => {
                 let a = self.expression_evaluator(a, encoded_variables, stat_children);
                 let b = self.expression_evaluator(b, encoded_variables, stat_children);
+                let literal_value_handlers = Rc::clone(&self.literal_value_handlers);
                 Rc::new(move |tuple| {
                     Some(
-                        match partial_cmp(&a(tuple)?, &b(tuple)?)? {
+                        match partial_cmp(&a(tuple)?, &b(tuple)?, &literal_value_handlers)? {
                             Ordering::Less | Ordering::Equal => true,
                             Ordering::Greater => false,
                         }
@@ -1996,6 +2172,7 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
             Expression::Add(a, b) => {
                 let a = self.expression_evaluator(a, encoded_variables, stat_children);
                 let b = self.expression_evaluator(b, encoded_variables, stat_children);
+                let integer_overflow_behavior = self.integer_overflow_behavior;
                 Rc::new(move |tuple| {
                     Some(match NumericBinaryOperands::new(a(tuple)?, b(tuple)?)? {
                         NumericBinaryOperands::Float(v1, v2) => {
@@ -2005,7 +2182,7 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
                             ExpressionTerm::DoubleLiteral(v1 + v2)
                         }
                         NumericBinaryOperands::Integer(v1, v2) => {
-                            ExpressionTerm::IntegerLiteral(v1.checked_add(v2)?)
+                            integer_overflow_behavior.add(v1, v2)?
                         }
                         NumericBinaryOperands::Decimal(v1, v2) => {
                             ExpressionTerm::DecimalLiteral(v1.checked_add(v2)?)
@@ -2064,6 +2241,7 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
             Expression::Subtract(a, b) => {
                 let a = self.expression_evaluator(a, encoded_variables, stat_children);
                 let b = self.expression_evaluator(b, encoded_variables, stat_children);
+                let integer_overflow_behavior = self.integer_overflow_behavior;
                 Rc::new(move |tuple| {
                     Some(match NumericBinaryOperands::new(a(tuple)?, b(tuple)?)? {
                         NumericBinaryOperands::Float(v1, v2) => {
@@ -2073,7 +2251,7 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
                             ExpressionTerm::DoubleLiteral(v1 - v2)
                         }
                         NumericBinaryOperands::Integer(v1, v2) => {
-                            ExpressionTerm::IntegerLiteral(v1.checked_sub(v2)?)
+                            integer_overflow_behavior.sub(v1, v2)?
                         }
                         NumericBinaryOperands::Decimal(v1, v2) => {
                             ExpressionTerm::DecimalLiteral(v1.checked_sub(v2)?)
@@ -2140,6 +2318,7 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
             Expression::Multiply(a, b) => {
                 let a = self.expression_evaluator(a, encoded_variables, stat_children);
                 let b = self.expression_evaluator(b, encoded_variables, stat_children);
+                let integer_overflow_behavior = self.integer_overflow_behavior;
                 Rc::new(move |tuple| {
                     Some(match NumericBinaryOperands::new(a(tuple)?, b(tuple)?)? {
                         NumericBinaryOperands::Float(v1, v2) => {
@@ -2149,7 +2328,7 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
                             ExpressionTerm::DoubleLiteral(v1 * v2)
                         }
                         NumericBinaryOperands::Integer(v1, v2) => {
-                            ExpressionTerm::IntegerLiteral(v1.checked_mul(v2)?)
+                            integer_overflow_behavior.mul(v1, v2)?
                         }
                         NumericBinaryOperands::Decimal(v1, v2) => {
                             ExpressionTerm::DecimalLiteral(v1.checked_mul(v2)?)
@@ -2213,6 +2392,7 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
             }
             Expression::UnaryMinus(e) => {
                 let e = self.expression_evaluator(e, encoded_variables, stat_children);
+                let integer_overflow_behavior = self.integer_overflow_behavior;
                 Rc::new(move |tuple| {
                     Some(match e(tuple)? {
                         ExpressionTerm::FloatLiteral(value) => ExpressionTerm::FloatLiteral(-value),
@@ -2220,7 +2400,7 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
                             ExpressionTerm::DoubleLiteral(-value)
                         }
                         ExpressionTerm::IntegerLiteral(value) => {
-                            ExpressionTerm::IntegerLiteral(value.checked_neg()?)
+                            integer_overflow_behavior.neg(value)?
                         }
                         ExpressionTerm::DecimalLiteral(value) => {
                             ExpressionTerm::DecimalLiteral(value.checked_neg()?)
@@ -2446,9 +2626,10 @@ impl<D: QueryableDataset> SimpleEvaluator<D> {
                 Function::Abs => {
                     let e =
                         self.expression_evaluator(&parameters[0], encoded_variables, stat_children);
+                    let integer_overflow_behavior = self.integer_overflow_behavior;
                     Rc::new(move |tuple| match e(tuple)? {
                         ExpressionTerm::IntegerLiteral(value) => {
-                            Some(ExpressionTerm::IntegerLiteral(value.checked_abs()?))
+                            integer_overflow_behavior.abs(value)
                         }
                         ExpressionTerm::DecimalLiteral(value) => {
                             Some(ExpressionTerm::DecimalLiteral(value.checked_abs()?))
@@ -3568,7 +3749,11 @@ impl<D: QueryableDataset> Clone for SimpleEvaluator<D> {
             now: self.now,
             service_handler: Rc::clone(&self.service_handler),
             custom_functions: Rc::clone(&self.custom_functions),
+            literal_value_handlers: Rc::clone(&self.literal_value_handlers),
+            dataset_statistics: self.dataset_statistics.clone(),
             run_stats: self.run_stats,
+            integer_overflow_behavior: self.integer_overflow_behavior,
+            service_batching: self.service_batching,
         }
     }
 }
@@ -3902,6 +4087,65 @@ impl<D: QueryableDataset> AccumulatorWrapper<D> {
     }
 }
 
+impl IntegerOverflowBehavior {
+    fn add(self, v1: Integer, v2: Integer) -> Option<ExpressionTerm> {
+        self.handle(
+            v1.checked_add(v2),
+            || Decimal::from(v1).checked_add(v2),
+            || i64::from(v1).saturating_add(v2.into()),
+        )
+    }
+
+    fn sub(self, v1: Integer, v2: Integer) -> Option<ExpressionTerm> {
+        self.handle(
+            v1.checked_sub(v2),
+            || Decimal::from(v1).checked_sub(v2),
+            || i64::from(v1).saturating_sub(v2.into()),
+        )
+    }
+
+    fn mul(self, v1: Integer, v2: Integer) -> Option<ExpressionTerm> {
+        self.handle(
+            v1.checked_mul(v2),
+            || Decimal::from(v1).checked_mul(v2),
+            || i64::from(v1).saturating_mul(v2.into()),
+        )
+    }
+
+    fn neg(self, value: Integer) -> Option<ExpressionTerm> {
+        self.handle(
+            value.checked_neg(),
+            || Decimal::from(value).checked_neg(),
+            || i64::from(value).saturating_neg(),
+        )
+    }
+
+    fn abs(self, value: Integer) -> Option<ExpressionTerm> {
+        self.handle(
+            value.checked_abs(),
+            || Decimal::from(value).checked_abs(),
+            || i64::from(value).saturating_abs(),
+        )
+    }
+
+    /// Returns the integer result if there is no overflow and else applies the overflow behavior
+    fn handle(
+        self,
+        result: Option<Integer>,
+        promoted: impl FnOnce() -> Option<Decimal>,
+        saturated: impl FnOnce() -> i64,
+    ) -> Option<ExpressionTerm> {
+        if let Some(result) = result {
+            return Some(ExpressionTerm::IntegerLiteral(result));
+        }
+        match self {
+            Self::Error => None,
+            Self::PromoteToDecimal => promoted().map(ExpressionTerm::DecimalLiteral),
+            Self::Saturate => Some(ExpressionTerm::IntegerLiteral(saturated().into())),
+        }
+    }
+}
+
 trait Accumulator {
     fn add(&mut self, element: ExpressionTerm);
 
@@ -3925,12 +4169,14 @@ impl Accumulator for CountAccumulator {
 
 struct SumAccumulator {
     sum: Option<ExpressionTerm>,
+    integer_overflow_behavior: IntegerOverflowBehavior,
 }
 
-impl Default for SumAccumulator {
-    fn default() -> Self {
+impl SumAccumulator {
+    fn new(integer_overflow_behavior: IntegerOverflowBehavior) -> Self {
         Self {
             sum: Some(ExpressionTerm::IntegerLiteral(Integer::default())),
+            integer_overflow_behavior,
         }
     }
 }
@@ -3948,7 +4194,7 @@ impl Accumulator for SumAccumulator {
                     Some(ExpressionTerm::DoubleLiteral(v1 + v2))
                 }
                 NumericBinaryOperands::Integer(v1, v2) => {
-                    v1.checked_add(v2).map(ExpressionTerm::IntegerLiteral)
+                    self.integer_overflow_behavior.add(v1, v2)
                 }
                 NumericBinaryOperands::Decimal(v1, v2) => {
                     v1.checked_add(v2).map(ExpressionTerm::DecimalLiteral)
@@ -3966,12 +4212,20 @@ impl Accumulator for SumAccumulator {
     }
 }
 
-#[derive(Default)]
 struct AvgAccumulator {
     sum: SumAccumulator,
     count: i64,
 }
 
+impl AvgAccumulator {
+    fn new(integer_overflow_behavior: IntegerOverflowBehavior) -> Self {
+        Self {
+            sum: SumAccumulator::new(integer_overflow_behavior),
+            count: 0,
+        }
+    }
+}
+
 impl Accumulator for AvgAccumulator {
     fn add(&mut self, element: ExpressionTerm) {
         self.sum.add(element);
@@ -4003,16 +4257,27 @@ impl Accumulator for AvgAccumulator {
     }
 }
 
-#[derive(Default)]
 #[expect(clippy::option_option)]
 struct MinAccumulator {
     min: Option<Option<ExpressionTerm>>,
+    literal_value_handlers: Rc<LiteralValueHandlerRegistry>,
+}
+
+impl MinAccumulator {
+    fn new(literal_value_handlers: Rc<LiteralValueHandlerRegistry>) -> Self {
+        Self {
+            min: None,
+            literal_value_handlers,
+        }
+    }
 }
 
 impl Accumulator for MinAccumulator {
     fn add(&mut self, element: ExpressionTerm) {
         if let Some(min) = &self.min {
-            if cmp_terms(Some(&element), min.as_ref()) == Ordering::Less {
+            if cmp_terms(Some(&element), min.as_ref(), &self.literal_value_handlers)
+                == Ordering::Less
+            {
                 self.min = Some(Some(element));
             }
         } else {
@@ -4025,16 +4290,27 @@ impl Accumulator for MinAccumulator {
     }
 }
 
-#[derive(Default)]
 #[expect(clippy::option_option)]
 struct MaxAccumulator {
     max: Option<Option<ExpressionTerm>>,
+    literal_value_handlers: Rc<LiteralValueHandlerRegistry>,
+}
+
+impl MaxAccumulator {
+    fn new(literal_value_handlers: Rc<LiteralValueHandlerRegistry>) -> Self {
+        Self {
+            max: None,
+            literal_value_handlers,
+        }
+    }
 }
 
 impl Accumulator for MaxAccumulator {
     fn add(&mut self, element: ExpressionTerm) {
         if let Some(max) = &self.max {
-            if cmp_terms(Some(&element), max.as_ref()) == Ordering::Greater {
+            if cmp_terms(Some(&element), max.as_ref(), &self.literal_value_handlers)
+                == Ordering::Greater
+            {
                 self.max = Some(Some(element))
             }
         } else {
@@ -4119,7 +4395,14 @@ fn slice_key<T: Eq>(slice: &[T], element: &T) -> Option<usize> {
 }
 
 /// Equality operator (=)
-fn equals(a: &ExpressionTerm, b: &ExpressionTerm) -> Option<bool> {
+fn equals(
+    a: &ExpressionTerm,
+    b: &ExpressionTerm,
+    literal_value_handlers: &LiteralValueHandlerRegistry,
+) -> Option<bool> {
+    if let Some((handler, a, b)) = literal_value_handlers.handler_for(a, b) {
+        return handler.values_equal(a, b);
+    }
     match a {
         ExpressionTerm::NamedNode(_)
         | ExpressionTerm::BlankNode(_)
@@ -4253,7 +4536,7 @@ fn equals(a: &ExpressionTerm, b: &ExpressionTerm) -> Option<bool> {
         #[cfg(feature = "sparql-12")]
         ExpressionTerm::Triple(a) => {
             if let ExpressionTerm::Triple(b) = b {
-                triple_equals(a, b)
+                triple_equals(a, b, literal_value_handlers)
             } else {
                 Some(false)
             }
@@ -4262,12 +4545,24 @@ fn equals(a: &ExpressionTerm, b: &ExpressionTerm) -> Option<bool> {
 }
 
 #[cfg(feature = "sparql-12")]
-fn triple_equals(a: &ExpressionTriple, b: &ExpressionTriple) -> Option<bool> {
-    Some(a.subject == b.subject && a.predicate == b.predicate && equals(&a.object, &b.object)?)
+fn triple_equals(
+    a: &ExpressionTriple,
+    b: &ExpressionTriple,
+    literal_value_handlers: &LiteralValueHandlerRegistry,
+) -> Option<bool> {
+    Some(
+        a.subject == b.subject
+            && a.predicate == b.predicate
+            && equals(&a.object, &b.object, literal_value_handlers)?,
+    )
 }
 
 /// Comparison for ordering
-fn cmp_terms(a: Option<&ExpressionTerm>, b: Option<&ExpressionTerm>) -> Ordering {
+fn cmp_terms(
+    a: Option<&ExpressionTerm>,
+    b: Option<&ExpressionTerm>,
+    literal_value_handlers: &LiteralValueHandlerRegistry,
+) -> Ordering {
     match (a, b) {
         (Some(a), Some(b)) => {
             match a {
@@ -4282,7 +4577,7 @@ fn cmp_terms(a: Option<&ExpressionTerm>, b: Option<&ExpressionTerm>) -> Ordering
                 },
                 #[cfg(feature = "sparql-12")]
                 ExpressionTerm::Triple(a) => match b {
-                    ExpressionTerm::Triple(b) => cmp_triples(a, b),
+                    ExpressionTerm::Triple(b) => cmp_triples(a, b, literal_value_handlers),
                     _ => Ordering::Greater,
                 },
                 _ => match b {
@@ -4292,7 +4587,7 @@ fn cmp_terms(a: Option<&ExpressionTerm>, b: Option<&ExpressionTerm>) -> Ordering
                     #[cfg(feature = "sparql-12")]
                     ExpressionTerm::Triple(_) => Ordering::Less,
                     _ => {
-                        if let Some(ord) = partial_cmp_literals(a, b) {
+                        if let Some(ord) = partial_cmp_literals(a, b, literal_value_handlers) {
                             ord
                         } else if let (Term::Literal(a), Term::Literal(b)) =
                             (a.clone().into(), b.clone().into())
@@ -4316,7 +4611,11 @@ fn cmp_terms(a: Option<&ExpressionTerm>, b: Option<&ExpressionTerm>) -> Ordering
 }
 
 #[cfg(feature = "sparql-12")]
-fn cmp_triples(a: &ExpressionTriple, b: &ExpressionTriple) -> Ordering {
+fn cmp_triples(
+    a: &ExpressionTriple,
+    b: &ExpressionTriple,
+    literal_value_handlers: &LiteralValueHandlerRegistry,
+) -> Ordering {
     match match &a.subject {
         NamedOrBlankNode::BlankNode(a) => match &b.subject {
             NamedOrBlankNode::BlankNode(b) => a.as_str().cmp(b.as_str()),
@@ -4328,7 +4627,7 @@ fn cmp_triples(a: &ExpressionTriple, b: &ExpressionTriple) -> Ordering {
         },
     } {
         Ordering::Equal => match a.predicate.as_str().cmp(b.predicate.as_str()) {
-            Ordering::Equal => cmp_terms(Some(&a.object), Some(&b.object)),
+            Ordering::Equal => cmp_terms(Some(&a.object), Some(&b.object), literal_value_handlers),
             o => o,
         },
         o => o,
@@ -4336,22 +4635,33 @@ fn cmp_triples(a: &ExpressionTriple, b: &ExpressionTriple) -> Ordering {
 }
 
 /// Comparison for <, >, <= and >= operators
-fn partial_cmp(a: &ExpressionTerm, b: &ExpressionTerm) -> Option<Ordering> {
+fn partial_cmp(
+    a: &ExpressionTerm,
+    b: &ExpressionTerm,
+    literal_value_handlers: &LiteralValueHandlerRegistry,
+) -> Option<Ordering> {
     if a == b {
         return Some(Ordering::Equal);
     }
     #[cfg(feature = "sparql-12")]
     if let ExpressionTerm::Triple(a) = a {
         return if let ExpressionTerm::Triple(b) = b {
-            partial_cmp_triples(a, b)
+            partial_cmp_triples(a, b, literal_value_handlers)
         } else {
             None
         };
     }
-    partial_cmp_literals(a, b)
+    partial_cmp_literals(a, b, literal_value_handlers)
 }
 
-fn partial_cmp_literals(a: &ExpressionTerm, b: &ExpressionTerm) -> Option<Ordering> {
+fn partial_cmp_literals(
+    a: &ExpressionTerm,
+    b: &ExpressionTerm,
+    literal_value_handlers: &LiteralValueHandlerRegistry,
+) -> Option<Ordering> {
+    if let Some((handler, a, b)) = literal_value_handlers.handler_for(a, b) {
+        return handler.values_cmp(a, b);
+    }
     match a {
         ExpressionTerm::StringLiteral(a) => {
             if let ExpressionTerm::StringLiteral(b) = b {
@@ -4512,7 +4822,11 @@ fn partial_cmp_literals(a: &ExpressionTerm, b: &ExpressionTerm) -> Option<Orderi
 }
 
 #[cfg(feature = "sparql-12")]
-fn partial_cmp_triples(a: &ExpressionTriple, b: &ExpressionTriple) -> Option<Ordering> {
+fn partial_cmp_triples(
+    a: &ExpressionTriple,
+    b: &ExpressionTriple,
+    literal_value_handlers: &LiteralValueHandlerRegistry,
+) -> Option<Ordering> {
     // We compare subjects
     match (&a.subject, &b.subject) {
         (NamedOrBlankNode::NamedNode(a), NamedOrBlankNode::NamedNode(b)) => {
@@ -4530,7 +4844,7 @@ fn partial_cmp_triples(a: &ExpressionTriple, b: &ExpressionTriple) -> Option<Ord
     if a.predicate != b.predicate {
         return None;
     }
-    partial_cmp(&a.object, &b.object)
+    partial_cmp(&a.object, &b.object, literal_value_handlers)
 }
 
 enum NumericBinaryOperands {
@@ -4944,18 +5258,12 @@ impl<D: QueryableDataset> PathEvaluator<D> {
                 if start == end {
                     self.is_subject_or_object_in_graph(start, graph_name)?
                 } else {
-                    look_in_transitive_closure(
-                        self.eval_from_in_graph(p, start, graph_name),
-                        move |e| self.eval_from_in_graph(p, &e, graph_name),
-                        end,
-                    )?
+                    self.eval_one_or_more_closed_in_graph(p, start, end, graph_name)?
                 }
             }
-            PropertyPath::OneOrMore(p) => look_in_transitive_closure(
-                self.eval_from_in_graph(p, start, graph_name),
-                move |e| self.eval_from_in_graph(p, &e, graph_name),
-                end,
-            )?,
+            PropertyPath::OneOrMore(p) => {
+                self.eval_one_or_more_closed_in_graph(p, start, end, graph_name)?
+            }
             PropertyPath::ZeroOrOne(p) => {
                 if start == end {
                     self.is_subject_or_object_in_graph(start, graph_name)
@@ -4981,6 +5289,25 @@ impl<D: QueryableDataset> PathEvaluator<D> {
         })
     }
 
+    /// Checks if `end` is reachable from `start` following `path` at least once.
+    ///
+    /// Both endpoints are bound so we search from both of them at the same time,
+    /// always expanding the smallest frontier.
+    fn eval_one_or_more_closed_in_graph(
+        &self,
+        path: &PropertyPath<D>,
+        start: &D::InternalTerm,
+        end: &D::InternalTerm,
+        graph_name: Option<&D::InternalTerm>,
+    ) -> Result<bool, QueryEvaluationError> {
+        look_in_transitive_closure_bidirectional(
+            self.eval_from_in_graph(path, start, graph_name),
+            |e| self.eval_from_in_graph(path, e, graph_name),
+            end.clone(),
+            |e| self.eval_to_in_graph(path, e, graph_name),
+        )
+    }
+
     fn eval_closed_in_unknown_graph(
         &self,
         path: &PropertyPath<D>,
@@ -5016,31 +5343,34 @@ impl<D: QueryableDataset> PathEvaluator<D> {
                 let end = end.clone();
                 let p = Rc::clone(p);
                 self.run_if_term_is_a_dataset_node(start, move |graph_name| {
-                    look_in_transitive_closure(
-                        Some(Ok(start2.clone())),
-                        |e| eval.eval_from_in_graph(&p, &e, graph_name.as_ref()),
-                        &end,
-                    )
-                    .map(|is_found| is_found.then_some(graph_name))
-                    .transpose()
+                    if start2 == end {
+                        return Some(Ok(graph_name));
+                    }
+                    eval.eval_one_or_more_closed_in_graph(&p, &start2, &end, graph_name.as_ref())
+                        .map(|is_found| is_found.then_some(graph_name))
+                        .transpose()
                 })
             }
             PropertyPath::OneOrMore(p) => {
                 let eval = self.clone();
+                let start2 = start.clone();
                 let end = end.clone();
                 let p = Rc::clone(p);
+                // We only look for the path in the graphs where the start has a successor, once per graph
                 Box::new(
-                    self.eval_from_in_unknown_graph(&p, start)
-                        .filter_map(move |r| {
-                            r.and_then(|(start, graph_name)| {
-                                look_in_transitive_closure(
-                                    Some(Ok(start)),
-                                    |e| eval.eval_from_in_graph(&p, &e, graph_name.as_ref()),
-                                    &end,
-                                )
-                                .map(|is_found| is_found.then_some(graph_name))
-                            })
-                            .transpose()
+                    hash_deduplicate(self.eval_from_in_unknown_graph(&p, start).map(|r| Ok(r?.1)))
+                        .filter_map(move |graph_name| {
+                            graph_name
+                                .and_then(|graph_name| {
+                                    eval.eval_one_or_more_closed_in_graph(
+                                        &p,
+                                        &start2,
+                                        &end,
+                                        graph_name.as_ref(),
+                                    )
+                                    .map(|is_found| is_found.then_some(graph_name))
+                                })
+                                .transpose()
                         }),
                 )
             }
@@ -5756,6 +6086,123 @@ impl<D: QueryableDataset> Iterator for HashJoinIterator<D> {
     }
 }
 
+struct BindJoinIterator<D: QueryableDataset> {
+    eval: SimpleEvaluator<D>,
+    outer: InternalTuplesIterator<D>,
+    service_name: NamedNode,
+    graph_pattern: Rc<spargebra::algebra::GraphPattern>,
+    keys: Rc<[(usize, Variable)]>,
+    variables: Rc<[Variable]>,
+    silent: bool,
+    batching: ServiceBatching,
+    current: InternalTuplesIterator<D>,
+}
+
+impl<D: QueryableDataset> BindJoinIterator<D> {
+    /// Builds the pattern sent to the service, restricted to the key bindings of the given tuples
+    fn batch_pattern(
+        &self,
+        tuples: &[InternalTuple<D>],
+    ) -> Result<spargebra::algebra::GraphPattern, QueryEvaluationError> {
+        let mut seen = FxHashSet::default();
+        let mut bindings = Vec::new();
+        for tuple in tuples {
+            let row = self
+                .keys
+                .iter()
+                .map(|(key, _)| {
+                    let Some(term) = tuple.get(*key) else {
+                        return Ok(None);
+                    };
+                    // Blank nodes can't be sent, the service results are filtered by the join instead
+                    Ok(
+                        GroundTerm::try_from(self.eval.dataset.externalize_term(term.clone())?)
+                            .ok(),
+                    )
+                })
+                .collect::<Result<Vec<_>, QueryEvaluationError>>()?;
+            if seen.insert(row.clone()) {
+                bindings.push(row);
+            }
+        }
+        Ok(spargebra::algebra::GraphPattern::Join {
+            left: Box::new(spargebra::algebra::GraphPattern::Values {
+                variables: self.keys.iter().map(|(_, v)| v.clone()).collect(),
+                bindings,
+            }),
+            right: Box::new(self.graph_pattern.as_ref().clone()),
+        })
+    }
+}
+
+impl<D: QueryableDataset> Iterator for BindJoinIterator<D> {
+    type Item = Result<InternalTuple<D>, QueryEvaluationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.current.next() {
+                return Some(result);
+            }
+            let mut tuples = Vec::new();
+            let mut errors = Vec::new();
+            for result in self
+                .outer
+                .by_ref()
+                .take(self.batching.batch_size * self.batching.parallelism)
+            {
+                match result {
+                    Ok(tuple) => tuples.push(tuple),
+                    Err(error) => errors.push(Err(error)),
+                }
+            }
+            if tuples.is_empty() && errors.is_empty() {
+                return None;
+            }
+            let mut batches = Vec::new();
+            let mut patterns = Vec::new();
+            for batch in tuples.chunks(self.batching.batch_size) {
+                match self.batch_pattern(batch) {
+                    Ok(pattern) => {
+                        batches.push(batch.to_vec());
+                        patterns.push(pattern);
+                    }
+                    Err(error) => errors.push(Err(error)),
+                }
+            }
+            let results = self.eval.call_service_batches(&self.service_name, patterns);
+            let mut iters = Vec::with_capacity(batches.len());
+            for (batch, result) in batches.into_iter().zip(results) {
+                let iter: InternalTuplesIterator<D> = match result {
+                    Ok(solutions) => {
+                        let mut built =
+                            InternalTupleSet::new(self.keys.iter().map(|(key, _)| *key).collect());
+                        built.extend(batch);
+                        Box::new(HashJoinIterator {
+                            probe_iter: encode_bindings(
+                                self.eval.dataset.clone(),
+                                Rc::clone(&self.variables),
+                                solutions,
+                            )
+                            .peekable(),
+                            built,
+                            buffered_results: Vec::new(),
+                        })
+                    }
+                    Err(error) => {
+                        if self.silent {
+                            Box::new(batch.into_iter().map(Ok))
+                        } else {
+                            Box::new(once(Err(error)))
+                        }
+                    }
+                };
+                iters.push(iter);
+            }
+            self.current = Box::new(errors.into_iter().chain(iters.into_iter().flatten()));
+        }
+    }
+}
+
 struct HashLeftJoinIterator<D: QueryableDataset> {
     left_iter: InternalTuplesIterator<D>,
     right: InternalTupleSet<D>,
@@ -6205,21 +6652,59 @@ fn transitive_closure<T: Clone + Eq + Hash, E, NI: Iterator<Item = Result<T, E>>
     errors.into_iter().map(Err).chain(all.into_iter().map(Ok))
 }
 
-fn look_in_transitive_closure<T: Clone + Eq + Hash, E, NI: Iterator<Item = Result<T, E>>>(
+/// Checks if `target` is reachable from the `start` set using `forward_next`.
+///
+/// `backward_next` must be the reverse of `forward_next`: the search is done from both sides,
+/// expanding the smallest frontier at each step, and stops as soon as the two sides meet.
+fn look_in_transitive_closure_bidirectional<
+    T: Clone + Eq + Hash,
+    E,
+    FI: Iterator<Item = Result<T, E>>,
+    BI: Iterator<Item = Result<T, E>>,
+>(
     start: impl IntoIterator<Item = Result<T, E>>,
-    mut next: impl FnMut(T) -> NI,
-    target: &T,
+    mut forward_next: impl FnMut(&T) -> FI,
+    target: T,
+    mut backward_next: impl FnMut(&T) -> BI,
 ) -> Result<bool, E> {
-    let mut todo = start.into_iter().collect::<Result<Vec<_>, _>>()?;
-    let mut all = todo.iter().cloned().collect::<FxHashSet<_>>();
-    while let Some(e) = todo.pop() {
-        if e == *target {
+    let mut forward_todo = Vec::new();
+    let mut forward_all = FxHashSet::default();
+    for e in start {
+        let e = e?;
+        if e == target {
             return Ok(true);
         }
-        for e in next(e) {
-            let e = e?;
-            if all.insert(e.clone()) {
-                todo.push(e);
+        if forward_all.insert(e.clone()) {
+            forward_todo.push(e);
+        }
+    }
+    let mut backward_todo = vec![target.clone()];
+    let mut backward_all = FxHashSet::default();
+    backward_all.insert(target);
+    while !forward_todo.is_empty() && !backward_todo.is_empty() {
+        if forward_todo.len() <= backward_todo.len() {
+            for e in take(&mut forward_todo) {
+                for e in forward_next(&e) {
+                    let e = e?;
+                    if backward_all.contains(&e) {
+                        return Ok(true);
+                    }
+                    if forward_all.insert(e.clone()) {
+                        forward_todo.push(e);
+                    }
+                }
+            }
+        } else {
+            for e in take(&mut backward_todo) {
+                for e in backward_next(&e) {
+                    let e = e?;
+                    if forward_all.contains(&e) {
+                        return Ok(true);
+                    }
+                    if backward_all.insert(e.clone()) {
+                        backward_todo.push(e);
+                    }
+                }
             }
         }
     }
@@ -6441,6 +6926,7 @@ impl<D: QueryableDataset> Iterator for StatsIterator<D> {
 pub struct EvalNodeWithStats {
     pub label: String,
     pub children: Vec<Rc<EvalNodeWithStats>>,
+    pub estimated_cardinality: usize,
     pub exec_count: Cell<usize>,
     pub exec_duration: Cell<Option<DayTimeDuration>>,
 }
@@ -6454,6 +6940,10 @@ impl EvalNodeWithStats {
         serializer.serialize_event(JsonEvent::StartObject)?;
         serializer.serialize_event(JsonEvent::ObjectKey("name".into()))?;
         serializer.serialize_event(JsonEvent::String((&self.label).into()))?;
+        serializer.serialize_event(JsonEvent::ObjectKey("estimated number of results".into()))?;
+        serializer.serialize_event(JsonEvent::Number(
+            self.estimated_cardinality.to_string().into(),
+        ))?;
         if with_stats {
             serializer.serialize_event(JsonEvent::ObjectKey("number of results".into()))?;
             serializer
@@ -6478,6 +6968,7 @@ impl fmt::Debug for EvalNodeWithStats {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut obj = f.debug_struct("Node");
         obj.field("name", &self.label);
+        obj.field("estimated number of results", &self.estimated_cardinality);
         if let Some(exec_duration) = self.exec_duration.get() {
             obj.field("number of results", &self.exec_count.get());
             obj.field(
@@ -6650,6 +7141,112 @@ impl Timer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{QueryEvaluator, QueryResults, QuerySolution};
+    use oxrdf::Dataset;
+    use spargebra::SparqlParser;
+    use std::str::FromStr;
+
+    /// Evaluates a query with the given integer overflow behavior and returns its first solution
+    fn evaluate(behavior: IntegerOverflowBehavior, query: &str) -> QuerySolution {
+        let query = SparqlParser::new().parse_query(query).unwrap();
+        let QueryResults::Solutions(mut solutions) = QueryEvaluator::new()
+            .with_integer_overflow_behavior(behavior)
+            .execute(Dataset::new(), &query)
+            .unwrap()
+        else {
+            unreachable!()
+        };
+        solutions.next().unwrap().unwrap()
+    }
+
+    fn decimal(value: &str) -> Term {
+        Literal::from(Decimal::from_str(value).unwrap()).into()
+    }
+
+    #[test]
+    fn integer_overflow_error() {
+        let behavior = IntegerOverflowBehavior::Error;
+        assert_eq!(
+            evaluate(behavior, "SELECT (9223372036854775807 + 1 AS ?v) {}").get("v"),
+            None
+        );
+        assert_eq!(
+            evaluate(behavior, "SELECT (-(-9223372036854775807 - 1) AS ?v) {}").get("v"),
+            None
+        );
+        assert_eq!(
+            evaluate(behavior, "SELECT (9223372036854775807 + 0 AS ?v) {}").get("v"),
+            Some(&Literal::from(i64::MAX).into())
+        );
+    }
+
+    #[test]
+    fn integer_overflow_promote_to_decimal() {
+        let behavior = IntegerOverflowBehavior::PromoteToDecimal;
+        assert_eq!(
+            evaluate(behavior, "SELECT (9223372036854775807 + 1 AS ?v) {}").get("v"),
+            Some(&decimal("9223372036854775808"))
+        );
+        assert_eq!(
+            evaluate(behavior, "SELECT (4294967296 * 4294967296 AS ?v) {}").get("v"),
+            Some(&decimal("18446744073709551616"))
+        );
+        assert_eq!(
+            evaluate(behavior, "SELECT (ABS(-9223372036854775807 - 1) AS ?v) {}").get("v"),
+            Some(&decimal("9223372036854775808"))
+        );
+        // Without overflow the result is still an integer
+        assert_eq!(
+            evaluate(behavior, "SELECT (1 + 1 AS ?v) {}").get("v"),
+            Some(&Literal::from(2).into())
+        );
+    }
+
+    #[test]
+    fn integer_overflow_saturate() {
+        let behavior = IntegerOverflowBehavior::Saturate;
+        assert_eq!(
+            evaluate(behavior, "SELECT (-9223372036854775807 - 2 AS ?v) {}").get("v"),
+            Some(&Literal::from(i64::MIN).into())
+        );
+        assert_eq!(
+            evaluate(behavior, "SELECT (-(-9223372036854775807 - 1) AS ?v) {}").get("v"),
+            Some(&Literal::from(i64::MAX).into())
+        );
+    }
+
+    #[test]
+    fn integer_overflow_in_aggregates() {
+        let sum = "SELECT (SUM(?x) AS ?v) { VALUES ?x { 9223372036854775807 1 } }";
+        let avg = "SELECT (AVG(?x) AS ?v) { VALUES ?x { 9223372036854775807 1 } }";
+        assert_eq!(evaluate(IntegerOverflowBehavior::Error, sum).get("v"), None);
+        assert_eq!(evaluate(IntegerOverflowBehavior::Error, avg).get("v"), None);
+        assert_eq!(
+            evaluate(IntegerOverflowBehavior::PromoteToDecimal, sum).get("v"),
+            Some(&decimal("9223372036854775808"))
+        );
+        assert_eq!(
+            evaluate(IntegerOverflowBehavior::PromoteToDecimal, avg).get("v"),
+            Some(&decimal("4611686018427387904"))
+        );
+        assert_eq!(
+            evaluate(IntegerOverflowBehavior::Saturate, sum).get("v"),
+            Some(&Literal::from(i64::MAX).into())
+        );
+        assert_eq!(
+            evaluate(IntegerOverflowBehavior::Saturate, avg).get("v"),
+            Some(&decimal("4611686018427387903.5"))
+        );
+        // The promoted sum keeps being summed as a decimal
+        assert_eq!(
+            evaluate(
+                IntegerOverflowBehavior::PromoteToDecimal,
+                "SELECT (SUM(?x) AS ?v) { VALUES ?x { 9223372036854775807 1 -2 } }"
+            )
+            .get("v"),
+            Some(&decimal("9223372036854775806"))
+        );
+    }
 
     #[test]
     fn uuid() {
@@ -7,6 +7,7 @@
 mod dataset;
 mod error;
 mod eval;
+mod literal;
 mod model;
 mod service;
 #[cfg(feature = "sparql-12")]
@@ -14,18 +15,22 @@ pub use crate::dataset::ExpressionTriple;
 pub use crate::dataset::{ExpressionTerm, InternalQuad, QueryableDataset};
 pub use crate::error::QueryEvaluationError;
 use crate::eval::{EvalNodeWithStats, SimpleEvaluator, Timer};
+pub use crate::literal::LiteralValueHandler;
+use crate::literal::LiteralValueHandlerRegistry;
 pub use crate::model::{QueryResults, QuerySolution, QuerySolutionIter, QueryTripleIter};
-use crate::service::ServiceHandlerRegistry;
 pub use crate::service::{DefaultServiceHandler, ServiceHandler};
+use crate::service::{ServiceBatching, ServiceHandlerRegistry, bind_service_sources};
 use json_event_parser::{JsonEvent, WriterJsonSerializer};
 use oxrdf::{NamedNode, Term, Variable};
 use oxsdatatypes::{DayTimeDuration, Float};
 use spargebra::Query;
 use sparopt::Optimizer;
 use sparopt::algebra::GraphPattern;
+pub use sparopt::{DatasetStatistics, PredicateStatistics};
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{fmt, io};
 
 /// Evaluates a query against a given [RDF dataset](https://www.w3.org/TR/rdf11-concepts/#dfn-rdf-dataset)
@@ -60,8 +65,15 @@ use std::{fmt, io};
 pub struct QueryEvaluator {
     service_handler: ServiceHandlerRegistry,
     custom_functions: CustomFunctionRegistry,
+    literal_value_handlers: LiteralValueHandlerRegistry,
     without_optimizations: bool,
+    optimizer: Optimizer,
+    dataset_statistics: Option<Arc<DatasetStatistics>>,
     run_stats: bool,
+    integer_overflow_behavior: IntegerOverflowBehavior,
+    service_batch_size: Option<usize>,
+    service_parallelism: usize,
+    service_source_variable: Option<Variable>,
 }
 
 impl QueryEvaluator {
@@ -137,9 +149,9 @@ impl QueryEvaluator {
             Query::Select {
                 pattern, base_iri, ..
             } => {
-                let mut pattern = GraphPattern::from(pattern);
-                if !self.without_optimizations {
-                    pattern = Optimizer::optimize_graph_pattern(pattern);
+                let mut pattern = self.optimize_graph_pattern(GraphPattern::from(pattern));
+                if let Some(variable) = &self.service_source_variable {
+                    pattern = bind_service_sources(pattern, variable);
                 }
                 let planning_duration = start_planning.elapsed();
                 let (results, explanation) = SimpleEvaluator::new(
@@ -147,7 +159,11 @@ impl QueryEvaluator {
                     base_iri.clone().map(Rc::new),
                     Rc::new(self.service_handler.clone()),
                     Rc::new(self.custom_functions.clone()),
+                    Rc::new(self.literal_value_handlers.clone()),
+                    self.dataset_statistics.clone(),
                     self.run_stats,
+                    self.integer_overflow_behavior,
+                    self.service_batching(),
                 )
                 .evaluate_select(&pattern, substitutions);
                 (
@@ -159,9 +175,9 @@ impl QueryEvaluator {
             Query::Ask {
                 pattern, base_iri, ..
             } => {
-                let mut pattern = GraphPattern::from(pattern);
-                if !self.without_optimizations {
-                    pattern = Optimizer::optimize_graph_pattern(pattern);
+                let mut pattern = self.optimize_graph_pattern(GraphPattern::from(pattern));
+                if let Some(variable) = &self.service_source_variable {
+                    pattern = bind_service_sources(pattern, variable);
                 }
                 let planning_duration = start_planning.elapsed();
                 let (results, explanation) = SimpleEvaluator::new(
@@ -169,7 +185,11 @@ impl QueryEvaluator {
                     base_iri.clone().map(Rc::new),
                     Rc::new(self.service_handler.clone()),
                     Rc::new(self.custom_functions.clone()),
+                    Rc::new(self.literal_value_handlers.clone()),
+                    self.dataset_statistics.clone(),
                     self.run_stats,
+                    self.integer_overflow_behavior,
+                    self.service_batching(),
                 )
                 .evaluate_ask(&pattern, substitutions);
                 (
@@ -184,9 +204,9 @@ impl QueryEvaluator {
                 base_iri,
                 ..
             } => {
-                let mut pattern = GraphPattern::from(pattern);
-                if !self.without_optimizations {
-                    pattern = Optimizer::optimize_graph_pattern(pattern);
+                let mut pattern = self.optimize_graph_pattern(GraphPattern::from(pattern));
+                if let Some(variable) = &self.service_source_variable {
+                    pattern = bind_service_sources(pattern, variable);
                 }
                 let planning_duration = start_planning.elapsed();
                 let (results, explanation) = SimpleEvaluator::new(
@@ -194,7 +214,11 @@ impl QueryEvaluator {
                     base_iri.clone().map(Rc::new),
                     Rc::new(self.service_handler.clone()),
                     Rc::new(self.custom_functions.clone()),
+                    Rc::new(self.literal_value_handlers.clone()),
+                    self.dataset_statistics.clone(),
                     self.run_stats,
+                    self.integer_overflow_behavior,
+                    self.service_batching(),
                 )
                 .evaluate_construct(&pattern, template, substitutions);
                 (
@@ -206,9 +230,9 @@ impl QueryEvaluator {
             Query::Describe {
                 pattern, base_iri, ..
             } => {
-                let mut pattern = GraphPattern::from(pattern);
-                if !self.without_optimizations {
-                    pattern = Optimizer::optimize_graph_pattern(pattern);
+                let mut pattern = self.optimize_graph_pattern(GraphPattern::from(pattern));
+                if let Some(variable) = &self.service_source_variable {
+                    pattern = bind_service_sources(pattern, variable);
                 }
                 let planning_duration = start_planning.elapsed();
                 let (results, explanation) = SimpleEvaluator::new(
@@ -216,7 +240,11 @@ impl QueryEvaluator {
                     base_iri.clone().map(Rc::new),
                     Rc::new(self.service_handler.clone()),
                     Rc::new(self.custom_functions.clone()),
+                    Rc::new(self.literal_value_handlers.clone()),
+                    self.dataset_statistics.clone(),
                     self.run_stats,
+                    self.integer_overflow_behavior,
+                    self.service_batching(),
                 )
                 .evaluate_describe(&pattern, substitutions);
                 (
@@ -302,7 +330,24 @@ impl QueryEvaluator {
         self
     }
 
+    /// Adds a handler for the values of the literals of a custom datatype.
+    ///
+    /// It is used to compare these literals in operators, `ORDER BY`, `MIN` and `MAX`.
+    /// See [`LiteralValueHandler`] for an example.
+    #[inline]
+    #[must_use]
+    pub fn with_literal_value_handler(
+        mut self,
+        datatype: NamedNode,
+        handler: impl LiteralValueHandler + 'static,
+    ) -> Self {
+        self.literal_value_handlers.insert(datatype, handler);
+        self
+    }
+
     /// Disables query optimizations and runs the query as it is.
+    ///
+    /// The rewrite passes registered with [`with_optimizer_rewrite_pass`](Self::with_optimizer_rewrite_pass) are still applied.
     #[inline]
     #[must_use]
     pub fn without_optimizations(mut self) -> Self {
@@ -310,6 +355,79 @@ impl QueryEvaluator {
         self
     }
 
+    /// Registers a custom rewrite pass of the [`sparopt`] algebra run after the built-in query optimizations.
+    ///
+    /// See [`Optimizer::with_rewrite_pass`].
+    ///
+    /// ```
+    /// use oxrdf::{Dataset, GraphName, NamedNode, Quad};
+    /// use spareval::{QueryEvaluator, QueryResults};
+    /// use spargebra::SparqlParser;
+    /// use sparopt::algebra::GraphPattern;
+    ///
+    /// let ex = NamedNode::new("http://example.com")?;
+    /// let dataset = Dataset::from_iter([
+    ///     Quad::new(ex.clone(), ex.clone(), ex.clone(), GraphName::DefaultGraph),
+    ///     Quad::new(ex.clone(), ex.clone(), ex.clone(), ex.clone()),
+    ///     Quad::new(ex.clone(), ex.clone(), ex.clone(), NamedNode::new("http://example.com/g")?),
+    /// ]);
+    /// // Returns at most one solution
+    /// let evaluator = QueryEvaluator::new()
+    ///     .with_optimizer_rewrite_pass(|pattern| GraphPattern::slice(pattern, 0, Some(1)));
+    /// let query = SparqlParser::new().parse_query("SELECT * WHERE { GRAPH ?g { ?s ?p ?o } }")?;
+    /// if let QueryResults::Solutions(solutions) = evaluator.execute(dataset, &query)? {
+    ///     assert_eq!(solutions.count(), 1);
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_optimizer_rewrite_pass(
+        mut self,
+        pass: impl Fn(GraphPattern) -> GraphPattern + Send + Sync + 'static,
+    ) -> Self {
+        self.optimizer = self.optimizer.with_rewrite_pass(pass);
+        self
+    }
+
+    /// Uses the given statistics about the queried dataset to optimize the queries.
+    ///
+    /// They allow to choose the join order and the join algorithms from the actual size of the triple patterns
+    /// instead of heuristics. They are also used for the estimated cardinalities of the [`QueryExplanation`].
+    ///
+    /// ```
+    /// use oxrdf::{Dataset, GraphName, NamedNode, Quad};
+    /// use spareval::{DatasetStatistics, PredicateStatistics, QueryEvaluator, QueryResults};
+    /// use spargebra::SparqlParser;
+    ///
+    /// let ex = NamedNode::new("http://example.com")?;
+    /// let dataset = Dataset::from_iter([Quad::new(
+    ///     ex.clone(),
+    ///     ex.clone(),
+    ///     ex.clone(),
+    ///     GraphName::DefaultGraph,
+    /// )]);
+    /// let mut statistics = DatasetStatistics::new(1);
+    /// statistics.insert_predicate(ex.clone(), PredicateStatistics::new(1, 1, 1));
+    /// let query = SparqlParser::new().parse_query("SELECT * WHERE { ?s ?p ?o . ?o ?p ?s }")?;
+    /// let results = QueryEvaluator::new()
+    ///     .with_dataset_statistics(statistics)
+    ///     .execute(dataset, &query);
+    /// if let QueryResults::Solutions(solutions) = results? {
+    ///     assert_eq!(solutions.count(), 1);
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_dataset_statistics(
+        mut self,
+        statistics: impl Into<Arc<DatasetStatistics>>,
+    ) -> Self {
+        self.dataset_statistics = Some(statistics.into());
+        self
+    }
+
     /// Compute statistics during evaluation and fills them in the explanation tree.
     #[inline]
     #[must_use]
@@ -317,6 +435,215 @@ impl QueryEvaluator {
         self.run_stats = true;
         self
     }
+
+    /// Sets what happens when an arithmetic operation on `xsd:integer` values overflows.
+    ///
+    /// By default, the overflow is an evaluation error.
+    ///
+    /// ```
+    /// use oxrdf::{Dataset, Literal};
+    /// use spareval::{IntegerOverflowBehavior, QueryEvaluator, QueryResults};
+    /// use spargebra::SparqlParser;
+    ///
+    /// let query =
+    ///     SparqlParser::new().parse_query("SELECT (9223372036854775807 + 1 AS ?v) WHERE {}")?;
+    /// let evaluator =
+    ///     QueryEvaluator::new().with_integer_overflow_behavior(IntegerOverflowBehavior::Saturate);
+    /// if let QueryResults::Solutions(mut solutions) = evaluator.execute(Dataset::new(), &query)? {
+    ///     assert_eq!(
+    ///         solutions.next().unwrap()?.get("v"),
+    ///         Some(&Literal::from(i64::MAX).into())
+    ///     );
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_integer_overflow_behavior(mut self, behavior: IntegerOverflowBehavior) -> Self {
+        self.integer_overflow_behavior = behavior;
+        self
+    }
+
+    /// Evaluates the joins between a SERVICE call and an other pattern as bind joins:
+    /// the other side of the join is evaluated first and its bindings are sent to the service
+    /// in `VALUES` blocks of at most `batch_size` rows.
+    ///
+    /// It massively reduces the amount of data returned by the services for selective joins.
+    /// The [`ServiceHandler`] and [`DefaultServiceHandler`] then get graph patterns joined with a `VALUES` block and must support them.
+    ///
+    /// By default, the SERVICE patterns are sent without any binding.
+    ///
+    /// ```
+    /// use oxrdf::{Dataset, GraphName, Literal, NamedNode, Quad, Variable};
+    /// use sparesults::QuerySolution;
+    /// use spareval::{QueryEvaluator, QueryResults, QuerySolutionIter, ServiceHandler};
+    /// use spargebra::SparqlParser;
+    /// use spargebra::algebra::GraphPattern;
+    /// use std::convert::Infallible;
+    /// use std::sync::Arc;
+    ///
+    /// /// Returns the VALUES rows of the pattern it gets
+    /// struct EchoServiceHandler;
+    ///
+    /// impl ServiceHandler for EchoServiceHandler {
+    ///     type Error = Infallible;
+    ///
+    ///     fn handle(
+    ///         &self,
+    ///         pattern: GraphPattern,
+    ///         _base_iri: Option<String>,
+    ///     ) -> Result<QuerySolutionIter, Self::Error> {
+    ///         let GraphPattern::Join { left, .. } = pattern else {
+    ///             unreachable!()
+    ///         };
+    ///         let GraphPattern::Values {
+    ///             variables,
+    ///             bindings,
+    ///         } = *left
+    ///         else {
+    ///             unreachable!()
+    ///         };
+    ///         let variables: Arc<[Variable]> = variables.into();
+    ///         Ok(QuerySolutionIter::new(
+    ///             Arc::clone(&variables),
+    ///             bindings.into_iter().map(move |row| {
+    ///                 Ok(QuerySolution::from((
+    ///                     Arc::clone(&variables),
+    ///                     row.into_iter().map(|v| v.map(Into::into)).collect::<Vec<_>>(),
+    ///                 )))
+    ///             }),
+    ///         ))
+    ///     }
+    /// }
+    ///
+    /// let ex = NamedNode::new("http://example.com")?;
+    /// let dataset = Dataset::from_iter((0..5).map(|i| {
+    ///     Quad::new(ex.clone(), ex.clone(), Literal::from(i), GraphName::DefaultGraph)
+    /// }));
+    /// let evaluator = QueryEvaluator::new()
+    ///     .with_service_handler(ex.clone(), EchoServiceHandler)
+    ///     .with_service_batch_size(2);
+    /// let query = SparqlParser::new().parse_query(
+    ///     "SELECT ?o WHERE { ?s ?p ?o SERVICE <http://example.com> { ?s ?p2 ?o } }",
+    /// )?;
+    /// if let QueryResults::Solutions(solutions) = evaluator.execute(dataset, &query)? {
+    ///     assert_eq!(solutions.count(), 5);
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_service_batch_size(mut self, batch_size: usize) -> Self {
+        self.service_batch_size = Some(batch_size.max(1));
+        self
+    }
+
+    /// Sets the maximal number of SERVICE calls of a bind join executed at the same time, each one in its own thread.
+    ///
+    /// It is only used if bind joins are enabled with [`with_service_batch_size`](Self::with_service_batch_size).
+    /// By default, the calls are executed one after the other in the current thread.
+    #[inline]
+    #[must_use]
+    pub fn with_service_parallelism(mut self, parallelism: usize) -> Self {
+        self.service_parallelism = parallelism;
+        self
+    }
+
+    /// Binds the given variable to the name of the [SPARQL 1.1 Federated Query](https://www.w3.org/TR/sparql11-federated-query/) SERVICE each solution comes from.
+    ///
+    /// It allows to know which endpoint returned which data when integrating several sources.
+    /// The variable must be explicitly selected in the `SELECT` clause (`SELECT *` does not include it)
+    /// or used in the `GROUP BY` clause or the `CONSTRUCT` template, and must not be bound by the query.
+    /// If a solution combines the results of several SERVICE calls, the variable is bound to the name of the first one in the query.
+    /// It is left unbound for the solutions that do not come from a SERVICE call.
+    ///
+    /// ```
+    /// use oxrdf::{Dataset, NamedNode, Variable};
+    /// use sparesults::QuerySolution;
+    /// use spareval::{DefaultServiceHandler, QueryEvaluator, QueryResults, QuerySolutionIter};
+    /// use spargebra::SparqlParser;
+    /// use spargebra::algebra::GraphPattern;
+    /// use std::convert::Infallible;
+    /// use std::iter::once;
+    /// use std::sync::Arc;
+    ///
+    /// /// Returns a single empty solution for any service
+    /// struct TestServiceHandler;
+    ///
+    /// impl DefaultServiceHandler for TestServiceHandler {
+    ///     type Error = Infallible;
+    ///
+    ///     fn handle(
+    ///         &self,
+    ///         _service_name: NamedNode,
+    ///         _pattern: GraphPattern,
+    ///         _base_iri: Option<String>,
+    ///     ) -> Result<QuerySolutionIter, Infallible> {
+    ///         let variables = Arc::<[Variable]>::from([]);
+    ///         Ok(QuerySolutionIter::new(
+    ///             Arc::clone(&variables),
+    ///             once(Ok(QuerySolution::from((variables, Vec::new())))),
+    ///         ))
+    ///     }
+    /// }
+    ///
+    /// let evaluator = QueryEvaluator::new()
+    ///     .with_default_service_handler(TestServiceHandler)
+    ///     .with_service_source_variable(Variable::new("source")?);
+    /// let query = SparqlParser::new().parse_query(
+    ///     "SELECT ?source WHERE { { SERVICE <http://example.com/a> {} } UNION { SERVICE <http://example.com/b> {} } } ORDER BY ?source",
+    /// )?;
+    /// if let QueryResults::Solutions(solutions) = evaluator.execute(Dataset::new(), &query)? {
+    ///     let sources = solutions
+    ///         .map(|solution| Ok(solution?.get("source").cloned()))
+    ///         .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+    ///     assert_eq!(
+    ///         sources,
+    ///         [
+    ///             Some(NamedNode::new("http://example.com/a")?.into()),
+    ///             Some(NamedNode::new("http://example.com/b")?.into())
+    ///         ]
+    ///     );
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_service_source_variable(mut self, variable: Variable) -> Self {
+        self.service_source_variable = Some(variable);
+        self
+    }
+
+    fn optimize_graph_pattern(&self, pattern: GraphPattern) -> GraphPattern {
+        if self.without_optimizations {
+            self.optimizer.apply_rewrite_passes(pattern)
+        } else {
+            self.optimizer
+                .optimize(pattern, self.dataset_statistics.as_deref())
+        }
+    }
+
+    fn service_batching(&self) -> Option<ServiceBatching> {
+        Some(ServiceBatching {
+            batch_size: self.service_batch_size?,
+            parallelism: self.service_parallelism.max(1),
+        })
+    }
+}
+
+/// What to do when an arithmetic operation on `xsd:integer` values does not fit in a 64 bits integer.
+///
+/// It applies to the `+`, `-` (binary and unary) and `*` operators, to the `ABS` function and to the `SUM` and `AVG` aggregates.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+#[non_exhaustive]
+pub enum IntegerOverflowBehavior {
+    /// The operation raises an evaluation error, leaving the result unbound (the default).
+    #[default]
+    Error,
+    /// The operation returns an `xsd:decimal`, raising an evaluation error only if it overflows too.
+    PromoteToDecimal,
+    /// The operation returns the largest or the smallest `xsd:integer`.
+    Saturate,
 }
 
 pub(crate) type CustomFunctionRegistry =
@@ -331,6 +658,36 @@ pub struct QueryExplanation {
 }
 
 impl QueryExplanation {
+    /// The root node of the evaluated query plan.
+    ///
+    /// ```
+    /// use oxrdf::Dataset;
+    /// use spareval::{QueryEvaluator, QueryResults};
+    /// use spargebra::SparqlParser;
+    ///
+    /// let query = SparqlParser::new().parse_query("SELECT * WHERE { VALUES ?s { 1 2 3 } }")?;
+    /// let (results, explanation) = QueryEvaluator::new()
+    ///     .compute_statistics()
+    ///     .explain(Dataset::new(), &query);
+    /// // The statistics are only complete once all the results have been read
+    /// if let QueryResults::Solutions(solutions) = results? {
+    ///     for solution in solutions {
+    ///         solution?;
+    ///     }
+    /// }
+    /// let plan = explanation.plan();
+    /// assert_eq!(plan.estimated_result_count(), 3);
+    /// assert_eq!(plan.result_count(), Some(3));
+    /// assert!(plan.duration().is_some());
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn plan(&self) -> QueryPlanNode<'_> {
+        QueryPlanNode {
+            inner: &self.inner,
+            with_stats: self.with_stats,
+        }
+    }
+
     /// Writes the explanation as JSON.
     pub fn write_in_json(&self, writer: impl io::Write) -> io::Result<()> {
         let mut serializer = WriterJsonSerializer::new(writer);
@@ -344,7 +701,9 @@ impl QueryExplanation {
         }
         serializer.serialize_event(JsonEvent::ObjectKey("plan".into()))?;
         self.inner.json_node(&mut serializer, self.with_stats)?;
-        serializer.serialize_event(JsonEvent::EndObject)
+        serializer.serialize_event(JsonEvent::EndObject)?;
+        serializer.finish()?;
+        Ok(())
     }
 }
 
@@ -361,3 +720,59 @@ impl fmt::Debug for QueryExplanation {
         obj.finish_non_exhaustive()
     }
 }
+
+/// A node of the query plan of a [`QueryExplanation`].
+///
+/// The actual number of results and the evaluation duration are only available
+/// if the statistics have been computed with [`QueryEvaluator::compute_statistics`].
+#[derive(Clone, Copy)]
+pub struct QueryPlanNode<'a> {
+    inner: &'a EvalNodeWithStats,
+    with_stats: bool,
+}
+
+impl<'a> QueryPlanNode<'a> {
+    /// The description of the operation, like `Filter(?o > 1)`.
+    #[inline]
+    pub fn label(&self) -> &'a str {
+        &self.inner.label
+    }
+
+    /// The nodes whose results are the inputs of this one.
+    #[inline]
+    pub fn children(&self) -> impl ExactSizeIterator<Item = QueryPlanNode<'a>> + 'a {
+        let with_stats = self.with_stats;
+        self.inner.children.iter().map(move |child| QueryPlanNode {
+            inner: child,
+            with_stats,
+        })
+    }
+
+    /// The number of results estimated by the optimizer without any knowledge of the data.
+    #[inline]
+    pub fn estimated_result_count(&self) -> usize {
+        self.inner.estimated_cardinality
+    }
+
+    /// The number of results actually returned by the node during the evaluation.
+    #[inline]
+    pub fn result_count(&self) -> Option<usize> {
+        self.with_stats.then(|| self.inner.exec_count.get())
+    }
+
+    /// The time spent evaluating the node, including its children.
+    #[inline]
+    pub fn duration(&self) -> Option<Duration> {
+        if !self.with_stats {
+            return None;
+        }
+        self.inner.exec_duration.get()?.try_into().ok()
+    }
+}
+
+impl fmt::Debug for QueryPlanNode<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
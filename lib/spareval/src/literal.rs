@@ -0,0 +1,129 @@
+use crate::dataset::ExpressionTerm;
+use oxrdf::NamedNode;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Handler for the values of literals of a custom datatype.
+///
+/// Should be given to [`QueryEvaluator`](super::QueryEvaluator::with_literal_value_handler())
+/// to make the `=`, `!=`, `<`, `>`, `<=` and `>=` operators, `ORDER BY`, `MIN` and `MAX` compare the values of the literals
+/// instead of raising an error (operators) or falling back to a lexical comparison (ordering).
+///
+/// Two literals of the datatype are equal if their values have the same canonical lexical form.
+/// A literal with an invalid lexical form is ill-typed: the operators return an error on it.
+///
+/// ```
+/// use oxrdf::{Dataset, Literal, NamedNode};
+/// use spareval::{LiteralValueHandler, QueryEvaluator, QueryResults};
+/// use spargebra::SparqlParser;
+/// use std::cmp::Ordering;
+///
+/// /// Lengths like "1.5km" or "20m"
+/// struct LengthHandler;
+///
+/// impl LiteralValueHandler for LengthHandler {
+///     type Value = f64; // In meters
+///
+///     fn parse(&self, lexical_form: &str) -> Option<f64> {
+///         if let Some(value) = lexical_form.strip_suffix("km") {
+///             Some(value.parse::<f64>().ok()? * 1000.)
+///         } else {
+///             lexical_form.strip_suffix('m')?.parse().ok()
+///         }
+///     }
+///
+///     fn canonicalize(&self, value: &f64) -> String {
+///         format!("{value}m")
+///     }
+///
+///     fn compare(&self, a: &f64, b: &f64) -> Option<Ordering> {
+///         a.partial_cmp(b)
+///     }
+/// }
+///
+/// let evaluator = QueryEvaluator::new()
+///     .with_literal_value_handler(NamedNode::new("http://example.com/length")?, LengthHandler);
+/// let query = SparqlParser::new().parse_query(
+///     "PREFIX ex: <http://example.com/>
+///     SELECT ?eq ?lt WHERE {
+///         BIND(\"1.5km\"^^ex:length = \"1500m\"^^ex:length AS ?eq)
+///         BIND(\"1.5km\"^^ex:length < \"20m\"^^ex:length AS ?lt)
+///     }",
+/// )?;
+/// if let QueryResults::Solutions(mut solutions) = evaluator.execute(Dataset::new(), &query)? {
+///     let solution = solutions.next().unwrap()?;
+///     assert_eq!(solution.get("eq"), Some(&Literal::from(true).into()));
+///     assert_eq!(solution.get("lt"), Some(&Literal::from(false).into()));
+/// }
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+pub trait LiteralValueHandler: Send + Sync {
+    /// The value of a literal.
+    type Value;
+
+    /// Parses the lexical form of a literal, returning `None` if it is not valid.
+    fn parse(&self, lexical_form: &str) -> Option<Self::Value>;
+
+    /// Returns the canonical lexical form of a value.
+    fn canonicalize(&self, value: &Self::Value) -> String;
+
+    /// Compares two values, returning `None` if they are not comparable.
+    fn compare(&self, a: &Self::Value, b: &Self::Value) -> Option<Ordering>;
+}
+
+/// [`LiteralValueHandler`] without the value type
+pub(crate) trait ErasedLiteralValueHandler: Send + Sync {
+    fn values_equal(&self, a: &str, b: &str) -> Option<bool>;
+
+    fn values_cmp(&self, a: &str, b: &str) -> Option<Ordering>;
+}
+
+impl<H: LiteralValueHandler> ErasedLiteralValueHandler for H {
+    fn values_equal(&self, a: &str, b: &str) -> Option<bool> {
+        Some(self.canonicalize(&self.parse(a)?) == self.canonicalize(&self.parse(b)?))
+    }
+
+    fn values_cmp(&self, a: &str, b: &str) -> Option<Ordering> {
+        self.compare(&self.parse(a)?, &self.parse(b)?)
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct LiteralValueHandlerRegistry {
+    handlers: HashMap<NamedNode, Arc<dyn ErasedLiteralValueHandler>>,
+}
+
+impl LiteralValueHandlerRegistry {
+    pub fn insert(&mut self, datatype: NamedNode, handler: impl LiteralValueHandler + 'static) {
+        self.handlers.insert(datatype, Arc::new(handler));
+    }
+
+    /// Returns the handler and the lexical forms of the two terms if they are literals of the same datatype with a handler
+    pub fn handler_for<'a>(
+        &'a self,
+        a: &'a ExpressionTerm,
+        b: &'a ExpressionTerm,
+    ) -> Option<(&'a dyn ErasedLiteralValueHandler, &'a str, &'a str)> {
+        if self.handlers.is_empty() {
+            return None;
+        }
+        let (
+            ExpressionTerm::OtherTypedLiteral {
+                value: a,
+                datatype: a_datatype,
+            },
+            ExpressionTerm::OtherTypedLiteral {
+                value: b,
+                datatype: b_datatype,
+            },
+        ) = (a, b)
+        else {
+            return None;
+        };
+        if a_datatype != b_datatype {
+            return None;
+        }
+        Some((self.handlers.get(a_datatype)?.as_ref(), a, b))
+    }
+}
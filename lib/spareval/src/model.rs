@@ -113,9 +113,8 @@ pub struct QueryTripleIter {
 }
 
 impl QueryTripleIter {
-    pub(crate) fn new(
-        iter: impl Iterator<Item = Result<Triple, QueryEvaluationError>> + 'static,
-    ) -> Self {
+    /// Construct a new iterator of triples from an iterator of triples or errors
+    pub fn new(iter: impl Iterator<Item = Result<Triple, QueryEvaluationError>> + 'static) -> Self {
         Self {
             iter: Box::new(iter),
         }
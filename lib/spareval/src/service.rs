@@ -1,6 +1,7 @@
 use crate::{QueryEvaluationError, QuerySolutionIter};
-use oxrdf::NamedNode;
+use oxrdf::{NamedNode, Variable};
 use spargebra::algebra::GraphPattern;
+use sparopt::algebra::{Expression, GraphPattern as OptGraphPattern};
 use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
@@ -134,6 +135,15 @@ pub trait DefaultServiceHandler: Send + Sync {
     ) -> Result<QuerySolutionIter, Self::Error>;
 }
 
+/// How the joins with SERVICE calls are evaluated as bind joins
+#[derive(Clone, Copy)]
+pub struct ServiceBatching {
+    /// Maximal number of rows in the VALUES block sent with each call
+    pub batch_size: usize,
+    /// Maximal number of calls executed at the same time
+    pub parallelism: usize,
+}
+
 #[derive(Clone, Default)]
 pub struct ServiceHandlerRegistry {
     default: Option<Arc<dyn DefaultServiceHandler<Error = QueryEvaluationError>>>,
@@ -214,3 +224,212 @@ fn wrap_service_error(error: impl Error + Send + Sync + 'static) -> QueryEvaluat
         Err(error) => QueryEvaluationError::Service(error),
     }
 }
+
+/// Binds `variable` to the name of the SERVICE each solution comes from.
+///
+/// Each SERVICE call binds a hidden variable to its name,
+/// then `variable` is bound to the first one of them that is bound, where the projections and the groupings use it.
+/// The hidden variables are not valid SPARQL variable names, so they never clash with the query variables.
+pub fn bind_service_sources(pattern: OptGraphPattern, variable: &Variable) -> OptGraphPattern {
+    let mut counter = 0;
+    let mut sources = Vec::new();
+    let pattern = bind_service_sources_in_pattern(pattern, variable, &mut counter, &mut sources);
+    extend_with_source(pattern, variable, sources)
+}
+
+fn bind_service_sources_in_pattern(
+    pattern: OptGraphPattern,
+    variable: &Variable,
+    counter: &mut usize,
+    sources: &mut Vec<Variable>,
+) -> OptGraphPattern {
+    let mut map = |pattern: Box<OptGraphPattern>| {
+        Box::new(bind_service_sources_in_pattern(
+            *pattern, variable, counter, sources,
+        ))
+    };
+    match pattern {
+        OptGraphPattern::Service {
+            name,
+            inner,
+            silent,
+        } => {
+            let source = new_source_variable(variable, counter, sources);
+            OptGraphPattern::Extend {
+                expression: name.clone().into(),
+                inner: Box::new(OptGraphPattern::Service {
+                    name,
+                    inner,
+                    silent,
+                }),
+                variable: source,
+            }
+        }
+        OptGraphPattern::Join {
+            left,
+            right,
+            algorithm,
+        } => {
+            // We bind the source after the join to keep the SERVICE a direct child of the join for bind joins
+            let service_name = match (&*left, &*right) {
+                (OptGraphPattern::Service { name, .. }, _) => Some((true, name.clone())),
+                (_, OptGraphPattern::Service { name, .. }) => Some((false, name.clone())),
+                _ => None,
+            };
+            let Some((service_on_left, service_name)) = service_name else {
+                return OptGraphPattern::Join {
+                    left: map(left),
+                    right: map(right),
+                    algorithm,
+                };
+            };
+            let (left, right) = if service_on_left {
+                (left, map(right))
+            } else {
+                (map(left), right)
+            };
+            let source = new_source_variable(variable, counter, sources);
+            OptGraphPattern::Extend {
+                inner: Box::new(OptGraphPattern::Join {
+                    left,
+                    right,
+                    algorithm,
+                }),
+                variable: source,
+                expression: service_name.into(),
+            }
+        }
+        OptGraphPattern::LeftJoin {
+            left,
+            right,
+            expression,
+            algorithm,
+        } => OptGraphPattern::LeftJoin {
+            left: map(left),
+            right: map(right),
+            expression,
+            algorithm,
+        },
+        #[cfg(feature = "sep-0006")]
+        OptGraphPattern::Lateral { left, right } => OptGraphPattern::Lateral {
+            left: map(left),
+            right: map(right),
+        },
+        OptGraphPattern::Filter { expression, inner } => OptGraphPattern::Filter {
+            expression,
+            inner: map(inner),
+        },
+        OptGraphPattern::Union { inner } => OptGraphPattern::Union {
+            inner: inner
+                .into_iter()
+                .map(|inner| bind_service_sources_in_pattern(inner, variable, counter, sources))
+                .collect(),
+        },
+        OptGraphPattern::Extend {
+            inner,
+            variable: extended,
+            expression,
+        } => OptGraphPattern::Extend {
+            inner: map(inner),
+            variable: extended,
+            expression,
+        },
+        OptGraphPattern::Minus {
+            left,
+            right,
+            algorithm,
+        } => OptGraphPattern::Minus {
+            left: map(left),
+            right: map(right),
+            algorithm,
+        },
+        OptGraphPattern::OrderBy { inner, expression } => OptGraphPattern::OrderBy {
+            inner: map(inner),
+            expression,
+        },
+        OptGraphPattern::Distinct { inner } => OptGraphPattern::Distinct { inner: map(inner) },
+        OptGraphPattern::Reduced { inner } => OptGraphPattern::Reduced { inner: map(inner) },
+        OptGraphPattern::Slice {
+            inner,
+            start,
+            length,
+        } => OptGraphPattern::Slice {
+            inner: map(inner),
+            start,
+            length,
+        },
+        // The projections and the groupings hide the sources of their inner pattern
+        OptGraphPattern::Project { inner, variables } => {
+            let mut inner_sources = Vec::new();
+            let inner =
+                bind_service_sources_in_pattern(*inner, variable, counter, &mut inner_sources);
+            let inner = if !variables.contains(variable) {
+                inner
+            } else if let OptGraphPattern::OrderBy {
+                inner: ordered,
+                expression,
+            } = inner
+            {
+                // The source might be used in ORDER BY
+                OptGraphPattern::OrderBy {
+                    inner: Box::new(extend_with_source(*ordered, variable, inner_sources)),
+                    expression,
+                }
+            } else {
+                extend_with_source(inner, variable, inner_sources)
+            };
+            OptGraphPattern::Project {
+                inner: Box::new(inner),
+                variables,
+            }
+        }
+        OptGraphPattern::Group {
+            inner,
+            variables,
+            aggregates,
+        } => {
+            let mut inner_sources = Vec::new();
+            let inner =
+                bind_service_sources_in_pattern(*inner, variable, counter, &mut inner_sources);
+            OptGraphPattern::Group {
+                inner: Box::new(if variables.contains(variable) {
+                    extend_with_source(inner, variable, inner_sources)
+                } else {
+                    inner
+                }),
+                variables,
+                aggregates,
+            }
+        }
+        other @ (OptGraphPattern::QuadPattern { .. }
+        | OptGraphPattern::Path { .. }
+        | OptGraphPattern::Graph { .. }
+        | OptGraphPattern::Values { .. }) => other,
+    }
+}
+
+fn new_source_variable(
+    variable: &Variable,
+    counter: &mut usize,
+    sources: &mut Vec<Variable>,
+) -> Variable {
+    *counter += 1;
+    let source = Variable::new_unchecked(format!("{}-service-{counter}", variable.as_str()));
+    sources.push(source.clone());
+    source
+}
+
+fn extend_with_source(
+    pattern: OptGraphPattern,
+    variable: &Variable,
+    sources: Vec<Variable>,
+) -> OptGraphPattern {
+    if sources.is_empty() {
+        return pattern;
+    }
+    OptGraphPattern::Extend {
+        inner: Box::new(pattern),
+        variable: variable.clone(),
+        expression: Expression::Coalesce(sources.into_iter().map(Expression::Variable).collect()),
+    }
+}
@@ -5,11 +5,13 @@
 #![doc(html_logo_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
 
 pub mod algebra;
+mod pagination;
 mod parser;
 mod query;
 pub mod term;
 mod update;
 
+pub use pagination::{KeysetPagination, KeysetPaginationError};
 pub use parser::{SparqlParser, SparqlSyntaxError};
 pub use query::*;
 pub use update::*;
@@ -0,0 +1,269 @@
+use crate::Query;
+use crate::algebra::{Expression, Function, GraphPattern, OrderExpression, QueryDataset};
+use crate::term::{Literal, Term, Variable};
+use oxiri::Iri;
+
+/// Keyset pagination of a `SELECT` query.
+///
+/// Instead of paging with an increasing `OFFSET` that makes the query engine compute and skip all the previous pages,
+/// the pages are sorted by key variables and each page only keeps the solutions after the key of the last solution of the previous page.
+///
+/// The key variables must be projected by the query and their values should identify the solutions:
+/// solutions with the same key as the last solution of a page are skipped.
+/// Their values should be IRIs or literals comparable with the `>` operator, like strings or numbers, or be unbound.
+/// Solutions with a literal key that is not comparable with the last seen key are skipped.
+///
+/// ```
+/// use spargebra::term::{NamedNode, Variable};
+/// use spargebra::{KeysetPagination, SparqlParser};
+///
+/// let query = SparqlParser::new().parse_query("SELECT ?s ?name WHERE { ?s <http://schema.org/name> ?name }")?;
+/// let pagination = KeysetPagination::new(query, [Variable::new("s")?], 100)?;
+/// assert_eq!(
+///     pagination.first_page().to_string(),
+///     "SELECT ?s ?name WHERE { ?s <http://schema.org/name> ?name . } ORDER BY ASC(?s) LIMIT 100"
+/// );
+/// let next_page = pagination.page_after(&[Some(NamedNode::new("http://example.com/s")?.into())])?;
+/// assert_eq!(
+///     next_page.to_string(),
+///     "SELECT ?s ?name WHERE { ?s <http://schema.org/name> ?name . FILTER((isLITERAL(?s) || (isIRI(?s) && (STR(?s) > \"http://example.com/s\")))) } ORDER BY ASC(?s) LIMIT 100"
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct KeysetPagination {
+    dataset: Option<QueryDataset>,
+    base_iri: Option<Iri<String>>,
+    pattern: GraphPattern,
+    variables: Vec<Variable>,
+    modifier: SelectionModifier,
+    keys: Vec<Variable>,
+    page_size: usize,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+enum SelectionModifier {
+    Default,
+    Distinct,
+    Reduced,
+}
+
+impl KeysetPagination {
+    /// Builds the pagination of a `SELECT` query without `ORDER BY`, `LIMIT` and `OFFSET` clauses,
+    /// sorted by the given key variables with `page_size` solutions per page.
+    pub fn new(
+        query: Query,
+        keys: impl IntoIterator<Item = Variable>,
+        page_size: usize,
+    ) -> Result<Self, KeysetPaginationError> {
+        let Query::Select {
+            dataset,
+            pattern,
+            base_iri,
+        } = query
+        else {
+            return Err(KeysetPaginationError::NotSelect);
+        };
+        if page_size == 0 {
+            return Err(KeysetPaginationError::EmptyPage);
+        }
+        let (pattern, modifier) = match pattern {
+            GraphPattern::Slice { .. } => return Err(KeysetPaginationError::AlreadySliced),
+            GraphPattern::Distinct { inner } => (*inner, SelectionModifier::Distinct),
+            GraphPattern::Reduced { inner } => (*inner, SelectionModifier::Reduced),
+            other => (other, SelectionModifier::Default),
+        };
+        let GraphPattern::Project { inner, variables } = pattern else {
+            return Err(KeysetPaginationError::NotSelect);
+        };
+        if matches!(*inner, GraphPattern::OrderBy { .. }) {
+            return Err(KeysetPaginationError::AlreadyOrdered);
+        }
+        let keys = keys.into_iter().collect::<Vec<_>>();
+        if keys.is_empty() {
+            return Err(KeysetPaginationError::NoKey);
+        }
+        if let Some(key) = keys.iter().find(|key| !variables.contains(key)) {
+            return Err(KeysetPaginationError::NotProjectedKey(key.clone()));
+        }
+        Ok(Self {
+            dataset,
+            base_iri,
+            pattern: *inner,
+            variables,
+            modifier,
+            keys,
+            page_size,
+        })
+    }
+
+    /// The key variables the solutions are sorted by.
+    #[inline]
+    pub fn keys(&self) -> &[Variable] {
+        &self.keys
+    }
+
+    /// The maximal number of solutions in a page.
+    #[inline]
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// The query returning the first page.
+    pub fn first_page(&self) -> Query {
+        self.page(self.pattern.clone())
+    }
+
+    /// The query returning the page after the solution with the given key values, `None` meaning unbound.
+    ///
+    /// The values must be in the same order as the [keys](Self::keys).
+    pub fn page_after(&self, last_key: &[Option<Term>]) -> Result<Query, KeysetPaginationError> {
+        if last_key.len() != self.keys.len() {
+            return Err(KeysetPaginationError::WrongKeySize {
+                expected: self.keys.len(),
+                actual: last_key.len(),
+            });
+        }
+        // (k1 > v1) || (k1 = v1 && k2 > v2) || ...
+        let mut after = None;
+        for (key, value) in self.keys.iter().zip(last_key).rev() {
+            let (mut condition, same) = key_conditions(key, value.as_ref())?;
+            if let Some(after) = after {
+                condition = Expression::Or(
+                    Box::new(condition),
+                    Box::new(Expression::And(Box::new(same), Box::new(after))),
+                );
+            }
+            after = Some(condition);
+        }
+        let Some(after) = after else {
+            return Err(KeysetPaginationError::NoKey);
+        };
+        Ok(self.page(GraphPattern::Filter {
+            expr: after,
+            inner: Box::new(self.pattern.clone()),
+        }))
+    }
+
+    fn page(&self, pattern: GraphPattern) -> Query {
+        let mut pattern = GraphPattern::Project {
+            inner: Box::new(GraphPattern::OrderBy {
+                inner: Box::new(pattern),
+                expression: self
+                    .keys
+                    .iter()
+                    .map(|key| OrderExpression::Asc(Expression::Variable(key.clone())))
+                    .collect(),
+            }),
+            variables: self.variables.clone(),
+        };
+        match self.modifier {
+            SelectionModifier::Default => (),
+            SelectionModifier::Distinct => {
+                pattern = GraphPattern::Distinct {
+                    inner: Box::new(pattern),
+                }
+            }
+            SelectionModifier::Reduced => {
+                pattern = GraphPattern::Reduced {
+                    inner: Box::new(pattern),
+                }
+            }
+        }
+        Query::Select {
+            dataset: self.dataset.clone(),
+            pattern: GraphPattern::Slice {
+                inner: Box::new(pattern),
+                start: 0,
+                length: Some(self.page_size),
+            },
+            base_iri: self.base_iri.clone(),
+        }
+    }
+}
+
+/// Builds the conditions on `key` to be sorted after `value` and to be equal to `value`.
+///
+/// The `ORDER BY` order is unbound values, then IRIs and then literals.
+fn key_conditions(
+    key: &Variable,
+    value: Option<&Term>,
+) -> Result<(Expression, Expression), KeysetPaginationError> {
+    let key_expression = || Expression::Variable(key.clone());
+    Ok(match value {
+        None => (
+            Expression::Bound(key.clone()),
+            Expression::Not(Box::new(Expression::Bound(key.clone()))),
+        ),
+        Some(Term::NamedNode(value)) => (
+            Expression::Or(
+                Box::new(Expression::FunctionCall(
+                    Function::IsLiteral,
+                    vec![key_expression()],
+                )),
+                Box::new(Expression::And(
+                    Box::new(Expression::FunctionCall(
+                        Function::IsIri,
+                        vec![key_expression()],
+                    )),
+                    Box::new(Expression::Greater(
+                        Box::new(Expression::FunctionCall(
+                            Function::Str,
+                            vec![key_expression()],
+                        )),
+                        Box::new(Expression::Literal(Literal::new_simple_literal(
+                            value.as_str(),
+                        ))),
+                    )),
+                )),
+            ),
+            Expression::SameTerm(
+                Box::new(key_expression()),
+                Box::new(Expression::NamedNode(value.clone())),
+            ),
+        ),
+        Some(Term::Literal(value)) => (
+            Expression::Greater(
+                Box::new(key_expression()),
+                Box::new(Expression::Literal(value.clone())),
+            ),
+            Expression::SameTerm(
+                Box::new(key_expression()),
+                Box::new(Expression::Literal(value.clone())),
+            ),
+        ),
+        Some(other) => return Err(KeysetPaginationError::UnsupportedKeyValue(other.clone())),
+    })
+}
+
+/// An error raised when building a [`KeysetPagination`] or one of its pages.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum KeysetPaginationError {
+    /// Only `SELECT` queries can be paginated
+    #[error("Only SELECT queries can be paginated")]
+    NotSelect,
+    /// The query already has an `ORDER BY` clause
+    #[error(
+        "The query must not have an ORDER BY clause, the pages are sorted by the pagination keys"
+    )]
+    AlreadyOrdered,
+    /// The query already has a `LIMIT` or an `OFFSET` clause
+    #[error("The query must not have LIMIT or OFFSET clauses, they are set by the pagination")]
+    AlreadySliced,
+    /// No key variable has been given
+    #[error("At least one pagination key variable must be given")]
+    NoKey,
+    /// A key variable is not in the query projection
+    #[error("The pagination key variable {0} is not projected by the query")]
+    NotProjectedKey(Variable),
+    /// The page size is 0
+    #[error("The page size must be greater than 0")]
+    EmptyPage,
+    /// The number of given key values is not the number of key variables
+    #[error("{expected} pagination key values are expected, {actual} found")]
+    WrongKeySize { expected: usize, actual: usize },
+    /// A key value is not an IRI or a literal
+    #[error("{0} can't be used as a pagination key value, only IRIs and literals are supported")]
+    UnsupportedKeyValue(Term),
+}
@@ -609,6 +609,61 @@ fn build_select(
     Ok(m)
 }
 
+/// Builds the pattern of a DESCRIBE query with explicit targets.
+///
+/// The IRI targets are always described, even if the WHERE clause has no solution.
+fn build_describe(
+    targets: Vec<NamedNodePattern>,
+    r#where: GraphPattern,
+    group: Option<(Vec<Variable>, Vec<(Expression, Variable)>)>,
+    having: Option<Expression>,
+    order_by: Option<Vec<OrderExpression>>,
+    offset_limit: Option<(usize, Option<usize>)>,
+    values: Option<GraphPattern>,
+    state: &mut ParserState,
+) -> Result<GraphPattern, &'static str> {
+    let mut variables = Vec::new();
+    let mut iris = Vec::new();
+    for target in targets {
+        match target {
+            NamedNodePattern::NamedNode(iri) => iris.push(vec![Some(iri.into())]),
+            NamedNodePattern::Variable(variable) => {
+                variables.push(SelectionMember::Variable(variable))
+            }
+        }
+    }
+    let has_variables = !variables.is_empty();
+    let pattern = build_select(
+        Selection {
+            option: SelectionOption::Default,
+            variables: SelectionVariables::Explicit(variables),
+        },
+        r#where,
+        group,
+        having,
+        order_by,
+        offset_limit,
+        values,
+        state,
+    )?;
+    if iris.is_empty() {
+        return Ok(pattern);
+    }
+    let iris = GraphPattern::Values {
+        variables: vec![variable()],
+        bindings: iris,
+    };
+    Ok(if has_variables {
+        GraphPattern::Union {
+            left: Box::new(pattern),
+            right: Box::new(iris),
+        }
+    } else {
+        // The WHERE clause can't bind anything to describe
+        iris
+    })
+}
+
 fn are_variables_bound(expression: &Expression, variables: &HashSet<Variable>) -> bool {
     match expression {
         Expression::NamedNode(_)
@@ -922,11 +977,15 @@ parser! {
                     )?,
                     base_iri: state.base_iri.clone()
                 })
+            } /
+            i("CONSTRUCT") _ DatasetClauses() _ i("WHERE") _ "{" _ ConstructQuery_optional_triple_template() _ ConstructQuery_not_triples() _ {?
+                Err("CONSTRUCT WHERE only allows triple patterns, use CONSTRUCT { template } WHERE { pattern } for FILTER, OPTIONAL, GRAPH or other patterns")
             }
         rule ConstructQuery_clear() = {
             state.currently_used_bnodes.clear();
         }
 
+        rule ConstructQuery_not_triples() = quiet! { i("FILTER") / i("OPTIONAL") / i("GRAPH") / i("MINUS") / i("BIND") / i("VALUES") / i("SERVICE") / i("LATERAL") / "{" }
         rule ConstructQuery_optional_triple_template() -> Vec<TriplePattern> = TriplesTemplate() / { Vec::new() }
 
         rule DescribeQuery() -> Query =
@@ -940,13 +999,7 @@ parser! {
             i("DESCRIBE") _ p:DescribeQuery_item()+ _ d:DatasetClauses() _ w:WhereClause()? _ g:GroupClause()? _ h:HavingClause()? _ o:OrderClause()? _ l:LimitOffsetClauses()? _ v:ValuesClause() {?
                 Ok(Query::Describe {
                     dataset: d,
-                    pattern: build_select(Selection {
-                        option: SelectionOption::Default,
-                        variables: SelectionVariables::Explicit(p.into_iter().map(|var_or_iri| match var_or_iri {
-                            NamedNodePattern::NamedNode(n) => SelectionMember::Expression(n.into(), variable()),
-                            NamedNodePattern::Variable(v) => SelectionMember::Variable(v)
-                        }).collect())
-                    }, w.unwrap_or_default(), g, h, o, l, v, state)?,
+                    pattern: build_describe(p, w.unwrap_or_default(), g, h, o, l, v, state)?,
                     base_iri: state.base_iri.clone()
                 })
             }
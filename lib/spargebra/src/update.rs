@@ -3,6 +3,8 @@ use crate::algebra::*;
 use crate::parser::SparqlSyntaxError;
 use crate::term::*;
 use oxiri::Iri;
+use oxrdf::dataset::DatasetDiff;
+use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
@@ -102,6 +104,148 @@ impl TryFrom<&String> for Update {
     }
 }
 
+/// Builds an update applying the changes of a [`DatasetDiff`].
+///
+/// The deleted quads without blank nodes are removed with a `DELETE DATA` operation,
+/// the other ones with a `DELETE WHERE` operation matching their blank nodes,
+/// and the added quads are inserted with an `INSERT DATA` operation.
+///
+/// Fails if a blank node is used as a graph name.
+///
+/// ```
+/// use oxrdf::{Dataset, GraphNameRef, LiteralRef, NamedNodeRef, QuadRef};
+/// use spargebra::Update;
+///
+/// let ex = NamedNodeRef::new("http://example.com")?;
+/// let old = Dataset::from_iter([QuadRef::new(ex, ex, LiteralRef::from("old"), ex)]);
+/// let new = Dataset::from_iter([QuadRef::new(ex, ex, LiteralRef::from("new"), GraphNameRef::DefaultGraph)]);
+/// let update = Update::try_from(&old.diff(&new)).unwrap();
+/// assert_eq!(
+///     update.to_string(),
+///     "DELETE DATA {\n\tGRAPH <http://example.com> { <http://example.com> <http://example.com> \"old\" }\n} ;\nINSERT DATA {\n\t<http://example.com> <http://example.com> \"new\" .\n} ;\n"
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+impl TryFrom<&DatasetDiff> for Update {
+    type Error = ();
+
+    fn try_from(diff: &DatasetDiff) -> Result<Self, Self::Error> {
+        let mut operations = Vec::new();
+        let mut delete_data = Vec::new();
+        let mut delete_patterns = Vec::new();
+        let mut where_patterns = Vec::<(GraphName, Vec<TriplePattern>)>::new();
+        let mut variables = HashMap::new();
+        for quad in diff.deletions() {
+            let graph_name = diff_graph_name(&quad.graph_name)?;
+            if let Ok(quad) = GroundQuad::try_from(Quad {
+                subject: quad.subject.clone(),
+                predicate: quad.predicate.clone(),
+                object: quad.object.clone(),
+                graph_name: graph_name.clone(),
+            }) {
+                delete_data.push(quad);
+                continue;
+            }
+            let pattern = TriplePattern {
+                subject: blank_nodes_to_variables(quad.subject.clone().into(), &mut variables),
+                predicate: quad.predicate.clone().into(),
+                object: blank_nodes_to_variables(quad.object.clone(), &mut variables),
+            };
+            delete_patterns.push(GroundQuadPattern {
+                subject: pattern.subject.clone().try_into()?,
+                predicate: pattern.predicate.clone(),
+                object: pattern.object.clone().try_into()?,
+                graph_name: graph_name.clone().into(),
+            });
+            if let Some((_, patterns)) = where_patterns.iter_mut().find(|(g, _)| *g == graph_name) {
+                patterns.push(pattern);
+            } else {
+                where_patterns.push((graph_name, vec![pattern]));
+            }
+        }
+        if !delete_data.is_empty() {
+            operations.push(GraphUpdateOperation::DeleteData { data: delete_data });
+        }
+        if let Some(pattern) = where_patterns
+            .into_iter()
+            .map(|(graph_name, patterns)| {
+                let bgp = GraphPattern::Bgp { patterns };
+                match graph_name {
+                    GraphName::NamedNode(name) => GraphPattern::Graph {
+                        name: name.into(),
+                        inner: Box::new(bgp),
+                    },
+                    GraphName::DefaultGraph => bgp,
+                }
+            })
+            .reduce(|left, right| GraphPattern::Join {
+                left: Box::new(left),
+                right: Box::new(right),
+            })
+        {
+            operations.push(GraphUpdateOperation::DeleteInsert {
+                delete: delete_patterns,
+                insert: Vec::new(),
+                using: None,
+                pattern: Box::new(pattern),
+            });
+        }
+        if !diff.insertions().is_empty() {
+            operations.push(GraphUpdateOperation::InsertData {
+                data: diff
+                    .insertions()
+                    .iter()
+                    .map(|quad| {
+                        Ok(Quad {
+                            subject: quad.subject.clone(),
+                            predicate: quad.predicate.clone(),
+                            object: quad.object.clone(),
+                            graph_name: diff_graph_name(&quad.graph_name)?,
+                        })
+                    })
+                    .collect::<Result<_, ()>>()?,
+            });
+        }
+        Ok(Self {
+            base_iri: None,
+            operations,
+        })
+    }
+}
+
+fn diff_graph_name(graph_name: &oxrdf::GraphName) -> Result<GraphName, ()> {
+    match graph_name {
+        oxrdf::GraphName::NamedNode(name) => Ok(name.clone().into()),
+        oxrdf::GraphName::DefaultGraph => Ok(GraphName::DefaultGraph),
+        oxrdf::GraphName::BlankNode(_) => Err(()),
+    }
+}
+
+/// Replaces the blank nodes by variables, the same blank node always getting the same variable
+fn blank_nodes_to_variables(
+    term: Term,
+    variables: &mut HashMap<BlankNode, Variable>,
+) -> TermPattern {
+    match term {
+        Term::BlankNode(bnode) => {
+            let len = variables.len();
+            variables
+                .entry(bnode)
+                .or_insert_with(|| Variable::new_unchecked(format!("b{len}")))
+                .clone()
+                .into()
+        }
+        #[cfg(feature = "sparql-12")]
+        Term::Triple(triple) => TriplePattern {
+            subject: blank_nodes_to_variables(triple.subject.into(), variables),
+            predicate: triple.predicate.into(),
+            object: blank_nodes_to_variables(triple.object, variables),
+        }
+        .into(),
+        other => other.into(),
+    }
+}
+
 /// The [graph update operations](https://www.w3.org/TR/sparql11-update/#formalModelGraphUpdate).
 #[derive(Eq, PartialEq, Debug, Clone, Hash)]
 pub enum GraphUpdateOperation {
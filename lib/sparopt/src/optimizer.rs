@@ -1,20 +1,114 @@
 use crate::algebra::{
     Expression, GraphPattern, JoinAlgorithm, LeftJoinAlgorithm, MinusAlgorithm, OrderExpression,
 };
+use crate::statistics::DatasetStatistics;
 use crate::type_inference::{
     VariableType, VariableTypes, infer_expression_type, infer_graph_pattern_types,
 };
-use oxrdf::Variable;
+use oxrdf::{NamedNode, Variable};
 use spargebra::algebra::PropertyPathExpression;
 use spargebra::term::{GroundTermPattern, NamedNodePattern};
 use std::cmp::{max, min};
+use std::sync::Arc;
 
-pub struct Optimizer;
+/// A SPARQL query optimizer.
+///
+/// The associated functions apply the built-in optimizations.
+/// An optimizer value also runs the custom rewrite passes registered with [`with_rewrite_pass`](Self::with_rewrite_pass) after them.
+#[derive(Clone, Default)]
+pub struct Optimizer {
+    rewrite_passes: Vec<Arc<dyn Fn(GraphPattern) -> GraphPattern + Send + Sync>>,
+}
 
 impl Optimizer {
+    /// Builds an optimizer without custom rewrite passes.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a custom rewrite pass run after the built-in optimizations.
+    ///
+    /// The passes are run in their registration order.
+    /// They must keep the query semantics they rely on: the optimizer does not check their output.
+    ///
+    /// ```
+    /// use sparopt::Optimizer;
+    /// use sparopt::algebra::GraphPattern;
+    ///
+    /// // Returns at most 1000 solutions
+    /// let optimizer =
+    ///     Optimizer::new().with_rewrite_pass(|pattern| GraphPattern::slice(pattern, 0, Some(1000)));
+    /// assert_eq!(
+    ///     optimizer.optimize(GraphPattern::empty_singleton(), None),
+    ///     GraphPattern::slice(GraphPattern::empty_singleton(), 0, Some(1000))
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_rewrite_pass(
+        mut self,
+        pass: impl Fn(GraphPattern) -> GraphPattern + Send + Sync + 'static,
+    ) -> Self {
+        self.rewrite_passes.push(Arc::new(pass));
+        self
+    }
+
+    /// Applies the built-in optimizations, using the dataset statistics if given, and then the custom rewrite passes.
+    pub fn optimize(
+        &self,
+        pattern: GraphPattern,
+        statistics: Option<&DatasetStatistics>,
+    ) -> GraphPattern {
+        self.apply_rewrite_passes(Self::optimize_built_in(pattern, statistics))
+    }
+
+    /// Applies only the custom rewrite passes, without the built-in optimizations.
+    pub fn apply_rewrite_passes(&self, pattern: GraphPattern) -> GraphPattern {
+        self.rewrite_passes
+            .iter()
+            .fold(pattern, |pattern, pass| pass(pattern))
+    }
+
+    /// Applies the built-in optimizations.
     pub fn optimize_graph_pattern(pattern: GraphPattern) -> GraphPattern {
+        Self::optimize_built_in(pattern, None)
+    }
+
+    /// Optimizes the graph pattern using the given statistics about the queried dataset.
+    ///
+    /// The statistics are used to choose the join order and the join algorithms
+    /// instead of the heuristics used by [`optimize_graph_pattern`](Self::optimize_graph_pattern).
+    pub fn optimize_graph_pattern_with_statistics(
+        pattern: GraphPattern,
+        statistics: &DatasetStatistics,
+    ) -> GraphPattern {
+        Self::optimize_built_in(pattern, Some(statistics))
+    }
+
+    /// Estimates the number of solutions of a graph pattern without any knowledge of the dataset.
+    ///
+    /// It is the rough estimation used to order the joins, useful to understand the optimizer choices.
+    pub fn estimate_cardinality(pattern: &GraphPattern) -> usize {
+        estimate_graph_pattern_size(pattern, &VariableTypes::default(), None)
+    }
+
+    /// Estimates the number of solutions of a graph pattern using the given statistics about the queried dataset.
+    ///
+    /// It is the estimation used by [`optimize_graph_pattern_with_statistics`](Self::optimize_graph_pattern_with_statistics).
+    pub fn estimate_cardinality_with_statistics(
+        pattern: &GraphPattern,
+        statistics: &DatasetStatistics,
+    ) -> usize {
+        estimate_graph_pattern_size(pattern, &VariableTypes::default(), Some(statistics))
+    }
+
+    fn optimize_built_in(
+        pattern: GraphPattern,
+        statistics: Option<&DatasetStatistics>,
+    ) -> GraphPattern {
         let pattern = Self::normalize_pattern(pattern, &VariableTypes::default());
-        let pattern = Self::reorder_joins(pattern, &VariableTypes::default());
+        let pattern = Self::reorder_joins(pattern, &VariableTypes::default(), statistics);
         Self::push_filters(pattern, Vec::new(), &VariableTypes::default())
     }
 
@@ -476,7 +570,11 @@ impl Optimizer {
         }
     }
 
-    fn reorder_joins(pattern: GraphPattern, input_types: &VariableTypes) -> GraphPattern {
+    fn reorder_joins(
+        pattern: GraphPattern,
+        input_types: &VariableTypes,
+        statistics: Option<&DatasetStatistics>,
+    ) -> GraphPattern {
         match pattern {
             GraphPattern::QuadPattern { .. }
             | GraphPattern::Path { .. }
@@ -510,7 +608,9 @@ impl Optimizer {
                     .enumerate()
                     .filter(|(_, v)| **v)
                     .map(|(i, _)| i)
-                    .min_by_key(|i| estimate_graph_pattern_size(&to_reorder[*i], input_types))
+                    .min_by_key(|i| {
+                        estimate_graph_pattern_size(&to_reorder[*i], input_types, statistics)
+                    })
                 {
                     not_yet_reordered_ids[next_entry_id] = false; // It's now done
                     let mut output = to_reorder[next_entry_id].clone();
@@ -526,18 +626,19 @@ impl Optimizer {
                         })
                         .min_by_key(|i| {
                             // Estimation of the join cost
-                            if cfg!(feature = "sep-0006")
-                                && is_fit_for_for_loop_join(
-                                    &to_reorder[*i],
-                                    input_types,
-                                    &output_types,
-                                )
-                            {
+                            if use_for_loop_join(
+                                &output,
+                                &output_types,
+                                &to_reorder[*i],
+                                input_types,
+                                statistics,
+                            ) {
                                 estimate_lateral_cost(
                                     &output,
                                     &output_types,
                                     &to_reorder[*i],
                                     input_types,
+                                    statistics,
                                 )
                             } else {
                                 estimate_join_cost(
@@ -551,6 +652,7 @@ impl Optimizer {
                                         ),
                                     },
                                     input_types,
+                                    statistics,
                                 )
                             }
                         })
@@ -559,8 +661,13 @@ impl Optimizer {
                         let next = to_reorder[next_id].clone();
                         #[cfg(feature = "sep-0006")]
                         {
-                            output = if is_fit_for_for_loop_join(&next, input_types, &output_types)
-                            {
+                            output = if use_for_loop_join(
+                                &output,
+                                &output_types,
+                                &next,
+                                input_types,
+                                statistics,
+                            ) {
                                 GraphPattern::lateral(output, next)
                             } else {
                                 GraphPattern::join(
@@ -602,8 +709,8 @@ impl Optimizer {
                             &infer_graph_pattern_types(&right, input_types.clone()),
                             input_types,
                         );
-                        if estimate_graph_pattern_size(&left, input_types)
-                            <= estimate_graph_pattern_size(&right, input_types)
+                        if estimate_graph_pattern_size(&left, input_types, statistics)
+                            <= estimate_graph_pattern_size(&right, input_types, statistics)
                         {
                             GraphPattern::join(
                                 left,
@@ -624,8 +731,8 @@ impl Optimizer {
             GraphPattern::Lateral { left, right } => {
                 let left_types = infer_graph_pattern_types(&left, input_types.clone());
                 GraphPattern::lateral(
-                    Self::reorder_joins(*left, input_types),
-                    Self::reorder_joins(*right, &left_types),
+                    Self::reorder_joins(*left, input_types, statistics),
+                    Self::reorder_joins(*right, &left_types, statistics),
                 )
             }
             GraphPattern::LeftJoin {
@@ -634,9 +741,9 @@ impl Optimizer {
                 expression,
                 ..
             } => {
-                let left = Self::reorder_joins(*left, input_types);
+                let left = Self::reorder_joins(*left, input_types, statistics);
                 let left_types = infer_graph_pattern_types(&left, input_types.clone());
-                let right = Self::reorder_joins(*right, input_types);
+                let right = Self::reorder_joins(*right, input_types, statistics);
                 let right_types = infer_graph_pattern_types(&right, input_types.clone());
                 #[cfg(feature = "sep-0006")]
                 {
@@ -664,9 +771,9 @@ impl Optimizer {
                 )
             }
             GraphPattern::Minus { left, right, .. } => {
-                let left = Self::reorder_joins(*left, input_types);
+                let left = Self::reorder_joins(*left, input_types, statistics);
                 let left_types = infer_graph_pattern_types(&left, input_types.clone());
-                let right = Self::reorder_joins(*right, input_types);
+                let right = Self::reorder_joins(*right, input_types, statistics);
                 let right_types = infer_graph_pattern_types(&right, input_types.clone());
                 GraphPattern::minus(
                     left,
@@ -681,35 +788,42 @@ impl Optimizer {
                 expression,
                 variable,
             } => GraphPattern::extend(
-                Self::reorder_joins(*inner, input_types),
+                Self::reorder_joins(*inner, input_types, statistics),
                 variable,
                 expression,
             ),
-            GraphPattern::Filter { inner, expression } => {
-                GraphPattern::filter(Self::reorder_joins(*inner, input_types), expression)
-            }
+            GraphPattern::Filter { inner, expression } => GraphPattern::filter(
+                Self::reorder_joins(*inner, input_types, statistics),
+                expression,
+            ),
             GraphPattern::Union { inner } => GraphPattern::union_all(
                 inner
                     .into_iter()
-                    .map(|c| Self::reorder_joins(c, input_types)),
+                    .map(|c| Self::reorder_joins(c, input_types, statistics)),
             ),
             GraphPattern::Slice {
                 inner,
                 start,
                 length,
-            } => GraphPattern::slice(Self::reorder_joins(*inner, input_types), start, length),
+            } => GraphPattern::slice(
+                Self::reorder_joins(*inner, input_types, statistics),
+                start,
+                length,
+            ),
             GraphPattern::Distinct { inner } => {
-                GraphPattern::distinct(Self::reorder_joins(*inner, input_types))
+                GraphPattern::distinct(Self::reorder_joins(*inner, input_types, statistics))
             }
             GraphPattern::Reduced { inner } => {
-                GraphPattern::reduced(Self::reorder_joins(*inner, input_types))
-            }
-            GraphPattern::Project { inner, variables } => {
-                GraphPattern::project(Self::reorder_joins(*inner, input_types), variables)
-            }
-            GraphPattern::OrderBy { inner, expression } => {
-                GraphPattern::order_by(Self::reorder_joins(*inner, input_types), expression)
+                GraphPattern::reduced(Self::reorder_joins(*inner, input_types, statistics))
             }
+            GraphPattern::Project { inner, variables } => GraphPattern::project(
+                Self::reorder_joins(*inner, input_types, statistics),
+                variables,
+            ),
+            GraphPattern::OrderBy { inner, expression } => GraphPattern::order_by(
+                Self::reorder_joins(*inner, input_types, statistics),
+                expression,
+            ),
             GraphPattern::Service { .. } => {
                 // We don't do join reordering inside of SERVICE calls, we don't know about cardinalities
                 pattern
@@ -719,7 +833,7 @@ impl Optimizer {
                 variables,
                 aggregates,
             } => GraphPattern::group(
-                Self::reorder_joins(*inner, input_types),
+                Self::reorder_joins(*inner, input_types, statistics),
                 variables,
                 aggregates,
             ),
@@ -889,7 +1003,11 @@ fn join_key_variables(
         .collect()
 }
 
-fn estimate_graph_pattern_size(pattern: &GraphPattern, input_types: &VariableTypes) -> usize {
+fn estimate_graph_pattern_size(
+    pattern: &GraphPattern,
+    input_types: &VariableTypes,
+    statistics: Option<&DatasetStatistics>,
+) -> usize {
     match pattern {
         GraphPattern::Values { bindings, .. } => bindings.len(),
         GraphPattern::QuadPattern {
@@ -897,11 +1015,23 @@ fn estimate_graph_pattern_size(pattern: &GraphPattern, input_types: &VariableTyp
             predicate,
             object,
             ..
-        } => estimate_triple_pattern_size(
-            is_term_pattern_bound(subject, input_types),
-            is_named_node_pattern_bound(predicate, input_types),
-            is_term_pattern_bound(object, input_types),
-        ),
+        } => {
+            if let NamedNodePattern::NamedNode(predicate) = predicate {
+                estimate_predicate_pattern_size(
+                    is_term_pattern_bound(subject, input_types),
+                    predicate,
+                    is_term_pattern_bound(object, input_types),
+                    statistics,
+                )
+            } else {
+                estimate_triple_pattern_size(
+                    is_term_pattern_bound(subject, input_types),
+                    is_named_node_pattern_bound(predicate, input_types),
+                    is_term_pattern_bound(object, input_types),
+                    statistics,
+                )
+            }
+        }
         GraphPattern::Path {
             subject,
             path,
@@ -911,6 +1041,7 @@ fn estimate_graph_pattern_size(pattern: &GraphPattern, input_types: &VariableTyp
             is_term_pattern_bound(subject, input_types),
             path,
             is_term_pattern_bound(object, input_types),
+            statistics,
         ),
         GraphPattern::Graph { graph_name } => {
             if is_named_node_pattern_bound(graph_name, input_types) {
@@ -923,7 +1054,7 @@ fn estimate_graph_pattern_size(pattern: &GraphPattern, input_types: &VariableTyp
             left,
             right,
             algorithm,
-        } => estimate_join_cost(left, right, algorithm, input_types),
+        } => estimate_join_cost(left, right, algorithm, input_types, statistics),
         GraphPattern::LeftJoin {
             left,
             right,
@@ -931,13 +1062,14 @@ fn estimate_graph_pattern_size(pattern: &GraphPattern, input_types: &VariableTyp
             ..
         } => match algorithm {
             LeftJoinAlgorithm::HashBuildRightProbeLeft { keys } => {
-                let left_size = estimate_graph_pattern_size(left, input_types);
+                let left_size = estimate_graph_pattern_size(left, input_types, statistics);
                 max(
                     left_size,
                     left_size
                         .saturating_mul(estimate_graph_pattern_size(
                             right,
                             &infer_graph_pattern_types(right, input_types.clone()),
+                            statistics,
                         ))
                         .saturating_div(1_000_usize.saturating_pow(keys.len().try_into().unwrap())),
                 )
@@ -949,12 +1081,15 @@ fn estimate_graph_pattern_size(pattern: &GraphPattern, input_types: &VariableTyp
             &infer_graph_pattern_types(left, input_types.clone()),
             right,
             input_types,
+            statistics,
         ),
         GraphPattern::Union { inner } => inner
             .iter()
-            .map(|inner| estimate_graph_pattern_size(inner, input_types))
+            .map(|inner| estimate_graph_pattern_size(inner, input_types, statistics))
             .fold(0, usize::saturating_add),
-        GraphPattern::Minus { left, .. } => estimate_graph_pattern_size(left, input_types),
+        GraphPattern::Minus { left, .. } => {
+            estimate_graph_pattern_size(left, input_types, statistics)
+        }
         GraphPattern::Filter { inner, .. }
         | GraphPattern::Extend { inner, .. }
         | GraphPattern::OrderBy { inner, .. }
@@ -962,15 +1097,18 @@ fn estimate_graph_pattern_size(pattern: &GraphPattern, input_types: &VariableTyp
         | GraphPattern::Distinct { inner, .. }
         | GraphPattern::Reduced { inner, .. }
         | GraphPattern::Group { inner, .. }
-        | GraphPattern::Service { inner, .. } => estimate_graph_pattern_size(inner, input_types),
+        | GraphPattern::Service { inner, .. } => {
+            estimate_graph_pattern_size(inner, input_types, statistics)
+        }
         GraphPattern::Slice {
             inner,
             start,
             length,
         } => {
-            let inner = estimate_graph_pattern_size(inner, input_types);
+            let inner =
+                estimate_graph_pattern_size(inner, input_types, statistics).saturating_sub(*start);
             if let Some(length) = length {
-                min(inner, *length - *start)
+                min(inner, *length)
             } else {
                 inner
             }
@@ -983,11 +1121,12 @@ fn estimate_join_cost(
     right: &GraphPattern,
     algorithm: &JoinAlgorithm,
     input_types: &VariableTypes,
+    statistics: Option<&DatasetStatistics>,
 ) -> usize {
     match algorithm {
         JoinAlgorithm::HashBuildLeftProbeRight { keys } => {
-            estimate_graph_pattern_size(left, input_types)
-                .saturating_mul(estimate_graph_pattern_size(right, input_types))
+            estimate_graph_pattern_size(left, input_types, statistics)
+                .saturating_mul(estimate_graph_pattern_size(right, input_types, statistics))
                 .saturating_div(1_000_usize.saturating_pow(keys.len().try_into().unwrap()))
         }
     }
@@ -997,16 +1136,77 @@ fn estimate_lateral_cost(
     left_types: &VariableTypes,
     right: &GraphPattern,
     input_types: &VariableTypes,
+    statistics: Option<&DatasetStatistics>,
 ) -> usize {
-    estimate_graph_pattern_size(left, input_types)
-        .saturating_mul(estimate_graph_pattern_size(right, left_types))
+    estimate_graph_pattern_size(left, input_types, statistics)
+        .saturating_mul(estimate_graph_pattern_size(right, left_types, statistics))
+}
+
+/// Estimates the cost of a hash join by the number of tuples read from its children
+fn estimate_hash_join_cost(
+    left: &GraphPattern,
+    right: &GraphPattern,
+    input_types: &VariableTypes,
+    statistics: Option<&DatasetStatistics>,
+) -> usize {
+    estimate_graph_pattern_size(left, input_types, statistics)
+        .saturating_add(estimate_graph_pattern_size(right, input_types, statistics))
+}
+
+/// Checks if `right` should be evaluated once for each solution of `left` instead of using a hash join
+///
+/// Without statistics, the for loop join is always used when possible.
+fn use_for_loop_join(
+    left: &GraphPattern,
+    left_types: &VariableTypes,
+    right: &GraphPattern,
+    input_types: &VariableTypes,
+    statistics: Option<&DatasetStatistics>,
+) -> bool {
+    if !cfg!(feature = "sep-0006") || !is_fit_for_for_loop_join(right, input_types, left_types) {
+        return false;
+    }
+    let Some(statistics) = statistics else {
+        return true;
+    };
+    estimate_lateral_cost(left, left_types, right, input_types, Some(statistics))
+        <= estimate_hash_join_cost(left, right, input_types, Some(statistics))
+}
+
+/// Estimates the size of a triple pattern with a fixed predicate using the dataset statistics if available
+fn estimate_predicate_pattern_size(
+    subject_bound: bool,
+    predicate: &NamedNode,
+    object_bound: bool,
+    statistics: Option<&DatasetStatistics>,
+) -> usize {
+    let Some(statistics) = statistics else {
+        return estimate_triple_pattern_size(subject_bound, true, object_bound, None);
+    };
+    let Some(predicate) = statistics.predicate(predicate) else {
+        // The predicate is not in the dataset. The statistics might be outdated so we don't return 0
+        return 1;
+    };
+    match (subject_bound, object_bound) {
+        (true, true) => 1,
+        (true, false) => predicate.count() / max(predicate.distinct_subject_count(), 1),
+        (false, true) => predicate.count() / max(predicate.distinct_object_count(), 1),
+        (false, false) => predicate.count(),
+    }
+    .max(1)
 }
 
 fn estimate_triple_pattern_size(
     subject_bound: bool,
     predicate_bound: bool,
     object_bound: bool,
+    statistics: Option<&DatasetStatistics>,
 ) -> usize {
+    if let Some(statistics) = statistics {
+        if !subject_bound && !predicate_bound && !object_bound {
+            return max(statistics.quad_count(), 1);
+        }
+    }
     match (subject_bound, predicate_bound, object_bound) {
         (true, true, true) => 1,
         (true, true, false) => 10,
@@ -1019,28 +1219,37 @@ fn estimate_triple_pattern_size(
     }
 }
 
-fn estimate_path_size(start_bound: bool, path: &PropertyPathExpression, end_bound: bool) -> usize {
+fn estimate_path_size(
+    start_bound: bool,
+    path: &PropertyPathExpression,
+    end_bound: bool,
+    statistics: Option<&DatasetStatistics>,
+) -> usize {
     match path {
-        PropertyPathExpression::NamedNode(_) => {
-            estimate_triple_pattern_size(start_bound, true, end_bound)
+        PropertyPathExpression::NamedNode(predicate) => {
+            estimate_predicate_pattern_size(start_bound, predicate, end_bound, statistics)
+        }
+        PropertyPathExpression::Reverse(p) => {
+            estimate_path_size(end_bound, p, start_bound, statistics)
         }
-        PropertyPathExpression::Reverse(p) => estimate_path_size(end_bound, p, start_bound),
         PropertyPathExpression::Sequence(a, b) => {
             // We do a for loop join in the best direction
             min(
-                estimate_path_size(start_bound, a, false)
-                    .saturating_mul(estimate_path_size(true, b, end_bound)),
-                estimate_path_size(start_bound, a, true)
-                    .saturating_mul(estimate_path_size(false, b, end_bound)),
+                estimate_path_size(start_bound, a, false, statistics)
+                    .saturating_mul(estimate_path_size(true, b, end_bound, statistics)),
+                estimate_path_size(start_bound, a, true, statistics)
+                    .saturating_mul(estimate_path_size(false, b, end_bound, statistics)),
             )
         }
-        PropertyPathExpression::Alternative(a, b) => estimate_path_size(start_bound, a, end_bound)
-            .saturating_add(estimate_path_size(start_bound, b, end_bound)),
+        PropertyPathExpression::Alternative(a, b) => {
+            estimate_path_size(start_bound, a, end_bound, statistics)
+                .saturating_add(estimate_path_size(start_bound, b, end_bound, statistics))
+        }
         PropertyPathExpression::ZeroOrMore(p) => {
             if start_bound && end_bound {
                 1
             } else if start_bound || end_bound {
-                estimate_path_size(start_bound, p, end_bound).saturating_mul(1000)
+                estimate_path_size(start_bound, p, end_bound, statistics).saturating_mul(1000)
             } else {
                 1_000_000_000
             }
@@ -1049,20 +1258,20 @@ fn estimate_path_size(start_bound: bool, path: &PropertyPathExpression, end_boun
             if start_bound && end_bound {
                 1
             } else {
-                estimate_path_size(start_bound, p, end_bound).saturating_mul(1000)
+                estimate_path_size(start_bound, p, end_bound, statistics).saturating_mul(1000)
             }
         }
         PropertyPathExpression::ZeroOrOne(p) => {
             if start_bound && end_bound {
                 1
             } else if start_bound || end_bound {
-                estimate_path_size(start_bound, p, end_bound)
+                estimate_path_size(start_bound, p, end_bound, statistics)
             } else {
                 1_000_000_000
             }
         }
         PropertyPathExpression::NegatedPropertySet(_) => {
-            estimate_triple_pattern_size(start_bound, false, end_bound)
+            estimate_triple_pattern_size(start_bound, false, end_bound, statistics)
         }
     }
 }
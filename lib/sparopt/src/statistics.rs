@@ -0,0 +1,105 @@
+use oxrdf::NamedNode;
+use std::collections::HashMap;
+
+/// Statistics about the content of a dataset used by the [`Optimizer`](crate::Optimizer) to estimate the size of the graph patterns.
+///
+/// They are usually computed by the storage and are allowed to be approximate or outdated:
+/// they only change the way the query is evaluated, not its results.
+///
+/// ```
+/// use oxrdf::NamedNode;
+/// use sparopt::{DatasetStatistics, PredicateStatistics};
+///
+/// let mut statistics = DatasetStatistics::new(1000);
+/// let name = NamedNode::new("http://schema.org/name")?;
+/// statistics.insert_predicate(name.clone(), PredicateStatistics::new(100, 100, 95));
+/// assert_eq!(statistics.quad_count(), 1000);
+/// assert_eq!(
+///     statistics.predicate(&name).map(|s| s.distinct_object_count()),
+///     Some(95)
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct DatasetStatistics {
+    quad_count: usize,
+    predicates: HashMap<NamedNode, PredicateStatistics>,
+}
+
+impl DatasetStatistics {
+    /// Builds statistics for a dataset of `quad_count` quads without any information about its predicates.
+    #[inline]
+    pub fn new(quad_count: usize) -> Self {
+        Self {
+            quad_count,
+            predicates: HashMap::new(),
+        }
+    }
+
+    /// The number of quads in the dataset.
+    #[inline]
+    pub fn quad_count(&self) -> usize {
+        self.quad_count
+    }
+
+    /// Sets the statistics of the quads with the given predicate.
+    ///
+    /// The predicates without statistics are considered to be not used in the dataset.
+    #[inline]
+    pub fn insert_predicate(&mut self, predicate: NamedNode, statistics: PredicateStatistics) {
+        self.predicates.insert(predicate, statistics);
+    }
+
+    /// The statistics of the quads with the given predicate, `None` if it is not used in the dataset.
+    #[inline]
+    pub fn predicate(&self, predicate: &NamedNode) -> Option<PredicateStatistics> {
+        self.predicates.get(predicate).copied()
+    }
+
+    /// Iterates on the statistics of all the predicates.
+    #[inline]
+    pub fn predicates(&self) -> impl Iterator<Item = (&NamedNode, PredicateStatistics)> {
+        self.predicates
+            .iter()
+            .map(|(predicate, statistics)| (predicate, *statistics))
+    }
+}
+
+/// Statistics about the quads of a dataset sharing the same predicate.
+///
+/// See [`DatasetStatistics`].
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Default)]
+pub struct PredicateStatistics {
+    count: usize,
+    distinct_subject_count: usize,
+    distinct_object_count: usize,
+}
+
+impl PredicateStatistics {
+    #[inline]
+    pub fn new(count: usize, distinct_subject_count: usize, distinct_object_count: usize) -> Self {
+        Self {
+            count,
+            distinct_subject_count,
+            distinct_object_count,
+        }
+    }
+
+    /// The number of quads with the predicate.
+    #[inline]
+    pub fn count(self) -> usize {
+        self.count
+    }
+
+    /// The number of distinct subjects of the quads with the predicate.
+    #[inline]
+    pub fn distinct_subject_count(self) -> usize {
+        self.distinct_subject_count
+    }
+
+    /// The number of distinct objects of the quads with the predicate.
+    #[inline]
+    pub fn distinct_object_count(self) -> usize {
+        self.distinct_object_count
+    }
+}
@@ -0,0 +1,228 @@
+//! A [Hierarchical Navigable Small World](https://arxiv.org/abs/1603.09320) graph.
+//!
+//! Vectors are normalized on insertion so the distance is `1 - cosine similarity`.
+//! Removed nodes are kept in the graph to preserve its connectivity and only filtered out of the results.
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+const MAX_LINKS: usize = 16;
+const EF_CONSTRUCTION: usize = 100;
+const EF_SEARCH: usize = 64;
+const MAX_LEVEL: usize = 16;
+
+#[derive(Default)]
+pub struct Hnsw {
+    nodes: Vec<Node>,
+    entry_point: Option<usize>,
+    random_state: u64,
+}
+
+struct Node {
+    vector: Vec<f32>,
+    links: Vec<Vec<usize>>,
+    removed: bool,
+}
+
+impl Hnsw {
+    /// Inserts a normalized vector and returns its node id
+    pub fn insert(&mut self, vector: Vec<f32>) -> usize {
+        let id = self.nodes.len();
+        let level = self.random_level();
+        self.nodes.push(Node {
+            vector,
+            links: vec![Vec::new(); level + 1],
+            removed: false,
+        });
+        let Some(mut entry_point) = self.entry_point else {
+            self.entry_point = Some(id);
+            return id;
+        };
+        let top_level = self.nodes[entry_point].links.len() - 1;
+        let vector = &self.nodes[id].vector;
+        for layer in (level + 1..=top_level).rev() {
+            entry_point = self.closest(vector, entry_point, layer);
+        }
+        for layer in (0..=level.min(top_level)).rev() {
+            let candidates =
+                self.search_layer(&self.nodes[id].vector, entry_point, EF_CONSTRUCTION, layer);
+            let neighbors = candidates
+                .iter()
+                .take(max_links(layer))
+                .map(|(_, neighbor)| *neighbor)
+                .collect::<Vec<_>>();
+            for neighbor in &neighbors {
+                self.link(*neighbor, id, layer);
+            }
+            self.nodes[id].links[layer] = neighbors;
+            if let Some((_, closest)) = candidates.first() {
+                entry_point = *closest;
+            }
+        }
+        if level > top_level {
+            self.entry_point = Some(id);
+        }
+        id
+    }
+
+    /// Marks a node as removed
+    pub fn remove(&mut self, id: usize) {
+        if let Some(node) = self.nodes.get_mut(id) {
+            node.removed = true;
+        }
+    }
+
+    /// Returns the vector of a node
+    pub fn vector(&self, id: usize) -> Option<&[f32]> {
+        let node = self.nodes.get(id)?;
+        (!node.removed).then_some(node.vector.as_slice())
+    }
+
+    /// Returns the `k` approximate nearest neighbors of a normalized vector with their distance
+    pub fn search(&self, vector: &[f32], k: usize) -> Vec<(f32, usize)> {
+        let Some(mut entry_point) = self.entry_point else {
+            return Vec::new();
+        };
+        for layer in (1..self.nodes[entry_point].links.len()).rev() {
+            entry_point = self.closest(vector, entry_point, layer);
+        }
+        let mut results = self.search_layer(vector, entry_point, k.max(EF_SEARCH), 0);
+        results.retain(|(_, id)| !self.nodes[*id].removed);
+        results.truncate(k);
+        results
+    }
+
+    /// Adds a link from `from` to `to` and prunes the links of `from` if there are too many of them
+    fn link(&mut self, from: usize, to: usize, layer: usize) {
+        let mut links = std::mem::take(&mut self.nodes[from].links[layer]);
+        links.push(to);
+        if links.len() > max_links(layer) {
+            let vector = &self.nodes[from].vector;
+            let mut scored = links
+                .iter()
+                .map(|id| (self.distance(vector, *id), *id))
+                .collect::<Vec<_>>();
+            scored.sort_by(|(d1, _), (d2, _)| d1.total_cmp(d2));
+            scored.truncate(max_links(layer));
+            links = scored.into_iter().map(|(_, id)| id).collect();
+        }
+        self.nodes[from].links[layer] = links;
+    }
+
+    /// Greedy search of the closest node in a layer
+    fn closest(&self, vector: &[f32], entry_point: usize, layer: usize) -> usize {
+        let mut current = entry_point;
+        let mut current_distance = self.distance(vector, current);
+        loop {
+            let mut changed = false;
+            for neighbor in self.nodes[current].links.get(layer).into_iter().flatten() {
+                let distance = self.distance(vector, *neighbor);
+                if distance < current_distance {
+                    current = *neighbor;
+                    current_distance = distance;
+                    changed = true;
+                }
+            }
+            if !changed {
+                return current;
+            }
+        }
+    }
+
+    /// Beam search in a layer, returns the `ef` closest nodes found sorted by distance
+    fn search_layer(
+        &self,
+        vector: &[f32],
+        entry_point: usize,
+        ef: usize,
+        layer: usize,
+    ) -> Vec<(f32, usize)> {
+        let entry = Scored(self.distance(vector, entry_point), entry_point);
+        let mut visited = HashSet::from([entry_point]);
+        let mut candidates = BinaryHeap::from([Reverse(entry)]);
+        let mut results = BinaryHeap::from([entry]);
+        while let Some(Reverse(Scored(distance, id))) = candidates.pop() {
+            if results
+                .peek()
+                .is_some_and(|Scored(furthest, _)| distance > *furthest)
+            {
+                break;
+            }
+            for neighbor in self.nodes[id].links.get(layer).into_iter().flatten() {
+                if !visited.insert(*neighbor) {
+                    continue;
+                }
+                let neighbor_distance = self.distance(vector, *neighbor);
+                if results.len() < ef
+                    || results
+                        .peek()
+                        .is_some_and(|Scored(furthest, _)| neighbor_distance < *furthest)
+                {
+                    candidates.push(Reverse(Scored(neighbor_distance, *neighbor)));
+                    results.push(Scored(neighbor_distance, *neighbor));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+        results
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Scored(distance, id)| (distance, id))
+            .collect()
+    }
+
+    fn distance(&self, vector: &[f32], id: usize) -> f32 {
+        1. - dot(vector, &self.nodes[id].vector)
+    }
+
+    /// Draws a level with an exponentially decaying probability using a xorshift generator
+    #[expect(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn random_level(&mut self) -> usize {
+        if self.random_state == 0 {
+            self.random_state = 0x2545_F491_4F6C_DD1D;
+        }
+        self.random_state ^= self.random_state << 13;
+        self.random_state ^= self.random_state >> 7;
+        self.random_state ^= self.random_state << 17;
+        let uniform = ((self.random_state >> 11) as f64 + 1.) / (1_u64 << 53) as f64;
+        let level = -uniform.ln() / (MAX_LINKS as f64).ln();
+        (level as usize).min(MAX_LEVEL)
+    }
+}
+
+fn max_links(layer: usize) -> usize {
+    if layer == 0 { 2 * MAX_LINKS } else { MAX_LINKS }
+}
+
+pub fn dot(left: &[f32], right: &[f32]) -> f32 {
+    left.iter().zip(right).map(|(l, r)| l * r).sum()
+}
+
+/// A node id with its distance, ordered by distance
+#[derive(Clone, Copy)]
+struct Scored(f32, usize);
+
+impl PartialEq for Scored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
@@ -0,0 +1,507 @@
+#![doc = include_str!("../README.md")]
+#![doc(test(attr(deny(warnings))))]
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![doc(html_favicon_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+#![doc(html_logo_url = "https://raw.githubusercontent.com/oxigraph/oxigraph/main/logo.svg")]
+
+mod hnsw;
+
+use crate::hnsw::{Hnsw, dot};
+use oxigraph::model::{
+    GraphName, Literal, LiteralRef, NamedNodeRef, NamedOrBlankNode, NamedOrBlankNodeRef, Quad, Term,
+};
+use oxigraph::sparql::{EvaluationError, Query, QueryOptions, QueryResults, ServiceHandler};
+use oxigraph::store::{StorageError, Store};
+use spargebra::algebra::GraphPattern;
+use spargebra::term::{NamedNodePattern, TermPattern, TriplePattern};
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Number of neighbors returned by the `vec:search` service if `vec:k` is not set.
+const DEFAULT_K: usize = 10;
+
+/// Registers the `vec:search` SERVICE and the `vec:cosineSimilarity` extension function in the [`QueryOptions`].
+///
+/// The `vec:search` service expects a `?item vec:nearTo ?target` triple where `?target` is either a `vec:Vector` literal
+/// or a resource of the index, in which case the resource itself is not returned.
+/// `?item` is bound to the nearest neighbors of the target, that might be restricted with a `vec:k` triple (10 by default).
+/// The cosine similarity with the target is returned by the `vec:score` property.
+///
+/// Like all SERVICE calls the service pattern is evaluated independently: the target and `k` must be constants.
+/// Add an `ORDER BY DESC(?score)` to the query to get the neighbors ranked.
+///
+/// ```
+/// use oxigraph::model::*;
+/// use oxigraph::sparql::{QueryOptions, QueryResults};
+/// use oxigraph::store::Store;
+/// use sparvec::{VectorIndex, register_vector_functions};
+/// use std::sync::Arc;
+///
+/// let index = Arc::new(VectorIndex::new());
+/// index.insert(NamedNode::new("http://example.com/a")?, &[1., 0.])?;
+/// index.insert(NamedNode::new("http://example.com/b")?, &[0., 1.])?;
+///
+/// let options = register_vector_functions(QueryOptions::default(), index);
+/// if let QueryResults::Solutions(mut solutions) = Store::new()?.query_opt(
+///     "PREFIX vec: <http://oxigraph.org/vector#>
+///     SELECT ?item ?score WHERE {
+///         SERVICE vec:search { ?item vec:nearTo \"[0.9, 0.1]\"^^vec:Vector ; vec:k 1 ; vec:score ?score }
+///     }",
+///     options,
+/// )? {
+///     let solution = solutions.next().unwrap()?;
+///     assert_eq!(
+///         solution.get("item"),
+///         Some(&NamedNode::new("http://example.com/a")?.into())
+///     );
+///     assert!(solutions.next().is_none());
+/// }
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+pub fn register_vector_functions(options: QueryOptions, index: Arc<VectorIndex>) -> QueryOptions {
+    options
+        .with_service_handler(vec::SEARCH, VectorSearchService { index })
+        .with_custom_function(vec::COSINE_SIMILARITY.into(), vec_cosine_similarity)
+}
+
+/// An index associating vectors (e.g. embeddings) to RDF resources.
+///
+/// The vectors are indexed in a [HNSW](https://arxiv.org/abs/1603.09320) graph allowing approximate nearest neighbors search
+/// using the cosine similarity.
+/// All the vectors of an index must have the same number of dimensions.
+///
+/// The index lives in memory and is not kept in sync with a [`Store`]:
+/// use [`VectorIndex::load_from_store`] to index the `vec:Vector` literals of a store.
+///
+/// ```
+/// use oxigraph::model::NamedNode;
+/// use sparvec::VectorIndex;
+///
+/// let a = NamedNode::new("http://example.com/a")?;
+/// let b = NamedNode::new("http://example.com/b")?;
+/// let index = VectorIndex::new();
+/// index.insert(a.clone(), &[1., 0., 0.])?;
+/// index.insert(b, &[0., 1., 0.])?;
+///
+/// let neighbors = index.search(&[0.8, 0.2, 0.], 1)?;
+/// assert_eq!(neighbors.len(), 1);
+/// assert_eq!(neighbors[0].0, a.into());
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Default)]
+pub struct VectorIndex {
+    content: RwLock<VectorIndexContent>,
+}
+
+#[derive(Default)]
+struct VectorIndexContent {
+    graph: Hnsw,
+    dimensions: Option<usize>,
+    ids: HashMap<NamedOrBlankNode, usize>,
+    resources: Vec<NamedOrBlankNode>,
+}
+
+impl VectorIndex {
+    /// Builds a new empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Associates a vector to a resource, replacing its previous vector if there was one.
+    ///
+    /// The first inserted vector sets the number of dimensions of the index.
+    pub fn insert(
+        &self,
+        resource: impl Into<NamedOrBlankNode>,
+        vector: &[f32],
+    ) -> Result<(), VectorError> {
+        let resource = resource.into();
+        let mut content = self.content.write().unwrap_or_else(PoisonError::into_inner);
+        let vector = content.normalize(vector)?;
+        content.dimensions = Some(vector.len());
+        if let Some(old_id) = content.ids.remove(&resource) {
+            content.graph.remove(old_id);
+        }
+        let id = content.graph.insert(vector);
+        content.ids.insert(resource.clone(), id);
+        content.resources.push(resource);
+        Ok(())
+    }
+
+    /// Removes the vector associated to a resource.
+    ///
+    /// Returns `true` if the resource had a vector.
+    pub fn remove<'a>(&self, resource: impl Into<NamedOrBlankNodeRef<'a>>) -> bool {
+        let resource = resource.into().into_owned();
+        let mut content = self.content.write().unwrap_or_else(PoisonError::into_inner);
+        let Some(id) = content.ids.remove(&resource) else {
+            return false;
+        };
+        content.graph.remove(id);
+        true
+    }
+
+    /// Returns the (normalized) vector associated to a resource.
+    pub fn get<'a>(&self, resource: impl Into<NamedOrBlankNodeRef<'a>>) -> Option<Vec<f32>> {
+        let resource = resource.into().into_owned();
+        let content = self.content.read().unwrap_or_else(PoisonError::into_inner);
+        content
+            .graph
+            .vector(*content.ids.get(&resource)?)
+            .map(ToOwned::to_owned)
+    }
+
+    /// Returns the number of resources with a vector.
+    pub fn len(&self) -> usize {
+        self.content
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .ids
+            .len()
+    }
+
+    /// Returns if the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the `k` resources with the vectors most similar to the given vector
+    /// with their cosine similarity, the most similar first.
+    ///
+    /// The search is approximate: some of the nearest neighbors might be missing.
+    pub fn search(
+        &self,
+        vector: &[f32],
+        k: usize,
+    ) -> Result<Vec<(NamedOrBlankNode, f32)>, VectorError> {
+        let content = self.content.read().unwrap_or_else(PoisonError::into_inner);
+        let vector = content.normalize(vector)?;
+        Ok(content.search(&vector, k, None))
+    }
+
+    /// Returns the `k` resources with the vectors most similar to the vector of the given resource
+    /// with their cosine similarity, the most similar first.
+    ///
+    /// The resource itself is not returned. Returns `None` if the resource has no vector.
+    pub fn search_similar<'a>(
+        &self,
+        resource: impl Into<NamedOrBlankNodeRef<'a>>,
+        k: usize,
+    ) -> Option<Vec<(NamedOrBlankNode, f32)>> {
+        let resource = resource.into().into_owned();
+        let content = self.content.read().unwrap_or_else(PoisonError::into_inner);
+        let id = *content.ids.get(&resource)?;
+        let vector = content.graph.vector(id)?;
+        Some(content.search(vector, k, Some(id)))
+    }
+
+    /// Indexes all the `vec:Vector` literals of the store.
+    ///
+    /// Each literal is associated to the subject of its triple.
+    /// Returns the number of indexed vectors.
+    ///
+    /// ```
+    /// use oxigraph::io::RdfFormat;
+    /// use oxigraph::model::NamedNodeRef;
+    /// use oxigraph::store::Store;
+    /// use sparvec::VectorIndex;
+    ///
+    /// let store = Store::new()?;
+    /// store.load_from_reader(
+    ///     RdfFormat::NTriples,
+    ///     b"<http://example.com/a> <http://example.com/embedding> \"[1, 0.5]\"^^<http://oxigraph.org/vector#Vector> .".as_slice(),
+    /// )?;
+    /// let index = VectorIndex::new();
+    /// assert_eq!(index.load_from_store(&store)?, 1);
+    /// assert!(index.get(NamedNodeRef::new("http://example.com/a")?).is_some());
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn load_from_store(&self, store: &Store) -> Result<usize, VectorError> {
+        let mut count = 0;
+        for quad in store {
+            let quad = quad?;
+            let Term::Literal(literal) = &quad.object else {
+                continue;
+            };
+            if literal.datatype() != vec::VECTOR {
+                continue;
+            }
+            let vector = parse_vector(literal.as_ref()).ok_or_else(|| {
+                VectorError::InvalidVector(format!("{literal} is not a valid vector"))
+            })?;
+            self.insert(quad.subject, &vector)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+impl VectorIndexContent {
+    /// Validates the vector dimensions and normalizes it
+    fn normalize(&self, vector: &[f32]) -> Result<Vec<f32>, VectorError> {
+        if let Some(dimensions) = self.dimensions {
+            if vector.len() != dimensions {
+                return Err(VectorError::DimensionMismatch {
+                    expected: dimensions,
+                    actual: vector.len(),
+                });
+            }
+        }
+        normalize(vector)
+            .ok_or_else(|| VectorError::InvalidVector("the vector must not be null".into()))
+    }
+
+    fn search(
+        &self,
+        vector: &[f32],
+        k: usize,
+        excluded: Option<usize>,
+    ) -> Vec<(NamedOrBlankNode, f32)> {
+        self.graph
+            .search(vector, k + usize::from(excluded.is_some()))
+            .into_iter()
+            .filter(|(_, id)| Some(*id) != excluded)
+            .take(k)
+            .map(|(distance, id)| (self.resources[id].clone(), 1. - distance))
+            .collect()
+    }
+}
+
+/// Returns the unit vector with the same direction or `None` if the vector is null or contains non finite numbers
+fn normalize(vector: &[f32]) -> Option<Vec<f32>> {
+    let norm = dot(vector, vector).sqrt();
+    (norm.is_finite() && norm > 0.).then(|| vector.iter().map(|v| v / norm).collect())
+}
+
+/// Parses the lexical form of a `vec:Vector` literal like `[0.1, 0.5, -0.3]`.
+///
+/// ```
+/// use oxigraph::model::LiteralRef;
+/// use sparvec::parse_vector;
+///
+/// assert_eq!(
+///     parse_vector(LiteralRef::new_typed_literal(
+///         "[1, 0.5]",
+///         sparvec::vec::VECTOR
+///     )),
+///     Some(vec![1., 0.5])
+/// );
+/// ```
+pub fn parse_vector(literal: LiteralRef<'_>) -> Option<Vec<f32>> {
+    if literal.datatype() != vec::VECTOR {
+        return None;
+    }
+    let values = literal
+        .value()
+        .trim()
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .trim();
+    if values.is_empty() {
+        return Some(Vec::new());
+    }
+    values
+        .split(',')
+        .map(|v| v.trim().parse::<f32>().ok().filter(|v| v.is_finite()))
+        .collect()
+}
+
+/// Builds a `vec:Vector` literal from a vector.
+///
+/// ```
+/// use sparvec::{parse_vector, vector_literal};
+///
+/// let literal = vector_literal(&[1., 0.5]);
+/// assert_eq!(literal.value(), "[1, 0.5]");
+/// assert_eq!(parse_vector(literal.as_ref()), Some(vec![1., 0.5]));
+/// ```
+pub fn vector_literal(vector: &[f32]) -> Literal {
+    let value = vector
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    Literal::new_typed_literal(format!("[{value}]"), vec::VECTOR)
+}
+
+fn vec_cosine_similarity(args: &[Term]) -> Option<Term> {
+    let [Term::Literal(left), Term::Literal(right)] = args else {
+        return None;
+    };
+    let left = normalize(&parse_vector(left.as_ref())?)?;
+    let right = normalize(&parse_vector(right.as_ref())?)?;
+    if left.len() != right.len() {
+        return None;
+    }
+    Some(Literal::from(f64::from(dot(&left, &right))).into())
+}
+
+/// Handler of the `vec:search` SERVICE.
+///
+/// It evaluates the service pattern against a temporary store with the triples describing the nearest neighbors.
+struct VectorSearchService {
+    index: Arc<VectorIndex>,
+}
+
+impl ServiceHandler for VectorSearchService {
+    type Error = VectorError;
+
+    fn handle(&self, query: Query) -> Result<QueryResults, VectorError> {
+        let spargebra::Query::Select { pattern, .. } = spargebra::Query::from(query.clone()) else {
+            return Err(VectorError::InvalidSearch(
+                "Only SELECT queries are supported".into(),
+            ));
+        };
+        let mut triples = Vec::new();
+        collect_triples(&pattern, &mut triples);
+        let k_triple = triples.iter().find(|t| has_predicate(t, vec::K));
+        let k = if let Some(k_triple) = k_triple {
+            if let TermPattern::Literal(k) = &k_triple.object {
+                k.value().parse::<usize>().ok().filter(|k| *k > 0)
+            } else {
+                None
+            }
+            .ok_or_else(|| {
+                VectorError::InvalidSearch(format!(
+                    "vec:k must be a positive integer, found {}",
+                    k_triple.object
+                ))
+            })?
+        } else {
+            DEFAULT_K
+        };
+
+        let data = Store::new()?;
+        let mut has_target = false;
+        for triple in &triples {
+            if !has_predicate(triple, vec::NEAR_TO) {
+                continue;
+            }
+            has_target = true;
+            let (target, neighbors) = match &triple.object {
+                TermPattern::Literal(literal) => {
+                    let vector = parse_vector(literal.as_ref()).ok_or_else(|| {
+                        VectorError::InvalidVector(format!("{literal} is not a valid vector"))
+                    })?;
+                    (literal.clone().into(), self.index.search(&vector, k)?)
+                }
+                TermPattern::NamedNode(node) => (
+                    node.clone().into(),
+                    self.index.search_similar(node, k).unwrap_or_default(),
+                ),
+                object => {
+                    return Err(VectorError::InvalidSearch(format!(
+                        "vec:nearTo object must be a vec:Vector literal or an IRI, found {object}"
+                    )));
+                }
+            };
+            for (neighbor, score) in neighbors {
+                let mut quads = vec![
+                    Quad::new(
+                        neighbor.clone(),
+                        vec::NEAR_TO,
+                        Term::clone(&target),
+                        GraphName::DefaultGraph,
+                    ),
+                    Quad::new(
+                        neighbor.clone(),
+                        vec::SCORE,
+                        Literal::from(f64::from(score)),
+                        GraphName::DefaultGraph,
+                    ),
+                ];
+                if let Some(TriplePattern {
+                    object: TermPattern::Literal(k),
+                    ..
+                }) = k_triple
+                {
+                    quads.push(Quad::new(
+                        neighbor,
+                        vec::K,
+                        k.clone(),
+                        GraphName::DefaultGraph,
+                    ));
+                }
+                data.extend(quads)?;
+            }
+        }
+        if !has_target {
+            return Err(VectorError::InvalidSearch(
+                "The vec:search service requires a vec:nearTo triple".into(),
+            ));
+        }
+        Ok(data.query(query)?)
+    }
+}
+
+fn has_predicate(triple: &TriplePattern, predicate: NamedNodeRef<'_>) -> bool {
+    matches!(&triple.predicate, NamedNodePattern::NamedNode(p) if *p == predicate)
+}
+
+/// Collects the triple patterns of the service pattern
+fn collect_triples<'a>(pattern: &'a GraphPattern, triples: &mut Vec<&'a TriplePattern>) {
+    match pattern {
+        GraphPattern::Bgp { patterns } => triples.extend(patterns),
+        GraphPattern::Join { left, right }
+        | GraphPattern::LeftJoin { left, right, .. }
+        | GraphPattern::Lateral { left, right }
+        | GraphPattern::Union { left, right }
+        | GraphPattern::Minus { left, right } => {
+            collect_triples(left, triples);
+            collect_triples(right, triples);
+        }
+        GraphPattern::Filter { inner, .. }
+        | GraphPattern::Graph { inner, .. }
+        | GraphPattern::Extend { inner, .. }
+        | GraphPattern::OrderBy { inner, .. }
+        | GraphPattern::Project { inner, .. }
+        | GraphPattern::Distinct { inner }
+        | GraphPattern::Reduced { inner }
+        | GraphPattern::Slice { inner, .. }
+        | GraphPattern::Group { inner, .. } => collect_triples(inner, triples),
+        GraphPattern::Path { .. } | GraphPattern::Values { .. } | GraphPattern::Service { .. } => {}
+    }
+}
+
+/// An error raised by a [`VectorIndex`] or the `vec:search` SERVICE.
+#[derive(Debug, thiserror::Error)]
+pub enum VectorError {
+    /// The vector does not have the same number of dimensions as the ones already in the index.
+    #[error("The vector has {actual} dimensions but the index vectors have {expected} dimensions")]
+    DimensionMismatch { expected: usize, actual: usize },
+    /// The vector is invalid.
+    #[error("Invalid vector: {0}")]
+    InvalidVector(String),
+    /// The `vec:search` SERVICE pattern is invalid.
+    #[error("{0}")]
+    InvalidSearch(String),
+    /// Error from the underlying storage.
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    /// Error during the evaluation of the `vec:search` SERVICE pattern.
+    #[error(transparent)]
+    Evaluation(#[from] EvaluationError),
+}
+
+pub mod vec {
+    //! Vocabulary used by this crate.
+    use oxigraph::model::NamedNodeRef;
+
+    /// Datatype of the vector literals like `"[0.1, 0.5, -0.3]"^^vec:Vector`.
+    pub const VECTOR: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://oxigraph.org/vector#Vector");
+    /// Name of the nearest neighbors search SERVICE.
+    pub const SEARCH: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://oxigraph.org/vector#search");
+    /// Relates a neighbor to the searched vector or resource in the `vec:search` SERVICE.
+    pub const NEAR_TO: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://oxigraph.org/vector#nearTo");
+    /// Maximal number of neighbors returned by the `vec:search` SERVICE.
+    pub const K: NamedNodeRef<'_> = NamedNodeRef::new_unchecked("http://oxigraph.org/vector#k");
+    /// Cosine similarity of a neighbor returned by the `vec:search` SERVICE.
+    pub const SCORE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://oxigraph.org/vector#score");
+    /// Cosine similarity between two `vec:Vector` literals.
+    pub const COSINE_SIMILARITY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://oxigraph.org/vector#cosineSimilarity");
+}
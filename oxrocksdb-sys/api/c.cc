@@ -1,24 +1,37 @@
 #include "c.h"
 
 #include <rocksdb/db.h>
+#include <rocksdb/env_encryption.h>
+#include <rocksdb/utilities/backup_engine.h>
 #include <rocksdb/utilities/checkpoint.h>
 #include <rocksdb/utilities/transaction_db.h>
 
 #include <vector>
 
+using ROCKSDB_NAMESPACE::BackupEngine;
+using ROCKSDB_NAMESPACE::BackupEngineOptions;
+using ROCKSDB_NAMESPACE::BackupID;
+using ROCKSDB_NAMESPACE::BlockCipher;
 using ROCKSDB_NAMESPACE::Checkpoint;
 using ROCKSDB_NAMESPACE::ColumnFamilyDescriptor;
 using ROCKSDB_NAMESPACE::ColumnFamilyHandle;
 using ROCKSDB_NAMESPACE::ColumnFamilyOptions;
 using ROCKSDB_NAMESPACE::CompactRangeOptions;
+using ROCKSDB_NAMESPACE::CreateBackupOptions;
 using ROCKSDB_NAMESPACE::DB;
 using ROCKSDB_NAMESPACE::DBOptions;
+using ROCKSDB_NAMESPACE::EncryptionProvider;
+using ROCKSDB_NAMESPACE::Env;
 using ROCKSDB_NAMESPACE::FlushOptions;
 using ROCKSDB_NAMESPACE::IngestExternalFileOptions;
 using ROCKSDB_NAMESPACE::Iterator;
+using ROCKSDB_NAMESPACE::NewEncryptedEnv;
 using ROCKSDB_NAMESPACE::Options;
 using ROCKSDB_NAMESPACE::PinnableSlice;
+using ROCKSDB_NAMESPACE::Range;
 using ROCKSDB_NAMESPACE::ReadOptions;
+using ROCKSDB_NAMESPACE::RestoreOptions;
+using ROCKSDB_NAMESPACE::SizeApproximationOptions;
 using ROCKSDB_NAMESPACE::Slice;
 using ROCKSDB_NAMESPACE::SstFileWriter;
 using ROCKSDB_NAMESPACE::Status;
@@ -34,6 +47,10 @@ struct rocksdb_t {
   DB* rep;
 };
 
+struct rocksdb_backup_engine_t {
+  BackupEngine* rep;
+};
+
 struct rocksdb_column_family_handle_t {
   ColumnFamilyHandle* rep;
 };
@@ -43,6 +60,11 @@ struct rocksdb_compactoptions_t {
   Slice full_history_ts_low;
 };
 
+struct rocksdb_env_t {
+  Env* rep;
+  bool is_default;
+};
+
 struct rocksdb_flushoptions_t {
   FlushOptions rep;
 };
@@ -108,6 +130,56 @@ static bool SaveStatus(rocksdb_status_t* target, const Status source) {
   return !source.ok();
 }
 
+static uint64_t ApproximateSize(DB* db, ColumnFamilyHandle* column_family,
+                                const char* start_key, size_t start_key_len,
+                                const char* limit_key, size_t limit_key_len,
+                                rocksdb_status_t* statusptr) {
+  SizeApproximationOptions options;
+  options.include_memtables = true;
+  options.include_files = true;
+  Range range(Slice(start_key, start_key_len), Slice(limit_key, limit_key_len));
+  uint64_t size = 0;
+  SaveStatus(statusptr, db->GetApproximateSizes(options, column_family, &range,
+                                                1, &size));
+  return size;
+}
+
+// Block cipher delegating to the functions given through the C API
+class CallbackBlockCipher : public BlockCipher {
+ public:
+  CallbackBlockCipher(void* state, void (*destructor)(void*), size_t block_size,
+                      void (*encrypt_block)(void*, char*),
+                      void (*decrypt_block)(void*, char*))
+      : state_(state),
+        destructor_(destructor),
+        block_size_(block_size),
+        encrypt_block_(encrypt_block),
+        decrypt_block_(decrypt_block) {}
+
+  ~CallbackBlockCipher() override { destructor_(state_); }
+
+  const char* Name() const override { return "CallbackBlockCipher"; }
+
+  size_t BlockSize() override { return block_size_; }
+
+  Status Encrypt(char* data) override {
+    encrypt_block_(state_, data);
+    return Status::OK();
+  }
+
+  Status Decrypt(char* data) override {
+    decrypt_block_(state_, data);
+    return Status::OK();
+  }
+
+ private:
+  void* state_;
+  void (*destructor_)(void*);
+  size_t block_size_;
+  void (*encrypt_block_)(void*, char*);
+  void (*decrypt_block_)(void*, char*);
+};
+
 extern "C" {
 
 rocksdb_pinnableslice_t* rocksdb_get_pinned_cf_with_status(
@@ -160,6 +232,46 @@ rocksdb_t* rocksdb_open_for_read_only_column_families_with_status(
   return result;
 }
 
+rocksdb_t* rocksdb_open_as_secondary_column_families_with_status(
+    const rocksdb_options_t* db_options, const char* name,
+    const char* secondary_path, int num_column_families,
+    const char* const* column_family_names,
+    const rocksdb_options_t* const* column_family_options,
+    rocksdb_column_family_handle_t** column_family_handles,
+    rocksdb_status_t* statusptr) {
+  vector<ColumnFamilyDescriptor> column_families;
+  for (int i = 0; i < num_column_families; i++) {
+    column_families.emplace_back(ColumnFamilyDescriptor(
+        std::string(column_family_names[i]),
+        ColumnFamilyOptions(column_family_options[i]->rep)));
+  }
+
+  DB* db;
+  vector<ColumnFamilyHandle*> handles;
+  if (SaveStatus(statusptr,
+                 DB::OpenAsSecondary(DBOptions(db_options->rep),
+                                     std::string(name),
+                                     std::string(secondary_path),
+                                     column_families, &handles, &db))) {
+    return nullptr;
+  }
+
+  for (size_t i = 0; i < handles.size(); i++) {
+    rocksdb_column_family_handle_t* c_handle =
+        new rocksdb_column_family_handle_t;
+    c_handle->rep = handles[i];
+    column_family_handles[i] = c_handle;
+  }
+  rocksdb_t* result = new rocksdb_t;
+  result->rep = db;
+  return result;
+}
+
+void rocksdb_try_catch_up_with_primary_with_status(
+    rocksdb_t* db, rocksdb_status_t* statusptr) {
+  SaveStatus(statusptr, db->rep->TryCatchUpWithPrimary());
+}
+
 void rocksdb_create_checkpoint_with_status(rocksdb_t* db,
                                            const char* checkpoint_dir,
                                            rocksdb_status_t* statusptr) {
@@ -246,6 +358,12 @@ void rocksdb_transactiondb_flush_cfs_with_status(
   SaveStatus(statusptr, db->rep->Flush(options->rep, column_family_handles));
 }
 
+void rocksdb_transactiondb_flush_wal_with_status(rocksdb_transactiondb_t* db,
+                                                 unsigned char sync,
+                                                 rocksdb_status_t* statusptr) {
+  SaveStatus(statusptr, db->rep->FlushWAL(sync));
+}
+
 void rocksdb_transactiondb_compact_range_cf_opt_with_status(
     rocksdb_transactiondb_t* db, rocksdb_column_family_handle_t* column_family,
     rocksdb_compactoptions_t* opt, const char* start_key, size_t start_key_len,
@@ -290,6 +408,74 @@ void rocksdb_transactiondb_create_checkpoint_with_status(
   delete checkpoint;
 }
 
+rocksdb_backup_engine_t* rocksdb_backup_engine_open_with_status(
+    const char* backup_dir, rocksdb_status_t* statusptr) {
+  BackupEngine* be;
+  if (SaveStatus(statusptr,
+                 BackupEngine::Open(BackupEngineOptions(std::string(backup_dir)),
+                                    Env::Default(), &be))) {
+    return nullptr;
+  }
+  rocksdb_backup_engine_t* result = new rocksdb_backup_engine_t;
+  result->rep = be;
+  return result;
+}
+
+rocksdb_backup_engine_t* rocksdb_backup_engine_open_with_envs_with_status(
+    const char* backup_dir, rocksdb_env_t* db_env, rocksdb_env_t* backup_env,
+    rocksdb_status_t* statusptr) {
+  BackupEngine* be;
+  if (SaveStatus(statusptr,
+                 BackupEngine::Open(BackupEngineOptions(std::string(backup_dir),
+                                                        backup_env->rep),
+                                    db_env->rep, &be))) {
+    return nullptr;
+  }
+  rocksdb_backup_engine_t* result = new rocksdb_backup_engine_t;
+  result->rep = be;
+  return result;
+}
+
+uint32_t rocksdb_create_new_backup_with_status(rocksdb_t* db,
+                                               rocksdb_backup_engine_t* be,
+                                               rocksdb_status_t* statusptr) {
+  BackupID backup_id = 0;
+  SaveStatus(statusptr, be->rep->CreateNewBackup(CreateBackupOptions(),
+                                                 db->rep, &backup_id));
+  return backup_id;
+}
+
+uint32_t rocksdb_transactiondb_create_new_backup_with_status(
+    rocksdb_transactiondb_t* db, rocksdb_backup_engine_t* be,
+    rocksdb_status_t* statusptr) {
+  CreateBackupOptions options;
+  options.flush_before_backup = true;
+  BackupID backup_id = 0;
+  SaveStatus(statusptr, be->rep->CreateNewBackup(options, db->rep, &backup_id));
+  return backup_id;
+}
+
+void rocksdb_backup_engine_verify_backup_with_status(
+    rocksdb_backup_engine_t* be, uint32_t backup_id,
+    rocksdb_status_t* statusptr) {
+  SaveStatus(statusptr,
+             be->rep->VerifyBackup(static_cast<BackupID>(backup_id), true));
+}
+
+void rocksdb_backup_engine_restore_db_from_backup_with_status(
+    rocksdb_backup_engine_t* be, uint32_t backup_id, const char* db_dir,
+    rocksdb_status_t* statusptr) {
+  SaveStatus(statusptr, be->rep->RestoreDBFromBackup(
+                            RestoreOptions(), static_cast<BackupID>(backup_id),
+                            std::string(db_dir), std::string(db_dir)));
+}
+
+void rocksdb_backup_engine_purge_old_backups_with_status(
+    rocksdb_backup_engine_t* be, uint32_t num_backups_to_keep,
+    rocksdb_status_t* statusptr) {
+  SaveStatus(statusptr, be->rep->PurgeOldBackups(num_backups_to_keep));
+}
+
 void rocksdb_transaction_commit_with_status(rocksdb_transaction_t* txn,
                                             rocksdb_status_t* statusptr) {
   SaveStatus(statusptr, txn->rep->Commit());
@@ -300,6 +486,11 @@ void rocksdb_transaction_rollback_with_status(rocksdb_transaction_t* txn,
   SaveStatus(statusptr, txn->rep->Rollback());
 }
 
+void rocksdb_transaction_rollback_to_savepoint_with_status(
+    rocksdb_transaction_t* txn, rocksdb_status_t* statusptr) {
+  SaveStatus(statusptr, txn->rep->RollbackToSavePoint());
+}
+
 rocksdb_pinnableslice_t* rocksdb_transaction_get_pinned_cf_with_status(
     rocksdb_transaction_t* txn, const rocksdb_readoptions_t* options,
     rocksdb_column_family_handle_t* column_family, const char* key,
@@ -368,6 +559,37 @@ void rocksdb_sstfilewriter_finish_with_status(rocksdb_sstfilewriter_t* writer,
   SaveStatus(statusptr, writer->rep->Finish(nullptr));
 }
 
+int rocksdb_transactiondb_property_int_cf(
+    rocksdb_transactiondb_t* db, rocksdb_column_family_handle_t* column_family,
+    const char* propname, uint64_t* out_val) {
+  if (db->rep->GetIntProperty(column_family->rep, Slice(propname), out_val)) {
+    return 0;
+  } else {
+    return -1;
+  }
+}
+
+uint64_t rocksdb_approximate_size_cf_with_status(
+    rocksdb_t* db, rocksdb_column_family_handle_t* column_family,
+    const char* start_key, size_t start_key_len, const char* limit_key,
+    size_t limit_key_len, rocksdb_status_t* statusptr) {
+  return ApproximateSize(db->rep, column_family->rep, start_key, start_key_len,
+                         limit_key, limit_key_len, statusptr);
+}
+
+uint64_t rocksdb_transactiondb_approximate_size_cf_with_status(
+    rocksdb_transactiondb_t* db, rocksdb_column_family_handle_t* column_family,
+    const char* start_key, size_t start_key_len, const char* limit_key,
+    size_t limit_key_len, rocksdb_status_t* statusptr) {
+  return ApproximateSize(db->rep, column_family->rep, start_key, start_key_len,
+                         limit_key, limit_key_len, statusptr);
+}
+
+void rocksdb_options_set_db_host_id(rocksdb_options_t* opt,
+                                    const char* host_id) {
+  opt->rep.db_host_id = std::string(host_id);
+}
+
 void rocksdb_iter_get_status(const rocksdb_iterator_t* iter,
                              rocksdb_status_t* statusptr) {
   SaveStatus(statusptr, iter->rep->status());
@@ -377,4 +599,16 @@ rocksdb_readoptions_t* rocksdb_readoptions_create_copy(
     rocksdb_readoptions_t* options) {
   return new rocksdb_readoptions_t(*options);
 }
+
+rocksdb_env_t* rocksdb_create_encrypted_env(
+    void* state, void (*destructor)(void*), size_t block_size,
+    void (*encrypt_block)(void*, char*), void (*decrypt_block)(void*, char*)) {
+  std::shared_ptr<BlockCipher> cipher = std::make_shared<CallbackBlockCipher>(
+      state, destructor, block_size, encrypt_block, decrypt_block);
+  rocksdb_env_t* result = new rocksdb_env_t;
+  result->rep = NewEncryptedEnv(Env::Default(),
+                                EncryptionProvider::NewCTRProvider(cipher));
+  result->is_default = false;
+  return result;
+}
 }
@@ -79,6 +79,18 @@ rocksdb_open_for_read_only_column_families_with_status(
     rocksdb_column_family_handle_t** column_family_handles,
     unsigned char error_if_wal_file_exists, rocksdb_status_t* statusptr);
 
+extern ROCKSDB_LIBRARY_API rocksdb_t*
+rocksdb_open_as_secondary_column_families_with_status(
+    const rocksdb_options_t* db_options, const char* name,
+    const char* secondary_path, int num_column_families,
+    const char* const* column_family_names,
+    const rocksdb_options_t* const* column_family_options,
+    rocksdb_column_family_handle_t** column_family_handles,
+    rocksdb_status_t* statusptr);
+
+extern ROCKSDB_LIBRARY_API void rocksdb_try_catch_up_with_primary_with_status(
+    rocksdb_t* db, rocksdb_status_t* statusptr);
+
 extern ROCKSDB_LIBRARY_API void rocksdb_create_checkpoint_with_status(
     rocksdb_t* db, const char* checkpoint_dir, rocksdb_status_t* statusptr);
 
@@ -107,6 +119,10 @@ extern ROCKSDB_LIBRARY_API void rocksdb_transactiondb_flush_cfs_with_status(
     rocksdb_column_family_handle_t** column_families, int num_column_families,
     rocksdb_status_t* statusptr);
 
+extern ROCKSDB_LIBRARY_API void rocksdb_transactiondb_flush_wal_with_status(
+    rocksdb_transactiondb_t* db, unsigned char sync,
+    rocksdb_status_t* statusptr);
+
 extern ROCKSDB_LIBRARY_API void
 rocksdb_transactiondb_compact_range_cf_opt_with_status(
     rocksdb_transactiondb_t* db, rocksdb_column_family_handle_t* column_family,
@@ -123,12 +139,48 @@ rocksdb_transactiondb_create_checkpoint_with_status(
     rocksdb_transactiondb_t* db, const char* checkpoint_dir,
     rocksdb_status_t* statusptr);
 
+extern ROCKSDB_LIBRARY_API rocksdb_backup_engine_t*
+rocksdb_backup_engine_open_with_status(const char* backup_dir,
+                                       rocksdb_status_t* statusptr);
+
+extern ROCKSDB_LIBRARY_API rocksdb_backup_engine_t*
+rocksdb_backup_engine_open_with_envs_with_status(const char* backup_dir,
+                                                 rocksdb_env_t* db_env,
+                                                 rocksdb_env_t* backup_env,
+                                                 rocksdb_status_t* statusptr);
+
+extern ROCKSDB_LIBRARY_API uint32_t rocksdb_create_new_backup_with_status(
+    rocksdb_t* db, rocksdb_backup_engine_t* be, rocksdb_status_t* statusptr);
+
+extern ROCKSDB_LIBRARY_API uint32_t
+rocksdb_transactiondb_create_new_backup_with_status(
+    rocksdb_transactiondb_t* db, rocksdb_backup_engine_t* be,
+    rocksdb_status_t* statusptr);
+
+extern ROCKSDB_LIBRARY_API void rocksdb_backup_engine_verify_backup_with_status(
+    rocksdb_backup_engine_t* be, uint32_t backup_id,
+    rocksdb_status_t* statusptr);
+
+extern ROCKSDB_LIBRARY_API void
+rocksdb_backup_engine_restore_db_from_backup_with_status(
+    rocksdb_backup_engine_t* be, uint32_t backup_id, const char* db_dir,
+    rocksdb_status_t* statusptr);
+
+extern ROCKSDB_LIBRARY_API void
+rocksdb_backup_engine_purge_old_backups_with_status(
+    rocksdb_backup_engine_t* be, uint32_t num_backups_to_keep,
+    rocksdb_status_t* statusptr);
+
 extern ROCKSDB_LIBRARY_API void rocksdb_transaction_commit_with_status(
     rocksdb_transaction_t* txn, rocksdb_status_t* statusptr);
 
 extern ROCKSDB_LIBRARY_API void rocksdb_transaction_rollback_with_status(
     rocksdb_transaction_t* txn, rocksdb_status_t* statusptr);
 
+extern ROCKSDB_LIBRARY_API void
+rocksdb_transaction_rollback_to_savepoint_with_status(
+    rocksdb_transaction_t* txn, rocksdb_status_t* statusptr);
+
 extern ROCKSDB_LIBRARY_API rocksdb_pinnableslice_t*
 rocksdb_transaction_get_pinned_cf_with_status(
     rocksdb_transaction_t* txn, const rocksdb_readoptions_t* options,
@@ -161,12 +213,37 @@ extern ROCKSDB_LIBRARY_API void rocksdb_sstfilewriter_put_with_status(
 extern ROCKSDB_LIBRARY_API void rocksdb_sstfilewriter_finish_with_status(
     rocksdb_sstfilewriter_t* writer, rocksdb_status_t* statusptr);
 
+/* returns 0 on success, -1 otherwise */
+extern ROCKSDB_LIBRARY_API int rocksdb_transactiondb_property_int_cf(
+    rocksdb_transactiondb_t* db, rocksdb_column_family_handle_t* column_family,
+    const char* propname, uint64_t* out_val);
+
+extern ROCKSDB_LIBRARY_API uint64_t rocksdb_approximate_size_cf_with_status(
+    rocksdb_t* db, rocksdb_column_family_handle_t* column_family,
+    const char* start_key, size_t start_key_len, const char* limit_key,
+    size_t limit_key_len, rocksdb_status_t* statusptr);
+
+extern ROCKSDB_LIBRARY_API uint64_t
+rocksdb_transactiondb_approximate_size_cf_with_status(
+    rocksdb_transactiondb_t* db, rocksdb_column_family_handle_t* column_family,
+    const char* start_key, size_t start_key_len, const char* limit_key,
+    size_t limit_key_len, rocksdb_status_t* statusptr);
+
+extern ROCKSDB_LIBRARY_API void rocksdb_options_set_db_host_id(
+    rocksdb_options_t* opt, const char* host_id);
+
 extern ROCKSDB_LIBRARY_API void rocksdb_iter_get_status(
     const rocksdb_iterator_t*, rocksdb_status_t* statusptr);
 
 extern ROCKSDB_LIBRARY_API rocksdb_readoptions_t*
 rocksdb_readoptions_create_copy(rocksdb_readoptions_t*);
 
+// Env encrypting the files in counter mode with the given block cipher.
+// The destructor is called on the state when the env is destroyed.
+extern ROCKSDB_LIBRARY_API rocksdb_env_t* rocksdb_create_encrypted_env(
+    void* state, void (*destructor)(void*), size_t block_size,
+    void (*encrypt_block)(void*, char*), void (*decrypt_block)(void*, char*));
+
 #ifdef __cplusplus
 }
 #endif
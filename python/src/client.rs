@@ -0,0 +1,249 @@
+use crate::io::{
+    PyRdfFormatInput, PyReadable, PyReadableInput, PyWritable, PyWritableOutput, lookup_rdf_format,
+};
+use crate::model::*;
+use crate::sparql::*;
+use oxigraph::io::{RdfParser, RdfSerializer};
+use oxigraph::model::GraphNameRef;
+use oxigraph::remote::RemoteStore;
+use oxigraph::sparql::{Query, QueryResults, Update};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Client for a remote SPARQL endpoint with the same API as :py:class:`Store`.
+///
+/// Queries are sent to the query endpoint using the `SPARQL 1.1 Protocol <https://www.w3.org/TR/sparql11-protocol/>`_.
+/// Their results are parsed while being iterated.
+/// Graphs are loaded and dumped using the `SPARQL 1.1 Graph Store HTTP Protocol <https://www.w3.org/TR/sparql11-http-rdf-update/>`_.
+///
+/// :param query_endpoint: the URL of the SPARQL query endpoint.
+/// :type query_endpoint: str
+/// :param update_endpoint: the URL of the SPARQL update endpoint. By default, the query endpoint is used.
+/// :type update_endpoint: str or None, optional
+/// :param graph_store_endpoint: the URL of the graph store endpoint. It is required by the :py:meth:`load`, :py:meth:`dump` and :py:meth:`remove_graph` methods.
+/// :type graph_store_endpoint: str or None, optional
+/// :param timeout: the timeout of the HTTP requests in seconds. By default, there is no timeout.
+/// :type timeout: float or None, optional
+/// :raises ValueError: if the timeout is invalid.
+///
+/// Usage with an Oxigraph server::
+///
+///     store = RemoteStore(
+///         "http://localhost:7878/query",
+///         update_endpoint="http://localhost:7878/update",
+///         graph_store_endpoint="http://localhost:7878/store",
+///     )
+///     store.load(b'<http://example.com> <http://example.com/p> "1" .', RdfFormat.N_TRIPLES)
+///     for solution in store.query("SELECT ?s WHERE { ?s ?p ?o }"):
+///         print(solution["s"])
+#[pyclass(frozen, name = "RemoteStore", module = "pyoxigraph.client")]
+pub struct PyRemoteStore {
+    inner: RemoteStore,
+}
+
+#[pymethods]
+impl PyRemoteStore {
+    #[new]
+    #[pyo3(signature = (query_endpoint, *, update_endpoint = None, graph_store_endpoint = None, timeout = None))]
+    fn new(
+        query_endpoint: String,
+        update_endpoint: Option<String>,
+        graph_store_endpoint: Option<String>,
+        timeout: Option<f64>,
+    ) -> PyResult<Self> {
+        let mut inner = RemoteStore::new(query_endpoint);
+        if let Some(update_endpoint) = update_endpoint {
+            inner = inner.with_update_endpoint(update_endpoint);
+        }
+        if let Some(graph_store_endpoint) = graph_store_endpoint {
+            inner = inner.with_graph_store_endpoint(graph_store_endpoint);
+        }
+        if let Some(timeout) = timeout {
+            inner =
+                inner.with_http_timeout(Duration::try_from_secs_f64(timeout).map_err(|e| {
+                    PyValueError::new_err(format!("Invalid timeout {timeout}: {e}"))
+                })?);
+        }
+        Ok(Self { inner })
+    }
+
+    /// Executes a `SPARQL 1.1 query <https://www.w3.org/TR/sparql11-query/>`_ on the remote endpoint.
+    ///
+    /// :param query: the query to execute.
+    /// :type query: str
+    /// :param base_iri: the base IRI used to resolve the relative IRIs in the SPARQL query or :py:const:`None` if relative IRI resolution should not be done.
+    /// :type base_iri: str or None, optional
+    /// :return: a :py:class:`bool` for ``ASK`` queries, an iterator of :py:class:`Triple` for ``CONSTRUCT`` and ``DESCRIBE`` queries and an iterator of :py:class:`QuerySolution` for ``SELECT`` queries.
+    /// :rtype: QuerySolutions or QueryBoolean or QueryTriples
+    /// :raises SyntaxError: if the provided query or the returned results are invalid.
+    /// :raises OSError: if an error happens during the HTTP request.
+    #[pyo3(signature = (query, *, base_iri = None))]
+    fn query<'py>(
+        &self,
+        query: &str,
+        base_iri: Option<&str>,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        pub struct UngilQueryResults(QueryResults);
+
+        #[expect(unsafe_code)]
+        // SAFETY: To derive Ungil
+        unsafe impl Send for UngilQueryResults {}
+
+        let results = py
+            .allow_threads(|| {
+                let query = Query::parse(query, base_iri)?;
+                Ok(UngilQueryResults(self.inner.query(query)?))
+            })
+            .map_err(map_evaluation_error)?
+            .0;
+        query_results_to_python(py, results)
+    }
+
+    /// Executes a `SPARQL 1.1 update <https://www.w3.org/TR/sparql11-update/>`_ on the remote endpoint.
+    ///
+    /// :param update: the update to execute.
+    /// :type update: str
+    /// :param base_iri: the base IRI used to resolve the relative IRIs in the SPARQL update or :py:const:`None` if relative IRI resolution should not be done.
+    /// :type base_iri: str or None, optional
+    /// :rtype: None
+    /// :raises SyntaxError: if the provided update is invalid.
+    /// :raises OSError: if an error happens during the HTTP request.
+    #[pyo3(signature = (update, *, base_iri = None))]
+    fn update(&self, update: &str, base_iri: Option<&str>, py: Python<'_>) -> PyResult<()> {
+        py.allow_threads(|| {
+            let update =
+                Update::parse(update, base_iri).map_err(|e| map_evaluation_error(e.into()))?;
+            self.inner.update(update).map_err(map_evaluation_error)
+        })
+    }
+
+    /// Adds the triples of an RDF serialization to a graph of the remote store.
+    ///
+    /// The serialization is parsed locally, so all the formats supported by :py:func:`parse` are available.
+    /// Datasets formats like N-Quads are only allowed if they do not contain named graphs.
+    ///
+    /// :param input: The :py:class:`str`, :py:class:`bytes` or I/O object to read from. For example, it could be the file content as a string or a file reader opened in binary mode with ``open('my_file.ttl', 'rb')``.
+    /// :type input: bytes or str or typing.IO[bytes] or typing.IO[str] or None, optional
+    /// :param format: the format of the RDF serialization. If :py:const:`None`, the format is guessed from the file name extension.
+    /// :type format: RdfFormat or None, optional
+    /// :param path: The file path to read from. Replaces the ``input`` parameter.
+    /// :type path: str or os.PathLike[str] or None, optional
+    /// :param base_iri: the base IRI used to resolve the relative IRIs in the file or :py:const:`None` if relative IRI resolution should not be done.
+    /// :type base_iri: str or None, optional
+    /// :param to_graph: the graph to load the triples into. By default, the default graph is used.
+    /// :type to_graph: NamedNode or DefaultGraph or None, optional
+    /// :rtype: None
+    /// :raises ValueError: if the format is not supported.
+    /// :raises SyntaxError: if the provided data is invalid.
+    /// :raises OSError: if an error happens during the HTTP request or while reading the file.
+    #[expect(clippy::needless_pass_by_value)]
+    #[pyo3(signature = (input = None, format = None, *, path = None, base_iri = None, to_graph = None))]
+    fn load(
+        &self,
+        input: Option<PyReadableInput>,
+        format: Option<PyRdfFormatInput>,
+        path: Option<PathBuf>,
+        base_iri: Option<&str>,
+        to_graph: Option<PyGraphNameRef<'_>>,
+        py: Python<'_>,
+    ) -> PyResult<()> {
+        let to_graph_name = to_graph
+            .as_ref()
+            .map_or(GraphNameRef::DefaultGraph, GraphNameRef::from);
+        let input = PyReadable::from_args(&path, input, py)?;
+        let format = lookup_rdf_format(format, path.as_deref())?;
+        py.allow_threads(|| {
+            let mut parser = RdfParser::from_format(format);
+            if let Some(base_iri) = base_iri {
+                parser = parser
+                    .with_base_iri(base_iri)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+            }
+            self.inner
+                .load_graph_from_reader(parser, input, to_graph_name)
+                .map_err(map_evaluation_error)
+        })
+    }
+
+    /// Dumps a graph of the remote store into a file.
+    ///
+    /// :param output: The I/O object or file path to write to. For example, it could be a file path as a string, a file writer opened in binary mode with ``open('my_file.ttl', 'wb')`` or a text I/O object like :py:class:`io.StringIO`. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
+    /// :type output: typing.IO[bytes] or typing.IO[str] or str or os.PathLike[str] or None, optional
+    /// :param format: the format of the RDF serialization. If :py:const:`None`, the format is guessed from the file name extension.
+    /// :type format: RdfFormat or None, optional
+    /// :param from_graph: the graph to dump. By default, the default graph is used.
+    /// :type from_graph: NamedNode or DefaultGraph or None, optional
+    /// :param prefixes: the prefixes used in the serialization if the format supports it.
+    /// :type prefixes: dict[str, str] or None, optional
+    /// :param base_iri: the base IRI used in the serialization if the format supports it.
+    /// :type base_iri: str or None, optional
+    /// :return: :py:class:`bytes` with the serialization if the ``output`` parameter is :py:const:`None`, :py:const:`None` if ``output`` is set.
+    /// :rtype: bytes or None
+    /// :raises ValueError: if the format is not supported.
+    /// :raises OSError: if an error happens during the HTTP request or while writing the file.
+    #[expect(clippy::needless_pass_by_value)]
+    #[pyo3(signature = (output = None, format = None, *, from_graph = None, prefixes = None, base_iri = None))]
+    fn dump(
+        &self,
+        output: Option<PyWritableOutput>,
+        format: Option<PyRdfFormatInput>,
+        from_graph: Option<PyGraphNameRef<'_>>,
+        prefixes: Option<BTreeMap<String, String>>,
+        base_iri: Option<&str>,
+        py: Python<'_>,
+    ) -> PyResult<Option<Vec<u8>>> {
+        let from_graph_name = from_graph
+            .as_ref()
+            .map_or(GraphNameRef::DefaultGraph, GraphNameRef::from);
+        PyWritable::do_write(
+            |output, file_path| {
+                py.allow_threads(|| {
+                    let format = lookup_rdf_format(format, file_path.as_deref())?;
+                    let mut serializer = RdfSerializer::from_format(format);
+                    for (prefix_name, prefix_iri) in prefixes.iter().flatten() {
+                        serializer =
+                            serializer
+                                .with_prefix(prefix_name, prefix_iri)
+                                .map_err(|e| {
+                                    PyValueError::new_err(format!(
+                                        "Invalid prefix {prefix_name} IRI '{prefix_iri}', {e}"
+                                    ))
+                                })?;
+                    }
+                    if let Some(base_iri) = base_iri {
+                        serializer = serializer.with_base_iri(base_iri).map_err(|e| {
+                            PyValueError::new_err(format!("Invalid base IRI '{base_iri}', {e}"))
+                        })?;
+                    }
+                    self.inner
+                        .dump_graph_to_writer(from_graph_name, serializer, output)
+                        .map_err(map_evaluation_error)
+                })
+            },
+            output,
+            py,
+        )
+    }
+
+    /// Removes a graph from the remote store.
+    ///
+    /// The default graph is cleared instead.
+    ///
+    /// :param graph_name: the name of the graph to remove.
+    /// :type graph_name: NamedNode or DefaultGraph
+    /// :rtype: None
+    /// :raises OSError: if an error happens during the HTTP request.
+    #[expect(clippy::needless_pass_by_value)]
+    fn remove_graph(&self, graph_name: PyGraphNameRef<'_>, py: Python<'_>) -> PyResult<()> {
+        let graph_name = GraphNameRef::from(&graph_name);
+        py.allow_threads(|| {
+            self.inner
+                .remove_graph(graph_name)
+                .map_err(map_evaluation_error)
+        })
+    }
+}
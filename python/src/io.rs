@@ -13,9 +13,15 @@ use std::collections::BTreeMap;
 use std::ffi::{CString, OsStr};
 use std::fs::File;
 use std::io::{self, BufWriter, Cursor, Read, Write};
+use std::mem::take;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
+/// Size of the buffer used when writing to a Python I/O object.
+///
+/// It is large to limit the number of times the GIL must be acquired.
+const WRITE_BUFFER_SIZE: usize = 64 * 1024;
+
 /// Parses RDF graph and dataset serialization formats.
 ///
 /// It currently supports the following formats:
@@ -99,10 +105,12 @@ pub fn parse(
 /// For example, ``application/turtle`` could also be used for `Turtle <https://www.w3.org/TR/turtle/>`_
 /// and ``application/xml`` or ``xml`` for `RDF/XML <https://www.w3.org/TR/rdf-syntax-grammar/>`_.
 ///
+/// The serialization is streamed: the input is consumed lazily and the output is written by chunks.
+///
 /// :param input: the RDF triples and quads to serialize.
 /// :type input: collections.abc.Iterable[Triple] or collections.abc.Iterable[Quad]
-/// :param output: The binary I/O object or file path to write to. For example, it could be a file path as a string or a file writer opened in binary mode with ``open('my_file.ttl', 'wb')``. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
-/// :type output: typing.IO[bytes] or str or os.PathLike[str] or None, optional
+/// :param output: The I/O object or file path to write to. For example, it could be a file path as a string, a file writer opened in binary mode with ``open('my_file.ttl', 'wb')`` or a text I/O object like :py:class:`io.StringIO`. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
+/// :type output: typing.IO[bytes] or typing.IO[str] or str or os.PathLike[str] or None, optional
 /// :param format: the format of the RDF serialization. If :py:const:`None`, the format is guessed from the file name extension.
 /// :type format: RdfFormat or None, optional
 /// :param prefixes: the prefixes used in the serialization if the format supports it.
@@ -123,6 +131,11 @@ pub fn parse(
 /// >>> serialize([Triple(NamedNode('http://example.com'), NamedNode('http://example.com/p'), Literal('1'))], output, RdfFormat.TURTLE, prefixes={"ex": "http://example.com/"}, base_iri="http://example.com")
 /// >>> output.getvalue()
 /// b'@base <http://example.com> .\n@prefix ex: </> .\n<> ex:p "1" .\n'
+///
+/// >>> output = io.StringIO()
+/// >>> serialize([Triple(NamedNode('http://example.com'), NamedNode('http://example.com/p'), Literal('1'))], output, RdfFormat.N_TRIPLES)
+/// >>> output.getvalue()
+/// '<http://example.com> <http://example.com/p> "1" .\n'
 #[pyfunction]
 #[pyo3(signature = (input, output = None, format = None, *, prefixes = None, base_iri = None))]
 pub fn serialize<'py>(
@@ -248,6 +261,7 @@ impl PyQuadParser {
 ///
 /// The following formats are supported:
 ///
+/// * `HDT <https://www.rdfhdt.org/hdt-binary-format/>`_ (:py:attr:`RdfFormat.HDT`)
 /// * `JSON-LD 1.0 <https://www.w3.org/TR/json-ld/>`_ (:py:attr:`RdfFormat.JSON_LD`)
 /// * `N-Triples <https://www.w3.org/TR/n-triples/>`_ (:py:attr:`RdfFormat.N_TRIPLES`)
 /// * `N-Quads <https://www.w3.org/TR/n-quads/>`_ (:py:attr:`RdfFormat.N_QUADS`)
@@ -255,6 +269,8 @@ impl PyQuadParser {
 /// * `TriG <https://www.w3.org/TR/trig/>`_ (:py:attr:`RdfFormat.TRIG`)
 /// * `N3 <https://w3c.github.io/N3/spec/>`_ (:py:attr:`RdfFormat.N3`)
 /// * `RDF/XML <https://www.w3.org/TR/rdf-syntax-grammar/>`_ (:py:attr:`RdfFormat.RDF_XML`)
+/// * `RDF/JSON <https://www.w3.org/TR/rdf-json/>`_ (:py:attr:`RdfFormat.RDF_JSON`)
+/// * `TriX <https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html>`_ (:py:attr:`RdfFormat.TRIX`)
 ///
 /// >>> RdfFormat.N3.media_type
 /// 'text/n3'
@@ -266,6 +282,11 @@ pub struct PyRdfFormat {
 
 #[pymethods]
 impl PyRdfFormat {
+    /// `HDT <https://www.rdfhdt.org/hdt-binary-format/>`_
+    #[classattr]
+    const HDT: Self = Self {
+        inner: RdfFormat::Hdt,
+    };
     /// `JSON-LD <https://www.w3.org/TR/json-ld/>`_
     #[classattr]
     const JSON_LD: Self = Self {
@@ -295,6 +316,11 @@ impl PyRdfFormat {
     const N_TRIPLES: Self = Self {
         inner: RdfFormat::NTriples,
     };
+    /// `RDF/JSON <https://www.w3.org/TR/rdf-json/>`_
+    #[classattr]
+    const RDF_JSON: Self = Self {
+        inner: RdfFormat::RdfJson,
+    };
     /// `RDF/XML <https://www.w3.org/TR/rdf-syntax-grammar/>`_
     #[classattr]
     const RDF_XML: Self = Self {
@@ -305,6 +331,11 @@ impl PyRdfFormat {
     const TRIG: Self = Self {
         inner: RdfFormat::TriG,
     };
+    /// `TriX <https://www.hpl.hp.com/techreports/2004/HPL-2004-56.html>`_
+    #[classattr]
+    const TRIX: Self = Self {
+        inner: RdfFormat::TriX,
+    };
     /// `Turtle <https://www.w3.org/TR/turtle/>`_
     #[classattr]
     const TURTLE: Self = Self {
@@ -495,6 +526,7 @@ impl From<PyReadableInput> for PyReadable {
 pub enum PyWritable {
     Bytes(Vec<u8>),
     Io(PyIo),
+    TextIo(PyTextIo),
     File(File),
 }
 
@@ -509,10 +541,22 @@ impl PyWritable {
                 Self::File(py.allow_threads(|| File::create(&file_path))?),
                 Some(file_path),
             ),
-            Some(PyWritableOutput::Io(object)) => (Self::Io(PyIo(object)), None),
+            Some(PyWritableOutput::Io(object)) => {
+                let text_io_class = py
+                    .import(intern!(py, "io"))?
+                    .getattr(intern!(py, "TextIOBase"))?;
+                if object.bind(py).is_instance(&text_io_class)? {
+                    (Self::TextIo(PyTextIo::new(object)), None)
+                } else {
+                    (Self::Io(PyIo(object)), None)
+                }
+            }
             None => (Self::Bytes(Vec::new()), None),
         };
-        let serializer = write(BufWriter::new(output), file_path)?;
+        let serializer = write(
+            BufWriter::with_capacity(WRITE_BUFFER_SIZE, output),
+            file_path,
+        )?;
         py.allow_threads(|| serializer.into_inner())?.close(py)
     }
 
@@ -530,6 +574,10 @@ impl PyWritable {
                 py.allow_threads(|| io.flush())?;
                 Ok(None)
             }
+            Self::TextIo(mut io) => {
+                py.allow_threads(|| io.close())?;
+                Ok(None)
+            }
         }
     }
 }
@@ -539,6 +587,7 @@ impl Write for PyWritable {
         match self {
             Self::Bytes(bytes) => bytes.write(buf),
             Self::Io(io) => io.write(buf),
+            Self::TextIo(io) => io.write(buf),
             Self::File(file) => file.write(buf),
         }
     }
@@ -547,6 +596,7 @@ impl Write for PyWritable {
         match self {
             Self::Bytes(_) => Ok(()),
             Self::Io(io) => io.flush(),
+            Self::TextIo(io) => io.flush(),
             Self::File(file) => file.flush(),
         }
     }
@@ -586,11 +636,13 @@ impl Read for PyIo {
 impl Write for PyIo {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         Python::with_gil(|py| {
-            Ok(self
-                .0
-                .bind(py)
-                .call_method1(intern!(py, "write"), (buf,))?
-                .extract::<usize>()?)
+            let written = self.0.bind(py).call_method1(intern!(py, "write"), (buf,))?;
+            // Some writers do not return the number of written bytes
+            Ok(if written.is_none() {
+                buf.len()
+            } else {
+                written.extract::<usize>()?
+            })
         })
     }
 
@@ -602,6 +654,63 @@ impl Write for PyIo {
     }
 }
 
+/// Writes UTF-8 bytes into a Python text I/O object.
+pub struct PyTextIo {
+    io: PyObject,
+    /// The beginning of a character that has been cut by the end of the previous write
+    incomplete: Vec<u8>,
+}
+
+impl PyTextIo {
+    fn new(io: PyObject) -> Self {
+        Self {
+            io,
+            incomplete: Vec::new(),
+        }
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        if !self.incomplete.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The serialization ends with an incomplete UTF-8 character",
+            ));
+        }
+        self.flush()
+    }
+}
+
+impl Write for PyTextIo {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut data = take(&mut self.incomplete);
+        data.extend_from_slice(buf);
+        let valid_up_to = match std::str::from_utf8(&data) {
+            Ok(_) => data.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+        self.incomplete = data.split_off(valid_up_to);
+        let text =
+            String::from_utf8(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if !text.is_empty() {
+            Python::with_gil(|py| {
+                self.io
+                    .bind(py)
+                    .call_method1(intern!(py, "write"), (text,))?;
+                io::Result::Ok(())
+            })?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Python::with_gil(|py| {
+            self.io.bind(py).call_method0(intern!(py, "flush"))?;
+            Ok(())
+        })
+    }
+}
+
 pub fn lookup_rdf_format(
     format: Option<PyRdfFormatInput>,
     path: Option<&Path>,
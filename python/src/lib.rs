@@ -1,15 +1,20 @@
 #![allow(
+    clippy::same_name_method, // Generated by create_exception!
     clippy::trivially_copy_pass_by_ref,
     clippy::unused_self,
     clippy::useless_conversion
 )]
 
+#[cfg(not(target_family = "wasm"))]
+mod client;
 mod dataset;
 mod io;
 mod model;
 mod sparql;
 mod store;
 
+#[cfg(not(target_family = "wasm"))]
+use crate::client::*;
 use crate::dataset::*;
 use crate::io::*;
 use crate::model::*;
@@ -20,6 +25,8 @@ use pyo3::prelude::*;
 /// Oxigraph Python bindings
 #[pymodule]
 pub mod pyoxigraph {
+    use pyo3::prelude::*;
+
     #[expect(non_upper_case_globals)]
     #[pymodule_export]
     const __version__: &str = env!("CARGO_PKG_VERSION");
@@ -30,7 +37,26 @@ pub mod pyoxigraph {
     use super::{
         PyBlankNode, PyCanonicalizationAlgorithm, PyDataset, PyDefaultGraph, PyLiteral,
         PyNamedNode, PyQuad, PyQuadParser, PyQueryBoolean, PyQueryResultsFormat, PyQuerySolution,
-        PyQuerySolutions, PyQueryTriples, PyRdfFormat, PyStore, PyTriple, PyVariable, parse,
-        parse_query_results, serialize,
+        PyQuerySolutions, PyQueryTriples, PyRdfFormat, PyStore, PyTriple, PyVariable,
+        QueryTimeoutError, parse, parse_query_results, serialize,
     };
+
+    /// Clients for remote SPARQL endpoints
+    #[cfg(not(target_family = "wasm"))]
+    #[pymodule]
+    pub mod client {
+        use pyo3::prelude::*;
+
+        #[pymodule_export]
+        use super::super::PyRemoteStore;
+
+        #[pymodule_init]
+        fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+            // Allows `import pyoxigraph.client`
+            m.py()
+                .import("sys")?
+                .getattr("modules")?
+                .set_item("pyoxigraph.client", m)
+        }
+    }
 }
@@ -8,11 +8,12 @@ use oxigraph::sparql::results::{
     ReaderQueryResultsParserOutput, ReaderSolutionsParser,
 };
 use oxigraph::sparql::{
-    EvaluationError, Query, QueryOptions, QueryResults, QuerySolution, QuerySolutionIter,
-    QueryTripleIter, Variable,
+    EvaluationError, HttpError, Query, QueryOptions, QueryResults, QuerySolution,
+    QuerySolutionIter, QueryTripleIter, Variable,
 };
 use pyo3::IntoPyObjectExt;
-use pyo3::exceptions::{PyRuntimeError, PySyntaxError, PyValueError};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRuntimeError, PySyntaxError, PyTimeoutError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
 use pyo3::types::PyTuple;
@@ -22,8 +23,16 @@ use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::vec::IntoIter;
 
+create_exception!(
+    pyoxigraph,
+    QueryTimeoutError,
+    PyTimeoutError,
+    "Raised when the SPARQL query evaluation timeout set with the ``timeout`` argument of :py:meth:`Store.query` is reached.\n\nIts ``partial_results`` attribute is :py:const:`True` if some results have already been returned or serialized before the timeout."
+);
+
 pub fn parse_query(
     query: &str,
     base_iri: Option<&str>,
@@ -79,8 +88,15 @@ pub fn parse_query(
 
 pub fn query_options_from_python(
     custom_functions: Option<HashMap<PyNamedNode, PyObject>>,
-) -> QueryOptions {
+    timeout: Option<f64>,
+) -> PyResult<QueryOptions> {
     let mut options = QueryOptions::default();
+    if let Some(timeout) = timeout {
+        options =
+            options.with_timeout(Duration::try_from_secs_f64(timeout).map_err(|e| {
+                PyValueError::new_err(format!("Invalid query timeout {timeout}: {e}"))
+            })?);
+    }
     #[cfg(feature = "geosparql")]
     {
         options = register_geosparql_functions(options);
@@ -106,7 +122,7 @@ pub fn query_options_from_python(
             })
         }
     }
-    options
+    Ok(options)
 }
 
 pub fn query_results_to_python(
@@ -116,10 +132,12 @@ pub fn query_results_to_python(
     match results {
         QueryResults::Solutions(inner) => PyQuerySolutions {
             inner: PyQuerySolutionsVariant::Query(UngilQuerySolutionIter(inner)),
+            has_returned_results: false,
         }
         .into_bound_py_any(py),
         QueryResults::Graph(inner) => PyQueryTriples {
             inner: UngilQueryTripleIter(inner),
+            has_returned_results: false,
         }
         .into_bound_py_any(py),
         QueryResults::Boolean(inner) => PyQueryBoolean { inner }.into_bound_py_any(py),
@@ -234,6 +252,7 @@ impl SolutionValueIter {
 #[pyclass(unsendable, name = "QuerySolutions", module = "pyoxigraph")]
 pub struct PyQuerySolutions {
     inner: PyQuerySolutionsVariant,
+    has_returned_results: bool,
 }
 
 #[allow(clippy::large_enum_variant, clippy::allow_attributes)]
@@ -286,8 +305,8 @@ impl PyQuerySolutions {
     /// It supports also some media type and extension aliases.
     /// For example, ``application/json`` could also be used for `JSON <https://www.w3.org/TR/sparql11-results-json/>`_.
     ///
-    /// :param output: The binary I/O object or file path to write to. For example, it could be a file path as a string or a file writer opened in binary mode with ``open('my_file.ttl', 'wb')``. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
-    /// :type output: typing.IO[bytes] or str or os.PathLike[str] or None, optional
+    /// :param output: The I/O object or file path to write to. For example, it could be a file path as a string, a file writer opened in binary mode with ``open('my_file.ttl', 'wb')`` or a text I/O object like :py:class:`io.StringIO`. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
+    /// :type output: typing.IO[bytes] or typing.IO[str] or str or os.PathLike[str] or None, optional
     /// :param format: the format of the query results serialization. If :py:const:`None`, the format is guessed from the file name extension.
     /// :type format: QueryResultsFormat or None, optional
     /// :rtype: bytes or None
@@ -326,6 +345,7 @@ impl PyQuerySolutions {
                         PyQuerySolutionsVariant::Query(inner) => {
                             for solution in &mut inner.0 {
                                 serializer.serialize(&solution.map_err(map_evaluation_error)?)?;
+                                self.has_returned_results = true;
                             }
                         }
                         PyQuerySolutionsVariant::Reader { iter, file_path } => {
@@ -343,6 +363,7 @@ impl PyQuerySolutions {
             output,
             py,
         )
+        .map_err(|e| with_partial_results(e, self.has_returned_results, py))
     }
 
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
@@ -350,17 +371,20 @@ impl PyQuerySolutions {
     }
 
     fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<PyQuerySolution>> {
-        Ok(match &mut self.inner {
+        let solution = match &mut self.inner {
             PyQuerySolutionsVariant::Query(inner) => py
                 .allow_threads(move || inner.0.next())
                 .transpose()
-                .map_err(map_evaluation_error),
+                .map_err(|e| {
+                    with_partial_results(map_evaluation_error(e), self.has_returned_results, py)
+                }),
             PyQuerySolutionsVariant::Reader { iter, file_path } => py
                 .allow_threads(|| iter.next())
                 .transpose()
                 .map_err(|e| map_query_results_parse_error(e, file_path.clone())),
-        }?
-        .map(move |inner| PyQuerySolution { inner }))
+        }?;
+        self.has_returned_results |= solution.is_some();
+        Ok(solution.map(move |inner| PyQuerySolution { inner }))
     }
 }
 
@@ -392,8 +416,8 @@ impl PyQueryBoolean {
     /// It supports also some media type and extension aliases.
     /// For example, ``application/json`` could also be used for `JSON <https://www.w3.org/TR/sparql11-results-json/>`_.
     ///
-    /// :param output: The binary I/O object or file path to write to. For example, it could be a file path as a string or a file writer opened in binary mode with ``open('my_file.ttl', 'wb')``. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
-    /// :type output: typing.IO[bytes] or str or os.PathLike[str] or None, optional
+    /// :param output: The I/O object or file path to write to. For example, it could be a file path as a string, a file writer opened in binary mode with ``open('my_file.ttl', 'wb')`` or a text I/O object like :py:class:`io.StringIO`. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
+    /// :type output: typing.IO[bytes] or typing.IO[str] or str or os.PathLike[str] or None, optional
     /// :param format: the format of the query results serialization. If :py:const:`None`, the format is guessed from the file name extension.
     /// :type format: QueryResultsFormat or None, optional
     /// :rtype: bytes or None
@@ -443,6 +467,7 @@ impl PyQueryBoolean {
 #[pyclass(unsendable, name = "QueryTriples", module = "pyoxigraph")]
 pub struct PyQueryTriples {
     inner: UngilQueryTripleIter,
+    has_returned_results: bool,
 }
 
 struct UngilQueryTripleIter(QueryTripleIter);
@@ -469,8 +494,8 @@ impl PyQueryTriples {
     /// For example, ``application/turtle`` could also be used for `Turtle <https://www.w3.org/TR/turtle/>`_
     /// and ``application/xml`` or ``xml`` for `RDF/XML <https://www.w3.org/TR/rdf-syntax-grammar/>`_.
     ///
-    /// :param output: The binary I/O object or file path to write to. For example, it could be a file path as a string or a file writer opened in binary mode with ``open('my_file.ttl', 'wb')``. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
-    /// :type output: typing.IO[bytes] or str or os.PathLike[str] or None, optional
+    /// :param output: The I/O object or file path to write to. For example, it could be a file path as a string, a file writer opened in binary mode with ``open('my_file.ttl', 'wb')`` or a text I/O object like :py:class:`io.StringIO`. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
+    /// :type output: typing.IO[bytes] or typing.IO[str] or str or os.PathLike[str] or None, optional
     /// :param format: the format of the RDF serialization. If :py:const:`None`, the format is guessed from the file name extension.
     /// :type format: RdfFormat or None, optional
     /// :rtype: bytes or None
@@ -489,13 +514,16 @@ impl PyQueryTriples {
         format: Option<PyRdfFormatInput>,
         py: Python<'_>,
     ) -> PyResult<Option<Vec<u8>>> {
+        let inner = &mut self.inner;
+        let has_returned_results = &mut self.has_returned_results;
         PyWritable::do_write(
             |output, file_path| {
                 let format = lookup_rdf_format(format, file_path.as_deref())?;
                 py.allow_threads(move || {
                     let mut serializer = RdfSerializer::from_format(format).for_writer(output);
-                    for triple in &mut self.inner.0 {
+                    for triple in &mut inner.0 {
                         serializer.serialize_triple(&triple.map_err(map_evaluation_error)?)?;
+                        *has_returned_results = true;
                     }
                     Ok(serializer.finish()?)
                 })
@@ -503,6 +531,7 @@ impl PyQueryTriples {
             output,
             py,
         )
+        .map_err(|e| with_partial_results(e, self.has_returned_results, py))
     }
 
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
@@ -510,11 +539,15 @@ impl PyQueryTriples {
     }
 
     fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<PyTriple>> {
-        Ok(py
-            .allow_threads(move || self.inner.0.next())
+        let inner = &mut self.inner;
+        let triple = py
+            .allow_threads(move || inner.0.next())
             .transpose()
-            .map_err(map_evaluation_error)?
-            .map(Into::into))
+            .map_err(|e| {
+                with_partial_results(map_evaluation_error(e), self.has_returned_results, py)
+            })?;
+        self.has_returned_results |= triple.is_some();
+        Ok(triple.map(Into::into))
     }
 }
 
@@ -565,6 +598,7 @@ pub fn parse_query_results(
                 iter,
                 file_path: path,
             },
+            has_returned_results: false,
         }
         .into_bound_py_any(py),
         ReaderQueryResultsParserOutput::Boolean(inner) => {
@@ -755,14 +789,29 @@ pub fn map_evaluation_error(error: EvaluationError) -> PyErr {
         EvaluationError::GraphParsing(error) => map_parse_error(error, None),
         EvaluationError::ResultsParsing(error) => map_query_results_parse_error(error, None),
         EvaluationError::ResultsSerialization(error) => error.into(),
+        EvaluationError::Http(error) => io::Error::from(error).into(),
         EvaluationError::Service(error) => match error.downcast::<io::Error>() {
             Ok(error) => (*error).into(),
-            Err(error) => PyRuntimeError::new_err(error.to_string()),
+            Err(error) => match error.downcast::<HttpError>() {
+                Ok(error) => io::Error::from(*error).into(),
+                Err(error) => PyRuntimeError::new_err(error.to_string()),
+            },
         },
+        EvaluationError::Timeout => QueryTimeoutError::new_err(error.to_string()),
         _ => PyRuntimeError::new_err(error.to_string()),
     }
 }
 
+/// Sets the ``partial_results`` attribute of :py:class:`QueryTimeoutError` errors.
+pub fn with_partial_results(error: PyErr, partial_results: bool, py: Python<'_>) -> PyErr {
+    if error.is_instance_of::<QueryTimeoutError>(py) {
+        if let Err(e) = error.value(py).setattr("partial_results", partial_results) {
+            return e;
+        }
+    }
+    error
+}
+
 pub fn map_query_results_parse_error(
     error: QueryResultsParseError,
     file_path: Option<PathBuf>,
@@ -230,10 +230,13 @@ impl PyStore {
     /// :type substitutions: dict[Variable, NamedNode or BlankNode or Literal or Triple] or None, optional
     /// :param custom_functions: dictionary of custom functions mapping function names to their definition. Custom functions takes for input some :py:class:`Term`s and return a :py:class:`Term` or :py:const:`None`.
     /// :type custom_functions: dict[NamedNode, typing.Callable[[NamedNode or BlankNode or Literal or Triple, ...], NamedNode or BlankNode or Literal or Triple or None]] or None, optional
+    /// :param timeout: maximal duration of the query evaluation in seconds. When it is reached, :py:class:`QueryTimeoutError` is raised by this method or while iterating over the results. By default, there is no timeout.
+    /// :type timeout: float or None, optional
     /// :return: a :py:class:`bool` for ``ASK`` queries, an iterator of :py:class:`Triple` for ``CONSTRUCT`` and ``DESCRIBE`` queries and an iterator of :py:class:`QuerySolution` for ``SELECT`` queries.
     /// :rtype: QuerySolutions or QueryBoolean or QueryTriples
     /// :raises SyntaxError: if the provided query is invalid.
     /// :raises OSError: if an error happens while reading the store.
+    /// :raises QueryTimeoutError: if the query evaluation timeout is reached.
     ///
     /// ``SELECT`` query:
     ///
@@ -255,7 +258,17 @@ impl PyStore {
     /// >>> store.add(Quad(NamedNode('http://example.com'), NamedNode('http://example.com/p'), Literal('1')))
     /// >>> bool(store.query('ASK { ?s ?p ?o }'))
     /// True
-    #[pyo3(signature = (query, *, base_iri = None, use_default_graph_as_union = false, default_graph = None, named_graphs = None, substitutions = None, custom_functions = None))]
+    ///
+    /// Query with a timeout:
+    ///
+    /// >>> store = Store()
+    /// >>> store.add(Quad(NamedNode('http://example.com'), NamedNode('http://example.com/p'), Literal('1')))
+    /// >>> try:
+    /// ...     list(store.query('SELECT * WHERE { ?s ?p ?o }', timeout=0))
+    /// ... except QueryTimeoutError as e:
+    /// ...     e.partial_results
+    /// False
+    #[pyo3(signature = (query, *, base_iri = None, use_default_graph_as_union = false, default_graph = None, named_graphs = None, substitutions = None, custom_functions = None, timeout = None))]
     fn query<'py>(
         &self,
         query: &str,
@@ -265,6 +278,7 @@ impl PyStore {
         named_graphs: Option<&Bound<'_, PyAny>>,
         substitutions: Option<HashMap<PyVariable, PyTerm>>,
         custom_functions: Option<HashMap<PyNamedNode, PyObject>>,
+        timeout: Option<f64>,
         py: Python<'py>,
     ) -> PyResult<Bound<'py, PyAny>> {
         pub struct UngilQueryResults(QueryResults);
@@ -281,7 +295,7 @@ impl PyStore {
             named_graphs,
             py,
         )?;
-        let options = query_options_from_python(custom_functions);
+        let options = query_options_from_python(custom_functions, timeout)?;
         let substitutions = substitutions
             .unwrap_or_default()
             .into_iter()
@@ -296,7 +310,7 @@ impl PyStore {
                     )?,
                 ))
             })
-            .map_err(map_evaluation_error)?
+            .map_err(|e| with_partial_results(map_evaluation_error(e), false, py))?
             .0;
         query_results_to_python(py, results)
     }
@@ -346,7 +360,7 @@ impl PyStore {
         py: Python<'_>,
     ) -> PyResult<()> {
         py.allow_threads(|| {
-            let options = query_options_from_python(custom_functions);
+            let options = query_options_from_python(custom_functions, None)?;
             let update =
                 Update::parse(update, base_iri).map_err(|e| map_evaluation_error(e.into()))?;
             self.inner
@@ -512,8 +526,11 @@ impl PyStore {
     /// For example, ``application/turtle`` could also be used for `Turtle <https://www.w3.org/TR/turtle/>`_
     /// and ``application/xml`` or ``xml`` for `RDF/XML <https://www.w3.org/TR/rdf-syntax-grammar/>`_.
     ///
-    /// :param output: The binary I/O object or file path to write to. For example, it could be a file path as a string or a file writer opened in binary mode with ``open('my_file.ttl', 'wb')``. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
-    /// :type output: typing.IO[bytes] or str or os.PathLike[str] or None, optional
+    /// The dump is streamed with a bounded memory usage and the GIL is only held while calling the ``output`` I/O object.
+    /// It is suitable to export very large stores.
+    ///
+    /// :param output: The I/O object or file path to write to. For example, it could be a file path as a string, a file writer opened in binary mode with ``open('my_file.ttl', 'wb')`` or a text I/O object like :py:class:`io.StringIO`. If :py:const:`None`, a :py:class:`bytes` buffer is returned with the serialized content.
+    /// :type output: typing.IO[bytes] or typing.IO[str] or str or os.PathLike[str] or None, optional
     /// :param format: the format of the RDF serialization.  If :py:const:`None`, the format is guessed from the file name extension.
     /// :type format: RdfFormat or None, optional
     /// :param from_graph: the store graph from which dump the triples. Required if the serialization format does not support named graphs. If it does supports named graphs the full dataset is written.
@@ -862,7 +879,9 @@ pub fn map_serializer_error(error: SerializerError) -> PyErr {
     match error {
         SerializerError::Storage(error) => map_storage_error(error),
         SerializerError::Io(error) => error.into(),
-        SerializerError::DatasetFormatExpected(_) => PyValueError::new_err(error.to_string()),
+        SerializerError::DatasetFormatExpected(_) | SerializerError::LineBasedFormatExpected(_) => {
+            PyValueError::new_err(error.to_string())
+        }
     }
 }
 
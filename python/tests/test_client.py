@@ -0,0 +1,109 @@
+import sys
+import unittest
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from threading import Thread
+from typing import Any, ClassVar, List, Tuple
+
+from pyoxigraph import NamedNode, QuerySolutions, RdfFormat, Triple
+
+if sys.platform != "emscripten":
+    from pyoxigraph.client import RemoteStore  # type: ignore[import-not-found]
+
+foo = NamedNode("http://foo")
+bar = NamedNode("http://bar")
+
+
+class MockHandler(BaseHTTPRequestHandler):
+    requests: ClassVar[List[Tuple[str, str, bytes]]] = []
+
+    def _handle(self) -> None:
+        length = int(self.headers.get("Content-Length", 0))
+        body = self.rfile.read(length)
+        self.requests.append((self.command, self.path, body))
+        if self.path == "/query" and b"SELECT" in body:
+            self._respond(
+                "application/sparql-results+json",
+                b'{"head":{"vars":["s"]},"results":{"bindings":[{"s":{"type":"uri","value":"http://foo"}}]}}',
+            )
+        elif self.path == "/query":
+            self._respond("application/n-triples", b"<http://foo> <http://bar> <http://foo> .\n")
+        elif self.path.startswith("/store") and self.command == "GET":
+            self._respond("text/turtle", b"<http://foo> <http://bar> <http://foo> .\n")
+        else:
+            self.send_response(204)
+            self.end_headers()
+
+    def _respond(self, content_type: str, content: bytes) -> None:
+        self.send_response(200)
+        self.send_header("Content-Type", content_type)
+        self.send_header("Content-Length", str(len(content)))
+        self.end_headers()
+        self.wfile.write(content)
+
+    do_GET = _handle
+    do_POST = _handle
+    do_DELETE = _handle
+
+    def log_message(self, *args: Any) -> None:
+        pass
+
+
+@unittest.skipIf(sys.platform == "emscripten", "no HTTP client")
+class TestRemoteStore(unittest.TestCase):
+    server: ThreadingHTTPServer
+    store: Any
+
+    @classmethod
+    def setUpClass(cls) -> None:
+        cls.server = ThreadingHTTPServer(("127.0.0.1", 0), MockHandler)
+        Thread(target=cls.server.serve_forever, daemon=True).start()
+        base = f"http://127.0.0.1:{cls.server.server_port}"
+        cls.store = RemoteStore(
+            f"{base}/query", update_endpoint=f"{base}/update", graph_store_endpoint=f"{base}/store"
+        )
+
+    @classmethod
+    def tearDownClass(cls) -> None:
+        cls.server.shutdown()
+        cls.server.server_close()
+
+    def setUp(self) -> None:
+        MockHandler.requests.clear()
+
+    def test_select_query(self) -> None:
+        results = self.store.query("SELECT ?s WHERE { ?s ?p ?o }")
+        self.assertIsInstance(results, QuerySolutions)
+        self.assertEqual([solution["s"] for solution in results], [foo])
+
+    def test_construct_query(self) -> None:
+        self.assertEqual(list(self.store.query("CONSTRUCT WHERE { ?s ?p ?o }")), [Triple(foo, bar, foo)])
+
+    def test_update(self) -> None:
+        self.store.update("INSERT DATA { <http://foo> <http://bar> <http://foo> }")
+        self.assertEqual(MockHandler.requests[0][:2], ("POST", "/update"))
+
+    def test_load(self) -> None:
+        self.store.load("<http://foo> <http://bar> <http://foo> .", RdfFormat.TURTLE, to_graph=bar)
+        self.assertEqual(
+            MockHandler.requests[0],
+            ("POST", "/store?graph=http%3A%2F%2Fbar", b"<http://foo> <http://bar> <http://foo> .\n"),
+        )
+
+    def test_dump(self) -> None:
+        self.assertEqual(
+            self.store.dump(format=RdfFormat.N_TRIPLES),
+            b"<http://foo> <http://bar> <http://foo> .\n",
+        )
+        self.assertEqual(MockHandler.requests[0][:2], ("GET", "/store?default"))
+
+    def test_remove_graph(self) -> None:
+        self.store.remove_graph(bar)
+        self.assertEqual(MockHandler.requests[0][:2], ("DELETE", "/store?graph=http%3A%2F%2Fbar"))
+
+    def test_missing_graph_store_endpoint(self) -> None:
+        with self.assertRaises(OSError):
+            RemoteStore(f"http://127.0.0.1:{self.server.server_port}/query").dump()
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -11,6 +11,7 @@ from pyoxigraph import (
     QueryResultsFormat,
     QuerySolutions,
     RdfFormat,
+    Triple,
     parse,
     parse_query_results,
     serialize,
@@ -178,6 +179,44 @@ class TestSerialize(unittest.TestCase):
             '<http://example.com/foo> <http://example.com/p> "éù" .\n',
         )
 
+    def test_serialize_to_str_io(self) -> None:
+        output = StringIO()
+        serialize([EXAMPLE_TRIPLE.triple], output, RdfFormat.TURTLE)
+        self.assertEqual(
+            output.getvalue(),
+            '<http://example.com/foo> <http://example.com/p> "éù" .\n',
+        )
+
+    def test_serialize_long_to_str_io(self) -> None:
+        triples = [
+            Triple(NamedNode("http://example.com/foo"), NamedNode("http://example.com/p"), Literal("é" * 100_000))
+        ]
+        output = StringIO()
+        serialize(triples, output, RdfFormat.N_TRIPLES)
+        self.assertEqual(
+            output.getvalue(),
+            f'<http://example.com/foo> <http://example.com/p> "{"é" * 100_000}" .\n',
+        )
+
+    def test_serialize_to_custom_writer(self) -> None:
+        class Writer:
+            def __init__(self) -> None:
+                self.chunks: list[bytes] = []
+
+            def write(self, data: bytes) -> None:
+                self.chunks.append(bytes(data))
+
+            def flush(self) -> None:
+                pass
+
+        output = Writer()
+        serialize([EXAMPLE_TRIPLE] * 10_000, output, RdfFormat.N_TRIPLES)
+        self.assertGreater(len(output.chunks), 1)
+        self.assertEqual(
+            b"".join(output.chunks).decode(),
+            '<http://example.com/foo> <http://example.com/p> "éù" .\n' * 10_000,
+        )
+
     def test_serialize_to_file(self) -> None:
         with NamedTemporaryFile(suffix=".ttl") as fp:
             serialize([EXAMPLE_TRIPLE], fp.name)
@@ -1,5 +1,6 @@
 import gc
 import sys
+import time
 import unittest
 from io import BytesIO, StringIO, UnsupportedOperation
 from pathlib import Path
@@ -16,6 +17,7 @@ from pyoxigraph import (
     QueryResultsFormat,
     QuerySolution,
     QuerySolutions,
+    QueryTimeoutError,
     QueryTriples,
     RdfFormat,
     Store,
@@ -225,6 +227,33 @@ class TestStore(unittest.TestCase):
         self.assertEqual(len(all_solutions), 1)
         self.assertEqual(all_solutions[0]["s"], foo)
 
+    def test_select_query_timeout(self) -> None:
+        store = Store()
+        store.add(Quad(foo, bar, baz))
+        with self.assertRaises(QueryTimeoutError) as cm:
+            list(store.query("SELECT ?s WHERE { ?s ?p ?o }", timeout=0))
+        self.assertFalse(cm.exception.partial_results)
+        self.assertIsInstance(cm.exception, TimeoutError)
+
+    def test_select_query_timeout_partial_results(self) -> None:
+        store = Store()
+        store.add(Quad(foo, bar, baz))
+        store.add(Quad(bar, bar, baz))
+
+        def sleep(_: Any) -> Literal:
+            time.sleep(0.2)
+            return Literal("true", datatype=NamedNode("http://www.w3.org/2001/XMLSchema#boolean"))
+
+        results = store.query(
+            "SELECT ?s WHERE { ?s ?p ?o FILTER(<http://sleep>(?s)) }",
+            custom_functions={NamedNode("http://sleep"): sleep},
+            timeout=0.1,
+        )
+        with self.assertRaises(QueryTimeoutError) as cm:
+            for _ in results:  # type: ignore[union-attr]
+                pass
+        self.assertTrue(cm.exception.partial_results)
+
     def test_select_query_dump(self) -> None:
         store = Store()
         store.add(Quad(foo, bar, baz))
@@ -377,6 +406,16 @@ class TestStore(unittest.TestCase):
             b"<http://graph> {\n\t<http://foo> <http://bar> <http://baz> .\n}\n",
         )
 
+    def test_dump_str_io(self) -> None:
+        store = Store()
+        store.add(Quad(foo, bar, baz, graph))
+        output = StringIO()
+        store.dump(output, RdfFormat.N_QUADS)
+        self.assertEqual(
+            output.getvalue(),
+            "<http://foo> <http://bar> <http://baz> <http://graph> .\n",
+        )
+
     def test_dump_file(self) -> None:
         with NamedTemporaryFile(delete=False) as fp:
             store = Store()
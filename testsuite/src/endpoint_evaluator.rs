@@ -0,0 +1,452 @@
+//! Evaluation of the SPARQL query and protocol tests against a running SPARQL endpoint.
+//!
+//! The test data is loaded into the endpoint using SPARQL UPDATE if an update endpoint is provided.
+//! Its content is removed before each evaluation test.
+use crate::evaluator::TestEvaluator;
+use crate::files::read_file_to_string;
+use crate::manifest::Test;
+use crate::report::{TestResult, TestSkipped};
+use crate::sparql_evaluator::{
+    StaticQueryResults, are_query_results_isomorphic, load_sparql_query_result, load_to_dataset,
+    results_diff,
+};
+use anyhow::{Context, Result, bail, ensure};
+use oxhttp::Client;
+use oxhttp::model::header::{ACCEPT, CONTENT_TYPE};
+use oxhttp::model::{Body, Method, Request, Response};
+use oxigraph::io::{RdfFormat, RdfParser};
+use oxigraph::model::{Dataset, Graph, GraphName, GraphNameRef, NamedNode, Triple};
+use oxigraph::sparql::QueryResults;
+use oxigraph::sparql::results::QueryResultsFormat;
+use spargebra::SparqlParser;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+const QUERY_ACCEPT: &str = "application/sparql-results+json, application/sparql-results+xml;q=0.9, application/n-triples;q=0.8, text/turtle;q=0.7";
+const PROTOCOL_MANIFEST: &str =
+    "http://www.w3.org/2009/sparql/docs/tests/data-sparql11/protocol/manifest#";
+
+/// A SPARQL endpoint the tests are run against
+#[derive(Clone)]
+pub struct SparqlEndpoint {
+    client: Arc<Client>,
+    query_url: String,
+    update_url: Option<String>,
+}
+
+impl SparqlEndpoint {
+    pub fn new(query_url: impl Into<String>) -> Result<Self> {
+        Ok(Self {
+            client: Arc::new(
+                Client::new()
+                    .with_global_timeout(Duration::from_secs(60))
+                    .with_user_agent(concat!("Oxigraph testsuite/", env!("CARGO_PKG_VERSION")))?,
+            ),
+            query_url: query_url.into(),
+            update_url: None,
+        })
+    }
+
+    /// Sets the SPARQL update endpoint used to load the test data
+    ///
+    /// Warning: the endpoint content is removed before each evaluation test.
+    #[must_use]
+    pub fn with_update_endpoint(mut self, update_url: impl Into<String>) -> Self {
+        self.update_url = Some(update_url.into());
+        self
+    }
+
+    fn request(
+        &self,
+        method: Method,
+        url: &str,
+        payload: Option<(Vec<u8>, Option<&str>)>,
+    ) -> Result<Response<Body>> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(url)
+            .header(ACCEPT, QUERY_ACCEPT);
+        let response = if let Some((payload, content_type)) = payload {
+            if let Some(content_type) = content_type {
+                request = request.header(CONTENT_TYPE, content_type);
+            }
+            self.client.request(request.body(payload)?)
+        } else {
+            self.client.request(request.body(())?)
+        };
+        response.with_context(|| format!("Failed to call {url}"))
+    }
+
+    fn query_url_with(&self, parameters: &[(&str, &str)]) -> String {
+        let mut url = self.query_url.clone();
+        url.push(if url.contains('?') { '&' } else { '?' });
+        url.push_str(&form_urlencode(parameters));
+        url
+    }
+
+    fn query(&self, query: &str) -> Result<StaticQueryResults> {
+        let response = self.request(
+            Method::POST,
+            &self.query_url,
+            Some((query.into(), Some("application/sparql-query"))),
+        )?;
+        let response = ensure_success(response)?;
+        let content_type = content_type(&response).context("No Content-Type returned")?;
+        if let Some(format) = QueryResultsFormat::from_media_type(&content_type) {
+            StaticQueryResults::from_query_results(
+                QueryResults::read(response.into_body(), format)?,
+                false,
+            )
+        } else if let Some(format) = RdfFormat::from_media_type(&content_type) {
+            StaticQueryResults::from_graph(
+                &RdfParser::from_format(format)
+                    .for_reader(response.into_body())
+                    .map(|quad| quad.map(Triple::from))
+                    .collect::<Result<Graph, _>>()?,
+            )
+        } else {
+            bail!("Unsupported response Content-Type {content_type}")
+        }
+    }
+
+    /// Replaces the endpoint content with the given dataset
+    fn load(&self, dataset: &Dataset) -> Result<()> {
+        let update_url = self.update_url.as_deref().ok_or(TestSkipped(
+            "the test requires data to be loaded and no update endpoint is set",
+        ))?;
+        let mut update = String::from("CLEAR ALL ;\nINSERT DATA {\n");
+        for quad in dataset {
+            match quad.graph_name {
+                GraphNameRef::DefaultGraph => {
+                    writeln!(
+                        update,
+                        "{} {} {} .",
+                        quad.subject, quad.predicate, quad.object
+                    )?;
+                }
+                GraphNameRef::NamedNode(graph_name) => {
+                    writeln!(
+                        update,
+                        "GRAPH {graph_name} {{ {} {} {} }}",
+                        quad.subject, quad.predicate, quad.object
+                    )?;
+                }
+                GraphNameRef::BlankNode(_) => bail!("Blank node graph names are not supported"),
+            }
+        }
+        update.push('}');
+        ensure_success(self.request(
+            Method::POST,
+            update_url,
+            Some((update.into(), Some("application/sparql-update"))),
+        )?)
+        .context("Failed to load the test data")?;
+        Ok(())
+    }
+}
+
+pub fn register_endpoint_tests(evaluator: &mut TestEvaluator, endpoint: &SparqlEndpoint) {
+    for test_type in [
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#PositiveSyntaxTest",
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#PositiveSyntaxTest11",
+    ] {
+        let endpoint = endpoint.clone();
+        evaluator.register(test_type, move |t| {
+            evaluate_positive_syntax_test(&endpoint, t)
+        });
+    }
+    for test_type in [
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#NegativeSyntaxTest",
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#NegativeSyntaxTest11",
+    ] {
+        let endpoint = endpoint.clone();
+        evaluator.register(test_type, move |t| {
+            evaluate_negative_syntax_test(&endpoint, t)
+        });
+    }
+    let endpoint = endpoint.clone();
+    evaluator.register(
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#QueryEvaluationTest",
+        move |t| evaluate_evaluation_test(&endpoint, t),
+    );
+    for test_type in [
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#PositiveUpdateSyntaxTest",
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#PositiveUpdateSyntaxTest11",
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#NegativeUpdateSyntaxTest",
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#NegativeUpdateSyntaxTest11",
+        "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#UpdateEvaluationTest",
+    ] {
+        evaluator.register(test_type, |_| {
+            Err(TestSkipped("update tests are not run against endpoints").into())
+        });
+    }
+}
+
+/// Runs the checks of the [SPARQL 1.1 protocol tests](https://w3c.github.io/rdf-tests/sparql/sparql11/protocol/) related to queries
+///
+/// The protocol tests are only described in prose in their manifest so the checks are implemented here.
+pub fn evaluate_protocol_tests(endpoint: &SparqlEndpoint) -> Vec<TestResult> {
+    let checks: [(&str, fn(&SparqlEndpoint) -> Result<()>); 14] = [
+        ("query_get", |e| {
+            ensure_ask(e.request(Method::GET, &e.query_url_with(&[("query", "ASK {}")]), None)?)
+        }),
+        ("query_post_form", |e| {
+            ensure_ask(e.request(
+                Method::POST,
+                &e.query_url,
+                Some((
+                    form_urlencode(&[("query", "ASK {}")]).into(),
+                    Some("application/x-www-form-urlencoded"),
+                )),
+            )?)
+        }),
+        ("query_post_direct", |e| {
+            ensure_ask(e.request(
+                Method::POST,
+                &e.query_url,
+                Some(("ASK {}".into(), Some("application/sparql-query"))),
+            )?)
+        }),
+        ("bad_query_method", |e| {
+            ensure_client_error(&e.request(
+                Method::PUT,
+                &e.query_url,
+                Some(("ASK {}".into(), Some("application/sparql-query"))),
+            )?)
+        }),
+        ("bad_multiple_queries", |e| {
+            ensure_client_error(&e.request(
+                Method::GET,
+                &e.query_url_with(&[("query", "ASK {}"), ("query", "SELECT * WHERE {}")]),
+                None,
+            )?)
+        }),
+        ("bad_query_wrong_media_type", |e| {
+            ensure_client_error(&e.request(
+                Method::POST,
+                &e.query_url,
+                Some(("ASK {}".into(), Some("text/plain"))),
+            )?)
+        }),
+        ("bad_query_missing_form_type", |e| {
+            ensure_client_error(&e.request(
+                Method::POST,
+                &e.query_url,
+                Some((form_urlencode(&[("query", "ASK {}")]).into(), None)),
+            )?)
+        }),
+        ("bad_query_missing_direct_type", |e| {
+            ensure_client_error(&e.request(
+                Method::POST,
+                &e.query_url,
+                Some(("ASK {}".into(), None)),
+            )?)
+        }),
+        ("bad_query_non_utf8", |e| {
+            ensure_client_error(&e.request(
+                Method::POST,
+                &e.query_url,
+                Some((
+                    "ASK {}".encode_utf16().flat_map(u16::to_be_bytes).collect(),
+                    Some("application/sparql-query; charset=UTF-16"),
+                )),
+            )?)
+        }),
+        ("bad_query_syntax", |e| {
+            ensure_client_error(&e.request(
+                Method::GET,
+                &e.query_url_with(&[("query", "ASK {")]),
+                None,
+            )?)
+        }),
+        ("query_content_type_select", |e| {
+            ensure_results_content_type(e, "SELECT * WHERE {}")
+        }),
+        ("query_content_type_ask", |e| {
+            ensure_results_content_type(e, "ASK {}")
+        }),
+        ("query_content_type_describe", |e| {
+            ensure_graph_content_type(e, "DESCRIBE <http://example.com/>")
+        }),
+        ("query_content_type_construct", |e| {
+            ensure_graph_content_type(e, "CONSTRUCT WHERE {}")
+        }),
+    ];
+    checks
+        .into_iter()
+        .map(|(name, check)| TestResult {
+            test: NamedNode::new_unchecked(format!("{PROTOCOL_MANIFEST}{name}")),
+            outcome: check(endpoint),
+            date: OffsetDateTime::now_utc(),
+        })
+        .collect()
+}
+
+fn evaluate_positive_syntax_test(endpoint: &SparqlEndpoint, test: &Test) -> Result<()> {
+    let query_file = test.action.as_deref().context("No action found")?;
+    endpoint
+        .query(&with_base(query_file)?)
+        .context("The query has been rejected by the endpoint")?;
+    Ok(())
+}
+
+fn evaluate_negative_syntax_test(endpoint: &SparqlEndpoint, test: &Test) -> Result<()> {
+    let query_file = test.action.as_deref().context("No action found")?;
+    ensure_client_error(&endpoint.request(
+        Method::POST,
+        &endpoint.query_url,
+        Some((
+            with_base(query_file)?.into(),
+            Some("application/sparql-query"),
+        )),
+    )?)
+}
+
+fn evaluate_evaluation_test(endpoint: &SparqlEndpoint, test: &Test) -> Result<()> {
+    if !test.service_data.is_empty() {
+        return Err(TestSkipped("SERVICE calls can't be mocked on endpoints").into());
+    }
+    let mut dataset = Dataset::new();
+    if let Some(data) = &test.data {
+        load_to_dataset(data, &mut dataset, GraphName::DefaultGraph)?;
+    }
+    for (name, value) in &test.graph_data {
+        load_to_dataset(value, &mut dataset, name.clone())?;
+    }
+    let query_file = test.query.as_deref().context("No action found")?;
+    let query = SparqlParser::new()
+        .with_base_iri(query_file)?
+        .parse_query(&read_file_to_string(query_file)?)
+        .context("Failure to parse query")?;
+
+    // FROM and FROM NAMED support. We make sure the data is in the endpoint
+    if let Some(query_dataset) = query.dataset() {
+        for graph_name in &query_dataset.default {
+            load_to_dataset(graph_name.as_str(), &mut dataset, GraphName::DefaultGraph)?;
+        }
+        if let Some(named_graphs) = &query_dataset.named {
+            for graph_name in named_graphs {
+                load_to_dataset(graph_name.as_str(), &mut dataset, graph_name.clone())?;
+            }
+        }
+    }
+    if endpoint.update_url.is_some() || !dataset.is_empty() {
+        endpoint.load(&dataset)?;
+    }
+
+    let expected_results = load_sparql_query_result(test.result.as_ref().unwrap())
+        .context("Error constructing expected graph")?;
+    let actual_results = endpoint
+        .query(&with_base(query_file)?)
+        .with_context(|| format!("Error when executing {query}"))?;
+    ensure!(
+        are_query_results_isomorphic(&expected_results, &actual_results),
+        "Not isomorphic results.\n{}\nParsed query:\n{query}\nData:\n{dataset}\n",
+        results_diff(expected_results, actual_results),
+    );
+    Ok(())
+}
+
+/// Reads a query file and sets its URL as the query base IRI
+fn with_base(query_file: &str) -> Result<String> {
+    Ok(format!(
+        "BASE <{query_file}>\n{}",
+        read_file_to_string(query_file)?
+    ))
+}
+
+fn ensure_success(response: Response<Body>) -> Result<Response<Body>> {
+    let status = response.status();
+    if !status.is_success() {
+        bail!(
+            "Error {status} returned with payload:\n{}",
+            response.into_body().to_string()?
+        );
+    }
+    Ok(response)
+}
+
+fn ensure_client_error(response: &Response<Body>) -> Result<()> {
+    let status = response.status();
+    ensure!(
+        status.is_client_error(),
+        "A 4xx status was expected, got {status}"
+    );
+    Ok(())
+}
+
+fn ensure_ask(response: Response<Body>) -> Result<()> {
+    let response = ensure_success(response)?;
+    let content_type = content_type(&response).context("No Content-Type returned")?;
+    let format = QueryResultsFormat::from_media_type(&content_type)
+        .with_context(|| format!("Unexpected Content-Type {content_type}"))?;
+    let QueryResults::Boolean(value) = QueryResults::read(response.into_body(), format)? else {
+        bail!("A boolean result was expected");
+    };
+    ensure!(value, "ASK {{}} should return true");
+    Ok(())
+}
+
+fn ensure_results_content_type(endpoint: &SparqlEndpoint, query: &str) -> Result<()> {
+    let response = ensure_success(endpoint.request(
+        Method::POST,
+        &endpoint.query_url,
+        Some((query.into(), Some("application/sparql-query"))),
+    )?)?;
+    let content_type = content_type(&response).context("No Content-Type returned")?;
+    ensure!(
+        QueryResultsFormat::from_media_type(&content_type).is_some(),
+        "A SPARQL results Content-Type was expected, got {content_type}"
+    );
+    Ok(())
+}
+
+fn ensure_graph_content_type(endpoint: &SparqlEndpoint, query: &str) -> Result<()> {
+    let response = ensure_success(endpoint.request(
+        Method::POST,
+        &endpoint.query_url,
+        Some((query.into(), Some("application/sparql-query"))),
+    )?)?;
+    let content_type = content_type(&response).context("No Content-Type returned")?;
+    ensure!(
+        RdfFormat::from_media_type(&content_type).is_some(),
+        "An RDF Content-Type was expected, got {content_type}"
+    );
+    Ok(())
+}
+
+fn content_type(response: &Response<Body>) -> Option<String> {
+    Some(
+        response
+            .headers()
+            .get(CONTENT_TYPE)?
+            .to_str()
+            .ok()?
+            .to_owned(),
+    )
+}
+
+fn form_urlencode(parameters: &[(&str, &str)]) -> String {
+    let mut encoded = String::new();
+    for (key, value) in parameters {
+        if !encoded.is_empty() {
+            encoded.push('&');
+        }
+        percent_encode(key, &mut encoded);
+        encoded.push('=');
+        percent_encode(value, &mut encoded);
+    }
+    encoded
+}
+
+fn percent_encode(value: &str, output: &mut String) {
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            output.push(char::from(byte));
+        } else {
+            write!(output, "%{byte:02X}").unwrap();
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Implementation of [W3C RDF tests](https://w3c.github.io/rdf-tests/) to tests Oxigraph conformance.
 
+pub mod endpoint_evaluator;
 pub mod evaluator;
 pub mod files;
 pub mod manifest;
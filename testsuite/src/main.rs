@@ -1,22 +1,62 @@
 #![allow(clippy::print_stdout)]
 use anyhow::Result;
 use clap::Parser;
+use oxigraph_testsuite::endpoint_evaluator::{
+    SparqlEndpoint, evaluate_protocol_tests, register_endpoint_tests,
+};
 use oxigraph_testsuite::evaluator::TestEvaluator;
 use oxigraph_testsuite::manifest::TestManifest;
 use oxigraph_testsuite::parser_evaluator::register_parser_tests;
-use oxigraph_testsuite::report::build_report;
+use oxigraph_testsuite::report::{build_matrix, build_report};
 use oxigraph_testsuite::sparql_evaluator::register_sparql_tests;
 
+const ENDPOINT_MANIFESTS: [&str; 3] = [
+    "https://w3c.github.io/rdf-tests/sparql/sparql10/manifest-syntax.ttl",
+    "https://w3c.github.io/rdf-tests/sparql/sparql10/manifest-evaluation.ttl",
+    "https://w3c.github.io/rdf-tests/sparql/sparql11/manifest-sparql11-query.ttl",
+];
+
 #[derive(Parser)]
 /// Oxigraph testsuite runner
 struct Args {
     /// URI of the testsuite manifest(s) to run
+    ///
+    /// Defaults to the SPARQL 1.0 and 1.1 query testsuites when --endpoint is set.
     manifest: Vec<String>,
+    /// URL of a SPARQL endpoint to run the SPARQL query and protocol tests against
+    ///
+    /// A pass/fail matrix is printed instead of the EARL report.
+    #[arg(long)]
+    endpoint: Option<String>,
+    /// URL of the SPARQL update endpoint used to load the test data into the --endpoint one
+    ///
+    /// Warning: the endpoint content is removed.
+    /// If not set, the tests requiring data are skipped and the endpoint is expected to be empty.
+    #[arg(long, requires = "endpoint")]
+    update_endpoint: Option<String>,
 }
 
 fn main() -> Result<()> {
     let matches = Args::parse();
 
+    if let Some(endpoint) = matches.endpoint {
+        let mut endpoint = SparqlEndpoint::new(endpoint)?;
+        if let Some(update_endpoint) = matches.update_endpoint {
+            endpoint = endpoint.with_update_endpoint(update_endpoint);
+        }
+        let mut evaluator = TestEvaluator::default();
+        register_endpoint_tests(&mut evaluator, &endpoint);
+        let manifest = if matches.manifest.is_empty() {
+            TestManifest::new(ENDPOINT_MANIFESTS)
+        } else {
+            TestManifest::new(matches.manifest)
+        };
+        let mut results = evaluate_protocol_tests(&endpoint);
+        results.extend(evaluator.evaluate(manifest)?);
+        print!("{}", build_matrix(&results));
+        return Ok(());
+    }
+
     let mut evaluator = TestEvaluator::default();
     register_parser_tests(&mut evaluator);
     register_sparql_tests(&mut evaluator);
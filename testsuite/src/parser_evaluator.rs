@@ -6,7 +6,6 @@ use crate::vocab::jld;
 use anyhow::{Context, Result, bail, ensure};
 use json_event_parser::{JsonEvent, SliceJsonParser};
 use oxigraph::io::{RdfFormat, RdfParser, RdfSerializer};
-use oxigraph::model::graph::CanonicalizationAlgorithm;
 use oxigraph::model::{BlankNode, Dataset, Quad, Term};
 use oxjsonld::{
     JsonLdParser, JsonLdProcessingMode, JsonLdProfile, JsonLdProfileSet, JsonLdRemoteDocument,
@@ -168,15 +167,13 @@ fn evaluate_eval_test(
     lenient: bool,
 ) -> Result<()> {
     let action = test.action.as_deref().context("No action found")?;
-    let mut actual_dataset = load_dataset(action, format, ignore_errors, lenient)
+    let actual_dataset = load_dataset(action, format, ignore_errors, lenient)
         .with_context(|| format!("Parse error on file {action}"))?;
-    actual_dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
     let results = test.result.as_ref().context("No tests result found")?;
-    let mut expected_dataset = load_dataset(results, guess_rdf_format(results)?, false, lenient)
+    let expected_dataset = load_dataset(results, guess_rdf_format(results)?, false, lenient)
         .with_context(|| format!("Parse error on file {results}"))?;
-    expected_dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
     ensure!(
-        expected_dataset == actual_dataset,
+        expected_dataset.is_isomorphic(&actual_dataset),
         "The two files are not isomorphic. Diff:\n{}",
         dataset_diff(&expected_dataset, &actual_dataset)
     );
@@ -211,15 +208,13 @@ fn evaluate_jsonld_to_rdf_test(test: &Test) -> Result<()> {
         .any(|t| t.as_ref() == jld::POSITIVE_EVALUATION_TEST)
     {
         let action = test.action.as_deref().context("No action found")?;
-        let mut actual_dataset = parse_json_ld(action, profile, processing_mode, base_url)?
+        let actual_dataset = parse_json_ld(action, profile, processing_mode, base_url)?
             .with_context(|| format!("Parse error on file {action}"))?;
-        actual_dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
         let results = test.result.as_ref().context("No tests result found")?;
-        let mut expected_dataset = load_dataset(results, guess_rdf_format(results)?, false, false)
+        let expected_dataset = load_dataset(results, guess_rdf_format(results)?, false, false)
             .with_context(|| format!("Parse error on file {results}"))?;
-        expected_dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
         ensure!(
-            expected_dataset == actual_dataset,
+            expected_dataset.is_isomorphic(&actual_dataset),
             "The two files are not isomorphic. Diff:\n{}",
             dataset_diff(&expected_dataset, &actual_dataset)
         );
@@ -285,17 +280,15 @@ fn evaluate_jsonld_from_rdf_test(test: &Test) -> Result<()> {
 
 fn evaluate_n3_eval_test(test: &Test, ignore_errors: bool) -> Result<()> {
     let action = test.action.as_deref().context("No action found")?;
-    let mut actual_dataset = n3_to_dataset(
+    let actual_dataset = n3_to_dataset(
         load_n3(action, ignore_errors).with_context(|| format!("Parse error on file {action}"))?,
     );
-    actual_dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
     let results = test.result.as_ref().context("No tests result found")?;
-    let mut expected_dataset = n3_to_dataset(
+    let expected_dataset = n3_to_dataset(
         load_n3(results, false).with_context(|| format!("Parse error on file {results}"))?,
     );
-    expected_dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
     ensure!(
-        expected_dataset == actual_dataset,
+        expected_dataset.is_isomorphic(&actual_dataset),
         "The two files are not isomorphic. Diff:\n{}",
         dataset_diff(&expected_dataset, &actual_dataset)
     );
@@ -1,6 +1,9 @@
 use anyhow::Result;
+use oxigraph::model::dataset::CanonicalizationAlgorithm;
 use oxigraph::model::{Dataset, NamedNode};
-use std::fmt::Write;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Write};
 use text_diff::{Difference, diff};
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
@@ -12,6 +15,18 @@ pub struct TestResult {
     pub date: OffsetDateTime,
 }
 
+/// Outcome of a test that could not be evaluated
+#[derive(Debug)]
+pub struct TestSkipped(pub &'static str);
+
+impl fmt::Display for TestSkipped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "skipped: {}", self.0)
+    }
+}
+
+impl Error for TestSkipped {}
+
 pub(super) fn dataset_diff(expected: &Dataset, actual: &Dataset) -> String {
     format_diff(
         &normalize_dataset_text(expected),
@@ -21,6 +36,8 @@ pub(super) fn dataset_diff(expected: &Dataset, actual: &Dataset) -> String {
 }
 
 fn normalize_dataset_text(store: &Dataset) -> String {
+    let mut store = store.clone();
+    store.canonicalize(CanonicalizationAlgorithm::Unstable);
     let mut quads: Vec<_> = store.iter().map(|q| q.to_string()).collect();
     quads.sort();
     quads.join("\n")
@@ -150,3 +167,53 @@ pub fn build_report(results: impl IntoIterator<Item = TestResult>) -> String {
     }
     buffer
 }
+
+/// Builds a plain text table with the number of passed, failed and skipped tests per manifest followed by the failures
+#[expect(unused_must_use)]
+pub fn build_matrix(results: &[TestResult]) -> String {
+    let mut counts = BTreeMap::<&str, [usize; 3]>::new();
+    for result in results {
+        let manifest = result
+            .test
+            .as_str()
+            .split_once('#')
+            .map_or(result.test.as_str(), |(manifest, _)| manifest);
+        let count = counts.entry(manifest).or_default();
+        match &result.outcome {
+            Ok(()) => count[0] += 1,
+            Err(error) if error.is::<TestSkipped>() => count[2] += 1,
+            Err(_) => count[1] += 1,
+        }
+    }
+    let mut buffer = String::new();
+    writeln!(
+        &mut buffer,
+        "{:>7} {:>7} {:>7}  manifest",
+        "passed", "failed", "skipped"
+    );
+    let mut total = [0; 3];
+    for (manifest, count) in counts {
+        writeln!(
+            &mut buffer,
+            "{:>7} {:>7} {:>7}  {manifest}",
+            count[0], count[1], count[2]
+        );
+        for (total, count) in total.iter_mut().zip(count) {
+            *total += count;
+        }
+    }
+    writeln!(
+        &mut buffer,
+        "{:>7} {:>7} {:>7}  total",
+        total[0], total[1], total[2]
+    );
+    for result in results {
+        if let Err(error) = &result.outcome {
+            if !error.is::<TestSkipped>() {
+                writeln!(&mut buffer);
+                writeln!(&mut buffer, "{} failed: {error:#}", result.test);
+            }
+        }
+    }
+    buffer
+}
@@ -255,12 +255,10 @@ fn evaluate_update_evaluation_test(test: &Test) -> Result<()> {
         .with_context(|| format!("Failure to deserialize \"{update}\""))?;
 
     store.update(update).context("Failure to execute update")?;
-    let mut store_dataset: Dataset = store.iter().collect::<Result<_, _>>()?;
-    store_dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
-    let mut result_store_dataset: Dataset = result_store.iter().collect::<Result<_, _>>()?;
-    result_store_dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
+    let store_dataset: Dataset = store.iter().collect::<Result<_, _>>()?;
+    let result_store_dataset: Dataset = result_store.iter().collect::<Result<_, _>>()?;
     ensure!(
-        store_dataset == result_store_dataset,
+        store_dataset.is_isomorphic(&result_store_dataset),
         "Not isomorphic result dataset.\nDiff:\n{}\nParsed update:\n{}\n",
         dataset_diff(&result_store_dataset, &store_dataset),
         Update::parse(&read_file_to_string(update_file)?, Some(update_file)).unwrap(),
@@ -268,7 +266,7 @@ fn evaluate_update_evaluation_test(test: &Test) -> Result<()> {
     Ok(())
 }
 
-fn load_sparql_query_result(url: &str) -> Result<StaticQueryResults> {
+pub(crate) fn load_sparql_query_result(url: &str) -> Result<StaticQueryResults> {
     if let Some(format) = url
         .rsplit_once('.')
         .and_then(|(_, extension)| QueryResultsFormat::from_extension(extension))
@@ -395,7 +393,7 @@ fn to_graph(result: QueryResults, with_order: bool) -> Result<Graph> {
     })
 }
 
-fn are_query_results_isomorphic(
+pub(crate) fn are_query_results_isomorphic(
     expected: &StaticQueryResults,
     actual: &StaticQueryResults,
 ) -> bool {
@@ -477,7 +475,7 @@ fn compare_terms<'a>(
     }
 }
 
-enum StaticQueryResults {
+pub(crate) enum StaticQueryResults {
     Graph(Box<Graph>),
     Solutions {
         variables: Vec<Variable>,
@@ -488,11 +486,11 @@ enum StaticQueryResults {
 }
 
 impl StaticQueryResults {
-    fn from_query_results(results: QueryResults, with_order: bool) -> Result<Self> {
+    pub(crate) fn from_query_results(results: QueryResults, with_order: bool) -> Result<Self> {
         Self::from_graph(&to_graph(results, with_order)?)
     }
 
-    fn from_graph(graph: &Graph) -> Result<Self> {
+    pub(crate) fn from_graph(graph: &Graph) -> Result<Self> {
         // Hack to normalize literals
         let store = Store::new()?;
         for t in graph {
@@ -577,7 +575,7 @@ impl StaticQueryResults {
     }
 }
 
-fn results_diff(expected: StaticQueryResults, actual: StaticQueryResults) -> String {
+pub(crate) fn results_diff(expected: StaticQueryResults, actual: StaticQueryResults) -> String {
     match expected {
         StaticQueryResults::Solutions {
             variables: mut expected_variables,
@@ -692,7 +690,7 @@ fn load_to_store(url: &str, store: &Store, to_graph_name: impl Into<GraphName>)
     Ok(())
 }
 
-fn load_to_dataset(
+pub(crate) fn load_to_dataset(
     url: &str,
     dataset: &mut Dataset,
     to_graph_name: impl Into<GraphName>,